@@ -0,0 +1,1330 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # DDL Catalog
+//!
+//! This module provides a catalog built from a dumped SQL schema file
+//! (e.g. `schema.sql` produced by `mysqldump --no-data` or `pg_dump -s`),
+//! or from a schema split across migration-style files (`db/schema/*.sql`).
+//!
+//! ## Supported Statements
+//!
+//! - `CREATE [TEMPORARY] TABLE ... ( ... )` with column and table constraints
+//!   (`PRIMARY KEY`, `FOREIGN KEY ... REFERENCES`, `NOT NULL`, `DEFAULT`, `COMMENT`)
+//! - `CREATE [OR REPLACE] [MATERIALIZED] VIEW ... AS SELECT ...`
+//! - `ALTER TABLE ... ADD [CONSTRAINT name] PRIMARY KEY / FOREIGN KEY ...`
+//!   (pg_dump emits keys this way)
+//!
+//! Every other statement (INSERT seed data, CREATE INDEX, SET, ...) is ignored.
+//!
+//! Files are parsed with the dialect's tree-sitter grammar and the DDL
+//! statements lowered to IR, the same path queries take, so the catalog
+//! reads exactly the syntax the editor understands.
+//!
+//! ## Error Handling
+//!
+//! A malformed statement is skipped with a warning (logged and available via
+//! [`DdlCatalog::warnings`]); it never aborts loading the rest of the file.
+//! [`DdlCatalog::error_counts`] summarizes skipped statements per file.
+//!
+//! ## Multiple Files
+//!
+//! Files load in lexical order. Views and `ALTER TABLE` statements that name
+//! a table defined further on (in a later file or later in the same file)
+//! are applied in a second pass, and a table redefined in a later file
+//! replaces the earlier definition with a warning.
+//!
+//! ## Usage
+//!
+//! ```rust,ignore
+//! use unified_sql_lsp_catalog::DdlCatalog;
+//! use unified_sql_lsp_ir::Dialect;
+//!
+//! let catalog = DdlCatalog::from_sql_file("schema.sql", Dialect::PostgreSQL)?;
+//! let columns = catalog.get_columns("users").await?;
+//!
+//! let catalog = DdlCatalog::from_sql_files(&["db/schema/*.sql"], Dialect::PostgreSQL)?;
+//! ```
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Parser};
+use unified_sql_grammar::DialectLanguage;
+use unified_sql_lsp_ir::dialect::DialectFamily;
+use unified_sql_lsp_ir::query::{SelectItem, SetOp, TableRef};
+use unified_sql_lsp_ir::{
+    AlterTableAction, AlterTableStatement, ColumnDef, CreateTableStatement, CreateViewStatement,
+    DdlStatement, Dialect, Expr, ForeignKeyTarget, IdentifierComparer, TableConstraint,
+};
+use unified_sql_lsp_lowering::dialect::{MySQLLowering, PostgreSQLLowering};
+use unified_sql_lsp_lowering::{Lowering, LoweringContext, TreeSitterNode};
+
+use crate::metadata::{
+    ColumnMetadata, DataType, FunctionMetadata, TableMetadata, TableReference, TableType,
+};
+use crate::{Catalog, CatalogError, CatalogResult};
+
+/// Catalog backed by CREATE TABLE / CREATE VIEW statements from SQL files
+#[derive(Debug, Clone)]
+pub struct DdlCatalog {
+    /// Configured paths or patterns (empty when built from an in-memory string)
+    patterns: Vec<PathBuf>,
+    /// Files that were loaded, in load order
+    paths: Vec<PathBuf>,
+    /// Dialect used for identifier quoting and case folding
+    dialect: Dialect,
+    /// Tables and views in definition order
+    tables: Vec<TableMetadata>,
+    /// Statements that were skipped or redefined tables while loading
+    warnings: Vec<DdlWarning>,
+}
+
+/// A problem found while loading DDL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DdlWarning {
+    /// Schema file the statement came from (None for in-memory SQL)
+    pub file: Option<PathBuf>,
+    /// 1-based index of the statement in the file
+    pub statement_index: usize,
+    /// What went wrong
+    pub kind: DdlWarningKind,
+    /// Human-readable reason
+    pub message: String,
+}
+
+/// Classification of a [`DdlWarning`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DdlWarningKind {
+    /// The statement could not be read and was skipped
+    Skipped,
+    /// A table or view from an earlier file was redefined; the later one wins
+    DuplicateTable,
+}
+
+/// Why a statement was not applied
+enum StatementError {
+    /// References a table that isn't defined (yet); retried after all files load
+    Unresolved(String),
+    /// The statement cannot be read
+    Malformed(String),
+}
+
+impl From<String> for StatementError {
+    fn from(message: String) -> Self {
+        StatementError::Malformed(message)
+    }
+}
+
+impl StatementError {
+    fn into_message(self) -> String {
+        match self {
+            StatementError::Unresolved(message) | StatementError::Malformed(message) => message,
+        }
+    }
+}
+
+impl DdlCatalog {
+    /// Load a catalog from a SQL DDL file
+    ///
+    /// # Errors
+    ///
+    /// Returns `CatalogError::ConfigurationError` if the file cannot be read.
+    /// Individual malformed statements do not produce an error.
+    pub fn from_sql_file(path: impl AsRef<Path>, dialect: Dialect) -> CatalogResult<Self> {
+        Self::from_sql_files(&[path], dialect)
+    }
+
+    /// Load a catalog from several SQL DDL files
+    ///
+    /// Entries are file paths or file-name patterns (see [`expand_schema_paths`]).
+    /// Files load in lexical path order, so `001_users.sql` precedes
+    /// `002_orders.sql`. Statements that reference a table defined in a later
+    /// file are retried once every file has loaded. A table redefined in a
+    /// later file replaces the earlier definition and records a
+    /// [`DdlWarningKind::DuplicateTable`] warning.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CatalogError::ConfigurationError` if no file matches or a
+    /// file cannot be read.
+    pub fn from_sql_files(patterns: &[impl AsRef<Path>], dialect: Dialect) -> CatalogResult<Self> {
+        let patterns: Vec<PathBuf> = patterns.iter().map(|p| p.as_ref().to_path_buf()).collect();
+        let paths = expand_schema_paths(&patterns)?;
+        if paths.is_empty() {
+            return Err(CatalogError::ConfigurationError(format!(
+                "No schema files match {}",
+                display_paths(&patterns)
+            )));
+        }
+
+        let mut sources = Vec::with_capacity(paths.len());
+        for path in &paths {
+            let sql = std::fs::read_to_string(path).map_err(|e| {
+                CatalogError::ConfigurationError(format!(
+                    "Failed to read schema file '{}': {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            sources.push((Some(path.clone()), sql));
+        }
+
+        let mut catalog = Self::load(&sources, dialect);
+        catalog.patterns = patterns;
+        catalog.paths = paths;
+        Ok(catalog)
+    }
+
+    /// Build a catalog from SQL DDL text
+    pub fn from_sql(sql: &str, dialect: Dialect) -> Self {
+        Self::load(&[(None, sql.to_string())], dialect)
+    }
+
+    /// Apply every source in order, then retry statements with forward references
+    fn load(sources: &[(Option<PathBuf>, String)], dialect: Dialect) -> Self {
+        let mut catalog = Self {
+            patterns: Vec::new(),
+            paths: Vec::new(),
+            dialect,
+            tables: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let statements: Vec<Vec<ParsedStatement>> = sources
+            .iter()
+            .map(|(_, sql)| parse_statements(sql, dialect))
+            .collect();
+        // Source index that last defined each (schema, name)
+        let mut origins: HashMap<(String, String), usize> = HashMap::new();
+        let mut deferred = Vec::new();
+
+        for (source, statements) in statements.iter().enumerate() {
+            let file = &sources[source].0;
+            for (index, statement) in statements.iter().enumerate() {
+                let statement = match statement {
+                    Ok(Some(statement)) => statement,
+                    Ok(None) => continue,
+                    Err(message) => {
+                        catalog.skip_statement(file, index, message.clone());
+                        continue;
+                    }
+                };
+                match catalog.apply_statement(statement, true) {
+                    Ok(defined) => {
+                        catalog.record_origin(&mut origins, defined, file, source, index)
+                    }
+                    Err(StatementError::Unresolved(_)) => deferred.push((source, index, statement)),
+                    Err(StatementError::Malformed(message)) => {
+                        catalog.skip_statement(file, index, message)
+                    }
+                }
+            }
+        }
+
+        for (source, index, statement) in deferred {
+            let file = &sources[source].0;
+            match catalog.apply_statement(statement, false) {
+                Ok(defined) => catalog.record_origin(&mut origins, defined, file, source, index),
+                Err(e) => catalog.skip_statement(file, index, e.into_message()),
+            }
+        }
+
+        catalog.resolve_primary_key_references(
+            statements
+                .iter()
+                .flatten()
+                .filter_map(|statement| statement.as_ref().ok().and_then(Option::as_ref)),
+        );
+
+        // Retried statements report in file order like everything else
+        let position = |file: &Option<PathBuf>| sources.iter().position(|(f, _)| f == file);
+        catalog
+            .warnings
+            .sort_by_key(|w| (position(&w.file), w.statement_index));
+
+        for (file, _) in sources.iter() {
+            if let Some(file) = file {
+                tracing::info!(
+                    "Loaded schema file {} ({} statements skipped)",
+                    file.display(),
+                    catalog.error_count(file)
+                );
+            }
+        }
+
+        catalog
+    }
+
+    /// Note where a table was defined, warning when an earlier file defined it too
+    fn record_origin(
+        &mut self,
+        origins: &mut HashMap<(String, String), usize>,
+        defined: Option<(String, String)>,
+        file: &Option<PathBuf>,
+        source: usize,
+        index: usize,
+    ) {
+        let Some(key) = defined else {
+            return;
+        };
+        if let Some(previous) = origins.insert(key.clone(), source)
+            && previous != source
+        {
+            let message = format!(
+                "Table {} is defined in more than one schema file; using the later definition",
+                key.1
+            );
+            tracing::warn!("{}", message);
+            self.warnings.push(DdlWarning {
+                file: file.clone(),
+                statement_index: index + 1,
+                kind: DdlWarningKind::DuplicateTable,
+                message,
+            });
+        }
+    }
+
+    fn skip_statement(&mut self, file: &Option<PathBuf>, index: usize, message: String) {
+        tracing::warn!("Skipping DDL statement #{}: {}", index + 1, message);
+        self.warnings.push(DdlWarning {
+            file: file.clone(),
+            statement_index: index + 1,
+            kind: DdlWarningKind::Skipped,
+            message,
+        });
+    }
+
+    /// Re-read the source files, replacing all tables
+    ///
+    /// Used when a file watcher reports that a schema file changed. Patterns
+    /// are expanded again, so newly added files are picked up.
+    /// Catalogs built with [`DdlCatalog::from_sql`] have nothing to reload.
+    pub fn reload(&self) -> CatalogResult<Self> {
+        if self.patterns.is_empty() {
+            return Ok(self.clone());
+        }
+        Self::from_sql_files(&self.patterns, self.dialect)
+    }
+
+    /// First source file path, if loaded from files
+    pub fn path(&self) -> Option<&Path> {
+        self.paths.first().map(PathBuf::as_path)
+    }
+
+    /// All loaded source files, in load order
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Whether a change to `path` affects this catalog
+    ///
+    /// True for loaded files and for new files matching a configured pattern.
+    pub fn watches(&self, path: &Path) -> bool {
+        self.paths.iter().any(|p| p == path)
+            || self.patterns.iter().any(|p| pattern_matches(p, path))
+    }
+
+    /// Dialect the files were read with
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// Problems found while loading
+    pub fn warnings(&self) -> &[DdlWarning] {
+        &self.warnings
+    }
+
+    /// Number of statements skipped in each loaded file, in load order
+    pub fn error_counts(&self) -> Vec<(&Path, usize)> {
+        self.paths
+            .iter()
+            .map(|path| (path.as_path(), self.error_count(path)))
+            .collect()
+    }
+
+    fn error_count(&self, path: &Path) -> usize {
+        self.warnings
+            .iter()
+            .filter(|w| w.kind == DdlWarningKind::Skipped && w.file.as_deref() == Some(path))
+            .count()
+    }
+
+    /// Default schema for unqualified table names
+    fn default_schema(&self) -> &'static str {
+        match self.dialect.family() {
+            DialectFamily::PostgreSQL => "public",
+            _ => "",
+        }
+    }
+
+    /// Find a table by (possibly schema-qualified, possibly quoted) name
+    fn find_table(&self, table: &str) -> Option<&TableMetadata> {
+        let (schema, name) = split_qualified(table);
+        let schema_matches = |t: &TableMetadata| schema.as_deref().is_none_or(|s| t.schema == s);
+
+        self.tables
+            .iter()
+            .find(|t| t.name == name && schema_matches(t))
+            .or_else(|| {
+                self.tables
+                    .iter()
+                    .find(|t| t.name.eq_ignore_ascii_case(&name) && schema_matches(t))
+            })
+    }
+
+    fn find_table_mut(&mut self, schema: &str, name: &str) -> Option<&mut TableMetadata> {
+        self.tables
+            .iter_mut()
+            .find(|t| t.schema == schema && t.name == name)
+    }
+
+    /// Insert or replace a table (later definitions win, like `CREATE OR REPLACE`)
+    fn upsert_table(&mut self, table: TableMetadata) {
+        match self.find_table_mut(&table.schema, &table.name) {
+            Some(existing) => *existing = table,
+            None => self.tables.push(table),
+        }
+    }
+
+    /// Schema of a statement's object, or the dialect's default schema
+    fn schema_or_default(&self, schema: &Option<String>) -> String {
+        schema
+            .clone()
+            .unwrap_or_else(|| self.default_schema().to_string())
+    }
+
+    /// Apply one statement, returning the table or view it defined
+    ///
+    /// With `defer_unresolved`, statements that depend on an unknown table
+    /// fail with [`StatementError::Unresolved`] instead of applying partially.
+    fn apply_statement(
+        &mut self,
+        statement: &DdlStatement,
+        defer_unresolved: bool,
+    ) -> Result<Option<(String, String)>, StatementError> {
+        let table = match statement {
+            DdlStatement::CreateTable(create) => self.create_table(create)?,
+            DdlStatement::CreateView(view) => self.create_view(view, defer_unresolved)?,
+            DdlStatement::AlterTable(alter) => {
+                self.alter_table(alter)?;
+                return Ok(None);
+            }
+        };
+
+        let key = (table.schema.clone(), table.name.clone());
+        self.upsert_table(table);
+        Ok(Some(key))
+    }
+
+    fn create_table(&self, create: &CreateTableStatement) -> Result<TableMetadata, String> {
+        if create.columns.is_empty() {
+            return Err(format!("CREATE TABLE {} defines no columns", create.name));
+        }
+
+        let columns = create
+            .columns
+            .iter()
+            .map(|column| table_column(column, &create.name))
+            .collect();
+        let mut table = TableMetadata::new(&create.name, self.schema_or_default(&create.schema))
+            .with_columns(columns);
+        if create.temporary {
+            table.table_type = TableType::Temporary;
+        }
+
+        let (primary_keys, foreign_keys) = key_constraints(&create.constraints);
+        apply_keys(&mut table, &primary_keys, &foreign_keys);
+        table.comment = create.comment.clone();
+        Ok(table)
+    }
+
+    fn create_view(
+        &self,
+        view: &CreateViewStatement,
+        defer_unresolved: bool,
+    ) -> Result<TableMetadata, StatementError> {
+        let select = match &view.query.body {
+            SetOp::Select(select) if !select.projection.is_empty() => select,
+            _ => return Err(format!("CREATE VIEW {} has no SELECT list", view.name).into()),
+        };
+
+        let source_refs: Vec<&TableRef> = select
+            .from
+            .iter()
+            .flat_map(|table| std::iter::once(table).chain(table.joins.iter().map(|j| &j.table)))
+            .filter(|table| !table.name.is_empty())
+            .collect();
+        if defer_unresolved
+            && let Some(missing) = source_refs
+                .iter()
+                .find(|source| self.find_table(&source.name).is_none())
+        {
+            return Err(StatementError::Unresolved(format!(
+                "CREATE VIEW {} references unknown table {}",
+                view.name, missing.name
+            )));
+        }
+        let sources: Vec<(&TableMetadata, Option<&str>)> = source_refs
+            .into_iter()
+            .filter_map(|source| {
+                self.find_table(&source.name)
+                    .map(|table| (table, source.alias.as_deref()))
+            })
+            .collect();
+
+        let mut columns = Vec::new();
+        for item in &select.projection {
+            let (name, source_column) = match item {
+                SelectItem::Wildcard => {
+                    for (source, _) in &sources {
+                        columns.extend(source.columns.iter().map(view_column));
+                    }
+                    continue;
+                }
+                SelectItem::QualifiedWildcard(qualifier) => {
+                    for (source, alias) in &sources {
+                        if same_name(&source.name, qualifier)
+                            || alias.is_some_and(|alias| same_name(alias, qualifier))
+                        {
+                            columns.extend(source.columns.iter().map(view_column));
+                        }
+                    }
+                    continue;
+                }
+                SelectItem::UnnamedExpr(Expr::Column(column)) => {
+                    (column.column.clone(), Some(&column.column))
+                }
+                // Unnamed computed columns get no usable name
+                SelectItem::UnnamedExpr(_) => continue,
+                SelectItem::AliasedExpr { expr, alias } => {
+                    let source_column = match expr {
+                        Expr::Column(column) => Some(&column.column),
+                        _ => None,
+                    };
+                    (alias.clone(), source_column)
+                }
+            };
+
+            let source = source_column.and_then(|column| {
+                sources
+                    .iter()
+                    .find_map(|(table, _)| find_column(table, column))
+            });
+            columns.push(match source {
+                Some(source) => ColumnMetadata {
+                    name,
+                    ..view_column(source)
+                },
+                None => ColumnMetadata::new(name, DataType::Other("unknown".to_string()))
+                    .with_nullable(true),
+            });
+        }
+
+        for (column, name) in columns.iter_mut().zip(&view.columns) {
+            column.name = name.clone();
+        }
+
+        let table_type = if view.materialized {
+            TableType::MaterializedView
+        } else {
+            TableType::View
+        };
+        Ok(
+            TableMetadata::new(&view.name, self.schema_or_default(&view.schema))
+                .with_columns(columns)
+                .with_type(table_type),
+        )
+    }
+
+    /// Point bare `REFERENCES t` foreign keys at t's primary key
+    ///
+    /// Runs once all `statements` are applied, since the primary key may be
+    /// declared after the reference. Foreign keys to a table without a
+    /// primary key are left unresolved.
+    fn resolve_primary_key_references<'a>(
+        &mut self,
+        statements: impl IntoIterator<Item = &'a DdlStatement>,
+    ) {
+        for statement in statements {
+            let Some((schema, table, columns)) = bare_references(statement) else {
+                continue;
+            };
+            let schema = self.schema_or_default(schema);
+            for (column, index) in columns {
+                // A later definition may have replaced the column
+                let Some(target) = self
+                    .find_table_mut(&schema, table)
+                    .and_then(|t| t.columns.iter().find(|c| c.name == column))
+                    .and_then(|c| c.references.as_ref())
+                    .filter(|reference| reference.column.is_empty())
+                    .map(|reference| reference.table.clone())
+                else {
+                    continue;
+                };
+                let key = self.find_table(&target).and_then(|t| {
+                    t.columns
+                        .iter()
+                        .filter(|c| c.is_primary_key)
+                        .nth(index)
+                        .map(|c| c.name.clone())
+                });
+                if let Some(column) = self
+                    .find_table_mut(&schema, table)
+                    .and_then(|t| t.columns.iter_mut().find(|c| c.name == column))
+                {
+                    column.references = key.map(|key| TableReference {
+                        table: target,
+                        column: key,
+                    });
+                }
+            }
+        }
+    }
+
+    fn alter_table(&mut self, alter: &AlterTableStatement) -> Result<(), StatementError> {
+        let schema = self.schema_or_default(&alter.schema);
+        let Some(table) = self.find_table_mut(&schema, &alter.name) else {
+            return Err(StatementError::Unresolved(format!(
+                "ALTER TABLE references unknown table {}",
+                alter.name
+            )));
+        };
+
+        for action in &alter.actions {
+            match action {
+                AlterTableAction::AddColumn(column) => {
+                    table.columns.push(table_column(column, &alter.name))
+                }
+                AlterTableAction::AddConstraint(constraint) => {
+                    let (primary_keys, foreign_keys) =
+                        key_constraints(std::slice::from_ref(constraint));
+                    apply_keys(table, &primary_keys, &foreign_keys);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Catalog for DdlCatalog {
+    async fn list_tables(&self) -> CatalogResult<Vec<TableMetadata>> {
+        Ok(self.tables.clone())
+    }
+
+    async fn get_columns(&self, table: &str) -> CatalogResult<Vec<ColumnMetadata>> {
+        self.find_table(table)
+            .map(|t| t.columns.clone())
+            .ok_or_else(|| {
+                CatalogError::TableNotFound(table.to_string(), self.default_schema().to_string())
+            })
+    }
+
+    async fn list_functions(&self) -> CatalogResult<Vec<FunctionMetadata>> {
+        // Function bodies in dumps are not analyzed
+        Ok(vec![])
+    }
+}
+
+/// Mark primary and foreign key columns declared as table constraints
+fn apply_keys(
+    table: &mut TableMetadata,
+    primary_keys: &[String],
+    foreign_keys: &[(Vec<String>, TableReference)],
+) {
+    for column in &mut table.columns {
+        if primary_keys.iter().any(|pk| pk == &column.name) {
+            column.is_primary_key = true;
+            column.nullable = false;
+        }
+        for (columns, reference) in foreign_keys {
+            if let Some(index) = columns.iter().position(|c| c == &column.name) {
+                column.is_foreign_key = true;
+                column.references = Some(TableReference {
+                    table: reference.table.clone(),
+                    // Composite keys reference columns positionally
+                    column: reference
+                        .column
+                        .split(',')
+                        .nth(index)
+                        .unwrap_or(&reference.column)
+                        .to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// Copy a source column into a view, dropping key flags
+fn view_column(source: &ColumnMetadata) -> ColumnMetadata {
+    ColumnMetadata {
+        is_primary_key: false,
+        is_foreign_key: false,
+        references: None,
+        ..source.clone()
+    }
+}
+
+/// Split "schema.table" (either part optionally quoted) into its parts
+fn split_qualified(name: &str) -> (Option<String>, String) {
+    let strip = |s: &str| s.trim_matches(|c| c == '"' || c == '`').to_string();
+    match name.rsplit_once('.') {
+        Some((schema, table)) => (Some(strip(schema)), strip(table)),
+        None => (None, strip(name)),
+    }
+}
+
+/// Column metadata for a lowered column definition of `table`
+fn table_column(column: &ColumnDef, table: &str) -> ColumnMetadata {
+    let mut metadata =
+        ColumnMetadata::new(&column.name, DataType::parse(&column.data_type)).with_nullable(true);
+    if SERIAL_TYPES
+        .iter()
+        .any(|serial| column.data_type.eq_ignore_ascii_case(serial))
+    {
+        // The implicit sequence PostgreSQL creates for a serial column
+        metadata.default_value = Some(format!(
+            "nextval('{}_{}_seq'::regclass)",
+            table, column.name
+        ));
+        metadata.nullable = false;
+    }
+
+    if !column.nullable {
+        metadata.nullable = false;
+    }
+    if column.primary_key {
+        metadata.is_primary_key = true;
+        metadata.nullable = false;
+    }
+    if let Some(default) = &column.default {
+        metadata.default_value = Some(default.clone());
+    }
+    metadata.comment = column.comment.clone();
+    if let Some(target) = &column.references {
+        metadata.is_foreign_key = true;
+        metadata.references = Some(table_reference(target));
+    }
+    metadata
+}
+
+const SERIAL_TYPES: &[&str] = &[
+    "SERIAL",
+    "BIGSERIAL",
+    "SMALLSERIAL",
+    "SERIAL4",
+    "SERIAL8",
+    "SERIAL2",
+];
+
+/// Reference for a foreign key target
+///
+/// A bare `REFERENCES t` gets an empty column until
+/// [`DdlCatalog::resolve_primary_key_references`] finds t's primary key.
+fn table_reference(target: &ForeignKeyTarget) -> TableReference {
+    TableReference {
+        table: target.table.clone(),
+        column: target.columns.join(","),
+    }
+}
+
+/// Schema and name of a table, and its columns with a bare `REFERENCES t`
+type BareReferences<'a> = (&'a Option<String>, &'a str, Vec<(&'a str, usize)>);
+
+/// Columns of a statement's table declared with a bare `REFERENCES t`
+///
+/// Each column comes with its position in the foreign key, which picks the
+/// matching primary key column.
+fn bare_references(statement: &DdlStatement) -> Option<BareReferences<'_>> {
+    let (schema, name, definitions, constraints): (_, _, Vec<_>, Vec<_>) = match statement {
+        DdlStatement::CreateTable(create) => (
+            &create.schema,
+            &create.name,
+            create.columns.iter().collect(),
+            create.constraints.iter().collect(),
+        ),
+        DdlStatement::AlterTable(alter) => (
+            &alter.schema,
+            &alter.name,
+            alter
+                .actions
+                .iter()
+                .filter_map(|action| match action {
+                    AlterTableAction::AddColumn(column) => Some(column),
+                    AlterTableAction::AddConstraint(_) => None,
+                })
+                .collect(),
+            alter
+                .actions
+                .iter()
+                .filter_map(|action| match action {
+                    AlterTableAction::AddConstraint(constraint) => Some(constraint),
+                    AlterTableAction::AddColumn(_) => None,
+                })
+                .collect(),
+        ),
+        DdlStatement::CreateView(_) => return None,
+    };
+
+    let is_bare = |target: &ForeignKeyTarget| target.columns.is_empty();
+    let mut columns: Vec<(&str, usize)> = definitions
+        .into_iter()
+        .filter(|column| column.references.as_ref().is_some_and(is_bare))
+        .map(|column| (column.name.as_str(), 0))
+        .collect();
+    for constraint in constraints {
+        if let TableConstraint::ForeignKey {
+            columns: keys,
+            references,
+        } = constraint
+            && is_bare(references)
+        {
+            columns.extend(keys.iter().enumerate().map(|(i, c)| (c.as_str(), i)));
+        }
+    }
+    Some((schema, name.as_str(), columns))
+}
+
+/// Primary key columns and foreign keys declared by table constraints
+fn key_constraints(
+    constraints: &[TableConstraint],
+) -> (Vec<String>, Vec<(Vec<String>, TableReference)>) {
+    let mut primary_keys = Vec::new();
+    let mut foreign_keys = Vec::new();
+    for constraint in constraints {
+        match constraint {
+            TableConstraint::PrimaryKey(columns) => primary_keys.extend(columns.iter().cloned()),
+            TableConstraint::ForeignKey {
+                columns,
+                references,
+            } => foreign_keys.push((columns.clone(), table_reference(references))),
+            TableConstraint::Unique(_) | TableConstraint::Index(_) => {}
+        }
+    }
+    (primary_keys, foreign_keys)
+}
+
+/// Find a column by exact name, then regardless of case
+fn find_column<'a>(table: &'a TableMetadata, name: &str) -> Option<&'a ColumnMetadata> {
+    table.get_column(name).or_else(|| {
+        table
+            .columns
+            .iter()
+            .find(|column| column.name.eq_ignore_ascii_case(name))
+    })
+}
+
+/// Whether two identifiers, either possibly quoted, name the same object
+fn same_name(a: &str, b: &str) -> bool {
+    IdentifierComparer::unquote(a).eq_ignore_ascii_case(&IdentifierComparer::unquote(b))
+}
+
+// =============================================================================
+// Parsing
+// =============================================================================
+
+/// A top-level statement of a schema file: lowered if it is DDL the catalog
+/// reads, `None` if it is something else, or the reason it was skipped
+type ParsedStatement = Result<Option<DdlStatement>, String>;
+
+/// Parse `sql` with the dialect's grammar and lower its DDL statements
+fn parse_statements(sql: &str, dialect: Dialect) -> Vec<ParsedStatement> {
+    let mut parser = Parser::new();
+    let language = match dialect.try_language() {
+        Ok(language) => language,
+        Err(e) => return vec![Err(e.to_string())],
+    };
+    if let Err(e) = parser.set_language(language) {
+        return vec![Err(format!(
+            "Failed to load the {:?} grammar: {}",
+            dialect, e
+        ))];
+    }
+    let Some(tree) = parser.parse(sql, None) else {
+        return vec![Err("Parser returned no syntax tree".to_string())];
+    };
+
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    root.named_children(&mut cursor)
+        .filter(|node| !node.is_extra())
+        .map(|node| parse_statement(node, sql, dialect))
+        .collect()
+}
+
+fn parse_statement(node: Node, sql: &str, dialect: Dialect) -> ParsedStatement {
+    // `statement` wraps the concrete statement node
+    let statement = match node.kind() {
+        "statement" => node.named_child(0).unwrap_or(node),
+        _ => node,
+    };
+    let is_ddl = matches!(
+        statement.kind(),
+        "create_table_statement" | "create_view_statement" | "alter_table_statement"
+    );
+
+    if statement.has_error() {
+        // Only broken statements that would have defined a table matter
+        return if is_ddl || defines_table(&leading_words(statement, sql)) {
+            Err(format!("Could not parse `{}`", snippet(statement, sql)))
+        } else {
+            Ok(None)
+        };
+    }
+    if !is_ddl {
+        return Ok(None);
+    }
+
+    let cst = TreeSitterNode::new(statement, sql);
+    let mut ctx = LoweringContext::new(dialect);
+    let lowered = match dialect.family() {
+        DialectFamily::PostgreSQL => PostgreSQLLowering.lower_ddl(&mut ctx, &cst),
+        DialectFamily::MySQL => MySQLLowering.lower_ddl(&mut ctx, &cst),
+    };
+    lowered.map(Some).map_err(|e| e.to_string())
+}
+
+/// The first few tokens of `node`, uppercased
+fn leading_words(node: Node, sql: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut cursor = node.walk();
+    let mut descended = true;
+    while words.len() < 8 {
+        let current = cursor.node();
+        if descended
+            && current.child_count() == 0
+            && !current.is_extra()
+            && let Some(text) = sql.get(current.start_byte()..current.end_byte())
+        {
+            words.push(text.to_uppercase());
+        }
+        if descended && cursor.goto_first_child() {
+            continue;
+        }
+        if cursor.goto_next_sibling() {
+            descended = true;
+            continue;
+        }
+        if !cursor.goto_parent() || cursor.node() == node {
+            break;
+        }
+        descended = false;
+    }
+    words
+}
+
+/// Whether a statement starting with `words` creates a table or view, or
+/// adds columns or keys to one
+fn defines_table(words: &[String]) -> bool {
+    match words {
+        [first, rest @ ..] if first == "CREATE" => rest
+            .iter()
+            .take(4)
+            .any(|word| word == "TABLE" || word == "VIEW"),
+        [first, second, rest @ ..] if first == "ALTER" && second == "TABLE" => {
+            rest.iter().any(|word| word == "ADD")
+        }
+        _ => false,
+    }
+}
+
+/// Single-line start of a statement for warnings
+fn snippet(node: Node, sql: &str) -> String {
+    let text = sql.get(node.start_byte()..node.end_byte()).unwrap_or("");
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match line.char_indices().nth(60) {
+        Some((end, _)) => format!("{}...", &line[..end]),
+        None => line,
+    }
+}
+
+// =============================================================================
+// Schema file discovery
+// =============================================================================
+
+/// Expand schema paths into the files to load, in lexical order
+///
+/// Each entry is either a file path or a pattern whose file name contains
+/// `*` or `?` (e.g. `db/schema/*.sql`); directory components are literal.
+/// Plain paths are kept even if missing so reading them reports the error.
+///
+/// # Errors
+///
+/// Returns `CatalogError::ConfigurationError` if a pattern's directory
+/// cannot be read.
+pub fn expand_schema_paths(patterns: &[impl AsRef<Path>]) -> CatalogResult<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+
+    for pattern in patterns {
+        let pattern = pattern.as_ref();
+        if !is_pattern(pattern) {
+            paths.push(pattern.to_path_buf());
+            continue;
+        }
+
+        let dir = match pattern.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let entries = std::fs::read_dir(dir).map_err(|e| {
+            CatalogError::ConfigurationError(format!(
+                "Failed to read schema directory '{}': {}",
+                dir.display(),
+                e
+            ))
+        })?;
+        for entry in entries.flatten() {
+            let path = dir.join(entry.file_name());
+            if path.is_file() && pattern_matches(pattern, &path) {
+                paths.push(path);
+            }
+        }
+    }
+
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+fn is_pattern(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.contains(['*', '?']))
+}
+
+/// Whether `path` is named by `pattern` (a plain path or a file-name pattern)
+fn pattern_matches(pattern: &Path, path: &Path) -> bool {
+    if !is_pattern(pattern) {
+        return pattern == path;
+    }
+
+    let dir = |p: &Path| {
+        p.parent()
+            .filter(|d| !d.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf()
+    };
+    let name = |p: &Path| p.file_name().and_then(|n| n.to_str()).map(str::to_string);
+
+    dir(pattern) == dir(path)
+        && match (name(pattern), name(path)) {
+            (Some(pattern), Some(name)) => wildcard_match(&pattern, &name),
+            _ => false,
+        }
+}
+
+/// Match `*` (any run of characters) and `?` (one character)
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the name position it was tried at
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| format!("'{}'", p.display()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ddl_catalog_column_constraints() {
+        let catalog = DdlCatalog::from_sql(
+            "CREATE TABLE users (
+                id INT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+                email VARCHAR(255) NOT NULL COMMENT 'login',
+                score DECIMAL(10, 2) DEFAULT 0.00
+            ) ENGINE=InnoDB COMMENT='accounts';",
+            Dialect::MySQL,
+        );
+
+        let columns = catalog.get_columns("users").await.unwrap();
+        assert_eq!(columns.len(), 3);
+        assert!(columns[0].is_primary_key);
+        assert_eq!(columns[0].default_value.as_deref(), Some("AUTO_INCREMENT"));
+        assert_eq!(columns[1].data_type, DataType::Varchar(Some(255)));
+        assert_eq!(columns[1].comment.as_deref(), Some("login"));
+        assert!(!columns[1].nullable);
+        assert!(columns[2].nullable);
+        assert_eq!(columns[2].default_value.as_deref(), Some("0.00"));
+
+        let tables = catalog.list_tables().await.unwrap();
+        assert_eq!(tables[0].comment.as_deref(), Some("accounts"));
+    }
+
+    #[test]
+    fn test_ddl_catalog_skips_malformed_statement() {
+        let catalog = DdlCatalog::from_sql(
+            "CREATE TABLE broken;\nCREATE TABLE ok (id integer);",
+            Dialect::PostgreSQL,
+        );
+        assert_eq!(catalog.tables.len(), 1);
+        assert_eq!(catalog.warnings().len(), 1);
+        assert_eq!(catalog.warnings()[0].statement_index, 1);
+    }
+
+    #[test]
+    fn test_ddl_catalog_applies_lowered_statements() {
+        let mut catalog = DdlCatalog::load(&[], Dialect::PostgreSQL);
+        let mut id = ColumnDef::new("id", "bigserial");
+        id.primary_key = true;
+        let mut user_id = ColumnDef::new("user_id", "integer");
+        user_id.references = Some(ForeignKeyTarget {
+            table: "users".to_string(),
+            columns: Vec::new(),
+        });
+        let create = |name: &str, columns: Vec<ColumnDef>| {
+            DdlStatement::CreateTable(CreateTableStatement {
+                schema: None,
+                name: name.to_string(),
+                temporary: false,
+                columns,
+                constraints: Vec::new(),
+                comment: None,
+            })
+        };
+
+        let alter = DdlStatement::AlterTable(AlterTableStatement {
+            schema: Some("public".to_string()),
+            name: "orders".to_string(),
+            actions: vec![AlterTableAction::AddConstraint(
+                TableConstraint::PrimaryKey(vec!["order_id".to_string()]),
+            )],
+        });
+        assert!(matches!(
+            catalog.apply_statement(&alter, true),
+            Err(StatementError::Unresolved(_))
+        ));
+
+        let users = create("users", vec![id]);
+        let orders = create(
+            "orders",
+            vec![ColumnDef::new("order_id", "integer"), user_id],
+        );
+        for statement in [&users, &orders, &alter] {
+            catalog.apply_statement(statement, true).ok().unwrap();
+        }
+        catalog.resolve_primary_key_references([&users, &orders, &alter]);
+
+        let users = catalog.find_table("public.users").unwrap();
+        assert_eq!(users.columns[0].sequence(), Some("users_id_seq"));
+        assert!(users.columns[0].is_primary_key);
+        let orders = catalog.find_table("orders").unwrap();
+        assert!(orders.columns[0].is_primary_key);
+        assert!(!orders.columns[0].nullable);
+        assert_eq!(
+            orders.columns[1].references,
+            Some(TableReference {
+                table: "users".to_string(),
+                column: "id".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_ddl_catalog_bare_references_use_primary_key() {
+        let mut catalog = DdlCatalog::load(&[], Dialect::PostgreSQL);
+        let bare = |table: &str| ForeignKeyTarget {
+            table: table.to_string(),
+            columns: Vec::new(),
+        };
+        let create = |name: &str, columns: Vec<ColumnDef>, constraints| {
+            DdlStatement::CreateTable(CreateTableStatement {
+                schema: None,
+                name: name.to_string(),
+                temporary: false,
+                columns,
+                constraints,
+                comment: None,
+            })
+        };
+
+        let mut account = ColumnDef::new("account", "integer");
+        account.references = Some(bare("accounts"));
+        let mut log = ColumnDef::new("log", "integer");
+        log.references = Some(bare("audit_log"));
+        let statements = [
+            create(
+                "accounts",
+                vec![
+                    ColumnDef::new("id", "integer"),
+                    ColumnDef::new("account_no", "text"),
+                ],
+                Vec::new(),
+            ),
+            create(
+                "ledger",
+                vec![
+                    ColumnDef::new("region", "text"),
+                    ColumnDef::new("branch", "text"),
+                ],
+                vec![
+                    TableConstraint::PrimaryKey(vec!["region".into(), "branch".into()]),
+                    TableConstraint::ForeignKey {
+                        columns: vec!["region".into(), "branch".into()],
+                        references: bare("branches"),
+                    },
+                ],
+            ),
+            create(
+                "branches",
+                vec![
+                    ColumnDef::new("country", "text"),
+                    ColumnDef::new("code", "text"),
+                ],
+                vec![TableConstraint::PrimaryKey(vec![
+                    "country".into(),
+                    "code".into(),
+                ])],
+            ),
+            create("entries", vec![account, log], Vec::new()),
+            create(
+                "audit_log",
+                vec![ColumnDef::new("id", "integer")],
+                Vec::new(),
+            ),
+            // The primary key is declared after the reference to it
+            DdlStatement::AlterTable(AlterTableStatement {
+                schema: None,
+                name: "accounts".to_string(),
+                actions: vec![AlterTableAction::AddConstraint(
+                    TableConstraint::PrimaryKey(vec!["account_no".to_string()]),
+                )],
+            }),
+        ];
+        for statement in &statements {
+            catalog.apply_statement(statement, false).ok().unwrap();
+        }
+        catalog.resolve_primary_key_references(&statements);
+
+        let reference = |table: &str, column: usize| {
+            catalog.find_table(table).unwrap().columns[column]
+                .references
+                .clone()
+        };
+        let to = |table: &str, column: &str| {
+            Some(TableReference {
+                table: table.to_string(),
+                column: column.to_string(),
+            })
+        };
+        assert_eq!(reference("entries", 0), to("accounts", "account_no"));
+        assert_eq!(reference("ledger", 0), to("branches", "country"));
+        assert_eq!(reference("ledger", 1), to("branches", "code"));
+        // audit_log has no primary key to point at
+        assert_eq!(reference("entries", 1), None);
+        assert!(catalog.find_table("entries").unwrap().columns[1].is_foreign_key);
+    }
+
+    #[test]
+    fn test_defines_table() {
+        let words =
+            |sql: &str| -> Vec<String> { sql.split_whitespace().map(str::to_string).collect() };
+        assert!(defines_table(&words("CREATE TEMPORARY TABLE t")));
+        assert!(defines_table(&words("CREATE OR REPLACE VIEW v")));
+        assert!(defines_table(&words("ALTER TABLE ONLY t ADD")));
+        assert!(!defines_table(&words("ALTER TABLE t OWNER TO")));
+        assert!(!defines_table(&words("CREATE INDEX idx ON t")));
+        assert!(!defines_table(&words("SET NAMES utf8mb4")));
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.sql", "001_users.sql"));
+        assert!(wildcard_match("0??_*.sql", "002_orders.sql"));
+        assert!(!wildcard_match("*.sql", "schema.sql.bak"));
+        assert!(pattern_matches(
+            Path::new("db/schema/*.sql"),
+            Path::new("db/schema/003_items.sql")
+        ));
+        assert!(!pattern_matches(
+            Path::new("db/schema/*.sql"),
+            Path::new("db/other/003_items.sql")
+        ));
+    }
+
+    #[test]
+    fn test_ddl_catalog_defers_forward_references() {
+        let catalog = DdlCatalog::from_sql(
+            "CREATE VIEW big_orders AS SELECT o.id FROM orders o;
+             ALTER TABLE orders ADD PRIMARY KEY (id);
+             CREATE TABLE orders (id integer, total numeric);",
+            Dialect::PostgreSQL,
+        );
+        assert!(catalog.warnings().is_empty());
+        let view = catalog.find_table("big_orders").unwrap();
+        assert_eq!(view.columns[0].data_type, DataType::Integer);
+        assert!(catalog.find_table("orders").unwrap().columns[0].is_primary_key);
+    }
+
+    #[test]
+    fn test_ddl_catalog_auto_generated_columns() {
+        let catalog = DdlCatalog::from_sql(
+            "CREATE TABLE users (id bigserial PRIMARY KEY, code int GENERATED BY DEFAULT AS IDENTITY, name text);",
+            Dialect::PostgreSQL,
+        );
+        let columns = &catalog.tables[0].columns;
+        assert_eq!(columns[0].sequence(), Some("users_id_seq"));
+        assert_eq!(columns[0].data_type, DataType::BigInt);
+        assert_eq!(
+            columns[1].default_value.as_deref(),
+            Some("GENERATED BY DEFAULT AS IDENTITY")
+        );
+        assert!(columns[0].is_auto_generated());
+        assert!(columns[1].is_auto_generated());
+        assert!(!columns[2].is_auto_generated());
+
+        let catalog = DdlCatalog::from_sql(
+            "CREATE TABLE orders (id INT AUTO_INCREMENT PRIMARY KEY);",
+            Dialect::MySQL,
+        );
+        assert!(catalog.tables[0].columns[0].is_auto_generated());
+        assert_eq!(catalog.tables[0].columns[0].sequence(), None);
+    }
+
+    #[test]
+    fn test_ddl_catalog_postgres_case_folding() {
+        let catalog = DdlCatalog::from_sql(
+            r#"CREATE TABLE Public.Users (ID serial, "DisplayName" text);"#,
+            Dialect::PostgreSQL,
+        );
+        let table = &catalog.tables[0];
+        assert_eq!(table.schema, "public");
+        assert_eq!(table.name, "users");
+        assert_eq!(table.columns[0].name, "id");
+        assert_eq!(table.columns[1].name, "DisplayName");
+    }
+}
@@ -6,6 +6,13 @@
 //! # Error types for Catalog operations
 //!
 //! This module defines the error types used throughout the catalog layer.
+//!
+//! Errors reported by database drivers are classified with
+//! [`CatalogError::from_driver`] so that the server can tell the user what to
+//! fix: wrong credentials, an unreachable host, a missing grant or an
+//! unsupported server version.
+
+use std::fmt;
 
 use serde::Serialize;
 use thiserror::Error;
@@ -13,6 +20,20 @@ use thiserror::Error;
 /// Result type alias for Catalog operations
 pub type CatalogResult<T> = Result<T, CatalogError>;
 
+/// Error message reported by a database driver
+///
+/// Kept as text so that [`CatalogError`] stays cloneable and serializable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DriverError(pub String);
+
+impl fmt::Display for DriverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for DriverError {}
+
 /// Errors that can occur during Catalog operations
 #[derive(Debug, Error, Clone, Serialize)]
 pub enum CatalogError {
@@ -20,13 +41,42 @@ pub enum CatalogError {
     #[error("Failed to connect to database: {0}")]
     ConnectionFailed(String),
 
-    /// Query execution failed
-    #[error("Query execution failed: {0}")]
-    QueryFailed(String),
+    /// The server rejected the credentials
+    #[error("Authentication failed: {0}")]
+    AuthFailed(String),
+
+    /// The database host could not be resolved or reached
+    #[error("Database server unreachable: {0}")]
+    NetworkUnreachable(String),
 
-    /// Query execution timed out
+    /// Connecting or querying timed out
     #[error("Query timed out after {0}s")]
-    QueryTimeout(u64),
+    Timeout(u64),
+
+    /// The user lacks a privilege on a catalog object
+    #[error("Permission denied for {object}")]
+    PermissionDenied {
+        /// Object the privilege is missing on, e.g. `information_schema`
+        object: String,
+    },
+
+    /// The server is older than the oldest supported version
+    #[error("Unsupported server version {found}, {required} or later is required")]
+    UnsupportedServerVersion {
+        /// Version reported by the server
+        found: String,
+        /// Oldest supported version
+        required: String,
+    },
+
+    /// Query execution failed
+    #[error("Query execution failed: {source}")]
+    QueryFailed {
+        /// Query that failed
+        sql: String,
+        /// Error reported by the driver
+        source: DriverError,
+    },
 
     /// Requested table was not found
     #[error("Table '{0}' not found in schema '{1}'")]
@@ -44,11 +94,322 @@ pub enum CatalogError {
     #[error("Invalid catalog configuration: {0}")]
     ConfigurationError(String),
 
-    /// Permission denied for requested operation
-    #[error("Permission denied: {0}")]
-    PermissionDenied(String),
-
     /// The specified feature is not supported by this catalog implementation
     #[error("Feature not supported: {0}")]
     NotSupported(String),
 }
+
+impl CatalogError {
+    /// Classify an error reported by a database driver
+    ///
+    /// `code` is the SQLSTATE (PostgreSQL) or error number (MySQL) when the
+    /// driver reports one, `sql` the failed query or `None` while connecting,
+    /// and `timeout_secs` the timeout reported when the server cancelled the
+    /// query. Errors that match no category become
+    /// [`CatalogError::ConnectionFailed`] or [`CatalogError::QueryFailed`].
+    pub fn from_driver(
+        code: Option<&str>,
+        message: &str,
+        sql: Option<&str>,
+        timeout_secs: u64,
+    ) -> Self {
+        match code {
+            Some("28P01" | "28000" | "1045") => return Self::AuthFailed(message.to_string()),
+            Some("42501" | "1044" | "1142" | "1143" | "1227") => {
+                return Self::PermissionDenied {
+                    object: denied_object(message),
+                };
+            }
+            Some("2002" | "2003" | "2005") => {
+                return Self::NetworkUnreachable(message.to_string());
+            }
+            Some("57014" | "3024") => return Self::Timeout(timeout_secs),
+            _ => {}
+        }
+
+        let lower = message.to_ascii_lowercase();
+        let mentions = |needles: &[&str]| needles.iter().any(|needle| lower.contains(needle));
+        if mentions(&[
+            "password authentication failed",
+            "using password:",
+            "authentication failed",
+        ]) {
+            Self::AuthFailed(message.to_string())
+        } else if mentions(&[
+            "permission denied",
+            "command denied",
+            "access denied for user",
+        ]) {
+            Self::PermissionDenied {
+                object: denied_object(message),
+            }
+        } else if mentions(&[
+            "connection refused",
+            "no route to host",
+            "network is unreachable",
+            "failed to lookup address",
+            "name or service not known",
+            "could not translate host name",
+            "unknown mysql server host",
+        ]) {
+            Self::NetworkUnreachable(message.to_string())
+        } else if mentions(&[
+            "timed out",
+            "statement timeout",
+            "maximum statement execution time",
+        ]) {
+            Self::Timeout(timeout_secs)
+        } else if let Some(sql) = sql {
+            Self::QueryFailed {
+                sql: sql.to_string(),
+                source: DriverError(message.to_string()),
+            }
+        } else {
+            Self::ConnectionFailed(message.to_string())
+        }
+    }
+
+    /// Classify an error returned by sqlx
+    #[cfg(any(feature = "mysql", feature = "postgresql"))]
+    pub fn from_sqlx(error: &sqlx::Error, sql: Option<&str>, timeout_secs: u64) -> Self {
+        match error {
+            sqlx::Error::PoolTimedOut => Self::Timeout(timeout_secs),
+            _ => {
+                let code = error.as_database_error().and_then(|e| e.code());
+                Self::from_driver(code.as_deref(), &error.to_string(), sql, timeout_secs)
+            }
+        }
+    }
+
+    /// Short stable name of the error category, e.g. `auth` or `permission`
+    pub fn category(&self) -> &'static str {
+        match self {
+            Self::ConnectionFailed(_) => "connection",
+            Self::AuthFailed(_) => "auth",
+            Self::NetworkUnreachable(_) => "network",
+            Self::Timeout(_) => "timeout",
+            Self::PermissionDenied { .. } => "permission",
+            Self::UnsupportedServerVersion { .. } => "version",
+            Self::QueryFailed { .. } => "query",
+            Self::TableNotFound(..) => "table",
+            Self::InvalidSchema(_) => "schema",
+            Self::SerializationError(_) => "serialization",
+            Self::ConfigurationError(_) => "configuration",
+            Self::NotSupported(_) => "unsupported",
+        }
+    }
+
+    /// What the user can do to fix the error, if anything
+    pub fn remediation(&self) -> Option<String> {
+        let hint = match self {
+            Self::AuthFailed(_) => {
+                "check the user name and password in the connection string".to_string()
+            }
+            Self::NetworkUnreachable(_) | Self::ConnectionFailed(_) => {
+                "check that the database server is running and that the host and port \
+                 in the connection string are reachable"
+                    .to_string()
+            }
+            Self::Timeout(_) => {
+                "the server answered too slowly; check its load or raise the query timeout"
+                    .to_string()
+            }
+            Self::PermissionDenied { object } => format!("grant SELECT on {object} to this user"),
+            Self::UnsupportedServerVersion { required, .. } => {
+                format!("connect to a server running version {required} or later")
+            }
+            Self::ConfigurationError(_) => {
+                "fix the connection settings in the server configuration".to_string()
+            }
+            Self::QueryFailed { .. }
+            | Self::TableNotFound(..)
+            | Self::InvalidSchema(_)
+            | Self::SerializationError(_)
+            | Self::NotSupported(_) => return None,
+        };
+        Some(hint)
+    }
+}
+
+/// Check a server version string against the oldest supported version
+///
+/// Only the leading `major.minor` numbers are compared, so suffixes such as
+/// `-MariaDB` or ` (Debian 16.2-1)` are ignored. Versions that cannot be
+/// parsed are accepted.
+pub fn check_server_version(found: &str, required: &str) -> CatalogResult<()> {
+    fn major_minor(version: &str) -> Option<(u32, u32)> {
+        let mut numbers = version
+            .trim()
+            .split(|c: char| !c.is_ascii_digit())
+            .take_while(|part| !part.is_empty())
+            .map(|part| part.parse::<u32>().ok());
+        let major = numbers.next()??;
+        let minor = numbers.next().flatten().unwrap_or(0);
+        Some((major, minor))
+    }
+
+    match (major_minor(found), major_minor(required)) {
+        (Some(found_version), Some(required_version)) if found_version < required_version => {
+            Err(CatalogError::UnsupportedServerVersion {
+                found: found.trim().to_string(),
+                required: required.to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Name of the object a permission error is about
+fn denied_object(message: &str) -> String {
+    let lower = message.to_ascii_lowercase();
+    for marker in [
+        "permission denied for table ",
+        "permission denied for relation ",
+        "permission denied for schema ",
+        "permission denied for ",
+        " for table ",
+        " to database ",
+    ] {
+        if let Some(pos) = lower.find(marker) {
+            let name = message[pos + marker.len()..]
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .trim_matches(|c: char| matches!(c, '\'' | '"' | '`' | ','));
+            if !name.is_empty() {
+                return name.to_string();
+            }
+        }
+    }
+    "information_schema".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(code: Option<&str>, message: &str) -> CatalogError {
+        CatalogError::from_driver(code, message, None, 5)
+    }
+
+    #[test]
+    fn test_classify_driver_errors() {
+        assert!(matches!(
+            classify(
+                Some("28P01"),
+                "password authentication failed for user \"app\""
+            ),
+            CatalogError::AuthFailed(_)
+        ));
+        assert!(matches!(
+            classify(
+                None,
+                "Access denied for user 'app'@'10.0.0.1' (using password: YES)"
+            ),
+            CatalogError::AuthFailed(_)
+        ));
+        assert!(matches!(
+            classify(
+                None,
+                "error connecting to server: Connection refused (os error 111)"
+            ),
+            CatalogError::NetworkUnreachable(_)
+        ));
+        assert!(matches!(
+            classify(
+                None,
+                "error communicating with database: failed to lookup address information"
+            ),
+            CatalogError::NetworkUnreachable(_)
+        ));
+        assert!(matches!(
+            classify(
+                Some("57014"),
+                "canceling statement due to statement timeout"
+            ),
+            CatalogError::Timeout(5)
+        ));
+        assert!(matches!(
+            classify(None, "pool timed out while waiting for an open connection"),
+            CatalogError::Timeout(5)
+        ));
+        assert!(matches!(
+            classify(None, "unexpected EOF"),
+            CatalogError::ConnectionFailed(_)
+        ));
+    }
+
+    #[test]
+    fn test_classify_permission_errors() {
+        let object = |code, message| match classify(code, message) {
+            CatalogError::PermissionDenied { object } => object,
+            other => panic!("expected PermissionDenied, got {other:?}"),
+        };
+        assert_eq!(
+            object(Some("42501"), "permission denied for table users"),
+            "users"
+        );
+        assert_eq!(
+            object(None, "permission denied for schema information_schema"),
+            "information_schema"
+        );
+        assert_eq!(
+            object(
+                Some("1142"),
+                "SELECT command denied to user 'app'@'%' for table 'ROUTINES'"
+            ),
+            "ROUTINES"
+        );
+        assert_eq!(
+            object(None, "Access denied for user 'app'@'%' to database 'shop'"),
+            "shop"
+        );
+    }
+
+    #[test]
+    fn test_query_failed_keeps_sql() {
+        let error = CatalogError::from_driver(
+            Some("42P01"),
+            "relation \"foo\" does not exist",
+            Some("SELECT 1 FROM foo"),
+            5,
+        );
+        match &error {
+            CatalogError::QueryFailed { sql, source } => {
+                assert_eq!(sql, "SELECT 1 FROM foo");
+                assert_eq!(source.0, "relation \"foo\" does not exist");
+            }
+            other => panic!("expected QueryFailed, got {other:?}"),
+        }
+        assert!(std::error::Error::source(&error).is_some());
+        assert_eq!(error.remediation(), None);
+    }
+
+    #[test]
+    fn test_remediation() {
+        let error = CatalogError::PermissionDenied {
+            object: "information_schema".to_string(),
+        };
+        assert_eq!(
+            error.remediation().as_deref(),
+            Some("grant SELECT on information_schema to this user")
+        );
+        assert_eq!(error.category(), "permission");
+    }
+
+    #[test]
+    fn test_check_server_version() {
+        assert!(check_server_version("8.0.36", "5.7").is_ok());
+        assert!(check_server_version("10.11.6-MariaDB", "5.7").is_ok());
+        assert!(check_server_version("16.2 (Debian 16.2-1.pgdg120+2)", "12").is_ok());
+        assert!(check_server_version("unknown", "12").is_ok());
+        assert!(matches!(
+            check_server_version("5.6.51-log", "5.7"),
+            Err(CatalogError::UnsupportedServerVersion { .. })
+        ));
+        assert!(matches!(
+            check_server_version("11.22", "12"),
+            Err(CatalogError::UnsupportedServerVersion { .. })
+        ));
+    }
+}
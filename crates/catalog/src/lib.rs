@@ -10,6 +10,7 @@
 //!
 //! - **Live Catalogs**: Direct database connections (MySQL, PostgreSQL, TiDB)
 //! - **Static Catalogs**: Schema definitions from files (YAML/JSON)
+//! - **DDL Catalogs**: Schema read from dumped `CREATE TABLE` / `CREATE VIEW` files
 //! - **Cached Catalogs**: Wrapper with LRU cache and TTL
 //!
 //! ## Architecture
@@ -67,20 +68,28 @@
 //! }
 //! ```
 
+pub mod ddl;
 pub mod error;
 pub mod live_mysql;
 pub mod live_postgres;
 pub mod metadata;
+pub mod null;
+pub mod paging;
 pub mod r#static;
 pub mod r#trait;
 
 // Re-exports
-pub use error::{CatalogError, CatalogResult};
+pub use ddl::{DdlCatalog, DdlWarning, DdlWarningKind, expand_schema_paths};
+pub use error::{CatalogError, CatalogResult, DriverError, check_server_version};
 pub use live_mysql::LiveMySQLCatalog;
 pub use live_postgres::LivePostgreSQLCatalog;
 pub use metadata::{
-    ColumnMetadata, DataType, FunctionMetadata, FunctionParameter, FunctionType, TableMetadata,
-    TableReference, TableType, format_data_type,
+    ColumnMetadata, DataType, FunctionMetadata, FunctionParameter, FunctionType, SampleRows,
+    SequenceMetadata, TableMetadata, TableReference, TableType, format_data_type,
 };
-pub use r#static::StaticCatalog;
+pub use null::NullCatalog;
+pub use paging::{
+    LoadedTables, TablePageHook, TablePageProgress, TablePaging, list_tables_paginated,
+};
+pub use r#static::{StaticCatalog, builtin_collations};
 pub use r#trait::Catalog;
@@ -36,14 +36,22 @@
 //! }
 //! ```
 
+#[cfg(feature = "mysql")]
+use crate::error::check_server_version;
 use crate::error::{CatalogError, CatalogResult};
-use crate::metadata::{ColumnMetadata, DataType, FunctionMetadata, FunctionType, TableMetadata};
+use crate::metadata::{
+    ColumnMetadata, DataType, FunctionMetadata, FunctionParameter, FunctionType, SampleRows,
+    TableMetadata,
+};
+use crate::paging::{LoadedTables, TablePageHook, TablePaging};
 use crate::r#trait::Catalog;
 
 use async_trait::async_trait;
 
 #[cfg(feature = "mysql")]
 use crate::metadata::TableType;
+#[cfg(feature = "mysql")]
+use crate::paging::list_tables_paginated;
 
 #[cfg(feature = "mysql")]
 use sqlx::{MySql, Pool};
@@ -54,6 +62,10 @@ const DEFAULT_POOL_SIZE: u32 = 10;
 /// Default query timeout in seconds
 const DEFAULT_TIMEOUT_SECS: u64 = 5;
 
+/// Oldest supported MySQL server version
+#[cfg(feature = "mysql")]
+const MIN_SERVER_VERSION: &str = "5.7";
+
 /// Live MySQL Catalog implementation
 ///
 /// This catalog connects to a live MySQL database and queries schema information
@@ -66,6 +78,12 @@ pub struct LiveMySQLCatalog {
     pool_size: u32,
     /// Query timeout in seconds
     timeout_secs: u64,
+    /// Page size and cap of `list_tables`
+    paging: TablePaging,
+    /// Tables published by the running `list_tables`
+    loaded: LoadedTables,
+    /// Called after every `list_tables` page
+    on_page: Option<TablePageHook>,
     /// Connection pool
     pool: Option<Pool<MySql>>,
 }
@@ -79,6 +97,12 @@ pub struct LiveMySQLCatalog {
     pool_size: u32,
     /// Query timeout in seconds
     timeout_secs: u64,
+    /// Page size and cap of `list_tables`
+    paging: TablePaging,
+    /// Tables published by the running `list_tables`
+    loaded: LoadedTables,
+    /// Called after every `list_tables` page
+    on_page: Option<TablePageHook>,
 }
 
 impl LiveMySQLCatalog {
@@ -106,14 +130,18 @@ impl LiveMySQLCatalog {
 
         #[cfg(feature = "mysql")]
         {
-            let pool = Some(Pool::<MySql>::connect(&conn_str).await.map_err(|e| {
-                CatalogError::ConnectionFailed(format!("Failed to connect to MySQL: {}", e))
-            })?);
+            let pool = Pool::<MySql>::connect(&conn_str)
+                .await
+                .map_err(|e| CatalogError::from_sqlx(&e, None, DEFAULT_TIMEOUT_SECS))?;
+            Self::check_version(&pool, DEFAULT_TIMEOUT_SECS).await?;
             Ok(Self {
                 connection_string: conn_str,
                 pool_size: DEFAULT_POOL_SIZE,
                 timeout_secs: DEFAULT_TIMEOUT_SECS,
-                pool,
+                paging: TablePaging::default(),
+                loaded: LoadedTables::default(),
+                on_page: None,
+                pool: Some(pool),
             })
         }
 
@@ -123,6 +151,9 @@ impl LiveMySQLCatalog {
                 connection_string: conn_str,
                 pool_size: DEFAULT_POOL_SIZE,
                 timeout_secs: DEFAULT_TIMEOUT_SECS,
+                paging: TablePaging::default(),
+                loaded: LoadedTables::default(),
+                on_page: None,
             })
         }
     }
@@ -166,14 +197,18 @@ impl LiveMySQLCatalog {
 
         #[cfg(feature = "mysql")]
         {
-            let pool = Some(Pool::<MySql>::connect(&conn_str).await.map_err(|e| {
-                CatalogError::ConnectionFailed(format!("Failed to connect to MySQL: {}", e))
-            })?);
+            let pool = Pool::<MySql>::connect(&conn_str)
+                .await
+                .map_err(|e| CatalogError::from_sqlx(&e, None, timeout_secs))?;
+            Self::check_version(&pool, timeout_secs).await?;
             Ok(Self {
                 connection_string: conn_str,
                 pool_size,
                 timeout_secs,
-                pool,
+                paging: TablePaging::default(),
+                loaded: LoadedTables::default(),
+                on_page: None,
+                pool: Some(pool),
             })
         }
 
@@ -183,10 +218,24 @@ impl LiveMySQLCatalog {
                 connection_string: conn_str,
                 pool_size,
                 timeout_secs,
+                paging: TablePaging::default(),
+                loaded: LoadedTables::default(),
+                on_page: None,
             })
         }
     }
 
+    /// Fail when the server is older than [`MIN_SERVER_VERSION`]
+    #[cfg(feature = "mysql")]
+    async fn check_version(pool: &Pool<MySql>, timeout_secs: u64) -> CatalogResult<()> {
+        let query = "SELECT VERSION()";
+        let version: String = sqlx::query_scalar(query)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| CatalogError::from_sqlx(&e, Some(query), timeout_secs))?;
+        check_server_version(&version, MIN_SERVER_VERSION)
+    }
+
     /// Validate the connection string format
     ///
     /// Basic validation to ensure the connection string has the correct format.
@@ -224,6 +273,23 @@ impl LiveMySQLCatalog {
         self.timeout_secs
     }
 
+    /// Get the page size and cap used by `list_tables`
+    pub fn paging(&self) -> &TablePaging {
+        &self.paging
+    }
+
+    /// Set the page size and cap used by `list_tables`
+    pub fn with_paging(mut self, paging: TablePaging) -> Self {
+        self.paging = paging;
+        self
+    }
+
+    /// Report the progress of every `list_tables` page to `hook`
+    pub fn with_page_hook(mut self, hook: TablePageHook) -> Self {
+        self.on_page = Some(hook);
+        self
+    }
+
     /// Parse MySQL data type to unified DataType
     ///
     /// Converts MySQL type strings (e.g., "varchar(255)", "int", "text")
@@ -307,16 +373,147 @@ impl LiveMySQLCatalog {
             })
             .and_then(|len: usize| if len == 0 { None } else { Some(len) })
     }
+
+    /// Quote a possibly schema-qualified name with backticks
+    #[allow(dead_code)]
+    fn quote_identifier(name: &str) -> String {
+        name.split('.')
+            .map(|part| format!("`{}`", part.replace('`', "``")))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// Build the metadata of a stored function or procedure
+    ///
+    /// `parameters` are `(name, type)` pairs in declaration order.
+    /// Procedures have no return type.
+    #[allow(dead_code)]
+    fn routine_metadata(
+        name: &str,
+        routine_type: &str,
+        return_type: Option<&str>,
+        schema: &str,
+        comment: Option<String>,
+        parameters: Vec<(String, String)>,
+    ) -> FunctionMetadata {
+        let return_type = match return_type {
+            Some(return_type) if routine_type.eq_ignore_ascii_case("FUNCTION") => {
+                Self::parse_mysql_type(return_type)
+            }
+            _ => DataType::Other("void".to_string()),
+        };
+        let parameters = parameters
+            .into_iter()
+            .map(|(name, data_type)| FunctionParameter {
+                name,
+                data_type: Self::parse_mysql_type(&data_type),
+                has_default: false,
+                is_variadic: false,
+            })
+            .collect();
+
+        let mut function = FunctionMetadata::new(name, return_type)
+            .with_type(FunctionType::Scalar)
+            .with_parameters(parameters)
+            .user_defined(schema);
+        if let Some(comment) = comment.filter(|c| !c.trim().is_empty()) {
+            function = function.with_description(comment);
+        }
+        function
+    }
+
+    /// Stored functions and procedures of the current database
+    ///
+    /// Read from `information_schema.ROUTINES` and `PARAMETERS`; a failure to
+    /// read them is logged and yields no routines.
+    async fn list_user_functions(&self) -> CatalogResult<Vec<FunctionMetadata>> {
+        #[cfg(feature = "mysql")]
+        if let Some(pool) = &self.pool {
+            use std::collections::HashMap;
+
+            let routines_query = r#"
+                SELECT
+                    CAST(ROUTINE_NAME AS CHAR),
+                    CAST(ROUTINE_TYPE AS CHAR),
+                    CAST(DTD_IDENTIFIER AS CHAR),
+                    CAST(ROUTINE_SCHEMA AS CHAR),
+                    CAST(ROUTINE_COMMENT AS CHAR)
+                FROM information_schema.ROUTINES
+                WHERE ROUTINE_SCHEMA = DATABASE()
+                ORDER BY ROUTINE_NAME
+            "#;
+            let parameters_query = r#"
+                SELECT
+                    CAST(SPECIFIC_NAME AS CHAR),
+                    CAST(PARAMETER_NAME AS CHAR),
+                    CAST(DTD_IDENTIFIER AS CHAR)
+                FROM information_schema.PARAMETERS
+                WHERE SPECIFIC_SCHEMA = DATABASE() AND ORDINAL_POSITION > 0
+                ORDER BY SPECIFIC_NAME, ORDINAL_POSITION
+            "#;
+
+            type RoutineRow = (String, String, Option<String>, String, Option<String>);
+            let fetch = async {
+                let routines = sqlx::query_as::<_, RoutineRow>(routines_query)
+                    .fetch_all(pool)
+                    .await?;
+                let parameters = sqlx::query_as::<_, (String, String, String)>(parameters_query)
+                    .fetch_all(pool)
+                    .await?;
+                Ok::<_, sqlx::Error>((routines, parameters))
+            };
+            let (routines, parameters) = match tokio::time::timeout(
+                std::time::Duration::from_secs(self.timeout_secs),
+                fetch,
+            )
+            .await
+            .map_err(|_| CatalogError::Timeout(self.timeout_secs))?
+            {
+                Ok(rows) => rows,
+                Err(e) => {
+                    tracing::warn!("Cannot read stored routines from information_schema: {}", e);
+                    return Ok(Vec::new());
+                }
+            };
+
+            let mut parameters_by_routine: HashMap<String, Vec<(String, String)>> = HashMap::new();
+            for (routine, name, data_type) in parameters {
+                parameters_by_routine
+                    .entry(routine)
+                    .or_default()
+                    .push((name, data_type));
+            }
+
+            return Ok(routines
+                .into_iter()
+                .map(|(name, routine_type, return_type, schema, comment)| {
+                    let parameters = parameters_by_routine.remove(&name).unwrap_or_default();
+                    Self::routine_metadata(
+                        &name,
+                        &routine_type,
+                        return_type.as_deref(),
+                        &schema,
+                        comment,
+                        parameters,
+                    )
+                })
+                .collect());
+        }
+
+        Ok(Vec::new())
+    }
 }
 
 #[async_trait]
 impl Catalog for LiveMySQLCatalog {
     /// List all tables in the database
     ///
-    /// Queries information_schema.tables to get all tables, views, and materialized views.
+    /// Queries information_schema.tables to get all tables, views, and materialized views,
+    /// one page at a time (see [`crate::paging`]).
     async fn list_tables(&self) -> CatalogResult<Vec<TableMetadata>> {
         #[cfg(feature = "mysql")]
         if let Some(pool) = &self.pool {
+            // Binary comparison, so names differing only in case both get listed
             let query = r#"
                 SELECT
                     CAST(TABLE_NAME AS CHAR) as table_name,
@@ -326,30 +523,44 @@ impl Catalog for LiveMySQLCatalog {
                 FROM information_schema.TABLES
                 WHERE TABLE_SCHEMA = DATABASE()
                   AND TABLE_TYPE IN ('BASE TABLE', 'VIEW')
-                ORDER BY TABLE_NAME
+                  AND BINARY TABLE_NAME > BINARY ?
+                ORDER BY BINARY TABLE_NAME
+                LIMIT ?
             "#;
 
-            let rows = sqlx::query_as::<_, (String, String, String, Option<String>)>(query)
-                .fetch_all(pool)
-                .await
-                .map_err(|e| CatalogError::QueryFailed(format!("Failed to list tables: {}", e)))?;
-
-            let tables = rows
-                .into_iter()
-                .map(|(name, schema, db_table_type, comment)| {
-                    let table_type = match db_table_type.as_str() {
-                        "BASE TABLE" => TableType::Table,
-                        "VIEW" => TableType::View,
-                        _ => TableType::Other(db_table_type),
-                    };
-
-                    TableMetadata::new(&name, &schema)
-                        .with_type(table_type)
-                        .with_comment(comment.unwrap_or_default())
-                })
-                .collect();
+            let fetch_page = |after: Option<(String, String)>, limit: usize| async move {
+                // Only the current database is listed, so the name alone is the key
+                let after_name = after.map(|(_, name)| name).unwrap_or_default();
+                let rows = sqlx::query_as::<_, (String, String, String, Option<String>)>(query)
+                    .bind(after_name)
+                    .bind(limit as u64)
+                    .fetch_all(pool)
+                    .await
+                    .map_err(|e| CatalogError::from_sqlx(&e, Some(query), self.timeout_secs))?;
 
-            return Ok(tables);
+                Ok(rows
+                    .into_iter()
+                    .map(|(name, schema, db_table_type, comment)| {
+                        let table_type = match db_table_type.as_str() {
+                            "BASE TABLE" => TableType::Table,
+                            "VIEW" => TableType::View,
+                            _ => TableType::Other(db_table_type),
+                        };
+
+                        TableMetadata::new(&name, &schema)
+                            .with_type(table_type)
+                            .with_comment(comment.unwrap_or_default())
+                    })
+                    .collect())
+            };
+
+            return list_tables_paginated(
+                &self.paging,
+                &self.loaded,
+                self.on_page.as_ref(),
+                fetch_page,
+            )
+            .await;
         } else {
             return Err(CatalogError::ConnectionFailed(
                 "Database pool not initialized".to_string(),
@@ -367,22 +578,37 @@ impl Catalog for LiveMySQLCatalog {
 
     /// Get column metadata for a specific table
     ///
-    /// Queries information_schema.columns to get column information.
+    /// Queries information_schema.columns to get column information, and
+    /// information_schema.KEY_COLUMN_USAGE for the foreign key targets.
     async fn get_columns(&self, table: &str) -> CatalogResult<Vec<ColumnMetadata>> {
         #[cfg(feature = "mysql")]
         if let Some(pool) = &self.pool {
             let query = r#"
                 SELECT
-                    CAST(COLUMN_NAME AS CHAR) as column_name,
-                    CAST(COLUMN_TYPE AS CHAR) as column_type,
-                    CAST(IS_NULLABLE AS CHAR) as is_nullable,
-                    CAST(COLUMN_DEFAULT AS CHAR) as column_default,
-                    CAST(COLUMN_COMMENT AS CHAR) as column_comment,
-                    CAST(COLUMN_KEY AS CHAR) as column_key
-                FROM information_schema.COLUMNS
-                WHERE TABLE_SCHEMA = DATABASE()
-                  AND TABLE_NAME = ?
-                ORDER BY ORDINAL_POSITION
+                    CAST(c.COLUMN_NAME AS CHAR) as column_name,
+                    CAST(c.COLUMN_TYPE AS CHAR) as column_type,
+                    CAST(c.IS_NULLABLE AS CHAR) as is_nullable,
+                    CAST(IF(c.EXTRA LIKE '%auto_increment%', 'AUTO_INCREMENT', c.COLUMN_DEFAULT)
+                        AS CHAR) as column_default,
+                    CAST(c.COLUMN_COMMENT AS CHAR) as column_comment,
+                    CAST(c.COLUMN_KEY AS CHAR) as column_key,
+                    CAST(fk.ref_table AS CHAR) as ref_table,
+                    CAST(fk.ref_column AS CHAR) as ref_column
+                FROM information_schema.COLUMNS c
+                LEFT JOIN (
+                    SELECT
+                        COLUMN_NAME,
+                        MIN(REFERENCED_TABLE_NAME) as ref_table,
+                        MIN(REFERENCED_COLUMN_NAME) as ref_column
+                    FROM information_schema.KEY_COLUMN_USAGE
+                    WHERE TABLE_SCHEMA = DATABASE()
+                      AND TABLE_NAME = ?
+                      AND REFERENCED_TABLE_NAME IS NOT NULL
+                    GROUP BY COLUMN_NAME
+                ) fk ON fk.COLUMN_NAME = c.COLUMN_NAME
+                WHERE c.TABLE_SCHEMA = DATABASE()
+                  AND c.TABLE_NAME = ?
+                ORDER BY c.ORDINAL_POSITION
             "#;
 
             let rows = sqlx::query_as::<
@@ -394,36 +620,45 @@ impl Catalog for LiveMySQLCatalog {
                     Option<String>,
                     Option<String>,
                     String,
+                    Option<String>,
+                    Option<String>,
                 ),
             >(query)
             .bind(table)
+            .bind(table)
             .fetch_all(pool)
             .await
-            .map_err(|e| {
-                CatalogError::QueryFailed(format!(
-                    "Failed to get columns for table '{}': {}",
-                    table, e
-                ))
-            })?;
+            .map_err(|e| CatalogError::from_sqlx(&e, Some(query), self.timeout_secs))?;
 
             let columns: Vec<ColumnMetadata> = rows
                 .into_iter()
                 .map(
-                    |(name, column_type, is_nullable, _default, comment, column_key)| {
+                    |(
+                        name,
+                        column_type,
+                        is_nullable,
+                        default,
+                        comment,
+                        column_key,
+                        ref_table,
+                        ref_column,
+                    )| {
                         let dt = Self::parse_mysql_type(&column_type);
                         let nullable = is_nullable == "YES";
                         let is_pk = column_key == "PRI";
-                        let is_fk = column_key == "MUL";
 
                         let mut col = ColumnMetadata::new(name, dt)
                             .with_nullable(nullable)
                             .with_comment(comment.unwrap_or_default());
 
+                        if let Some(default) = default {
+                            col = col.with_default(default);
+                        }
                         if is_pk {
                             col = col.with_primary_key();
                         }
-                        if is_fk {
-                            col = col.with_foreign_key("", "");
+                        if let (Some(ref_table), Some(ref_column)) = (ref_table, ref_column) {
+                            col = col.with_foreign_key(ref_table, ref_column);
                         }
 
                         col
@@ -450,48 +685,149 @@ impl Catalog for LiveMySQLCatalog {
 
     /// List all available functions
     ///
-    /// Returns a list of built-in MySQL functions and custom stored procedures/functions.
+    /// Returns the built-in MySQL functions merged with the stored functions
+    /// and procedures of the current database, see
+    /// [`FunctionRegistry::with_catalog_functions`](unified_sql_lsp_function_registry::FunctionRegistry::with_catalog_functions).
     async fn list_functions(&self) -> CatalogResult<Vec<FunctionMetadata>> {
         use unified_sql_lsp_function_registry::FunctionRegistry;
         use unified_sql_lsp_ir::Dialect;
 
-        // Start with builtin functions from function-registry
-        let registry = FunctionRegistry::new();
-        let builtin_functions = registry.get_functions(Dialect::MySQL);
-        let mut all_functions: Vec<FunctionMetadata> = builtin_functions;
+        let user_functions = self.list_user_functions().await?;
+
+        Ok(FunctionRegistry::new()
+            .with_catalog_functions(Dialect::MySQL, user_functions)
+            .get_functions(Dialect::MySQL))
+    }
 
-        // Add custom stored procedures/functions from database
+    /// List roles from `mysql.user`
+    ///
+    /// A failure to read the table is logged once and yields no roles.
+    ///
+    /// Reading `mysql.user` needs a global privilege most application
+    /// accounts lack; that is expected and not reported as an error.
+    async fn list_roles(&self) -> CatalogResult<Vec<String>> {
         #[cfg(feature = "mysql")]
         if let Some(pool) = &self.pool {
-            // Query custom stored procedures/functions from mysql.proc
-            let custom_query = r#"
-                SELECT
-                    name as function_name,
-                    param_list as parameters,
-                    returns as return_type,
-                    db as schema_name
-                FROM mysql.proc
-                WHERE db = DATABASE()
-                  AND type IN ('FUNCTION', 'PROCEDURE')
-            "#;
+            let query =
+                "SELECT DISTINCT CAST(User AS CHAR) FROM mysql.user WHERE User <> '' ORDER BY 1";
+            let fetch = sqlx::query_scalar::<_, String>(query).fetch_all(pool);
+            return match tokio::time::timeout(
+                std::time::Duration::from_secs(self.timeout_secs),
+                fetch,
+            )
+            .await
+            .map_err(|_| CatalogError::Timeout(self.timeout_secs))?
+            {
+                Ok(roles) => Ok(roles),
+                Err(e) => {
+                    tracing::warn!("Cannot read roles from mysql.user: {}", e);
+                    Ok(Vec::new())
+                }
+            };
+        }
 
-            let custom_funcs: Vec<FunctionMetadata> =
-                sqlx::query_as::<_, (String, String, String, String)>(custom_query)
-                    .fetch_all(pool)
+        Ok(Vec::new())
+    }
+
+    /// List collations from `information_schema.COLLATIONS`
+    ///
+    /// A failure to read the table is logged and yields no collations.
+    async fn list_collations(&self) -> CatalogResult<Vec<String>> {
+        #[cfg(feature = "mysql")]
+        if let Some(pool) = &self.pool {
+            let query =
+                "SELECT CAST(COLLATION_NAME AS CHAR) FROM information_schema.COLLATIONS ORDER BY 1";
+            let fetch = sqlx::query_scalar::<_, String>(query).fetch_all(pool);
+            return match tokio::time::timeout(
+                std::time::Duration::from_secs(self.timeout_secs),
+                fetch,
+            )
+            .await
+            .map_err(|_| CatalogError::Timeout(self.timeout_secs))?
+            {
+                Ok(collations) => Ok(collations),
+                Err(e) => {
+                    tracing::warn!("Cannot read collations from information_schema: {}", e);
+                    Ok(Vec::new())
+                }
+            };
+        }
+
+        Ok(Vec::new())
+    }
+
+    /// Pages the running `list_tables` has published so far
+    fn loaded_tables(&self) -> Option<Vec<TableMetadata>> {
+        let tables = self.loaded.snapshot();
+        (!tables.is_empty()).then_some(tables)
+    }
+
+    /// Fetch the first rows of a table
+    ///
+    /// Every column is cast to CHAR so any column type renders as text.
+    /// The query is cancelled after the catalog's timeout.
+    async fn sample_rows(&self, table: &str, limit: usize) -> CatalogResult<SampleRows> {
+        #[cfg(feature = "mysql")]
+        if let Some(pool) = &self.pool {
+            use sqlx::Row;
+
+            let columns: Vec<String> = self
+                .get_columns(table)
+                .await?
+                .into_iter()
+                .map(|c| c.name)
+                .collect();
+            if columns.is_empty() {
+                return Err(CatalogError::TableNotFound(
+                    table.to_string(),
+                    "DATABASE()".to_string(),
+                ));
+            }
+
+            let select_list = columns
+                .iter()
+                .map(|c| format!("CAST({} AS CHAR)", Self::quote_identifier(c)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let query = format!(
+                "SELECT {} FROM {} LIMIT {}",
+                select_list,
+                Self::quote_identifier(table),
+                limit
+            );
+
+            let fetch = sqlx::query(&query).fetch_all(pool);
+            let rows =
+                tokio::time::timeout(std::time::Duration::from_secs(self.timeout_secs), fetch)
                     .await
-                    .unwrap_or(vec![]) // Don't fail if mysql.proc not accessible
-                    .into_iter()
-                    .map(|(name, _params, ret, schema)| {
-                        FunctionMetadata::new(&name, Self::parse_mysql_type(&ret))
-                            .with_type(FunctionType::Scalar)
-                            .with_description(format!("Custom function from {}", schema))
-                    })
-                    .collect();
+                    .map_err(|_| CatalogError::Timeout(self.timeout_secs))?
+                    .map_err(|e| CatalogError::from_sqlx(&e, Some(&query), self.timeout_secs))?;
+
+            let rows = rows
+                .iter()
+                .map(|row| {
+                    (0..columns.len())
+                        .map(|i| row.try_get::<Option<String>, _>(i))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| CatalogError::from_sqlx(&e, Some(&query), self.timeout_secs))?;
 
-            all_functions.extend(custom_funcs);
+            return Ok(SampleRows { columns, rows });
+        } else {
+            return Err(CatalogError::ConnectionFailed(
+                "Database pool not initialized".to_string(),
+            ));
         }
 
-        Ok(all_functions)
+        #[cfg(not(feature = "mysql"))]
+        return Err(CatalogError::NotSupported(format!(
+            "sample_rows requires 'mysql' feature enabled (table: '{}', limit: {})",
+            table, limit
+        )));
+
+        #[cfg(all(feature = "mysql", not(feature = "mysql")))]
+        unreachable!()
     }
 }
 
@@ -499,6 +835,36 @@ impl Catalog for LiveMySQLCatalog {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_routine_metadata() {
+        let function = LiveMySQLCatalog::routine_metadata(
+            "order_total",
+            "FUNCTION",
+            Some("decimal(10,2)"),
+            "test_db",
+            Some("Sum of the line items of an order".to_string()),
+            vec![("p_order_id".to_string(), "int".to_string())],
+        );
+        assert_eq!(function.return_type, DataType::Decimal);
+        assert_eq!(function.parameters.len(), 1);
+        assert_eq!(function.parameters[0].name, "p_order_id");
+        assert_eq!(function.parameters[0].data_type, DataType::Integer);
+        assert_eq!(function.schema.as_deref(), Some("test_db"));
+        assert!(!function.is_builtin);
+        assert!(function.description.is_some());
+
+        let procedure = LiveMySQLCatalog::routine_metadata(
+            "archive_orders",
+            "PROCEDURE",
+            None,
+            "test_db",
+            Some(String::new()),
+            Vec::new(),
+        );
+        assert_eq!(procedure.return_type, DataType::Other("void".to_string()));
+        assert_eq!(procedure.description, None);
+    }
+
     #[test]
     fn test_parse_mysql_varchar() {
         let dt = LiveMySQLCatalog::parse_mysql_type("varchar(255)");
@@ -541,6 +907,16 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_quote_identifier() {
+        assert_eq!(LiveMySQLCatalog::quote_identifier("users"), "`users`");
+        assert_eq!(
+            LiveMySQLCatalog::quote_identifier("app.users"),
+            "`app`.`users`"
+        );
+        assert_eq!(LiveMySQLCatalog::quote_identifier("we`ird"), "`we``ird`");
+    }
+
     #[tokio::test]
     async fn test_new_catalog_invalid_connection_string() {
         let result = LiveMySQLCatalog::new("").await;
@@ -36,14 +36,22 @@
 //! }
 //! ```
 
+#[cfg(feature = "postgresql")]
+use crate::error::check_server_version;
 use crate::error::{CatalogError, CatalogResult};
-use crate::metadata::{ColumnMetadata, DataType, FunctionMetadata, FunctionType, TableMetadata};
+use crate::metadata::{
+    ColumnMetadata, DataType, FunctionMetadata, FunctionParameter, FunctionType, SampleRows,
+    SequenceMetadata, TableMetadata,
+};
+use crate::paging::{LoadedTables, TablePageHook, TablePaging};
 use crate::r#trait::Catalog;
 
 use async_trait::async_trait;
 
 #[cfg(feature = "postgresql")]
 use crate::metadata::TableType;
+#[cfg(feature = "postgresql")]
+use crate::paging::list_tables_paginated;
 
 #[cfg(feature = "postgresql")]
 use sqlx::{Pool, Postgres};
@@ -54,6 +62,10 @@ const DEFAULT_POOL_SIZE: u32 = 10;
 /// Default query timeout in seconds
 const DEFAULT_TIMEOUT_SECS: u64 = 5;
 
+/// Oldest supported PostgreSQL server version
+#[cfg(feature = "postgresql")]
+const MIN_SERVER_VERSION: &str = "12";
+
 /// Live PostgreSQL Catalog implementation
 ///
 /// This catalog connects to a live PostgreSQL database and queries schema information
@@ -66,6 +78,12 @@ pub struct LivePostgreSQLCatalog {
     pool_size: u32,
     /// Query timeout in seconds
     timeout_secs: u64,
+    /// Page size and cap of `list_tables`
+    paging: TablePaging,
+    /// Tables published by the running `list_tables`
+    loaded: LoadedTables,
+    /// Called after every `list_tables` page
+    on_page: Option<TablePageHook>,
     /// Connection pool
     pool: Option<Pool<Postgres>>,
 }
@@ -79,6 +97,12 @@ pub struct LivePostgreSQLCatalog {
     pool_size: u32,
     /// Query timeout in seconds
     timeout_secs: u64,
+    /// Page size and cap of `list_tables`
+    paging: TablePaging,
+    /// Tables published by the running `list_tables`
+    loaded: LoadedTables,
+    /// Called after every `list_tables` page
+    on_page: Option<TablePageHook>,
 }
 
 impl LivePostgreSQLCatalog {
@@ -114,8 +138,9 @@ impl LivePostgreSQLCatalog {
                 let err_msg = format!("!!! Failed to connect to PostgreSQL: {}", e);
                 eprintln!("{}", err_msg);
                 tracing::error!("{}", err_msg);
-                CatalogError::ConnectionFailed(format!("Failed to connect to PostgreSQL: {}", e))
+                CatalogError::from_sqlx(&e, None, DEFAULT_TIMEOUT_SECS)
             })?;
+            Self::check_version(&pool, DEFAULT_TIMEOUT_SECS).await?;
             let success_msg = "!!! PostgreSQL connection pool created successfully";
             eprintln!("{}", success_msg);
             tracing::info!("{}", success_msg);
@@ -123,6 +148,9 @@ impl LivePostgreSQLCatalog {
                 connection_string: conn_str,
                 pool_size: DEFAULT_POOL_SIZE,
                 timeout_secs: DEFAULT_TIMEOUT_SECS,
+                paging: TablePaging::default(),
+                loaded: LoadedTables::default(),
+                on_page: None,
                 pool: Some(pool),
             })
         }
@@ -136,6 +164,9 @@ impl LivePostgreSQLCatalog {
                 connection_string: conn_str,
                 pool_size: DEFAULT_POOL_SIZE,
                 timeout_secs: DEFAULT_TIMEOUT_SECS,
+                paging: TablePaging::default(),
+                loaded: LoadedTables::default(),
+                on_page: None,
             })
         }
     }
@@ -179,14 +210,18 @@ impl LivePostgreSQLCatalog {
 
         #[cfg(feature = "postgresql")]
         {
-            let pool = Some(Pool::<Postgres>::connect(&conn_str).await.map_err(|e| {
-                CatalogError::ConnectionFailed(format!("Failed to connect to PostgreSQL: {}", e))
-            })?);
+            let pool = Pool::<Postgres>::connect(&conn_str)
+                .await
+                .map_err(|e| CatalogError::from_sqlx(&e, None, timeout_secs))?;
+            Self::check_version(&pool, timeout_secs).await?;
             Ok(Self {
                 connection_string: conn_str,
                 pool_size,
                 timeout_secs,
-                pool,
+                paging: TablePaging::default(),
+                loaded: LoadedTables::default(),
+                on_page: None,
+                pool: Some(pool),
             })
         }
 
@@ -196,10 +231,24 @@ impl LivePostgreSQLCatalog {
                 connection_string: conn_str,
                 pool_size,
                 timeout_secs,
+                paging: TablePaging::default(),
+                loaded: LoadedTables::default(),
+                on_page: None,
             })
         }
     }
 
+    /// Fail when the server is older than [`MIN_SERVER_VERSION`]
+    #[cfg(feature = "postgresql")]
+    async fn check_version(pool: &Pool<Postgres>, timeout_secs: u64) -> CatalogResult<()> {
+        let query = "SHOW server_version";
+        let version: String = sqlx::query_scalar(query)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| CatalogError::from_sqlx(&e, Some(query), timeout_secs))?;
+        check_server_version(&version, MIN_SERVER_VERSION)
+    }
+
     /// Validate the connection string format
     ///
     /// Basic validation to ensure the connection string has the correct format.
@@ -237,6 +286,23 @@ impl LivePostgreSQLCatalog {
         self.timeout_secs
     }
 
+    /// Get the page size and cap used by `list_tables`
+    pub fn paging(&self) -> &TablePaging {
+        &self.paging
+    }
+
+    /// Set the page size and cap used by `list_tables`
+    pub fn with_paging(mut self, paging: TablePaging) -> Self {
+        self.paging = paging;
+        self
+    }
+
+    /// Report the progress of every `list_tables` page to `hook`
+    pub fn with_page_hook(mut self, hook: TablePageHook) -> Self {
+        self.on_page = Some(hook);
+        self
+    }
+
     /// Parse PostgreSQL data type to unified DataType
     ///
     /// Converts PostgreSQL type strings (e.g., "character varying(255)", "integer", "text")
@@ -351,13 +417,194 @@ impl LivePostgreSQLCatalog {
             })
             .and_then(|len: usize| if len == 0 { None } else { Some(len) })
     }
+
+    /// Quote a possibly schema-qualified name with double quotes
+    #[allow(dead_code)]
+    fn quote_identifier(name: &str) -> String {
+        name.split('.')
+            .map(|part| format!("\"{}\"", part.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// Routines of user schemas from `pg_proc`
+    ///
+    /// A failure to read `pg_proc` is logged and yields no routines.
+    async fn list_user_functions(&self) -> CatalogResult<Vec<FunctionMetadata>> {
+        #[cfg(feature = "postgresql")]
+        if let Some(pool) = &self.pool {
+            let custom_query = r#"
+                SELECT
+                    p.proname AS function_name,
+                    pg_get_function_result(p.oid) AS return_type,
+                    pg_get_function_arguments(p.oid) AS arguments,
+                    n.nspname AS schema_name,
+                    p.prokind::text AS kind,
+                    obj_description(p.oid, 'pg_proc') AS comment
+                FROM pg_catalog.pg_proc p
+                JOIN pg_catalog.pg_namespace n ON p.pronamespace = n.oid
+                WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+                  AND n.nspname NOT LIKE 'pg\_toast%'
+                  AND n.nspname NOT LIKE 'pg\_temp%'
+                  AND p.prorettype <> 'pg_catalog.trigger'::regtype
+                ORDER BY n.nspname, p.proname
+            "#;
+
+            type RoutineRow = (
+                String,
+                Option<String>,
+                String,
+                String,
+                String,
+                Option<String>,
+            );
+            let fetch = sqlx::query_as::<_, RoutineRow>(custom_query).fetch_all(pool);
+            let rows = match tokio::time::timeout(
+                std::time::Duration::from_secs(self.timeout_secs),
+                fetch,
+            )
+            .await
+            {
+                Ok(Ok(rows)) => rows,
+                // Don't fail if pg_proc is not accessible
+                Ok(Err(e)) => {
+                    tracing::warn!("Cannot read user-defined functions from pg_proc: {}", e);
+                    Vec::new()
+                }
+                Err(_) => return Err(CatalogError::Timeout(self.timeout_secs)),
+            };
+
+            return Ok(rows
+                .into_iter()
+                .map(|(name, result, arguments, schema, kind, comment)| {
+                    Self::routine_metadata(
+                        &name,
+                        result.as_deref(),
+                        &arguments,
+                        &schema,
+                        &kind,
+                        comment,
+                    )
+                })
+                .collect());
+        }
+
+        Ok(Vec::new())
+    }
+
+    /// Parse the output of `pg_get_function_arguments` into call parameters
+    ///
+    /// Handles argument modes, defaults and unnamed arguments; `OUT`
+    /// arguments aren't passed in calls and are skipped.
+    #[allow(dead_code)]
+    fn parse_function_arguments(arguments: &str) -> Vec<FunctionParameter> {
+        // Types starting with these words span several words, e.g. `double precision`
+        const MULTI_WORD_TYPES: &[&str] = &[
+            "bit",
+            "character",
+            "double",
+            "interval",
+            "national",
+            "time",
+            "timestamp",
+        ];
+
+        let mut parts = Vec::new();
+        let mut depth = 0usize;
+        let mut start = 0;
+        for (i, c) in arguments.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    parts.push(&arguments[start..i]);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        parts.push(&arguments[start..]);
+
+        parts
+            .into_iter()
+            .filter_map(|part| {
+                let (declaration, has_default) = match part.find(" DEFAULT ") {
+                    Some(index) => (&part[..index], true),
+                    None => (part, false),
+                };
+                let mut words: Vec<&str> = declaration.split_whitespace().collect();
+                let mode = match words.first().copied() {
+                    Some(mode @ ("IN" | "OUT" | "INOUT" | "VARIADIC")) => {
+                        words.remove(0);
+                        mode
+                    }
+                    _ => "IN",
+                };
+                if mode == "OUT" || words.is_empty() {
+                    return None;
+                }
+
+                let unnamed = words.len() == 1
+                    || MULTI_WORD_TYPES.contains(&words[0].to_lowercase().as_str());
+                let (name, data_type) = if unnamed {
+                    (String::new(), words.join(" "))
+                } else {
+                    (words[0].to_string(), words[1..].join(" "))
+                };
+                Some(FunctionParameter {
+                    name,
+                    data_type: Self::parse_postgres_type(&data_type),
+                    has_default,
+                    is_variadic: mode == "VARIADIC",
+                })
+            })
+            .collect()
+    }
+
+    /// Build the metadata of a user-defined routine from `pg_proc`
+    ///
+    /// `kind` is `prokind`: `a` for aggregates, `w` for window functions and
+    /// `p` for procedures, which have no result.
+    #[allow(dead_code)]
+    fn routine_metadata(
+        name: &str,
+        result: Option<&str>,
+        arguments: &str,
+        schema: &str,
+        kind: &str,
+        comment: Option<String>,
+    ) -> FunctionMetadata {
+        let result = result.unwrap_or("void");
+        let returns_set = result.starts_with("SETOF ") || result.starts_with("TABLE(");
+        let function_type = match kind {
+            "a" => FunctionType::Aggregate,
+            "w" => FunctionType::Window,
+            _ if returns_set => FunctionType::Table,
+            _ => FunctionType::Scalar,
+        };
+        let return_type = if kind == "p" || result == "void" {
+            DataType::Other("void".to_string())
+        } else {
+            Self::parse_postgres_type(result)
+        };
+
+        let mut function = FunctionMetadata::new(name, return_type)
+            .with_type(function_type)
+            .with_parameters(Self::parse_function_arguments(arguments))
+            .user_defined(schema);
+        if let Some(comment) = comment.filter(|c| !c.trim().is_empty()) {
+            function = function.with_description(comment);
+        }
+        function
+    }
 }
 
 #[async_trait]
 impl Catalog for LivePostgreSQLCatalog {
     /// List all tables in the database
     ///
-    /// Queries information_schema.tables to get all tables, views, and materialized views.
+    /// Queries information_schema.tables to get all tables, views, and materialized views,
+    /// one page at a time (see [`crate::paging`]).
     async fn list_tables(&self) -> CatalogResult<Vec<TableMetadata>> {
         eprintln!("!!! LivePostgreSQLCatalog::list_tables() called");
         tracing::debug!("!!! LivePostgreSQLCatalog::list_tables() called");
@@ -372,59 +619,62 @@ impl Catalog for LivePostgreSQLCatalog {
                 tracing::debug!("!!! Pool is available, executing query");
                 let query = r#"
                     SELECT
-                        t.table_name,
-                        t.table_schema,
+                        t.table_name::text,
+                        t.table_schema::text,
                         CASE
                             WHEN t.table_type = 'BASE TABLE' THEN 'table'
                             WHEN t.table_type = 'VIEW' THEN 'view'
                             WHEN t.table_type = 'MATERIALIZED VIEW' THEN 'materialized'
                             ELSE 'other'
                         END as table_type,
-                        obj_description((t.table_schema||'.'||t.table_name)::regclass, 'pg_class') as table_comment
+                        obj_description((quote_ident(t.table_schema)||'.'||quote_ident(t.table_name))::regclass, 'pg_class') as table_comment
                     FROM information_schema.tables t
                     WHERE t.table_schema NOT IN ('pg_catalog', 'information_schema')
                       AND t.table_type IN ('BASE TABLE', 'VIEW', 'MATERIALIZED VIEW')
-                    ORDER BY t.table_schema, t.table_name
+                      AND (t.table_schema::text, t.table_name::text) > ($1, $2)
+                    ORDER BY t.table_schema::text, t.table_name::text
+                    LIMIT $3
                 "#;
 
-                let rows = sqlx::query_as::<_, (String, String, String, Option<String>)>(query)
-                    .fetch_all(pool)
-                    .await
-                    .map_err(|e| {
-                        let err_msg = format!("!!! Failed to list tables: {}", e);
-                        eprintln!("{}", err_msg);
-                        tracing::error!("{}", err_msg);
-                        CatalogError::QueryFailed(format!("Failed to list tables: {}", e))
-                    })?;
-
-                eprintln!("!!! Query returned {} rows", rows.len());
-                tracing::debug!("!!! Query returned {} rows", rows.len());
-
-                let tables: Vec<TableMetadata> = rows
-                    .into_iter()
-                    .map(|(name, schema, db_table_type, comment)| {
-                        eprintln!(
-                            "!!! Found table: {}.{} (type: {})",
-                            schema, name, db_table_type
-                        );
-                        tracing::debug!(
-                            "!!! Found table: {}.{} (type: {})",
-                            schema,
-                            name,
-                            db_table_type
-                        );
-                        let table_type = match db_table_type.as_str() {
-                            "table" => TableType::Table,
-                            "view" => TableType::View,
-                            "materialized" => TableType::MaterializedView,
-                            _ => TableType::Other(db_table_type),
-                        };
-
-                        TableMetadata::new(&name, &schema)
-                            .with_type(table_type)
-                            .with_comment(comment.unwrap_or_default())
-                    })
-                    .collect();
+                let fetch_page = |after: Option<(String, String)>, limit: usize| async move {
+                    let (after_schema, after_name) = after.unwrap_or_default();
+                    let rows = sqlx::query_as::<_, (String, String, String, Option<String>)>(query)
+                        .bind(after_schema)
+                        .bind(after_name)
+                        .bind(limit as i64)
+                        .fetch_all(pool)
+                        .await
+                        .map_err(|e| {
+                            let err_msg = format!("!!! Failed to list tables: {}", e);
+                            eprintln!("{}", err_msg);
+                            tracing::error!("{}", err_msg);
+                            CatalogError::from_sqlx(&e, Some(query), self.timeout_secs)
+                        })?;
+
+                    Ok(rows
+                        .into_iter()
+                        .map(|(name, schema, db_table_type, comment)| {
+                            let table_type = match db_table_type.as_str() {
+                                "table" => TableType::Table,
+                                "view" => TableType::View,
+                                "materialized" => TableType::MaterializedView,
+                                _ => TableType::Other(db_table_type),
+                            };
+
+                            TableMetadata::new(&name, &schema)
+                                .with_type(table_type)
+                                .with_comment(comment.unwrap_or_default())
+                        })
+                        .collect())
+                };
+
+                let tables = list_tables_paginated(
+                    &self.paging,
+                    &self.loaded,
+                    self.on_page.as_ref(),
+                    fetch_page,
+                )
+                .await?;
 
                 eprintln!("!!! list_tables() returning {} tables", tables.len());
                 tracing::info!("!!! list_tables() returning {} tables", tables.len());
@@ -456,6 +706,9 @@ impl Catalog for LivePostgreSQLCatalog {
     /// Get column metadata for a specific table
     ///
     /// Queries information_schema.columns and pg_catalog to get column information.
+    /// Foreign key targets come from pg_constraint, pairing each `conkey`
+    /// column with its `confkey` counterpart. `table` is matched as stored,
+    /// so a table created as `"Users"` is looked up as `Users`.
     async fn get_columns(&self, table: &str) -> CatalogResult<Vec<ColumnMetadata>> {
         tracing::debug!(
             "!!! LivePostgreSQLCatalog::get_columns() called for table: {}",
@@ -470,15 +723,22 @@ impl Catalog for LivePostgreSQLCatalog {
                     c.column_name,
                     c.data_type,
                     c.is_nullable,
-                    c.column_default,
+                    COALESCE(
+                        c.column_default,
+                        CASE WHEN c.is_identity = 'YES'
+                            THEN 'GENERATED ' || c.identity_generation || ' AS IDENTITY'
+                        END
+                    ) as column_default,
                     pgd.description as column_comment,
                     CASE
                         WHEN pk.column_name IS NOT NULL THEN 'YES'
                         ELSE 'NO'
-                    END as is_primary_key
+                    END as is_primary_key,
+                    fk.ref_table,
+                    fk.ref_column
                 FROM information_schema.columns c
                 LEFT JOIN pg_catalog.pg_description pgd
-                    ON pgd.objoid = (c.table_schema||'.'||c.table_name)::regclass
+                    ON pgd.objoid = (quote_ident(c.table_schema)||'.'||quote_ident(c.table_name))::regclass
                     AND pgd.objsubid = c.ordinal_position
                 LEFT JOIN (
                     SELECT ku.column_name
@@ -489,6 +749,20 @@ impl Catalog for LivePostgreSQLCatalog {
                         AND tc.table_schema = 'public'
                         AND tc.table_name = $1
                 ) pk ON pk.column_name = c.column_name
+                LEFT JOIN LATERAL (
+                    SELECT rc.relname::text AS ref_table, ra.attname::text AS ref_column
+                    FROM pg_catalog.pg_constraint con
+                    CROSS JOIN LATERAL unnest(con.conkey, con.confkey) AS k(attnum, ref_attnum)
+                    JOIN pg_catalog.pg_attribute a
+                        ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+                    JOIN pg_catalog.pg_class rc ON rc.oid = con.confrelid
+                    JOIN pg_catalog.pg_attribute ra
+                        ON ra.attrelid = con.confrelid AND ra.attnum = k.ref_attnum
+                    WHERE con.contype = 'f'
+                        AND con.conrelid = (quote_ident(c.table_schema)||'.'||quote_ident(c.table_name))::regclass
+                        AND a.attname = c.column_name
+                    LIMIT 1
+                ) fk ON true
                 WHERE c.table_schema NOT IN ('pg_catalog', 'information_schema')
                   AND c.table_name = $1
                 ORDER BY c.ordinal_position
@@ -503,6 +777,8 @@ impl Catalog for LivePostgreSQLCatalog {
                     Option<String>,
                     Option<String>,
                     String,
+                    Option<String>,
+                    Option<String>,
                 ),
             >(query)
             .bind(table)
@@ -510,32 +786,46 @@ impl Catalog for LivePostgreSQLCatalog {
             .await
             .map_err(|e| {
                 tracing::error!("!!! Failed to get columns: {}", e);
-                CatalogError::QueryFailed(format!(
-                    "Failed to get columns for table '{}': {}",
-                    table, e
-                ))
+                CatalogError::from_sqlx(&e, Some(query), self.timeout_secs)
             })?;
 
             tracing::debug!("!!! get_columns query returned {} rows", rows.len());
 
             let columns = rows
                 .into_iter()
-                .map(|(name, data_type, is_nullable, _default, comment, is_pk)| {
-                    tracing::debug!("!!! Found column: {} ({})", name, data_type);
-                    let dt = Self::parse_postgres_type(&data_type);
-                    let nullable = is_nullable == "YES";
-                    let is_pk = is_pk == "YES";
-
-                    let mut col = ColumnMetadata::new(name, dt)
-                        .with_nullable(nullable)
-                        .with_comment(comment.unwrap_or_default());
-
-                    if is_pk {
-                        col = col.with_primary_key();
-                    }
-
-                    col
-                })
+                .map(
+                    |(
+                        name,
+                        data_type,
+                        is_nullable,
+                        default,
+                        comment,
+                        is_pk,
+                        ref_table,
+                        ref_column,
+                    )| {
+                        tracing::debug!("!!! Found column: {} ({})", name, data_type);
+                        let dt = Self::parse_postgres_type(&data_type);
+                        let nullable = is_nullable == "YES";
+                        let is_pk = is_pk == "YES";
+
+                        let mut col = ColumnMetadata::new(name, dt)
+                            .with_nullable(nullable)
+                            .with_comment(comment.unwrap_or_default());
+
+                        if let Some(default) = default {
+                            col = col.with_default(default);
+                        }
+                        if is_pk {
+                            col = col.with_primary_key();
+                        }
+                        if let (Some(ref_table), Some(ref_column)) = (ref_table, ref_column) {
+                            col = col.with_foreign_key(ref_table, ref_column);
+                        }
+
+                        col
+                    },
+                )
                 .collect();
 
             return Ok(columns);
@@ -557,48 +847,198 @@ impl Catalog for LivePostgreSQLCatalog {
 
     /// List all available functions
     ///
-    /// Returns a list of built-in PostgreSQL functions and custom functions.
+    /// Returns the built-in PostgreSQL functions merged with the routines of
+    /// user schemas from `pg_proc`, see
+    /// [`FunctionRegistry::with_catalog_functions`](unified_sql_lsp_function_registry::FunctionRegistry::with_catalog_functions).
+    /// Trigger functions aren't callable and are left out.
     async fn list_functions(&self) -> CatalogResult<Vec<FunctionMetadata>> {
         use unified_sql_lsp_function_registry::FunctionRegistry;
         use unified_sql_lsp_ir::Dialect;
 
-        // Start with builtin functions from function-registry
-        let registry = FunctionRegistry::new();
-        let builtin_functions = registry.get_functions(Dialect::PostgreSQL);
-        let mut all_functions: Vec<FunctionMetadata> = builtin_functions;
+        let user_functions = self.list_user_functions().await?;
+
+        Ok(FunctionRegistry::new()
+            .with_catalog_functions(Dialect::PostgreSQL, user_functions)
+            .get_functions(Dialect::PostgreSQL))
+    }
 
-        // Add custom functions from database
+    /// List roles from `pg_roles`
+    ///
+    /// A failure to read the table is logged once and yields no roles.
+    async fn list_roles(&self) -> CatalogResult<Vec<String>> {
         #[cfg(feature = "postgresql")]
         if let Some(pool) = &self.pool {
-            // Query custom functions from pg_catalog.pg_proc
-            let custom_query = r#"
-                SELECT
-                    p.proname as function_name,
-                    pg_get_function_result(p.oid) as return_type,
-                    pg_get_function_arguments(p.oid) as arguments,
-                    n.nspname as schema_name
-                FROM pg_catalog.pg_proc p
-                JOIN pg_catalog.pg_namespace n ON p.pronamespace = n.oid
-                WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+            let query = "SELECT rolname FROM pg_catalog.pg_roles ORDER BY rolname";
+            let fetch = sqlx::query_scalar::<_, String>(query).fetch_all(pool);
+            return match tokio::time::timeout(
+                std::time::Duration::from_secs(self.timeout_secs),
+                fetch,
+            )
+            .await
+            .map_err(|_| CatalogError::Timeout(self.timeout_secs))?
+            {
+                Ok(roles) => Ok(roles),
+                Err(e) => {
+                    tracing::warn!("Cannot read roles from pg_roles: {}", e);
+                    Ok(Vec::new())
+                }
+            };
+        }
+
+        Ok(Vec::new())
+    }
+
+    /// List collations from `pg_collation`
+    ///
+    /// Only collations usable with the database encoding are listed; a
+    /// failure to read the catalog is logged and yields no collations.
+    async fn list_collations(&self) -> CatalogResult<Vec<String>> {
+        #[cfg(feature = "postgresql")]
+        if let Some(pool) = &self.pool {
+            let query = r#"
+                SELECT DISTINCT collname::text
+                FROM pg_catalog.pg_collation
+                WHERE collencoding IN (-1, pg_catalog.pg_char_to_encoding(pg_catalog.getdatabaseencoding()))
+                ORDER BY 1
             "#;
+            let fetch = sqlx::query_scalar::<_, String>(query).fetch_all(pool);
+            return match tokio::time::timeout(
+                std::time::Duration::from_secs(self.timeout_secs),
+                fetch,
+            )
+            .await
+            .map_err(|_| CatalogError::Timeout(self.timeout_secs))?
+            {
+                Ok(collations) => Ok(collations),
+                Err(e) => {
+                    tracing::warn!("Cannot read collations from pg_collation: {}", e);
+                    Ok(Vec::new())
+                }
+            };
+        }
 
-            let custom_funcs: Vec<FunctionMetadata> =
-                sqlx::query_as::<_, (String, String, String, String)>(custom_query)
-                    .fetch_all(pool)
-                    .await
-                    .unwrap_or(vec![]) // Don't fail if pg_proc not accessible
+        Ok(Vec::new())
+    }
+
+    /// List sequences from `pg_sequences`
+    ///
+    /// Only sequences the user may read are listed; a failure to read the
+    /// view is logged and yields no sequences.
+    async fn list_sequences(&self) -> CatalogResult<Vec<SequenceMetadata>> {
+        #[cfg(feature = "postgresql")]
+        if let Some(pool) = &self.pool {
+            let query = r#"
+                SELECT schemaname::text, sequencename::text, increment_by, start_value, last_value
+                FROM pg_catalog.pg_sequences
+                WHERE schemaname NOT IN ('pg_catalog', 'information_schema')
+                  AND has_sequence_privilege(
+                      quote_ident(schemaname) || '.' || quote_ident(sequencename), 'SELECT'
+                  )
+                ORDER BY schemaname, sequencename
+            "#;
+            let fetch =
+                sqlx::query_as::<_, (String, String, i64, i64, Option<i64>)>(query).fetch_all(pool);
+            return match tokio::time::timeout(
+                std::time::Duration::from_secs(self.timeout_secs),
+                fetch,
+            )
+            .await
+            .map_err(|_| CatalogError::Timeout(self.timeout_secs))?
+            {
+                Ok(rows) => Ok(rows
                     .into_iter()
-                    .map(|(name, ret, _args, schema)| {
-                        FunctionMetadata::new(&name, Self::parse_postgres_type(&ret))
-                            .with_type(FunctionType::Scalar)
-                            .with_description(format!("Custom function from {}", schema))
+                    .map(|(schema, name, increment, start, last_value)| {
+                        let sequence = SequenceMetadata::new(name, schema)
+                            .with_increment(increment)
+                            .with_start(start);
+                        match last_value {
+                            Some(last_value) => sequence.with_last_value(last_value),
+                            None => sequence,
+                        }
                     })
-                    .collect();
+                    .collect()),
+                Err(e) => {
+                    tracing::warn!("Cannot read sequences from pg_sequences: {}", e);
+                    Ok(Vec::new())
+                }
+            };
+        }
+
+        Ok(Vec::new())
+    }
+
+    /// Pages the running `list_tables` has published so far
+    fn loaded_tables(&self) -> Option<Vec<TableMetadata>> {
+        let tables = self.loaded.snapshot();
+        (!tables.is_empty()).then_some(tables)
+    }
+
+    /// Fetch the first rows of a table
+    ///
+    /// Every column is cast to text so any column type renders as text.
+    /// The query is cancelled after the catalog's timeout.
+    async fn sample_rows(&self, table: &str, limit: usize) -> CatalogResult<SampleRows> {
+        #[cfg(feature = "postgresql")]
+        if let Some(pool) = &self.pool {
+            use sqlx::Row;
+
+            let columns: Vec<String> = self
+                .get_columns(table)
+                .await?
+                .into_iter()
+                .map(|c| c.name)
+                .collect();
+            if columns.is_empty() {
+                return Err(CatalogError::TableNotFound(
+                    table.to_string(),
+                    "public".to_string(),
+                ));
+            }
 
-            all_functions.extend(custom_funcs);
+            let select_list = columns
+                .iter()
+                .map(|c| format!("{}::text", Self::quote_identifier(c)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let query = format!(
+                "SELECT {} FROM {} LIMIT {}",
+                select_list,
+                Self::quote_identifier(table),
+                limit
+            );
+
+            let fetch = sqlx::query(&query).fetch_all(pool);
+            let rows =
+                tokio::time::timeout(std::time::Duration::from_secs(self.timeout_secs), fetch)
+                    .await
+                    .map_err(|_| CatalogError::Timeout(self.timeout_secs))?
+                    .map_err(|e| CatalogError::from_sqlx(&e, Some(&query), self.timeout_secs))?;
+
+            let rows = rows
+                .iter()
+                .map(|row| {
+                    (0..columns.len())
+                        .map(|i| row.try_get::<Option<String>, _>(i))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| CatalogError::from_sqlx(&e, Some(&query), self.timeout_secs))?;
+
+            return Ok(SampleRows { columns, rows });
+        } else {
+            return Err(CatalogError::ConnectionFailed(
+                "Database pool not initialized".to_string(),
+            ));
         }
 
-        Ok(all_functions)
+        #[cfg(not(feature = "postgresql"))]
+        return Err(CatalogError::NotSupported(format!(
+            "sample_rows requires 'postgresql' feature enabled (table: '{}', limit: {})",
+            table, limit
+        )));
+
+        #[cfg(all(feature = "postgresql", not(feature = "postgresql")))]
+        unreachable!()
     }
 }
 
@@ -606,6 +1046,74 @@ impl Catalog for LivePostgreSQLCatalog {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_function_arguments() {
+        let params = LivePostgreSQLCatalog::parse_function_arguments(
+            "p_order_id integer, p_note character varying(20) DEFAULT 'x'::text, \
+             OUT total numeric, numeric(10,2), double precision, VARIADIC tags text[]",
+        );
+        let summary: Vec<(&str, bool, bool)> = params
+            .iter()
+            .map(|p| (p.name.as_str(), p.has_default, p.is_variadic))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("p_order_id", false, false),
+                ("p_note", true, false),
+                ("", false, false),
+                ("", false, false),
+                ("tags", false, true),
+            ]
+        );
+        assert_eq!(params[0].data_type, DataType::Integer);
+        assert_eq!(params[1].data_type, DataType::Varchar(Some(20)));
+        assert_eq!(params[2].data_type, DataType::Decimal);
+
+        assert!(LivePostgreSQLCatalog::parse_function_arguments("").is_empty());
+    }
+
+    #[test]
+    fn test_routine_metadata() {
+        let function = LivePostgreSQLCatalog::routine_metadata(
+            "order_total",
+            Some("numeric"),
+            "p_order_id integer",
+            "public",
+            "f",
+            Some("Sum of the line items of an order".to_string()),
+        );
+        assert_eq!(function.function_type, FunctionType::Scalar);
+        assert_eq!(function.return_type, DataType::Decimal);
+        assert_eq!(function.schema.as_deref(), Some("public"));
+        assert!(!function.is_builtin);
+        assert_eq!(
+            function.description.as_deref(),
+            Some("Sum of the line items of an order")
+        );
+
+        let rows = LivePostgreSQLCatalog::routine_metadata(
+            "active_users",
+            Some("SETOF users"),
+            "",
+            "app",
+            "f",
+            Some(String::new()),
+        );
+        assert_eq!(rows.function_type, FunctionType::Table);
+        assert_eq!(rows.description, None);
+
+        let procedure = LivePostgreSQLCatalog::routine_metadata(
+            "archive",
+            None,
+            "days integer",
+            "app",
+            "p",
+            None,
+        );
+        assert_eq!(procedure.return_type, DataType::Other("void".to_string()));
+    }
+
     #[test]
     fn test_parse_postgres_varchar() {
         let dt = LivePostgreSQLCatalog::parse_postgres_type("character varying(255)");
@@ -732,6 +1240,22 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_quote_identifier() {
+        assert_eq!(
+            LivePostgreSQLCatalog::quote_identifier("users"),
+            "\"users\""
+        );
+        assert_eq!(
+            LivePostgreSQLCatalog::quote_identifier("app.users"),
+            "\"app\".\"users\""
+        );
+        assert_eq!(
+            LivePostgreSQLCatalog::quote_identifier("we\"ird"),
+            "\"we\"\"ird\""
+        );
+    }
+
     #[tokio::test]
     async fn test_new_catalog_invalid_connection_string() {
         let result = LivePostgreSQLCatalog::new("").await;
@@ -10,10 +10,21 @@
 
 // Re-export all metadata types from the ir crate
 pub use unified_sql_lsp_ir::{
-    ColumnMetadata, DataType, FunctionMetadata, FunctionParameter, FunctionType, TableMetadata,
-    TableReference, TableType,
+    ColumnMetadata, DataType, FunctionMetadata, FunctionParameter, FunctionType, SequenceMetadata,
+    TableMetadata, TableReference, TableType,
 };
 
+/// First rows of a table, as returned by [`crate::Catalog::sample_rows`]
+///
+/// Values are the database's text rendering of each cell; `None` is SQL NULL.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SampleRows {
+    /// Column names, in table order
+    pub columns: Vec<String>,
+    /// Row values, one entry per column
+    pub rows: Vec<Vec<Option<String>>>,
+}
+
 /// Format a DataType to a display string
 ///
 /// Converts the DataType enum to a human-readable string representation.
@@ -0,0 +1,64 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Null Catalog
+//!
+//! The catalog used when neither a database connection nor DDL files are
+//! configured. It knows no tables, columns or functions and never fails, so
+//! callers can keep treating every catalog alike; completion checks
+//! [`Catalog::has_schema`] to offer keywords and builtin functions instead.
+//!
+//! ## Usage
+//!
+//! ```rust,ignore
+//! use unified_sql_lsp_catalog::{Catalog, NullCatalog};
+//!
+//! let catalog = NullCatalog;
+//! assert!(!catalog.has_schema());
+//! assert!(catalog.list_tables().await?.is_empty());
+//! ```
+
+use async_trait::async_trait;
+
+use crate::metadata::{ColumnMetadata, FunctionMetadata, TableMetadata};
+use crate::{Catalog, CatalogResult};
+
+/// Catalog without any schema
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullCatalog;
+
+#[async_trait]
+impl Catalog for NullCatalog {
+    async fn list_tables(&self) -> CatalogResult<Vec<TableMetadata>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_columns(&self, _table: &str) -> CatalogResult<Vec<ColumnMetadata>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_functions(&self) -> CatalogResult<Vec<FunctionMetadata>> {
+        Ok(Vec::new())
+    }
+
+    fn has_schema(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_null_catalog_is_empty_and_never_fails() {
+        let catalog = NullCatalog;
+        assert!(!catalog.has_schema());
+        assert!(catalog.list_tables().await.unwrap().is_empty());
+        assert!(catalog.get_columns("users").await.unwrap().is_empty());
+        assert!(catalog.list_functions().await.unwrap().is_empty());
+        assert!(catalog.list_roles().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,305 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Paginated table listing
+//!
+//! A database with tens of thousands of tables returns more rows from one
+//! information_schema query than drivers comfortably buffer. The live
+//! catalogs therefore list tables in pages of [`TablePaging::page_size`],
+//! ordered by `(schema, name)`; each page starts after the last key of the
+//! previous one (keyset pagination), so later pages don't get slower.
+//!
+//! Pages are published to [`LoadedTables`] as they arrive, which lets
+//! completion serve the first pages while later ones are still loading
+//! (see [`crate::Catalog::loaded_tables`]). Listing stops at
+//! [`TablePaging::max_tables`]; the remaining tables are skipped with a
+//! warning.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::error::CatalogResult;
+use crate::metadata::TableMetadata;
+
+/// Default number of tables fetched per query
+pub const DEFAULT_TABLE_PAGE_SIZE: usize = 1000;
+
+/// Default cap on the number of listed tables
+pub const DEFAULT_MAX_TABLES: usize = 50_000;
+
+/// Page size and cap of a paginated table listing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TablePaging {
+    /// Tables fetched per query
+    pub page_size: usize,
+
+    /// Tables listed at most; the rest are skipped with a warning
+    pub max_tables: usize,
+}
+
+impl Default for TablePaging {
+    fn default() -> Self {
+        Self {
+            page_size: DEFAULT_TABLE_PAGE_SIZE,
+            max_tables: DEFAULT_MAX_TABLES,
+        }
+    }
+}
+
+impl TablePaging {
+    /// Paging with the given page size and the default cap
+    pub fn new(page_size: usize) -> Self {
+        Self {
+            page_size,
+            ..Default::default()
+        }
+    }
+
+    /// Set the cap on listed tables
+    pub fn with_max_tables(mut self, max_tables: usize) -> Self {
+        self.max_tables = max_tables;
+        self
+    }
+}
+
+/// Progress of a paginated listing, reported after every page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TablePageProgress {
+    /// Pages fetched so far
+    pub pages: usize,
+
+    /// Tables listed so far
+    pub tables: usize,
+
+    /// Whether this was the last page
+    pub done: bool,
+}
+
+/// Callback receiving the [`TablePageProgress`] of each page
+pub type TablePageHook = Arc<dyn Fn(TablePageProgress) + Send + Sync>;
+
+/// Tables published by the running (or last) paginated listing
+///
+/// Every listing starts a new generation; pages of a listing that was
+/// superseded by a newer one are dropped, so concurrent listings never mix.
+#[derive(Debug, Default)]
+pub struct LoadedTables {
+    generation: AtomicU64,
+    tables: Mutex<(u64, Vec<TableMetadata>)>,
+}
+
+impl LoadedTables {
+    /// Tables published so far
+    pub fn snapshot(&self) -> Vec<TableMetadata> {
+        self.tables.lock().unwrap().1.clone()
+    }
+
+    /// Start a new listing, dropping what earlier listings published
+    fn start(&self) -> u64 {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        *self.tables.lock().unwrap() = (generation, Vec::new());
+        generation
+    }
+
+    /// Publish a page of the listing `generation`, unless it was superseded
+    fn publish(&self, generation: u64, page: &[TableMetadata]) {
+        let mut tables = self.tables.lock().unwrap();
+        if tables.0 == generation {
+            tables.1.extend_from_slice(page);
+        }
+    }
+}
+
+/// List tables page by page
+///
+/// `fetch_page(after, limit)` returns at most `limit` tables ordered by
+/// `(schema, name)`, all after the `(schema, name)` key `after`. A page
+/// shorter than `limit` ends the listing. Every page is published to
+/// `loaded` and reported to `on_page` before the next one is requested.
+pub async fn list_tables_paginated<F, Fut>(
+    paging: &TablePaging,
+    loaded: &LoadedTables,
+    on_page: Option<&TablePageHook>,
+    mut fetch_page: F,
+) -> CatalogResult<Vec<TableMetadata>>
+where
+    F: FnMut(Option<(String, String)>, usize) -> Fut,
+    Fut: Future<Output = CatalogResult<Vec<TableMetadata>>>,
+{
+    let generation = loaded.start();
+    let page_size = paging.page_size.max(1);
+    let mut tables: Vec<TableMetadata> = Vec::new();
+    let mut pages = 0;
+
+    loop {
+        // One row past the cap tells whether any table gets skipped
+        let remaining = paging.max_tables - tables.len();
+        let limit = page_size.min(remaining + 1);
+        let after = tables.last().map(|t| (t.schema.clone(), t.name.clone()));
+        let mut page = fetch_page(after, limit).await?;
+        pages += 1;
+
+        let capped = page.len() > remaining;
+        if capped {
+            page.truncate(remaining);
+            tracing::warn!(
+                "Listed {} tables, the configured maximum; skipping the remaining tables",
+                paging.max_tables
+            );
+        }
+        let done = capped || page.len() < limit;
+
+        loaded.publish(generation, &page);
+        tables.extend(page);
+        tracing::debug!(
+            "Listed table page {} ({} tables so far)",
+            pages,
+            tables.len()
+        );
+        if let Some(on_page) = on_page {
+            on_page(TablePageProgress {
+                pages,
+                tables: tables.len(),
+                done,
+            });
+        }
+
+        if done {
+            return Ok(tables);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Page source over `count` tables named `t0000`, `t0001`, ...
+    ///
+    /// Records the size of every requested page and, before serving a page,
+    /// what `loaded` held at that point.
+    struct MockSource {
+        tables: Vec<TableMetadata>,
+        requests: Mutex<Vec<usize>>,
+        seen_loaded: Mutex<Vec<usize>>,
+    }
+
+    impl MockSource {
+        fn new(count: usize) -> Self {
+            Self {
+                tables: (0..count)
+                    .map(|i| TableMetadata::new(format!("t{:04}", i), "public"))
+                    .collect(),
+                requests: Mutex::new(Vec::new()),
+                seen_loaded: Mutex::new(Vec::new()),
+            }
+        }
+
+        async fn page(
+            &self,
+            loaded: &LoadedTables,
+            after: Option<(String, String)>,
+            limit: usize,
+        ) -> CatalogResult<Vec<TableMetadata>> {
+            self.requests.lock().unwrap().push(limit);
+            self.seen_loaded
+                .lock()
+                .unwrap()
+                .push(loaded.snapshot().len());
+            Ok(self
+                .tables
+                .iter()
+                .filter(|t| after.as_ref().is_none_or(|(_, name)| t.name > *name))
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pages_are_available_incrementally() {
+        let source = MockSource::new(25);
+        let loaded = LoadedTables::default();
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let hook: TablePageHook = {
+            let progress = progress.clone();
+            Arc::new(move |p| progress.lock().unwrap().push(p))
+        };
+
+        let tables = list_tables_paginated(
+            &TablePaging::new(10),
+            &loaded,
+            Some(&hook),
+            |after, limit| source.page(&loaded, after, limit),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(tables.len(), 25);
+        assert_eq!(tables[10].name, "t0010");
+        assert_eq!(*source.requests.lock().unwrap(), vec![10, 10, 10]);
+        // Each page was published before the next one was requested
+        assert_eq!(*source.seen_loaded.lock().unwrap(), vec![0, 10, 20]);
+        assert_eq!(loaded.snapshot().len(), 25);
+
+        let progress = progress.lock().unwrap();
+        assert_eq!(progress.len(), 3);
+        assert_eq!(
+            progress[2],
+            TablePageProgress {
+                pages: 3,
+                tables: 25,
+                done: true
+            }
+        );
+        assert!(!progress[0].done);
+    }
+
+    #[tokio::test]
+    async fn test_listing_stops_at_max_tables() {
+        let source = MockSource::new(25);
+        let loaded = LoadedTables::default();
+
+        let tables = list_tables_paginated(
+            &TablePaging::new(10).with_max_tables(15),
+            &loaded,
+            None,
+            |after, limit| source.page(&loaded, after, limit),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(tables.len(), 15);
+        assert_eq!(tables.last().unwrap().name, "t0014");
+        assert_eq!(*source.requests.lock().unwrap(), vec![10, 6]);
+        assert_eq!(loaded.snapshot().len(), 15);
+
+        // Exactly `max_tables` tables: nothing is skipped
+        let source = MockSource::new(15);
+        let tables = list_tables_paginated(
+            &TablePaging::new(10).with_max_tables(15),
+            &loaded,
+            None,
+            |after, limit| source.page(&loaded, after, limit),
+        )
+        .await
+        .unwrap();
+        assert_eq!(tables.len(), 15);
+    }
+
+    #[test]
+    fn test_superseded_listing_is_not_published() {
+        let loaded = LoadedTables::default();
+        let stale = loaded.start();
+        let current = loaded.start();
+
+        loaded.publish(stale, &[TableMetadata::new("old", "public")]);
+        loaded.publish(current, &[TableMetadata::new("new", "public")]);
+
+        let names: Vec<_> = loaded.snapshot().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["new"]);
+    }
+}
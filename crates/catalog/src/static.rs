@@ -17,22 +17,63 @@
 //! ```
 
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+
+use unified_sql_lsp_ir::Dialect;
+use unified_sql_lsp_ir::dialect::DialectFamily;
 
 use crate::metadata::{ColumnMetadata, DataType, FunctionMetadata, TableMetadata, TableType};
 use crate::{Catalog, CatalogError, CatalogResult};
 
+/// Common MySQL collations
+const MYSQL_COLLATIONS: &[&str] = &[
+    "utf8mb4_0900_ai_ci",
+    "utf8mb4_0900_as_cs",
+    "utf8mb4_general_ci",
+    "utf8mb4_unicode_ci",
+    "utf8mb4_bin",
+    "utf8mb3_general_ci",
+    "latin1_swedish_ci",
+    "ascii_general_ci",
+    "binary",
+];
+
+/// Collations every PostgreSQL server has, plus common ICU and libc ones
+const POSTGRES_COLLATIONS: &[&str] = &[
+    "default",
+    "C",
+    "POSIX",
+    "ucs_basic",
+    "und-x-icu",
+    "en-US-x-icu",
+    "en_US",
+    "en_US.utf8",
+];
+
+/// Shortlist of collations for catalogs that can't list the server's
+///
+/// Used by [`StaticCatalog`] and by completion when a catalog returns no
+/// collations of its own.
+pub fn builtin_collations(dialect: Dialect) -> Vec<String> {
+    let collations = match dialect.family() {
+        DialectFamily::MySQL => MYSQL_COLLATIONS,
+        DialectFamily::PostgreSQL => POSTGRES_COLLATIONS,
+    };
+    collations.iter().map(|name| name.to_string()).collect()
+}
+
 /// Static catalog with predefined schema data
 ///
 /// This is used for the playground and testing without requiring a real database.
 pub struct StaticCatalog {
-    tables: HashMap<String, TableMetadata>,
+    /// Ordered by name so tables are listed the same every time
+    tables: BTreeMap<String, TableMetadata>,
 }
 
 impl StaticCatalog {
     /// Create a new static catalog with default playground schema
     pub fn new() -> Self {
-        let mut tables = HashMap::new();
+        let mut tables = BTreeMap::new();
 
         // Users table
         tables.insert(
@@ -264,6 +305,11 @@ impl Catalog for StaticCatalog {
         // Return empty list for now
         Ok(vec![])
     }
+
+    /// The playground schema is MySQL's, so are its collations
+    async fn list_collations(&self) -> CatalogResult<Vec<String>> {
+        Ok(builtin_collations(Dialect::MySQL))
+    }
 }
 
 #[cfg(test)]
@@ -293,6 +339,16 @@ mod tests {
         assert!(matches!(result, Err(CatalogError::TableNotFound(_, _))));
     }
 
+    #[tokio::test]
+    async fn test_static_catalog_lists_builtin_collations() {
+        let collations = StaticCatalog::new().list_collations().await.unwrap();
+        assert!(collations.contains(&"utf8mb4_general_ci".to_string()));
+
+        let postgres = builtin_collations(Dialect::PostgreSQL);
+        assert!(postgres.contains(&"C".to_string()));
+        assert!(!postgres.contains(&"utf8mb4_general_ci".to_string()));
+    }
+
     #[tokio::test]
     async fn test_static_catalog_from_file() {
         let catalog = StaticCatalog::from_file("test.sql").unwrap();
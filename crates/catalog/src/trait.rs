@@ -7,8 +7,11 @@
 //!
 //! This module defines the async Catalog trait used for querying database schema information.
 
-use crate::error::CatalogResult;
-use crate::metadata::{ColumnMetadata, FunctionMetadata, TableMetadata};
+use crate::error::{CatalogError, CatalogResult};
+use crate::metadata::{
+    ColumnMetadata, FunctionMetadata, SampleRows, SequenceMetadata, TableMetadata,
+};
+use std::sync::Arc;
 
 /// Catalog trait for database schema abstraction
 ///
@@ -42,7 +45,7 @@ pub trait Catalog: Send + Sync {
     /// # Errors
     ///
     /// Returns `CatalogError::ConnectionFailed` if database connection fails.
-    /// Returns `CatalogError::QueryTimeout` if the query exceeds timeout.
+    /// Returns `CatalogError::Timeout` if the query exceeds timeout.
     ///
     /// # Examples
     ///
@@ -79,6 +82,20 @@ pub trait Catalog: Send + Sync {
     /// ```
     async fn get_columns(&self, table: &str) -> CatalogResult<Vec<ColumnMetadata>>;
 
+    /// Get column metadata for a specific table as a shared list
+    ///
+    /// Callers that only read the columns should prefer this over
+    /// [`Catalog::get_columns`]: caching catalogs hand every caller the same
+    /// list, so wide tables aren't copied per request. The default wraps
+    /// `get_columns`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Catalog::get_columns`].
+    async fn get_columns_shared(&self, table: &str) -> CatalogResult<Arc<[ColumnMetadata]>> {
+        self.get_columns(table).await.map(Arc::from)
+    }
+
     /// List all available functions
     ///
     /// Returns metadata for all functions available in the database,
@@ -91,7 +108,7 @@ pub trait Catalog: Send + Sync {
     /// # Errors
     ///
     /// Returns `CatalogError::ConnectionFailed` if database connection fails.
-    /// Returns `CatalogError::QueryTimeout` if the query exceeds timeout.
+    /// Returns `CatalogError::Timeout` if the query exceeds timeout.
     ///
     /// # Examples
     ///
@@ -102,4 +119,81 @@ pub trait Catalog: Send + Sync {
     ///     .collect();
     /// ```
     async fn list_functions(&self) -> CatalogResult<Vec<FunctionMetadata>>;
+
+    /// List the role and user names that privileges can be granted to
+    ///
+    /// Catalogs without access to account information keep the default,
+    /// which returns no roles. Implementations that can't read the role
+    /// tables (e.g. for lack of privileges) should also return no roles.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CatalogError::Timeout` if the query exceeds timeout.
+    async fn list_roles(&self) -> CatalogResult<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// List the collation names that `COLLATE` accepts
+    ///
+    /// Catalogs without a server to ask keep the default, which returns no
+    /// collations; callers fall back to [`crate::builtin_collations`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `CatalogError::Timeout` if the query exceeds timeout.
+    async fn list_collations(&self) -> CatalogResult<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// List the sequences that `nextval` and `setval` can use
+    ///
+    /// Only PostgreSQL has standalone sequences; other catalogs keep the
+    /// default, which returns no sequences.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CatalogError::Timeout` if the query exceeds timeout.
+    async fn list_sequences(&self) -> CatalogResult<Vec<SequenceMetadata>> {
+        Ok(Vec::new())
+    }
+
+    /// Tables already listed by a paginated `list_tables` call
+    ///
+    /// While a listing is still loading later pages, this returns the pages
+    /// that arrived so far (see [`crate::paging`]). Catalogs that list tables
+    /// in one go keep the default, which returns `None`.
+    fn loaded_tables(&self) -> Option<Vec<TableMetadata>> {
+        None
+    }
+
+    /// Whether the catalog describes a schema at all
+    ///
+    /// Only the [`crate::NullCatalog`], used when no schema source is
+    /// configured, returns `false`; an empty database still has a schema.
+    /// Overlay catalogs forward this to the catalog they wrap.
+    fn has_schema(&self) -> bool {
+        true
+    }
+
+    /// Fetch the first rows of a table
+    ///
+    /// Only live database catalogs support this; schema-only catalogs keep
+    /// the default, which never touches any data.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - Table name (may include schema qualifier like "schema.table")
+    /// * `limit` - Maximum number of rows to return
+    ///
+    /// # Errors
+    ///
+    /// Returns `CatalogError::NotSupported` for catalogs without data access.
+    /// Returns `CatalogError::Timeout` if the query exceeds timeout.
+    async fn sample_rows(&self, table: &str, limit: usize) -> CatalogResult<SampleRows> {
+        let _ = limit;
+        Err(CatalogError::NotSupported(format!(
+            "sample rows are not available for table '{}'",
+            table
+        )))
+    }
 }
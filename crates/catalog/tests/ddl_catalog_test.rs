@@ -0,0 +1,220 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! Integration tests for loading a catalog from a dumped schema.sql file
+
+use std::path::PathBuf;
+
+use unified_sql_lsp_catalog::{
+    Catalog, CatalogError, DataType, DdlCatalog, DdlWarningKind, TableType,
+};
+use unified_sql_lsp_ir::Dialect;
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/schema.sql")
+}
+
+fn multi_schema_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/multi_schema")
+}
+
+fn load_fixture() -> DdlCatalog {
+    DdlCatalog::from_sql_file(fixture_path(), Dialect::MySQL).expect("fixture should load")
+}
+
+#[tokio::test]
+async fn test_ddl_catalog_loads_all_tables() {
+    let catalog = load_fixture();
+    let tables = catalog.list_tables().await.unwrap();
+
+    let names: Vec<&str> = tables.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "users",
+            "products",
+            "orders",
+            "order_items",
+            "audit_log",
+            "active_products"
+        ]
+    );
+    assert_eq!(catalog.path(), Some(fixture_path().as_path()));
+}
+
+#[tokio::test]
+async fn test_ddl_catalog_skips_malformed_statement_with_warning() {
+    let catalog = load_fixture();
+
+    assert_eq!(catalog.warnings().len(), 1);
+    assert!(catalog.warnings()[0].message.contains("broken"));
+    assert!(matches!(
+        catalog.get_columns("broken").await,
+        Err(CatalogError::TableNotFound(_, _))
+    ));
+}
+
+#[tokio::test]
+async fn test_ddl_catalog_get_columns_types_and_nullability() {
+    let catalog = load_fixture();
+    let columns = catalog.get_columns("users").await.unwrap();
+
+    assert_eq!(columns.len(), 4);
+    assert_eq!(columns[0].name, "id");
+    assert_eq!(columns[0].data_type, DataType::Integer);
+    assert_eq!(columns[1].data_type, DataType::Varchar(Some(255)));
+    assert_eq!(columns[1].comment.as_deref(), Some("Login email"));
+    assert!(!columns[1].nullable);
+    assert!(columns[2].nullable);
+    assert_eq!(columns[3].data_type, DataType::Timestamp);
+    assert_eq!(
+        columns[3].default_value.as_deref(),
+        Some("CURRENT_TIMESTAMP")
+    );
+
+    let products = catalog.get_columns("products").await.unwrap();
+    assert_eq!(products[2].data_type, DataType::Decimal);
+    assert_eq!(
+        products[3].data_type,
+        DataType::Enum(vec!["active".to_string(), "retired".to_string()])
+    );
+    assert_eq!(products[4].data_type, DataType::Json);
+
+    let audit = catalog.get_columns("audit_log").await.unwrap();
+    assert_eq!(audit[4].data_type, DataType::Boolean);
+}
+
+#[tokio::test]
+async fn test_ddl_catalog_primary_and_foreign_keys() {
+    let catalog = load_fixture();
+
+    let users = catalog.get_columns("users").await.unwrap();
+    assert!(users[0].is_primary_key);
+    assert!(!users[1].is_primary_key);
+
+    let orders = catalog.get_columns("orders").await.unwrap();
+    assert!(orders[0].is_primary_key);
+    assert_eq!(orders[0].data_type, DataType::BigInt);
+    assert!(orders[1].is_foreign_key);
+    let reference = orders[1].references.as_ref().unwrap();
+    assert_eq!(reference.table, "users");
+    assert_eq!(reference.column, "id");
+
+    let items = catalog.get_columns("order_items").await.unwrap();
+    assert!(items[0].is_primary_key && items[1].is_primary_key);
+    assert_eq!(items[0].references.as_ref().unwrap().table, "orders");
+    assert_eq!(items[1].references.as_ref().unwrap().table, "products");
+}
+
+#[tokio::test]
+async fn test_ddl_catalog_views_resolve_source_columns() {
+    let catalog = load_fixture();
+    let tables = catalog.list_tables().await.unwrap();
+    let view = tables.iter().find(|t| t.name == "active_products").unwrap();
+    assert_eq!(view.table_type, TableType::View);
+
+    let columns = catalog.get_columns("active_products").await.unwrap();
+    let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["id", "code", "price"]);
+    assert_eq!(columns[1].data_type, DataType::Char(Some(12)));
+    assert!(!columns[0].is_primary_key);
+}
+
+#[tokio::test]
+async fn test_ddl_catalog_postgres_alter_table_constraints() {
+    let catalog = DdlCatalog::from_sql(
+        r#"
+        CREATE TABLE public.accounts (
+            id integer NOT NULL,
+            owner_id integer,
+            balance numeric(12,2) DEFAULT 0,
+            tags text[]
+        );
+        ALTER TABLE ONLY public.accounts ADD CONSTRAINT accounts_pkey PRIMARY KEY (id);
+        ALTER TABLE ONLY public.accounts
+            ADD CONSTRAINT accounts_owner_fk FOREIGN KEY (owner_id) REFERENCES public.users(id);
+        "#,
+        Dialect::PostgreSQL,
+    );
+
+    assert!(catalog.warnings().is_empty());
+    let columns = catalog.get_columns("public.accounts").await.unwrap();
+    assert!(columns[0].is_primary_key);
+    assert!(!columns[0].nullable);
+    assert_eq!(columns[1].references.as_ref().unwrap().table, "users");
+    assert_eq!(
+        columns[3].data_type,
+        DataType::Array(Box::new(DataType::Text))
+    );
+}
+
+#[test]
+fn test_ddl_catalog_missing_file_is_configuration_error() {
+    let result = DdlCatalog::from_sql_file("/nonexistent/schema.sql", Dialect::MySQL);
+    assert!(matches!(result, Err(CatalogError::ConfigurationError(_))));
+}
+
+#[tokio::test]
+async fn test_ddl_catalog_multiple_files_resolve_forward_references() {
+    let catalog =
+        DdlCatalog::from_sql_files(&[multi_schema_dir().join("*.sql")], Dialect::MySQL).unwrap();
+
+    let files: Vec<_> = catalog
+        .paths()
+        .iter()
+        .map(|p| p.file_name().unwrap())
+        .collect();
+    assert_eq!(files, vec!["001_orders.sql", "002_users.sql"]);
+
+    // FK added in the first file against a table from the second
+    let orders = catalog.get_columns("orders").await.unwrap();
+    assert!(orders[1].is_foreign_key);
+    assert_eq!(orders[1].references.as_ref().unwrap().table, "users");
+
+    // View in the first file reads source columns from the second
+    let view = catalog.get_columns("user_orders").await.unwrap();
+    assert_eq!(view[0].name, "email");
+    assert_eq!(view[0].data_type, DataType::Varchar(Some(255)));
+}
+
+#[tokio::test]
+async fn test_ddl_catalog_multiple_files_duplicates_and_error_counts() {
+    let dir = multi_schema_dir();
+    // Listed out of order; loading still follows lexical order
+    let catalog = DdlCatalog::from_sql_files(
+        &[dir.join("002_users.sql"), dir.join("001_orders.sql")],
+        Dialect::MySQL,
+    )
+    .unwrap();
+
+    let settings = catalog.get_columns("settings").await.unwrap();
+    assert_eq!(settings.len(), 2, "last definition wins");
+    let duplicates: Vec<_> = catalog
+        .warnings()
+        .iter()
+        .filter(|w| w.kind == DdlWarningKind::DuplicateTable)
+        .collect();
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(
+        duplicates[0].file.as_deref(),
+        Some(dir.join("002_users.sql").as_path())
+    );
+
+    let counts: Vec<_> = catalog
+        .error_counts()
+        .into_iter()
+        .map(|(path, count)| (path.file_name().unwrap().to_owned(), count))
+        .collect();
+    assert_eq!(
+        counts,
+        vec![("001_orders.sql".into(), 1), ("002_users.sql".into(), 0)]
+    );
+}
+
+#[test]
+fn test_ddl_catalog_pattern_without_matches_is_configuration_error() {
+    let result = DdlCatalog::from_sql_files(&[multi_schema_dir().join("*.ddl")], Dialect::MySQL);
+    assert!(matches!(result, Err(CatalogError::ConfigurationError(_))));
+}
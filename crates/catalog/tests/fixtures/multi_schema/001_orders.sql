@@ -0,0 +1,15 @@
+-- Fixture for ddl_catalog_test.rs: references users from 002_users.sql
+CREATE TABLE orders (
+  id BIGINT PRIMARY KEY,
+  user_id INT NOT NULL,
+  total DECIMAL(12,2)
+);
+
+ALTER TABLE orders ADD CONSTRAINT fk_orders_user FOREIGN KEY (user_id) REFERENCES users (id);
+
+CREATE VIEW user_orders AS
+  SELECT u.email, o.total FROM users u JOIN orders o ON o.user_id = u.id;
+
+CREATE TABLE settings (name VARCHAR(50));
+
+CREATE TABLE broken;
@@ -0,0 +1,10 @@
+-- Fixture for ddl_catalog_test.rs: defines the table 001_orders.sql references
+CREATE TABLE users (
+  id INT PRIMARY KEY,
+  email VARCHAR(255) NOT NULL
+);
+
+-- Redefines settings from 001_orders.sql; this definition wins
+CREATE TABLE settings (name VARCHAR(50), value TEXT);
+
+INSERT INTO settings (name, value) VALUES ('theme', 'dark');
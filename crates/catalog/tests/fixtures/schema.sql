@@ -0,0 +1,53 @@
+-- Fixture schema dump used by ddl_catalog_test.rs
+SET NAMES utf8mb4;
+
+CREATE TABLE IF NOT EXISTS `users` (
+  `id` INT NOT NULL AUTO_INCREMENT,
+  `email` VARCHAR(255) NOT NULL COMMENT 'Login email',
+  `display_name` VARCHAR(100) DEFAULT NULL,
+  `created_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `uk_users_email` (`email`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='User accounts';
+
+CREATE TABLE `products` (
+  `id` INT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+  `sku` CHAR(12) NOT NULL,
+  `price` DECIMAL(10,2) NOT NULL DEFAULT '0.00',
+  `status` ENUM('active','retired') NOT NULL DEFAULT 'active',
+  `attributes` JSON
+) ENGINE=InnoDB;
+
+CREATE TABLE `orders` (
+  `id` BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+  `user_id` INT NOT NULL,
+  `total` DECIMAL(12,2) NOT NULL,
+  `placed_at` DATETIME,
+  PRIMARY KEY (`id`),
+  KEY `idx_orders_user` (`user_id`),
+  CONSTRAINT `fk_orders_user` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE
+);
+
+CREATE TABLE `order_items` (
+  `order_id` BIGINT UNSIGNED NOT NULL,
+  `product_id` INT NOT NULL REFERENCES `products` (`id`),
+  `quantity` SMALLINT NOT NULL DEFAULT 1,
+  PRIMARY KEY (`order_id`, `product_id`),
+  CONSTRAINT FOREIGN KEY (`order_id`) REFERENCES `orders` (`id`)
+);
+
+-- Intentionally malformed: must be skipped without aborting the load
+CREATE TABLE `broken` ;
+
+CREATE TABLE `audit_log` (
+  `id` BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+  `actor_id` INT,
+  `action` VARCHAR(32) NOT NULL,
+  `payload` TEXT,
+  `is_system` TINYINT(1) NOT NULL DEFAULT 0
+);
+
+INSERT INTO `users` (`email`) VALUES ('seed@example.com');
+
+CREATE VIEW `active_products` AS
+  SELECT p.id, p.sku AS code, p.price FROM products p WHERE p.status = 'active';
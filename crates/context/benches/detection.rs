@@ -0,0 +1,44 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! Text-based context detection on minified single-line queries
+//!
+//! Run with `cargo bench -p unified-sql-lsp-context`. Detection at the end
+//! of a 50KB line should stay under a millisecond.
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use unified_sql_lsp_context::{Position, detect_completion_context_from_text};
+
+/// A single-line query of about `len` bytes whose last clause is `tail`
+fn minified_query(len: usize, tail: &str) -> String {
+    let mut query = String::from("SELECT ");
+    let mut i = 0;
+    while query.len() < len {
+        query.push_str(&format!("u.column_{i}, "));
+        i += 1;
+    }
+    query.push_str("u.id FROM users u JOIN orders o ON o.user_id = u.id ");
+    query.push_str(tail);
+    query
+}
+
+fn bench_detection(c: &mut Criterion) {
+    let mut group = c.benchmark_group("detect_50kb_single_line");
+    for (name, tail) in [
+        ("where_qualifier", "WHERE o."),
+        ("join", "LEFT JOIN "),
+        ("order_by", "ORDER BY "),
+    ] {
+        let source = minified_query(50 * 1024, tail);
+        let position = Position::new(0, source.len() as u32);
+        group.bench_function(name, |b| {
+            b.iter(|| detect_completion_context_from_text(black_box(position), black_box(&source)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_detection);
+criterion_main!(benches);
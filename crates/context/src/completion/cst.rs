@@ -0,0 +1,805 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # CST context detectors
+//!
+//! Each detector handles one kind of clause node. The pipeline asks them for
+//! every ancestor of the node at the cursor, so [`DetectionInput::node`] is
+//! the ancestor being examined. When the tree around the cursor is too
+//! incomplete to decide, a detector defers to the text stage with
+//! [`detect_from_text`].
+
+use tracing::debug;
+use tree_sitter::Node;
+
+use super::pipeline::{ContextDetector, DetectionInput, detect_from_text};
+use super::text::innermost_open_paren;
+use super::{CompletionContext, WindowFunctionPart, extract_table_qualifier};
+use crate::cst_utils::{Position, extract_identifier_name, position_to_byte_offset, walk_tree};
+use crate::table_extractor::{TableExtractor, TableRefInfo};
+use crate::tokens::lex;
+
+/// SELECT projection list, or a window specification inside it
+pub struct CstSelect;
+
+impl ContextDetector for CstSelect {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let node = input.node.filter(|n| n.kind() == "select_statement")?;
+        let (source, position) = (input.source, input.position);
+
+        if is_in_projection(&node, position) {
+            // If CST extraction failed (incomplete SQL), use text-based fallback
+            let mut tables: Vec<String> = extract_tables_from_from_clause(&node, source)
+                .iter()
+                .map(TableRefInfo::reference)
+                .collect();
+            if tables.is_empty() {
+                debug!(
+                    "!!! LSP: CST extraction returned empty tables, using text-based extraction"
+                );
+                tables = input.statement_tables();
+            }
+            retain_table_names(&mut tables);
+
+            if let Some(window_part) = enclosing_over_clause(input.text_before) {
+                return Some(CompletionContext::WindowFunctionClause {
+                    tables,
+                    window_part,
+                });
+            }
+
+            let qualifier = input
+                .qualifier
+                .clone()
+                .or_else(|| extract_table_qualifier_from_position(source, position));
+            return Some(CompletionContext::SelectProjection { tables, qualifier });
+        }
+
+        // Right after SELECT with an empty projection list, e.g. "SELECT | FROM table"
+        if is_right_after_select_keyword(&node, source, position) {
+            let text_before = input.text_before;
+
+            // CST parsing is often incomplete for nested queries, so the
+            // table of a subquery is taken from its text
+            let is_subquery = text_before.matches('(').count() > text_before.matches(')').count();
+            let tables = if is_subquery {
+                subquery_tables(source, text_before)
+            } else {
+                let mut tables: Vec<String> = extract_tables_from_from_clause(&node, source)
+                    .iter()
+                    .map(TableRefInfo::reference)
+                    .collect();
+                if tables.is_empty() {
+                    tables = input.statement_tables();
+                }
+                retain_table_names(&mut tables);
+                tables
+            };
+
+            let qualifier = input
+                .qualifier
+                .clone()
+                .or_else(|| extract_table_qualifier_from_position(source, position));
+            return Some(CompletionContext::SelectProjection { tables, qualifier });
+        }
+
+        None
+    }
+}
+
+/// FROM clause table list
+pub struct CstFrom;
+
+impl ContextDetector for CstFrom {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let node = input.node.filter(|n| n.kind() == "from_clause")?;
+
+        // tree-sitter may parse an incomplete OVER clause as a from_clause
+        if let Some(window_part) = enclosing_over_clause(input.text_before) {
+            return Some(CompletionContext::WindowFunctionClause {
+                tables: input.statement_tables(),
+                window_part,
+            });
+        }
+
+        // Incomplete SQL may also mark subquery content as a from_clause; a
+        // cursor before the node is likely in a subquery projection
+        if input.byte_offset < node.start_byte() {
+            return Some(detect_from_text(input));
+        }
+
+        // Tables already in the FROM clause are excluded in JOIN contexts
+        Some(CompletionContext::FromClause {
+            exclude_tables: extract_tables_from_from_clause_node(&node, input.source),
+        })
+    }
+}
+
+/// WHERE clause
+pub struct CstWhere;
+
+impl ContextDetector for CstWhere {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        input.node.filter(|n| n.kind() == "where_clause")?;
+        Some(CompletionContext::WhereClause {
+            tables: input
+                .dml_tables()
+                .unwrap_or_else(|| input.statement_tables()),
+            qualifier: input.qualifier.clone(),
+        })
+    }
+}
+
+/// Common table expression: its name or its subquery projection
+pub struct CstCte;
+
+impl ContextDetector for CstCte {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        input
+            .node
+            .filter(|n| matches!(n.kind(), "common_table_expression" | "cte"))?;
+        let text_before = input.text_before;
+        let text_upper = text_before.to_ascii_uppercase();
+
+        // Pattern: "WITH cte_name | AS" or "WITH | AS"
+        if text_upper.ends_with("WITH ")
+            || text_upper.ends_with("WITH,")
+            || text_upper.ends_with("WITH\t")
+        {
+            return Some(CompletionContext::CteDefinition {
+                available_tables: input.statement_tables(),
+                defined_ctes: vec![],
+            });
+        }
+
+        // Pattern: "WITH cte_name AS (SELECT |" or inside CTE subquery
+        if text_upper.contains(" AS (")
+            && let Some(select_pos) = text_upper.rfind("SELECT ")
+        {
+            let after_select = &text_upper[select_pos + 7..];
+            let open_parens = text_before.matches('(').count();
+            let close_parens = text_before.matches(')').count();
+
+            if open_parens > close_parens && !after_select.contains("FROM ") {
+                return Some(CompletionContext::SelectProjection {
+                    tables: input.statement_tables(),
+                    qualifier: input.qualifier.clone(),
+                });
+            }
+        }
+
+        Some(detect_from_text(input))
+    }
+}
+
+/// Window specification (OVER clause or WINDOW definition)
+pub struct CstWindow;
+
+impl ContextDetector for CstWindow {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        input
+            .node
+            .filter(|n| matches!(n.kind(), "window_specification" | "window_definition"))?;
+        debug!("!!! LSP CST: Found window_specification/window_definition node");
+        let text_upper = input.text_before.to_ascii_uppercase();
+
+        let window_part = if text_upper.ends_with("OVER (") || text_upper.ends_with("OVER( ") {
+            WindowFunctionPart::OverStart
+        } else if text_upper.ends_with("PARTITION BY ") || text_upper.ends_with("PARTITION BY") {
+            WindowFunctionPart::PartitionBy
+        } else if text_upper.ends_with("ORDER BY ") || text_upper.ends_with("ORDER BY") {
+            WindowFunctionPart::OrderBy
+        } else {
+            // Text-based detection handles the more complex cases
+            return Some(detect_from_text(input));
+        };
+
+        Some(CompletionContext::WindowFunctionClause {
+            tables: input.statement_tables(),
+            window_part,
+        })
+    }
+}
+
+/// JOIN clause: the joined table or its ON condition
+pub struct CstJoin;
+
+impl ContextDetector for CstJoin {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let node = input.node.filter(|n| n.kind() == "join_clause")?;
+        let (source, text_before) = (input.source, input.text_before);
+
+        // Inside a joined subquery, e.g. "JOIN (SELECT | FROM orders)"
+        if text_before.matches('(').count() > text_before.matches(')').count() {
+            let text_before_upper = text_before.to_ascii_uppercase();
+            if text_before_upper.ends_with("SELECT ")
+                || text_before_upper.ends_with("SELECT\t")
+                || text_before_upper.ends_with("SELECT(")
+                || text_before_upper.trim_end().ends_with("(SELECT")
+            {
+                return Some(CompletionContext::SelectProjection {
+                    tables: subquery_tables(source, text_before),
+                    qualifier: None,
+                });
+            }
+
+            return Some(detect_from_text(input));
+        }
+
+        // Without the right table yet, the user is typing the table name
+        let (left_table, right_table, join_type) = extract_join_tables(&node, source);
+        if right_table.is_none() {
+            return Some(CompletionContext::FromClause {
+                exclude_tables: extract_tables_from_join_parent(&node, source),
+            });
+        }
+
+        // CROSS and NATURAL joins have no ON condition to complete
+        if join_type.as_deref().is_some_and(is_conditionless_join_type) {
+            return Some(detect_from_text(input));
+        }
+
+        // Otherwise the cursor is in the ON condition
+        Some(CompletionContext::JoinCondition {
+            left_table,
+            right_table,
+            qualifier: None,
+        })
+    }
+}
+
+/// Part of an unclosed `OVER (` specification before the cursor
+fn enclosing_over_clause(text_before: &str) -> Option<WindowFunctionPart> {
+    let text_upper = text_before.to_ascii_uppercase();
+    let over_pos = text_upper
+        .rfind("OVER (")
+        .or_else(|| text_upper.rfind("OVER("))?;
+    let after_over = &text_upper[over_pos..];
+    if after_over.matches('(').count() <= after_over.matches(')').count() {
+        return None;
+    }
+
+    if after_over.contains("PARTITION BY") {
+        if let Some(order_pos) = after_over.rfind("ORDER BY")
+            && !after_over[order_pos + 8..].contains(')')
+        {
+            return Some(WindowFunctionPart::OrderBy);
+        }
+        return Some(WindowFunctionPart::PartitionBy);
+    }
+    if after_over.contains("ORDER BY") {
+        return Some(WindowFunctionPart::OrderBy);
+    }
+    Some(WindowFunctionPart::OverStart)
+}
+
+/// Drop SQL keywords that were incorrectly extracted as table names
+fn retain_table_names(tables: &mut Vec<String>) {
+    tables.retain(|t| {
+        !matches!(
+            t.as_str(),
+            "SELECT" | "FROM" | "WHERE" | "JOIN" | "ON" | "AND" | "OR" | "*"
+        )
+    });
+}
+
+/// Tables of the subquery whose unclosed `(` is nearest the end of
+/// `text_before`, read from the rest of `source`
+fn subquery_tables(source: &str, text_before: &str) -> Vec<String> {
+    let Some((paren, _)) = innermost_open_paren(text_before) else {
+        return Vec::new();
+    };
+    TableExtractor::statement_tables(&source[paren + 1..])
+        .iter()
+        .map(TableRefInfo::reference)
+        .collect()
+}
+
+/// Extract table qualifier based on cursor position
+/// This is a more precise version that uses the cursor position
+fn extract_table_qualifier_from_position(source: &str, position: Position) -> Option<String> {
+    // Get the byte position of the cursor; the end of the source is valid
+    let byte_offset = position_to_byte_offset(source, position);
+
+    // Get text before cursor
+    let text_before = &source[..byte_offset];
+
+    // Use extract_table_qualifier on the text before cursor
+    extract_table_qualifier(text_before)
+}
+
+/// Check if the position is within the SELECT projection list
+fn is_in_projection(select_node: &Node, position: Position) -> bool {
+    // The projection is typically the first child after "SELECT" keyword
+    for child in select_node.children(&mut select_node.walk()) {
+        if child.kind() == "projection" {
+            let start = child.start_position();
+            let end = child.end_position();
+
+            return position.line as usize >= start.row
+                && position.line as usize <= end.row
+                && position.character as usize >= start.column;
+        }
+    }
+
+    false
+}
+
+/// Check if the position is right after the SELECT keyword
+/// This handles cases where the projection list is empty (e.g., "SELECT | FROM table")
+fn is_right_after_select_keyword(select_node: &Node, source: &str, position: Position) -> bool {
+    // Get the byte offset of the cursor position
+    let byte_offset = position_to_byte_offset(source, position);
+
+    // Get the text before the cursor
+    let text_before = &source[..byte_offset];
+
+    // Check if the text ends with "SELECT" (possibly with whitespace)
+    let text_upper = text_before.trim_end().to_ascii_uppercase();
+    eprintln!(
+        "!!! is_right_after_select_keyword: text_upper='{}'",
+        text_upper
+    );
+    if text_upper.ends_with("SELECT")
+        || text_upper.ends_with("SELECT ")
+        || text_upper.ends_with("SELECT\t")
+    {
+        eprintln!("!!! is_right_after_select_keyword: text ends with SELECT");
+        // Additional check: make sure we're after the SELECT keyword within this select_statement
+        // Find the SELECT keyword position in the source
+        let node_start = select_node.byte_range().start;
+        let node_end = select_node.byte_range().end;
+
+        // Look for SELECT keyword within this node
+        // Use rfind to get the LAST SELECT before the cursor (handles CTEs with nested SELECT)
+        let node_end = node_end.min(source.len());
+        let node_text = &source[node_start..node_end];
+        let cursor_in_node = byte_offset.clamp(node_start, node_end) - node_start;
+        let node_text_before_cursor = &node_text[..cursor_in_node];
+        // ASCII only, so offsets in the uppercase text are offsets in the source
+        let node_text_before_cursor_upper = node_text_before_cursor.to_ascii_uppercase();
+
+        if let Some(select_pos) = node_text_before_cursor_upper.rfind("SELECT") {
+            let absolute_select_pos = node_start + select_pos + 6; // +6 for "SELECT"
+            // Check if cursor is after SELECT and within reasonable distance (e.g., within 10 chars)
+            if byte_offset >= absolute_select_pos && byte_offset - absolute_select_pos <= 10 {
+                // Also check that there's no FROM keyword between SELECT and cursor
+                let text_before_cursor_in_node = &node_text[select_pos + 6..cursor_in_node];
+
+                // If we haven't reached FROM yet, we're in the projection
+                !text_before_cursor_in_node
+                    .to_ascii_uppercase()
+                    .contains("FROM")
+            } else {
+                false
+            }
+        } else {
+            false
+        }
+    } else {
+        false
+    }
+}
+
+/// Tables of the FROM clause of a select statement, with their aliases
+fn extract_tables_from_from_clause(select_node: &Node, source: &str) -> Vec<TableRefInfo> {
+    let mut tables = Vec::new();
+
+    // Try to find from_clause as a direct child first
+    let mut found_from = false;
+    for child in select_node.named_children(&mut select_node.walk()) {
+        if child.kind() == "from_clause" {
+            extract_table_refs(&child, source, &mut tables);
+            found_from = true;
+        }
+    }
+
+    // If not found as a direct child, search the entire tree
+    if !found_from {
+        find_and_extract_from_clause(select_node, source, &mut tables);
+    }
+
+    debug!(?tables, "Tables of the FROM clause");
+    tables
+}
+
+/// Search for from_clause nodes and extract their tables
+fn find_and_extract_from_clause(node: &Node, source: &str, tables: &mut Vec<TableRefInfo>) {
+    walk_tree(node, |node| {
+        if node.kind() != "from_clause" {
+            return true;
+        }
+        extract_table_refs(&node, source, tables);
+        false
+    });
+}
+
+/// Extract the tables of the table_reference and join_clause nodes of a
+/// node and its descendants
+///
+/// An `alias` belongs to the `table_name` before it; tables written as
+/// subqueries are skipped.
+fn extract_table_refs(node: &Node, source: &str, tables: &mut Vec<TableRefInfo>) {
+    walk_tree(node, |node| {
+        match node.kind() {
+            "table_reference" | "join_clause" => {
+                let children: Vec<Node> = node.named_children(&mut node.walk()).collect();
+                tables.extend(table_ref(&children, source));
+                // Dialects nest joins inside table references
+                for child in children.iter().filter(|c| c.kind() == "join_clause") {
+                    extract_table_refs(child, source, tables);
+                }
+            }
+            // A table name outside a table reference, its alias following
+            "table_name" => {
+                let name_and_alias: Vec<Node> =
+                    std::iter::successors(Some(node), |n| n.next_named_sibling())
+                        .take(2)
+                        .collect();
+                tables.extend(table_ref(&name_and_alias, source));
+            }
+            _ => return true,
+        }
+        false
+    });
+}
+
+/// The table named by the first `table_name` of `nodes`, with the alias
+/// right after it
+fn table_ref(nodes: &[Node], source: &str) -> Option<TableRefInfo> {
+    let position = nodes.iter().position(|n| n.kind() == "table_name")?;
+    let table = TableRefInfo::new(extract_identifier_name(&nodes[position], source)?);
+    let alias = nodes
+        .get(position + 1)
+        .filter(|n| n.kind() == "alias")
+        .and_then(|alias| extract_identifier_name(alias, source));
+    Some(match alias {
+        Some(alias) => table.with_alias(alias),
+        None => table,
+    })
+}
+
+/// Extract table qualifier if cursor is after a dot
+pub fn extract_qualifier(node: &Node, source: &str, position: Position) -> Option<String> {
+    // Check if the node contains a dot and cursor is after it
+    let node_text = &source[node.byte_range()];
+    let cursor_offset = position_to_byte_offset(source, position);
+
+    // Find dots in the node text; a dot inside a quoted identifier like
+    // `"Total.Rows"` doesn't end a qualifier
+    if let Some(dot_pos) = last_unquoted_dot(node_text) {
+        let dot_abs_pos = node.start_byte() + dot_pos;
+        if cursor_offset > dot_abs_pos {
+            // Cursor is after the dot, extract qualifier (text before dot)
+            let qualifier = node_text[..dot_pos].trim();
+            return Some(qualifier.to_string());
+        }
+    }
+
+    None
+}
+
+/// Byte offset of the last `.` of `text` outside quoted identifiers
+fn last_unquoted_dot(text: &str) -> Option<usize> {
+    lex(text)
+        .filter(|token| token.is_symbol('.'))
+        .last()
+        .map(|token| token.offset)
+}
+
+/// Names of the tables of a from_clause node, without aliases
+fn extract_tables_from_from_clause_node(from_node: &Node, source: &str) -> Vec<String> {
+    let mut tables = Vec::new();
+    extract_table_refs(from_node, source, &mut tables);
+    tables.iter().map(TableRefInfo::qualified_name).collect()
+}
+
+/// Names of the tables of the FROM clause when in a JOIN context
+///
+/// This walks up from the join_clause to find the select_statement,
+/// then extracts tables from the FROM clause.
+fn extract_tables_from_join_parent(join_node: &Node, source: &str) -> Vec<String> {
+    let mut tables = Vec::new();
+
+    // Walk up to find the select_statement
+    let mut current = join_node.parent();
+    while let Some(node) = current {
+        if node.kind() == "select_statement" {
+            // Found the select statement, now find the from_clause
+            for child in node.children(&mut node.walk()) {
+                if child.kind() == "from_clause" {
+                    extract_table_refs(&child, source, &mut tables);
+                    break;
+                }
+            }
+            break;
+        }
+        current = node.parent();
+    }
+
+    tables.iter().map(TableRefInfo::qualified_name).collect()
+}
+
+/// Extract left and right tables and the join type from a join clause
+///
+/// For a JOIN like `users LEFT JOIN orders ON users.id = orders.user_id`,
+/// this extracts ("users", "orders", "LEFT"). Tables with an alias are
+/// given by their alias.
+fn extract_join_tables(
+    join_node: &Node,
+    source: &str,
+) -> (Option<String>, Option<String>, Option<String>) {
+    // Get parent from_clause to find the left table
+    let mut left_table = None;
+    let mut right_table = None;
+    let mut join_start = join_node.start_byte();
+
+    // First, try to get the right table (the table being joined)
+    // In the join_clause node, the table_name is typically the second child (after JOIN keyword)
+    let mut walk = join_node.walk();
+    let mut children = join_node.children(&mut walk);
+    let mut found_join_keyword = false;
+
+    for child in &mut children {
+        if source[child.byte_range()].eq_ignore_ascii_case("JOIN") {
+            join_start = child.start_byte();
+        }
+        match child.kind() {
+            "JOIN" | "INNER" | "LEFT" | "RIGHT" | "FULL" | "CROSS" | "join_type" => {
+                found_join_keyword = true;
+            }
+            "table_name" | "table_reference" if found_join_keyword => {
+                let mut refs = Vec::new();
+                extract_table_refs(&child, source, &mut refs);
+                if let Some(table) = refs.first() {
+                    right_table = Some(table.reference());
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Now, try to get the left table from the parent context
+    // Walk up to find the from_clause and get tables before this join
+    if let Some(parent) = join_node.parent()
+        && (parent.kind() == "from_clause" || parent.kind() == "select_statement")
+    {
+        // Look for table_reference nodes that come before this join
+        let from_tables = extract_tables_from_from_clause(&parent, source);
+        if !from_tables.is_empty() {
+            // The last table before the join is typically the left table
+            left_table = from_tables.first().map(TableRefInfo::reference);
+        }
+    }
+
+    // The grammar has no CROSS join type, so its keyword may sit in an
+    // error node before the clause; read the type from the text instead
+    let join_type = join_type_before(&source[..join_start]);
+
+    (left_table, right_table, join_type)
+}
+
+/// Join type keywords at the end of `text`, which stops before `JOIN`
+///
+/// Returns e.g. "LEFT OUTER" or "CROSS", or `None` for a plain JOIN.
+fn join_type_before(text: &str) -> Option<String> {
+    let mut words: Vec<String> = text
+        .split_whitespace()
+        .rev()
+        .map(str::to_uppercase)
+        .take_while(|word| {
+            matches!(
+                word.as_str(),
+                "INNER" | "LEFT" | "RIGHT" | "FULL" | "OUTER" | "CROSS" | "NATURAL"
+            )
+        })
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    words.reverse();
+    Some(words.join(" "))
+}
+
+/// Whether a join of `join_type` takes no ON or USING condition
+fn is_conditionless_join_type(join_type: &str) -> bool {
+    join_type == "CROSS" || join_type.starts_with("NATURAL")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::completion::pipeline::CST;
+    use crate::cst_utils::find_node_at_position;
+    use tree_sitter::Parser;
+    use unified_sql_grammar::{DialectVersion, language_for_dialect_with_version};
+    use unified_sql_lsp_ir::Dialect;
+
+    /// Ask `detector` about each ancestor of the node at `|`, innermost first
+    fn detect_at_marker(
+        detector: &dyn ContextDetector,
+        dialect: Dialect,
+        marked: &str,
+    ) -> Option<CompletionContext> {
+        let version = match dialect {
+            Dialect::PostgreSQL => DialectVersion::PostgreSQL14,
+            _ => DialectVersion::MySQL80,
+        };
+        let lang = language_for_dialect_with_version(dialect, Some(version))
+            .expect("Failed to get language");
+        let mut parser = Parser::new();
+        parser.set_language(lang).expect("Failed to set language");
+
+        let cursor = marked.find('|').expect("case has a cursor marker");
+        let source = marked.replacen('|', "", 1);
+        let tree = parser.parse(&source, None).expect("Failed to parse");
+        let root = tree.root_node();
+        let position = Position::new(0, cursor as u32);
+        let input = DetectionInput::new(Some(root), &source, position);
+
+        let mut current = find_node_at_position(&root, position, &source);
+        while let Some(node) = current {
+            let mut at_node = input.clone();
+            at_node.node = Some(node);
+            if let Some(ctx) = detector.detect(&at_node) {
+                return Some(ctx);
+            }
+            current = node.parent();
+        }
+        None
+    }
+
+    #[test]
+    fn test_detectors_need_a_node() {
+        let input = DetectionInput::new(None, "SELECT * FROM users WHERE ", Position::new(0, 26));
+        for detector in CST {
+            assert_eq!(detector.detect(&input), None);
+        }
+    }
+
+    #[test]
+    fn test_select_projection() {
+        let ctx = detect_at_marker(&CstSelect, Dialect::MySQL, "SELECT i|d FROM users");
+        assert_eq!(
+            ctx,
+            Some(CompletionContext::SelectProjection {
+                tables: vec!["users".to_string()],
+                qualifier: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_clause() {
+        let ctx = detect_at_marker(&CstFrom, Dialect::MySQL, "SELECT id FROM us|ers");
+        assert!(matches!(ctx, Some(CompletionContext::FromClause { .. })));
+    }
+
+    #[test]
+    fn test_where_clause() {
+        let ctx = detect_at_marker(
+            &CstWhere,
+            Dialect::MySQL,
+            "SELECT id FROM users WHERE i|d = 1",
+        );
+        assert!(matches!(
+            ctx,
+            Some(CompletionContext::WhereClause { ref tables, .. }) if tables == &["users"]
+        ));
+    }
+
+    #[test]
+    fn test_cte_only_handles_cte_nodes() {
+        // Neither grammar has a CTE node yet, so the text stage handles WITH
+        let ctx = detect_at_marker(
+            &CstCte,
+            Dialect::MySQL,
+            "WITH recent AS (SELECT i|d FROM orders) SELECT * FROM recent",
+        );
+        assert_eq!(ctx, None);
+    }
+
+    #[test]
+    fn test_window_definition() {
+        let ctx = detect_at_marker(
+            &CstWindow,
+            Dialect::PostgreSQL,
+            "SELECT rank() OVER w FROM emp WINDOW w AS (PARTITION BY dept ORDER BY |salary)",
+        );
+        assert!(matches!(
+            ctx,
+            Some(CompletionContext::WindowFunctionClause {
+                window_part: WindowFunctionPart::OrderBy,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_join_condition() {
+        let ctx = detect_at_marker(
+            &CstJoin,
+            Dialect::MySQL,
+            "SELECT * FROM users u JOIN orders o ON u.id = o.user_i|d",
+        );
+        assert!(matches!(ctx, Some(CompletionContext::JoinCondition { .. })));
+    }
+
+    #[test]
+    fn test_cross_join_has_no_condition() {
+        for dialect in [Dialect::MySQL, Dialect::PostgreSQL] {
+            let ctx = detect_at_marker(
+                &CstJoin,
+                dialect,
+                "SELECT * FROM users u CROSS JOIN orders o |",
+            );
+            assert!(!matches!(
+                ctx,
+                Some(CompletionContext::JoinCondition { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_join_type_before() {
+        assert_eq!(join_type_before("SELECT * FROM users u "), None);
+        assert_eq!(
+            join_type_before("SELECT * FROM users u left outer "),
+            Some("LEFT OUTER".to_string())
+        );
+        assert_eq!(
+            join_type_before("SELECT * FROM users u FULL "),
+            Some("FULL".to_string())
+        );
+        assert_eq!(
+            join_type_before("SELECT * FROM users u CROSS "),
+            Some("CROSS".to_string())
+        );
+
+        assert!(is_conditionless_join_type("CROSS"));
+        assert!(is_conditionless_join_type("NATURAL LEFT"));
+        assert!(!is_conditionless_join_type("FULL OUTER"));
+        assert!(!is_conditionless_join_type("LEFT"));
+    }
+
+    #[test]
+    fn test_enclosing_over_clause() {
+        assert_eq!(
+            enclosing_over_clause("SELECT rank() OVER ("),
+            Some(WindowFunctionPart::OverStart)
+        );
+        assert_eq!(
+            enclosing_over_clause("SELECT rank() over (partition by dept"),
+            Some(WindowFunctionPart::PartitionBy)
+        );
+        assert_eq!(
+            enclosing_over_clause("SELECT rank() OVER (PARTITION BY dept ORDER BY "),
+            Some(WindowFunctionPart::OrderBy)
+        );
+        assert_eq!(
+            enclosing_over_clause("SELECT rank() OVER (ORDER BY id) "),
+            None
+        );
+    }
+
+    #[test]
+    fn test_subquery_tables() {
+        let source = "SELECT * FROM users JOIN (SELECT  FROM orders o) o2";
+        assert_eq!(
+            subquery_tables(source, "SELECT * FROM users JOIN (SELECT "),
+            vec!["o".to_string()]
+        );
+        let source = "SELECT * FROM users JOIN (SELECT ";
+        assert!(subquery_tables(source, source).is_empty());
+    }
+
+    #[test]
+    fn test_last_unquoted_dot() {
+        assert_eq!(last_unquoted_dot("u."), Some(1));
+        assert_eq!(last_unquoted_dot("\"Total.Rows\"."), Some(12));
+        assert_eq!(last_unquoted_dot("`a.b`"), None);
+    }
+}
@@ -0,0 +1,377 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! Golden cases for completion context detection
+//!
+//! Expectations were recorded from the implementation before detection was
+//! split into a detector pipeline, quirks included, so reordering or
+//! changing detectors shows up here. [`CASES`] run without a tree, which
+//! exercises the text stages; [`PARSED_CASES`] run on a valid parse.
+
+use super::{CompletionContext, pipeline};
+use crate::cst_utils::Position;
+use tree_sitter::Parser;
+use unified_sql_grammar::{DialectVersion, language_for_dialect_with_version};
+use unified_sql_lsp_ir::Dialect;
+
+/// SQL with `|` at the cursor, and the expected context in `Debug` form
+const CASES: &[(&str, &str)] = &[
+    (
+        "|",
+        "Keywords { statement_type: None, existing_clauses: [] }",
+    ),
+    (
+        "SE|",
+        "Keywords { statement_type: None, existing_clauses: [] }",
+    ),
+    (
+        "SELECT |",
+        "SelectProjection { tables: [], qualifier: None }",
+    ),
+    (
+        "SELECT | FROM users",
+        r#"SelectProjection { tables: ["users"], qualifier: None }"#,
+    ),
+    (
+        "SELECT id, | FROM users",
+        r#"SelectProjection { tables: ["users"], qualifier: None }"#,
+    ),
+    (
+        "SELECT u.| FROM users u",
+        r#"SelectProjection { tables: ["u"], qualifier: Some("u") }"#,
+    ),
+    ("SELECT * FROM |", "FromClause { exclude_tables: [] }"),
+    (
+        "SELECT * FROM users JOIN |",
+        r#"FromClause { exclude_tables: ["users"] }"#,
+    ),
+    (
+        "SELECT * FROM users u LEFT JOIN |",
+        r#"FromClause { exclude_tables: ["users"] }"#,
+    ),
+    (
+        "SELECT * FROM users, |",
+        r#"FromClause { exclude_tables: ["users"] }"#,
+    ),
+    (
+        "SELECT * FROM users WHERE |",
+        r#"WhereClause { tables: ["users"], qualifier: None }"#,
+    ),
+    (
+        "SELECT * FROM users WHERE id = 1 AND |",
+        r#"WhereClause { tables: ["users"], qualifier: None }"#,
+    ),
+    (
+        "SELECT * FROM users u WHERE u.|",
+        r#"WhereClause { tables: ["u"], qualifier: Some("u") }"#,
+    ),
+    (
+        "SELECT * FROM users u JOIN orders o ON |",
+        r#"JoinCondition { left_table: Some("u"), right_table: Some("o"), qualifier: None }"#,
+    ),
+    (
+        "SELECT * FROM users u JOIN orders o ON u.id = o.|",
+        r#"JoinCondition { left_table: None, right_table: Some("o"), qualifier: Some("o") }"#,
+    ),
+    (
+        "SELECT * FROM users JOIN orders USING (|",
+        r#"JoinCondition { left_table: Some("users"), right_table: Some("orders"), qualifier: None }"#,
+    ),
+    (
+        "SELECT * FROM users ORDER BY |",
+        r#"OrderByClause { tables: ["users"], qualifier: None }"#,
+    ),
+    (
+        "SELECT * FROM users ORDER BY name, |",
+        r#"FromClause { exclude_tables: ["users"] }"#,
+    ),
+    (
+        "SELECT status FROM orders GROUP BY |",
+        r#"GroupByClause { tables: ["orders"], qualifier: None }"#,
+    ),
+    (
+        "SELECT status, COUNT(*) FROM orders GROUP BY status HAVING |",
+        r#"HavingClause { tables: ["orders"], qualifier: None }"#,
+    ),
+    ("SELECT * FROM users LIMIT |", "LimitClause"),
+    ("SELECT * FROM users LIMIT 10 OFFSET |", "LimitClause"),
+    (
+        "UPDATE |",
+        r#"Keywords { statement_type: Some("UPDATE"), existing_clauses: [] }"#,
+    ),
+    (
+        "INSERT |",
+        r#"Keywords { statement_type: Some("INSERT"), existing_clauses: [] }"#,
+    ),
+    (
+        "DELETE |",
+        r#"Keywords { statement_type: Some("DELETE"), existing_clauses: [] }"#,
+    ),
+    ("INSERT INTO |", "Unknown"),
+    ("DELETE FROM |", "FromClause { exclude_tables: [] }"),
+    ("UPDATE users SET |", "Unknown"),
+    (
+        "INSERT INTO users (name) VALUES ('x') RETURNING |",
+        r#"ReturningClause { tables: ["users"], qualifier: None }"#,
+    ),
+    (
+        "DELETE FROM users WHERE id = 1 RETURNING |",
+        r#"WhereClause { tables: ["users"], qualifier: None }"#,
+    ),
+    (
+        "SELECT id FROM users UNION |",
+        r#"Keywords { statement_type: Some("UNION"), existing_clauses: [] }"#,
+    ),
+    ("SELECT id FROM users UNION ALL |", "Unknown"),
+    (
+        "WITH |",
+        "CteDefinition { available_tables: [], defined_ctes: [] }",
+    ),
+    (
+        "WITH recent AS (SELECT * FROM orders), |",
+        r#"CteDefinition { available_tables: [], defined_ctes: ["recent"] }"#,
+    ),
+    (
+        "WITH recent AS (SELECT | FROM orders)",
+        r#"SelectProjection { tables: ["orders"], qualifier: None }"#,
+    ),
+    (
+        "WITH recent AS (SELECT * FROM orders) SELECT | FROM recent",
+        "Unknown",
+    ),
+    (
+        "WITH recent AS (SELECT * FROM orders) SELECT * FROM recent WHERE |",
+        r#"WhereClause { tables: ["recent"], qualifier: None }"#,
+    ),
+    (
+        "SELECT ROW_NUMBER() OVER (|) FROM users",
+        r#"WindowFunctionClause { tables: ["users"], window_part: OverStart }"#,
+    ),
+    (
+        "SELECT ROW_NUMBER() OVER (PARTITION BY |) FROM users",
+        r#"WindowFunctionClause { tables: ["users"], window_part: PartitionBy }"#,
+    ),
+    (
+        "SELECT ROW_NUMBER() OVER (PARTITION BY dept ORDER BY |) FROM employees",
+        r#"WindowFunctionClause { tables: ["employees"], window_part: OrderBy }"#,
+    ),
+    (
+        "SELECT SUM(x) OVER (ORDER BY |) FROM t",
+        r#"WindowFunctionClause { tables: ["t"], window_part: OrderBy }"#,
+    ),
+    ("SELECT RANK() OVER |", "WindowName { windows: [] }"),
+    (
+        "SELECT RANK() OVER w FROM t WINDOW w AS (PARTITION BY |)",
+        r#"WindowFunctionClause { tables: ["t"], window_part: PartitionBy }"#,
+    ),
+    (
+        "CREATE |",
+        r#"Keywords { statement_type: Some("CREATE"), existing_clauses: [] }"#,
+    ),
+    (
+        "ALTER |",
+        r#"Keywords { statement_type: Some("ALTER"), existing_clauses: [] }"#,
+    ),
+    (
+        "DROP |",
+        r#"Keywords { statement_type: Some("DROP"), existing_clauses: [] }"#,
+    ),
+    (
+        "CREATE TABLE t (id |",
+        r#"ColumnDefinition { column: "id", data_type: None }"#,
+    ),
+    (
+        "CREATE TABLE t (id INT |",
+        r#"ColumnDefinition { column: "id", data_type: Some("INT") }"#,
+    ),
+    (
+        "SELECT * FROM users WHERE name LIKE '|",
+        "StringLiteral { role: LikePattern }",
+    ),
+    (
+        "SELECT DATE_FORMAT(created_at, '|",
+        "StringLiteral { role: DateFormat }",
+    ),
+    (
+        "SELECT * FROM users WHERE name = 'a|",
+        "StringLiteral { role: Generic }",
+    ),
+    ("SELECT id::|", "TypeName"),
+    ("SELECT price::num|", "TypeName"),
+    (
+        "SELECT * FROM users ORDER BY name COLLATE |",
+        "CollationName { in_quotes: false }",
+    ),
+    (
+        "REVOKE SELECT ON users FROM |",
+        r#"RoleName { statement_type: "REVOKE" }"#,
+    ),
+    (
+        "MERGE |",
+        r#"Keywords { statement_type: Some("MERGE"), existing_clauses: [] }"#,
+    ),
+    ("MERGE INTO |", "FromClause { exclude_tables: [] }"),
+    (
+        "MERGE INTO target t USING |",
+        r#"FromClause { exclude_tables: ["target"] }"#,
+    ),
+    (
+        "MERGE INTO target t USING source s ON t.id = s.id WHEN MATCHED THEN UPDATE SET |",
+        r#"MergeClause { target: "target", source: Some("source"), target_only: true, qualifier: None }"#,
+    ),
+    (
+        "MERGE INTO target t USING source s ON t.id = s.id WHEN NOT MATCHED THEN INSERT (|",
+        r#"MergeClause { target: "target", source: Some("source"), target_only: true, qualifier: None }"#,
+    ),
+    (
+        "SELECT DISTINCT ON (|",
+        "DistinctOnList { tables: [], qualifier: None }",
+    ),
+    (
+        "SELECT DISTINCT ON (user_id) | FROM orders",
+        r#"SelectProjection { tables: ["orders"], qualifier: None }"#,
+    ),
+    (
+        "SELECT * FROM (SELECT | FROM orders) sub",
+        r#"SelectProjection { tables: ["orders"], qualifier: None }"#,
+    ),
+    (
+        "SELECT * FROM users;\nSELECT * FROM orders WHERE |",
+        r#"WhereClause { tables: ["orders"], qualifier: None }"#,
+    ),
+    (
+        "SELECT * FROM orders;\nDELETE FROM users u WHERE u.|",
+        r#"WhereClause { tables: ["u"], qualifier: Some("u") }"#,
+    ),
+    (
+        "UPDATE users SET name = 'x' FROM orders o WHERE |",
+        r#"WhereClause { tables: ["users", "o"], qualifier: None }"#,
+    ),
+    (
+        "SELECT id\nFROM users\nWHERE |",
+        r#"WhereClause { tables: ["users"], qualifier: None }"#,
+    ),
+    (
+        "SELECT * FROM users u\nJOIN orders o\n  ON |",
+        r#"JoinCondition { left_table: Some("u"), right_table: Some("o"), qualifier: None }"#,
+    ),
+    (
+        "select * from users where |",
+        r#"WhereClause { tables: ["users"], qualifier: None }"#,
+    ),
+    ("SELECT COUNT(|) FROM users", "Unknown"),
+    (
+        "SELECT * FROM users WHERE id IN (SELECT | FROM orders)",
+        r#"SelectProjection { tables: ["orders"], qualifier: None }"#,
+    ),
+];
+
+/// Complete statements (MySQL 8.0) with `|` at the cursor, as when going
+/// back to an earlier clause, and the expected context in `Debug` form
+const PARSED_CASES: &[(&str, &str)] = &[
+    (
+        "SELECT | FROM users",
+        r#"SelectProjection { tables: ["users"], qualifier: None }"#,
+    ),
+    // `*` replaced by columns after the joins were written
+    (
+        "SELECT | FROM users AS u JOIN orders AS o ON u.id = o.user_id",
+        r#"SelectProjection { tables: ["u", "o"], qualifier: None }"#,
+    ),
+    (
+        "SELECT u.id, | FROM users AS u JOIN orders AS o ON u.id = o.user_id",
+        r#"SelectProjection { tables: ["u", "o"], qualifier: None }"#,
+    ),
+    (
+        "SELECT o.| FROM users AS u JOIN orders AS o ON u.id = o.user_id",
+        r#"SelectProjection { tables: ["u", "o"], qualifier: Some("o") }"#,
+    ),
+    (
+        "SELECT | FROM users AS u, orders WHERE u.id = orders.user_id",
+        r#"SelectProjection { tables: ["u", "orders"], qualifier: None }"#,
+    ),
+];
+
+/// `marked` without its `|`, and the position of the `|`
+fn split_marker(marked: &str) -> (String, Position) {
+    let cursor = marked.find('|').expect("case has a cursor marker");
+    let source = marked.replacen('|', "", 1);
+    let before = &source[..cursor];
+    let line = before.matches('\n').count() as u32;
+    let character = (cursor - before.rfind('\n').map_or(0, |pos| pos + 1)) as u32;
+    (source, Position::new(line, character))
+}
+
+fn detect_at_marker(marked: &str) -> CompletionContext {
+    let (source, position) = split_marker(marked);
+    pipeline::detect(None, position, &source)
+}
+
+fn detect_parsed_at_marker(marked: &str) -> CompletionContext {
+    let (source, position) = split_marker(marked);
+    let lang = language_for_dialect_with_version(Dialect::MySQL, Some(DialectVersion::MySQL80))
+        .expect("Failed to get MySQL 8.0 language");
+    let mut parser = Parser::new();
+    parser.set_language(lang).expect("Failed to set language");
+    let tree = parser.parse(&source, None).expect("Failed to parse");
+    assert!(!tree.root_node().has_error(), "{:?} doesn't parse", source);
+    pipeline::detect(Some(tree.root_node()), position, &source)
+}
+
+/// Compare each case's context with the expected one
+fn assert_golden(cases: &[(&str, &str)], detect: fn(&str) -> CompletionContext) {
+    let mismatches: Vec<String> = cases
+        .iter()
+        .filter_map(|(marked, expected)| {
+            let actual = format!("{:?}", detect(marked));
+            (actual != *expected).then(|| {
+                format!(
+                    "{:?}\n  expected: {}\n  actual:   {}",
+                    marked, expected, actual
+                )
+            })
+        })
+        .collect();
+
+    assert!(
+        mismatches.is_empty(),
+        "{} golden case(s) changed:\n{}",
+        mismatches.len(),
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn test_golden_contexts() {
+    assert_golden(CASES, detect_at_marker);
+}
+
+#[test]
+fn test_golden_contexts_on_valid_parse() {
+    assert_golden(PARSED_CASES, detect_parsed_at_marker);
+}
+
+/// Minified queries arrive on a single line; a long projection before the
+/// clause being typed must not change the context
+#[test]
+fn test_long_single_line_queries() {
+    let long_projection = (0..5000)
+        .map(|i| format!("u.column_{i}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    for template in [
+        "SELECT {} FROM users u WHERE u.|",
+        "SELECT {} FROM users u JOIN |",
+        "SELECT {} FROM users u JOIN orders o ON o.|",
+        "SELECT {} FROM users u ORDER BY |",
+        "SELECT {}, rank() OVER (PARTITION BY |",
+        "SELECT {}, rank() OVER |",
+    ] {
+        let short = detect_at_marker(&template.replace("{}", "u.id"));
+        let long = detect_at_marker(&template.replace("{}", &long_projection));
+        assert_eq!(long, short, "{}", template);
+    }
+}
@@ -0,0 +1,711 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Completion context detection
+//!
+//! This module provides context detection for SQL completion.
+//! It analyzes the tree-sitter CST and the text before the cursor to
+//! determine what kind of completion should be provided.
+//!
+//! Detection is a pipeline of small detectors (see the `pipeline` module):
+//! CST detectors live in `cst`, text-based ones in `text`.
+
+mod cst;
+mod pipeline;
+mod text;
+
+#[cfg(test)]
+mod golden_tests;
+
+pub use text::{detect_comparison_operand, detect_completed_operand, projection_aliases};
+
+use crate::cst_utils::Position;
+use tree_sitter::Node;
+
+/// Parts of a window function specification (OVER clause)
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowFunctionPart {
+    /// Inside OVER ( at the beginning
+    OverStart,
+    /// Inside PARTITION BY
+    PartitionBy,
+    /// Inside ORDER BY (within OVER clause)
+    OrderBy,
+    /// Inside window frame specification
+    WindowFrame,
+}
+
+/// What a string literal at the cursor is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringLiteralRole {
+    /// Right-hand side of LIKE / ILIKE, e.g. `name LIKE '|'`
+    LikePattern,
+    /// Format argument of a date formatting function, e.g. `DATE_FORMAT(d, '|')`
+    DateFormat,
+    /// Any other string; no completion is offered so typing isn't interrupted
+    Generic,
+}
+
+/// Left-hand side of the comparison whose value is at the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComparisonOperand {
+    /// A column tested with `=`, `<>` or `!=`, e.g. `active = |`
+    Column {
+        /// Table qualifier, e.g. "u" in `u.active = |`
+        qualifier: Option<String>,
+        /// Column name
+        column: String,
+    },
+    /// The operand of `IS`, e.g. `deleted_at IS |`
+    NullTest,
+    /// A bound of a `BETWEEN` range, e.g. `age BETWEEN | AND |`
+    Between {
+        /// Table qualifier of the tested column
+        qualifier: Option<String>,
+        /// Column name
+        column: String,
+        /// Which bound is being written
+        bound: BetweenBound,
+    },
+    /// An element of an `IN` value list, e.g. `status IN ('active', |)`
+    ValueList {
+        /// Table qualifier of the tested column
+        qualifier: Option<String>,
+        /// Column name
+        column: String,
+    },
+}
+
+/// Column the cursor follows as a complete operand, e.g. `name` in `WHERE name |`
+///
+/// An operator is expected next rather than another operand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletedOperand {
+    /// Table qualifier, e.g. "u" in `u.name |`
+    pub qualifier: Option<String>,
+    /// Column name
+    pub column: String,
+    /// Where the operand starts, including its qualifier
+    pub start: Position,
+}
+
+/// What the parenthesis right before the cursor closes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClosedParenthesis {
+    /// A derived table of FROM or JOIN, e.g. `FROM (SELECT id FROM users) |`;
+    /// an alias, a join or the next clause follows
+    DerivedTable,
+    /// A function call, subquery or parenthesized expression, e.g.
+    /// `WHERE lower(name) |`; an operator follows
+    Expression {
+        /// Whether the expression is an item of the SELECT list, where an
+        /// alias or `FROM` may follow too
+        in_projection: bool,
+    },
+}
+
+/// Bound of a `BETWEEN` range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BetweenBound {
+    /// Before `AND`, e.g. `age BETWEEN |`
+    Low,
+    /// After `AND`, e.g. `age BETWEEN 18 AND |`
+    High,
+}
+
+/// Completion context types
+///
+/// Represents different SQL contexts where completion can be triggered.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompletionContext {
+    /// SELECT clause projection
+    ///
+    /// User is typing in the SELECT projection list, e.g., `SELECT id, | FROM users`
+    SelectProjection {
+        /// Tables visible in this scope
+        tables: Vec<String>,
+        /// Optional table qualifier (e.g., "users." if cursor is after "users.")
+        qualifier: Option<String>,
+    },
+
+    /// FROM clause
+    ///
+    /// User is typing in the FROM clause, e.g., `SELECT * FROM |`
+    FromClause {
+        /// Tables to exclude from completion (already in FROM clause)
+        exclude_tables: Vec<String>,
+    },
+
+    /// WHERE clause
+    ///
+    /// User is typing in the WHERE clause, e.g., `SELECT * FROM users WHERE |`
+    WhereClause {
+        /// Tables visible in this scope
+        tables: Vec<String>,
+        /// Optional table qualifier (e.g., "users" if cursor is after "users.")
+        qualifier: Option<String>,
+    },
+
+    /// JOIN ON condition
+    ///
+    /// User is typing in the JOIN ON condition, e.g., `SELECT * FROM users JOIN orders ON |`
+    JoinCondition {
+        /// Left table in the join
+        left_table: Option<String>,
+        /// Right table in the join
+        right_table: Option<String>,
+        /// Qualifier (table alias) if user typed one, e.g., "o" in "ON o.|"
+        qualifier: Option<String>,
+    },
+
+    /// ORDER BY clause
+    ///
+    /// User is typing in the ORDER BY clause, e.g., `SELECT * FROM users ORDER BY |`
+    OrderByClause {
+        /// Tables visible in this scope
+        tables: Vec<String>,
+        /// Optional table qualifier (e.g., "users." if cursor is after "users.")
+        qualifier: Option<String>,
+    },
+
+    /// GROUP BY clause
+    ///
+    /// User is typing in the GROUP BY clause, e.g., `SELECT * FROM users GROUP BY |`
+    GroupByClause {
+        /// Tables visible in this scope
+        tables: Vec<String>,
+        /// Optional table qualifier (e.g., "users." if cursor is after "users.")
+        qualifier: Option<String>,
+    },
+
+    /// LIMIT clause
+    ///
+    /// User is typing in the LIMIT clause, e.g., `SELECT * FROM users LIMIT |`
+    LimitClause,
+
+    /// HAVING clause
+    ///
+    /// User is typing in the HAVING clause, e.g., `SELECT * FROM users GROUP BY col HAVING |`
+    HavingClause {
+        /// Tables visible in this scope
+        tables: Vec<String>,
+        /// Optional table qualifier (e.g., "users." if cursor is after "users.")
+        qualifier: Option<String>,
+    },
+
+    /// CTE (Common Table Expression) definition
+    ///
+    /// User is typing in a WITH clause, defining a CTE
+    CteDefinition {
+        /// Tables that can be used to create CTEs from
+        available_tables: Vec<String>,
+        /// CTEs that have already been defined in this WITH clause
+        defined_ctes: Vec<String>,
+    },
+
+    /// Window function specification (OVER clause)
+    ///
+    /// User is typing in an OVER clause, e.g., `SELECT ROW_NUMBER() OVER (|`
+    WindowFunctionClause {
+        /// Tables visible in this scope
+        tables: Vec<String>,
+        /// Which part of the OVER clause we're in
+        window_part: WindowFunctionPart,
+    },
+
+    /// DISTINCT ON column list (PostgreSQL)
+    ///
+    /// User is typing inside the parentheses, e.g., `SELECT DISTINCT ON (|) id FROM users`
+    DistinctOnList {
+        /// Tables visible in this scope
+        tables: Vec<String>,
+        /// Optional table qualifier (e.g., "u" if cursor is after "u.")
+        qualifier: Option<String>,
+    },
+
+    /// Window reference after OVER
+    ///
+    /// User is typing after OVER, e.g., `SELECT rank() OVER | FROM t WINDOW w AS (...)`
+    WindowName {
+        /// Windows defined in the statement's WINDOW clause
+        windows: Vec<String>,
+    },
+
+    /// RETURNING clause (PostgreSQL, MySQL)
+    ///
+    /// User is typing in a RETURNING clause, e.g., `INSERT INTO users ... RETURNING |`
+    ReturningClause {
+        /// Tables visible in this scope
+        tables: Vec<String>,
+        /// Optional table qualifier
+        qualifier: Option<String>,
+    },
+
+    /// Inside a MERGE statement (PostgreSQL 15+)
+    ///
+    /// User is typing a column in the ON condition or a WHEN clause, e.g.
+    /// `MERGE INTO users u USING staging s ON u.id = s.id WHEN MATCHED THEN UPDATE SET |`
+    MergeClause {
+        /// Target table after `MERGE INTO`
+        target: String,
+        /// Source table after `USING`, if already typed
+        source: Option<String>,
+        /// Only target columns apply (SET targets and the INSERT column list)
+        target_only: bool,
+        /// Optional table qualifier (e.g., "s" if cursor is after "s.")
+        qualifier: Option<String>,
+    },
+
+    /// Column list of an INSERT statement
+    ///
+    /// User is typing a target column, e.g. `INSERT INTO users (name, |`
+    InsertColumnList {
+        /// Table inserted into
+        table: String,
+        /// Columns already in the list
+        listed: Vec<String>,
+    },
+
+    /// Row constructor of a VALUES list outside INSERT
+    ///
+    /// User is typing a value of a standalone or CTE VALUES list, e.g.
+    /// `WITH data(id, name) AS (VALUES (1, 'a'), (|))`
+    ValuesRow {
+        /// Zero-based column of the value being typed
+        column: usize,
+        /// Name of the column from the CTE column list, if any
+        column_name: Option<String>,
+        /// Value of the same column in the first row, unless the cursor is
+        /// in the first row
+        first_row_value: Option<String>,
+    },
+
+    /// Inside a string literal
+    ///
+    /// User is typing inside quotes, e.g., `SELECT * FROM users WHERE name LIKE '|'`
+    StringLiteral {
+        /// What the string is used for
+        role: StringLiteralRole,
+    },
+
+    /// Type name after a PostgreSQL `::` cast
+    ///
+    /// User is typing the target of a cast, e.g., `SELECT id::| FROM users`
+    TypeName,
+
+    /// Collation name after `COLLATE`
+    ///
+    /// User is typing a collation, e.g. `SELECT * FROM users ORDER BY name COLLATE |`
+    CollationName {
+        /// Whether the cursor is already inside the double quotes of the name
+        in_quotes: bool,
+    },
+
+    /// Sequence name argument of a sequence function (PostgreSQL)
+    ///
+    /// User is typing the first argument of `nextval`, `setval` or `currval`,
+    /// e.g. `SELECT nextval('|')`
+    SequenceName {
+        /// Whether the cursor is already inside the quotes of the name
+        in_string: bool,
+    },
+
+    /// Role or user name
+    ///
+    /// User is typing a grantee or role, e.g., `GRANT SELECT ON users TO |`,
+    /// `REVOKE admin FROM |` or `SET ROLE |`
+    RoleName {
+        /// Statement introducing the name: "GRANT", "REVOKE" or "SET ROLE"
+        statement_type: String,
+    },
+
+    /// Column definition in the column list of a CREATE TABLE
+    ///
+    /// User is typing the type of a column, e.g. `CREATE TABLE t (id |`, or
+    /// its constraints and attributes after the type, e.g.
+    /// `CREATE TABLE t (id integer |`
+    ColumnDefinition {
+        /// Column name as written
+        column: String,
+        /// Type as written, or `None` while the type is typed
+        data_type: Option<String>,
+    },
+
+    /// After the closing parenthesis of a subquery or function call
+    ///
+    /// User is typing after `)`, e.g. `SELECT * FROM (SELECT id FROM users) |`
+    /// or `SELECT * FROM users WHERE lower(name) |`
+    AfterParenthesis {
+        /// What the parenthesis closes
+        closed: ClosedParenthesis,
+    },
+
+    /// Keyword completion
+    ///
+    /// User is typing at a position where SQL keywords are appropriate
+    Keywords {
+        /// The type of statement being typed
+        statement_type: Option<String>,
+        /// Existing clauses that should not be suggested again
+        existing_clauses: Vec<String>,
+    },
+
+    /// Unknown context
+    ///
+    /// Cursor is in a position that doesn't match known completion contexts
+    Unknown,
+}
+
+impl CompletionContext {
+    /// Check if this is a SELECT projection context
+    pub fn is_select_projection(&self) -> bool {
+        matches!(self, CompletionContext::SelectProjection { .. })
+    }
+
+    /// Check if this is a FROM clause context
+    pub fn is_from_clause(&self) -> bool {
+        matches!(self, CompletionContext::FromClause { .. })
+    }
+
+    /// Check if this is a WHERE clause context
+    pub fn is_where_clause(&self) -> bool {
+        matches!(self, CompletionContext::WhereClause { .. })
+    }
+
+    /// Check if this is a JOIN ON condition context
+    pub fn is_join_condition(&self) -> bool {
+        matches!(self, CompletionContext::JoinCondition { .. })
+    }
+
+    /// Check if this is a keyword completion context
+    pub fn is_keywords(&self) -> bool {
+        matches!(self, CompletionContext::Keywords { .. })
+    }
+
+    /// Check if this is a RETURNING clause context
+    pub fn is_returning_clause(&self) -> bool {
+        matches!(self, CompletionContext::ReturningClause { .. })
+    }
+
+    /// Check if this is a MERGE statement context
+    pub fn is_merge_clause(&self) -> bool {
+        matches!(self, CompletionContext::MergeClause { .. })
+    }
+
+    /// Check if this is an INSERT column list context
+    pub fn is_insert_column_list(&self) -> bool {
+        matches!(self, CompletionContext::InsertColumnList { .. })
+    }
+
+    /// Check if this is a VALUES row constructor context
+    pub fn is_values_row(&self) -> bool {
+        matches!(self, CompletionContext::ValuesRow { .. })
+    }
+
+    /// Check if the cursor is inside a string literal
+    pub fn is_string_literal(&self) -> bool {
+        matches!(self, CompletionContext::StringLiteral { .. })
+    }
+
+    /// Check if the cursor is at the target type of a `::` cast
+    pub fn is_type_name(&self) -> bool {
+        matches!(self, CompletionContext::TypeName)
+    }
+
+    /// Check if this is a collation name context
+    pub fn is_collation_name(&self) -> bool {
+        matches!(self, CompletionContext::CollationName { .. })
+    }
+
+    /// Check if this is a sequence name context
+    pub fn is_sequence_name(&self) -> bool {
+        matches!(self, CompletionContext::SequenceName { .. })
+    }
+
+    /// Check if this is a role name context
+    pub fn is_role_name(&self) -> bool {
+        matches!(self, CompletionContext::RoleName { .. })
+    }
+
+    /// Check if this is a column definition context
+    pub fn is_column_definition(&self) -> bool {
+        matches!(self, CompletionContext::ColumnDefinition { .. })
+    }
+
+    /// Name of the variant, e.g. "WhereClause"
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CompletionContext::SelectProjection { .. } => "SelectProjection",
+            CompletionContext::FromClause { .. } => "FromClause",
+            CompletionContext::WhereClause { .. } => "WhereClause",
+            CompletionContext::JoinCondition { .. } => "JoinCondition",
+            CompletionContext::OrderByClause { .. } => "OrderByClause",
+            CompletionContext::GroupByClause { .. } => "GroupByClause",
+            CompletionContext::LimitClause => "LimitClause",
+            CompletionContext::HavingClause { .. } => "HavingClause",
+            CompletionContext::CteDefinition { .. } => "CteDefinition",
+            CompletionContext::WindowFunctionClause { .. } => "WindowFunctionClause",
+            CompletionContext::DistinctOnList { .. } => "DistinctOnList",
+            CompletionContext::WindowName { .. } => "WindowName",
+            CompletionContext::ReturningClause { .. } => "ReturningClause",
+            CompletionContext::MergeClause { .. } => "MergeClause",
+            CompletionContext::InsertColumnList { .. } => "InsertColumnList",
+            CompletionContext::ValuesRow { .. } => "ValuesRow",
+            CompletionContext::StringLiteral { .. } => "StringLiteral",
+            CompletionContext::TypeName => "TypeName",
+            CompletionContext::CollationName { .. } => "CollationName",
+            CompletionContext::SequenceName { .. } => "SequenceName",
+            CompletionContext::RoleName { .. } => "RoleName",
+            CompletionContext::ColumnDefinition { .. } => "ColumnDefinition",
+            CompletionContext::AfterParenthesis { .. } => "AfterParenthesis",
+            CompletionContext::Keywords { .. } => "Keywords",
+            CompletionContext::Unknown => "Unknown",
+        }
+    }
+}
+
+/// Pipeline stage that produced a completion context
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectionStage {
+    /// A text detector that takes precedence over the tree, e.g. string literals
+    BeforeCst,
+    /// A detector for the clause node enclosing the cursor
+    Cst,
+    /// The text detectors, used when no clause node matched
+    Text,
+}
+
+/// Kind of the statement holding the cursor, from its first top-level keyword
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementKind {
+    /// `SELECT`, `VALUES` or `TABLE`, including after a WITH clause
+    Select,
+    /// `INSERT` or `REPLACE`
+    Insert,
+    /// `UPDATE`
+    Update,
+    /// `DELETE`
+    Delete,
+    /// `MERGE`
+    Merge,
+    /// `CREATE`, `ALTER`, `DROP` or `TRUNCATE`
+    Ddl,
+    /// Any other statement, e.g. `GRANT` or `SET`
+    Other,
+    /// Nothing typed yet
+    Empty,
+}
+
+/// A completion context with what detection learned about its statement
+///
+/// The flags are computed once while detecting, so completion doesn't
+/// scan the source again for them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedContext {
+    /// The completion context
+    pub context: CompletionContext,
+    /// Pipeline stage that produced `context`
+    pub stage: DetectionStage,
+    /// Kind of the statement holding the cursor
+    pub statement_kind: StatementKind,
+    /// Byte range of the `;`-separated statement holding the cursor
+    pub statement: std::ops::Range<usize>,
+    /// The clause before the cursor is a join's `USING`, e.g. `JOIN o USING (|`
+    pub in_using_clause: bool,
+    /// The cursor is inside a `CASE` expression not closed by `END` yet
+    pub in_case_expression: bool,
+    /// The query level of the cursor has a `GROUP BY`, before or after it
+    pub has_group_by: bool,
+}
+
+/// Detect the completion context based on cursor position
+///
+/// # Arguments
+///
+/// * `root` - Root node of the parsed tree
+/// * `position` - Cursor position (line, character)
+/// * `source` - Source code text
+///
+/// # Returns
+///
+/// The detected completion context
+///
+/// # Examples
+///
+/// ```no_run
+/// use unified_sql_lsp_context::{Position, detect_completion_context};
+/// use tree_sitter::Parser;
+/// use unified_sql_grammar::language_for_dialect;
+/// use unified_sql_lsp_ir::Dialect;
+///
+/// let source = "SELECT id FROM users";
+/// let mut parser = Parser::new();
+/// let lang = language_for_dialect(Dialect::MySQL).unwrap();
+/// parser.set_language(&lang).unwrap();
+/// let tree = parser.parse(source, None).unwrap();
+/// let ctx = detect_completion_context(
+///     &tree.root_node(),
+///     Position::new(0, 10),
+///     source
+/// );
+/// # let _ = ctx;
+/// ```
+pub fn detect_completion_context(
+    root: &Node,
+    position: Position,
+    source: &str,
+) -> CompletionContext {
+    pipeline::detect(Some(*root), position, source)
+}
+
+/// Detect the completion context along with its statement kind and flags
+///
+/// Like [`detect_completion_context`], or
+/// [`detect_completion_context_from_text`] without a tree; the text
+/// fallback fills in the same flags. The stage tells callers how often the
+/// tree was bypassed in favor of the text detectors.
+///
+/// # Examples
+///
+/// ```
+/// use unified_sql_lsp_context::{Position, StatementKind, detect_context};
+///
+/// let source = "SELECT CASE WHEN x THEN 1 ";
+/// let detected = detect_context(None, Position::new(0, 26), source);
+/// assert_eq!(detected.statement_kind, StatementKind::Select);
+/// assert!(detected.in_case_expression);
+/// ```
+pub fn detect_context(root: Option<&Node>, position: Position, source: &str) -> DetectedContext {
+    pipeline::detect_with_stage(root.copied(), position, source)
+}
+
+/// Detect the completion context from the text alone, without a parsed tree
+///
+/// Only the text detectors run, as they do for SQL the tree doesn't cover.
+///
+/// # Examples
+///
+/// ```
+/// use unified_sql_lsp_context::{Position, detect_completion_context_from_text};
+///
+/// let ctx = detect_completion_context_from_text(Position::new(0, 14), "SELECT * FROM ");
+/// assert!(ctx.is_from_clause());
+/// ```
+pub fn detect_completion_context_from_text(position: Position, source: &str) -> CompletionContext {
+    pipeline::detect(None, position, source)
+}
+
+/// Extract table qualifier from text (e.g., "u." -> "u", "\"Users\"." -> "\"Users\"")
+fn extract_table_qualifier(text: &str) -> Option<String> {
+    // Look for pattern like "table_name." at the end of text
+    let trimmed = text.trim();
+    if let Some(dot_pos) = trimmed.rfind('.') {
+        // Check if the dot is followed by whitespace or is at the end
+        // Also handle test patterns that end with "|" as cursor marker
+        let after_dot = &trimmed[dot_pos + 1..];
+        let is_at_end = after_dot.is_empty()
+            || after_dot.starts_with(' ')
+            || after_dot.starts_with('\t')
+            || after_dot == "|";
+
+        if is_at_end {
+            // Get the identifier before the dot
+            let before_dot = &trimmed[..dot_pos];
+            if let Some(delimiter @ ('"' | '`')) = before_dot.chars().last() {
+                // Quoted qualifier: keep the quotes up to the opening
+                // delimiter, skipping doubled delimiters inside it
+                let inner = &before_dot[..before_dot.len() - 1];
+                let mut open = inner.rfind(delimiter)?;
+                while inner[..open].ends_with(delimiter) {
+                    open = inner[..open - 1].rfind(delimiter)?;
+                }
+                return Some(before_dot[open..].to_string());
+            }
+            if let Some(ident_end) = before_dot.rfind(|c: char| !c.is_alphanumeric() && c != '_') {
+                Some(trimmed[ident_end + 1..dot_pos].to_string())
+            } else {
+                Some(before_dot.to_string())
+            }
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_context_is_select_projection() {
+        let ctx = CompletionContext::SelectProjection {
+            tables: vec!["users".to_string()],
+            qualifier: None,
+        };
+        assert!(ctx.is_select_projection());
+        assert!(!ctx.is_from_clause());
+        assert!(!ctx.is_where_clause());
+        assert!(!ctx.is_join_condition());
+    }
+
+    #[test]
+    fn test_completion_context_is_from_clause() {
+        let ctx = CompletionContext::FromClause {
+            exclude_tables: vec![],
+        };
+        assert!(!ctx.is_select_projection());
+        assert!(ctx.is_from_clause());
+        assert!(!ctx.is_where_clause());
+        assert!(!ctx.is_join_condition());
+    }
+
+    #[test]
+    fn test_completion_context_is_where_clause() {
+        let ctx = CompletionContext::WhereClause {
+            tables: vec![],
+            qualifier: None,
+        };
+        assert!(!ctx.is_select_projection());
+        assert!(!ctx.is_from_clause());
+        assert!(ctx.is_where_clause());
+        assert!(!ctx.is_join_condition());
+    }
+
+    #[test]
+    fn test_completion_context_is_join_condition() {
+        let ctx = CompletionContext::JoinCondition {
+            left_table: Some("users".to_string()),
+            right_table: Some("orders".to_string()),
+            qualifier: None,
+        };
+        assert!(!ctx.is_select_projection());
+        assert!(!ctx.is_from_clause());
+        assert!(!ctx.is_where_clause());
+        assert!(ctx.is_join_condition());
+    }
+
+    #[test]
+    fn test_quoted_qualifiers_keep_quotes() {
+        assert_eq!(
+            extract_table_qualifier("SELECT \"Users\"."),
+            Some("\"Users\"".to_string())
+        );
+        assert_eq!(
+            extract_table_qualifier("SELECT `Users`."),
+            Some("`Users`".to_string())
+        );
+        assert_eq!(extract_table_qualifier("SELECT u."), Some("u".to_string()));
+        assert_eq!(
+            extract_table_qualifier("ORDER BY \"Total Rows\"."),
+            Some("\"Total Rows\"".to_string())
+        );
+        assert_eq!(
+            extract_table_qualifier("ORDER BY \"a\"\"b\"."),
+            Some("\"a\"\"b\"".to_string())
+        );
+    }
+}
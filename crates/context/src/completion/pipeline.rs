@@ -0,0 +1,673 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Context detection pipeline
+//!
+//! Completion context is decided by an ordered list of [`ContextDetector`]s;
+//! the first one returning a context wins. Detection runs in three stages:
+//!
+//! 1. [`BEFORE_CST`]: text detectors for constructs the CST rarely covers
+//!    while they're being typed (open string literals, casts, MERGE,
+//!    VALUES lists, DISTINCT ON, `CREATE TABLE ... AS`, the position after
+//!    a closing parenthesis)
+//! 2. [`CST`]: asked for each ancestor of the node at the cursor, innermost
+//!    first, so the closest enclosing clause decides
+//! 3. [`TEXT`]: text detectors for incomplete SQL, used when no CST detector
+//!    matched or a CST detector defers to them
+//!
+//! A new detector is added by implementing [`ContextDetector`] and placing
+//! it in the stage and position where it should take precedence.
+
+use std::ops::Range;
+use std::sync::{Arc, OnceLock};
+
+use tracing::debug;
+use tree_sitter::Node;
+
+use super::cst::{CstCte, CstFrom, CstJoin, CstSelect, CstWhere, CstWindow, extract_qualifier};
+use super::text::{
+    TextClosedParenthesis, TextCollation, TextColumnDefinition, TextCreateTableAs, TextCte,
+    TextDdl, TextDistinctOn, TextDmlKeyword, TextFrom, TextGroupBy, TextHaving, TextInsertColumns,
+    TextJoinModifier, TextJoinOn, TextJoinUsing, TextLimit, TextMerge, TextOrderBy, TextProjection,
+    TextReturning, TextRoleName, TextSequenceName, TextStatementStart, TextStringLiteral,
+    TextTypeCast, TextUnion, TextValueList, TextValuesRow, TextWhere, TextWindow,
+};
+use super::{CompletionContext, DetectedContext, DetectionStage, StatementKind};
+use crate::ScopeBuilder;
+use crate::cst_utils::{
+    Position, byte_to_position, find_node_at_position, position_to_byte_offset, statement_range,
+};
+use crate::table_extractor::{TableExtractor, TableRefInfo};
+use crate::tokens::tokens;
+
+/// Decides the completion context for one kind of cursor location
+pub trait ContextDetector: Sync {
+    /// Return the context if this detector recognizes the input, `None` to
+    /// let the next detector try
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext>;
+}
+
+/// Bytes before the cursor that backward scans look at
+///
+/// Qualifiers, `OVER` and the like sit right before the cursor, so minified
+/// single-line queries don't need to be rescanned from their start.
+pub const SCAN_WINDOW: usize = 2048;
+
+/// Keywords looked up in the text before the cursor, with their first and
+/// last occurrence cached on first use
+const INDEXED_KEYWORDS: &[&str] = &[
+    "FROM", "JOIN", "WHERE", "WITH", "USING", "ON", "OVER(", "OVER (", "SELECT", " AS (",
+];
+
+/// Everything detectors look at, computed once per request
+#[derive(Clone)]
+pub struct DetectionInput<'a> {
+    /// Root of the parsed tree, if the caller has one
+    pub root: Option<Node<'a>>,
+    /// Ancestor of the node at the cursor being examined (CST stage only)
+    pub node: Option<Node<'a>>,
+    /// Table qualifier found while walking up to `node` (CST stage only)
+    pub qualifier: Option<String>,
+    /// Full document text
+    pub source: &'a str,
+    /// Cursor position
+    pub position: Position,
+    /// Byte offset of the cursor, clamped to the source length
+    pub byte_offset: usize,
+    /// Source text up to the cursor
+    pub text_before: &'a str,
+    /// Byte range of the `;`-separated statement containing the cursor
+    pub statement: Range<usize>,
+    /// `text_before` with ASCII letters uppercased; byte offsets are the same
+    pub text_before_upper: Arc<str>,
+    /// `source` with ASCII letters uppercased; byte offsets are the same
+    pub source_upper: Arc<str>,
+    /// Number of `(` and `)` in `text_before`
+    parens: (usize, usize),
+    keywords: Arc<KeywordIndex>,
+}
+
+impl<'a> DetectionInput<'a> {
+    pub fn new(root: Option<Node<'a>>, source: &'a str, position: Position) -> Self {
+        let byte_offset = position_to_byte_offset(source, position);
+        Self::with_text(
+            root,
+            source,
+            position,
+            byte_offset,
+            statement_range(source, byte_offset),
+        )
+    }
+
+    fn with_text(
+        root: Option<Node<'a>>,
+        source: &'a str,
+        position: Position,
+        byte_offset: usize,
+        statement: Range<usize>,
+    ) -> Self {
+        let text_before = &source[..byte_offset];
+        let source_upper: Arc<str> = source.to_ascii_uppercase().into();
+        let text_before_upper: Arc<str> = source_upper[..byte_offset].into();
+
+        Self {
+            root,
+            node: None,
+            qualifier: None,
+            source,
+            position,
+            byte_offset,
+            text_before,
+            statement,
+            keywords: Arc::new(KeywordIndex::new(text_before_upper.clone())),
+            parens: tokens(text_before).fold((0, 0), |(open, close), token| match token.paren() {
+                Some(true) => (open + 1, close),
+                Some(false) => (open, close + 1),
+                None => (open, close),
+            }),
+            text_before_upper,
+            source_upper,
+        }
+    }
+
+    /// Text of the current statement up to the cursor
+    pub fn statement_text_before(&self) -> &'a str {
+        &self.source[self.statement.start..self.byte_offset]
+    }
+
+    /// `statement_text_before` with ASCII letters uppercased
+    pub fn statement_upper_before(&self) -> &str {
+        &self.text_before_upper[self.statement.start..]
+    }
+
+    /// Tables of the current statement if it's an UPDATE or DELETE, by alias
+    /// when they have one
+    ///
+    /// Their WHERE clause only sees these tables, not those of other
+    /// statements in the document.
+    pub fn dml_tables(&self) -> Option<Vec<String>> {
+        let tables = ScopeBuilder::dml_tables_from_text(&self.source[self.statement.clone()]);
+        (!tables.is_empty()).then(|| {
+            tables
+                .into_iter()
+                .map(|table| table.alias.unwrap_or(table.table_name))
+                .collect()
+        })
+    }
+
+    /// Tables of the current statement, by alias when they have one
+    pub fn statement_tables(&self) -> Vec<String> {
+        TableExtractor::statement_tables(&self.source[self.statement.clone()])
+            .iter()
+            .map(TableRefInfo::reference)
+            .collect()
+    }
+
+    /// Names of the tables of the current statement, without aliases, for
+    /// excluding them from table suggestions
+    pub fn statement_table_names(&self) -> Vec<String> {
+        TableExtractor::statement_tables(&self.source[self.statement.clone()])
+            .iter()
+            .map(TableRefInfo::qualified_name)
+            .collect()
+    }
+
+    /// The same input with the statement from byte `start` on as the whole
+    /// source, e.g. the query of `CREATE TABLE ... AS`
+    ///
+    /// The tree covers the full document, so it's dropped. `start` must not
+    /// be past the cursor.
+    pub fn query_from(&self, start: usize) -> Self {
+        let source = &self.source[start..self.statement.end];
+        let byte_offset = self.byte_offset - start;
+
+        Self::with_text(
+            None,
+            source,
+            byte_to_position(byte_offset, source),
+            byte_offset,
+            0..source.len(),
+        )
+    }
+
+    /// The last [`SCAN_WINDOW`] bytes of `text_before`
+    pub fn window_before(&self) -> &'a str {
+        let mut start = self.byte_offset.saturating_sub(SCAN_WINDOW);
+        while !self.text_before.is_char_boundary(start) {
+            start += 1;
+        }
+        &self.text_before[start..]
+    }
+
+    /// Whether the cursor is inside parentheses opened before it
+    pub fn in_parentheses(&self) -> bool {
+        self.parens.0 > self.parens.1
+    }
+
+    /// Number of `(` and `)` before the cursor
+    pub fn paren_counts(&self) -> (usize, usize) {
+        self.parens
+    }
+
+    /// Byte offset of the first `keyword` in `text_before_upper`
+    pub fn find_keyword(&self, keyword: &str) -> Option<usize> {
+        self.keywords.lookup(keyword).0
+    }
+
+    /// Byte offset of the last `keyword` in `text_before_upper`
+    pub fn rfind_keyword(&self, keyword: &str) -> Option<usize> {
+        self.keywords.lookup(keyword).1
+    }
+
+    /// Whether `keyword` appears anywhere in `text_before_upper`
+    pub fn contains_keyword(&self, keyword: &str) -> bool {
+        self.find_keyword(keyword).is_some()
+    }
+
+    /// `statement` with ASCII letters uppercased
+    fn statement_upper(&self) -> &str {
+        &self.source_upper[self.statement.clone()]
+    }
+
+    /// First word of the current statement, uppercased, after any leading
+    /// whitespace and comments
+    pub fn statement_first_word(&self) -> Option<&str> {
+        tokens(self.statement_upper()).find_map(|token| token.word())
+    }
+
+    /// Kind of the current statement, from its first top-level keyword
+    pub fn statement_kind(&self) -> StatementKind {
+        let mut words = tokens(self.statement_upper())
+            .filter(|token| token.depth == 0)
+            .filter_map(|token| token.word());
+        let Some(first) = words.next() else {
+            return StatementKind::Empty;
+        };
+        std::iter::once(first)
+            .chain(words)
+            .find_map(|word| match word {
+                "SELECT" | "VALUES" | "TABLE" => Some(StatementKind::Select),
+                "INSERT" | "REPLACE" => Some(StatementKind::Insert),
+                "UPDATE" => Some(StatementKind::Update),
+                "DELETE" => Some(StatementKind::Delete),
+                "MERGE" => Some(StatementKind::Merge),
+                "CREATE" | "ALTER" | "DROP" | "TRUNCATE" => Some(StatementKind::Ddl),
+                // Skip the CTE names and AS of a WITH clause
+                "WITH" | "RECURSIVE" | "AS" | "NOT" | "MATERIALIZED" => None,
+                _ if first == "WITH" => None,
+                _ => Some(StatementKind::Other),
+            })
+            .unwrap_or(StatementKind::Other)
+    }
+
+    /// Whether the last clause keyword before the cursor is `USING`
+    pub fn in_using_clause(&self) -> bool {
+        const CLAUSES: &[&str] = &[
+            "SELECT",
+            "FROM",
+            "JOIN",
+            "ON",
+            "USING",
+            "WHERE",
+            "GROUP",
+            "HAVING",
+            "ORDER",
+            "LIMIT",
+            "SET",
+            "RETURNING",
+            "WHEN",
+        ];
+        tokens(self.statement_upper_before())
+            .filter_map(|token| token.word())
+            .filter(|word| CLAUSES.contains(word))
+            .last()
+            == Some("USING")
+    }
+
+    /// Whether a `CASE` before the cursor has no `END` yet
+    pub fn in_case_expression(&self) -> bool {
+        let open = tokens(self.statement_upper_before())
+            .filter_map(|token| token.word())
+            .fold(0isize, |open, word| match word {
+                "CASE" => open + 1,
+                "END" => open - 1,
+                _ => open,
+            });
+        open > 0
+    }
+
+    /// Whether the query level of the cursor has a `GROUP BY`
+    ///
+    /// The level is the innermost parentheses around the cursor, or the
+    /// whole statement outside them.
+    pub fn has_group_by(&self) -> bool {
+        let mut opened = Vec::new();
+        for token in tokens(self.statement_upper_before()) {
+            match token.paren() {
+                Some(true) => opened.push(token.offset + 1),
+                Some(false) => {
+                    opened.pop();
+                }
+                None => {}
+            }
+        }
+        let start = opened.last().copied().unwrap_or(0);
+
+        let mut previous = None;
+        for token in tokens(&self.statement_upper()[start..]) {
+            if token.depth < 0 {
+                break;
+            }
+            if token.depth == 0 && previous == Some("GROUP") && token.word() == Some("BY") {
+                return true;
+            }
+            if token.word().is_some() {
+                previous = token.word().filter(|_| token.depth == 0);
+            }
+        }
+        false
+    }
+
+    /// The same input, focused on an ancestor of the cursor node
+    fn at(&self, node: Node<'a>, qualifier: Option<String>) -> Self {
+        Self {
+            node: Some(node),
+            qualifier,
+            ..self.clone()
+        }
+    }
+}
+
+/// First and last occurrences of [`INDEXED_KEYWORDS`] in the uppercased
+/// text before the cursor
+///
+/// Several detectors ask for the same keywords; each is searched for once.
+/// Keywords outside the list are searched for on every lookup.
+struct KeywordIndex {
+    text: Arc<str>,
+    positions: [OnceLock<(Option<usize>, Option<usize>)>; INDEXED_KEYWORDS.len()],
+}
+
+impl KeywordIndex {
+    fn new(text: Arc<str>) -> Self {
+        Self {
+            text,
+            positions: std::array::from_fn(|_| OnceLock::new()),
+        }
+    }
+
+    fn lookup(&self, keyword: &str) -> (Option<usize>, Option<usize>) {
+        let search = || {
+            let first = self.text.find(keyword);
+            (first, first.and_then(|_| self.text.rfind(keyword)))
+        };
+        match INDEXED_KEYWORDS.iter().position(|k| *k == keyword) {
+            Some(i) => *self.positions[i].get_or_init(search),
+            None => search(),
+        }
+    }
+}
+
+/// Text detectors that take precedence over the CST
+pub static BEFORE_CST: &[&dyn ContextDetector] = &[
+    &TextSequenceName,
+    &TextStringLiteral,
+    &TextCollation,
+    &TextStatementStart,
+    &TextColumnDefinition,
+    &TextTypeCast,
+    &TextInsertColumns,
+    &TextValueList,
+    &TextMerge,
+    &TextValuesRow,
+    &TextDistinctOn,
+    &TextCreateTableAs,
+    &TextRoleName,
+    &TextJoinModifier,
+    &TextClosedParenthesis,
+];
+
+/// Detectors for the clause nodes enclosing the cursor
+pub static CST: &[&dyn ContextDetector] = &[
+    &CstSelect, &CstFrom, &CstWhere, &CstCte, &CstWindow, &CstJoin,
+];
+
+/// Text detectors for SQL the CST doesn't cover, in order of precedence
+pub static TEXT: &[&dyn ContextDetector] = &[
+    &TextDmlKeyword,
+    &TextUnion,
+    &TextCte,
+    &TextWindow,
+    &TextFrom,
+    &TextProjection,
+    &TextWhere,
+    &TextJoinUsing,
+    &TextJoinOn,
+    &TextOrderBy,
+    &TextHaving,
+    &TextGroupBy,
+    &TextLimit,
+    &TextReturning,
+    &TextDdl,
+];
+
+/// Run the whole pipeline
+///
+/// Without a tree only the text stages run.
+pub fn detect(root: Option<Node>, position: Position, source: &str) -> CompletionContext {
+    let input = DetectionInput::new(root, source, position);
+    run_stages(&input).0
+}
+
+/// Run the whole pipeline, reporting the stage that produced the context
+/// and the flags of its statement
+pub fn detect_with_stage(root: Option<Node>, position: Position, source: &str) -> DetectedContext {
+    let input = DetectionInput::new(root, source, position);
+    let (context, stage) = run_stages(&input);
+    DetectedContext {
+        context,
+        stage,
+        statement_kind: input.statement_kind(),
+        statement: input.statement.clone(),
+        in_using_clause: input.in_using_clause(),
+        in_case_expression: input.in_case_expression(),
+        has_group_by: input.has_group_by(),
+    }
+}
+
+fn run_stages(input: &DetectionInput) -> (CompletionContext, DetectionStage) {
+    if let Some(ctx) = first_match(BEFORE_CST, input) {
+        return (ctx, DetectionStage::BeforeCst);
+    }
+    if let Some(ctx) = detect_from_cst(input) {
+        return (ctx, DetectionStage::Cst);
+    }
+    (detect_from_text(input), DetectionStage::Text)
+}
+
+/// Run the stages that don't need a tree
+pub fn detect_without_tree(input: &DetectionInput) -> CompletionContext {
+    first_match(BEFORE_CST, input).unwrap_or_else(|| detect_from_text(input))
+}
+
+/// Run the text stage; used as the fallback by CST detectors
+pub fn detect_from_text(input: &DetectionInput) -> CompletionContext {
+    first_match(TEXT, input).unwrap_or(CompletionContext::Unknown)
+}
+
+/// Walk up from the node at the cursor until a CST detector matches
+fn detect_from_cst(input: &DetectionInput) -> Option<CompletionContext> {
+    let root = input.root?;
+    let node = find_node_at_position(&root, input.position, input.source)?;
+    debug!(
+        "!!! Found node '{}' at position {:?}",
+        node.kind(),
+        input.position
+    );
+
+    let mut current = Some(node);
+    let mut qualifier = None;
+    while let Some(n) = current {
+        // Cursor after a table qualifier (e.g., "users.")
+        if n.kind() == "table_reference"
+            && let Some(q) = extract_qualifier(&n, input.source, input.position)
+        {
+            qualifier = Some(q);
+        }
+
+        if let Some(ctx) = first_match(CST, &input.at(n, qualifier.clone())) {
+            return Some(ctx);
+        }
+        current = n.parent();
+    }
+
+    None
+}
+
+fn first_match(
+    detectors: &[&dyn ContextDetector],
+    input: &DetectionInput,
+) -> Option<CompletionContext> {
+    detectors.iter().find_map(|detector| detector.detect(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_statement_range() {
+        let source = "SELECT 1; SELECT id FROM users; SELECT 2";
+        let cursor = source.find("id").unwrap();
+        let input = DetectionInput::new(None, source, Position::new(0, cursor as u32));
+
+        assert_eq!(input.byte_offset, cursor);
+        assert_eq!(&source[input.statement.clone()], " SELECT id FROM users");
+        assert_eq!(input.statement_text_before(), " SELECT ");
+        assert_eq!(input.text_before, "SELECT 1; SELECT ");
+    }
+
+    #[test]
+    fn test_detect_reports_stage() {
+        let at_end = |source: &str| {
+            let column = source.chars().count() as u32;
+            let detected = detect_with_stage(None, Position::new(0, column), source);
+            (detected.context.kind(), detected.stage)
+        };
+
+        assert_eq!(
+            at_end("SELECT * FROM users WHERE name = 'x"),
+            ("StringLiteral", DetectionStage::BeforeCst)
+        );
+        assert_eq!(
+            at_end("SELECT * FROM "),
+            ("FromClause", DetectionStage::Text)
+        );
+    }
+
+    #[test]
+    fn test_input_clamps_cursor_past_end() {
+        let input = DetectionInput::new(None, "SELECT", Position::new(0, 40));
+        assert_eq!(input.byte_offset, 6);
+        assert_eq!(input.statement, 0..6);
+    }
+
+    #[test]
+    fn test_input_query_from() {
+        let source = "SELECT 1;\nCREATE TABLE t AS SELECT id FROM users; SELECT 2";
+        let cursor = source.find("id").unwrap();
+        let start = source.find("SELECT id").unwrap();
+        let input = DetectionInput::new(None, source, Position::new(1, 25)).query_from(start);
+
+        assert_eq!(input.source, "SELECT id FROM users");
+        assert_eq!(input.text_before, "SELECT ");
+        assert_eq!(input.byte_offset, cursor - start);
+        assert_eq!(input.position, Position::new(0, 7));
+        assert_eq!(input.statement, 0..20);
+    }
+
+    #[test]
+    fn test_input_window_and_keywords() {
+        let source = format!(
+            "select {} from users u join orders o on o.",
+            "x, ".repeat(1000)
+        );
+        let input = DetectionInput::new(None, &source, Position::new(0, source.len() as u32));
+
+        assert_eq!(input.window_before().len(), SCAN_WINDOW);
+        assert!(source.ends_with(input.window_before()));
+        assert_eq!(input.text_before_upper.len(), input.text_before.len());
+        assert_eq!(input.find_keyword("JOIN"), source.find("join"));
+        assert_eq!(input.rfind_keyword("ON"), source.rfind("on"));
+        // Keywords outside the index are still found
+        assert_eq!(input.find_keyword("ORDERS"), source.find("orders"));
+        assert!(!input.contains_keyword("WHERE"));
+        assert!(!input.in_parentheses());
+    }
+
+    #[test]
+    fn test_statement_kind_and_flags() {
+        let at_end = |source: &str| {
+            let column = source.chars().count() as u32;
+            detect_with_stage(None, Position::new(0, column), source)
+        };
+
+        let detected = at_end("WITH t AS (SELECT 1) UPDATE t SET a = CASE WHEN b THEN ");
+        assert_eq!(detected.statement_kind, StatementKind::Update);
+        assert!(detected.in_case_expression);
+        assert!(!at_end("SELECT CASE WHEN a THEN 1 END, ").in_case_expression);
+        // A keyword in a string or comment doesn't count
+        assert!(!at_end("SELECT 'case' -- CASE\n, ").in_case_expression);
+        assert!(!at_end("SELECT 'it''s a CASE', $$ CASE $$, ").in_case_expression);
+        let source = "SELECT * FROM t WHERE x = 'it''s (' AND ";
+        let input = DetectionInput::new(None, source, Position::new(0, source.len() as u32));
+        assert!(!input.in_parentheses());
+
+        assert_eq!(
+            at_end("-- delete\nselect ").statement_kind,
+            StatementKind::Select
+        );
+        assert_eq!(at_end("DROP TABLE ").statement_kind, StatementKind::Ddl);
+        assert_eq!(
+            at_end("GRANT SELECT ON ").statement_kind,
+            StatementKind::Other
+        );
+        assert_eq!(at_end("SELECT 1; ").statement_kind, StatementKind::Empty);
+
+        assert!(at_end("SELECT * FROM a JOIN b USING (").in_using_clause);
+        assert!(!at_end("SELECT * FROM a JOIN b USING (id) JOIN c ON ").in_using_clause);
+        assert!(!at_end("MERGE INTO t USING s ON ").in_using_clause);
+        assert!(!at_end("SELECT * FROM a JOIN b ON a.x = $$ USING ( $$ AND ").in_using_clause);
+
+        let source = "SELECT * FROM t WHERE  GROUP BY a";
+        let detected = detect_with_stage(None, Position::new(0, 22), source);
+        assert!(detected.has_group_by);
+        // GROUP BY of the outer query doesn't count inside a subquery
+        let source = "SELECT * FROM t WHERE a IN (SELECT b FROM u WHERE ) GROUP BY a";
+        let detected = detect_with_stage(None, Position::new(0, 50), source);
+        assert!(!detected.has_group_by);
+        assert!(at_end("SELECT a FROM t GROUP BY a HAVING ").has_group_by);
+        let source = "SELECT * FROM t WHERE  AND x = 'it''s GROUP BY a'";
+        let detected = detect_with_stage(None, Position::new(0, 22), source);
+        assert!(!detected.has_group_by);
+    }
+
+    #[test]
+    fn test_cst_stage_needs_a_tree() {
+        let input = DetectionInput::new(None, "SELECT * FROM users WHERE ", Position::new(0, 26));
+        assert_eq!(detect_from_cst(&input), None);
+        assert!(detect_from_text(&input).is_where_clause());
+    }
+
+    #[test]
+    fn test_detect_at_end_of_file() {
+        fn qualifier(context: &CompletionContext) -> Option<&str> {
+            match context {
+                CompletionContext::SelectProjection { qualifier, .. }
+                | CompletionContext::WhereClause { qualifier, .. }
+                | CompletionContext::JoinCondition { qualifier, .. } => qualifier.as_deref(),
+                _ => None,
+            }
+        }
+
+        // Cursor at the very end of the document, given as (line, character)
+        // in chars so multi-byte text before it doesn't shift the offset
+        fn at_eof(source: &str, past_line_end: u32) -> (&'static str, Option<String>) {
+            let last = source.split('\n').next_back().unwrap_or("");
+            let line = source.matches('\n').count() as u32;
+            let column = last.chars().count() as u32 + past_line_end;
+            let detected = detect_with_stage(None, Position::new(line, column), source);
+            let qualifier = qualifier(&detected.context).map(str::to_string);
+            (detected.context.kind(), qualifier)
+        }
+
+        let cases = [
+            ("SELECT * FROM ", "FromClause", None),
+            ("SELECT * FROM users WHERE ", "WhereClause", None),
+            ("SELECT * FROM users u WHERE u.", "WhereClause", Some("u")),
+            ("SELECT u.", "SelectProjection", Some("u")),
+            (
+                "SELECT * FROM users u JOIN orders o ON o.",
+                "JoinCondition",
+                Some("o"),
+            ),
+        ];
+
+        for (source, kind, expected) in cases {
+            let expected = (kind, expected.map(str::to_string));
+            let crlf = format!("-- é ü ß\r\n{}", source.replace(" FROM", "\r\nFROM"));
+            let trailing = format!("{source}\n");
+
+            assert_eq!(at_eof(source, 0), expected, "{source:?}");
+            assert_eq!(at_eof(source, 5), expected, "{source:?} past line end");
+            assert_eq!(at_eof(&crlf, 0), expected, "{crlf:?}");
+            // A trailing newline after the cursor doesn't change the result
+            let line = trailing.matches('\n').count() as u32 - 1;
+            let column = source.chars().count() as u32;
+            let detected = detect_with_stage(None, Position::new(line, column), &trailing);
+            assert_eq!(detected.context.kind(), kind, "{trailing:?}");
+        }
+    }
+}
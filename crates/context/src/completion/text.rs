@@ -0,0 +1,3681 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Text context detectors
+//!
+//! Detectors that work on the text before the cursor. They cover SQL that
+//! is too incomplete for the CST to have the expected nodes, and constructs
+//! that must be recognized before the CST is consulted.
+
+use tracing::debug;
+use tree_sitter::Node;
+
+use super::pipeline::{ContextDetector, DetectionInput, detect_from_text, detect_without_tree};
+use super::{
+    BetweenBound, ClosedParenthesis, ComparisonOperand, CompletedOperand, CompletionContext,
+    StringLiteralRole, WindowFunctionPart, extract_table_qualifier,
+};
+use crate::cst_utils::{Position, byte_to_position, position_to_byte_offset};
+use crate::scope_builder::ScopeBuilder;
+use crate::tokens::{TokenKind, lex, tokens};
+
+/// Cursor inside a string literal
+///
+/// Nothing else applies inside quotes, and the CST may not cover an
+/// unterminated literal.
+pub struct TextStringLiteral;
+
+impl ContextDetector for TextStringLiteral {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        string_literal_role(input.text_before).map(|role| CompletionContext::StringLiteral { role })
+    }
+}
+
+/// First argument of `nextval`, `setval` and `currval`
+///
+/// Claims the string before [`TextStringLiteral`] does, which would offer
+/// nothing inside `nextval('|')`.
+pub struct TextSequenceName;
+
+impl ContextDetector for TextSequenceName {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let text_before = input.text_before;
+        let (call_text, in_string) = match open_string_literal_start(text_before) {
+            Some(quote) => (&text_before[..quote], true),
+            None => (
+                text_before.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_'),
+                false,
+            ),
+        };
+        if !call_text.trim_end().ends_with('(') {
+            return None;
+        }
+
+        let (function, argument) = enclosing_call(call_text)?;
+        (argument == 0
+            && SEQUENCE_FUNCTIONS
+                .iter()
+                .any(|f| f.eq_ignore_ascii_case(&function)))
+        .then_some(CompletionContext::SequenceName { in_string })
+    }
+}
+
+/// Collation name after `COLLATE`
+///
+/// COLLATE may follow any expression or column type, so the keyword alone
+/// decides. PostgreSQL names are double quoted, e.g. `COLLATE "C"`.
+pub struct TextCollation;
+
+impl ContextDetector for TextCollation {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        collation_name_start(input.statement_text_before())
+            .map(|in_quotes| CompletionContext::CollationName { in_quotes })
+    }
+}
+
+/// Target type of a `::` cast
+pub struct TextTypeCast;
+
+impl ContextDetector for TextTypeCast {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        is_after_type_cast(input.text_before).then_some(CompletionContext::TypeName)
+    }
+}
+
+/// An `IN (` value list in a WHERE clause
+///
+/// Claims the parenthesis before the subquery detectors do, so `IN (|` is
+/// completed with values instead of a SELECT projection. `IN (SELECT ...`
+/// is left to them.
+pub struct TextValueList;
+
+impl ContextDetector for TextValueList {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        if !input.contains_keyword("WHERE") {
+            return None;
+        }
+        value_list_start(input.statement_text_before())?;
+
+        let tables = input
+            .dml_tables()
+            .unwrap_or_else(|| input.statement_tables());
+        let qualifier = extract_table_qualifier(input.window_before());
+        Some(CompletionContext::WhereClause { tables, qualifier })
+    }
+}
+
+/// Column list of `INSERT [INTO] table (`
+///
+/// The list is claimed before the subquery detectors see its parenthesis.
+pub struct TextInsertColumns;
+
+impl ContextDetector for TextInsertColumns {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let statement = input.statement_text_before();
+        let (paren, _) = innermost_open_paren(statement)?;
+        let list = &statement[paren + 1..];
+        if !list
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | ',' | '"' | '`') || c.is_whitespace())
+        {
+            return None;
+        }
+        if words(list).next().is_some_and(|(_, word)| {
+            word.eq_ignore_ascii_case("SELECT") || word.eq_ignore_ascii_case("WITH")
+        }) {
+            return None;
+        }
+
+        let mut words = words(&statement[..paren]).map(|(_, word)| word);
+        if !words.next()?.eq_ignore_ascii_case("INSERT") {
+            return None;
+        }
+        let mut table = words.next()?;
+        if table.eq_ignore_ascii_case("INTO") {
+            table = words.next()?;
+        }
+        if words.next().is_some() {
+            return None;
+        }
+
+        let mut listed: Vec<String> = list.split(',').map(|c| table_name(c.trim())).collect();
+        // The last item is the one being typed
+        listed.pop();
+        debug!("!!! LSP: Detected INSERT column list of {}", table);
+        Some(CompletionContext::InsertColumnList {
+            table: table_name(table),
+            listed,
+        })
+    }
+}
+
+/// Row constructors of a VALUES list used as a query
+///
+/// Covers standalone lists and CTE bodies, e.g.
+/// `WITH data(id, name) AS (VALUES (1, 'a'), (|`. The VALUES of INSERT and
+/// MERGE follow a table or column list and are left to their detectors.
+/// After a closed row, the continuations of the list are offered.
+pub struct TextValuesRow;
+
+impl ContextDetector for TextValuesRow {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let statement = input.statement_text_before();
+        let values = find_last_keyword(input.statement_upper_before(), "VALUES")?;
+
+        // Only a query position: statement start, `(` or a set operation
+        let before = statement[..values].trim_end();
+        let is_query = before.is_empty()
+            || before.ends_with('(')
+            || ["UNION", "ALL", "INTERSECT", "EXCEPT"]
+                .iter()
+                .any(|keyword| ends_with_keyword(before, keyword));
+        if !is_query {
+            return None;
+        }
+
+        match values_list_state(&statement[values + "VALUES".len()..])? {
+            ValuesListState::InRow { rows, column } => {
+                let first_row_value = rows
+                    .first()
+                    .and_then(|row| split_top_level(row).into_iter().nth(column))
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                    .map(String::from);
+                let column_name =
+                    cte_column_names(before).and_then(|names| names.into_iter().nth(column));
+                debug!("!!! LSP: Detected VALUES row, column {}", column);
+                Some(CompletionContext::ValuesRow {
+                    column,
+                    column_name,
+                    first_row_value,
+                })
+            }
+            ValuesListState::AfterRow => Some(CompletionContext::Keywords {
+                statement_type: Some("VALUES".to_string()),
+                existing_clauses: vec![],
+            }),
+        }
+    }
+}
+
+/// Where the cursor is in the rows following `VALUES`
+enum ValuesListState<'a> {
+    /// Inside a row, directly in its parentheses
+    InRow {
+        /// Contents of the rows closed so far
+        rows: Vec<&'a str>,
+        /// Zero-based item of the row being typed
+        column: usize,
+    },
+    /// After a closed row, possibly with a word typed
+    AfterRow,
+}
+
+/// Scan the rows of a VALUES list, `list` being the text after `VALUES`
+///
+/// Returns `None` when the text isn't a list of rows, when the cursor is in
+/// a nested parenthesis (e.g. a function call in a row) or awaits a row.
+fn values_list_state(list: &str) -> Option<ValuesListState<'_>> {
+    let mut rows = Vec::new();
+    let mut depth = 0usize;
+    let mut row_start = 0;
+    let mut column = 0;
+    let mut awaits_row = true;
+
+    for token in lex(list) {
+        match token.kind {
+            TokenKind::Comment => {}
+            TokenKind::OpenParen => {
+                if depth == 0 {
+                    if !awaits_row {
+                        return None;
+                    }
+                    row_start = token.end();
+                    column = 0;
+                }
+                depth += 1;
+            }
+            TokenKind::CloseParen if depth == 0 => return None,
+            TokenKind::CloseParen => {
+                depth -= 1;
+                if depth == 0 {
+                    rows.push(&list[row_start..token.offset]);
+                    awaits_row = false;
+                }
+            }
+            _ if token.is_symbol(',') && depth == 1 => column += 1,
+            _ if token.is_symbol(',') && depth == 0 && !awaits_row => awaits_row = true,
+            _ if depth > 0 => {}
+            // A clause keyword being typed after the last row
+            TokenKind::Word if !awaits_row && token.end() == list.len() => {
+                return Some(ValuesListState::AfterRow);
+            }
+            _ => return None,
+        }
+    }
+
+    match depth {
+        1 => Some(ValuesListState::InRow { rows, column }),
+        0 if !awaits_row => Some(ValuesListState::AfterRow),
+        _ => None,
+    }
+}
+
+/// Split `text` at the commas outside parentheses, string literals and
+/// quoted identifiers
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    for token in lex(text) {
+        if token.depth <= 0 && token.is_symbol(',') {
+            items.push(&text[start..token.offset]);
+            start = token.end();
+        }
+    }
+    items.push(&text[start..]);
+    items
+}
+
+/// Column names of a CTE whose body starts at the end of `text`
+///
+/// `text` ends with the `(` of `name(col, ...) AS (`.
+fn cte_column_names(text: &str) -> Option<Vec<String>> {
+    let before = strip_keyword(text.strip_suffix('(')?, "AS")?.trim_end();
+    let list = before.strip_suffix(')')?;
+    let open = list.rfind('(')?;
+    Some(
+        list[open + 1..]
+            .split(',')
+            .map(|name| table_name(name.trim()))
+            .collect(),
+    )
+}
+
+/// Detect the clause of a MERGE statement at the cursor
+///
+/// Returns `None` when the statement being typed isn't a MERGE.
+pub struct TextMerge;
+
+impl ContextDetector for TextMerge {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let statement = input.statement_text_before().trim_start();
+        if !statement
+            .get(..5)
+            .is_some_and(|word| word.eq_ignore_ascii_case("MERGE"))
+            || statement[5..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_')
+        {
+            return None;
+        }
+
+        debug!("!!! LSP: Detected MERGE statement");
+        let merge_keywords = |statement_type: &str| CompletionContext::Keywords {
+            statement_type: Some(statement_type.to_string()),
+            existing_clauses: vec![],
+        };
+
+        if ends_with_keyword(statement, "MERGE") {
+            return Some(merge_keywords("MERGE"));
+        }
+
+        let tables = ScopeBuilder::merge_tables_from_text(statement);
+        let target = tables.first().map(|t| t.table_name.clone());
+
+        if ends_with_keyword(statement, "INTO") {
+            return Some(CompletionContext::FromClause {
+                exclude_tables: vec![],
+            });
+        }
+        if ends_with_keyword(statement, "USING") {
+            return Some(CompletionContext::FromClause {
+                exclude_tables: target.into_iter().collect(),
+            });
+        }
+
+        let target = target?;
+        let source_table = tables.get(1).map(|t| t.table_name.clone());
+        let upper = statement.to_ascii_uppercase();
+        let column_context = |target_only: bool| CompletionContext::MergeClause {
+            target: target.clone(),
+            source: source_table.clone(),
+            target_only,
+            qualifier: extract_table_qualifier(statement),
+        };
+
+        // Text of the WHEN clause being typed, if any
+        let Some(when_pos) = find_last_keyword(&upper, "WHEN") else {
+            // Before the first WHEN, columns only make sense in the ON condition
+            return Some(if find_last_keyword(&upper, "ON").is_some() {
+                column_context(false)
+            } else {
+                merge_keywords("MERGE")
+            });
+        };
+        let when_clause = &upper[when_pos..];
+
+        if ends_with_keyword(statement, "THEN") {
+            return Some(merge_keywords("MERGE WHEN"));
+        }
+
+        if let Some(set_pos) = find_last_keyword(when_clause, "SET") {
+            // Left of `=` in the current assignment names a target column;
+            // right of it is an expression over both tables
+            let assignment = when_clause[set_pos + 3..].rsplit(',').next().unwrap_or("");
+            return Some(column_context(!assignment.contains('=')));
+        }
+
+        if find_last_keyword(when_clause, "INSERT").is_some() {
+            if find_last_keyword(when_clause, "VALUES").is_some() {
+                return Some(column_context(false));
+            }
+            if when_clause.contains('(') && !when_clause.contains(')') {
+                return Some(column_context(true));
+            }
+            return Some(merge_keywords("MERGE"));
+        }
+
+        if find_last_keyword(when_clause, "AND").is_some() {
+            return Some(column_context(false));
+        }
+
+        Some(merge_keywords("MERGE"))
+    }
+}
+
+/// Detect the column list of `DISTINCT ON (` around the cursor
+pub struct TextDistinctOn;
+
+impl ContextDetector for TextDistinctOn {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let upper = input.statement_upper_before();
+
+        let distinct_pos = find_last_keyword(upper, "DISTINCT")?;
+        let after_distinct = upper[distinct_pos + "DISTINCT".len()..].trim_start();
+        let after_on = after_distinct.strip_prefix("ON")?.trim_start();
+        let inside = after_on.strip_prefix('(')?;
+        if inside.matches('(').count() < inside.matches(')').count() {
+            return None;
+        }
+
+        debug!("!!! LSP: Detected DISTINCT ON column list");
+        Some(CompletionContext::DistinctOnList {
+            tables: input.statement_tables(),
+            qualifier: extract_table_qualifier(input.window_before()),
+        })
+    }
+}
+
+/// Query of `CREATE TABLE ... AS` or a PostgreSQL `SELECT ... INTO`
+///
+/// The CREATE prefix would route to DDL keywords, so the query is detected
+/// on its own. The new table doesn't exist yet and is kept out of the
+/// tables offered; while its name is typed nothing is offered.
+pub struct TextCreateTableAs;
+
+impl ContextDetector for TextCreateTableAs {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        if let Some((target, query)) = create_table_as_query(input.statement_text_before()) {
+            debug!("!!! LSP: Detected CREATE TABLE ... AS query");
+            let query = input.query_from(input.statement.start + query);
+            return Some(without_table(detect_without_tree(&query), &target));
+        }
+
+        // Words of the whole statement are walked looking for INTO, so
+        // statements without one are skipped first
+        if !input.source_upper[input.statement.clone()].contains("INTO") {
+            return None;
+        }
+
+        let statement = &input.source[input.statement.clone()];
+        let cursor = input.byte_offset - input.statement.start;
+        let (into_end, target) = select_into_target(statement)?;
+        if cursor < into_end {
+            return target.map(|(_, name)| without_table(detect_from_text(input), &name));
+        }
+
+        debug!("!!! LSP: Detected SELECT ... INTO");
+        match target {
+            Some((name_end, name)) if cursor > name_end => {
+                Some(without_table(detect_from_text(input), &name))
+            }
+            _ => Some(CompletionContext::Unknown),
+        }
+    }
+}
+
+/// Target table and start of the query in `CREATE TABLE name AS query`
+///
+/// `AS` must be followed by whitespace; MySQL's form without `AS` is also
+/// recognized. A parenthesis around the query is skipped.
+fn create_table_as_query(statement: &str) -> Option<(String, usize)> {
+    let mut words = words(statement);
+    if !words.next()?.1.eq_ignore_ascii_case("CREATE") {
+        return None;
+    }
+
+    let mut word = words.next()?.1;
+    while [
+        "OR",
+        "REPLACE",
+        "GLOBAL",
+        "LOCAL",
+        "TEMP",
+        "TEMPORARY",
+        "UNLOGGED",
+    ]
+    .iter()
+    .any(|modifier| word.eq_ignore_ascii_case(modifier))
+    {
+        word = words.next()?.1;
+    }
+    if !word.eq_ignore_ascii_case("TABLE") {
+        return None;
+    }
+
+    let mut name = words.next()?.1;
+    if name.eq_ignore_ascii_case("IF") {
+        // IF NOT EXISTS
+        name = words.nth(2)?.1;
+    }
+
+    let (pos, word) = words.next()?;
+    let query = if word.eq_ignore_ascii_case("AS") {
+        pos + "AS".len()
+    } else if word.eq_ignore_ascii_case("SELECT") {
+        pos
+    } else {
+        return None;
+    };
+    let rest = &statement[query..];
+    if query == pos + "AS".len() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let skipped = rest.len()
+        - rest
+            .trim_start_matches(|c: char| c.is_whitespace() || c == '(')
+            .len();
+    Some((table_name(name), query + skipped))
+}
+
+/// End of `INTO` and the target table, if written, of `SELECT ... INTO name`
+///
+/// The target is returned with the offset of its end. MySQL's `INTO @var`
+/// and `INTO OUTFILE` don't create a table and aren't recognized.
+fn select_into_target(statement: &str) -> Option<(usize, Option<(usize, String)>)> {
+    let mut words = words(statement);
+    if !words.next()?.1.eq_ignore_ascii_case("SELECT") {
+        return None;
+    }
+
+    let mut depth = 0i32;
+    let (pos, _) = words.find(|(_, word)| {
+        let top_level = depth == 0;
+        depth += word.matches('(').count() as i32 - word.matches(')').count() as i32;
+        top_level && word.eq_ignore_ascii_case("INTO")
+    })?;
+    let into_end = pos + "INTO".len();
+
+    let target = words.find(|(_, word)| {
+        !["TEMP", "TEMPORARY", "UNLOGGED", "TABLE"]
+            .iter()
+            .any(|modifier| word.eq_ignore_ascii_case(modifier))
+    });
+    if let Some((_, word)) = target
+        && (word.starts_with('@')
+            || word.eq_ignore_ascii_case("OUTFILE")
+            || word.eq_ignore_ascii_case("DUMPFILE"))
+    {
+        return None;
+    }
+
+    Some((
+        into_end,
+        target.map(|(pos, word)| (pos + word.len(), table_name(word))),
+    ))
+}
+
+/// Whitespace-separated words of `text` with their byte offsets
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_whitespace()
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+/// Unqualified, unquoted table name of a written name like `app."Report"`
+fn table_name(written: &str) -> String {
+    let name = written.rsplit('.').next().unwrap_or(written);
+    name.trim_matches(|c| c == '"' || c == '`').to_string()
+}
+
+/// Drop `table` from the tables of `ctx` and exclude it from FROM suggestions
+fn without_table(mut ctx: CompletionContext, table: &str) -> CompletionContext {
+    match &mut ctx {
+        CompletionContext::FromClause { exclude_tables }
+            if !exclude_tables.iter().any(|t| t.eq_ignore_ascii_case(table)) =>
+        {
+            exclude_tables.push(table.to_string());
+        }
+        CompletionContext::SelectProjection { tables, .. }
+        | CompletionContext::WhereClause { tables, .. }
+        | CompletionContext::OrderByClause { tables, .. }
+        | CompletionContext::GroupByClause { tables, .. }
+        | CompletionContext::HavingClause { tables, .. }
+        | CompletionContext::WindowFunctionClause { tables, .. }
+        | CompletionContext::DistinctOnList { tables, .. } => {
+            tables.retain(|t| !t.eq_ignore_ascii_case(table));
+        }
+        _ => {}
+    }
+    ctx
+}
+
+/// Role or user name after `GRANT ... TO`, `REVOKE ... FROM` and `SET ROLE`
+///
+/// GRANT and REVOKE aren't in the grammar, and the FROM of a REVOKE would
+/// otherwise be taken for a FROM clause offering tables.
+pub struct TextRoleName;
+
+impl ContextDetector for TextRoleName {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let statement = input.statement_text_before();
+        let (statement_type, list_start) = grantee_list_start(statement)?;
+        let list = &statement[list_start..];
+        if list.is_empty() {
+            return None;
+        }
+
+        // Names are separated by commas; a space after a name ends the list
+        let current = list.rsplit(',').next().unwrap_or(list).trim_start();
+        if current.contains(char::is_whitespace) {
+            return Some(CompletionContext::Unknown);
+        }
+
+        debug!("!!! LSP: Detected role name after {}", statement_type);
+        Some(CompletionContext::RoleName {
+            statement_type: statement_type.to_string(),
+        })
+    }
+}
+
+/// Rest of a join type after its modifier, e.g. `FROM users LEFT |`
+///
+/// Runs before the CST, which takes the modifier for a table alias, and
+/// the FROM patterns, which would offer table names. The modifier goes in
+/// `statement_type`; the dialect decides what may follow it.
+pub struct TextJoinModifier;
+
+/// Words that start or refine a join type
+const JOIN_MODIFIERS: &[&str] = &[
+    "INNER", "LEFT", "RIGHT", "FULL", "CROSS", "NATURAL", "OUTER",
+];
+
+impl ContextDetector for TextJoinModifier {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let statement = input.statement_upper_before();
+        // A partially typed word after the modifier is the join keyword
+        let before_word =
+            statement.trim_end_matches(|c: char| c.is_ascii_alphanumeric() || c == '_');
+        if !before_word.ends_with(char::is_whitespace) {
+            return None;
+        }
+        let mut previous = before_word.split_whitespace().rev();
+        let modifier = previous
+            .next()
+            .filter(|word| JOIN_MODIFIERS.contains(word))?;
+
+        // Only between the tables of a FROM clause
+        let clause = previous.find(|word| {
+            [
+                "SELECT", "FROM", "JOIN", "ON", "USING", "WHERE", "GROUP", "HAVING", "ORDER",
+                "LIMIT", "SET",
+            ]
+            .contains(word)
+        })?;
+        if !["FROM", "JOIN", "ON", "USING"].contains(&clause) {
+            return None;
+        }
+
+        debug!("!!! LSP: Detected join type after {}", modifier);
+        Some(CompletionContext::Keywords {
+            statement_type: Some(modifier.to_string()),
+            existing_clauses: vec![],
+        })
+    }
+}
+
+/// Right after the closing parenthesis of a subquery or function call,
+/// e.g. `FROM (SELECT id FROM users) |` or `WHERE lower(name) |`
+///
+/// The CST reads what follows a derived table as its alias, and the FROM
+/// patterns offer table names. The parenthesis is matched on the text
+/// without strings and comments; the clause it sits in decides what it
+/// closes. When the statement parses, the CST node of the parenthesis
+/// decides instead.
+pub struct TextClosedParenthesis;
+
+impl ContextDetector for TextClosedParenthesis {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let statement = input.statement_upper_before();
+        // A partially typed word after the parenthesis is the next keyword
+        let before_word =
+            statement.trim_end_matches(|c: char| c.is_ascii_alphanumeric() || c == '_');
+        if !before_word.ends_with(char::is_whitespace) {
+            return None;
+        }
+        let paren = before_word.trim_end().strip_suffix(')')?.len();
+
+        let closed = closed_parenthesis(&statement[..=paren])?;
+        let closed = match input.root.filter(|root| !root.has_error()) {
+            Some(root) => {
+                closed_parenthesis_in_tree(root, input.statement.start + paren).unwrap_or(closed)
+            }
+            None => closed,
+        };
+
+        debug!(
+            "!!! LSP: Detected position after closing parenthesis: {:?}",
+            closed
+        );
+        Some(CompletionContext::AfterParenthesis { closed })
+    }
+}
+
+/// What the `)` ending `text` closes, from the clause its `(` is in
+///
+/// `None` outside the SELECT list, FROM, JOIN and conditions, e.g. after a
+/// column list, `VALUES` row or `DISTINCT ON` list, and after `OVER (...)`.
+fn closed_parenthesis(text: &str) -> Option<ClosedParenthesis> {
+    let tokens: Vec<_> = tokens(text).collect();
+    let close = tokens.last().filter(|token| token.paren() == Some(false))?;
+
+    // The matching `(` and the `(` enclosing it, if any
+    let mut opened = Vec::new();
+    let mut open = None;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.paren() {
+            Some(true) => opened.push(i),
+            Some(false) if token.offset == close.offset => open = opened.pop(),
+            Some(false) => {
+                opened.pop();
+            }
+            None => {}
+        }
+    }
+    let open = open?;
+    let enclosing = opened.last().map_or(0, |&i| i + 1);
+
+    let mut before = tokens[..open].iter().rev().map(|token| token.word());
+    match (before.next().flatten(), before.next().flatten()) {
+        (Some("OVER" | "AS" | "VALUES" | "FILTER" | "WITHIN"), _) => return None,
+        // The column list of `DISTINCT ON (...)` isn't a join condition
+        (Some("ON"), Some("DISTINCT")) => return None,
+        _ => {}
+    }
+    let subquery = matches!(
+        tokens.get(open + 1).and_then(|token| token.word()),
+        Some("SELECT" | "WITH")
+    );
+
+    const CLAUSES: &[&str] = &[
+        "SELECT",
+        "FROM",
+        "JOIN",
+        "ON",
+        "USING",
+        "WHERE",
+        "GROUP",
+        "HAVING",
+        "ORDER",
+        "LIMIT",
+        "SET",
+        "VALUES",
+        "INTO",
+        "RETURNING",
+        "WINDOW",
+        "TABLE",
+    ];
+    let clause = tokens[enclosing..open]
+        .iter()
+        .rev()
+        .filter(|token| token.depth == tokens[open].depth)
+        .filter_map(|token| token.word())
+        .find(|word| CLAUSES.contains(word))?;
+
+    match clause {
+        "FROM" | "JOIN" if subquery => Some(ClosedParenthesis::DerivedTable),
+        "SELECT" => Some(ClosedParenthesis::Expression {
+            in_projection: true,
+        }),
+        "WHERE" | "ON" | "HAVING" => Some(ClosedParenthesis::Expression {
+            in_projection: false,
+        }),
+        _ => None,
+    }
+}
+
+/// What the `)` at byte `offset` closes, from its parent node
+fn closed_parenthesis_in_tree(root: Node, offset: usize) -> Option<ClosedParenthesis> {
+    let paren = root.descendant_for_byte_range(offset, offset + 1)?;
+    if paren.kind() != ")" {
+        return None;
+    }
+    let parent = paren.parent()?;
+    match parent.kind() {
+        "table_reference" => Some(ClosedParenthesis::DerivedTable),
+        "function_call" | "parenthesized_expression" | "cast_expression" => {
+            let in_projection = std::iter::successors(Some(parent), Node::parent)
+                .map(|node| node.kind())
+                .take_while(|kind| !kind.ends_with("_statement"))
+                .any(|kind| kind == "projection");
+            Some(ClosedParenthesis::Expression { in_projection })
+        }
+        _ => None,
+    }
+}
+
+/// Column definition in the column list of a CREATE TABLE
+///
+/// The CST of an unfinished column list rarely has a column definition
+/// node. Table constraints (`PRIMARY KEY (id)`, `CONSTRAINT ...`) and the
+/// parentheses of a type or CHECK expression aren't column definitions.
+pub struct TextColumnDefinition;
+
+impl ContextDetector for TextColumnDefinition {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let item = column_definition_item(input.statement_text_before())?;
+
+        // A word directly before the cursor is still being typed
+        let mut words: Vec<&str> = item.split_whitespace().collect();
+        if item.ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+            words.pop();
+        }
+        let column = words.first()?;
+        if TABLE_CONSTRAINT_KEYWORDS
+            .iter()
+            .any(|k| column.eq_ignore_ascii_case(k))
+        {
+            return None;
+        }
+
+        debug!("!!! LSP: Detected column definition of {}", column);
+        Some(CompletionContext::ColumnDefinition {
+            column: column.to_string(),
+            // Without the parameters of `varchar(64)`
+            data_type: words
+                .get(1)
+                .map(|t| t.split('(').next().unwrap_or(t).to_string()),
+        })
+    }
+}
+
+/// Words starting a table constraint or option in a CREATE TABLE list
+const TABLE_CONSTRAINT_KEYWORDS: &[&str] = &[
+    "CONSTRAINT",
+    "PRIMARY",
+    "FOREIGN",
+    "UNIQUE",
+    "CHECK",
+    "KEY",
+    "INDEX",
+    "FULLTEXT",
+    "SPATIAL",
+    "EXCLUDE",
+    "LIKE",
+];
+
+/// Text of the column list item of a CREATE TABLE `statement` ending at
+/// its end, if it ends at the top level of the list
+fn column_definition_item(statement: &str) -> Option<&str> {
+    let mut words = words(statement);
+    if !words.next()?.1.eq_ignore_ascii_case("CREATE") {
+        return None;
+    }
+    let open = statement.find('(')?;
+    let head: Vec<&str> = words
+        .take_while(|(pos, _)| *pos < open)
+        .map(|(_, word)| word)
+        .collect();
+    if !head.iter().any(|w| w.eq_ignore_ascii_case("TABLE"))
+        || head
+            .iter()
+            .any(|w| w.eq_ignore_ascii_case("AS") || w.eq_ignore_ascii_case("SELECT"))
+    {
+        return None;
+    }
+
+    let mut depth = 0usize;
+    let mut item_start = open + 1;
+    let mut closed = true;
+    for token in lex(&statement[open..]) {
+        closed = token.is_closed();
+        match token.kind {
+            TokenKind::OpenParen => depth += 1,
+            TokenKind::CloseParen => {
+                depth -= 1;
+                if depth == 0 {
+                    return None;
+                }
+            }
+            _ if depth == 1 && token.is_symbol(',') => item_start = open + token.end(),
+            _ => {}
+        }
+    }
+    (depth == 1 && closed).then(|| &statement[item_start..])
+}
+
+/// Statement type and end of the keyword introducing its list of roles
+fn grantee_list_start(statement: &str) -> Option<(&'static str, usize)> {
+    let mut words = words(statement);
+    let first = words.next()?.1;
+    let (statement_type, keyword) = if first.eq_ignore_ascii_case("GRANT") {
+        ("GRANT", "TO")
+    } else if first.eq_ignore_ascii_case("REVOKE") {
+        ("REVOKE", "FROM")
+    } else if first.eq_ignore_ascii_case("SET") {
+        // SET [SESSION | LOCAL] ROLE
+        let (pos, word) = words.find(|(_, word)| {
+            !word.eq_ignore_ascii_case("SESSION") && !word.eq_ignore_ascii_case("LOCAL")
+        })?;
+        return word
+            .eq_ignore_ascii_case("ROLE")
+            .then_some(("SET ROLE", pos + word.len()));
+    } else {
+        return None;
+    };
+
+    words
+        .filter(|(_, word)| word.eq_ignore_ascii_case(keyword))
+        .last()
+        .map(|(pos, word)| (statement_type, pos + word.len()))
+}
+
+/// Keyword expected after UPDATE, INSERT or DELETE (SET, INTO, FROM)
+///
+/// Must run before the FROM/JOIN patterns, which would offer table names.
+pub struct TextDmlKeyword;
+
+impl ContextDetector for TextDmlKeyword {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let trimmed = input.text_before.trim_end();
+        ["UPDATE", "INSERT", "DELETE"]
+            .into_iter()
+            .find(|keyword| trimmed.ends_with(&format!("{} ", keyword)) || trimmed == *keyword)
+            .map(|keyword| {
+                debug!("!!! LSP: Detected {} - expecting its next keyword", keyword);
+                CompletionContext::Keywords {
+                    statement_type: Some(keyword.to_string()),
+                    existing_clauses: vec![],
+                }
+            })
+    }
+}
+
+/// Set operation keyword after UNION (ALL or SELECT)
+pub struct TextUnion;
+
+impl ContextDetector for TextUnion {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let trimmed = input.text_before.trim_end();
+        if trimmed.ends_with("UNION ") || trimmed == "UNION" || trimmed.ends_with("UNION") {
+            debug!("!!! LSP: Detected UNION - expecting ALL or SELECT");
+            return Some(CompletionContext::Keywords {
+                statement_type: Some("UNION".to_string()),
+                existing_clauses: vec![],
+            });
+        }
+
+        None
+    }
+}
+
+/// Detect CTE (Common Table Expression) context
+///
+/// Detects if the cursor is in a WITH clause where we're defining or referencing CTEs
+pub struct TextCte;
+
+impl ContextDetector for TextCte {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let (source, text_before) = (input.source, input.text_before);
+        let (upper, source_upper) = (&*input.text_before_upper, &*input.source_upper);
+
+        debug!(
+            "!!! LSP: detect_cte_context called, text_before='{}'",
+            text_before
+        );
+
+        // Check if we're in a WITH clause at all
+        if !input.contains_keyword("WITH") {
+            return None;
+        }
+
+        // Pattern 1: "WITH | AS" or "WITH | AS (" - defining first CTE, suggest all tables
+        // Check: cursor is right after "WITH "
+        if upper == "WITH " || upper.ends_with("WITH ") || upper.ends_with("WITH\t") {
+            debug!("!!! LSP: Detected CTE definition start (Pattern 1)");
+            // For CTE name completion, we want to show ALL tables from catalog
+            // So we pass empty available_tables - the completion handler will fetch all tables
+            return Some(CompletionContext::CteDefinition {
+                available_tables: vec![], // Empty = show all tables from catalog
+                defined_ctes: vec![],
+            });
+        }
+
+        // Pattern 2: "WITH cte1 AS (...), | AS" - defining another CTE after comma
+        // Check: cursor is after ", "
+        if let Some(comma_pos) = upper.rfind(", ") {
+            let after_comma = &upper[comma_pos + 2..];
+            // Check if next non-whitespace would be AS
+            let trimmed = after_comma.trim_start();
+            if trimmed.starts_with("AS") || trimmed.is_empty() {
+                debug!("!!! LSP: Detected additional CTE definition (Pattern 2)");
+                let defined_ctes = extract_defined_ctes_before_cursor(source, text_before);
+                // For CTE name completion, we want to show ALL tables from catalog
+                return Some(CompletionContext::CteDefinition {
+                    available_tables: vec![], // Empty = show all tables from catalog
+                    defined_ctes,
+                });
+            }
+        }
+
+        // Pattern 3: "WITH cte AS (SELECT | FROM" - inside CTE subquery projection
+        // Check if we're inside parentheses after "AS ("
+        if let Some(as_pos) = input.find_keyword(" AS (") {
+            let after_as = &upper[as_pos + 5..]; // Skip " AS ("
+            let before_as = &upper[..as_pos];
+
+            // Count parentheses to check if we're still inside CTE definition
+            let open_parens = before_as.matches('(').count();
+            let close_parens = before_as.matches(')').count();
+
+            if open_parens > close_parens {
+                // We're inside the CTE definition
+                // Check if cursor is after SELECT and before FROM
+                if let Some(select_pos) = after_as.rfind("SELECT ") {
+                    let after_select = &after_as[select_pos + 7..];
+                    if !after_select.contains("FROM ") {
+                        debug!("!!! LSP: Detected column completion in CTE subquery (Pattern 3)");
+                        // Treat as SELECT projection for columns
+                        return TextProjection.detect(input);
+                    }
+                }
+            }
+        }
+
+        // Pattern 4: "WITH cte AS (...) SELECT | FROM cte" - in main query after CTE definition
+        // Check if cursor is after CTE definition closes
+        if let Some(with_pos) = source_upper.find("WITH ") {
+            let after_with = &source_upper[with_pos + 5..];
+
+            // Count parentheses to find where CTE definition ends
+            let mut paren_count = 0;
+            let mut cte_def_end = 0;
+
+            for (i, ch) in after_with.chars().enumerate() {
+                if ch == '(' {
+                    paren_count += 1;
+                } else if ch == ')' {
+                    paren_count -= 1;
+                    if paren_count == 0 {
+                        cte_def_end = with_pos + 5 + i + 1;
+                        break;
+                    }
+                }
+            }
+
+            if cte_def_end > 0 && text_before.len() > cte_def_end {
+                let after_cte = &text_before[cte_def_end..].trim_start();
+                // Check if cursor is in the main SELECT
+                if after_cte.starts_with("SELECT") || after_cte.contains(" SELECT") {
+                    debug!("!!! LSP: Detected main query after CTE definition (Pattern 4)");
+                    // Check if cursor is after FROM to reference CTE
+                    if after_cte.contains("FROM ") {
+                        // Suggest columns from the CTE
+                        let cte_names = extract_defined_ctes(source);
+                        let tables = cte_names;
+                        let qualifier = None;
+                        return detect_projection_context_with_tables(
+                            source,
+                            text_before,
+                            tables,
+                            qualifier,
+                        );
+                    } else {
+                        // Before FROM, suggest CTE names and columns
+                        let cte_names = extract_defined_ctes(source);
+                        let mut tables = cte_names.clone();
+                        tables.extend(input.statement_tables());
+                        let qualifier = None;
+                        return detect_projection_context_with_tables(
+                            source,
+                            text_before,
+                            tables,
+                            qualifier,
+                        );
+                    }
+                }
+            }
+        }
+
+        debug!("!!! LSP: No CTE pattern matched");
+        None
+    }
+}
+
+/// Detect window function (OVER clause) context
+///
+/// Detects if the cursor is in an OVER clause for window functions
+pub struct TextWindow;
+
+impl ContextDetector for TextWindow {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let (source, text_before) = (input.source, input.text_before);
+        let upper = &*input.text_before_upper;
+
+        debug!(
+            "!!! LSP: detect_window_function_context called, text_before='{}'",
+            text_before
+        );
+
+        // Pattern 0: "OVER |" - a named window or an inline specification follows
+        if is_after_over_keyword(input.window_before()) {
+            debug!("!!! LSP: Detected window reference after OVER");
+            return Some(CompletionContext::WindowName {
+                windows: extract_window_names(source),
+            });
+        }
+
+        // Check if we have an OVER clause (handle variations in spacing)
+        // or are inside a WINDOW clause definition
+        let window_def_start = window_definition_start(upper, input.window_before().len());
+        let has_over = input.contains_keyword("OVER(")
+            || input.contains_keyword("OVER (")
+            || upper.ends_with("OVER")
+            || window_def_start.is_some();
+
+        if !has_over {
+            debug!("!!! LSP: No OVER clause found, returning None");
+            return None;
+        }
+
+        // Pattern 1: "OVER (|" - just opened OVER clause
+        if upper.ends_with("OVER (") || upper.ends_with("OVER( ") {
+            debug!("!!! LSP: Detected OVER clause start");
+            return Some(CompletionContext::WindowFunctionClause {
+                tables: input.statement_tables(),
+                window_part: WindowFunctionPart::OverStart,
+            });
+        }
+
+        // Pattern 2: "OVER (PARTITION BY |" - inside PARTITION BY
+        if upper.ends_with("PARTITION BY ") || upper.ends_with("PARTITION BY") {
+            debug!("!!! LSP: Detected PARTITION BY clause");
+            return Some(CompletionContext::WindowFunctionClause {
+                tables: input.statement_tables(),
+                window_part: WindowFunctionPart::PartitionBy,
+            });
+        }
+
+        // Pattern 3: "OVER (ORDER BY |" - inside ORDER BY
+        if upper.ends_with("ORDER BY ") || upper.ends_with("ORDER BY") {
+            debug!("!!! LSP: Detected ORDER BY in window function");
+            return Some(CompletionContext::WindowFunctionClause {
+                tables: input.statement_tables(),
+                window_part: WindowFunctionPart::OrderBy,
+            });
+        }
+
+        // Pattern 4: Check if we're inside OVER clause or a WINDOW definition
+        // Handle both "OVER (" and "OVER(" variants
+        // Both offsets point at the opening parenthesis so it is counted below
+        let over_start = input
+            .rfind_keyword("OVER (")
+            .map(|pos| pos + 5)
+            .or_else(|| input.rfind_keyword("OVER(").map(|pos| pos + 4));
+        let window_start = window_def_start.map(|start| start - 1);
+        let spec_start = match (over_start, window_start) {
+            (Some(over), Some(window)) => Some(over.max(window)),
+            (over, window) => over.or(window),
+        };
+
+        if let Some(spec_start) = spec_start {
+            let after_over = &upper[spec_start..];
+            let open_parens = after_over.matches('(').count();
+            let close_parens = after_over.matches(')').count();
+
+            // More opens than closes means we're still inside the OVER clause
+            if open_parens > close_parens {
+                debug!("!!! LSP: Detected inside OVER clause");
+
+                // Check which part we're in
+                if after_over.contains("PARTITION BY") {
+                    // Check if cursor is after ORDER BY
+                    if let Some(order_pos) = after_over.rfind("ORDER BY") {
+                        let after_order = &after_over[order_pos + 8..];
+                        if !after_order.contains(')') {
+                            return Some(CompletionContext::WindowFunctionClause {
+                                tables: input.statement_tables(),
+                                window_part: WindowFunctionPart::OrderBy,
+                            });
+                        }
+                    }
+                    // We're in PARTITION BY
+                    return Some(CompletionContext::WindowFunctionClause {
+                        tables: input.statement_tables(),
+                        window_part: WindowFunctionPart::PartitionBy,
+                    });
+                } else if after_over.contains("ORDER BY") {
+                    return Some(CompletionContext::WindowFunctionClause {
+                        tables: input.statement_tables(),
+                        window_part: WindowFunctionPart::OrderBy,
+                    });
+                }
+
+                // Just after OVER (
+                return Some(CompletionContext::WindowFunctionClause {
+                    tables: input.statement_tables(),
+                    window_part: WindowFunctionPart::OverStart,
+                });
+            }
+        }
+
+        debug!("!!! LSP: No window function pattern matched");
+        None
+    }
+}
+
+/// Detect if cursor is in FROM or JOIN clause (for table completion)
+pub struct TextFrom;
+
+impl ContextDetector for TextFrom {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let (source, text_before) = (input.source, input.text_before);
+        let text_before_upper = &*input.text_before_upper;
+
+        // Check if cursor is inside a subquery (parentheses)
+        // If we're in a subquery, let other context detectors handle the inner context
+        if input.in_parentheses() {
+            return None;
+        }
+
+        // Additional check: if we're right after FROM keyword in a subquery like "(SELECT | FROM ...)",
+        // the paren counting above should have caught it, but double-check the position
+        // If the text ends with "SELECT " (just typed SELECT), we should skip FROM detection
+        if text_before_upper.trim_end().ends_with("SELECT ")
+            || text_before_upper.trim_end().ends_with("SELECT\t")
+            || text_before_upper.trim_end() == "SELECT"
+        {
+            return None;
+        }
+
+        // Additional check for patterns like "JOIN (SELECT |" where we're in a subquery projection
+        let trimmed = text_before.trim_end();
+
+        // More robust check: if we have "(SELECT" and very little content after it, we're in subquery projection
+        let in_subquery_after_select = trimmed.contains("(SELECT")
+            && (trimmed.ends_with("SELECT ")
+                || trimmed.ends_with("SELECT\t")
+                || trimmed.ends_with("SELECT(")
+                || trimmed.ends_with("(SELECT")
+                || (trimmed.contains("(SELECT ") && {
+                    let after_select = trimmed.split("(SELECT ").last().unwrap_or("");
+                    after_select.split_whitespace().count() <= 2
+                }));
+
+        if in_subquery_after_select {
+            return None;
+        }
+
+        // Check for JOIN pattern (e.g., "...JOIN |")
+        // Look for the last JOIN keyword - if it's at the end, we're likely typing table name
+        if let Some(join_pos) = input.rfind_keyword("JOIN") {
+            // Get text after the last JOIN
+            let after_join = &text_before_upper[join_pos + 4..]; // +4 for "JOIN"
+
+            debug!(
+                "!!! LSP: Found JOIN at pos {}, after='{}' (len={})",
+                join_pos,
+                after_join,
+                after_join.len()
+            );
+
+            // Check if what follows looks like we're still typing the table name
+            // Split by whitespace and check
+            let words: Vec<&str> = after_join.split_whitespace().collect();
+
+            debug!(
+                "!!! LSP: words after JOIN: {:?}, count={}",
+                words,
+                words.len()
+            );
+            debug!("!!! LSP: ends_with(' '): {}", after_join.ends_with(' '));
+            debug!(
+                "!!! LSP: trim_end().ends_with(' '): {}",
+                after_join.trim_end().ends_with(' ')
+            );
+
+            debug!(
+                "!!! LSP: Checking condition: words.is_empty()={}, words.len()={}, trim_end().ends_with(' ')={}",
+                words.is_empty(),
+                words.len(),
+                after_join.trim_end().ends_with(' ')
+            );
+            if words.is_empty() || (words.len() <= 3 && after_join.trim_end().ends_with(' ')) {
+                // We're likely right after JOIN, or after "JOIN <partial_word>"
+                debug!("!!! LSP: Detected FROM/JOIN context (after JOIN)");
+                // Extract tables from source for exclusion (use real table names, not aliases)
+                let exclude_tables = input.statement_table_names();
+
+                // Check if we should allow self-join by examining aliases
+                // Pattern: "employees e1 JOIN" - alias with number suffix suggests self-join
+                // Pattern: "users u JOIN" - simple alias, likely joining to different table
+                debug!(
+                    "!!! LSP: Checking for self-join hint, exclude_tables={:?}",
+                    exclude_tables
+                );
+                let allow_self_join = if exclude_tables.len() == 1 {
+                    // Check if the table alias has a number suffix (e1, e2, t1, t2, etc.)
+                    if let Some(table) = exclude_tables.first() {
+                        let source_upper = &input.source_upper;
+                        // Look for pattern "<TABLE> <ALIAS>" where alias ends with digit
+                        let pattern = format!("{} ", table).to_ascii_uppercase();
+                        debug!("!!! LSP: Looking for pattern '{}' in source", pattern);
+                        if let Some(pos) = source_upper.find(&pattern) {
+                            let after_table = &source[pos + pattern.len()..];
+                            // Get the next word (alias)
+                            let alias = after_table.split_whitespace().next().unwrap_or("");
+                            debug!("!!! LSP: Found alias '{}' after table '{}'", alias, table);
+                            // Check if alias ends with a digit (e1, e2, t1, t2, etc.)
+                            let ends_with_digit =
+                                alias.chars().last().is_some_and(|c| c.is_numeric());
+                            debug!(
+                                "!!! LSP: Alias '{}' ends with digit: {}",
+                                alias, ends_with_digit
+                            );
+                            ends_with_digit
+                        } else {
+                            debug!("!!! LSP: Pattern '{}' not found in source", pattern);
+                            false
+                        }
+                    } else {
+                        debug!("!!! LSP: No tables in exclude list");
+                        false
+                    }
+                } else {
+                    debug!("!!! LSP: More than 1 table, not checking for self-join");
+                    false
+                };
+
+                let final_exclude = if allow_self_join {
+                    debug!(
+                        "!!! LSP: Detected numbered alias (likely self-join), not excluding table"
+                    );
+                    vec![]
+                } else {
+                    debug!(
+                        "!!! LSP: Excluding {} already-used tables",
+                        exclude_tables.len()
+                    );
+                    exclude_tables
+                };
+
+                return Some(CompletionContext::FromClause {
+                    exclude_tables: final_exclude,
+                });
+            }
+        }
+
+        // Check for comma-style join pattern: "FROM table1, table2, |"
+        // This needs to be checked before the regular FROM pattern
+        if input.contains_keyword("FROM") && text_before.trim_end().ends_with(',') {
+            debug!("!!! LSP: Detected comma-style join pattern");
+            // Extract tables from source for exclusion
+            let exclude_tables = input.statement_table_names();
+            return Some(CompletionContext::FromClause { exclude_tables });
+        }
+
+        // Check for FROM pattern (e.g., "...FROM |")
+        if is_after_keyword(text_before_upper, "FROM") {
+            let after_from = extract_after_keyword(text_before_upper, "FROM");
+            debug!(
+                "!!! LSP: after_from='{}', trimmed.len()={}",
+                after_from,
+                after_from.trim().len()
+            );
+            if after_from.trim().len() < 10 {
+                // Check if we've already typed a table name (FROM <table> <space>)
+                // Pattern: "FROM table " should suggest clauses, not tables
+                // EXCEPT: "FROM table, " should suggest tables (comma-style join)
+                let trimmed = after_from.trim();
+                let words: Vec<&str> = trimmed.split_whitespace().collect();
+                debug!("!!! LSP: words={:?}, words.len()={}", words, words.len());
+
+                if !words.is_empty() {
+                    // We have at least one word after FROM
+                    // Check if cursor is after a space or at the end (meaning table name is complete)
+                    // Handle test patterns that end with "|" as cursor marker
+                    let ends_with_space = after_from.ends_with(' ') || after_from.ends_with('\t');
+                    let ends_with_cursor =
+                        trimmed.ends_with('|') || words.last().is_some_and(|w| *w == "|");
+
+                    debug!(
+                        "!!! LSP: ends_with_space={}, ends_with_cursor={}",
+                        ends_with_space, ends_with_cursor
+                    );
+
+                    if ends_with_space || ends_with_cursor {
+                        // Check if this is a comma-style join: "FROM table1, table2, |"
+                        // If the trimmed text ends with comma, it's a comma join
+                        if trimmed.ends_with(',') {
+                            debug!("!!! LSP: Detected comma-style join, suggesting tables");
+                            // Extract tables from source for exclusion
+                            let exclude_tables = input.statement_table_names();
+                            return Some(CompletionContext::FromClause { exclude_tables });
+                        }
+
+                        debug!("!!! LSP: Detected clause context after table name");
+                        // Return Keywords context with existing clauses
+                        return Some(CompletionContext::Keywords {
+                            statement_type: Some("SELECT".to_string()),
+                            existing_clauses: vec!["SELECT".to_string(), "FROM".to_string()],
+                        });
+                    }
+                }
+
+                debug!("!!! LSP: Detected FROM/JOIN context (after FROM)");
+                return Some(CompletionContext::FromClause {
+                    exclude_tables: vec![],
+                });
+            }
+        }
+
+        debug!("!!! LSP: detect_from_or_join_context returning None");
+        None
+    }
+}
+
+/// Detect if cursor is in SELECT projection (e.g., "SELECT |" or "SELECT id, |")
+pub struct TextProjection;
+
+impl ContextDetector for TextProjection {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let source = input.source;
+        let (source_upper, text_before_upper) = (&*input.source_upper, &*input.text_before_upper);
+
+        // Check if cursor is inside a subquery: "JOIN (SELECT | FROM ...)"
+        // More opens than closes before the cursor means we're inside parentheses
+        let (open_parens, close_parens) = input.paren_counts();
+        let in_subquery = input.in_parentheses();
+
+        debug!(
+            "!!! LSP: detect_projection_context: in_subquery={}, open_parens={}, close_parens={}",
+            in_subquery, open_parens, close_parens
+        );
+
+        // Pattern: "SELECT" at statement start followed by incomplete projection
+        // OR: Inside a subquery that starts with SELECT
+        if input.statement_first_word() == Some("SELECT") || in_subquery {
+            // Check if we have FROM keyword in the full source
+            if source_upper.contains("FROM") {
+                // We have "SELECT ... FROM ..."
+                // If cursor is before FROM, it's in projection
+                // Check if the text before cursor contains FROM
+                // BUT: If we're in a subquery, check for FROM within the subquery
+                if in_subquery {
+                    // In subquery: find the last SELECT to check if cursor is before its FROM
+                    // For "JOIN (SELECT | FROM orders)", check if we just typed SELECT or if cursor is after SELECT in parens
+                    let just_typed_select = text_before_upper.ends_with("SELECT ")
+                        || text_before_upper.ends_with("SELECT\t")
+                        || text_before_upper.ends_with("SELECT(")
+                        || text_before_upper.trim_end().ends_with("(SELECT");
+
+                    if just_typed_select {
+                        debug!(
+                            "!!! LSP: Detected SELECT projection context (in subquery, just typed SELECT)"
+                        );
+                        debug!("!!! LSP: source='{}'", source);
+
+                        // Try to extract table from the subquery's FROM clause
+                        // For "JOIN (SELECT | FROM orders)", we need "orders" table
+                        // We need to find the FROM that's inside the subquery (after the last SELECT)
+                        let tables = if let Some(last_select_pos) = source_upper.rfind("SELECT") {
+                            debug!("!!! LSP: last_select_pos={}", last_select_pos);
+                            // Look for FROM after this SELECT
+                            let after_select = &source_upper[last_select_pos..];
+                            debug!("!!! LSP: after_select='{}'", after_select);
+                            if let Some(from_pos_relative) = after_select.find("FROM") {
+                                let from_pos_absolute = last_select_pos + from_pos_relative;
+                                debug!("!!! LSP: from_pos_absolute={}", from_pos_absolute);
+                                // Get text after this FROM
+                                let after_from = &source[from_pos_absolute + 4..];
+                                debug!("!!! LSP: after_from='{}'", after_from);
+                                // Trim leading whitespace first
+                                let after_from_trimmed = after_from.trim_start();
+                                debug!("!!! LSP: after_from_trimmed='{}'", after_from_trimmed);
+                                // Extract first word as table name
+                                if let Some(table_end) = after_from_trimmed.find([' ', ')', ';']) {
+                                    let table_name =
+                                        after_from_trimmed[..table_end].trim().to_string();
+                                    debug!(
+                                        "!!! LSP: Extracted table from subquery FROM: '{}'",
+                                        table_name
+                                    );
+                                    vec![table_name]
+                                } else {
+                                    debug!("!!! LSP: No table end found in after_from");
+                                    vec![]
+                                }
+                            } else {
+                                debug!("!!! LSP: No FROM found after last SELECT");
+                                vec![]
+                            }
+                        } else {
+                            debug!("!!! LSP: No SELECT found in source");
+                            vec![]
+                        };
+
+                        return Some(CompletionContext::SelectProjection {
+                            tables,
+                            qualifier: None,
+                        });
+                    }
+                } else if !input.contains_keyword("FROM") {
+                    debug!("!!! LSP: Detected SELECT projection context");
+
+                    // Extract table names from FROM clause
+                    // Simple extraction: find FROM keyword, then get the next word(s)
+                    let tables = input.statement_tables();
+                    debug!("!!! LSP: Extracted tables from source: {:?}", tables);
+
+                    // Extract table qualifier (e.g., "users." from "SELECT users.|")
+                    let qualifier = extract_table_qualifier(input.window_before());
+                    debug!(
+                        "!!! LSP: Extracted qualifier from text_before: {:?}",
+                        qualifier
+                    );
+
+                    return Some(CompletionContext::SelectProjection { tables, qualifier });
+                }
+            } else {
+                // No FROM yet, definitely in projection
+                debug!("!!! LSP: Detected SELECT projection context (no FROM yet)");
+
+                // Extract table qualifier (e.g., "users." from "SELECT users.|")
+                let qualifier = extract_table_qualifier(input.window_before());
+                debug!(
+                    "!!! LSP: Extracted qualifier from text_before: {:?}",
+                    qualifier
+                );
+
+                return Some(CompletionContext::SelectProjection {
+                    tables: vec![],
+                    qualifier,
+                });
+            }
+        }
+
+        None
+    }
+}
+
+/// Detect if cursor is in WHERE clause
+pub struct TextWhere;
+
+impl ContextDetector for TextWhere {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        // Check if we're after WHERE keyword
+        // We should detect WHERE regardless of what comes after (even complex expressions)
+        if input.contains_keyword("WHERE") {
+            debug!("!!! LSP: Detected WHERE context");
+            // UPDATE and DELETE only see their own tables
+            let tables = input
+                .dml_tables()
+                .unwrap_or_else(|| input.statement_tables());
+            // Check for table qualifier (e.g., "u.")
+            let qualifier = extract_table_qualifier(input.window_before());
+            return Some(CompletionContext::WhereClause { tables, qualifier });
+        }
+
+        None
+    }
+}
+
+/// Detect if cursor is in JOIN USING clause
+pub struct TextJoinUsing;
+
+impl ContextDetector for TextJoinUsing {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let (source, text_before) = (input.source, input.text_before);
+
+        debug!(
+            "!!! LSP: detect_join_using_context: text_before='{}'",
+            text_before
+        );
+        debug!(
+            "!!! LSP: detect_join_using_context: contains USING={}",
+            input.contains_keyword("USING")
+        );
+        debug!(
+            "!!! LSP: detect_join_using_context: ends_with '({}'",
+            text_before.trim_end().ends_with('(')
+        );
+
+        // Pattern: "...JOIN ... USING (|"
+        // Check if we're after USING keyword and have an opening paren
+        if input.contains_keyword("USING") && text_before.trim_end().ends_with('(') {
+            // Also verify we're in a JOIN statement
+            if !input.contains_keyword("JOIN") {
+                return None;
+            }
+
+            debug!("!!! LSP: Detected JOIN USING context");
+
+            // For USING, we need to extract table names manually
+            // Pattern: "FROM table1 JOIN table2 USING ("
+            // We need to find the table names before USING
+            let source_upper = &input.source_upper;
+
+            // Find the last FROM keyword
+            if let Some(from_pos) = source_upper.find("FROM") {
+                let after_from = &source[from_pos + 4..]; // Skip "FROM"
+
+                // Extract tables by looking for JOIN keywords
+                // Pattern: "table1 JOIN table2 USING"
+                let mut tables = Vec::new();
+
+                // Split by JOIN to get individual table references
+                let parts: Vec<&str> = after_from.split("JOIN").collect();
+
+                for part in parts {
+                    // Extract the table name (first word before any alias or USING)
+                    let trimmed = part.trim();
+                    if !trimmed.is_empty() && !trimmed.starts_with("USING") {
+                        // Get the first word (table name or alias)
+                        if let Some(first_word) = trimmed.split_whitespace().next() {
+                            tables.push(first_word.to_string());
+                        }
+                    }
+                }
+
+                debug!("!!! LSP: JOIN USING: extracted tables={:?}", tables);
+
+                if tables.len() >= 2 {
+                    // Get the last two tables (the ones being joined)
+                    let left_table = Some(tables[tables.len() - 2].clone());
+                    let right_table = Some(tables[tables.len() - 1].clone());
+
+                    debug!(
+                        "!!! LSP: JOIN USING tables - left: {:?}, right: {:?}",
+                        left_table, right_table
+                    );
+
+                    return Some(CompletionContext::JoinCondition {
+                        left_table,
+                        right_table,
+                        qualifier: None,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Detect if cursor is in JOIN ON clause
+pub struct TextJoinOn;
+
+impl ContextDetector for TextJoinOn {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        // Pattern: "...JOIN ... ON |" or "...JOIN ... ON ... AND |"
+        // We need to check if cursor is after ON keyword, possibly with AND/OR in between
+        // Check if ON exists in the text
+        if input.contains_keyword("ON") {
+            // Also verify we're in a JOIN statement
+            if !input.contains_keyword("JOIN") {
+                return None;
+            }
+            // Check for table qualifier (e.g., "u.")
+            let qualifier = extract_table_qualifier(input.window_before());
+            debug!(
+                "!!! LSP: Detected JOIN ON context, qualifier: {:?}",
+                qualifier
+            );
+
+            // Extract table aliases from source
+            // For "SELECT * FROM users u JOIN orders o ON u.|", we need ["u", "o"]
+            let table_aliases = input.statement_tables();
+
+            // For JOIN ON, we need to determine which tables are available
+            // If qualifier is provided, show columns from that table
+            // Otherwise, we might want to show columns from both tables
+            let (left_table, right_table) = if table_aliases.len() >= 2 {
+                // Assume first two aliases are the left and right tables
+                (
+                    Some(table_aliases[0].clone()),
+                    Some(table_aliases[1].clone()),
+                )
+            } else {
+                (None, None)
+            };
+
+            // If qualifier is provided, only show columns from that table
+            // This matches the test expectation: "u." should only show u.* columns, not o.*
+            let (left_table, right_table) = match &qualifier {
+                Some(q) => {
+                    // Find which table the qualifier refers to
+                    if left_table.as_ref().map(|t| t == q).unwrap_or(false) {
+                        // Qualifier matches left table, only show left table columns
+                        (left_table, None)
+                    } else if right_table.as_ref().map(|t| t == q).unwrap_or(false) {
+                        // Qualifier matches right table, only show right table columns
+                        (None, right_table)
+                    } else {
+                        // Unknown qualifier, show both tables
+                        (left_table, right_table)
+                    }
+                }
+                None => (left_table, right_table),
+            };
+
+            debug!(
+                "!!! LSP: JOIN ON tables - left: {:?}, right: {:?}, qualifier: {:?}",
+                left_table, right_table, qualifier
+            );
+
+            // Note: We preserve the original qualifier here so that the completion engine
+            // can decide whether to add the qualifier prefix based on user input
+            return Some(CompletionContext::JoinCondition {
+                left_table,
+                right_table,
+                qualifier,
+            });
+        }
+
+        None
+    }
+}
+
+/// Detect if cursor is in ORDER BY clause
+pub struct TextOrderBy;
+
+impl ContextDetector for TextOrderBy {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let text_upper = &*input.text_before_upper;
+
+        // Check if we're after ORDER BY keyword
+        if is_after_keyword(text_upper, "ORDER BY") {
+            debug!("!!! LSP: Detected ORDER BY context");
+            // Extract tables from source
+            let tables = input.statement_tables();
+            // Check for table qualifier
+            let qualifier = extract_table_qualifier(input.window_before());
+            return Some(CompletionContext::OrderByClause { tables, qualifier });
+        }
+
+        None
+    }
+}
+
+/// Detect if cursor is in GROUP BY clause
+pub struct TextGroupBy;
+
+impl ContextDetector for TextGroupBy {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let text_upper = &*input.text_before_upper;
+
+        // Check if we're after GROUP BY keyword
+        if is_after_keyword(text_upper, "GROUP BY") {
+            debug!("!!! LSP: Detected GROUP BY context");
+            // Extract tables from source
+            let tables = input.statement_tables();
+            // Check for table qualifier
+            let qualifier = extract_table_qualifier(input.window_before());
+            return Some(CompletionContext::GroupByClause { tables, qualifier });
+        }
+
+        None
+    }
+}
+
+/// Detect if cursor is in LIMIT clause
+pub struct TextLimit;
+
+impl ContextDetector for TextLimit {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let text_upper = &*input.text_before_upper;
+
+        // Check if we're after LIMIT keyword
+        if is_after_keyword(text_upper, "LIMIT") {
+            debug!("!!! LSP: Detected LIMIT context");
+            return Some(CompletionContext::LimitClause);
+        }
+
+        None
+    }
+}
+
+/// Detect if cursor is in HAVING clause
+pub struct TextHaving;
+
+impl ContextDetector for TextHaving {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let text_upper = &*input.text_before_upper;
+
+        // Check if we're after HAVING keyword
+        if is_after_keyword(text_upper, "HAVING") {
+            debug!("!!! LSP: Detected HAVING context");
+            // Extract tables from source
+            let tables = input.statement_tables();
+            // Check for table qualifier
+            let qualifier = extract_table_qualifier(input.window_before());
+            return Some(CompletionContext::HavingClause { tables, qualifier });
+        }
+
+        None
+    }
+}
+
+/// Detect if cursor is in RETURNING clause (PostgreSQL, MySQL)
+pub struct TextReturning;
+
+impl ContextDetector for TextReturning {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let text_upper = &*input.text_before_upper;
+
+        // Check if we're after RETURNING keyword
+        if is_after_keyword(text_upper, "RETURNING") {
+            debug!("!!! LSP: Detected RETURNING context");
+            // Extract tables from source - look for INSERT/UPDATE/DELETE statement
+            let tables = input.statement_tables();
+            // Check for table qualifier
+            let qualifier = extract_table_qualifier(input.window_before());
+            return Some(CompletionContext::ReturningClause { tables, qualifier });
+        }
+
+        None
+    }
+}
+
+/// Detect DDL/DML statement context (CREATE, ALTER, DROP, INSERT, UPDATE, DELETE)
+pub struct TextDdl;
+
+impl ContextDetector for TextDdl {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let text_before = input.text_before;
+        let trimmed = text_before.trim_end();
+
+        // Check for CREATE pattern
+        if trimmed.ends_with("CREATE ") || trimmed == "CREATE" {
+            debug!("!!! LSP: Detected CREATE context");
+            return Some(CompletionContext::Keywords {
+                statement_type: Some("CREATE".to_string()),
+                existing_clauses: vec![],
+            });
+        }
+
+        // Check for ALTER pattern
+        if trimmed.ends_with("ALTER ") || trimmed == "ALTER" {
+            debug!("!!! LSP: Detected ALTER context");
+            return Some(CompletionContext::Keywords {
+                statement_type: Some("ALTER".to_string()),
+                existing_clauses: vec![],
+            });
+        }
+
+        // Check for DROP pattern
+        if trimmed.ends_with("DROP ") || trimmed == "DROP" {
+            debug!("!!! LSP: Detected DROP context");
+            return Some(CompletionContext::Keywords {
+                statement_type: Some("DROP".to_string()),
+                existing_clauses: vec![],
+            });
+        }
+
+        // NOTE: INSERT, UPDATE, DELETE after the keyword require table names,
+        // so we don't detect them here - they fall through to table completion
+
+        None
+    }
+}
+
+/// Start of a statement, where statement keywords apply
+///
+/// Matches an empty document, the text right after a `;`, and a first
+/// word of at most two letters. Runs before the CST, whose nodes around
+/// the cursor belong to the previous statement after a `;`.
+pub struct TextStatementStart;
+
+impl ContextDetector for TextStatementStart {
+    fn detect(&self, input: &DetectionInput) -> Option<CompletionContext> {
+        let statement = input.statement_text_before().trim_start();
+        if statement.is_empty()
+            || (statement.len() < 3 && statement.chars().all(|c| c.is_ascii_alphabetic()))
+        {
+            debug!("!!! LSP: Detected keyword context (beginning of statement)");
+            return Some(CompletionContext::Keywords {
+                statement_type: None,
+                existing_clauses: vec![],
+            });
+        }
+
+        None
+    }
+}
+
+/// Byte offset of the last whole-word occurrence of `keyword` in uppercase `text`
+fn find_last_keyword(text: &str, keyword: &str) -> Option<usize> {
+    text.match_indices(keyword)
+        .filter(|(pos, _)| {
+            let before = text[..*pos].chars().next_back();
+            let after = text[pos + keyword.len()..].chars().next();
+            !before.is_some_and(|c| c.is_alphanumeric() || c == '_')
+                && !after.is_some_and(|c| c.is_alphanumeric() || c == '_')
+        })
+        .map(|(pos, _)| pos)
+        .last()
+}
+
+/// Functions whose first argument is a sequence name
+const SEQUENCE_FUNCTIONS: &[&str] = &["NEXTVAL", "SETVAL", "CURRVAL"];
+
+/// Functions whose second argument is a date format string
+const DATE_FORMAT_FUNCTIONS: &[&str] = &[
+    "DATE_FORMAT",
+    "TIME_FORMAT",
+    "STR_TO_DATE",
+    "TO_CHAR",
+    "TO_DATE",
+    "TO_TIMESTAMP",
+];
+
+/// Detect whether the cursor is inside a single-quoted string literal
+///
+/// Returns the role of the literal, or `None` if the cursor is outside any
+/// string. Comments and quoted identifiers are skipped so their quotes don't
+/// count.
+fn string_literal_role(text_before: &str) -> Option<StringLiteralRole> {
+    let quote = open_string_literal_start(text_before)?;
+    let before_quote = &text_before[..quote];
+
+    if ends_with_keyword(before_quote, "LIKE") || ends_with_keyword(before_quote, "ILIKE") {
+        return Some(StringLiteralRole::LikePattern);
+    }
+
+    if let Some((function, argument)) = enclosing_call(before_quote)
+        && argument == 1
+        && DATE_FORMAT_FUNCTIONS
+            .iter()
+            .any(|f| f.eq_ignore_ascii_case(&function))
+    {
+        return Some(StringLiteralRole::DateFormat);
+    }
+
+    Some(StringLiteralRole::Generic)
+}
+
+/// Find the left-hand side of the comparison whose value is at the cursor
+///
+/// A partially typed value is ignored, so `active = tr|` still finds
+/// `active`. Besides `=`, `<>`, `!=` and `IS`, the bounds of `BETWEEN` and
+/// the elements of an `IN (` value list are recognized. Returns `None`
+/// inside string literals and anywhere else.
+pub fn detect_comparison_operand(source: &str, position: Position) -> Option<ComparisonOperand> {
+    let byte_offset = position_to_byte_offset(source, position).min(source.len());
+    let text_before = source.get(..byte_offset)?;
+    if open_string_literal_start(text_before).is_some() {
+        return None;
+    }
+
+    if let Some(paren) = value_list_start(text_before) {
+        let before_in = strip_keyword(&text_before[..paren], "IN")?;
+        let before_in = strip_keyword(before_in, "NOT").unwrap_or(before_in);
+        let (qualifier, column) = operand_before(before_in)?;
+        return Some(ComparisonOperand::ValueList { qualifier, column });
+    }
+
+    let stripped = text_before.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_');
+    if let Some(before) = strip_keyword(stripped, "BETWEEN") {
+        return between_operand(before, BetweenBound::Low);
+    }
+    if let Some(before_and) = strip_keyword(stripped, "AND") {
+        // `BETWEEN low AND |`: the AND closes the low bound unless the range is complete
+        let upper = before_and.to_ascii_uppercase();
+        if let Some(between) = find_last_keyword(&upper, "BETWEEN") {
+            let low = &upper[between + "BETWEEN".len()..];
+            if !low.trim().is_empty() && find_last_keyword(low, "AND").is_none() {
+                return between_operand(&before_and[..between], BetweenBound::High);
+            }
+        }
+    }
+    if ends_with_keyword(stripped, "IS") {
+        return Some(ComparisonOperand::NullTest);
+    }
+
+    let stripped = stripped.trim_end();
+    let before_operator = ["<>", "!=", "="]
+        .iter()
+        .find_map(|op| stripped.strip_suffix(op))?;
+    // `<=`, `>=` and `:=` aren't equality tests
+    if before_operator.ends_with(['<', '>', '!', ':']) {
+        return None;
+    }
+
+    let (qualifier, column) = operand_before(before_operator)?;
+    Some(ComparisonOperand::Column { qualifier, column })
+}
+
+/// Words after which the cursor starts an operand instead of following one
+const OPERAND_INTRODUCERS: &[&str] = &[
+    "SELECT",
+    "DISTINCT",
+    "ALL",
+    "WHERE",
+    "HAVING",
+    "ON",
+    "BY",
+    "AND",
+    "OR",
+    "NOT",
+    "WHEN",
+    "THEN",
+    "ELSE",
+    "CASE",
+    "SET",
+    "IS",
+    "IN",
+    "LIKE",
+    "ILIKE",
+    "BETWEEN",
+    "AS",
+    "FROM",
+    "JOIN",
+    "USING",
+    "RETURNING",
+    "NULL",
+    "TRUE",
+    "FALSE",
+    "END",
+];
+
+/// Detect the column the cursor follows as a complete operand
+///
+/// The cursor must be separated from the column by whitespace, with an
+/// operator possibly being typed, e.g. `WHERE name |` or `WHERE u.name IL|`.
+/// Returns `None` inside string literals and where an operand is expected,
+/// e.g. after `WHERE` or `AND`.
+pub fn detect_completed_operand(source: &str, position: Position) -> Option<CompletedOperand> {
+    let byte_offset = position_to_byte_offset(source, position).min(source.len());
+    let text_before = source.get(..byte_offset)?;
+    if open_string_literal_start(text_before).is_some() {
+        return None;
+    }
+
+    let stripped = text_before.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_');
+    let before = stripped.trim_end();
+    if before.len() == stripped.len() {
+        return None;
+    }
+
+    let (qualifier, column) = operand_before(before)?;
+    if qualifier.is_none()
+        && OPERAND_INTRODUCERS
+            .iter()
+            .any(|kw| kw.eq_ignore_ascii_case(&column))
+    {
+        return None;
+    }
+
+    let start = before
+        .rfind(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '"' | '`')))
+        .map_or(0, |pos| pos + 1);
+    Some(CompletedOperand {
+        qualifier,
+        column,
+        start: byte_to_position(start, source),
+    })
+}
+
+/// Aliases of the SELECT list items of the query at the cursor
+///
+/// Both `expr AS alias` and `expr alias` count. Aliases are returned as
+/// written, so a quoted alias like `"Total Rows"` keeps its delimiters.
+/// ORDER BY and HAVING may refer to them.
+pub fn projection_aliases(source: &str, position: Position) -> Vec<String> {
+    let input = DetectionInput::new(None, source, position);
+    let statement = &source[input.statement.clone()];
+    let before = input.statement_text_before();
+
+    // The query holding the cursor starts after the nearest enclosing
+    // parenthesis that opens a SELECT, or with the statement
+    let mut end = before.len();
+    let list = loop {
+        let open = innermost_open_paren(&before[..end]).map(|(open, _)| open);
+        let start = open.map_or(0, |open| open + 1);
+        if let Some(list) = select_list(&statement[start..]) {
+            break list;
+        }
+        match open {
+            Some(open) => end = open,
+            None => return Vec::new(),
+        }
+    };
+
+    split_top_level(list)
+        .into_iter()
+        .filter_map(item_alias)
+        .map(str::to_string)
+        .collect()
+}
+
+/// The SELECT list of the first SELECT in `query`, up to its FROM
+///
+/// Scanning stops at a parenthesis closing one opened before `query`.
+fn select_list(query: &str) -> Option<&str> {
+    let mut list_start = None;
+    for token in lex(query) {
+        match token.kind {
+            TokenKind::CloseParen if token.depth < 0 => break,
+            TokenKind::Word if token.depth == 0 => match list_start {
+                None if token.text.eq_ignore_ascii_case("SELECT") => {
+                    list_start = Some(token.end());
+                }
+                Some(start) if token.text.eq_ignore_ascii_case("FROM") => {
+                    return Some(&query[start..token.offset]);
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    list_start.map(|start| &query[start..])
+}
+
+/// Alias of one SELECT list item, as written
+fn item_alias(item: &str) -> Option<&str> {
+    let atoms = sql_atoms(item);
+    let [.., before, alias] = atoms.as_slice() else {
+        return None;
+    };
+    let is_introducer = |atom: &str| {
+        OPERAND_INTRODUCERS
+            .iter()
+            .any(|kw| kw.eq_ignore_ascii_case(atom))
+    };
+    let is_quoted = |atom: &str| atom.starts_with(['"', '`']);
+    let is_name = |atom: &str| atom.starts_with(|c: char| c.is_alphabetic() || c == '_');
+
+    if before.eq_ignore_ascii_case("AS") {
+        return (is_quoted(alias) || is_name(alias)).then_some(*alias);
+    }
+    // Without AS, the alias must follow a complete operand
+    let ends_operand = *before == ")"
+        || is_quoted(before)
+        || before.starts_with(|c: char| c == '\'' || c.is_ascii_digit())
+        || (is_name(before) && !is_introducer(before));
+    let is_alias = is_quoted(alias) || (is_name(alias) && !is_introducer(alias));
+    (ends_operand && is_alias).then_some(*alias)
+}
+
+/// Words, quoted identifiers, string literals and single punctuation
+/// characters of `text`, skipping whitespace and comments
+fn sql_atoms(text: &str) -> Vec<&str> {
+    lex(text)
+        .filter(|token| token.kind != TokenKind::Comment)
+        .map(|token| token.text)
+        .collect()
+}
+
+/// The column tested by `[NOT] BETWEEN`, written before the keyword
+fn between_operand(before_between: &str, bound: BetweenBound) -> Option<ComparisonOperand> {
+    let before = strip_keyword(before_between, "NOT").unwrap_or(before_between);
+    let (qualifier, column) = operand_before(before)?;
+    Some(ComparisonOperand::Between {
+        qualifier,
+        column,
+        bound,
+    })
+}
+
+/// The possibly qualified column name at the end of `text`
+fn operand_before(text: &str) -> Option<(Option<String>, String)> {
+    let text = text.trim_end();
+    let operand_start = text
+        .rfind(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '"' | '`')))
+        .map_or(0, |pos| pos + 1);
+    let unquote = |name: &str| name.trim_matches(|c| c == '"' || c == '`').to_string();
+    let (qualifier, column) = match text[operand_start..].rsplit_once('.') {
+        Some((qualifier, column)) => (Some(unquote(qualifier)), unquote(column)),
+        None => (None, unquote(&text[operand_start..])),
+    };
+
+    if column.is_empty() || column.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((qualifier, column))
+}
+
+/// Byte offset of the `(` opening an `IN` value list around the end of `text`
+///
+/// `IN (SELECT ...` and `IN (WITH ...` open a subquery, not a value list.
+fn value_list_start(text: &str) -> Option<usize> {
+    let (paren, _) = innermost_open_paren(text)?;
+    strip_keyword(&text[..paren], "IN")?;
+
+    let first_word = text[paren + 1..]
+        .trim_start()
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .next()
+        .unwrap_or_default();
+    let is_subquery = ["SELECT", "WITH"]
+        .iter()
+        .any(|keyword| keyword.eq_ignore_ascii_case(first_word));
+    (!is_subquery).then_some(paren)
+}
+
+/// Check if the cursor follows a `::` cast, possibly with a partial type name typed
+fn is_after_type_cast(text_before: &str) -> bool {
+    text_before
+        .trim_end_matches(|c: char| c.is_alphanumeric() || c == '_')
+        .ends_with("::")
+}
+
+/// Whether `text_before` ends in a collation name after `COLLATE`
+///
+/// Returns whether the name is inside double quotes.
+fn collation_name_start(text_before: &str) -> Option<bool> {
+    let before_name =
+        text_before.trim_end_matches(|c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    let (before_name, in_quotes) = match before_name.strip_suffix('"') {
+        Some(before_quote) => (before_quote, true),
+        None => (before_name, false),
+    };
+    // The keyword itself is still being typed without the space
+    if !before_name.ends_with(char::is_whitespace) {
+        return None;
+    }
+    ends_with_keyword(before_name, "COLLATE").then_some(in_quotes)
+}
+
+/// The text before `keyword` if `text` ends with it as a whole word
+fn strip_keyword<'a>(text: &'a str, keyword: &str) -> Option<&'a str> {
+    ends_with_keyword(text, keyword).then(|| {
+        let trimmed = text.trim_end();
+        &trimmed[..trimmed.len() - keyword.len()]
+    })
+}
+
+/// Check if `text` ends with `keyword` as a whole word, ignoring trailing whitespace
+fn ends_with_keyword(text: &str, keyword: &str) -> bool {
+    let trimmed = text.trim_end();
+    let Some(start) = trimmed.len().checked_sub(keyword.len()) else {
+        return false;
+    };
+    trimmed.is_char_boundary(start)
+        && trimmed[start..].eq_ignore_ascii_case(keyword)
+        && !trimmed[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Byte offset of the quote opening an unterminated string at the end of `text`
+fn open_string_literal_start(text: &str) -> Option<usize> {
+    // An unterminated literal runs to the end of the text
+    lex(text)
+        .last()
+        .filter(|token| {
+            token.kind == TokenKind::String && token.text.starts_with('\'') && !token.is_closed()
+        })
+        .map(|token| token.offset)
+}
+
+/// Find the function call enclosing the end of `text`
+///
+/// Returns the function name and the zero-based index of the argument being
+/// written.
+fn enclosing_call(text: &str) -> Option<(String, usize)> {
+    let (paren, argument) = innermost_open_paren(text)?;
+    let name: String = text[..paren]
+        .trim_end()
+        .chars()
+        .rev()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    (!name.is_empty()).then_some((name, argument))
+}
+
+/// Find the unclosed parenthesis nearest the end of `text`
+///
+/// Returns its byte offset and the zero-based index of the comma-separated
+/// item being written inside it. Parentheses and commas in string literals,
+/// quoted identifiers and comments are skipped.
+pub fn innermost_open_paren(text: &str) -> Option<(usize, usize)> {
+    // Offset and current item of each parenthesis still open
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    for token in lex(text) {
+        match token.kind {
+            TokenKind::OpenParen => open.push((token.offset, 0)),
+            TokenKind::CloseParen => {
+                open.pop();
+            }
+            _ if token.is_symbol(',') => {
+                if let Some((_, argument)) = open.last_mut() {
+                    *argument += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    open.pop()
+}
+
+/// Check if cursor is after a specific keyword in uppercase `text`
+fn is_after_keyword(text: &str, keyword: &str) -> bool {
+    // Find the keyword
+    if let Some(pos) = text.find(keyword) {
+        // Check if there's nothing significant between keyword and cursor position
+        let after_keyword = &text[pos + keyword.len()..];
+        // Only whitespace and maybe partial identifier should be after keyword
+        let trimmed = after_keyword.trim_start();
+        // If it's very short or just identifiers/whitespace, we're likely right after the keyword
+        trimmed.len() < 20 || trimmed.split_whitespace().count() <= 2
+    } else {
+        false
+    }
+}
+
+/// Extract text after a specific keyword in uppercase `text`
+fn extract_after_keyword(text: &str, keyword: &str) -> String {
+    if let Some(pos) = text.find(keyword) {
+        text[pos + keyword.len()..].to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// Whether the cursor follows `OVER` and an optional partial window name
+///
+/// Matches `OVER |` and `OVER w|`, but not `OVER|` or `OVER (|`.
+fn is_after_over_keyword(text_before: &str) -> bool {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let without_word = text_before.trim_end_matches(is_ident);
+    let keyword_end = without_word.trim_end();
+    if keyword_end.len() == without_word.len() {
+        return false;
+    }
+
+    let upper = keyword_end.to_ascii_uppercase();
+    upper.ends_with("OVER")
+        && !upper[..upper.len() - 4]
+            .chars()
+            .next_back()
+            .is_some_and(is_ident)
+}
+
+/// Byte offset just past the `(` of the WINDOW definition enclosing the cursor
+///
+/// Returns `None` unless the cursor sits inside `WINDOW name AS (...`. Only
+/// the last `window` bytes of `upper` are searched for `WINDOW`.
+fn window_definition_start(upper: &str, window: usize) -> Option<usize> {
+    let base = upper.len() - window;
+    let window_pos = base + upper[base..].rfind("WINDOW ")?;
+    let after_window = &upper[window_pos..];
+    let paren = after_window
+        .rfind(" AS (")
+        .map(|pos| pos + 5)
+        .or_else(|| after_window.rfind(" AS(").map(|pos| pos + 4))?;
+
+    let start = window_pos + paren;
+    let inside = &upper[start..];
+    let open_parens = inside.matches('(').count() + 1;
+    let close_parens = inside.matches(')').count();
+    (open_parens > close_parens).then_some(start)
+}
+
+/// Extract window names defined in the WINDOW clause of `source`
+///
+/// Pattern: "WINDOW w AS (...), w2 AS (...)"
+fn extract_window_names(source: &str) -> Vec<String> {
+    let mut windows = Vec::new();
+    let upper = source.to_ascii_uppercase();
+    let Some(window_pos) = upper.find("WINDOW ") else {
+        return windows;
+    };
+
+    let mut depth = 0usize;
+    let mut previous: Option<&str> = None;
+    let clause = &source[window_pos + 7..];
+    let mut rest = clause;
+    while let Some(c) = rest.chars().next() {
+        if c == '(' || c == ')' {
+            depth = if c == '(' {
+                depth + 1
+            } else {
+                depth.saturating_sub(1)
+            };
+            previous = None;
+            rest = &rest[1..];
+            continue;
+        }
+        if c == ';' && depth == 0 {
+            break;
+        }
+        if !(c.is_ascii_alphanumeric() || c == '_' || c == '"') {
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '"'))
+            .unwrap_or(rest.len());
+        let word = &rest[..len];
+        rest = &rest[len..];
+        if depth > 0 {
+            continue;
+        }
+
+        let keyword = word.to_uppercase();
+        if matches!(
+            keyword.as_str(),
+            "ORDER" | "LIMIT" | "OFFSET" | "FETCH" | "FOR"
+        ) {
+            break;
+        }
+        if keyword == "AS"
+            && let Some(name) = previous
+        {
+            let name = name.trim_matches('"').to_string();
+            if !windows.contains(&name) {
+                windows.push(name);
+            }
+        }
+        previous = Some(word);
+    }
+
+    windows
+}
+
+/// Extract CTE names that have already been defined in the current WITH clause
+fn extract_defined_ctes(source: &str) -> Vec<String> {
+    let mut ctes = Vec::new();
+    let upper = source.to_ascii_uppercase();
+
+    // Find WITH clause
+    if let Some(with_pos) = upper.find("WITH ") {
+        let after_with = &source[with_pos + 5..]; // Skip "WITH "
+
+        // Split by comma to find individual CTE definitions
+        // Pattern: "cte_name AS (SELECT ...)"
+        for part in after_with.split(',') {
+            let trimmed = part.trim();
+            if let Some(as_pos) = trimmed.to_ascii_uppercase().find(" AS ") {
+                let cte_name = trimmed[..as_pos].trim();
+                if !cte_name.is_empty() {
+                    ctes.push(cte_name.to_string());
+                }
+            }
+        }
+    }
+
+    ctes
+}
+
+/// Extract CTE names that have been defined before the cursor position
+fn extract_defined_ctes_before_cursor(_source: &str, text_before: &str) -> Vec<String> {
+    let mut ctes = Vec::new();
+    let upper = text_before.to_ascii_uppercase();
+
+    // Find WITH clause in text before cursor
+    if let Some(with_pos) = upper.find("WITH ") {
+        let after_with = &text_before[with_pos + 5..]; // Skip "WITH "
+
+        // Split by comma to find individual CTE definitions
+        // Pattern: "cte_name AS (SELECT ...)"
+        for part in after_with.split(',') {
+            let trimmed = part.trim();
+            if let Some(as_pos) = trimmed.to_ascii_uppercase().find(" AS ") {
+                let cte_name = trimmed[..as_pos].trim();
+                if !cte_name.is_empty() {
+                    ctes.push(cte_name.to_string());
+                }
+            }
+        }
+    }
+
+    ctes
+}
+
+/// Detect projection context with provided tables and qualifier
+/// This is a variant of detect_projection_context that accepts pre-computed values
+fn detect_projection_context_with_tables(
+    source: &str,
+    text_before: &str,
+    tables: Vec<String>,
+    qualifier: Option<String>,
+) -> Option<CompletionContext> {
+    let source_upper = source.to_ascii_uppercase();
+    let text_before_upper = text_before.to_ascii_uppercase();
+
+    // Check if cursor is inside a subquery
+    let open_parens = text_before.matches('(').count();
+    let close_parens = text_before.matches(')').count();
+    let in_subquery = open_parens > close_parens;
+
+    debug!(
+        "!!! LSP: detect_projection_context_with_tables: in_subquery={}, tables={:?}, qualifier={:?}",
+        in_subquery, tables, qualifier
+    );
+
+    // If we're in SELECT context (at start or in subquery)
+    if source_upper.starts_with("SELECT") || in_subquery {
+        // If we haven't reached FROM yet, it's in projection
+        if !text_before_upper.contains("FROM") {
+            debug!("!!! LSP: Detected SELECT projection context (no FROM yet)");
+            return Some(CompletionContext::SelectProjection { tables, qualifier });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run a detector with the cursor at the end of a single-line `source`
+    fn at_end(detector: &dyn ContextDetector, source: &str) -> Option<CompletionContext> {
+        let line = source.matches('\n').count() as u32;
+        let column = source.split('\n').next_back().unwrap_or("").chars().count() as u32;
+        detector.detect(&DetectionInput::new(
+            None,
+            source,
+            Position::new(line, column),
+        ))
+    }
+
+    fn role_at_end(source: &str) -> Option<StringLiteralRole> {
+        string_literal_role(source)
+    }
+
+    #[test]
+    fn test_comparison_operand() {
+        let operand = |source: &str| {
+            let column = source.chars().count() as u32;
+            detect_comparison_operand(source, Position::new(0, column))
+        };
+        let column = |qualifier: Option<&str>, column: &str| {
+            Some(ComparisonOperand::Column {
+                qualifier: qualifier.map(String::from),
+                column: column.to_string(),
+            })
+        };
+
+        assert_eq!(
+            operand("SELECT * FROM users WHERE active = "),
+            column(None, "active")
+        );
+        assert_eq!(
+            operand("SELECT * FROM users u WHERE u.active <> tr"),
+            column(Some("u"), "active")
+        );
+        assert_eq!(
+            operand("SELECT * FROM users WHERE deleted_at IS "),
+            Some(ComparisonOperand::NullTest)
+        );
+        assert_eq!(
+            operand("SELECT * FROM users WHERE deleted_at IS N"),
+            Some(ComparisonOperand::NullTest)
+        );
+        assert_eq!(operand("SELECT * FROM users WHERE deleted_at IS"), None);
+        assert_eq!(operand("SELECT * FROM users WHERE age >= "), None);
+        assert_eq!(operand("SELECT * FROM users WHERE name = '"), None);
+        assert_eq!(operand("SELECT * FROM users WHERE "), None);
+    }
+
+    #[test]
+    fn test_between_bounds() {
+        let operand = |source: &str| {
+            let column = source.chars().count() as u32;
+            detect_comparison_operand(source, Position::new(0, column))
+        };
+        let between = |qualifier: Option<&str>, bound| {
+            Some(ComparisonOperand::Between {
+                qualifier: qualifier.map(String::from),
+                column: "age".to_string(),
+                bound,
+            })
+        };
+
+        assert_eq!(
+            operand("SELECT * FROM users WHERE age BETWEEN "),
+            between(None, BetweenBound::Low)
+        );
+        assert_eq!(
+            operand("SELECT * FROM users u WHERE u.age NOT BETWEEN 1"),
+            between(Some("u"), BetweenBound::Low)
+        );
+        assert_eq!(
+            operand("SELECT * FROM users WHERE age BETWEEN 18 AND "),
+            between(None, BetweenBound::High)
+        );
+        assert_eq!(
+            operand("SELECT * FROM users WHERE age BETWEEN 18 AND 6"),
+            between(None, BetweenBound::High)
+        );
+        // Once both bounds exist, AND is a boolean operator again
+        assert_eq!(
+            operand("SELECT * FROM users WHERE age BETWEEN 18 AND 65 AND "),
+            None
+        );
+        assert_eq!(operand("SELECT * FROM users WHERE age BETWEEN 18 "), None);
+    }
+
+    #[test]
+    fn test_in_value_list() {
+        let operand = |source: &str| {
+            let column = source.chars().count() as u32;
+            detect_comparison_operand(source, Position::new(0, column))
+        };
+        let status = Some(ComparisonOperand::ValueList {
+            qualifier: None,
+            column: "status".to_string(),
+        });
+
+        assert_eq!(operand("SELECT * FROM users WHERE status IN ("), status);
+        assert_eq!(
+            operand("SELECT * FROM users WHERE status NOT IN ('active', 'x)', "),
+            status
+        );
+        assert_eq!(
+            operand("SELECT * FROM users WHERE status IN (SELECT "),
+            None
+        );
+        assert_eq!(
+            operand("SELECT * FROM users WHERE status IN ('a') AND "),
+            None
+        );
+
+        // The parenthesis isn't mistaken for a subquery
+        assert_eq!(
+            at_end(&TextValueList, "SELECT * FROM users WHERE status IN ("),
+            Some(CompletionContext::WhereClause {
+                tables: vec!["users".to_string()],
+                qualifier: None,
+            })
+        );
+        assert_eq!(
+            at_end(&TextValueList, "SELECT * FROM users WHERE id IN (SELECT "),
+            None
+        );
+        assert_eq!(
+            at_end(&TextValueList, "SELECT * FROM users WHERE lower(name"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_string_literal_like_pattern() {
+        assert_eq!(
+            role_at_end("SELECT * FROM users WHERE name LIKE '"),
+            Some(StringLiteralRole::LikePattern)
+        );
+        assert_eq!(
+            role_at_end("SELECT * FROM users WHERE name NOT ILIKE 'jo"),
+            Some(StringLiteralRole::LikePattern)
+        );
+    }
+
+    #[test]
+    fn test_string_literal_date_format() {
+        assert_eq!(
+            role_at_end("SELECT DATE_FORMAT(created_at, '"),
+            Some(StringLiteralRole::DateFormat)
+        );
+        assert_eq!(
+            role_at_end("SELECT to_char(now(), 'YYYY"),
+            Some(StringLiteralRole::DateFormat)
+        );
+        // The first argument is not a format string
+        assert_eq!(
+            role_at_end("SELECT STR_TO_DATE('"),
+            Some(StringLiteralRole::Generic)
+        );
+    }
+
+    #[test]
+    fn test_string_literal_generic_and_outside() {
+        assert_eq!(
+            role_at_end("SELECT * FROM users WHERE name = 'it''s"),
+            Some(StringLiteralRole::Generic)
+        );
+        assert_eq!(
+            role_at_end("SELECT * FROM users WHERE name = 'x' AND "),
+            None
+        );
+        assert_eq!(role_at_end("SELECT \"it's\" FROM t -- don't\nWHERE "), None);
+    }
+
+    #[test]
+    fn test_collation_name_after_collate() {
+        assert_eq!(collation_name_start("ORDER BY name COLLATE "), Some(false));
+        assert_eq!(
+            collation_name_start("ORDER BY name collate utf8mb4_"),
+            Some(false)
+        );
+        assert_eq!(
+            collation_name_start("ORDER BY name COLLATE \"en-"),
+            Some(true)
+        );
+        assert_eq!(collation_name_start("ORDER BY name COLLATE"), None);
+        assert_eq!(collation_name_start("ORDER BY name COLLATE\""), None);
+        assert_eq!(collation_name_start("ORDER BY name "), None);
+        assert_eq!(collation_name_start("SELECT nocollate "), None);
+    }
+
+    #[test]
+    fn test_type_cast_target() {
+        let at_end = is_after_type_cast;
+
+        assert!(at_end("SELECT id::"));
+        assert!(at_end("SELECT id::te"));
+        assert!(at_end("SELECT (price * 2)::numeric"));
+        assert!(!at_end("SELECT id:"));
+        assert!(!at_end("SELECT id, "));
+    }
+
+    fn merge_context_at_end(source: &str) -> Option<CompletionContext> {
+        at_end(&TextMerge, source)
+    }
+
+    #[test]
+    fn test_merge_update_set_offers_target_columns() {
+        let ctx = merge_context_at_end(
+            "MERGE INTO users u USING staging s ON u.id = s.id WHEN MATCHED THEN UPDATE SET ",
+        );
+        assert_eq!(
+            ctx,
+            Some(CompletionContext::MergeClause {
+                target: "users".to_string(),
+                source: Some("staging".to_string()),
+                target_only: true,
+                qualifier: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_merge_source_qualifier() {
+        let ctx = merge_context_at_end(
+            "MERGE INTO users u USING staging s ON u.id = s.id \
+             WHEN MATCHED THEN UPDATE SET name = s.",
+        );
+        assert!(matches!(
+            ctx,
+            Some(CompletionContext::MergeClause {
+                target_only: false,
+                qualifier: Some(ref q),
+                ..
+            }) if q == "s"
+        ));
+
+        let ctx = merge_context_at_end("MERGE INTO users u USING staging s ON u.");
+        assert!(matches!(
+            ctx,
+            Some(CompletionContext::MergeClause {
+                target_only: false,
+                qualifier: Some(ref q),
+                ..
+            }) if q == "u"
+        ));
+    }
+
+    #[test]
+    fn test_merge_insert_columns_and_values() {
+        let prefix = "MERGE INTO users u USING staging s ON u.id = s.id WHEN NOT MATCHED THEN ";
+        assert!(matches!(
+            merge_context_at_end(&format!("{prefix}INSERT (")),
+            Some(CompletionContext::MergeClause {
+                target_only: true,
+                ..
+            })
+        ));
+        assert!(matches!(
+            merge_context_at_end(&format!("{prefix}INSERT (id) VALUES (")),
+            Some(CompletionContext::MergeClause {
+                target_only: false,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_merge_tables_and_keywords() {
+        assert_eq!(
+            merge_context_at_end("MERGE INTO "),
+            Some(CompletionContext::FromClause {
+                exclude_tables: vec![]
+            })
+        );
+        assert_eq!(
+            merge_context_at_end("MERGE INTO users USING "),
+            Some(CompletionContext::FromClause {
+                exclude_tables: vec!["users".to_string()]
+            })
+        );
+        assert_eq!(
+            merge_context_at_end(
+                "MERGE INTO users u USING staging s ON u.id = s.id WHEN MATCHED THEN "
+            ),
+            Some(CompletionContext::Keywords {
+                statement_type: Some("MERGE WHEN".to_string()),
+                existing_clauses: vec![],
+            })
+        );
+        assert_eq!(merge_context_at_end("SELECT * FROM users WHERE "), None);
+        assert_eq!(merge_context_at_end("MERGED "), None);
+    }
+
+    fn distinct_on_at_end(source: &str) -> Option<CompletionContext> {
+        at_end(&TextDistinctOn, source)
+    }
+
+    #[test]
+    fn test_distinct_on_list_offers_columns() {
+        assert!(matches!(
+            distinct_on_at_end("SELECT DISTINCT ON ("),
+            Some(CompletionContext::DistinctOnList {
+                qualifier: None,
+                ..
+            })
+        ));
+        assert!(matches!(
+            distinct_on_at_end("SELECT DISTINCT ON (user_id, "),
+            Some(CompletionContext::DistinctOnList { .. })
+        ));
+        assert!(matches!(
+            distinct_on_at_end("SELECT DISTINCT ON (lower(u.email), u."),
+            Some(CompletionContext::DistinctOnList {
+                qualifier: Some(ref q),
+                ..
+            }) if q == "u"
+        ));
+    }
+
+    #[test]
+    fn test_distinct_on_outside_list() {
+        assert_eq!(distinct_on_at_end("SELECT DISTINCT ON (user_id) "), None);
+        assert_eq!(distinct_on_at_end("SELECT DISTINCT "), None);
+        assert_eq!(distinct_on_at_end("SELECT DISTINCT ON (a); SELECT "), None);
+    }
+
+    /// Detect with the cursor at `|`
+    fn create_table_as_at(marked: &str) -> Option<CompletionContext> {
+        let cursor = marked.find('|').unwrap();
+        let source = marked.replacen('|', "", 1);
+        TextCreateTableAs.detect(&DetectionInput::new(
+            None,
+            &source,
+            Position::new(0, cursor as u32),
+        ))
+    }
+
+    #[test]
+    fn test_create_table_as_detects_query() {
+        assert_eq!(
+            create_table_as_at("CREATE TABLE report AS SELECT | FROM users"),
+            Some(CompletionContext::SelectProjection {
+                tables: names(&["users"]),
+                qualifier: None,
+            })
+        );
+        assert_eq!(
+            create_table_as_at(
+                "CREATE TEMP TABLE IF NOT EXISTS report AS (SELECT u.| FROM users u)"
+            ),
+            Some(CompletionContext::SelectProjection {
+                tables: names(&["u"]),
+                qualifier: Some("u".to_string()),
+            })
+        );
+        assert_eq!(
+            create_table_as_at("SELECT 1; CREATE TABLE report SELECT * FROM orders WHERE |"),
+            Some(CompletionContext::WhereClause {
+                tables: names(&["orders"]),
+                qualifier: None,
+            })
+        );
+        assert_eq!(
+            create_table_as_at("CREATE TABLE report AS |"),
+            keywords(None)
+        );
+    }
+
+    #[test]
+    fn test_create_table_as_excludes_new_table() {
+        assert_eq!(
+            create_table_as_at("CREATE TABLE app.report AS SELECT * FROM users JOIN |"),
+            Some(CompletionContext::FromClause {
+                exclude_tables: names(&["users", "report"]),
+            })
+        );
+        assert_eq!(
+            create_table_as_at("SELECT id INTO report FROM |"),
+            Some(CompletionContext::FromClause {
+                exclude_tables: names(&["report"]),
+            })
+        );
+        assert_eq!(
+            create_table_as_at("SELECT | INTO TEMP report FROM users"),
+            Some(CompletionContext::SelectProjection {
+                tables: names(&["users"]),
+                qualifier: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_select_into_target_offers_nothing() {
+        assert_eq!(
+            create_table_as_at("SELECT id INTO |"),
+            Some(CompletionContext::Unknown)
+        );
+        assert_eq!(
+            create_table_as_at("SELECT id INTO rep| FROM users"),
+            Some(CompletionContext::Unknown)
+        );
+    }
+
+    #[test]
+    fn test_create_table_as_ignores_other_statements() {
+        assert_eq!(create_table_as_at("CREATE TABLE report (|"), None);
+        assert_eq!(create_table_as_at("CREATE TABLE report AS|"), None);
+        assert_eq!(create_table_as_at("CREATE VIEW v AS SELECT |"), None);
+        assert_eq!(create_table_as_at("INSERT INTO t SELECT |"), None);
+        assert_eq!(create_table_as_at("SELECT id INTO @v FROM |"), None);
+        assert_eq!(create_table_as_at("SELECT (SELECT 1 INTO x) FROM |"), None);
+    }
+
+    #[test]
+    fn test_over_offers_named_windows() {
+        let source = "SELECT rank() OVER  FROM emp \
+                      WINDOW w AS (PARTITION BY dept), w2 AS (w ORDER BY salary)";
+        let cursor = Position::new(0, "SELECT rank() OVER ".len() as u32);
+        assert_eq!(
+            TextWindow.detect(&DetectionInput::new(None, source, cursor)),
+            Some(CompletionContext::WindowName {
+                windows: vec!["w".to_string(), "w2".to_string()],
+            })
+        );
+        assert!(matches!(
+            at_end(&TextWindow, "SELECT rank() OVER w"),
+            Some(CompletionContext::WindowName { .. })
+        ));
+        assert!(!is_after_over_keyword("SELECT rank() OVER"));
+        assert!(!is_after_over_keyword("SELECT rank() OVER ("));
+        assert!(!is_after_over_keyword("SELECT MOVER "));
+    }
+
+    #[test]
+    fn test_window_clause_reuses_window_parts() {
+        let window_part = |text: &str| match at_end(&TextWindow, text) {
+            Some(CompletionContext::WindowFunctionClause { window_part, .. }) => Some(window_part),
+            _ => None,
+        };
+
+        let prefix = "SELECT rank() OVER w FROM emp WINDOW w AS (";
+        assert_eq!(window_part(prefix), Some(WindowFunctionPart::OverStart));
+        assert_eq!(
+            window_part(&format!("{prefix}PARTITION BY ")),
+            Some(WindowFunctionPart::PartitionBy)
+        );
+        assert_eq!(
+            window_part(&format!("{prefix}PARTITION BY dept ORDER BY ")),
+            Some(WindowFunctionPart::OrderBy)
+        );
+        // Past the closing parenthesis the WINDOW clause no longer applies
+        assert_eq!(
+            window_part("SELECT id FROM emp WINDOW w AS (PARTITION BY dept) ORDER BY "),
+            None
+        );
+    }
+
+    fn keywords(statement_type: Option<&str>) -> Option<CompletionContext> {
+        Some(CompletionContext::Keywords {
+            statement_type: statement_type.map(str::to_string),
+            existing_clauses: vec![],
+        })
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_dml_keyword_after_statement_keyword() {
+        assert_eq!(at_end(&TextDmlKeyword, "UPDATE "), keywords(Some("UPDATE")));
+        assert_eq!(at_end(&TextDmlKeyword, "INSERT"), keywords(Some("INSERT")));
+        assert_eq!(at_end(&TextDmlKeyword, "DELETE "), keywords(Some("DELETE")));
+        assert_eq!(at_end(&TextDmlKeyword, "DELETE FROM "), None);
+    }
+
+    #[test]
+    fn test_union_expects_set_operation() {
+        assert_eq!(
+            at_end(&TextUnion, "SELECT id FROM users UNION "),
+            keywords(Some("UNION"))
+        );
+        assert_eq!(at_end(&TextUnion, "SELECT id FROM users UNION ALL "), None);
+    }
+
+    #[test]
+    fn test_cte_definitions() {
+        assert_eq!(
+            at_end(&TextCte, "WITH "),
+            Some(CompletionContext::CteDefinition {
+                available_tables: vec![],
+                defined_ctes: vec![],
+            })
+        );
+        assert_eq!(
+            at_end(&TextCte, "WITH recent AS (SELECT * FROM orders), "),
+            Some(CompletionContext::CteDefinition {
+                available_tables: vec![],
+                defined_ctes: names(&["recent"]),
+            })
+        );
+        assert_eq!(at_end(&TextCte, "SELECT * FROM users WHERE "), None);
+    }
+
+    #[test]
+    fn test_from_offers_tables() {
+        assert_eq!(
+            at_end(&TextFrom, "SELECT * FROM "),
+            Some(CompletionContext::FromClause {
+                exclude_tables: vec![],
+            })
+        );
+        assert_eq!(
+            at_end(&TextFrom, "SELECT * FROM users u LEFT JOIN "),
+            Some(CompletionContext::FromClause {
+                exclude_tables: names(&["users"]),
+            })
+        );
+        assert_eq!(at_end(&TextFrom, "SELECT * FROM users WHERE "), None);
+    }
+
+    #[test]
+    fn test_projection_before_from() {
+        assert_eq!(
+            at_end(&TextProjection, "SELECT "),
+            Some(CompletionContext::SelectProjection {
+                tables: vec![],
+                qualifier: None,
+            })
+        );
+        assert_eq!(at_end(&TextProjection, "UPDATE users SET "), None);
+    }
+
+    #[test]
+    fn test_where_clause_columns() {
+        assert_eq!(
+            at_end(&TextWhere, "SELECT * FROM users u WHERE u."),
+            Some(CompletionContext::WhereClause {
+                tables: names(&["u"]),
+                qualifier: Some("u".to_string()),
+            })
+        );
+        assert_eq!(at_end(&TextWhere, "SELECT * FROM users ORDER BY "), None);
+    }
+
+    #[test]
+    fn test_dml_where_sees_only_target_tables() {
+        assert_eq!(
+            at_end(&TextWhere, "SELECT * FROM orders; DELETE FROM users WHERE "),
+            Some(CompletionContext::WhereClause {
+                tables: names(&["users"]),
+                qualifier: None,
+            })
+        );
+        assert_eq!(
+            at_end(&TextWhere, "DELETE FROM users u WHERE u."),
+            Some(CompletionContext::WhereClause {
+                tables: names(&["u"]),
+                qualifier: Some("u".to_string()),
+            })
+        );
+        assert_eq!(
+            at_end(
+                &TextWhere,
+                "DELETE FROM users USING orders WHERE users.id = orders.user_id AND "
+            ),
+            Some(CompletionContext::WhereClause {
+                tables: names(&["users", "orders"]),
+                qualifier: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_join_using_columns() {
+        assert_eq!(
+            at_end(&TextJoinUsing, "SELECT * FROM users JOIN orders USING ("),
+            Some(CompletionContext::JoinCondition {
+                left_table: Some("users".to_string()),
+                right_table: Some("orders".to_string()),
+                qualifier: None,
+            })
+        );
+        assert_eq!(
+            at_end(&TextJoinUsing, "SELECT * FROM users JOIN orders ON "),
+            None
+        );
+    }
+
+    #[test]
+    fn test_join_on_columns() {
+        assert_eq!(
+            at_end(&TextJoinOn, "SELECT * FROM users u JOIN orders o ON "),
+            Some(CompletionContext::JoinCondition {
+                left_table: Some("u".to_string()),
+                right_table: Some("o".to_string()),
+                qualifier: None,
+            })
+        );
+        assert_eq!(at_end(&TextJoinOn, "SELECT * FROM users u JOIN "), None);
+    }
+
+    #[test]
+    fn test_order_by_columns() {
+        assert_eq!(
+            at_end(&TextOrderBy, "SELECT * FROM users ORDER BY "),
+            Some(CompletionContext::OrderByClause {
+                tables: names(&["users"]),
+                qualifier: None,
+            })
+        );
+        assert_eq!(at_end(&TextOrderBy, "SELECT * FROM users WHERE "), None);
+    }
+
+    #[test]
+    fn test_group_by_columns() {
+        assert_eq!(
+            at_end(&TextGroupBy, "SELECT status FROM orders GROUP BY "),
+            Some(CompletionContext::GroupByClause {
+                tables: names(&["orders"]),
+                qualifier: None,
+            })
+        );
+        assert_eq!(at_end(&TextGroupBy, "SELECT status FROM orders "), None);
+    }
+
+    #[test]
+    fn test_limit_clause() {
+        assert_eq!(
+            at_end(&TextLimit, "SELECT * FROM users LIMIT "),
+            Some(CompletionContext::LimitClause)
+        );
+        assert_eq!(at_end(&TextLimit, "SELECT * FROM users "), None);
+    }
+
+    #[test]
+    fn test_having_clause() {
+        assert_eq!(
+            at_end(
+                &TextHaving,
+                "SELECT status FROM orders GROUP BY status HAVING "
+            ),
+            Some(CompletionContext::HavingClause {
+                tables: names(&["orders"]),
+                qualifier: None,
+            })
+        );
+        assert_eq!(at_end(&TextHaving, "SELECT status FROM orders "), None);
+    }
+
+    #[test]
+    fn test_returning_clause() {
+        assert_eq!(
+            at_end(
+                &TextReturning,
+                "INSERT INTO users (name) VALUES ('x') RETURNING "
+            ),
+            Some(CompletionContext::ReturningClause {
+                tables: names(&["users"]),
+                qualifier: None,
+            })
+        );
+        assert_eq!(at_end(&TextReturning, "INSERT INTO users "), None);
+    }
+
+    #[test]
+    fn test_ddl_keywords() {
+        assert_eq!(at_end(&TextDdl, "CREATE "), keywords(Some("CREATE")));
+        assert_eq!(at_end(&TextDdl, "ALTER"), keywords(Some("ALTER")));
+        assert_eq!(at_end(&TextDdl, "DROP "), keywords(Some("DROP")));
+        assert_eq!(at_end(&TextDdl, "CREATE TABLE "), None);
+    }
+
+    #[test]
+    fn test_statement_start_keywords() {
+        assert_eq!(at_end(&TextStatementStart, ""), keywords(None));
+        assert_eq!(at_end(&TextStatementStart, "SE"), keywords(None));
+        assert_eq!(at_end(&TextStatementStart, "SELECT id FROM "), None);
+        assert_eq!(at_end(&TextStatementStart, "SELECT 1;\n"), keywords(None));
+        assert_eq!(at_end(&TextStatementStart, "SELECT 1; up"), keywords(None));
+        assert_eq!(at_end(&TextStatementStart, "SELECT 1; UPDATE "), None);
+        assert_eq!(at_end(&TextStatementStart, "(("), None);
+    }
+
+    #[test]
+    fn test_column_definition() {
+        let definition = |column: &str, data_type: Option<&str>| {
+            Some(CompletionContext::ColumnDefinition {
+                column: column.to_string(),
+                data_type: data_type.map(String::from),
+            })
+        };
+
+        assert_eq!(
+            at_end(&TextColumnDefinition, "CREATE TABLE t (id "),
+            definition("id", None)
+        );
+        assert_eq!(
+            at_end(&TextColumnDefinition, "CREATE TABLE t (id int"),
+            definition("id", None)
+        );
+        assert_eq!(
+            at_end(
+                &TextColumnDefinition,
+                "CREATE TABLE IF NOT EXISTS t (\n  id integer NOT NULL,\n  price decimal(10, 2) "
+            ),
+            definition("price", Some("decimal"))
+        );
+        assert_eq!(
+            at_end(
+                &TextColumnDefinition,
+                "CREATE TABLE t (name text DEFAULT 'a, b' NOT "
+            ),
+            definition("name", Some("text"))
+        );
+
+        // Column names, table constraints, nested parentheses, closed lists
+        assert_eq!(
+            at_end(&TextColumnDefinition, "CREATE TABLE t (id int, "),
+            None
+        );
+        assert_eq!(
+            at_end(
+                &TextColumnDefinition,
+                "CREATE TABLE t (id int, PRIMARY KEY "
+            ),
+            None
+        );
+        assert_eq!(
+            at_end(&TextColumnDefinition, "CREATE TABLE t (name varchar("),
+            None
+        );
+        assert_eq!(
+            at_end(&TextColumnDefinition, "CREATE TABLE t (id int) "),
+            None
+        );
+        assert_eq!(
+            at_end(&TextColumnDefinition, "CREATE TABLE t AS SELECT (id "),
+            None
+        );
+        assert_eq!(
+            at_end(&TextColumnDefinition, "SELECT * FROM t WHERE id IN (1 "),
+            None
+        );
+    }
+
+    fn role_name(statement_type: &str) -> Option<CompletionContext> {
+        Some(CompletionContext::RoleName {
+            statement_type: statement_type.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_role_name_after_grant_and_revoke() {
+        assert_eq!(
+            at_end(&TextRoleName, "GRANT SELECT ON users TO "),
+            role_name("GRANT")
+        );
+        assert_eq!(
+            at_end(&TextRoleName, "GRANT admin TO alice, bo"),
+            role_name("GRANT")
+        );
+        assert_eq!(
+            at_end(&TextRoleName, "REVOKE INSERT ON orders FROM "),
+            role_name("REVOKE")
+        );
+        // Past the grantees
+        assert_eq!(
+            at_end(&TextRoleName, "GRANT SELECT ON users TO alice "),
+            Some(CompletionContext::Unknown)
+        );
+        // Before TO, and the keyword still being typed
+        assert_eq!(at_end(&TextRoleName, "GRANT SELECT ON "), None);
+        assert_eq!(at_end(&TextRoleName, "GRANT SELECT ON users TO"), None);
+    }
+
+    #[test]
+    fn test_role_name_after_set_role() {
+        assert_eq!(at_end(&TextRoleName, "SET ROLE "), role_name("SET ROLE"));
+        assert_eq!(
+            at_end(&TextRoleName, "SET LOCAL ROLE adm"),
+            role_name("SET ROLE")
+        );
+        assert_eq!(at_end(&TextRoleName, "SET search_path TO "), None);
+        assert_eq!(at_end(&TextRoleName, "SELECT * FROM "), None);
+    }
+
+    #[test]
+    fn test_join_type_after_modifier() {
+        let join_type = |modifier: &str| {
+            Some(CompletionContext::Keywords {
+                statement_type: Some(modifier.to_string()),
+                existing_clauses: vec![],
+            })
+        };
+        for modifier in JOIN_MODIFIERS {
+            assert_eq!(
+                at_end(
+                    &TextJoinModifier,
+                    &format!("SELECT * FROM users u {modifier} ")
+                ),
+                join_type(modifier)
+            );
+        }
+        // The last modifier decides, and the join keyword may be half typed
+        assert_eq!(
+            at_end(&TextJoinModifier, "SELECT * FROM a natural left jo"),
+            join_type("LEFT")
+        );
+        assert_eq!(
+            at_end(
+                &TextJoinModifier,
+                "SELECT * FROM a JOIN b ON a.id = b.id LEFT OUTER "
+            ),
+            join_type("OUTER")
+        );
+
+        // Still typing the modifier, or not between tables
+        assert_eq!(at_end(&TextJoinModifier, "SELECT * FROM users LEFT"), None);
+        assert_eq!(at_end(&TextJoinModifier, "SELECT LEFT "), None);
+        assert_eq!(
+            at_end(&TextJoinModifier, "SELECT * FROM t WHERE a AND RIGHT "),
+            None
+        );
+    }
+
+    #[test]
+    fn test_after_closed_parenthesis() {
+        let after = |closed| Some(CompletionContext::AfterParenthesis { closed });
+        let derived = after(ClosedParenthesis::DerivedTable);
+        let expression = |in_projection| after(ClosedParenthesis::Expression { in_projection });
+
+        assert_eq!(
+            at_end(
+                &TextClosedParenthesis,
+                "SELECT * FROM (SELECT id FROM users) "
+            ),
+            derived
+        );
+        assert_eq!(
+            at_end(
+                &TextClosedParenthesis,
+                "SELECT * FROM users u JOIN (SELECT user_id FROM orders) wh"
+            ),
+            derived
+        );
+        assert_eq!(
+            at_end(
+                &TextClosedParenthesis,
+                "SELECT * FROM users WHERE lower(name) "
+            ),
+            expression(false)
+        );
+        assert_eq!(
+            at_end(
+                &TextClosedParenthesis,
+                "SELECT * FROM users WHERE id IN (SELECT user_id FROM orders) a"
+            ),
+            expression(false)
+        );
+        assert_eq!(
+            at_end(&TextClosedParenthesis, "SELECT count(*) "),
+            expression(true)
+        );
+        assert_eq!(
+            at_end(
+                &TextClosedParenthesis,
+                "SELECT * FROM (SELECT coalesce(name, ')') "
+            ),
+            expression(true)
+        );
+
+        // Column lists, rows and window specifications
+        assert_eq!(
+            at_end(&TextClosedParenthesis, "INSERT INTO users (id) "),
+            None
+        );
+        assert_eq!(
+            at_end(&TextClosedParenthesis, "INSERT INTO users VALUES (1) "),
+            None
+        );
+        assert_eq!(
+            at_end(
+                &TextClosedParenthesis,
+                "SELECT row_number() OVER (ORDER BY id) "
+            ),
+            None
+        );
+        assert_eq!(
+            at_end(&TextClosedParenthesis, "SELECT DISTINCT ON (user_id) "),
+            None
+        );
+        // Not after the parenthesis
+        assert_eq!(at_end(&TextClosedParenthesis, "SELECT count(*)"), None);
+        assert_eq!(
+            at_end(&TextClosedParenthesis, "SELECT count(*) AS n "),
+            None
+        );
+        assert_eq!(at_end(&TextClosedParenthesis, "SELECT count("), None);
+    }
+
+    #[test]
+    fn test_after_closed_parenthesis_in_parsed_statement() {
+        use tree_sitter::Parser;
+        use unified_sql_grammar::{DialectVersion, language_for_dialect_with_version};
+        use unified_sql_lsp_ir::Dialect;
+
+        let lang = language_for_dialect_with_version(Dialect::MySQL, Some(DialectVersion::MySQL80))
+            .expect("Failed to get language");
+        let mut parser = Parser::new();
+        parser.set_language(lang).expect("Failed to set language");
+        let mut detect = |marked: &str| {
+            let cursor = marked.find('|').expect("case has a cursor marker");
+            let source = marked.replacen('|', "", 1);
+            let tree = parser.parse(&source, None).expect("Failed to parse");
+            assert!(!tree.root_node().has_error(), "{source} parses");
+            TextClosedParenthesis.detect(&DetectionInput::new(
+                Some(tree.root_node()),
+                &source,
+                Position::new(0, cursor as u32),
+            ))
+        };
+
+        assert_eq!(
+            detect("SELECT * FROM (SELECT id FROM users) |t"),
+            Some(CompletionContext::AfterParenthesis {
+                closed: ClosedParenthesis::DerivedTable
+            })
+        );
+        assert_eq!(
+            detect("SELECT lower(name) |FROM users"),
+            Some(CompletionContext::AfterParenthesis {
+                closed: ClosedParenthesis::Expression {
+                    in_projection: true
+                }
+            })
+        );
+        assert_eq!(
+            detect("SELECT * FROM users WHERE (id > 1) |AND active = 1"),
+            Some(CompletionContext::AfterParenthesis {
+                closed: ClosedParenthesis::Expression {
+                    in_projection: false
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_after_closed_parenthesis_in_broken_statement() {
+        // Text before and after the statement doesn't parse
+        assert_eq!(
+            at_end(
+                &TextClosedParenthesis,
+                "SELECT * FROM users WHERE upper(name) = 'A' AND lower(name) AN"
+            ),
+            Some(CompletionContext::AfterParenthesis {
+                closed: ClosedParenthesis::Expression {
+                    in_projection: false
+                }
+            })
+        );
+        assert_eq!(
+            at_end(
+                &TextClosedParenthesis,
+                "SELECT * FROM (SELECT id, FROM users WHERE) "
+            ),
+            Some(CompletionContext::AfterParenthesis {
+                closed: ClosedParenthesis::DerivedTable
+            })
+        );
+    }
+
+    #[test]
+    fn test_sequence_name_argument() {
+        let sequence = |in_string| Some(CompletionContext::SequenceName { in_string });
+        assert_eq!(
+            at_end(&TextSequenceName, "SELECT nextval("),
+            sequence(false)
+        );
+        assert_eq!(
+            at_end(&TextSequenceName, "SELECT NEXTVAL(us"),
+            sequence(false)
+        );
+        assert_eq!(
+            at_end(&TextSequenceName, "SELECT setval('users_"),
+            sequence(true)
+        );
+        assert_eq!(
+            at_end(&TextSequenceName, "SELECT currval( '"),
+            sequence(true)
+        );
+        // Later arguments and other functions
+        assert_eq!(
+            at_end(&TextSequenceName, "SELECT setval('users_id_seq', "),
+            None
+        );
+        assert_eq!(at_end(&TextSequenceName, "SELECT lower('"), None);
+        assert_eq!(at_end(&TextSequenceName, "SELECT nextval('a') + "), None);
+    }
+
+    #[test]
+    fn test_insert_column_list() {
+        let insert = |table: &str, listed: &[&str]| {
+            Some(CompletionContext::InsertColumnList {
+                table: table.to_string(),
+                listed: listed.iter().map(|c| c.to_string()).collect(),
+            })
+        };
+        assert_eq!(
+            at_end(&TextInsertColumns, "INSERT INTO users ("),
+            insert("users", &[])
+        );
+        assert_eq!(
+            at_end(&TextInsertColumns, "INSERT INTO app.users (name, em"),
+            insert("users", &["name"])
+        );
+        assert_eq!(
+            at_end(&TextInsertColumns, "INSERT orders(id, `status`, "),
+            insert("orders", &["id", "status"])
+        );
+        // VALUES lists and subqueries
+        assert_eq!(
+            at_end(&TextInsertColumns, "INSERT INTO users (id) VALUES ("),
+            None
+        );
+        assert_eq!(
+            at_end(&TextInsertColumns, "INSERT INTO users (SELECT "),
+            None
+        );
+        assert_eq!(at_end(&TextInsertColumns, "SELECT count("), None);
+    }
+
+    #[test]
+    fn test_values_row() {
+        let row = |column: usize, column_name: Option<&str>, first_row_value: Option<&str>| {
+            Some(CompletionContext::ValuesRow {
+                column,
+                column_name: column_name.map(String::from),
+                first_row_value: first_row_value.map(String::from),
+            })
+        };
+        let cte = "WITH data(id, name) AS (VALUES (1, 'a, b'), ";
+
+        assert_eq!(at_end(&TextValuesRow, "VALUES ("), row(0, None, None));
+        assert_eq!(
+            at_end(&TextValuesRow, &format!("{cte}(")),
+            row(0, Some("id"), Some("1"))
+        );
+        assert_eq!(
+            at_end(&TextValuesRow, &format!("{cte}(2, ")),
+            row(1, Some("name"), Some("'a, b'"))
+        );
+        assert_eq!(
+            at_end(
+                &TextValuesRow,
+                "SELECT * FROM (VALUES (1, DATE '2024-01-01'), (2, "
+            ),
+            row(1, None, Some("DATE '2024-01-01'"))
+        );
+
+        // After a closed row
+        let keywords = Some(CompletionContext::Keywords {
+            statement_type: Some("VALUES".to_string()),
+            existing_clauses: vec![],
+        });
+        assert_eq!(at_end(&TextValuesRow, "VALUES (1, 'a') "), keywords);
+        assert_eq!(
+            at_end(&TextValuesRow, &format!("{cte}(2, 'c') OR")),
+            keywords
+        );
+
+        // INSERT and MERGE rows, nested calls and closed CTE bodies
+        assert_eq!(at_end(&TextValuesRow, "INSERT INTO users VALUES ("), None);
+        assert_eq!(
+            at_end(&TextValuesRow, "INSERT INTO users (id) VALUES ("),
+            None
+        );
+        assert_eq!(at_end(&TextValuesRow, "VALUES (1, lower("), None);
+        assert_eq!(at_end(&TextValuesRow, "VALUES (1), "), None);
+        assert_eq!(
+            at_end(&TextValuesRow, "WITH d AS (VALUES (1)) SELECT "),
+            None
+        );
+    }
+
+    #[test]
+    fn test_completed_operand() {
+        let operand = |source: &str| {
+            let column = source.chars().count() as u32;
+            detect_completed_operand(source, Position::new(0, column))
+        };
+
+        assert_eq!(
+            operand("SELECT * FROM users u WHERE u.name "),
+            Some(CompletedOperand {
+                qualifier: Some("u".to_string()),
+                column: "name".to_string(),
+                start: Position::new(0, 28),
+            })
+        );
+        assert_eq!(
+            operand("SELECT email IL").map(|o| o.column),
+            Some("email".to_string())
+        );
+        // An operand is still expected
+        assert_eq!(operand("SELECT * FROM users WHERE "), None);
+        assert_eq!(operand("SELECT * FROM users WHERE a = 1 AND "), None);
+        assert_eq!(operand("SELECT * FROM users WHERE na"), None);
+        assert_eq!(operand("SELECT * FROM users WHERE name = 'a "), None);
+        assert_eq!(operand("SELECT * FROM users LIMIT 10 "), None);
+    }
+
+    #[test]
+    fn test_projection_aliases() {
+        let aliases = |source: &str| {
+            let column = source.chars().count() as u32;
+            projection_aliases(source, Position::new(0, column))
+        };
+
+        assert_eq!(
+            aliases("SELECT count(*) AS \"Total Rows\", status s FROM t ORDER BY "),
+            vec!["\"Total Rows\"".to_string(), "s".to_string()]
+        );
+        assert_eq!(
+            aliases("SELECT a, sum(b) AS `Total Rows`, c.d, e + f FROM t GROUP BY a HAVING "),
+            vec!["`Total Rows`".to_string()]
+        );
+        // Delimiters, commas and dots inside a quoted alias stay verbatim
+        assert_eq!(
+            aliases("SELECT x \"a, \"\"b\"\".c\" FROM t ORDER BY "),
+            vec!["\"a, \"\"b\"\".c\"".to_string()]
+        );
+        // Operands and keywords aren't aliases
+        assert!(
+            aliases("SELECT DISTINCT a, b IS NULL, CASE WHEN c THEN 1 END FROM t ORDER BY ")
+                .is_empty()
+        );
+        // The aliases of the query holding the cursor, not of the outer one
+        assert_eq!(
+            aliases("SELECT a AS outer_a FROM (SELECT b AS inner_b FROM t ORDER BY "),
+            vec!["inner_b".to_string()]
+        );
+        assert_eq!(
+            aliases("SELECT a AS x FROM t ORDER BY coalesce("),
+            vec!["x".to_string()]
+        );
+        assert!(aliases("SELECT 1; UPDATE t SET ").is_empty());
+    }
+
+    #[test]
+    fn test_having_after_group_by() {
+        let context = |source: &str| {
+            let column = source.chars().count() as u32;
+            detect_from_text(&DetectionInput::new(None, source, Position::new(0, column)))
+        };
+
+        assert_eq!(
+            context("SELECT a FROM t GROUP BY a HAVING `Total Rows`."),
+            CompletionContext::HavingClause {
+                tables: names(&["t"]),
+                qualifier: Some("`Total Rows`".to_string()),
+            }
+        );
+        assert_eq!(
+            context("SELECT a FROM t GROUP BY a HAVING ").kind(),
+            "HavingClause"
+        );
+        assert_eq!(context("SELECT a FROM t GROUP BY ").kind(), "GroupByClause");
+    }
+
+    #[test]
+    fn test_scanners_skip_quoted_text_and_comments() {
+        assert_eq!(innermost_open_paren("f(a, ')', \"b,c\" "), Some((1, 2)));
+        assert_eq!(innermost_open_paren("f(a /* ) */, "), Some((1, 1)));
+        assert_eq!(innermost_open_paren("f($$ ( $$)"), None);
+
+        assert_eq!(open_string_literal_start("SELECT 'it''s"), Some(7));
+        assert_eq!(open_string_literal_start("SELECT 'a' -- '"), None);
+        assert_eq!(open_string_literal_start("SELECT \"'\" "), None);
+
+        assert_eq!(
+            select_list("SELECT ')', \"FROM\" FROM t"),
+            Some(" ')', \"FROM\" ")
+        );
+        assert_eq!(
+            split_top_level("a, 'b,c', f(d, e)"),
+            vec!["a", " 'b,c'", " f(d, e)"]
+        );
+    }
+}
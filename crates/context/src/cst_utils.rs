@@ -10,11 +10,13 @@
 
 use tree_sitter::{Node, TreeCursor};
 
+use crate::tokens::lex;
+
 /// Position in a document (line, character)
 ///
 /// This mirrors tower_lsp::lsp_types::Position but is defined here
 /// to avoid the dependency on tower_lsp in the context crate.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Position {
     /// Line position in a document (zero-based)
     pub line: u32,
@@ -94,11 +96,14 @@ pub fn find_node_at_position<'a>(
 /// # Arguments
 ///
 /// * `source` - Source code text
-/// * `position` - LSP position (line, character)
+/// * `position` - LSP position (line, character in chars)
 ///
 /// # Returns
 ///
-/// Byte offset in the source string
+/// Byte offset in the source string, always a char boundary. A character
+/// past the end of its line clamps to the line end (before a `\r\n`), and
+/// a line past the end of the source to `source.len()`, which is the valid
+/// position after the last character.
 ///
 /// # Examples
 ///
@@ -108,20 +113,25 @@ pub fn find_node_at_position<'a>(
 /// let source = "SELECT *\nFROM users";
 /// let offset = position_to_byte_offset(source, Position::new(1, 0));
 /// assert_eq!(offset, 9); // After "SELECT *\n" (8 + 1 for newline)
+/// assert_eq!(position_to_byte_offset(source, Position::new(1, 10)), source.len());
 /// ```
 pub fn position_to_byte_offset(source: &str, position: Position) -> usize {
-    let lines: Vec<&str> = source.lines().collect();
-    let mut byte_offset = 0;
-
-    for (i, line) in lines.iter().enumerate() {
-        if i == position.line as usize {
-            byte_offset += position.character as usize;
-            break;
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match source[line_start..].find('\n') {
+            Some(newline) => line_start += newline + 1,
+            None => return source.len(),
         }
-        byte_offset += line.len() + 1; // +1 for newline character
     }
 
-    byte_offset
+    let line = &source[line_start..];
+    let line = &line[..line.find('\n').unwrap_or(line.len())];
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    line_start
+        + line
+            .char_indices()
+            .nth(position.character as usize)
+            .map_or(line.len(), |(offset, _)| offset)
 }
 
 /// Convert byte offset to LSP Position (UTF-8 aware)
@@ -173,6 +183,32 @@ pub fn byte_to_position(byte_offset: usize, source: &str) -> Position {
     Position::new(line as u32, char_in_line as u32)
 }
 
+/// Byte range of the `;`-separated statement containing `byte_offset`
+///
+/// The range excludes the semicolons; `byte_offset` is clamped to the
+/// source length. Semicolons in strings, quoted identifiers, comments and
+/// dollar-quoted bodies don't separate statements.
+///
+/// # Examples
+///
+/// ```
+/// use unified_sql_lsp_context::cst_utils::statement_range;
+///
+/// let source = "SELECT 1; DELETE FROM users";
+/// assert_eq!(&source[statement_range(source, 12)], " DELETE FROM users");
+/// ```
+pub fn statement_range(source: &str, byte_offset: usize) -> std::ops::Range<usize> {
+    let byte_offset = byte_offset.min(source.len());
+    let mut start = 0;
+    for semicolon in lex(source).filter(|token| token.is_symbol(';')) {
+        if semicolon.offset >= byte_offset {
+            return start..semicolon.offset;
+        }
+        start = semicolon.end();
+    }
+    start..source.len()
+}
+
 /// Convert a tree-sitter node to LSP Range
 ///
 /// # Arguments
@@ -267,6 +303,26 @@ impl<'a> Iterator for ChildIter<'a> {
     }
 }
 
+/// Visit `root` and its descendants in document order, parents first
+///
+/// `visit` returns whether to visit the node's children too. The walk moves
+/// a tree cursor instead of recursing, so queries nested hundreds of levels
+/// deep can't overflow the stack.
+pub fn walk_tree<'a>(root: &Node<'a>, mut visit: impl FnMut(Node<'a>) -> bool) {
+    let mut cursor = root.walk();
+    loop {
+        if visit(cursor.node()) && cursor.goto_first_child() {
+            continue;
+        }
+        // The cursor can't leave `root`, so climbing back to it ends the walk
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return;
+            }
+        }
+    }
+}
+
 /// Extension trait for tree-sitter Node to provide more convenient child traversal
 ///
 /// This trait eliminates the ugly `node.children(&mut node.walk())` pattern.
@@ -356,6 +412,34 @@ pub fn extract_alias(node: &Node, source: &str) -> Option<String> {
     None
 }
 
+/// Plain column references already in the projection of the SELECT at `position`
+///
+/// Returns `(column, qualifier)` pairs as written. Columns used inside an
+/// expression (e.g. a function argument) aren't projected themselves and are
+/// left out. Returns `None` if no SELECT encloses the position or its
+/// projection has parse errors.
+pub fn projection_column_references(
+    root: &Node,
+    source: &str,
+    position: Position,
+) -> Option<Vec<(String, Option<String>)>> {
+    let offset = position_to_byte_offset(source, position);
+    let node = root.descendant_for_byte_range(offset.saturating_sub(1), offset)?;
+    let select = find_parent_select(&node)?;
+    let projection = find_select_clause(&select)?;
+    if projection.has_error() {
+        return None;
+    }
+
+    Some(
+        projection
+            .iter_children()
+            .filter(|child| child.kind() == "column_reference")
+            .filter_map(|child| extract_column_info(&child, source))
+            .collect(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -375,6 +459,52 @@ mod tests {
         assert_eq!(offset, 6);
     }
 
+    #[test]
+    fn test_position_to_byte_offset_at_boundaries() {
+        // End of the last line without a trailing newline
+        let source = "SELECT u.";
+        assert_eq!(position_to_byte_offset(source, Position::new(0, 9)), 9);
+        assert_eq!(position_to_byte_offset(source, Position::new(0, 20)), 9);
+        assert_eq!(position_to_byte_offset(source, Position::new(3, 0)), 9);
+
+        // Empty line after a trailing newline
+        let source = "SELECT 1\n";
+        assert_eq!(position_to_byte_offset(source, Position::new(1, 0)), 9);
+
+        // Characters, not bytes, and CRLF line ends
+        let source = "SELECT 'é'\r\nFROM ü";
+        assert_eq!(position_to_byte_offset(source, Position::new(0, 10)), 11);
+        assert_eq!(position_to_byte_offset(source, Position::new(0, 11)), 11);
+        assert_eq!(position_to_byte_offset(source, Position::new(1, 5)), 18);
+        assert_eq!(
+            position_to_byte_offset(source, Position::new(1, 6)),
+            source.len()
+        );
+    }
+
+    #[test]
+    fn test_statement_range_skips_quoted_semicolons() {
+        let source = "SELECT ';' FROM t; SELECT 2";
+        assert_eq!(&source[statement_range(source, 3)], "SELECT ';' FROM t");
+        assert_eq!(&source[statement_range(source, 8)], "SELECT ';' FROM t");
+        assert_eq!(&source[statement_range(source, 20)], " SELECT 2");
+
+        let source = "SELECT 1 -- a;b
+FROM t; SELECT \"x;y\"";
+        assert_eq!(
+            &source[statement_range(source, 0)],
+            "SELECT 1 -- a;b\nFROM t"
+        );
+        assert_eq!(&source[statement_range(source, 26)], " SELECT \"x;y\"");
+
+        let source = "CREATE FUNCTION f() AS $$ SELECT 1; $$; SELECT 'it''s;'";
+        assert_eq!(
+            &source[statement_range(source, 30)],
+            "CREATE FUNCTION f() AS $$ SELECT 1; $$"
+        );
+        assert_eq!(&source[statement_range(source, 45)], " SELECT 'it''s;'");
+    }
+
     #[test]
     fn test_byte_to_position_multiline() {
         let source = "SELECT id\nFROM users\nWHERE id = 1";
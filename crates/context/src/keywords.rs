@@ -8,7 +8,10 @@
 //! This module defines SQL keywords organized by context and dialect.
 
 use std::collections::HashSet;
-use unified_sql_lsp_ir::Dialect;
+use unified_sql_lsp_ir::dialect::DialectFamily;
+use unified_sql_lsp_ir::{Dialect, DialectExtensions};
+
+use crate::completion::ClosedParenthesis;
 
 /// SQL keyword with metadata
 #[derive(Debug, Clone, PartialEq)]
@@ -19,6 +22,11 @@ pub struct SqlKeyword {
     pub description: Option<String>,
     /// Sort order (lower = higher priority)
     pub sort_priority: i32,
+    /// Newer dialect version the keyword needs, e.g. `MySQL ≥ 8.0`
+    ///
+    /// Only set on keywords offered although the configured version lacks
+    /// them, see [`KeywordProvider::with_unsupported`].
+    pub requires: Option<String>,
 }
 
 impl SqlKeyword {
@@ -28,6 +36,7 @@ impl SqlKeyword {
             label: label.to_uppercase(),
             description: description.map(|d| d.to_string()),
             sort_priority,
+            requires: None,
         }
     }
 
@@ -65,21 +74,173 @@ impl KeywordSet {
     }
 }
 
+/// Skeleton of a whole statement, offered at the start of a statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementTemplate {
+    /// Label shown in the completion list, e.g. `SELECT … FROM …`
+    pub label: String,
+    /// Leading keyword of the statement
+    pub keyword: String,
+    /// Body in LSP snippet syntax, keywords in uppercase
+    pub snippet: String,
+    pub description: String,
+    /// Sort order, that of the statement keyword
+    pub sort_priority: i32,
+    /// Whether the statement modifies table rows
+    pub writes: bool,
+}
+
+/// A statement template and whether the dialect version has the statement
+struct TemplateSpec {
+    label: &'static str,
+    snippet: &'static str,
+    description: &'static str,
+    sort_priority: i32,
+    writes: bool,
+    available: Gate,
+}
+
+const STATEMENT_TEMPLATES: &[TemplateSpec] = &[
+    TemplateSpec {
+        label: "SELECT … FROM …",
+        snippet: "SELECT ${1:*} FROM ${2:table}$0",
+        description: "Query rows of a table",
+        sort_priority: 1,
+        writes: false,
+        available: |_| true,
+    },
+    TemplateSpec {
+        label: "INSERT INTO … VALUES (…)",
+        snippet: "INSERT INTO ${1:table} (${2:columns}) VALUES (${3:values})$0",
+        description: "Insert a row into a table",
+        sort_priority: 2,
+        writes: true,
+        available: |_| true,
+    },
+    TemplateSpec {
+        label: "UPDATE … SET … WHERE …",
+        snippet: "UPDATE ${1:table} SET ${2:column} = ${3:value} WHERE ${4:condition}$0",
+        description: "Modify the rows matching a condition",
+        sort_priority: 3,
+        writes: true,
+        available: |_| true,
+    },
+    TemplateSpec {
+        label: "DELETE FROM … WHERE …",
+        snippet: "DELETE FROM ${1:table} WHERE ${2:condition}$0",
+        description: "Delete the rows matching a condition",
+        sort_priority: 4,
+        writes: true,
+        available: |_| true,
+    },
+    TemplateSpec {
+        label: "CREATE TABLE … ( … )",
+        snippet: "CREATE TABLE ${1:name} (\n\t${2:id} ${3:integer} PRIMARY KEY$0\n)",
+        description: "Create a table",
+        sort_priority: 5,
+        writes: false,
+        available: |_| true,
+    },
+    TemplateSpec {
+        label: "MERGE INTO … USING …",
+        snippet: "MERGE INTO ${1:target} USING ${2:source} ON ${3:condition}\nWHEN MATCHED THEN UPDATE SET ${4:column} = ${5:value}\nWHEN NOT MATCHED THEN INSERT (${6:columns}) VALUES (${7:values})$0",
+        description: "Insert, update or delete rows from a source table",
+        sort_priority: 10,
+        writes: true,
+        available: |capabilities| capabilities.merge,
+    },
+];
+
+/// Version-dependent features of the configured dialect
+///
+/// The grammar for a dialect family may accept syntax that only newer
+/// server versions run; these flags keep such keywords out of completion
+/// for older versions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DialectCapabilities {
+    /// MERGE statement (PostgreSQL 15+)
+    pub merge: bool,
+    /// FULL [OUTER] JOIN (PostgreSQL family; MySQL has none)
+    pub full_outer_join: bool,
+    /// GENERATED ... AS IDENTITY columns (PostgreSQL 10+)
+    pub identity_columns: bool,
+    /// WITH RECURSIVE (PostgreSQL family, MySQL 8.0+, TiDB 5.1+)
+    pub recursive_cte: bool,
+    /// Window functions and the WINDOW clause (MySQL 8.0+, everywhere else)
+    pub window_functions: bool,
+}
+
+impl DialectCapabilities {
+    /// Features every version of `dialect` supports
+    pub fn for_dialect(dialect: Dialect) -> Self {
+        Self {
+            merge: false,
+            full_outer_join: dialect.supports(DialectExtensions::FullOuterJoin),
+            identity_columns: dialect.supports(DialectExtensions::IdentityColumns),
+            recursive_cte: dialect.family() == DialectFamily::PostgreSQL,
+            window_functions: dialect != Dialect::MySQL,
+        }
+    }
+}
+
+/// Whether a set of version-dependent features has a feature
+type Gate = fn(&DialectCapabilities) -> bool;
+
 /// Keyword provider for different SQL contexts
 pub struct KeywordProvider {
     /// SQL dialect
     dialect: Dialect,
+    /// Version-dependent features
+    capabilities: DialectCapabilities,
+    /// Newer versions of the dialect whose keywords are offered anyway
+    newer: Vec<(String, DialectCapabilities)>,
 }
 
 impl KeywordProvider {
     /// Create a new keyword provider
     pub fn new(dialect: Dialect) -> Self {
-        Self { dialect }
+        Self {
+            dialect,
+            capabilities: DialectCapabilities::for_dialect(dialect),
+            newer: Vec::new(),
+        }
+    }
+
+    /// Set the version-dependent features to offer
+    pub fn with_capabilities(mut self, capabilities: DialectCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Also offer the keywords of features only newer versions have
+    ///
+    /// `newer` lists those versions oldest first, each with the label its
+    /// keywords carry in [`SqlKeyword::requires`] and its features. Keywords
+    /// no version of the dialect has stay hidden.
+    pub fn with_unsupported(mut self, newer: Vec<(String, DialectCapabilities)>) -> Self {
+        self.newer = newer;
+        self
+    }
+
+    /// `keyword` if the configured version has its feature, or marked with
+    /// the first newer version that does
+    fn gated(&self, available: Gate, keyword: SqlKeyword) -> Option<SqlKeyword> {
+        if available(&self.capabilities) {
+            return Some(keyword);
+        }
+        let (version, _) = self
+            .newer
+            .iter()
+            .find(|(_, capabilities)| available(capabilities))?;
+        Some(SqlKeyword {
+            requires: Some(version.clone()),
+            ..keyword
+        })
     }
 
     /// Get statement keywords (for start of statement)
     pub fn statement_keywords(&self) -> KeywordSet {
-        let keywords = vec![
+        let mut keywords = vec![
             SqlKeyword::new("SELECT", Some("Retrieve data from tables"), 1),
             SqlKeyword::new("INSERT", Some("Insert new rows into a table"), 2),
             SqlKeyword::new("UPDATE", Some("Modify existing rows in a table"), 3),
@@ -91,6 +252,82 @@ impl KeywordProvider {
             SqlKeyword::new("WITH", Some("Common Table Expression (CTE)"), 9),
         ];
 
+        keywords.extend(self.gated(
+            |capabilities| capabilities.merge,
+            SqlKeyword::new(
+                "MERGE",
+                Some("Insert, update or delete rows from a source table"),
+                10,
+            ),
+        ));
+
+        KeywordSet::new(keywords)
+    }
+
+    /// Statement templates for the start of a statement
+    ///
+    /// Gated by version like [`Self::statement_keywords`]: MERGE only when
+    /// the dialect version has it.
+    pub fn statement_templates(&self) -> Vec<StatementTemplate> {
+        STATEMENT_TEMPLATES
+            .iter()
+            .filter(|spec| (spec.available)(&self.capabilities))
+            .map(|spec| StatementTemplate {
+                label: spec.label.to_string(),
+                keyword: spec.label.split(' ').next().unwrap_or_default().to_string(),
+                snippet: spec.snippet.to_string(),
+                description: spec.description.to_string(),
+                sort_priority: spec.sort_priority,
+                writes: spec.writes,
+            })
+            .collect()
+    }
+
+    /// Column constraints and attributes, after the type in CREATE TABLE
+    ///
+    /// MySQL gets `AUTO_INCREMENT`, PostgreSQL versions with identity
+    /// columns `GENERATED {ALWAYS|BY DEFAULT} AS IDENTITY`.
+    pub fn column_constraint_keywords(&self) -> KeywordSet {
+        let mut keywords = vec![
+            SqlKeyword::new("NOT NULL", Some("Column may not contain NULL"), 1),
+            SqlKeyword::new("NULL", Some("Column may contain NULL"), 2),
+            SqlKeyword::new("DEFAULT", Some("Value used when none is given"), 3),
+            SqlKeyword::new("PRIMARY KEY", Some("Column identifies the row"), 4),
+            SqlKeyword::new("UNIQUE", Some("Values must be distinct"), 5),
+            SqlKeyword::new("REFERENCES", Some("Foreign key to another table"), 6),
+            SqlKeyword::new("CHECK", Some("Values must satisfy a condition"), 7),
+            SqlKeyword::new("COLLATE", Some("Collation of the column"), 9),
+        ];
+
+        if self.dialect.supports(DialectExtensions::AutoIncrement) {
+            keywords.push(SqlKeyword::new(
+                "AUTO_INCREMENT",
+                Some("Generate the next integer for each new row"),
+                8,
+            ));
+            keywords.push(SqlKeyword::new("COMMENT", Some("Column comment"), 10));
+        }
+        keywords.extend(
+            [
+                SqlKeyword::new(
+                    "GENERATED ALWAYS AS IDENTITY",
+                    Some(
+                        "Generate the next integer for each new row; explicit values are rejected",
+                    ),
+                    8,
+                ),
+                SqlKeyword::new(
+                    "GENERATED BY DEFAULT AS IDENTITY",
+                    Some("Generate the next integer for rows inserted without a value"),
+                    8,
+                ),
+            ]
+            .into_iter()
+            .filter_map(|keyword| {
+                self.gated(|capabilities| capabilities.identity_columns, keyword)
+            }),
+        );
+
         KeywordSet::new(keywords)
     }
 
@@ -109,7 +346,6 @@ impl KeywordProvider {
             SqlKeyword::new("INNER JOIN", Some("Inner join with another table"), 10),
             SqlKeyword::new("LEFT JOIN", Some("Left outer join"), 11),
             SqlKeyword::new("RIGHT JOIN", Some("Right outer join"), 12),
-            SqlKeyword::new("FULL JOIN", Some("Full outer join"), 13),
             SqlKeyword::new("CROSS JOIN", Some("Cross join"), 14),
             SqlKeyword::new("STRAIGHT_JOIN", Some("Straight join (MySQL)"), 15),
             SqlKeyword::new("UNION", Some("Combine result sets"), 16),
@@ -123,6 +359,25 @@ impl KeywordProvider {
         ];
 
         // Add dialect-specific keywords
+        keywords.extend(
+            [
+                SqlKeyword::new("FULL JOIN", Some("Full outer join"), 13),
+                SqlKeyword::new("FULL OUTER JOIN", Some("Full outer join"), 13),
+            ]
+            .into_iter()
+            .filter_map(|keyword| self.gated(|capabilities| capabilities.full_outer_join, keyword)),
+        );
+        keywords.extend(self.gated(
+            |capabilities| capabilities.window_functions,
+            SqlKeyword::new("WINDOW", Some("Define named windows"), 26),
+        ));
+        if self.dialect.supports(DialectExtensions::DistinctOn) {
+            keywords.push(SqlKeyword::new(
+                "DISTINCT ON",
+                Some("Keep the first row of each group"),
+                20,
+            ));
+        }
         if self.dialect == Dialect::PostgreSQL {
             keywords.push(SqlKeyword::new("FETCH", Some("Fetch specific rows"), 24));
             keywords.push(SqlKeyword::new(
@@ -148,15 +403,65 @@ impl KeywordProvider {
 
     /// Get JOIN type keywords
     pub fn join_type_keywords(&self) -> KeywordSet {
-        let keywords = vec![
+        let mut keywords = vec![
             SqlKeyword::new("INNER", Some("Inner join"), 1),
             SqlKeyword::new("LEFT", Some("Left outer join"), 2),
             SqlKeyword::new("RIGHT", Some("Right outer join"), 3),
-            SqlKeyword::new("FULL", Some("Full outer join"), 4),
             SqlKeyword::new("CROSS", Some("Cross join"), 5),
             SqlKeyword::new("NATURAL", Some("Natural join"), 6),
             SqlKeyword::new("LATERAL", Some("Lateral join"), 7),
         ];
+        keywords.extend(self.gated(
+            |capabilities| capabilities.full_outer_join,
+            SqlKeyword::new("FULL", Some("Full outer join"), 4),
+        ));
+
+        KeywordSet::new(keywords)
+    }
+
+    /// Keywords completing a join type after `modifier`, e.g. `JOIN` and
+    /// `OUTER JOIN` after `LEFT`
+    ///
+    /// Empty for `FULL` without FULL OUTER JOIN support.
+    pub fn join_continuation_keywords(&self, modifier: &str) -> KeywordSet {
+        let full = |keyword| self.gated(|capabilities| capabilities.full_outer_join, keyword);
+        let keywords = match modifier {
+            "LEFT" | "RIGHT" => vec![
+                SqlKeyword::new("JOIN", Some("Outer join"), 1),
+                SqlKeyword::new("OUTER JOIN", Some("Outer join"), 2),
+            ],
+            "FULL" => [
+                SqlKeyword::new("JOIN", Some("Full outer join"), 1),
+                SqlKeyword::new("OUTER JOIN", Some("Full outer join"), 2),
+            ]
+            .into_iter()
+            .filter_map(full)
+            .collect(),
+            "NATURAL" => {
+                let mut keywords = vec![
+                    SqlKeyword::new("JOIN", Some("Join on the common columns"), 1),
+                    SqlKeyword::new(
+                        "LEFT JOIN",
+                        Some("Left outer join on the common columns"),
+                        2,
+                    ),
+                    SqlKeyword::new(
+                        "RIGHT JOIN",
+                        Some("Right outer join on the common columns"),
+                        3,
+                    ),
+                    SqlKeyword::new("INNER JOIN", Some("Inner join on the common columns"), 5),
+                ];
+                keywords.extend(full(SqlKeyword::new(
+                    "FULL JOIN",
+                    Some("Full outer join on the common columns"),
+                    4,
+                )));
+                keywords
+            }
+            "INNER" | "CROSS" | "OUTER" => vec![SqlKeyword::new("JOIN", Some("Join"), 1)],
+            _ => Vec::new(),
+        };
 
         KeywordSet::new(keywords)
     }
@@ -193,6 +498,36 @@ impl KeywordProvider {
         KeywordSet::new(keywords)
     }
 
+    /// Keywords after the closing parenthesis of a subquery or function call
+    ///
+    /// A derived table is followed by its alias, a join or the next clause;
+    /// an expression by a predicate or a logical operator, and in the SELECT
+    /// list by an alias or FROM.
+    pub fn after_parenthesis_keywords(&self, closed: ClosedParenthesis) -> KeywordSet {
+        match closed {
+            ClosedParenthesis::DerivedTable => {
+                let not_after_table = HashSet::from(
+                    ["CASE", "FROM", "DISTINCT", "DISTINCT ON", "ALL", "INTO"].map(String::from),
+                );
+                KeywordSet::new(self.select_clause_keywords().exclude(&not_after_table))
+            }
+            ClosedParenthesis::Expression { in_projection } => {
+                let mut keywords: Vec<SqlKeyword> = self
+                    .expression_keywords()
+                    .keywords
+                    .into_iter()
+                    .take_while(|keyword| keyword.label != "CASE")
+                    .filter(|keyword| keyword.label != "EXISTS")
+                    .collect();
+                if in_projection {
+                    keywords.push(SqlKeyword::new("AS", Some("Alias for the column"), 13));
+                    keywords.push(SqlKeyword::new("FROM", Some("Specify tables to query"), 14));
+                }
+                KeywordSet::new(keywords)
+            }
+        }
+    }
+
     /// Get CREATE statement keywords
     pub fn create_keywords(&self) -> KeywordSet {
         let keywords = vec![
@@ -291,6 +626,51 @@ impl KeywordProvider {
         KeywordSet::new(keywords)
     }
 
+    /// Get keywords after a row of a standalone VALUES list
+    pub fn values_keywords(&self) -> KeywordSet {
+        let keywords = vec![
+            SqlKeyword::new(",", Some("Add another row"), 1),
+            SqlKeyword::new("ORDER BY", Some("Sort the rows"), 2),
+            SqlKeyword::new("LIMIT", Some("Limit the number of rows"), 3),
+            SqlKeyword::new("OFFSET", Some("Skip rows"), 4),
+            SqlKeyword::new("UNION", Some("Combine with another query"), 5),
+            SqlKeyword::new("UNION ALL", Some("Combine, keeping duplicates"), 6),
+            SqlKeyword::new("INTERSECT", Some("Rows in both queries"), 7),
+            SqlKeyword::new("EXCEPT", Some("Rows not in the other query"), 8),
+        ];
+
+        KeywordSet::new(keywords)
+    }
+
+    /// Get MERGE statement clause keywords
+    pub fn merge_keywords(&self) -> KeywordSet {
+        let keywords = vec![
+            SqlKeyword::new("INTO", Some("Target table to merge into"), 1),
+            SqlKeyword::new("USING", Some("Source table or query"), 2),
+            SqlKeyword::new("ON", Some("Join condition between target and source"), 3),
+            SqlKeyword::new("WHEN MATCHED THEN", Some("Action for matched rows"), 4),
+            SqlKeyword::new(
+                "WHEN NOT MATCHED THEN",
+                Some("Action for source rows without a match"),
+                5,
+            ),
+        ];
+
+        KeywordSet::new(keywords)
+    }
+
+    /// Get MERGE WHEN clause action keywords
+    pub fn merge_action_keywords(&self) -> KeywordSet {
+        let keywords = vec![
+            SqlKeyword::new("UPDATE SET", Some("Update the matched target row"), 1),
+            SqlKeyword::new("DELETE", Some("Delete the matched target row"), 2),
+            SqlKeyword::new("INSERT", Some("Insert the source row"), 3),
+            SqlKeyword::new("DO NOTHING", Some("Skip the row"), 4),
+        ];
+
+        KeywordSet::new(keywords)
+    }
+
     /// Get keywords available after a specific clause
     pub fn keywords_after_clause(&self, clause: &str) -> Vec<SqlKeyword> {
         match clause {
@@ -356,6 +736,7 @@ impl KeywordProvider {
         set.insert("LEFT JOIN".to_string());
         set.insert("RIGHT JOIN".to_string());
         set.insert("FULL JOIN".to_string());
+        set.insert("FULL OUTER JOIN".to_string());
         set.insert("CROSS JOIN".to_string());
         set.insert("STRAIGHT_JOIN".to_string());
         set.insert("UNION".to_string());
@@ -372,13 +753,34 @@ impl KeywordProvider {
         set
     }
 
-    /// Get sort direction keywords (ASC, DESC)
+    /// Get the keywords that may follow a sort key
+    ///
+    /// ASC, DESC and COLLATE everywhere; NULLS FIRST / NULLS LAST only for
+    /// the PostgreSQL family, as MySQL has no NULL ordering clause.
     pub fn sort_direction_keywords(&self) -> KeywordSet {
-        let keywords = vec![
+        let mut keywords = vec![
             SqlKeyword::new("ASC", Some("Ascending order"), 1),
             SqlKeyword::new("DESC", Some("Descending order"), 2),
         ];
 
+        if self.dialect.family() == DialectFamily::PostgreSQL {
+            keywords.push(SqlKeyword::new(
+                "NULLS FIRST",
+                Some("Sort NULL values before all others"),
+                3,
+            ));
+            keywords.push(SqlKeyword::new(
+                "NULLS LAST",
+                Some("Sort NULL values after all others"),
+                4,
+            ));
+        }
+        keywords.push(SqlKeyword::new(
+            "COLLATE",
+            Some("Compare with a specific collation"),
+            5,
+        ));
+
         KeywordSet::new(keywords)
     }
 
@@ -497,6 +899,248 @@ mod tests {
         assert!(select_keywords.keywords.iter().any(|k| k.label == "FETCH"));
     }
 
+    #[test]
+    fn test_distinct_on_keyword_only_for_postgresql() {
+        let has_distinct_on = |dialect: Dialect| {
+            KeywordProvider::new(dialect)
+                .select_clause_keywords()
+                .keywords
+                .iter()
+                .any(|k| k.label == "DISTINCT ON")
+        };
+
+        assert!(has_distinct_on(Dialect::PostgreSQL));
+        assert!(!has_distinct_on(Dialect::MySQL));
+        assert!(!has_distinct_on(Dialect::TiDB));
+    }
+
+    #[test]
+    fn test_merge_keyword_gated_by_capabilities() {
+        let has_merge = |provider: &KeywordProvider| {
+            provider
+                .statement_keywords()
+                .keywords
+                .iter()
+                .any(|k| k.label == "MERGE")
+        };
+
+        assert!(!has_merge(&KeywordProvider::new(Dialect::PostgreSQL)));
+        assert!(has_merge(
+            &KeywordProvider::new(Dialect::PostgreSQL).with_capabilities(DialectCapabilities {
+                merge: true,
+                ..DialectCapabilities::for_dialect(Dialect::PostgreSQL)
+            })
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_keywords_carry_newer_version() {
+        let window = |provider: &KeywordProvider| {
+            provider
+                .select_clause_keywords()
+                .keywords
+                .into_iter()
+                .find(|k| k.label == "WINDOW")
+        };
+        let provider = KeywordProvider::new(Dialect::MySQL);
+        assert_eq!(window(&provider), None);
+
+        let mysql80 = DialectCapabilities {
+            window_functions: true,
+            recursive_cte: true,
+            ..DialectCapabilities::for_dialect(Dialect::MySQL)
+        };
+        let provider = provider.with_unsupported(vec![("MySQL ≥ 8.0".to_string(), mysql80)]);
+        assert_eq!(
+            window(&provider).unwrap().requires.as_deref(),
+            Some("MySQL ≥ 8.0")
+        );
+        // No MySQL version has FULL JOIN
+        assert!(
+            provider
+                .join_continuation_keywords("FULL")
+                .keywords
+                .is_empty()
+        );
+        assert!(
+            provider
+                .select_clause_keywords()
+                .keywords
+                .iter()
+                .filter(|k| k.label != "WINDOW")
+                .all(|k| k.requires.is_none())
+        );
+    }
+
+    #[test]
+    fn test_statement_templates_gated_by_capabilities() {
+        let keywords = |provider: &KeywordProvider| -> Vec<String> {
+            provider
+                .statement_templates()
+                .into_iter()
+                .map(|t| t.keyword)
+                .collect()
+        };
+
+        let provider = KeywordProvider::new(Dialect::MySQL);
+        assert_eq!(
+            keywords(&provider),
+            vec!["SELECT", "INSERT", "UPDATE", "DELETE", "CREATE"]
+        );
+        let select = &provider.statement_templates()[0];
+        assert_eq!(select.label, "SELECT … FROM …");
+        assert_eq!(select.snippet, "SELECT ${1:*} FROM ${2:table}$0");
+        assert!(!select.writes);
+        assert!(provider.statement_templates()[1].writes);
+
+        let merge =
+            KeywordProvider::new(Dialect::PostgreSQL).with_capabilities(DialectCapabilities {
+                merge: true,
+                ..DialectCapabilities::for_dialect(Dialect::PostgreSQL)
+            });
+        assert_eq!(keywords(&merge).last().map(String::as_str), Some("MERGE"));
+    }
+
+    #[test]
+    fn test_column_constraint_keywords_follow_dialect() {
+        let mysql = KeywordProvider::new(Dialect::MySQL)
+            .column_constraint_keywords()
+            .labels();
+        assert!(mysql.contains("AUTO_INCREMENT"));
+        assert!(mysql.contains("NOT NULL"));
+        assert!(!mysql.contains("GENERATED ALWAYS AS IDENTITY"));
+
+        let postgres = KeywordProvider::new(Dialect::PostgreSQL)
+            .column_constraint_keywords()
+            .labels();
+        assert!(postgres.contains("GENERATED ALWAYS AS IDENTITY"));
+        assert!(postgres.contains("GENERATED BY DEFAULT AS IDENTITY"));
+        assert!(!postgres.contains("AUTO_INCREMENT"));
+
+        // Versions without identity columns
+        let legacy = KeywordProvider::new(Dialect::PostgreSQL)
+            .with_capabilities(DialectCapabilities {
+                identity_columns: false,
+                ..DialectCapabilities::for_dialect(Dialect::PostgreSQL)
+            })
+            .column_constraint_keywords()
+            .labels();
+        assert!(!legacy.contains("GENERATED ALWAYS AS IDENTITY"));
+    }
+
+    #[test]
+    fn test_full_outer_join_gated_by_dialect() {
+        let labels = |dialect: Dialect| {
+            let provider = KeywordProvider::new(dialect);
+            let mut labels = provider.select_clause_keywords().labels();
+            labels.extend(provider.join_type_keywords().labels());
+            labels
+        };
+
+        let postgres = labels(Dialect::PostgreSQL);
+        assert!(postgres.contains("FULL JOIN"));
+        assert!(postgres.contains("FULL OUTER JOIN"));
+        assert!(postgres.contains("FULL"));
+        assert!(postgres.contains("CROSS JOIN"));
+
+        let mysql = labels(Dialect::MySQL);
+        assert!(!mysql.contains("FULL JOIN"));
+        assert!(!mysql.contains("FULL OUTER JOIN"));
+        assert!(!mysql.contains("FULL"));
+        assert!(mysql.contains("CROSS JOIN"));
+        assert!(mysql.contains("CROSS"));
+    }
+
+    #[test]
+    fn test_join_continuations() {
+        let labels = |dialect: Dialect, modifier: &str| {
+            let mut labels: Vec<String> = KeywordProvider::new(dialect)
+                .join_continuation_keywords(modifier)
+                .labels()
+                .into_iter()
+                .collect();
+            labels.sort();
+            labels
+        };
+
+        for modifier in ["LEFT", "RIGHT"] {
+            assert_eq!(labels(Dialect::MySQL, modifier), ["JOIN", "OUTER JOIN"]);
+        }
+        for modifier in ["INNER", "CROSS", "OUTER"] {
+            assert_eq!(labels(Dialect::MySQL, modifier), ["JOIN"]);
+        }
+        assert_eq!(
+            labels(Dialect::MySQL, "NATURAL"),
+            ["INNER JOIN", "JOIN", "LEFT JOIN", "RIGHT JOIN"]
+        );
+        assert_eq!(
+            labels(Dialect::PostgreSQL, "NATURAL"),
+            ["FULL JOIN", "INNER JOIN", "JOIN", "LEFT JOIN", "RIGHT JOIN"]
+        );
+
+        // FULL joins are PostgreSQL only
+        assert_eq!(labels(Dialect::PostgreSQL, "FULL"), ["JOIN", "OUTER JOIN"]);
+        assert!(labels(Dialect::MySQL, "FULL").is_empty());
+    }
+
+    #[test]
+    fn test_sort_keywords_gated_by_dialect() {
+        let labels = |dialect: Dialect| {
+            KeywordProvider::new(dialect)
+                .sort_direction_keywords()
+                .labels()
+        };
+
+        for dialect in [Dialect::PostgreSQL, Dialect::CockroachDB] {
+            let labels = labels(dialect);
+            assert!(labels.contains("NULLS FIRST"));
+            assert!(labels.contains("NULLS LAST"));
+            assert!(labels.contains("COLLATE"));
+        }
+        for dialect in [Dialect::MySQL, Dialect::MariaDB, Dialect::TiDB] {
+            let labels = labels(dialect);
+            assert!(!labels.contains("NULLS FIRST"));
+            assert!(!labels.contains("NULLS LAST"));
+            assert!(labels.contains("ASC"));
+            assert!(labels.contains("COLLATE"));
+        }
+    }
+
+    #[test]
+    fn test_after_parenthesis_keywords() {
+        let provider = KeywordProvider::new(Dialect::MySQL);
+
+        let after_table = provider
+            .after_parenthesis_keywords(ClosedParenthesis::DerivedTable)
+            .labels();
+        for label in ["AS", "JOIN", "LEFT JOIN", "WHERE", "GROUP BY"] {
+            assert!(after_table.contains(label), "{label}");
+        }
+        for label in ["FROM", "CASE", "DISTINCT", "INTO"] {
+            assert!(!after_table.contains(label), "{label}");
+        }
+
+        let after_condition = provider
+            .after_parenthesis_keywords(ClosedParenthesis::Expression {
+                in_projection: false,
+            })
+            .labels();
+        for label in ["AND", "OR", "IN", "BETWEEN", "IS NOT NULL"] {
+            assert!(after_condition.contains(label), "{label}");
+        }
+        for label in ["AS", "FROM", "CASE", "EXISTS", "NULL"] {
+            assert!(!after_condition.contains(label), "{label}");
+        }
+
+        let after_item = provider
+            .after_parenthesis_keywords(ClosedParenthesis::Expression {
+                in_projection: true,
+            })
+            .labels();
+        assert!(after_item.contains("AS"));
+        assert!(after_item.contains("FROM"));
+    }
+
     #[test]
     fn test_keywords_after_clause() {
         let provider = KeywordProvider::new(Dialect::MySQL);
@@ -30,6 +30,11 @@
 //! The [`keywords`] module provides SQL keyword definitions organized by context
 //! and dialect.
 //!
+//! ### Table Extraction
+//!
+//! The [`table_extractor`] module reads the tables of a statement from its text,
+//! for incomplete SQL the CST doesn't cover.
+//!
 //! ## Examples
 //!
 //! ### Detecting Completion Context
@@ -60,22 +65,35 @@ pub mod completion;
 pub mod cst_utils;
 pub mod definition;
 pub mod keywords;
+pub mod nesting;
 pub mod scope_builder;
 pub mod symbols;
+pub mod table_extractor;
+pub mod tokens;
 
 // Re-export commonly used types
-pub use completion::{CompletionContext, WindowFunctionPart, detect_completion_context};
+pub use completion::{
+    BetweenBound, ClosedParenthesis, ComparisonOperand, CompletedOperand, CompletionContext,
+    DetectedContext, DetectionStage, StatementKind, StringLiteralRole, WindowFunctionPart,
+    detect_comparison_operand, detect_completed_operand, detect_completion_context,
+    detect_completion_context_from_text, detect_context, projection_aliases,
+};
 pub use cst_utils::{
     ChildIter, NodeExt, Position, Range, byte_to_position, extract_alias, extract_column_info,
     extract_identifier_name, extract_node_text, extract_table_name, find_from_clause,
     find_node_at_position, find_parent_select, find_select_clause, node_to_range,
-    position_to_byte_offset,
+    position_to_byte_offset, projection_column_references, statement_range, walk_tree,
 };
 pub use definition::{
     ColumnDefinition, Definition, DefinitionError, DefinitionFinder, TableDefinition,
 };
-pub use keywords::{KeywordProvider, KeywordSet, SqlKeyword};
+pub use keywords::{
+    DialectCapabilities, KeywordProvider, KeywordSet, SqlKeyword, StatementTemplate,
+};
+pub use nesting::{DEFAULT_MAX_NESTING_DEPTH, nesting_depth_at, too_deep_subquery};
 pub use scope_builder::{ScopeBuildError, ScopeBuilder};
 pub use symbols::{
     QuerySymbol, SymbolBuilder, SymbolError, TableSymbolWithRange as ContextTableSymbolWithRange,
 };
+pub use table_extractor::{TableExtractor, TableRefInfo};
+pub use tokens::{Token, TokenKind, lex, mask, mask_ranges, tokens};
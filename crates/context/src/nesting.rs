@@ -0,0 +1,132 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Subquery nesting depth
+//!
+//! Generated SQL can nest subqueries hundreds of levels deep. Analysis stops
+//! at a nesting cap ([`DEFAULT_MAX_NESTING_DEPTH`] unless configured):
+//! diagnostics report the first subquery past it once, and completion inside
+//! such a subquery only offers keywords.
+//!
+//! Depth is read from the text, so it is known for SQL that doesn't parse.
+//! A parenthesis opens a subquery when `SELECT` or `WITH` follows it.
+
+use crate::tokens::tokens;
+
+/// Subquery nesting levels analyzed by default
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 64;
+
+/// Subqueries of `source` enclosing byte `offset`
+///
+/// An offset right after the opening parenthesis is inside the subquery, and
+/// one right before the closing parenthesis too.
+pub fn nesting_depth_at(source: &str, offset: usize) -> usize {
+    depth_changes(source)
+        .into_iter()
+        .take_while(|(at, _)| *at <= offset)
+        .last()
+        .map_or(0, |(_, depth)| depth)
+}
+
+/// Byte offset of the parenthesis opening the first subquery of `source`
+/// nested deeper than `max_depth`
+pub fn too_deep_subquery(source: &str, max_depth: usize) -> Option<usize> {
+    depth_changes(source)
+        .into_iter()
+        .find(|(_, depth)| *depth > max_depth)
+        .map(|(at, _)| at - 1)
+}
+
+/// Subquery depth from each byte offset where it changes on
+fn depth_changes(source: &str) -> Vec<(usize, usize)> {
+    let mut changes = Vec::new();
+    // Whether each parenthesis still open opens a subquery
+    let mut open: Vec<bool> = Vec::new();
+    let mut depth = 0;
+    let mut after_paren = None;
+
+    for token in tokens(source) {
+        match token.paren() {
+            Some(true) => {
+                open.push(false);
+                after_paren = Some(token.offset);
+                continue;
+            }
+            Some(false) => {
+                if open.pop() == Some(true) {
+                    depth -= 1;
+                    changes.push((token.offset + 1, depth));
+                }
+            }
+            None => {
+                let opens_query = token.word().is_some_and(|word| {
+                    word.eq_ignore_ascii_case("SELECT") || word.eq_ignore_ascii_case("WITH")
+                });
+                if let Some(paren) = after_paren
+                    && opens_query
+                    && let Some(subquery) = open.last_mut()
+                {
+                    *subquery = true;
+                    depth += 1;
+                    changes.push((paren + 1, depth));
+                }
+            }
+        }
+        after_paren = None;
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `levels` subqueries nested in FROM, innermost reading `users`
+    fn nested_query(levels: usize) -> String {
+        let mut sql = "SELECT id FROM users".to_string();
+        for level in 0..levels {
+            sql = format!("SELECT id FROM ({}) t{}", sql, level);
+        }
+        sql
+    }
+
+    #[test]
+    fn test_nesting_depth_at() {
+        let sql = "SELECT * FROM (SELECT id FROM (SELECT id FROM users) a) b WHERE (id > 1)";
+        let at = |needle: &str| nesting_depth_at(sql, sql.find(needle).unwrap());
+        assert_eq!(at("SELECT *"), 0);
+        assert_eq!(at("SELECT id FROM ("), 1);
+        assert_eq!(at("users"), 2);
+        assert_eq!(at(") a"), 2);
+        assert_eq!(at(" a)"), 1);
+        assert_eq!(at(" b"), 0);
+        // Parentheses around expressions don't count
+        assert_eq!(at("id > 1"), 0);
+
+        let sql = "WITH a AS (SELECT ')' FROM users) SELECT * FROM a";
+        assert_eq!(nesting_depth_at(sql, sql.find("users").unwrap()), 1);
+        assert_eq!(nesting_depth_at(sql, sql.len()), 0);
+    }
+
+    #[test]
+    fn test_generated_deep_query() {
+        let sql = nested_query(300);
+        let innermost = sql.find("users").unwrap();
+        assert_eq!(nesting_depth_at(&sql, innermost), 300);
+        assert_eq!(nesting_depth_at(&sql, sql.len()), 0);
+
+        let too_deep = too_deep_subquery(&sql, DEFAULT_MAX_NESTING_DEPTH).unwrap();
+        assert_eq!(&sql[too_deep..too_deep + 7], "(SELECT");
+        assert_eq!(nesting_depth_at(&sql, too_deep), DEFAULT_MAX_NESTING_DEPTH);
+        assert_eq!(
+            nesting_depth_at(&sql, too_deep + 1),
+            DEFAULT_MAX_NESTING_DEPTH + 1
+        );
+
+        assert_eq!(too_deep_subquery(&nested_query(64), 64), None);
+        assert!(too_deep_subquery(&nested_query(65), 64).is_some());
+    }
+}
@@ -30,7 +30,7 @@
 //! ## Architecture
 //!
 //! This is a simplified version of the full semantic analysis (SEMANTIC-002):
-//! - Only builds scopes for SELECT statements (not INSERT/UPDATE/DELETE)
+//! - Only builds scopes for SELECT, MERGE, UPDATE and DELETE statements (not INSERT)
 //! - Only extracts table references (not full column resolution)
 //! - Populated incrementally by catalog during completion (not upfront)
 //!
@@ -41,6 +41,9 @@ use tracing::warn;
 use tree_sitter::Node;
 use unified_sql_lsp_semantic::{ScopeManager, ScopeType, TableSymbol};
 
+use crate::cst_utils::walk_tree;
+use crate::table_extractor::TableExtractor;
+
 /// Scope builder error
 #[derive(Debug, thiserror::Error)]
 pub enum ScopeBuildError {
@@ -109,6 +112,165 @@ impl ScopeBuilder {
         Ok(manager)
     }
 
+    /// Build scopes for a MERGE statement
+    ///
+    /// The scope holds the target table and then the source table, each with
+    /// its alias, so `s.` in `UPDATE SET name = s.|` resolves to the source.
+    /// With `target_only`, only the target is added: the columns being set
+    /// or inserted always belong to it.
+    ///
+    /// Incomplete MERGE statements rarely parse, so when no `merge_statement`
+    /// node is found the tables are read from the text instead.
+    pub fn build_from_merge(
+        root: &Node,
+        source: &str,
+        target_only: bool,
+    ) -> Result<ScopeManager, ScopeBuildError> {
+        let mut tables = match Self::find_merge_statement(root) {
+            Some(merge) => {
+                let mut tables = Vec::new();
+                for field in ["target", "source"] {
+                    if let Some(table) = merge.child_by_field_name(field) {
+                        tables.push(Self::parse_table_reference(&table, source)?);
+                    }
+                }
+                tables
+            }
+            None => Self::merge_tables_from_text(source),
+        };
+
+        if tables.is_empty() {
+            return Err(ScopeBuildError::ScopeBuild(
+                "MERGE target table not found".to_string(),
+            ));
+        }
+        if target_only {
+            tables.truncate(1);
+        }
+
+        let mut manager = ScopeManager::new();
+        let scope_id = manager.create_scope(ScopeType::Query, None);
+        let scope = manager.get_scope_mut(scope_id).unwrap();
+        for table in tables {
+            scope.add_table(table)?;
+        }
+
+        Ok(manager)
+    }
+
+    /// Find the first merge_statement node under `node`
+    fn find_merge_statement<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+        let mut merge = None;
+        walk_tree(node, |node| {
+            if node.kind() == "merge_statement" {
+                merge = Some(node);
+            }
+            merge.is_none()
+        });
+        merge
+    }
+
+    /// Read the target and source tables of the last MERGE in `sql`
+    ///
+    /// Handles `MERGE INTO target [[AS] alias] USING source [[AS] alias]`;
+    /// a subquery source is skipped. The target comes first.
+    pub fn merge_tables_from_text(sql: &str) -> Vec<TableSymbol> {
+        let words: Vec<&str> = sql
+            .split(|c: char| c.is_whitespace() || c == ';')
+            .filter(|w| !w.is_empty())
+            .collect();
+        let Some(merge_pos) = words.iter().rposition(|w| w.eq_ignore_ascii_case("MERGE")) else {
+            return Vec::new();
+        };
+
+        let mut tables = Vec::new();
+        let mut rest = &words[merge_pos + 1..];
+        for keyword in ["INTO", "USING"] {
+            let Some(start) = rest.iter().position(|w| w.eq_ignore_ascii_case(keyword)) else {
+                break;
+            };
+            let Some(name) = rest.get(start + 1).filter(|name| !name.starts_with('(')) else {
+                break;
+            };
+
+            let mut table = TableSymbol::new(*name);
+            let mut next = start + 2;
+            if rest.get(next).is_some_and(|w| w.eq_ignore_ascii_case("AS")) {
+                next += 1;
+            }
+            if let Some(alias) = rest.get(next).filter(|w| {
+                !["USING", "ON", "WHEN"]
+                    .iter()
+                    .any(|kw| w.eq_ignore_ascii_case(kw))
+            }) {
+                table = table.with_alias(*alias);
+            }
+
+            tables.push(table);
+            rest = &rest[start + 1..];
+        }
+
+        tables
+    }
+
+    /// Build the scope of an UPDATE or DELETE statement from its text
+    ///
+    /// Only the statement's own tables are visible: the target(s), the
+    /// tables joined in MySQL's multi-table forms and those of a PostgreSQL
+    /// `UPDATE ... FROM` or `DELETE ... USING`.
+    pub fn build_from_dml(statement: &str) -> Result<ScopeManager, ScopeBuildError> {
+        let tables = Self::dml_tables_from_text(statement);
+        if tables.is_empty() {
+            return Err(ScopeBuildError::ScopeBuild(
+                "UPDATE or DELETE target table not found".to_string(),
+            ));
+        }
+
+        let mut manager = ScopeManager::new();
+        let scope_id = manager.create_scope(ScopeType::Query, None);
+        let scope = manager.get_scope_mut(scope_id).unwrap();
+        for table in tables {
+            scope.add_table(table)?;
+        }
+
+        Ok(manager)
+    }
+
+    /// Read the tables of the UPDATE or DELETE statement `statement`
+    ///
+    /// Returns nothing for other statements. Tables are deduplicated by
+    /// name and alias, so `DELETE t1 FROM t1 JOIN t2` lists `t1` once.
+    pub fn dml_tables_from_text(statement: &str) -> Vec<TableSymbol> {
+        match TableExtractor::statement_keyword(statement) {
+            Some("UPDATE" | "DELETE") => Self::text_tables(statement),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Read the tables of the top-level FROM clause of the SELECT `statement`
+    ///
+    /// Returns nothing for other statements. Tables of subqueries, CTE
+    /// definitions and set operations after the first query are not included.
+    pub fn select_tables_from_text(statement: &str) -> Vec<TableSymbol> {
+        match TableExtractor::statement_keyword(statement) {
+            Some("SELECT") => Self::text_tables(statement),
+            _ => Vec::new(),
+        }
+    }
+
+    fn text_tables(statement: &str) -> Vec<TableSymbol> {
+        TableExtractor::statement_tables(statement)
+            .into_iter()
+            .map(|table| {
+                let symbol = TableSymbol::new(table.qualified_name());
+                match table.alias {
+                    Some(alias) => symbol.with_alias(alias),
+                    None => symbol,
+                }
+            })
+            .collect()
+    }
+
     /// Find the FROM clause in a SELECT statement
     pub fn find_from_clause<'a>(select_node: &'a Node) -> Option<Node<'a>> {
         select_node
@@ -127,53 +289,60 @@ impl ScopeBuilder {
         let mut table_counts: HashMap<String, usize> = HashMap::new();
 
         // Find all table_reference nodes
-        Self::extract_tables_recursive(from_clause, source, &mut tables, &mut table_counts)?;
+        Self::extract_tables(from_clause, source, &mut tables, &mut table_counts)?;
 
         Ok(tables)
     }
 
-    /// Recursively extract table references from a node
+    /// Extract the table references of a node and its descendants
     ///
     /// Phase 3: Allows duplicate table references (self-joins) as long as they have different aliases
-    fn extract_tables_recursive(
+    fn extract_tables(
         node: &Node,
         source: &str,
         tables: &mut Vec<TableSymbol>,
         table_counts: &mut HashMap<String, usize>,
     ) -> Result<(), ScopeBuildError> {
-        if node.kind() == "table_reference" {
-            let table = Self::parse_table_reference(node, source)?;
-            let display_name = table.display_name().to_string();
-            *table_counts.entry(display_name.clone()).or_insert(0) += 1;
-
-            // Phase 3: Remove duplicate restriction - allow self-joins with different aliases
-            // For example: "FROM users AS u1 JOIN users AS u2" should be allowed
-            // The duplicate check is removed because different aliases make them distinct
-            tables.push(table);
-            return Ok(());
-        }
-
-        // Handle join_clause nodes (Phase 1: JOIN alias support)
-        if node.kind() == "join_clause" {
-            match Self::parse_join_clause(node, source) {
-                Ok(table) => {
-                    let display_name = table.display_name().to_string();
-                    *table_counts.entry(display_name.clone()).or_insert(0) += 1;
-                    tables.push(table);
+        let mut result = Ok(());
+        walk_tree(node, |node| {
+            if result.is_err() {
+                return false;
+            }
+            match node.kind() {
+                "table_reference" => {
+                    match Self::parse_table_reference(&node, source) {
+                        Ok(table) => {
+                            let display_name = table.display_name().to_string();
+                            *table_counts.entry(display_name.clone()).or_insert(0) += 1;
+
+                            // Phase 3: Remove duplicate restriction - allow self-joins with different aliases
+                            // For example: "FROM users AS u1 JOIN users AS u2" should be allowed
+                            // The duplicate check is removed because different aliases make them distinct
+                            tables.push(table);
+                        }
+                        Err(e) => result = Err(e),
+                    }
+                    false
                 }
-                Err(e) => {
-                    warn!("Failed to parse JOIN clause: {}", e);
-                    // Continue - don't fail entire scope build
+                // Handle join_clause nodes (Phase 1: JOIN alias support)
+                "join_clause" => {
+                    match Self::parse_join_clause(&node, source) {
+                        Ok(table) => {
+                            let display_name = table.display_name().to_string();
+                            *table_counts.entry(display_name.clone()).or_insert(0) += 1;
+                            tables.push(table);
+                        }
+                        Err(e) => {
+                            warn!("Failed to parse JOIN clause: {}", e);
+                            // Continue - don't fail entire scope build
+                        }
+                    }
+                    false
                 }
+                _ => true,
             }
-            return Ok(());
-        }
-
-        // Recurse into children
-        for child in node.children(&mut node.walk()) {
-            Self::extract_tables_recursive(&child, source, tables, table_counts)?;
-        }
-        Ok(())
+        });
+        result
     }
 
     /// Parse a table_reference node
@@ -339,6 +508,28 @@ mod tests {
         None
     }
 
+    #[test]
+    fn test_deeply_nested_query_scope() {
+        let mut sql = "SELECT id FROM users".to_string();
+        for level in 0..300 {
+            sql = format!("SELECT id FROM ({}) t{}", sql, level);
+        }
+        let lang = language_for_dialect_with_version(Dialect::MySQL, Some(DialectVersion::MySQL80))
+            .expect("Failed to get MySQL 8.0 language");
+
+        let mut parser = Parser::new();
+        parser.set_language(lang).expect("Failed to set language");
+        let tree = parser.parse(&sql, None).expect("Failed to parse SQL");
+        let select_stmt =
+            find_select_statement(&tree.root_node()).expect("Failed to find select_statement");
+
+        // The outermost FROM holds a single derived table
+        let manager =
+            ScopeBuilder::build_from_select(&select_stmt, &sql).expect("Failed to build scope");
+        let scope = manager.get_scope(0).unwrap();
+        assert_eq!(scope.tables.len(), 1);
+    }
+
     #[test]
     fn test_extract_join_with_alias() {
         let sql = "SELECT u.id FROM users AS u JOIN orders AS o ON u.id = o.user_id";
@@ -346,7 +537,7 @@ mod tests {
             .expect("Failed to get MySQL 8.0 language");
 
         let mut parser = Parser::new();
-        parser.set_language(&lang).expect("Failed to set language");
+        parser.set_language(lang).expect("Failed to set language");
         let tree = parser.parse(sql, None).expect("Failed to parse SQL");
         let root = tree.root_node();
 
@@ -387,7 +578,7 @@ mod tests {
             .expect("Failed to get MySQL 8.0 language");
 
         let mut parser = Parser::new();
-        parser.set_language(&lang).expect("Failed to set language");
+        parser.set_language(lang).expect("Failed to set language");
         let tree = parser.parse(sql, None).expect("Failed to parse SQL");
         let root = tree.root_node();
 
@@ -426,7 +617,7 @@ mod tests {
             .expect("Failed to get MySQL 8.0 language");
 
         let mut parser = Parser::new();
-        parser.set_language(&lang).expect("Failed to set language");
+        parser.set_language(lang).expect("Failed to set language");
         let tree = parser.parse(sql, None).expect("Failed to parse SQL");
         let root = tree.root_node();
 
@@ -458,7 +649,7 @@ mod tests {
             .expect("Failed to get MySQL 8.0 language");
 
         let mut parser = Parser::new();
-        parser.set_language(&lang).expect("Failed to set language");
+        parser.set_language(lang).expect("Failed to set language");
         let tree = parser.parse(sql, None).expect("Failed to parse SQL");
         let root = tree.root_node();
 
@@ -487,7 +678,7 @@ mod tests {
             .expect("Failed to get MySQL 8.0 language");
 
         let mut parser = Parser::new();
-        parser.set_language(&lang).expect("Failed to set language");
+        parser.set_language(lang).expect("Failed to set language");
         let tree = parser.parse(sql, None).expect("Failed to parse SQL");
         let root = tree.root_node();
 
@@ -513,7 +704,7 @@ mod tests {
             .expect("Failed to get MySQL 8.0 language");
 
         let mut parser = Parser::new();
-        parser.set_language(&lang).expect("Failed to set language");
+        parser.set_language(lang).expect("Failed to set language");
         let tree = parser.parse(sql, None).expect("Failed to parse SQL");
         let root = tree.root_node();
 
@@ -543,7 +734,7 @@ mod tests {
             .expect("Failed to get MySQL 8.0 language");
 
         let mut parser = Parser::new();
-        parser.set_language(&lang).expect("Failed to set language");
+        parser.set_language(lang).expect("Failed to set language");
         let tree = parser.parse(sql, None).expect("Failed to parse SQL");
         let root = tree.root_node();
 
@@ -574,7 +765,7 @@ mod tests {
             .expect("Failed to get MySQL 8.0 language");
 
         let mut parser = Parser::new();
-        parser.set_language(&lang).expect("Failed to set language");
+        parser.set_language(lang).expect("Failed to set language");
         let tree = parser.parse(sql, None).expect("Failed to parse SQL");
         let root = tree.root_node();
 
@@ -602,4 +793,120 @@ mod tests {
             .expect("Second users table with alias u2 not found");
         assert_eq!(users2.table_name, "users");
     }
+
+    #[test]
+    fn test_merge_tables_from_text() {
+        let tables = ScopeBuilder::merge_tables_from_text(
+            "MERGE INTO users AS u USING staging s ON u.id = s.id WHEN MATCHED THEN UPDATE SET ",
+        );
+        assert_eq!(tables.len(), 2);
+        assert_eq!(tables[0].table_name, "users");
+        assert_eq!(tables[0].alias.as_deref(), Some("u"));
+        assert_eq!(tables[1].table_name, "staging");
+        assert_eq!(tables[1].alias.as_deref(), Some("s"));
+
+        let tables = ScopeBuilder::merge_tables_from_text("MERGE INTO users USING ");
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].alias, None);
+
+        assert!(ScopeBuilder::merge_tables_from_text("SELECT 1").is_empty());
+    }
+
+    #[test]
+    fn test_dml_tables_from_text() {
+        let names = |sql: &str| -> Vec<(String, Option<String>)> {
+            ScopeBuilder::dml_tables_from_text(sql)
+                .into_iter()
+                .map(|t| (t.table_name, t.alias))
+                .collect()
+        };
+        let table = |name: &str, alias: Option<&str>| (name.to_string(), alias.map(String::from));
+
+        assert_eq!(
+            names("DELETE FROM users WHERE "),
+            vec![table("users", None)]
+        );
+        assert_eq!(
+            names("DELETE FROM users u WHERE u.id = 1"),
+            vec![table("users", Some("u"))]
+        );
+        assert_eq!(
+            names("DELETE FROM users AS u USING orders o WHERE u.id = o.user_id"),
+            vec![table("users", Some("u")), table("orders", Some("o"))]
+        );
+        assert_eq!(
+            names("UPDATE ONLY users SET name = 'x' FROM orders WHERE "),
+            vec![table("users", None), table("orders", None)]
+        );
+        assert_eq!(
+            names("UPDATE users u JOIN orders o ON u.id = o.user_id SET u.name = o.note WHERE "),
+            vec![table("users", Some("u")), table("orders", Some("o"))]
+        );
+        assert_eq!(
+            names("DELETE t1 FROM t1 JOIN t2 ON t1.id = t2.id WHERE "),
+            vec![table("t1", None), table("t2", None)]
+        );
+        // Subqueries in the condition don't leak their tables
+        assert_eq!(
+            names("DELETE FROM users WHERE id IN (SELECT user_id FROM orders)"),
+            vec![table("users", None)]
+        );
+        assert!(names("SELECT * FROM users WHERE ").is_empty());
+    }
+
+    #[test]
+    fn test_select_tables_from_text() {
+        let names = |sql: &str| -> Vec<(String, Option<String>)> {
+            ScopeBuilder::select_tables_from_text(sql)
+                .into_iter()
+                .map(|t| (t.table_name, t.alias))
+                .collect()
+        };
+        let table = |name: &str, alias: Option<&str>| (name.to_string(), alias.map(String::from));
+
+        assert_eq!(
+            names("SELECT * FROM users u JOIN orders o ON u.id = o.user_id WHERE u.id IS NULL"),
+            vec![table("users", Some("u")), table("orders", Some("o"))]
+        );
+        assert_eq!(
+            names("SELECT (SELECT 1 FROM t) FROM users ORDER BY id"),
+            vec![table("users", None)]
+        );
+        assert!(names("SELECT 1").is_empty());
+        assert!(names("DELETE FROM users WHERE id IS NULL").is_empty());
+    }
+
+    #[test]
+    fn test_build_from_dml_scopes() {
+        let manager = ScopeBuilder::build_from_dml("DELETE FROM users u WHERE u.").unwrap();
+        let scope = manager.get_scope(0).unwrap();
+        assert_eq!(scope.tables.len(), 1);
+        assert!(scope.find_table("u").is_some());
+
+        assert!(ScopeBuilder::build_from_dml("SELECT 1").is_err());
+    }
+
+    #[test]
+    fn test_build_from_merge_scopes() {
+        let sql = "MERGE INTO users u USING staging s ON u.id = s.id \
+                   WHEN MATCHED THEN UPDATE SET name = s.name";
+        let lang = language_for_dialect_with_version(
+            Dialect::PostgreSQL,
+            Some(DialectVersion::PostgreSQL14),
+        )
+        .unwrap();
+        let mut parser = Parser::new();
+        parser.set_language(lang).unwrap();
+        let tree = parser.parse(sql, None).unwrap();
+
+        let manager = ScopeBuilder::build_from_merge(&tree.root_node(), sql, false).unwrap();
+        let scope = manager.get_scope(0).unwrap();
+        assert_eq!(scope.tables.len(), 2);
+        assert!(scope.find_table("s").is_some());
+
+        let manager = ScopeBuilder::build_from_merge(&tree.root_node(), sql, true).unwrap();
+        let scope = manager.get_scope(0).unwrap();
+        assert_eq!(scope.tables.len(), 1);
+        assert_eq!(scope.tables[0].table_name, "users");
+    }
 }
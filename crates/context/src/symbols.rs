@@ -5,7 +5,9 @@
 
 //! CST symbol extraction helpers for document symbols.
 
-use crate::{Range, extract_alias, extract_node_text, node_to_range as context_node_to_range};
+use crate::{
+    Range, extract_alias, extract_node_text, node_to_range as context_node_to_range, walk_tree,
+};
 use tree_sitter::Node;
 use unified_sql_lsp_semantic::TableSymbol;
 
@@ -50,19 +52,18 @@ impl SymbolBuilder {
     }
 
     fn find_select_statements(node: &Node<'_>, source: &str, queries: &mut Vec<QuerySymbol>) {
-        if (node.kind() == "select_statement" || node.kind() == "statement")
-            && (node.kind() == "select_statement" || Self::is_select_statement(node))
-        {
-            let range = context_node_to_range(node, source);
-            queries.push(QuerySymbol {
-                range,
-                tables: Vec::new(),
-            });
-        }
-
-        for child in node.children(&mut node.walk()) {
-            Self::find_select_statements(&child, source, queries);
-        }
+        walk_tree(node, |node| {
+            if (node.kind() == "select_statement" || node.kind() == "statement")
+                && (node.kind() == "select_statement" || Self::is_select_statement(&node))
+            {
+                let range = context_node_to_range(&node, source);
+                queries.push(QuerySymbol {
+                    range,
+                    tables: Vec::new(),
+                });
+            }
+            true
+        });
     }
 
     fn is_select_statement(node: &Node<'_>) -> bool {
@@ -88,16 +89,15 @@ impl SymbolBuilder {
         source: &str,
         tables: &mut Vec<TableSymbolWithRange>,
     ) {
-        if node.kind() == "table_reference" {
-            if let Ok(table) = Self::parse_table_reference(node, source) {
+        walk_tree(node, |node| {
+            if node.kind() != "table_reference" {
+                return true;
+            }
+            if let Ok(table) = Self::parse_table_reference(&node, source) {
                 tables.push(table);
             }
-            return;
-        }
-
-        for child in node.children(&mut node.walk()) {
-            Self::find_table_references(&child, source, tables);
-        }
+            false
+        });
     }
 
     fn parse_table_reference(
@@ -0,0 +1,620 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Table extraction from text
+//!
+//! Reads the tables a statement refers to from its text alone, for SQL the
+//! tree doesn't cover: incomplete statements while typing, or syntax the
+//! grammar doesn't support. The text-fallback completion detectors, the
+//! tables excluded from FROM suggestions and the text scope builders all
+//! read tables through [`TableExtractor`].
+//!
+//! A table list is split into `table [[AS] alias]` references at commas and
+//! joins. Join conditions, subqueries, index hints and the contents of
+//! quotes are skipped, and the list ends at the next clause, a `;`, or the
+//! `)` closing the subquery it is written in. Names keep the quotes they are
+//! written with, so a quoted name still matches only its exact spelling.
+
+use std::ops::Range;
+
+use crate::tokens::{TokenKind, lex};
+
+/// A table named in a statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableRefInfo {
+    /// Schema (or database) qualifier, e.g. `app` in `app.users`
+    pub schema: Option<String>,
+    /// Table name as written, quotes included
+    pub name: String,
+    pub alias: Option<String>,
+}
+
+impl TableRefInfo {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            schema: None,
+            name: name.into(),
+            alias: None,
+        }
+    }
+
+    pub fn with_schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+
+    pub fn with_alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// The name with its schema, as written: `app.users`
+    pub fn qualified_name(&self) -> String {
+        match &self.schema {
+            Some(schema) => format!("{}.{}", schema, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// How columns of the table are qualified: by its alias if it has one
+    pub fn reference(&self) -> String {
+        self.alias.clone().unwrap_or_else(|| self.qualified_name())
+    }
+}
+
+/// Words between table references that are neither a table nor an alias
+const JOIN_KEYWORDS: &[&str] = &[
+    "JOIN",
+    "INNER",
+    "LEFT",
+    "RIGHT",
+    "FULL",
+    "OUTER",
+    "CROSS",
+    "NATURAL",
+    "STRAIGHT_JOIN",
+    "LATERAL",
+    "ON",
+    "USING",
+    "ONLY",
+    "LOW_PRIORITY",
+    "QUICK",
+    "IGNORE",
+    "USE",
+    "FORCE",
+    "INDEX",
+    "KEY",
+    "AS",
+    "AND",
+    "OR",
+    "NOT",
+];
+
+/// Clauses that end a table list
+const CLAUSE_KEYWORDS: &[&str] = &[
+    "WHERE",
+    "GROUP",
+    "HAVING",
+    "WINDOW",
+    "ORDER",
+    "LIMIT",
+    "OFFSET",
+    "FETCH",
+    "UNION",
+    "INTERSECT",
+    "EXCEPT",
+    "INTO",
+    "FOR",
+    "SET",
+    "VALUES",
+    "VALUE",
+    "SELECT",
+    "DEFAULT",
+    "RETURNING",
+    "PARTITION",
+    "TABLESAMPLE",
+    "WHEN",
+];
+
+/// Keywords starting the statements tables are read from
+const STATEMENT_KEYWORDS: &[&str] = &["SELECT", "INSERT", "REPLACE", "UPDATE", "DELETE"];
+
+/// Words that may come before the statement keyword, as in
+/// `WITH ... SELECT` or `CREATE TABLE ... AS SELECT`
+const PREFIX_KEYWORDS: &[&str] = &["WITH", "EXPLAIN", "CREATE"];
+
+/// Reads the tables of statements and table lists from text
+pub struct TableExtractor;
+
+impl TableExtractor {
+    /// Keyword of the statement: `SELECT`, `INSERT`, `REPLACE`, `UPDATE` or
+    /// `DELETE`, after any WITH clause
+    pub fn statement_keyword(statement: &str) -> Option<&'static str> {
+        leading_keyword(&statement[..statement_end(statement)]).map(|(keyword, _)| keyword)
+    }
+
+    /// Tables of `statement`, in the order written
+    ///
+    /// - SELECT: the top-level FROM list; tables of subqueries and of set
+    ///   operations after the first query are not included
+    /// - INSERT and REPLACE: the target table
+    /// - UPDATE: the updated tables and those of a FROM clause
+    /// - DELETE: the tables after FROM and those of a USING clause
+    ///
+    /// Other statements have no tables. Tables named twice with the same
+    /// alias are listed once, so `DELETE t1 FROM t1 JOIN t2` lists `t1` once.
+    pub fn statement_tables(statement: &str) -> Vec<TableRefInfo> {
+        let statement = &statement[..statement_end(statement)];
+        let Some((keyword, at)) = leading_keyword(statement) else {
+            return Vec::new();
+        };
+        let after = at + keyword.len();
+
+        let mut lists: Vec<Range<usize>> = Vec::new();
+        match keyword {
+            "SELECT" => {
+                if let Some(from) = top_level_keyword(statement, "FROM", after) {
+                    lists.push(from + "FROM".len()..statement.len());
+                }
+            }
+            "INSERT" | "REPLACE" => {
+                let start = top_level_keyword(statement, "INTO", after)
+                    .map_or(after, |into| into + "INTO".len());
+                let target = Self::table_list(&statement[start..]);
+                return target.into_iter().take(1).collect();
+            }
+            "UPDATE" => {
+                let end = first_keyword(statement, &["SET"], after);
+                lists.push(after..end);
+                if let Some(from) = top_level_keyword(statement, "FROM", end) {
+                    lists.push(from + "FROM".len()..statement.len());
+                }
+            }
+            _ => {
+                let Some(from) = top_level_keyword(statement, "FROM", after) else {
+                    return Vec::new();
+                };
+                let start = from + "FROM".len();
+                let end = first_keyword(statement, &["USING", "WHERE", "RETURNING"], start);
+                lists.push(start..end);
+                if let Some(using) =
+                    top_level_keyword(statement, "USING", start).filter(|&u| u == end)
+                {
+                    lists.push(using + "USING".len()..statement.len());
+                }
+            }
+        }
+
+        let mut tables: Vec<TableRefInfo> = Vec::new();
+        for list in lists {
+            for table in Self::table_list(&statement[list]) {
+                let duplicate = tables.iter().any(|t| {
+                    t.qualified_name()
+                        .eq_ignore_ascii_case(&table.qualified_name())
+                        && t.alias.as_deref().map(str::to_lowercase)
+                            == table.alias.as_deref().map(str::to_lowercase)
+                });
+                if !duplicate {
+                    tables.push(table);
+                }
+            }
+        }
+        tables
+    }
+
+    /// Parse `table [[AS] alias]` references separated by commas or joins
+    ///
+    /// `list` starts right after FROM (or the keyword taking the list).
+    /// Reading stops at the first clause keyword after it.
+    pub fn table_list(list: &str) -> Vec<TableRefInfo> {
+        let tokens = tokens(list);
+        let mut tables = Vec::new();
+        let mut expect_table = true;
+        let mut i = 0;
+        while i < tokens.len() {
+            let word = tokens[i].word();
+            let is = |keywords: &[&str]| word.is_some_and(|word| is_one_of(word, keywords));
+            match &tokens[i] {
+                Token::Comma => expect_table = true,
+                // Subqueries and column lists
+                Token::Open => expect_table = false,
+                _ if is(CLAUSE_KEYWORDS) => break,
+                _ if is(&["JOIN", "STRAIGHT_JOIN"]) => expect_table = true,
+                // Join conditions, up to the next table
+                _ if is(&["ON", "USING"]) => expect_table = false,
+                Token::Name(parts) if expect_table && !is(JOIN_KEYWORDS) => {
+                    let Some(mut table) = table_ref(parts) else {
+                        i += 1;
+                        continue;
+                    };
+                    let mut next = i + 1;
+                    if tokens
+                        .get(next)
+                        .and_then(Token::word)
+                        .is_some_and(|word| word.eq_ignore_ascii_case("AS"))
+                    {
+                        next += 1;
+                    }
+                    if let Some(alias) = tokens.get(next).and_then(Token::word)
+                        && is_alias(alias)
+                    {
+                        table.alias = Some(alias.to_string());
+                        i = next;
+                    }
+                    tables.push(table);
+                    expect_table = false;
+                }
+                Token::Name(_) => {}
+            }
+            i += 1;
+        }
+        tables
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token<'a> {
+    /// A name and its qualifiers as written, e.g. `app` and `"Users"`; a
+    /// `.` not followed by a name yet leaves an empty last part
+    Name(Vec<&'a str>),
+    Comma,
+    /// A `(`; everything up to its `)` is skipped
+    Open,
+}
+
+impl<'a> Token<'a> {
+    /// The word of an unqualified name, to compare with keywords
+    fn word(&self) -> Option<&'a str> {
+        match self {
+            Token::Name(parts) if parts.len() == 1 => Some(parts[0]),
+            _ => None,
+        }
+    }
+}
+
+/// Top-level tokens of `list`, up to a `;` or an unmatched `)`
+fn tokens(list: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    for token in lex(list) {
+        if token.depth < 0 || (token.depth == 0 && token.is_symbol(';')) {
+            break;
+        }
+        if token.depth > 0 {
+            continue;
+        }
+        match token.kind {
+            TokenKind::OpenParen => tokens.push(Token::Open),
+            TokenKind::Word | TokenKind::QuotedIdentifier => match tokens.last_mut() {
+                Some(Token::Name(parts)) if parts.last().is_some_and(|part| part.is_empty()) => {
+                    parts.pop();
+                    parts.push(token.text);
+                }
+                _ => tokens.push(Token::Name(vec![token.text])),
+            },
+            TokenKind::Symbol if token.is_symbol(',') => tokens.push(Token::Comma),
+            TokenKind::Symbol if token.is_symbol('.') => {
+                if let Some(Token::Name(parts)) = tokens.last_mut() {
+                    parts.push("");
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+/// Split a written name like `app."Users"` into schema and name, unless a
+/// part is missing, as in `app.` while typing
+fn table_ref(parts: &[&str]) -> Option<TableRefInfo> {
+    let is_identifier = |part: &&str| {
+        part.chars()
+            .any(|c| c.is_alphabetic() || matches!(c, '_' | '"' | '`'))
+    };
+    let (name, schema) = parts.split_last()?;
+    if !parts.iter().all(is_identifier) {
+        return None;
+    }
+
+    let table = TableRefInfo::new(*name);
+    if schema.is_empty() {
+        Some(table)
+    } else {
+        Some(table.with_schema(schema.join(".")))
+    }
+}
+
+fn is_alias(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_alphabetic() || matches!(c, '_' | '"' | '`'))
+        && !is_one_of(word, JOIN_KEYWORDS)
+        && !is_one_of(word, CLAUSE_KEYWORDS)
+}
+
+fn is_one_of(word: &str, keywords: &[&str]) -> bool {
+    keywords.iter().any(|k| word.eq_ignore_ascii_case(k))
+}
+
+/// End of `statement`: its first top-level `;`, or an unmatched `)`
+/// closing the subquery it is written in
+fn statement_end(statement: &str) -> usize {
+    lex(statement)
+        .find(|token| token.depth < 0 || (token.depth == 0 && token.is_symbol(';')))
+        .map_or(statement.len(), |token| token.offset)
+}
+
+/// Words of `statement` outside parentheses, with their byte offsets
+fn top_level_words(statement: &str) -> impl Iterator<Item = (usize, &str)> {
+    lex(statement)
+        .filter(|token| token.depth == 0)
+        .filter_map(|token| Some((token.offset, token.word()?)))
+}
+
+/// The statement keyword of `statement` and its offset, after any prefix
+/// like a WITH clause
+fn leading_keyword(statement: &str) -> Option<(&'static str, usize)> {
+    let mut words = top_level_words(statement);
+    let (mut at, mut word) = words.next()?;
+    let prefixed = is_one_of(word, PREFIX_KEYWORDS);
+    loop {
+        if let Some(keyword) = STATEMENT_KEYWORDS
+            .iter()
+            .find(|k| word.eq_ignore_ascii_case(k))
+        {
+            return Some((keyword, at));
+        }
+        if !prefixed {
+            return None;
+        }
+        (at, word) = words.next()?;
+    }
+}
+
+/// Byte offset of `keyword` as a top-level word of `statement`, from byte
+/// `from`
+fn top_level_keyword(statement: &str, keyword: &str, from: usize) -> Option<usize> {
+    top_level_words(&statement[from..])
+        .find(|(_, word)| word.eq_ignore_ascii_case(keyword))
+        .map(|(at, _)| from + at)
+}
+
+/// Offset of the first of `keywords` after `from`, or the end of
+/// `statement`
+fn first_keyword(statement: &str, keywords: &[&str], from: usize) -> usize {
+    keywords
+        .iter()
+        .filter_map(|keyword| top_level_keyword(statement, keyword, from))
+        .min()
+        .unwrap_or(statement.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(name: &str) -> TableRefInfo {
+        TableRefInfo::new(name)
+    }
+
+    #[test]
+    fn test_statement_tables() {
+        let cases: &[(&str, Vec<TableRefInfo>)] = &[
+            // Single tables and aliases
+            ("SELECT * FROM users", vec![table("users")]),
+            (
+                "SELECT * FROM users u",
+                vec![table("users").with_alias("u")],
+            ),
+            (
+                "SELECT * FROM users AS u WHERE u.id = 1",
+                vec![table("users").with_alias("u")],
+            ),
+            ("select * from users where ", vec![table("users")]),
+            ("SELECT * FROM users\nWHERE id = 1", vec![table("users")]),
+            ("SELECT * FROM ", vec![]),
+            ("SELECT 1", vec![]),
+            // Comma joins
+            (
+                "SELECT * FROM users, orders",
+                vec![table("users"), table("orders")],
+            ),
+            (
+                "SELECT * FROM users u, orders o WHERE ",
+                vec![
+                    table("users").with_alias("u"),
+                    table("orders").with_alias("o"),
+                ],
+            ),
+            (
+                "SELECT * FROM users AS u,orders AS o",
+                vec![
+                    table("users").with_alias("u"),
+                    table("orders").with_alias("o"),
+                ],
+            ),
+            ("SELECT * FROM users, ", vec![table("users")]),
+            // Join styles
+            (
+                "SELECT * FROM users JOIN orders ON users.id = orders.user_id",
+                vec![table("users"), table("orders")],
+            ),
+            (
+                "SELECT * FROM users u LEFT OUTER JOIN orders o ON u.id = o.user_id \
+                 RIGHT JOIN items i ON i.order_id = o.id",
+                vec![
+                    table("users").with_alias("u"),
+                    table("orders").with_alias("o"),
+                    table("items").with_alias("i"),
+                ],
+            ),
+            (
+                "SELECT * FROM users INNER JOIN orders USING (id) CROSS JOIN items",
+                vec![table("users"), table("orders"), table("items")],
+            ),
+            (
+                "SELECT * FROM users NATURAL JOIN orders FULL JOIN items ON true",
+                vec![table("users"), table("orders"), table("items")],
+            ),
+            (
+                "SELECT * FROM users u STRAIGHT_JOIN orders o ON u.id = o.user_id",
+                vec![
+                    table("users").with_alias("u"),
+                    table("orders").with_alias("o"),
+                ],
+            ),
+            (
+                "SELECT STRAIGHT_JOIN * FROM users, orders",
+                vec![table("users"), table("orders")],
+            ),
+            (
+                "SELECT * FROM users u LEFT JOIN ",
+                vec![table("users").with_alias("u")],
+            ),
+            (
+                "SELECT * FROM users u JOIN orders o ON u.id = o.user_id AND o.total > 0 \
+                 JOIN items ON ",
+                vec![
+                    table("users").with_alias("u"),
+                    table("orders").with_alias("o"),
+                    table("items"),
+                ],
+            ),
+            (
+                "SELECT * FROM users u JOIN LATERAL (SELECT * FROM orders) o ON true",
+                vec![table("users").with_alias("u")],
+            ),
+            (
+                "SELECT * FROM users USE INDEX (idx_email) JOIN orders FORCE INDEX (idx_user)",
+                vec![table("users"), table("orders")],
+            ),
+            // Schema qualifiers
+            (
+                "SELECT * FROM app.users u",
+                vec![table("users").with_schema("app").with_alias("u")],
+            ),
+            (
+                "SELECT * FROM app.users JOIN billing.invoices AS i ON ",
+                vec![
+                    table("users").with_schema("app"),
+                    table("invoices").with_schema("billing").with_alias("i"),
+                ],
+            ),
+            (
+                "SELECT * FROM \"App\".\"Users\" \"U\"",
+                vec![
+                    table("\"Users\"")
+                        .with_schema("\"App\"")
+                        .with_alias("\"U\""),
+                ],
+            ),
+            (
+                "SELECT * FROM \"Users\" JOIN users ON ",
+                vec![table("\"Users\""), table("users")],
+            ),
+            (
+                "SELECT * FROM `my db`.`order items`",
+                vec![table("`order items`").with_schema("`my db`")],
+            ),
+            // A qualifier still being typed names no table yet
+            ("SELECT * FROM app.", vec![]),
+            ("SELECT * FROM users, app.", vec![table("users")]),
+            // Trailing semicolons and parentheses
+            ("SELECT * FROM users;", vec![table("users")]),
+            (
+                "SELECT * FROM users u;\nSELECT * FROM orders",
+                vec![table("users").with_alias("u")],
+            ),
+            ("SELECT * FROM orders) sub WHERE x", vec![table("orders")]),
+            (
+                "SELECT * FROM (SELECT * FROM orders) sub, users",
+                vec![table("users")],
+            ),
+            ("SELECT * FROM ONLY users *", vec![table("users")]),
+            // Subqueries, CTEs, set operations and strings
+            (
+                "SELECT (SELECT 1 FROM t) FROM users ORDER BY id",
+                vec![table("users")],
+            ),
+            (
+                "WITH recent AS (SELECT * FROM orders) SELECT * FROM recent r",
+                vec![table("recent").with_alias("r")],
+            ),
+            (
+                "SELECT * FROM users UNION SELECT * FROM admins",
+                vec![table("users")],
+            ),
+            (
+                "SELECT 'x FROM y' FROM users WHERE name = 'a;b'",
+                vec![table("users")],
+            ),
+            (
+                "SELECT * FROM users WHERE updated_at > now()",
+                vec![table("users")],
+            ),
+            (
+                "SELECT 'it''s FROM x' AS s FROM users -- JOIN orders\n",
+                vec![table("users")],
+            ),
+            (
+                "SELECT $$ FROM x; $$ FROM users /* , orders */ u",
+                vec![table("users").with_alias("u")],
+            ),
+            // DML
+            (
+                "INSERT INTO users (name) VALUES ('x')",
+                vec![table("users")],
+            ),
+            (
+                "INSERT IGNORE INTO app.users SELECT * FROM staging",
+                vec![table("users").with_schema("app")],
+            ),
+            ("INSERT INTO ", vec![]),
+            (
+                "UPDATE users u SET u.name = 'x'",
+                vec![table("users").with_alias("u")],
+            ),
+            (
+                "UPDATE users SET name = 'x' FROM orders o WHERE ",
+                vec![table("users"), table("orders").with_alias("o")],
+            ),
+            (
+                "DELETE FROM users AS u USING orders o WHERE u.id = o.user_id",
+                vec![
+                    table("users").with_alias("u"),
+                    table("orders").with_alias("o"),
+                ],
+            ),
+            (
+                "DELETE t1 FROM t1 JOIN t2 ON t1.id = t2.id WHERE ",
+                vec![table("t1"), table("t2")],
+            ),
+            ("GRANT SELECT ON users TO app", vec![]),
+            (
+                "CREATE TABLE report AS SELECT * FROM orders",
+                vec![table("orders")],
+            ),
+        ];
+
+        for (statement, expected) in cases {
+            assert_eq!(
+                &TableExtractor::statement_tables(statement),
+                expected,
+                "{}",
+                statement
+            );
+        }
+    }
+
+    #[test]
+    fn test_table_ref_names() {
+        let aliased = table("users").with_schema("app").with_alias("u");
+        assert_eq!(aliased.qualified_name(), "app.users");
+        assert_eq!(aliased.reference(), "u");
+        assert_eq!(table("users").with_schema("app").reference(), "app.users");
+
+        assert_eq!(
+            TableExtractor::table_list(" users u, orders WHERE id = 1"),
+            vec![table("users").with_alias("u"), table("orders")]
+        );
+    }
+}
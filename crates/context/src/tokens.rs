@@ -0,0 +1,351 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # SQL Lexer
+//!
+//! Scanning of SQL text without a syntax tree, shared by every text-based
+//! analysis so they agree on what is code and what is a string or comment.
+//!
+//! The lexer follows the grammar's lexical rules for all dialects:
+//!
+//! - `'...'` strings with `''` escapes, and PostgreSQL `$tag$...$tag$` bodies
+//! - `"..."`, `` `...` `` and `[...]` quoted identifiers, with doubled
+//!   delimiters as escapes
+//! - `--` and `#` line comments, `/* ... */` block comments
+//!
+//! Unterminated strings, identifiers and comments run to the end of the text.
+
+use std::ops::Range;
+
+/// Classification of a [`Token`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Keyword, identifier, number or `$n` parameter
+    Word,
+    /// Delimited identifier, including its delimiters
+    QuotedIdentifier,
+    /// String literal, including its quotes or dollar tags
+    String,
+    /// Line or block comment
+    Comment,
+    /// `(`
+    OpenParen,
+    /// `)`
+    CloseParen,
+    /// Any other single character
+    Symbol,
+}
+
+/// A token of SQL text, see [`lex`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// What the token is
+    pub kind: TokenKind,
+    /// Byte offset in the scanned text
+    pub offset: usize,
+    /// The token as written
+    pub text: &'a str,
+    /// Parentheses open at the token, relative to the start of the text;
+    /// a closing parenthesis is at the depth after it
+    pub depth: isize,
+}
+
+impl<'a> Token<'a> {
+    /// The word, for a word token
+    pub fn word(&self) -> Option<&'a str> {
+        (self.kind == TokenKind::Word).then_some(self.text)
+    }
+
+    /// `Some(true)` for `(`, `Some(false)` for `)`
+    pub fn paren(&self) -> Option<bool> {
+        match self.kind {
+            TokenKind::OpenParen => Some(true),
+            TokenKind::CloseParen => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Whether the token is the symbol `symbol`
+    pub fn is_symbol(&self, symbol: char) -> bool {
+        self.kind == TokenKind::Symbol && self.text.starts_with(symbol)
+    }
+
+    /// Byte offset just past the token
+    pub fn end(&self) -> usize {
+        self.offset + self.text.len()
+    }
+
+    /// Whether a string, quoted identifier or block comment has its closing
+    /// delimiter; other tokens always do
+    pub fn is_closed(&self) -> bool {
+        let bytes = self.text.as_bytes();
+        match (self.kind, bytes[0]) {
+            (TokenKind::String, b'$') => dollar_tag(bytes, 0)
+                .is_some_and(|tag| bytes.len() >= 2 * tag.len() && self.text.ends_with(tag)),
+            (TokenKind::String | TokenKind::QuotedIdentifier, quote @ (b'\'' | b'"' | b'`')) => {
+                closing_quote(bytes, 0, quote).is_some()
+            }
+            (TokenKind::QuotedIdentifier, _) => self.text.ends_with(']'),
+            (TokenKind::Comment, b'/') => bytes.len() >= 4 && self.text.ends_with("*/"),
+            _ => true,
+        }
+    }
+}
+
+/// Every token of SQL text except whitespace
+pub fn lex(text: &str) -> impl Iterator<Item = Token<'_>> {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    let mut depth = 0isize;
+    std::iter::from_fn(move || {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i >= bytes.len() {
+            return None;
+        }
+
+        let start = i;
+        let next = bytes.get(i + 1).copied();
+        let (kind, end) = match bytes[i] {
+            b'(' => (TokenKind::OpenParen, i + 1),
+            b')' => (TokenKind::CloseParen, i + 1),
+            b'\'' => (
+                TokenKind::String,
+                closing_quote(bytes, i, b'\'').unwrap_or(bytes.len()),
+            ),
+            quote @ (b'"' | b'`') => (
+                TokenKind::QuotedIdentifier,
+                closing_quote(bytes, i, quote).unwrap_or(bytes.len()),
+            ),
+            b'[' if next.is_some_and(|b| b != b']') => (
+                TokenKind::QuotedIdentifier,
+                find(text, i + 1, "]").map_or(bytes.len(), |close| close + 1),
+            ),
+            b'-' if next == Some(b'-') => (TokenKind::Comment, line_end(text, i)),
+            b'#' => (TokenKind::Comment, line_end(text, i)),
+            b'/' if next == Some(b'*') => (
+                TokenKind::Comment,
+                find(text, i + 2, "*/").map_or(bytes.len(), |close| close + 2),
+            ),
+            b'$' => match dollar_tag(bytes, i) {
+                Some(tag) => {
+                    let body = i + tag.len();
+                    let end = find(text, body, tag).map_or(bytes.len(), |close| close + tag.len());
+                    (TokenKind::String, end)
+                }
+                None if next.is_some_and(|b| b.is_ascii_digit()) => {
+                    (TokenKind::Word, word_end(bytes, i + 1))
+                }
+                None => (TokenKind::Symbol, i + 1),
+            },
+            b if is_word_byte(b) => (TokenKind::Word, word_end(bytes, i)),
+            _ => (TokenKind::Symbol, i + 1),
+        };
+        i = end;
+
+        let token_depth = match kind {
+            TokenKind::OpenParen => {
+                depth += 1;
+                depth - 1
+            }
+            TokenKind::CloseParen => {
+                depth -= 1;
+                depth
+            }
+            _ => depth,
+        };
+        Some(Token {
+            kind,
+            offset: start,
+            text: &text[start..end],
+            depth: token_depth,
+        })
+    })
+}
+
+/// Words and parentheses of SQL text, skipping everything else
+///
+/// Words keep the case of the text; callers scanning for keywords pass
+/// uppercased text.
+pub fn tokens(text: &str) -> impl Iterator<Item = Token<'_>> {
+    lex(text).filter(|token| {
+        matches!(
+            token.kind,
+            TokenKind::Word | TokenKind::OpenParen | TokenKind::CloseParen
+        )
+    })
+}
+
+/// `text` with the tokens of `kinds` blanked out
+///
+/// See [`mask_ranges`] for how the text is blanked.
+pub fn mask(text: &str, kinds: &[TokenKind]) -> String {
+    mask_ranges(
+        text,
+        lex(text)
+            .filter(|token| kinds.contains(&token.kind))
+            .map(|token| token.offset..token.end()),
+    )
+}
+
+/// `text` with the byte `ranges` blanked out
+///
+/// Every character in a range becomes spaces (one per byte) except
+/// newlines, so byte offsets and lines found in the result apply to `text`.
+/// Ranges are in order and don't overlap.
+pub fn mask_ranges(text: &str, ranges: impl IntoIterator<Item = Range<usize>>) -> String {
+    let mut masked = String::with_capacity(text.len());
+    let mut copied = 0;
+    for range in ranges {
+        masked.push_str(&text[copied..range.start]);
+        for c in text[range.clone()].chars() {
+            if c == '\n' {
+                masked.push('\n');
+            } else {
+                masked.extend(std::iter::repeat_n(' ', c.len_utf8()));
+            }
+        }
+        copied = range.end;
+    }
+    masked.push_str(&text[copied..]);
+    masked
+}
+
+fn is_word_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b >= 0x80
+}
+
+fn word_end(bytes: &[u8], start: usize) -> usize {
+    // `$` continues a word (MySQL and PostgreSQL allow it in identifiers)
+    bytes[start..]
+        .iter()
+        .position(|&b| !(is_word_byte(b) || b == b'$'))
+        .map_or(bytes.len(), |p| start + p)
+}
+
+/// Offset just past the quote closing a span opened at `start`, where a
+/// doubled `quote` escapes it
+fn closing_quote(bytes: &[u8], start: usize, quote: u8) -> Option<usize> {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        if bytes[i] == quote {
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+            return Some(i + 1);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// The `$tag$` opening a dollar-quoted string at `start`, if there is one
+fn dollar_tag(bytes: &[u8], start: usize) -> Option<&str> {
+    let mut i = start + 1;
+    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+        // A tag can't start with a digit; `$1` is a parameter
+        if i == start + 1 && bytes[i].is_ascii_digit() {
+            return None;
+        }
+        i += 1;
+    }
+    (bytes.get(i) == Some(&b'$')).then(|| std::str::from_utf8(&bytes[start..=i]).ok())?
+}
+
+fn find(text: &str, from: usize, pattern: &str) -> Option<usize> {
+    text[from..].find(pattern).map(|found| from + found)
+}
+
+fn line_end(text: &str, start: usize) -> usize {
+    find(text, start, "\n").unwrap_or(text.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<(TokenKind, &str)> {
+        lex(text).map(|token| (token.kind, token.text)).collect()
+    }
+
+    #[test]
+    fn test_lex_strings_and_identifiers() {
+        assert_eq!(
+            kinds(r#"SELECT 'it''s', "a""b", `c`, [d e] FROM t"#),
+            vec![
+                (TokenKind::Word, "SELECT"),
+                (TokenKind::String, "'it''s'"),
+                (TokenKind::Symbol, ","),
+                (TokenKind::QuotedIdentifier, r#""a""b""#),
+                (TokenKind::Symbol, ","),
+                (TokenKind::QuotedIdentifier, "`c`"),
+                (TokenKind::Symbol, ","),
+                (TokenKind::QuotedIdentifier, "[d e]"),
+                (TokenKind::Word, "FROM"),
+                (TokenKind::Word, "t"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_dollar_quotes_and_parameters() {
+        assert_eq!(
+            kinds("AS $fn$ SELECT ';' $$ $fn$ WHERE id = $1"),
+            vec![
+                (TokenKind::Word, "AS"),
+                (TokenKind::String, "$fn$ SELECT ';' $$ $fn$"),
+                (TokenKind::Word, "WHERE"),
+                (TokenKind::Word, "id"),
+                (TokenKind::Symbol, "="),
+                (TokenKind::Word, "$1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_comments_and_depth() {
+        let tokens: Vec<_> = lex("f(a) -- x)\n# y\n/* ( */ )").collect();
+        let comments: Vec<_> = tokens
+            .iter()
+            .filter(|token| token.kind == TokenKind::Comment)
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(comments, vec!["-- x)", "# y", "/* ( */"]);
+
+        let depths: Vec<_> = tokens
+            .iter()
+            .filter(|token| token.kind != TokenKind::Comment)
+            .map(|token| (token.text, token.depth))
+            .collect();
+        assert_eq!(
+            depths,
+            vec![("f", 0), ("(", 0), ("a", 1), (")", 0), (")", -1)]
+        );
+    }
+
+    #[test]
+    fn test_unclosed_tokens() {
+        let closed = |text: &str| lex(text).last().unwrap().is_closed();
+        assert!(closed("'it''s'"));
+        assert!(!closed("'it''"));
+        assert!(!closed("'"));
+        assert!(closed("$fn$ a $fn$"));
+        assert!(!closed("$fn$ a $"));
+        assert!(!closed("\"a"));
+        assert!(!closed("[a"));
+        assert!(!closed("/* a"));
+        assert!(closed("-- a"));
+    }
+
+    #[test]
+    fn test_mask_preserves_offsets() {
+        let text = "SELECT 'a;b', \"é\" -- c;\nFROM t";
+        let masked = mask(text, &[TokenKind::String, TokenKind::Comment]);
+        assert_eq!(masked.len(), text.len());
+        assert_eq!(masked, "SELECT      , \"é\"      \nFROM t");
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! Context detection over the e2e suites
+//!
+//! Every completion case of the YAML suites under `tests/e2e-rs/tests` is
+//! parsed with the grammar of its engine version, and the detected context
+//! is compared with the snapshot of its suite in `tests/snapshots/e2e/`.
+//! This catches detection regressions without Docker, and the snapshots
+//! document how each case is currently understood.
+//!
+//! Snapshots of new suites are recorded on the first run. After an
+//! intended detection change, record them again with `UPDATE_SNAPSHOTS=1`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tree_sitter::Parser;
+use unified_sql_grammar::{DialectVersion, language_for_dialect_with_version};
+use unified_sql_lsp_context::{Position, detect_completion_context};
+use unified_sql_lsp_ir::Dialect;
+use unified_sql_lsp_test_utils::{E2eCase, E2eSuite, e2e_suites_dir, load_e2e_suites};
+
+fn snapshots_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots/e2e")
+}
+
+/// The case's SQL with `|` at the cursor
+fn marked_sql(case: &E2eCase) -> String {
+    let mut offset = 0;
+    for (line, text) in case.sql.split_inclusive('\n').enumerate() {
+        if line as u32 == case.cursor.line {
+            offset += text
+                .char_indices()
+                .nth(case.cursor.character as usize)
+                .map_or(text.len(), |(i, _)| i);
+            break;
+        }
+        offset += text.len();
+    }
+    let offset = offset.min(case.sql.len());
+    format!("{}|{}", &case.sql[..offset], &case.sql[offset..])
+}
+
+/// Snapshot of a suite: each case's marked SQL and detected context
+fn render_suite(
+    suite: &E2eSuite,
+    parsers: &mut HashMap<(Dialect, Option<String>), Parser>,
+) -> String {
+    let mut snapshot = format!("# {}\n", suite.name);
+    for case in &suite.cases {
+        let parser = parsers
+            .entry((case.dialect, suite.version.clone()))
+            .or_insert_with(|| {
+                let version = suite.version.as_deref().and_then(DialectVersion::parse);
+                let language = language_for_dialect_with_version(case.dialect, version)
+                    .expect("grammar for the suite's engine");
+                let mut parser = Parser::new();
+                parser.set_language(language).expect("grammar loads");
+                parser
+            });
+        let tree = parser
+            .parse(&case.sql, None)
+            .expect("parser returns a tree");
+        let ctx = detect_completion_context(
+            &tree.root_node(),
+            Position::new(case.cursor.line, case.cursor.character),
+            &case.sql,
+        );
+
+        snapshot.push_str(&format!(
+            "\n## {}\n{}\n-> {:?}\n",
+            case.name,
+            marked_sql(case),
+            ctx
+        ));
+    }
+    snapshot
+}
+
+#[test]
+fn test_e2e_case_contexts_match_snapshots() {
+    let root = e2e_suites_dir();
+    let suites = load_e2e_suites(&root).unwrap();
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+    let mut parsers = HashMap::new();
+
+    let mut changed = Vec::new();
+    for suite in suites.iter().filter(|s| !s.cases.is_empty()) {
+        let actual = render_suite(suite, &mut parsers);
+        let path = snapshots_dir()
+            .join(suite.path.strip_prefix(&root).unwrap())
+            .with_extension("snap");
+
+        match fs::read_to_string(&path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) if !update => changed.push(format!(
+                "{}\n--- expected\n{}\n--- actual\n{}",
+                path.display(),
+                expected,
+                actual
+            )),
+            _ => {
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(&path, actual).unwrap();
+            }
+        }
+    }
+
+    assert!(
+        changed.is_empty(),
+        "{} snapshot(s) changed; rerun with UPDATE_SNAPSHOTS=1 if intended:\n{}",
+        changed.len(),
+        changed.join("\n")
+    );
+}
+
+#[test]
+fn test_marked_sql_restores_cursor() {
+    let case = E2eCase {
+        name: "multi-line".to_string(),
+        dialect: Dialect::PostgreSQL,
+        sql: "SELECT *\nFROM users u\nWHERE u.".to_string(),
+        cursor: lsp_types::Position::new(2, 8),
+    };
+    assert_eq!(marked_sql(&case), "SELECT *\nFROM users u\nWHERE u.|");
+}
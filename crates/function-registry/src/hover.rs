@@ -18,14 +18,20 @@
 //! ```
 
 use crate::{Dialect, FunctionRegistry};
-use unified_sql_lsp_ir::DataType;
+use unified_sql_lsp_ir::{DataType, FunctionType};
 
 /// Column metadata needed for hover information
 pub struct ColumnHoverInfo {
     pub name: String,
+    /// Table the column belongs to, shown as `table.column` when known
+    pub table: Option<String>,
     pub data_type: DataType,
+    pub nullable: bool,
+    pub comment: Option<String>,
     pub is_primary_key: bool,
     pub is_foreign_key: bool,
+    /// Referenced `table.column` of a foreign key
+    pub references: Option<String>,
 }
 
 /// Hover information provider for SQL completion
@@ -45,6 +51,13 @@ impl HoverInfoProvider {
         }
     }
 
+    /// Look functions up in `registry`, e.g. one with catalog functions
+    /// layered over the builtins
+    pub fn with_function_registry(mut self, registry: FunctionRegistry) -> Self {
+        self.function_registry = registry;
+        self
+    }
+
     /// Get hover information for a SQL function
     ///
     /// # Arguments
@@ -71,7 +84,11 @@ impl HoverInfoProvider {
             .iter()
             .find(|f| f.name.eq_ignore_ascii_case(name))?;
 
-        let desc = func.description.as_deref().unwrap_or("SQL function");
+        let fallback = match &func.schema {
+            Some(schema) if !func.is_builtin => format!("User-defined function in `{}`", schema),
+            _ => "SQL function".to_string(),
+        };
+        let desc = func.description.as_deref().unwrap_or(&fallback);
         let params: Vec<String> = func.parameters.iter().map(|p| p.name.clone()).collect();
         let params_str = if params.is_empty() {
             String::new()
@@ -92,6 +109,61 @@ impl HoverInfoProvider {
         ))
     }
 
+    /// Get hover information for an aggregate function call
+    ///
+    /// Adds the return type and how the call groups rows to the function
+    /// hover.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Function name (case-insensitive)
+    /// * `dialect` - SQL dialect
+    /// * `group_by` - Expressions of the statement's GROUP BY, `None` without one
+    ///
+    /// # Returns
+    ///
+    /// Markdown-formatted hover text, or None if `name` is not an aggregate
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use unified_sql_lsp_function_registry::HoverInfoProvider;
+    /// # use unified_sql_lsp_ir::Dialect;
+    /// let provider = HoverInfoProvider::new();
+    /// let info = provider.get_aggregate_hover("SUM", &Dialect::MySQL, None).unwrap();
+    /// assert!(info.contains("aggregates over all rows"));
+    /// assert!(provider.get_aggregate_hover("UPPER", &Dialect::MySQL, None).is_none());
+    /// ```
+    pub fn get_aggregate_hover(
+        &self,
+        name: &str,
+        dialect: &Dialect,
+        group_by: Option<&[String]>,
+    ) -> Option<String> {
+        let func = self.function_registry.get_function(*dialect, name)?;
+        if func.function_type != FunctionType::Aggregate {
+            return None;
+        }
+
+        let mut detail = self.get_function_hover(name, dialect)?;
+        detail.push_str(&format!(
+            "\n\nReturns: {}",
+            self.format_data_type(&func.return_type)
+        ));
+        match group_by {
+            Some(columns) if !columns.is_empty() => {
+                let columns: Vec<String> = columns.iter().map(|c| format!("`{}`", c)).collect();
+                detail.push_str(&format!(
+                    "\n\nOne value per group of GROUP BY {}",
+                    columns.join(", ")
+                ));
+            }
+            _ => detail.push_str("\n\nNo GROUP BY: aggregates over all rows"),
+        }
+
+        Some(detail)
+    }
+
     /// Get hover information for a column
     ///
     /// # Arguments
@@ -102,17 +174,53 @@ impl HoverInfoProvider {
     ///
     /// Markdown-formatted hover text with column type
     pub fn get_column_hover(&self, column_info: &ColumnHoverInfo) -> String {
+        let name = match &column_info.table {
+            Some(table) => format!("{}.{}", table, column_info.name),
+            None => column_info.name.clone(),
+        };
         let mut detail = format!(
-            "```sql\n{}\n```\n\nColumn type: {}",
-            column_info.name,
-            self.format_data_type(&column_info.data_type)
+            "```sql\n{}\n```\n\nColumn type: {} {}",
+            name,
+            self.format_data_type(&column_info.data_type),
+            if column_info.nullable {
+                "NULL"
+            } else {
+                "NOT NULL"
+            }
         );
 
+        if let Some(comment) = &column_info.comment {
+            detail.push_str(&format!("\n\n{}", comment));
+        }
         if column_info.is_primary_key {
             detail.push_str("\n\n**Primary Key**");
         }
         if column_info.is_foreign_key {
             detail.push_str("\n\n**Foreign Key**");
+            if let Some(references) = &column_info.references {
+                detail.push_str(&format!(" → `{}`", references));
+            }
+        }
+
+        detail
+    }
+
+    /// Get hover information for an unqualified column found in several tables
+    ///
+    /// Lists every candidate table instead of picking one.
+    pub fn get_ambiguous_column_hover(&self, name: &str, candidates: &[ColumnHoverInfo]) -> String {
+        let mut detail = format!(
+            "```sql\n{}\n```\n\nAmbiguous column, found in {} tables:\n",
+            name,
+            candidates.len()
+        );
+
+        for candidate in candidates {
+            detail.push_str(&format!(
+                "\n- `{}`: {}",
+                candidate.table.as_deref().unwrap_or("?"),
+                self.format_data_type(&candidate.data_type)
+            ));
         }
 
         detail
@@ -228,6 +336,41 @@ mod tests {
         assert!(info.unwrap().contains("COUNT"));
     }
 
+    #[test]
+    fn test_aggregate_hover_grouped() {
+        let provider = HoverInfoProvider::new();
+        let group_by = vec!["country".to_string(), "DATE(created_at)".to_string()];
+        let info = provider
+            .get_aggregate_hover("count", &Dialect::MySQL, Some(&group_by))
+            .unwrap();
+        assert!(info.starts_with("```sql\nCOUNT"));
+        assert!(info.contains("Returns: BIGINT"));
+        assert!(info.contains("One value per group of GROUP BY `country`, `DATE(created_at)`"));
+        assert!(!info.contains("all rows"));
+    }
+
+    #[test]
+    fn test_aggregate_hover_ungrouped() {
+        let provider = HoverInfoProvider::new();
+        let info = provider
+            .get_aggregate_hover("SUM", &Dialect::PostgreSQL, None)
+            .unwrap();
+        assert!(info.contains("Returns:"));
+        assert!(info.contains("No GROUP BY: aggregates over all rows"));
+
+        // Scalar functions and unknown names get no aggregate hover
+        assert!(
+            provider
+                .get_aggregate_hover("UPPER", &Dialect::MySQL, None)
+                .is_none()
+        );
+        assert!(
+            provider
+                .get_aggregate_hover("NOTAREALFUNCTION", &Dialect::MySQL, None)
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_is_function() {
         let provider = HoverInfoProvider::new();
@@ -259,13 +402,58 @@ mod tests {
         let provider = HoverInfoProvider::new();
         let column_info = ColumnHoverInfo {
             name: "id".to_string(),
+            table: None,
             data_type: DataType::Integer,
+            nullable: false,
+            comment: None,
             is_primary_key: true,
             is_foreign_key: false,
+            references: None,
         };
         let info = provider.get_column_hover(&column_info);
         assert!(info.contains("id"));
         assert!(info.contains("INT"));
         assert!(info.contains("Primary Key"));
     }
+
+    fn order_column(name: &str, table: &str) -> ColumnHoverInfo {
+        ColumnHoverInfo {
+            name: name.to_string(),
+            table: Some(table.to_string()),
+            data_type: DataType::BigInt,
+            nullable: false,
+            comment: None,
+            is_primary_key: false,
+            is_foreign_key: false,
+            references: None,
+        }
+    }
+
+    #[test]
+    fn test_qualified_column_hover() {
+        let provider = HoverInfoProvider::new();
+        let column_info = ColumnHoverInfo {
+            comment: Some("Owner of the order".to_string()),
+            is_foreign_key: true,
+            references: Some("users.id".to_string()),
+            ..order_column("user_id", "orders")
+        };
+
+        assert_eq!(
+            provider.get_column_hover(&column_info),
+            "```sql\norders.user_id\n```\n\nColumn type: BIGINT NOT NULL\n\n\
+             Owner of the order\n\n**Foreign Key** → `users.id`"
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_column_hover() {
+        let provider = HoverInfoProvider::new();
+        let candidates = [order_column("id", "users"), order_column("id", "orders")];
+
+        let info = provider.get_ambiguous_column_hover("id", &candidates);
+        assert!(info.contains("Ambiguous column, found in 2 tables"));
+        assert!(info.contains("- `users`: BIGINT"));
+        assert!(info.contains("- `orders`: BIGINT"));
+    }
 }
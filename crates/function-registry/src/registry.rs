@@ -10,6 +10,9 @@ use std::collections::HashMap;
 ///
 /// This struct stores and provides lookup for builtin functions
 /// across different SQL dialects.
+///
+/// Functions read from a database catalog can be layered over the builtins
+/// with [`FunctionRegistry::with_catalog_functions`].
 #[derive(Debug, Clone)]
 pub struct FunctionRegistry {
     /// Functions organized by dialect
@@ -43,6 +46,57 @@ impl FunctionRegistry {
         registry
     }
 
+    /// Layer functions read from a database catalog over the builtins
+    ///
+    /// Functions are deduplicated by name (case-insensitive) and arity. When
+    /// a catalog function matches a builtin, the catalog entry wins, keeping
+    /// the builtin's documentation only where the catalog has none. Catalog
+    /// functions without a builtin counterpart are added after the builtins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use unified_sql_lsp_function_registry::FunctionRegistry;
+    /// # use unified_sql_lsp_ir::{DataType, Dialect, FunctionMetadata};
+    /// let udf = FunctionMetadata::new("order_total", DataType::Decimal)
+    ///     .user_defined("public")
+    ///     .with_description("Sum of the line items of an order");
+    /// let registry = FunctionRegistry::new().with_catalog_functions(Dialect::PostgreSQL, vec![udf]);
+    /// assert!(registry.has_function(Dialect::PostgreSQL, "ORDER_TOTAL"));
+    /// ```
+    pub fn with_catalog_functions(
+        mut self,
+        dialect: Dialect,
+        functions: impl IntoIterator<Item = FunctionMetadata>,
+    ) -> Self {
+        let merged = self.functions.entry(dialect).or_default();
+        let builtin_count = merged.len();
+
+        for function in functions {
+            let existing = merged.iter().position(|f| {
+                f.name.eq_ignore_ascii_case(&function.name) && f.arity() == function.arity()
+            });
+            match existing {
+                // Builtin counterpart: the catalog entry wins
+                Some(index) if index < builtin_count => {
+                    let builtin = std::mem::replace(&mut merged[index], function);
+                    let entry = &mut merged[index];
+                    if entry.description.is_none() {
+                        entry.description = builtin.description;
+                    }
+                    if entry.example.is_none() {
+                        entry.example = builtin.example;
+                    }
+                }
+                // The same routine listed twice: the first one wins
+                Some(_) => {}
+                None => merged.push(function),
+            }
+        }
+
+        self
+    }
+
     /// Get all functions for a specific dialect
     ///
     /// # Arguments
@@ -141,6 +195,79 @@ mod tests {
         assert!(mixed.is_some());
     }
 
+    #[test]
+    fn test_catalog_functions_override_builtins() {
+        use unified_sql_lsp_ir::DataType;
+
+        let builtins = FunctionRegistry::new();
+        let builtin_count = builtins.get_functions(Dialect::PostgreSQL).len();
+        let upper = builtins
+            .get_function(Dialect::PostgreSQL, "UPPER")
+            .unwrap()
+            .clone();
+
+        let registry = FunctionRegistry::new().with_catalog_functions(
+            Dialect::PostgreSQL,
+            vec![
+                // Same name and arity as the builtin, with a routine comment
+                FunctionMetadata::new("upper", DataType::Text)
+                    .with_parameters(upper.parameters.clone())
+                    .user_defined("util")
+                    .with_description("Uppercase with locale rules"),
+                // A new routine
+                FunctionMetadata::new("order_total", DataType::Decimal).user_defined("public"),
+                // Listed again from another schema
+                FunctionMetadata::new("order_total", DataType::Integer).user_defined("archive"),
+            ],
+        );
+
+        let functions = registry.get_functions(Dialect::PostgreSQL);
+        assert_eq!(functions.len(), builtin_count + 1);
+
+        let merged = registry.get_function(Dialect::PostgreSQL, "UPPER").unwrap();
+        assert_eq!(merged.schema.as_deref(), Some("util"));
+        assert_eq!(
+            merged.description.as_deref(),
+            Some("Uppercase with locale rules")
+        );
+        // Documentation the catalog lacks comes from the builtin
+        assert_eq!(merged.example, upper.example);
+
+        let udf = registry
+            .get_function(Dialect::PostgreSQL, "order_total")
+            .unwrap();
+        assert_eq!(udf.schema.as_deref(), Some("public"));
+        assert!(!udf.is_builtin);
+
+        // Other dialects are untouched
+        assert!(!registry.has_function(Dialect::MySQL, "order_total"));
+    }
+
+    #[test]
+    fn test_catalog_function_with_other_arity_is_kept() {
+        use unified_sql_lsp_ir::DataType;
+
+        let builtin_count = FunctionRegistry::new().get_functions(Dialect::MySQL).len();
+        let registry = FunctionRegistry::new().with_catalog_functions(
+            Dialect::MySQL,
+            vec![
+                FunctionMetadata::new("upper", DataType::Text)
+                    .with_parameters(vec![crate::FunctionParameter {
+                        name: "value".to_string(),
+                        data_type: DataType::Text,
+                        has_default: false,
+                        is_variadic: false,
+                    }])
+                    .user_defined("app")
+                    .with_description("One-argument overload"),
+            ],
+        );
+
+        let functions = registry.get_functions(Dialect::MySQL);
+        assert_eq!(functions.len(), builtin_count + 1);
+        assert!(functions.iter().any(|f| f.is_builtin && f.name == "UPPER"));
+    }
+
     #[test]
     fn test_has_function() {
         let registry = FunctionRegistry::new();
@@ -10,6 +10,7 @@
  * - LIMIT offset, count syntax
  * - Backtick identifiers
  * - SQL_CALC_FOUND_ROWS, SQL_CACHE, SQL_NO_CACHE
+ * - JSON path operators (-> and ->>)
  */
 
 module.exports = {
@@ -24,6 +25,9 @@ module.exports = {
     $.insert_statement,
     $.update_statement,
     $.delete_statement,
+    $.create_table_statement,
+    $.create_view_statement,
+    $.alter_table_statement,
     $.replace_statement  // MySQL-specific REPLACE statement
   ),
 
@@ -61,6 +65,31 @@ module.exports = {
     seq('LIMIT', $.expression, ',', $.expression)  // MySQL-specific syntax
   ),
 
+  // =============================================================================
+  // Expression Extensions
+  // =============================================================================
+
+  // Override expression to add JSON path access
+  expression: $ => choice(
+    $.binary_expression,
+    $.unary_expression,
+    $.column_reference,
+    $.literal,
+    $.function_call,
+    $.case_expression,
+    $.cast_expression,
+    $.json_path_expression,  // MySQL-specific JSON path access
+    $.parenthesized_expression,
+    '*'
+  ),
+
+  // column->'$.path' extracts a JSON value, column->>'$.path' also unquotes it
+  json_path_expression: $ => prec.left(3, seq(
+    field('expression', $.expression),
+    field('operator', choice('->', '->>')),
+    field('path', $.string_literal)
+  )),
+
   // =============================================================================
   // Identifier Extensions
   // =============================================================================
@@ -8,8 +8,12 @@
  * Features in this dialect:
  * - RETURNING clause
  * - DEFAULT VALUES
+ * - INSERT ... SELECT and ON CONFLICT (upsert)
  * - Dollar-quoted string literals
  * - Double-quote identifiers
+ * - :: type casts
+ * - DISTINCT ON
+ * - Window functions (OVER) and named windows (WINDOW clause)
  *
  * Source: PostgreSQL 12 Documentation
  * https://www.postgresql.org/docs/release/12.0/
@@ -28,6 +32,41 @@ module.exports = {
   // DEFAULT VALUES (PostgreSQL-specific INSERT syntax)
   default_values: $ => 'DEFAULT VALUES',
 
+  // INSERT with every source form and an optional upsert clause
+  insert_statement: $ => seq(
+    'INSERT',
+    'INTO',
+    $.table_name,
+    optional(seq(/[Aa][Ss]/, $.alias)),
+    optional($.column_list),
+    choice(
+      seq('VALUES', $.value_list, repeat(seq(',', $.value_list))),
+      $.select_statement,
+      $.default_values
+    ),
+    optional($.on_conflict_clause),
+    optional($.returning_clause)
+  ),
+
+  // ON CONFLICT [(columns)] DO NOTHING | DO UPDATE SET ... [WHERE ...]
+  on_conflict_clause: $ => seq(
+    'ON',
+    'CONFLICT',
+    optional($.column_list),
+    choice($.conflict_do_nothing, $.conflict_do_update)
+  ),
+
+  conflict_do_nothing: $ => seq('DO', 'NOTHING'),
+
+  conflict_do_update: $ => seq(
+    'DO',
+    'UPDATE',
+    'SET',
+    $.assignment,
+    repeat(seq(',', $.assignment)),
+    optional($.where_clause)
+  ),
+
   // Dollar-quoted string literals (PostgreSQL-specific)
   // Format: $$string$$ or $tag$string$tag$
   string_literal: $ => choice(
@@ -35,6 +74,134 @@ module.exports = {
     seq(/\$[A-Za-z0-9_]*\$/, /.*/, /\$[A-Za-z0-9_]*\$/)
   ),
 
+  // Override expression to add :: type casts
+  expression: $ => choice(
+    $.binary_expression,
+    $.unary_expression,
+    $.column_reference,
+    $.literal,
+    $.function_call,
+    $.case_expression,
+    $.cast_expression,
+    $.type_cast_expression,  // PostgreSQL-specific expr::type
+    $.parenthesized_expression,
+    '*'
+  ),
+
+  // SELECT with DISTINCT ON and a WINDOW clause between HAVING and ORDER BY
+  select_statement: $ => seq(
+    optional($.cte_clause),
+    'SELECT',
+    optional(choice($.distinct_on_clause, $.set_quantifier)),
+    $.projection,
+    optional($.from_clause),
+    optional($.where_clause),
+    optional($.group_by_clause),
+    optional($.having_clause),
+    optional($.window_clause),
+    optional($.order_by_clause),
+    optional($.limit_clause)
+  ),
+
+  // DISTINCT ON (expr, ...) keeps the first row of each distinct group
+  distinct_on_clause: $ => seq(
+    'DISTINCT',
+    'ON',
+    '(',
+    $.expression,
+    repeat(seq(',', $.expression)),
+    ')'
+  ),
+
+  // func(args) [OVER window_name | OVER (window_specification)]
+  function_call: $ => seq(
+    $.function_name,
+    '(',
+    optional(seq($.expression, repeat(seq(',', $.expression)))),
+    ')',
+    optional($.over_clause)
+  ),
+
+  over_clause: $ => seq(
+    'OVER',
+    choice(
+      $.window_name,
+      seq('(', optional($.window_name), $._window_specification, ')')
+    )
+  ),
+
+  // WINDOW w AS (...) [, w2 AS (...)]
+  window_clause: $ => seq(
+    'WINDOW',
+    $.window_definition,
+    repeat(seq(',', $.window_definition))
+  ),
+
+  window_definition: $ => seq(
+    $.window_name,
+    /[Aa][Ss]/,
+    '(',
+    $._window_specification,
+    ')'
+  ),
+
+  window_name: $ => $.identifier,
+
+  _window_specification: $ => seq(
+    optional($.partition_by),
+    optional($.order_by),
+    optional($.window_frame)
+  ),
+
+  partition_by: $ => seq(
+    'PARTITION',
+    'BY',
+    $.expression,
+    repeat(seq(',', $.expression))
+  ),
+
+  order_by: $ => seq(
+    'ORDER',
+    'BY',
+    $.order_by_item,
+    repeat(seq(',', $.order_by_item))
+  ),
+
+  order_by_item: $ => seq(
+    $.expression,
+    optional(choice('ASC', 'DESC'))
+  ),
+
+  window_frame: $ => seq(
+    $.frame_units,
+    choice(
+      seq('BETWEEN', $.frame_start, 'AND', $.frame_end),
+      $.frame_start
+    )
+  ),
+
+  frame_units: $ => choice('ROWS', 'RANGE', 'GROUPS'),
+
+  frame_start: $ => $._frame_bound,
+
+  frame_end: $ => $._frame_bound,
+
+  _frame_bound: $ => choice(
+    seq('UNBOUNDED', choice('PRECEDING', 'FOLLOWING')),
+    seq('CURRENT', 'ROW'),
+    seq($.literal, choice('PRECEDING', 'FOLLOWING'))
+  ),
+
+  // expr::type, binds tighter than any binary operator
+  type_cast_expression: $ => prec.left(3, seq(
+    field('expression', $.expression),
+    '::',
+    field('type', $.data_type)
+  )),
+
+  // PostgreSQL type names are ordinary identifiers (text, int4, jsonb, ...)
+  type_name: $ => $.identifier,
+
   // PostgreSQL-style identifiers (double-quote notation)
   identifier: $ => choice(
     /[a-zA-Z_][a-zA-Z0-9_]*/,
@@ -7,6 +7,10 @@
  * - SQL-standard SEARCH and CYCLE for CTEs
  * - Multirange data types
  *
+ * The same grammar parses PostgreSQL 15 and 16, so it also carries MERGE
+ * (added in 15). Completion only suggests MERGE where the configured
+ * version supports it.
+ *
  * Source: PostgreSQL 14 Release Notes
  * https://www.postgresql.org/docs/release/14.0/
  */
@@ -76,4 +80,65 @@ module.exports = {
   // Procedure creation with OUT parameters
   // This would be part of CREATE PROCEDURE statement
   // Placeholder for future implementation
+
+  // =============================================================================
+  // PostgreSQL 15-Specific: MERGE
+  // https://www.postgresql.org/docs/release/15.0/
+  // "Add SQL MERGE command"
+  // =============================================================================
+
+  statement: $ => choice(
+    $.select_statement,
+    $.insert_statement,
+    $.update_statement,
+    $.delete_statement,
+    $.create_table_statement,
+    $.create_view_statement,
+    $.alter_table_statement,
+    $.merge_statement,
+    $.values_statement
+  ),
+
+  merge_statement: $ => seq(
+    'MERGE',
+    'INTO',
+    field('target', $.table_reference),
+    'USING',
+    field('source', $.table_reference),
+    'ON',
+    field('on', $.expression),
+    repeat1($.merge_when_clause)
+  ),
+
+  // WHEN [NOT] MATCHED [AND condition] THEN action
+  merge_when_clause: $ => seq(
+    'WHEN',
+    optional(field('not', 'NOT')),
+    'MATCHED',
+    optional(seq('AND', field('condition', $.expression))),
+    'THEN',
+    field('action', choice(
+      $.merge_update,
+      $.merge_delete,
+      $.merge_insert,
+      $.merge_do_nothing
+    ))
+  ),
+
+  merge_update: $ => seq(
+    'UPDATE',
+    'SET',
+    $.assignment,
+    repeat(seq(',', $.assignment))
+  ),
+
+  merge_delete: $ => 'DELETE',
+
+  merge_insert: $ => seq(
+    'INSERT',
+    optional($.column_list),
+    choice(seq('VALUES', $.value_list), $.default_values)
+  ),
+
+  merge_do_nothing: $ => seq('DO', 'NOTHING'),
 };
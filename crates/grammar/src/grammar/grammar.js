@@ -103,7 +103,10 @@ module.exports = grammar({
       $.insert_statement,
       $.update_statement,
       $.delete_statement,
-      $.create_table_statement
+      $.create_table_statement,
+      $.create_view_statement,
+      $.alter_table_statement,
+      $.values_statement
     ),
 
     select_statement: $ => seq(
@@ -169,6 +172,13 @@ module.exports = grammar({
       ')'
     ),
 
+    // Standalone VALUES list: a query of literal rows, e.g. a CTE body
+    values_statement: $ => seq(
+      'VALUES',
+      $.value_list,
+      repeat(seq(',', $.value_list))
+    ),
+
     // =============================================================================
     // UPDATE Statement
     // =============================================================================
@@ -200,26 +210,200 @@ module.exports = grammar({
     ),
 
     // =============================================================================
-    // CREATE TABLE Statement
+    // DDL Statements
     // =============================================================================
 
     create_table_statement: $ => seq(
       'CREATE',
+      optional(field('temporary', choice('TEMPORARY', 'TEMP'))),
       'TABLE',
-      $.table_name,
+      optional($.if_not_exists),
+      field('name', $.object_name),
       '(',
-      $.column_definition,
-      repeat(seq(',', $.column_definition)),
+      $._table_element,
+      repeat(seq(',', $._table_element)),
       optional(','),
-      ')'
+      ')',
+      repeat($.table_option)
+    ),
+
+    create_view_statement: $ => seq(
+      'CREATE',
+      optional(seq('OR', 'REPLACE')),
+      optional(field('materialized', 'MATERIALIZED')),
+      'VIEW',
+      optional($.if_not_exists),
+      field('name', $.object_name),
+      optional(field('columns', $.column_list)),
+      'AS',
+      field('query', $.select_statement)
+    ),
+
+    alter_table_statement: $ => seq(
+      'ALTER',
+      'TABLE',
+      optional(seq('IF', 'EXISTS')),
+      optional('ONLY'),
+      field('name', $.object_name),
+      $.alter_table_action,
+      repeat(seq(',', $.alter_table_action))
+    ),
+
+    // Only additions change the shape of a table as a catalog sees it
+    alter_table_action: $ => seq(
+      'ADD',
+      choice(
+        seq(optional('COLUMN'), $.column_definition),
+        $.table_constraint
+      )
+    ),
+
+    if_not_exists: $ => seq('IF', 'NOT', 'EXISTS'),
+
+    // Table or view name, optionally qualified by its schema
+    object_name: $ => seq(
+      optional(seq(field('schema', $.identifier), '.')),
+      field('name', $.table_name)
+    ),
+
+    _table_element: $ => choice(
+      $.column_definition,
+      $.table_constraint
     ),
 
     column_definition: $ => seq(
-      $.column_name,
-      $.data_type
+      field('name', $.column_name),
+      field('type', $.data_type),
+      repeat($._column_constraint)
+    ),
+
+    _column_constraint: $ => choice(
+      $.not_null_constraint,
+      $.null_constraint,
+      $.primary_key_constraint,
+      $.unique_constraint,
+      $.default_constraint,
+      $.auto_increment_constraint,
+      $.identity_constraint,
+      $.comment_constraint,
+      $.collation_constraint,
+      $.references_clause
+    ),
+
+    not_null_constraint: $ => seq('NOT', 'NULL'),
+
+    null_constraint: $ => 'NULL',
+
+    primary_key_constraint: $ => seq('PRIMARY', 'KEY'),
+
+    unique_constraint: $ => seq('UNIQUE', optional('KEY')),
+
+    default_constraint: $ => seq('DEFAULT', field('value', $.expression)),
+
+    auto_increment_constraint: $ => 'AUTO_INCREMENT',
+
+    identity_constraint: $ => seq(
+      'GENERATED',
+      choice('ALWAYS', seq('BY', 'DEFAULT')),
+      'AS',
+      'IDENTITY'
     ),
 
-    data_type: $ => choice(
+    comment_constraint: $ => seq('COMMENT', field('text', $.string_literal)),
+
+    collation_constraint: $ => choice(
+      seq('COLLATE', $.identifier),
+      seq('CHARACTER', 'SET', $.identifier)
+    ),
+
+    references_clause: $ => seq(
+      'REFERENCES',
+      field('table', $.object_name),
+      optional(field('columns', $.column_list)),
+      repeat($.referential_action)
+    ),
+
+    referential_action: $ => seq(
+      'ON',
+      choice('DELETE', 'UPDATE'),
+      choice(
+        'CASCADE',
+        'RESTRICT',
+        seq('SET', 'NULL'),
+        seq('SET', 'DEFAULT'),
+        seq('NO', 'ACTION')
+      )
+    ),
+
+    table_constraint: $ => seq(
+      // MySQL allows CONSTRAINT without a name
+      optional(seq('CONSTRAINT', optional(field('name', $.identifier)))),
+      choice(
+        $.primary_key_definition,
+        $.foreign_key_definition,
+        $.unique_definition,
+        $.index_definition
+      )
+    ),
+
+    primary_key_definition: $ => seq(
+      'PRIMARY',
+      'KEY',
+      field('columns', $.column_list)
+    ),
+
+    foreign_key_definition: $ => seq(
+      'FOREIGN',
+      'KEY',
+      optional(field('name', $.identifier)),
+      field('columns', $.column_list),
+      field('references', $.references_clause)
+    ),
+
+    unique_definition: $ => seq(
+      'UNIQUE',
+      optional(choice('KEY', 'INDEX')),
+      optional(field('name', $.identifier)),
+      field('columns', $.column_list)
+    ),
+
+    index_definition: $ => seq(
+      choice('KEY', 'INDEX'),
+      optional(field('name', $.identifier)),
+      field('columns', $.column_list)
+    ),
+
+    // Options after the element list, e.g. ENGINE=InnoDB COMMENT='...'
+    table_option: $ => choice(
+      $.table_comment,
+      seq(
+        optional('DEFAULT'),
+        field('name', choice($.identifier, 'AUTO_INCREMENT', 'COLLATE')),
+        optional('='),
+        field('value', choice($.identifier, $.string_literal, $.number_literal))
+      )
+    ),
+
+    table_comment: $ => seq('COMMENT', optional('='), field('text', $.string_literal)),
+
+    data_type: $ => seq(
+      $.type_name,
+      optional($.type_parameters),
+      optional('UNSIGNED'),
+      // Array types, e.g. text[]
+      repeat(seq('[', ']'))
+    ),
+
+    // Length / precision and scale, e.g. VARCHAR(255), DECIMAL(10,2), or
+    // the values of ENUM('a','b')
+    type_parameters: $ => seq(
+      '(',
+      choice($.number_literal, $.string_literal),
+      repeat(seq(',', choice($.number_literal, $.string_literal))),
+      ')'
+    ),
+
+    type_name: $ => choice(
       // Common integer types
       'INT', 'INTEGER', 'TINYINT', 'SMALLINT', 'MEDIUMINT', 'BIGINT',
       // String types
@@ -233,7 +417,11 @@ module.exports = grammar({
       // Binary types
       'BINARY', 'VARBINARY', 'BLOB', 'TINYBLOB', 'MEDIUMBLOB', 'LONGBLOB',
       // JSON
-      'JSON'
+      'JSON',
+      // Enumerations
+      'ENUM',
+      // Auto-incrementing integers (PostgreSQL)
+      'SERIAL', 'SMALLSERIAL', 'BIGSERIAL'
     ),
 
     // =============================================================================
@@ -282,7 +470,7 @@ module.exports = grammar({
       optional($.join_type),
       /[Jj][Oo][Ii][Nn]/,
       $.table_name,
-      optional(seq(/[Aa][Ss]/, $.alias)),
+      optional(seq(optional(/[Aa][Ss]/), $.alias)),
       /[Oo][Nn]/,
       $.expression
     ),
@@ -306,9 +494,10 @@ module.exports = grammar({
 
     cte_definition: $ => seq(
       $.table_name,
+      optional($.column_list),
       optional('AS'),
       '(',
-      $.select_statement,
+      choice($.select_statement, $.values_statement),
       ')'
     ),
 
@@ -323,6 +512,7 @@ module.exports = grammar({
       $.literal,
       $.function_call,
       $.case_expression,
+      $.cast_expression,
       $.parenthesized_expression,
       '*'
     ),
@@ -334,6 +524,7 @@ module.exports = grammar({
       $.literal,
       $.function_call,
       $.case_expression,
+      $.cast_expression,
       $.parenthesized_expression,
       '*'
     ),
@@ -367,6 +558,16 @@ module.exports = grammar({
       ')'
     ),
 
+    // CAST(expr AS type)
+    cast_expression: $ => seq(
+      /[Cc][Aa][Ss][Tt]/,
+      '(',
+      field('expression', $.expression),
+      /[Aa][Ss]/,
+      field('type', $.data_type),
+      ')'
+    ),
+
     case_expression: $ => seq(
       'CASE',
       repeat(seq('WHEN', $.expression, 'THEN', $.expression)),
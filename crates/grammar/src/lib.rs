@@ -49,29 +49,94 @@
 //!
 //! To get a parser for a specific dialect:
 //!
-//! ```text,ignore
+//! ```rust
 //! use unified_sql_lsp_ir::Dialect;
-//! use unified_sql_grammar::{language_for_dialect, language_for_dialect_with_version, DialectVersion};
+//! use unified_sql_grammar::{
+//!     DialectLanguage, DialectVersion, language_for_dialect_with_version,
+//! };
 //!
-//! // Get base dialect parser (MySQL 5.7, PostgreSQL 12)
-//! let mysql_57_lang = language_for_dialect(Dialect::MySQL).unwrap();
-//! let pg_12_lang = language_for_dialect(Dialect::PostgreSQL).unwrap();
+//! // Base dialect parser (MySQL 5.7, PostgreSQL 12)
+//! match Dialect::MySQL.try_language() {
+//!     Ok(language) => {
+//!         let mut parser = tree_sitter::Parser::new();
+//!         parser.set_language(language).unwrap();
+//!     }
+//!     Err(e) => eprintln!("MySQL grammar unavailable: {e}"),
+//! }
+//! let pg_12_lang = Dialect::PostgreSQL.language();
 //!
-//! // Get version-specific parser
-//! let mysql_80_lang = language_for_dialect_with_version(
-//!     Dialect::MySQL,
-//!     Some(DialectVersion::MySQL80)
-//! ).unwrap();
-//!
-//! let pg_14_lang = language_for_dialect_with_version(
-//!     Dialect::PostgreSQL,
-//!     Some(DialectVersion::PostgreSQL14)
-//! ).unwrap();
+//! // Version-specific parser
+//! let mysql_80_lang =
+//!     language_for_dialect_with_version(Dialect::MySQL, Some(DialectVersion::MySQL80));
 //! ```
 
+use std::ops::RangeInclusive;
 use std::sync::OnceLock;
+
+use thiserror::Error;
+use tracing::error;
 use unified_sql_lsp_ir::Dialect;
 
+/// Why the grammar of a dialect can't be used
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum GrammarError {
+    /// No grammar is compiled for the dialect and version
+    #[error("no grammar for {dialect:?} {version:?}")]
+    Unsupported {
+        dialect: Dialect,
+        version: DialectVersion,
+    },
+
+    /// The build produced no parser for the grammar, e.g. because
+    /// `tree-sitter generate` failed
+    #[error("grammar {grammar} was built without a parser")]
+    NotBuilt { grammar: &'static str },
+
+    /// The grammar was generated for a tree-sitter ABI version the linked
+    /// runtime can't load
+    #[error(
+        "grammar {grammar} has tree-sitter ABI version {abi_version}, but the runtime supports {}..={}",
+        supported.start(),
+        supported.end()
+    )]
+    IncompatibleAbi {
+        grammar: &'static str,
+        abi_version: usize,
+        supported: RangeInclusive<usize>,
+    },
+}
+
+/// Grammar of a [`Dialect`], e.g. `Dialect::MySQL.language()`
+///
+/// `Dialect` belongs to the IR crate, which doesn't depend on tree-sitter,
+/// so the methods come with this trait.
+pub trait DialectLanguage {
+    /// Grammar of the dialect's base version, see [`language_for_dialect`]
+    ///
+    /// `None` when [`try_language`](Self::try_language) fails.
+    fn language(&self) -> Option<&'static tree_sitter::Language>;
+
+    /// Grammar of the dialect's base version, or why it can't be used
+    fn try_language(&self) -> Result<&'static tree_sitter::Language, GrammarError>;
+}
+
+impl DialectLanguage for Dialect {
+    fn language(&self) -> Option<&'static tree_sitter::Language> {
+        self.try_language().ok()
+    }
+
+    fn try_language(&self) -> Result<&'static tree_sitter::Language, GrammarError> {
+        // MySQL family → MySQL 5.7, PostgreSQL family → PostgreSQL 12
+        let version = match self {
+            Dialect::MySQL | Dialect::TiDB | Dialect::MariaDB => DialectVersion::MySQL57,
+            Dialect::PostgreSQL | Dialect::CockroachDB => DialectVersion::PostgreSQL12,
+            // Reported as unsupported
+            _ => DialectVersion::MySQL57,
+        };
+        try_language_for(*self, version)
+    }
+}
+
 /// Get the tree-sitter Language for a specific SQL dialect
 ///
 /// This function returns a compiled tree-sitter grammar for the given dialect.
@@ -103,11 +168,13 @@ use unified_sql_lsp_ir::Dialect;
 /// # Returns
 ///
 /// - `Some(Language)` - Compiled tree-sitter language object for the dialect
-/// - `None` - Dialect not supported or grammar compilation failed
+/// - `None` - Dialect not supported, or the grammar's tree-sitter ABI
+///   version isn't in [`SUPPORTED_ABI_VERSIONS`] (logged as an error);
+///   [`DialectLanguage::try_language`] tells which
 ///
 /// # Example
 ///
-/// ```rust,ignore
+/// ```rust
 /// use unified_sql_lsp_ir::Dialect;
 /// use unified_sql_grammar::language_for_dialect;
 ///
@@ -119,35 +186,7 @@ use unified_sql_lsp_ir::Dialect;
 /// }
 /// ```
 pub fn language_for_dialect(dialect: Dialect) -> Option<&'static tree_sitter::Language> {
-    static MYSQL_57_LANG: OnceLock<Option<tree_sitter::Language>> = OnceLock::new();
-    static POSTGRESQL_12_LANG: OnceLock<Option<tree_sitter::Language>> = OnceLock::new();
-
-    // Map IR dialects to BASE version grammar implementations
-    // MySQL family → MySQL 5.7 (base dialect)
-    // PostgreSQL family → PostgreSQL 12 (base dialect)
-    match dialect {
-        Dialect::MySQL | Dialect::TiDB | Dialect::MariaDB => MYSQL_57_LANG
-            .get_or_init(|| unsafe {
-                // Safety: tree_sitter_unified_sql_mysql_5_7() returns a pointer to the
-                // language object compiled by tree-sitter from src/grammar/dialect/mysql-5.7.js
-                // MySQL 5.7 is the base dialect for the MySQL family
-                Some(std::mem::transmute::<*const (), tree_sitter::Language>(
-                    tree_sitter_unified_sql_mysql_5_7(),
-                ))
-            })
-            .as_ref(),
-        Dialect::PostgreSQL | Dialect::CockroachDB => POSTGRESQL_12_LANG
-            .get_or_init(|| unsafe {
-                // Safety: tree_sitter_unified_sql_postgresql_12() returns a pointer to the
-                // language object compiled by tree-sitter from src/grammar/dialect/postgresql-12.js
-                // PostgreSQL 12 is the base dialect for the PostgreSQL family
-                Some(std::mem::transmute::<*const (), tree_sitter::Language>(
-                    tree_sitter_unified_sql_postgresql_12(),
-                ))
-            })
-            .as_ref(),
-        _ => None, // Unsupported dialect
-    }
+    dialect.language()
 }
 
 /// SQL dialect version for version-specific grammar selection
@@ -190,11 +229,12 @@ impl DialectVersion {
 /// # Returns
 ///
 /// - `Some(Language)` - Compiled tree-sitter language object
-/// - `None` - Dialect/version not supported or grammar compilation failed
+/// - `None` - Dialect/version not supported, or the grammar's tree-sitter ABI
+///   version isn't in [`SUPPORTED_ABI_VERSIONS`] (logged as an error)
 ///
 /// # Example
 ///
-/// ```rust,ignore
+/// ```rust
 /// use unified_sql_lsp_ir::Dialect;
 /// use unified_sql_grammar::{language_for_dialect_with_version, DialectVersion};
 ///
@@ -229,52 +269,164 @@ pub fn language_for_dialect_with_version(
         _ => DialectVersion::MySQL80, // Fallback
     });
 
-    // Map to the appropriate version-specific grammar
-    match (dialect, version) {
-        (Dialect::MySQL | Dialect::TiDB | Dialect::MariaDB, DialectVersion::MySQL57) => {
-            static MYSQL_57_LANG: OnceLock<Option<tree_sitter::Language>> = OnceLock::new();
-            MYSQL_57_LANG
-                .get_or_init(|| unsafe {
-                    Some(std::mem::transmute::<*const (), tree_sitter::Language>(
-                        tree_sitter_unified_sql_mysql_5_7(),
-                    ))
-                })
-                .as_ref()
-        }
-        (Dialect::MySQL | Dialect::TiDB | Dialect::MariaDB, DialectVersion::MySQL80) => {
-            static MYSQL_80_LANG: OnceLock<Option<tree_sitter::Language>> = OnceLock::new();
-            MYSQL_80_LANG
-                .get_or_init(|| unsafe {
-                    Some(std::mem::transmute::<*const (), tree_sitter::Language>(
-                        tree_sitter_unified_sql_mysql_8_0(),
-                    ))
-                })
-                .as_ref()
-        }
-        (Dialect::PostgreSQL | Dialect::CockroachDB, DialectVersion::PostgreSQL12) => {
-            static POSTGRESQL_12_LANG: OnceLock<Option<tree_sitter::Language>> = OnceLock::new();
-            POSTGRESQL_12_LANG
-                .get_or_init(|| unsafe {
-                    Some(std::mem::transmute::<*const (), tree_sitter::Language>(
-                        tree_sitter_unified_sql_postgresql_12(),
-                    ))
-                })
-                .as_ref()
+    try_language_for(dialect, version).ok()
+}
+
+/// Grammar of `dialect` at `version`, or why it can't be used
+fn try_language_for(
+    dialect: Dialect,
+    version: DialectVersion,
+) -> Result<&'static tree_sitter::Language, GrammarError> {
+    let index = match (dialect, version) {
+        (Dialect::MySQL | Dialect::TiDB | Dialect::MariaDB, DialectVersion::MySQL57) => MYSQL_57,
+        (Dialect::MySQL | Dialect::TiDB | Dialect::MariaDB, DialectVersion::MySQL80) => MYSQL_80,
+        (Dialect::PostgreSQL | Dialect::CockroachDB, DialectVersion::PostgreSQL12) => POSTGRESQL_12,
+        (Dialect::PostgreSQL | Dialect::CockroachDB, DialectVersion::PostgreSQL14) => POSTGRESQL_14,
+        _ => return Err(GrammarError::Unsupported { dialect, version }),
+    };
+    language(index)
+}
+
+/// Tree-sitter ABI versions the linked runtime can load
+pub const SUPPORTED_ABI_VERSIONS: RangeInclusive<usize> =
+    tree_sitter::MIN_COMPATIBLE_LANGUAGE_VERSION..=tree_sitter::LANGUAGE_VERSION;
+
+/// A compiled grammar, as reported by [`grammar_info`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrammarInfo {
+    /// Grammar name, e.g. `"mysql-5.7"`
+    pub name: &'static str,
+    pub dialect: Dialect,
+    pub version: DialectVersion,
+    /// Tree-sitter ABI version the grammar was generated for
+    pub abi_version: usize,
+    /// Number of node kinds the grammar defines
+    pub node_kind_count: usize,
+    /// Whether the ABI version is in [`SUPPORTED_ABI_VERSIONS`]; an
+    /// incompatible grammar is never returned by the language functions
+    pub compatible: bool,
+}
+
+/// Every compiled grammar with its ABI version and size
+///
+/// Grammars whose build produced no language object are left out.
+///
+/// Used at startup (`--check`) and in status notifications to tell a
+/// grammar built against the wrong tree-sitter version apart from a
+/// dialect that isn't supported.
+pub fn grammar_info() -> Vec<GrammarInfo> {
+    GRAMMARS
+        .iter()
+        .filter_map(|grammar| {
+            let language = grammar.raw_language()?;
+            Some(GrammarInfo {
+                name: grammar.name,
+                dialect: grammar.dialect,
+                version: grammar.version,
+                abi_version: language.abi_version(),
+                node_kind_count: language.node_kind_count(),
+                compatible: SUPPORTED_ABI_VERSIONS.contains(&language.abi_version()),
+            })
+        })
+        .collect()
+}
+
+/// A grammar compiled by `build.rs`
+struct Grammar {
+    name: &'static str,
+    dialect: Dialect,
+    version: DialectVersion,
+    entry: unsafe extern "C" fn() -> *const (),
+}
+
+impl Grammar {
+    /// Language object of the grammar, whatever its ABI version
+    fn raw_language(&self) -> Option<tree_sitter::Language> {
+        // Safety: the entry points return a pointer to the static language
+        // object generated by tree-sitter for the grammar's dialect, or null
+        // when generation failed
+        let language = unsafe { (self.entry)() };
+        if language.is_null() {
+            error!("Grammar {} was built without a parser", self.name);
+            return None;
         }
-        (Dialect::PostgreSQL | Dialect::CockroachDB, DialectVersion::PostgreSQL14) => {
-            static POSTGRESQL_14_LANG: OnceLock<Option<tree_sitter::Language>> = OnceLock::new();
-            POSTGRESQL_14_LANG
-                .get_or_init(|| unsafe {
-                    Some(std::mem::transmute::<*const (), tree_sitter::Language>(
-                        tree_sitter_unified_sql_postgresql_14(),
-                    ))
-                })
-                .as_ref()
+        Some(unsafe { std::mem::transmute::<*const (), tree_sitter::Language>(language) })
+    }
+
+    /// Language object of the grammar if its ABI version is in `supported`
+    ///
+    /// Setting an incompatible language on a parser fails, and an
+    /// incompatible grammar that is used anyway can crash, so it is treated
+    /// like a missing one.
+    fn load(
+        &self,
+        supported: RangeInclusive<usize>,
+    ) -> Result<tree_sitter::Language, GrammarError> {
+        let language = self
+            .raw_language()
+            .ok_or(GrammarError::NotBuilt { grammar: self.name })?;
+        let abi_version = language.abi_version();
+        if supported.contains(&abi_version) {
+            Ok(language)
+        } else {
+            Err(GrammarError::IncompatibleAbi {
+                grammar: self.name,
+                abi_version,
+                supported,
+            })
         }
-        _ => None,
     }
 }
 
+// Indices into GRAMMARS
+const MYSQL_57: usize = 0;
+const MYSQL_80: usize = 1;
+const POSTGRESQL_12: usize = 2;
+const POSTGRESQL_14: usize = 3;
+
+const GRAMMARS: [Grammar; 4] = [
+    Grammar {
+        name: "mysql-5.7",
+        dialect: Dialect::MySQL,
+        version: DialectVersion::MySQL57,
+        entry: tree_sitter_unified_sql_mysql_5_7,
+    },
+    Grammar {
+        name: "mysql-8.0",
+        dialect: Dialect::MySQL,
+        version: DialectVersion::MySQL80,
+        entry: tree_sitter_unified_sql_mysql_8_0,
+    },
+    Grammar {
+        name: "postgresql-12",
+        dialect: Dialect::PostgreSQL,
+        version: DialectVersion::PostgreSQL12,
+        entry: tree_sitter_unified_sql_postgresql_12,
+    },
+    Grammar {
+        name: "postgresql-14",
+        dialect: Dialect::PostgreSQL,
+        version: DialectVersion::PostgreSQL14,
+        entry: tree_sitter_unified_sql_postgresql_14,
+    },
+];
+
+/// Language of `GRAMMARS[index]`, loaded and checked once
+///
+/// A failure is logged when first seen.
+fn language(index: usize) -> Result<&'static tree_sitter::Language, GrammarError> {
+    static LANGUAGES: [OnceLock<Result<tree_sitter::Language, GrammarError>>; GRAMMARS.len()] =
+        [const { OnceLock::new() }; GRAMMARS.len()];
+    LANGUAGES[index]
+        .get_or_init(|| {
+            GRAMMARS[index]
+                .load(SUPPORTED_ABI_VERSIONS)
+                .inspect_err(|e| error!("{}", e))
+        })
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
 // External functions from compiled grammars
 // Rust 2024 edition requires extern blocks to be unsafe
 //
@@ -340,6 +492,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_grammar_info() {
+        let info = grammar_info();
+        let names: Vec<_> = info.iter().map(|grammar| grammar.name).collect();
+        assert_eq!(
+            names,
+            ["mysql-5.7", "mysql-8.0", "postgresql-12", "postgresql-14"]
+        );
+        for grammar in &info {
+            assert!(grammar.compatible, "{} is incompatible", grammar.name);
+            assert!(SUPPORTED_ABI_VERSIONS.contains(&grammar.abi_version));
+            assert!(grammar.node_kind_count > 0);
+        }
+        // MySQL 8.0 extends 5.7
+        assert!(info[1].node_kind_count > info[0].node_kind_count);
+    }
+
+    #[test]
+    fn test_incompatible_abi_version_is_not_loaded() {
+        let grammar = &GRAMMARS[MYSQL_80];
+        let abi_version = grammar.raw_language().unwrap().abi_version();
+        assert!(grammar.load(SUPPORTED_ABI_VERSIONS).is_ok());
+        assert_eq!(
+            grammar.load(abi_version + 1..=abi_version + 2),
+            Err(GrammarError::IncompatibleAbi {
+                grammar: "mysql-8.0",
+                abi_version,
+                supported: abi_version + 1..=abi_version + 2,
+            })
+        );
+        assert!(grammar.load(0..=abi_version - 1).is_err());
+    }
+
+    /// Entry point of a grammar whose generation failed
+    extern "C" fn missing_parser() -> *const () {
+        std::ptr::null()
+    }
+
+    #[test]
+    fn test_grammar_without_parser_is_not_loaded() {
+        let grammar = Grammar {
+            name: "broken",
+            dialect: Dialect::MySQL,
+            version: DialectVersion::MySQL57,
+            entry: missing_parser,
+        };
+        let error = grammar.load(SUPPORTED_ABI_VERSIONS).unwrap_err();
+        assert_eq!(error, GrammarError::NotBuilt { grammar: "broken" });
+        assert_eq!(
+            error.to_string(),
+            "grammar broken was built without a parser"
+        );
+    }
+
+    #[test]
+    fn test_dialect_language_methods() {
+        for dialect in [Dialect::MySQL, Dialect::MariaDB, Dialect::CockroachDB] {
+            assert_eq!(dialect.language(), language_for_dialect(dialect));
+            assert_eq!(dialect.try_language().ok(), dialect.language());
+        }
+
+        // No grammar pairs a dialect with another family's version
+        assert_eq!(
+            try_language_for(Dialect::MySQL, DialectVersion::PostgreSQL14),
+            Err(GrammarError::Unsupported {
+                dialect: Dialect::MySQL,
+                version: DialectVersion::PostgreSQL14,
+            })
+        );
+        assert_eq!(
+            language_for_dialect_with_version(Dialect::PostgreSQL, Some(DialectVersion::MySQL57)),
+            None
+        );
+    }
+
     #[test]
     fn test_dialect_version_parsing() {
         // Test version string parsing
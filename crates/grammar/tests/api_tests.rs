@@ -108,6 +108,30 @@ fn test_parse_postgresql_specific_syntax() {
     assert!(!tree.root_node().has_error());
 }
 
+#[test]
+fn test_parse_postgresql_merge() {
+    let language =
+        language_for_dialect(Dialect::PostgreSQL).expect("PostgreSQL language not found");
+
+    let mut parser = Parser::new();
+    parser
+        .set_language(language)
+        .expect("Failed to set language");
+
+    let source = "MERGE INTO users u USING staging s ON u.id = s.id \
+                  WHEN MATCHED THEN UPDATE SET name = s.name \
+                  WHEN NOT MATCHED THEN INSERT (id, name) VALUES (s.id, s.name)";
+    let tree = parser.parse(source, None).expect("Failed to parse");
+
+    let root = tree.root_node();
+    assert!(!root.has_error());
+
+    let statement = root.child(0).and_then(|s| s.child(0)).unwrap();
+    assert_eq!(statement.kind(), "merge_statement");
+    assert!(statement.child_by_field_name("target").is_some());
+    assert!(statement.child_by_field_name("source").is_some());
+}
+
 #[test]
 fn test_parse_with_syntax_error() {
     let language = language_for_dialect(Dialect::MySQL).expect("MySQL language not found");
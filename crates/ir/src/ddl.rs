@@ -0,0 +1,159 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # DDL Representation
+//!
+//! Schema-defining statements lowered from the same CST as queries.
+//!
+//! Only what a catalog needs to describe tables and views is kept: names,
+//! column definitions and keys. Storage options, index kinds and
+//! referential actions are parsed but not represented.
+//!
+//! Identifiers are already normalized by the dialect's lowering (quotes
+//! removed, PostgreSQL unquoted names folded to lowercase).
+
+use crate::query::Query;
+use serde::{Deserialize, Serialize};
+
+/// A lowered DDL statement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DdlStatement {
+    /// `CREATE [TEMPORARY] TABLE`
+    CreateTable(CreateTableStatement),
+    /// `CREATE [MATERIALIZED] VIEW`
+    CreateView(CreateViewStatement),
+    /// `ALTER TABLE ... ADD ...`
+    AlterTable(AlterTableStatement),
+}
+
+/// `CREATE TABLE name (elements) [options]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreateTableStatement {
+    /// Schema qualifier, if written
+    pub schema: Option<String>,
+
+    /// Table name
+    pub name: String,
+
+    /// Whether the table is `TEMPORARY`
+    pub temporary: bool,
+
+    /// Column definitions, in declaration order
+    pub columns: Vec<ColumnDef>,
+
+    /// Table-level constraints
+    pub constraints: Vec<TableConstraint>,
+
+    /// Table comment (MySQL `COMMENT='...'` option)
+    pub comment: Option<String>,
+}
+
+/// A column definition inside CREATE TABLE or ALTER TABLE ADD COLUMN
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnDef {
+    /// Column name
+    pub name: String,
+
+    /// Data type as written, e.g. `VARCHAR(255)` or `text[]`
+    pub data_type: String,
+
+    /// False when declared `NOT NULL`
+    pub nullable: bool,
+
+    /// Declared `PRIMARY KEY` inline
+    pub primary_key: bool,
+
+    /// Default expression as written; `AUTO_INCREMENT` and
+    /// `GENERATED ... AS IDENTITY` are recorded here too
+    pub default: Option<String>,
+
+    /// Column comment (MySQL `COMMENT '...'`)
+    pub comment: Option<String>,
+
+    /// Inline `REFERENCES` target
+    pub references: Option<ForeignKeyTarget>,
+}
+
+impl ColumnDef {
+    /// A nullable column with no constraints
+    pub fn new(name: impl Into<String>, data_type: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            data_type: data_type.into(),
+            nullable: true,
+            primary_key: false,
+            default: None,
+            comment: None,
+            references: None,
+        }
+    }
+}
+
+/// The table and columns a foreign key points at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForeignKeyTarget {
+    /// Referenced table, without its schema
+    pub table: String,
+
+    /// Referenced columns; empty means the table's primary key
+    pub columns: Vec<String>,
+}
+
+/// A table-level constraint or key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TableConstraint {
+    /// `PRIMARY KEY (columns)`
+    PrimaryKey(Vec<String>),
+    /// `FOREIGN KEY (columns) REFERENCES target`
+    ForeignKey {
+        columns: Vec<String>,
+        references: ForeignKeyTarget,
+    },
+    /// `UNIQUE [KEY] (columns)`
+    Unique(Vec<String>),
+    /// `KEY|INDEX (columns)`
+    Index(Vec<String>),
+}
+
+/// `CREATE [MATERIALIZED] VIEW name [(columns)] AS query`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreateViewStatement {
+    /// Schema qualifier, if written
+    pub schema: Option<String>,
+
+    /// View name
+    pub name: String,
+
+    /// Whether the view is `MATERIALIZED`
+    pub materialized: bool,
+
+    /// Explicit column names, renaming the query's output columns
+    pub columns: Vec<String>,
+
+    /// Defining query
+    pub query: Box<Query>,
+}
+
+/// `ALTER TABLE name action, ...`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlterTableStatement {
+    /// Schema qualifier, if written
+    pub schema: Option<String>,
+
+    /// Table name
+    pub name: String,
+
+    /// Actions, in source order
+    pub actions: Vec<AlterTableAction>,
+}
+
+/// A single ALTER TABLE action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AlterTableAction {
+    /// `ADD [COLUMN] column_definition`
+    AddColumn(ColumnDef),
+    /// `ADD [CONSTRAINT name] constraint`
+    AddConstraint(TableConstraint),
+}
@@ -38,6 +38,9 @@
 //! - `TiDBSnapshot`: TiDB's `TIDB_SNAPSHOT` for reading historical data
 //! - `CTE`: Common Table Expressions (WITH clauses)
 //! - `FullOuterJoin`: FULL OUTER JOIN support
+//! - `AutoIncrement`: MySQL's `AUTO_INCREMENT` column attribute
+//! - `IdentityColumns`: `GENERATED ... AS IDENTITY` columns
+//! - `Returning`: PostgreSQL's `RETURNING` list of INSERT, UPDATE and DELETE
 //!
 //! ## Version Support
 //!
@@ -85,6 +88,7 @@ impl Dialect {
             DialectExtensions::LimitOffset
                 | DialectExtensions::MultiDelete
                 | DialectExtensions::StraightJoin
+                | DialectExtensions::AutoIncrement
         );
 
         // PostgreSQL family extensions
@@ -94,6 +98,9 @@ impl Dialect {
                 | DialectExtensions::DistinctOn
                 | DialectExtensions::LateralJoin
                 | DialectExtensions::WindowFunctions
+                | DialectExtensions::FullOuterJoin
+                | DialectExtensions::IdentityColumns
+                | DialectExtensions::Returning
         );
 
         match self {
@@ -104,6 +111,17 @@ impl Dialect {
             Dialect::CockroachDB => postgresql_family,
         }
     }
+
+    /// File extensions of SQL files written for this dialect, with the dot
+    ///
+    /// `.sql` comes first; the others are the dialect family's hints, e.g.
+    /// `.pgsql`, which editors and the workspace indexer treat as SQL too.
+    pub fn file_extensions(&self) -> &'static [&'static str] {
+        match self.family() {
+            DialectFamily::MySQL => &[".sql", ".mysql"],
+            DialectFamily::PostgreSQL => &[".sql", ".pgsql", ".psql"],
+        }
+    }
 }
 
 /// Dialect family groupings
@@ -144,8 +162,18 @@ pub enum DialectExtensions {
     /// CTE (Common Table Expression) - WITH clauses
     CTE,
 
-    /// FULL OUTER JOIN
+    /// FULL OUTER JOIN (PostgreSQL, CockroachDB)
     FullOuterJoin,
+
+    /// AUTO_INCREMENT column attribute (MySQL family)
+    AutoIncrement,
+
+    /// GENERATED {ALWAYS|BY DEFAULT} AS IDENTITY columns (PostgreSQL 10+,
+    /// CockroachDB)
+    IdentityColumns,
+
+    /// RETURNING list of INSERT, UPDATE and DELETE (PostgreSQL, CockroachDB)
+    Returning,
 }
 
 impl DialectExtensions {
@@ -157,3 +185,18 @@ impl DialectExtensions {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_extensions() {
+        assert_eq!(Dialect::MySQL.file_extensions(), [".sql", ".mysql"]);
+        assert_eq!(Dialect::MariaDB.file_extensions(), [".sql", ".mysql"]);
+        assert_eq!(
+            Dialect::CockroachDB.file_extensions(),
+            [".sql", ".pgsql", ".psql"]
+        );
+    }
+}
@@ -17,8 +17,10 @@
 //! - **Unary operations**: Negation, NOT, EXISTS
 //! - **Function calls**: Built-in and user-defined functions
 //! - **Case expressions**: Conditional logic (CASE WHEN...THEN...ELSE)
-//! - **Cast expressions**: Type conversions
+//! - **Cast expressions**: Type conversions (`CAST(x AS type)`, PostgreSQL `x::type`)
+//! - **JSON path expressions**: MySQL `column->'$.path'` and `column->>'$.path'`
 //! - **Lists**: Value lists for IN clauses
+//! - **Subqueries**: `IN (SELECT ...)` and other subqueries used as values
 //!
 //! ## Expression Hierarchy
 //!
@@ -124,6 +126,7 @@
 //!   OR (is_admin = true)
 //! ```
 
+use crate::query::Query;
 use serde::{Deserialize, Serialize};
 
 /// A SQL expression
@@ -166,14 +169,91 @@ pub enum Expr {
         else_result: Option<Box<Expr>>,
     },
 
-    /// CAST expression
-    Cast { expr: Box<Expr>, type_name: String },
+    /// CAST expression (`CAST(x AS numeric(10,2))` or PostgreSQL `x::text`)
+    ///
+    /// `target_type` is the type as written, including any parameters.
+    Cast {
+        expr: Box<Expr>,
+        target_type: String,
+    },
+
+    /// JSON path extraction (MySQL `data->'$.name'`)
+    ///
+    /// `unquote` is set for `->>`, which returns the value as unquoted text.
+    JsonPath {
+        expr: Box<Expr>,
+        path: String,
+        unquote: bool,
+    },
 
     /// Parenthesized expression
     Paren(Box<Expr>),
 
     /// List of expressions (e.g., for IN clause)
     List(Vec<Expr>),
+
+    /// Subquery used as a value (e.g., `id IN (SELECT user_id FROM orders)`)
+    Subquery(Box<Query>),
+}
+
+impl Expr {
+    /// Binary operations in this expression, itself included
+    ///
+    /// They come in the order lowering builds them: operands left to right
+    /// before the operation. Lowering numbers the source of each in that
+    /// order, so the index of an operation here finds its source. Those of
+    /// subqueries are left out, as lowering numbers them on their own.
+    pub fn binary_exprs(&self) -> Vec<&Expr> {
+        let mut exprs = Vec::new();
+        self.collect_binary_exprs(&mut exprs);
+        exprs
+    }
+
+    fn collect_binary_exprs<'a>(&'a self, exprs: &mut Vec<&'a Expr>) {
+        match self {
+            Expr::BinaryOp { left, right, .. } => {
+                left.collect_binary_exprs(exprs);
+                right.collect_binary_exprs(exprs);
+                exprs.push(self);
+            }
+            Expr::UnaryOp { expr, .. }
+            | Expr::Cast { expr, .. }
+            | Expr::JsonPath { expr, .. }
+            | Expr::Paren(expr) => expr.collect_binary_exprs(exprs),
+            Expr::Function {
+                args, filter, over, ..
+            } => {
+                for arg in args.iter().chain(filter.as_deref()) {
+                    arg.collect_binary_exprs(exprs);
+                }
+                if let Some(over) = over {
+                    let order_by = over.order_by.iter().map(|order| &order.expr);
+                    for expr in over.partition_by.iter().chain(order_by) {
+                        expr.collect_binary_exprs(exprs);
+                    }
+                }
+            }
+            Expr::Case {
+                conditions,
+                results,
+                else_result,
+            } => {
+                for (condition, result) in conditions.iter().zip(results) {
+                    condition.collect_binary_exprs(exprs);
+                    result.collect_binary_exprs(exprs);
+                }
+                if let Some(else_result) = else_result {
+                    else_result.collect_binary_exprs(exprs);
+                }
+            }
+            Expr::List(items) => {
+                for item in items {
+                    item.collect_binary_exprs(exprs);
+                }
+            }
+            Expr::Column(_) | Expr::Literal(_) | Expr::Subquery(_) => {}
+        }
+    }
 }
 
 /// Column reference
@@ -268,6 +348,8 @@ pub enum UnaryOp {
 /// dependencies with the query module's WindowDef.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WindowSpec {
+    /// Named window this specification refers to (`OVER w` or `OVER (w ...)`)
+    pub name: Option<String>,
     /// PARTITION BY expressions
     pub partition_by: Vec<Expr>,
     /// ORDER BY clauses (uses query module's OrderBy)
@@ -0,0 +1,317 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Identifier comparison
+//!
+//! Table names are written in SQL either bare (`users`) or delimited
+//! (`"Users"` in PostgreSQL, `` `Users` `` in MySQL). The two compare
+//! differently against catalog names:
+//!
+//! - A delimited identifier names exactly the catalog object spelled between
+//!   the delimiters, so `"Users"` matches `Users` but not `users`.
+//! - A bare identifier matches regardless of case, so `users` matches `users`
+//!   and, when no exact match exists, `USERS`.
+//!
+//! Both delimiters are accepted whatever the dialect, since a reference is
+//! compared before the statement's dialect is always known. Rendering a name
+//! back into SQL uses the dialect's own delimiter.
+//!
+//! Column names are the exception: MySQL compares them regardless of case
+//! even when they are delimited, so [`IdentifierComparer::column`] drops the
+//! delimiters of a MySQL column reference before it is compared.
+//!
+//! Names a statement defines itself, such as CTEs and table aliases, are
+//! compared with each other through [`IdentifierComparer::fold`].
+
+use crate::dialect::{Dialect, DialectFamily};
+
+/// Compares identifiers written in SQL with catalog names, and renders
+/// catalog names as identifiers of a dialect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdentifierComparer {
+    dialect: Dialect,
+}
+
+impl IdentifierComparer {
+    /// Create a comparer rendering identifiers for `dialect`
+    pub fn new(dialect: Dialect) -> Self {
+        Self { dialect }
+    }
+
+    /// Whether `identifier` is delimited by double quotes or backticks
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use unified_sql_lsp_ir::IdentifierComparer;
+    ///
+    /// assert!(IdentifierComparer::is_quoted("\"Users\""));
+    /// assert!(IdentifierComparer::is_quoted("`Users`"));
+    /// assert!(!IdentifierComparer::is_quoted("users"));
+    /// ```
+    pub fn is_quoted(identifier: &str) -> bool {
+        let mut chars = identifier.chars();
+        match (chars.next(), chars.next_back()) {
+            (Some(open), Some(close)) => open == close && (open == '"' || open == '`'),
+            _ => false,
+        }
+    }
+
+    /// The name an identifier refers to: delimiters removed and doubled
+    /// delimiters inside it unescaped
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use unified_sql_lsp_ir::IdentifierComparer;
+    ///
+    /// assert_eq!(IdentifierComparer::unquote("\"Users\""), "Users");
+    /// assert_eq!(IdentifierComparer::unquote("\"we\"\"ird\""), "we\"ird");
+    /// assert_eq!(IdentifierComparer::unquote("users"), "users");
+    /// ```
+    pub fn unquote(identifier: &str) -> String {
+        if !Self::is_quoted(identifier) {
+            return identifier.to_string();
+        }
+
+        let delimiter = &identifier[..1];
+        identifier[1..identifier.len() - 1].replace(&delimiter.repeat(2), delimiter)
+    }
+
+    /// Whether `identifier`, as written in SQL, refers to the catalog name `name`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use unified_sql_lsp_ir::IdentifierComparer;
+    ///
+    /// assert!(IdentifierComparer::matches("\"Users\"", "Users"));
+    /// assert!(!IdentifierComparer::matches("\"Users\"", "users"));
+    /// assert!(IdentifierComparer::matches("USERS", "users"));
+    /// ```
+    pub fn matches(identifier: &str, name: &str) -> bool {
+        if Self::is_quoted(identifier) {
+            Self::unquote(identifier) == name
+        } else {
+            identifier.eq_ignore_ascii_case(name)
+        }
+    }
+
+    /// Find the name `identifier` refers to among `names`
+    ///
+    /// A bare identifier prefers the name spelled exactly like it over one
+    /// that only differs in case.
+    pub fn find<'a, T>(
+        identifier: &str,
+        names: impl IntoIterator<Item = &'a T>,
+        name: impl Fn(&T) -> &str,
+    ) -> Option<&'a T>
+    where
+        T: 'a + ?Sized,
+    {
+        let mut case_insensitive = None;
+        for candidate in names {
+            let candidate_name = name(candidate);
+            if Self::is_quoted(identifier) || candidate_name == identifier {
+                if Self::matches(identifier, candidate_name) {
+                    return Some(candidate);
+                }
+            } else if case_insensitive.is_none() && Self::matches(identifier, candidate_name) {
+                case_insensitive = Some(candidate);
+            }
+        }
+        case_insensitive
+    }
+
+    /// The form of the column reference `identifier` to compare with
+    /// column names
+    ///
+    /// PostgreSQL keeps the delimiters, so a quoted column matches only its
+    /// exact spelling. MySQL column names never depend on case, so backticks
+    /// are dropped there; double quotes are kept, since `"name"` is a string
+    /// literal in MySQL's default SQL mode and names no column.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use unified_sql_lsp_ir::{Dialect, IdentifierComparer};
+    ///
+    /// let postgres = IdentifierComparer::new(Dialect::PostgreSQL);
+    /// assert_eq!(postgres.column("\"Name\""), "\"Name\"");
+    ///
+    /// let mysql = IdentifierComparer::new(Dialect::MySQL);
+    /// assert_eq!(mysql.column("`Name`"), "Name");
+    /// assert_eq!(mysql.column("\"Name\""), "\"Name\"");
+    /// ```
+    pub fn column(&self, identifier: &str) -> String {
+        match self.dialect.family() {
+            DialectFamily::PostgreSQL => identifier.to_string(),
+            DialectFamily::MySQL if identifier.starts_with('`') => Self::unquote(identifier),
+            DialectFamily::MySQL => identifier.to_string(),
+        }
+    }
+
+    /// The name `identifier` defines, folded so that two definitions
+    /// colliding in `dialect` fold to the same string
+    ///
+    /// PostgreSQL folds bare identifiers to lowercase and keeps delimited
+    /// ones as spelled, so `"Recent"` and `recent` are different names there.
+    /// MySQL compares them regardless of case, delimited or not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use unified_sql_lsp_ir::{Dialect, IdentifierComparer};
+    ///
+    /// let postgres = IdentifierComparer::new(Dialect::PostgreSQL);
+    /// assert_eq!(postgres.fold("Recent"), postgres.fold("\"recent\""));
+    /// assert_ne!(postgres.fold("\"Recent\""), postgres.fold("recent"));
+    ///
+    /// let mysql = IdentifierComparer::new(Dialect::MySQL);
+    /// assert_eq!(mysql.fold("`Recent`"), mysql.fold("recent"));
+    /// ```
+    pub fn fold(&self, identifier: &str) -> String {
+        match self.dialect.family() {
+            DialectFamily::PostgreSQL if Self::is_quoted(identifier) => Self::unquote(identifier),
+            DialectFamily::PostgreSQL => identifier.to_lowercase(),
+            DialectFamily::MySQL => Self::unquote(identifier).to_lowercase(),
+        }
+    }
+
+    /// Whether `name` must be delimited to be written as an identifier
+    ///
+    /// PostgreSQL folds bare identifiers to lowercase, so names with
+    /// uppercase letters need quotes there; MySQL keeps their case.
+    pub fn needs_quotes(&self, name: &str) -> bool {
+        let Some(first) = name.chars().next() else {
+            return true;
+        };
+
+        match self.dialect.family() {
+            DialectFamily::PostgreSQL => {
+                !(first.is_ascii_lowercase() || first == '_')
+                    || !name.chars().all(|c| {
+                        c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '$'
+                    })
+            }
+            DialectFamily::MySQL => {
+                name.chars().all(|c| c.is_ascii_digit())
+                    || !name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+            }
+        }
+    }
+
+    /// Delimit `name` with the dialect's quote character
+    pub fn quote(&self, name: &str) -> String {
+        let delimiter = match self.dialect.family() {
+            DialectFamily::PostgreSQL => "\"",
+            DialectFamily::MySQL => "`",
+        };
+        format!(
+            "{}{}{}",
+            delimiter,
+            name.replace(delimiter, &delimiter.repeat(2)),
+            delimiter
+        )
+    }
+
+    /// Write `name` as an identifier, delimited only if it has to be
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use unified_sql_lsp_ir::{Dialect, IdentifierComparer};
+    ///
+    /// let postgres = IdentifierComparer::new(Dialect::PostgreSQL);
+    /// assert_eq!(postgres.render("Users"), "\"Users\"");
+    /// assert_eq!(postgres.render("users"), "users");
+    ///
+    /// let mysql = IdentifierComparer::new(Dialect::MySQL);
+    /// assert_eq!(mysql.render("Users"), "Users");
+    /// assert_eq!(mysql.render("order items"), "`order items`");
+    /// ```
+    pub fn render(&self, name: &str) -> String {
+        if self.needs_quotes(name) {
+            self.quote(name)
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quoted_identifiers_match_exactly() {
+        assert!(IdentifierComparer::matches("\"Users\"", "Users"));
+        assert!(IdentifierComparer::matches("`Users`", "Users"));
+        assert!(!IdentifierComparer::matches("\"Users\"", "users"));
+        assert!(!IdentifierComparer::matches("`users`", "Users"));
+
+        assert!(IdentifierComparer::matches("users", "Users"));
+        assert!(IdentifierComparer::matches("Users", "users"));
+        // A lone delimiter is not a quoted identifier
+        assert!(!IdentifierComparer::is_quoted("\""));
+    }
+
+    #[test]
+    fn test_find_prefers_exact_spelling() {
+        let names = ["Users", "users"];
+        let find = |identifier| IdentifierComparer::find(identifier, &names, |n| *n);
+
+        assert_eq!(find("users"), Some(&"users"));
+        assert_eq!(find("\"Users\""), Some(&"Users"));
+        assert_eq!(find("USERS"), Some(&"Users"));
+        assert_eq!(find("\"USERS\""), None);
+    }
+
+    #[test]
+    fn test_quoted_columns_per_dialect() {
+        let columns = ["name", "Title"];
+        let find = |dialect, identifier: &str| {
+            let identifier = IdentifierComparer::new(dialect).column(identifier);
+            IdentifierComparer::find(&identifier, &columns, |n| *n).copied()
+        };
+
+        assert_eq!(find(Dialect::PostgreSQL, "Name"), Some("name"));
+        assert_eq!(find(Dialect::PostgreSQL, "\"Name\""), None);
+        assert_eq!(find(Dialect::PostgreSQL, "\"Title\""), Some("Title"));
+        assert_eq!(find(Dialect::MySQL, "Name"), Some("name"));
+        assert_eq!(find(Dialect::MySQL, "`Name`"), Some("name"));
+        assert_eq!(find(Dialect::MySQL, "`TITLE`"), Some("Title"));
+        // A double-quoted string literal, not a column
+        assert_eq!(find(Dialect::MySQL, "\"TITLE\""), None);
+    }
+
+    #[test]
+    fn test_render_per_dialect() {
+        let postgres = IdentifierComparer::new(Dialect::PostgreSQL);
+        assert_eq!(postgres.render("order_items"), "order_items");
+        assert_eq!(postgres.render("2024_sales"), "\"2024_sales\"");
+        assert_eq!(postgres.render("we\"ird"), "\"we\"\"ird\"");
+
+        let mysql = IdentifierComparer::new(Dialect::MySQL);
+        assert_eq!(mysql.render("OrderItems"), "OrderItems");
+        assert_eq!(mysql.render("2024_sales"), "2024_sales");
+        assert_eq!(mysql.render("2024"), "`2024`");
+        assert_eq!(mysql.render("we`ird"), "`we``ird`");
+    }
+
+    #[test]
+    fn test_fold_per_dialect() {
+        let postgres = IdentifierComparer::new(Dialect::PostgreSQL);
+        assert_eq!(postgres.fold("Recent"), "recent");
+        assert_eq!(postgres.fold("\"Recent\""), "Recent");
+        assert_eq!(postgres.fold("\"recent\""), postgres.fold("RECENT"));
+
+        let mysql = IdentifierComparer::new(Dialect::MySQL);
+        assert_eq!(mysql.fold("`Recent`"), "recent");
+        assert_eq!(mysql.fold("\"RECENT\""), mysql.fold("recent"));
+    }
+}
@@ -12,21 +12,28 @@
 //! - Enable lowering from CST (Concrete Syntax Tree)
 //! - Support dialect-specific extensions
 
+pub mod ddl;
 pub mod dialect;
 pub mod expr;
+pub mod identifier;
 pub mod metadata;
 pub mod query;
 
 // Re-export commonly used types
+pub use ddl::{
+    AlterTableAction, AlterTableStatement, ColumnDef, CreateTableStatement, CreateViewStatement,
+    DdlStatement, ForeignKeyTarget, TableConstraint,
+};
 pub use dialect::{Dialect, DialectExtensions};
 pub use expr::{BinaryOp, ColumnRef, Expr, Literal, UnaryOp};
 pub use expr::{WindowFrame, WindowFrameBound, WindowFrameUnits, WindowSpec};
+pub use identifier::IdentifierComparer;
 pub use metadata::{
-    ColumnMetadata, DataType, FunctionMetadata, FunctionParameter, FunctionType, TableMetadata,
-    TableReference, TableType,
+    ColumnMetadata, DataType, FunctionMetadata, FunctionParameter, FunctionType, SequenceMetadata,
+    TableMetadata, TableReference, TableType,
 };
 pub use query::{
     Assignment, CommonTableExpr, DeleteStatement, InsertSource, InsertStatement, Join,
-    JoinCondition, JoinType, OnConflict, OrderBy, Query, SelectItem, SelectStatement, SetOp,
-    SortDirection, TableRef, UpdateStatement, WindowDef,
+    JoinCondition, JoinType, MergeAction, MergeStatement, MergeWhenClause, OnConflict, OrderBy,
+    Query, SelectItem, SelectStatement, SetOp, SortDirection, TableRef, UpdateStatement, WindowDef,
 };
@@ -54,6 +54,88 @@ pub enum DataType {
     Other(String),
 }
 
+impl DataType {
+    /// Parse a SQL type name as written in DDL into a unified DataType
+    ///
+    /// Accepts both MySQL and PostgreSQL spellings (e.g. `VARCHAR(255)`,
+    /// `character varying(64)`, `int4`, `double precision`, `text[]`).
+    /// Unrecognized names are preserved as `DataType::Other`.
+    pub fn parse(type_str: &str) -> Self {
+        let trimmed = type_str.trim();
+        let lower = trimmed.to_lowercase();
+
+        if let Some(inner) = lower.strip_suffix("[]") {
+            return DataType::Array(Box::new(DataType::parse(inner)));
+        }
+
+        // Type name is everything before the first '(' with whitespace collapsed,
+        // so "double  precision" and "character varying (10)" both match.
+        let name = lower
+            .split('(')
+            .next()
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let length = Self::parse_length(&lower);
+
+        match name.as_str() {
+            "tinyint" if length == Some(1) => DataType::Boolean,
+            "tinyint" => DataType::TinyInt,
+            "smallint" | "int2" | "smallserial" => DataType::SmallInt,
+            "int" | "integer" | "mediumint" | "int4" | "serial" => DataType::Integer,
+            "bigint" | "int8" | "bigserial" => DataType::BigInt,
+            "decimal" | "numeric" | "dec" | "money" => DataType::Decimal,
+            "float" | "real" | "float4" => DataType::Float,
+            "double" | "double precision" | "float8" => DataType::Double,
+            "varchar" | "character varying" | "nvarchar" => DataType::Varchar(length),
+            "char" | "character" | "nchar" | "bpchar" => DataType::Char(length),
+            "text" | "tinytext" | "mediumtext" | "longtext" | "citext" => DataType::Text,
+            "binary" => DataType::Binary,
+            "varbinary" => DataType::VarBinary(length),
+            "blob" | "tinyblob" | "mediumblob" | "longblob" | "bytea" => DataType::Blob,
+            "date" => DataType::Date,
+            "time" | "time without time zone" | "time with time zone" | "timetz" => DataType::Time,
+            "datetime" => DataType::DateTime,
+            "timestamp"
+            | "timestamp without time zone"
+            | "timestamp with time zone"
+            | "timestamptz" => DataType::Timestamp,
+            "bool" | "boolean" => DataType::Boolean,
+            "json" | "jsonb" => DataType::Json,
+            "uuid" => DataType::Uuid,
+            "enum" => DataType::Enum(Self::parse_enum_values(trimmed)),
+            _ => DataType::Other(trimmed.to_string()),
+        }
+    }
+
+    /// Extract the first numeric type parameter, e.g. `varchar(255)` -> `Some(255)`
+    fn parse_length(type_str: &str) -> Option<usize> {
+        let start = type_str.find('(')?;
+        let end = type_str[start..].find(')')? + start;
+        type_str[start + 1..end]
+            .split(',')
+            .next()?
+            .trim()
+            .parse()
+            .ok()
+            .filter(|len| *len > 0)
+    }
+
+    /// Extract the quoted values of a MySQL `ENUM('a', 'b')` type
+    fn parse_enum_values(type_str: &str) -> Vec<String> {
+        let Some(start) = type_str.find('(') else {
+            return Vec::new();
+        };
+        let end = type_str.rfind(')').unwrap_or(type_str.len());
+        type_str[start + 1..end]
+            .split(',')
+            .map(|v| v.trim().trim_matches('\'').to_string())
+            .filter(|v| !v.is_empty())
+            .collect()
+    }
+}
+
 /// Table type classification
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TableType {
@@ -141,6 +223,32 @@ impl ColumnMetadata {
         });
         self
     }
+
+    /// Sequence feeding this column, from a `nextval('users_id_seq')` default
+    ///
+    /// Returns the quoted name as written, which may be schema-qualified.
+    pub fn sequence(&self) -> Option<&str> {
+        let default = self.default_value.as_deref()?.trim();
+        let (head, rest) = default.split_at_checked("nextval(".len())?;
+        if !head.eq_ignore_ascii_case("nextval(") {
+            return None;
+        }
+        let name = rest.trim_start().strip_prefix('\'')?;
+        let end = name.find('\'')?;
+        Some(&name[..end])
+    }
+
+    /// Whether the database generates the value when an INSERT omits the column
+    ///
+    /// True for MySQL `AUTO_INCREMENT`, PostgreSQL `SERIAL` (a `nextval`
+    /// default) and identity columns.
+    pub fn is_auto_generated(&self) -> bool {
+        let Some(default) = self.default_value.as_deref() else {
+            return false;
+        };
+        let default = default.to_ascii_uppercase();
+        default == "AUTO_INCREMENT" || default.ends_with("AS IDENTITY") || self.sequence().is_some()
+    }
 }
 
 /// Metadata for a database table
@@ -208,6 +316,52 @@ impl TableMetadata {
     }
 }
 
+/// Metadata for a database sequence (PostgreSQL)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SequenceMetadata {
+    /// Sequence name
+    pub name: String,
+    /// Schema name
+    pub schema: String,
+    /// Value added on each `nextval`
+    pub increment: i64,
+    /// Value of the first `nextval`
+    pub start: i64,
+    /// Last value handed out, `None` if `nextval` was never called
+    pub last_value: Option<i64>,
+}
+
+impl SequenceMetadata {
+    /// Create a sequence starting at 1 with an increment of 1
+    pub fn new(name: impl Into<String>, schema: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            schema: schema.into(),
+            increment: 1,
+            start: 1,
+            last_value: None,
+        }
+    }
+
+    /// Builder method: set increment
+    pub fn with_increment(mut self, increment: i64) -> Self {
+        self.increment = increment;
+        self
+    }
+
+    /// Builder method: set start value
+    pub fn with_start(mut self, start: i64) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Builder method: set last value
+    pub fn with_last_value(mut self, last_value: i64) -> Self {
+        self.last_value = Some(last_value);
+        self
+    }
+}
+
 /// Function parameter definition
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionParameter {
@@ -247,6 +401,8 @@ pub struct FunctionMetadata {
     pub example: Option<String>,
     /// Whether this is a built-in function
     pub is_builtin: bool,
+    /// Schema of a user-defined function
+    pub schema: Option<String>,
 }
 
 impl FunctionMetadata {
@@ -260,9 +416,22 @@ impl FunctionMetadata {
             description: None,
             example: None,
             is_builtin: true,
+            schema: None,
         }
     }
 
+    /// Builder method: mark as a user-defined routine of `schema`
+    pub fn user_defined(mut self, schema: impl Into<String>) -> Self {
+        self.is_builtin = false;
+        self.schema = Some(schema.into());
+        self
+    }
+
+    /// Number of declared parameters
+    pub fn arity(&self) -> usize {
+        self.parameters.len()
+    }
+
     /// Builder method: add parameters
     pub fn with_parameters(mut self, params: Vec<FunctionParameter>) -> Self {
         self.parameters = params;
@@ -324,7 +324,7 @@
 //! - Version-specific syntax handling
 
 use crate::dialect::Dialect;
-use crate::expr::Expr;
+use crate::expr::{Expr, WindowFrame};
 use serde::{Deserialize, Serialize};
 
 /// A SQL query (SELECT statement or set operation)
@@ -403,6 +403,15 @@ pub enum SetOp {
     /// DELETE statement
     Delete(Box<DeleteStatement>),
 
+    /// MERGE statement (PostgreSQL 15+)
+    Merge(Box<MergeStatement>),
+
+    /// VALUES (row1), (row2), ... as a query of its own
+    ///
+    /// A standalone statement or the body of a CTE; rows of an INSERT are an
+    /// [`InsertSource::Values`] instead.
+    Values(Vec<Vec<Expr>>),
+
     /// UNION [ALL | DISTINCT]
     Union {
         left: Box<Query>,
@@ -491,11 +500,13 @@ pub enum OnConflict {
     /// PostgreSQL: ON CONFLICT (columns) DO NOTHING
     DoNothing { columns: Vec<String> },
 
-    /// PostgreSQL: ON CONFLICT (columns) DO UPDATE SET ...
+    /// PostgreSQL: ON CONFLICT (columns) DO UPDATE SET ... [WHERE ...]
     DoUpdate {
         conflict_columns: Vec<String>,
         update_columns: Vec<String>,
         update_exprs: Vec<Expr>,
+        /// Condition limiting which conflicting rows are updated
+        where_clause: Option<Expr>,
     },
 
     /// MySQL: ON DUPLICATE KEY UPDATE ...
@@ -517,6 +528,9 @@ pub struct UpdateStatement {
     /// Column assignments
     pub assignments: Vec<Assignment>,
 
+    /// Tables of a PostgreSQL `UPDATE ... FROM`
+    pub from: Vec<TableRef>,
+
     /// WHERE clause
     pub where_clause: Option<Expr>,
 
@@ -537,6 +551,9 @@ pub struct DeleteStatement {
     /// Table to delete from
     pub table: TableRef,
 
+    /// Tables of a PostgreSQL `DELETE ... USING`
+    pub using: Vec<TableRef>,
+
     /// WHERE clause
     pub where_clause: Option<Expr>,
 
@@ -544,6 +561,60 @@ pub struct DeleteStatement {
     pub returning: Option<Vec<SelectItem>>,
 }
 
+/// MERGE statement
+///
+/// ```sql
+/// MERGE INTO target t USING source s ON t.id = s.id
+/// WHEN MATCHED THEN UPDATE SET name = s.name
+/// WHEN NOT MATCHED THEN INSERT (id, name) VALUES (s.id, s.name)
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MergeStatement {
+    /// Table being modified
+    pub target: TableRef,
+
+    /// Table providing the rows to merge
+    pub source: TableRef,
+
+    /// Join condition between target and source
+    pub on: Expr,
+
+    /// WHEN clauses, in source order
+    pub when_clauses: Vec<MergeWhenClause>,
+}
+
+/// `WHEN [NOT] MATCHED [AND condition] THEN action` in a MERGE
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MergeWhenClause {
+    /// Whether the clause applies to matched rows (`WHEN MATCHED`)
+    pub matched: bool,
+
+    /// Extra condition after `AND`
+    pub condition: Option<Expr>,
+
+    /// Action to take
+    pub action: MergeAction,
+}
+
+/// Action of a MERGE WHEN clause
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MergeAction {
+    /// UPDATE SET col = expr, ...
+    Update { assignments: Vec<Assignment> },
+
+    /// DELETE
+    Delete,
+
+    /// INSERT [(columns)] VALUES (exprs) | DEFAULT VALUES
+    Insert {
+        columns: Vec<String>,
+        values: Option<Vec<Expr>>,
+    },
+
+    /// DO NOTHING
+    DoNothing,
+}
+
 /// Item in a SELECT projection list
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SelectItem {
@@ -628,6 +699,9 @@ pub struct CommonTableExpr {
 }
 
 /// Window definition
+///
+/// A named window from a `WINDOW name AS (...)` clause. Shares its frame type
+/// with the inline [`WindowSpec`](crate::expr::WindowSpec) of an OVER clause.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WindowDef {
     pub name: Option<String>,
@@ -635,27 +709,3 @@ pub struct WindowDef {
     pub order_by: Vec<OrderBy>,
     pub window_frame: Option<WindowFrame>,
 }
-
-/// Window frame
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct WindowFrame {
-    pub units: WindowFrameUnits,
-    pub start_bound: WindowFrameBound,
-    pub end_bound: Option<WindowFrameBound>,
-}
-
-/// Window frame units
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum WindowFrameUnits {
-    Rows,
-    Range,
-    Groups,
-}
-
-/// Window frame bound
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub enum WindowFrameBound {
-    CurrentRow,
-    Preceding(Option<Expr>),
-    Following(Option<Expr>),
-}
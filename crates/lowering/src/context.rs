@@ -7,6 +7,7 @@
 
 use crate::error::{LoweringError, LoweringOutcome};
 use std::collections::HashMap;
+use std::ops::Range;
 use unified_sql_lsp_ir::{Dialect, Expr};
 
 /// Context for tracking state during CST → IR lowering
@@ -36,6 +37,17 @@ pub struct LoweringContext {
     /// Source mappings from IR nodes to CST node locations
     /// Maps: "query:0" -> (line: 5, column: 10)
     source_mappings: HashMap<String, SourceLocation>,
+
+    /// Byte ranges of IR nodes in the source
+    /// Maps: "binary_expr:0" -> 12..25, "binary_expr:0:operator" -> 15..17,
+    /// "definition:0" -> 5..11
+    source_spans: HashMap<String, Range<usize>>,
+
+    /// Binary expressions mapped so far
+    binary_expr_counter: usize,
+
+    /// Definitions mapped so far
+    definition_counter: usize,
 }
 
 /// Source location in the original SQL text
@@ -59,6 +71,9 @@ impl LoweringContext {
             recursion_depth: 0,
             max_recursion_depth: 100,
             source_mappings: HashMap::new(),
+            source_spans: HashMap::new(),
+            binary_expr_counter: 0,
+            definition_counter: 0,
         }
     }
 
@@ -71,6 +86,9 @@ impl LoweringContext {
             recursion_depth: 0,
             max_recursion_depth: max_depth,
             source_mappings: HashMap::new(),
+            source_spans: HashMap::new(),
+            binary_expr_counter: 0,
+            definition_counter: 0,
         }
     }
 
@@ -168,6 +186,50 @@ impl LoweringContext {
         self.source_mappings.get(ir_id)
     }
 
+    /// Map the binary expression just built to `span`, its operator to `operator`
+    ///
+    /// Binary expressions are numbered in the order they are built, the order
+    /// of [`Expr::binary_exprs`]; see [`binary_expr_id`].
+    pub fn add_binary_expr_span(&mut self, span: Range<usize>, operator: Range<usize>) {
+        let id = binary_expr_id(self.binary_expr_counter);
+        self.binary_expr_counter += 1;
+        self.source_spans
+            .insert(format!("{}:operator", id), operator);
+        self.source_spans.insert(id, span);
+    }
+
+    /// Map the name just defined, a CTE or a table of a FROM list, to `span`
+    ///
+    /// Definitions are numbered in source order: each CTE before the
+    /// definitions of its query, each table before its joins. See
+    /// [`definition_id`].
+    pub fn add_definition_span(&mut self, span: Range<usize>) {
+        let id = definition_id(self.definition_counter);
+        self.definition_counter += 1;
+        self.source_spans.insert(id, span);
+    }
+
+    /// Run `lower` without mapping what it builds to its source
+    ///
+    /// Subqueries are lowered this way, so their binary expressions don't
+    /// number among those of the enclosing expression, nor their tables
+    /// among the definitions of the enclosing query.
+    pub fn unmapped<T>(&mut self, lower: impl FnOnce(&mut Self) -> T) -> T {
+        let spans = std::mem::take(&mut self.source_spans);
+        let binary_exprs = std::mem::take(&mut self.binary_expr_counter);
+        let definitions = std::mem::take(&mut self.definition_counter);
+        let lowered = lower(self);
+        self.source_spans = spans;
+        self.binary_expr_counter = binary_exprs;
+        self.definition_counter = definitions;
+        lowered
+    }
+
+    /// Get the byte range of an IR node in the source
+    pub fn get_source_span(&self, ir_id: &str) -> Option<Range<usize>> {
+        self.source_spans.get(ir_id).cloned()
+    }
+
     /// Increment recursion depth and check for overflow
     pub fn enter_recursive_context(&mut self) -> Result<(), LoweringError> {
         self.recursion_depth += 1;
@@ -195,6 +257,19 @@ impl LoweringContext {
     }
 }
 
+/// Source map id of the binary expression at `index` of [`Expr::binary_exprs`]
+///
+/// Its operator is mapped under the id followed by `:operator`.
+pub fn binary_expr_id(index: usize) -> String {
+    format!("binary_expr:{}", index)
+}
+
+/// Source map id of the definition numbered `index` by
+/// [`LoweringContext::add_definition_span`]
+pub fn definition_id(index: usize) -> String {
+    format!("definition:{}", index)
+}
+
 /// Create a SourceLocation from a tree-sitter node position
 ///
 /// This utility function converts tree-sitter's Point structure
@@ -305,6 +380,38 @@ mod tests {
         assert_eq!(retrieved.unwrap().line, 5);
     }
 
+    #[test]
+    fn test_binary_expr_spans() {
+        let mut ctx = LoweringContext::new(Dialect::MySQL);
+
+        // `a = 1 AND b = 2`: the comparisons are built before the AND
+        ctx.add_binary_expr_span(0..5, 2..3);
+        ctx.add_binary_expr_span(10..15, 12..13);
+        ctx.add_binary_expr_span(0..15, 6..9);
+
+        assert_eq!(ctx.get_source_span(&binary_expr_id(1)), Some(10..15));
+        assert_eq!(ctx.get_source_span("binary_expr:2:operator"), Some(6..9));
+        assert_eq!(ctx.get_source_span(&binary_expr_id(3)), None);
+
+        // A subquery's expressions leave the numbering alone
+        ctx.unmapped(|ctx| ctx.add_binary_expr_span(20..25, 22..23));
+        ctx.add_binary_expr_span(16..30, 16..18);
+        assert_eq!(ctx.get_source_span(&binary_expr_id(3)), Some(16..30));
+        assert_eq!(ctx.get_source_span(&binary_expr_id(4)), None);
+    }
+
+    #[test]
+    fn test_definition_spans() {
+        let mut ctx = LoweringContext::new(Dialect::PostgreSQL);
+        ctx.add_definition_span(5..11);
+        ctx.unmapped(|ctx| ctx.add_definition_span(40..45));
+        ctx.add_definition_span(60..62);
+
+        assert_eq!(ctx.get_source_span(&definition_id(0)), Some(5..11));
+        assert_eq!(ctx.get_source_span(&definition_id(1)), Some(60..62));
+        assert_eq!(ctx.get_source_span(&definition_id(2)), None);
+    }
+
     #[test]
     fn test_clear_errors() {
         let mut ctx = LoweringContext::new(Dialect::MySQL);
@@ -3,12 +3,11 @@
 // Licensed under the MIT License or Apache License 2.0
 // See LICENSE files for details
 
-//! Generic CST node wrapper for pre-Tree-sitter integration
+//! Generic CST node wrapper
 //!
-//! This module provides a generic abstraction over CST nodes that can work
-//! with the actual Tree-sitter implementation when it's integrated.
-//!
-//! For now, it provides a mock implementation for testing the lowering trait.
+//! This module provides a generic abstraction over CST nodes, an owned
+//! adapter for Tree-sitter nodes, and a mock implementation for testing
+//! the lowering trait.
 
 use std::collections::HashMap;
 use std::fmt;
@@ -144,6 +143,108 @@ impl CstNode for MockCstNode {
     }
 }
 
+/// Owned copy of a Tree-sitter subtree
+///
+/// Lowering looks clauses up by node kind (`children("from_clause")`), so
+/// [`CstNode::children`] matches the Tree-sitter field name first and falls
+/// back to children of that kind. Comments (extra nodes) are dropped.
+#[derive(Debug, Clone)]
+pub struct TreeSitterNode {
+    kind: &'static str,
+    named: bool,
+    children: Vec<TreeSitterNode>,
+    fields: Vec<Option<&'static str>>,
+    start_byte: usize,
+    end_byte: usize,
+    text: String,
+}
+
+impl TreeSitterNode {
+    /// Copy `node` and its descendants, taking text from `source`
+    pub fn new(node: tree_sitter::Node, source: &str) -> Self {
+        let mut children = Vec::new();
+        let mut fields = Vec::new();
+        let mut cursor = node.walk();
+        if cursor.goto_first_child() {
+            loop {
+                let child = cursor.node();
+                if !child.is_extra() {
+                    fields.push(cursor.field_name());
+                    children.push(Self::new(child, source));
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+        }
+
+        Self {
+            kind: node.kind(),
+            named: node.is_named(),
+            children,
+            fields,
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+            text: source
+                .get(node.start_byte()..node.end_byte())
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+}
+
+impl CstNode for TreeSitterNode {
+    fn kind(&self) -> &str {
+        self.kind
+    }
+
+    fn children(&self, field: &str) -> Vec<&Self> {
+        let by_field: Vec<&Self> = self
+            .children
+            .iter()
+            .zip(&self.fields)
+            .filter(|(_, name)| **name == Some(field))
+            .map(|(child, _)| child)
+            .collect();
+        if !by_field.is_empty() {
+            return by_field;
+        }
+
+        self.children
+            .iter()
+            .filter(|child| child.kind == field)
+            .collect()
+    }
+
+    fn all_children(&self) -> Vec<&Self> {
+        self.children.iter().collect()
+    }
+
+    fn child_count(&self) -> usize {
+        self.children.len()
+    }
+
+    fn start_byte(&self) -> usize {
+        self.start_byte
+    }
+
+    fn end_byte(&self) -> usize {
+        self.end_byte
+    }
+
+    fn text(&self) -> Option<&str> {
+        Some(&self.text)
+    }
+
+    fn is_named(&self) -> bool {
+        self.named
+    }
+
+    fn parent(&self) -> Option<&Self> {
+        None // Copies are owned top-down
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,357 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! Shared DDL lowering
+//!
+//! CREATE TABLE, CREATE VIEW and ALTER TABLE have the same shape in every
+//! supported dialect grammar; only identifier normalization and the view's
+//! defining query are dialect-specific, so both are passed in as callbacks.
+
+use crate::dialect::SharedLowering;
+use crate::{CstNode, LoweringContext, LoweringError, LoweringResult};
+use unified_sql_lsp_ir::Query;
+use unified_sql_lsp_ir::ddl::{
+    AlterTableAction, AlterTableStatement, ColumnDef, CreateTableStatement, CreateViewStatement,
+    DdlStatement, ForeignKeyTarget, TableConstraint,
+};
+
+impl SharedLowering {
+    /// Lower a `create_table_statement`, `create_view_statement` or
+    /// `alter_table_statement` node
+    pub fn lower_ddl<N, F, Q>(
+        ctx: &mut LoweringContext,
+        node: &N,
+        normalize_fn: F,
+        lower_query_fn: Q,
+    ) -> LoweringResult<DdlStatement>
+    where
+        N: CstNode,
+        F: Fn(&str) -> String + Copy,
+        Q: Fn(&mut LoweringContext, &N) -> LoweringResult<Query>,
+    {
+        match node.kind() {
+            "create_table_statement" => {
+                Self::lower_create_table(ctx, node, normalize_fn).map(DdlStatement::CreateTable)
+            }
+            "create_view_statement" => {
+                Self::lower_create_view(ctx, node, normalize_fn, lower_query_fn)
+                    .map(DdlStatement::CreateView)
+            }
+            "alter_table_statement" => {
+                Self::lower_alter_table(ctx, node, normalize_fn).map(DdlStatement::AlterTable)
+            }
+            _ => Err(LoweringError::UnexpectedNodeType {
+                expected: "CREATE TABLE, CREATE VIEW, or ALTER TABLE statement".to_string(),
+                found: node.kind().to_string(),
+            }),
+        }
+    }
+
+    fn lower_create_table<N, F>(
+        ctx: &mut LoweringContext,
+        node: &N,
+        normalize_fn: F,
+    ) -> LoweringResult<CreateTableStatement>
+    where
+        N: CstNode,
+        F: Fn(&str) -> String + Copy,
+    {
+        let (schema, name) = Self::lower_object_name(node, "create_table_statement", normalize_fn)?;
+        let mut statement = CreateTableStatement {
+            schema,
+            name,
+            temporary: Self::has_keyword(node, &["TEMPORARY", "TEMP"]),
+            columns: Vec::new(),
+            constraints: Vec::new(),
+            comment: None,
+        };
+
+        for child in node.all_children() {
+            match child.kind() {
+                "column_definition" => {
+                    if let Some(column) = Self::lower_column_definition(ctx, child, normalize_fn) {
+                        statement.columns.push(column);
+                    }
+                }
+                "table_constraint" => {
+                    if let Some(constraint) = Self::lower_table_constraint(child, normalize_fn) {
+                        statement.constraints.push(constraint);
+                    }
+                }
+                "table_option" => {
+                    if let Some(comment) = optional_child(child, "table_comment")
+                        .and_then(|comment| optional_child(comment, "string_literal"))
+                    {
+                        statement.comment = Some(Self::string_value(comment));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(statement)
+    }
+
+    fn lower_create_view<N, F, Q>(
+        ctx: &mut LoweringContext,
+        node: &N,
+        normalize_fn: F,
+        lower_query_fn: Q,
+    ) -> LoweringResult<CreateViewStatement>
+    where
+        N: CstNode,
+        F: Fn(&str) -> String + Copy,
+        Q: Fn(&mut LoweringContext, &N) -> LoweringResult<Query>,
+    {
+        let (schema, name) = Self::lower_object_name(node, "create_view_statement", normalize_fn)?;
+        let Some(query_node) = optional_child(node, "select_statement") else {
+            return Err(LoweringError::MissingChild {
+                context: "create_view_statement".to_string(),
+                expected: "defining query".to_string(),
+            });
+        };
+
+        Ok(CreateViewStatement {
+            schema,
+            name,
+            materialized: Self::has_keyword(node, &["MATERIALIZED"]),
+            columns: optional_child(node, "column_list")
+                .map(|list| Self::column_names(list, normalize_fn))
+                .unwrap_or_default(),
+            query: Box::new(lower_query_fn(ctx, query_node)?),
+        })
+    }
+
+    fn lower_alter_table<N, F>(
+        ctx: &mut LoweringContext,
+        node: &N,
+        normalize_fn: F,
+    ) -> LoweringResult<AlterTableStatement>
+    where
+        N: CstNode,
+        F: Fn(&str) -> String + Copy,
+    {
+        let (schema, name) = Self::lower_object_name(node, "alter_table_statement", normalize_fn)?;
+        let mut actions = Vec::new();
+
+        for action in node.children("alter_table_action") {
+            if let Some(column) = optional_child(action, "column_definition") {
+                if let Some(column) = Self::lower_column_definition(ctx, column, normalize_fn) {
+                    actions.push(AlterTableAction::AddColumn(column));
+                }
+            } else if let Some(constraint) = optional_child(action, "table_constraint")
+                .and_then(|constraint| Self::lower_table_constraint(constraint, normalize_fn))
+            {
+                actions.push(AlterTableAction::AddConstraint(constraint));
+            }
+        }
+
+        Ok(AlterTableStatement {
+            schema,
+            name,
+            actions,
+        })
+    }
+
+    /// The `(schema, name)` of the statement's `object_name`
+    fn lower_object_name<N, F>(
+        node: &N,
+        context: &str,
+        normalize_fn: F,
+    ) -> LoweringResult<(Option<String>, String)>
+    where
+        N: CstNode,
+        F: Fn(&str) -> String + Copy,
+    {
+        let object_name = optional_child(node, "object_name");
+        let Some(table_name) = object_name.and_then(|name| optional_child(name, "table_name"))
+        else {
+            return Err(LoweringError::MissingChild {
+                context: context.to_string(),
+                expected: "object name".to_string(),
+            });
+        };
+
+        let schema = object_name
+            .and_then(|name| optional_child(name, "identifier"))
+            .map(|schema| normalize_fn(schema.text().unwrap_or("")));
+        Ok((schema, normalize_fn(table_name.text().unwrap_or(""))))
+    }
+
+    fn lower_column_definition<N, F>(
+        ctx: &mut LoweringContext,
+        node: &N,
+        normalize_fn: F,
+    ) -> Option<ColumnDef>
+    where
+        N: CstNode,
+        F: Fn(&str) -> String + Copy,
+    {
+        let (Some(name), Some(data_type)) = (
+            optional_child(node, "column_name"),
+            optional_child(node, "data_type"),
+        ) else {
+            ctx.add_error(LoweringError::MissingChild {
+                context: "column_definition".to_string(),
+                expected: "column name and data type".to_string(),
+            });
+            return None;
+        };
+
+        let mut column = ColumnDef::new(
+            normalize_fn(name.text().unwrap_or("")),
+            Self::data_type_text(data_type),
+        );
+        for constraint in node.all_children() {
+            match constraint.kind() {
+                "not_null_constraint" => column.nullable = false,
+                "null_constraint" => column.nullable = true,
+                "primary_key_constraint" => {
+                    column.primary_key = true;
+                    column.nullable = false;
+                }
+                "default_constraint" => {
+                    column.default = constraint
+                        .all_children()
+                        .into_iter()
+                        .find(|value| value.is_named())
+                        .and_then(|value| value.text())
+                        .map(str::to_string);
+                }
+                "auto_increment_constraint" => {
+                    column.default = Some("AUTO_INCREMENT".to_string());
+                }
+                "identity_constraint" => {
+                    let text = constraint.text().unwrap_or("");
+                    column.default = Some(text.split_whitespace().collect::<Vec<_>>().join(" "));
+                }
+                "comment_constraint" => {
+                    column.comment =
+                        optional_child(constraint, "string_literal").map(Self::string_value);
+                }
+                "references_clause" => {
+                    column.references = Self::lower_references(constraint, normalize_fn);
+                }
+                _ => {}
+            }
+        }
+
+        Some(column)
+    }
+
+    fn lower_table_constraint<N, F>(node: &N, normalize_fn: F) -> Option<TableConstraint>
+    where
+        N: CstNode,
+        F: Fn(&str) -> String + Copy,
+    {
+        let definition = node.all_children().into_iter().find(|child| {
+            matches!(
+                child.kind(),
+                "primary_key_definition"
+                    | "foreign_key_definition"
+                    | "unique_definition"
+                    | "index_definition"
+            )
+        })?;
+        let columns = optional_child(definition, "column_list")
+            .map(|list| Self::column_names(list, normalize_fn))
+            .unwrap_or_default();
+
+        match definition.kind() {
+            "primary_key_definition" => Some(TableConstraint::PrimaryKey(columns)),
+            "foreign_key_definition" => {
+                let references = optional_child(definition, "references_clause")
+                    .and_then(|clause| Self::lower_references(clause, normalize_fn))?;
+                Some(TableConstraint::ForeignKey {
+                    columns,
+                    references,
+                })
+            }
+            "unique_definition" => Some(TableConstraint::Unique(columns)),
+            _ => Some(TableConstraint::Index(columns)),
+        }
+    }
+
+    /// `REFERENCES table [(columns)]`
+    fn lower_references<N, F>(node: &N, normalize_fn: F) -> Option<ForeignKeyTarget>
+    where
+        N: CstNode,
+        F: Fn(&str) -> String + Copy,
+    {
+        let table = optional_child(node, "object_name")
+            .and_then(|name| optional_child(name, "table_name"))?;
+        Some(ForeignKeyTarget {
+            table: normalize_fn(table.text().unwrap_or("")),
+            columns: optional_child(node, "column_list")
+                .map(|list| Self::column_names(list, normalize_fn))
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Type name, parameters and array dimensions, e.g. `DECIMAL(10,2)`
+    fn data_type_text<N>(node: &N) -> String
+    where
+        N: CstNode,
+    {
+        let mut text = String::new();
+        for child in node.all_children() {
+            match child.kind() {
+                "type_name" => text.push_str(child.text().unwrap_or("")),
+                "type_parameters" => {
+                    let parameters: Vec<&str> = child
+                        .all_children()
+                        .into_iter()
+                        .filter(|parameter| parameter.is_named())
+                        .filter_map(|parameter| parameter.text())
+                        .collect();
+                    text.push_str(&format!("({})", parameters.join(",")));
+                }
+                "[" => text.push('['),
+                "]" => text.push(']'),
+                _ => {}
+            }
+        }
+        text
+    }
+
+    fn column_names<N, F>(node: &N, normalize_fn: F) -> Vec<String>
+    where
+        N: CstNode,
+        F: Fn(&str) -> String + Copy,
+    {
+        node.children("column_name")
+            .into_iter()
+            .map(|column| normalize_fn(column.text().unwrap_or("")))
+            .collect()
+    }
+
+    /// Contents of a string literal with `''` unescaped
+    fn string_value<N>(node: &N) -> String
+    where
+        N: CstNode,
+    {
+        let text = node.text().unwrap_or("");
+        text.strip_prefix('\'')
+            .and_then(|inner| inner.strip_suffix('\''))
+            .unwrap_or(text)
+            .replace("''", "'")
+    }
+
+    fn has_keyword<N>(node: &N, keywords: &[&str]) -> bool
+    where
+        N: CstNode,
+    {
+        node.all_children()
+            .into_iter()
+            .any(|child| keywords.contains(&child.kind()))
+    }
+}
+
+fn optional_child<'a, N>(node: &'a N, kind: &str) -> Option<&'a N>
+where
+    N: CstNode,
+{
+    node.children(kind).first().copied()
+}
@@ -6,6 +6,7 @@
 //! Dialect-specific lowering implementations
 
 pub mod base;
+pub mod ddl;
 pub mod mysql;
 pub mod postgresql;
 pub mod shared;
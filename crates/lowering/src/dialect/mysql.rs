@@ -11,6 +11,7 @@
 //! - Backtick identifiers
 //! - REPLACE statement
 //! - SELECT modifiers (SQL_CALC_FOUND_ROWS, SQL_CACHE, SQL_NO_CACHE)
+//! - JSON path operators (`->` and `->>`)
 
 use crate::dialect::{DialectLoweringBase, SharedLowering};
 use crate::{CstNode, Lowering, LoweringContext, LoweringError, LoweringResult};
@@ -31,6 +32,15 @@ where
         match node.kind() {
             "select_statement" => self.lower_select_statement(ctx, node),
             "replace_statement" => self.lower_replace_statement(ctx, node),
+            "merge_statement" => {
+                // MySQL has no MERGE; keep going with an empty query
+                ctx.add_error(LoweringError::UnsupportedSyntax {
+                    dialect: "MySQL".to_string(),
+                    feature: "MERGE statement".to_string(),
+                    suggestion: "Use INSERT ... ON DUPLICATE KEY UPDATE instead".to_string(),
+                });
+                Ok(Query::new(Dialect::MySQL))
+            }
             _ => Err(LoweringError::UnexpectedNodeType {
                 expected: "SELECT or REPLACE statement".to_string(),
                 found: node.kind().to_string(),
@@ -51,13 +61,22 @@ where
             "literal" => SharedLowering::lower_literal(ctx, node),
             "function_call" => self.lower_function_call(ctx, node),
             "case_expression" => SharedLowering::lower_case_expr(ctx, node, "MySQL"),
-            "parenthesized_expression" => {
+            "cast_expression" => {
+                let lower_fn = |ctx: &mut LoweringContext, n: &N| self.lower_expr(ctx, n);
+                SharedLowering::lower_cast_expr(ctx, node, lower_fn)
+            }
+            "json_path_expression" => self.lower_json_path_expr(ctx, node),
+            "subquery" => {
+                let lower_query = |ctx: &mut LoweringContext, n: &N| self.lower_query(ctx, n);
+                SharedLowering::lower_subquery(ctx, node, lower_query)
+            }
+            "expression" | "parenthesized_expression" => {
                 let children = node.all_children();
                 if let Some(inner) = children.first() {
                     self.lower_expr(ctx, *inner)
                 } else {
                     ctx.add_error(LoweringError::MissingChild {
-                        context: "parenthesized_expression".to_string(),
+                        context: node.kind().to_string(),
                         expected: "inner expression".to_string(),
                     });
                     Ok(ctx.create_placeholder())
@@ -86,6 +105,9 @@ where
                 | "literal"
                 | "function_call"
                 | "case_expression"
+                | "cast_expression"
+                | "json_path_expression"
+                | "subquery"
                 | "parenthesized_expression"
         )
     }
@@ -107,6 +129,17 @@ impl MySQLLowering {
     {
         let mut select = SelectStatement::default();
 
+        // Lower WITH clause (MySQL 8.0+)
+        let ctes = match self.optional_child(node, "cte_clause") {
+            Some(cte_node) => SharedLowering::lower_cte_clause(
+                ctx,
+                cte_node,
+                Self::normalize_identifier,
+                |ctx, n| self.lower_query(ctx, n),
+            )?,
+            None => Vec::new(),
+        };
+
         // Lower projection (SELECT clause)
         if let Some(proj_node) = self.optional_child(node, "projection") {
             let lower_expr = |ctx: &mut LoweringContext, n: &N| self.lower_expr(ctx, n);
@@ -163,6 +196,7 @@ impl MySQLLowering {
         // Build query with SELECT body
         let mut query = Query::new(Dialect::MySQL);
         query.body = unified_sql_lsp_ir::SetOp::Select(Box::new(select));
+        query.ctes = ctes;
 
         // Lower ORDER BY clause
         if let Some(order_node) = self.optional_child(node, "order_by_clause") {
@@ -322,6 +356,41 @@ impl MySQLLowering {
         })
     }
 
+    /// Lower JSON path extraction (`expr->'$.path'`, `expr->>'$.path'`)
+    fn lower_json_path_expr<N>(&self, ctx: &mut LoweringContext, node: &N) -> LoweringResult<Expr>
+    where
+        N: CstNode,
+    {
+        let (Some(expr_node), Some(path_node)) = (
+            self.optional_child(node, "expression"),
+            self.optional_child(node, "path"),
+        ) else {
+            ctx.add_error(LoweringError::MissingChild {
+                context: "json_path_expression".to_string(),
+                expected: "expression and path".to_string(),
+            });
+            return Ok(ctx.create_placeholder());
+        };
+
+        let expr = self.lower_expr(ctx, expr_node)?;
+        let unquote = self
+            .optional_child(node, "operator")
+            .and_then(|op| op.text())
+            == Some("->>");
+        let path_text = path_node.text().unwrap_or("").trim();
+        let path = path_text
+            .strip_prefix('\'')
+            .and_then(|p| p.strip_suffix('\''))
+            .unwrap_or(path_text)
+            .replace("''", "'");
+
+        Ok(Expr::JsonPath {
+            expr: Box::new(expr),
+            path,
+            unquote,
+        })
+    }
+
     /// Normalize identifier by removing backticks
     fn normalize_identifier(identifier: &str) -> String {
         identifier
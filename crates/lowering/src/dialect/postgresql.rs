@@ -12,8 +12,12 @@
 //! - Dollar-quoted string literals ($$string$$ or $tag$string$tag$)
 //! - CTE (WITH clauses) including MATERIALIZED
 //! - RETURNING clause (placeholder for future IR support)
+//! - INSERT ... ON CONFLICT DO NOTHING / DO UPDATE
+//! - MERGE (PostgreSQL 15+)
 //! - DISTINCT ON (graceful degradation to regular DISTINCT)
 //! - LATERAL JOIN (placeholder for future IR support)
+//! - `::` type casts
+//! - Standalone VALUES lists, e.g. as a CTE body
 //!
 //! ## Error Handling
 //!
@@ -21,15 +25,16 @@
 //! - Critical errors (missing structure) → Return `Err(LoweringError)`
 //! - Recoverable errors (unsupported syntax) → Add to context, insert placeholder, continue
 
-use crate::dialect::DialectLoweringBase;
+use crate::dialect::{DialectLoweringBase, SharedLowering};
 use crate::{CstNode, Lowering, LoweringContext, LoweringError, LoweringResult};
 use unified_sql_lsp_ir::expr::{BinaryOp, ColumnRef, Literal, UnaryOp};
 use unified_sql_lsp_ir::query::{OrderBy, SelectItem, SelectStatement, SortDirection, TableRef};
 use unified_sql_lsp_ir::{
     Assignment, DeleteStatement, InsertSource, InsertStatement, Join, JoinCondition, JoinType,
-    UpdateStatement, WindowFrame, WindowFrameBound, WindowFrameUnits, WindowSpec,
+    MergeAction, MergeStatement, MergeWhenClause, OnConflict, UpdateStatement, WindowDef,
+    WindowFrame, WindowFrameBound, WindowFrameUnits, WindowSpec,
 };
-use unified_sql_lsp_ir::{Dialect, Expr, Query};
+use unified_sql_lsp_ir::{Dialect, Expr, Query, SetOp};
 
 /// PostgreSQL CST → IR lowering implementation
 pub struct PostgreSQLLowering;
@@ -46,8 +51,10 @@ where
             "insert_statement" => self.lower_insert_statement(ctx, node),
             "update_statement" => self.lower_update_statement(ctx, node),
             "delete_statement" => self.lower_delete_statement(ctx, node),
+            "merge_statement" => self.lower_merge_statement(ctx, node),
+            "values_statement" => self.lower_values_statement(ctx, node),
             _ => Err(LoweringError::UnexpectedNodeType {
-                expected: "SELECT, INSERT, UPDATE, or DELETE statement".to_string(),
+                expected: "SELECT, INSERT, UPDATE, DELETE, MERGE, or VALUES statement".to_string(),
                 found: node.kind().to_string(),
             }),
         }
@@ -61,15 +68,23 @@ where
             "literal" => self.lower_postgresql_literal(ctx, node),
             "function_call" => self.lower_function_call(ctx, node),
             "case_expression" => self.lower_case_expr(ctx, node),
-            "parenthesized_expression" => {
-                // Lower the inner expression
+            "cast_expression" | "type_cast_expression" => {
+                let lower_fn = |ctx: &mut LoweringContext, n: &N| self.lower_expr(ctx, n);
+                SharedLowering::lower_cast_expr(ctx, node, lower_fn)
+            }
+            "subquery" => {
+                let lower_query = |ctx: &mut LoweringContext, n: &N| self.lower_query(ctx, n);
+                SharedLowering::lower_subquery(ctx, node, lower_query)
+            }
+            "expression" | "parenthesized_expression" => {
+                // Lower the inner expression; `expression` wraps every operand
                 let children = node.all_children();
                 if let Some(inner) = children.first() {
                     // Dereference to get &N from &&N
                     self.lower_expr(ctx, *inner)
                 } else {
                     ctx.add_error(LoweringError::MissingChild {
-                        context: "parenthesized_expression".to_string(),
+                        context: node.kind().to_string(),
                         expected: "inner expression".to_string(),
                     });
                     Ok(ctx.create_placeholder())
@@ -94,6 +109,8 @@ where
                 | "insert_statement"
                 | "update_statement"
                 | "delete_statement"
+                | "merge_statement"
+                | "values_statement"
                 | "binary_expression"
                 | "unary_expression"
                 | "column_reference"
@@ -101,6 +118,9 @@ where
                 | "literal"
                 | "function_call"
                 | "case_expression"
+                | "cast_expression"
+                | "type_cast_expression"
+                | "subquery"
                 | "parenthesized_expression"
         )
     }
@@ -122,6 +142,18 @@ impl PostgreSQLLowering {
     {
         let mut select = SelectStatement::default();
 
+        // Lower WITH clause first, so CTE names number before the tables
+        // using them
+        let ctes = match self.optional_child(node, "cte_clause") {
+            Some(cte_node) => SharedLowering::lower_cte_clause(
+                ctx,
+                cte_node,
+                |identifier| self.normalize_identifier(identifier),
+                |ctx, n| self.lower_query(ctx, n),
+            )?,
+            None => Vec::new(),
+        };
+
         // Check for DISTINCT ON clause (PostgreSQL-specific)
         if let Some(distinct_on_node) = self.optional_child(node, "distinct_on_clause") {
             match self.handle_distinct_on(ctx, distinct_on_node) {
@@ -164,9 +196,15 @@ impl PostgreSQLLowering {
             select.having = Some(self.lower_having_clause(ctx, having_node)?);
         }
 
+        // Lower WINDOW clause
+        if let Some(window_node) = self.optional_child(node, "window_clause") {
+            select.window = self.lower_window_clause(ctx, window_node)?;
+        }
+
         // Build query with SELECT body
         let mut query = Query::new(Dialect::PostgreSQL);
         query.body = unified_sql_lsp_ir::SetOp::Select(Box::new(select));
+        query.ctes = ctes;
 
         // Lower ORDER BY clause
         if let Some(order_node) = self.optional_child(node, "order_by_clause") {
@@ -183,6 +221,53 @@ impl PostgreSQLLowering {
         Ok(query)
     }
 
+    /// Lower a standalone VALUES list, e.g. the body of a CTE
+    fn lower_values_statement<N>(
+        &self,
+        ctx: &mut LoweringContext,
+        node: &N,
+    ) -> LoweringResult<Query>
+    where
+        N: CstNode,
+    {
+        let rows: Vec<Vec<Expr>> = node
+            .all_children()
+            .into_iter()
+            .filter(|row| row.kind() == "value_list")
+            .map(|row| self.lower_value_row(ctx, row))
+            .collect();
+        if rows.is_empty() {
+            return Err(LoweringError::MissingChild {
+                context: "values_statement".to_string(),
+                expected: "value_list".to_string(),
+            });
+        }
+
+        let mut query = Query::new(Dialect::PostgreSQL);
+        query.body = SetOp::Values(rows);
+        Ok(query)
+    }
+
+    /// Lower the values of one `(...)` row of a VALUES list
+    fn lower_value_row<N>(&self, ctx: &mut LoweringContext, row: &N) -> Vec<Expr>
+    where
+        N: CstNode,
+    {
+        row.all_children()
+            .into_iter()
+            .filter(|value| !matches!(value.kind(), "(" | "," | ")"))
+            .map(|value| {
+                // Unwrap the `expression` node around the value
+                let value = match value.kind() {
+                    "expression" => value.all_children().first().copied().unwrap_or(value),
+                    _ => value,
+                };
+                self.lower_expr(ctx, value)
+                    .unwrap_or_else(|_| ctx.create_placeholder())
+            })
+            .collect()
+    }
+
     /// Lower an INSERT statement with RETURNING clause support
     fn lower_insert_statement<N>(
         &self,
@@ -231,6 +316,11 @@ impl PostgreSQLLowering {
             insert.source = InsertSource::DefaultValues;
         }
 
+        // Handle ON CONFLICT clause
+        if let Some(conflict_node) = self.optional_child(node, "on_conflict_clause") {
+            insert.on_conflict = self.lower_on_conflict_clause(ctx, conflict_node)?;
+        }
+
         // Handle RETURNING clause
         if let Some(returning_node) = self.optional_child(node, "returning_clause") {
             insert.returning = Some(self.handle_returning_clause(ctx, returning_node)?);
@@ -258,6 +348,7 @@ impl PostgreSQLLowering {
                 joins: Vec::new(),
             },
             assignments: Vec::new(),
+            from: Vec::new(),
             where_clause: None,
             returning: None,
         };
@@ -270,32 +361,7 @@ impl PostgreSQLLowering {
 
         // Extract SET assignments
         if let Some(set_node) = self.optional_child(node, "set_clause") {
-            for child in set_node.all_children() {
-                if child.kind() == "assignment" {
-                    // Parse column = value
-                    let children = child.all_children();
-                    let column = if let Some(col_node) = children.first() {
-                        if col_node.kind() == "identifier" {
-                            self.normalize_identifier(col_node.text().unwrap_or(""))
-                        } else {
-                            String::new()
-                        }
-                    } else {
-                        String::new()
-                    };
-
-                    let value = if let Some(expr_node) = children.last() {
-                        match self.lower_expr(ctx, *expr_node) {
-                            Ok(expr) => expr,
-                            Err(_) => ctx.create_placeholder(),
-                        }
-                    } else {
-                        ctx.create_placeholder()
-                    };
-
-                    update.assignments.push(Assignment { column, value });
-                }
-            }
+            update.assignments = self.lower_assignments(ctx, set_node);
         }
 
         // Extract WHERE clause
@@ -329,6 +395,7 @@ impl PostgreSQLLowering {
                 alias: None,
                 joins: Vec::new(),
             },
+            using: Vec::new(),
             where_clause: None,
             returning: None,
         };
@@ -355,6 +422,202 @@ impl PostgreSQLLowering {
         Ok(query)
     }
 
+    /// Lower a MERGE statement (PostgreSQL 15+)
+    fn lower_merge_statement<N>(&self, ctx: &mut LoweringContext, node: &N) -> LoweringResult<Query>
+    where
+        N: CstNode,
+    {
+        let target = self.require_child(ctx, node, "target")?;
+        let target = self.lower_merge_table(ctx, target)?;
+
+        let source = self.require_child(ctx, node, "source")?;
+        let source = self.lower_merge_table(ctx, source)?;
+
+        let on = match self.optional_child(node, "on") {
+            Some(on_node) => self.lower_expr(ctx, on_node)?,
+            None => {
+                ctx.add_error(LoweringError::MissingChild {
+                    context: "merge_statement".to_string(),
+                    expected: "ON condition".to_string(),
+                });
+                ctx.create_placeholder()
+            }
+        };
+
+        let mut when_clauses = Vec::new();
+        for child in node.all_children() {
+            if child.kind() == "merge_when_clause" {
+                when_clauses.push(self.lower_merge_when_clause(ctx, child)?);
+            }
+        }
+
+        let merge = MergeStatement {
+            target,
+            source,
+            on,
+            when_clauses,
+        };
+
+        let mut query = Query::new(Dialect::PostgreSQL);
+        query.body = unified_sql_lsp_ir::SetOp::Merge(Box::new(merge));
+
+        Ok(query)
+    }
+
+    /// Lower the target or source of a MERGE (`table [AS] alias`)
+    fn lower_merge_table<N>(&self, ctx: &mut LoweringContext, node: &N) -> LoweringResult<TableRef>
+    where
+        N: CstNode,
+    {
+        if node.kind() != "table_reference" {
+            return Ok(self
+                .lower_table_reference(ctx, node)?
+                .unwrap_or_else(|| TableRef {
+                    name: String::new(),
+                    alias: None,
+                    joins: Vec::new(),
+                }));
+        }
+
+        let mut table_ref = TableRef {
+            name: String::new(),
+            alias: None,
+            joins: Vec::new(),
+        };
+        for child in node.all_children() {
+            match child.kind() {
+                "table_name" | "identifier" if table_ref.name.is_empty() => {
+                    table_ref.name = self.normalize_identifier(child.text().unwrap_or(""));
+                }
+                "alias" => {
+                    table_ref.alias = Some(self.normalize_identifier(child.text().unwrap_or("")));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(table_ref)
+    }
+
+    /// Lower one `WHEN [NOT] MATCHED [AND condition] THEN action` clause
+    fn lower_merge_when_clause<N>(
+        &self,
+        ctx: &mut LoweringContext,
+        node: &N,
+    ) -> LoweringResult<MergeWhenClause>
+    where
+        N: CstNode,
+    {
+        let matched = self.optional_child(node, "not").is_none();
+
+        let condition = match self.optional_child(node, "condition") {
+            Some(cond_node) => Some(self.lower_expr(ctx, cond_node)?),
+            None => None,
+        };
+
+        let action_node = self.require_child(ctx, node, "action")?;
+        let action = match action_node.kind() {
+            "merge_update" => MergeAction::Update {
+                assignments: self.lower_assignments(ctx, action_node),
+            },
+            "merge_delete" => MergeAction::Delete,
+            "merge_insert" => {
+                let columns = self.extract_column_list(ctx, action_node);
+                let values = self
+                    .extract_values_clause(ctx, action_node)
+                    .map(|rows| rows.into_iter().next().unwrap_or_default());
+                MergeAction::Insert { columns, values }
+            }
+            "merge_do_nothing" => MergeAction::DoNothing,
+            other => {
+                return Err(LoweringError::UnexpectedNodeType {
+                    expected: "MERGE action".to_string(),
+                    found: other.to_string(),
+                });
+            }
+        };
+
+        Ok(MergeWhenClause {
+            matched,
+            condition,
+            action,
+        })
+    }
+
+    /// Lower `ON CONFLICT [(columns)] DO NOTHING | DO UPDATE SET ... [WHERE ...]`
+    fn lower_on_conflict_clause<N>(
+        &self,
+        ctx: &mut LoweringContext,
+        node: &N,
+    ) -> LoweringResult<Option<OnConflict>>
+    where
+        N: CstNode,
+    {
+        let conflict_columns = self.extract_column_list(ctx, node);
+
+        if self.optional_child(node, "conflict_do_nothing").is_some() {
+            return Ok(Some(OnConflict::DoNothing {
+                columns: conflict_columns,
+            }));
+        }
+
+        if let Some(update_node) = self.optional_child(node, "conflict_do_update") {
+            let (update_columns, update_exprs) = self
+                .lower_assignments(ctx, update_node)
+                .into_iter()
+                .map(|a| (a.column, a.value))
+                .unzip();
+            let where_clause = match self.optional_child(update_node, "where_clause") {
+                Some(where_node) => Some(self.lower_where_clause(ctx, where_node)?),
+                None => None,
+            };
+            return Ok(Some(OnConflict::DoUpdate {
+                conflict_columns,
+                update_columns,
+                update_exprs,
+                where_clause,
+            }));
+        }
+
+        ctx.add_error(LoweringError::MissingChild {
+            context: "on_conflict_clause".to_string(),
+            expected: "DO NOTHING or DO UPDATE".to_string(),
+        });
+        Ok(None)
+    }
+
+    /// Lower the `column = value` assignments directly under `node`
+    fn lower_assignments<N>(&self, ctx: &mut LoweringContext, node: &N) -> Vec<Assignment>
+    where
+        N: CstNode,
+    {
+        let mut assignments = Vec::new();
+        for child in node.all_children() {
+            if child.kind() != "assignment" {
+                continue;
+            }
+
+            // Parse column = value
+            let children = child.all_children();
+            let column = match children.first() {
+                Some(col_node) if matches!(col_node.kind(), "identifier" | "column_name") => {
+                    self.normalize_identifier(col_node.text().unwrap_or(""))
+                }
+                _ => String::new(),
+            };
+
+            let value = match children.last() {
+                Some(expr_node) => self
+                    .lower_expr(ctx, *expr_node)
+                    .unwrap_or_else(|_| ctx.create_placeholder()),
+                None => ctx.create_placeholder(),
+            };
+
+            assignments.push(Assignment { column, value });
+        }
+        assignments
+    }
+
     /// Handle DISTINCT ON clause (PostgreSQL-specific)
     ///
     /// Parses the DISTINCT ON clause and returns a list of expressions.
@@ -432,51 +695,13 @@ impl PostgreSQLLowering {
     where
         N: CstNode,
     {
-        let mut items = Vec::new();
-
-        for child in node.all_children() {
-            match child.kind() {
-                "expression" | "column_ref" => {
-                    let expr = self.lower_expr(ctx, child)?;
-                    items.push(SelectItem::UnnamedExpr(expr));
-                }
-                "aliased_expression" => {
-                    // Extract expression and alias
-                    let children = child.all_children();
-                    if children.len() >= 2 {
-                        let expr = self.lower_expr(ctx, children[0])?;
-                        let alias = self.normalize_identifier(children[1].text().unwrap_or(""));
-                        items.push(SelectItem::AliasedExpr { expr, alias });
-                    } else {
-                        ctx.add_error(LoweringError::MissingChild {
-                            context: "aliased_expression".to_string(),
-                            expected: "expression and alias".to_string(),
-                        });
-                        items.push(SelectItem::UnnamedExpr(ctx.create_placeholder()));
-                    }
-                }
-                "wildcard" | "*" => {
-                    items.push(SelectItem::Wildcard);
-                }
-                "qualified_wildcard" => {
-                    // Extract table name for table.*
-                    let text = child.text().unwrap_or("");
-                    let table_name = text.trim_end_matches('.').to_string();
-                    items.push(SelectItem::QualifiedWildcard(table_name));
-                }
-                _ => {
-                    // Unknown projection item
-                    ctx.add_error(LoweringError::UnsupportedSyntax {
-                        dialect: "PostgreSQL".to_string(),
-                        feature: format!("projection item: {}", child.kind()),
-                        suggestion: "Use valid SELECT expressions".to_string(),
-                    });
-                    items.push(SelectItem::UnnamedExpr(ctx.create_placeholder()));
-                }
-            }
-        }
-
-        Ok(items)
+        SharedLowering::lower_projection(
+            ctx,
+            node,
+            "PostgreSQL",
+            |identifier| self.normalize_identifier(identifier),
+            |ctx, n| self.lower_expr(ctx, n),
+        )
     }
 
     /// Lower FROM clause with table references
@@ -488,15 +713,28 @@ impl PostgreSQLLowering {
     where
         N: CstNode,
     {
-        let mut tables = Vec::new();
+        let mut tables: Vec<TableRef> = Vec::new();
 
         for child in node.all_children() {
+            if child.kind() == "join_clause" {
+                // A JOIN attaches to the table reference before it
+                if let Some(join) =
+                    SharedLowering::lower_join_clause(ctx, child, "PostgreSQL", |identifier| {
+                        self.normalize_identifier(identifier)
+                    })?
+                    && let Some(table) = tables.last_mut()
+                {
+                    SharedLowering::add_definition_span(ctx, child);
+                    table.joins.push(join);
+                }
+                continue;
+            }
             if matches!(
                 child.kind(),
                 "table_reference" | "table_name" | "joined_table"
             ) && let Some(table) = self.lower_table_reference(ctx, child)?
             {
-                tables.push(table);
+                SharedLowering::push_table(ctx, &mut tables, table, child);
             }
         }
 
@@ -542,6 +780,14 @@ impl PostgreSQLLowering {
                 // Handle JOIN syntax
                 return self.lower_joined_table(ctx, node);
             }
+            "table_reference" => {
+                return SharedLowering::lower_table_reference(
+                    ctx,
+                    node,
+                    "PostgreSQL",
+                    |identifier| self.normalize_identifier(identifier),
+                );
+            }
             _ => {
                 ctx.add_error(LoweringError::UnsupportedSyntax {
                     dialect: "PostgreSQL".to_string(),
@@ -853,6 +1099,10 @@ impl PostgreSQLLowering {
                 return Ok(Expr::Literal(Literal::Null));
             }
         };
+        ctx.add_binary_expr_span(
+            node.start_byte()..node.end_byte(),
+            children[1].start_byte()..children[1].end_byte(),
+        );
 
         Ok(Expr::BinaryOp {
             left: Box::new(left),
@@ -1132,103 +1382,163 @@ impl PostgreSQLLowering {
 
         // Parse OVER clause (window functions)
         let over = if let Some(over_node) = self.optional_child(node, "over_clause") {
-            let mut partition_by = Vec::new();
-            let mut order_by = Vec::new();
-            let mut window_frame = None;
-
-            // Parse PARTITION BY
-            if let Some(partition_node) = self.optional_child(over_node, "partition_by") {
-                for child in partition_node.all_children() {
-                    if matches!(child.kind(), "expression" | "column_ref") {
-                        match self.lower_expr(ctx, child) {
-                            Ok(expr) => partition_by.push(expr),
-                            Err(_) => partition_by.push(ctx.create_placeholder()),
-                        }
+            let name = self
+                .optional_child(over_node, "window_name")
+                .and_then(|n| n.text())
+                .map(|text| self.normalize_identifier(text));
+            let (partition_by, order_by, window_frame) =
+                self.lower_window_specification(ctx, over_node)?;
+
+            Some(WindowSpec {
+                name,
+                partition_by,
+                order_by,
+                window_frame,
+            })
+        } else {
+            None
+        };
+
+        Ok(Expr::Function {
+            name: func_name,
+            args,
+            distinct,
+            filter,
+            over,
+        })
+    }
+
+    /// Lower the PARTITION BY, ORDER BY and frame parts of a window specification
+    ///
+    /// Shared by `OVER (...)` and the definitions of a `WINDOW` clause.
+    fn lower_window_specification<N>(
+        &self,
+        ctx: &mut LoweringContext,
+        node: &N,
+    ) -> LoweringResult<(Vec<Expr>, Vec<OrderBy>, Option<WindowFrame>)>
+    where
+        N: CstNode,
+    {
+        let mut partition_by = Vec::new();
+        let mut order_by = Vec::new();
+        let mut window_frame = None;
+
+        // Parse PARTITION BY
+        if let Some(partition_node) = self.optional_child(node, "partition_by") {
+            for child in partition_node.all_children() {
+                if matches!(child.kind(), "expression" | "column_ref") {
+                    match self.lower_expr(ctx, child) {
+                        Ok(expr) => partition_by.push(expr),
+                        Err(_) => partition_by.push(ctx.create_placeholder()),
                     }
                 }
             }
+        }
 
-            // Parse ORDER BY
-            if let Some(order_node) = self.optional_child(over_node, "order_by") {
-                for child in order_node.all_children() {
-                    if child.kind() == "order_by_item" {
-                        let item_children = child.all_children();
-                        let expr = if let Some(expr_node) = item_children.first() {
-                            self.lower_expr(ctx, *expr_node)
-                                .unwrap_or_else(|_| ctx.create_placeholder())
-                        } else {
-                            ctx.create_placeholder()
-                        };
-
-                        let direction = if item_children.len() > 1 {
-                            let dir_text = item_children[1].text().unwrap_or("");
-                            if dir_text.eq_ignore_ascii_case("DESC") {
-                                Some(SortDirection::Desc)
-                            } else if dir_text.eq_ignore_ascii_case("ASC") {
-                                Some(SortDirection::Asc)
-                            } else {
-                                None
-                            }
+        // Parse ORDER BY
+        if let Some(order_node) = self.optional_child(node, "order_by") {
+            for child in order_node.all_children() {
+                if child.kind() == "order_by_item" {
+                    let item_children = child.all_children();
+                    let expr = if let Some(expr_node) = item_children.first() {
+                        self.lower_expr(ctx, *expr_node)
+                            .unwrap_or_else(|_| ctx.create_placeholder())
+                    } else {
+                        ctx.create_placeholder()
+                    };
+
+                    let direction = if item_children.len() > 1 {
+                        let dir_text = item_children[1].text().unwrap_or("");
+                        if dir_text.eq_ignore_ascii_case("DESC") {
+                            Some(SortDirection::Desc)
+                        } else if dir_text.eq_ignore_ascii_case("ASC") {
+                            Some(SortDirection::Asc)
                         } else {
                             None
-                        };
+                        }
+                    } else {
+                        None
+                    };
 
-                        order_by.push(OrderBy { expr, direction });
-                    }
+                    order_by.push(OrderBy { expr, direction });
                 }
             }
+        }
 
-            // Parse window frame (ROWS BETWEEN ...)
-            if let Some(frame_node) = self.optional_child(over_node, "window_frame") {
-                let units = if let Some(units_node) = self.optional_child(frame_node, "frame_units")
-                {
-                    match units_node.text().unwrap_or("").to_uppercase().as_str() {
-                        "ROWS" => WindowFrameUnits::Rows,
-                        "RANGE" => WindowFrameUnits::Range,
-                        "GROUPS" => WindowFrameUnits::Groups,
-                        _ => WindowFrameUnits::Rows,
-                    }
+        // Parse window frame (ROWS BETWEEN ...)
+        if let Some(frame_node) = self.optional_child(node, "window_frame") {
+            let units = if let Some(units_node) = self.optional_child(frame_node, "frame_units") {
+                match units_node.text().unwrap_or("").to_uppercase().as_str() {
+                    "ROWS" => WindowFrameUnits::Rows,
+                    "RANGE" => WindowFrameUnits::Range,
+                    "GROUPS" => WindowFrameUnits::Groups,
+                    _ => WindowFrameUnits::Rows,
+                }
+            } else {
+                WindowFrameUnits::Rows
+            };
+
+            let start_bound =
+                if let Some(start_node) = self.optional_child(frame_node, "frame_start") {
+                    self.parse_window_bound(ctx, start_node)?
                 } else {
-                    WindowFrameUnits::Rows
+                    WindowFrameBound::Unbounded
                 };
 
-                let start_bound =
-                    if let Some(start_node) = self.optional_child(frame_node, "frame_start") {
-                        self.parse_window_bound(ctx, start_node)?
-                    } else {
-                        WindowFrameBound::Unbounded
-                    };
+            let end_bound = if let Some(end_node) = self.optional_child(frame_node, "frame_end") {
+                Some(self.parse_window_bound(ctx, end_node)?)
+            } else {
+                None
+            };
 
-                let end_bound = if let Some(end_node) = self.optional_child(frame_node, "frame_end")
-                {
-                    Some(self.parse_window_bound(ctx, end_node)?)
-                } else {
-                    None
-                };
+            window_frame = Some(WindowFrame {
+                units,
+                start_bound,
+                end_bound,
+            });
+        }
+
+        Ok((partition_by, order_by, window_frame))
+    }
 
-                window_frame = Some(WindowFrame {
-                    units,
-                    start_bound,
-                    end_bound,
+    /// Lower a WINDOW clause into named window definitions
+    ///
+    /// Grammar: window_clause: seq('WINDOW', window_definition, repeat(seq(',', window_definition)))
+    fn lower_window_clause<N>(
+        &self,
+        ctx: &mut LoweringContext,
+        node: &N,
+    ) -> LoweringResult<Vec<WindowDef>>
+    where
+        N: CstNode,
+    {
+        let mut windows = Vec::new();
+
+        for def_node in node.children("window_definition") {
+            let name = self
+                .optional_child(def_node, "window_name")
+                .and_then(|n| n.text())
+                .map(|text| self.normalize_identifier(text));
+            if name.is_none() {
+                ctx.add_error(LoweringError::UnsupportedSyntax {
+                    dialect: "PostgreSQL".to_string(),
+                    feature: "window definition without a name".to_string(),
+                    suggestion: "Use WINDOW name AS (...)".to_string(),
                 });
+                continue;
             }
 
-            Some(WindowSpec {
+            let (partition_by, order_by, window_frame) =
+                self.lower_window_specification(ctx, def_node)?;
+            windows.push(WindowDef {
+                name,
                 partition_by,
                 order_by,
                 window_frame,
-            })
-        } else {
-            None
-        };
+            });
+        }
 
-        Ok(Expr::Function {
-            name: func_name,
-            args,
-            distinct,
-            filter,
-            over,
-        })
+        Ok(windows)
     }
 
     /// Parse a window frame bound (UNBOUNDED PRECEDING, CURRENT ROW, n PRECEDING/FOLLOWING)
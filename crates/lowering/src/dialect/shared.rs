@@ -10,9 +10,24 @@
 
 use crate::{CstNode, LoweringContext, LoweringError, LoweringResult};
 use unified_sql_lsp_ir::expr::{BinaryOp, ColumnRef, Literal, UnaryOp};
-use unified_sql_lsp_ir::query::{OrderBy, SelectItem, SortDirection, TableRef};
+use unified_sql_lsp_ir::query::{
+    CommonTableExpr, OrderBy, Query, SelectItem, SortDirection, TableRef,
+};
 use unified_sql_lsp_ir::{Expr, Join, JoinCondition, JoinType};
 
+/// Expression node kinds that appear directly in a projection
+const PROJECTION_EXPRESSION_KINDS: &[&str] = &[
+    "column_reference",
+    "literal",
+    "function_call",
+    "binary_expression",
+    "unary_expression",
+    "case_expression",
+    "cast_expression",
+    "json_path_expression",
+    "parenthesized_expression",
+];
+
 /// Shared lowering utilities for all dialects
 pub struct SharedLowering;
 
@@ -46,6 +61,10 @@ impl SharedLowering {
         let right = lower_fn(ctx, children[2])?;
 
         let op = Self::parse_binary_op(op_str, dialect_name, dialect_operators, ctx);
+        ctx.add_binary_expr_span(
+            node.start_byte()..node.end_byte(),
+            children[1].start_byte()..children[1].end_byte(),
+        );
 
         Ok(Expr::BinaryOp {
             left: Box::new(left),
@@ -185,6 +204,39 @@ impl SharedLowering {
         }
     }
 
+    /// Lower type cast (`CAST(expr AS type)`, PostgreSQL `expr::type`)
+    ///
+    /// Both forms expose the operand as the `expression` field and the target
+    /// as the `type` field. The type is kept as written, parameters included.
+    pub fn lower_cast_expr<N, E>(
+        ctx: &mut LoweringContext,
+        node: &N,
+        lower_fn: E,
+    ) -> LoweringResult<Expr>
+    where
+        N: CstNode,
+        E: Fn(&mut LoweringContext, &N) -> LoweringResult<Expr>,
+    {
+        let (Some(expr_node), Some(type_node)) = (
+            Self::optional_child(node, "expression"),
+            Self::optional_child(node, "type"),
+        ) else {
+            ctx.add_error(LoweringError::MissingChild {
+                context: node.kind().to_string(),
+                expected: "expression and target type".to_string(),
+            });
+            return Ok(ctx.create_placeholder());
+        };
+
+        let expr = lower_fn(ctx, expr_node)?;
+        let target_type = type_node.text().unwrap_or("").trim().to_string();
+
+        Ok(Expr::Cast {
+            expr: Box::new(expr),
+            target_type,
+        })
+    }
+
     /// Lower literal value (string, number, boolean, NULL)
     ///
     /// Shared implementation for standard SQL literals.
@@ -234,6 +286,97 @@ impl SharedLowering {
         Ok(ctx.create_placeholder())
     }
 
+    /// Lower a subquery used as a value, as in `id IN (SELECT ...)`
+    ///
+    /// The query is lowered [unmapped](LoweringContext::unmapped).
+    pub fn lower_subquery<N, Q>(
+        ctx: &mut LoweringContext,
+        node: &N,
+        lower_query: Q,
+    ) -> LoweringResult<Expr>
+    where
+        N: CstNode,
+        Q: Fn(&mut LoweringContext, &N) -> LoweringResult<Query>,
+    {
+        let children = node.all_children();
+        let Some(select) = children.iter().find(|c| c.kind() == "select_statement") else {
+            ctx.add_error(LoweringError::MissingChild {
+                context: "subquery".to_string(),
+                expected: "select_statement".to_string(),
+            });
+            return Ok(ctx.create_placeholder());
+        };
+
+        let query = ctx.unmapped(|ctx| lower_query(ctx, *select))?;
+        Ok(Expr::Subquery(Box::new(query)))
+    }
+
+    /// Lower the CTE definitions of a WITH clause
+    ///
+    /// A CTE body is either a SELECT or a VALUES list, lowered by
+    /// `lower_query`; its optional column list names the output columns.
+    /// Each CTE name is mapped as a definition before its query.
+    pub fn lower_cte_clause<N, F, Q>(
+        ctx: &mut LoweringContext,
+        node: &N,
+        normalize_fn: F,
+        lower_query: Q,
+    ) -> LoweringResult<Vec<CommonTableExpr>>
+    where
+        N: CstNode,
+        F: Fn(&str) -> String + Copy,
+        Q: Fn(&mut LoweringContext, &N) -> LoweringResult<Query>,
+    {
+        let mut ctes = Vec::new();
+        for definition in node.all_children() {
+            if definition.kind() != "cte_definition" {
+                continue;
+            }
+
+            let Some(name_node) = Self::optional_child(definition, "table_name") else {
+                return Err(LoweringError::MissingChild {
+                    context: "cte_definition".to_string(),
+                    expected: "name".to_string(),
+                });
+            };
+            let name = normalize_fn(name_node.text().unwrap_or(""));
+            ctx.add_definition_span(name_node.start_byte()..name_node.end_byte());
+
+            let columns = Self::optional_child(definition, "column_list")
+                .map(|list| {
+                    list.all_children()
+                        .into_iter()
+                        .filter(|column| column.kind() == "column_name")
+                        .filter_map(|column| column.text())
+                        .map(normalize_fn)
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let body = Self::optional_child(definition, "select_statement")
+                .or_else(|| Self::optional_child(definition, "values_statement"));
+            let query = match body {
+                Some(body) => lower_query(ctx, body)?,
+                None => {
+                    ctx.add_error(LoweringError::MissingChild {
+                        context: "cte_definition".to_string(),
+                        expected: "SELECT or VALUES query".to_string(),
+                    });
+                    Query::new(ctx.dialect())
+                }
+            };
+
+            ctes.push(CommonTableExpr {
+                name,
+                columns,
+                query: Box::new(query),
+                materialized: None,
+            });
+        }
+
+        Ok(ctes)
+    }
+
     /// Lower WHERE clause
     pub fn lower_where_clause<N>(
         ctx: &mut LoweringContext,
@@ -472,6 +615,64 @@ impl SharedLowering {
         }))
     }
 
+    /// Lower `[join_type] JOIN table_name [[AS] alias] ON expression`
+    ///
+    /// Shared implementation for all dialects.
+    pub fn lower_join_clause<N, F>(
+        ctx: &mut LoweringContext,
+        node: &N,
+        dialect_name: &str,
+        normalize_fn: F,
+    ) -> LoweringResult<Option<Join>>
+    where
+        N: CstNode,
+        F: Fn(&str) -> String + Copy,
+    {
+        let join_type = match Self::optional_child(node, "join_type")
+            .and_then(|join_type| join_type.text())
+            .and_then(|text| text.split_whitespace().next())
+            .map(str::to_uppercase)
+            .as_deref()
+        {
+            Some("LEFT") => JoinType::Left,
+            Some("RIGHT") => JoinType::Right,
+            Some("FULL") => JoinType::Full,
+            Some("CROSS") => JoinType::Cross,
+            _ => JoinType::Inner,
+        };
+
+        let Some(table_node) = Self::optional_child(node, "table_name") else {
+            ctx.add_error(LoweringError::MissingChild {
+                context: "join_clause".to_string(),
+                expected: "table name".to_string(),
+            });
+            return Ok(None);
+        };
+        let table = TableRef {
+            name: normalize_fn(table_node.text().unwrap_or("")),
+            alias: Self::optional_child(node, "alias")
+                .map(|alias| normalize_fn(alias.text().unwrap_or(""))),
+            joins: Vec::new(),
+        };
+
+        // The ON expression is the last named child
+        let condition = match node.all_children().into_iter().rev().find(|child| {
+            child.is_named() && !matches!(child.kind(), "join_type" | "table_name" | "alias")
+        }) {
+            Some(expr_node) => match Self::lower_expr_generic(ctx, expr_node, dialect_name) {
+                Ok(expr) => JoinCondition::On(expr),
+                Err(_) => JoinCondition::On(ctx.create_placeholder()),
+            },
+            None => JoinCondition::On(ctx.create_placeholder()),
+        };
+
+        Ok(Some(Join {
+            join_type,
+            table,
+            condition,
+        }))
+    }
+
     /// Lower FROM clause with table references
     pub fn lower_from_clause<N, F>(
         ctx: &mut LoweringContext,
@@ -483,16 +684,26 @@ impl SharedLowering {
         N: CstNode,
         F: Fn(&str) -> String + Copy,
     {
-        let mut tables = Vec::new();
+        let mut tables: Vec<TableRef> = Vec::new();
 
         for child in node.all_children() {
+            if child.kind() == "join_clause" {
+                // A JOIN attaches to the table reference before it
+                if let Some(join) = Self::lower_join_clause(ctx, child, dialect_name, normalize_fn)?
+                    && let Some(table) = tables.last_mut()
+                {
+                    Self::add_definition_span(ctx, child);
+                    table.joins.push(join);
+                }
+                continue;
+            }
             if matches!(
                 child.kind(),
                 "table_reference" | "table_name" | "joined_table"
             ) && let Some(table) =
                 Self::lower_table_reference(ctx, child, dialect_name, normalize_fn)?
             {
-                tables.push(table);
+                Self::push_table(ctx, &mut tables, table, child);
             }
         }
 
@@ -506,6 +717,39 @@ impl SharedLowering {
         Ok(tables)
     }
 
+    /// Add `table`, lowered from the FROM-list entry `node`, to `tables`
+    ///
+    /// The name it defines is mapped, unless the entry lowered to neither a
+    /// table name nor a join.
+    pub fn push_table<N>(
+        ctx: &mut LoweringContext,
+        tables: &mut Vec<TableRef>,
+        table: TableRef,
+        node: &N,
+    ) where
+        N: CstNode,
+    {
+        if !table.name.is_empty() || !table.joins.is_empty() {
+            Self::add_definition_span(ctx, node);
+        }
+        tables.push(table);
+    }
+
+    /// Map the name a FROM-list entry `node` defines: its alias if it has
+    /// one, else its table name
+    pub fn add_definition_span<N>(ctx: &mut LoweringContext, node: &N)
+    where
+        N: CstNode,
+    {
+        let defined = match node.kind() {
+            "aliased_table" => node.all_children().get(1).copied(),
+            _ => Self::optional_child(node, "alias")
+                .or_else(|| Self::optional_child(node, "table_name")),
+        }
+        .unwrap_or(node);
+        ctx.add_definition_span(defined.start_byte()..defined.end_byte());
+    }
+
     /// Lower a single table reference
     pub fn lower_table_reference<N, F>(
         ctx: &mut LoweringContext,
@@ -540,6 +784,18 @@ impl SharedLowering {
                 }
             }
             "joined_table" => Self::lower_joined_table(ctx, node, dialect_name, normalize_fn),
+            // `table_name [[AS] alias]`; a derived table has no name to resolve
+            "table_reference" => {
+                let Some(table_node) = Self::optional_child(node, "table_name") else {
+                    return Ok(None);
+                };
+                Ok(Some(TableRef {
+                    name: normalize_fn(table_node.text().unwrap_or("")),
+                    alias: Self::optional_child(node, "alias")
+                        .map(|alias| normalize_fn(alias.text().unwrap_or(""))),
+                    joins: Vec::new(),
+                }))
+            }
             _ => {
                 ctx.add_error(LoweringError::UnsupportedSyntax {
                     dialect: dialect_name.to_string(),
@@ -554,7 +810,8 @@ impl SharedLowering {
     /// Lower projection list (SELECT clause)
     ///
     /// Uses a callback function for expression lowering to allow dialect-specific
-    /// handling of expressions.
+    /// handling of expressions. An `alias` node names the item before it;
+    /// separators and the AS keyword are skipped.
     pub fn lower_projection<N, F, E>(
         ctx: &mut LoweringContext,
         node: &N,
@@ -597,6 +854,26 @@ impl SharedLowering {
                     let table_name = text.trim_end_matches('.').to_string();
                     items.push(SelectItem::QualifiedWildcard(table_name));
                 }
+                "alias" => {
+                    let alias = normalize_fn(child.text().unwrap_or(""));
+                    match items.pop() {
+                        Some(SelectItem::UnnamedExpr(expr)) => {
+                            items.push(SelectItem::AliasedExpr { expr, alias });
+                        }
+                        other => {
+                            items.extend(other);
+                            ctx.add_error(LoweringError::MissingChild {
+                                context: "alias".to_string(),
+                                expected: "aliased expression".to_string(),
+                            });
+                        }
+                    }
+                }
+                kind if PROJECTION_EXPRESSION_KINDS.contains(&kind) => {
+                    let expr = lower_expr_fn(ctx, child)?;
+                    items.push(SelectItem::UnnamedExpr(expr));
+                }
+                _ if !child.is_named() => {}
                 _ => {
                     ctx.add_error(LoweringError::UnsupportedSyntax {
                         dialect: dialect_name.to_string(),
@@ -804,19 +1081,24 @@ impl SharedLowering {
                 Self::lower_column_ref(ctx, node, |s| s.to_string())
             }
             "literal" => Self::lower_literal(ctx, node),
-            "parenthesized_expression" => {
+            "expression" | "parenthesized_expression" => {
                 let children = node.all_children();
                 if let Some(inner) = children.first() {
                     Self::lower_expr_generic(ctx, *inner, dialect_name)
                 } else {
                     ctx.add_error(LoweringError::MissingChild {
-                        context: "parenthesized_expression".to_string(),
+                        context: node.kind().to_string(),
                         expected: "inner expression".to_string(),
                     });
                     Ok(ctx.create_placeholder())
                 }
             }
             "case_expression" => Self::lower_case_expr(ctx, node, dialect_name),
+            "cast_expression" | "type_cast_expression" => {
+                Self::lower_cast_expr(ctx, node, |ctx, n| {
+                    Self::lower_expr_generic(ctx, n, dialect_name)
+                })
+            }
             _ => {
                 ctx.add_error(LoweringError::UnsupportedSyntax {
                     dialect: dialect_name.to_string(),
@@ -70,11 +70,13 @@ pub mod cst;
 pub mod dialect;
 pub mod error;
 
-pub use context::{LoweringContext, SourceLocation};
-pub use cst::CstNode;
+pub use context::{LoweringContext, SourceLocation, binary_expr_id, definition_id};
+pub use cst::{CstNode, TreeSitterNode};
 pub use error::{ErrorSeverity, LoweringError, LoweringOutcome, LoweringResult};
 
-use unified_sql_lsp_ir::{Dialect, Expr, Query};
+use dialect::SharedLowering;
+use unified_sql_lsp_ir::dialect::DialectFamily;
+use unified_sql_lsp_ir::{DdlStatement, Dialect, Expr, IdentifierComparer, Query};
 
 /// Core trait for lowering CST to IR
 ///
@@ -189,4 +191,20 @@ where
 
     /// Get the dialect this lowering implementation targets
     fn dialect(&self) -> Dialect;
+
+    /// Lower a DDL statement (CREATE TABLE, CREATE VIEW, ALTER TABLE)
+    ///
+    /// Names are normalized the way the dialect stores them: PostgreSQL
+    /// folds unquoted identifiers to lowercase, MySQL keeps them as written.
+    /// A view's defining query goes through [`Lowering::lower_query`].
+    fn lower_ddl(&self, ctx: &mut LoweringContext, node: &N) -> LoweringResult<DdlStatement> {
+        let dialect = self.dialect();
+        let normalize = |identifier: &str| match dialect.family() {
+            DialectFamily::PostgreSQL => IdentifierComparer::new(dialect).fold(identifier),
+            DialectFamily::MySQL => IdentifierComparer::unquote(identifier),
+        };
+        SharedLowering::lower_ddl(ctx, node, normalize, |ctx, query| {
+            self.lower_query(ctx, query)
+        })
+    }
 }
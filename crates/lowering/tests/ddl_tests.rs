@@ -0,0 +1,392 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! Integration tests for DDL CST → IR lowering
+//!
+//! The mock trees mirror the grammar's `create_table_statement`,
+//! `create_view_statement` and `alter_table_statement` nodes.
+
+use unified_sql_lsp_ir::expr::{ColumnRef, Expr};
+use unified_sql_lsp_ir::query::{JoinType, SelectItem, SetOp};
+use unified_sql_lsp_ir::{
+    AlterTableAction, DdlStatement, Dialect, ForeignKeyTarget, TableConstraint,
+};
+use unified_sql_lsp_lowering::cst::MockCstNode;
+use unified_sql_lsp_lowering::dialect::{MySQLLowering, PostgreSQLLowering};
+use unified_sql_lsp_lowering::{Lowering, LoweringContext, LoweringError};
+
+/// A node whose children are reachable by their kind, as Tree-sitter
+/// nodes are through `TreeSitterNode`
+fn node(kind: &str, children: Vec<MockCstNode>) -> MockCstNode {
+    children
+        .into_iter()
+        .fold(MockCstNode::new(kind), |parent, child| {
+            let field = child.kind.clone();
+            parent.with_child(Some(&field), child)
+        })
+}
+
+fn leaf(kind: &str, text: &str) -> MockCstNode {
+    MockCstNode::new(kind).with_text(text)
+}
+
+fn keyword(text: &str) -> MockCstNode {
+    MockCstNode::new(text)
+}
+
+fn object_name(schema: Option<&str>, name: &str) -> MockCstNode {
+    let mut children = Vec::new();
+    if let Some(schema) = schema {
+        children.push(leaf("identifier", schema));
+        children.push(keyword("."));
+    }
+    children.push(leaf("table_name", name));
+    node("object_name", children)
+}
+
+fn column_list(names: &[&str]) -> MockCstNode {
+    node(
+        "column_list",
+        names.iter().map(|name| leaf("column_name", name)).collect(),
+    )
+}
+
+fn data_type(name: &str, parameters: &[&str]) -> MockCstNode {
+    let mut children = vec![leaf("type_name", name)];
+    if !parameters.is_empty() {
+        children.push(node(
+            "type_parameters",
+            parameters
+                .iter()
+                .map(|parameter| leaf("number_literal", parameter))
+                .collect(),
+        ));
+    }
+    node("data_type", children)
+}
+
+fn column(name: &str, data_type: MockCstNode, constraints: Vec<MockCstNode>) -> MockCstNode {
+    let mut children = vec![leaf("column_name", name), data_type];
+    children.extend(constraints);
+    node("column_definition", children)
+}
+
+fn references(table: &str, columns: &[&str]) -> MockCstNode {
+    let mut children = vec![keyword("REFERENCES"), object_name(None, table)];
+    if !columns.is_empty() {
+        children.push(column_list(columns));
+    }
+    node("references_clause", children)
+}
+
+#[test]
+fn test_mysql_create_table() {
+    // CREATE TABLE `app`.`users` (
+    //   id INT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    //   email VARCHAR(255) DEFAULT NULL COMMENT 'user''s email',
+    //   PRIMARY KEY (id)
+    // ) ENGINE=InnoDB COMMENT='accounts'
+    let cst = node(
+        "create_table_statement",
+        vec![
+            keyword("CREATE"),
+            keyword("TABLE"),
+            object_name(Some("`app`"), "`users`"),
+            column(
+                "id",
+                data_type("INT", &[]),
+                vec![
+                    node("not_null_constraint", vec![]),
+                    node("auto_increment_constraint", vec![]),
+                    node("primary_key_constraint", vec![]),
+                ],
+            ),
+            column(
+                "email",
+                data_type("VARCHAR", &["255"]),
+                vec![
+                    node("default_constraint", vec![leaf("literal", "NULL")]),
+                    node(
+                        "comment_constraint",
+                        vec![leaf("string_literal", "'user''s email'")],
+                    ),
+                ],
+            ),
+            node(
+                "table_constraint",
+                vec![node("primary_key_definition", vec![column_list(&["id"])])],
+            ),
+            node(
+                "table_option",
+                vec![leaf("identifier", "ENGINE"), leaf("identifier", "InnoDB")],
+            ),
+            node(
+                "table_option",
+                vec![node(
+                    "table_comment",
+                    vec![leaf("string_literal", "'accounts'")],
+                )],
+            ),
+        ],
+    );
+
+    let mut ctx = LoweringContext::new(Dialect::MySQL);
+    let DdlStatement::CreateTable(table) = MySQLLowering.lower_ddl(&mut ctx, &cst).unwrap() else {
+        panic!("expected CREATE TABLE");
+    };
+
+    assert!(!ctx.has_errors());
+    assert_eq!(table.schema.as_deref(), Some("app"));
+    assert_eq!(table.name, "users");
+    assert!(!table.temporary);
+    assert_eq!(table.comment.as_deref(), Some("accounts"));
+    assert_eq!(
+        table.constraints,
+        vec![TableConstraint::PrimaryKey(vec!["id".to_string()])]
+    );
+
+    let id = &table.columns[0];
+    assert_eq!(id.data_type, "INT");
+    assert!(!id.nullable);
+    assert!(id.primary_key);
+    assert_eq!(id.default.as_deref(), Some("AUTO_INCREMENT"));
+
+    let email = &table.columns[1];
+    assert_eq!(email.data_type, "VARCHAR(255)");
+    assert!(email.nullable);
+    assert_eq!(email.default.as_deref(), Some("NULL"));
+    assert_eq!(email.comment.as_deref(), Some("user's email"));
+}
+
+#[test]
+fn test_postgresql_create_table_folds_names() {
+    // CREATE TEMPORARY TABLE Users (
+    //   ID integer GENERATED BY DEFAULT AS IDENTITY,
+    //   "Tags" text[],
+    //   Org_Id integer REFERENCES Orgs
+    // )
+    let cst = node(
+        "create_table_statement",
+        vec![
+            keyword("CREATE"),
+            keyword("TEMPORARY"),
+            keyword("TABLE"),
+            object_name(None, "Users"),
+            column(
+                "ID",
+                data_type("integer", &[]),
+                vec![leaf(
+                    "identity_constraint",
+                    "GENERATED  BY DEFAULT\n AS IDENTITY",
+                )],
+            ),
+            column(
+                "\"Tags\"",
+                node(
+                    "data_type",
+                    vec![leaf("type_name", "text"), keyword("["), keyword("]")],
+                ),
+                vec![],
+            ),
+            column(
+                "Org_Id",
+                data_type("integer", &[]),
+                vec![references("Orgs", &[])],
+            ),
+        ],
+    );
+
+    let mut ctx = LoweringContext::new(Dialect::PostgreSQL);
+    let DdlStatement::CreateTable(table) = PostgreSQLLowering.lower_ddl(&mut ctx, &cst).unwrap()
+    else {
+        panic!("expected CREATE TABLE");
+    };
+
+    assert!(table.temporary);
+    assert_eq!(table.name, "users");
+    let names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["id", "Tags", "org_id"]);
+    assert_eq!(
+        table.columns[0].default.as_deref(),
+        Some("GENERATED BY DEFAULT AS IDENTITY")
+    );
+    assert_eq!(table.columns[1].data_type, "text[]");
+    assert_eq!(
+        table.columns[2].references,
+        Some(ForeignKeyTarget {
+            table: "orgs".to_string(),
+            columns: Vec::new(),
+        })
+    );
+}
+
+#[test]
+fn test_alter_table_add_foreign_key() {
+    // ALTER TABLE orders
+    //   ADD COLUMN note TEXT,
+    //   ADD CONSTRAINT fk FOREIGN KEY (user_id) REFERENCES users (id)
+    let cst = node(
+        "alter_table_statement",
+        vec![
+            keyword("ALTER"),
+            keyword("TABLE"),
+            object_name(None, "orders"),
+            node(
+                "alter_table_action",
+                vec![
+                    keyword("ADD"),
+                    keyword("COLUMN"),
+                    column("note", data_type("TEXT", &[]), vec![]),
+                ],
+            ),
+            node(
+                "alter_table_action",
+                vec![
+                    keyword("ADD"),
+                    node(
+                        "table_constraint",
+                        vec![
+                            keyword("CONSTRAINT"),
+                            leaf("identifier", "fk"),
+                            node(
+                                "foreign_key_definition",
+                                vec![column_list(&["user_id"]), references("users", &["id"])],
+                            ),
+                        ],
+                    ),
+                ],
+            ),
+        ],
+    );
+
+    let mut ctx = LoweringContext::new(Dialect::MySQL);
+    let DdlStatement::AlterTable(alter) = MySQLLowering.lower_ddl(&mut ctx, &cst).unwrap() else {
+        panic!("expected ALTER TABLE");
+    };
+
+    assert_eq!(alter.name, "orders");
+    assert_eq!(alter.actions.len(), 2);
+    assert!(matches!(
+        &alter.actions[0],
+        AlterTableAction::AddColumn(column) if column.name == "note"
+    ));
+    assert_eq!(
+        alter.actions[1],
+        AlterTableAction::AddConstraint(TableConstraint::ForeignKey {
+            columns: vec!["user_id".to_string()],
+            references: ForeignKeyTarget {
+                table: "users".to_string(),
+                columns: vec!["id".to_string()],
+            },
+        })
+    );
+}
+
+#[test]
+fn test_create_view_lowers_query() {
+    // CREATE VIEW product_codes (id, code) AS
+    //   SELECT p.id, p.sku AS code FROM products p JOIN stock s ON p.id = s.product_id
+    let column_ref = |table: &str, column: &str| {
+        node(
+            "column_reference",
+            vec![
+                leaf("table_name", table),
+                keyword("."),
+                leaf("column_name", column),
+            ],
+        )
+        .with_text(format!("{table}.{column}"))
+    };
+    // The AS keyword is a hidden regex token in the grammar
+    let projection = MockCstNode::new("projection")
+        .with_child(None, column_ref("p", "id"))
+        .with_child(None, MockCstNode::new("\",\""))
+        .with_child(None, column_ref("p", "sku"))
+        .with_child(None, leaf("alias", "code"));
+    let from = MockCstNode::new("from_clause")
+        .with_child(None, keyword("FROM"))
+        .with_child(
+            None,
+            node(
+                "table_reference",
+                vec![leaf("table_name", "products"), leaf("alias", "p")],
+            ),
+        )
+        .with_child(
+            None,
+            node(
+                "join_clause",
+                vec![
+                    keyword("JOIN"),
+                    leaf("table_name", "stock"),
+                    leaf("alias", "s"),
+                    keyword("ON"),
+                    column_ref("p", "id"),
+                ],
+            ),
+        );
+    let select = node(
+        "select_statement",
+        vec![keyword("SELECT"), projection, from],
+    );
+    let cst = node(
+        "create_view_statement",
+        vec![
+            keyword("CREATE"),
+            keyword("VIEW"),
+            object_name(None, "product_codes"),
+            column_list(&["id", "code"]),
+            keyword("AS"),
+            select,
+        ],
+    );
+
+    let mut ctx = LoweringContext::new(Dialect::MySQL);
+    let DdlStatement::CreateView(view) = MySQLLowering.lower_ddl(&mut ctx, &cst).unwrap() else {
+        panic!("expected CREATE VIEW");
+    };
+
+    assert_eq!(view.name, "product_codes");
+    assert!(!view.materialized);
+    assert_eq!(view.columns, vec!["id", "code"]);
+
+    let SetOp::Select(select) = &view.query.body else {
+        panic!("expected SELECT");
+    };
+    assert_eq!(
+        select.projection,
+        vec![
+            SelectItem::UnnamedExpr(Expr::Column(ColumnRef {
+                table: Some("p".to_string()),
+                column: "id".to_string(),
+            })),
+            SelectItem::AliasedExpr {
+                expr: Expr::Column(ColumnRef {
+                    table: Some("p".to_string()),
+                    column: "sku".to_string(),
+                }),
+                alias: "code".to_string(),
+            },
+        ]
+    );
+    assert_eq!(select.from.len(), 1);
+    assert_eq!(select.from[0].name, "products");
+    assert_eq!(select.from[0].alias.as_deref(), Some("p"));
+    assert_eq!(select.from[0].joins.len(), 1);
+    assert_eq!(select.from[0].joins[0].join_type, JoinType::Inner);
+    assert_eq!(select.from[0].joins[0].table.name, "stock");
+    assert_eq!(select.from[0].joins[0].table.alias.as_deref(), Some("s"));
+}
+
+#[test]
+fn test_lower_ddl_rejects_other_statements() {
+    let cst = MockCstNode::new("select_statement");
+    let mut ctx = LoweringContext::new(Dialect::MySQL);
+
+    assert!(matches!(
+        MySQLLowering.lower_ddl(&mut ctx, &cst),
+        Err(LoweringError::UnexpectedNodeType { .. })
+    ));
+}
@@ -10,10 +10,12 @@
 //! and MySQL-specific syntax.
 
 use unified_sql_lsp_ir::query::SortDirection;
-use unified_sql_lsp_ir::{BinaryOp, Dialect, Expr, Literal};
+use unified_sql_lsp_ir::{BinaryOp, ColumnRef, Dialect, Expr, Literal};
 use unified_sql_lsp_lowering::cst::MockCstNode;
 use unified_sql_lsp_lowering::dialect::MySQLLowering;
-use unified_sql_lsp_lowering::{Lowering, LoweringContext, LoweringError};
+use unified_sql_lsp_lowering::{
+    Lowering, LoweringContext, LoweringError, binary_expr_id, definition_id,
+};
 
 // =============================================================================
 // Basic SELECT Statement Tests
@@ -141,6 +143,110 @@ fn test_mysql_comparison_expressions() {
     }
 }
 
+#[test]
+fn test_mysql_in_subquery_keeps_its_limit() {
+    let lowering = MySQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::MySQL);
+
+    // id IN (SELECT user_id FROM orders WHERE total > 0 LIMIT 5)
+    let subquery_where = MockCstNode::new("where_clause").with_child(
+        None,
+        MockCstNode::new("binary_expression")
+            .with_range(41, 50)
+            .with_child(None, MockCstNode::new("column_ref").with_text("total"))
+            .with_child(None, MockCstNode::new("operator").with_text(">"))
+            .with_child(None, MockCstNode::new("literal").with_text("0")),
+    );
+    let select = MockCstNode::new("select_statement")
+        .with_child(Some("where_clause"), subquery_where)
+        .with_child(
+            Some("limit_clause"),
+            MockCstNode::new("limit_clause")
+                .with_child(None, MockCstNode::new("literal").with_text("5")),
+        );
+    let expr = MockCstNode::new("binary_expression")
+        .with_range(0, 59)
+        .with_child(None, MockCstNode::new("column_ref").with_text("id"))
+        .with_child(
+            None,
+            MockCstNode::new("operator")
+                .with_range(3, 5)
+                .with_text("IN"),
+        )
+        .with_child(
+            None,
+            MockCstNode::new("subquery")
+                .with_range(6, 59)
+                .with_child(None, select),
+        );
+
+    let Ok(Expr::BinaryOp { op, right, .. }) = lowering.lower_expr(&mut ctx, &expr) else {
+        panic!("Expected BinaryOp expression");
+    };
+    assert_eq!(op, BinaryOp::In);
+    let Expr::Subquery(query) = *right else {
+        panic!("Expected Subquery expression");
+    };
+    assert_eq!(query.limit, Some(Expr::Literal(Literal::Integer(5))));
+
+    // The subquery's comparison is numbered on its own
+    assert_eq!(ctx.get_source_span(&binary_expr_id(0)), Some(0..59));
+    assert_eq!(ctx.get_source_span("binary_expr:0:operator"), Some(3..5));
+    assert_eq!(ctx.get_source_span(&binary_expr_id(1)), None);
+}
+
+#[test]
+fn test_mysql_cte_and_table_definitions_are_mapped() {
+    let lowering = MySQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::MySQL);
+
+    // WITH recent AS (SELECT id FROM orders) SELECT id FROM recent r JOIN users u ON 1
+    let named = |kind: &str, text: &str, start: usize| {
+        MockCstNode::new(kind)
+            .with_range(start, start + text.len())
+            .with_text(text)
+    };
+    let cte_query = MockCstNode::new("select_statement").with_child(
+        Some("from_clause"),
+        MockCstNode::new("from_clause")
+            .with_child(Some("table_name"), named("table_name", "orders", 31)),
+    );
+    let cte_clause = MockCstNode::new("cte_clause").with_child(
+        None,
+        MockCstNode::new("cte_definition")
+            .with_child(Some("table_name"), named("table_name", "recent", 5))
+            .with_child(Some("select_statement"), cte_query),
+    );
+    let from = MockCstNode::new("from_clause")
+        .with_child(
+            None,
+            MockCstNode::new("table_reference")
+                .with_child(Some("table_name"), named("table_name", "recent", 54))
+                .with_child(Some("alias"), named("alias", "r", 61)),
+        )
+        .with_child(
+            None,
+            MockCstNode::new("join_clause")
+                .with_child(Some("table_name"), named("table_name", "users", 68))
+                .with_child(Some("alias"), named("alias", "u", 74))
+                .with_child(None, named("literal", "1", 79)),
+        );
+    let cst = MockCstNode::new("select_statement")
+        .with_child(Some("cte_clause"), cte_clause)
+        .with_child(Some("from_clause"), from);
+
+    let query = lowering.lower_query(&mut ctx, &cst).unwrap();
+    assert_eq!(query.ctes.len(), 1);
+    assert_eq!(query.ctes[0].name, "recent");
+
+    // The CTE, its table, then the outer table and its join
+    assert_eq!(ctx.get_source_span(&definition_id(0)), Some(5..11));
+    assert_eq!(ctx.get_source_span(&definition_id(1)), Some(31..37));
+    assert_eq!(ctx.get_source_span(&definition_id(2)), Some(61..62));
+    assert_eq!(ctx.get_source_span(&definition_id(3)), Some(74..75));
+    assert_eq!(ctx.get_source_span(&definition_id(4)), None);
+}
+
 #[test]
 fn test_mysql_literal_integer() {
     let lowering = MySQLLowering;
@@ -263,6 +369,22 @@ fn test_mysql_unsupported_expression_placeholder() {
     }
 }
 
+#[test]
+fn test_mysql_merge_is_unsupported() {
+    let lowering = MySQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::MySQL);
+
+    let cst = MockCstNode::new("merge_statement");
+    let result = lowering.lower_query(&mut ctx, &cst);
+
+    // Recoverable: an empty query plus an UnsupportedSyntax error
+    assert!(result.is_ok());
+    assert!(matches!(
+        ctx.errors(),
+        [LoweringError::UnsupportedSyntax { feature, .. }] if feature == "MERGE statement"
+    ));
+}
+
 #[test]
 fn test_mysql_partial_success() {
     let mut ctx = LoweringContext::new(Dialect::MySQL);
@@ -340,6 +462,68 @@ fn test_mysql_replace_statement() {
     let _ = query; // Suppress unused warning
 }
 
+#[test]
+fn test_mysql_json_path_expressions() {
+    let lowering = MySQLLowering;
+
+    // data->'$.name' and data->>'$.name'
+    for (operator, unquote) in [("->", false), ("->>", true)] {
+        let mut ctx = LoweringContext::new(Dialect::MySQL);
+        let expr = MockCstNode::new("json_path_expression")
+            .with_child(
+                Some("expression"),
+                MockCstNode::new("column_ref").with_text("data"),
+            )
+            .with_child(
+                Some("operator"),
+                MockCstNode::new("operator").with_text(operator),
+            )
+            .with_child(
+                Some("path"),
+                MockCstNode::new("string_literal").with_text("'$.name'"),
+            );
+
+        let result = lowering.lower_expr(&mut ctx, &expr).unwrap();
+
+        assert!(!ctx.has_errors());
+        assert_eq!(
+            result,
+            Expr::JsonPath {
+                expr: Box::new(Expr::Column(ColumnRef::new("data"))),
+                path: "$.name".to_string(),
+                unquote,
+            }
+        );
+    }
+}
+
+#[test]
+fn test_mysql_cast_expression() {
+    let lowering = MySQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::MySQL);
+
+    // CAST(price AS DECIMAL(10,2))
+    let expr = MockCstNode::new("cast_expression")
+        .with_child(
+            Some("expression"),
+            MockCstNode::new("column_ref").with_text("price"),
+        )
+        .with_child(
+            Some("type"),
+            MockCstNode::new("data_type").with_text("DECIMAL(10,2)"),
+        );
+
+    let result = lowering.lower_expr(&mut ctx, &expr).unwrap();
+
+    assert_eq!(
+        result,
+        Expr::Cast {
+            expr: Box::new(Expr::Column(ColumnRef::new("price"))),
+            target_type: "DECIMAL(10,2)".to_string(),
+        }
+    );
+}
+
 // =============================================================================
 // Integration Tests
 // =============================================================================
@@ -10,7 +10,7 @@
 //! PostgreSQL-specific syntax.
 
 use unified_sql_lsp_ir::query::SortDirection;
-use unified_sql_lsp_ir::{Dialect, Expr};
+use unified_sql_lsp_ir::{ColumnRef, Dialect, Expr, InsertSource, MergeAction, OnConflict, SetOp};
 use unified_sql_lsp_lowering::cst::MockCstNode;
 use unified_sql_lsp_lowering::dialect::PostgreSQLLowering;
 use unified_sql_lsp_lowering::{Lowering, LoweringContext};
@@ -354,6 +354,59 @@ fn test_postgresql_case_expression_malformed_when_error() {
     );
 }
 
+// =============================================================================
+// Type Cast Tests
+// =============================================================================
+
+#[test]
+fn test_postgresql_type_cast_expressions() {
+    let lowering = PostgreSQLLowering;
+
+    // id::text and CAST(x AS numeric(10,2))
+    for (kind, column, target_type) in [
+        ("type_cast_expression", "id", "text"),
+        ("cast_expression", "x", "numeric(10,2)"),
+    ] {
+        let mut ctx = LoweringContext::new(Dialect::PostgreSQL);
+        let expr = MockCstNode::new(kind)
+            .with_child(
+                Some("expression"),
+                MockCstNode::new("column_ref").with_text(column),
+            )
+            .with_child(
+                Some("type"),
+                MockCstNode::new("data_type").with_text(target_type),
+            );
+
+        let result = lowering.lower_expr(&mut ctx, &expr).unwrap();
+
+        assert!(!ctx.has_errors());
+        assert_eq!(
+            result,
+            Expr::Cast {
+                expr: Box::new(Expr::Column(ColumnRef::new(column))),
+                target_type: target_type.to_string(),
+            }
+        );
+    }
+}
+
+#[test]
+fn test_postgresql_type_cast_missing_type() {
+    let lowering = PostgreSQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::PostgreSQL);
+
+    let expr = MockCstNode::new("type_cast_expression").with_child(
+        Some("expression"),
+        MockCstNode::new("column_ref").with_text("id"),
+    );
+
+    let result = lowering.lower_expr(&mut ctx, &expr);
+
+    assert!(result.is_ok(), "Missing type should degrade gracefully");
+    assert!(ctx.has_errors());
+}
+
 // =============================================================================
 // ORDER BY Direction Tests (LOWERING-004)
 // =============================================================================
@@ -651,3 +704,493 @@ fn test_postgresql_order_by_with_limit() {
     assert!(query.limit.is_some(), "Query should have LIMIT clause");
     assert!(!ctx.has_errors(), "Should have no errors");
 }
+
+// =============================================================================
+// Upsert and MERGE Tests
+// =============================================================================
+
+fn mock_assignment(column: &str, value: MockCstNode) -> MockCstNode {
+    MockCstNode::new("assignment")
+        .with_child(None, MockCstNode::new("column_name").with_text(column))
+        .with_child(None, value)
+}
+
+fn mock_column_list(columns: &[&str]) -> MockCstNode {
+    let identifiers = columns
+        .iter()
+        .fold(MockCstNode::new("identifier_list"), |list, c| {
+            list.with_child(None, MockCstNode::new("identifier").with_text(*c))
+        });
+    MockCstNode::new("column_list").with_child(Some("identifier_list"), identifiers)
+}
+
+fn mock_equals(left: &str, right: &str) -> MockCstNode {
+    MockCstNode::new("binary_expression")
+        .with_child(None, MockCstNode::new("column_ref").with_text(left))
+        .with_child(None, MockCstNode::new("operator").with_text("="))
+        .with_child(None, MockCstNode::new("column_ref").with_text(right))
+}
+
+#[test]
+fn test_postgresql_insert_select_on_conflict_do_update() {
+    let lowering = PostgreSQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::PostgreSQL);
+
+    // INSERT INTO users (id, name) SELECT id, name FROM staging
+    // ON CONFLICT (id) DO UPDATE SET name = 'x' WHERE active = 1
+    let select = MockCstNode::new("select_statement")
+        .with_child(
+            Some("projection"),
+            MockCstNode::new("projection")
+                .with_child(None, MockCstNode::new("column_ref").with_text("id"))
+                .with_child(None, MockCstNode::new("column_ref").with_text("name")),
+        )
+        .with_child(
+            Some("from"),
+            MockCstNode::new("from_clause").with_child(
+                Some("table"),
+                MockCstNode::new("table_name").with_text("staging"),
+            ),
+        );
+
+    let do_update = MockCstNode::new("conflict_do_update")
+        .with_child(
+            None,
+            mock_assignment("name", MockCstNode::new("literal").with_text("'x'")),
+        )
+        .with_child(
+            Some("where_clause"),
+            MockCstNode::new("where_clause").with_child(
+                None,
+                MockCstNode::new("binary_expression")
+                    .with_child(None, MockCstNode::new("column_ref").with_text("active"))
+                    .with_child(None, MockCstNode::new("operator").with_text("="))
+                    .with_child(None, MockCstNode::new("literal").with_text("1")),
+            ),
+        );
+    let on_conflict = MockCstNode::new("on_conflict_clause")
+        .with_child(Some("column_list"), mock_column_list(&["id"]))
+        .with_child(Some("conflict_do_update"), do_update);
+
+    let cst = MockCstNode::new("insert_statement")
+        .with_child(
+            Some("table_name"),
+            MockCstNode::new("table_name").with_text("users"),
+        )
+        .with_child(Some("column_list"), mock_column_list(&["id", "name"]))
+        .with_child(Some("select_statement"), select)
+        .with_child(Some("on_conflict_clause"), on_conflict);
+
+    let query = lowering
+        .lower_query(&mut ctx, &cst)
+        .expect("Lowering should succeed");
+    assert!(
+        !ctx.has_errors(),
+        "Should have no errors: {:?}",
+        ctx.errors()
+    );
+
+    let SetOp::Insert(insert) = query.body else {
+        panic!("Expected INSERT, got {:?}", query.body);
+    };
+    assert_eq!(insert.columns, vec!["id", "name"]);
+    assert!(matches!(insert.source, InsertSource::Query(_)));
+    match insert.on_conflict {
+        Some(OnConflict::DoUpdate {
+            conflict_columns,
+            update_columns,
+            update_exprs,
+            where_clause,
+        }) => {
+            assert_eq!(conflict_columns, vec!["id"]);
+            assert_eq!(update_columns, vec!["name"]);
+            assert_eq!(update_exprs.len(), 1);
+            assert!(where_clause.is_some(), "DO UPDATE WHERE should be kept");
+        }
+        other => panic!("Expected DoUpdate, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_postgresql_insert_on_conflict_do_nothing() {
+    let lowering = PostgreSQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::PostgreSQL);
+
+    // INSERT INTO users DEFAULT VALUES ON CONFLICT DO NOTHING
+    let on_conflict = MockCstNode::new("on_conflict_clause").with_child(
+        Some("conflict_do_nothing"),
+        MockCstNode::new("conflict_do_nothing"),
+    );
+    let cst = MockCstNode::new("insert_statement")
+        .with_child(
+            Some("table_name"),
+            MockCstNode::new("table_name").with_text("users"),
+        )
+        .with_child(Some("default_values"), MockCstNode::new("default_values"))
+        .with_child(Some("on_conflict_clause"), on_conflict);
+
+    let query = lowering
+        .lower_query(&mut ctx, &cst)
+        .expect("Lowering should succeed");
+
+    let SetOp::Insert(insert) = query.body else {
+        panic!("Expected INSERT, got {:?}", query.body);
+    };
+    assert_eq!(
+        insert.on_conflict,
+        Some(OnConflict::DoNothing { columns: vec![] })
+    );
+}
+
+#[test]
+fn test_postgresql_merge_statement() {
+    let lowering = PostgreSQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::PostgreSQL);
+
+    // MERGE INTO users u USING staging s ON u.id = s.id
+    // WHEN MATCHED AND s.deleted = u.deleted THEN DELETE
+    // WHEN MATCHED THEN UPDATE SET name = s.name
+    // WHEN NOT MATCHED THEN INSERT (id, name) VALUES (s.id, s.name)
+    let target = MockCstNode::new("table_reference")
+        .with_child(None, MockCstNode::new("table_name").with_text("users"))
+        .with_child(None, MockCstNode::new("alias").with_text("u"));
+    let source = MockCstNode::new("table_reference")
+        .with_child(None, MockCstNode::new("table_name").with_text("staging"))
+        .with_child(None, MockCstNode::new("alias").with_text("s"));
+
+    let when_delete = MockCstNode::new("merge_when_clause")
+        .with_child(Some("condition"), mock_equals("s.deleted", "u.deleted"))
+        .with_child(Some("action"), MockCstNode::new("merge_delete"));
+    let when_update = MockCstNode::new("merge_when_clause").with_child(
+        Some("action"),
+        MockCstNode::new("merge_update").with_child(
+            None,
+            mock_assignment("name", MockCstNode::new("column_ref").with_text("s.name")),
+        ),
+    );
+    let values = MockCstNode::new("values").with_child(
+        Some("value_row_list"),
+        MockCstNode::new("value_row_list").with_child(
+            None,
+            MockCstNode::new("value_row")
+                .with_child(None, MockCstNode::new("expression").with_text("s.id"))
+                .with_child(None, MockCstNode::new("expression").with_text("s.name")),
+        ),
+    );
+    let when_insert = MockCstNode::new("merge_when_clause")
+        .with_child(Some("not"), MockCstNode::new("NOT"))
+        .with_child(
+            Some("action"),
+            MockCstNode::new("merge_insert")
+                .with_child(Some("column_list"), mock_column_list(&["id", "name"]))
+                .with_child(Some("values"), values),
+        );
+
+    let cst = MockCstNode::new("merge_statement")
+        .with_child(Some("target"), target)
+        .with_child(Some("source"), source)
+        .with_child(Some("on"), mock_equals("u.id", "s.id"))
+        .with_child(None, when_delete)
+        .with_child(None, when_update)
+        .with_child(None, when_insert);
+
+    let query = lowering
+        .lower_query(&mut ctx, &cst)
+        .expect("Lowering should succeed");
+
+    let SetOp::Merge(merge) = query.body else {
+        panic!("Expected MERGE, got {:?}", query.body);
+    };
+    assert_eq!(merge.target.name, "users");
+    assert_eq!(merge.target.alias.as_deref(), Some("u"));
+    assert_eq!(merge.source.name, "staging");
+    assert_eq!(merge.source.alias.as_deref(), Some("s"));
+    assert_eq!(merge.when_clauses.len(), 3);
+
+    let delete = &merge.when_clauses[0];
+    assert!(delete.matched);
+    assert!(delete.condition.is_some());
+    assert_eq!(delete.action, MergeAction::Delete);
+
+    let update = &merge.when_clauses[1];
+    assert!(update.matched);
+    assert!(update.condition.is_none());
+    match &update.action {
+        MergeAction::Update { assignments } => {
+            assert_eq!(assignments.len(), 1);
+            assert_eq!(assignments[0].column, "name");
+            assert_eq!(
+                assignments[0].value,
+                Expr::Column(ColumnRef {
+                    table: Some("s".to_string()),
+                    column: "name".to_string(),
+                })
+            );
+        }
+        other => panic!("Expected UPDATE action, got {:?}", other),
+    }
+
+    let insert = &merge.when_clauses[2];
+    assert!(!insert.matched);
+    match &insert.action {
+        MergeAction::Insert { columns, values } => {
+            assert_eq!(columns, &vec!["id".to_string(), "name".to_string()]);
+            assert_eq!(values.as_ref().map(Vec::len), Some(2));
+        }
+        other => panic!("Expected INSERT action, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_postgresql_merge_missing_target_is_error() {
+    let lowering = PostgreSQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::PostgreSQL);
+
+    let cst = MockCstNode::new("merge_statement");
+
+    assert!(lowering.lower_query(&mut ctx, &cst).is_err());
+}
+
+// =============================================================================
+// Window Function Tests
+// =============================================================================
+
+fn mock_partition_by(column: &str) -> MockCstNode {
+    MockCstNode::new("partition_by")
+        .with_child(None, MockCstNode::new("PARTITION"))
+        .with_child(None, MockCstNode::new("BY"))
+        .with_child(None, MockCstNode::new("column_ref").with_text(column))
+}
+
+fn mock_window_order_by(column: &str) -> MockCstNode {
+    MockCstNode::new("order_by").with_child(
+        None,
+        MockCstNode::new("order_by_item")
+            .with_child(None, MockCstNode::new("column_ref").with_text(column)),
+    )
+}
+
+#[test]
+fn test_postgresql_distinct_on() {
+    let lowering = PostgreSQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::PostgreSQL);
+
+    // SELECT DISTINCT ON (user_id) id FROM orders
+    let distinct_on = MockCstNode::new("distinct_on_clause")
+        .with_child(None, MockCstNode::new("DISTINCT"))
+        .with_child(None, MockCstNode::new("ON"))
+        .with_child(None, MockCstNode::new("column_ref").with_text("user_id"));
+    let projection = MockCstNode::new("projection")
+        .with_child(None, MockCstNode::new("column_ref").with_text("id"));
+    let from = MockCstNode::new("from_clause").with_child(
+        Some("table"),
+        MockCstNode::new("table_name").with_text("orders"),
+    );
+
+    let cst = MockCstNode::new("select_statement")
+        .with_child(Some("distinct_on_clause"), distinct_on)
+        .with_child(Some("projection"), projection)
+        .with_child(Some("from_clause"), from);
+
+    let query = lowering.lower_query(&mut ctx, &cst).unwrap();
+    let SetOp::Select(select) = query.body else {
+        panic!("Expected SELECT body");
+    };
+
+    assert!(select.distinct);
+    assert_eq!(
+        select.distinct_on,
+        Some(vec![Expr::Column(ColumnRef {
+            table: None,
+            column: "user_id".to_string(),
+        })])
+    );
+    assert!(!ctx.has_errors());
+}
+
+#[test]
+fn test_postgresql_named_window_clause() {
+    let lowering = PostgreSQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::PostgreSQL);
+
+    // SELECT id FROM t WINDOW w AS (PARTITION BY dept ORDER BY salary)
+    let projection = MockCstNode::new("projection")
+        .with_child(None, MockCstNode::new("column_ref").with_text("id"));
+
+    let table = MockCstNode::new("table_name").with_text("t");
+    let from = MockCstNode::new("from_clause").with_child(Some("table"), table);
+
+    let definition = MockCstNode::new("window_definition")
+        .with_child(
+            Some("window_name"),
+            MockCstNode::new("window_name").with_text("w"),
+        )
+        .with_child(Some("partition_by"), mock_partition_by("dept"))
+        .with_child(Some("order_by"), mock_window_order_by("salary"));
+    let window = MockCstNode::new("window_clause")
+        .with_child(None, MockCstNode::new("WINDOW"))
+        .with_child(Some("window_definition"), definition);
+
+    let cst = MockCstNode::new("select_statement")
+        .with_child(Some("projection"), projection)
+        .with_child(Some("from_clause"), from)
+        .with_child(Some("window_clause"), window);
+
+    let query = lowering.lower_query(&mut ctx, &cst).unwrap();
+    let SetOp::Select(select) = query.body else {
+        panic!("Expected SELECT body");
+    };
+
+    assert_eq!(select.window.len(), 1);
+    let def = &select.window[0];
+    assert_eq!(def.name.as_deref(), Some("w"));
+    assert_eq!(
+        def.partition_by,
+        vec![Expr::Column(ColumnRef {
+            table: None,
+            column: "dept".to_string(),
+        })]
+    );
+    assert_eq!(def.order_by.len(), 1);
+    assert!(def.window_frame.is_none());
+    assert!(!ctx.has_errors());
+}
+
+#[test]
+fn test_postgresql_over_named_window() {
+    let lowering = PostgreSQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::PostgreSQL);
+
+    // rank() OVER w
+    let over = MockCstNode::new("over_clause")
+        .with_child(None, MockCstNode::new("OVER"))
+        .with_child(
+            Some("window_name"),
+            MockCstNode::new("window_name").with_text("w"),
+        );
+    let rank = MockCstNode::new("function_call")
+        .with_child(None, MockCstNode::new("function_name").with_text("rank"))
+        .with_child(Some("over_clause"), over);
+
+    match lowering.lower_expr(&mut ctx, &rank).unwrap() {
+        Expr::Function { name, over, .. } => {
+            assert_eq!(name, "rank");
+            let over = over.expect("OVER clause should be lowered");
+            assert_eq!(over.name.as_deref(), Some("w"));
+            assert!(over.partition_by.is_empty());
+            assert!(over.order_by.is_empty());
+        }
+        other => panic!("Expected function call, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_postgresql_window_definition_without_name_is_reported() {
+    let lowering = PostgreSQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::PostgreSQL);
+
+    let definition = MockCstNode::new("window_definition")
+        .with_child(Some("partition_by"), mock_partition_by("dept"));
+    let window =
+        MockCstNode::new("window_clause").with_child(Some("window_definition"), definition);
+    let cst = MockCstNode::new("select_statement")
+        .with_child(
+            Some("projection"),
+            MockCstNode::new("projection")
+                .with_child(None, MockCstNode::new("column_ref").with_text("id")),
+        )
+        .with_child(Some("window_clause"), window);
+
+    let query = lowering.lower_query(&mut ctx, &cst).unwrap();
+    let SetOp::Select(select) = query.body else {
+        panic!("Expected SELECT body");
+    };
+
+    assert!(select.window.is_empty());
+    assert!(ctx.has_errors());
+}
+
+// =============================================================================
+// VALUES Lists
+// =============================================================================
+
+/// `(1, 'a')` as a `value_list` node
+fn value_row(id: &str, name: &str) -> MockCstNode {
+    MockCstNode::new("value_list")
+        .with_child(None, MockCstNode::new("("))
+        .with_child(
+            None,
+            MockCstNode::new("expression")
+                .with_child(None, MockCstNode::new("literal").with_text(id)),
+        )
+        .with_child(None, MockCstNode::new(","))
+        .with_child(
+            None,
+            MockCstNode::new("expression")
+                .with_child(None, MockCstNode::new("literal").with_text(name)),
+        )
+        .with_child(None, MockCstNode::new(")"))
+}
+
+#[test]
+fn test_postgresql_standalone_values() {
+    let lowering = PostgreSQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::PostgreSQL);
+
+    // VALUES (1, 'a'), (2, 'b')
+    let cst = MockCstNode::new("values_statement")
+        .with_child(None, MockCstNode::new("VALUES"))
+        .with_child(None, value_row("1", "'a'"))
+        .with_child(None, MockCstNode::new(","))
+        .with_child(None, value_row("2", "'b'"));
+
+    let query = lowering.lower_query(&mut ctx, &cst).unwrap();
+    let SetOp::Values(rows) = query.body else {
+        panic!("expected a VALUES body, got {:?}", query.body);
+    };
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].len(), 2);
+    assert!(!ctx.has_errors(), "{:?}", ctx.errors());
+}
+
+#[test]
+fn test_postgresql_cte_over_values() {
+    let lowering = PostgreSQLLowering;
+    let mut ctx = LoweringContext::new(Dialect::PostgreSQL);
+
+    // WITH data(id, name) AS (VALUES (1, 'a')) SELECT id FROM data
+    let columns = MockCstNode::new("column_list")
+        .with_child(None, MockCstNode::new("column_name").with_text("id"))
+        .with_child(None, MockCstNode::new("column_name").with_text("name"));
+    let values = MockCstNode::new("values_statement").with_child(None, value_row("1", "'a'"));
+    let definition = MockCstNode::new("cte_definition")
+        .with_child(
+            Some("table_name"),
+            MockCstNode::new("table_name").with_text("data"),
+        )
+        .with_child(Some("column_list"), columns)
+        .with_child(Some("values_statement"), values);
+    let cte_clause = MockCstNode::new("cte_clause").with_child(None, definition);
+
+    let projection = MockCstNode::new("projection")
+        .with_child(None, MockCstNode::new("column_ref").with_text("id"));
+    let from = MockCstNode::new("from_clause").with_child(
+        Some("table"),
+        MockCstNode::new("table_name").with_text("data"),
+    );
+    let cst = MockCstNode::new("select_statement")
+        .with_child(Some("cte_clause"), cte_clause)
+        .with_child(Some("projection"), projection)
+        .with_child(Some("from"), from);
+
+    let query = lowering.lower_query(&mut ctx, &cst).unwrap();
+    assert_eq!(query.ctes.len(), 1);
+    let cte = &query.ctes[0];
+    assert_eq!(cte.name, "data");
+    assert_eq!(cte.columns, vec!["id", "name"]);
+    let SetOp::Values(rows) = &cte.query.body else {
+        panic!("expected a VALUES body, got {:?}", cte.query.body);
+    };
+    assert_eq!(rows.len(), 1);
+    assert!(matches!(query.body, SetOp::Select(_)));
+}
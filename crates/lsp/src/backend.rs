@@ -32,6 +32,14 @@
 //! - textDocument/didChange
 //! - textDocument/didClose
 //!
+//! Extension methods:
+//! - `unifiedSqlLsp/syntaxTree`: the parse tree of a document, for debugging
+//!   grammar issues (see [`SyntaxTreeParams`]). Registered by
+//!   [`LspBackend::service`].
+//! - `unifiedSqlLsp/status`: notification describing the active document,
+//!   sent after it is opened or changed and after catalog refreshes (see
+//!   [`crate::status`]).
+//!
 //! Planned (in future features):
 //! - textDocument/completion (LSP-003)
 //! - textDocument/hover (HOVER-001)
@@ -53,19 +61,90 @@
 //! ```
 
 use crate::catalog_manager::CatalogManager;
+use crate::code_actions::quick_fixes;
 use crate::completion::CompletionEngine;
-use crate::config::EngineConfig;
-use crate::diagnostic::{DiagnosticCollector, publish_diagnostics_for_document};
+use crate::completion::stats::CompletionStats;
+use crate::config::{
+    CatalogPrefetch, CompletionConfig, EngineConfig, SETTINGS_SECTION, SchemaSource, WorkspaceMode,
+};
+use crate::diagnostics_coalescer::DiagnosticsCoalescer;
+use crate::diagnostics_scheduler::{ClientDiagnosticsPublisher, DiagnosticsScheduler};
 use crate::document::{Document, DocumentError, DocumentStore, ParseMetadata};
+use crate::events::{EventBus, ServerEvent};
+use crate::parsing::{DEBUG_TREE_NODE_LIMIT, debug_tree};
+use crate::positions::to_context_range;
+use crate::prefetch::{RecentTables, RequestActivity, prefetch_catalog, recent_tables_path};
+use crate::rename;
 use crate::request_context::RequestContext;
+use crate::rules::CustomRules;
+use crate::schema_diff::{SCHEMA_DIFF_LIMIT, diff_catalogs};
+use crate::server_info::{
+    CatalogInfo, CatalogKind, SERVER_INFO_METHOD, ServerInfoResult, compiled_dialects,
+    compiled_features, supported_abi_versions,
+};
+use crate::status::{DocumentStatus, StatusDiagnosticsSink, StatusReporter};
 use crate::symbols::{SymbolBuilder, SymbolCatalogFetcher, SymbolError, SymbolRenderer};
 use crate::sync::DocumentSync;
+use crate::workspace::WorkspaceRoot;
+use crate::workspace_index::WorkspaceIndex;
+use crate::workspace_symbols::WorkspaceSymbolProvider;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::{RwLock, broadcast};
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
-use tower_lsp::{Client, LanguageServer};
+use tower_lsp::{Client, ClientSocket, LanguageServer, LspService};
 use tracing::{debug, error, info, warn};
+use unified_sql_grammar::language_for_dialect;
+use unified_sql_lsp_catalog::{CatalogError, expand_schema_paths};
+
+/// Command that drops cached catalogs and revalidates open documents
+pub const REFRESH_CATALOG_COMMAND: &str = "unified-sql-lsp.refreshCatalog";
+
+/// Command that reports diagnostics publishing counters
+pub const DIAGNOSTICS_STATS_COMMAND: &str = "unified-sql-lsp.diagnosticsStats";
+
+/// Command that reports completion counters per context
+///
+/// The counters are kept in memory only and never leave the server. Pass
+/// `{"reset": true}` as the first argument to clear them after reporting.
+pub const COMPLETION_STATS_COMMAND: &str = "unified-sql-lsp.completionStats";
+
+/// Command comparing the DDL schema files with the connected database
+///
+/// Pass `{"uri": "..."}` as the first argument to use the config of the
+/// workspace root holding that document. Returns `{"diff": ..., "markdown": ...}`
+/// with at most [`SCHEMA_DIFF_LIMIT`] differences.
+pub const SCHEMA_DIFF_COMMAND: &str = "unifiedSqlLsp.schemaDiff";
+
+/// Commands accepted by `workspace/executeCommand`
+pub const COMMANDS: [&str; 4] = [
+    REFRESH_CATALOG_COMMAND,
+    DIAGNOSTICS_STATS_COMMAND,
+    COMPLETION_STATS_COMMAND,
+    SCHEMA_DIFF_COMMAND,
+];
+
+/// Custom request returning a document's parse tree
+pub const SYNTAX_TREE_METHOD: &str = "unifiedSqlLsp/syntaxTree";
+
+/// Parameters of the `unifiedSqlLsp/syntaxTree` request
+///
+/// The result is the tree rendered by [`debug_tree`] as a string, or `null`
+/// when the document isn't open or hasn't been parsed.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyntaxTreeParams {
+    /// Document to render
+    pub text_document: TextDocumentIdentifier,
+
+    /// Only render nodes overlapping this range
+    #[serde(default)]
+    pub range: Option<Range>,
+}
 
 /// LSP backend implementation
 ///
@@ -76,39 +155,350 @@ pub struct LspBackend {
     config: Arc<RwLock<Option<EngineConfig>>>,
     doc_sync: Arc<DocumentSync>,
     request_context: RequestContext,
-    diagnostic_collector: DiagnosticCollector,
+    diagnostics: DiagnosticsScheduler,
+    /// Rate limits and deduplicates outbound diagnostics notifications
+    diagnostics_output: DiagnosticsCoalescer,
+    /// Status notifications for the active document
+    status: StatusReporter,
+    /// Counters of completion results per context
+    completion_stats: Arc<CompletionStats>,
+    /// Definitions of the SQL files under the workspace roots
+    workspace_index: Arc<WorkspaceIndex>,
+    /// Categories of catalog errors already shown to the user
+    reported_catalog_errors: std::sync::Mutex<HashSet<&'static str>>,
+    /// Config, catalog and document changes, for the components deriving
+    /// state from them
+    events: EventBus,
+    /// Subscriptions made at construction, handed to their components once
+    /// initialized
+    event_receivers: std::sync::Mutex<Option<EventReceivers>>,
+    /// Whether the client accepts snippet completion items
+    snippet_support: Arc<AtomicBool>,
+    /// Whether the client accepts insert/replace completion edits
+    insert_replace_support: Arc<AtomicBool>,
+    /// Whether the client answers `workspace/configuration` requests
+    configuration_pull: AtomicBool,
+    /// Whether `workspace/didChangeConfiguration` can be registered dynamically
+    configuration_registration: AtomicBool,
+    /// Completions and hovers in flight, which the catalog prefetch yields to
+    request_activity: Arc<RequestActivity>,
+    /// Tables whose columns completions read, kept across sessions
+    recent_tables: Arc<RecentTables>,
+    /// Background catalog prefetch started once initialized
+    prefetch_task: std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>,
+}
+
+/// Event subscriptions of the components listening on the backend's bus
+struct EventReceivers {
+    diagnostics: broadcast::Receiver<ServerEvent>,
+    status: broadcast::Receiver<ServerEvent>,
+    request_context: broadcast::Receiver<ServerEvent>,
+    workspace_index: broadcast::Receiver<ServerEvent>,
+}
+
+impl EventReceivers {
+    fn subscribe(events: &EventBus) -> Self {
+        Self {
+            diagnostics: events.subscribe(),
+            status: events.subscribe(),
+            request_context: events.subscribe(),
+            workspace_index: events.subscribe(),
+        }
+    }
 }
 
 impl LspBackend {
     pub fn new(client: Client) -> Self {
         debug!("!!! LSP: LspBackend::new() called");
         let config = Arc::new(RwLock::new(None));
-        let doc_sync = Arc::new(DocumentSync::new(config.clone()));
-        let catalog_manager = Arc::new(RwLock::new(CatalogManager::new()));
+        let events = EventBus::new();
+        let event_receivers = EventReceivers::subscribe(&events);
+        let catalog_manager = Arc::new(RwLock::new(
+            CatalogManager::new().with_event_bus(events.clone()),
+        ));
         let request_context = RequestContext::new(config.clone(), catalog_manager.clone());
+        let doc_sync = Arc::new(
+            DocumentSync::new(config.clone())
+                .with_workspace_roots(request_context.workspace_roots()),
+        );
+        let documents = Arc::new(DocumentStore::new());
+        let status = StatusReporter::new(Arc::new(client.clone()));
+        let diagnostics_output = DiagnosticsCoalescer::new(Arc::new(StatusDiagnosticsSink::new(
+            Arc::new(client.clone()),
+            status.clone(),
+        )));
+        let workspace_index = Arc::new(WorkspaceIndex::new());
+        let diagnostics = DiagnosticsScheduler::new(
+            documents.clone(),
+            Arc::new(
+                ClientDiagnosticsPublisher::new(
+                    diagnostics_output.clone(),
+                    request_context.clone(),
+                )
+                .with_workspace_index(workspace_index.clone()),
+            ),
+        );
 
         debug!("!!! LSP: LspBackend created successfully");
         Self {
             client,
-            documents: Arc::new(DocumentStore::new()),
+            documents,
             config,
             doc_sync,
             request_context,
-            diagnostic_collector: DiagnosticCollector::new(),
+            diagnostics,
+            diagnostics_output,
+            status,
+            completion_stats: Arc::new(CompletionStats::new()),
+            workspace_index,
+            reported_catalog_errors: std::sync::Mutex::new(HashSet::new()),
+            events,
+            event_receivers: std::sync::Mutex::new(Some(event_receivers)),
+            snippet_support: Arc::new(AtomicBool::new(false)),
+            insert_replace_support: Arc::new(AtomicBool::new(false)),
+            configuration_pull: AtomicBool::new(false),
+            configuration_registration: AtomicBool::new(false),
+            request_activity: Arc::new(RequestActivity::default()),
+            recent_tables: Arc::new(RecentTables::default()),
+            prefetch_task: std::sync::Mutex::new(None),
         }
     }
 
+    /// Build the LSP service, with the extension methods registered
+    pub fn service() -> (LspService<Self>, ClientSocket) {
+        LspService::build(Self::new)
+            .custom_method(SYNTAX_TREE_METHOD, Self::syntax_tree)
+            .custom_method(SERVER_INFO_METHOD, Self::server_info)
+            .finish()
+    }
+
     pub fn documents(&self) -> &DocumentStore {
         &self.documents
     }
 
+    pub fn document_sync(&self) -> &DocumentSync {
+        &self.doc_sync
+    }
+
+    /// Receive the server events emitted from now on
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ServerEvent> {
+        self.events.subscribe()
+    }
+
+    /// Handle `unifiedSqlLsp/syntaxTree`
+    pub async fn syntax_tree(&self, params: SyntaxTreeParams) -> Result<Option<String>> {
+        let uri = params.text_document.uri;
+        let Some(document) = self.documents.get_document(&uri).await else {
+            debug!("Syntax tree for closed document: {}", uri);
+            return Ok(None);
+        };
+        let Some(tree) = document.tree() else {
+            return Ok(None);
+        };
+
+        let tree = tree.lock().await;
+        Ok(Some(debug_tree(
+            &tree,
+            params.range.map(to_context_range),
+            DEBUG_TREE_NODE_LIMIT,
+        )))
+    }
+
+    /// Handle `unifiedSqlLsp/serverInfo`
+    pub async fn server_info(&self) -> Result<ServerInfoResult> {
+        let config = self.request_context.config_or_fallback().await;
+        let kind = CatalogKind::of(&config);
+        let connected =
+            kind != CatalogKind::None && self.request_context.has_catalog(&config).await;
+
+        Ok(ServerInfoResult {
+            version: crate::VERSION.to_string(),
+            features: compiled_features(),
+            supported_abi_versions: supported_abi_versions(),
+            dialects: compiled_dialects(),
+            catalog: CatalogInfo { kind, connected },
+            commands: COMMANDS.map(String::from).to_vec(),
+        })
+    }
+
     pub async fn get_config(&self) -> Option<EngineConfig> {
         self.config.read().await.clone()
     }
 
+    /// Configuration for the document at `uri`
+    ///
+    /// The config of the deepest workspace root holding the document, or
+    /// the client settings for documents outside a configured root.
+    pub async fn config_for(&self, uri: &Url) -> EngineConfig {
+        self.request_context.config_for(uri).await
+    }
+
+    /// Diff the DDL schema files of `config` against its database
+    ///
+    /// Both a DDL `schemaSource` and a connection string are needed.
+    async fn schema_diff(&self, config: &EngineConfig) -> Result<serde_json::Value> {
+        use tower_lsp::jsonrpc::Error;
+        if !matches!(config.schema_source, SchemaSource::Ddl { .. }) {
+            return Err(Error::invalid_params(
+                "Schema diff needs a DDL schemaSource",
+            ));
+        }
+        if config.connection_string.is_empty() {
+            return Err(Error::invalid_params(
+                "Schema diff needs a connectionString",
+            ));
+        }
+        let database_config = EngineConfig {
+            schema_source: SchemaSource::Connection,
+            ..config.clone()
+        };
+
+        let diff = async {
+            let ddl = self.request_context.catalog_for_config(config).await?;
+            let database = self
+                .request_context
+                .catalog_for_config(&database_config)
+                .await?;
+            diff_catalogs(ddl.as_ref(), database.as_ref()).await
+        };
+        let mut diff = diff
+            .await
+            .map_err(|e| Error::invalid_params(e.to_string()))?;
+        diff.truncate(SCHEMA_DIFF_LIMIT);
+        info!(
+            "Schema diff found {} differences",
+            diff.len() + diff.truncated
+        );
+
+        Ok(serde_json::json!({ "diff": diff, "markdown": diff.to_markdown() }))
+    }
+
     pub async fn set_config(&self, config: EngineConfig) {
         info!("Engine configuration updated: dialect={:?}", config.dialect);
         *self.config.write().await = Some(config);
+        self.events.emit(ServerEvent::ConfigChanged);
+    }
+
+    /// Apply a client settings payload holding the `unifiedSqlLsp` section
+    ///
+    /// Returns whether the settings parsed into an engine configuration.
+    async fn apply_settings(&self, settings: &serde_json::Value) -> bool {
+        // Parse configuration from client settings
+        match EngineConfig::from_lsp_settings(settings) {
+            Some(config) => {
+                debug!(
+                    "!!! LSP: Successfully parsed config: dialect={:?}",
+                    config.dialect
+                );
+                let schema_files = match &config.schema_source {
+                    SchemaSource::Ddl { paths } => paths.clone(),
+                    SchemaSource::Connection => Vec::new(),
+                };
+                let (custom_rules, rule_warnings) =
+                    CustomRules::load(&config.custom_rules, config.dialect);
+                for warning in rule_warnings {
+                    self.show_message(&warning, MessageType::WARNING).await;
+                }
+
+                let unknown_rules: Vec<&str> = config
+                    .diagnostics
+                    .unknown_codes()
+                    .into_iter()
+                    .filter(|code| !custom_rules.defines_code(code))
+                    .collect();
+                if !unknown_rules.is_empty() {
+                    self.show_message(
+                        &format!(
+                            "Unknown diagnostic rule codes in settings: {}",
+                            unknown_rules.join(", ")
+                        ),
+                        MessageType::WARNING,
+                    )
+                    .await;
+                }
+                self.request_context.set_custom_rules(custom_rules).await;
+                self.set_config(config).await;
+                if !schema_files.is_empty() {
+                    self.watch_schema_files(&schema_files).await;
+                }
+                debug!("!!! LSP: Engine configuration updated from client settings");
+                true
+            }
+            None => {
+                debug!("!!! LSP: Failed to parse configuration from client settings");
+                false
+            }
+        }
+    }
+
+    /// Request the `unifiedSqlLsp` section with `workspace/configuration`
+    ///
+    /// Returns whether settings were applied. Clients without the
+    /// capability, failed requests and empty sections are ignored.
+    async fn pull_configuration(&self) -> bool {
+        if !self.configuration_pull.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        let item = ConfigurationItem {
+            scope_uri: None,
+            section: Some(SETTINGS_SECTION.to_string()),
+        };
+        match self.client.configuration(vec![item]).await {
+            Ok(mut values) => match values.pop() {
+                Some(section) if !section.is_null() => {
+                    let settings = serde_json::json!({ SETTINGS_SECTION: section });
+                    self.apply_settings(&settings).await
+                }
+                _ => false,
+            },
+            Err(e) => {
+                debug!("workspace/configuration request failed: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Ask the client to push configuration changes
+    ///
+    /// Clients supporting dynamic registration only send
+    /// `workspace/didChangeConfiguration` once it is registered.
+    async fn register_configuration_changes(&self) {
+        let registration = Registration {
+            id: "unified-sql-lsp/configuration".to_string(),
+            method: "workspace/didChangeConfiguration".to_string(),
+            register_options: None,
+        };
+
+        if let Err(e) = self.client.register_capability(vec![registration]).await {
+            warn!("Failed to register for configuration changes: {}", e);
+        }
+    }
+
+    /// Ask the client to report changes to DDL schema files
+    ///
+    /// Lets `did_change_watched_files` reload the catalog when `schema.sql` is edited.
+    /// Patterns such as `db/schema/*.sql` are passed through as watcher globs,
+    /// so newly created schema files are reported as well.
+    async fn watch_schema_files(&self, paths: &[std::path::PathBuf]) {
+        let registration = Registration {
+            id: "unified-sql-lsp/schema-file".to_string(),
+            method: "workspace/didChangeWatchedFiles".to_string(),
+            register_options: serde_json::to_value(DidChangeWatchedFilesRegistrationOptions {
+                watchers: paths
+                    .iter()
+                    .map(|path| FileSystemWatcher {
+                        glob_pattern: GlobPattern::String(path.to_string_lossy().into_owned()),
+                        kind: None,
+                    })
+                    .collect(),
+            })
+            .ok(),
+        };
+
+        if let Err(e) = self.client.register_capability(vec![registration]).await {
+            warn!("Failed to register schema file watcher: {}", e);
+        }
     }
 
     async fn log_message(&self, message: &str, message_type: MessageType) {
@@ -119,6 +509,185 @@ impl LspBackend {
         self.client.show_message(message_type, message).await;
     }
 
+    /// Index the SQL files of `roots` in the background
+    ///
+    /// Each root is filtered by the `workspaceIndex` settings that apply to
+    /// it; the user is warned when a root holds more files than the cap.
+    async fn index_workspace_roots(&self, roots: Vec<WorkspaceRoot>) {
+        for root in roots {
+            let config = self
+                .request_context
+                .config_for(&root.uri)
+                .await
+                .workspace_index;
+            let index = self.workspace_index.clone();
+            let client = self.client.clone();
+            tokio::spawn(async move {
+                let path = root.path.clone();
+                let max_files = config.max_files;
+                match tokio::task::spawn_blocking(move || index.index_root(&path, &config)).await {
+                    Ok(summary) => {
+                        info!(
+                            "Indexed {} SQL files under {}",
+                            summary.files,
+                            root.path.display()
+                        );
+                        if summary.truncated {
+                            let message = format!(
+                                "Only the first {} SQL files under {} were indexed; \
+                                 narrow workspaceIndex.include or raise workspaceIndex.maxFiles",
+                                max_files,
+                                root.path.display()
+                            );
+                            client.show_message(MessageType::WARNING, message).await;
+                        }
+                    }
+                    Err(e) => warn!("Failed to index {}: {}", root.path.display(), e),
+                }
+            });
+        }
+    }
+
+    /// Ask the client to report changes to the SQL files of the workspace
+    ///
+    /// Registers the `workspaceIndex.include` globs of the client settings
+    /// and of every workspace root config.
+    async fn watch_workspace_files(&self) {
+        let mut globs = self
+            .request_context
+            .config_or_fallback()
+            .await
+            .workspace_index
+            .include;
+        for root in self.request_context.workspace_roots().all() {
+            globs.extend(
+                root.config
+                    .into_iter()
+                    .flat_map(|c| c.workspace_index.include),
+            );
+        }
+        globs.sort();
+        globs.dedup();
+
+        let registration = Registration {
+            id: "unified-sql-lsp/workspace-index".to_string(),
+            method: "workspace/didChangeWatchedFiles".to_string(),
+            register_options: serde_json::to_value(DidChangeWatchedFilesRegistrationOptions {
+                watchers: globs
+                    .into_iter()
+                    .map(|glob| FileSystemWatcher {
+                        glob_pattern: GlobPattern::String(glob),
+                        kind: None,
+                    })
+                    .collect(),
+            })
+            .ok(),
+        };
+
+        if let Err(e) = self.client.register_capability(vec![registration]).await {
+            warn!("Failed to register workspace file watcher: {}", e);
+        }
+    }
+
+    /// Warm up the catalog in the background as `catalog.prefetch` asks
+    ///
+    /// Restores the tables used in the previous session first, so `full`
+    /// mode fetches their columns. Replaces a prefetch still running.
+    async fn start_prefetch(&self) {
+        let config = self.request_context.config_or_fallback().await;
+        if let Some(path) = recent_tables_path(&config) {
+            self.recent_tables.restore(path);
+        }
+        if config.catalog.prefetch == CatalogPrefetch::Off {
+            return;
+        }
+
+        let request_context = self.request_context.clone();
+        let activity = self.request_activity.clone();
+        let recent = self.recent_tables.recent(config.catalog.recent_tables);
+        let task = tokio::spawn(async move {
+            // Connecting takes the catalog manager, so let requests go first
+            activity.idle().await;
+            let catalog = match request_context.catalog_for_config(&config).await {
+                Ok(catalog) => catalog,
+                Err(e) => {
+                    debug!("No catalog to prefetch: {}", e);
+                    return;
+                }
+            };
+            let results = request_context.late_catalog_results().await;
+            let summary = prefetch_catalog(
+                catalog,
+                &results,
+                &activity,
+                config.catalog.prefetch,
+                recent,
+                Duration::from_secs(config.query_timeout_secs),
+            )
+            .await;
+            info!(
+                "Prefetched {} tables and the columns of {}",
+                summary.tables, summary.columns
+            );
+        });
+        if let Some(previous) = self.prefetch_task.lock().unwrap().replace(task) {
+            previous.abort();
+        }
+    }
+
+    /// Rebuild the workspace index after its settings may have changed
+    async fn reindex_workspace(&self) {
+        self.watch_workspace_files().await;
+        self.index_workspace_roots(self.request_context.workspace_roots().all())
+            .await;
+        self.index_schema_files().await;
+    }
+
+    /// Index the definitions of the configured DDL schema files
+    ///
+    /// `workspace/symbol` lists them from the index rather than reading
+    /// the files on every request.
+    async fn index_schema_files(&self) {
+        let paths = match self
+            .request_context
+            .config_or_fallback()
+            .await
+            .schema_source
+        {
+            SchemaSource::Ddl { paths } => paths,
+            SchemaSource::Connection => Vec::new(),
+        };
+        let index = self.workspace_index.clone();
+        let indexed = tokio::task::spawn_blocking(move || {
+            index.index_schema_files(&expand_schema_paths(&paths).unwrap_or_default())
+        })
+        .await;
+        if let Err(e) = indexed {
+            warn!("Failed to index schema files: {}", e);
+        }
+    }
+
+    /// Tell the user about a catalog error with what to do about it
+    ///
+    /// Only the first error of each category is shown; later ones go to
+    /// the log so that every keystroke doesn't pop up the same message.
+    async fn report_catalog_error(&self, error: &CatalogError) {
+        let message = match error.remediation() {
+            Some(hint) => format!("{error}; {hint}"),
+            None => error.to_string(),
+        };
+        let first = self
+            .reported_catalog_errors
+            .lock()
+            .unwrap()
+            .insert(error.category());
+        if first {
+            self.show_message(&message, MessageType::ERROR).await;
+        } else {
+            self.log_message(&message, MessageType::ERROR).await;
+        }
+    }
+
     #[allow(dead_code)]
     async fn publish_diagnostics(&self, uri: Url, diagnostics: Vec<Diagnostic>) {
         self.client
@@ -126,25 +695,141 @@ impl LspBackend {
             .await;
     }
 
-    /// Publish diagnostics for a document
-    ///
-    /// Shared helper for publishing diagnostics after parsing.
-    async fn publish_document_diagnostics(&self, uri: &Url) {
-        let updated_document = self.documents.get_document(uri).await;
-        if let Some(doc) = updated_document {
-            let source = doc.get_content();
-            let tree_ref = doc.tree();
-            publish_diagnostics_for_document(
-                &self.diagnostic_collector,
-                &self.client,
-                uri.clone(),
-                &tree_ref,
-                &source,
-            )
+    /// Clear a document's diagnostics through the outbound coalescer
+    async fn clear_diagnostics(&self, uri: &Url) {
+        let interval = self
+            .request_context
+            .config_for(uri)
+            .await
+            .diagnostics
+            .publish_interval;
+        self.diagnostics_output
+            .publish(uri.clone(), Vec::new(), None, interval)
             .await;
+    }
+
+    /// Store a parsed tree, tolerating documents closed while parsing
+    async fn store_tree(&self, uri: &Url, tree: tree_sitter::Tree, metadata: ParseMetadata) {
+        match self
+            .documents
+            .update_document_tree(uri, tree, metadata)
+            .await
+        {
+            Ok(()) => {}
+            Err(DocumentError::DocumentNotFound(_)) => {
+                debug!("Document closed while parsing: {}", uri);
+            }
+            Err(e) => error!("Failed to update document tree: {}", e),
         }
     }
 
+    /// Keep the pending migrations of open documents in the workspace index,
+    /// revalidating the other documents when they change
+    ///
+    /// A lagging subscription resyncs the index with the open documents. The
+    /// task ends when the event bus is dropped.
+    fn follow_open_documents(
+        &self,
+        mut events: broadcast::Receiver<ServerEvent>,
+    ) -> tokio::task::JoinHandle<()> {
+        let documents = self.documents.clone();
+        let index = self.workspace_index.clone();
+        let request_context = self.request_context.clone();
+        let diagnostics = self.diagnostics.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let changed = match events.recv().await {
+                    Ok(ServerEvent::DocumentChanged { uri, .. }) => {
+                        match documents.get_document(&uri).await {
+                            Some(document) => index
+                                .update_open_document(&uri, &document.get_content())
+                                .then_some(uri),
+                            None => None,
+                        }
+                    }
+                    Ok(ServerEvent::DocumentClosed { uri }) => {
+                        index.close_document(&uri).then_some(uri)
+                    }
+                    Ok(_) => None,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        debug!("Skipped {} server events, resyncing migrations", skipped);
+                        let open = documents.list_uris().await;
+                        let mut changed = index.retain_open_documents(&open);
+                        for uri in &open {
+                            if let Some(document) = documents.get_document(uri).await {
+                                changed |= index.update_open_document(uri, &document.get_content());
+                            }
+                        }
+                        if changed {
+                            diagnostics.revalidate_all().await;
+                        }
+                        None
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+
+                // Only the other documents read the migrations of `uri`
+                if let Some(uri) = changed
+                    && request_context
+                        .config_for(&uri)
+                        .await
+                        .diagnostics
+                        .pending_migrations
+                {
+                    for other in diagnostics.tracked_uris() {
+                        if other != uri {
+                            diagnostics.schedule(other);
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// Report the status of a document, counting its catalog's tables in the
+    /// background when they are unknown or stale
+    async fn report_status(&self, uri: &Url) {
+        let Some(document) = self.documents.get_document(uri).await else {
+            return;
+        };
+        let metadata = document.parse_metadata();
+        let dialect = metadata
+            .map(|metadata| metadata.dialect)
+            .unwrap_or_else(|| self.doc_sync.resolve_dialect(&document));
+        let config = self
+            .request_context
+            .config_for(uri)
+            .await
+            .for_dialect(dialect);
+
+        if self.status.begin_catalog_count() {
+            let request_context = self.request_context.clone();
+            let status = self.status.clone();
+            let config = config.clone();
+            tokio::spawn(async move {
+                match request_context.catalog_for_config(&config).await {
+                    Ok(catalog) => status.count_tables(catalog).await,
+                    Err(e) => {
+                        debug!("No catalog to count tables: {}", e);
+                        status.count_failed();
+                    }
+                }
+            });
+        }
+
+        self.status
+            .report(DocumentStatus {
+                uri: uri.clone(),
+                dialect,
+                version: config.version.number().to_string(),
+                grammar_abi_version: language_for_dialect(dialect)
+                    .map(|language| language.abi_version()),
+                parse_time_ms: metadata.map(|metadata| metadata.parse_time_ms),
+            })
+            .await;
+    }
+
     /// Parse document and update its tree in the store
     ///
     /// Shared helper for did_open and did_change handlers.
@@ -155,35 +840,29 @@ impl LspBackend {
             crate::parsing::ParseResult::Success { tree, parse_time } => {
                 info!("Document parsed successfully in {:?}", parse_time);
                 let metadata = ParseMetadata::new(parse_time.as_millis() as u64, dialect, false, 0);
-                if let Some(tree) = tree
-                    && let Err(e) = self
-                        .documents
-                        .update_document_tree(uri, tree, metadata)
-                        .await
-                {
-                    error!("Failed to update document tree: {}", e);
+                if let Some(tree) = tree {
+                    self.store_tree(uri, tree, metadata).await;
                 }
-                self.publish_document_diagnostics(uri).await;
+                self.diagnostics.schedule_now(uri.clone());
             }
-            crate::parsing::ParseResult::Partial { tree, errors } => {
+            crate::parsing::ParseResult::Partial {
+                tree,
+                errors,
+                error_ranges,
+                missing_nodes,
+            } => {
                 warn!("Document parsed with {} errors", errors.len());
-                let metadata = ParseMetadata::new(0, dialect, true, errors.len());
-                if let Some(tree) = tree
-                    && let Err(e) = self
-                        .documents
-                        .update_document_tree(uri, tree, metadata)
-                        .await
-                {
-                    error!("Failed to update document tree: {}", e);
+                let metadata = ParseMetadata::new(0, dialect, true, errors.len())
+                    .with_syntax_errors(error_ranges, missing_nodes);
+                if let Some(tree) = tree {
+                    self.store_tree(uri, tree, metadata).await;
                 }
-                self.publish_document_diagnostics(uri).await;
+                self.diagnostics.schedule_now(uri.clone());
             }
             crate::parsing::ParseResult::Failed { error } => {
                 error!("Failed to parse document: {}", error);
                 // Clear diagnostics on parse failure
-                self.client
-                    .publish_diagnostics(uri.clone(), Vec::new(), None)
-                    .await;
+                self.clear_diagnostics(uri).await;
             }
         }
     }
@@ -207,28 +886,24 @@ impl LspBackend {
             crate::parsing::ParseResult::Success { tree, parse_time } => {
                 info!("Document reparsed in {:?}", parse_time);
                 let metadata = ParseMetadata::new(parse_time.as_millis() as u64, dialect, false, 0);
-                if let Some(tree) = tree
-                    && let Err(e) = self
-                        .documents
-                        .update_document_tree(uri, tree, metadata)
-                        .await
-                {
-                    error!("Failed to update document tree: {}", e);
+                if let Some(tree) = tree {
+                    self.store_tree(uri, tree, metadata).await;
                 }
-                self.publish_document_diagnostics(uri).await;
+                self.diagnostics.schedule(uri.clone());
             }
-            crate::parsing::ParseResult::Partial { tree, errors } => {
+            crate::parsing::ParseResult::Partial {
+                tree,
+                errors,
+                error_ranges,
+                missing_nodes,
+            } => {
                 warn!("Document reparsed with {} errors", errors.len());
-                let metadata = ParseMetadata::new(0, dialect, true, errors.len());
-                if let Some(tree) = tree
-                    && let Err(e) = self
-                        .documents
-                        .update_document_tree(uri, tree, metadata)
-                        .await
-                {
-                    error!("Failed to update document tree: {}", e);
+                let metadata = ParseMetadata::new(0, dialect, true, errors.len())
+                    .with_syntax_errors(error_ranges, missing_nodes);
+                if let Some(tree) = tree {
+                    self.store_tree(uri, tree, metadata).await;
                 }
-                self.publish_document_diagnostics(uri).await;
+                self.diagnostics.schedule(uri.clone());
             }
             crate::parsing::ParseResult::Failed { error } => {
                 error!("Failed to reparse document: {}", error);
@@ -236,9 +911,7 @@ impl LspBackend {
                     error!("Failed to clear document tree: {}", e);
                 }
                 // Clear diagnostics on parse failure
-                self.client
-                    .publish_diagnostics(uri.clone(), Vec::new(), None)
-                    .await;
+                self.clear_diagnostics(uri).await;
             }
         }
     }
@@ -255,6 +928,53 @@ impl LanguageServer for LspBackend {
         info!("Initializing LSP server");
         info!("Client info: {:?}", params.client_info);
 
+        let completion_item = params
+            .capabilities
+            .text_document
+            .as_ref()
+            .and_then(|td| td.completion.as_ref())
+            .and_then(|c| c.completion_item.as_ref());
+        self.snippet_support.store(
+            completion_item
+                .and_then(|item| item.snippet_support)
+                .unwrap_or(false),
+            Ordering::Relaxed,
+        );
+        self.insert_replace_support.store(
+            completion_item
+                .and_then(|item| item.insert_replace_support)
+                .unwrap_or(false),
+            Ordering::Relaxed,
+        );
+
+        let workspace = params.capabilities.workspace.as_ref();
+        self.configuration_pull.store(
+            workspace.and_then(|w| w.configuration).unwrap_or(false),
+            Ordering::Relaxed,
+        );
+        self.configuration_registration.store(
+            workspace
+                .and_then(|w| w.did_change_configuration.as_ref())
+                .and_then(|c| c.dynamic_registration)
+                .unwrap_or(false),
+            Ordering::Relaxed,
+        );
+
+        // Serve every workspace folder; older clients only send a root URI
+        let roots = self.request_context.workspace_roots();
+        match (&params.workspace_folders, &params.root_uri) {
+            (Some(folders), _) => {
+                for folder in folders {
+                    roots.add(&folder.uri);
+                }
+            }
+            (None, Some(root_uri)) => {
+                roots.add(root_uri);
+            }
+            (None, None) => {}
+        }
+        info!("Workspace roots: {:?}", roots.uris());
+
         // Log client capabilities
         if let Some(capabilities) = params.capabilities.text_document {
             info!(
@@ -278,7 +998,12 @@ impl LanguageServer for LspBackend {
                 // Completion (will be implemented in LSP-003)
                 completion_provider: Some(CompletionOptions {
                     resolve_provider: Some(false),
-                    trigger_characters: Some(vec![".".to_string(), " ".to_string()]),
+                    trigger_characters: Some(vec![
+                        ".".to_string(),
+                        " ".to_string(),
+                        "'".to_string(),
+                        ":".to_string(),
+                    ]),
                     work_done_progress_options: WorkDoneProgressOptions {
                         work_done_progress: Some(false),
                     },
@@ -304,17 +1029,40 @@ impl LanguageServer for LspBackend {
                 // Definition (future feature)
                 definition_provider: Some(OneOf::Left(true)),
 
+                // Rename of tables, views and CTEs the document defines
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                })),
+
                 // Document formatting (will be implemented in FORMAT-001)
                 document_formatting_provider: Some(OneOf::Left(true)),
 
                 // Document symbols (future feature)
                 document_symbol_provider: Some(OneOf::Left(true)),
 
+                // Workspace symbols (catalog tables + workspace DDL and CTEs)
+                workspace_symbol_provider: Some(OneOf::Left(true)),
+
+                // Quick fixes for diagnostics that carry a suggested edit
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+                        ..Default::default()
+                    },
+                )),
+
+                // Commands
+                execute_command_provider: Some(ExecuteCommandOptions {
+                    commands: COMMANDS.map(String::from).to_vec(),
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                }),
+
                 // Other capabilities
                 workspace: Some(WorkspaceServerCapabilities {
                     workspace_folders: Some(WorkspaceFoldersServerCapabilities {
-                        supported: Some(false),
-                        change_notifications: Some(OneOf::Left(false)),
+                        supported: Some(true),
+                        change_notifications: Some(OneOf::Left(true)),
                     }),
                     ..Default::default()
                 }),
@@ -330,7 +1078,9 @@ impl LanguageServer for LspBackend {
 
     /// Initialized notification
     ///
-    /// Called after `initialize` completes successfully.
+    /// Called after `initialize` completes successfully. Clients that answer
+    /// `workspace/configuration` are asked for their settings here instead
+    /// of waiting for a `workspace/didChangeConfiguration` push.
     async fn initialized(&self, _params: InitializedParams) {
         info!("LSP server initialized successfully");
 
@@ -341,9 +1091,26 @@ impl LanguageServer for LspBackend {
         )
         .await;
 
-        // TODO: (CONFIG-001) Load configuration from client settings
-        // For now, we'll wait for the client to send configuration
-        // through workspace/didChangeConfiguration
+        // Revalidate open documents, drop caches and follow the open
+        // documents as the config, the catalog and the documents change
+        let receivers = self.event_receivers.lock().unwrap().take();
+        if let Some(receivers) = receivers {
+            self.diagnostics.listen(receivers.diagnostics);
+            self.status.listen(receivers.status);
+            self.request_context.listen(receivers.request_context);
+            self.follow_open_documents(receivers.workspace_index);
+        }
+
+        if self.configuration_registration.load(Ordering::Relaxed) {
+            self.register_configuration_changes().await;
+        }
+
+        // Documents opened while the settings were requested are revalidated
+        // on the resulting config change
+        self.pull_configuration().await;
+
+        self.start_prefetch().await;
+        self.reindex_workspace().await;
     }
 
     /// Shutdown the LSP server
@@ -352,8 +1119,12 @@ impl LanguageServer for LspBackend {
     async fn shutdown(&self) -> Result<()> {
         info!("Shutting down LSP server");
 
-        // Clean up resources
-        // (None to clean up yet, but this will be important for catalog connections)
+        if let Some(task) = self.prefetch_task.lock().unwrap().take() {
+            task.abort();
+        }
+        if let Err(e) = self.recent_tables.save() {
+            warn!("Failed to save recently used tables: {}", e);
+        }
 
         Ok(())
     }
@@ -385,6 +1156,7 @@ impl LanguageServer for LspBackend {
             .await
         {
             Ok(()) => {
+                self.diagnostics.track(uri.clone());
                 self.log_message(&format!("Document opened: {}", uri), MessageType::INFO)
                     .await;
 
@@ -392,6 +1164,11 @@ impl LanguageServer for LspBackend {
                 if let Some(document) = self.documents.get_document(&uri).await {
                     self.parse_and_update_tree(&uri, &document).await;
                 }
+                self.events.emit(ServerEvent::DocumentChanged {
+                    uri: uri.clone(),
+                    version,
+                });
+                self.report_status(&uri).await;
             }
             Err(e) => {
                 error!("Failed to open document: {}", e);
@@ -432,6 +1209,24 @@ impl LanguageServer for LspBackend {
             Ok(()) => {
                 // Trigger re-parsing using shared helper
                 if let Some(document) = self.documents.get_document(&uri).await {
+                    // Some clients resend the full text on focus changes. The
+                    // tree and diagnostics of the previous version still hold;
+                    // republishing them would be dropped as a duplicate anyway.
+                    let unchanged = old_document.as_ref().is_some_and(|old| {
+                        old.content_hash() == document.content_hash()
+                            && old.parse_metadata().is_some_and(|metadata| {
+                                metadata.dialect == self.doc_sync.resolve_dialect(&document)
+                            })
+                    });
+                    if unchanged {
+                        debug!("Content unchanged, skipping reparse: uri={}", uri);
+                        return;
+                    }
+
+                    // Keep published squiggles on their text until recomputed
+                    self.diagnostics_output
+                        .shift(&uri, &changes, document.version())
+                        .await;
                     self.parse_and_update_tree_incremental(
                         &uri,
                         &document,
@@ -439,6 +1234,11 @@ impl LanguageServer for LspBackend {
                         &changes,
                     )
                     .await;
+                    self.events.emit(ServerEvent::DocumentChanged {
+                        uri: uri.clone(),
+                        version: document.version(),
+                    });
+                    self.report_status(&uri).await;
                 }
             }
             Err(DocumentError::DocumentNotFound(uri)) => {
@@ -464,13 +1264,17 @@ impl LanguageServer for LspBackend {
         info!("Document closed: uri={}", uri);
 
         // Remove from document store
-        if self.documents.close_document(&uri).await {
-            // Clear parse data
-            self.doc_sync.on_document_close(&uri);
-
-            // Clear diagnostics
+        if let Some(document) = self.documents.close_document(&uri).await {
+            // Stop pending diagnostics before clearing, so none land afterwards
+            self.diagnostics.untrack(&uri);
+            self.diagnostics_output.forget(&uri);
+            self.events
+                .emit(ServerEvent::DocumentClosed { uri: uri.clone() });
+
+            // Clear parse data and the client's diagnostics, once per close
+            let cleared = self.doc_sync.on_document_close(&document);
             self.client
-                .publish_diagnostics(uri.clone(), Vec::new(), None)
+                .publish_diagnostics(cleared.uri, cleared.diagnostics, cleared.version)
                 .await;
 
             self.log_message(&format!("Document closed: {}", uri), MessageType::INFO)
@@ -485,6 +1289,7 @@ impl LanguageServer for LspBackend {
     /// Called when the user requests completion (e.g., Ctrl+Space).
     /// Implements COMPLETION-001: SELECT clause column completion.
     async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
+        let _active = self.request_activity.begin();
         let uri = params.text_document_position.text_document.uri;
         let position = params.text_document_position.position;
 
@@ -502,21 +1307,23 @@ impl LanguageServer for LspBackend {
         let document = match self.documents.get_document(&uri).await {
             Some(doc) => doc,
             None => {
-                warn!("Document not found for completion: {}", uri);
+                debug!("Completion for closed document: {}", uri);
                 return Ok(None);
             }
         };
 
-        let (config, catalog) = match self.request_context.config_and_catalog().await {
+        // The dialect belongs to the document, never to the server
+        let dialect = self.doc_sync.resolve_dialect(&document);
+        let (config, catalog) = match self
+            .request_context
+            .config_and_catalog_for_document(&uri, dialect)
+            .await
+        {
             Ok(result) => result,
             Err(e) => {
                 debug!("!!! LSP: Failed to get catalog: {}", e);
                 error!("Failed to get catalog: {}", e);
-                self.log_message(
-                    &format!("Failed to connect to database: {}", e),
-                    MessageType::ERROR,
-                )
-                .await;
+                self.report_catalog_error(&e).await;
                 return Ok(None);
             }
         };
@@ -524,19 +1331,67 @@ impl LanguageServer for LspBackend {
 
         // Create completion engine and perform completion
         debug!("!!! LSP: Creating completion engine");
-        let engine = CompletionEngine::new(catalog);
+        let completion_config = CompletionConfig {
+            snippet_support: self.snippet_support.load(Ordering::Relaxed),
+            insert_replace_support: self.insert_replace_support.load(Ordering::Relaxed),
+            read_only: config.mode == WorkspaceMode::ReadOnly,
+            ..config.completion.clone()
+        };
+        let engine = CompletionEngine::new(catalog)
+            .with_dialect(config.dialect)
+            .with_config(completion_config)
+            .with_custom_rules(self.request_context.custom_rules().await)
+            .with_capabilities(config.version.capabilities())
+            .with_newer_versions(config.version.newer())
+            .with_schema_filter(config.schema_filter.clone())
+            .with_stats(self.completion_stats.clone())
+            .with_late_results(&self.request_context.late_catalog_results().await)
+            .with_recent_tables(&self.recent_tables)
+            .with_max_nesting_depth(config.max_nesting_depth);
         debug!("!!! LSP: Calling complete with position {:?}", position);
-        match engine.complete(&document, position).await {
-            Ok(Some(items)) => {
-                debug!("!!! LSP: Completion returned {} items", items.len());
-                for (i, item) in items.iter().take(5).enumerate() {
+
+        // Inside embedded SQL, e.g. a SQL function body, complete on its own parse
+        let result = match document.embedded_region_at(position) {
+            Some(region) => {
+                debug!(
+                    "!!! LSP: Completing inside embedded region of {}",
+                    region.owner
+                );
+                let embedded = self.doc_sync.parse_embedded(&document, &region);
+                let inner = region.to_inner(position).unwrap_or_default();
+                engine
+                    .with_parameters(region.parameters.clone())
+                    .complete_list(&embedded, inner)
+                    .await
+                    .map(|list| {
+                        list.map(|mut list| {
+                            list.items = list
+                                .items
+                                .into_iter()
+                                .map(|item| region.to_outer_item(item))
+                                .collect();
+                            list
+                        })
+                    })
+            }
+            None => engine.complete_list(&document, position).await,
+        };
+        match result {
+            Ok(Some(list)) => {
+                debug!("!!! LSP: Completion returned {} items", list.items.len());
+                for (i, item) in list.items.iter().take(5).enumerate() {
                     debug!(
                         "!!! LSP:   Item {}: label={}, kind={:?}",
                         i, item.label, item.kind
                     );
                 }
-                info!("Completion returned {} items", items.len());
-                Ok(Some(CompletionResponse::Array(items)))
+                info!("Completion returned {} items", list.items.len());
+                if list.is_incomplete {
+                    // Partial items within the latency budget; the client asks again
+                    Ok(Some(CompletionResponse::List(list)))
+                } else {
+                    Ok(Some(CompletionResponse::Array(list.items)))
+                }
             }
             Ok(None) => {
                 // No completion available (wrong context)
@@ -562,6 +1417,7 @@ impl LanguageServer for LspBackend {
     /// Called when the user hovers over a symbol.
     /// Uses HoverEngine for CST-based hover information.
     async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let _active = self.request_activity.begin();
         let uri = params.text_document_position_params.text_document.uri;
         let position = params.text_document_position_params.position;
 
@@ -579,23 +1435,39 @@ impl LanguageServer for LspBackend {
         let document = match self.documents.get_document(&uri).await {
             Some(doc) => doc,
             None => {
-                warn!("Document not found for hover: {}", uri);
+                debug!("Hover for closed document: {}", uri);
                 return Ok(None);
             }
         };
 
-        let (config, catalog) = match self.request_context.config_and_catalog().await {
+        let dialect = self.doc_sync.resolve_dialect(&document);
+        let (config, catalog) = match self
+            .request_context
+            .config_and_catalog_for_document(&uri, dialect)
+            .await
+        {
             Ok(result) => result,
             Err(e) => {
                 debug!("!!! LSP: Failed to get catalog for hover: {}", e);
                 error!("Failed to get catalog for hover: {}", e);
+                self.report_catalog_error(&e).await;
                 return Ok(None);
             }
         };
 
         // Use HoverEngine for CST-based hover
         use crate::hover::HoverEngine;
-        let engine = HoverEngine::new(catalog, config.dialect);
+        let mut engine = HoverEngine::new(catalog, config.dialect)
+            .with_schema_filter(config.schema_filter.clone());
+
+        // Sample rows are opt-in and only ever read from a live connection
+        if config.hover.sample_rows > 0 && config.schema_source == SchemaSource::Connection {
+            engine = engine.with_sample_rows(
+                config.hover.sample_rows,
+                self.request_context.sample_rows_cache(),
+                config.connection_string.clone(),
+            );
+        }
 
         if let Some(text) = engine.get_hover(&document, position).await {
             debug!("!!! LSP: Returning hover info: {}", text);
@@ -642,7 +1514,7 @@ impl LanguageServer for LspBackend {
         let document = match self.documents.get_document(&uri).await {
             Some(doc) => doc,
             None => {
-                warn!("Document not found: {}", uri);
+                debug!("Request for closed document: {}", uri);
                 return Ok(None);
             }
         };
@@ -700,6 +1572,42 @@ impl LanguageServer for LspBackend {
         }
     }
 
+    /// Prepare rename request
+    ///
+    /// Returns the range of the name at the cursor when it is a table, view
+    /// or CTE the document defines, so clients refuse other renames early.
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        let uri = params.text_document.uri;
+        let Some(document) = self.documents.get_document(&uri).await else {
+            return Ok(None);
+        };
+        let content = document.get_content();
+        Ok(rename::prepare_rename(&uri, &content, params.position)
+            .map(PrepareRenameResponse::Range))
+    }
+
+    /// Rename request
+    ///
+    /// Renames a table, view or CTE defined by the document in every
+    /// statement that sees it.
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let uri = params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+        info!("Rename requested: uri={}, pos={:?}", uri, position);
+
+        let Some(document) = self.documents.get_document(&uri).await else {
+            return Ok(None);
+        };
+        let content = document.get_content();
+        match rename::rename(&uri, &content, position, &params.new_name) {
+            Ok(edit) => Ok(Some(edit)),
+            Err(e) => Err(tower_lsp::jsonrpc::Error::invalid_params(e.to_string())),
+        }
+    }
+
     /// Document formatting request
     ///
     /// Called when the user formats a document.
@@ -734,7 +1642,7 @@ impl LanguageServer for LspBackend {
         let document = match self.documents.get_document(&uri).await {
             Some(doc) => doc,
             None => {
-                warn!("Document not found: {}", uri);
+                debug!("Request for closed document: {}", uri);
                 return Ok(None);
             }
         };
@@ -749,7 +1657,11 @@ impl LanguageServer for LspBackend {
         };
 
         // 3. Get catalog (optional for graceful degradation)
-        let catalog = match self.get_config().await {
+        let config = match self.request_context.workspace_roots().config_for(&uri) {
+            Some(config) => Some(config),
+            None => self.get_config().await,
+        };
+        let catalog = match config {
             Some(config) => match self.request_context.catalog_for_config(&config).await {
                 Ok(cat) => Some(cat),
                 Err(e) => {
@@ -807,6 +1719,39 @@ impl LanguageServer for LspBackend {
         Ok(Some(DocumentSymbolResponse::Nested(document_symbols)))
     }
 
+    /// Workspace symbols request
+    ///
+    /// Searches catalog tables and views plus tables, views and CTEs defined in
+    /// open documents and the indexed workspace and schema files. When the
+    /// catalog is unavailable the workspace definitions are still returned.
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        info!("Workspace symbols requested: query={:?}", params.query);
+
+        let config = self.request_context.config_or_fallback().await;
+        let mut provider = WorkspaceSymbolProvider::new(config.workspace_symbol_limit)
+            .with_index(self.workspace_index.definitions());
+        match self.request_context.catalog_for_config(&config).await {
+            Ok(catalog) => provider = provider.with_catalog(catalog),
+            Err(e) => warn!("Catalog unavailable for workspace symbols: {}", e),
+        }
+
+        let mut documents = Vec::new();
+        for uri in self.documents.list_uris().await {
+            if let Some(document) = self.documents.get_document(&uri).await {
+                documents.push(document);
+            }
+        }
+
+        let symbols = provider.search(&params.query, &documents).await;
+        info!("Workspace symbols returned: {} symbols", symbols.len());
+        Ok(Some(WorkspaceSymbolProvider::to_symbol_information(
+            symbols,
+        )))
+    }
+
     /// Configuration change notification
     ///
     /// Called when the client's configuration changes.
@@ -818,18 +1763,168 @@ impl LanguageServer for LspBackend {
         );
         debug!("!!! LSP: Settings value: {:?}", params.settings);
 
-        // Parse configuration from client settings
-        match EngineConfig::from_lsp_settings(&params.settings) {
-            Some(config) => {
-                debug!(
-                    "!!! LSP: Successfully parsed config: dialect={:?}",
-                    config.dialect
-                );
-                self.set_config(config).await;
-                debug!("!!! LSP: Engine configuration updated from client settings");
+        // Clients using the pull model send no settings with the notification
+        if params.settings.get(SETTINGS_SECTION).is_none() && self.pull_configuration().await {
+            self.reindex_workspace().await;
+            return;
+        }
+        if self.apply_settings(&params.settings).await {
+            self.reindex_workspace().await;
+        }
+    }
+
+    /// Workspace folders change notification
+    ///
+    /// Adds and removes roots, then reparses open documents whose dialect
+    /// changed with them and revalidates the rest.
+    async fn did_change_workspace_folders(&self, params: DidChangeWorkspaceFoldersParams) {
+        let roots = self.request_context.workspace_roots();
+        for folder in &params.event.removed {
+            roots.remove(&folder.uri);
+            if let Ok(path) = folder.uri.to_file_path() {
+                self.workspace_index.remove_root(&path);
             }
-            None => {
-                debug!("!!! LSP: Failed to parse configuration from client settings");
+        }
+        for folder in &params.event.added {
+            roots.add(&folder.uri);
+        }
+        info!("Workspace roots: {:?}", roots.uris());
+
+        let added: Vec<WorkspaceRoot> = roots
+            .all()
+            .into_iter()
+            .filter(|root| params.event.added.iter().any(|f| f.uri == root.uri))
+            .collect();
+        self.index_workspace_roots(added).await;
+
+        for uri in self.documents.list_uris().await {
+            let Some(document) = self.documents.get_document(&uri).await else {
+                continue;
+            };
+            // Documents never parsed have no tree to go stale
+            let Some(metadata) = document.parse_metadata() else {
+                continue;
+            };
+            if metadata.dialect != self.doc_sync.resolve_dialect(&document) {
+                self.parse_and_update_tree(&uri, &document).await;
+            }
+        }
+        self.events.emit(ServerEvent::ConfigChanged);
+    }
+
+    /// Watched files notification
+    ///
+    /// Updates the workspace index and reloads DDL-file catalogs when their
+    /// schema file changes on disk.
+    async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        let roots = self.request_context.workspace_roots();
+        for change in params.changes {
+            let Ok(path) = change.uri.to_file_path() else {
+                continue;
+            };
+
+            if let Some(root) = roots.root_for(&change.uri) {
+                let config = self.request_context.config_for(&change.uri).await;
+                self.workspace_index
+                    .update_file(&root.path, &path, &config.workspace_index);
+            }
+
+            match self.request_context.reload_schema_file(&path).await {
+                Ok(true) => {
+                    info!("Reloaded schema file: {}", path.display());
+                    self.index_schema_files().await;
+                    self.log_message(
+                        &format!("Reloaded schema file: {}", path.display()),
+                        MessageType::INFO,
+                    )
+                    .await;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    warn!("Failed to reload schema file {}: {}", path.display(), e);
+                    self.log_message(
+                        &format!("Failed to reload schema file: {}", e),
+                        MessageType::WARNING,
+                    )
+                    .await;
+                }
+            }
+        }
+    }
+
+    /// Code action request
+    ///
+    /// Offers quick fixes for diagnostics in the requested range.
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let uri = &params.text_document.uri;
+
+        // Don't rewrite text inside regions the parser couldn't make sense of
+        let document = self.documents.get_document(uri).await;
+        let diagnostics: Vec<Diagnostic> = params
+            .context
+            .diagnostics
+            .into_iter()
+            .filter(|d| {
+                !document
+                    .as_ref()
+                    .is_some_and(|doc| doc.has_errors_in_range(d.range))
+            })
+            .collect();
+
+        let actions = quick_fixes(uri, &diagnostics);
+        Ok((!actions.is_empty()).then_some(actions))
+    }
+
+    /// Execute command request
+    ///
+    /// Supports refreshing the catalog, which also revalidates open documents,
+    /// reporting diagnostics publishing and completion counters, and diffing
+    /// the schema files against the database.
+    async fn execute_command(
+        &self,
+        params: ExecuteCommandParams,
+    ) -> Result<Option<serde_json::Value>> {
+        match params.command.as_str() {
+            REFRESH_CATALOG_COMMAND => {
+                info!("Refreshing catalog");
+                self.request_context.refresh_catalogs().await;
+                Ok(None)
+            }
+            DIAGNOSTICS_STATS_COMMAND => {
+                Ok(serde_json::to_value(self.diagnostics_output.stats()).ok())
+            }
+            COMPLETION_STATS_COMMAND => {
+                let stats = serde_json::to_value(self.completion_stats.snapshot()).ok();
+                let reset = params
+                    .arguments
+                    .first()
+                    .and_then(|options| options.get("reset"))
+                    .and_then(serde_json::Value::as_bool)
+                    .unwrap_or(false);
+                if reset {
+                    self.completion_stats.reset();
+                }
+                Ok(stats)
+            }
+            SCHEMA_DIFF_COMMAND => {
+                let uri = params
+                    .arguments
+                    .first()
+                    .and_then(|options| options.get("uri"))
+                    .and_then(serde_json::Value::as_str)
+                    .and_then(|uri| Url::parse(uri).ok());
+                let config = match uri {
+                    Some(uri) => self.config_for(&uri).await,
+                    None => self.request_context.config_or_fallback().await,
+                };
+                self.schema_diff(&config).await.map(Some)
+            }
+            command => {
+                warn!("Unknown command: {}", command);
+                Err(tower_lsp::jsonrpc::Error::invalid_params(format!(
+                    "Unknown command: {}",
+                    command
+                )))
             }
         }
     }
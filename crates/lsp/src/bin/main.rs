@@ -20,6 +20,28 @@ async fn main() {
         .position(|arg| arg == "--catalog")
         .and_then(|idx| args.get(idx + 1));
 
+    // Check for --max-connections and --idle-timeout (seconds) flags, TCP mode only
+    let max_connections = args
+        .iter()
+        .position(|arg| arg == "--max-connections")
+        .and_then(|idx| args.get(idx + 1))
+        .and_then(|max_str| max_str.parse::<usize>().ok());
+    let idle_timeout = args
+        .iter()
+        .position(|arg| arg == "--idle-timeout")
+        .and_then(|idx| args.get(idx + 1))
+        .and_then(|secs_str| secs_str.parse::<u64>().ok())
+        .map(std::time::Duration::from_secs);
+
+    // --check reports the compiled grammars and exits
+    if args.iter().any(|arg| arg == "--check") {
+        tracing_subscriber::fmt()
+            .with_env_filter("unified_sql_grammar=error")
+            .with_writer(std::io::stderr)
+            .init();
+        std::process::exit(check_grammars());
+    }
+
     if let Some(port) = tcp_port {
         // Run in TCP mode
         eprintln!("!!! LSP SERVER: Running in TCP mode on port {}", port);
@@ -37,9 +59,15 @@ async fn main() {
 
         // Load static catalog
         let catalog = std::sync::Arc::new(unified_sql_lsp_catalog::StaticCatalog::new());
-        let server = unified_sql_lsp_lsp::tcp::TcpServer::new(port, catalog)
+        let mut server = unified_sql_lsp_lsp::tcp::TcpServer::new(port, catalog)
             .await
             .expect("Failed to start TCP server");
+        if let Some(max_connections) = max_connections {
+            server = server.with_max_connections(max_connections);
+        }
+        if let Some(idle_timeout) = idle_timeout {
+            server = server.with_idle_timeout(idle_timeout);
+        }
 
         server.serve().await.expect("TCP server error");
     } else {
@@ -51,21 +79,65 @@ async fn main() {
             eprintln!("!!! LSP SERVER: Using catalog: {}", catalog);
         }
 
-        // DO NOT initialize logging - it interferes with JSON-RPC protocol on stdout
-        // The LSP protocol requires stdout to be used exclusively for JSON-RPC messages
-        // Logs should only go to stderr, but tracing_subscriber doesn't respect this in all cases
-        // So we completely disable logging when running as LSP server
+        // The LSP protocol requires stdout to be used exclusively for JSON-RPC messages,
+        // so logging stays off unless RUST_LOG asks for it, and then only goes to stderr
+        // (the e2e runner captures it per test case)
+        if env::var_os("RUST_LOG").is_some() {
+            tracing_subscriber::fmt()
+                .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+                .with_writer(std::io::stderr)
+                .with_ansi(false)
+                .init();
+        }
 
-        use tower_lsp::{LspService, Server};
+        use tower_lsp::Server;
 
         // Create stdin/stdout streams
         let stdin = tokio::io::stdin();
         let stdout = tokio::io::stdout();
 
         // Create the LSP service
-        let (service, socket) = LspService::new(unified_sql_lsp_lsp::backend::LspBackend::new);
+        let (service, socket) = unified_sql_lsp_lsp::backend::LspBackend::service();
 
         // Run the server using Server::new
         Server::new(stdin, stdout, socket).serve(service).await;
     }
 }
+
+/// Print every compiled grammar with its tree-sitter ABI version
+///
+/// Returns the exit code: 1 when a grammar is incompatible with the linked
+/// tree-sitter runtime or a dialect family has no grammar, 0 otherwise.
+fn check_grammars() -> i32 {
+    use unified_sql_grammar::{SUPPORTED_ABI_VERSIONS, grammar_info, language_for_dialect};
+    use unified_sql_lsp_ir::Dialect;
+
+    println!(
+        "tree-sitter runtime supports ABI versions {}..={}",
+        SUPPORTED_ABI_VERSIONS.start(),
+        SUPPORTED_ABI_VERSIONS.end()
+    );
+    let grammars = grammar_info();
+    for grammar in &grammars {
+        println!(
+            "{:<14} ABI {:>2} {:>5} node kinds  {}",
+            grammar.name,
+            grammar.abi_version,
+            grammar.node_kind_count,
+            if grammar.compatible {
+                "ok"
+            } else {
+                "INCOMPATIBLE"
+            }
+        );
+    }
+
+    let families_loaded = [Dialect::MySQL, Dialect::PostgreSQL]
+        .into_iter()
+        .all(|dialect| language_for_dialect(dialect).is_some());
+    if families_loaded && grammars.iter().all(|grammar| grammar.compatible) {
+        0
+    } else {
+        1
+    }
+}
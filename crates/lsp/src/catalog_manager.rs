@@ -9,16 +9,31 @@
 //!
 //! The catalog manager is responsible for:
 //! - Creating catalog instances based on engine configuration
+//! - Handing out a [`NullCatalog`] when no schema source is configured, so
+//!   completion falls back to keywords instead of failing on every request
 //! - Reusing catalog connections across multiple completion requests
 //! - Managing catalog lifecycle
+//! - Emitting [`ServerEvent::CatalogInvalidated`] when cached metadata is
+//!   reloaded or dropped
+//! - Keeping catalog results that arrived after a completion gave up waiting
+//!   ([`LateCatalogResults`])
 
+use async_trait::async_trait;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::future::Future;
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 use unified_sql_lsp_catalog::{
-    Catalog, CatalogError, CatalogResult, LiveMySQLCatalog, LivePostgreSQLCatalog,
+    Catalog, CatalogError, CatalogResult, ColumnMetadata, DdlCatalog, FunctionMetadata,
+    LiveMySQLCatalog, LivePostgreSQLCatalog, NullCatalog, SampleRows, SequenceMetadata,
+    TableMetadata,
 };
 
-use crate::config::EngineConfig;
+use crate::config::{EngineConfig, SchemaSource};
+use crate::events::{CatalogScope, EventBus, ServerEvent};
 
 /// Catalog manager
 ///
@@ -29,6 +44,15 @@ pub struct CatalogManager {
 
     /// PostgreSQL catalog instances (keyed by connection string)
     postgres_catalogs: HashMap<String, Arc<LivePostgreSQLCatalog>>,
+
+    /// DDL file catalog instances (keyed by configured schema paths)
+    ddl_catalogs: HashMap<Vec<PathBuf>, Arc<DdlCatalog>>,
+
+    /// Bus receiving catalog invalidation events
+    events: EventBus,
+
+    /// Results of catalog calls that outlived a completion latency budget
+    late_results: Arc<LateCatalogResults>,
 }
 
 impl CatalogManager {
@@ -37,9 +61,33 @@ impl CatalogManager {
         Self {
             mysql_catalogs: HashMap::new(),
             postgres_catalogs: HashMap::new(),
+            ddl_catalogs: HashMap::new(),
+            events: EventBus::new(),
+            late_results: Arc::new(LateCatalogResults::default()),
         }
     }
 
+    /// Catalog results kept for completions retriggered after a timeout
+    pub fn late_results(&self) -> Arc<LateCatalogResults> {
+        self.late_results.clone()
+    }
+
+    /// Emit invalidation events on `events` instead of a private bus
+    pub fn with_event_bus(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Subscribe to the events of the manager's bus
+    pub fn subscribe(&self) -> broadcast::Receiver<ServerEvent> {
+        self.events.subscribe()
+    }
+
+    /// Notify subscribers that the catalogs in `scope` changed
+    fn notify(&self, scope: CatalogScope) {
+        self.events.emit(ServerEvent::CatalogInvalidated { scope });
+    }
+
     /// Get or create a catalog for the given configuration
     ///
     /// # Arguments
@@ -48,7 +96,8 @@ impl CatalogManager {
     ///
     /// # Returns
     ///
-    /// An Arc to the catalog instance
+    /// An Arc to the catalog instance; a [`NullCatalog`] when no connection
+    /// string is configured
     ///
     /// # Examples
     ///
@@ -57,6 +106,17 @@ impl CatalogManager {
     /// let columns = catalog.get_columns("users").await?;
     /// ```
     pub async fn get_catalog(&mut self, config: &EngineConfig) -> CatalogResult<Arc<dyn Catalog>> {
+        if let SchemaSource::Ddl { paths } = &config.schema_source {
+            return self
+                .get_ddl_catalog(paths, config)
+                .map(|c| c as Arc<dyn Catalog>);
+        }
+
+        // Nothing to connect to: keyword-only completion, whatever the dialect
+        if config.connection_string.is_empty() {
+            return Ok(Arc::new(NullCatalog));
+        }
+
         match config.dialect {
             unified_sql_lsp_ir::Dialect::MySQL => self
                 .get_mysql_catalog(config)
@@ -74,6 +134,25 @@ impl CatalogManager {
         }
     }
 
+    /// Whether the catalog of `config` was created and is still cached
+    ///
+    /// True once a live catalog connected or DDL schema files loaded, until
+    /// the next [`refresh`](Self::refresh).
+    pub fn has_catalog(&self, config: &EngineConfig) -> bool {
+        match &config.schema_source {
+            SchemaSource::Ddl { paths } => self.ddl_catalogs.contains_key(paths),
+            SchemaSource::Connection => match config.dialect {
+                unified_sql_lsp_ir::Dialect::MySQL => {
+                    self.mysql_catalogs.contains_key(&config.connection_string)
+                }
+                unified_sql_lsp_ir::Dialect::PostgreSQL => self
+                    .postgres_catalogs
+                    .contains_key(&config.connection_string),
+                _ => false,
+            },
+        }
+    }
+
     /// Get or create a MySQL catalog
     async fn get_mysql_catalog(
         &mut self,
@@ -86,8 +165,8 @@ impl CatalogManager {
 
         // Create new catalog
         let catalog = LiveMySQLCatalog::new(&config.connection_string)
-            .await
-            .map_err(|e| CatalogError::ConnectionFailed(e.to_string()))?;
+            .await?
+            .with_paging(config.table_paging.clone());
 
         let catalog = Arc::new(catalog);
         self.mysql_catalogs
@@ -108,8 +187,8 @@ impl CatalogManager {
 
         // Create new catalog
         let catalog = LivePostgreSQLCatalog::new(&config.connection_string)
-            .await
-            .map_err(|e| CatalogError::ConnectionFailed(e.to_string()))?;
+            .await?
+            .with_paging(config.table_paging.clone());
 
         let catalog = Arc::new(catalog);
         self.postgres_catalogs
@@ -118,12 +197,222 @@ impl CatalogManager {
         Ok(catalog)
     }
 
+    /// Get or load a catalog from DDL schema files
+    fn get_ddl_catalog(
+        &mut self,
+        paths: &[PathBuf],
+        config: &EngineConfig,
+    ) -> CatalogResult<Arc<DdlCatalog>> {
+        if let Some(catalog) = self.ddl_catalogs.get(paths) {
+            return Ok(catalog.clone());
+        }
+
+        let catalog = Arc::new(DdlCatalog::from_sql_files(paths, config.dialect)?);
+        self.ddl_catalogs.insert(paths.to_vec(), catalog.clone());
+
+        Ok(catalog)
+    }
+
+    /// Reload DDL catalogs after one of their schema files changed on disk
+    ///
+    /// A new file matching a configured pattern counts as a change too.
+    /// Returns `Ok(false)` if no catalog watches `path`.
+    /// On a read failure the previous catalog is kept so completion keeps working.
+    pub fn reload_ddl_catalog(&mut self, path: &Path) -> CatalogResult<bool> {
+        let affected: Vec<(Vec<PathBuf>, Arc<DdlCatalog>)> = self
+            .ddl_catalogs
+            .iter()
+            .filter(|(_, catalog)| catalog.watches(path))
+            .map(|(key, catalog)| (key.clone(), catalog.clone()))
+            .collect();
+        if affected.is_empty() {
+            return Ok(false);
+        }
+
+        for (key, existing) in affected {
+            let reloaded = existing.reload()?;
+            self.ddl_catalogs.insert(key, Arc::new(reloaded));
+        }
+        self.late_results.clear();
+        self.notify(CatalogScope::SchemaFile(path.to_path_buf()));
+        Ok(true)
+    }
+
+    /// Drop all cached catalogs so they are recreated on next use
+    ///
+    /// Subscribers receive [`CatalogScope::All`] invalidations so they can
+    /// recompute anything derived from catalog metadata.
+    pub fn refresh(&mut self) {
+        self.mysql_catalogs.clear();
+        self.postgres_catalogs.clear();
+        self.ddl_catalogs.clear();
+        self.late_results.clear();
+        self.notify(CatalogScope::All);
+    }
+
     /// Close all catalog connections
     ///
     /// This should be called when shutting down the server.
     pub async fn close_all(&mut self) {
         self.mysql_catalogs.clear();
         self.postgres_catalogs.clear();
+        self.ddl_catalogs.clear();
+        self.late_results.clear();
+    }
+}
+
+/// Column list shared by every caller that reads it
+type SharedColumns = Arc<[ColumnMetadata]>;
+
+/// Catalog results that arrived after a completion's latency budget
+///
+/// A completion that stops waiting on a slow catalog leaves the call running.
+/// Its result lands here, so the request the client sends on the next
+/// keystroke is served from memory instead of waiting again. The startup
+/// prefetch ([`crate::prefetch`]) keeps its results here too. Entries live
+/// until the catalogs are refreshed or reloaded.
+#[derive(Debug, Default)]
+pub struct LateCatalogResults {
+    tables: Mutex<HashMap<usize, Vec<TableMetadata>>>,
+    functions: Mutex<HashMap<usize, Vec<FunctionMetadata>>>,
+    columns: Mutex<HashMap<(usize, String), SharedColumns>>,
+}
+
+/// Identity of a catalog, so results never cross catalogs
+fn catalog_key(catalog: &Arc<dyn Catalog>) -> usize {
+    Arc::as_ptr(catalog) as *const () as usize
+}
+
+impl LateCatalogResults {
+    /// Wrap `catalog` so calls slower than `budget` keep their results here
+    ///
+    /// Reads through the wrapper are answered from kept results first.
+    /// Without a budget nothing new is kept, but kept results, such as
+    /// prefetched ones, are still read.
+    pub fn wrap(
+        self: &Arc<Self>,
+        catalog: Arc<dyn Catalog>,
+        budget: Option<Duration>,
+    ) -> Arc<dyn Catalog> {
+        Arc::new(LateCachingCatalog {
+            key: catalog_key(&catalog),
+            inner: catalog,
+            budget,
+            results: self.clone(),
+        })
+    }
+
+    /// Keep the table list of `catalog`
+    pub fn keep_tables(&self, catalog: &Arc<dyn Catalog>, tables: Vec<TableMetadata>) {
+        self.tables
+            .lock()
+            .unwrap()
+            .insert(catalog_key(catalog), tables);
+    }
+
+    /// Keep the columns of `table` in `catalog`
+    pub fn keep_columns(&self, catalog: &Arc<dyn Catalog>, table: &str, columns: SharedColumns) {
+        self.columns
+            .lock()
+            .unwrap()
+            .insert((catalog_key(catalog), table.to_string()), columns);
+    }
+
+    /// Drop all kept results
+    pub fn clear(&self) {
+        self.tables.lock().unwrap().clear();
+        self.functions.lock().unwrap().clear();
+        self.columns.lock().unwrap().clear();
+    }
+}
+
+/// Catalog wrapper backing [`LateCatalogResults::wrap`]
+struct LateCachingCatalog {
+    inner: Arc<dyn Catalog>,
+    /// Identity of `inner`, see [`catalog_key`]
+    key: usize,
+    budget: Option<Duration>,
+    results: Arc<LateCatalogResults>,
+}
+
+impl LateCachingCatalog {
+    async fn cached<K, T>(
+        &self,
+        entries: &Mutex<HashMap<K, T>>,
+        key: K,
+        fetch: impl Future<Output = CatalogResult<T>>,
+    ) -> CatalogResult<T>
+    where
+        K: Eq + Hash,
+        T: Clone,
+    {
+        if let Some(value) = entries.lock().unwrap().get(&key) {
+            return Ok(value.clone());
+        }
+
+        let started = Instant::now();
+        let value = fetch.await?;
+        if self.budget.is_some_and(|budget| started.elapsed() > budget) {
+            entries.lock().unwrap().insert(key, value.clone());
+        }
+        Ok(value)
+    }
+}
+
+#[async_trait]
+impl Catalog for LateCachingCatalog {
+    async fn list_tables(&self) -> CatalogResult<Vec<TableMetadata>> {
+        self.cached(&self.results.tables, self.key, self.inner.list_tables())
+            .await
+    }
+
+    async fn get_columns(&self, table: &str) -> CatalogResult<Vec<ColumnMetadata>> {
+        self.get_columns_shared(table)
+            .await
+            .map(|columns| columns.to_vec())
+    }
+
+    async fn get_columns_shared(&self, table: &str) -> CatalogResult<Arc<[ColumnMetadata]>> {
+        let key = (self.key, table.to_string());
+        self.cached(
+            &self.results.columns,
+            key,
+            self.inner.get_columns_shared(table),
+        )
+        .await
+    }
+
+    async fn list_functions(&self) -> CatalogResult<Vec<FunctionMetadata>> {
+        self.cached(
+            &self.results.functions,
+            self.key,
+            self.inner.list_functions(),
+        )
+        .await
+    }
+
+    async fn list_roles(&self) -> CatalogResult<Vec<String>> {
+        self.inner.list_roles().await
+    }
+
+    async fn list_collations(&self) -> CatalogResult<Vec<String>> {
+        self.inner.list_collations().await
+    }
+
+    async fn list_sequences(&self) -> CatalogResult<Vec<SequenceMetadata>> {
+        self.inner.list_sequences().await
+    }
+
+    fn loaded_tables(&self) -> Option<Vec<TableMetadata>> {
+        self.inner.loaded_tables()
+    }
+
+    fn has_schema(&self) -> bool {
+        self.inner.has_schema()
+    }
+
+    async fn sample_rows(&self, table: &str, limit: usize) -> CatalogResult<SampleRows> {
+        self.inner.sample_rows(table, limit).await
     }
 }
 
@@ -136,6 +425,7 @@ impl Default for CatalogManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use unified_sql_lsp_catalog::DataType;
     use unified_sql_lsp_ir::Dialect;
 
     #[tokio::test]
@@ -159,6 +449,7 @@ mod tests {
         // TODO: (DIALECT-TIDB-001) Remove this test once TiDB catalog support is implemented
         let config = EngineConfig {
             dialect: Dialect::TiDB, // Not supported yet
+            connection_string: "mysql://root@localhost:4000/test".to_string(),
             ..Default::default()
         };
 
@@ -166,6 +457,29 @@ mod tests {
         assert!(matches!(result, Err(CatalogError::NotSupported(_))));
     }
 
+    #[tokio::test]
+    async fn test_catalog_manager_without_connection_string() {
+        let mut manager = CatalogManager::new();
+        for dialect in [
+            Dialect::MySQL,
+            Dialect::TiDB,
+            Dialect::MariaDB,
+            Dialect::PostgreSQL,
+            Dialect::CockroachDB,
+        ] {
+            let config = EngineConfig {
+                dialect,
+                ..Default::default()
+            };
+
+            let catalog = manager.get_catalog(&config).await.unwrap();
+            assert!(!catalog.has_schema(), "{:?}", dialect);
+            assert!(catalog.list_tables().await.unwrap().is_empty());
+        }
+        assert!(manager.mysql_catalogs.is_empty());
+        assert!(manager.postgres_catalogs.is_empty());
+    }
+
     #[tokio::test]
     async fn test_catalog_manager_close_all() {
         let mut manager = CatalogManager::new();
@@ -174,6 +488,167 @@ mod tests {
         assert!(manager.postgres_catalogs.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_catalog_manager_ddl_source_and_reload() {
+        let path = std::env::temp_dir().join(format!(
+            "unified-sql-lsp-catalog-manager-{}.sql",
+            std::process::id()
+        ));
+        std::fs::write(&path, "CREATE TABLE users (id INT PRIMARY KEY);").unwrap();
+
+        let mut manager = CatalogManager::new();
+        let config = EngineConfig {
+            schema_source: SchemaSource::Ddl {
+                paths: vec![path.clone()],
+            },
+            ..Default::default()
+        };
+
+        let catalog = manager.get_catalog(&config).await.unwrap();
+        assert_eq!(catalog.get_columns("users").await.unwrap().len(), 1);
+
+        let mut events = manager.subscribe();
+        std::fs::write(&path, "CREATE TABLE users (id INT PRIMARY KEY, name TEXT);").unwrap();
+        assert!(manager.reload_ddl_catalog(&path).unwrap());
+        assert_eq!(
+            events.try_recv().unwrap(),
+            ServerEvent::CatalogInvalidated {
+                scope: CatalogScope::SchemaFile(path.clone())
+            }
+        );
+        let catalog = manager.get_catalog(&config).await.unwrap();
+        assert_eq!(catalog.get_columns("users").await.unwrap().len(), 2);
+
+        assert!(!manager.reload_ddl_catalog(Path::new("/other.sql")).unwrap());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_catalog_manager_ddl_pattern_picks_up_new_file() {
+        let dir = std::env::temp_dir().join(format!(
+            "unified-sql-lsp-catalog-manager-{}-schema",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("001_users.sql"), "CREATE TABLE users (id INT);").unwrap();
+
+        let mut manager = CatalogManager::new();
+        let config = EngineConfig {
+            schema_source: SchemaSource::Ddl {
+                paths: vec![dir.join("*.sql")],
+            },
+            ..Default::default()
+        };
+        let catalog = manager.get_catalog(&config).await.unwrap();
+        assert!(catalog.get_columns("orders").await.is_err());
+
+        let added = dir.join("002_orders.sql");
+        std::fs::write(&added, "CREATE TABLE orders (id INT, user_id INT);").unwrap();
+        assert!(manager.reload_ddl_catalog(&added).unwrap());
+        let catalog = manager.get_catalog(&config).await.unwrap();
+        assert_eq!(catalog.get_columns("orders").await.unwrap().len(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Catalog whose tables take `delay` to list and count the calls
+    struct SlowTables {
+        delay: Duration,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Catalog for SlowTables {
+        async fn list_tables(&self) -> CatalogResult<Vec<TableMetadata>> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            Ok(vec![TableMetadata::new("users", "public")])
+        }
+
+        async fn get_columns(&self, table: &str) -> CatalogResult<Vec<ColumnMetadata>> {
+            if table != "wide" {
+                return Err(CatalogError::TableNotFound(
+                    table.to_string(),
+                    String::new(),
+                ));
+            }
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            Ok((0..500)
+                .map(|i| ColumnMetadata::new(format!("c{}", i), DataType::Integer))
+                .collect())
+        }
+
+        async fn list_functions(&self) -> CatalogResult<Vec<FunctionMetadata>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_late_results_share_column_lists() {
+        let results = Arc::new(LateCatalogResults::default());
+        let slow = Arc::new(SlowTables {
+            delay: Duration::from_millis(30),
+            calls: Default::default(),
+        });
+        let wrapped = results.wrap(slow.clone(), Some(Duration::from_millis(10)));
+
+        let first = wrapped.get_columns_shared("wide").await.unwrap();
+        let second = wrapped.get_columns_shared("wide").await.unwrap();
+        assert_eq!(first.len(), 500);
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(wrapped.get_columns("wide").await.unwrap().len(), 500);
+        assert_eq!(slow.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_late_results_keep_only_slow_calls() {
+        let results = Arc::new(LateCatalogResults::default());
+        let slow = Arc::new(SlowTables {
+            delay: Duration::from_millis(30),
+            calls: Default::default(),
+        });
+        let fast = Arc::new(SlowTables {
+            delay: Duration::ZERO,
+            calls: Default::default(),
+        });
+
+        let wrapped = results.wrap(slow.clone(), Some(Duration::from_millis(10)));
+        wrapped.list_tables().await.unwrap();
+        assert_eq!(wrapped.list_tables().await.unwrap().len(), 1);
+        assert_eq!(slow.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // Calls within the budget aren't kept
+        let wrapped = results.wrap(fast.clone(), Some(Duration::from_millis(10)));
+        wrapped.list_tables().await.unwrap();
+        wrapped.list_tables().await.unwrap();
+        assert_eq!(fast.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        results.clear();
+        results
+            .wrap(slow.clone(), Some(Duration::from_millis(10)))
+            .list_tables()
+            .await
+            .unwrap();
+        assert_eq!(slow.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_catalog_manager_refresh_notifies() {
+        let mut manager = CatalogManager::new();
+        let mut events = manager.subscribe();
+
+        manager.refresh();
+
+        assert_eq!(
+            events.try_recv().unwrap(),
+            ServerEvent::CatalogInvalidated {
+                scope: CatalogScope::All
+            }
+        );
+        assert!(manager.ddl_catalogs.is_empty());
+    }
+
     // Note: Tests with actual database connections require
     // integration test setup with running databases.
     // Those tests are in the integration test suite.
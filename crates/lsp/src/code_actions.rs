@@ -0,0 +1,207 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Code actions
+//!
+//! Quick fixes derived from diagnostics published by this server.
+//!
+//! Diagnostics that can be fixed carry the fix in their `data` field, so the
+//! code action request doesn't need to re-run analysis:
+//! - Unknown column (`usql/unknown-column`) with `data.replacement`: replace the
+//!   reference with the suggested column
+//! - Comparison with `= NULL` (`usql/equals-null`): rewrite it to `IS NULL`,
+//!   or `<> NULL` to `IS NOT NULL`
+//! - Dialect feature (`usql/dialect-feature`) with `data.replacement`: use the
+//!   dialect's own syntax, e.g. an identity column for `AUTO_INCREMENT` in
+//!   PostgreSQL
+//!
+//! A fix replaces the diagnostic's range, or `data.range` when it covers
+//! more than the flagged text.
+
+use std::collections::HashMap;
+
+use tower_lsp::lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, NumberOrString, TextEdit, Url,
+    WorkspaceEdit,
+};
+
+use crate::diagnostic::DiagnosticCode;
+
+/// Build quick fixes for the diagnostics in a code action request
+pub fn quick_fixes(uri: &Url, diagnostics: &[Diagnostic]) -> Vec<CodeActionOrCommand> {
+    diagnostics
+        .iter()
+        .filter_map(|diagnostic| apply_replacement(uri, diagnostic))
+        .map(CodeActionOrCommand::CodeAction)
+        .collect()
+}
+
+/// Replace the range of a fixable diagnostic with its `data.replacement`
+fn apply_replacement(uri: &Url, diagnostic: &Diagnostic) -> Option<CodeAction> {
+    let fixable = [
+        DiagnosticCode::UndefinedColumn,
+        DiagnosticCode::EqualsNull,
+        DiagnosticCode::DialectFeature,
+    ];
+    if !fixable
+        .into_iter()
+        .any(|code| diagnostic.code.as_ref() == Some(&NumberOrString::from(code)))
+    {
+        return None;
+    }
+
+    let data = diagnostic.data.as_ref()?;
+    let replacement = data.get("replacement")?.as_str()?;
+    let range = data
+        .get("range")
+        .and_then(|range| serde_json::from_value(range.clone()).ok())
+        .unwrap_or(diagnostic.range);
+    let edit = TextEdit {
+        range,
+        new_text: replacement.to_string(),
+    };
+
+    Some(CodeAction {
+        title: format!("Replace with '{}'", replacement.trim_start()),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+            ..Default::default()
+        }),
+        is_preferred: Some(true),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::{DiagnosticCollector, SqlDiagnostic};
+    use tower_lsp::lsp_types::{Position, Range};
+    use unified_sql_lsp_ir::Dialect;
+    use unified_sql_lsp_lowering::cst::MockCstNode;
+    use unified_sql_lsp_semantic::UnknownColumn;
+
+    fn unknown_column(suggestion: Option<&str>) -> Diagnostic {
+        let unknown = UnknownColumn {
+            reference: "emial".to_string(),
+            suggestion: suggestion.map(str::to_string),
+            case_mismatch: false,
+        };
+        let range = Range::new(Position::new(0, 7), Position::new(0, 12));
+        SqlDiagnostic::unknown_column(&unknown, range).to_lsp()
+    }
+
+    #[test]
+    fn test_quick_fix_replaces_unknown_column() {
+        let uri = Url::parse("file:///query.sql").unwrap();
+        let diagnostic = unknown_column(Some("email"));
+
+        let actions = quick_fixes(&uri, std::slice::from_ref(&diagnostic));
+
+        assert_eq!(actions.len(), 1);
+        let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+            panic!("expected a code action");
+        };
+        assert_eq!(action.title, "Replace with 'email'");
+        assert_eq!(action.kind, Some(CodeActionKind::QUICKFIX));
+        let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+        assert_eq!(edits[0].range, diagnostic.range);
+        assert_eq!(edits[0].new_text, "email");
+    }
+
+    #[test]
+    fn test_quick_fix_rewrites_equals_null() {
+        let uri = Url::parse("file:///query.sql").unwrap();
+        let source = "DELETE FROM users WHERE deleted_at=NULL";
+        // `deleted_at=NULL` as the parser gives it
+        let condition = MockCstNode::new("binary_expression")
+            .with_range(24, 39)
+            .with_child(
+                None,
+                MockCstNode::new("column_reference")
+                    .with_range(24, 34)
+                    .with_text("deleted_at"),
+            )
+            .with_child(
+                None,
+                MockCstNode::new("=").with_range(34, 35).with_text("="),
+            )
+            .with_child(
+                None,
+                MockCstNode::new("literal")
+                    .with_range(35, 39)
+                    .with_text("NULL"),
+            );
+        let collector = DiagnosticCollector::new();
+        let diagnostics: Vec<Diagnostic> = collector
+            .condition_null_comparisons(source, 0, &[condition], Vec::new(), Dialect::PostgreSQL)
+            .into_iter()
+            .map(|d| d.to_lsp())
+            .collect();
+        assert_eq!(diagnostics.len(), 1);
+
+        let actions = quick_fixes(&uri, &diagnostics);
+
+        let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+            panic!("expected a code action");
+        };
+        assert_eq!(action.title, "Replace with 'IS NULL'");
+        let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+        assert_eq!(
+            edits[0].range,
+            Range::new(Position::new(0, 34), Position::new(0, 39))
+        );
+        // The glued operator gets a space before `IS`
+        assert_eq!(edits[0].new_text, " IS NULL");
+    }
+
+    #[test]
+    fn test_quick_fix_replaces_auto_increment_in_postgres() {
+        let uri = Url::parse("file:///schema.sql").unwrap();
+        let source = "CREATE TABLE t (id INT AUTO_INCREMENT PRIMARY KEY)";
+        let diagnostics: Vec<Diagnostic> = DiagnosticCollector::new()
+            .dialect_hints(source, Dialect::PostgreSQL)
+            .into_iter()
+            .map(|d| d.to_lsp())
+            .collect();
+
+        let actions = quick_fixes(&uri, &diagnostics);
+        assert_eq!(actions.len(), 1);
+        let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+            panic!("expected a code action");
+        };
+        assert_eq!(
+            action.title,
+            "Replace with 'GENERATED BY DEFAULT AS IDENTITY'"
+        );
+        let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+        assert_eq!(
+            edits[0].range,
+            Range::new(Position::new(0, 23), Position::new(0, 37))
+        );
+
+        // Hints without a replacement have no fix
+        let source = "SELECT DISTINCT ON (a) a FROM t";
+        let diagnostics: Vec<Diagnostic> = DiagnosticCollector::new()
+            .dialect_hints(source, Dialect::MySQL)
+            .into_iter()
+            .map(|d| d.to_lsp())
+            .collect();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(quick_fixes(&uri, &diagnostics).is_empty());
+    }
+
+    #[test]
+    fn test_no_quick_fix_without_suggestion() {
+        let uri = Url::parse("file:///query.sql").unwrap();
+        let syntax = SqlDiagnostic::error("Syntax error".to_string(), Range::default())
+            .with_code(DiagnosticCode::SyntaxError)
+            .to_lsp();
+
+        assert!(quick_fixes(&uri, &[unknown_column(None), syntax]).is_empty());
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Column migrations
+//!
+//! Column additions, drops and renames made by `ALTER TABLE` statements,
+//! found by a token scan. The workspace index records those of open
+//! documents so diagnostics can overlay them on the catalog (see
+//! [`crate::migration_catalog`]).
+
+use tower_lsp::lsp_types::{Range, Url};
+
+use crate::token_scan::{Token, skip_parens, tokenize};
+
+/// Column change made by an `ALTER TABLE` statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnChange {
+    /// `ADD [COLUMN] name ...`
+    Added(String),
+    /// `DROP [COLUMN] name`
+    Dropped(String),
+    /// `RENAME [COLUMN] from TO to`, or MySQL's `CHANGE [COLUMN] from to ...`
+    Renamed { from: String, to: String },
+}
+
+/// A column change found in a workspace document
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnMigration {
+    /// Altered table (without schema qualifier)
+    pub table: String,
+
+    /// What happened to the column
+    pub change: ColumnChange,
+
+    /// Document the `ALTER TABLE` statement lives in
+    pub uri: Url,
+
+    /// Range of the column name the change applies to
+    pub range: Range,
+}
+
+impl ColumnMigration {
+    /// The column of `table` that no longer exists after this change, if any
+    pub fn removed_column(&self) -> Option<&str> {
+        match &self.change {
+            ColumnChange::Added(_) => None,
+            ColumnChange::Dropped(name) => Some(name),
+            ColumnChange::Renamed { from, .. } => Some(from),
+        }
+    }
+}
+
+/// Words after `ADD` or `DROP` that alter something other than a column
+const NON_COLUMN_ACTIONS: &[&str] = &[
+    "CONSTRAINT",
+    "PRIMARY",
+    "FOREIGN",
+    "UNIQUE",
+    "CHECK",
+    "INDEX",
+    "KEY",
+    "PARTITION",
+];
+
+/// Column additions, drops and renames made by the `ALTER TABLE`
+/// statements of a document, in document order
+///
+/// Like [`crate::workspace_symbols::index_document`], this is a token scan that works on documents
+/// that don't parse cleanly. Every comma-separated action of a statement
+/// is read; actions on constraints, indexes and partitions are skipped.
+pub fn column_migrations(uri: &Url, content: &str) -> Vec<ColumnMigration> {
+    let tokens = tokenize(content);
+    let mut migrations = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        if !(tokens[i].is_keyword("ALTER")
+            && tokens.get(i + 1).is_some_and(|t| t.is_keyword("TABLE")))
+        {
+            i += 1;
+            continue;
+        }
+        i += 2;
+        while tokens
+            .get(i)
+            .is_some_and(|t| t.is_keyword("IF") || t.is_keyword("EXISTS") || t.is_keyword("ONLY"))
+        {
+            i += 1;
+        }
+        let Some(mut table) = tokens.get(i).filter(|t| t.is_identifier()) else {
+            continue;
+        };
+        i += 1;
+        if tokens.get(i).is_some_and(|t| t.is_symbol('.'))
+            && let Some(name) = tokens.get(i + 1).filter(|t| t.is_identifier())
+        {
+            table = name;
+            i += 2;
+        }
+
+        // One action per iteration, up to the end of the statement
+        while tokens.get(i).is_some_and(|t| !t.is_symbol(';')) {
+            if let Some((change, column)) = column_action(&tokens, i) {
+                migrations.push(ColumnMigration {
+                    table: table.text.clone(),
+                    change,
+                    uri: uri.clone(),
+                    range: column.range,
+                });
+            }
+            // Skip to the comma before the next action
+            while let Some(token) = tokens.get(i) {
+                if token.is_symbol(',') {
+                    i += 1;
+                    break;
+                }
+                if token.is_symbol(';') {
+                    break;
+                }
+                if token.is_symbol('(') {
+                    i = skip_parens(&tokens, i);
+                    continue;
+                }
+                i += 1;
+            }
+        }
+    }
+
+    migrations
+}
+
+/// Read the column action starting at `i`, with the token of the column
+/// name it applies to
+fn column_action(tokens: &[Token], mut i: usize) -> Option<(ColumnChange, &Token)> {
+    let verb = tokens.get(i)?;
+    i += 1;
+    let skip = |words: &[&str], i: &mut usize| {
+        while tokens
+            .get(*i)
+            .is_some_and(|t| words.iter().any(|w| t.is_keyword(w)))
+        {
+            *i += 1;
+        }
+    };
+
+    if verb.is_keyword("ADD") || verb.is_keyword("DROP") {
+        if tokens
+            .get(i)
+            .is_some_and(|t| NON_COLUMN_ACTIONS.iter().any(|k| t.is_keyword(k)))
+        {
+            return None;
+        }
+        skip(&["COLUMN", "IF", "NOT", "EXISTS"], &mut i);
+        let column = tokens.get(i).filter(|t| t.is_identifier())?;
+        let change = if verb.is_keyword("ADD") {
+            ColumnChange::Added(column.text.clone())
+        } else {
+            ColumnChange::Dropped(column.text.clone())
+        };
+        return Some((change, column));
+    }
+
+    if verb.is_keyword("RENAME") {
+        // `RENAME TO` renames the table, `RENAME CONSTRAINT` a constraint
+        if tokens.get(i).is_some_and(|t| {
+            t.is_keyword("TO")
+                || t.is_keyword("AS")
+                || NON_COLUMN_ACTIONS.iter().any(|k| t.is_keyword(k))
+        }) {
+            return None;
+        }
+        skip(&["COLUMN"], &mut i);
+        let from = tokens.get(i).filter(|t| t.is_identifier())?;
+        let to = tokens
+            .get(i + 1)
+            .filter(|t| t.is_keyword("TO"))
+            .and(tokens.get(i + 2))
+            .filter(|t| t.is_identifier())?;
+        let change = ColumnChange::Renamed {
+            from: from.text.clone(),
+            to: to.text.clone(),
+        };
+        return Some((change, from));
+    }
+
+    if verb.is_keyword("CHANGE") {
+        skip(&["COLUMN"], &mut i);
+        let from = tokens.get(i).filter(|t| t.is_identifier())?;
+        let to = tokens.get(i + 1).filter(|t| t.is_identifier())?;
+        if from.text.eq_ignore_ascii_case(&to.text) {
+            return None;
+        }
+        let change = ColumnChange::Renamed {
+            from: from.text.clone(),
+            to: to.text.clone(),
+        };
+        return Some((change, from));
+    }
+
+    None
+}
@@ -0,0 +1,331 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Column references
+//!
+//! Token scans for the columns a statement names, used by the diagnostics
+//! that resolve them against the catalog.
+
+use tower_lsp::lsp_types::Range;
+
+use crate::token_scan::{Token, TokenKind, tokenize};
+
+/// Words of a condition that aren't column references
+const CONDITION_KEYWORDS: &[&str] = &[
+    "AND",
+    "OR",
+    "NOT",
+    "IN",
+    "IS",
+    "NULL",
+    "TRUE",
+    "FALSE",
+    "UNKNOWN",
+    "LIKE",
+    "ILIKE",
+    "SIMILAR",
+    "ESCAPE",
+    "BETWEEN",
+    "SYMMETRIC",
+    "DISTINCT",
+    "FROM",
+    "TO",
+    "EXISTS",
+    "ANY",
+    "ALL",
+    "SOME",
+    "CASE",
+    "WHEN",
+    "THEN",
+    "ELSE",
+    "END",
+    "AS",
+    "COLLATE",
+    "INTERVAL",
+    "DATE",
+    "TIME",
+    "TIMESTAMP",
+    "CURRENT_DATE",
+    "CURRENT_TIME",
+    "CURRENT_TIMESTAMP",
+    "LOCALTIME",
+    "LOCALTIMESTAMP",
+    "CURRENT_USER",
+    "SESSION_USER",
+    "USER",
+    "DEFAULT",
+    "REGEXP",
+    "RLIKE",
+    "DIV",
+    "MOD",
+    "XOR",
+];
+
+/// Keywords of an ORDER BY item that aren't column references
+const ORDERING_KEYWORDS: &[&str] = &["ASC", "DESC", "NULLS", "FIRST", "LAST"];
+
+/// Column references in the WHERE and ORDER BY clauses of the UPDATE or
+/// DELETE `statement`, as qualifier, column and range of the whole reference
+///
+/// With `returning`, those of the RETURNING list follow; output names,
+/// with or without `AS`, aren't references. Quoted columns keep their
+/// delimiters, so they are resolved by their exact spelling. A clause with a
+/// subquery, and `WHERE CURRENT OF`, is skipped entirely: it sees other
+/// tables. Function names, types, ordinals and schema-qualified references
+/// are never returned.
+pub fn dml_column_references(
+    statement: &str,
+    returning: bool,
+) -> Vec<(Option<String>, String, Range)> {
+    let tokens = tokenize(statement);
+    if !tokens
+        .first()
+        .is_some_and(|t| t.is_keyword("UPDATE") || t.is_keyword("DELETE"))
+    {
+        return Vec::new();
+    }
+
+    // Clauses run from their top-level keyword to the next clause
+    let mut depth = 0i32;
+    let mut condition = None;
+    let mut ordering = None;
+    let mut returned = None;
+    for (i, token) in tokens.iter().enumerate() {
+        if token.is_symbol('(') {
+            depth += 1;
+        } else if token.is_symbol(')') {
+            depth -= 1;
+        } else if depth == 0 && token.is_keyword("WHERE") && condition.is_none() {
+            condition = Some(i + 1);
+        } else if depth == 0
+            && token.is_keyword("ORDER")
+            && tokens.get(i + 1).is_some_and(|t| t.is_keyword("BY"))
+        {
+            ordering = Some(i + 2);
+        } else if depth == 0 && token.is_keyword("RETURNING") {
+            returned = Some(i + 1);
+        }
+    }
+    let clause = |start: usize, until: &[&str]| {
+        let end = tokens[start..]
+            .iter()
+            .position(|t| until.iter().any(|k| t.is_keyword(k)))
+            .map_or(tokens.len(), |p| start + p);
+        &tokens[start..end]
+    };
+
+    let mut references = Vec::new();
+    if let Some(start) = condition {
+        let condition = clause(start, &["RETURNING", "ORDER", "LIMIT"]);
+        if !condition.first().is_some_and(|t| t.is_keyword("CURRENT"))
+            && !condition.iter().any(|t| t.is_keyword("SELECT"))
+        {
+            references.extend(expression_references(condition, CONDITION_KEYWORDS));
+        }
+    }
+    if let Some(start) = ordering {
+        let ordering = clause(start, &["RETURNING", "LIMIT"]);
+        if !ordering.iter().any(|t| t.is_keyword("SELECT")) {
+            references.extend(expression_references(
+                ordering,
+                &[CONDITION_KEYWORDS, ORDERING_KEYWORDS].concat(),
+            ));
+        }
+    }
+    if returning
+        && let Some(start) = returned
+        && !tokens[start..].iter().any(|t| t.is_keyword("SELECT"))
+    {
+        let mut depth = 0i32;
+        for item in tokens[start..].split(|t| {
+            if t.is_symbol('(') {
+                depth += 1;
+            } else if t.is_symbol(')') {
+                depth -= 1;
+            }
+            depth == 0 && t.is_symbol(',')
+        }) {
+            // `expr name` names the output like `expr AS name`
+            let item = match item {
+                [.., previous, name]
+                    if name.is_identifier()
+                        && (previous.is_symbol(')')
+                            || (previous.is_identifier()
+                                && !CONDITION_KEYWORDS.iter().any(|k| previous.is_keyword(k)))) =>
+                {
+                    &item[..item.len() - 1]
+                }
+                _ => item,
+            };
+            references.extend(expression_references(item, CONDITION_KEYWORDS));
+        }
+    }
+    references
+}
+
+/// Column references of the expression `tokens`, skipping `keywords`
+fn expression_references(
+    tokens: &[Token],
+    keywords: &[&str],
+) -> Vec<(Option<String>, String, Range)> {
+    let mut references = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        let previous = i.checked_sub(1).map(|p| &tokens[p]);
+        let skipped = !token.is_identifier()
+            || (token.kind == TokenKind::Word
+                && (token
+                    .text
+                    .starts_with(|c: char| c.is_ascii_digit() || c == '$')
+                    || keywords.iter().any(|k| token.is_keyword(k))))
+            || previous.is_some_and(|p| {
+                p.is_symbol(':')
+                    || p.is_symbol('$')
+                    || p.is_symbol('@')
+                    || p.is_keyword("AS")
+                    || p.is_keyword("INTERVAL")
+                    || p.is_keyword("COLLATE")
+            });
+        if skipped {
+            i += 1;
+            continue;
+        }
+
+        // Collect `name(.name)*`
+        let mut parts = vec![token];
+        let mut next = i + 1;
+        while tokens.get(next).is_some_and(|t| t.is_symbol('.'))
+            && let Some(part) = tokens.get(next + 1).filter(|t| t.is_identifier())
+        {
+            parts.push(part);
+            next += 2;
+        }
+        let call = tokens.get(next).is_some_and(|t| t.is_symbol('('));
+        i = next;
+        if call {
+            continue;
+        }
+
+        let range = Range::new(parts[0].range.start, parts[parts.len() - 1].range.end);
+        match parts.as_slice() {
+            [column] => references.push((None, column.written(), range)),
+            [table, column] => references.push((Some(table.text.clone()), column.written(), range)),
+            _ => {}
+        }
+    }
+
+    references
+}
+
+/// Every column-like reference of `statement`, as qualifier, column and
+/// range of the whole reference
+///
+/// A reference is a name or `qualifier.name` that isn't called and doesn't
+/// follow `AS`. Keywords aren't filtered out, so callers look for specific
+/// names.
+pub fn column_references(statement: &str) -> Vec<(Option<String>, String, Range)> {
+    let tokens = tokenize(statement);
+    let mut references = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = &tokens[i];
+        let previous = i.checked_sub(1).map(|p| &tokens[p]);
+        if !token.is_identifier()
+            || previous.is_some_and(|p| p.is_keyword("AS") || p.is_symbol(':'))
+        {
+            i += 1;
+            continue;
+        }
+
+        let mut parts = vec![token];
+        let mut next = i + 1;
+        while tokens.get(next).is_some_and(|t| t.is_symbol('.'))
+            && let Some(part) = tokens.get(next + 1).filter(|t| t.is_identifier())
+        {
+            parts.push(part);
+            next += 2;
+        }
+        let call = tokens.get(next).is_some_and(|t| t.is_symbol('('));
+        i = next;
+        if call {
+            continue;
+        }
+
+        let range = Range::new(parts[0].range.start, parts[parts.len() - 1].range.end);
+        match parts.as_slice() {
+            [column] => references.push((None, column.text.clone(), range)),
+            [table, column] => {
+                references.push((Some(table.text.clone()), column.text.clone(), range))
+            }
+            _ => {}
+        }
+    }
+
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dml_column_references() {
+        let references = |statement| -> Vec<_> {
+            dml_column_references(statement, true)
+                .into_iter()
+                .map(|(qualifier, column, _)| (qualifier, column))
+                .collect()
+        };
+        assert_eq!(
+            references(
+                "UPDATE users SET name = 'x' WHERE u.id = $1 AND lower(email) LIKE 'a%' \
+                 AND created_at > DATE '2024-01-01' AND kind::text <> 'y' RETURNING id"
+            ),
+            vec![
+                (Some("u".to_string()), "id".to_string()),
+                (None, "email".to_string()),
+                (None, "created_at".to_string()),
+                (None, "kind".to_string()),
+                (None, "id".to_string()),
+            ]
+        );
+
+        // RETURNING items resolve against the target's alias; output names
+        // aren't references
+        assert_eq!(
+            references(
+                "UPDATE users AS u SET name = 'x' WHERE u.id = 1 \
+                 RETURNING u.name, lower(u.email) AS email, created_at created, *"
+            ),
+            vec![
+                (Some("u".to_string()), "id".to_string()),
+                (Some("u".to_string()), "name".to_string()),
+                (Some("u".to_string()), "email".to_string()),
+                (None, "created_at".to_string()),
+            ]
+        );
+        assert_eq!(
+            dml_column_references("DELETE FROM users RETURNING id", false),
+            Vec::new()
+        );
+
+        // ORDER BY items keep the delimiters of quoted columns
+        assert_eq!(
+            references(
+                "DELETE FROM users WHERE id > 1 ORDER BY \"Name\" DESC, u.`Email`, 2 LIMIT 5"
+            ),
+            vec![
+                (None, "id".to_string()),
+                (None, "\"Name\"".to_string()),
+                (Some("u".to_string()), "`Email`".to_string()),
+            ]
+        );
+
+        assert!(references("DELETE FROM users WHERE id IN (SELECT 1)").is_empty());
+        assert!(references("SELECT * FROM users WHERE id = 1 ORDER BY name").is_empty());
+    }
+}
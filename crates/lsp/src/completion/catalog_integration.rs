@@ -11,16 +11,24 @@
 use tracing::debug;
 
 use crate::completion::error::CompletionError;
-use std::sync::Arc;
-use unified_sql_lsp_catalog::{Catalog, ColumnMetadata, FunctionMetadata, TableMetadata};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use unified_sql_lsp_catalog::{
+    Catalog, ColumnMetadata, FunctionMetadata, SequenceMetadata, TableMetadata, builtin_collations,
+};
+use unified_sql_lsp_ir::Dialect;
 use unified_sql_lsp_semantic::{ColumnSymbol, TableSymbol};
 
 /// Catalog fetcher for completion
 ///
 /// Fetches schema information from the catalog and converts it
 /// to semantic symbols for completion.
+///
+/// Converted column lists are kept per table, so every symbol populated
+/// for the same table shares one list.
 pub struct CatalogCompletionFetcher {
     catalog: Arc<dyn Catalog>,
+    columns: Mutex<HashMap<String, Arc<[ColumnSymbol]>>>,
 }
 
 impl CatalogCompletionFetcher {
@@ -30,7 +38,10 @@ impl CatalogCompletionFetcher {
     ///
     /// * `catalog` - The catalog to fetch from
     pub fn new(catalog: Arc<dyn Catalog>) -> Self {
-        Self { catalog }
+        Self {
+            catalog,
+            columns: Mutex::new(HashMap::new()),
+        }
     }
 
     /// Get the inner catalog
@@ -57,6 +68,11 @@ impl CatalogCompletionFetcher {
             .map_err(CompletionError::Catalog)
     }
 
+    /// Tables a still-running paginated listing has loaded so far
+    pub fn loaded_tables(&self) -> Option<Vec<TableMetadata>> {
+        self.catalog.loaded_tables()
+    }
+
     /// List all functions from the catalog
     ///
     /// # Returns
@@ -76,6 +92,42 @@ impl CatalogCompletionFetcher {
             .map_err(CompletionError::Catalog)
     }
 
+    /// List role names from the catalog
+    ///
+    /// Roles only decorate completion, so a catalog error is logged and
+    /// yields no roles rather than failing the request.
+    pub async fn list_roles(&self) -> Vec<String> {
+        self.catalog.list_roles().await.unwrap_or_else(|e| {
+            debug!("Failed to list roles: {}", e);
+            Vec::new()
+        })
+    }
+
+    /// List collation names from the catalog
+    ///
+    /// Catalogs that list none, and catalog errors, fall back to the
+    /// builtin shortlist for `dialect`.
+    pub async fn list_collations(&self, dialect: Dialect) -> Vec<String> {
+        match self.catalog.list_collations().await {
+            Ok(collations) if !collations.is_empty() => collations,
+            Ok(_) => builtin_collations(dialect),
+            Err(e) => {
+                debug!("Failed to list collations: {}", e);
+                builtin_collations(dialect)
+            }
+        }
+    }
+
+    /// List sequences from the catalog
+    ///
+    /// Like roles, a catalog error is logged and yields no sequences.
+    pub async fn list_sequences(&self) -> Vec<SequenceMetadata> {
+        self.catalog.list_sequences().await.unwrap_or_else(|e| {
+            debug!("Failed to list sequences: {}", e);
+            Vec::new()
+        })
+    }
+
     /// Populate table columns from the catalog
     ///
     /// # Arguments
@@ -97,20 +149,29 @@ impl CatalogCompletionFetcher {
         &self,
         table: &mut TableSymbol,
     ) -> Result<(), CompletionError> {
+        if let Some(columns) = self.columns.lock().unwrap().get(&table.table_name) {
+            table.columns = Arc::clone(columns);
+            return Ok(());
+        }
+
         // Query catalog for columns
         let columns_metadata = self
             .catalog
-            .get_columns(&table.table_name)
+            .get_columns_shared(&table.table_name)
             .await
             .map_err(CompletionError::Catalog)?;
 
         // Convert ColumnMetadata to ColumnSymbol
-        let columns: Vec<ColumnSymbol> = columns_metadata
+        let columns: Arc<[ColumnSymbol]> = columns_metadata
             .iter()
             .map(|meta| Self::metadata_to_symbol(meta, &table.table_name))
             .collect();
 
         // Update table with columns
+        self.columns
+            .lock()
+            .unwrap()
+            .insert(table.table_name.clone(), Arc::clone(&columns));
         table.columns = columns;
 
         Ok(())
@@ -190,6 +251,9 @@ impl CatalogCompletionFetcher {
         if meta.is_foreign_key {
             symbol = symbol.with_foreign_key();
         }
+        symbol = symbol
+            .with_references(meta.references.clone())
+            .with_not_null(!meta.nullable);
 
         symbol
     }
@@ -240,6 +304,77 @@ mod tests {
         assert!(true);
     }
 
+    #[tokio::test]
+    async fn test_list_roles_degrades_on_error() {
+        struct DeniedRoles;
+
+        #[async_trait::async_trait]
+        impl Catalog for DeniedRoles {
+            async fn list_tables(
+                &self,
+            ) -> unified_sql_lsp_catalog::CatalogResult<Vec<TableMetadata>> {
+                Ok(Vec::new())
+            }
+
+            async fn get_columns(
+                &self,
+                _table: &str,
+            ) -> unified_sql_lsp_catalog::CatalogResult<Vec<ColumnMetadata>> {
+                Ok(Vec::new())
+            }
+
+            async fn list_functions(
+                &self,
+            ) -> unified_sql_lsp_catalog::CatalogResult<Vec<FunctionMetadata>> {
+                Ok(Vec::new())
+            }
+
+            async fn list_roles(&self) -> unified_sql_lsp_catalog::CatalogResult<Vec<String>> {
+                Err(CatalogError::PermissionDenied {
+                    object: "mysql.user".to_string(),
+                })
+            }
+        }
+
+        let fetcher = CatalogCompletionFetcher::new(Arc::new(DeniedRoles));
+        assert!(fetcher.list_roles().await.is_empty());
+
+        // Catalogs without role support list none
+        let catalog = Arc::new(MockCatalog {
+            tables: std::collections::HashMap::new(),
+        });
+        assert!(
+            CatalogCompletionFetcher::new(catalog)
+                .list_roles()
+                .await
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_populated_tables_share_columns() {
+        let wide: Vec<ColumnMetadata> = (0..500)
+            .map(|i| ColumnMetadata::new(format!("c{}", i), DataType::Integer))
+            .collect();
+        let mut tables = std::collections::HashMap::new();
+        tables.insert("wide".to_string(), wide);
+        let fetcher = CatalogCompletionFetcher::new(Arc::new(MockCatalog { tables }));
+
+        // A self join references the table twice
+        let mut symbols = vec![
+            TableSymbol::new("wide").with_alias("a"),
+            TableSymbol::new("wide").with_alias("b"),
+        ];
+        fetcher.populate_all_tables(&mut symbols).await.unwrap();
+
+        assert_eq!(symbols[0].columns.len(), 500);
+        assert!(Arc::ptr_eq(&symbols[0].columns, &symbols[1].columns));
+
+        // Cloning a symbol for a later stage copies no columns
+        let cloned = symbols[0].clone();
+        assert!(Arc::ptr_eq(&cloned.columns, &symbols[0].columns));
+    }
+
     #[tokio::test]
     async fn test_populate_table_columns() {
         let mut tables = std::collections::HashMap::new();
@@ -0,0 +1,68 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Collation name completion
+//!
+//! Completion items for the name after `COLLATE`: the collations listed by
+//! the catalog. PostgreSQL collation names are identifiers that are usually
+//! mixed case or contain `-`, so they are inserted double quoted.
+
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind};
+use unified_sql_lsp_ir::Dialect;
+use unified_sql_lsp_ir::dialect::DialectFamily;
+
+/// Render collation names
+///
+/// `in_quotes` tells whether the cursor is already inside the double quotes
+/// of the name.
+pub fn render_collation_names(
+    collations: &[String],
+    in_quotes: bool,
+    dialect: Dialect,
+) -> Vec<CompletionItem> {
+    let quote = dialect.family() == DialectFamily::PostgreSQL && !in_quotes;
+
+    collations
+        .iter()
+        .map(|name| CompletionItem {
+            label: name.clone(),
+            kind: Some(CompletionItemKind::CONSTANT),
+            detail: Some("collation".to_string()),
+            filter_text: Some(name.clone()),
+            insert_text: quote.then(|| format!("\"{}\"", name)),
+            sort_text: Some(format!("0_{}", name)),
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mysql_collations_are_bare() {
+        let collations = vec!["utf8mb4_general_ci".to_string(), "binary".to_string()];
+
+        let items = render_collation_names(&collations, false, Dialect::MySQL);
+        let labels: Vec<_> = items.iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(labels, vec!["utf8mb4_general_ci", "binary"]);
+        assert_eq!(items[0].kind, Some(CompletionItemKind::CONSTANT));
+        assert_eq!(items[0].detail.as_deref(), Some("collation"));
+        assert_eq!(items[0].insert_text, None);
+    }
+
+    #[test]
+    fn test_postgres_collations_are_quoted_outside_quotes() {
+        let collations = vec!["C".to_string(), "en-US-x-icu".to_string()];
+
+        let items = render_collation_names(&collations, false, Dialect::PostgreSQL);
+        assert_eq!(items[1].label, "en-US-x-icu");
+        assert_eq!(items[1].insert_text.as_deref(), Some("\"en-US-x-icu\""));
+
+        let items = render_collation_names(&collations, true, Dialect::PostgreSQL);
+        assert_eq!(items[1].insert_text, None);
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Column definition completion
+//!
+//! Completion inside the column list of CREATE TABLE. Right after the column
+//! name come the column types of the dialect; after the type, the column
+//! constraints and attributes: `AUTO_INCREMENT` in MySQL, identity columns
+//! in PostgreSQL. With snippet support both identity forms collapse into one
+//! item that lets the user pick `ALWAYS` or `BY DEFAULT`.
+
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind, Documentation, InsertTextFormat};
+use unified_sql_lsp_context::{DialectCapabilities, KeywordProvider};
+use unified_sql_lsp_ir::Dialect;
+
+use crate::completion::render::CompletionRenderer;
+use crate::completion::types::render_column_types;
+use crate::config::CompletionConfig;
+
+/// Label of the identity column snippet
+pub const IDENTITY_SNIPPET_LABEL: &str = "GENERATED … AS IDENTITY";
+
+/// Render completion for a column definition
+///
+/// `data_type` is the type already written after the column name, if any.
+pub fn render_column_definition(
+    data_type: Option<&str>,
+    dialect: Dialect,
+    capabilities: DialectCapabilities,
+    config: &CompletionConfig,
+) -> Vec<CompletionItem> {
+    if data_type.is_none() {
+        return render_column_types(dialect, capabilities.identity_columns);
+    }
+
+    let keywords = KeywordProvider::new(dialect)
+        .with_capabilities(capabilities)
+        .column_constraint_keywords()
+        .keywords;
+    let mut items = CompletionRenderer::render_keywords(&keywords);
+    if config.snippet_support
+        && let Some(first) = items.iter().position(is_identity_keyword)
+    {
+        let sort_text = items[first].sort_text.clone();
+        items.retain(|item| !is_identity_keyword(item));
+        items.insert(first, identity_snippet(sort_text, config));
+    }
+    items
+}
+
+fn is_identity_keyword(item: &CompletionItem) -> bool {
+    item.kind == Some(CompletionItemKind::KEYWORD) && item.label.starts_with("GENERATED ")
+}
+
+fn identity_snippet(sort_text: Option<String>, config: &CompletionConfig) -> CompletionItem {
+    CompletionItem {
+        label: IDENTITY_SNIPPET_LABEL.to_string(),
+        kind: Some(CompletionItemKind::SNIPPET),
+        detail: Some("Identity column".to_string()),
+        documentation: Some(Documentation::String(
+            "Values come from an implicit sequence. ALWAYS rejects explicit values \
+             unless OVERRIDING SYSTEM VALUE is given; BY DEFAULT accepts them."
+                .to_string(),
+        )),
+        sort_text,
+        filter_text: Some("GENERATED AS IDENTITY".to_string()),
+        insert_text: Some(
+            config
+                .keyword_case
+                .apply("GENERATED ${1|ALWAYS,BY DEFAULT|} AS IDENTITY$0"),
+        ),
+        insert_text_format: Some(InsertTextFormat::SNIPPET),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn complete(
+        data_type: Option<&str>,
+        dialect: Dialect,
+        config: &CompletionConfig,
+    ) -> Vec<CompletionItem> {
+        render_column_definition(
+            data_type,
+            dialect,
+            DialectCapabilities::for_dialect(dialect),
+            config,
+        )
+    }
+
+    fn labels(items: &[CompletionItem]) -> Vec<&str> {
+        items.iter().map(|i| i.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_mysql_column_attributes() {
+        let items = complete(Some("INT"), Dialect::MySQL, &CompletionConfig::default());
+        let labels = labels(&items);
+        assert!(labels.contains(&"AUTO_INCREMENT"));
+        assert!(labels.contains(&"NOT NULL"));
+        assert!(!labels.iter().any(|l| l.starts_with("GENERATED")));
+
+        let types = complete(None, Dialect::MySQL, &CompletionConfig::default());
+        assert!(types.iter().any(|i| i.label == "VARCHAR"));
+        assert!(!types.iter().any(|i| i.label == "serial"));
+    }
+
+    #[test]
+    fn test_postgres_identity_columns() {
+        let config = CompletionConfig::default();
+        let items = complete(Some("integer"), Dialect::PostgreSQL, &config);
+        let labels = labels(&items);
+        assert!(!labels.contains(&"AUTO_INCREMENT"));
+        assert!(labels.contains(&"GENERATED ALWAYS AS IDENTITY"));
+        assert!(labels.contains(&"GENERATED BY DEFAULT AS IDENTITY"));
+
+        let config = CompletionConfig {
+            snippet_support: true,
+            ..Default::default()
+        };
+        let items = complete(Some("integer"), Dialect::PostgreSQL, &config);
+        let identity: Vec<_> = items
+            .iter()
+            .filter(|i| i.label.starts_with("GENERATED"))
+            .collect();
+        assert_eq!(identity.len(), 1);
+        assert_eq!(identity[0].label, IDENTITY_SNIPPET_LABEL);
+        assert_eq!(
+            identity[0].insert_text.as_deref(),
+            Some("GENERATED ${1|ALWAYS,BY DEFAULT|} AS IDENTITY$0")
+        );
+        assert_eq!(
+            identity[0].insert_text_format,
+            Some(InsertTextFormat::SNIPPET)
+        );
+
+        let types = complete(None, Dialect::PostgreSQL, &config);
+        let serial = types.iter().find(|i| i.label == "bigserial").unwrap();
+        assert_eq!(serial.detail.as_deref(), Some("Data type (legacy)"));
+    }
+}
@@ -0,0 +1,241 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Comparison value completion
+//!
+//! Literal values offered on the right-hand side of a comparison, ranked
+//! above columns and keywords:
+//! - `TRUE` / `FALSE` after `=`, `<>` or `!=` with a boolean column, or
+//!   `1` / `0` on MySQL where BOOLEAN is TINYINT(1)
+//! - `NULL` / `NOT NULL` after `IS`, whatever the operand's type
+//! - the values of an enum column after `=` and inside `IN (`
+//! - `CURRENT_DATE` and friends for a date or time column in `BETWEEN`
+//!
+//! Other types get no literals, so their completion is unchanged. Inside
+//! `IN (` a SELECT snippet is offered after the literals.
+
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind, Documentation, InsertTextFormat};
+use unified_sql_lsp_catalog::DataType;
+use unified_sql_lsp_context::ComparisonOperand;
+use unified_sql_lsp_ir::Dialect;
+use unified_sql_lsp_ir::dialect::DialectFamily;
+
+/// Operands of `IS`: label, description
+const NULL_TESTS: &[(&str, &str)] = &[
+    ("NULL", "True if the value is NULL"),
+    ("NOT NULL", "True if the value is not NULL"),
+];
+
+/// Current date and time functions: label, types they fit
+const CURRENT_TIME_FUNCTIONS: &[(&str, &[DataType])] = &[
+    (
+        "CURRENT_DATE",
+        &[DataType::Date, DataType::DateTime, DataType::Timestamp],
+    ),
+    (
+        "CURRENT_TIMESTAMP",
+        &[DataType::DateTime, DataType::Timestamp],
+    ),
+    ("CURRENT_TIME", &[DataType::Time]),
+];
+
+/// Render the literals for the value compared with `operand`
+///
+/// `data_type` is the type of the operand column, if it was found in the
+/// catalog.
+pub fn render_comparison_literals(
+    operand: &ComparisonOperand,
+    data_type: Option<&DataType>,
+    dialect: Dialect,
+) -> Vec<CompletionItem> {
+    let literals: Vec<(String, String, String)> = match (operand, data_type) {
+        (ComparisonOperand::NullTest, _) => NULL_TESTS
+            .iter()
+            .map(|(label, description)| {
+                (
+                    label.to_string(),
+                    "NULL test".to_string(),
+                    description.to_string(),
+                )
+            })
+            .collect(),
+        (
+            ComparisonOperand::Column { .. } | ComparisonOperand::ValueList { .. },
+            Some(DataType::Boolean),
+        ) => {
+            let values = match dialect.family() {
+                DialectFamily::PostgreSQL => [("TRUE", "true"), ("FALSE", "false")],
+                DialectFamily::MySQL => [("1", "true"), ("0", "false")],
+            };
+            values
+                .iter()
+                .map(|(label, value)| {
+                    (
+                        label.to_string(),
+                        "boolean".to_string(),
+                        format!("Boolean {}", value),
+                    )
+                })
+                .collect()
+        }
+        (
+            ComparisonOperand::Column { .. } | ComparisonOperand::ValueList { .. },
+            Some(DataType::Enum(values)),
+        ) => values
+            .iter()
+            .map(|value| {
+                (
+                    format!("'{}'", value.replace('\'', "''")),
+                    "enum".to_string(),
+                    format!("Enum value {}", value),
+                )
+            })
+            .collect(),
+        (ComparisonOperand::Between { .. }, Some(data_type)) => CURRENT_TIME_FUNCTIONS
+            .iter()
+            .filter(|(_, types)| types.contains(data_type))
+            .map(|(label, _)| {
+                (
+                    label.to_string(),
+                    "function".to_string(),
+                    format!("{} at the start of the transaction", label),
+                )
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    literals
+        .into_iter()
+        .enumerate()
+        .map(|(i, (label, detail, description))| CompletionItem {
+            label,
+            kind: Some(CompletionItemKind::VALUE),
+            detail: Some(detail),
+            documentation: Some(Documentation::String(description)),
+            // `!` sorts before the `0…` sort texts of columns
+            sort_text: Some(format!("!{}", i)),
+            preselect: Some(i == 0),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Render a subquery snippet for an `IN (` value list
+///
+/// Sorts after the literals and before columns.
+pub fn render_value_list_subquery(snippet_support: bool) -> CompletionItem {
+    let (insert_text, insert_text_format) = if snippet_support {
+        (
+            "SELECT ${1:column} FROM ${2:table}".to_string(),
+            InsertTextFormat::SNIPPET,
+        )
+    } else {
+        ("SELECT ".to_string(), InsertTextFormat::PLAIN_TEXT)
+    };
+
+    CompletionItem {
+        label: "SELECT …".to_string(),
+        kind: Some(CompletionItemKind::SNIPPET),
+        detail: Some("subquery".to_string()),
+        documentation: Some(Documentation::String(
+            "Match the values returned by a subquery".to_string(),
+        )),
+        sort_text: Some("!~".to_string()),
+        insert_text: Some(insert_text),
+        insert_text_format: Some(insert_text_format),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unified_sql_lsp_context::BetweenBound;
+
+    fn labels(items: &[CompletionItem]) -> Vec<&str> {
+        items.iter().map(|i| i.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_boolean_literals_follow_dialect() {
+        let active = ComparisonOperand::Column {
+            qualifier: None,
+            column: "active".to_string(),
+        };
+
+        let postgres =
+            render_comparison_literals(&active, Some(&DataType::Boolean), Dialect::PostgreSQL);
+        assert_eq!(labels(&postgres), vec!["TRUE", "FALSE"]);
+        assert_eq!(postgres[0].preselect, Some(true));
+
+        let mysql = render_comparison_literals(&active, Some(&DataType::Boolean), Dialect::MySQL);
+        assert_eq!(labels(&mysql), vec!["1", "0"]);
+    }
+
+    #[test]
+    fn test_null_tests_ignore_type() {
+        for data_type in [Some(DataType::Text), None] {
+            let items = render_comparison_literals(
+                &ComparisonOperand::NullTest,
+                data_type.as_ref(),
+                Dialect::PostgreSQL,
+            );
+            assert_eq!(labels(&items), vec!["NULL", "NOT NULL"]);
+        }
+    }
+
+    #[test]
+    fn test_value_list_enum_values() {
+        let status = ComparisonOperand::ValueList {
+            qualifier: None,
+            column: "status".to_string(),
+        };
+        let data_type = DataType::Enum(vec!["active".to_string(), "it's".to_string()]);
+
+        let items = render_comparison_literals(&status, Some(&data_type), Dialect::MySQL);
+        assert_eq!(labels(&items), vec!["'active'", "'it''s'"]);
+
+        let subquery = render_value_list_subquery(true);
+        assert_eq!(subquery.insert_text_format, Some(InsertTextFormat::SNIPPET));
+        assert!(subquery.sort_text > items.last().unwrap().sort_text);
+    }
+
+    #[test]
+    fn test_between_bounds_follow_type() {
+        let created_at = ComparisonOperand::Between {
+            qualifier: None,
+            column: "created_at".to_string(),
+            bound: BetweenBound::Low,
+        };
+
+        let timestamp = render_comparison_literals(
+            &created_at,
+            Some(&DataType::Timestamp),
+            Dialect::PostgreSQL,
+        );
+        assert_eq!(
+            labels(&timestamp),
+            vec!["CURRENT_DATE", "CURRENT_TIMESTAMP"]
+        );
+
+        let integer =
+            render_comparison_literals(&created_at, Some(&DataType::Integer), Dialect::MySQL);
+        assert!(integer.is_empty());
+    }
+
+    #[test]
+    fn test_other_types_add_nothing() {
+        let name = ComparisonOperand::Column {
+            qualifier: None,
+            column: "name".to_string(),
+        };
+        for data_type in [Some(DataType::Text), Some(DataType::Integer), None] {
+            assert!(
+                render_comparison_literals(&name, data_type.as_ref(), Dialect::MySQL).is_empty()
+            );
+        }
+    }
+}
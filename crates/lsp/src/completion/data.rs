@@ -0,0 +1,287 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Completion item data
+//!
+//! Every rendered completion item carries a [`CompletionData`] in its `data`
+//! field recording the catalog object it refers to, so that resolve, usage
+//! counting and similar features can tell what a chosen item meant without
+//! re-running completion.
+//!
+//! The payload is wrapped in a versioned envelope:
+//!
+//! ```json
+//! { "v": 1, "completion": { "source": "column", "table": "users", "name": "id" } }
+//! ```
+//!
+//! Clients may hand back items cached by an older server, so
+//! [`CompletionData::decode`] never fails hard: anything it doesn't
+//! understand yields `None`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tower_lsp::lsp_types::CompletionItem;
+use unified_sql_lsp_ir::Dialect;
+
+/// Version of the envelope written by [`CompletionData::encode`]
+pub const COMPLETION_DATA_VERSION: u64 = 1;
+
+/// Kind of object a completion item refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompletionSource {
+    Table,
+    View,
+    Column,
+    Function,
+    Keyword,
+    Cte,
+}
+
+/// Catalog origin of a completion item
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompletionData {
+    /// Kind of object the item refers to
+    pub source: CompletionSource,
+
+    /// Schema of the table, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+
+    /// Table a column belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
+
+    /// Name of the object itself
+    pub name: String,
+
+    /// Dialect the item was completed for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialect: Option<Dialect>,
+}
+
+impl CompletionData {
+    fn new(source: CompletionSource, name: impl Into<String>) -> Self {
+        Self {
+            source,
+            schema: None,
+            table: None,
+            name: name.into(),
+            dialect: None,
+        }
+    }
+
+    /// A catalog table (or view)
+    pub fn table(name: impl Into<String>) -> Self {
+        Self::new(CompletionSource::Table, name)
+    }
+
+    /// A catalog view or materialized view
+    pub fn view(name: impl Into<String>) -> Self {
+        Self::new(CompletionSource::View, name)
+    }
+
+    /// A column of `table`
+    pub fn column(table: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            table: Some(table.into()),
+            ..Self::new(CompletionSource::Column, name)
+        }
+    }
+
+    /// A function from the function registry
+    pub fn function(name: impl Into<String>) -> Self {
+        Self::new(CompletionSource::Function, name)
+    }
+
+    /// A SQL keyword
+    pub fn keyword(name: impl Into<String>) -> Self {
+        Self::new(CompletionSource::Keyword, name)
+    }
+
+    /// A common table expression defined in the statement
+    pub fn cte(name: impl Into<String>) -> Self {
+        Self::new(CompletionSource::Cte, name)
+    }
+
+    /// Set the schema of a table
+    pub fn with_schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+
+    /// Set the dialect the item was completed for
+    pub fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = Some(dialect);
+        self
+    }
+
+    /// Wrap the data in the versioned envelope stored in `CompletionItem::data`
+    pub fn encode(&self) -> Value {
+        serde_json::json!({
+            "v": COMPLETION_DATA_VERSION,
+            "completion": self,
+        })
+    }
+
+    /// Read the data back from a `CompletionItem::data` value
+    ///
+    /// Returns `None` for values without the envelope, with a different
+    /// envelope version, or whose source or name is missing or unknown.
+    /// Unknown fields are ignored, and an unrecognized dialect is dropped
+    /// rather than rejecting the whole payload.
+    pub fn decode(value: Value) -> Option<Self> {
+        let Value::Object(mut envelope) = value else {
+            return None;
+        };
+        if envelope.get("v")?.as_u64()? != COMPLETION_DATA_VERSION {
+            return None;
+        }
+        let Value::Object(mut payload) = envelope.remove("completion")? else {
+            return None;
+        };
+
+        let source = serde_json::from_value(payload.remove("source")?).ok()?;
+        let name = payload.remove("name")?.as_str()?.to_string();
+        let mut string_field = |key: &str| {
+            payload
+                .remove(key)
+                .and_then(|v| v.as_str().map(str::to_string))
+        };
+        let schema = string_field("schema");
+        let table = string_field("table");
+        let dialect = payload
+            .remove("dialect")
+            .and_then(|v| serde_json::from_value(v).ok());
+
+        Some(Self {
+            source,
+            schema,
+            table,
+            name,
+            dialect,
+        })
+    }
+
+    /// Attach the data to `item`
+    pub fn attach(self, mut item: CompletionItem) -> CompletionItem {
+        item.data = Some(self.encode());
+        item
+    }
+}
+
+/// Record `dialect` on every item whose data doesn't name one yet
+///
+/// Renderers mostly don't know the document's dialect, so the engine stamps
+/// it once the items are collected.
+pub fn stamp_dialect(items: &mut [CompletionItem], dialect: Dialect) {
+    for item in items {
+        let Some(data) = item.data.take().and_then(CompletionData::decode) else {
+            continue;
+        };
+        let data = match data.dialect {
+            Some(_) => data,
+            None => data.with_dialect(dialect),
+        };
+        item.data = Some(data.encode());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn round_trip(data: CompletionData) {
+        assert_eq!(CompletionData::decode(data.encode()), Some(data));
+    }
+
+    #[test]
+    fn test_round_trip_each_source() {
+        round_trip(CompletionData::table("users").with_schema("public"));
+        round_trip(CompletionData::view("active_users").with_schema("public"));
+        round_trip(CompletionData::column("users", "id").with_dialect(Dialect::PostgreSQL));
+        round_trip(CompletionData::function("COUNT").with_dialect(Dialect::MySQL));
+        round_trip(CompletionData::keyword("SELECT"));
+        round_trip(CompletionData::cte("recent_orders"));
+    }
+
+    #[test]
+    fn test_encode_envelope() {
+        let value = CompletionData::column("users", "id").encode();
+        assert_eq!(
+            value,
+            json!({
+                "v": 1,
+                "completion": { "source": "column", "table": "users", "name": "id" }
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_is_tolerant() {
+        // Unknown fields and an unknown dialect are ignored
+        let data = CompletionData::decode(json!({
+            "v": 1,
+            "extra": true,
+            "completion": {
+                "source": "table",
+                "name": "users",
+                "schema": "public",
+                "dialect": "Oracle",
+                "rank": 3
+            }
+        }))
+        .unwrap();
+        assert_eq!(data, CompletionData::table("users").with_schema("public"));
+
+        // Payloads from other versions or other servers are rejected
+        assert_eq!(CompletionData::decode(Value::Null), None);
+        assert_eq!(CompletionData::decode(json!("users")), None);
+        assert_eq!(
+            CompletionData::decode(json!({ "v": 2, "completion": {} })),
+            None
+        );
+        assert_eq!(
+            CompletionData::decode(json!({ "completion": { "source": "table", "name": "t" } })),
+            None
+        );
+        assert_eq!(
+            CompletionData::decode(
+                json!({ "v": 1, "completion": { "source": "index", "name": "t" } })
+            ),
+            None
+        );
+        assert_eq!(
+            CompletionData::decode(json!({ "v": 1, "completion": { "source": "table" } })),
+            None
+        );
+    }
+
+    #[test]
+    fn test_stamp_dialect() {
+        let mut items = vec![
+            CompletionData::keyword("FROM").attach(CompletionItem::default()),
+            CompletionData::function("NOW")
+                .with_dialect(Dialect::MySQL)
+                .attach(CompletionItem::default()),
+            CompletionItem::default(),
+        ];
+        stamp_dialect(&mut items, Dialect::PostgreSQL);
+
+        let dialects: Vec<_> = items
+            .into_iter()
+            .map(|item| {
+                item.data
+                    .and_then(CompletionData::decode)
+                    .and_then(|d| d.dialect)
+            })
+            .collect();
+        assert_eq!(
+            dialects,
+            vec![Some(Dialect::PostgreSQL), Some(Dialect::MySQL), None]
+        );
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Fuzzy ranking against the typed word
+//!
+//! When part of a name has been typed (`SELECT crtd|`), items whose name
+//! contains it as a subsequence move to the top, best match first, using
+//! the fuzzy mode of [`ResolutionConfig`].
+//!
+//! Ranking removes no item: clients filter on their own as the user keeps
+//! typing, and dropping items here would fight that. Matched items get a
+//! `filter_text` starting with the typed word so that clients filtering by
+//! prefix keep them too.
+//!
+//! Function lists are the exception, since a dialect registry holds
+//! hundreds of them: [`trim_functions`] keeps only those matching the typed
+//! word, or only the common ones before anything is typed.
+
+use tower_lsp::lsp_types::CompletionItem;
+use unified_sql_lsp_semantic::ResolutionConfig;
+
+use crate::completion::data::{CompletionData, CompletionSource};
+
+/// Minimum fuzzy score for an item to count as a match
+///
+/// A query whose first character starts a word (the `c` of `created_at`)
+/// reaches it; one matched only by scattered mid-word characters doesn't.
+const MIN_FUZZY_SCORE: u32 = 7;
+
+/// Identifier characters directly before the cursor, if any
+pub fn typed_word(text_before_cursor: &str) -> Option<&str> {
+    let start = text_before_cursor
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+        .last()
+        .map(|(idx, _)| idx)?;
+    Some(&text_before_cursor[start..])
+}
+
+/// Rank items by how well their name matches the typed word
+///
+/// Matches sort first by descending score, keeping their original
+/// `sort_text` order among equal scores; everything else follows in its
+/// original order.
+pub fn rank_by_typed_word(mut items: Vec<CompletionItem>, typed: &str) -> Vec<CompletionItem> {
+    let config = ResolutionConfig::fuzzy(MIN_FUZZY_SCORE);
+
+    for item in &mut items {
+        let name = item
+            .filter_text
+            .as_deref()
+            .unwrap_or(&item.label)
+            .rsplit('.')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let sort_text = item.sort_text.take().unwrap_or_else(|| item.label.clone());
+
+        match config.fuzzy_match(typed, &name) {
+            Some(score) => {
+                item.sort_text = Some(format!("0_{:010}_{}", u32::MAX - score, sort_text));
+                item.filter_text = Some(format!("{}{}", typed, name));
+            }
+            None => item.sort_text = Some(format!("1_{}", sort_text)),
+        }
+    }
+
+    items
+}
+
+/// Drop function items not matching the typed word
+///
+/// With a typed word, functions whose name starts with it or fuzzily
+/// matches it are kept; without one, only those named in `common`. Other
+/// items are kept either way. Returns whether any function was dropped, in
+/// which case the list should be marked incomplete so the client asks again
+/// as the user types.
+pub fn trim_functions(
+    items: Vec<CompletionItem>,
+    typed: Option<&str>,
+    common: &[String],
+) -> (Vec<CompletionItem>, bool) {
+    let config = ResolutionConfig::fuzzy(MIN_FUZZY_SCORE);
+    let before = items.len();
+
+    let items: Vec<CompletionItem> = items
+        .into_iter()
+        .filter(|item| {
+            let Some(name) = function_name(item) else {
+                return true;
+            };
+            match typed {
+                Some(typed) => {
+                    name.to_lowercase().starts_with(&typed.to_lowercase())
+                        || config.fuzzy_match(typed, &name).is_some()
+                }
+                None => common
+                    .iter()
+                    .any(|common| common.eq_ignore_ascii_case(&name)),
+            }
+        })
+        .collect();
+
+    let trimmed = items.len() < before;
+    (items, trimmed)
+}
+
+/// Name of the function a completion item offers, if it offers one
+fn function_name(item: &CompletionItem) -> Option<String> {
+    let data = CompletionData::decode(item.data.clone()?)?;
+    (data.source == CompletionSource::Function).then_some(data.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::CompletionItemKind;
+
+    fn item(label: &str, sort_text: &str) -> CompletionItem {
+        CompletionItem {
+            label: label.to_string(),
+            kind: Some(CompletionItemKind::FIELD),
+            sort_text: Some(sort_text.to_string()),
+            filter_text: Some(label.rsplit('.').next().unwrap().to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn function(name: &str) -> CompletionItem {
+        CompletionItem {
+            label: name.to_string(),
+            kind: Some(CompletionItemKind::CLASS),
+            data: Some(CompletionData::function(name).encode()),
+            ..Default::default()
+        }
+    }
+
+    fn labels(items: &[CompletionItem]) -> Vec<&str> {
+        items.iter().map(|i| i.label.as_str()).collect()
+    }
+
+    fn sorted_labels(items: &[CompletionItem]) -> Vec<&str> {
+        let mut sorted: Vec<&CompletionItem> = items.iter().collect();
+        sorted.sort_by(|a, b| a.sort_text.cmp(&b.sort_text));
+        sorted.iter().map(|i| i.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_typed_word() {
+        assert_eq!(typed_word("SELECT crtd"), Some("crtd"));
+        assert_eq!(typed_word("SELECT u.crtd"), Some("crtd"));
+        assert_eq!(typed_word("SELECT "), None);
+        assert_eq!(typed_word("SELECT u."), None);
+        assert_eq!(typed_word(""), None);
+    }
+
+    #[test]
+    fn test_rank_by_typed_word_keeps_all_items() {
+        let items = vec![
+            item("id", "00_pk_id"),
+            item("credit_card", "02_credit_card"),
+            item("name", "02_name"),
+            item("created_at", "02_created_at"),
+        ];
+
+        let ranked = rank_by_typed_word(items, "crtd");
+
+        assert_eq!(ranked.len(), 4);
+        assert_eq!(
+            sorted_labels(&ranked),
+            vec!["created_at", "credit_card", "id", "name"]
+        );
+
+        let created = ranked.iter().find(|i| i.label == "created_at").unwrap();
+        assert_eq!(created.filter_text.as_deref(), Some("crtdcreated_at"));
+        let id = ranked.iter().find(|i| i.label == "id").unwrap();
+        assert_eq!(id.filter_text.as_deref(), Some("id"));
+    }
+
+    #[test]
+    fn test_rank_by_typed_word_matches_unqualified_name() {
+        let items = vec![item("u.name", "02_name"), item("u.email", "02_email")];
+
+        let ranked = rank_by_typed_word(items, "nm");
+
+        assert_eq!(sorted_labels(&ranked), vec!["u.name", "u.email"]);
+    }
+
+    #[test]
+    fn test_trim_functions_to_common_set() {
+        let items = vec![
+            item("id", "02_id"),
+            function("COUNT"),
+            function("JSON_EXTRACT"),
+            function("now"),
+            function("SOUNDEX"),
+        ];
+        let common = vec!["count".to_string(), "NOW".to_string()];
+
+        let (items, trimmed) = trim_functions(items, None, &common);
+
+        assert!(trimmed);
+        assert_eq!(labels(&items), vec!["id", "COUNT", "now"]);
+    }
+
+    #[test]
+    fn test_trim_functions_to_typed_word() {
+        let items = || {
+            vec![
+                item("json_col", "02_json_col"),
+                function("JSON_EXTRACT"),
+                function("JSON_ARRAY"),
+                function("COUNT"),
+                function("SOUNDEX"),
+            ]
+        };
+
+        let (prefixed, trimmed) = trim_functions(items(), Some("json"), &[]);
+        assert!(trimmed);
+        assert_eq!(
+            labels(&prefixed),
+            vec!["json_col", "JSON_EXTRACT", "JSON_ARRAY"]
+        );
+
+        // Fuzzy matches count too; uncommon functions are offered once typed
+        let (fuzzy, _) = trim_functions(items(), Some("jsex"), &[]);
+        assert_eq!(labels(&fuzzy), vec!["json_col", "JSON_EXTRACT"]);
+        let (uncommon, _) = trim_functions(items(), Some("sou"), &[]);
+        assert_eq!(labels(&uncommon), vec!["json_col", "SOUNDEX"]);
+    }
+
+    #[test]
+    fn test_trim_functions_reports_nothing_dropped() {
+        let items = vec![item("id", "02_id"), function("COUNT")];
+        let (items, trimmed) = trim_functions(items, Some("cou"), &[]);
+        assert!(!trimmed);
+        assert_eq!(items.len(), 2);
+    }
+}
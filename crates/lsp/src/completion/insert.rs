@@ -0,0 +1,72 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # INSERT column list completion
+//!
+//! Completion items for the column list of `INSERT INTO table (...)`.
+//! Columns the database fills in itself (`AUTO_INCREMENT`, `SERIAL`,
+//! identity) can be left out of the list: they are sorted after the others
+//! and marked "auto-generated".
+
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind};
+use unified_sql_lsp_catalog::{ColumnMetadata, format_data_type};
+
+use crate::completion::data::CompletionData;
+
+/// Render the columns of `table` that aren't `listed` yet, in table order
+pub fn render_insert_columns(
+    table: &str,
+    columns: &[ColumnMetadata],
+    listed: &[String],
+) -> Vec<CompletionItem> {
+    columns
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| !listed.iter().any(|l| l.eq_ignore_ascii_case(&column.name)))
+        .map(|(position, column)| {
+            let omittable = column.is_auto_generated();
+            let detail = if omittable {
+                "auto-generated".to_string()
+            } else {
+                format_data_type(&column.data_type)
+            };
+            CompletionItem {
+                label: column.name.clone(),
+                kind: Some(CompletionItemKind::FIELD),
+                detail: Some(detail),
+                sort_text: Some(format!("{}_{:04}", u8::from(omittable), position)),
+                filter_text: Some(column.name.clone()),
+                data: Some(CompletionData::column(table, &column.name).encode()),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unified_sql_lsp_catalog::DataType;
+
+    #[test]
+    fn test_auto_generated_columns_sort_last() {
+        let columns = vec![
+            ColumnMetadata::new("id", DataType::Integer)
+                .with_primary_key()
+                .with_default("nextval('users_id_seq'::regclass)"),
+            ColumnMetadata::new("email", DataType::Text),
+            ColumnMetadata::new("code", DataType::Integer).with_default("AUTO_INCREMENT"),
+            ColumnMetadata::new("name", DataType::Text).with_nullable(true),
+        ];
+
+        let mut items = render_insert_columns("users", &columns, &["NAME".to_string()]);
+        items.sort_by(|a, b| a.sort_text.cmp(&b.sort_text));
+        let labels: Vec<_> = items.iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(labels, vec!["email", "id", "code"]);
+        assert_eq!(items[0].detail.as_deref(), Some("Text"));
+        assert_eq!(items[1].detail.as_deref(), Some("auto-generated"));
+        assert_eq!(items[2].detail.as_deref(), Some("auto-generated"));
+    }
+}
@@ -0,0 +1,225 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Keyword-only completion
+//!
+//! Without a database connection or DDL files there are no tables or
+//! columns to offer (the catalog is a [`NullCatalog`]). Positions expecting
+//! them get the keywords of their clause and the builtin functions of the
+//! dialect instead, so completion stays usable from the keyboard. An
+//! informational item points at the missing schema source; it can be
+//! dismissed with `completion.schemaHint: false`.
+//!
+//! The catalog is never queried in this mode, so nothing fails (or gets
+//! logged) on every keystroke.
+//!
+//! [`NullCatalog`]: unified_sql_lsp_catalog::NullCatalog
+
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind, Documentation};
+use unified_sql_lsp_catalog::FunctionType;
+use unified_sql_lsp_context::{CompletionContext, KeywordProvider};
+
+use crate::completion::render::CompletionRenderer;
+use crate::config::CompletionConfig;
+
+/// Label of the informational item shown in place of tables and columns
+pub const SCHEMA_HINT_LABEL: &str = "connect a database for schema-aware completion";
+
+/// Whether completion in `ctx` offers tables or columns from the catalog
+pub fn needs_schema(ctx: &CompletionContext) -> bool {
+    matches!(
+        ctx,
+        CompletionContext::SelectProjection { .. }
+            | CompletionContext::DistinctOnList { .. }
+            | CompletionContext::FromClause { .. }
+            | CompletionContext::WhereClause { .. }
+            | CompletionContext::JoinCondition { .. }
+            | CompletionContext::OrderByClause { .. }
+            | CompletionContext::GroupByClause { .. }
+            | CompletionContext::HavingClause { .. }
+            | CompletionContext::ReturningClause { .. }
+            | CompletionContext::MergeClause { .. }
+            | CompletionContext::InsertColumnList { .. }
+    )
+}
+
+/// Render the keyword-only items for a context that [`needs_schema`]
+///
+/// `functions` renders the builtin functions of the dialect of a type (all
+/// for `None`); `typed` is the partially typed word, which accepting the
+/// hint leaves in place.
+pub fn render_keyword_only(
+    ctx: &CompletionContext,
+    provider: &KeywordProvider,
+    functions: impl Fn(Option<FunctionType>) -> Vec<CompletionItem>,
+    config: &CompletionConfig,
+    typed: Option<&str>,
+) -> Vec<CompletionItem> {
+    let keywords = |keywords: &[_]| CompletionRenderer::render_keywords(keywords);
+
+    let mut items = match ctx {
+        // Columns of a named table: nothing to offer but the hint
+        CompletionContext::SelectProjection {
+            qualifier: Some(_), ..
+        }
+        | CompletionContext::DistinctOnList {
+            qualifier: Some(_), ..
+        }
+        | CompletionContext::WhereClause {
+            qualifier: Some(_), ..
+        }
+        | CompletionContext::JoinCondition {
+            qualifier: Some(_), ..
+        }
+        | CompletionContext::OrderByClause {
+            qualifier: Some(_), ..
+        }
+        | CompletionContext::GroupByClause {
+            qualifier: Some(_), ..
+        }
+        | CompletionContext::HavingClause {
+            qualifier: Some(_), ..
+        } => Vec::new(),
+        CompletionContext::SelectProjection { .. } => {
+            let mut items = vec![CompletionRenderer::wildcard_item()];
+            items.extend(functions(None));
+            items.extend(keywords(&provider.keywords_after_clause("SELECT")));
+            items
+        }
+        CompletionContext::FromClause { .. } => {
+            let mut items = keywords(&provider.join_type_keywords().keywords);
+            items.extend(keywords(&provider.keywords_after_clause("FROM")));
+            items
+        }
+        CompletionContext::WhereClause { .. } => {
+            let mut items = functions(None);
+            items.extend(keywords(&provider.expression_keywords().keywords));
+            items.extend(keywords(&provider.keywords_after_clause("WHERE")));
+            items
+        }
+        CompletionContext::JoinCondition { .. } => {
+            let mut items = functions(Some(FunctionType::Scalar));
+            items.extend(keywords(&provider.expression_keywords().keywords));
+            items
+        }
+        CompletionContext::OrderByClause { .. } => {
+            let mut items = functions(None);
+            items.extend(keywords(&provider.sort_direction_keywords().keywords));
+            items.extend(keywords(&provider.keywords_after_clause("ORDER BY")));
+            items
+        }
+        CompletionContext::GroupByClause { .. } => {
+            let mut items = functions(None);
+            items.extend(keywords(&provider.keywords_after_clause("GROUP BY")));
+            items
+        }
+        CompletionContext::HavingClause { .. } => {
+            let mut items = functions(Some(FunctionType::Aggregate));
+            items.extend(keywords(&provider.expression_keywords().keywords));
+            items
+        }
+        CompletionContext::DistinctOnList { .. }
+        | CompletionContext::ReturningClause { .. }
+        | CompletionContext::MergeClause { .. } => functions(None),
+        _ => Vec::new(),
+    };
+
+    if config.schema_hint {
+        items.push(render_schema_hint(typed));
+    }
+    items
+}
+
+/// Informational item telling that no schema source is configured
+///
+/// Accepting it inserts the typed word again, leaving the text unchanged.
+pub fn render_schema_hint(typed: Option<&str>) -> CompletionItem {
+    CompletionItem {
+        label: SCHEMA_HINT_LABEL.to_string(),
+        kind: Some(CompletionItemKind::TEXT),
+        detail: Some("No schema source configured".to_string()),
+        documentation: Some(Documentation::String(
+            "Set `connectionString` or a DDL `schemaSource` to complete tables and columns. \
+             Hide this hint with `completion.schemaHint: false`."
+                .to_string(),
+        )),
+        insert_text: Some(typed.unwrap_or_default().to_string()),
+        // After every keyword and function
+        sort_text: Some("~".to_string()),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unified_sql_lsp_context::{Position, detect_completion_context_from_text};
+    use unified_sql_lsp_function_registry::FunctionRegistry;
+    use unified_sql_lsp_ir::Dialect;
+
+    fn complete(source: &str, config: &CompletionConfig) -> Vec<CompletionItem> {
+        let ctx =
+            detect_completion_context_from_text(Position::new(0, source.len() as u32), source);
+        assert!(needs_schema(&ctx), "{:?}", ctx);
+        let functions = FunctionRegistry::new().get_functions(Dialect::MySQL);
+        render_keyword_only(
+            &ctx,
+            &KeywordProvider::new(Dialect::MySQL),
+            |filter| CompletionRenderer::render_functions_with_config(&functions, filter, config),
+            config,
+            None,
+        )
+    }
+
+    fn has(items: &[CompletionItem], label: &str) -> bool {
+        items.iter().any(|i| i.label == label)
+    }
+
+    #[test]
+    fn test_clause_keywords_without_schema() {
+        let config = CompletionConfig::default();
+
+        let from = complete("SELECT * FROM ", &config);
+        assert!(has(&from, "WHERE"));
+        assert!(has(&from, "LEFT"));
+        assert!(!has(&from, "COUNT"));
+        let hint = from.last().unwrap();
+        assert_eq!(hint.label, SCHEMA_HINT_LABEL);
+        assert_eq!(hint.insert_text.as_deref(), Some(""));
+
+        let filter = complete("SELECT * FROM users WHERE ", &config);
+        assert!(has(&filter, "AND"));
+        assert!(has(&filter, "ORDER BY"));
+        assert!(has(&filter, "COALESCE"));
+        assert!(has(&filter, "UPPER"));
+        assert!(has(&filter, SCHEMA_HINT_LABEL));
+    }
+
+    #[test]
+    fn test_schema_hint_can_be_dismissed() {
+        let config = CompletionConfig {
+            schema_hint: false,
+            ..Default::default()
+        };
+        let items = complete("SELECT * FROM ", &config);
+        assert!(has(&items, "WHERE"));
+        assert!(!has(&items, SCHEMA_HINT_LABEL));
+
+        // A qualified column position has only the hint to offer
+        let ctx = CompletionContext::WhereClause {
+            tables: vec!["users".to_string()],
+            qualifier: Some("u".to_string()),
+        };
+        let items = render_keyword_only(
+            &ctx,
+            &KeywordProvider::new(Dialect::PostgreSQL),
+            |_| Vec::new(),
+            &CompletionConfig::default(),
+            Some("na"),
+        );
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].insert_text.as_deref(), Some("na"));
+    }
+}
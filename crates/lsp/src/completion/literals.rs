@@ -0,0 +1,275 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # String literal completion
+//!
+//! Completion items offered while the cursor is inside a string literal:
+//! - LIKE patterns (`%…%`, `…%`, `%…`)
+//! - Date format strings and their specifiers, chosen by dialect family
+//!   (`%Y-%m-%d` for MySQL `DATE_FORMAT`, `YYYY-MM-DD` for PostgreSQL `to_char`)
+//!
+//! Other strings get no items so typing a plain value isn't interrupted.
+
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind, Documentation, InsertTextFormat};
+use unified_sql_lsp_context::StringLiteralRole;
+use unified_sql_lsp_ir::Dialect;
+use unified_sql_lsp_ir::dialect::DialectFamily;
+
+use crate::config::CompletionConfig;
+
+/// LIKE patterns: label, snippet, plain text, description
+const LIKE_PATTERNS: &[(&str, &str, &str, &str)] = &[
+    ("%…%", "%${1}%", "%%", "Contains the text"),
+    ("…%", "${1}%", "%", "Starts with the text"),
+    ("%…", "%${1}", "%", "Ends with the text"),
+];
+
+/// MySQL `DATE_FORMAT` / `STR_TO_DATE` specifiers
+const MYSQL_SPECIFIERS: &[(&str, &str)] = &[
+    ("%Y", "Year, four digits"),
+    ("%y", "Year, two digits"),
+    ("%m", "Month, 01-12"),
+    ("%c", "Month, 1-12"),
+    ("%M", "Month name (January-December)"),
+    ("%b", "Abbreviated month name (Jan-Dec)"),
+    ("%d", "Day of the month, 01-31"),
+    ("%e", "Day of the month, 1-31"),
+    ("%j", "Day of the year, 001-366"),
+    ("%W", "Weekday name (Sunday-Saturday)"),
+    ("%a", "Abbreviated weekday name (Sun-Sat)"),
+    ("%H", "Hour, 00-23"),
+    ("%h", "Hour, 01-12"),
+    ("%i", "Minutes, 00-59"),
+    ("%s", "Seconds, 00-59"),
+    ("%f", "Microseconds, 000000-999999"),
+    ("%p", "AM or PM"),
+    ("%%", "A literal % character"),
+];
+
+/// Common MySQL date formats
+const MYSQL_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%Y-%m-%d %H:%i:%s",
+    "%d/%m/%Y",
+    "%m/%d/%Y",
+    "%H:%i:%s",
+    "%Y%m%d",
+];
+
+/// PostgreSQL `to_char` / `to_date` template patterns
+const POSTGRES_SPECIFIERS: &[(&str, &str)] = &[
+    ("YYYY", "Year, four digits"),
+    ("YY", "Year, two digits"),
+    ("MM", "Month, 01-12"),
+    ("Month", "Month name, blank-padded to 9 characters"),
+    ("Mon", "Abbreviated month name (Jan-Dec)"),
+    ("DD", "Day of the month, 01-31"),
+    ("DDD", "Day of the year, 001-366"),
+    ("Day", "Weekday name, blank-padded to 9 characters"),
+    ("Dy", "Abbreviated weekday name (Sun-Sat)"),
+    ("HH24", "Hour, 00-23"),
+    ("HH12", "Hour, 01-12"),
+    ("MI", "Minutes, 00-59"),
+    ("SS", "Seconds, 00-59"),
+    ("MS", "Milliseconds, 000-999"),
+    ("US", "Microseconds, 000000-999999"),
+    ("AM", "Meridiem indicator"),
+    ("TZ", "Time zone abbreviation"),
+];
+
+/// Common PostgreSQL date formats
+const POSTGRES_FORMATS: &[&str] = &[
+    "YYYY-MM-DD",
+    "YYYY-MM-DD HH24:MI:SS",
+    "DD/MM/YYYY",
+    "MM/DD/YYYY",
+    "HH24:MI:SS",
+    "YYYYMMDD",
+];
+
+/// Render completion items for a string literal
+///
+/// Returns `None` for [`StringLiteralRole::Generic`] strings.
+pub fn render_string_literal(
+    role: StringLiteralRole,
+    dialect: Dialect,
+    config: &CompletionConfig,
+) -> Option<Vec<CompletionItem>> {
+    match role {
+        StringLiteralRole::LikePattern => Some(render_like_patterns(config)),
+        StringLiteralRole::DateFormat => Some(render_date_formats(dialect)),
+        StringLiteralRole::Generic => None,
+    }
+}
+
+/// Render LIKE pattern items, as snippets when the client supports them
+pub fn render_like_patterns(config: &CompletionConfig) -> Vec<CompletionItem> {
+    LIKE_PATTERNS
+        .iter()
+        .enumerate()
+        .map(|(i, (label, snippet, plain, description))| {
+            let (insert_text, insert_text_format) = if config.snippet_support {
+                (snippet.to_string(), InsertTextFormat::SNIPPET)
+            } else {
+                (plain.to_string(), InsertTextFormat::PLAIN_TEXT)
+            };
+
+            CompletionItem {
+                label: label.to_string(),
+                kind: Some(CompletionItemKind::SNIPPET),
+                detail: Some("LIKE pattern".to_string()),
+                documentation: Some(Documentation::String(format!(
+                    "{}\n\n`%` matches any sequence of characters, `_` matches a single character",
+                    description
+                ))),
+                sort_text: Some(format!("{:02}", i)),
+                insert_text: Some(insert_text),
+                insert_text_format: Some(insert_text_format),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Render common date formats followed by the individual specifiers
+///
+/// Each format documents the specifiers it contains.
+pub fn render_date_formats(dialect: Dialect) -> Vec<CompletionItem> {
+    let (formats, specifiers) = match dialect.family() {
+        DialectFamily::MySQL => (MYSQL_FORMATS, MYSQL_SPECIFIERS),
+        DialectFamily::PostgreSQL => (POSTGRES_FORMATS, POSTGRES_SPECIFIERS),
+    };
+
+    let format_items = formats.iter().enumerate().map(|(i, format)| {
+        let explained: Vec<String> = format_specifiers(format, specifiers)
+            .into_iter()
+            .map(|(token, description)| format!("{}: {}", token, description))
+            .collect();
+
+        CompletionItem {
+            label: format.to_string(),
+            kind: Some(CompletionItemKind::VALUE),
+            detail: Some("Date format".to_string()),
+            documentation: Some(Documentation::String(explained.join("\n"))),
+            sort_text: Some(format!("0_{:02}", i)),
+            ..Default::default()
+        }
+    });
+
+    let specifier_items = specifiers
+        .iter()
+        .enumerate()
+        .map(|(i, (token, description))| CompletionItem {
+            label: token.to_string(),
+            kind: Some(CompletionItemKind::CONSTANT),
+            detail: Some("Format specifier".to_string()),
+            documentation: Some(Documentation::String(description.to_string())),
+            sort_text: Some(format!("1_{:02}", i)),
+            ..Default::default()
+        });
+
+    format_items.chain(specifier_items).collect()
+}
+
+/// Split a format string into the specifiers it uses, in order of appearance
+///
+/// The longest specifier matching at each position wins, so `DDD` is not read
+/// as `DD` followed by `D`. Separators are skipped.
+fn format_specifiers(
+    format: &str,
+    specifiers: &[(&'static str, &'static str)],
+) -> Vec<(&'static str, &'static str)> {
+    let mut found: Vec<(&'static str, &'static str)> = Vec::new();
+    let mut rest = format;
+
+    while let Some(c) = rest.chars().next() {
+        let longest = specifiers
+            .iter()
+            .filter(|(token, _)| rest.starts_with(token))
+            .max_by_key(|(token, _)| token.len());
+
+        match longest {
+            Some(&(token, description)) => {
+                if !found.iter().any(|(t, _)| *t == token) {
+                    found.push((token, description));
+                }
+                rest = &rest[token.len()..];
+            }
+            None => rest = &rest[c.len_utf8()..],
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn documentation(item: &CompletionItem) -> &str {
+        match &item.documentation {
+            Some(Documentation::String(s)) => s,
+            _ => "",
+        }
+    }
+
+    #[test]
+    fn test_like_patterns_use_snippets_when_supported() {
+        let config = CompletionConfig {
+            snippet_support: true,
+            ..Default::default()
+        };
+        let items = render_like_patterns(&config);
+
+        assert_eq!(items[0].insert_text.as_deref(), Some("%${1}%"));
+        assert_eq!(items[1].insert_text.as_deref(), Some("${1}%"));
+        assert_eq!(items[0].insert_text_format, Some(InsertTextFormat::SNIPPET));
+
+        let plain = render_like_patterns(&CompletionConfig::default());
+        assert_eq!(plain[0].insert_text.as_deref(), Some("%%"));
+        assert_eq!(
+            plain[0].insert_text_format,
+            Some(InsertTextFormat::PLAIN_TEXT)
+        );
+    }
+
+    #[test]
+    fn test_date_formats_follow_dialect() {
+        let mysql = render_date_formats(Dialect::MySQL);
+        let item = mysql.iter().find(|i| i.label == "%Y-%m-%d").unwrap();
+        assert_eq!(
+            documentation(item),
+            "%Y: Year, four digits\n%m: Month, 01-12\n%d: Day of the month, 01-31"
+        );
+        assert!(!mysql.iter().any(|i| i.label == "YYYY-MM-DD"));
+
+        let postgres = render_date_formats(Dialect::PostgreSQL);
+        let item = postgres
+            .iter()
+            .find(|i| i.label == "YYYY-MM-DD HH24:MI:SS")
+            .unwrap();
+        assert!(documentation(item).contains("HH24: Hour, 00-23"));
+        assert!(!postgres.iter().any(|i| i.label == "%Y-%m-%d"));
+    }
+
+    #[test]
+    fn test_format_specifiers_prefer_longest_match() {
+        let found = format_specifiers("DDD-DD", POSTGRES_SPECIFIERS);
+        let tokens: Vec<_> = found.iter().map(|(t, _)| *t).collect();
+        assert_eq!(tokens, vec!["DDD", "DD"]);
+    }
+
+    #[test]
+    fn test_generic_string_yields_nothing() {
+        let config = CompletionConfig::default();
+        assert!(
+            render_string_literal(StringLiteralRole::Generic, Dialect::MySQL, &config).is_none()
+        );
+        assert!(
+            render_string_literal(StringLiteralRole::LikePattern, Dialect::MySQL, &config)
+                .is_some()
+        );
+    }
+}
@@ -0,0 +1,411 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Completion item merging
+//!
+//! Completion items are gathered from several sources (catalog tables, CTEs,
+//! columns, functions, keywords). This module provides the final stage that
+//! removes duplicates and disambiguates colliding labels so that clients which
+//! dedupe by label still show every distinct suggestion.
+//!
+//! ## Rules
+//!
+//! - CTEs shadow catalog tables of the same name (the catalog table is dropped)
+//! - Same label and same source collapse into one item, keeping the richest detail
+//! - Same label with different sources are all kept, each with a distinct
+//!   `label_details` describing its source (e.g. "(CTE)" vs "(table)")
+//!
+//! The source of an item is the [`CompletionSource`] its renderer recorded in
+//! the item's [`CompletionData`]; items without data have no source.
+
+use std::collections::{HashMap, HashSet};
+
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemLabelDetails, Documentation};
+
+use crate::completion::data::{CompletionData, CompletionSource};
+
+/// Source recorded in the data of `item`
+fn source_of(item: &CompletionItem) -> Option<CompletionSource> {
+    let data = CompletionData::decode(item.data.clone()?)?;
+    Some(data.source)
+}
+
+/// Name of a source shown next to colliding labels
+fn source_label(source: CompletionSource) -> &'static str {
+    match source {
+        CompletionSource::Cte => "CTE",
+        CompletionSource::Table => "table",
+        CompletionSource::View => "view",
+        CompletionSource::Column => "column",
+        CompletionSource::Function => "function",
+        CompletionSource::Keyword => "keyword",
+    }
+}
+
+fn is_relation(source: Option<CompletionSource>) -> bool {
+    matches!(
+        source,
+        Some(CompletionSource::Table | CompletionSource::View)
+    )
+}
+
+/// Deduplicate and merge completion items gathered from different sources
+///
+/// Labels are compared case-insensitively when detecting collisions, so a
+/// `desc` column and the `DESC` keyword are treated as colliding. The order of
+/// first occurrence is preserved.
+///
+/// # Examples
+///
+/// ```
+/// # use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind};
+/// # use unified_sql_lsp_lsp::completion::data::CompletionData;
+/// # use unified_sql_lsp_lsp::completion::merge::merge_completion_items;
+/// let id = CompletionData::column("users", "id");
+/// let items = vec![
+///     id.clone().attach(CompletionItem {
+///         label: "id".to_string(),
+///         kind: Some(CompletionItemKind::FIELD),
+///         ..Default::default()
+///     }),
+///     id.attach(CompletionItem {
+///         label: "id".to_string(),
+///         kind: Some(CompletionItemKind::FIELD),
+///         detail: Some("Integer".to_string()),
+///         ..Default::default()
+///     }),
+/// ];
+/// let merged = merge_completion_items(items);
+/// assert_eq!(merged.len(), 1);
+/// assert_eq!(merged[0].detail.as_deref(), Some("Integer"));
+/// ```
+pub fn merge_completion_items(items: Vec<CompletionItem>) -> Vec<CompletionItem> {
+    let items: Vec<(Option<CompletionSource>, CompletionItem)> = items
+        .into_iter()
+        .map(|item| (source_of(&item), item))
+        .collect();
+
+    // CTE names shadow catalog relations with the same name
+    let cte_names: HashSet<String> = items
+        .iter()
+        .filter(|(source, _)| *source == Some(CompletionSource::Cte))
+        .map(|(_, item)| item.label.to_lowercase())
+        .collect();
+
+    // Collapse same label + same source, keeping first-occurrence order.
+    // Sources are compared rather than kinds because functions share CLASS
+    // with tables.
+    let mut merged: Vec<(Option<CompletionSource>, CompletionItem)> =
+        Vec::with_capacity(items.len());
+    let mut index: HashMap<(String, Option<CompletionSource>), usize> = HashMap::new();
+
+    for (source, item) in items {
+        if is_relation(source) && cte_names.contains(&item.label.to_lowercase()) {
+            continue;
+        }
+
+        let key = (item.label.clone(), source);
+        match index.get(&key) {
+            Some(&existing) => merge_into(&mut merged[existing].1, item),
+            None => {
+                index.insert(key, merged.len());
+                merged.push((source, item));
+            }
+        }
+    }
+
+    // Disambiguate labels shared by items of different sources
+    let mut sources_by_label: HashMap<String, HashSet<CompletionSource>> = HashMap::new();
+    for (source, item) in &merged {
+        if let Some(source) = source {
+            sources_by_label
+                .entry(item.label.to_lowercase())
+                .or_default()
+                .insert(*source);
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|(source, mut item)| {
+            let collides = sources_by_label
+                .get(&item.label.to_lowercase())
+                .is_some_and(|sources| sources.len() > 1);
+            if let Some(source) = source
+                && collides
+                && item.label_details.is_none()
+            {
+                item.label_details = Some(CompletionItemLabelDetails {
+                    detail: Some(format!(" ({})", source_label(source))),
+                    description: None,
+                });
+            }
+            item
+        })
+        .collect()
+}
+
+/// Merge a duplicate item into the one already kept
+///
+/// The longer detail wins, and missing documentation is filled in.
+fn merge_into(kept: &mut CompletionItem, duplicate: CompletionItem) {
+    if !has_documentation(kept) && has_documentation(&duplicate) {
+        kept.documentation = duplicate.documentation;
+    }
+
+    let kept_len = kept.detail.as_ref().map_or(0, String::len);
+    let dup_len = duplicate.detail.as_ref().map_or(0, String::len);
+    if dup_len > kept_len {
+        kept.detail = duplicate.detail;
+    }
+
+    if duplicate.preselect == Some(true) {
+        kept.preselect = Some(true);
+    }
+}
+
+fn has_documentation(item: &CompletionItem) -> bool {
+    match &item.documentation {
+        Some(Documentation::String(s)) => !s.is_empty(),
+        Some(Documentation::MarkupContent(m)) => !m.value.is_empty(),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::CompletionItemKind;
+
+    fn item(
+        label: &str,
+        kind: CompletionItemKind,
+        detail: &str,
+        data: CompletionData,
+    ) -> CompletionItem {
+        data.attach(CompletionItem {
+            label: label.to_string(),
+            kind: Some(kind),
+            detail: Some(detail.to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn label_detail(item: &CompletionItem) -> Option<&str> {
+        item.label_details.as_ref()?.detail.as_deref()
+    }
+
+    #[test]
+    fn test_cte_shadows_catalog_table() {
+        let items = vec![
+            item(
+                "orders",
+                CompletionItemKind::CLASS,
+                "public.orders [TABLE]",
+                CompletionData::table("orders"),
+            ),
+            item(
+                "users",
+                CompletionItemKind::CLASS,
+                "public.users [TABLE]",
+                CompletionData::table("users"),
+            ),
+            item(
+                "orders",
+                CompletionItemKind::VARIABLE,
+                "CTE: orders",
+                CompletionData::cte("orders"),
+            ),
+        ];
+
+        let merged = merge_completion_items(items);
+
+        let orders: Vec<_> = merged.iter().filter(|i| i.label == "orders").collect();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].detail.as_deref(), Some("CTE: orders"));
+        assert!(orders[0].label_details.is_none());
+        assert!(merged.iter().any(|i| i.label == "users"));
+    }
+
+    #[test]
+    fn test_cte_shadowing_is_case_insensitive() {
+        let items = vec![
+            item(
+                "Orders",
+                CompletionItemKind::CLASS,
+                "Table: Orders",
+                CompletionData::table("Orders"),
+            ),
+            item(
+                "orders",
+                CompletionItemKind::CLASS,
+                "CTE: orders",
+                CompletionData::cte("orders"),
+            ),
+        ];
+
+        let merged = merge_completion_items(items);
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].detail.as_deref(), Some("CTE: orders"));
+    }
+
+    #[test]
+    fn test_column_keyword_collision_keeps_both() {
+        let items = vec![
+            item(
+                "desc",
+                CompletionItemKind::FIELD,
+                "Text",
+                CompletionData::column("t", "desc"),
+            ),
+            item(
+                "DESC",
+                CompletionItemKind::KEYWORD,
+                "SQL keyword",
+                CompletionData::keyword("DESC"),
+            ),
+            item(
+                "ASC",
+                CompletionItemKind::KEYWORD,
+                "SQL keyword",
+                CompletionData::keyword("ASC"),
+            ),
+        ];
+
+        let merged = merge_completion_items(items);
+
+        assert_eq!(merged.len(), 3);
+        let column = merged.iter().find(|i| i.label == "desc").unwrap();
+        let keyword = merged.iter().find(|i| i.label == "DESC").unwrap();
+        assert_eq!(label_detail(column), Some(" (column)"));
+        assert_eq!(label_detail(keyword), Some(" (keyword)"));
+
+        // Non-colliding items are left untouched
+        let asc = merged.iter().find(|i| i.label == "ASC").unwrap();
+        assert!(asc.label_details.is_none());
+    }
+
+    #[test]
+    fn test_same_label_same_kind_keeps_richest_detail() {
+        let items = vec![
+            item(
+                "users",
+                CompletionItemKind::CLASS,
+                "Table: users",
+                CompletionData::table("users"),
+            ),
+            item(
+                "id",
+                CompletionItemKind::FIELD,
+                "Integer",
+                CompletionData::column("users", "id"),
+            ),
+            item(
+                "users",
+                CompletionItemKind::CLASS,
+                "public.users [TABLE]",
+                CompletionData::table("users"),
+            ),
+        ];
+
+        let merged = merge_completion_items(items);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].label, "users");
+        assert_eq!(merged[0].detail.as_deref(), Some("public.users [TABLE]"));
+        assert_eq!(merged[1].label, "id");
+    }
+
+    #[test]
+    fn test_table_function_collision_labels() {
+        let function = item(
+            "users",
+            CompletionItemKind::CLASS,
+            "users() -> Integer",
+            CompletionData::function("users"),
+        );
+        let items = vec![
+            item(
+                "users",
+                CompletionItemKind::CLASS,
+                "public.users [TABLE]",
+                CompletionData::table("users"),
+            ),
+            function,
+        ];
+
+        let merged = merge_completion_items(items);
+
+        // Same kind but different sources are not collapsed
+        assert_eq!(merged.len(), 2);
+        assert_eq!(label_detail(&merged[0]), Some(" (table)"));
+        assert_eq!(label_detail(&merged[1]), Some(" (function)"));
+    }
+
+    #[test]
+    fn test_source_comes_from_data_not_detail() {
+        // Details worded like another source don't change the source
+        let items = vec![
+            item(
+                "orders",
+                CompletionItemKind::CLASS,
+                "CTE: orders",
+                CompletionData::table("orders"),
+            ),
+            item(
+                "orders",
+                CompletionItemKind::CLASS,
+                "orders() -> Integer",
+                CompletionData::table("orders"),
+            ),
+            item(
+                "recent",
+                CompletionItemKind::CLASS,
+                "recent [VIEW]",
+                CompletionData::table("recent"),
+            ),
+            item(
+                "recent",
+                CompletionItemKind::CLASS,
+                "Table: recent",
+                CompletionData::view("recent"),
+            ),
+        ];
+
+        let merged = merge_completion_items(items);
+
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0].detail.as_deref(), Some("orders() -> Integer"));
+        assert!(merged[0].label_details.is_none());
+        assert_eq!(label_detail(&merged[1]), Some(" (table)"));
+        assert_eq!(label_detail(&merged[2]), Some(" (view)"));
+    }
+
+    #[test]
+    fn test_items_without_data_have_no_source() {
+        let untagged = CompletionItem {
+            label: "users".to_string(),
+            kind: Some(CompletionItemKind::CLASS),
+            detail: Some("CTE".to_string()),
+            ..Default::default()
+        };
+        let items = vec![
+            item(
+                "users",
+                CompletionItemKind::CLASS,
+                "public.users [TABLE]",
+                CompletionData::table("users"),
+            ),
+            untagged,
+        ];
+
+        let merged = merge_completion_items(items);
+
+        // Not treated as a CTE shadowing the table, nor labeled
+        assert_eq!(merged.len(), 2);
+        assert!(merged.iter().all(|item| item.label_details.is_none()));
+    }
+}
@@ -14,6 +14,13 @@
 //! - `scopes`: Builds semantic scopes from CST nodes
 //! - `catalog_integration`: Fetches schema information from the catalog
 //! - `render`: Converts semantic symbols to LSP completion items
+//! - `literals`: LIKE patterns and date formats inside string literals
+//! - `types`: Type names after a `::` cast
+//! - `column_definition`: Column types and attributes in CREATE TABLE
+//! - `keyword_only`: Keywords and builtin functions when no schema source is configured
+//! - `merge`: Deduplicates completion items gathered from different sources
+//! - `fuzzy`: Ranks items against a partially typed word
+//! - `stats`: Local counters of completion results per context
 //! - `error`: Error types for completion operations
 //!
 //! ## Flow
@@ -29,52 +36,172 @@
 //!    ↓
 //! 5. CompletionEngine.render_completion()
 //!    ↓
-//! 6. Return CompletionResponse to client
+//! 6. trim_functions() (SELECT lists and WHERE clauses)
+//!    ↓
+//! 7. merge_completion_items() (dedup across sources)
+//!    ↓
+//! 8. rank_by_typed_word() (when part of a name is typed)
+//!    ↓
+//! 9. CompletionRenderer::apply_text_edits() (replace the typed token)
+//!    ↓
+//! 10. Return CompletionResponse to client
 //! ```
+//!
+//! ## Latency Budget
+//!
+//! With `CompletionConfig::latency_budget` set, catalog work races a timer.
+//! If the catalog loses, the items known without it are returned with
+//! `isIncomplete: true`, and the late result is kept in
+//! [`LateCatalogResults`] for the request the client retriggers.
 
 pub mod catalog_integration;
+pub mod collations;
+pub mod column_definition;
+pub mod comparison;
+pub mod data;
 pub mod error;
+pub mod fuzzy;
+pub mod insert;
+pub mod keyword_only;
+pub mod literals;
+pub mod merge;
+pub mod operators;
 pub mod render;
+pub mod roles;
+pub mod sequences;
+pub mod stats;
+pub mod types;
+pub mod values;
 
 // Note: alias_resolution and scopes modules are now provided by semantic and context crates
 // Note: context and keywords modules are now provided by unified_sql-lsp-context crate
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
+use std::future::Future;
 use std::sync::Arc;
-use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind, InsertTextFormat, Position};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tower_lsp::lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionList, InsertTextFormat, Position,
+};
 use tracing::{debug, instrument};
-use unified_sql_lsp_catalog::{Catalog, FunctionType};
-use unified_sql_lsp_ir::Dialect;
+use unified_sql_lsp_catalog::{Catalog, DataType, FunctionMetadata, FunctionType};
+use unified_sql_lsp_function_registry::FunctionRegistry;
+use unified_sql_lsp_ir::dialect::DialectFamily;
+use unified_sql_lsp_ir::{Dialect, DialectExtensions, IdentifierComparer};
 
 // Import from semantic crate (moved from LSP)
-use unified_sql_lsp_semantic::{CompletionService, CompletionTextHeuristics};
+use unified_sql_lsp_semantic::{
+    ColumnSymbol, CompletionService, CompletionTextHeuristics, TableSymbol,
+};
 
 // Import from context crate (moved from LSP)
-use unified_sql_lsp_context::ScopeBuilder;
+use unified_sql_lsp_context::{
+    ClosedParenthesis, ComparisonOperand, DEFAULT_MAX_NESTING_DEPTH, DetectedContext, ScopeBuilder,
+    StatementKind, detect_comparison_operand, detect_completed_operand, nesting_depth_at,
+    position_to_byte_offset, projection_aliases, projection_column_references,
+};
 
+use crate::catalog_manager::LateCatalogResults;
 use crate::completion::catalog_integration::CatalogCompletionFetcher;
+use crate::completion::collations::render_collation_names;
+use crate::completion::column_definition::render_column_definition;
+use crate::completion::comparison::{render_comparison_literals, render_value_list_subquery};
+use crate::completion::data::{CompletionData, CompletionSource, stamp_dialect};
 use crate::completion::error::CompletionError;
-use crate::completion::render::CompletionRenderer;
+use crate::completion::fuzzy::{rank_by_typed_word, trim_functions, typed_word};
+use crate::completion::insert::render_insert_columns;
+use crate::completion::keyword_only::{needs_schema, render_keyword_only};
+use crate::completion::literals::render_string_literal;
+use crate::completion::merge::merge_completion_items;
+use crate::completion::operators::{render_comparison_operators, render_operators};
+use crate::completion::render::{CompletionRenderer, TypedToken};
+use crate::completion::roles::render_role_names;
+use crate::completion::sequences::render_sequence_names;
+use crate::completion::stats::CompletionStats;
+use crate::completion::types::render_type_names;
+use crate::completion::values::render_values_row_literals;
+use crate::config::{CompletionConfig, SchemaFilter, ShowUnsupported};
 use crate::document::Document;
+use crate::document_catalog::DocumentCatalog;
+use crate::positions::to_context_position;
+use crate::prefetch::RecentTables;
+use crate::rules::CustomRules;
+use crate::table_references::visible_ctes;
 
 // Use context crate for keywords
-use unified_sql_lsp_context::KeywordProvider;
+use unified_sql_lsp_context::{DialectCapabilities, KeywordProvider};
 
 // Use context crate for context detection
 // Re-export the context types for backward compatibility
 pub use unified_sql_lsp_context::{CompletionContext, CompletionContext as SqlCompletionContext};
 
-/// Convert tower_lsp Position to context Position
-fn to_context_pos(pos: Position) -> unified_sql_lsp_context::Position {
-    unified_sql_lsp_context::Position::new(pos.line, pos.character)
+/// Resolve the qualifiers of projected `(column, qualifier)` references to
+/// table names through the aliases of `tables`
+///
+/// Returns `(table, column)` pairs; the table is `None` for unqualified columns.
+fn resolve_projected_columns(
+    references: Vec<(String, Option<String>)>,
+    tables: &[TableSymbol],
+) -> Vec<(Option<String>, String)> {
+    references
+        .into_iter()
+        .map(|(column, qualifier)| {
+            let table = qualifier.map(|qualifier| {
+                tables
+                    .iter()
+                    .find(|t| {
+                        t.alias
+                            .as_deref()
+                            .is_some_and(|alias| alias.eq_ignore_ascii_case(&qualifier))
+                    })
+                    .map_or(qualifier, |t| t.table_name.clone())
+            });
+            (table, column)
+        })
+        .collect()
+}
+
+/// Whether `item` is a column among the `selected` `(table, column)` pairs
+fn is_projected_column(item: &CompletionItem, selected: &[(Option<String>, String)]) -> bool {
+    let Some(data) = item.data.clone().and_then(CompletionData::decode) else {
+        return false;
+    };
+    data.source == CompletionSource::Column
+        && selected.iter().any(|(table, column)| {
+            column.eq_ignore_ascii_case(&data.name)
+                && table.as_deref().is_none_or(|table| {
+                    data.table
+                        .as_deref()
+                        .is_some_and(|t| t.eq_ignore_ascii_case(table))
+                })
+        })
 }
 
 /// Completion engine
 ///
 /// Orchestrates the completion flow from context detection to rendering.
+#[derive(Clone)]
 pub struct CompletionEngine {
     catalog_fetcher: Arc<CatalogCompletionFetcher>,
     dialect: Dialect,
+    config: CompletionConfig,
+    custom_rules: Arc<CustomRules>,
+    /// Version-dependent features; derived from the dialect when unset
+    capabilities: Option<DialectCapabilities>,
+    /// Names completed like columns in every scope, e.g. function parameters
+    parameters: Vec<ColumnSymbol>,
+    /// Schemas whose user-defined functions are offered
+    schema_filter: SchemaFilter,
+    /// Set when the latency budget ran out during the current request
+    budget_exceeded: Arc<AtomicBool>,
+    /// Set when function items were trimmed during the current request
+    functions_trimmed: Arc<AtomicBool>,
+    /// Counters updated by every completion, if collected
+    stats: Option<Arc<CompletionStats>>,
+    /// Subquery nesting levels analyzed; deeper ones complete keywords only
+    max_nesting_depth: usize,
+    /// Newer versions of the configured dialect, offered per `show_unsupported`
+    newer_versions: Vec<(String, DialectCapabilities)>,
 }
 
 impl CompletionEngine {
@@ -88,6 +215,147 @@ impl CompletionEngine {
         Self {
             catalog_fetcher: Arc::new(CatalogCompletionFetcher::new(catalog)),
             dialect,
+            config: CompletionConfig::default(),
+            custom_rules: Arc::new(CustomRules::default()),
+            capabilities: None,
+            parameters: Vec::new(),
+            schema_filter: SchemaFilter::default(),
+            budget_exceeded: Arc::new(AtomicBool::new(false)),
+            functions_trimmed: Arc::new(AtomicBool::new(false)),
+            stats: None,
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+            newer_versions: Vec::new(),
+        }
+    }
+
+    /// Set the subquery nesting levels analyzed
+    pub fn with_max_nesting_depth(mut self, max_nesting_depth: usize) -> Self {
+        self.max_nesting_depth = max_nesting_depth;
+        self
+    }
+
+    /// Set the dialect used for documents without parse metadata
+    pub fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Set the completion insert behaviors
+    pub fn with_config(mut self, config: CompletionConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Set the custom rules whose keywords are injected per context
+    pub fn with_custom_rules(mut self, custom_rules: Arc<CustomRules>) -> Self {
+        self.custom_rules = custom_rules;
+        self
+    }
+
+    /// Only offer user-defined functions of schemas allowed by `filter`
+    pub fn with_schema_filter(mut self, filter: SchemaFilter) -> Self {
+        self.schema_filter = filter;
+        self
+    }
+
+    /// Count each completion's context and result in `stats`
+    pub fn with_stats(mut self, stats: Arc<CompletionStats>) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// Set the version-dependent features of the configured dialect
+    pub fn with_capabilities(mut self, capabilities: DialectCapabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Set the newer versions of the configured dialect, see
+    /// [`DialectVersion::newer`]
+    ///
+    /// Their keywords and functions are offered marked as unsupported when
+    /// the config's `show_unsupported` is [`ShowUnsupported::Warn`].
+    ///
+    /// [`DialectVersion::newer`]: crate::config::DialectVersion::newer
+    pub fn with_newer_versions(mut self, newer: Vec<(String, DialectCapabilities)>) -> Self {
+        self.newer_versions = newer;
+        self
+    }
+
+    /// Offer `parameters` wherever unqualified columns are completed
+    ///
+    /// Used for embedded SQL, e.g. the parameters of a SQL function inside
+    /// its body, see [`crate::embedded`].
+    pub fn with_parameters(mut self, parameters: Vec<ColumnSymbol>) -> Self {
+        self.parameters = parameters;
+        self
+    }
+
+    /// Keep catalog results that outlive the latency budget in `results`
+    ///
+    /// Uses the budget from the config set by [`Self::with_config`], so call
+    /// it afterwards. Results already kept, e.g. by the startup prefetch,
+    /// are read with or without a budget.
+    pub fn with_late_results(mut self, results: &Arc<LateCatalogResults>) -> Self {
+        let catalog = results.wrap(self.catalog_fetcher.catalog(), self.config.latency_budget);
+        self.catalog_fetcher = Arc::new(CatalogCompletionFetcher::new(catalog));
+        self
+    }
+
+    /// Record the tables whose columns completions read in `recent`
+    ///
+    /// Call it after [`Self::with_late_results`] so reads served from kept
+    /// results count as uses too.
+    pub fn with_recent_tables(mut self, recent: &Arc<RecentTables>) -> Self {
+        let catalog = recent.wrap(self.catalog_fetcher.catalog());
+        self.catalog_fetcher = Arc::new(CatalogCompletionFetcher::new(catalog));
+        self
+    }
+
+    /// Perform completion, reporting whether the item list is partial
+    ///
+    /// The list is marked incomplete when the latency budget ran out before
+    /// the catalog answered, or when function items were trimmed to the typed
+    /// word, so the client asks again as the user types.
+    pub async fn complete_list(
+        &self,
+        document: &Document,
+        position: Position,
+    ) -> Result<Option<CompletionList>, CompletionError> {
+        self.budget_exceeded.store(false, Ordering::Relaxed);
+        self.functions_trimmed.store(false, Ordering::Relaxed);
+        let items = self.complete(document, position).await?;
+        Ok(items.map(|items| CompletionList {
+            is_incomplete: self.budget_exceeded.load(Ordering::Relaxed)
+                || self.functions_trimmed.load(Ordering::Relaxed),
+            items,
+        }))
+    }
+
+    /// Await catalog work for at most the latency budget
+    ///
+    /// Returns `None` when the budget ran out. The work keeps running in the
+    /// background so its catalog results still reach [`LateCatalogResults`].
+    async fn within_budget<T: Send + 'static>(
+        &self,
+        work: impl Future<Output = T> + Send + 'static,
+    ) -> Option<T> {
+        let Some(budget) = self.config.latency_budget else {
+            return Some(work.await);
+        };
+
+        let mut task = tokio::spawn(work);
+        match tokio::time::timeout(budget, &mut task).await {
+            Ok(Ok(value)) => Some(value),
+            Ok(Err(e)) => {
+                debug!(error = %e, "Catalog task failed");
+                None
+            }
+            Err(_) => {
+                debug!(?budget, "Catalog exceeded completion latency budget");
+                self.budget_exceeded.store(true, Ordering::Relaxed);
+                None
+            }
         }
     }
 
@@ -125,12 +393,41 @@ impl CompletionEngine {
         &self,
         document: &Document,
         position: Position,
+    ) -> Result<Option<Vec<CompletionItem>>, CompletionError> {
+        // The dialect the document was parsed with wins over the engine default,
+        // so keywords and literals never come from another document's dialect
+        let dialect = document
+            .parse_metadata()
+            .map_or(self.dialect, |metadata| metadata.dialect);
+        let mut engine = Self {
+            dialect,
+            ..self.clone()
+        };
+
+        // Tables created by earlier statements of the document
+        let overlay = DocumentCatalog::overlay(
+            engine.catalog_fetcher.catalog(),
+            document.uri(),
+            &document.get_content(),
+            position,
+        );
+        if let Some(catalog) = overlay {
+            engine.catalog_fetcher = Arc::new(CatalogCompletionFetcher::new(catalog));
+        }
+        engine.complete_in_document(document, position).await
+    }
+
+    /// Perform completion with the engine's catalog as is
+    async fn complete_in_document(
+        &self,
+        document: &Document,
+        position: Position,
     ) -> Result<Option<Vec<CompletionItem>>, CompletionError> {
         // Clone source to avoid holding document reference
         let source = document.get_content().to_string();
 
         // Get the parsed tree and do all synchronous parsing
-        let (ctx, scope_manager) = {
+        let (detected, has_parse_error, scope_manager, projected) = {
             let tree = document.tree().ok_or(CompletionError::NotParsed)?;
             let tree_lock = tree.try_lock().map_err(|_| CompletionError::NotParsed)?;
             let tree = tree_lock.clone();
@@ -140,23 +437,49 @@ impl CompletionEngine {
 
             // Detect completion context (synchronous)
             // Convert tower_lsp Position to context Position
-            let ctx = unified_sql_lsp_context::detect_completion_context(
-                &root_node,
-                to_context_pos(position),
+            let mut detected = unified_sql_lsp_context::detect_context(
+                Some(&root_node),
+                to_context_position(position),
                 &source,
             );
+            // Subqueries past the nesting cap aren't analyzed
+            let offset = position_to_byte_offset(&source, to_context_position(position));
+            if nesting_depth_at(&source, offset) > self.max_nesting_depth {
+                debug!(
+                    max_nesting_depth = self.max_nesting_depth,
+                    "Cursor nested past the cap, completing keywords only"
+                );
+                detected.context = CompletionContext::Keywords {
+                    statement_type: Some("SELECT".to_string()),
+                    existing_clauses: Vec::new(),
+                };
+            }
+            let ctx = &detected.context;
 
-            debug!(?ctx, "Detected completion context");
-            if let CompletionContext::FromClause { exclude_tables } = &ctx {
+            debug!(?ctx, statement_kind = ?detected.statement_kind, "Detected completion context");
+            if let CompletionContext::FromClause { exclude_tables } = ctx {
                 debug!(?exclude_tables, "FromClause context with excluded tables");
             }
 
             // Build scope synchronously if needed
-            let scope_manager = match &ctx {
+            let statement = &source[detected.statement.clone()];
+            let scope_manager = match ctx {
                 CompletionContext::SelectProjection { .. }
+                | CompletionContext::DistinctOnList { .. }
                 | CompletionContext::WhereClause { .. } => {
+                    // The WHERE of an UPDATE or DELETE only sees its own tables
+                    let dml_scope = match (ctx, detected.statement_kind) {
+                        (
+                            CompletionContext::WhereClause { .. },
+                            StatementKind::Update | StatementKind::Delete,
+                        ) => ScopeBuilder::build_from_dml(statement).ok(),
+                        _ => None,
+                    };
+
                     // Try to build scope from CST, but don't fail if it's incomplete
-                    match ScopeBuilder::build_from_select(&root_node, &source) {
+                    match dml_scope
+                        .map_or_else(|| ScopeBuilder::build_from_select(&root_node, &source), Ok)
+                    {
                         Ok(scope) => Some(scope),
                         Err(e) => {
                             debug!(error = ?e, "Failed to build scope from CST, will use context_tables");
@@ -164,11 +487,44 @@ impl CompletionEngine {
                         }
                     }
                 }
+                // RETURNING of an UPDATE or DELETE sees the target under its
+                // alias and the FROM or USING tables
+                CompletionContext::ReturningClause { .. }
+                    if self.dialect.supports(DialectExtensions::Returning) =>
+                {
+                    ScopeBuilder::build_from_dml(statement).ok()
+                }
+                CompletionContext::MergeClause { target_only, .. } => {
+                    match ScopeBuilder::build_from_merge(&root_node, &source, *target_only) {
+                        Ok(scope) => Some(scope),
+                        Err(e) => {
+                            debug!(error = ?e, "Failed to build MERGE scope, will use context tables");
+                            None
+                        }
+                    }
+                }
                 _ => None,
             };
 
-            (ctx, scope_manager)
+            // Columns already in the projection, read from the tree when it's intact
+            let projected = match ctx {
+                CompletionContext::SelectProjection { .. } => {
+                    projection_column_references(&root_node, &source, to_context_position(position))
+                }
+                _ => None,
+            };
+
+            (detected, root_node.has_error(), scope_manager, projected)
         }; // root_node and tree_lock dropped here
+        let DetectedContext {
+            context: ctx,
+            stage,
+            statement,
+            in_using_clause,
+            in_case_expression,
+            has_group_by,
+            ..
+        } = detected;
 
         debug!(
             scope_manager_exists = scope_manager.is_some(),
@@ -176,8 +532,41 @@ impl CompletionEngine {
             "Context detection complete"
         );
 
+        let custom_keywords = self.custom_rules.keywords_for(&ctx);
+        let parameter_items = self.parameter_items(&ctx);
+        let operator_items = self
+            .operator_items(&ctx, &scope_manager, &source, position)
+            .await;
+        let context_kind = ctx.kind();
+
+        // Partially typed name to rank against and replace (not inside string literals)
+        let in_string = matches!(ctx, CompletionContext::StringLiteral { .. });
+        let text_before_cursor = Self::text_before_cursor(document, position);
+        let typed = text_before_cursor
+            .as_deref()
+            .filter(|_| !in_string)
+            .and_then(typed_word);
+        let token = TypedToken::at(&source, position).filter(|_| !in_string);
+
+        // Unqualified expressions are where whole function registries show up
+        let trims_functions = matches!(
+            ctx,
+            CompletionContext::SelectProjection {
+                qualifier: None,
+                ..
+            } | CompletionContext::WhereClause {
+                qualifier: None,
+                ..
+            }
+        );
+
         // Now handle async operations with only owned data
-        match ctx {
+        let result = match ctx {
+            // No schema source: never ask the catalog for tables or columns
+            ctx if needs_schema(&ctx) && !self.catalog_fetcher.catalog().has_schema() => {
+                debug!(?ctx, "No schema source, completing keywords only");
+                Ok(Some(self.complete_without_schema(&ctx, typed)))
+            }
             CompletionContext::SelectProjection { tables, qualifier } => {
                 eprintln!(
                     "!!! LSP: SelectProjection with tables={:?}, qualifier={:?}",
@@ -188,17 +577,23 @@ impl CompletionEngine {
                     &scope_manager,
                     tables,
                     qualifier,
-                    &source,
-                    document,
+                    projected,
+                    &source[statement.clone()],
+                    in_case_expression,
                 )
                 .await
             }
+            CompletionContext::DistinctOnList { tables, qualifier } => {
+                self.complete_distinct_on_list(&scope_manager, tables, qualifier)
+                    .await
+            }
             CompletionContext::FromClause { exclude_tables } => {
                 self.complete_from_clause(document, position, exclude_tables)
                     .await
             }
             CompletionContext::WhereClause { tables, qualifier } => {
-                self.complete_where_clause(&scope_manager, tables, qualifier)
+                let operand = detect_comparison_operand(&source, to_context_position(position));
+                self.complete_where_clause(&scope_manager, tables, qualifier, operand, has_group_by)
                     .await
             }
             CompletionContext::JoinCondition {
@@ -237,14 +632,12 @@ impl CompletionEngine {
                     "Resolved tables for JOIN"
                 );
 
-                // Determine if we should force qualification.
-                let force_qualifier = CompletionTextHeuristics::should_force_join_qualifier(
-                    &source,
-                    tables_with_columns.len(),
-                );
+                // USING takes bare column names; ON needs qualifiers once
+                // several tables are joined
+                let force_qualifier = !in_using_clause && tables_with_columns.len() > 1;
 
                 // Fetch functions from catalog (scalar functions only for JOINs)
-                let functions = self.catalog_fetcher.list_functions().await?;
+                let functions = self.list_functions().await?;
 
                 debug!(
                     tables_count = tables_to_render.len(),
@@ -252,12 +645,14 @@ impl CompletionEngine {
                 );
 
                 // Render with PK/FK prioritization
-                let mut items =
-                    CompletionRenderer::render_join_columns(&tables_to_render, force_qualifier);
+                let mut items = CompletionRenderer::render_join_columns_with_config(
+                    &tables_to_render,
+                    force_qualifier,
+                    &self.config,
+                );
 
                 // Add function completion items (scalar functions only for JOINs)
-                let function_items =
-                    CompletionRenderer::render_functions(&functions, Some(FunctionType::Scalar));
+                let function_items = self.render_functions(&functions, Some(FunctionType::Scalar));
                 items.extend(function_items);
 
                 debug!(
@@ -276,7 +671,7 @@ impl CompletionEngine {
                     .parse_metadata()
                     .map(|m| m.dialect)
                     .unwrap_or(self.dialect);
-                let provider = KeywordProvider::new(dialect);
+                let provider = self.keyword_provider(dialect);
 
                 // Render completion items
                 let items = if let Some(stmt_type) = &statement_type {
@@ -297,7 +692,9 @@ impl CompletionEngine {
                             // Get table names for UPDATE statement
                             let tables =
                                 self.catalog_fetcher.list_tables().await.unwrap_or_default();
-                            let table_items = CompletionRenderer::render_tables(&tables, false);
+                            let table_items = CompletionRenderer::render_tables_for_dialect(
+                                &tables, false, dialect,
+                            );
 
                             // Render keywords
                             let keyword_items = CompletionRenderer::render_keywords(&keywords);
@@ -316,7 +713,9 @@ impl CompletionEngine {
                             // Get table names for DELETE statement
                             let tables =
                                 self.catalog_fetcher.list_tables().await.unwrap_or_default();
-                            let table_items = CompletionRenderer::render_tables(&tables, false);
+                            let table_items = CompletionRenderer::render_tables_for_dialect(
+                                &tables, false, dialect,
+                            );
 
                             // Render keywords
                             let keyword_items = CompletionRenderer::render_keywords(&keywords);
@@ -346,21 +745,44 @@ impl CompletionEngine {
                             let keywords = provider.union_keywords().keywords;
                             CompletionRenderer::render_keywords(&keywords)
                         }
+                        "MERGE" => {
+                            let keywords = provider.merge_keywords().keywords;
+                            CompletionRenderer::render_keywords(&keywords)
+                        }
+                        "VALUES" => {
+                            let keywords = provider.values_keywords().keywords;
+                            CompletionRenderer::render_keywords(&keywords)
+                        }
+                        // Rest of a join type, e.g. `LEFT |`; no table fits here
+                        "INNER" | "LEFT" | "RIGHT" | "FULL" | "CROSS" | "NATURAL" | "OUTER" => {
+                            let keywords = provider.join_continuation_keywords(stmt_type).keywords;
+                            CompletionRenderer::render_keywords(&keywords)
+                        }
+                        "MERGE WHEN" => {
+                            let keywords = provider.merge_action_keywords().keywords;
+                            CompletionRenderer::render_keywords(&keywords)
+                        }
                         _ => {
                             let keywords = provider.select_clause_keywords().keywords;
                             CompletionRenderer::render_keywords(&keywords)
                         }
                     }
                 } else {
-                    // No statement type, show statement keywords
+                    // Start of a statement: statement keywords and templates
                     let keywords = provider.statement_keywords().keywords;
-                    CompletionRenderer::render_keywords(&keywords)
+                    let mut items = CompletionRenderer::render_keywords(&keywords);
+                    items.extend(CompletionRenderer::render_statement_templates(
+                        &provider.statement_templates(),
+                        &self.config,
+                    ));
+                    items
                 };
 
                 Ok(Some(items))
             }
             CompletionContext::OrderByClause { tables, qualifier } => {
-                self.complete_order_by_clause(&scope_manager, tables, qualifier)
+                let aliases = projection_aliases(&source, to_context_position(position));
+                self.complete_order_by_clause(&scope_manager, tables, qualifier, aliases)
                     .await
             }
             CompletionContext::GroupByClause { tables, qualifier } => {
@@ -369,7 +791,8 @@ impl CompletionEngine {
             }
             CompletionContext::LimitClause => self.complete_limit_clause().await,
             CompletionContext::HavingClause { tables, qualifier } => {
-                self.complete_having_clause(&scope_manager, tables, qualifier)
+                let aliases = projection_aliases(&source, to_context_position(position));
+                self.complete_having_clause(&scope_manager, tables, qualifier, aliases)
                     .await
             }
             CompletionContext::CteDefinition {
@@ -392,25 +815,357 @@ impl CompletionEngine {
                 self.complete_window_function_clause(&scope_manager, tables, window_part)
                     .await
             }
+            CompletionContext::WindowName { windows } => Ok(Some(
+                CompletionRenderer::render_window_names(&windows, &self.config),
+            )),
             CompletionContext::ReturningClause { tables, qualifier } => {
                 self.complete_returning_clause(&scope_manager, tables, qualifier)
                     .await
             }
+            CompletionContext::MergeClause {
+                target,
+                source: merge_source,
+                target_only,
+                qualifier,
+            } => {
+                let tables = if target_only {
+                    vec![target]
+                } else {
+                    std::iter::once(target).chain(merge_source).collect()
+                };
+                self.complete_merge_clause(&scope_manager, tables, qualifier)
+                    .await
+            }
+            CompletionContext::StringLiteral { role } => {
+                let dialect = document
+                    .parse_metadata()
+                    .map(|m| m.dialect)
+                    .unwrap_or(self.dialect);
+                Ok(render_string_literal(role, dialect, &self.config))
+            }
+            CompletionContext::InsertColumnList { table, listed } => {
+                let columns = self
+                    .catalog_fetcher
+                    .catalog()
+                    .get_columns(&table)
+                    .await
+                    .map_err(CompletionError::Catalog)?;
+                Ok(Some(render_insert_columns(&table, &columns, &listed)))
+            }
+            CompletionContext::ValuesRow {
+                column,
+                column_name,
+                first_row_value,
+            } => {
+                let dialect = document
+                    .parse_metadata()
+                    .map(|m| m.dialect)
+                    .unwrap_or(self.dialect);
+                Ok(Some(render_values_row_literals(
+                    column,
+                    column_name.as_deref(),
+                    first_row_value.as_deref(),
+                    dialect,
+                    self.config.snippet_support,
+                )))
+            }
+            CompletionContext::TypeName => {
+                let dialect = document
+                    .parse_metadata()
+                    .map(|m| m.dialect)
+                    .unwrap_or(self.dialect);
+                Ok(Some(render_type_names(dialect)))
+            }
+            CompletionContext::ColumnDefinition { data_type, .. } => {
+                let dialect = document
+                    .parse_metadata()
+                    .map(|m| m.dialect)
+                    .unwrap_or(self.dialect);
+                Ok(Some(render_column_definition(
+                    data_type.as_deref(),
+                    dialect,
+                    self.capabilities_for(dialect),
+                    &self.config,
+                )))
+            }
+            CompletionContext::CollationName { in_quotes } => {
+                let dialect = document
+                    .parse_metadata()
+                    .map(|m| m.dialect)
+                    .unwrap_or(self.dialect);
+                let collations = self.catalog_fetcher.list_collations(dialect).await;
+                Ok(Some(render_collation_names(
+                    &collations,
+                    in_quotes,
+                    dialect,
+                )))
+            }
+            CompletionContext::SequenceName { in_string } => {
+                let sequences = self.catalog_fetcher.list_sequences().await;
+                Ok(Some(render_sequence_names(&sequences, in_string)))
+            }
+            CompletionContext::RoleName { statement_type } => {
+                let dialect = document
+                    .parse_metadata()
+                    .map(|m| m.dialect)
+                    .unwrap_or(self.dialect);
+                let roles = self.catalog_fetcher.list_roles().await;
+                Ok(Some(render_role_names(&roles, &statement_type, dialect)))
+            }
+            CompletionContext::AfterParenthesis { closed } => {
+                let dialect = document
+                    .parse_metadata()
+                    .map(|m| m.dialect)
+                    .unwrap_or(self.dialect);
+                let keywords = self
+                    .keyword_provider(dialect)
+                    .after_parenthesis_keywords(closed)
+                    .keywords;
+                let mut items = match closed {
+                    ClosedParenthesis::DerivedTable => Vec::new(),
+                    ClosedParenthesis::Expression { .. } => render_comparison_operators(),
+                };
+                items.extend(CompletionRenderer::render_keywords(&keywords));
+                Ok(Some(items))
+            }
             CompletionContext::Unknown => Ok(None),
+        };
+
+        // Operators after a complete operand go first
+        let result = result.map(|items| {
+            if operator_items.is_empty() {
+                return items;
+            }
+            let mut items = items.unwrap_or_default();
+            items.retain(|item| !operator_items.iter().any(|op| op.label == item.label));
+            items.splice(0..0, operator_items);
+            Some(items)
+        });
+
+        // Append keywords injected by custom rules
+        let result = result.map(|items| {
+            if custom_keywords.is_empty() {
+                return items;
+            }
+            let mut items = items.unwrap_or_default();
+            items.extend(CompletionRenderer::render_keywords(&custom_keywords));
+            Some(items)
+        });
+
+        // Append parameters of the enclosing embedded region
+        let result = result.map(|items| {
+            if parameter_items.is_empty() {
+                return items;
+            }
+            let mut items = items.unwrap_or_default();
+            items.extend(parameter_items);
+            Some(items)
+        });
+
+        // Keep the functions matching the typed name, or the common ones
+        let result = result.map(|items| {
+            if !trims_functions {
+                return items;
+            }
+            items.map(|items| {
+                let (items, trimmed) = trim_functions(items, typed, &self.config.common_functions);
+                if trimmed {
+                    self.functions_trimmed.store(true, Ordering::Relaxed);
+                }
+                items
+            })
+        });
+
+        // Collapse duplicates produced by the different item sources,
+        // rank against the partially typed name, insert keywords in its case
+        // and replace the typed token
+        let keyword_case = self.config.keyword_case_for(typed);
+        let dialect = document
+            .parse_metadata()
+            .map(|m| m.dialect)
+            .unwrap_or(self.dialect);
+        let result = result.map(|items| {
+            items.map(merge_completion_items).map(|items| {
+                let mut items = match typed {
+                    Some(word) => rank_by_typed_word(items, word),
+                    None => items,
+                };
+                CompletionRenderer::apply_keyword_case(&mut items, keyword_case);
+                if let Some(token) = &token {
+                    CompletionRenderer::apply_text_edits(
+                        &mut items,
+                        token,
+                        self.config.insert_replace_support,
+                    );
+                }
+                stamp_dialect(&mut items, dialect);
+                items
+            })
+        });
+
+        if let (Some(stats), Ok(items)) = (&self.stats, &result) {
+            let count = items.as_ref().map_or(0, Vec::len);
+            stats.record(context_kind, stage, has_parse_error, count);
+        }
+        result
+    }
+
+    /// Keywords and builtin functions for a context that needs a schema
+    fn complete_without_schema(
+        &self,
+        ctx: &CompletionContext,
+        typed: Option<&str>,
+    ) -> Vec<CompletionItem> {
+        let provider = self.keyword_provider(self.dialect);
+        let functions = FunctionRegistry::new().get_functions(self.dialect);
+        render_keyword_only(
+            ctx,
+            &provider,
+            |filter| self.render_functions(&functions, filter),
+            &self.config,
+            typed,
+        )
+    }
+
+    /// Features of the configured dialect version, or those every version
+    /// of `dialect` has
+    fn capabilities_for(&self, dialect: Dialect) -> DialectCapabilities {
+        self.capabilities
+            .unwrap_or_else(|| DialectCapabilities::for_dialect(dialect))
+    }
+
+    /// Newer dialect versions whose keywords and functions are offered too
+    fn offered_newer_versions(&self) -> Vec<(String, DialectCapabilities)> {
+        match self.config.show_unsupported {
+            ShowUnsupported::Hide => Vec::new(),
+            ShowUnsupported::Warn => self.newer_versions.clone(),
         }
     }
 
+    /// Keywords of `dialect` gated by the configured version
+    fn keyword_provider(&self, dialect: Dialect) -> KeywordProvider {
+        KeywordProvider::new(dialect)
+            .with_capabilities(self.capabilities_for(dialect))
+            .with_unsupported(self.offered_newer_versions())
+    }
+
+    /// Render `functions` gated by the configured version like keywords
+    ///
+    /// Window functions need [`DialectCapabilities::window_functions`].
+    fn render_functions(
+        &self,
+        functions: &[FunctionMetadata],
+        filter: Option<FunctionType>,
+    ) -> Vec<CompletionItem> {
+        let window_functions = self.capabilities_for(self.dialect).window_functions;
+        let (supported, unsupported): (Vec<_>, Vec<_>) = functions
+            .iter()
+            .cloned()
+            .partition(|f| window_functions || f.function_type != FunctionType::Window);
+
+        let mut items =
+            CompletionRenderer::render_functions_with_config(&supported, filter, &self.config);
+        let newer = self.offered_newer_versions();
+        if let Some((requires, _)) = newer.iter().find(|(_, c)| c.window_functions) {
+            let mut unsupported = CompletionRenderer::render_functions_with_config(
+                &unsupported,
+                filter,
+                &self.config,
+            );
+            for item in &mut unsupported {
+                CompletionRenderer::mark_unsupported(item, requires);
+            }
+            items.extend(unsupported);
+        }
+        items
+    }
+
+    /// Functions from the catalog, without those of filtered-out schemas
+    async fn list_functions(&self) -> Result<Vec<FunctionMetadata>, CompletionError> {
+        let mut functions = self.catalog_fetcher.list_functions().await?;
+        functions.retain(|f| self.schema_filter.is_function_allowed(f));
+        Ok(functions)
+    }
+
+    /// Parameter items for contexts completing unqualified columns
+    fn parameter_items(&self, ctx: &CompletionContext) -> Vec<CompletionItem> {
+        let unqualified = match ctx {
+            CompletionContext::SelectProjection { qualifier, .. }
+            | CompletionContext::DistinctOnList { qualifier, .. }
+            | CompletionContext::WhereClause { qualifier, .. }
+            | CompletionContext::JoinCondition { qualifier, .. }
+            | CompletionContext::OrderByClause { qualifier, .. }
+            | CompletionContext::GroupByClause { qualifier, .. }
+            | CompletionContext::HavingClause { qualifier, .. } => qualifier.is_none(),
+            _ => false,
+        };
+        if unqualified {
+            CompletionRenderer::render_parameters(&self.parameters)
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Operator items for a cursor following a complete operand
+    ///
+    /// Applies to projection, WHERE and HAVING expressions, e.g.
+    /// `WHERE name |`; the operators depend on the operand column's type.
+    async fn operator_items(
+        &self,
+        ctx: &CompletionContext,
+        scope_manager: &Option<unified_sql_lsp_semantic::ScopeManager>,
+        source: &str,
+        position: Position,
+    ) -> Vec<CompletionItem> {
+        let tables = match ctx {
+            CompletionContext::SelectProjection {
+                tables,
+                qualifier: None,
+            }
+            | CompletionContext::WhereClause {
+                tables,
+                qualifier: None,
+            }
+            | CompletionContext::HavingClause {
+                tables,
+                qualifier: None,
+            } => tables,
+            _ => return Vec::new(),
+        };
+        let Some(operand) = detect_completed_operand(source, to_context_position(position)) else {
+            return Vec::new();
+        };
+
+        let data_type = self
+            .column_type(
+                scope_manager,
+                tables,
+                operand.qualifier.as_deref(),
+                &operand.column,
+            )
+            .await;
+        debug!(?operand, ?data_type, "Adding operators");
+        render_operators(
+            &operand,
+            data_type.as_ref(),
+            self.dialect,
+            position,
+            self.config.snippet_support,
+        )
+    }
+
     /// Complete SELECT projection with columns, functions, and SELECT modifiers
     ///
     /// This is specialized for SELECT clause completion.
-    #[instrument(skip(self, document, source))]
+    #[instrument(skip(self, statement))]
     async fn complete_select_projection(
         &self,
         scope_manager: &Option<unified_sql_lsp_semantic::ScopeManager>,
         tables: Vec<String>,
         qualifier: Option<String>,
-        source: &str,
-        document: &Document,
+        projected: Option<Vec<(String, Option<String>)>>,
+        statement: &str,
+        in_case_expression: bool,
     ) -> Result<Option<Vec<CompletionItem>>, CompletionError> {
         debug!("Starting SELECT projection completion");
 
@@ -444,24 +1199,34 @@ impl CompletionEngine {
 
         // Exclude columns that are already selected in the SELECT clause
         // Pattern: "SELECT id, username, | FROM users" -> exclude "id" and "username"
-        let selected_columns = CompletionTextHeuristics::selected_projection_columns_upper(source);
-        if !selected_columns.is_empty() {
-            items.retain(|item| !selected_columns.contains(&item.label.to_uppercase()));
+        match projected {
+            Some(references) => {
+                let scope_tables = scope_manager
+                    .as_ref()
+                    .and_then(|manager| manager.get_scope(0))
+                    .map(|scope| scope.tables.as_slice())
+                    .unwrap_or_default();
+                let selected = resolve_projected_columns(references, scope_tables);
+                items.retain(|item| !is_projected_column(item, &selected));
+            }
+            None => {
+                // Broken parse: fall back to reading the text
+                let selected_columns =
+                    CompletionTextHeuristics::selected_projection_columns_upper(statement);
+                if !selected_columns.is_empty() {
+                    items.retain(|item| !selected_columns.contains(&item.label.to_uppercase()));
+                }
+            }
         }
 
         // Add SELECT clause keywords (DISTINCT, ALL, etc.)
-        // Get dialect from document metadata, fallback to stored dialect
-        let dialect = document
-            .parse_metadata()
-            .map(|m| m.dialect)
-            .unwrap_or(self.dialect);
-        let provider = KeywordProvider::new(dialect);
+        let provider = self.keyword_provider(self.dialect);
         let select_keywords = provider.select_clause_keywords().keywords;
         let keyword_items = CompletionRenderer::render_keywords(&select_keywords);
         items.extend(keyword_items);
 
         // Add expression keywords (WHEN, THEN, ELSE, etc.) if we're in a CASE expression
-        if CompletionTextHeuristics::ends_with_case_expression(source) {
+        if in_case_expression {
             debug!("Detected CASE expression, adding expression keywords");
             let expr_keywords = provider.expression_keywords().keywords;
             let expr_items = CompletionRenderer::render_keywords(&expr_keywords);
@@ -473,13 +1238,18 @@ impl CompletionEngine {
 
     /// Complete WHERE clause with columns, operators, and clause keywords
     ///
-    /// This is specialized for WHERE clause completion.
+    /// This is specialized for WHERE clause completion. When the cursor is
+    /// at the value of a comparison, literals matching the `operand` come
+    /// first. Keywords are left out of BETWEEN bounds and IN lists, where
+    /// only a value fits.
     #[instrument(skip(self))]
     async fn complete_where_clause(
         &self,
         scope_manager: &Option<unified_sql_lsp_semantic::ScopeManager>,
         tables: Vec<String>,
         qualifier: Option<String>,
+        operand: Option<ComparisonOperand>,
+        has_group_by: bool,
     ) -> Result<Option<Vec<CompletionItem>>, CompletionError> {
         debug!("Starting WHERE clause completion");
 
@@ -487,7 +1257,7 @@ impl CompletionEngine {
         let mut items = match self
             .complete_with_scope(
                 scope_manager,
-                tables,
+                tables.clone(),
                 qualifier.clone(),
                 true, // exclude_wildcard
                 None, // function_filter (show all)
@@ -516,25 +1286,98 @@ impl CompletionEngine {
         }
 
         // Add WHERE clause keywords (AND, OR, etc.) and subsequent clauses
-        // But NOT if we have a table qualifier (e.g., "u.") - in that case, only show columns
-        if qualifier.is_none() {
+        // But NOT if we have a table qualifier (e.g., "u.") - in that case, only show columns,
+        // nor while a BETWEEN bound or IN list value is being written
+        let in_value = matches!(
+            operand,
+            Some(ComparisonOperand::Between { .. } | ComparisonOperand::ValueList { .. })
+        );
+        if qualifier.is_none() && !in_value {
             let dialect = self.dialect;
-            let provider = KeywordProvider::new(dialect);
+            let provider = self.keyword_provider(dialect);
 
             // Get expression keywords (AND, OR, NOT, etc.)
             let expr_keywords = provider.expression_keywords().keywords;
             let expr_items = CompletionRenderer::render_keywords(&expr_keywords);
             items.extend(expr_items);
 
-            // Get keywords after WHERE clause (GROUP BY, ORDER BY, etc.)
-            let clause_keywords = provider.keywords_after_clause("WHERE");
+            // Get keywords after WHERE clause (GROUP BY, ORDER BY, etc.),
+            // without a GROUP BY the query already has
+            let mut clause_keywords = provider.keywords_after_clause("WHERE");
+            if has_group_by {
+                clause_keywords.retain(|keyword| keyword.label != "GROUP BY");
+            }
             let clause_items = CompletionRenderer::render_keywords(&clause_keywords);
             items.extend(clause_items);
         }
 
+        if let Some(operand) = operand {
+            let data_type = match &operand {
+                ComparisonOperand::Column { qualifier, column }
+                | ComparisonOperand::Between {
+                    qualifier, column, ..
+                }
+                | ComparisonOperand::ValueList { qualifier, column } => {
+                    self.column_type(scope_manager, &tables, qualifier.as_deref(), column)
+                        .await
+                }
+                ComparisonOperand::NullTest => None,
+            };
+            debug!(?operand, ?data_type, "Adding comparison literals");
+            let mut literals =
+                render_comparison_literals(&operand, data_type.as_ref(), self.dialect);
+            if matches!(operand, ComparisonOperand::ValueList { .. }) {
+                literals.push(render_value_list_subquery(self.config.snippet_support));
+            }
+            // Literals replace keywords of the same name (NULL)
+            items.retain(|item| !literals.iter().any(|l| l.label == item.label));
+            items.splice(0..0, literals);
+        }
+
         Ok(Some(items))
     }
 
+    /// Catalog type of `column` in the tables of the current clause
+    ///
+    /// Tables come from the scope when it has any, otherwise from the
+    /// context. With a `qualifier`, only the table it names is searched.
+    async fn column_type(
+        &self,
+        scope_manager: &Option<unified_sql_lsp_semantic::ScopeManager>,
+        context_tables: &[String],
+        qualifier: Option<&str>,
+        column: &str,
+    ) -> Option<DataType> {
+        let scope_tables = scope_manager
+            .as_ref()
+            .and_then(|manager| manager.get_scope(0))
+            .map(|scope| scope.tables.as_slice())
+            .unwrap_or_default();
+        let names: Vec<String> = if scope_tables.is_empty() {
+            context_tables
+                .iter()
+                .filter(|t| qualifier.is_none_or(|q| t.eq_ignore_ascii_case(q)))
+                .cloned()
+                .collect()
+        } else {
+            scope_tables
+                .iter()
+                .filter(|t| qualifier.is_none_or(|q| t.matches(q)))
+                .map(|t| t.table_name.clone())
+                .collect()
+        };
+
+        let catalog = self.catalog_fetcher.catalog();
+        for name in names {
+            if let Ok(columns) = catalog.get_columns(&name).await
+                && let Some(found) = columns.iter().find(|c| c.name.eq_ignore_ascii_case(column))
+            {
+                return Some(found.data_type.clone());
+            }
+        }
+        None
+    }
+
     /// Shared completion logic for contexts with scope (SELECT/WHERE)
     ///
     /// This consolidates the duplicate logic between SelectProjection and WhereClause.
@@ -546,6 +1389,82 @@ impl CompletionEngine {
         qualifier: Option<String>,
         exclude_wildcard: bool,
         function_filter: Option<FunctionType>,
+    ) -> Result<Option<Vec<CompletionItem>>, CompletionError> {
+        let engine = self.clone();
+        let scope = scope_manager.clone();
+        let tables = context_tables.clone();
+        let typed_qualifier = qualifier.clone();
+        let work = async move {
+            engine
+                .scoped_items(
+                    &scope,
+                    tables,
+                    typed_qualifier,
+                    exclude_wildcard,
+                    function_filter,
+                )
+                .await
+        };
+
+        match self.within_budget(work).await {
+            Some(result) => result,
+            None => Ok(Some(Self::known_scope_items(
+                scope_manager,
+                &context_tables,
+                qualifier.is_some(),
+            ))),
+        }
+    }
+
+    /// Items available without the catalog: names of the tables in scope
+    ///
+    /// After a qualifier only that table's columns fit, so nothing is offered.
+    fn known_scope_items(
+        scope_manager: &Option<unified_sql_lsp_semantic::ScopeManager>,
+        context_tables: &[String],
+        qualified: bool,
+    ) -> Vec<CompletionItem> {
+        if qualified {
+            return Vec::new();
+        }
+
+        let scope_names: Vec<String> = scope_manager
+            .as_ref()
+            .and_then(|manager| manager.get_scope(0))
+            .map(|scope| {
+                scope
+                    .tables
+                    .iter()
+                    .map(|t| t.alias.clone().unwrap_or_else(|| t.table_name.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let names = if scope_names.is_empty() {
+            context_tables.to_vec()
+        } else {
+            scope_names
+        };
+
+        names
+            .into_iter()
+            .map(|name| CompletionItem {
+                detail: Some(format!("Table in scope: {}", name)),
+                kind: Some(CompletionItemKind::CLASS),
+                data: Some(CompletionData::table(name.as_str()).encode()),
+                label: name,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// Columns and functions for the tables in scope, read from the catalog
+    async fn scoped_items(
+        &self,
+        scope_manager: &Option<unified_sql_lsp_semantic::ScopeManager>,
+        context_tables: Vec<String>,
+        qualifier: Option<String>,
+        exclude_wildcard: bool,
+        function_filter: Option<FunctionType>,
     ) -> Result<Option<Vec<CompletionItem>>, CompletionError> {
         debug!(
             ?context_tables,
@@ -605,14 +1524,18 @@ impl CompletionEngine {
             let tables_to_render = resolution.tables_to_render;
 
             // Fetch functions from catalog
-            let functions = self.catalog_fetcher.list_functions().await?;
+            let functions = self.list_functions().await?;
 
             debug!(tables_count = tables_to_render.len(), "Tables to render");
 
             // Render completion items
             // Force qualifier if there are multiple tables or if an explicit qualifier was provided
             let force_qualifier = qualifier.is_some() || tables_to_render.len() > 1;
-            let mut items = CompletionRenderer::render_columns(&tables_to_render, force_qualifier);
+            let mut items = CompletionRenderer::render_columns_with_config(
+                &tables_to_render,
+                force_qualifier,
+                &self.config,
+            );
 
             debug!(item_count = items.len(), "Rendered column items");
 
@@ -644,6 +1567,7 @@ impl CompletionEngine {
                         detail: Some(format!("CTE: {}", cte_name)),
                         insert_text: Some(format!("{}.*", cte_name)),
                         insert_text_format: Some(InsertTextFormat::PLAIN_TEXT),
+                        data: Some(CompletionData::cte(cte_name.as_str()).encode()),
                         ..Default::default()
                     });
                 }
@@ -655,7 +1579,7 @@ impl CompletionEngine {
             }
 
             // Add function completion items
-            let function_items = CompletionRenderer::render_functions(&functions, function_filter);
+            let function_items = self.render_functions(&functions, function_filter);
             items.extend(function_items);
 
             debug!(
@@ -675,7 +1599,7 @@ impl CompletionEngine {
         let completion_service = CompletionService::new(self.catalog_fetcher.catalog());
 
         // Fetch functions from catalog
-        let functions = self.catalog_fetcher.list_functions().await?;
+        let functions = self.list_functions().await?;
 
         // Resolve qualifier if present to filter tables
         let tables_to_render = match completion_service
@@ -691,7 +1615,11 @@ impl CompletionEngine {
 
         // Render completion items
         let force_qualifier = qualifier.is_some();
-        let mut items = CompletionRenderer::render_columns(&tables_to_render, force_qualifier);
+        let mut items = CompletionRenderer::render_columns_with_config(
+            &tables_to_render,
+            force_qualifier,
+            &self.config,
+        );
 
         // Filter out wildcard if needed
         if exclude_wildcard {
@@ -699,12 +1627,26 @@ impl CompletionEngine {
         }
 
         // Add function completion items
-        let function_items = CompletionRenderer::render_functions(&functions, function_filter);
+        let function_items = self.render_functions(&functions, function_filter);
         items.extend(function_items);
 
         Ok(Some(items))
     }
 
+    /// Get the text of the cursor line up to the cursor position
+    ///
+    /// The empty line after a trailing newline is a line too.
+    fn text_before_cursor(document: &Document, position: Position) -> Option<String> {
+        let content = document.get_content();
+        let line = content.split('\n').nth(position.line as usize)?;
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let end = line
+            .char_indices()
+            .nth(position.character as usize)
+            .map_or(line.len(), |(idx, _)| idx);
+        Some(line[..end].to_string())
+    }
+
     /// Extract the prefix being typed before cursor for filtering
     ///
     /// For example, in "SELECT * FROM or|", returns "or"
@@ -715,13 +1657,17 @@ impl CompletionEngine {
         let rope = Rope::from_str(content.as_str());
         let line = rope.get_line(position.line as usize)?;
 
-        // Convert line to string
+        // Convert line to string, without its line break
         let line_text = line.to_string();
+        let line_text = line_text.trim_end_matches(['\n', '\r']);
 
-        // Get text up to cursor position
-        // Clamp to string length to avoid out-of-bounds panic
-        let char_index = (position.character as usize).min(line_text.len());
-        let up_to_cursor = &line_text[..char_index];
+        // Get text up to cursor position; the position counts chars, and a
+        // cursor past the line end is at its end
+        let end = line_text
+            .char_indices()
+            .nth(position.character as usize)
+            .map_or(line_text.len(), |(idx, _)| idx);
+        let up_to_cursor = &line_text[..end];
 
         // If cursor is immediately after a comma or space, there's no prefix
         // Check BEFORE trimming to detect trailing delimiters
@@ -744,11 +1690,33 @@ impl CompletionEngine {
 
     /// Complete ORDER BY clause with columns and sort directions
     #[instrument(skip(self))]
+    /// Complete the column list of DISTINCT ON (PostgreSQL)
+    ///
+    /// Columns and functions only; `*` and keywords are not valid here.
+    async fn complete_distinct_on_list(
+        &self,
+        scope_manager: &Option<unified_sql_lsp_semantic::ScopeManager>,
+        tables: Vec<String>,
+        qualifier: Option<String>,
+    ) -> Result<Option<Vec<CompletionItem>>, CompletionError> {
+        debug!("Starting DISTINCT ON list completion");
+
+        self.complete_with_scope(
+            scope_manager,
+            tables,
+            qualifier,
+            true, // exclude_wildcard
+            None, // function_filter (show all)
+        )
+        .await
+    }
+
     async fn complete_order_by_clause(
         &self,
         scope_manager: &Option<unified_sql_lsp_semantic::ScopeManager>,
         tables: Vec<String>,
         qualifier: Option<String>,
+        aliases: Vec<String>,
     ) -> Result<Option<Vec<CompletionItem>>, CompletionError> {
         debug!("Starting ORDER BY clause completion");
 
@@ -764,9 +1732,20 @@ impl CompletionEngine {
             .await?
             .unwrap_or_default();
 
+        // After a qualifier only that table's columns fit
+        if qualifier.is_some() {
+            return Ok(Some(items));
+        }
+
+        // Output columns can be sorted by their alias
+        items.extend(CompletionRenderer::render_projection_aliases(
+            &aliases,
+            self.dialect,
+        ));
+
         // Add sort direction keywords (ASC, DESC)
         let dialect = self.dialect;
-        let provider = KeywordProvider::new(dialect);
+        let provider = self.keyword_provider(dialect);
         let sort_keywords = provider.sort_direction_keywords().keywords;
         let sort_items = CompletionRenderer::render_keywords(&sort_keywords);
         items.extend(sort_items);
@@ -798,7 +1777,7 @@ impl CompletionEngine {
 
         // Add HAVING keyword
         let dialect = self.dialect;
-        let provider = KeywordProvider::new(dialect);
+        let provider = self.keyword_provider(dialect);
         let having_keywords = provider.having_keywords().keywords;
         let having_items = CompletionRenderer::render_keywords(&having_keywords);
         items.extend(having_items);
@@ -813,7 +1792,7 @@ impl CompletionEngine {
 
         // Add common LIMIT numbers and OFFSET keyword
         let dialect = self.dialect;
-        let provider = KeywordProvider::new(dialect);
+        let provider = self.keyword_provider(dialect);
         let limit_keywords = provider.limit_keywords().keywords;
         let items = CompletionRenderer::render_keywords(&limit_keywords);
 
@@ -827,11 +1806,12 @@ impl CompletionEngine {
         scope_manager: &Option<unified_sql_lsp_semantic::ScopeManager>,
         tables: Vec<String>,
         qualifier: Option<String>,
+        aliases: Vec<String>,
     ) -> Result<Option<Vec<CompletionItem>>, CompletionError> {
         debug!("Starting HAVING clause completion");
 
         // Get columns using the shared scope completion logic
-        let items: Vec<CompletionItem> = self
+        let mut items: Vec<CompletionItem> = self
             .complete_with_scope(
                 scope_manager,
                 tables,
@@ -842,6 +1822,14 @@ impl CompletionEngine {
             .await?
             .unwrap_or_default();
 
+        // MySQL resolves SELECT list aliases in HAVING; PostgreSQL doesn't
+        if qualifier.is_none() && self.dialect.family() == DialectFamily::MySQL {
+            items.extend(CompletionRenderer::render_projection_aliases(
+                &aliases,
+                self.dialect,
+            ));
+        }
+
         Ok(Some(items))
     }
 
@@ -878,6 +1866,42 @@ impl CompletionEngine {
         Ok(Some(items))
     }
 
+    /// Complete a column inside a MERGE statement
+    ///
+    /// The scope already holds only the tables that apply at the cursor
+    /// (just the target for SET targets and INSERT column lists).
+    #[instrument(skip(self, scope_manager))]
+    async fn complete_merge_clause(
+        &self,
+        scope_manager: &Option<unified_sql_lsp_semantic::ScopeManager>,
+        tables: Vec<String>,
+        qualifier: Option<String>,
+    ) -> Result<Option<Vec<CompletionItem>>, CompletionError> {
+        debug!("Starting MERGE clause completion");
+
+        let mut items = match self
+            .complete_with_scope(
+                scope_manager,
+                tables,
+                qualifier.clone(),
+                true, // exclude_wildcard
+                None, // function_filter (show all)
+            )
+            .await?
+        {
+            Some(items) => items,
+            None => return Ok(None),
+        };
+
+        // With a qualifier only that table's columns apply
+        if let Some(ref q) = qualifier {
+            let qualifier_prefix = format!("{}.", q);
+            items.retain(|i| i.label.starts_with(&qualifier_prefix));
+        }
+
+        Ok(Some(items))
+    }
+
     /// Complete CTE (Common Table Expression) definition
     ///
     /// Suggests table names that can be used as sources for CTEs
@@ -897,6 +1921,11 @@ impl CompletionEngine {
 
         let mut items = Vec::new();
 
+        // Defined CTEs are written as typed (possibly quoted, any case), so
+        // compare unquoted and case-insensitively
+        let key = |name: &str| IdentifierComparer::unquote(name).to_lowercase();
+        let exclude: Vec<String> = defined_ctes.iter().map(|n| key(n)).collect();
+
         // If available_tables is empty, fetch all tables from catalog
         if available_tables.is_empty() {
             debug!("available_tables is empty, fetching from catalog");
@@ -904,13 +1933,9 @@ impl CompletionEngine {
             let catalog_tables = self.catalog_fetcher.list_tables().await?;
             debug!("catalog returned {} tables", catalog_tables.len());
 
-            // Filter out already defined CTEs
-            let exclude_lower: Vec<String> =
-                defined_ctes.iter().map(|n| n.to_lowercase()).collect();
-
             for table in catalog_tables {
                 // Skip if table name matches a defined CTE
-                if exclude_lower.contains(&table.name.to_lowercase()) {
+                if exclude.contains(&key(&table.name)) {
                     continue;
                 }
 
@@ -930,7 +1955,11 @@ impl CompletionEngine {
                     additional_text_edits: None,
                     command: None,
                     commit_characters: None,
-                    data: None,
+                    data: Some(
+                        CompletionData::table(&table.name)
+                            .with_schema(&table.schema)
+                            .encode(),
+                    ),
                     tags: None,
                     label_details: None,
                 });
@@ -940,7 +1969,7 @@ impl CompletionEngine {
             debug!("!!! LSP: using {} provided tables", available_tables.len());
             for table_name in available_tables {
                 // Skip already defined CTEs
-                if defined_ctes.contains(&table_name) {
+                if exclude.contains(&key(&table_name)) {
                     continue;
                 }
 
@@ -960,15 +1989,20 @@ impl CompletionEngine {
                     additional_text_edits: None,
                     command: None,
                     commit_characters: None,
-                    data: None,
+                    data: Some(CompletionData::table(&table_name).encode()),
                     tags: None,
                     label_details: None,
                 });
             }
         }
 
-        // Add already defined CTEs
+        // Add already defined CTEs, once per name
+        let mut seen = Vec::new();
         for cte_name in &defined_ctes {
+            if seen.contains(&key(cte_name)) {
+                continue;
+            }
+            seen.push(key(cte_name));
             items.push(CompletionItem {
                 label: cte_name.clone(),
                 kind: Some(CompletionItemKind::VARIABLE),
@@ -985,7 +2019,7 @@ impl CompletionEngine {
                 additional_text_edits: None,
                 command: None,
                 commit_characters: None,
-                data: None,
+                data: Some(CompletionData::cte(cte_name).encode()),
                 tags: None,
                 label_details: None,
             });
@@ -1013,7 +2047,7 @@ impl CompletionEngine {
         match window_part {
             unified_sql_lsp_context::WindowFunctionPart::OverStart => {
                 // At OVER (|), suggest window function keywords
-                let provider = KeywordProvider::new(self.dialect);
+                let provider = self.keyword_provider(self.dialect);
                 let keywords = provider.window_function_keywords().keywords;
                 let items = CompletionRenderer::render_keywords(&keywords);
                 Ok(Some(items))
@@ -1036,7 +2070,7 @@ impl CompletionEngine {
             }
             unified_sql_lsp_context::WindowFunctionPart::WindowFrame => {
                 // Window frame specification - suggest frame keywords
-                let provider = KeywordProvider::new(self.dialect);
+                let provider = self.keyword_provider(self.dialect);
                 let keywords = provider.window_frame_keywords().keywords;
                 let items = CompletionRenderer::render_keywords(&keywords);
                 Ok(Some(items))
@@ -1047,6 +2081,7 @@ impl CompletionEngine {
     /// Complete FROM clause with table names
     ///
     /// Filters out already-included tables and SQL keywords from the completion list.
+    /// CTEs visible at the cursor are offered alongside the catalog tables.
     #[instrument(skip(self, document))]
     async fn complete_from_clause(
         &self,
@@ -1058,25 +2093,73 @@ impl CompletionEngine {
         let prefix = Self::extract_prefix_from_document(document, position)
             .filter(|p| !Self::is_sql_keyword(p));
 
-        let mut tables = self.catalog_fetcher.list_tables().await?;
+        let fetcher = self.catalog_fetcher.clone();
+        let mut tables = match self
+            .within_budget(async move { fetcher.list_tables().await })
+            .await
+        {
+            Some(tables) => tables?,
+            // Serve the pages loaded so far; the retriggered request gets the rest
+            None => match self.catalog_fetcher.loaded_tables() {
+                Some(tables) => tables,
+                None => return Ok(Some(Vec::new())),
+            },
+        };
 
-        // Filter out excluded tables
+        let dialect = document
+            .parse_metadata()
+            .map(|m| m.dialect)
+            .unwrap_or(self.dialect);
+        let recursive_cte = self
+            .capabilities
+            .unwrap_or_else(|| DialectCapabilities::for_dialect(dialect))
+            .recursive_cte;
+        let mut ctes = visible_ctes(&document.get_content(), position, recursive_cte);
+        ctes.retain(|cte| !exclude_tables.iter().any(|t| t.eq_ignore_ascii_case(cte)));
+
+        // Filter out excluded tables; a quoted name only excludes its exact spelling
         if !exclude_tables.is_empty() {
-            let exclude_lower: Vec<String> =
-                exclude_tables.iter().map(|n| n.to_lowercase()).collect();
-            tables.retain(|t| !exclude_lower.contains(&t.name.to_lowercase()));
+            let excluded: Vec<String> = exclude_tables
+                .iter()
+                .filter_map(|name| IdentifierComparer::find(name, &tables, |t| t.name.as_str()))
+                .map(|t| t.name.clone())
+                .collect();
+            tables.retain(|t| !excluded.contains(&t.name));
         }
 
-        // Filter by prefix if present
+        // Filter by prefix if present, ignoring an opening quote
         if let Some(ref p) = prefix
             && !p.is_empty()
         {
-            tables.retain(|t| t.name.to_lowercase().starts_with(&p.to_lowercase()));
+            let p = p.trim_start_matches(['"', '`']).to_lowercase();
+            tables.retain(|t| t.name.to_lowercase().starts_with(&p));
+            ctes.retain(|cte| cte.to_lowercase().starts_with(&p));
         }
 
         // Show schema qualifier if multiple schemas
-        let schemas: HashSet<&str> = tables.iter().map(|t| t.schema.as_str()).collect();
-        let items = CompletionRenderer::render_tables(&tables, schemas.len() > 1);
+        let schemas: BTreeSet<&str> = tables.iter().map(|t| t.schema.as_str()).collect();
+        let show_schema = schemas.len() > 1;
+        // CROSS and NATURAL joins take no ON clause, so skip its scaffolding
+        let after_join = Self::text_before_cursor(document, position).is_some_and(|text| {
+            CompletionTextHeuristics::ends_with_join_keyword(&text)
+                && !CompletionTextHeuristics::ends_with_conditionless_join(&text)
+        });
+        let mut items = CompletionRenderer::render_ctes(&ctes, dialect);
+        if after_join {
+            CompletionRenderer::add_join_on_scaffold(&mut items, &self.config);
+            items.extend(CompletionRenderer::render_join_tables(
+                &tables,
+                show_schema,
+                dialect,
+                &self.config,
+            ));
+        } else {
+            items.extend(CompletionRenderer::render_tables_for_dialect(
+                &tables,
+                show_schema,
+                dialect,
+            ));
+        }
 
         Ok(Some(items))
     }
@@ -1440,4 +2523,585 @@ mod tests {
         assert!(id_items.iter().any(|i| i.label == "users.id"));
         assert!(id_items.iter().any(|i| i.label == "orders.id"));
     }
+
+    /// Catalog that answers like `inner` after `delay`
+    struct SlowCatalog {
+        inner: Arc<dyn Catalog>,
+        delay: std::time::Duration,
+    }
+
+    #[async_trait::async_trait]
+    impl Catalog for SlowCatalog {
+        async fn list_tables(
+            &self,
+        ) -> unified_sql_lsp_catalog::CatalogResult<Vec<unified_sql_lsp_catalog::TableMetadata>>
+        {
+            tokio::time::sleep(self.delay).await;
+            self.inner.list_tables().await
+        }
+
+        async fn get_columns(
+            &self,
+            table: &str,
+        ) -> unified_sql_lsp_catalog::CatalogResult<Vec<unified_sql_lsp_catalog::ColumnMetadata>>
+        {
+            tokio::time::sleep(self.delay).await;
+            self.inner.get_columns(table).await
+        }
+
+        async fn list_functions(
+            &self,
+        ) -> unified_sql_lsp_catalog::CatalogResult<Vec<unified_sql_lsp_catalog::FunctionMetadata>>
+        {
+            tokio::time::sleep(self.delay).await;
+            self.inner.list_functions().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_from_clause_offers_ctes() {
+        use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+        let catalog = Arc::new(MockCatalogBuilder::new().with_standard_schema().build());
+        let engine = CompletionEngine::new(catalog);
+        let labels = |items: Vec<CompletionItem>| -> Vec<String> {
+            items.into_iter().map(|i| i.label).collect()
+        };
+
+        let sql = "WITH active AS (SELECT * FROM users), recent AS (SELECT * FROM orders) \
+                   SELECT * FROM recent JOIN ";
+        let document = create_test_document(sql, "mysql").await;
+        let items = engine
+            .complete(&document, Position::new(0, sql.len() as u32))
+            .await
+            .unwrap()
+            .unwrap();
+        let active = items.iter().find(|i| i.label == "active").unwrap();
+        assert_eq!(active.kind, Some(CompletionItemKind::CLASS));
+        assert_eq!(active.detail.as_deref(), Some("CTE"));
+        let labels = labels(items);
+        assert!(labels.contains(&"users".to_string()));
+        // Already in the FROM clause
+        assert!(!labels.contains(&"recent".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_cte_definition_excludes_defined_names_case_insensitively() {
+        use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+        let catalog = Arc::new(MockCatalogBuilder::new().with_standard_schema().build());
+        let engine = CompletionEngine::new(catalog);
+        let document = Document::new(
+            Url::parse("file:///test.sql").unwrap(),
+            String::new(),
+            1,
+            "postgresql".to_string(),
+        );
+        let defined = vec![
+            "Recent".to_string(),
+            "\"totals\"".to_string(),
+            "RECENT".to_string(),
+        ];
+        let items = engine
+            .complete_cte_definition(
+                &None,
+                &document,
+                Position::new(0, 0),
+                vec!["recent".into(), "totals".into(), "users".into()],
+                defined,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        let tables: Vec<&str> = items
+            .iter()
+            .filter(|i| i.kind == Some(CompletionItemKind::CLASS))
+            .map(|i| i.label.as_str())
+            .collect();
+        assert_eq!(tables, vec!["users"]);
+        let ctes: Vec<&str> = items
+            .iter()
+            .filter(|i| i.kind == Some(CompletionItemKind::VARIABLE))
+            .map(|i| i.label.as_str())
+            .collect();
+        assert_eq!(ctes, vec!["Recent", "\"totals\""]);
+    }
+
+    #[tokio::test]
+    async fn test_order_by_offers_quoted_aliases() {
+        use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+        let catalog = Arc::new(MockCatalogBuilder::new().with_standard_schema().build());
+        let engine = CompletionEngine::new(catalog);
+        let complete = async |sql: &str, dialect: &str| {
+            let document = create_test_document(sql, dialect).await;
+            engine
+                .complete(&document, Position::new(0, sql.len() as u32))
+                .await
+                .unwrap()
+                .unwrap_or_default()
+        };
+        let alias = |items: &[CompletionItem], label: &str| {
+            items
+                .iter()
+                .find(|i| i.label == label && i.detail.as_deref() == Some("Column alias"))
+                .and_then(|i| i.insert_text.clone())
+        };
+
+        let items = complete(
+            "SELECT count(*) AS \"Total Rows\", id AS total FROM users ORDER BY ",
+            "postgresql",
+        )
+        .await;
+        assert_eq!(
+            alias(&items, "Total Rows").as_deref(),
+            Some("\"Total Rows\"")
+        );
+        assert_eq!(alias(&items, "total").as_deref(), Some("total"));
+
+        let items = complete(
+            "SELECT count(*) AS `Total Rows` FROM users GROUP BY id HAVING ",
+            "mysql",
+        )
+        .await;
+        assert_eq!(alias(&items, "Total Rows").as_deref(), Some("`Total Rows`"));
+
+        // An alias isn't a table, so nothing follows it
+        let items = complete(
+            "SELECT count(*) AS \"Total Rows\" FROM users ORDER BY \"Total Rows\".",
+            "postgresql",
+        )
+        .await;
+        assert!(items.is_empty());
+    }
+
+    #[test]
+    fn test_mysql57_unsupported_items_hidden_or_warned() {
+        use crate::config::DialectVersion;
+        use tower_lsp::lsp_types::CompletionItemTag;
+        use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+        let catalog = Arc::new(MockCatalogBuilder::new().build());
+        let functions = FunctionRegistry::new().get_functions(Dialect::MySQL);
+        let render = |show_unsupported| {
+            let engine = CompletionEngine::new(catalog.clone())
+                .with_config(CompletionConfig {
+                    show_unsupported,
+                    ..Default::default()
+                })
+                .with_capabilities(DialectVersion::MySQL57.capabilities())
+                .with_newer_versions(DialectVersion::MySQL57.newer());
+            let keywords = engine
+                .keyword_provider(Dialect::MySQL)
+                .select_clause_keywords()
+                .keywords;
+            let mut items = CompletionRenderer::render_keywords(&keywords);
+            items.extend(engine.render_functions(&functions, None));
+            items
+        };
+
+        let hidden = render(ShowUnsupported::Hide);
+        assert!(hidden.iter().any(|i| i.label == "FROM"));
+        assert!(hidden.iter().any(|i| i.label == "COUNT"));
+        assert!(!hidden.iter().any(|i| i.label == "WINDOW"));
+        assert!(!hidden.iter().any(|i| i.label == "ROW_NUMBER"));
+        assert!(hidden.iter().all(|i| i.tags.is_none()));
+
+        let warned = render(ShowUnsupported::Warn);
+        assert_eq!(warned.len(), hidden.len() + 6);
+        for label in ["WINDOW", "ROW_NUMBER", "RANK", "DENSE_RANK", "LAG", "LEAD"] {
+            let item = warned.iter().find(|i| i.label == label).unwrap();
+            assert_eq!(item.tags, Some(vec![CompletionItemTag::DEPRECATED]));
+            assert!(
+                item.detail
+                    .as_deref()
+                    .unwrap()
+                    .starts_with("⚠ requires MySQL ≥ 8.0")
+            );
+        }
+        // Sorted after every supported item
+        let mut sorted = warned.clone();
+        sorted.sort_by(|a, b| a.sort_text.cmp(&b.sort_text));
+        assert!(sorted[hidden.len()..].iter().all(|i| i.tags.is_some()));
+        // FULL JOIN exists in no MySQL version
+        assert!(!warned.iter().any(|i| i.label == "FULL JOIN"));
+    }
+
+    #[tokio::test]
+    async fn test_nesting_past_cap_completes_keywords() {
+        use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+        let catalog = Arc::new(MockCatalogBuilder::new().with_standard_schema().build());
+        let engine = CompletionEngine::new(catalog).with_max_nesting_depth(64);
+        // 300 subqueries, with the cursor in the WHERE of the innermost
+        let mut sql = "SELECT id FROM users WHERE ".to_string();
+        let cursor = sql.len() as u32;
+        for level in 0..300 {
+            sql = format!("SELECT id FROM ({}) t{}", sql, level);
+        }
+        let cursor = cursor + sql.find("SELECT id FROM users").unwrap() as u32;
+        let document = create_test_document(&sql, "mysql").await;
+
+        let items = engine
+            .complete(&document, Position::new(0, cursor))
+            .await
+            .unwrap()
+            .unwrap_or_default();
+        assert!(!items.is_empty());
+        assert!(
+            items
+                .iter()
+                .all(|item| item.kind == Some(CompletionItemKind::KEYWORD))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_latency_budget_returns_partial_then_complete() {
+        use std::time::{Duration, Instant};
+        use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+        let catalog = Arc::new(SlowCatalog {
+            inner: Arc::new(MockCatalogBuilder::new().with_standard_schema().build()),
+            delay: Duration::from_millis(200),
+        });
+        let config = CompletionConfig {
+            latency_budget: Some(Duration::from_millis(50)),
+            ..CompletionConfig::default()
+        };
+        let late_results = Arc::new(LateCatalogResults::default());
+        let engine = CompletionEngine::new(catalog)
+            .with_config(config)
+            .with_late_results(&late_results);
+        let document = create_test_document("SELECT * FROM ", "mysql").await;
+
+        let started = Instant::now();
+        let partial = engine
+            .complete_list(&document, Position::new(0, 14))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_millis(150));
+        assert!(partial.is_incomplete);
+        assert!(!partial.items.iter().any(|i| i.label == "users"));
+
+        // Let the abandoned catalog call land in the late results
+        tokio::time::sleep(Duration::from_millis(250)).await;
+
+        let complete = engine
+            .complete_list(&document, Position::new(0, 14))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!complete.is_incomplete);
+        assert!(complete.items.iter().any(|i| i.label == "users"));
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_answers_completion_without_catalog_delay() {
+        use crate::config::CatalogPrefetch;
+        use crate::prefetch::{RequestActivity, prefetch_catalog};
+        use std::time::{Duration, Instant};
+        use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+        let delay = Duration::from_millis(200);
+        let catalog: Arc<dyn Catalog> = Arc::new(SlowCatalog {
+            inner: Arc::new(MockCatalogBuilder::new().with_standard_schema().build()),
+            delay,
+        });
+        let late_results = Arc::new(LateCatalogResults::default());
+        let summary = prefetch_catalog(
+            catalog.clone(),
+            &late_results,
+            &RequestActivity::default(),
+            CatalogPrefetch::Full,
+            vec!["users".to_string()],
+            Duration::from_secs(5),
+        )
+        .await;
+        assert_eq!(summary.columns, 1);
+
+        let recent = Arc::new(RecentTables::default());
+        let engine = CompletionEngine::new(catalog)
+            .with_late_results(&late_results)
+            .with_recent_tables(&recent);
+        let document = create_test_document("SELECT * FROM ", "mysql").await;
+
+        let started = Instant::now();
+        let list = engine
+            .complete_list(&document, Position::new(0, 14))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(started.elapsed() < delay);
+        assert!(!list.is_incomplete);
+        assert!(list.items.iter().any(|i| i.label == "users"));
+    }
+
+    #[tokio::test]
+    async fn test_within_budget_gives_up_without_cancelling() {
+        use std::time::Duration;
+        use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+        let catalog = Arc::new(MockCatalogBuilder::new().build());
+        let unbudgeted = CompletionEngine::new(catalog.clone());
+        let slow = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            1
+        };
+        assert_eq!(unbudgeted.within_budget(slow).await, Some(1));
+        assert!(!unbudgeted.budget_exceeded.load(Ordering::Relaxed));
+
+        let engine = CompletionEngine::new(catalog).with_config(CompletionConfig {
+            latency_budget: Some(Duration::from_millis(10)),
+            ..CompletionConfig::default()
+        });
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let slow = async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            tx.send(()).unwrap();
+        };
+        assert_eq!(engine.within_budget(slow).await, None);
+        assert!(engine.budget_exceeded.load(Ordering::Relaxed));
+        // The abandoned work still finishes
+        rx.await.unwrap();
+    }
+
+    /// Counts the error-level events logged while it is the default subscriber
+    #[derive(Clone, Default)]
+    struct ErrorCounter(Arc<std::sync::atomic::AtomicUsize>);
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for ErrorCounter {
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            if *event.metadata().level() == tracing::Level::ERROR {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keyword_only_completion_without_catalog() {
+        use crate::completion::keyword_only::SCHEMA_HINT_LABEL;
+        use tracing_subscriber::layer::SubscriberExt;
+        use unified_sql_lsp_catalog::NullCatalog;
+
+        let errors = ErrorCounter::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(errors.clone()));
+        let engine = CompletionEngine::new(Arc::new(NullCatalog));
+
+        let source = "SELECT * FROM ";
+        let document = create_test_document(source, "mysql").await;
+        let items = engine
+            .complete(&document, Position::new(0, source.len() as u32))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(items.iter().any(|i| i.label == "WHERE"));
+        assert!(items.iter().any(|i| i.label == SCHEMA_HINT_LABEL));
+
+        let source = "SELECT * FROM users WHERE ";
+        let document = create_test_document(source, "mysql").await;
+        let items = engine
+            .complete(&document, Position::new(0, source.len() as u32))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(items.iter().any(|i| i.label == "AND"));
+        assert!(items.iter().any(|i| i.label == "COALESCE"));
+        assert!(items.iter().any(|i| i.label == SCHEMA_HINT_LABEL));
+
+        assert_eq!(errors.0.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_statement_templates_at_statement_start() {
+        use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+        let catalog = MockCatalogBuilder::new().with_standard_schema().build();
+        let engine = CompletionEngine::new(Arc::new(catalog)).with_config(CompletionConfig {
+            snippet_support: true,
+            ..Default::default()
+        });
+        let complete = |source: &'static str, position: Position| {
+            let engine = &engine;
+            async move {
+                let document = create_test_document(source, "mysql").await;
+                engine
+                    .complete(&document, position)
+                    .await
+                    .unwrap()
+                    .unwrap_or_default()
+            }
+        };
+        let template = |items: &[CompletionItem], label: &str| {
+            items
+                .iter()
+                .any(|i| i.label == label && i.kind == Some(CompletionItemKind::SNIPPET))
+        };
+
+        // Empty document: templates next to the plain keywords
+        let items = complete("", Position::new(0, 0)).await;
+        assert!(template(&items, "SELECT … FROM …"));
+        assert!(template(&items, "CREATE TABLE … ( … )"));
+        assert!(items.iter().any(|i| i.label == "SELECT"));
+
+        // After a semicolon
+        let items = complete("SELECT * FROM users;\n", Position::new(1, 0)).await;
+        assert!(template(&items, "INSERT INTO … VALUES (…)"));
+
+        // Never mid-statement
+        let items = complete("SELECT * FROM users WHERE ", Position::new(0, 26)).await;
+        assert!(
+            !items
+                .iter()
+                .any(|i| i.kind == Some(CompletionItemKind::SNIPPET))
+        );
+    }
+
+    /// The same request gives byte-identical items, also against a freshly
+    /// built catalog, whose maps are seeded differently
+    #[tokio::test]
+    async fn test_completion_is_deterministic() {
+        use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+        let cases = [
+            "SELECT | FROM users",
+            "SELECT * FROM |",
+            "SELECT * FROM users u JOIN orders o ON |",
+            "SELECT * FROM users WHERE |",
+            "SELECT * FROM orders ORDER BY |",
+        ];
+        for marked in cases {
+            let cursor = marked.find('|').unwrap();
+            let source = marked.replacen('|', "", 1);
+            let mut runs = Vec::new();
+            for _ in 0..20 {
+                let catalog = MockCatalogBuilder::new().with_standard_schema().build();
+                let engine = CompletionEngine::new(Arc::new(catalog));
+                let document = create_test_document(&source, "mysql").await;
+                let items = engine
+                    .complete(&document, Position::new(0, cursor as u32))
+                    .await
+                    .unwrap()
+                    .unwrap_or_default();
+                runs.push(serde_json::to_string(&items).unwrap());
+            }
+
+            assert_ne!(runs[0], "[]", "{}", marked);
+            assert!(runs.iter().all(|run| *run == runs[0]), "{}", marked);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_role_name_completion() {
+        use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+        let catalog = MockCatalogBuilder::new()
+            .with_standard_schema()
+            .with_role("analyst")
+            .with_role("reporting")
+            .build();
+        let engine = CompletionEngine::new(Arc::new(catalog));
+
+        let source = "REVOKE SELECT ON users FROM ";
+        let document = create_test_document(source, "postgresql").await;
+        let items = engine
+            .complete(&document, Position::new(0, source.len() as u32))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let analyst = items.iter().find(|i| i.label == "analyst").unwrap();
+        assert_eq!(analyst.kind, Some(CompletionItemKind::ENUM_MEMBER));
+        assert_eq!(analyst.detail.as_deref(), Some("role"));
+        assert!(items.iter().any(|i| i.label == "CURRENT_USER"));
+        // Not a FROM clause
+        assert!(!items.iter().any(|i| i.label == "users"));
+    }
+
+    #[test]
+    fn test_parameters_complete_inside_sql_function_body() {
+        use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+        let source = "CREATE FUNCTION user_email(in_user_id int) RETURNS text AS $$\n  SELECT email FROM users WHERE \n$$ LANGUAGE sql;";
+        let document = Document::new(
+            Url::parse("file:///test.sql").unwrap(),
+            source.to_string(),
+            1,
+            "postgresql".to_string(),
+        );
+        let cursor = Position::new(1, 32);
+        let region = document.embedded_region_at(cursor).unwrap();
+        let inner = region.to_inner(cursor).unwrap();
+        assert_eq!(inner, Position::new(1, 32));
+
+        let ctx = unified_sql_lsp_context::detect_completion_context_from_text(
+            to_context_position(inner),
+            &region.text,
+        );
+        assert!(
+            matches!(&ctx, CompletionContext::WhereClause { tables, qualifier: None } if tables == &["users"]),
+            "{:?}",
+            ctx
+        );
+
+        let engine = CompletionEngine::new(Arc::new(MockCatalogBuilder::new().build()))
+            .with_parameters(region.parameters.clone());
+        let items = engine.parameter_items(&ctx);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].label, "in_user_id");
+        assert_eq!(items[0].detail.as_deref(), Some("Integer"));
+
+        // Qualified references only see the qualifier's columns
+        let qualified = CompletionContext::WhereClause {
+            tables: vec!["users".to_string()],
+            qualifier: Some("users".to_string()),
+        };
+        assert!(engine.parameter_items(&qualified).is_empty());
+    }
+
+    #[test]
+    fn test_projected_columns_resolve_aliases() {
+        let tables = vec![
+            TableSymbol::new("users").with_alias("u"),
+            TableSymbol::new("orders").with_alias("o"),
+        ];
+        let selected = resolve_projected_columns(
+            vec![
+                ("id".to_string(), Some("u".to_string())),
+                ("total".to_string(), None),
+            ],
+            &tables,
+        );
+        assert_eq!(
+            selected,
+            vec![
+                (Some("users".to_string()), "id".to_string()),
+                (None, "total".to_string())
+            ]
+        );
+
+        let column = |table: &str, name: &str| CompletionItem {
+            label: name.to_string(),
+            data: Some(CompletionData::column(table, name).encode()),
+            ..Default::default()
+        };
+        assert!(is_projected_column(&column("users", "id"), &selected));
+        assert!(!is_projected_column(&column("orders", "id"), &selected));
+        assert!(is_projected_column(&column("orders", "total"), &selected));
+        // Columns only passed to a call aren't projected references
+        assert!(!is_projected_column(
+            &column("users", "first_name"),
+            &selected
+        ));
+        assert!(!is_projected_column(
+            &CompletionRenderer::wildcard_item(),
+            &selected
+        ));
+    }
 }
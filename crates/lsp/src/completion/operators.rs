@@ -0,0 +1,252 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Operator completion
+//!
+//! Operators offered after a complete operand, e.g. `WHERE name |`, ranked
+//! above columns and keywords. Only text operands get operators for now:
+//! - PostgreSQL: `||`, `LIKE`, `NOT LIKE`, `ILIKE`, `SIMILAR TO`
+//! - MySQL: `CONCAT(...)`, `LIKE`, `NOT LIKE`, `REGEXP`, `RLIKE`
+//!
+//! After a closing parenthesis, e.g. `WHERE lower(name) |`, the type of the
+//! operand is unknown and the comparison operators are offered.
+//!
+//! MySQL reads `||` as logical OR unless `PIPES_AS_CONCAT` is set, so it
+//! gets a `CONCAT(` item instead, which wraps the operand.
+
+use tower_lsp::lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionTextEdit, Documentation, InsertTextFormat,
+    Position, Range, TextEdit,
+};
+use unified_sql_lsp_catalog::DataType;
+use unified_sql_lsp_context::CompletedOperand;
+use unified_sql_lsp_ir::Dialect;
+use unified_sql_lsp_ir::dialect::DialectFamily;
+
+/// PostgreSQL operators of text operands: label, description
+const POSTGRES_TEXT_OPERATORS: &[(&str, &str)] = &[
+    ("||", "Concatenate strings"),
+    ("LIKE", "Pattern matching"),
+    ("NOT LIKE", "Negated pattern matching"),
+    ("ILIKE", "Case-insensitive pattern matching"),
+    ("SIMILAR TO", "SQL regular expression matching"),
+];
+
+/// MySQL operators of text operands: label, description
+const MYSQL_TEXT_OPERATORS: &[(&str, &str)] = &[
+    ("LIKE", "Pattern matching"),
+    ("NOT LIKE", "Negated pattern matching"),
+    ("REGEXP", "Regular expression matching"),
+    ("RLIKE", "Regular expression matching, synonym of REGEXP"),
+];
+
+/// Comparison operators of any operand: label, description
+const COMPARISON_OPERATORS: &[(&str, &str)] = &[
+    ("=", "Equal"),
+    ("<>", "Not equal"),
+    ("<", "Less than"),
+    (">", "Greater than"),
+    ("<=", "Less than or equal"),
+    (">=", "Greater than or equal"),
+];
+
+/// Operators that apply to an operand of `data_type` in `dialect`
+pub fn operators_for(
+    dialect: Dialect,
+    data_type: &DataType,
+) -> &'static [(&'static str, &'static str)] {
+    let is_text = matches!(
+        data_type,
+        DataType::Text | DataType::Varchar(_) | DataType::Char(_)
+    );
+    match (dialect.family(), is_text) {
+        (DialectFamily::PostgreSQL, true) => POSTGRES_TEXT_OPERATORS,
+        (DialectFamily::MySQL, true) => MYSQL_TEXT_OPERATORS,
+        (_, false) => &[],
+    }
+}
+
+/// Render the operators for `operand`
+///
+/// `data_type` is the type of the operand column, if it was found in the
+/// catalog; `cursor` is the completion position, up to which the MySQL
+/// `CONCAT(` item replaces the operand.
+pub fn render_operators(
+    operand: &CompletedOperand,
+    data_type: Option<&DataType>,
+    dialect: Dialect,
+    cursor: Position,
+    snippet_support: bool,
+) -> Vec<CompletionItem> {
+    let Some(data_type) = data_type else {
+        return Vec::new();
+    };
+    let operators = operators_for(dialect, data_type);
+    if operators.is_empty() {
+        return Vec::new();
+    }
+
+    let mut items: Vec<CompletionItem> = operators
+        .iter()
+        .map(|(label, description)| CompletionItem {
+            label: label.to_string(),
+            kind: Some(CompletionItemKind::OPERATOR),
+            detail: Some("operator".to_string()),
+            documentation: Some(Documentation::String(description.to_string())),
+            ..Default::default()
+        })
+        .collect();
+
+    if dialect.family() == DialectFamily::MySQL {
+        let written = match &operand.qualifier {
+            Some(qualifier) => format!("{}.{}", qualifier, operand.column),
+            None => operand.column.clone(),
+        };
+        let (new_text, format) = if snippet_support {
+            (
+                format!("CONCAT({}, $0)", written),
+                InsertTextFormat::SNIPPET,
+            )
+        } else {
+            (
+                format!("CONCAT({}, ", written),
+                InsertTextFormat::PLAIN_TEXT,
+            )
+        };
+        let start = Position::new(operand.start.line, operand.start.character);
+        items.insert(
+            0,
+            CompletionItem {
+                label: "CONCAT(...)".to_string(),
+                kind: Some(CompletionItemKind::OPERATOR),
+                detail: Some("operator".to_string()),
+                documentation: Some(Documentation::String(format!(
+                    "Concatenate {} with other strings",
+                    written
+                ))),
+                filter_text: Some(format!("{} CONCAT", written)),
+                insert_text_format: Some(format),
+                text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(
+                    Range::new(start, cursor),
+                    new_text,
+                ))),
+                ..Default::default()
+            },
+        );
+    }
+
+    for (i, item) in items.iter_mut().enumerate() {
+        item.sort_text = Some(format!("!{}", i));
+    }
+    items
+}
+
+/// Render the comparison operators, for an operand of unknown type such as
+/// a function call, e.g. `WHERE lower(name) |`
+pub fn render_comparison_operators() -> Vec<CompletionItem> {
+    COMPARISON_OPERATORS
+        .iter()
+        .enumerate()
+        .map(|(i, (label, description))| CompletionItem {
+            label: label.to_string(),
+            kind: Some(CompletionItemKind::OPERATOR),
+            detail: Some("operator".to_string()),
+            documentation: Some(Documentation::String(description.to_string())),
+            sort_text: Some(format!("!{}", i)),
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unified_sql_lsp_context::Position as ContextPosition;
+
+    fn operand() -> CompletedOperand {
+        CompletedOperand {
+            qualifier: Some("u".to_string()),
+            column: "name".to_string(),
+            start: ContextPosition::new(0, 28),
+        }
+    }
+
+    fn labels(dialect: Dialect, data_type: &DataType) -> Vec<String> {
+        render_operators(
+            &operand(),
+            Some(data_type),
+            dialect,
+            Position::new(0, 35),
+            true,
+        )
+        .into_iter()
+        .map(|item| item.label)
+        .collect()
+    }
+
+    #[test]
+    fn test_postgres_text_operators() {
+        let labels = labels(Dialect::PostgreSQL, &DataType::Text);
+        for operator in ["||", "LIKE", "ILIKE", "SIMILAR TO"] {
+            assert!(labels.iter().any(|l| l == operator), "{operator}");
+        }
+        for operator in ["RLIKE", "REGEXP", "CONCAT(...)"] {
+            assert!(!labels.iter().any(|l| l == operator), "{operator}");
+        }
+    }
+
+    #[test]
+    fn test_mysql_text_operators() {
+        let labels = labels(Dialect::MySQL, &DataType::Varchar(Some(255)));
+        for operator in ["CONCAT(...)", "LIKE", "REGEXP", "RLIKE"] {
+            assert!(labels.iter().any(|l| l == operator), "{operator}");
+        }
+        for operator in ["||", "ILIKE", "SIMILAR TO"] {
+            assert!(!labels.iter().any(|l| l == operator), "{operator}");
+        }
+
+        let items = render_operators(
+            &operand(),
+            Some(&DataType::Text),
+            Dialect::MySQL,
+            Position::new(0, 35),
+            false,
+        );
+        let Some(CompletionTextEdit::Edit(edit)) = &items[0].text_edit else {
+            panic!("CONCAT replaces the operand");
+        };
+        assert_eq!(edit.new_text, "CONCAT(u.name, ");
+        assert_eq!(edit.range.start, Position::new(0, 28));
+    }
+
+    #[test]
+    fn test_comparison_operators_rank_first() {
+        let items = render_comparison_operators();
+        let labels: Vec<_> = items.iter().map(|item| item.label.as_str()).collect();
+        assert_eq!(labels, ["=", "<>", "<", ">", "<=", ">="]);
+        assert!(
+            items
+                .iter()
+                .all(|item| item.kind == Some(CompletionItemKind::OPERATOR)
+                    && item.sort_text.as_ref().is_some_and(|s| s.starts_with('!')))
+        );
+    }
+
+    #[test]
+    fn test_non_text_operands_get_no_operators() {
+        assert!(labels(Dialect::PostgreSQL, &DataType::Integer).is_empty());
+        assert!(labels(Dialect::MySQL, &DataType::Boolean).is_empty());
+        assert!(
+            render_operators(
+                &operand(),
+                None,
+                Dialect::PostgreSQL,
+                Position::new(0, 35),
+                true
+            )
+            .is_empty()
+        );
+    }
+}
@@ -0,0 +1,1133 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Completion rendering
+//!
+//! This module provides functionality to render LSP completion items
+//! from semantic symbols.
+//!
+//! ## Text edits
+//!
+//! Items carry a `text_edit` over the identifier being typed ([`TypedToken`]),
+//! so clients that don't infer a replacement range don't append to the
+//! typed prefix. After a dot only the tail is replaced, and the qualifier is
+//! dropped from items that repeat it (`u.id` inserts `id` after `u.`).
+
+use tower_lsp::lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionItemLabelDetails, CompletionItemTag,
+    CompletionTextEdit, Documentation, InsertReplaceEdit, InsertTextFormat, Position, Range,
+    TextEdit,
+};
+use unified_sql_lsp_catalog::{
+    FunctionMetadata, FunctionType, TableMetadata, TableType, format_data_type,
+};
+use unified_sql_lsp_ir::{Dialect, IdentifierComparer};
+use unified_sql_lsp_semantic::{ColumnSymbol, TableSymbol};
+
+// Import keyword types from context crate
+use unified_sql_lsp_context::{SqlKeyword, StatementTemplate};
+
+use crate::completion::data::CompletionData;
+use crate::config::{CompletionConfig, KeywordCase};
+
+/// Aggregate functions that accept `*` as their argument
+const STAR_AGGREGATES: &[&str] = &["count"];
+
+/// Whether `c` can be part of an unquoted identifier
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The identifier being typed at the cursor, replaced by completion items
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedToken {
+    /// From the start of the identifier to the cursor
+    pub insert: Range,
+    /// The whole identifier, including characters after the cursor
+    pub replace: Range,
+    /// Identifier before the dot when the token follows one (`u` in `u.na|`)
+    pub qualifier: Option<String>,
+}
+
+impl TypedToken {
+    /// Token at `position` in `source`
+    ///
+    /// The token is empty at the cursor when no identifier is being typed.
+    /// Returns `None` when `position` is not inside `source`.
+    pub fn at(source: &str, position: Position) -> Option<Self> {
+        let line: Vec<char> = source
+            .split('\n')
+            .nth(position.line as usize)?
+            .trim_end_matches('\r')
+            .chars()
+            .collect();
+        let cursor = position.character as usize;
+        if cursor > line.len() {
+            return None;
+        }
+
+        let start = cursor
+            - line[..cursor]
+                .iter()
+                .rev()
+                .take_while(|c| is_identifier_char(**c))
+                .count();
+        let end = cursor
+            + line[cursor..]
+                .iter()
+                .take_while(|c| is_identifier_char(**c))
+                .count();
+
+        let qualifier = (start > 0 && line[start - 1] == '.').then(|| {
+            let dot = start - 1;
+            let qualifier_start = dot
+                - line[..dot]
+                    .iter()
+                    .rev()
+                    .take_while(|c| is_identifier_char(**c))
+                    .count();
+            line[qualifier_start..dot].iter().collect::<String>()
+        });
+
+        let at = |character: usize| Position::new(position.line, character as u32);
+        Some(Self {
+            insert: Range::new(at(start), at(cursor)),
+            replace: Range::new(at(start), at(end)),
+            qualifier: qualifier.filter(|q| !q.is_empty()),
+        })
+    }
+
+    /// Text `item` should put in place of the token
+    ///
+    /// Drops a leading qualifier the user already typed.
+    fn new_text(&self, item: &CompletionItem) -> String {
+        let text = item.insert_text.as_deref().unwrap_or(&item.label);
+        if let Some(qualifier) = &self.qualifier
+            && let Some(typed) = text.get(..qualifier.len())
+            && typed.eq_ignore_ascii_case(qualifier)
+            && let Some(rest) = text[qualifier.len()..].strip_prefix('.')
+            && !rest.is_empty()
+        {
+            return rest.to_string();
+        }
+        text.to_string()
+    }
+}
+
+/// Completion renderer
+///
+/// Converts semantic symbols to LSP CompletionItem representations.
+pub struct CompletionRenderer;
+
+impl CompletionRenderer {
+    /// Render column completion items
+    ///
+    /// Uses the default [`CompletionConfig`], see [`Self::render_columns_with_config`].
+    ///
+    /// # Arguments
+    ///
+    /// * `tables` - Tables with their columns
+    /// * `force_qualifier` - Whether to force table qualifier (e.g., "users.id")
+    ///
+    /// # Returns
+    ///
+    /// Vector of completion items
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use unified_sql_lsp_lsp::completion::render::CompletionRenderer;
+    /// # use unified_sql_lsp_semantic::{TableSymbol, ColumnSymbol};
+    /// # use unified_sql_lsp_catalog::DataType;
+    /// # let tables = vec![
+    /// #     TableSymbol::new("users").with_columns(vec![
+    /// #         ColumnSymbol::new("id", DataType::Integer, "users"),
+    /// #         ColumnSymbol::new("name", DataType::Text, "users"),
+    /// #     ])
+    /// # ];
+    /// let items = CompletionRenderer::render_columns(&tables, false);
+    /// assert!(items.iter().any(|i| i.label == "id"));
+    /// ```
+    pub fn render_columns(tables: &[TableSymbol], force_qualifier: bool) -> Vec<CompletionItem> {
+        Self::render_columns_with_config(tables, force_qualifier, &CompletionConfig::default())
+    }
+
+    /// Render column completion items with the given rendering options
+    ///
+    /// With `show_relationships` on, foreign key columns show their target
+    /// (`Integer → users.id`) in the detail and label details.
+    pub fn render_columns_with_config(
+        tables: &[TableSymbol],
+        force_qualifier: bool,
+        config: &CompletionConfig,
+    ) -> Vec<CompletionItem> {
+        let mut items = Vec::new();
+
+        // Add wildcard (*) completion
+        items.push(Self::wildcard_item());
+
+        // Group columns by name to detect ambiguity
+        let mut column_map: std::collections::HashMap<String, Vec<&ColumnSymbol>> =
+            std::collections::HashMap::new();
+
+        for table in tables {
+            for column in table.columns.iter() {
+                column_map
+                    .entry(column.name.clone())
+                    .or_default()
+                    .push(column);
+            }
+        }
+
+        // Generate completion items
+        for table in tables {
+            for column in table.columns.iter() {
+                // Check if column is ambiguous
+                let is_ambiguous = column_map[&column.name].len() > 1;
+
+                // Force qualifier if ambiguous or explicitly requested
+                let needs_qualifier = force_qualifier || is_ambiguous;
+
+                items.push(Self::column_item(
+                    column,
+                    table,
+                    needs_qualifier,
+                    config.show_relationships,
+                ));
+            }
+        }
+
+        sort_items(&mut items);
+        items
+    }
+
+    /// Render JOIN condition column completion items with PK/FK prioritization
+    ///
+    /// Uses the default [`CompletionConfig`], see [`Self::render_join_columns_with_config`].
+    ///
+    /// # Arguments
+    ///
+    /// * `tables` - Tables with their columns (typically 2 tables for JOIN)
+    /// * `force_qualifier` - Whether to force table qualifier (always true for JOINs)
+    ///
+    /// # Returns
+    ///
+    /// Vector of completion items sorted by PK/FK priority
+    ///
+    /// # Sorting Strategy
+    ///
+    /// - Tier 1: Primary keys (sort: "00_pk_<name>", preselect: true)
+    /// - Tier 2: Foreign keys (sort: "01_fk_<name>", preselect: true)
+    /// - Tier 3: Regular columns (sort: "02_<name>", alphabetical)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use unified_sql_lsp_lsp::completion::render::CompletionRenderer;
+    /// # use unified_sql_lsp_semantic::{TableSymbol, ColumnSymbol};
+    /// # use unified_sql_lsp_catalog::DataType;
+    /// # let left_table = TableSymbol::new("users").with_columns(vec![
+    /// #     ColumnSymbol::new("id", DataType::Integer, "users").with_primary_key(),
+    /// #     ColumnSymbol::new("name", DataType::Text, "users"),
+    /// # ]);
+    /// # let right_table = TableSymbol::new("orders").with_columns(vec![
+    /// #     ColumnSymbol::new("id", DataType::Integer, "orders").with_primary_key(),
+    /// #     ColumnSymbol::new("user_id", DataType::Integer, "orders").with_foreign_key(),
+    /// # ]);
+    /// let items = CompletionRenderer::render_join_columns(&[left_table, right_table], true);
+    /// assert!(items[0].preselect.unwrap()); // PK/FK columns should be preselected
+    /// ```
+    pub fn render_join_columns(
+        tables: &[TableSymbol],
+        force_qualifier: bool,
+    ) -> Vec<CompletionItem> {
+        Self::render_join_columns_with_config(tables, force_qualifier, &CompletionConfig::default())
+    }
+
+    /// Render JOIN condition column completion items with the given rendering options
+    ///
+    /// Foreign key targets are shown as in [`Self::render_columns_with_config`].
+    pub fn render_join_columns_with_config(
+        tables: &[TableSymbol],
+        force_qualifier: bool,
+        config: &CompletionConfig,
+    ) -> Vec<CompletionItem> {
+        let mut pk_columns: Vec<CompletionItem> = Vec::new();
+        let mut fk_columns: Vec<CompletionItem> = Vec::new();
+        let mut regular_columns: Vec<CompletionItem> = Vec::new();
+
+        for table in tables {
+            for column in table.columns.iter() {
+                let item =
+                    Self::column_item(column, table, force_qualifier, config.show_relationships);
+
+                if column.is_primary_key {
+                    // Mark as preselect (top suggestion)
+                    let mut item = item;
+                    item.preselect = Some(true);
+                    item.sort_text = Some(format!("00_pk_{}", column.name));
+                    pk_columns.push(item);
+                } else if column.is_foreign_key {
+                    // Mark as preselect (top suggestion)
+                    let mut item = item;
+                    item.preselect = Some(true);
+                    item.sort_text = Some(format!("01_fk_{}", column.name));
+                    fk_columns.push(item);
+                } else {
+                    // Regular columns
+                    let mut item = item;
+                    item.sort_text = Some(format!("02_{}", column.name));
+                    regular_columns.push(item);
+                }
+            }
+        }
+
+        // Concatenate in priority order: PK → FK → Regular
+        let mut items = Vec::new();
+        items.extend(pk_columns);
+        items.extend(fk_columns);
+        items.extend(regular_columns);
+
+        // The tier prefixes of the sort text keep PK → FK → Regular
+        sort_items(&mut items);
+        items
+    }
+
+    /// Render table completion items
+    ///
+    /// # Arguments
+    ///
+    /// * `tables` - Vector of table metadata from catalog
+    /// * `show_schema` - Whether to show schema qualifier (e.g., "public.users")
+    ///
+    /// # Returns
+    ///
+    /// Vector of completion items
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use unified_sql_lsp_lsp::completion::render::CompletionRenderer;
+    /// # use unified_sql_lsp_catalog::{TableMetadata, TableType};
+    /// # let tables = vec![
+    /// #     TableMetadata {
+    /// #         name: "users".to_string(),
+    /// #         schema: "public".to_string(),
+    /// #         columns: vec![],
+    /// #         row_count_estimate: None,
+    /// #         comment: None,
+    /// #         table_type: TableType::Table,
+    /// #     }
+    /// # ];
+    /// let items = CompletionRenderer::render_tables(&tables, false);
+    /// assert!(items.iter().any(|i| i.label == "users"));
+    /// ```
+    pub fn render_tables(tables: &[TableMetadata], show_schema: bool) -> Vec<CompletionItem> {
+        Self::render_table_items(tables, show_schema, None)
+    }
+
+    /// Render table completion items inserted as identifiers of `dialect`
+    ///
+    /// Names that can't be written bare are quoted in the insert text, e.g. a
+    /// PostgreSQL table created as `"Users"` inserts `"Users"` while `users`
+    /// inserts `users`. Labels stay unquoted.
+    pub fn render_tables_for_dialect(
+        tables: &[TableMetadata],
+        show_schema: bool,
+        dialect: Dialect,
+    ) -> Vec<CompletionItem> {
+        Self::render_table_items(tables, show_schema, Some(IdentifierComparer::new(dialect)))
+    }
+
+    fn render_table_items(
+        tables: &[TableMetadata],
+        show_schema: bool,
+        identifiers: Option<IdentifierComparer>,
+    ) -> Vec<CompletionItem> {
+        let mut items = Vec::new();
+
+        for table in tables {
+            items.push(Self::table_item(table, show_schema, identifiers.as_ref()));
+        }
+
+        // Sort alphabetically by label; same-named tables by schema
+        items.sort_by(|a, b| {
+            (&a.label, &a.sort_text, &a.detail).cmp(&(&b.label, &b.sort_text, &b.detail))
+        });
+
+        items
+    }
+
+    /// Render table completion items for the table position after JOIN
+    ///
+    /// When `join_on_scaffold` is enabled, the insert text is followed by
+    /// ` ON ` (plain text) or ` ON ${1:condition}` (snippet).
+    ///
+    /// # Arguments
+    ///
+    /// * `tables` - Vector of table metadata from catalog
+    /// * `show_schema` - Whether to show schema qualifier (e.g., "public.users")
+    /// * `dialect` - Dialect the names are quoted for, see [`Self::render_tables_for_dialect`]
+    /// * `config` - Completion insert behaviors
+    pub fn render_join_tables(
+        tables: &[TableMetadata],
+        show_schema: bool,
+        dialect: Dialect,
+        config: &CompletionConfig,
+    ) -> Vec<CompletionItem> {
+        let mut items = Self::render_tables_for_dialect(tables, show_schema, dialect);
+        Self::add_join_on_scaffold(&mut items, config);
+        items
+    }
+
+    /// Follow the insert text of table items with ` ON ` when
+    /// `join_on_scaffold` is enabled, see [`Self::render_join_tables`]
+    pub fn add_join_on_scaffold(items: &mut [CompletionItem], config: &CompletionConfig) {
+        if !config.join_on_scaffold {
+            return;
+        }
+        for item in items {
+            let table = item
+                .insert_text
+                .take()
+                .unwrap_or_else(|| item.label.clone());
+            if config.snippet_support {
+                item.insert_text = Some(format!("{} ON ${{1:condition}}", table));
+                item.insert_text_format = Some(InsertTextFormat::SNIPPET);
+            } else {
+                item.insert_text = Some(format!("{} ON ", table));
+                item.insert_text_format = Some(InsertTextFormat::PLAIN_TEXT);
+            }
+        }
+    }
+
+    /// Render CTE completion items for a table position
+    ///
+    /// Names are quoted for `dialect` like catalog tables, see
+    /// [`Self::render_tables_for_dialect`]. CTEs sort before catalog tables.
+    pub fn render_ctes(names: &[String], dialect: Dialect) -> Vec<CompletionItem> {
+        let identifiers = IdentifierComparer::new(dialect);
+        names
+            .iter()
+            .map(|name| CompletionItem {
+                label: name.clone(),
+                kind: Some(CompletionItemKind::CLASS),
+                detail: Some("CTE".to_string()),
+                sort_text: Some(format!("0_{}", name)),
+                filter_text: Some(identifiers.render(name)),
+                insert_text: Some(identifiers.render(name)),
+                data: Some(CompletionData::cte(name).encode()),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// Render SELECT list aliases for ORDER BY and HAVING
+    ///
+    /// `aliases` are as written in the query. The label is the name the
+    /// alias stands for; the insert text quotes it with the delimiters of
+    /// `dialect` when it was written quoted or needs quoting.
+    pub fn render_projection_aliases(aliases: &[String], dialect: Dialect) -> Vec<CompletionItem> {
+        let identifiers = IdentifierComparer::new(dialect);
+        aliases
+            .iter()
+            .map(|alias| {
+                let name = IdentifierComparer::unquote(alias);
+                let insert_text = if IdentifierComparer::is_quoted(alias) {
+                    identifiers.quote(&name)
+                } else {
+                    identifiers.render(&name)
+                };
+                CompletionItem {
+                    label: name.clone(),
+                    kind: Some(CompletionItemKind::VARIABLE),
+                    detail: Some("Column alias".to_string()),
+                    sort_text: Some(format!("0_{}", name)),
+                    filter_text: Some(insert_text.clone()),
+                    insert_text: Some(insert_text),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    /// Render a single table completion item
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The table metadata
+    /// * `show_schema` - Whether to include schema qualifier in label
+    /// * `identifiers` - Quotes names in the insert text when set
+    fn table_item(
+        table: &TableMetadata,
+        show_schema: bool,
+        identifiers: Option<&IdentifierComparer>,
+    ) -> CompletionItem {
+        let label = if show_schema {
+            format!("{}.{}", table.schema, table.name)
+        } else {
+            table.name.clone()
+        };
+
+        let detail = Self::format_table_detail(table);
+        let documentation = Self::format_table_documentation(table);
+        let render = |name: &str| match identifiers {
+            Some(identifiers) => identifiers.render(name),
+            None => name.to_string(),
+        };
+
+        CompletionItem {
+            label,
+            kind: Some(CompletionItemKind::CLASS),
+            detail: Some(detail),
+            documentation: Some(Documentation::String(documentation)),
+            deprecated: Some(false),
+            preselect: Some(false),
+            sort_text: Some(Self::table_sort_text(table, show_schema)),
+            filter_text: Some(render(&table.name)),
+            insert_text: Some(if show_schema {
+                format!("{}.{}", render(&table.schema), render(&table.name))
+            } else {
+                render(&table.name)
+            }),
+            data: Some(
+                match table.table_type {
+                    TableType::View | TableType::MaterializedView => {
+                        CompletionData::view(&table.name)
+                    }
+                    _ => CompletionData::table(&table.name),
+                }
+                .with_schema(&table.schema)
+                .encode(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    /// Format the detail string for a table
+    ///
+    /// Shows the schema name and table type
+    fn format_table_detail(table: &TableMetadata) -> String {
+        let type_str = match &table.table_type {
+            TableType::Table => "TABLE",
+            TableType::View => "VIEW",
+            TableType::MaterializedView => "MATERIALIZED VIEW",
+            TableType::Temporary => "TEMPORARY",
+            TableType::System => "SYSTEM",
+            TableType::Other(s) => s,
+        };
+        format!("{}.{} [{}]", table.schema, table.name, type_str)
+    }
+
+    /// Format the documentation string for a table
+    ///
+    /// Shows column count and comment if available
+    fn format_table_documentation(table: &TableMetadata) -> String {
+        let mut parts = Vec::new();
+
+        // Add column count
+        let column_count = table.columns.len();
+        if column_count > 0 {
+            parts.push(format!("{} columns", column_count));
+
+            // List column names if there are few (<= 5)
+            if column_count <= 5 {
+                let column_names: Vec<&str> =
+                    table.columns.iter().map(|c| c.name.as_str()).collect();
+                parts.push(format!("Columns: {}", column_names.join(", ")));
+            }
+        }
+
+        // Add comment if available
+        if let Some(comment) = &table.comment {
+            parts.push(comment.clone());
+        }
+
+        // Add row count estimate if available
+        if let Some(row_count) = table.row_count_estimate {
+            parts.push(format!("~{} rows", row_count));
+        }
+
+        if parts.is_empty() {
+            "Database table".to_string()
+        } else {
+            parts.join("\n\n")
+        }
+    }
+
+    /// Generate sort text for a table
+    ///
+    /// Tables are sorted alphabetically by schema.table name
+    fn table_sort_text(table: &TableMetadata, show_schema: bool) -> String {
+        if show_schema {
+            format!("{}_.{}", table.schema, table.name)
+        } else {
+            format!("{}_{}", table.schema, table.name)
+        }
+    }
+
+    /// Render a single column completion item
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - The column symbol
+    /// * `table` - The table symbol
+    /// * `qualified` - Whether to use qualified name (e.g., "users.id")
+    /// * `show_relationships` - Whether to show the foreign key target
+    fn column_item(
+        column: &ColumnSymbol,
+        table: &TableSymbol,
+        qualified: bool,
+        show_relationships: bool,
+    ) -> CompletionItem {
+        let label = if qualified {
+            format!("{}.{}", table.display_name(), column.name)
+        } else {
+            column.name.clone()
+        };
+
+        let mut detail = Self::format_column_detail(column);
+        let mut label_details = None;
+        if show_relationships && let Some(target) = &column.references {
+            let relationship = format!("→ {}.{}", target.table, target.column);
+            detail = format!("{} {}", detail, relationship);
+            label_details = Some(CompletionItemLabelDetails {
+                detail: None,
+                description: Some(relationship),
+            });
+        }
+
+        CompletionItem {
+            label,
+            label_details,
+            kind: Some(CompletionItemKind::FIELD),
+            detail: Some(detail),
+            documentation: None,
+            deprecated: Some(false),
+            preselect: Some(false),
+            sort_text: Some(Self::sort_text(column)),
+            filter_text: Some(column.name.clone()),
+            insert_text: Some(if qualified {
+                format!("{}.{}", table.display_name(), column.name)
+            } else {
+                column.name.clone()
+            }),
+            data: Some(CompletionData::column(&table.table_name, &column.name).encode()),
+            ..Default::default()
+        }
+    }
+
+    /// Render parameters completed like columns
+    ///
+    /// Each parameter's `table_name` is the object declaring it, e.g. the
+    /// function whose body is being completed.
+    pub fn render_parameters(parameters: &[ColumnSymbol]) -> Vec<CompletionItem> {
+        let mut items: Vec<CompletionItem> = parameters
+            .iter()
+            .map(|parameter| CompletionItem {
+                label: parameter.name.clone(),
+                kind: Some(CompletionItemKind::VARIABLE),
+                detail: Some(Self::format_column_detail(parameter)),
+                documentation: Some(Documentation::String(format!(
+                    "Parameter of {}",
+                    parameter.table_name
+                ))),
+                sort_text: Some(Self::sort_text(parameter)),
+                data: Some(CompletionData::column(&parameter.table_name, &parameter.name).encode()),
+                ..Default::default()
+            })
+            .collect();
+
+        sort_items(&mut items);
+        items
+    }
+
+    /// Create a wildcard (*) completion item
+    pub fn wildcard_item() -> CompletionItem {
+        CompletionItem {
+            label: "*".to_string(),
+            kind: Some(CompletionItemKind::FIELD),
+            detail: Some("All columns".to_string()),
+            documentation: Some(Documentation::String(
+                "Selects all columns from all tables in the FROM clause".to_string(),
+            )),
+            sort_text: Some("00_wildcard".to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Format the detail string for a column
+    ///
+    /// Shows the data type and whether it's nullable
+    fn format_column_detail(column: &ColumnSymbol) -> String {
+        format_data_type(&column.data_type)
+    }
+
+    /// Generate sort text for a column
+    ///
+    /// Columns are sorted alphabetically by name
+    fn sort_text(column: &ColumnSymbol) -> String {
+        format!("01_{}", column.name)
+    }
+
+    /// Render function completion items
+    ///
+    /// Uses the default [`CompletionConfig`], see [`Self::render_functions_with_config`].
+    ///
+    /// # Arguments
+    ///
+    /// * `functions` - Vector of function metadata
+    /// * `filter` - Optional function type filter (None = show all)
+    ///
+    /// # Returns
+    ///
+    /// Vector of completion items
+    ///
+    /// # Examples
+    ///
+    /// To show all functions:
+    ///
+    /// ```text,ignore
+    /// let items = CompletionRenderer::render_functions(&functions, None);
+    /// ```
+    ///
+    /// To show only aggregate functions:
+    ///
+    /// ```text,ignore
+    /// let items = CompletionRenderer::render_functions(
+    ///     &functions,
+    ///     Some(FunctionType::Aggregate)
+    /// );
+    /// ```
+    pub fn render_functions(
+        functions: &[FunctionMetadata],
+        filter: Option<FunctionType>,
+    ) -> Vec<CompletionItem> {
+        Self::render_functions_with_config(functions, filter, &CompletionConfig::default())
+    }
+
+    /// Render function completion items with the given insert behaviors
+    ///
+    /// # Arguments
+    ///
+    /// * `functions` - Vector of function metadata
+    /// * `filter` - Optional function type filter (None = show all)
+    /// * `config` - Completion insert behaviors
+    ///
+    /// # Insert Text
+    ///
+    /// - `function_call_parens` off: `NAME`
+    /// - `function_call_parens` on, snippets supported: `NAME($1)` (cursor inside)
+    /// - `function_call_parens` on, no snippet support: `NAME()`
+    /// - `aggregate_star_variant` on: an extra `COUNT(*)` item after `COUNT`
+    pub fn render_functions_with_config(
+        functions: &[FunctionMetadata],
+        filter: Option<FunctionType>,
+        config: &CompletionConfig,
+    ) -> Vec<CompletionItem> {
+        let mut items = Vec::new();
+
+        for function in functions {
+            // Apply filter if specified
+            if let Some(ft) = &filter
+                && &function.function_type != ft
+            {
+                continue;
+            }
+
+            items.push(Self::function_item(function, config));
+
+            if config.aggregate_star_variant
+                && function.function_type == FunctionType::Aggregate
+                && STAR_AGGREGATES.contains(&function.name.to_lowercase().as_str())
+            {
+                items.push(Self::star_variant_item(function));
+            }
+        }
+
+        // Sort by function type priority, then alphabetically
+        sort_items(&mut items);
+
+        items
+    }
+
+    /// Render a single function completion item
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function metadata
+    /// * `config` - Completion insert behaviors
+    fn function_item(function: &FunctionMetadata, config: &CompletionConfig) -> CompletionItem {
+        let label = function.name.clone();
+        let detail = Self::format_function_detail(function);
+        let documentation = Self::format_function_documentation(function);
+
+        let (insert_text, insert_text_format) = if !config.function_call_parens {
+            (function.name.clone(), InsertTextFormat::PLAIN_TEXT)
+        } else if config.snippet_support {
+            (format!("{}($1)", function.name), InsertTextFormat::SNIPPET)
+        } else {
+            (format!("{}()", function.name), InsertTextFormat::PLAIN_TEXT)
+        };
+
+        CompletionItem {
+            label,
+            kind: Some(CompletionItemKind::CLASS), // TODO: (COMPLETION-006) Use Function when tower-lsp upgrades to LSP 3.17+
+            detail: Some(detail),
+            documentation: Some(Documentation::String(documentation)),
+            deprecated: Some(false),
+            preselect: Some(false),
+            sort_text: Some(Self::function_sort_text(function)),
+            filter_text: Some(function.name.clone()),
+            insert_text: Some(insert_text),
+            insert_text_format: Some(insert_text_format),
+            data: Some(CompletionData::function(&function.name).encode()),
+            ..Default::default()
+        }
+    }
+
+    /// Render the `NAME(*)` variant of an aggregate function
+    ///
+    /// Sorted directly after the plain function item.
+    fn star_variant_item(function: &FunctionMetadata) -> CompletionItem {
+        let call = format!("{}(*)", function.name);
+
+        CompletionItem {
+            label: call.clone(),
+            kind: Some(CompletionItemKind::CLASS),
+            detail: Some(Self::format_function_detail(function)),
+            documentation: Some(Documentation::String(format!(
+                "Count all rows, including NULLs\n\n{}",
+                Self::format_function_documentation(function)
+            ))),
+            deprecated: Some(false),
+            preselect: Some(false),
+            sort_text: Some(format!("{}_star", Self::function_sort_text(function))),
+            filter_text: Some(function.name.clone()),
+            insert_text: Some(call),
+            insert_text_format: Some(InsertTextFormat::PLAIN_TEXT),
+            data: Some(CompletionData::function(&function.name).encode()),
+            ..Default::default()
+        }
+    }
+
+    /// Generate sort text for a function
+    ///
+    /// Functions are sorted by type priority, then alphabetically
+    fn function_sort_text(function: &FunctionMetadata) -> String {
+        let sort_prefix = match function.function_type {
+            FunctionType::Aggregate => "00_aggregate_",
+            FunctionType::Window => "01_window_",
+            FunctionType::Table => "02_table_",
+            FunctionType::Scalar => "03_scalar_",
+        };
+        format!("{}{}", sort_prefix, function.name)
+    }
+
+    /// Format the detail string for a function
+    ///
+    /// Shows the function signature with parameters and return type
+    fn format_function_detail(function: &FunctionMetadata) -> String {
+        // Use the existing signature() method from FunctionMetadata
+        function.signature()
+    }
+
+    /// Format the documentation string for a function
+    ///
+    /// Shows description, example, and parameter details
+    fn format_function_documentation(function: &FunctionMetadata) -> String {
+        let mut parts = Vec::new();
+
+        // Add description
+        if let Some(desc) = &function.description {
+            parts.push(desc.clone());
+        }
+
+        // Add parameter details
+        if !function.parameters.is_empty() {
+            let params: Vec<String> = function
+                .parameters
+                .iter()
+                .map(|p| {
+                    let default = if p.has_default { " = default" } else { "" };
+                    let variadic = if p.is_variadic { "..." } else { "" };
+                    format!("- `{} {:?}{}{}`", p.name, p.data_type, variadic, default)
+                })
+                .collect();
+            parts.push(format!("Parameters:\n{}", params.join("\n")));
+        }
+
+        // Add example if available
+        if let Some(example) = &function.example {
+            parts.push(format!("Example:\n```sql\n{}\n```", example));
+        }
+
+        if parts.is_empty() {
+            "SQL function".to_string()
+        } else {
+            parts.join("\n\n")
+        }
+    }
+
+    /// Render completion items for the window reference after `OVER`
+    ///
+    /// Offers each named window from the WINDOW clause, followed by an item
+    /// that opens an inline window specification.
+    ///
+    /// # Arguments
+    ///
+    /// * `windows` - Window names defined in the statement
+    /// * `config` - Completion insert behaviors
+    pub fn render_window_names(
+        windows: &[String],
+        config: &CompletionConfig,
+    ) -> Vec<CompletionItem> {
+        let mut items: Vec<CompletionItem> = windows
+            .iter()
+            .map(|name| CompletionItem {
+                label: name.clone(),
+                kind: Some(CompletionItemKind::REFERENCE),
+                detail: Some("Named window".to_string()),
+                documentation: Some(Documentation::String(format!(
+                    "Window defined in the WINDOW clause as {}",
+                    name
+                ))),
+                sort_text: Some(format!("00_{}", name)),
+                ..Default::default()
+            })
+            .collect();
+
+        let (insert_text, insert_text_format) = if config.snippet_support {
+            ("($0)", InsertTextFormat::SNIPPET)
+        } else {
+            ("(", InsertTextFormat::PLAIN_TEXT)
+        };
+        items.push(CompletionItem {
+            label: "(".to_string(),
+            kind: Some(CompletionItemKind::SNIPPET),
+            detail: Some("Inline window specification".to_string()),
+            documentation: Some(Documentation::String(
+                "OVER (PARTITION BY ... ORDER BY ...)".to_string(),
+            )),
+            sort_text: Some("01_(".to_string()),
+            insert_text: Some(insert_text.to_string()),
+            insert_text_format: Some(insert_text_format),
+            ..Default::default()
+        });
+
+        sort_items(&mut items);
+        items
+    }
+
+    /// Render statement templates as snippet items
+    ///
+    /// Returns nothing for clients without snippet support, which get the
+    /// plain statement keywords only. Keywords of a template are inserted in
+    /// the configured `keyword_case`; placeholder texts are kept. Read-only
+    /// workspaces get no templates of write statements.
+    pub fn render_statement_templates(
+        templates: &[StatementTemplate],
+        config: &CompletionConfig,
+    ) -> Vec<CompletionItem> {
+        if !config.snippet_support {
+            return Vec::new();
+        }
+
+        let mut items: Vec<CompletionItem> = templates
+            .iter()
+            .filter(|template| !(config.read_only && template.writes))
+            .map(|template| CompletionItem {
+                label: template.label.clone(),
+                kind: Some(CompletionItemKind::SNIPPET),
+                detail: Some("Statement template".to_string()),
+                documentation: Some(Documentation::String(format!(
+                    "{}\n\n```sql\n{}\n```",
+                    template.description,
+                    snippet_text(&template.snippet)
+                ))),
+                // Right after the statement keyword
+                sort_text: Some(format!("{:05}_{}", template.sort_priority, template.label)),
+                filter_text: Some(template.keyword.clone()),
+                insert_text: Some(case_outside_placeholders(
+                    &template.snippet,
+                    config.keyword_case,
+                )),
+                insert_text_format: Some(InsertTextFormat::SNIPPET),
+                ..Default::default()
+            })
+            .collect();
+
+        sort_items(&mut items);
+        items
+    }
+
+    /// Render keyword completion items
+    ///
+    /// # Arguments
+    ///
+    /// * `keywords` - Vector of SQL keywords
+    ///
+    /// # Returns
+    ///
+    /// Vector of completion items
+    pub fn render_keywords(keywords: &[SqlKeyword]) -> Vec<CompletionItem> {
+        let mut items = Vec::new();
+
+        for keyword in keywords {
+            items.push(Self::keyword_item(keyword));
+        }
+
+        // Sort by priority
+        sort_items(&mut items);
+
+        items
+    }
+
+    /// Insert keyword items in the given case
+    ///
+    /// Labels keep their canonical spelling; only the inserted text (and
+    /// text edit, if any) changes.
+    pub fn apply_keyword_case(items: &mut [CompletionItem], case: KeywordCase) {
+        for item in items
+            .iter_mut()
+            .filter(|item| item.kind == Some(CompletionItemKind::KEYWORD))
+        {
+            let text = case.apply(item.insert_text.as_deref().unwrap_or(&item.label));
+            match &mut item.text_edit {
+                Some(CompletionTextEdit::Edit(edit)) => edit.new_text = case.apply(&edit.new_text),
+                Some(CompletionTextEdit::InsertAndReplace(edit)) => {
+                    edit.new_text = case.apply(&edit.new_text)
+                }
+                None => {}
+            }
+            if item.insert_text.is_some() || text != item.label {
+                item.insert_text = Some(text);
+            }
+        }
+    }
+
+    /// Make every item replace `token` instead of relying on the client
+    ///
+    /// Sets an [`InsertReplaceEdit`] when the client supports one and a plain
+    /// [`TextEdit`] over the typed part otherwise. Items that already carry a
+    /// text edit are left alone.
+    pub fn apply_text_edits(
+        items: &mut [CompletionItem],
+        token: &TypedToken,
+        insert_replace_support: bool,
+    ) {
+        for item in items.iter_mut().filter(|item| item.text_edit.is_none()) {
+            let new_text = token.new_text(item);
+            item.text_edit = Some(if insert_replace_support {
+                CompletionTextEdit::InsertAndReplace(InsertReplaceEdit {
+                    new_text,
+                    insert: token.insert,
+                    replace: token.replace,
+                })
+            } else {
+                CompletionTextEdit::Edit(TextEdit::new(token.insert, new_text))
+            });
+        }
+    }
+
+    /// Render a single keyword completion item
+    ///
+    /// # Arguments
+    ///
+    /// * `keyword` - The SQL keyword
+    fn keyword_item(keyword: &SqlKeyword) -> CompletionItem {
+        let label = keyword.label.clone();
+        let documentation = if let Some(desc) = &keyword.description {
+            Documentation::String(desc.clone())
+        } else {
+            Documentation::String("SQL keyword".to_string())
+        };
+
+        let mut item = CompletionItem {
+            label,
+            kind: Some(CompletionItemKind::KEYWORD),
+            detail: Some("SQL keyword".to_string()),
+            documentation: Some(documentation),
+            deprecated: Some(false),
+            preselect: Some(false),
+            sort_text: Some(format!("{:05}_{}", keyword.sort_priority, keyword.label)),
+            data: Some(CompletionData::keyword(&keyword.label).encode()),
+            ..Default::default()
+        };
+        if let Some(requires) = &keyword.requires {
+            Self::mark_unsupported(&mut item, requires);
+        }
+        item
+    }
+
+    /// Mark an item the configured dialect version doesn't support
+    ///
+    /// The item is tagged deprecated (the closest LSP has to "unavailable"),
+    /// its detail starts with `⚠ requires <requires>`, and it sorts after
+    /// the supported items.
+    pub fn mark_unsupported(item: &mut CompletionItem, requires: &str) {
+        item.tags = Some(vec![CompletionItemTag::DEPRECATED]);
+        item.deprecated = Some(true);
+        item.detail = Some(match item.detail.take() {
+            Some(detail) => format!("⚠ requires {} · {}", requires, detail),
+            None => format!("⚠ requires {}", requires),
+        });
+        let sort_text = item.sort_text.take().unwrap_or_else(|| item.label.clone());
+        item.sort_text = Some(format!("~{}", sort_text));
+    }
+}
+
+/// `snippet` with each placeholder replaced by its default text
+fn snippet_text(snippet: &str) -> String {
+    let mut text = String::with_capacity(snippet.len());
+    let mut rest = snippet;
+    while let Some(start) = rest.find('$') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        if let Some(placeholder) = rest.strip_prefix('{')
+            && let Some(end) = placeholder.find('}')
+        {
+            let body = &placeholder[..end];
+            text.push_str(body.split_once(':').map_or("", |(_, default)| default));
+            rest = &placeholder[end + 1..];
+        } else {
+            rest = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// `snippet` in `case`, leaving the `${n:...}` placeholders alone
+fn case_outside_placeholders(snippet: &str, case: KeywordCase) -> String {
+    let mut cased = String::with_capacity(snippet.len());
+    let mut rest = snippet;
+    while let Some(start) = rest.find("${") {
+        cased.push_str(&case.apply(&rest[..start]));
+        let end = rest[start..]
+            .find('}')
+            .map_or(rest.len(), |end| start + end + 1);
+        cased.push_str(&rest[start..end]);
+        rest = &rest[end..];
+    }
+    cased.push_str(&case.apply(rest));
+    cased
+}
+
+/// Order items by sort text, then label and detail
+///
+/// Catalogs and scopes don't guarantee an iteration order, so render
+/// functions return items in this total order to keep output stable.
+fn sort_items(items: &mut [CompletionItem]) {
+    items.sort_by(|a, b| {
+        (&a.sort_text, &a.label, &a.detail).cmp(&(&b.sort_text, &b.label, &b.detail))
+    });
+}
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(test)]
+mod snapshot_tests;
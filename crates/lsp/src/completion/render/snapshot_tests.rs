@@ -0,0 +1,235 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! Golden snapshot tests for [`CompletionRenderer`] output
+//!
+//! Each case renders a fixed input and compares the full item list, serialized
+//! as pretty JSON, against `snapshots/<name>.json` next to this file. The
+//! opaque `data` field is replaced with `"[data]"` before comparison.
+//!
+//! ## Updating snapshots
+//!
+//! After an intentional rendering change, regenerate and review the diff:
+//!
+//! ```text
+//! make test-snapshots-update
+//! git diff crates/lsp/src/completion/render/snapshots
+//! ```
+//!
+//! which runs these tests with `UPDATE_SNAPSHOTS=1`. A missing snapshot file
+//! is written on first run and the test fails so the new file gets reviewed.
+
+use std::path::PathBuf;
+
+use tower_lsp::lsp_types::CompletionItem;
+use unified_sql_lsp_catalog::{
+    ColumnMetadata, DataType, FunctionMetadata, FunctionParameter, FunctionType, TableMetadata,
+};
+use unified_sql_lsp_context::SqlKeyword;
+use unified_sql_lsp_semantic::{ColumnSymbol, TableSymbol};
+
+use super::*;
+
+fn snapshot_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/completion/render/snapshots")
+        .join(format!("{name}.json"))
+}
+
+fn serialize(items: &[CompletionItem]) -> String {
+    let mut value = serde_json::to_value(items).expect("completion items serialize");
+    if let Some(items) = value.as_array_mut() {
+        for item in items {
+            if let Some(data) = item.get_mut("data") {
+                *data = serde_json::Value::String("[data]".to_string());
+            }
+        }
+    }
+    let mut out = serde_json::to_string_pretty(&value).expect("JSON value serializes");
+    out.push('\n');
+    out
+}
+
+fn assert_snapshot(name: &str, items: &[CompletionItem]) {
+    let actual = serialize(items);
+    let path = snapshot_path(name);
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+
+    if update || !path.exists() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, &actual).unwrap();
+        assert!(
+            update,
+            "snapshot {} was missing and has been written; review it and rerun",
+            path.display()
+        );
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        expected == actual,
+        "snapshot {name} differs from {}\n\
+         rerun with UPDATE_SNAPSHOTS=1 if the change is intended\n\
+         --- expected\n{expected}\n+++ actual\n{actual}",
+        path.display()
+    );
+}
+
+/// `users` and `orders` joined on `orders.user_id`
+fn scope_tables() -> Vec<TableSymbol> {
+    vec![
+        TableSymbol::new("users").with_alias("u").with_columns(vec![
+            ColumnSymbol::new("id", DataType::Integer, "users").with_primary_key(),
+            ColumnSymbol::new("name", DataType::Text, "users"),
+            ColumnSymbol::new("email", DataType::Varchar(Some(255)), "users"),
+        ]),
+        TableSymbol::new("orders").with_columns(vec![
+            ColumnSymbol::new("id", DataType::Integer, "orders").with_primary_key(),
+            ColumnSymbol::new("user_id", DataType::Integer, "orders").with_foreign_key(),
+            ColumnSymbol::new("total", DataType::Decimal, "orders"),
+        ]),
+    ]
+}
+
+fn catalog_tables() -> Vec<TableMetadata> {
+    vec![
+        TableMetadata::new("users", "public")
+            .with_comment("Registered users")
+            .with_columns(vec![
+                ColumnMetadata::new("id", DataType::Integer).with_primary_key(),
+                ColumnMetadata::new("name", DataType::Text),
+            ]),
+        TableMetadata::new("orders", "public").with_columns(vec![
+            ColumnMetadata::new("id", DataType::Integer).with_primary_key(),
+            ColumnMetadata::new("user_id", DataType::Integer).with_foreign_key("users", "id"),
+        ]),
+    ]
+}
+
+fn functions() -> Vec<FunctionMetadata> {
+    let param = |name: &str, data_type| FunctionParameter {
+        name: name.to_string(),
+        data_type,
+        has_default: false,
+        is_variadic: false,
+    };
+
+    vec![
+        FunctionMetadata::new("upper", DataType::Text)
+            .with_type(FunctionType::Scalar)
+            .with_parameters(vec![param("str", DataType::Text)])
+            .with_description("Convert to upper case"),
+        FunctionMetadata::new("count", DataType::BigInt)
+            .with_type(FunctionType::Aggregate)
+            .with_parameters(vec![param("expr", DataType::Integer)])
+            .with_example("COUNT(*)"),
+        FunctionMetadata::new("row_number", DataType::BigInt).with_type(FunctionType::Window),
+        FunctionMetadata::new("generate_series", DataType::Integer)
+            .with_type(FunctionType::Table)
+            .with_parameters(vec![
+                param("start", DataType::Integer),
+                param("stop", DataType::Integer),
+            ]),
+    ]
+}
+
+fn keywords() -> Vec<SqlKeyword> {
+    vec![
+        SqlKeyword::new("WHERE", Some("Filter rows"), 2),
+        SqlKeyword::new("SELECT", Some("Retrieve rows"), 1),
+        SqlKeyword::new("FROM", None, 1),
+        SqlKeyword::new("LIMIT", Some("Limit the result"), 3),
+    ]
+}
+
+#[test]
+fn snapshot_columns() {
+    assert_snapshot(
+        "columns",
+        &CompletionRenderer::render_columns(&scope_tables(), false),
+    );
+}
+
+#[test]
+fn snapshot_join_columns() {
+    assert_snapshot(
+        "join_columns",
+        &CompletionRenderer::render_join_columns(&scope_tables(), true),
+    );
+}
+
+#[test]
+fn snapshot_tables() {
+    assert_snapshot(
+        "tables",
+        &CompletionRenderer::render_tables(&catalog_tables(), false),
+    );
+}
+
+#[test]
+fn snapshot_join_tables_snippet() {
+    let config = CompletionConfig {
+        join_on_scaffold: true,
+        snippet_support: true,
+        ..CompletionConfig::default()
+    };
+    assert_snapshot(
+        "join_tables_snippet",
+        &CompletionRenderer::render_join_tables(&catalog_tables(), false, Dialect::MySQL, &config),
+    );
+}
+
+#[test]
+fn snapshot_join_tables_plain() {
+    let config = CompletionConfig {
+        join_on_scaffold: true,
+        ..CompletionConfig::default()
+    };
+    assert_snapshot(
+        "join_tables_plain",
+        &CompletionRenderer::render_join_tables(&catalog_tables(), false, Dialect::MySQL, &config),
+    );
+}
+
+#[test]
+fn snapshot_functions() {
+    let config = CompletionConfig {
+        snippet_support: true,
+        ..CompletionConfig::default()
+    };
+    assert_snapshot(
+        "functions",
+        &CompletionRenderer::render_functions_with_config(&functions(), None, &config),
+    );
+}
+
+#[test]
+fn snapshot_keywords() {
+    assert_snapshot(
+        "keywords",
+        &CompletionRenderer::render_keywords(&keywords()),
+    );
+}
+
+#[test]
+fn test_render_is_order_independent() {
+    let mut reversed = scope_tables();
+    reversed.reverse();
+    assert_eq!(
+        serialize(&CompletionRenderer::render_join_columns(
+            &scope_tables(),
+            true
+        )),
+        serialize(&CompletionRenderer::render_join_columns(&reversed, true)),
+    );
+
+    let mut reversed = functions();
+    reversed.reverse();
+    assert_eq!(
+        serialize(&CompletionRenderer::render_functions(&functions(), None)),
+        serialize(&CompletionRenderer::render_functions(&reversed, None)),
+    );
+}
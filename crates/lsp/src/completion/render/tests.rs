@@ -0,0 +1,1122 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! Unit tests for [`CompletionRenderer`]
+
+use super::*;
+use unified_sql_lsp_catalog::{ColumnMetadata, DataType, TableType};
+
+#[test]
+fn test_render_columns_simple() {
+    let table = TableSymbol::new("users").with_columns(vec![
+        ColumnSymbol::new("id", DataType::Integer, "users"),
+        ColumnSymbol::new("name", DataType::Text, "users"),
+    ]);
+
+    let items = CompletionRenderer::render_columns(&[table], false);
+
+    // Should have wildcard + 2 columns
+    assert_eq!(items.len(), 3);
+    assert!(items.iter().any(|i| i.label == "id"));
+    assert!(items.iter().any(|i| i.label == "name"));
+    assert!(items.iter().any(|i| i.label == "*"));
+}
+
+#[test]
+fn test_render_columns_qualified() {
+    let table = TableSymbol::new("users").with_columns(vec![ColumnSymbol::new(
+        "id",
+        DataType::Integer,
+        "users",
+    )]);
+
+    let items = CompletionRenderer::render_columns(&[table], true);
+
+    assert!(items.iter().any(|i| i.label == "users.id"));
+}
+
+#[test]
+fn test_render_columns_with_alias() {
+    let table = TableSymbol::new("users")
+        .with_alias("u")
+        .with_columns(vec![ColumnSymbol::new("id", DataType::Integer, "users")]);
+
+    let items = CompletionRenderer::render_columns(&[table], true);
+
+    // Should use alias "u" instead of table name "users"
+    assert!(items.iter().any(|i| i.label == "u.id"));
+}
+
+#[test]
+fn test_render_columns_ambiguous() {
+    let table1 = TableSymbol::new("users").with_columns(vec![ColumnSymbol::new(
+        "id",
+        DataType::Integer,
+        "users",
+    )]);
+
+    let table2 = TableSymbol::new("orders").with_columns(vec![ColumnSymbol::new(
+        "id",
+        DataType::Integer,
+        "orders",
+    )]);
+
+    let items = CompletionRenderer::render_columns(&[table1, table2], false);
+
+    // Both columns are named "id", so both should be qualified
+    let id_items: Vec<_> = items.iter().filter(|i| i.label.contains("id")).collect();
+    assert_eq!(id_items.len(), 2);
+    assert!(id_items.iter().any(|i| i.label == "users.id"));
+    assert!(id_items.iter().any(|i| i.label == "orders.id"));
+}
+
+#[test]
+fn test_render_columns_show_relationships() {
+    let orders = TableSymbol::new("orders").with_columns(vec![
+        ColumnSymbol::new("id", DataType::Integer, "orders").with_primary_key(),
+        ColumnSymbol::new("user_id", DataType::Integer, "orders").with_reference("users", "id"),
+    ]);
+    let find = |items: &[CompletionItem], label: &str| {
+        items.iter().find(|i| i.label == label).cloned().unwrap()
+    };
+
+    let items = CompletionRenderer::render_columns(std::slice::from_ref(&orders), false);
+    let user_id = find(&items, "user_id");
+    assert_eq!(user_id.detail.as_deref(), Some("Integer → users.id"));
+    assert_eq!(
+        user_id.label_details.unwrap().description.as_deref(),
+        Some("→ users.id")
+    );
+    let id = find(&items, "id");
+    assert_eq!(id.detail.as_deref(), Some("Integer"));
+    assert!(id.label_details.is_none());
+
+    let join = CompletionRenderer::render_join_columns(std::slice::from_ref(&orders), true);
+    let user_id = find(&join, "orders.user_id");
+    assert_eq!(user_id.detail.as_deref(), Some("Integer → users.id"));
+
+    let config = CompletionConfig {
+        show_relationships: false,
+        ..Default::default()
+    };
+    for items in [
+        CompletionRenderer::render_columns_with_config(
+            std::slice::from_ref(&orders),
+            false,
+            &config,
+        ),
+        CompletionRenderer::render_join_columns_with_config(
+            std::slice::from_ref(&orders),
+            false,
+            &config,
+        ),
+    ] {
+        let user_id = find(&items, "user_id");
+        assert_eq!(user_id.detail.as_deref(), Some("Integer"));
+        assert!(user_id.label_details.is_none());
+    }
+
+    let settings = serde_json::json!({ "showRelationships": false });
+    assert_eq!(CompletionConfig::from_lsp_settings(&settings), config);
+}
+
+#[test]
+fn test_format_data_type() {
+    assert_eq!(format_data_type(&DataType::Integer), "Integer");
+    assert_eq!(
+        format_data_type(&DataType::Varchar(Some(255))),
+        "VarChar(255)"
+    );
+    assert_eq!(format_data_type(&DataType::Text), "Text");
+    assert_eq!(
+        format_data_type(&DataType::Array(Box::new(DataType::Integer))),
+        "Integer[]"
+    );
+}
+
+#[test]
+fn test_sort_text() {
+    let pk_col = ColumnSymbol::new("id", DataType::Integer, "users");
+    let regular_col = ColumnSymbol::new("name", DataType::Text, "users");
+
+    let pk_sort = CompletionRenderer::sort_text(&pk_col);
+    let regular_sort = CompletionRenderer::sort_text(&regular_col);
+
+    // Sort alphabetically by name
+    assert_eq!(pk_sort, "01_id");
+    assert_eq!(regular_sort, "01_name");
+    assert!(pk_sort < regular_sort);
+}
+
+#[test]
+fn test_render_tables_simple() {
+    let table = TableMetadata::new("users", "public")
+        .with_columns(vec![
+            ColumnMetadata::new("id", DataType::Integer).with_primary_key(),
+            ColumnMetadata::new("name", DataType::Text),
+        ])
+        .with_row_count(100);
+
+    let items = CompletionRenderer::render_tables(&[table], false);
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].label, "users");
+    assert_eq!(items[0].kind, Some(CompletionItemKind::CLASS));
+    assert!(items[0].detail.as_ref().unwrap().contains("TABLE"));
+    match items[0].documentation.as_ref().unwrap() {
+        Documentation::String(s) => assert!(s.contains("2 columns")),
+        Documentation::MarkupContent(m) => assert!(m.value.contains("2 columns")),
+    }
+}
+
+#[test]
+fn test_render_tables_with_schema() {
+    let table = TableMetadata::new("users", "public")
+        .with_columns(vec![ColumnMetadata::new("id", DataType::Integer)]);
+
+    let items = CompletionRenderer::render_tables(&[table], true);
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].label, "public.users");
+    assert!(items[0].detail.as_ref().unwrap().contains("public"));
+}
+
+#[test]
+fn test_render_tables_multiple_schemas() {
+    let table1 = TableMetadata::new("users", "public")
+        .with_columns(vec![ColumnMetadata::new("id", DataType::Integer)]);
+    let table2 = TableMetadata::new("users", "myapp")
+        .with_columns(vec![ColumnMetadata::new("id", DataType::Integer)]);
+
+    let items = CompletionRenderer::render_tables(&[table1, table2], true);
+
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().any(|i| i.label == "public.users"));
+    assert!(items.iter().any(|i| i.label == "myapp.users"));
+}
+
+#[test]
+fn test_render_tables_with_view() {
+    let view = TableMetadata::new("active_users", "public")
+        .with_columns(vec![ColumnMetadata::new("id", DataType::Integer)])
+        .with_type(TableType::View);
+
+    let items = CompletionRenderer::render_tables(&[view], false);
+
+    assert_eq!(items.len(), 1);
+    assert!(items[0].detail.as_ref().unwrap().contains("VIEW"));
+}
+
+#[test]
+fn test_render_tables_with_materialized_view() {
+    let mv = TableMetadata::new("user_summary", "public")
+        .with_columns(vec![ColumnMetadata::new("id", DataType::Integer)])
+        .with_type(TableType::MaterializedView);
+
+    let items = CompletionRenderer::render_tables(&[mv], false);
+
+    assert_eq!(items.len(), 1);
+    assert!(
+        items[0]
+            .detail
+            .as_ref()
+            .unwrap()
+            .contains("MATERIALIZED VIEW")
+    );
+}
+
+#[test]
+fn test_render_tables_with_comment() {
+    let table = TableMetadata::new("users", "public")
+        .with_columns(vec![ColumnMetadata::new("id", DataType::Integer)])
+        .with_comment("User accounts table");
+
+    let items = CompletionRenderer::render_tables(&[table], false);
+
+    assert_eq!(items.len(), 1);
+    match items[0].documentation.as_ref().unwrap() {
+        Documentation::String(s) => assert!(s.contains("User accounts table")),
+        Documentation::MarkupContent(m) => assert!(m.value.contains("User accounts table")),
+    }
+}
+
+#[test]
+fn test_render_tables_few_columns_lists_names() {
+    let table = TableMetadata::new("users", "public").with_columns(vec![
+        ColumnMetadata::new("id", DataType::Integer),
+        ColumnMetadata::new("name", DataType::Text),
+        ColumnMetadata::new("email", DataType::Text),
+    ]);
+
+    let items = CompletionRenderer::render_tables(&[table], false);
+
+    assert_eq!(items.len(), 1);
+    match items[0].documentation.as_ref().unwrap() {
+        Documentation::String(s) => assert!(s.contains("id, name, email")),
+        Documentation::MarkupContent(m) => assert!(m.value.contains("id, name, email")),
+    }
+}
+
+#[test]
+fn test_render_tables_many_columns_hides_names() {
+    // Create a table with more than 5 columns
+    let columns: Vec<_> = (0..10)
+        .map(|i| ColumnMetadata::new(format!("col{}", i), DataType::Text))
+        .collect();
+
+    let table = TableMetadata::new("wide_table", "public").with_columns(columns);
+
+    let items = CompletionRenderer::render_tables(&[table], false);
+
+    assert_eq!(items.len(), 1);
+    // Should not list column names for wide tables
+    match items[0].documentation.as_ref().unwrap() {
+        Documentation::String(s) => {
+            assert!(s.contains("10 columns"));
+            assert!(!s.contains("col0"));
+        }
+        Documentation::MarkupContent(m) => {
+            assert!(m.value.contains("10 columns"));
+            assert!(!m.value.contains("col0"));
+        }
+    }
+}
+
+#[test]
+fn test_render_tables_sort_order() {
+    let table1 = TableMetadata::new("zebra", "public")
+        .with_columns(vec![ColumnMetadata::new("id", DataType::Integer)]);
+    let table2 = TableMetadata::new("apple", "public")
+        .with_columns(vec![ColumnMetadata::new("id", DataType::Integer)]);
+
+    let items = CompletionRenderer::render_tables(&[table1, table2], false);
+
+    assert_eq!(items.len(), 2);
+    // Items should be sorted alphabetically
+    assert_eq!(items[0].label, "apple");
+    assert_eq!(items[1].label, "zebra");
+}
+
+#[test]
+fn test_render_join_columns_basic() {
+    let left = TableSymbol::new("users").with_columns(vec![
+        ColumnSymbol::new("id", DataType::Integer, "users").with_primary_key(),
+        ColumnSymbol::new("name", DataType::Text, "users"),
+    ]);
+
+    let right = TableSymbol::new("orders").with_columns(vec![
+        ColumnSymbol::new("id", DataType::Integer, "orders").with_primary_key(),
+        ColumnSymbol::new("user_id", DataType::Integer, "orders").with_foreign_key(),
+    ]);
+
+    let items = CompletionRenderer::render_join_columns(&[left, right], true);
+
+    // Should have 4 columns total (no wildcard for JOINs)
+    assert_eq!(items.len(), 4);
+
+    // First items should be PK columns (preselect: true)
+    assert!(items[0].preselect.unwrap());
+    assert!(items[1].preselect.unwrap());
+
+    // PK/FK columns should come before regular columns
+    assert!(items[0].sort_text.as_ref().unwrap().starts_with("00_pk_"));
+    assert!(items[1].sort_text.as_ref().unwrap().starts_with("00_pk_"));
+    assert!(items[2].sort_text.as_ref().unwrap().starts_with("01_fk_"));
+    assert!(items[3].sort_text.as_ref().unwrap().starts_with("02_"));
+
+    // All columns should be qualified
+    assert!(items.iter().all(|i| i.label.contains('.')));
+}
+
+#[test]
+fn test_render_join_columns_pk_fk_priority() {
+    let users = TableSymbol::new("users").with_columns(vec![
+        ColumnSymbol::new("id", DataType::Integer, "users").with_primary_key(),
+        ColumnSymbol::new("name", DataType::Text, "users"),
+    ]);
+
+    let orders = TableSymbol::new("orders").with_columns(vec![
+        ColumnSymbol::new("id", DataType::Integer, "orders").with_primary_key(),
+        ColumnSymbol::new("user_id", DataType::Integer, "orders").with_foreign_key(),
+        ColumnSymbol::new("total", DataType::Decimal, "orders"),
+    ]);
+
+    let items = CompletionRenderer::render_join_columns(&[users, orders], true);
+
+    // Verify ordering: PKs first, then FKs, then regular
+    let pk_items: Vec<_> = items
+        .iter()
+        .filter(|i| i.sort_text.as_ref().unwrap().starts_with("00_pk_"))
+        .collect();
+    let fk_items: Vec<_> = items
+        .iter()
+        .filter(|i| i.sort_text.as_ref().unwrap().starts_with("01_fk_"))
+        .collect();
+    let regular_items: Vec<_> = items
+        .iter()
+        .filter(|i| i.sort_text.as_ref().unwrap().starts_with("02_"))
+        .collect();
+
+    assert_eq!(pk_items.len(), 2); // users.id, orders.id
+    assert_eq!(fk_items.len(), 1); // orders.user_id
+    assert_eq!(regular_items.len(), 2); // users.name, orders.total (sorted alphabetically)
+
+    // Verify PK/FK items are preselected
+    assert!(pk_items.iter().all(|i| i.preselect.unwrap()));
+    assert!(fk_items.iter().all(|i| i.preselect.unwrap()));
+    assert!(!regular_items.iter().all(|i| i.preselect.unwrap()));
+}
+
+#[test]
+fn test_render_join_columns_qualified() {
+    let table = TableSymbol::new("users").with_columns(vec![
+        ColumnSymbol::new("id", DataType::Integer, "users").with_primary_key(),
+        ColumnSymbol::new("name", DataType::Text, "users"),
+    ]);
+
+    let items = CompletionRenderer::render_join_columns(&[table], true);
+
+    // All items should be qualified
+    assert!(items.iter().all(|i| i.label.contains('.')));
+    assert!(items.iter().any(|i| i.label == "users.id"));
+    assert!(items.iter().any(|i| i.label == "users.name"));
+}
+
+#[test]
+fn test_render_join_columns_with_alias() {
+    let table = TableSymbol::new("users").with_alias("u").with_columns(vec![
+        ColumnSymbol::new("id", DataType::Integer, "users").with_primary_key(),
+        ColumnSymbol::new("name", DataType::Text, "users"),
+    ]);
+
+    let items = CompletionRenderer::render_join_columns(&[table], true);
+
+    // Should use alias instead of table name
+    assert!(items.iter().any(|i| i.label == "u.id"));
+    assert!(items.iter().any(|i| i.label == "u.name"));
+    assert!(!items.iter().any(|i| i.label.starts_with("users.")));
+}
+
+#[test]
+fn test_render_join_columns_composite_pk() {
+    let table = TableSymbol::new("order_items").with_columns(vec![
+        ColumnSymbol::new("order_id", DataType::Integer, "order_items").with_primary_key(),
+        ColumnSymbol::new("item_id", DataType::Integer, "order_items").with_primary_key(),
+        ColumnSymbol::new("quantity", DataType::Integer, "order_items"),
+    ]);
+
+    let items = CompletionRenderer::render_join_columns(&[table], true);
+
+    // Both PK columns should be marked as preselect
+    let pk_items: Vec<_> = items.iter().filter(|i| i.preselect.unwrap()).collect();
+
+    assert_eq!(pk_items.len(), 2);
+    assert!(
+        pk_items
+            .iter()
+            .all(|i| i.sort_text.as_ref().unwrap().starts_with("00_pk_"))
+    );
+}
+
+#[test]
+fn test_render_functions_all() {
+    use unified_sql_lsp_catalog::FunctionMetadata;
+
+    let functions = vec![
+        FunctionMetadata::new("count", DataType::BigInt)
+            .with_type(FunctionType::Aggregate)
+            .with_description("Count rows"),
+        FunctionMetadata::new("abs", DataType::Integer)
+            .with_type(FunctionType::Scalar)
+            .with_description("Absolute value"),
+    ];
+
+    let items = CompletionRenderer::render_functions(&functions, None);
+
+    // count, its count(*) variant and abs
+    assert_eq!(items.len(), 3);
+    assert!(items.iter().any(|i| i.label == "count"));
+    assert!(items.iter().any(|i| i.label == "count(*)"));
+    assert!(items.iter().any(|i| i.label == "abs"));
+}
+
+#[test]
+fn test_render_functions_filtered() {
+    use unified_sql_lsp_catalog::FunctionMetadata;
+
+    let functions = vec![
+        FunctionMetadata::new("count", DataType::BigInt).with_type(FunctionType::Aggregate),
+        FunctionMetadata::new("abs", DataType::Integer).with_type(FunctionType::Scalar),
+    ];
+
+    let items = CompletionRenderer::render_functions(&functions, Some(FunctionType::Aggregate));
+
+    // Should only show aggregate functions
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].label, "count");
+    assert_eq!(items[1].label, "count(*)");
+}
+
+#[test]
+fn test_function_item_signature() {
+    use unified_sql_lsp_catalog::{FunctionMetadata, FunctionParameter};
+
+    let func =
+        FunctionMetadata::new("count", DataType::BigInt).with_parameters(vec![FunctionParameter {
+            name: "expr".to_string(),
+            data_type: DataType::Integer,
+            has_default: false,
+            is_variadic: false,
+        }]);
+
+    let item = CompletionRenderer::function_item(&func, &CompletionConfig::default());
+
+    assert_eq!(item.label, "count");
+    assert!(item.detail.as_ref().unwrap().contains("count"));
+    assert_eq!(item.kind, Some(CompletionItemKind::CLASS)); // Using CLASS for functions
+    assert_eq!(item.insert_text.as_ref().unwrap(), "count()");
+}
+
+#[test]
+fn test_function_sort_order() {
+    use unified_sql_lsp_catalog::FunctionMetadata;
+
+    let functions = vec![
+        FunctionMetadata::new("abs", DataType::Integer).with_type(FunctionType::Scalar),
+        FunctionMetadata::new("count", DataType::BigInt).with_type(FunctionType::Aggregate),
+        FunctionMetadata::new("row_number", DataType::BigInt).with_type(FunctionType::Window),
+    ];
+
+    let items = CompletionRenderer::render_functions(&functions, None);
+
+    // Aggregates should come first
+    assert!(
+        items[0]
+            .sort_text
+            .as_ref()
+            .unwrap()
+            .starts_with("00_aggregate_")
+    );
+    assert_eq!(items[0].label, "count");
+    assert_eq!(items[1].label, "count(*)");
+
+    // Window functions second
+    assert!(
+        items[2]
+            .sort_text
+            .as_ref()
+            .unwrap()
+            .starts_with("01_window_")
+    );
+    assert_eq!(items[2].label, "row_number");
+
+    // Scalar functions last
+    assert!(
+        items[3]
+            .sort_text
+            .as_ref()
+            .unwrap()
+            .starts_with("03_scalar_")
+    );
+    assert_eq!(items[3].label, "abs");
+}
+
+#[test]
+fn test_function_item_with_parameters() {
+    use unified_sql_lsp_catalog::{FunctionMetadata, FunctionParameter};
+
+    let func = FunctionMetadata::new("concat", DataType::Text)
+        .with_type(FunctionType::Scalar)
+        .with_description("Concatenate strings")
+        .with_parameters(vec![
+            FunctionParameter {
+                name: "str1".to_string(),
+                data_type: DataType::Text,
+                has_default: false,
+                is_variadic: false,
+            },
+            FunctionParameter {
+                name: "str2".to_string(),
+                data_type: DataType::Text,
+                has_default: false,
+                is_variadic: false,
+            },
+        ])
+        .with_example("SELECT CONCAT(first, ' ', last) FROM users");
+
+    let item = CompletionRenderer::function_item(&func, &CompletionConfig::default());
+
+    assert_eq!(item.label, "concat");
+    assert_eq!(item.kind, Some(CompletionItemKind::CLASS)); // Using CLASS for functions
+
+    // Check documentation contains parameter details
+    match item.documentation.as_ref().unwrap() {
+        Documentation::String(doc) => {
+            assert!(doc.contains("Concatenate strings"));
+            assert!(doc.contains("str1"));
+            assert!(doc.contains("str2"));
+            assert!(doc.contains("CONCAT"));
+        }
+        _ => panic!("Expected string documentation"),
+    }
+}
+
+#[test]
+fn test_function_item_insert_text() {
+    use unified_sql_lsp_catalog::FunctionMetadata;
+
+    let func = FunctionMetadata::new("count", DataType::BigInt).with_type(FunctionType::Aggregate);
+
+    let item = CompletionRenderer::function_item(&func, &CompletionConfig::default());
+
+    // Clients without snippet support get both parens and no placeholder
+    assert_eq!(item.insert_text.as_ref().unwrap(), "count()");
+    assert_eq!(item.insert_text_format, Some(InsertTextFormat::PLAIN_TEXT));
+}
+
+#[test]
+fn test_function_item_snippet_parens() {
+    use unified_sql_lsp_catalog::FunctionMetadata;
+
+    let func = FunctionMetadata::new("count", DataType::BigInt).with_type(FunctionType::Aggregate);
+    let config = CompletionConfig {
+        snippet_support: true,
+        ..Default::default()
+    };
+
+    let item = CompletionRenderer::function_item(&func, &config);
+
+    // Cursor lands inside the parens
+    assert_eq!(item.insert_text.as_ref().unwrap(), "count($1)");
+    assert_eq!(item.insert_text_format, Some(InsertTextFormat::SNIPPET));
+}
+
+#[test]
+fn test_function_item_parens_disabled() {
+    use unified_sql_lsp_catalog::FunctionMetadata;
+
+    let func = FunctionMetadata::new("upper", DataType::Text);
+    let config = CompletionConfig {
+        function_call_parens: false,
+        snippet_support: true,
+        ..Default::default()
+    };
+
+    let item = CompletionRenderer::function_item(&func, &config);
+
+    assert_eq!(item.insert_text.as_ref().unwrap(), "upper");
+    assert_eq!(item.insert_text_format, Some(InsertTextFormat::PLAIN_TEXT));
+}
+
+#[test]
+fn test_render_functions_star_variant() {
+    use unified_sql_lsp_catalog::FunctionMetadata;
+
+    let functions = vec![
+        FunctionMetadata::new("COUNT", DataType::BigInt).with_type(FunctionType::Aggregate),
+        FunctionMetadata::new("SUM", DataType::Decimal).with_type(FunctionType::Aggregate),
+    ];
+
+    let items = CompletionRenderer::render_functions_with_config(
+        &functions,
+        None,
+        &CompletionConfig::default(),
+    );
+
+    // Only COUNT accepts `*`
+    let labels: Vec<_> = items.iter().map(|i| i.label.as_str()).collect();
+    assert_eq!(labels, vec!["COUNT", "COUNT(*)", "SUM"]);
+    let star = &items[1];
+    assert_eq!(star.insert_text.as_deref(), Some("COUNT(*)"));
+    assert_eq!(star.filter_text.as_deref(), Some("COUNT"));
+}
+
+#[test]
+fn test_render_functions_star_variant_disabled() {
+    use unified_sql_lsp_catalog::FunctionMetadata;
+
+    let functions =
+        vec![FunctionMetadata::new("count", DataType::BigInt).with_type(FunctionType::Aggregate)];
+    let config = CompletionConfig {
+        aggregate_star_variant: false,
+        ..Default::default()
+    };
+
+    let items = CompletionRenderer::render_functions_with_config(&functions, None, &config);
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].insert_text.as_deref(), Some("count()"));
+}
+
+#[test]
+fn test_render_tables_quotes_mixed_case_names() {
+    let tables = [
+        TableMetadata::new("users", "public"),
+        TableMetadata::new("Users", "public"),
+    ];
+    let inserts = |items: Vec<CompletionItem>| {
+        items
+            .into_iter()
+            .map(|i| (i.label, i.insert_text.unwrap()))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        inserts(CompletionRenderer::render_tables_for_dialect(
+            &tables,
+            false,
+            Dialect::PostgreSQL
+        )),
+        vec![
+            ("Users".to_string(), "\"Users\"".to_string()),
+            ("users".to_string(), "users".to_string()),
+        ]
+    );
+    assert_eq!(
+        inserts(CompletionRenderer::render_tables_for_dialect(
+            &tables,
+            true,
+            Dialect::PostgreSQL
+        ))[0],
+        ("public.Users".to_string(), "public.\"Users\"".to_string())
+    );
+
+    // MySQL keeps the case of bare names
+    let mysql = inserts(CompletionRenderer::render_tables_for_dialect(
+        &[
+            TableMetadata::new("Users", "app"),
+            TableMetadata::new("order items", "app"),
+        ],
+        false,
+        Dialect::MySQL,
+    ));
+    assert_eq!(mysql[0].1, "Users");
+    assert_eq!(mysql[1].1, "`order items`");
+}
+
+#[test]
+fn test_render_join_tables_scaffold_disabled() {
+    let table = TableMetadata::new("orders", "public");
+
+    let items = CompletionRenderer::render_join_tables(
+        &[table],
+        false,
+        Dialect::MySQL,
+        &CompletionConfig::default(),
+    );
+
+    assert_eq!(items[0].insert_text.as_deref(), Some("orders"));
+}
+
+#[test]
+fn test_render_join_tables_scaffold_plain() {
+    let table = TableMetadata::new("orders", "public");
+    let config = CompletionConfig {
+        join_on_scaffold: true,
+        ..Default::default()
+    };
+
+    let items = CompletionRenderer::render_join_tables(&[table], false, Dialect::MySQL, &config);
+
+    assert_eq!(items[0].label, "orders");
+    assert_eq!(items[0].insert_text.as_deref(), Some("orders ON "));
+    assert_eq!(
+        items[0].insert_text_format,
+        Some(InsertTextFormat::PLAIN_TEXT)
+    );
+}
+
+#[test]
+fn test_render_join_tables_scaffold_snippet() {
+    let table = TableMetadata::new("orders", "public");
+    let config = CompletionConfig {
+        join_on_scaffold: true,
+        snippet_support: true,
+        ..Default::default()
+    };
+
+    let items = CompletionRenderer::render_join_tables(&[table], true, Dialect::MySQL, &config);
+
+    assert_eq!(
+        items[0].insert_text.as_deref(),
+        Some("public.orders ON ${1:condition}")
+    );
+    assert_eq!(items[0].insert_text_format, Some(InsertTextFormat::SNIPPET));
+}
+
+#[test]
+fn test_render_window_names() {
+    let windows = vec!["w".to_string(), "by_dept".to_string()];
+
+    let items = CompletionRenderer::render_window_names(&windows, &CompletionConfig::default());
+
+    let labels: Vec<_> = items.iter().map(|i| i.label.as_str()).collect();
+    assert_eq!(labels, vec!["by_dept", "w", "("]);
+    assert_eq!(items[0].kind, Some(CompletionItemKind::REFERENCE));
+    assert_eq!(items[2].insert_text.as_deref(), Some("("));
+    assert_eq!(
+        items[2].insert_text_format,
+        Some(InsertTextFormat::PLAIN_TEXT)
+    );
+}
+
+#[test]
+fn test_render_window_names_snippet() {
+    let config = CompletionConfig {
+        snippet_support: true,
+        ..Default::default()
+    };
+
+    let items = CompletionRenderer::render_window_names(&[], &config);
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].insert_text.as_deref(), Some("($0)"));
+    assert_eq!(items[0].insert_text_format, Some(InsertTextFormat::SNIPPET));
+}
+
+#[test]
+fn test_render_statement_templates() {
+    let templates =
+        unified_sql_lsp_context::KeywordProvider::new(Dialect::MySQL).statement_templates();
+
+    // Clients without snippets get the plain keywords only
+    assert!(
+        CompletionRenderer::render_statement_templates(&templates, &CompletionConfig::default())
+            .is_empty()
+    );
+
+    let config = CompletionConfig {
+        snippet_support: true,
+        keyword_case: KeywordCase::Lower,
+        ..Default::default()
+    };
+    let items = CompletionRenderer::render_statement_templates(&templates, &config);
+    let update = items
+        .iter()
+        .find(|i| i.label == "UPDATE … SET … WHERE …")
+        .unwrap();
+    assert_eq!(update.kind, Some(CompletionItemKind::SNIPPET));
+    assert_eq!(update.insert_text_format, Some(InsertTextFormat::SNIPPET));
+    assert_eq!(update.filter_text.as_deref(), Some("UPDATE"));
+    assert_eq!(
+        update.insert_text.as_deref(),
+        Some("update ${1:table} set ${2:column} = ${3:value} where ${4:condition}$0")
+    );
+    assert_eq!(
+        update.documentation,
+        Some(Documentation::String(
+            "Modify the rows matching a condition\n\n```sql\n\
+             UPDATE table SET column = value WHERE condition\n```"
+                .to_string()
+        ))
+    );
+}
+
+#[test]
+fn test_render_statement_templates_read_only() {
+    let templates =
+        unified_sql_lsp_context::KeywordProvider::new(Dialect::MySQL).statement_templates();
+    let labels = |read_only: bool| -> Vec<String> {
+        let config = CompletionConfig {
+            snippet_support: true,
+            read_only,
+            ..Default::default()
+        };
+        CompletionRenderer::render_statement_templates(&templates, &config)
+            .into_iter()
+            .map(|item| item.label)
+            .collect()
+    };
+
+    assert_eq!(labels(false).len(), 5);
+    assert_eq!(
+        labels(true),
+        vec!["SELECT … FROM …", "CREATE TABLE … ( … )"]
+    );
+}
+
+fn keyword_items() -> Vec<CompletionItem> {
+    let mut items = CompletionRenderer::render_keywords(&[
+        SqlKeyword::new("SELECT", None, 1),
+        SqlKeyword::new("ORDER BY", None, 2),
+    ]);
+    items.extend(CompletionRenderer::render_columns(
+        &[
+            TableSymbol::new("users").with_columns(vec![ColumnSymbol::new(
+                "Email",
+                DataType::Text,
+                "users",
+            )]),
+        ],
+        false,
+    ));
+    items
+}
+
+#[test]
+fn test_keyword_case_follows_typed_prefix() {
+    let config = CompletionConfig::default();
+
+    assert_eq!(config.keyword_case_for(Some("sel")), KeywordCase::Lower);
+    assert_eq!(config.keyword_case_for(Some("SEL")), KeywordCase::Upper);
+    assert_eq!(config.keyword_case_for(Some("Sel")), KeywordCase::Upper);
+    assert_eq!(config.keyword_case_for(None), KeywordCase::Upper);
+}
+
+#[test]
+fn test_keyword_case_config_override() {
+    let lower = CompletionConfig {
+        keyword_case: KeywordCase::Lower,
+        ..Default::default()
+    };
+    // Mixed case and missing prefixes follow the configured case
+    assert_eq!(lower.keyword_case_for(Some("sEl")), KeywordCase::Lower);
+    assert_eq!(lower.keyword_case_for(Some("_1")), KeywordCase::Lower);
+    // An uppercase prefix still wins over the config
+    assert_eq!(lower.keyword_case_for(Some("SEL")), KeywordCase::Upper);
+
+    let fixed = CompletionConfig {
+        keyword_case: KeywordCase::Lower,
+        match_typed_case: false,
+        ..Default::default()
+    };
+    assert_eq!(fixed.keyword_case_for(Some("SEL")), KeywordCase::Lower);
+
+    let settings = serde_json::json!({ "keywordCase": "lower", "matchTypedCase": false });
+    assert_eq!(CompletionConfig::from_lsp_settings(&settings), fixed);
+}
+
+#[test]
+fn test_show_unsupported_setting() {
+    use crate::config::ShowUnsupported;
+
+    let settings = serde_json::json!({ "showUnsupported": "warn" });
+    assert_eq!(
+        CompletionConfig::from_lsp_settings(&settings).show_unsupported,
+        ShowUnsupported::Warn
+    );
+    let settings = serde_json::json!({ "showUnsupported": "sometimes" });
+    assert_eq!(
+        CompletionConfig::from_lsp_settings(&settings).show_unsupported,
+        ShowUnsupported::Hide
+    );
+}
+
+#[test]
+fn test_common_functions_setting() {
+    let defaults = CompletionConfig::default();
+    assert!(defaults.common_functions.contains(&"COALESCE".to_string()));
+    assert_eq!(
+        CompletionConfig::from_lsp_settings(&serde_json::json!({})).common_functions,
+        defaults.common_functions
+    );
+
+    let settings = serde_json::json!({ "commonFunctions": ["COUNT", 1, "json_extract"] });
+    assert_eq!(
+        CompletionConfig::from_lsp_settings(&settings).common_functions,
+        vec!["COUNT", "json_extract"]
+    );
+}
+
+#[test]
+fn test_apply_keyword_case() {
+    let mut items = keyword_items();
+    CompletionRenderer::apply_keyword_case(&mut items, KeywordCase::Lower);
+
+    let inserted: Vec<_> = items
+        .iter()
+        .filter(|i| i.label != "*")
+        .map(|i| (i.label.as_str(), i.insert_text.as_deref()))
+        .collect();
+    assert_eq!(
+        inserted,
+        vec![
+            ("SELECT", Some("select")),
+            ("ORDER BY", Some("order by")),
+            ("Email", Some("Email")),
+        ]
+    );
+
+    // Uppercase keeps the canonical label as the inserted text
+    let mut items = keyword_items();
+    CompletionRenderer::apply_keyword_case(&mut items, KeywordCase::Upper);
+    assert!(
+        items
+            .iter()
+            .filter(|i| i.kind == Some(CompletionItemKind::KEYWORD))
+            .all(|i| i.insert_text.is_none())
+    );
+}
+
+fn item_data(item: &CompletionItem) -> CompletionData {
+    CompletionData::decode(item.data.clone().expect("rendered items carry data"))
+        .expect("rendered data decodes")
+}
+
+#[test]
+fn test_rendered_items_carry_data() {
+    let table = TableMetadata::new("users", "public");
+    let items = CompletionRenderer::render_tables(&[table], true);
+    assert_eq!(
+        item_data(&items[0]),
+        CompletionData::table("users").with_schema("public")
+    );
+
+    let symbol = TableSymbol::new("users")
+        .with_alias("u")
+        .with_columns(vec![ColumnSymbol::new("id", DataType::Integer, "users")]);
+    let items = CompletionRenderer::render_columns(&[symbol], true);
+    let column = items.iter().find(|i| i.label == "u.id").unwrap();
+    // The data names the real table, not the alias
+    assert_eq!(item_data(column), CompletionData::column("users", "id"));
+
+    let function =
+        FunctionMetadata::new("count", DataType::BigInt).with_type(FunctionType::Aggregate);
+    let items = CompletionRenderer::render_functions_with_config(
+        &[function],
+        None,
+        &CompletionConfig {
+            aggregate_star_variant: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(items.len(), 2);
+    assert!(
+        items
+            .iter()
+            .all(|i| item_data(i) == CompletionData::function("count"))
+    );
+
+    let items = CompletionRenderer::render_keywords(&[SqlKeyword::new("SELECT", None, 1)]);
+    assert_eq!(item_data(&items[0]), CompletionData::keyword("SELECT"));
+}
+
+fn edit_of(item: &CompletionItem) -> &TextEdit {
+    match &item.text_edit {
+        Some(CompletionTextEdit::Edit(edit)) => edit,
+        other => panic!("expected a plain text edit, got {:?}", other),
+    }
+}
+
+fn range(line: u32, start: u32, end: u32) -> Range {
+    Range::new(Position::new(line, start), Position::new(line, end))
+}
+
+#[test]
+fn test_text_edit_replaces_typed_prefix() {
+    let source = "SELECT id\nFROM user";
+    let token = TypedToken::at(source, Position::new(1, 9)).unwrap();
+    assert_eq!(token.insert, range(1, 5, 9));
+    assert_eq!(token.qualifier, None);
+
+    let mut items =
+        CompletionRenderer::render_tables(&[TableMetadata::new("users", "public")], false);
+    CompletionRenderer::apply_text_edits(&mut items, &token, false);
+
+    let edit = edit_of(&items[0]);
+    assert_eq!(edit.range, range(1, 5, 9));
+    assert_eq!(edit.new_text, "users");
+}
+
+#[test]
+fn test_text_edit_after_dot_keeps_qualifier() {
+    let table = TableSymbol::new("users")
+        .with_alias("u")
+        .with_columns(vec![ColumnSymbol::new("name", DataType::Text, "users")]);
+    let mut items = CompletionRenderer::render_columns(&[table], true);
+
+    // `u.na|` only replaces `na`, and `u.name` drops the typed `u.`
+    let token = TypedToken::at("SELECT u.na FROM users u", Position::new(0, 11)).unwrap();
+    assert_eq!(token.qualifier.as_deref(), Some("u"));
+    CompletionRenderer::apply_text_edits(&mut items, &token, false);
+
+    let name = items.iter().find(|i| i.label == "u.name").unwrap();
+    let edit = edit_of(name);
+    assert_eq!(edit.range, range(0, 9, 11));
+    assert_eq!(edit.new_text, "name");
+    let star = items.iter().find(|i| i.label == "*").unwrap();
+    assert_eq!(edit_of(star).new_text, "*");
+}
+
+#[test]
+fn test_text_edit_with_empty_prefix() {
+    // Directly after a dot
+    let token = TypedToken::at("SELECT u.", Position::new(0, 9)).unwrap();
+    assert_eq!(token.insert, range(0, 9, 9));
+    assert_eq!(token.qualifier.as_deref(), Some("u"));
+
+    // After whitespace
+    let token = TypedToken::at("SELECT ", Position::new(0, 7)).unwrap();
+    assert_eq!(token.insert, range(0, 7, 7));
+    let mut items = CompletionRenderer::render_keywords(&[SqlKeyword::new("DISTINCT", None, 1)]);
+    CompletionRenderer::apply_text_edits(&mut items, &token, false);
+    let edit = edit_of(&items[0]);
+    assert_eq!(edit.range, range(0, 7, 7));
+    assert_eq!(edit.new_text, "DISTINCT");
+
+    // Positions outside the source have no token
+    assert!(TypedToken::at("SELECT ", Position::new(0, 8)).is_none());
+    assert!(TypedToken::at("SELECT ", Position::new(1, 0)).is_none());
+}
+
+#[test]
+fn test_insert_replace_edit_covers_whole_identifier() {
+    // Cursor in the middle of `usrs`
+    let token = TypedToken::at("SELECT * FROM usrs", Position::new(0, 16)).unwrap();
+    let mut items =
+        CompletionRenderer::render_tables(&[TableMetadata::new("users", "public")], false);
+    CompletionRenderer::apply_text_edits(&mut items, &token, true);
+
+    match &items[0].text_edit {
+        Some(CompletionTextEdit::InsertAndReplace(edit)) => {
+            assert_eq!(edit.insert, range(0, 14, 16));
+            assert_eq!(edit.replace, range(0, 14, 18));
+            assert_eq!(edit.new_text, "users");
+        }
+        other => panic!("expected an insert/replace edit, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_render_projection_aliases() {
+    let aliases = vec![
+        "\"Total Rows\"".to_string(),
+        "`Total Rows`".to_string(),
+        "total".to_string(),
+        "\"Total\"".to_string(),
+    ];
+    let render = |dialect| {
+        CompletionRenderer::render_projection_aliases(&aliases, dialect)
+            .into_iter()
+            .map(|item| (item.label, item.insert_text.unwrap()))
+            .collect::<Vec<_>>()
+    };
+    let pair = |label: &str, insert: &str| (label.to_string(), insert.to_string());
+
+    // Quoted aliases are re-emitted with the dialect's delimiters
+    assert_eq!(
+        render(Dialect::PostgreSQL),
+        vec![
+            pair("Total Rows", "\"Total Rows\""),
+            pair("Total Rows", "\"Total Rows\""),
+            pair("total", "total"),
+            pair("Total", "\"Total\""),
+        ]
+    );
+    assert_eq!(
+        render(Dialect::MySQL),
+        vec![
+            pair("Total Rows", "`Total Rows`"),
+            pair("Total Rows", "`Total Rows`"),
+            pair("total", "total"),
+            pair("Total", "`Total`"),
+        ]
+    );
+
+    let item = &CompletionRenderer::render_projection_aliases(&aliases[..1], Dialect::MySQL)[0];
+    assert_eq!(item.kind, Some(CompletionItemKind::VARIABLE));
+    assert_eq!(item.detail.as_deref(), Some("Column alias"));
+    assert_eq!(item.filter_text.as_deref(), Some("`Total Rows`"));
+}
@@ -0,0 +1,104 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Role name completion
+//!
+//! Completion items for the grantees of GRANT / REVOKE and the role of
+//! `SET ROLE`: role names from the catalog, followed by the keywords the
+//! dialect accepts in their place (`CURRENT_USER`, `PUBLIC`, ...).
+
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind, Documentation};
+use unified_sql_lsp_ir::Dialect;
+use unified_sql_lsp_ir::dialect::DialectFamily;
+
+use crate::completion::data::CompletionData;
+
+/// Role specifications of PostgreSQL GRANT and REVOKE
+const POSTGRES_GRANTEE_KEYWORDS: &[(&str, &str)] = &[
+    ("PUBLIC", "All roles, including those created later"),
+    ("CURRENT_USER", "The user executing the statement"),
+    ("CURRENT_ROLE", "The currently enabled role"),
+    ("SESSION_USER", "The user who opened the session"),
+];
+
+/// Alternatives to a role name in PostgreSQL `SET ROLE`
+const POSTGRES_SET_ROLE_KEYWORDS: &[(&str, &str)] =
+    &[("NONE", "Reset the current role to the session user")];
+
+/// Account keywords of MySQL GRANT and REVOKE
+const MYSQL_GRANTEE_KEYWORDS: &[(&str, &str)] =
+    &[("CURRENT_USER", "The account executing the statement")];
+
+/// Alternatives to a role name in MySQL `SET ROLE`
+const MYSQL_SET_ROLE_KEYWORDS: &[(&str, &str)] = &[
+    ("DEFAULT", "Activate the account's default roles"),
+    ("NONE", "Deactivate all roles"),
+    ("ALL", "Activate all roles granted to the account"),
+];
+
+/// Render catalog roles and the dialect's role keywords
+///
+/// `statement_type` is the statement introducing the name, as reported by
+/// the completion context ("GRANT", "REVOKE" or "SET ROLE").
+pub fn render_role_names(
+    roles: &[String],
+    statement_type: &str,
+    dialect: Dialect,
+) -> Vec<CompletionItem> {
+    let set_role = statement_type == "SET ROLE";
+    let keywords = match (dialect.family(), set_role) {
+        (DialectFamily::PostgreSQL, false) => POSTGRES_GRANTEE_KEYWORDS,
+        (DialectFamily::PostgreSQL, true) => POSTGRES_SET_ROLE_KEYWORDS,
+        (DialectFamily::MySQL, false) => MYSQL_GRANTEE_KEYWORDS,
+        (DialectFamily::MySQL, true) => MYSQL_SET_ROLE_KEYWORDS,
+    };
+
+    let roles = roles.iter().map(|role| CompletionItem {
+        label: role.clone(),
+        kind: Some(CompletionItemKind::ENUM_MEMBER),
+        detail: Some("role".to_string()),
+        sort_text: Some(format!("0_{}", role)),
+        ..Default::default()
+    });
+    let keywords = keywords
+        .iter()
+        .enumerate()
+        .map(|(i, (keyword, description))| CompletionItem {
+            label: keyword.to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            detail: Some("Keyword".to_string()),
+            documentation: Some(Documentation::String(description.to_string())),
+            sort_text: Some(format!("1_{:02}", i)),
+            data: Some(CompletionData::keyword(*keyword).encode()),
+            ..Default::default()
+        });
+
+    roles.chain(keywords).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_names_follow_statement_and_dialect() {
+        let roles = vec!["analyst".to_string()];
+
+        let grant = render_role_names(&roles, "GRANT", Dialect::PostgreSQL);
+        assert_eq!(grant[0].label, "analyst");
+        assert_eq!(grant[0].kind, Some(CompletionItemKind::ENUM_MEMBER));
+        assert_eq!(grant[0].detail.as_deref(), Some("role"));
+        assert!(grant.iter().any(|i| i.label == "PUBLIC"));
+        assert!(grant.iter().any(|i| i.label == "SESSION_USER"));
+
+        let set_role = render_role_names(&roles, "SET ROLE", Dialect::PostgreSQL);
+        let labels: Vec<_> = set_role.iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(labels, vec!["analyst", "NONE"]);
+
+        let mysql = render_role_names(&[], "REVOKE", Dialect::MySQL);
+        let labels: Vec<_> = mysql.iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(labels, vec!["CURRENT_USER"]);
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Sequence name completion
+//!
+//! Completion items for the first argument of `nextval`, `setval` and
+//! `currval`: the sequences listed by the catalog. Outside quotes the name
+//! is inserted as the string literal the functions expect.
+
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind};
+use unified_sql_lsp_catalog::SequenceMetadata;
+
+/// Render catalog sequences
+///
+/// Sequences outside `public` are schema-qualified. `in_string` tells
+/// whether the cursor is already inside the quotes of the argument.
+pub fn render_sequence_names(
+    sequences: &[SequenceMetadata],
+    in_string: bool,
+) -> Vec<CompletionItem> {
+    sequences
+        .iter()
+        .map(|sequence| {
+            let name = if sequence.schema == "public" {
+                sequence.name.clone()
+            } else {
+                format!("{}.{}", sequence.schema, sequence.name)
+            };
+            CompletionItem {
+                label: name.clone(),
+                kind: Some(CompletionItemKind::CONSTANT),
+                detail: Some(format!("sequence (increment {})", sequence.increment)),
+                filter_text: Some(name.clone()),
+                insert_text: (!in_string).then(|| format!("'{}'", name)),
+                sort_text: Some(format!("0_{}", name)),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_names_quote_outside_strings() {
+        let sequences = vec![
+            SequenceMetadata::new("users_id_seq", "public").with_increment(10),
+            SequenceMetadata::new("invoice_no", "billing"),
+        ];
+
+        let items = render_sequence_names(&sequences, false);
+        assert_eq!(items[0].label, "users_id_seq");
+        assert_eq!(items[0].kind, Some(CompletionItemKind::CONSTANT));
+        assert_eq!(items[0].detail.as_deref(), Some("sequence (increment 10)"));
+        assert_eq!(items[0].insert_text.as_deref(), Some("'users_id_seq'"));
+        assert_eq!(items[1].label, "billing.invoice_no");
+
+        let items = render_sequence_names(&sequences, true);
+        assert_eq!(items[0].insert_text, None);
+    }
+}
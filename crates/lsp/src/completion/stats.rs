@@ -0,0 +1,149 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Completion statistics
+//!
+//! In-memory counters per `CompletionContext` variant, reported by the
+//! `unified-sql-lsp.completionStats` command to help decide which context
+//! detection to improve.
+//!
+//! The counters are local only: they are never written to disk or sent
+//! anywhere, hold no document text or names, and are lost when the server
+//! exits.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use unified_sql_lsp_context::DetectionStage;
+
+/// Counters of one context variant
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextStats {
+    /// Completion requests served in this context
+    pub requests: u64,
+    /// Average number of items returned, counting empty results as zero
+    pub average_items: f64,
+    /// Requests that returned no items
+    pub zero_results: u64,
+    /// Requests whose context came from the text detectors
+    /// because no clause node of the tree matched
+    pub text_fallbacks: u64,
+    /// Share of requests whose document had parse errors, from 0 to 1
+    pub parse_error_ratio: f64,
+}
+
+#[derive(Default)]
+struct Counters {
+    requests: u64,
+    items: u64,
+    zero_results: u64,
+    text_fallbacks: u64,
+    parse_errors: u64,
+}
+
+impl Counters {
+    fn snapshot(&self) -> ContextStats {
+        let ratio = |count: u64| match self.requests {
+            0 => 0.0,
+            requests => count as f64 / requests as f64,
+        };
+        ContextStats {
+            requests: self.requests,
+            average_items: ratio(self.items),
+            zero_results: self.zero_results,
+            text_fallbacks: self.text_fallbacks,
+            parse_error_ratio: ratio(self.parse_errors),
+        }
+    }
+}
+
+/// Completion counters shared by every completion request of a server
+#[derive(Default)]
+pub struct CompletionStats {
+    contexts: Mutex<BTreeMap<&'static str, Counters>>,
+}
+
+impl CompletionStats {
+    /// Create empty counters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one completion request
+    ///
+    /// `context` is the variant name from `CompletionContext::kind`;
+    /// `items` is the number of items returned, zero when completion
+    /// returned nothing.
+    pub fn record(
+        &self,
+        context: &'static str,
+        stage: DetectionStage,
+        has_parse_error: bool,
+        items: usize,
+    ) {
+        let mut contexts = self.contexts.lock().unwrap();
+        let counters = contexts.entry(context).or_default();
+        counters.requests += 1;
+        counters.items += items as u64;
+        counters.zero_results += u64::from(items == 0);
+        counters.text_fallbacks += u64::from(stage == DetectionStage::Text);
+        counters.parse_errors += u64::from(has_parse_error);
+    }
+
+    /// Snapshot of the counters, keyed by context variant name
+    pub fn snapshot(&self) -> BTreeMap<&'static str, ContextStats> {
+        self.contexts
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(kind, counters)| (*kind, counters.snapshot()))
+            .collect()
+    }
+
+    /// Clear all counters
+    pub fn reset(&self) {
+        self.contexts.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unified_sql_lsp_context::CompletionContext;
+
+    #[test]
+    fn test_counts_per_context() {
+        let stats = CompletionStats::new();
+        let from = CompletionContext::FromClause {
+            exclude_tables: vec![],
+        };
+        let limit = CompletionContext::LimitClause;
+
+        stats.record(from.kind(), DetectionStage::Cst, false, 4);
+        stats.record(from.kind(), DetectionStage::Text, true, 0);
+        stats.record(limit.kind(), DetectionStage::Cst, false, 2);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(
+            snapshot["FromClause"],
+            ContextStats {
+                requests: 2,
+                average_items: 2.0,
+                zero_results: 1,
+                text_fallbacks: 1,
+                parse_error_ratio: 0.5,
+            }
+        );
+        assert_eq!(snapshot["LimitClause"].requests, 1);
+
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["FromClause"]["zeroResults"], 1);
+
+        stats.reset();
+        assert!(stats.snapshot().is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Type name completion
+//!
+//! Completion items for the target of a type cast (`id::|`), chosen by
+//! dialect family. PostgreSQL type names are offered in lowercase as they are
+//! usually written after `::`; MySQL lists the types `CAST` accepts.
+//!
+//! Column definitions in CREATE TABLE get the column types instead, which
+//! differ from the `CAST` targets in MySQL.
+
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind, Documentation};
+use unified_sql_lsp_ir::Dialect;
+use unified_sql_lsp_ir::dialect::DialectFamily;
+
+/// PostgreSQL built-in types
+const POSTGRES_TYPES: &[(&str, &str)] = &[
+    ("text", "Variable-length character string"),
+    ("integer", "Signed four-byte integer"),
+    ("bigint", "Signed eight-byte integer"),
+    ("smallint", "Signed two-byte integer"),
+    (
+        "numeric",
+        "Exact numeric of selectable precision, e.g. numeric(10,2)",
+    ),
+    ("real", "Single precision floating-point number"),
+    ("double precision", "Double precision floating-point number"),
+    (
+        "varchar",
+        "Variable-length character string with limit, e.g. varchar(64)",
+    ),
+    ("char", "Fixed-length character string"),
+    ("boolean", "Logical Boolean (true/false)"),
+    ("date", "Calendar date (year, month, day)"),
+    ("time", "Time of day (no time zone)"),
+    ("timestamp", "Date and time (no time zone)"),
+    ("timestamptz", "Date and time, including time zone"),
+    ("interval", "Time span"),
+    ("uuid", "Universally unique identifier"),
+    ("json", "Textual JSON data"),
+    ("jsonb", "Binary JSON data, decomposed"),
+    ("bytea", "Binary data"),
+];
+
+/// Target types accepted by MySQL `CAST` / `CONVERT`
+const MYSQL_TYPES: &[(&str, &str)] = &[
+    ("CHAR", "Character string, optionally CHAR(N)"),
+    ("SIGNED", "Signed 64-bit integer"),
+    ("UNSIGNED", "Unsigned 64-bit integer"),
+    ("DECIMAL", "Exact numeric, e.g. DECIMAL(10,2)"),
+    ("DOUBLE", "Double precision floating-point number"),
+    ("FLOAT", "Single precision floating-point number"),
+    ("DATE", "Calendar date"),
+    ("DATETIME", "Date and time"),
+    ("TIME", "Time of day"),
+    ("BINARY", "Binary string"),
+    ("JSON", "JSON document"),
+];
+
+/// MySQL column types
+const MYSQL_COLUMN_TYPES: &[(&str, &str)] = &[
+    ("INT", "Signed four-byte integer"),
+    ("BIGINT", "Signed eight-byte integer"),
+    ("SMALLINT", "Signed two-byte integer"),
+    ("TINYINT", "Signed one-byte integer"),
+    ("DECIMAL", "Exact numeric, e.g. DECIMAL(10,2)"),
+    ("DOUBLE", "Double precision floating-point number"),
+    ("FLOAT", "Single precision floating-point number"),
+    (
+        "VARCHAR",
+        "Variable-length string with limit, e.g. VARCHAR(255)",
+    ),
+    ("CHAR", "Fixed-length string"),
+    ("TEXT", "Variable-length string up to 64 KB"),
+    ("BOOLEAN", "Synonym for TINYINT(1)"),
+    ("DATE", "Calendar date"),
+    ("DATETIME", "Date and time"),
+    ("TIMESTAMP", "Date and time, stored as UTC"),
+    ("TIME", "Time of day"),
+    ("JSON", "JSON document"),
+    ("BLOB", "Binary data up to 64 KB"),
+];
+
+/// PostgreSQL integer types backed by a sequence of their own
+const POSTGRES_SERIAL_TYPES: &[(&str, &str)] = &[
+    ("serial", "Auto-incrementing four-byte integer"),
+    ("bigserial", "Auto-incrementing eight-byte integer"),
+    ("smallserial", "Auto-incrementing two-byte integer"),
+];
+
+/// Render the type names of the dialect's family
+pub fn render_type_names(dialect: Dialect) -> Vec<CompletionItem> {
+    let types = match dialect.family() {
+        DialectFamily::MySQL => MYSQL_TYPES,
+        DialectFamily::PostgreSQL => POSTGRES_TYPES,
+    };
+    type_items(types, "Data type", 0)
+}
+
+/// Render the column types of the dialect's family, for CREATE TABLE
+///
+/// PostgreSQL's serial types come last. Where the version has identity
+/// columns they are marked legacy and point at `GENERATED ... AS IDENTITY`.
+pub fn render_column_types(dialect: Dialect, identity_columns: bool) -> Vec<CompletionItem> {
+    match dialect.family() {
+        DialectFamily::MySQL => type_items(MYSQL_COLUMN_TYPES, "Data type", 0),
+        DialectFamily::PostgreSQL => {
+            let mut items = type_items(POSTGRES_TYPES, "Data type", 0);
+            if identity_columns {
+                items.extend(
+                    type_items(POSTGRES_SERIAL_TYPES, "Data type (legacy)", items.len())
+                        .into_iter()
+                        .map(|mut item| {
+                            if let Some(Documentation::String(description)) =
+                                &mut item.documentation
+                            {
+                                description.push_str(
+                                    ". Legacy: prefer `integer GENERATED ALWAYS AS IDENTITY`",
+                                );
+                            }
+                            item
+                        }),
+                );
+            } else {
+                items.extend(type_items(POSTGRES_SERIAL_TYPES, "Data type", items.len()));
+            }
+            items
+        }
+    }
+}
+
+fn type_items(types: &[(&str, &str)], detail: &str, first: usize) -> Vec<CompletionItem> {
+    types
+        .iter()
+        .enumerate()
+        .map(|(i, (name, description))| CompletionItem {
+            label: name.to_string(),
+            kind: Some(CompletionItemKind::TYPE_PARAMETER),
+            detail: Some(detail.to_string()),
+            documentation: Some(Documentation::String(description.to_string())),
+            sort_text: Some(format!("{:02}", first + i)),
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_names_follow_dialect() {
+        let postgres = render_type_names(Dialect::PostgreSQL);
+        assert_eq!(postgres[0].label, "text");
+        assert!(postgres.iter().any(|i| i.label == "jsonb"));
+        assert!(!postgres.iter().any(|i| i.label == "SIGNED"));
+
+        let mysql = render_type_names(Dialect::MySQL);
+        assert!(mysql.iter().any(|i| i.label == "SIGNED"));
+        assert!(!mysql.iter().any(|i| i.label == "jsonb"));
+    }
+
+    #[test]
+    fn test_column_types_mark_serial_legacy() {
+        let mysql = render_column_types(Dialect::MySQL, false);
+        assert!(mysql.iter().any(|i| i.label == "VARCHAR"));
+        assert!(!mysql.iter().any(|i| i.label == "SIGNED"));
+
+        let postgres = render_column_types(Dialect::PostgreSQL, true);
+        let serial = postgres.iter().find(|i| i.label == "serial").unwrap();
+        assert_eq!(serial.detail.as_deref(), Some("Data type (legacy)"));
+        assert_eq!(
+            serial.documentation,
+            Some(Documentation::String(
+                "Auto-incrementing four-byte integer. Legacy: prefer \
+                 `integer GENERATED ALWAYS AS IDENTITY`"
+                    .to_string()
+            ))
+        );
+        // After every other type
+        assert_eq!(postgres[postgres.len() - 3].label, "serial");
+
+        let without_identity = render_column_types(Dialect::PostgreSQL, false);
+        let serial = without_identity
+            .iter()
+            .find(|i| i.label == "serial")
+            .unwrap();
+        assert_eq!(serial.detail.as_deref(), Some("Data type"));
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # VALUES row completion
+//!
+//! Literal hints for a row constructor of a standalone or CTE VALUES list,
+//! ranked above columns and keywords. A VALUES list has no catalog types,
+//! so the type of a column is inferred from its value in the first row:
+//! - `TRUE` / `FALSE` for a boolean, or `1` / `0` on MySQL
+//! - an empty string literal for text
+//! - `CURRENT_DATE` or `CURRENT_TIMESTAMP` for a date or timestamp
+//!
+//! `NULL` is offered for every column, and alone when the type is unknown.
+
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind, Documentation, InsertTextFormat};
+use unified_sql_lsp_ir::Dialect;
+use unified_sql_lsp_ir::dialect::DialectFamily;
+
+/// Type of a first-row value, as far as its text tells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueType {
+    Integer,
+    Numeric,
+    Text,
+    Boolean,
+    Date,
+    Timestamp,
+}
+
+impl ValueType {
+    /// Infer the type of a literal written in a VALUES row
+    fn of_literal(value: &str) -> Option<Self> {
+        let upper = value.trim().to_ascii_uppercase();
+        let unsigned = upper.trim_start_matches(['-', '+']);
+        if !unsigned.is_empty() && unsigned.chars().all(|c| c.is_ascii_digit()) {
+            return Some(Self::Integer);
+        }
+        if unsigned.parse::<f64>().is_ok() && unsigned.starts_with(|c: char| c.is_ascii_digit()) {
+            return Some(Self::Numeric);
+        }
+
+        match upper.as_str() {
+            "TRUE" | "FALSE" => Some(Self::Boolean),
+            "CURRENT_DATE" => Some(Self::Date),
+            "CURRENT_TIMESTAMP" | "NOW()" | "LOCALTIMESTAMP" => Some(Self::Timestamp),
+            _ if upper.starts_with("DATE '") => Some(Self::Date),
+            _ if upper.starts_with("TIMESTAMP '") => Some(Self::Timestamp),
+            // Quoted text; with a cast after the quotes the type is unknown
+            _ if upper.starts_with('\'') && upper.ends_with('\'') && upper.len() >= 2 => {
+                Some(Self::Text)
+            }
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Integer => "integer",
+            Self::Numeric => "numeric",
+            Self::Text => "text",
+            Self::Boolean => "boolean",
+            Self::Date => "date",
+            Self::Timestamp => "timestamp",
+        }
+    }
+}
+
+/// Render the literal hints for a value of a VALUES row
+///
+/// `column` is zero-based; `column_name` comes from a CTE column list and
+/// `first_row_value` is the value of the same column in the first row.
+pub fn render_values_row_literals(
+    column: usize,
+    column_name: Option<&str>,
+    first_row_value: Option<&str>,
+    dialect: Dialect,
+    snippet_support: bool,
+) -> Vec<CompletionItem> {
+    let value_type = first_row_value.and_then(ValueType::of_literal);
+    let column = column_name
+        .map(String::from)
+        .unwrap_or_else(|| format!("column{}", column + 1));
+    let detail = match value_type {
+        Some(value_type) => format!("{} ({})", column, value_type.name()),
+        None => column,
+    };
+
+    // label, inserted snippet (if any), description
+    let mut literals: Vec<(&str, Option<&str>, String)> = match value_type {
+        Some(ValueType::Boolean) => {
+            let values = match dialect.family() {
+                DialectFamily::PostgreSQL => [("TRUE", "true"), ("FALSE", "false")],
+                DialectFamily::MySQL => [("1", "true"), ("0", "false")],
+            };
+            values
+                .iter()
+                .map(|(label, value)| (*label, None, format!("Boolean {}", value)))
+                .collect()
+        }
+        Some(ValueType::Text) => vec![("''", Some("'$1'"), "String literal".to_string())],
+        Some(ValueType::Date) => vec![(
+            "CURRENT_DATE",
+            None,
+            "CURRENT_DATE at the start of the transaction".to_string(),
+        )],
+        Some(ValueType::Timestamp) => vec![(
+            "CURRENT_TIMESTAMP",
+            None,
+            "CURRENT_TIMESTAMP at the start of the transaction".to_string(),
+        )],
+        Some(ValueType::Integer | ValueType::Numeric) | None => Vec::new(),
+    };
+    literals.push(("NULL", None, "No value".to_string()));
+
+    literals
+        .into_iter()
+        .enumerate()
+        .map(|(i, (label, snippet, description))| {
+            let (insert_text, insert_text_format) = match snippet {
+                Some(snippet) if snippet_support => {
+                    (Some(snippet.to_string()), Some(InsertTextFormat::SNIPPET))
+                }
+                _ => (None, None),
+            };
+            CompletionItem {
+                label: label.to_string(),
+                kind: Some(CompletionItemKind::VALUE),
+                detail: Some(detail.clone()),
+                documentation: Some(Documentation::String(description)),
+                // `!` sorts before the `0…` sort texts of columns
+                sort_text: Some(format!("!{}", i)),
+                preselect: Some(i == 0),
+                insert_text,
+                insert_text_format,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(items: &[CompletionItem]) -> Vec<&str> {
+        items.iter().map(|i| i.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_literals_follow_first_row_type() {
+        let items =
+            render_values_row_literals(1, Some("name"), Some("'a'"), Dialect::PostgreSQL, true);
+        assert_eq!(labels(&items), vec!["''", "NULL"]);
+        assert_eq!(items[0].detail.as_deref(), Some("name (text)"));
+        assert_eq!(items[0].insert_text.as_deref(), Some("'$1'"));
+        assert_eq!(items[0].insert_text_format, Some(InsertTextFormat::SNIPPET));
+        assert_eq!(items[0].kind, Some(CompletionItemKind::VALUE));
+        assert_eq!(items[0].preselect, Some(true));
+
+        let items = render_values_row_literals(0, None, Some("true"), Dialect::MySQL, false);
+        assert_eq!(labels(&items), vec!["1", "0", "NULL"]);
+        assert_eq!(items[0].detail.as_deref(), Some("column1 (boolean)"));
+
+        let items = render_values_row_literals(
+            2,
+            None,
+            Some("DATE '2024-01-01'"),
+            Dialect::PostgreSQL,
+            true,
+        );
+        assert_eq!(labels(&items), vec!["CURRENT_DATE", "NULL"]);
+    }
+
+    #[test]
+    fn test_unknown_type_offers_null() {
+        let items = render_values_row_literals(0, Some("id"), None, Dialect::PostgreSQL, true);
+        assert_eq!(labels(&items), vec!["NULL"]);
+        assert_eq!(items[0].detail.as_deref(), Some("id"));
+
+        let items =
+            render_values_row_literals(0, Some("id"), Some("-12"), Dialect::PostgreSQL, true);
+        assert_eq!(labels(&items), vec!["NULL"]);
+        assert_eq!(items[0].detail.as_deref(), Some("id (integer)"));
+
+        let items =
+            render_values_row_literals(0, None, Some("'1'::int"), Dialect::PostgreSQL, false);
+        assert_eq!(items[0].detail.as_deref(), Some("column1"));
+    }
+}
@@ -42,15 +42,43 @@
 //!     .collect();
 //! ```
 
+use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tower_lsp::lsp_types::*;
 use tracing::{debug, info};
-use unified_sql_lsp_semantic::SyntaxDiagnosticAnalyzer;
+use unified_sql_grammar::language_for_dialect;
+use unified_sql_lsp_context::{
+    ScopeBuilder, TokenKind, byte_to_position, mask, statement_range, tokens, too_deep_subquery,
+};
+use unified_sql_lsp_ir::dialect::DialectFamily;
+use unified_sql_lsp_ir::{
+    ColumnRef, Dialect, DialectExtensions, Expr, IdentifierComparer, Literal, Query,
+};
+use unified_sql_lsp_lowering::dialect::{MySQLLowering, PostgreSQLLowering};
+use unified_sql_lsp_lowering::{
+    CstNode, Lowering, LoweringContext, TreeSitterNode, binary_expr_id, definition_id,
+};
+use unified_sql_lsp_semantic::{
+    ColumnResolver, LintRule, ScopeManager, ScopeType, SyntaxDiagnosticAnalyzer, TableSymbol,
+    UnknownColumn, lint_condition, lint_in_subquery_limits,
+};
+
+use crate::column_migrations::{ColumnChange, ColumnMigration};
+use crate::column_references::{column_references, dml_column_references};
+use crate::config::{DiagnosticsConfig, RuleLevel};
+use crate::diagnostics_coalescer::DiagnosticsCoalescer;
+use crate::document::{Document, ParseMetadata};
+use crate::migration_catalog::removing_migration;
+use crate::positions::to_lsp_position;
+use crate::table_references::{cte_cycles, cte_self_references, table_references};
+use crate::workspace_symbols::index_document;
 
 /// Diagnostic code identifying the type of diagnostic
 ///
 /// These codes are used to categorize different types of SQL errors and warnings.
+/// Built-in codes are namespaced (`usql/<rule>`) and stable, so users can
+/// refer to them in `diagnostics.rules` settings.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DiagnosticCode {
     /// Syntax error in SQL (DIAG-002)
@@ -65,6 +93,33 @@ pub enum DiagnosticCode {
     /// Ambiguous column reference (DIAG-005)
     AmbiguousColumn,
 
+    /// Syntax specific to another dialect
+    DialectFeature,
+
+    /// Column removed by a migration pending in another open document
+    PendingMigration,
+
+    /// `IS [NOT] NULL` on a column declared `NOT NULL`
+    NotNullCheck,
+
+    /// Comparison with `= NULL` or `<> NULL` instead of `IS [NOT] NULL`
+    EqualsNull,
+
+    /// Statement modifying the database in a read-only workspace
+    ReadOnlyWrite,
+
+    /// `LIMIT` in a subquery of `IN`, which the dialect rejects
+    LimitInSubquery,
+
+    /// CTEs of one `WITH` clause referencing each other in a cycle
+    CteCycle,
+
+    /// Subqueries nested deeper than analysis goes
+    NestingDepth,
+
+    /// Name defined twice in one `WITH` clause or FROM list
+    DuplicateName,
+
     /// Custom diagnostic code with description
     Custom(String),
 }
@@ -73,14 +128,47 @@ impl DiagnosticCode {
     /// Get the string representation of this diagnostic code
     pub fn as_str(&self) -> String {
         match self {
-            DiagnosticCode::SyntaxError => "SYNTAX-001".to_string(),
-            DiagnosticCode::UndefinedTable => "SEMANTIC-001".to_string(),
-            DiagnosticCode::UndefinedColumn => "SEMANTIC-002".to_string(),
-            DiagnosticCode::AmbiguousColumn => "SEMANTIC-003".to_string(),
+            DiagnosticCode::SyntaxError => "usql/syntax".to_string(),
+            DiagnosticCode::UndefinedTable => "usql/unknown-table".to_string(),
+            DiagnosticCode::UndefinedColumn => "usql/unknown-column".to_string(),
+            DiagnosticCode::AmbiguousColumn => "usql/ambiguous-column".to_string(),
+            DiagnosticCode::DialectFeature => "usql/dialect-feature".to_string(),
+            DiagnosticCode::PendingMigration => "usql/pending-migration".to_string(),
+            DiagnosticCode::NotNullCheck => "usql/not-null-check".to_string(),
+            DiagnosticCode::EqualsNull => "usql/equals-null".to_string(),
+            DiagnosticCode::ReadOnlyWrite => "usql/read-only-write".to_string(),
+            DiagnosticCode::LimitInSubquery => "usql/limit-in-subquery".to_string(),
+            DiagnosticCode::CteCycle => "usql/cte-cycle".to_string(),
+            DiagnosticCode::NestingDepth => "usql/nesting-depth".to_string(),
+            DiagnosticCode::DuplicateName => "usql/duplicate-name".to_string(),
             DiagnosticCode::Custom(s) => s.clone(),
         }
     }
 
+    /// All built-in diagnostic codes
+    pub fn builtin() -> [DiagnosticCode; 13] {
+        [
+            DiagnosticCode::SyntaxError,
+            DiagnosticCode::UndefinedTable,
+            DiagnosticCode::UndefinedColumn,
+            DiagnosticCode::AmbiguousColumn,
+            DiagnosticCode::DialectFeature,
+            DiagnosticCode::PendingMigration,
+            DiagnosticCode::NotNullCheck,
+            DiagnosticCode::EqualsNull,
+            DiagnosticCode::ReadOnlyWrite,
+            DiagnosticCode::LimitInSubquery,
+            DiagnosticCode::CteCycle,
+            DiagnosticCode::NestingDepth,
+            DiagnosticCode::DuplicateName,
+        ]
+    }
+
+    /// Whether a code string names a built-in diagnostic
+    pub fn is_builtin(code: &str) -> bool {
+        Self::builtin().iter().any(|c| c.as_str() == code)
+    }
+
     /// Get a human-readable description of this diagnostic code
     pub fn description(&self) -> String {
         match self {
@@ -88,6 +176,17 @@ impl DiagnosticCode {
             DiagnosticCode::UndefinedTable => "Undefined table reference".to_string(),
             DiagnosticCode::UndefinedColumn => "Undefined column reference".to_string(),
             DiagnosticCode::AmbiguousColumn => "Ambiguous column reference".to_string(),
+            DiagnosticCode::DialectFeature => "Syntax not supported by the dialect".to_string(),
+            DiagnosticCode::PendingMigration => "Column changed by a pending migration".to_string(),
+            DiagnosticCode::NotNullCheck => "NULL check on a NOT NULL column".to_string(),
+            DiagnosticCode::EqualsNull => "Comparison with NULL using = or <>".to_string(),
+            DiagnosticCode::ReadOnlyWrite => "Write statement in a read-only workspace".to_string(),
+            DiagnosticCode::LimitInSubquery => "LIMIT in an IN subquery".to_string(),
+            DiagnosticCode::CteCycle => "CTEs referencing each other in a cycle".to_string(),
+            DiagnosticCode::NestingDepth => "Subqueries nested too deep to analyze".to_string(),
+            DiagnosticCode::DuplicateName => {
+                "Name defined twice in one WITH clause or FROM list".to_string()
+            }
             DiagnosticCode::Custom(s) => format!("Custom diagnostic: {}", s),
         }
     }
@@ -122,6 +221,9 @@ pub struct SqlDiagnostic {
 
     /// Related information (e.g., suggestions, related locations)
     pub related_information: Option<Vec<DiagnosticRelatedInformation>>,
+
+    /// Data preserved for code actions (e.g., a suggested replacement)
+    pub data: Option<serde_json::Value>,
 }
 
 impl SqlDiagnostic {
@@ -140,9 +242,23 @@ impl SqlDiagnostic {
             code: None,
             source: "unified-sql-lsp".to_string(),
             related_information: None,
+            data: None,
         }
     }
 
+    /// Create an unknown column diagnostic
+    ///
+    /// When a replacement is suggested it is stored in `data.replacement`,
+    /// which the quick fix code action applies to `range`.
+    pub fn unknown_column(unknown: &UnknownColumn, range: Range) -> Self {
+        let mut diagnostic =
+            Self::error(unknown.to_string(), range).with_code(DiagnosticCode::UndefinedColumn);
+        if let Some(replacement) = &unknown.suggestion {
+            diagnostic.data = Some(serde_json::json!({ "replacement": replacement }));
+        }
+        diagnostic
+    }
+
     /// Set the diagnostic code
     pub fn with_code(mut self, code: DiagnosticCode) -> Self {
         self.code = Some(code);
@@ -166,7 +282,7 @@ impl SqlDiagnostic {
             message: self.message,
             related_information: self.related_information,
             tags: None,
-            data: None,
+            data: self.data,
         }
     }
 
@@ -265,6 +381,11 @@ impl DiagnosticCollector {
         // - unified_sql_lsp_semantic::SemanticAnalyzer for analysis
         // - unified_sql_lsp_semantic::SemanticValidator for validation
 
+        // An empty buffer (e.g. a freshly opened untitled document) isn't an error
+        if source.trim().is_empty() {
+            return Vec::new();
+        }
+
         self.syntax_analyzer
             .collect_diagnostics(tree, source)
             .into_iter()
@@ -341,227 +462,1913 @@ impl DiagnosticCollector {
 
         self.collect_diagnostics(&tree_guard, source, uri)
     }
-}
 
-/// Helper to publish diagnostics from a document
-///
-/// This function handles the common pattern of:
-/// 1. Getting the document tree
-/// 2. Collecting diagnostics
-/// 3. Converting to LSP format
-/// 4. Publishing to client
-///
-/// # Arguments
-///
-/// - `collector`: The diagnostic collector
-/// - `client`: The LSP client
-/// - `uri`: The document URI
-/// - `tree`: The optional tree from document
-/// - `source`: The source code
-///
-/// # Returns
-///
-/// The number of diagnostics published
-pub async fn publish_diagnostics_for_document(
-    collector: &DiagnosticCollector,
-    client: &tower_lsp::Client,
-    uri: Url,
-    tree: &Option<Arc<Mutex<tree_sitter::Tree>>>,
-    source: &str,
-) -> usize {
-    let sql_diagnostics = collector.collect_from_arc(tree, source, &uri);
+    /// Collect diagnostics for a document
+    ///
+    /// Uses the error ranges recorded in the document's parse metadata, so the
+    /// tree is only visited at the recorded locations. Falls back to walking the
+    /// tree when the document has no parse metadata.
+    pub fn collect_for_document(&self, document: &Document) -> Vec<SqlDiagnostic> {
+        let source = document.get_content();
+        if source.trim().is_empty() {
+            return Vec::new();
+        }
 
-    let diagnostics: Vec<Diagnostic> = sql_diagnostics.into_iter().map(|d| d.to_lsp()).collect();
+        let tree = document.tree();
+        let Some(metadata) = document.parse_metadata() else {
+            return self.collect_from_arc(&tree, &source, document.uri());
+        };
+        if !metadata.has_errors {
+            return Vec::new();
+        }
 
-    let count = diagnostics.len();
-    if count > 0 {
-        info!("Publishing {} diagnostics for {}", count, uri);
+        let Some(tree_arc) = tree else {
+            return Vec::new();
+        };
+        let Ok(tree_guard) = tree_arc.try_lock() else {
+            debug!(
+                "Failed to acquire tree lock for diagnostics: {}",
+                document.uri()
+            );
+            return Vec::new();
+        };
+
+        self.collect_from_metadata(&tree_guard, &source, metadata)
     }
 
-    client.publish_diagnostics(uri, diagnostics, None).await;
+    /// Collect syntax diagnostics from the error ranges recorded at parse time
+    ///
+    /// ERROR nodes nested in an already reported one are skipped, as are
+    /// ERROR nodes covering at most one character.
+    pub fn collect_from_metadata(
+        &self,
+        tree: &tree_sitter::Tree,
+        source: &str,
+        metadata: &ParseMetadata,
+    ) -> Vec<SqlDiagnostic> {
+        let root = tree.root_node();
+        let mut reported: Vec<Range> = Vec::new();
+        let mut diagnostics = Vec::new();
+
+        for range in &metadata.error_ranges {
+            if reported
+                .iter()
+                .any(|outer| outer.start <= range.start && range.end <= outer.end)
+            {
+                continue;
+            }
 
-    count
-}
+            let Some(node) = error_node_at(&root, *range) else {
+                continue;
+            };
+            if self.syntax_analyzer.should_ignore_error_node(&node, source) {
+                continue;
+            }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tower_lsp::lsp_types::Position;
+            let diagnostic = self.syntax_analyzer.create_error_diagnostic(&node, source);
+            diagnostics.push(
+                SqlDiagnostic::error(diagnostic.message, *range)
+                    .with_code(DiagnosticCode::SyntaxError),
+            );
+            reported.push(*range);
+        }
 
-    fn create_test_range(start_line: u32, start_col: u32, end_line: u32, end_col: u32) -> Range {
-        Range {
-            start: Position {
-                line: start_line,
-                character: start_col,
-            },
-            end: Position {
-                line: end_line,
-                character: end_col,
-            },
+        for (kind, range) in &metadata.missing_nodes {
+            diagnostics.push(
+                SqlDiagnostic::error(format!("Missing {}", kind), *range)
+                    .with_code(DiagnosticCode::SyntaxError),
+            );
         }
-    }
 
-    #[test]
-    fn test_sql_diagnostic_new() {
-        let range = create_test_range(0, 0, 0, 10);
-        let diagnostic =
-            SqlDiagnostic::new("Test message".to_string(), DiagnosticSeverity::ERROR, range);
+        diagnostics
+    }
 
-        assert_eq!(diagnostic.message, "Test message");
-        assert_eq!(diagnostic.severity, DiagnosticSeverity::ERROR);
-        assert_eq!(diagnostic.source, "unified-sql-lsp");
-        assert!(diagnostic.code.is_none());
-        assert!(diagnostic.related_information.is_none());
+    /// Warn about syntax the document's dialect does not support
+    ///
+    /// Runs on the source text, since the grammar of the document's dialect
+    /// usually cannot parse the foreign construct. Strings and comments are
+    /// skipped.
+    pub fn dialect_hints(&self, source: &str, dialect: Dialect) -> Vec<SqlDiagnostic> {
+        let code = mask(source, MASKED_TOKENS).to_uppercase();
+        let mut diagnostics = Vec::new();
+        if !dialect.supports(DialectExtensions::DistinctOn) {
+            diagnostics.extend(Self::distinct_on_hints(source, &code, dialect));
+        }
+        if !dialect.supports(DialectExtensions::FullOuterJoin) {
+            diagnostics.extend(Self::full_join_hints(source, &code, dialect));
+        }
+        if !dialect.supports(DialectExtensions::AutoIncrement) {
+            diagnostics.extend(Self::auto_increment_hints(source, &code));
+        }
+        if !dialect.supports(DialectExtensions::Returning) {
+            diagnostics.extend(Self::returning_hints(source, &code, dialect));
+        }
+        diagnostics
     }
 
-    #[test]
-    fn test_sql_diagnostic_with_code() {
-        let range = create_test_range(0, 0, 0, 10);
-        let diagnostic =
-            SqlDiagnostic::error("Error".to_string(), range).with_code(DiagnosticCode::SyntaxError);
+    /// Flag `RETURNING` in `code`, the masked and uppercased `source`
+    fn returning_hints(source: &str, code: &str, dialect: Dialect) -> Vec<SqlDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut from = 0;
+        while let Some(found) = code[from..].find("RETURNING") {
+            let start = from + found;
+            from = start + "RETURNING".len();
+            if !is_word_boundary(code, start, from) {
+                continue;
+            }
 
-        assert_eq!(diagnostic.code, Some(DiagnosticCode::SyntaxError));
+            let range = Range::new(
+                to_lsp_position(byte_to_position(start, source)),
+                to_lsp_position(byte_to_position(from, source)),
+            );
+            diagnostics.push(
+                SqlDiagnostic::warning(
+                    format!(
+                        "RETURNING is PostgreSQL syntax and not supported by {:?}; \
+                         query the changed rows in a separate SELECT",
+                        dialect
+                    ),
+                    range,
+                )
+                .with_code(DiagnosticCode::DialectFeature),
+            );
+        }
+
+        diagnostics
     }
 
-    #[test]
-    fn test_sql_diagnostic_error() {
-        let range = create_test_range(1, 5, 1, 10);
-        let diagnostic = SqlDiagnostic::error("Syntax error".to_string(), range);
+    /// Flag `DISTINCT ON` in `code`, the masked and uppercased `source`
+    fn distinct_on_hints(source: &str, code: &str, dialect: Dialect) -> Vec<SqlDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut from = 0;
+        while let Some(found) = code[from..].find("DISTINCT") {
+            let start = from + found;
+            from = start + "DISTINCT".len();
+            if !is_word_boundary(code, start, from) {
+                continue;
+            }
+            let rest = &code[from..];
+            let on_start = from + (rest.len() - rest.trim_start().len());
+            let on_end = on_start + "ON".len();
+            if on_start == from
+                || !code[on_start..].starts_with("ON")
+                || !is_word_boundary(code, on_start, on_end)
+            {
+                continue;
+            }
 
-        assert_eq!(diagnostic.message, "Syntax error");
-        assert_eq!(diagnostic.severity, DiagnosticSeverity::ERROR);
+            let range = Range::new(
+                to_lsp_position(byte_to_position(start, source)),
+                to_lsp_position(byte_to_position(on_end, source)),
+            );
+            diagnostics.push(
+                SqlDiagnostic::warning(
+                    format!(
+                        "DISTINCT ON is PostgreSQL syntax and not supported by {:?}",
+                        dialect
+                    ),
+                    range,
+                )
+                .with_code(DiagnosticCode::DialectFeature),
+            );
+        }
+
+        diagnostics
     }
 
-    #[test]
-    fn test_sql_diagnostic_warning() {
-        let range = create_test_range(0, 0, 0, 5);
-        let diagnostic = SqlDiagnostic::warning("Warning".to_string(), range);
+    /// Flag `AUTO_INCREMENT` in `code`, the masked and uppercased `source`
+    ///
+    /// `data.replacement` holds the identity column clause, which like
+    /// `AUTO_INCREMENT` still accepts explicit values.
+    fn auto_increment_hints(source: &str, code: &str) -> Vec<SqlDiagnostic> {
+        const IDENTITY: &str = "GENERATED BY DEFAULT AS IDENTITY";
+
+        let mut diagnostics = Vec::new();
+        let mut from = 0;
+        while let Some(found) = code[from..].find("AUTO_INCREMENT") {
+            let start = from + found;
+            from = start + "AUTO_INCREMENT".len();
+            if !is_word_boundary(code, start, from) {
+                continue;
+            }
 
-        assert_eq!(diagnostic.message, "Warning");
-        assert_eq!(diagnostic.severity, DiagnosticSeverity::WARNING);
+            let range = Range::new(
+                to_lsp_position(byte_to_position(start, source)),
+                to_lsp_position(byte_to_position(from, source)),
+            );
+            let mut diagnostic = SqlDiagnostic::warning(
+                format!(
+                    "AUTO_INCREMENT is MySQL syntax; use {} for an identity column",
+                    IDENTITY
+                ),
+                range,
+            )
+            .with_code(DiagnosticCode::DialectFeature);
+            diagnostic.data = Some(serde_json::json!({ "replacement": IDENTITY }));
+            diagnostics.push(diagnostic);
+        }
+
+        diagnostics
     }
 
-    #[test]
-    fn test_sql_diagnostic_to_lsp() {
-        let range = create_test_range(0, 0, 1, 5);
-        let sql_diagnostic = SqlDiagnostic::error("Error".to_string(), range)
-            .with_code(DiagnosticCode::UndefinedTable);
+    /// Flag `FULL [OUTER] JOIN` in `code`, the masked and uppercased `source`
+    fn full_join_hints(source: &str, code: &str, dialect: Dialect) -> Vec<SqlDiagnostic> {
+        // Start of the next word after `from`, skipping whitespace
+        let next_word = |from: usize, word: &str| {
+            let rest = &code[from..];
+            let start = from + (rest.len() - rest.trim_start().len());
+            let end = start + word.len();
+            (start > from && code[start..].starts_with(word) && is_word_boundary(code, start, end))
+                .then_some(end)
+        };
 
-        let lsp_diagnostic = sql_diagnostic.to_lsp();
+        let mut diagnostics = Vec::new();
+        let mut from = 0;
+        while let Some(found) = code[from..].find("FULL") {
+            let start = from + found;
+            from = start + "FULL".len();
+            if !is_word_boundary(code, start, from) {
+                continue;
+            }
+            let after_outer = next_word(from, "OUTER").unwrap_or(from);
+            let Some(end) = next_word(after_outer, "JOIN") else {
+                continue;
+            };
+
+            let range = Range::new(
+                to_lsp_position(byte_to_position(start, source)),
+                to_lsp_position(byte_to_position(end, source)),
+            );
+            diagnostics.push(
+                SqlDiagnostic::warning(
+                    format!(
+                        "FULL OUTER JOIN is not supported by {:?}; rewrite it as a LEFT JOIN \
+                         UNION a RIGHT JOIN of the same tables",
+                        dialect
+                    ),
+                    range,
+                )
+                .with_code(DiagnosticCode::DialectFeature),
+            );
+        }
 
-        assert_eq!(lsp_diagnostic.message, "Error");
-        assert_eq!(lsp_diagnostic.range, range);
-        assert_eq!(lsp_diagnostic.severity, Some(DiagnosticSeverity::ERROR));
-        assert_eq!(lsp_diagnostic.source, Some("unified-sql-lsp".to_string()));
+        diagnostics
     }
 
-    #[test]
-    fn test_diagnostic_code_as_str() {
-        assert_eq!(DiagnosticCode::SyntaxError.as_str(), "SYNTAX-001");
-        assert_eq!(DiagnosticCode::UndefinedTable.as_str(), "SEMANTIC-001");
-        assert_eq!(DiagnosticCode::UndefinedColumn.as_str(), "SEMANTIC-002");
-        assert_eq!(DiagnosticCode::AmbiguousColumn.as_str(), "SEMANTIC-003");
-        assert_eq!(
-            DiagnosticCode::Custom("CUSTOM-123".to_string()).as_str(),
-            "CUSTOM-123"
-        );
-    }
+    /// Report `LIMIT` in subqueries of `IN` on dialects that reject it
+    ///
+    /// Each is an error at the subquery, found by [`lint_in_subquery_limits`]
+    /// in the lowered WHERE, ON and HAVING conditions of `source`.
+    pub fn limit_in_subqueries(&self, source: &str, dialect: Dialect) -> Vec<SqlDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut start = 0;
+        while start < source.len() {
+            let statement = statement_range(source, start);
+            start = statement.end + 1;
+            let conditions = condition_nodes(&source[statement.clone()], dialect);
+            diagnostics.extend(self.condition_limits_in_subqueries(
+                source,
+                statement.start,
+                &conditions,
+                dialect,
+            ));
+        }
 
-    #[test]
-    fn test_diagnostic_code_description() {
-        assert_eq!(
-            DiagnosticCode::SyntaxError.description(),
-            "SQL syntax error"
-        );
-        assert_eq!(
-            DiagnosticCode::UndefinedTable.description(),
-            "Undefined table reference"
-        );
-        assert_eq!(
-            DiagnosticCode::UndefinedColumn.description(),
-            "Undefined column reference"
-        );
-        assert_eq!(
-            DiagnosticCode::AmbiguousColumn.description(),
-            "Ambiguous column reference"
-        );
+        diagnostics
     }
 
-    #[test]
-    fn test_diagnostic_code_to_number_or_string() {
-        let code: NumberOrString = DiagnosticCode::SyntaxError.into();
-        assert!(matches!(code, NumberOrString::String(s) if s == "SYNTAX-001"));
+    /// [`Self::limit_in_subqueries`] of `conditions`, nodes of the statement
+    /// starting at byte `offset` of `source`
+    pub fn condition_limits_in_subqueries<N: CstNode>(
+        &self,
+        source: &str,
+        offset: usize,
+        conditions: &[N],
+        dialect: Dialect,
+    ) -> Vec<SqlDiagnostic> {
+        let mut diagnostics = Vec::new();
+        for node in conditions {
+            let Some((condition, ctx)) = lower_condition(node, dialect) else {
+                continue;
+            };
+            for (index, finding) in lint_in_subquery_limits(&condition, dialect) {
+                let id = binary_expr_id(index);
+                let (Some(span), Some(operator)) = (
+                    ctx.get_source_span(&id),
+                    ctx.get_source_span(&format!("{}:operator", id)),
+                ) else {
+                    continue;
+                };
+                // The subquery follows the operator
+                let text = &source[offset + operator.end..offset + span.end];
+                let subquery = span.end - text.trim_start().len()..span.end;
+                let range = Range::new(
+                    to_lsp_position(byte_to_position(offset + subquery.start, source)),
+                    to_lsp_position(byte_to_position(offset + subquery.end, source)),
+                );
+                diagnostics.push(
+                    SqlDiagnostic::error(finding.message, range)
+                        .with_code(DiagnosticCode::LimitInSubquery),
+                );
+            }
+        }
 
-        let custom: NumberOrString = DiagnosticCode::Custom("TEST-001".to_string()).into();
-        assert!(matches!(custom, NumberOrString::String(s) if s == "TEST-001"));
+        diagnostics
     }
 
-    #[test]
-    fn test_diagnostic_collector_new() {
-        let collector = DiagnosticCollector::new();
-        // Just ensure it creates without panicking
-        let _ = collector;
+    /// Report CTEs of one `WITH` clause referencing each other in a cycle
+    ///
+    /// Each cycle is an error at the reference closing it.
+    pub fn cte_cycles(&self, source: &str) -> Vec<SqlDiagnostic> {
+        cte_cycles(source)
+            .into_iter()
+            .map(|(names, range)| {
+                SqlDiagnostic::error(
+                    format!(
+                        "CTEs reference each other in a cycle: {}",
+                        names.join(" → ")
+                    ),
+                    range,
+                )
+                .with_code(DiagnosticCode::CteCycle)
+            })
+            .collect()
     }
 
-    #[test]
-    fn test_diagnostic_collector_collect_diagnostics() {
-        let collector = DiagnosticCollector::new();
-        let uri = Url::parse("file:///test.sql").unwrap();
-
-        // Create a minimal tree (this will fail if no grammar is compiled)
-        // But we can still test the empty case
-        if let Some(language) =
-            unified_sql_grammar::language_for_dialect(unified_sql_lsp_ir::Dialect::MySQL)
-        {
-            let mut parser = tree_sitter::Parser::new();
-            if parser.set_language(language).is_ok()
-                && let Some(tree) = parser.parse("SELECT 1", None)
-            {
-                let diagnostics = collector.collect_diagnostics(&tree, "SELECT 1", &uri);
-                // Currently returns empty (will be implemented in DIAG-002 to DIAG-005)
-                assert!(diagnostics.is_empty());
+    /// Report CTE names repeated in one `WITH` clause and names repeated in
+    /// one FROM list
+    ///
+    /// Each repetition is an error at the later definition, pointing at the
+    /// first one in `related_information`. SELECT statements are lowered and
+    /// the errors of [`ScopeManager::for_query`] reported. Names are compared
+    /// the way `dialect` folds identifiers, so `"Recent"` and `recent` only
+    /// collide in MySQL.
+    pub fn duplicate_definitions(
+        &self,
+        uri: &Url,
+        source: &str,
+        dialect: Dialect,
+    ) -> Vec<SqlDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut start = 0;
+        while start < source.len() {
+            let range = statement_range(source, start);
+            for select in select_nodes(&source[range.clone()], dialect) {
+                diagnostics.extend(self.query_duplicate_definitions(
+                    uri,
+                    source,
+                    range.start,
+                    &select,
+                    dialect,
+                ));
             }
+            start = range.end + 1;
         }
+        diagnostics
     }
 
-    #[test]
-    fn test_diagnostic_collector_collect_from_arc_none() {
-        let collector = DiagnosticCollector::new();
-        let uri = Url::parse("file:///test.sql").unwrap();
-        let tree: Option<Arc<Mutex<tree_sitter::Tree>>> = None;
+    /// [`Self::duplicate_definitions`] of `query`, a SELECT node of the
+    /// statement starting at byte `offset` of `source`
+    pub fn query_duplicate_definitions<N: CstNode>(
+        &self,
+        uri: &Url,
+        source: &str,
+        offset: usize,
+        query: &N,
+        dialect: Dialect,
+    ) -> Vec<SqlDiagnostic> {
+        let Some((query, ctx)) = lower_query(query, dialect) else {
+            return Vec::new();
+        };
+        let range = |bytes: &std::ops::Range<usize>| {
+            Range::new(
+                to_lsp_position(byte_to_position(offset + bytes.start, source)),
+                to_lsp_position(byte_to_position(offset + bytes.end, source)),
+            )
+        };
 
-        let diagnostics = collector.collect_from_arc(&tree, "SELECT 1", &uri);
-        assert!(diagnostics.is_empty());
+        let mut diagnostics: Vec<SqlDiagnostic> = ScopeManager::for_query(&query, dialect)
+            .redefinitions()
+            .filter_map(|(error, (first, duplicate))| {
+                let first = ctx.get_source_span(&definition_id(first))?;
+                let duplicate = ctx.get_source_span(&definition_id(duplicate))?;
+                let first_name = &source[offset + first.start..offset + first.end];
+                Some(
+                    SqlDiagnostic::error(error.to_string(), range(&duplicate))
+                        .with_code(DiagnosticCode::DuplicateName)
+                        .with_related(vec![DiagnosticRelatedInformation {
+                            location: Location::new(uri.clone(), range(&first)),
+                            message: format!("'{}' first defined here", first_name),
+                        }]),
+                )
+            })
+            .collect();
+        diagnostics.sort_by_key(|d| d.range.start);
+        diagnostics
     }
 
-    // Tests for pattern detection helpers
-
-    #[test]
-    fn test_is_identifier() {
-        let collector = DiagnosticCollector::new();
+    /// Note the first subquery nested deeper than `max_depth`, where
+    /// analysis of `source` stops
+    pub fn nesting_depth(&self, source: &str, max_depth: usize) -> Option<SqlDiagnostic> {
+        let offset = too_deep_subquery(source, max_depth)?;
+        let range = Range::new(
+            to_lsp_position(byte_to_position(offset, source)),
+            to_lsp_position(byte_to_position(offset + 1, source)),
+        );
+        Some(
+            SqlDiagnostic::information(
+                format!(
+                    "Subqueries nest deeper than {} levels; the query isn't analyzed further",
+                    max_depth
+                ),
+                range,
+            )
+            .with_code(DiagnosticCode::NestingDepth),
+        )
+    }
 
-        // Valid identifiers
-        assert!(collector.is_identifier("id"));
-        assert!(collector.is_identifier("username"));
-        assert!(collector.is_identifier("_id"));
-        assert!(collector.is_identifier("`id`"));
-        assert!(collector.is_identifier("\"id\""));
+    /// Report statements that modify the database, for read-only workspaces
+    ///
+    /// INSERT, REPLACE, UPDATE, DELETE, MERGE, TRUNCATE, ALTER and DROP
+    /// statements are errors spanning the statement, as are queries locking
+    /// rows with `FOR UPDATE`. Only the statement keyword is checked, after
+    /// any WITH clause.
+    pub fn write_statements(&self, source: &str) -> Vec<SqlDiagnostic> {
+        let code = mask(source, MASKED_TOKENS).to_uppercase();
+        let mut diagnostics = Vec::new();
+        let mut start = 0;
+        while start < code.len() {
+            let statement = statement_range(&code, start);
+            start = statement.end + 1;
+            let text = &code[statement.clone()];
+            let Some(keyword) = write_keyword(text) else {
+                continue;
+            };
+
+            let begin = statement.start + (text.len() - text.trim_start().len());
+            let end = statement.start + text.trim_end().len();
+            let range = Range::new(
+                to_lsp_position(byte_to_position(begin, source)),
+                to_lsp_position(byte_to_position(end, source)),
+            );
+            diagnostics.push(
+                SqlDiagnostic::error(
+                    format!("write statement in read-only workspace: {}", keyword),
+                    range,
+                )
+                .with_code(DiagnosticCode::ReadOnlyWrite),
+            );
+        }
 
-        // Invalid identifiers
-        assert!(!collector.is_identifier(""));
-        assert!(!collector.is_identifier("123"));
-        assert!(!collector.is_identifier("id username"));
-        assert!(!collector.is_identifier("id,"));
+        diagnostics
     }
 
-    #[test]
-    fn test_is_missing_comma_pattern() {
-        let collector = DiagnosticCollector::new();
-
-        // Missing comma patterns
-        assert!(collector.is_missing_comma_pattern("id username"));
-        assert!(collector.is_missing_comma_pattern("col1 col2 col3"));
-        assert!(collector.is_missing_comma_pattern("username FROM"));
+    /// Report tables read by `FROM` or `JOIN` that the catalog doesn't have
+    ///
+    /// `catalog_tables` holds the catalog's table names. Tables, views and
+    /// CTEs the document defines itself are known too, so the target of
+    /// `CREATE TABLE ... AS` or `SELECT ... INTO` is never reported. A CTE
+    /// named in its own body is, unless the catalog has a table of that
+    /// name: that takes `WITH RECURSIVE`, on dialect versions where
+    /// `recursive_cte` is set.
+    pub fn unknown_tables(
+        &self,
+        uri: &Url,
+        source: &str,
+        catalog_tables: &[String],
+        recursive_cte: bool,
+    ) -> Vec<SqlDiagnostic> {
+        let defined = index_document(uri, source);
+        let in_catalog = |name: &str| {
+            name.eq_ignore_ascii_case("dual")
+                || catalog_tables.iter().any(|t| t.eq_ignore_ascii_case(name))
+        };
+        let is_known = |name: &str| {
+            in_catalog(name) || defined.iter().any(|d| d.name.eq_ignore_ascii_case(name))
+        };
+        let self_references = cte_self_references(source, recursive_cte);
+
+        table_references(source)
+            .into_iter()
+            .filter_map(|(name, range)| {
+                let message = if self_references.contains(&(name.clone(), range)) {
+                    if in_catalog(&name) {
+                        return None;
+                    }
+                    if recursive_cte {
+                        format!("CTE '{}' references itself without WITH RECURSIVE", name)
+                    } else {
+                        format!(
+                            "CTE '{}' references itself, but recursive CTEs aren't supported",
+                            name
+                        )
+                    }
+                } else if is_known(&name) {
+                    return None;
+                } else {
+                    format!("Unknown table '{}'", name)
+                };
+                Some(SqlDiagnostic::error(message, range).with_code(DiagnosticCode::UndefinedTable))
+            })
+            .collect()
+    }
+
+    /// UPDATE and DELETE statements of `source`, as byte range and tables
+    pub fn dml_statements(source: &str) -> Vec<(std::ops::Range<usize>, Vec<TableSymbol>)> {
+        let mut statements = Vec::new();
+        let mut start = 0;
+        while start < source.len() {
+            let range = statement_range(source, start);
+            let tables = ScopeBuilder::dml_tables_from_text(&source[range.clone()]);
+            if !tables.is_empty() {
+                statements.push((range.clone(), tables));
+            }
+            start = range.end + 1;
+        }
+        statements
+    }
+
+    /// Report WHERE, ORDER BY and RETURNING columns of an UPDATE or DELETE
+    /// that none of its tables has
+    ///
+    /// `tables` are the statement's tables with their catalog columns: the
+    /// same scope column completion offers there. References through a
+    /// qualifier naming none of them, such as PostgreSQL's `old` and `new`,
+    /// are left alone. RETURNING is only checked on dialects that have it;
+    /// elsewhere [`Self::dialect_hints`] flags it. Columns are compared
+    /// the way `dialect` does: a quoted column only matches its exact
+    /// spelling in PostgreSQL, but any case in MySQL.
+    pub fn unknown_columns(
+        &self,
+        source: &str,
+        statement: std::ops::Range<usize>,
+        tables: Vec<TableSymbol>,
+        dialect: Dialect,
+    ) -> Vec<SqlDiagnostic> {
+        let in_scope = |qualifier: &str| {
+            tables.iter().any(|t| {
+                t.alias
+                    .as_deref()
+                    .unwrap_or(&t.table_name)
+                    .eq_ignore_ascii_case(qualifier)
+            })
+        };
+        let returning = dialect.supports(DialectExtensions::Returning);
+        let references: Vec<_> = dml_column_references(&source[statement.clone()], returning)
+            .into_iter()
+            .filter(|(qualifier, _, _)| qualifier.as_deref().is_none_or(in_scope))
+            .collect();
+
+        let Some((resolver, scope_id)) = statement_resolver(tables) else {
+            return Vec::new();
+        };
+
+        let base = to_lsp_position(byte_to_position(statement.start, source));
+        let identifiers = IdentifierComparer::new(dialect);
+
+        references
+            .into_iter()
+            .filter_map(|(qualifier, column, range)| {
+                let mut column_ref = ColumnRef::new(identifiers.column(&column));
+                if let Some(qualifier) = qualifier {
+                    column_ref = column_ref.with_table(qualifier);
+                }
+                let unknown = resolver.diagnose_unknown(&column_ref, scope_id)?;
+                Some(SqlDiagnostic::unknown_column(
+                    &unknown,
+                    offset_range(base, range),
+                ))
+            })
+            .collect()
+    }
+
+    /// Statements of `source` with the tables their conditions see
+    ///
+    /// UPDATE and DELETE statements see their own tables, SELECT statements
+    /// those of their top-level FROM clause. Other statements have none.
+    pub fn condition_statements(source: &str) -> Vec<(std::ops::Range<usize>, Vec<TableSymbol>)> {
+        let mut statements = Vec::new();
+        let mut start = 0;
+        while start < source.len() {
+            let range = statement_range(source, start);
+            let text = &source[range.clone()];
+            if !text.trim().is_empty() {
+                let mut tables = ScopeBuilder::dml_tables_from_text(text);
+                if tables.is_empty() {
+                    tables = ScopeBuilder::select_tables_from_text(text);
+                }
+                statements.push((range.clone(), tables));
+            }
+            start = range.end + 1;
+        }
+        statements
+    }
+
+    /// Report `NULL` comparisons in the WHERE, ON and HAVING conditions of
+    /// a statement
+    ///
+    /// `IS [NOT] NULL` on a column of `tables` declared `NOT NULL` is a
+    /// warning. `= NULL` and `<> NULL` are errors whose `data.replacement`
+    /// rewrites the comparison to `IS [NOT] NULL`. Conditions are lowered
+    /// and linted by [`lint_condition`]; operands of unknown nullability are
+    /// never reported.
+    pub fn null_comparisons(
+        &self,
+        source: &str,
+        statement: std::ops::Range<usize>,
+        tables: Vec<TableSymbol>,
+        dialect: Dialect,
+    ) -> Vec<SqlDiagnostic> {
+        let conditions = condition_nodes(&source[statement.clone()], dialect);
+        self.condition_null_comparisons(source, statement.start, &conditions, tables, dialect)
+    }
+
+    /// [`Self::null_comparisons`] of `conditions`, nodes of the statement
+    /// starting at byte `offset` of `source`
+    pub fn condition_null_comparisons<N: CstNode>(
+        &self,
+        source: &str,
+        offset: usize,
+        conditions: &[N],
+        tables: Vec<TableSymbol>,
+        dialect: Dialect,
+    ) -> Vec<SqlDiagnostic> {
+        let (resolver, scope_id) = statement_resolver(tables)
+            .or_else(|| statement_resolver(Vec::new()))
+            .expect("an empty scope has no conflicting tables");
+        let range = |bytes: std::ops::Range<usize>| {
+            Range::new(
+                to_lsp_position(byte_to_position(offset + bytes.start, source)),
+                to_lsp_position(byte_to_position(offset + bytes.end, source)),
+            )
+        };
+
+        let mut diagnostics = Vec::new();
+        for node in conditions {
+            let Some((condition, ctx)) = lower_condition(node, dialect) else {
+                continue;
+            };
+            let comparisons = condition.binary_exprs();
+            for (index, finding) in lint_condition(&condition, &resolver, scope_id) {
+                let id = binary_expr_id(index);
+                let (Some(span), Some(operator)) = (
+                    ctx.get_source_span(&id),
+                    ctx.get_source_span(&format!("{}:operator", id)),
+                ) else {
+                    continue;
+                };
+
+                match finding.rule {
+                    LintRule::NotNullIsNull => diagnostics.push(
+                        SqlDiagnostic::warning(finding.message, range(span))
+                            .with_code(DiagnosticCode::NotNullCheck),
+                    ),
+                    LintRule::EqualsNull => {
+                        let null_last = matches!(
+                            comparisons[index],
+                            Expr::BinaryOp { right, .. } if **right == Expr::Literal(Literal::Null)
+                        );
+                        // The operator and `NULL`, on whichever side it is
+                        let reported = if null_last {
+                            operator.start..span.end
+                        } else {
+                            span.start..operator.end
+                        };
+                        let mut diagnostic =
+                            SqlDiagnostic::error(finding.message, range(reported.clone()))
+                                .with_code(DiagnosticCode::EqualsNull);
+                        if let Some(replacement) = finding.replacement.filter(|_| null_last) {
+                            let glued = source[..offset + reported.start]
+                                .chars()
+                                .next_back()
+                                .is_some_and(|c| !c.is_whitespace());
+                            let replacement = if glued {
+                                format!(" {}", replacement)
+                            } else {
+                                replacement
+                            };
+                            diagnostic.data =
+                                Some(serde_json::json!({ "replacement": replacement }));
+                        }
+                        diagnostics.push(diagnostic);
+                    }
+                    LintRule::LimitInSubquery => {}
+                }
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Report references to columns that `migrations`, pending in other
+    /// open documents, drop or rename
+    ///
+    /// A statement's references are checked against the tables its `FROM`
+    /// and `JOIN` clauses read. A qualifier naming none of them is taken as
+    /// an alias. The diagnostic points at the change in `related_information`.
+    pub fn pending_migration_columns(
+        &self,
+        source: &str,
+        migrations: &[ColumnMigration],
+    ) -> Vec<SqlDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut start = 0;
+        while start < source.len() {
+            let statement = statement_range(source, start);
+            start = statement.end + 1;
+            let text = &source[statement.clone()];
+            let tables: Vec<String> = table_references(text)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect();
+            if tables.is_empty() {
+                continue;
+            }
+
+            let base = to_lsp_position(byte_to_position(statement.start, source));
+            for (qualifier, column, range) in column_references(text) {
+                let named: Vec<&String> = tables
+                    .iter()
+                    .filter(|t| {
+                        qualifier
+                            .as_ref()
+                            .is_some_and(|q| t.eq_ignore_ascii_case(q))
+                    })
+                    .collect();
+                let candidates = if named.is_empty() {
+                    tables.iter().collect()
+                } else {
+                    named
+                };
+                let Some(migration) = candidates
+                    .into_iter()
+                    .find_map(|table| removing_migration(migrations, table, &column))
+                else {
+                    continue;
+                };
+                diagnostics.push(Self::pending_migration(
+                    migration,
+                    offset_range(base, range),
+                ));
+            }
+        }
+        diagnostics
+    }
+
+    fn pending_migration(migration: &ColumnMigration, range: Range) -> SqlDiagnostic {
+        let file = migration
+            .uri
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or(migration.uri.as_str());
+        let location = format!("{}:{}", file, migration.range.start.line + 1);
+        let (message, related) = match &migration.change {
+            ColumnChange::Renamed { to, .. } => (
+                format!(
+                    "column renamed to '{}' by pending migration ({})",
+                    to, location
+                ),
+                "Column renamed here",
+            ),
+            _ => (
+                format!("column dropped by pending migration ({})", location),
+                "Column dropped here",
+            ),
+        };
+
+        SqlDiagnostic::information(message, range)
+            .with_code(DiagnosticCode::PendingMigration)
+            .with_related(vec![DiagnosticRelatedInformation {
+                location: Location::new(migration.uri.clone(), migration.range),
+                message: related.to_string(),
+            }])
+    }
+}
+
+/// Resolver over a single scope holding `tables`
+///
+/// Returns `None` when two tables share a name or alias.
+fn statement_resolver(tables: Vec<TableSymbol>) -> Option<(ColumnResolver, usize)> {
+    let mut manager = ScopeManager::new();
+    let scope_id = manager.create_scope(ScopeType::Query, None);
+    let scope = manager.get_scope_mut(scope_id)?;
+    for table in tables {
+        scope.add_table(table).ok()?;
+    }
+    Some((ColumnResolver::new(manager), scope_id))
+}
+
+/// WHERE, ON and HAVING conditions of `statement`, in source order
+///
+/// Conditions with syntax errors are left out.
+fn condition_nodes(statement: &str, dialect: Dialect) -> Vec<TreeSitterNode> {
+    let Some(tree) = parse_statement(statement, dialect) else {
+        return Vec::new();
+    };
+
+    let mut conditions = Vec::new();
+    let mut pending = vec![tree.root_node()];
+    while let Some(node) = pending.pop() {
+        let mut cursor = node.walk();
+        let children: Vec<_> = node.named_children(&mut cursor).collect();
+        if matches!(
+            node.kind(),
+            "where_clause" | "join_clause" | "having_clause"
+        ) && !node.has_error()
+            && let Some(condition) = children.iter().rfind(|c| c.kind() == "expression")
+        {
+            conditions.push(TreeSitterNode::new(*condition, statement));
+        }
+        pending.extend(children);
+    }
+    conditions.sort_by_key(|condition| condition.start_byte());
+    conditions
+}
+
+/// Outermost SELECT statements of `statement`, in source order
+///
+/// Statements with syntax errors are left out.
+fn select_nodes(statement: &str, dialect: Dialect) -> Vec<TreeSitterNode> {
+    let Some(tree) = parse_statement(statement, dialect) else {
+        return Vec::new();
+    };
+
+    let mut selects = Vec::new();
+    let mut pending = vec![tree.root_node()];
+    while let Some(node) = pending.pop() {
+        if node.kind() == "select_statement" {
+            if !node.has_error() {
+                selects.push(TreeSitterNode::new(node, statement));
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        pending.extend(node.named_children(&mut cursor));
+    }
+    selects.sort_by_key(|select| select.start_byte());
+    selects
+}
+
+/// Parse `statement` with the grammar of `dialect`
+fn parse_statement(statement: &str, dialect: Dialect) -> Option<tree_sitter::Tree> {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(language_for_dialect(dialect)?).ok()?;
+    parser.parse(statement, None)
+}
+
+/// Lower a query with the lowering of `dialect`'s family
+///
+/// The context maps the names the query defines to their source.
+fn lower_query<N: CstNode>(node: &N, dialect: Dialect) -> Option<(Query, LoweringContext)> {
+    let mut ctx = LoweringContext::new(dialect);
+    let lowered = match dialect.family() {
+        DialectFamily::PostgreSQL => PostgreSQLLowering.lower_query(&mut ctx, node),
+        DialectFamily::MySQL => MySQLLowering.lower_query(&mut ctx, node),
+    };
+    lowered.ok().map(|query| (query, ctx))
+}
+
+/// Lower a condition with the lowering of `dialect`'s family
+///
+/// The context maps the condition's binary expressions to their source.
+fn lower_condition<N: CstNode>(node: &N, dialect: Dialect) -> Option<(Expr, LoweringContext)> {
+    let mut ctx = LoweringContext::new(dialect);
+    let lowered = match dialect.family() {
+        DialectFamily::PostgreSQL => PostgreSQLLowering.lower_expr(&mut ctx, node),
+        DialectFamily::MySQL => MySQLLowering.lower_expr(&mut ctx, node),
+    };
+    lowered.ok().map(|condition| (condition, ctx))
+}
+
+/// Move a range found in a statement starting at `base` to document positions
+fn offset_range(base: Position, range: Range) -> Range {
+    let offset = |position: Position| {
+        if position.line == 0 {
+            Position::new(base.line, base.character + position.character)
+        } else {
+            Position::new(base.line + position.line, position.character)
+        }
+    };
+    Range::new(offset(range.start), offset(range.end))
+}
+
+/// Tokens blanked out before scanning source text for keywords
+const MASKED_TOKENS: &[TokenKind] = &[
+    TokenKind::String,
+    TokenKind::QuotedIdentifier,
+    TokenKind::Comment,
+];
+
+/// Keywords of statements that modify the database
+const WRITE_KEYWORDS: &[&str] = &[
+    "INSERT", "REPLACE", "UPDATE", "DELETE", "MERGE", "TRUNCATE", "ALTER", "DROP",
+];
+
+/// Keyword of `statement` if it modifies the database
+///
+/// `statement` is masked and uppercased. The keyword after a leading WITH
+/// clause decides; a SELECT locking rows with `FOR [NO KEY] UPDATE` is
+/// reported as `SELECT ... FOR UPDATE`.
+fn write_keyword(statement: &str) -> Option<&'static str> {
+    let words: Vec<&str> = tokens(statement)
+        .filter(|token| token.depth <= 0)
+        .filter_map(|token| token.word())
+        .collect();
+
+    let is_keyword = |word: &&str| *word == "SELECT" || WRITE_KEYWORDS.contains(word);
+    let keyword = match words.first() {
+        Some(&"WITH") => words.iter().skip(1).copied().find(is_keyword)?,
+        first => *first?,
+    };
+    if let Some(write) = WRITE_KEYWORDS.iter().find(|write| **write == keyword) {
+        return Some(write);
+    }
+
+    let locks_for_update = words.windows(2).enumerate().any(|(i, pair)| {
+        pair[0] == "FOR"
+            && (pair[1] == "UPDATE" || words[i + 1..].starts_with(&["NO", "KEY", "UPDATE"]))
+    });
+    (keyword == "SELECT" && locks_for_update).then_some("SELECT ... FOR UPDATE")
+}
+
+/// Whether `text[start..end]` is a whole word
+fn is_word_boundary(text: &str, start: usize, end: usize) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    !text[..start].chars().next_back().is_some_and(is_word)
+        && !text[end..].chars().next().is_some_and(is_word)
+}
+
+/// Find the ERROR node covering exactly `range`
+fn error_node_at<'tree>(
+    root: &tree_sitter::Node<'tree>,
+    range: Range,
+) -> Option<tree_sitter::Node<'tree>> {
+    let point = |position: Position| tree_sitter::Point {
+        row: position.line as usize,
+        column: position.character as usize,
+    };
+
+    let mut node = root.descendant_for_point_range(point(range.start), point(range.end))?;
+    loop {
+        if node.is_error() && node_to_range(&node) == range {
+            return Some(node);
+        }
+        node = node.parent()?;
+    }
+}
+
+/// Publish computed diagnostics for a document
+///
+/// This is the single place computed diagnostics reach the client, so
+/// configured rule levels (see [`apply_rules`]) are applied here, the list
+/// is put in its published form by [`finalize_diagnostics`] and trimmed to
+/// `config.max_per_document` by [`cap_diagnostics`]. Delivery goes through
+/// the coalescer, which rate limits and deduplicates it.
+///
+/// # Arguments
+///
+/// - `coalescer`: Outbound coalescer in front of the LSP client
+/// - `uri`: The document URI
+/// - `diagnostics`: Diagnostics computed for the document
+/// - `version`: The document version they were computed for
+/// - `config`: Diagnostic rule configuration and publish interval
+///
+/// # Returns
+///
+/// The number of diagnostics published
+pub async fn publish_diagnostics_for_document(
+    coalescer: &DiagnosticsCoalescer,
+    uri: Url,
+    diagnostics: Vec<Diagnostic>,
+    version: Option<i32>,
+    config: &DiagnosticsConfig,
+) -> usize {
+    let diagnostics = cap_diagnostics(
+        finalize_diagnostics(apply_rules(diagnostics, config)),
+        config.max_per_document,
+    );
+
+    let count = diagnostics.len();
+    if count > 0 {
+        info!("Publishing {} diagnostics for {}", count, uri);
+    }
+
+    coalescer
+        .publish(uri, diagnostics, version, config.publish_interval)
+        .await;
+
+    count
+}
+
+/// Key of the stable identifier in a published diagnostic's `data`
+pub const DIAGNOSTIC_ID_KEY: &str = "id";
+
+/// Put diagnostics in their published form
+///
+/// Diagnostic passes append in traversal order and can overlap, so:
+/// - diagnostics are sorted by start position, severity and code (then end
+///   position and message, so the order is total)
+/// - of those with the same code, range and message only the first is kept,
+///   so identifiers are unique
+/// - each gets a stable identifier in `data` (see [`diagnostic_id`]), a hash
+///   of its code, range and message, merged into any existing `data` object
+pub fn finalize_diagnostics(mut diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+    diagnostics.sort_by(|a, b| {
+        let key = |d: &Diagnostic| {
+            (
+                d.range.start,
+                // Diagnostics without a severity go after those with one
+                d.severity.is_none(),
+                d.severity,
+                code_string(d),
+                d.range.end,
+            )
+        };
+        key(a).cmp(&key(b)).then_with(|| a.message.cmp(&b.message))
+    });
+
+    let mut seen = HashSet::with_capacity(diagnostics.len());
+    diagnostics.retain(|diagnostic| seen.insert(identity(diagnostic)));
+    for diagnostic in &mut diagnostics {
+        stamp_id(diagnostic);
+    }
+
+    diagnostics
+}
+
+/// Keep at most `max` of the `diagnostics` from [`finalize_diagnostics`],
+/// with a note on top counting the others
+///
+/// A pasted dump checked against the wrong dialect can yield thousands of
+/// diagnostics, which some clients freeze on. Errors are kept first, then
+/// warnings and so on, earlier positions first among the same severity. The
+/// kept diagnostics stay in published order, so the result only depends on
+/// the finalized list.
+pub fn cap_diagnostics(diagnostics: Vec<Diagnostic>, max: usize) -> Vec<Diagnostic> {
+    if diagnostics.len() <= max {
+        return diagnostics;
+    }
+
+    let mut ranked: Vec<usize> = (0..diagnostics.len()).collect();
+    ranked.sort_by_key(|&i| {
+        let severity = diagnostics[i].severity;
+        (severity.is_none(), severity, i)
+    });
+    let mut kept = vec![false; diagnostics.len()];
+    for &i in &ranked[..max] {
+        kept[i] = true;
+    }
+
+    let hidden = diagnostics.len() - max;
+    let mut summary = SqlDiagnostic::information(
+        format!(
+            "{} additional problem{} not shown",
+            hidden,
+            if hidden == 1 { "" } else { "s" }
+        ),
+        Range::default(),
+    )
+    .to_lsp();
+    stamp_id(&mut summary);
+
+    std::iter::once(summary)
+        .chain(
+            diagnostics
+                .into_iter()
+                .zip(kept)
+                .filter_map(|(diagnostic, kept)| kept.then_some(diagnostic)),
+        )
+        .collect()
+}
+
+/// Merge the stable identifier of `diagnostic` into its `data` object
+fn stamp_id(diagnostic: &mut Diagnostic) {
+    let id = serde_json::Value::String(stable_id(diagnostic));
+    match &mut diagnostic.data {
+        Some(serde_json::Value::Object(data)) => {
+            data.insert(DIAGNOSTIC_ID_KEY.to_string(), id);
+        }
+        // Non-object data is left as is
+        Some(_) => {}
+        None => diagnostic.data = Some(serde_json::json!({ DIAGNOSTIC_ID_KEY: id })),
+    }
+}
+
+/// Stable identifier stamped by [`finalize_diagnostics`]
+///
+/// Code actions receive the diagnostics back from the client with their
+/// `data`, and can use this to find the diagnostic they act on.
+pub fn diagnostic_id(diagnostic: &Diagnostic) -> Option<&str> {
+    diagnostic.data.as_ref()?.get(DIAGNOSTIC_ID_KEY)?.as_str()
+}
+
+fn code_string(diagnostic: &Diagnostic) -> Option<String> {
+    match diagnostic.code.as_ref()? {
+        NumberOrString::String(code) => Some(code.clone()),
+        NumberOrString::Number(code) => Some(code.to_string()),
+    }
+}
+
+/// Code, range and message of a diagnostic, which identify it
+fn identity(diagnostic: &Diagnostic) -> String {
+    let range = diagnostic.range;
+    format!(
+        "{}|{}:{}-{}:{}|{}",
+        code_string(diagnostic).unwrap_or_default(),
+        range.start.line,
+        range.start.character,
+        range.end.line,
+        range.end.character,
+        diagnostic.message
+    )
+}
+
+/// FNV-1a hash of the [`identity`] of a diagnostic, as 16 hex digits
+///
+/// Unlike `std`'s hasher the result doesn't depend on the Rust release, so
+/// identifiers stay the same across server builds.
+fn stable_id(diagnostic: &Diagnostic) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in identity(diagnostic).bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Apply configured rule levels to diagnostics
+///
+/// Diagnostics whose code is configured `off` are dropped; other configured
+/// codes have their severity replaced. Diagnostics without a configured code
+/// pass through unchanged.
+pub fn apply_rules(diagnostics: Vec<Diagnostic>, config: &DiagnosticsConfig) -> Vec<Diagnostic> {
+    if config.rules.is_empty() {
+        return diagnostics;
+    }
+
+    diagnostics
+        .into_iter()
+        .filter_map(|mut diagnostic| {
+            let level = match &diagnostic.code {
+                Some(NumberOrString::String(code)) => config.level(code),
+                Some(NumberOrString::Number(code)) => config.level(&code.to_string()),
+                None => None,
+            };
+
+            let severity = match level {
+                None => return Some(diagnostic),
+                Some(RuleLevel::Off) => return None,
+                Some(RuleLevel::Error) => DiagnosticSeverity::ERROR,
+                Some(RuleLevel::Warning) => DiagnosticSeverity::WARNING,
+                Some(RuleLevel::Info) => DiagnosticSeverity::INFORMATION,
+                Some(RuleLevel::Hint) => DiagnosticSeverity::HINT,
+            };
+            diagnostic.severity = Some(severity);
+            Some(diagnostic)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::Position;
+    use unified_sql_lsp_lowering::cst::MockCstNode;
+
+    fn create_test_range(start_line: u32, start_col: u32, end_line: u32, end_col: u32) -> Range {
+        Range {
+            start: Position {
+                line: start_line,
+                character: start_col,
+            },
+            end: Position {
+                line: end_line,
+                character: end_col,
+            },
+        }
+    }
+
+    #[test]
+    fn test_sql_diagnostic_new() {
+        let range = create_test_range(0, 0, 0, 10);
+        let diagnostic =
+            SqlDiagnostic::new("Test message".to_string(), DiagnosticSeverity::ERROR, range);
+
+        assert_eq!(diagnostic.message, "Test message");
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::ERROR);
+        assert_eq!(diagnostic.source, "unified-sql-lsp");
+        assert!(diagnostic.code.is_none());
+        assert!(diagnostic.related_information.is_none());
+    }
+
+    #[test]
+    fn test_sql_diagnostic_with_code() {
+        let range = create_test_range(0, 0, 0, 10);
+        let diagnostic =
+            SqlDiagnostic::error("Error".to_string(), range).with_code(DiagnosticCode::SyntaxError);
+
+        assert_eq!(diagnostic.code, Some(DiagnosticCode::SyntaxError));
+    }
+
+    #[test]
+    fn test_sql_diagnostic_error() {
+        let range = create_test_range(1, 5, 1, 10);
+        let diagnostic = SqlDiagnostic::error("Syntax error".to_string(), range);
+
+        assert_eq!(diagnostic.message, "Syntax error");
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::ERROR);
+    }
+
+    #[test]
+    fn test_sql_diagnostic_warning() {
+        let range = create_test_range(0, 0, 0, 5);
+        let diagnostic = SqlDiagnostic::warning("Warning".to_string(), range);
+
+        assert_eq!(diagnostic.message, "Warning");
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::WARNING);
+    }
+
+    #[test]
+    fn test_sql_diagnostic_to_lsp() {
+        let range = create_test_range(0, 0, 1, 5);
+        let sql_diagnostic = SqlDiagnostic::error("Error".to_string(), range)
+            .with_code(DiagnosticCode::UndefinedTable);
+
+        let lsp_diagnostic = sql_diagnostic.to_lsp();
+
+        assert_eq!(lsp_diagnostic.message, "Error");
+        assert_eq!(lsp_diagnostic.range, range);
+        assert_eq!(lsp_diagnostic.severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(lsp_diagnostic.source, Some("unified-sql-lsp".to_string()));
+    }
+
+    #[test]
+    fn test_unknown_column_diagnostic_carries_replacement() {
+        let range = create_test_range(0, 7, 0, 14);
+        let unknown = UnknownColumn {
+            reference: "u.emial".to_string(),
+            suggestion: Some("u.email".to_string()),
+            case_mismatch: false,
+        };
+
+        let lsp_diagnostic = SqlDiagnostic::unknown_column(&unknown, range).to_lsp();
+
+        assert_eq!(
+            lsp_diagnostic.message,
+            "unknown column 'u.emial'; did you mean 'u.email'?"
+        );
+        assert_eq!(
+            lsp_diagnostic.code,
+            Some(NumberOrString::String("usql/unknown-column".to_string()))
+        );
+        assert_eq!(
+            lsp_diagnostic.data,
+            Some(serde_json::json!({ "replacement": "u.email" }))
+        );
+    }
+
+    #[test]
+    fn test_unknown_columns_in_dml_where() {
+        use unified_sql_lsp_catalog::DataType;
+        use unified_sql_lsp_semantic::ColumnSymbol;
+
+        let table = |name: &str, columns: &[&str]| {
+            TableSymbol::new(name).with_columns(
+                columns
+                    .iter()
+                    .map(|c| ColumnSymbol::new(*c, DataType::Integer, name))
+                    .collect(),
+            )
+        };
+        let collector = DiagnosticCollector::new();
+        let source =
+            "SELECT total FROM orders;\nDELETE FROM users u WHERE u.emial = 1 AND total > 2";
+
+        let statements = DiagnosticCollector::dml_statements(source);
+        assert_eq!(statements.len(), 1);
+        let (statement, tables) = statements.into_iter().next().unwrap();
+        assert_eq!(tables[0].alias.as_deref(), Some("u"));
+
+        let users = table("users", &["id", "email"]).with_alias("u");
+        let diagnostics =
+            collector.unknown_columns(source, statement, vec![users], Dialect::PostgreSQL);
+        let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        // `total` belongs to the other statement's table
+        assert_eq!(
+            messages,
+            vec![
+                "unknown column 'u.emial'; did you mean 'u.email'?",
+                "unknown column 'total'"
+            ]
+        );
+        assert_eq!(diagnostics[0].range, create_test_range(1, 26, 1, 33));
+
+        // USING tables are in scope too
+        let source = "DELETE FROM users USING orders o WHERE users.id = o.user_id";
+        let (statement, _) = DiagnosticCollector::dml_statements(source).remove(0);
+        let tables = vec![
+            table("users", &["id"]),
+            table("orders", &["user_id"]).with_alias("o"),
+        ];
+        assert!(
+            collector
+                .unknown_columns(source, statement, tables, Dialect::PostgreSQL)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_unknown_columns_in_aliased_update_returning() {
+        use unified_sql_lsp_catalog::DataType;
+        use unified_sql_lsp_semantic::ColumnSymbol;
+
+        let collector = DiagnosticCollector::new();
+        let source = "UPDATE users AS u SET name = 'x' WHERE u.id = 1 \
+                      RETURNING u.id, u.emial AS mail, name, old.name";
+        let (statement, tables) = DiagnosticCollector::dml_statements(source).remove(0);
+        assert_eq!(tables[0].alias.as_deref(), Some("u"));
+
+        let users = TableSymbol::new("users").with_alias("u").with_columns(vec![
+            ColumnSymbol::new("id", DataType::Integer, "users"),
+            ColumnSymbol::new("name", DataType::Text, "users"),
+            ColumnSymbol::new("email", DataType::Text, "users"),
+        ]);
+        let messages: Vec<_> = collector
+            .unknown_columns(
+                source,
+                statement.clone(),
+                vec![users.clone()],
+                Dialect::PostgreSQL,
+            )
+            .into_iter()
+            .map(|d| d.message)
+            .collect();
+        assert_eq!(
+            messages,
+            vec!["unknown column 'u.emial'; did you mean 'u.email'?"]
+        );
+
+        // MySQL has no RETURNING scope; the dialect hint reports the clause
+        assert!(
+            collector
+                .unknown_columns(source, statement, vec![users], Dialect::MySQL)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_unknown_quoted_columns_in_dml_order_by() {
+        use unified_sql_lsp_catalog::DataType;
+        use unified_sql_lsp_semantic::ColumnSymbol;
+
+        let collector = DiagnosticCollector::new();
+        let unknown = |source: &str, dialect| -> Vec<String> {
+            let (statement, _) = DiagnosticCollector::dml_statements(source).remove(0);
+            let users = TableSymbol::new("users").with_columns(vec![
+                ColumnSymbol::new("name", DataType::Text, "users"),
+                ColumnSymbol::new("Title", DataType::Text, "users"),
+            ]);
+            collector
+                .unknown_columns(source, statement, vec![users], dialect)
+                .into_iter()
+                .map(|d| d.message)
+                .collect()
+        };
+
+        // Bare names match regardless of case on both dialects
+        let bare = "DELETE FROM users WHERE NAME = 'x' ORDER BY Name, title LIMIT 1";
+        assert!(unknown(bare, Dialect::PostgreSQL).is_empty());
+        assert!(unknown(bare, Dialect::MySQL).is_empty());
+
+        // PostgreSQL quoted names only match their exact spelling
+        assert_eq!(
+            unknown(
+                "DELETE FROM users WHERE \"name\" = 'x' ORDER BY \"Name\", \"Title\"",
+                Dialect::PostgreSQL
+            ),
+            vec![
+                "unknown column '\"Name\"'; did you mean '\"name\"'? (quoted identifiers \
+                 are case-sensitive; unquoted ones are folded to lowercase)"
+            ]
+        );
+        // A near-miss that isn't only a case difference gets no case-folding note
+        assert_eq!(
+            unknown(
+                "UPDATE users SET name = 'x' ORDER BY \"nme\"",
+                Dialect::PostgreSQL
+            ),
+            vec!["unknown column '\"nme\"'; did you mean '\"name\"'?"]
+        );
+
+        // MySQL column names ignore case even when quoted
+        assert!(
+            unknown(
+                "DELETE FROM users WHERE `NAME` = 'x' ORDER BY `Name`, `title` LIMIT 1",
+                Dialect::MySQL
+            )
+            .is_empty()
+        );
+    }
+
+    /// Mock node of `kind` spanning the first `text` of `source` at or after `from`
+    fn mock_node(kind: &str, source: &str, text: &str, from: usize) -> MockCstNode {
+        let start = from + source[from..].find(text).unwrap();
+        MockCstNode::new(kind)
+            .with_range(start, start + text.len())
+            .with_text(text)
+    }
+
+    /// Mock `binary_expression` of `left`, the first `operator` after it and `right`
+    fn mock_binary(
+        source: &str,
+        left: MockCstNode,
+        operator: &str,
+        right: MockCstNode,
+    ) -> MockCstNode {
+        let operator = mock_node(operator, source, operator, left.end_byte);
+        let (start, end) = (left.start_byte, right.end_byte);
+        MockCstNode::new("binary_expression")
+            .with_range(start, end)
+            .with_text(&source[start..end])
+            .with_child(Some("left"), left)
+            .with_child(Some("operator"), operator)
+            .with_child(Some("right"), right)
+    }
+
+    /// Mock comparison of the first `column` of `source` through `operator` to `NULL`
+    fn mock_null_comparison(source: &str, column: &str, operator: &str) -> MockCstNode {
+        let column = mock_node("column_reference", source, column, 0);
+        let null = mock_node("literal", source, "NULL", column.end_byte);
+        mock_binary(source, column, operator, null)
+    }
+
+    #[test]
+    fn test_null_comparisons() {
+        use unified_sql_lsp_catalog::DataType;
+        use unified_sql_lsp_semantic::ColumnSymbol;
+
+        let collector = DiagnosticCollector::new();
+        let check = |source: &str,
+                     condition: MockCstNode|
+         -> Vec<(String, DiagnosticSeverity, Option<String>)> {
+            let (_, tables) = DiagnosticCollector::condition_statements(source).remove(0);
+            let tables = tables
+                .into_iter()
+                .map(|table| {
+                    let name = table.table_name.clone();
+                    table.with_columns(vec![
+                        ColumnSymbol::new("id", DataType::Integer, &name).with_not_null(true),
+                        ColumnSymbol::new("deleted_at", DataType::Timestamp, &name),
+                    ])
+                })
+                .collect();
+            collector
+                .condition_null_comparisons(source, 0, &[condition], tables, Dialect::PostgreSQL)
+                .into_iter()
+                .map(|d| {
+                    let replacement = d
+                        .data
+                        .as_ref()
+                        .and_then(|data| data["replacement"].as_str())
+                        .map(String::from);
+                    (d.message, d.severity, replacement)
+                })
+                .collect()
+        };
+
+        let source = "SELECT * FROM users u WHERE u.id IS NULL OR deleted_at IS NOT NULL";
+        let condition = mock_binary(
+            source,
+            mock_null_comparison(source, "u.id", "IS"),
+            "OR",
+            mock_null_comparison(source, "deleted_at", "IS NOT"),
+        );
+        assert_eq!(
+            check(source, condition),
+            vec![(
+                "'u.id' is declared NOT NULL, so 'IS NULL' is always false".to_string(),
+                DiagnosticSeverity::WARNING,
+                None
+            )]
+        );
+
+        let source = "UPDATE users SET deleted_at = NULL WHERE deleted_at <> NULL";
+        let where_at = source.find("WHERE").unwrap();
+        let column = mock_node("column_reference", source, "deleted_at", where_at);
+        let null = mock_node("literal", source, "NULL", column.end_byte);
+        assert_eq!(
+            check(source, mock_binary(source, column, "<>", null)),
+            vec![(
+                "comparison '<> NULL' is never true; use 'IS NOT NULL'".to_string(),
+                DiagnosticSeverity::ERROR,
+                Some("IS NOT NULL".to_string())
+            )]
+        );
+
+        // Parameters and expressions have unknown nullability
+        let source = "DELETE FROM users WHERE $1 IS NULL OR coalesce(id) IS NULL";
+        let call = mock_node("function_call", source, "coalesce(id)", 0)
+            .with_child(None, mock_node("function_name", source, "coalesce", 0))
+            .with_child(None, mock_node("column_reference", source, "id", 0));
+        let null = mock_node("literal", source, "NULL", call.end_byte);
+        let condition = mock_binary(
+            source,
+            mock_null_comparison(source, "$1", "IS"),
+            "OR",
+            mock_binary(source, call, "IS", null),
+        );
+        assert!(check(source, condition).is_empty());
+
+        // Without tables only `= NULL` is reported
+        let source = "SELECT * FROM users WHERE id IS NULL AND id = NULL";
+        let is_null = mock_null_comparison(source, "id", "IS");
+        let column = mock_node("column_reference", source, "id", is_null.end_byte);
+        let null = mock_node("literal", source, "NULL", column.end_byte);
+        let condition = mock_binary(
+            source,
+            is_null,
+            "AND",
+            mock_binary(source, column, "=", null),
+        );
+        let diagnostics = collector.condition_null_comparisons(
+            source,
+            0,
+            &[condition],
+            Vec::new(),
+            Dialect::MySQL,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, Some(DiagnosticCode::EqualsNull));
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(0, 44), Position::new(0, 50))
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_code_as_str() {
+        assert_eq!(DiagnosticCode::SyntaxError.as_str(), "usql/syntax");
+        assert_eq!(
+            DiagnosticCode::UndefinedTable.as_str(),
+            "usql/unknown-table"
+        );
+        assert_eq!(
+            DiagnosticCode::UndefinedColumn.as_str(),
+            "usql/unknown-column"
+        );
+        assert_eq!(
+            DiagnosticCode::AmbiguousColumn.as_str(),
+            "usql/ambiguous-column"
+        );
+        assert_eq!(
+            DiagnosticCode::ReadOnlyWrite.as_str(),
+            "usql/read-only-write"
+        );
+        assert_eq!(
+            DiagnosticCode::LimitInSubquery.as_str(),
+            "usql/limit-in-subquery"
+        );
+        assert_eq!(
+            DiagnosticCode::Custom("CUSTOM-123".to_string()).as_str(),
+            "CUSTOM-123"
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_code_description() {
+        assert_eq!(
+            DiagnosticCode::SyntaxError.description(),
+            "SQL syntax error"
+        );
+        assert_eq!(
+            DiagnosticCode::UndefinedTable.description(),
+            "Undefined table reference"
+        );
+        assert_eq!(
+            DiagnosticCode::UndefinedColumn.description(),
+            "Undefined column reference"
+        );
+        assert_eq!(
+            DiagnosticCode::AmbiguousColumn.description(),
+            "Ambiguous column reference"
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_code_to_number_or_string() {
+        let code: NumberOrString = DiagnosticCode::SyntaxError.into();
+        assert!(matches!(code, NumberOrString::String(s) if s == "usql/syntax"));
+
+        let custom: NumberOrString = DiagnosticCode::Custom("TEST-001".to_string()).into();
+        assert!(matches!(custom, NumberOrString::String(s) if s == "TEST-001"));
+    }
+
+    #[test]
+    fn test_apply_rules_drops_and_downgrades() {
+        let range = Range {
+            start: Position::new(0, 0),
+            end: Position::new(0, 5),
+        };
+        let diagnostics = vec![
+            SqlDiagnostic::error("syntax".to_string(), range)
+                .with_code(DiagnosticCode::SyntaxError)
+                .to_lsp(),
+            SqlDiagnostic::error("table".to_string(), range)
+                .with_code(DiagnosticCode::UndefinedTable)
+                .to_lsp(),
+            SqlDiagnostic::error("column".to_string(), range)
+                .with_code(DiagnosticCode::UndefinedColumn)
+                .to_lsp(),
+        ];
+
+        let config = DiagnosticsConfig::from_lsp_settings(&serde_json::json!({
+            "rules": {
+                "usql/unknown-table": "off",
+                "usql/unknown-column": "warning"
+            }
+        }));
+        let published = apply_rules(diagnostics, &config);
+
+        let summary: Vec<_> = published
+            .iter()
+            .map(|d| (d.message.as_str(), d.severity))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("syntax", Some(DiagnosticSeverity::ERROR)),
+                ("column", Some(DiagnosticSeverity::WARNING)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_finalize_diagnostics_sorts_dedupes_and_stamps_ids() {
+        let unknown_column = |line: u32, message: &str| {
+            SqlDiagnostic::warning(message.to_string(), create_test_range(line, 7, line, 10))
+                .with_code(DiagnosticCode::UndefinedColumn)
+                .to_lsp()
+        };
+        let syntax = SqlDiagnostic::error("syntax".to_string(), create_test_range(1, 7, 1, 12))
+            .with_code(DiagnosticCode::SyntaxError)
+            .to_lsp();
+
+        // Two passes that overlap on the unknown column of line 1
+        let first_pass = vec![unknown_column(3, "c"), unknown_column(1, "b")];
+        let second_pass = vec![unknown_column(1, "b"), syntax, unknown_column(0, "a")];
+
+        let published = finalize_diagnostics([first_pass.clone(), second_pass.clone()].concat());
+        let summary: Vec<_> = published
+            .iter()
+            .map(|d| (d.range.start.line, d.severity, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, Some(DiagnosticSeverity::WARNING), "a"),
+                (1, Some(DiagnosticSeverity::ERROR), "syntax"),
+                (1, Some(DiagnosticSeverity::WARNING), "b"),
+                (3, Some(DiagnosticSeverity::WARNING), "c"),
+            ]
+        );
+
+        let ids: Vec<_> = published
+            .iter()
+            .map(|d| diagnostic_id(d).unwrap())
+            .collect();
+        assert_eq!(ids.len(), 4);
+        assert!(ids.iter().all(|id| id.len() == 16));
+        assert!(ids.iter().enumerate().all(|(i, id)| !ids[..i].contains(id)));
+
+        // Passes arriving in the other order publish the same list
+        assert_eq!(
+            finalize_diagnostics([second_pass, first_pass].concat()),
+            published
+        );
+    }
+
+    #[test]
+    fn test_finalize_diagnostics_keeps_one_per_identity() {
+        let range = create_test_range(0, 0, 0, 5);
+        let as_warning = SqlDiagnostic::warning("bad".to_string(), range)
+            .with_code(DiagnosticCode::SyntaxError)
+            .to_lsp();
+        let as_error = SqlDiagnostic::error("bad".to_string(), range)
+            .with_code(DiagnosticCode::SyntaxError)
+            .to_lsp();
+
+        let published = finalize_diagnostics(vec![as_warning.clone(), as_error, as_warning]);
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].severity, Some(DiagnosticSeverity::ERROR));
+    }
+
+    #[test]
+    fn test_finalize_diagnostics_keeps_existing_data() {
+        let diagnostic = Diagnostic {
+            range: create_test_range(0, 0, 0, 4),
+            message: "Unknown column 'nme'".to_string(),
+            data: Some(serde_json::json!({ "replacement": "name" })),
+            ..Default::default()
+        };
+
+        let published = finalize_diagnostics(vec![diagnostic]);
+        let data = published[0].data.as_ref().unwrap();
+        assert_eq!(data["replacement"], "name");
+        assert_eq!(diagnostic_id(&published[0]), data["id"].as_str());
+        assert!(diagnostic_id(&published[0]).is_some());
+    }
+
+    /// `count` diagnostics of a pass reporting every line, with an error on
+    /// every `error_every`th line and warnings on the others
+    fn mock_pass(count: u32, error_every: u32) -> Vec<Diagnostic> {
+        (0..count)
+            .map(|line| {
+                let range = create_test_range(line, 0, line, 4);
+                let diagnostic = if line % error_every == 0 {
+                    SqlDiagnostic::error(format!("error {}", line), range)
+                } else {
+                    SqlDiagnostic::warning(format!("warning {}", line), range)
+                };
+                diagnostic.to_lsp()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cap_diagnostics_keeps_errors_then_earliest() {
+        // Lines 0, 10, ..., 590 have errors
+        let finalized = finalize_diagnostics(mock_pass(600, 10));
+        let published = cap_diagnostics(finalized.clone(), 100);
+
+        assert_eq!(published.len(), 101);
+        let summary = &published[0];
+        assert_eq!(summary.message, "500 additional problems not shown");
+        assert_eq!(summary.severity, Some(DiagnosticSeverity::INFORMATION));
+        assert_eq!(summary.range, Range::default());
+        assert!(diagnostic_id(summary).is_some());
+
+        // All 60 errors, then the warnings of the first lines
+        let kept = &published[1..];
+        let errors = kept
+            .iter()
+            .filter(|d| d.severity == Some(DiagnosticSeverity::ERROR))
+            .count();
+        assert_eq!(errors, 60);
+        let last_warning = kept
+            .iter()
+            .filter(|d| d.severity == Some(DiagnosticSeverity::WARNING))
+            .map(|d| d.range.start.line)
+            .max();
+        assert_eq!(last_warning, Some(44));
+
+        // Still in published order, and the same for the same input
+        assert!(kept.windows(2).all(|w| w[0].range.start < w[1].range.start));
+        assert_eq!(cap_diagnostics(finalized, 100), published);
+    }
+
+    #[test]
+    fn test_cap_diagnostics_under_the_cap() {
+        let finalized = finalize_diagnostics(mock_pass(20, 3));
+        assert_eq!(cap_diagnostics(finalized.clone(), 20), finalized);
+
+        let published = cap_diagnostics(finalized, 19);
+        assert_eq!(published.len(), 20);
+        assert_eq!(published[0].message, "1 additional problem not shown");
+    }
+
+    #[test]
+    fn test_diagnostics_config_max_per_document() {
+        let config = DiagnosticsConfig::from_lsp_settings(&serde_json::json!({}));
+        assert_eq!(
+            config.max_per_document,
+            DiagnosticsConfig::DEFAULT_MAX_PER_DOCUMENT
+        );
+        assert!(!config.skips_later_passes(2500));
+        assert!(config.skips_later_passes(2501));
+
+        let config =
+            DiagnosticsConfig::from_lsp_settings(&serde_json::json!({ "maxPerDocument": 10 }));
+        assert_eq!(config.max_per_document, 10);
+        assert!(config.skips_later_passes(51));
+    }
+
+    #[test]
+    fn test_diagnostics_config_reports_unknown_codes() {
+        let config = DiagnosticsConfig::from_lsp_settings(&serde_json::json!({
+            "rules": {
+                "usql/syntax": "hint",
+                "usql/typo": "off",
+                "other/rule": "error",
+                "usql/unknown-table": "loud"
+            }
+        }));
+
+        assert_eq!(config.level("usql/syntax"), Some(RuleLevel::Hint));
+        assert_eq!(config.level("usql/unknown-table"), None);
+        assert_eq!(config.unknown_codes(), vec!["other/rule", "usql/typo"]);
+    }
+
+    #[test]
+    fn test_diagnostics_config_publish_interval() {
+        let config = DiagnosticsConfig::from_lsp_settings(&serde_json::json!({}));
+        assert_eq!(
+            config.publish_interval,
+            DiagnosticsConfig::DEFAULT_PUBLISH_INTERVAL
+        );
+
+        let config =
+            DiagnosticsConfig::from_lsp_settings(&serde_json::json!({ "publishIntervalMs": 0 }));
+        assert_eq!(config.publish_interval, std::time::Duration::ZERO);
+    }
+
+    #[test]
+    fn test_diagnostics_config_pending_migrations() {
+        let config = DiagnosticsConfig::from_lsp_settings(&serde_json::json!({}));
+        assert!(!config.pending_migrations);
+
+        let config =
+            DiagnosticsConfig::from_lsp_settings(&serde_json::json!({ "pendingMigrations": true }));
+        assert!(config.pending_migrations);
+    }
+
+    #[test]
+    fn test_collect_for_empty_untitled_document() {
+        let collector = DiagnosticCollector::new();
+        let document = Document::new(
+            Url::parse("untitled:Untitled-1").unwrap(),
+            "  \n".to_string(),
+            1,
+            "sql".to_string(),
+        );
+        assert!(collector.collect_for_document(&document).is_empty());
+    }
+
+    #[test]
+    fn test_diagnostic_collector_new() {
+        let collector = DiagnosticCollector::new();
+        // Just ensure it creates without panicking
+        let _ = collector;
+    }
+
+    #[test]
+    fn test_diagnostic_collector_collect_diagnostics() {
+        let collector = DiagnosticCollector::new();
+        let uri = Url::parse("file:///test.sql").unwrap();
+
+        // Create a minimal tree (this will fail if no grammar is compiled)
+        // But we can still test the empty case
+        if let Some(language) =
+            unified_sql_grammar::language_for_dialect(unified_sql_lsp_ir::Dialect::MySQL)
+        {
+            let mut parser = tree_sitter::Parser::new();
+            if parser.set_language(language).is_ok()
+                && let Some(tree) = parser.parse("SELECT 1", None)
+            {
+                let diagnostics = collector.collect_diagnostics(&tree, "SELECT 1", &uri);
+                // Currently returns empty (will be implemented in DIAG-002 to DIAG-005)
+                assert!(diagnostics.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_diagnostic_collector_collect_from_arc_none() {
+        let collector = DiagnosticCollector::new();
+        let uri = Url::parse("file:///test.sql").unwrap();
+        let tree: Option<Arc<Mutex<tree_sitter::Tree>>> = None;
+
+        let diagnostics = collector.collect_from_arc(&tree, "SELECT 1", &uri);
+        assert!(diagnostics.is_empty());
+    }
+
+    // Tests for pattern detection helpers
+
+    #[test]
+    fn test_is_identifier() {
+        let collector = DiagnosticCollector::new();
+
+        // Valid identifiers
+        assert!(collector.is_identifier("id"));
+        assert!(collector.is_identifier("username"));
+        assert!(collector.is_identifier("_id"));
+        assert!(collector.is_identifier("`id`"));
+        assert!(collector.is_identifier("\"id\""));
+
+        // Invalid identifiers
+        assert!(!collector.is_identifier(""));
+        assert!(!collector.is_identifier("123"));
+        assert!(!collector.is_identifier("id username"));
+        assert!(!collector.is_identifier("id,"));
+    }
+
+    #[test]
+    fn test_is_missing_comma_pattern() {
+        let collector = DiagnosticCollector::new();
+
+        // Missing comma patterns
+        assert!(collector.is_missing_comma_pattern("id username"));
+        assert!(collector.is_missing_comma_pattern("col1 col2 col3"));
+        assert!(collector.is_missing_comma_pattern("username FROM"));
 
         // Not missing comma
         assert!(!collector.is_missing_comma_pattern("id, username"));
@@ -682,4 +2489,473 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_limit_in_subqueries_on_mysql() {
+        let collector = DiagnosticCollector::new();
+        let source = "SELECT * FROM users;\n\
+                      SELECT * FROM users WHERE id NOT IN (SELECT user_id FROM bans LIMIT 5)";
+        let statement = source.find('\n').unwrap() + 1;
+        // `id NOT IN (...)` of the second statement, relative to it
+        let text = &source[statement..];
+        let subquery = text.find('(').unwrap();
+        let limit = MockCstNode::new("limit_clause")
+            .with_child(None, mock_node("literal", text, "5", subquery));
+        let condition = mock_binary(
+            text,
+            mock_node("column_reference", text, "id", subquery - 10),
+            "NOT IN",
+            mock_node("subquery", text, &text[subquery..], subquery).with_child(
+                None,
+                MockCstNode::new("select_statement").with_child(Some("limit_clause"), limit),
+            ),
+        );
+        let conditions = [condition];
+
+        let diagnostics = collector.condition_limits_in_subqueries(
+            source,
+            statement,
+            &conditions,
+            Dialect::MySQL,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::ERROR);
+        assert_eq!(diagnostics[0].code, Some(DiagnosticCode::LimitInSubquery));
+        assert_eq!(diagnostics[0].range, create_test_range(1, 36, 1, 70));
+        // Rewriting it as a join could change the rows returned
+        assert_eq!(diagnostics[0].data, None);
+
+        assert!(
+            collector
+                .condition_limits_in_subqueries(source, statement, &conditions, Dialect::PostgreSQL)
+                .is_empty()
+        );
+
+        // A subquery without LIMIT
+        let text = "SELECT * FROM users WHERE id IN (SELECT user_id FROM orders) LIMIT 5";
+        let subquery = text.find('(').unwrap();
+        let condition = mock_binary(
+            text,
+            mock_node("column_reference", text, "id", 0),
+            "IN",
+            mock_node("subquery", text, "(SELECT user_id FROM orders)", subquery)
+                .with_child(None, MockCstNode::new("select_statement")),
+        );
+        assert!(
+            collector
+                .condition_limits_in_subqueries(text, 0, &[condition], Dialect::MySQL)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_write_statements() {
+        let collector = DiagnosticCollector::new();
+        let keywords = |sql: &str| -> Vec<String> {
+            collector
+                .write_statements(sql)
+                .into_iter()
+                .map(|d| d.message)
+                .collect()
+        };
+
+        for (sql, keyword) in [
+            ("INSERT INTO users (id) VALUES (1)", "INSERT"),
+            ("update users set name = 'x'", "UPDATE"),
+            ("DELETE FROM users WHERE id = 1", "DELETE"),
+            ("TRUNCATE TABLE users", "TRUNCATE"),
+            ("ALTER TABLE users ADD COLUMN age INT", "ALTER"),
+            ("DROP TABLE users", "DROP"),
+            (
+                "WITH old AS (SELECT id FROM users) DELETE FROM users WHERE id IN (SELECT id FROM old)",
+                "DELETE",
+            ),
+            (
+                "SELECT * FROM users WHERE id = 1 FOR UPDATE",
+                "SELECT ... FOR UPDATE",
+            ),
+            (
+                "SELECT * FROM users FOR NO KEY UPDATE",
+                "SELECT ... FOR UPDATE",
+            ),
+        ] {
+            assert_eq!(
+                keywords(sql),
+                vec![format!(
+                    "write statement in read-only workspace: {}",
+                    keyword
+                )],
+                "{}",
+                sql
+            );
+        }
+
+        for sql in [
+            "SELECT * FROM users",
+            "WITH ids AS (SELECT id FROM users) SELECT * FROM ids",
+            "SELECT 'DELETE FROM users' -- DROP TABLE users",
+            "SELECT * FROM users FOR SHARE",
+            "SELECT * FROM (SELECT * FROM users FOR UPDATE) locked",
+            "CREATE TABLE audit (id INT)",
+        ] {
+            assert!(keywords(sql).is_empty(), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_write_statements_span_the_statement() {
+        let collector = DiagnosticCollector::new();
+        let sql = "SELECT 'a;b' FROM users;
+  DELETE FROM users
+  WHERE id = 1 ;
+SELECT 1";
+
+        let diagnostics = collector.write_statements(sql);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::ERROR);
+        assert_eq!(diagnostics[0].code, Some(DiagnosticCode::ReadOnlyWrite));
+        assert_eq!(diagnostics[0].range, create_test_range(1, 2, 2, 14));
+    }
+
+    #[test]
+    fn test_dialect_hints_flag_distinct_on_for_mysql() {
+        let collector = DiagnosticCollector::new();
+        let sql = "SELECT id FROM users;\nselect distinct  on (email) id FROM users";
+
+        let hints = collector.dialect_hints(sql, Dialect::MySQL);
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].severity, DiagnosticSeverity::WARNING);
+        assert_eq!(hints[0].code, Some(DiagnosticCode::DialectFeature));
+        assert_eq!(hints[0].range, create_test_range(1, 7, 1, 19));
+
+        assert!(collector.dialect_hints(sql, Dialect::PostgreSQL).is_empty());
+    }
+
+    #[test]
+    fn test_dialect_hints_skip_strings_comments_and_identifiers() {
+        let collector = DiagnosticCollector::new();
+        let sql = "-- DISTINCT ON (a)\n\
+                   SELECT 'DISTINCT ON (a)', /* distinct on */ DISTINCT ONE.id, distinct_on FROM one";
+
+        assert!(collector.dialect_hints(sql, Dialect::MySQL).is_empty());
+    }
+
+    #[test]
+    fn test_dialect_hints_flag_full_join_for_mysql() {
+        let collector = DiagnosticCollector::new();
+        let sql = "SELECT * FROM a\nFULL OUTER JOIN b ON a.id = b.id\nfull join c ON a.id = c.id";
+
+        let hints = collector.dialect_hints(sql, Dialect::MySQL);
+        assert_eq!(hints.len(), 2);
+        assert_eq!(hints[0].code, Some(DiagnosticCode::DialectFeature));
+        assert_eq!(hints[0].range, create_test_range(1, 0, 1, 15));
+        assert_eq!(hints[1].range, create_test_range(2, 0, 2, 9));
+        assert!(hints[0].message.contains("LEFT JOIN UNION a RIGHT JOIN"));
+
+        assert!(collector.dialect_hints(sql, Dialect::PostgreSQL).is_empty());
+
+        // CROSS JOIN and a column named `full` are fine everywhere
+        let sql = "SELECT full, 'full join' FROM a CROSS JOIN b -- full outer join";
+        assert!(collector.dialect_hints(sql, Dialect::MySQL).is_empty());
+    }
+
+    #[test]
+    fn test_dialect_hints_flag_auto_increment_for_postgres() {
+        let collector = DiagnosticCollector::new();
+        let sql = "CREATE TABLE t (\n  id INT auto_increment PRIMARY KEY,\n  note TEXT -- AUTO_INCREMENT\n)";
+
+        let hints = collector.dialect_hints(sql, Dialect::PostgreSQL);
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].code, Some(DiagnosticCode::DialectFeature));
+        assert_eq!(hints[0].range, create_test_range(1, 9, 1, 23));
+        assert_eq!(
+            hints[0].data,
+            Some(serde_json::json!({ "replacement": "GENERATED BY DEFAULT AS IDENTITY" }))
+        );
+
+        assert!(collector.dialect_hints(sql, Dialect::MySQL).is_empty());
+    }
+
+    #[test]
+    fn test_dialect_hints_flag_returning_for_mysql() {
+        let collector = DiagnosticCollector::new();
+        let sql = "UPDATE users u SET name = 'x' WHERE u.id = 1\nreturning u.name";
+
+        let hints = collector.dialect_hints(sql, Dialect::MySQL);
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].code, Some(DiagnosticCode::DialectFeature));
+        assert_eq!(hints[0].range, create_test_range(1, 0, 1, 9));
+
+        assert!(collector.dialect_hints(sql, Dialect::PostgreSQL).is_empty());
+    }
+
+    fn unknown_tables(sql: &str) -> Vec<SqlDiagnostic> {
+        let uri = Url::parse("file:///test.sql").unwrap();
+        let catalog_tables = vec!["users".to_string(), "orders".to_string()];
+        DiagnosticCollector::new().unknown_tables(&uri, sql, &catalog_tables, true)
+    }
+
+    #[test]
+    fn test_unknown_tables_reports_missing_tables() {
+        let diagnostics =
+            unknown_tables("SELECT * FROM users u, missing m JOIN ordrs o ON u.id = o.user_id");
+
+        let names: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["Unknown table 'missing'", "Unknown table 'ordrs'"]
+        );
+        assert_eq!(diagnostics[0].code, Some(DiagnosticCode::UndefinedTable));
+        assert_eq!(diagnostics[0].range, create_test_range(0, 23, 0, 30));
+    }
+
+    #[test]
+    fn test_unknown_tables_skips_create_table_as_target() {
+        let sql = "CREATE TABLE report AS SELECT id, email FROM users;\n\
+                   SELECT id INTO TEMP recent FROM orders;\n\
+                   SELECT r.id FROM report r JOIN recent USING (id)";
+        assert!(unknown_tables(sql).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_tables_skips_non_table_from() {
+        let sql = "WITH totals AS (SELECT 1) SELECT EXTRACT(YEAR FROM created_at), \
+                   a IS DISTINCT FROM b FROM totals, generate_series(1, 3), app.audit, \
+                   (SELECT 1 FROM dual) t";
+        assert!(unknown_tables(sql).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_tables_cte_self_reference() {
+        let uri = Url::parse("file:///test.sql").unwrap();
+        let catalog_tables = vec!["users".to_string()];
+        let unknown = |sql: &str, recursive_cte: bool| -> Vec<String> {
+            DiagnosticCollector::new()
+                .unknown_tables(&uri, sql, &catalog_tables, recursive_cte)
+                .into_iter()
+                .map(|d| d.message)
+                .collect()
+        };
+
+        let recursive = "WITH RECURSIVE tree AS (SELECT id FROM users \
+                         UNION ALL SELECT u.id FROM users u JOIN tree t ON u.id = t.id) \
+                         SELECT * FROM tree";
+        assert!(unknown(recursive, true).is_empty());
+        assert_eq!(
+            unknown(recursive, false),
+            vec!["CTE 'tree' references itself, but recursive CTEs aren't supported"]
+        );
+
+        let plain = recursive.replace("RECURSIVE ", "");
+        assert_eq!(
+            unknown(&plain, true),
+            vec!["CTE 'tree' references itself without WITH RECURSIVE"]
+        );
+
+        // The catalog table the CTE shadows outside its body
+        let shadowing = "WITH users AS (SELECT * FROM users WHERE active) SELECT * FROM users";
+        assert!(unknown(shadowing, true).is_empty());
+
+        // A CTE joined in the main query
+        let joined = "WITH active AS (SELECT id FROM users) \
+                      SELECT * FROM users u JOIN active a ON a.id = u.id";
+        assert!(unknown(joined, false).is_empty());
+    }
+
+    #[test]
+    fn test_cte_cycles() {
+        let sql = "WITH a AS (SELECT * FROM b), b AS (SELECT * FROM a) SELECT * FROM a";
+        let diagnostics = DiagnosticCollector::new().cte_cycles(sql);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "CTEs reference each other in a cycle: a → b → a"
+        );
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::ERROR);
+        assert_eq!(diagnostics[0].code, Some(DiagnosticCode::CteCycle));
+        // The CTEs are defined by the document, so neither is unknown
+        assert!(unknown_tables(sql).is_empty());
+    }
+
+    /// Mock `node` holding `children`, each under the field of its kind
+    fn mock_parent(node: MockCstNode, children: Vec<MockCstNode>) -> MockCstNode {
+        children.into_iter().fold(node, |parent, child| {
+            let kind = child.kind().to_string();
+            parent.with_child(Some(&kind), child)
+        })
+    }
+
+    /// Mock FROM-list entry `table [alias]` of kind `kind`, found from `from`
+    fn mock_table(
+        kind: &str,
+        source: &str,
+        table: &str,
+        alias: Option<&str>,
+        from: usize,
+    ) -> MockCstNode {
+        let table = mock_node("table_name", source, table, from);
+        let alias = alias.map(|alias| mock_node("alias", source, alias, table.end_byte));
+        mock_parent(
+            MockCstNode::new(kind),
+            [Some(table), alias].into_iter().flatten().collect(),
+        )
+    }
+
+    /// Mock SELECT of `source` defining the CTEs `names`, in order
+    fn mock_with(source: &str, names: &[&str]) -> MockCstNode {
+        let mut from = 0;
+        let mut clause = MockCstNode::new("cte_clause");
+        for name in names {
+            let name = mock_node("table_name", source, name, from);
+            from = name.end_byte;
+            let definition = mock_parent(
+                MockCstNode::new("cte_definition"),
+                vec![name, MockCstNode::new("select_statement")],
+            );
+            clause = clause.with_child(None, definition);
+        }
+        mock_parent(MockCstNode::new("select_statement"), vec![clause])
+    }
+
+    #[test]
+    fn test_duplicate_cte() {
+        let uri = Url::parse("file:///dup.sql").unwrap();
+        let check = |sql: &str, names: &[&str], dialect: Dialect| {
+            DiagnosticCollector::new().query_duplicate_definitions(
+                &uri,
+                sql,
+                0,
+                &mock_with(sql, names),
+                dialect,
+            )
+        };
+
+        let sql = "WITH recent AS (SELECT 1), totals AS (SELECT 2), recent AS (SELECT 3) \
+                   SELECT * FROM totals";
+        let diagnostics = check(sql, &["recent", "totals", "recent"], Dialect::PostgreSQL);
+
+        assert_eq!(diagnostics.len(), 1);
+        let duplicate = &diagnostics[0];
+        assert_eq!(duplicate.message, "Duplicate CTE name: recent");
+        assert_eq!(duplicate.severity, DiagnosticSeverity::ERROR);
+        assert_eq!(duplicate.code, Some(DiagnosticCode::DuplicateName));
+        assert_eq!(duplicate.range.start, Position::new(0, 49));
+        let related = duplicate.related_information.as_ref().unwrap();
+        let first = Range::new(Position::new(0, 5), Position::new(0, 11));
+        assert_eq!(related[0].location, Location::new(uri.clone(), first));
+        assert_eq!(related[0].message, "'recent' first defined here");
+
+        // A quoted name keeps its case in PostgreSQL only
+        let quoted = "WITH recent AS (SELECT 1), \"Recent\" AS (SELECT 2) SELECT 1";
+        assert!(check(quoted, &["recent", "\"Recent\""], Dialect::PostgreSQL).is_empty());
+        let quoted = "WITH recent AS (SELECT 1), `Recent` AS (SELECT 2) SELECT 1";
+        assert_eq!(
+            check(quoted, &["recent", "`Recent`"], Dialect::MySQL).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_duplicate_alias() {
+        let uri = Url::parse("file:///dup.sql").unwrap();
+        let check = |sql: &str, select: MockCstNode| {
+            DiagnosticCollector::new().query_duplicate_definitions(
+                &uri,
+                sql,
+                0,
+                &select,
+                Dialect::MySQL,
+            )
+        };
+
+        let sql = "SELECT * FROM users u JOIN orders U ON U.user_id = u.id \
+                   WHERE u.id IN (SELECT user_id FROM orders u)";
+        let users = mock_table("table_reference", sql, "users", Some("u"), 0);
+        let on = mock_binary(
+            sql,
+            mock_node("column_reference", sql, "U.user_id", 0),
+            "=",
+            mock_node("column_reference", sql, "u.id", 40),
+        );
+        let orders = mock_table("join_clause", sql, "orders", Some("U"), 0).with_child(None, on);
+        let subquery_from = mock_table("table_reference", sql, "orders", Some("u"), 80);
+        let subquery = mock_parent(
+            MockCstNode::new("select_statement"),
+            vec![mock_parent(
+                MockCstNode::new("from_clause"),
+                vec![subquery_from],
+            )],
+        );
+        let (start, end) = (sql.find("(SELECT").unwrap(), sql.len());
+        let subquery = MockCstNode::new("subquery")
+            .with_range(start, end)
+            .with_child(None, subquery);
+        let condition = mock_binary(
+            sql,
+            mock_node("column_reference", sql, "u.id", 56),
+            "IN",
+            subquery,
+        );
+        let select = mock_parent(
+            MockCstNode::new("select_statement"),
+            vec![
+                mock_parent(MockCstNode::new("from_clause"), vec![users, orders]),
+                MockCstNode::new("where_clause").with_child(None, condition),
+            ],
+        );
+        let diagnostics = check(sql, select);
+
+        // The subquery's own FROM list may reuse the alias
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Duplicate table alias: U");
+        assert_eq!(diagnostics[0].range.start, Position::new(0, 34));
+        let related = diagnostics[0].related_information.as_ref().unwrap();
+        assert_eq!(related[0].location.range.start, Position::new(0, 20));
+        assert_eq!(related[0].message, "'u' first defined here");
+
+        // A table joined to itself needs an alias
+        let self_join = |sql: &str, alias: Option<&str>| {
+            let users = mock_table("table_reference", sql, "users", None, 0);
+            let joined = mock_table("join_clause", sql, "users", alias, users.end_byte)
+                .with_child(None, mock_node("literal", sql, "true", 0));
+            let from = mock_parent(MockCstNode::new("from_clause"), vec![users, joined]);
+            check(
+                sql,
+                mock_parent(MockCstNode::new("select_statement"), vec![from]),
+            )
+        };
+        let diagnostics = self_join("SELECT * FROM users JOIN users ON true", None);
+        assert_eq!(diagnostics[0].message, "Duplicate table alias: users");
+        assert!(
+            self_join(
+                "SELECT * FROM users JOIN users manager ON true",
+                Some("manager")
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_nesting_depth_of_generated_query() {
+        let mut sql = "SELECT id FROM users".to_string();
+        for level in 0..300 {
+            sql = format!("SELECT id FROM ({}) t{}", sql, level);
+        }
+        let collector = DiagnosticCollector::new();
+
+        let note = collector.nesting_depth(&sql, 64).expect("cap reported");
+        assert_eq!(note.severity, DiagnosticSeverity::INFORMATION);
+        assert_eq!(note.code, Some(DiagnosticCode::NestingDepth));
+        assert_eq!(
+            note.message,
+            "Subqueries nest deeper than 64 levels; the query isn't analyzed further"
+        );
+        // The 65th subquery from the outside
+        let start = note.range.start.character as usize;
+        assert_eq!(&sql[start..start + 7], "(SELECT");
+        assert_eq!(sql[..start].matches("(SELECT").count(), 64);
+
+        assert!(collector.nesting_depth(&sql, 300).is_none());
+    }
 }
@@ -0,0 +1,327 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Diagnostic range shifting
+//!
+//! Recomputing diagnostics waits for the debounce, so squiggles published for
+//! the previous version sit on the wrong lines right after an edit above them.
+//! [`shift_diagnostics`] moves published ranges through the applied content
+//! changes with text-delta math only, so they can be republished at once and
+//! replaced when the recomputed set arrives:
+//!
+//! - Ranges before an edit stay, ranges after it move with the text
+//! - Ranges an edit overlaps are dropped until the recomputation
+//! - A fix range in `data.range` (see [`crate::code_actions`]) moves too
+//!
+//! Changes of one notification apply in order, each to the text the previous
+//! one left, like [`Document::apply_changes`](crate::document::Document::apply_changes).
+//! Characters are counted the same way.
+
+use ropey::Rope;
+use tower_lsp::lsp_types::{Diagnostic, Position, Range, TextDocumentContentChangeEvent};
+
+/// An incremental change, in the coordinates of the text it applied to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Edit {
+    /// Replaced range
+    range: Range,
+    /// Line breaks in the inserted text
+    lines: u32,
+    /// Characters after the last line break of the inserted text
+    last_line_chars: u32,
+}
+
+impl Edit {
+    /// `None` for a full text replacement, which can't be shifted through
+    fn from_change(change: &TextDocumentContentChangeEvent) -> Option<Self> {
+        let range = change.range?;
+        let text = Rope::from_str(&change.text);
+        let lines = text.len_lines() - 1;
+        Some(Self {
+            range,
+            lines: lines as u32,
+            last_line_chars: text.line(lines).len_chars() as u32,
+        })
+    }
+
+    /// Where a position at or after the end of the replaced range moves to
+    fn shift_position(&self, position: Position) -> Position {
+        let Range { start, end } = self.range;
+        let line = position.line - end.line + start.line + self.lines;
+        if position.line != end.line {
+            return Position::new(line, position.character);
+        }
+        let column = if self.lines == 0 {
+            start.character + self.last_line_chars
+        } else {
+            self.last_line_chars
+        };
+        Position::new(line, column + position.character - end.character)
+    }
+
+    /// `range` after the edit; `None` when the edit overlaps it
+    ///
+    /// An insertion where the range starts pushes it; one where it ends
+    /// leaves it.
+    fn shift_range(&self, range: Range) -> Option<Range> {
+        if self.range.end <= range.start {
+            Some(Range::new(
+                self.shift_position(range.start),
+                self.shift_position(range.end),
+            ))
+        } else if range.end <= self.range.start {
+            Some(range)
+        } else {
+            None
+        }
+    }
+}
+
+/// `diagnostics` moved through `changes`, without those the changes overlap
+///
+/// `None` if a change replaced the whole text, since nothing is known about
+/// where the ranges went.
+pub fn shift_diagnostics(
+    diagnostics: &[Diagnostic],
+    changes: &[TextDocumentContentChangeEvent],
+) -> Option<Vec<Diagnostic>> {
+    let edits = changes
+        .iter()
+        .map(Edit::from_change)
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(
+        diagnostics
+            .iter()
+            .filter_map(|diagnostic| {
+                edits
+                    .iter()
+                    .try_fold(diagnostic.clone(), |diagnostic, edit| {
+                        shift_diagnostic(diagnostic, edit)
+                    })
+            })
+            .collect(),
+    )
+}
+
+fn shift_diagnostic(mut diagnostic: Diagnostic, edit: &Edit) -> Option<Diagnostic> {
+    diagnostic.range = edit.shift_range(diagnostic.range)?;
+    let fix_range = diagnostic
+        .data
+        .as_mut()
+        .and_then(|data| data.get_mut("range"));
+    if let Some(fix_range) = fix_range
+        && let Ok(range) = serde_json::from_value::<Range>(fix_range.clone())
+    {
+        *fix_range = serde_json::to_value(edit.shift_range(range)?).ok()?;
+    }
+    Some(diagnostic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::Document;
+    use serde_json::json;
+    use tower_lsp::lsp_types::Url;
+
+    fn change(range: Range, text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: Some(range),
+            range_length: Some(0),
+            text: text.to_string(),
+        }
+    }
+
+    fn range(start: (u32, u32), end: (u32, u32)) -> Range {
+        Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+    }
+
+    fn diagnostic(range: Range, message: &str) -> Diagnostic {
+        Diagnostic {
+            range,
+            message: message.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_line_inserted_above_moves_diagnostics_down() {
+        let published = vec![diagnostic(range((1, 14), (1, 20)), "Unknown table")];
+        let insert = change(range((0, 0), (0, 0)), "-- note\n");
+
+        let shifted = shift_diagnostics(&published, &[insert]).unwrap();
+        assert_eq!(shifted[0].range, range((2, 14), (2, 20)));
+    }
+
+    #[test]
+    fn test_edits_on_the_diagnostic_line() {
+        let published = vec![diagnostic(range((0, 14), (0, 20)), "Unknown table")];
+        let shift = |edit| shift_diagnostics(&published, &[edit]).unwrap();
+
+        // Before the range on its line: moves right
+        assert_eq!(
+            shift(change(range((0, 0), (0, 0)), "  "))[0].range,
+            range((0, 16), (0, 22))
+        );
+        // Line break before the range: moves to the new line
+        assert_eq!(
+            shift(change(range((0, 13), (0, 13)), "\n  "))[0].range,
+            range((1, 3), (1, 9))
+        );
+        // Right after the range: stays
+        assert_eq!(
+            shift(change(range((0, 20), (0, 20)), " x"))[0].range,
+            range((0, 14), (0, 20))
+        );
+        // Inside the range: dropped until recomputed
+        assert!(shift(change(range((0, 15), (0, 16)), "")).is_empty());
+    }
+
+    #[test]
+    fn test_changes_apply_in_order() {
+        let published = vec![
+            diagnostic(range((2, 0), (2, 4)), "kept"),
+            diagnostic(range((3, 2), (3, 4)), "overlapped"),
+        ];
+        let changes = [
+            // Join lines 0 and 1
+            change(range((0, 5), (1, 0)), " "),
+            // Line 3 is now line 2
+            change(range((2, 3), (2, 3)), "xx"),
+        ];
+
+        let shifted = shift_diagnostics(&published, &changes).unwrap();
+        assert_eq!(shifted.len(), 1);
+        assert_eq!(shifted[0].message, "kept");
+        assert_eq!(shifted[0].range, range((1, 0), (1, 4)));
+    }
+
+    #[test]
+    fn test_fix_range_in_data_moves_too() {
+        let mut fixable = diagnostic(range((1, 7), (1, 9)), "Unknown column");
+        fixable.data = Some(json!({
+            "replacement": "id",
+            "range": range((1, 0), (1, 9)),
+        }));
+        let shifted =
+            shift_diagnostics(&[fixable], &[change(range((0, 0), (0, 0)), "\n")]).unwrap();
+        assert_eq!(
+            shifted[0].data.as_ref().unwrap()["range"],
+            json!(range((2, 0), (2, 9)))
+        );
+
+        // An edit inside the fix range drops the diagnostic
+        let mut fixable = diagnostic(range((1, 7), (1, 9)), "Unknown column");
+        fixable.data = Some(json!({ "replacement": "id", "range": range((1, 0), (1, 9)) }));
+        let edit = change(range((1, 2), (1, 2)), "x");
+        assert!(shift_diagnostics(&[fixable], &[edit]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_full_replacement_cannot_be_shifted() {
+        let published = vec![diagnostic(range((0, 0), (0, 1)), "x")];
+        let full = TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "SELECT 1".to_string(),
+        };
+        assert_eq!(shift_diagnostics(&published, &[full]), None);
+    }
+
+    /// Deterministic pseudo-random numbers for the property test
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, bound: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % bound as u64) as usize
+        }
+
+        fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+            items[self.below(items.len())]
+        }
+    }
+
+    /// Position of the character at `char_index` of `text`
+    fn position(text: &Rope, char_index: usize) -> Position {
+        let line = text.char_to_line(char_index);
+        Position::new(line as u32, (char_index - text.line_to_char(line)) as u32)
+    }
+
+    /// Character span of the only occurrence of `marker` in `document`
+    fn span(document: &Document, marker: &str) -> (usize, usize) {
+        let text = document.get_content();
+        let start = text[..text.find(marker).unwrap()].chars().count();
+        (start, start + marker.chars().count())
+    }
+
+    /// Range of the only occurrence of `marker` in `document`
+    fn find(document: &Document, marker: &str) -> Range {
+        let text = Rope::from_str(&document.get_content());
+        let (start, end) = span(document, marker);
+        Range::new(position(&text, start), position(&text, end))
+    }
+
+    /// Shifting agrees with diagnostics recomputed on the edited text when
+    /// no edit touches a flagged word
+    #[test]
+    fn test_shifted_ranges_match_recomputed_ones() {
+        const MARKERS: [&str; 3] = ["ÉRR_A", "ERR_B", "ERR_C"];
+        let words = ["SELECT", "id", "é", "\n", "  ", "FROM", "t"];
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+        for round in 0..200 {
+            let mut text = String::new();
+            for marker in MARKERS {
+                for _ in 0..rng.below(6) {
+                    text.push_str(rng.pick(&words));
+                    text.push(' ');
+                }
+                text.push_str(marker);
+                text.push(' ');
+            }
+            let uri = Url::parse("file:///shift.sql").unwrap();
+            let mut document = Document::new(uri, text, 1, "sql".to_string());
+            let mut published: Vec<Diagnostic> = MARKERS
+                .iter()
+                .map(|marker| diagnostic(find(&document, marker), marker))
+                .collect();
+
+            // One notification of up to three changes, each avoiding the markers
+            let mut changes = Vec::new();
+            for _ in 0..1 + rng.below(3) {
+                let text = Rope::from_str(&document.get_content());
+                let start = rng.below(text.len_chars() + 1);
+                let end = (start + rng.below(4)).min(text.len_chars());
+                let overlaps = MARKERS.iter().any(|marker| {
+                    let (s, e) = span(&document, marker);
+                    start < e && end > s
+                });
+                if overlaps {
+                    continue;
+                }
+                let edit = change(
+                    Range::new(position(&text, start), position(&text, end)),
+                    rng.pick(&["", "x", "\n", "é\n ", " \n\n"]),
+                );
+                document
+                    .apply_changes(std::slice::from_ref(&edit), 2)
+                    .unwrap();
+                changes.push(edit);
+            }
+
+            published = shift_diagnostics(&published, &changes).unwrap();
+            let recomputed: Vec<Range> = MARKERS
+                .iter()
+                .map(|marker| find(&document, marker))
+                .collect();
+            let shifted: Vec<Range> = published.iter().map(|d| d.range).collect();
+            assert_eq!(shifted, recomputed, "round {round}: {changes:?}");
+        }
+    }
+}
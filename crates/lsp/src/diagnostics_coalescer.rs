@@ -0,0 +1,413 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Diagnostics coalescer
+//!
+//! Rate limits outbound `textDocument/publishDiagnostics` notifications.
+//!
+//! During rapid edits the scheduler can compute diagnostics for many
+//! intermediate versions, and some clients flicker when each one is
+//! published. [`DiagnosticsCoalescer`] sits between the computed results and
+//! the client:
+//!
+//! - At most one notification per document is sent per publish interval.
+//!   Results arriving within the interval replace each other and the latest
+//!   one is sent when the interval ends.
+//! - A set equal to the one last sent for the document is not sent at all.
+//!   The comparison ignores fields that don't affect rendering (`data`) and
+//!   the order of diagnostics.
+//! - After an edit, [`DiagnosticsCoalescer::shift`] republishes the last set
+//!   at once with its ranges moved through the edit (see
+//!   [`crate::diagnostic_shift`]), until the recomputed set replaces it.
+//!
+//! [`PublishStats`] counts what happened to each result.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tower_lsp::Client;
+use tower_lsp::lsp_types::{Diagnostic, TextDocumentContentChangeEvent, Url};
+
+use crate::diagnostic_shift::shift_diagnostics;
+
+/// Delivers a diagnostics notification to the client
+#[tower_lsp::async_trait]
+pub trait DiagnosticsSink: Send + Sync {
+    async fn send(&self, uri: Url, diagnostics: Vec<Diagnostic>, version: Option<i32>);
+}
+
+#[tower_lsp::async_trait]
+impl DiagnosticsSink for Client {
+    async fn send(&self, uri: Url, diagnostics: Vec<Diagnostic>, version: Option<i32>) {
+        self.publish_diagnostics(uri, diagnostics, version).await;
+    }
+}
+
+/// Counters for diagnostics handed to the coalescer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishStats {
+    /// Notifications sent to the client
+    pub published: u64,
+    /// Results dropped because they equal the set last sent
+    pub suppressed_duplicate: u64,
+    /// Results replaced by a newer one within the publish interval
+    pub coalesced: u64,
+    /// Sets republished with their ranges shifted through an edit
+    pub shifted: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    published: AtomicU64,
+    suppressed_duplicate: AtomicU64,
+    coalesced: AtomicU64,
+    shifted: AtomicU64,
+}
+
+/// Outbound state of one document
+#[derive(Default)]
+struct DocumentState {
+    /// Normalized form of the set last sent
+    last_sent: Option<Vec<Diagnostic>>,
+    /// The set last sent, as it was sent
+    last_sent_original: Vec<Diagnostic>,
+    last_sent_at: Option<Instant>,
+    /// Latest result waiting for the interval to end
+    pending: Option<(Vec<Diagnostic>, Option<i32>)>,
+    /// Whether a delayed flush is already scheduled
+    flush_scheduled: bool,
+}
+
+/// Per-document coalescer for diagnostics notifications
+///
+/// Cloning is cheap and clones share document state and counters.
+#[derive(Clone)]
+pub struct DiagnosticsCoalescer {
+    sink: Arc<dyn DiagnosticsSink>,
+    documents: Arc<Mutex<HashMap<Url, DocumentState>>>,
+    counters: Arc<Counters>,
+}
+
+impl DiagnosticsCoalescer {
+    pub fn new(sink: Arc<dyn DiagnosticsSink>) -> Self {
+        Self {
+            sink,
+            documents: Arc::new(Mutex::new(HashMap::new())),
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    /// Publish diagnostics for a document, at most once per `interval`
+    ///
+    /// Sends immediately if the interval has passed since the last
+    /// notification for `uri`; otherwise the result is held and sent when the
+    /// interval ends, unless a newer one replaces it first.
+    pub async fn publish(
+        &self,
+        uri: Url,
+        diagnostics: Vec<Diagnostic>,
+        version: Option<i32>,
+        interval: Duration,
+    ) {
+        let delay = {
+            let mut documents = self.documents.lock().unwrap();
+            let state = documents.entry(uri.clone()).or_default();
+            if state.pending.replace((diagnostics, version)).is_some() {
+                self.counters.coalesced.fetch_add(1, Ordering::Relaxed);
+            }
+            if state.flush_scheduled {
+                return;
+            }
+
+            let elapsed = state.last_sent_at.map(|at| at.elapsed());
+            match elapsed {
+                Some(elapsed) if elapsed < interval => {
+                    state.flush_scheduled = true;
+                    interval - elapsed
+                }
+                _ => Duration::ZERO,
+            }
+        };
+
+        if delay.is_zero() {
+            self.flush(&uri).await;
+        } else {
+            let coalescer = self.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                coalescer.flush(&uri).await;
+            });
+        }
+    }
+
+    /// Move the diagnostics of `uri` through the content changes that made
+    /// `version`, and publish them right away
+    ///
+    /// The set waiting for the interval to end is shifted if there is one,
+    /// otherwise the set last sent. Diagnostics the changes overlap are
+    /// dropped. Nothing happens for a full text replacement or when no range
+    /// moved.
+    pub async fn shift(&self, uri: &Url, changes: &[TextDocumentContentChangeEvent], version: i32) {
+        {
+            let mut documents = self.documents.lock().unwrap();
+            let Some(state) = documents.get_mut(uri) else {
+                return;
+            };
+            let current = match &state.pending {
+                Some((pending, _)) => pending,
+                None => &state.last_sent_original,
+            };
+            let Some(shifted) = shift_diagnostics(current, changes) else {
+                return;
+            };
+            if &shifted == current {
+                return;
+            }
+            state.pending = Some((shifted, Some(version)));
+        }
+
+        self.counters.shifted.fetch_add(1, Ordering::Relaxed);
+        self.flush(uri).await;
+    }
+
+    /// Forget a closed document
+    ///
+    /// A pending result is dropped and the next publish for the URI is sent
+    /// immediately, even if it equals the set last sent.
+    pub fn forget(&self, uri: &Url) {
+        self.documents.lock().unwrap().remove(uri);
+    }
+
+    /// Snapshot of the counters
+    pub fn stats(&self) -> PublishStats {
+        PublishStats {
+            published: self.counters.published.load(Ordering::Relaxed),
+            suppressed_duplicate: self.counters.suppressed_duplicate.load(Ordering::Relaxed),
+            coalesced: self.counters.coalesced.load(Ordering::Relaxed),
+            shifted: self.counters.shifted.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Send the pending result for a document unless it repeats the last one
+    async fn flush(&self, uri: &Url) {
+        let (diagnostics, version) = {
+            let mut documents = self.documents.lock().unwrap();
+            let Some(state) = documents.get_mut(uri) else {
+                return;
+            };
+            state.flush_scheduled = false;
+            let Some((diagnostics, version)) = state.pending.take() else {
+                return;
+            };
+
+            let normalized = normalize(&diagnostics);
+            if state.last_sent.as_ref() == Some(&normalized) {
+                self.counters
+                    .suppressed_duplicate
+                    .fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+            state.last_sent = Some(normalized);
+            state.last_sent_original = diagnostics.clone();
+            state.last_sent_at = Some(Instant::now());
+            (diagnostics, version)
+        };
+
+        self.counters.published.fetch_add(1, Ordering::Relaxed);
+        self.sink.send(uri.clone(), diagnostics, version).await;
+    }
+}
+
+/// Diagnostics without volatile fields, in a canonical order
+fn normalize(diagnostics: &[Diagnostic]) -> Vec<Diagnostic> {
+    let mut normalized: Vec<Diagnostic> = diagnostics
+        .iter()
+        .map(|diagnostic| Diagnostic {
+            data: None,
+            ..diagnostic.clone()
+        })
+        .collect();
+    normalized.sort_by(|a, b| {
+        let key = |d: &Diagnostic| {
+            (
+                d.range.start.line,
+                d.range.start.character,
+                d.range.end.line,
+                d.range.end.character,
+            )
+        };
+        key(a).cmp(&key(b)).then_with(|| a.message.cmp(&b.message))
+    });
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::{Position, Range};
+
+    type Notification = (Url, Vec<Diagnostic>, Option<i32>);
+
+    /// Records every notification instead of sending it
+    #[derive(Default)]
+    struct RecordingSink {
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[tower_lsp::async_trait]
+    impl DiagnosticsSink for RecordingSink {
+        async fn send(&self, uri: Url, diagnostics: Vec<Diagnostic>, version: Option<i32>) {
+            self.sent.lock().unwrap().push((uri, diagnostics, version));
+        }
+    }
+
+    fn uri() -> Url {
+        Url::parse("file:///tmp/coalesced.sql").unwrap()
+    }
+
+    fn diagnostic(line: u32, message: &str) -> Diagnostic {
+        Diagnostic {
+            range: Range::new(Position::new(line, 0), Position::new(line, 5)),
+            message: message.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn coalescer() -> (DiagnosticsCoalescer, Arc<RecordingSink>) {
+        let sink = Arc::new(RecordingSink::default());
+        (DiagnosticsCoalescer::new(sink.clone()), sink)
+    }
+
+    #[tokio::test]
+    async fn test_identical_diagnostics_are_published_once() {
+        let (coalescer, sink) = coalescer();
+        let computed = vec![diagnostic(0, "Unknown table 'orderz'")];
+
+        coalescer
+            .publish(uri(), computed.clone(), Some(1), Duration::ZERO)
+            .await;
+        // Same set for a newer version, with only volatile fields changed
+        let mut recomputed = computed.clone();
+        recomputed[0].data = Some(serde_json::json!({ "computedAt": 42 }));
+        coalescer
+            .publish(uri(), recomputed, Some(2), Duration::ZERO)
+            .await;
+
+        assert_eq!(sink.sent.lock().unwrap().len(), 1);
+        assert_eq!(
+            coalescer.stats(),
+            PublishStats {
+                published: 1,
+                suppressed_duplicate: 1,
+                coalesced: 0,
+                shifted: 0,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_order_does_not_make_sets_different() {
+        let (coalescer, sink) = coalescer();
+        let a = diagnostic(0, "first");
+        let b = diagnostic(3, "second");
+
+        coalescer
+            .publish(uri(), vec![a.clone(), b.clone()], Some(1), Duration::ZERO)
+            .await;
+        coalescer
+            .publish(uri(), vec![b, a], Some(2), Duration::ZERO)
+            .await;
+
+        assert_eq!(sink.sent.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_burst_within_interval_publishes_latest() {
+        let (coalescer, sink) = coalescer();
+        let interval = Duration::from_millis(50);
+
+        coalescer
+            .publish(uri(), vec![diagnostic(0, "v1")], Some(1), interval)
+            .await;
+        for version in 2..=4 {
+            let message = format!("v{}", version);
+            coalescer
+                .publish(
+                    uri(),
+                    vec![diagnostic(0, &message)],
+                    Some(version),
+                    interval,
+                )
+                .await;
+        }
+        assert_eq!(sink.sent.lock().unwrap().len(), 1);
+
+        tokio::time::sleep(interval * 2).await;
+
+        let sent = sink.sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[1].1[0].message, "v4");
+        assert_eq!(sent[1].2, Some(4));
+        assert_eq!(coalescer.stats().coalesced, 2);
+    }
+
+    #[tokio::test]
+    async fn test_forget_allows_republishing_same_set() {
+        let (coalescer, sink) = coalescer();
+
+        coalescer
+            .publish(uri(), Vec::new(), Some(1), Duration::ZERO)
+            .await;
+        coalescer.forget(&uri());
+        coalescer
+            .publish(uri(), Vec::new(), Some(1), Duration::ZERO)
+            .await;
+
+        assert_eq!(sink.sent.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_edit_above_republishes_shifted_set() {
+        let (coalescer, sink) = coalescer();
+        let interval = Duration::from_secs(60);
+        coalescer
+            .publish(
+                uri(),
+                vec![diagnostic(1, "Unknown table")],
+                Some(1),
+                interval,
+            )
+            .await;
+
+        let insert_line = TextDocumentContentChangeEvent {
+            range: Some(Range::new(Position::new(0, 0), Position::new(0, 0))),
+            range_length: Some(0),
+            text: "\n".to_string(),
+        };
+        // Sent within the interval, without waiting for it to end
+        coalescer
+            .shift(&uri(), std::slice::from_ref(&insert_line), 2)
+            .await;
+        {
+            let sent = sink.sent.lock().unwrap();
+            assert_eq!(sent.len(), 2);
+            assert_eq!(sent[1].1[0].range.start.line, 2);
+            assert_eq!(sent[1].2, Some(2));
+        }
+
+        // An edit after the diagnostics moves nothing
+        let append = TextDocumentContentChangeEvent {
+            range: Some(Range::new(Position::new(5, 0), Position::new(5, 0))),
+            ..insert_line
+        };
+        coalescer.shift(&uri(), &[append], 3).await;
+        assert_eq!(sink.sent.lock().unwrap().len(), 2);
+        assert_eq!(coalescer.stats().shifted, 1);
+    }
+}
@@ -0,0 +1,983 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Diagnostics scheduler
+//!
+//! Single entry point for recomputing and publishing document diagnostics.
+//!
+//! Diagnostics are recomputed for two reasons:
+//! - A document changed: the request is debounced per URI so a burst of
+//!   keystrokes only triggers one recomputation
+//! - The catalog changed: every open document is revalidated, at most
+//!   [`DEFAULT_CONCURRENCY`] at a time
+//!
+//! Both paths go through [`DiagnosticsScheduler::revalidate`], which checks the
+//! document content after computing and drops the result if the document was
+//! edited in the meantime. The newer edit schedules its own run, so stale
+//! diagnostics never overwrite fresh ones. A change that left the text as it
+//! was schedules nothing, so the result is kept and published for the newer
+//! version.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{Semaphore, broadcast};
+use tokio::task::JoinHandle;
+use tower_lsp::lsp_types::{Diagnostic, Url};
+use tracing::{debug, warn};
+use unified_sql_lsp_catalog::Catalog;
+use unified_sql_lsp_ir::Dialect;
+use unified_sql_lsp_semantic::TableSymbol;
+
+use crate::column_migrations::ColumnMigration;
+use crate::completion::catalog_integration::CatalogCompletionFetcher;
+use crate::config::WorkspaceMode;
+use crate::diagnostic::{DiagnosticCollector, publish_diagnostics_for_document};
+use crate::diagnostics_coalescer::DiagnosticsCoalescer;
+use crate::document::{Document, DocumentStore};
+use crate::embedded::mask_embedded_regions;
+use crate::events::ServerEvent;
+use crate::migration_catalog::MigrationCatalog;
+use crate::request_context::RequestContext;
+use crate::workspace_index::WorkspaceIndex;
+use crate::workspace_symbols::index_document;
+
+/// Maximum number of documents revalidated concurrently
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Delay between the last change to a document and its revalidation
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Computes diagnostics for a document and delivers them to the client
+#[tower_lsp::async_trait]
+pub trait DiagnosticsPublisher: Send + Sync {
+    /// Compute diagnostics for a snapshot of a document
+    async fn compute(&self, document: &Document) -> Vec<Diagnostic>;
+
+    /// Publish diagnostics computed for the given document version
+    async fn publish(&self, uri: Url, diagnostics: Vec<Diagnostic>, version: i32);
+}
+
+/// Publisher backed by the diagnostic collector and the LSP client
+///
+/// Custom rule diagnostics are added on compute and rule levels from the
+/// current configuration are applied on publish. Notifications reach the
+/// client through a [`DiagnosticsCoalescer`].
+pub struct ClientDiagnosticsPublisher {
+    coalescer: DiagnosticsCoalescer,
+    collector: DiagnosticCollector,
+    request_context: RequestContext,
+    workspace_index: Arc<WorkspaceIndex>,
+}
+
+impl ClientDiagnosticsPublisher {
+    pub fn new(coalescer: DiagnosticsCoalescer, request_context: RequestContext) -> Self {
+        Self {
+            coalescer,
+            collector: DiagnosticCollector::new(),
+            request_context,
+            workspace_index: Arc::new(WorkspaceIndex::new()),
+        }
+    }
+
+    /// Set the index holding the pending migrations of open documents
+    pub fn with_workspace_index(mut self, workspace_index: Arc<WorkspaceIndex>) -> Self {
+        self.workspace_index = workspace_index;
+        self
+    }
+}
+
+impl ClientDiagnosticsPublisher {
+    /// Text-based checks of `source`, a whole document or SQL embedded in one
+    ///
+    /// `parameters` is a pseudo-table added to the scope of every DML
+    /// statement, see [`EmbeddedRegion::parameter_table`](crate::embedded::EmbeddedRegion::parameter_table).
+    async fn text_diagnostics(
+        &self,
+        uri: &Url,
+        source: &str,
+        dialect: Dialect,
+        catalog: &Option<Arc<dyn Catalog>>,
+        catalog_tables: &[String],
+        parameters: Option<TableSymbol>,
+    ) -> Vec<Diagnostic> {
+        // Past the nesting cap, one note replaces every other check
+        let max_nesting_depth = self.request_context.config_for(uri).await.max_nesting_depth;
+        if let Some(too_deep) = self.collector.nesting_depth(source, max_nesting_depth) {
+            return vec![too_deep.to_lsp()];
+        }
+
+        let mut diagnostics: Vec<Diagnostic> = self
+            .collector
+            .dialect_hints(source, dialect)
+            .into_iter()
+            .chain(self.collector.limit_in_subqueries(source, dialect))
+            .chain(self.collector.cte_cycles(source))
+            .chain(self.collector.duplicate_definitions(uri, source, dialect))
+            .map(|d| d.to_lsp())
+            .collect();
+
+        // Unknown tables are only reported against a catalog that lists tables
+        if !catalog_tables.is_empty() {
+            let capabilities = self
+                .request_context
+                .config_for(uri)
+                .await
+                .version
+                .capabilities();
+            diagnostics.extend(
+                self.collector
+                    .unknown_tables(uri, source, catalog_tables, capabilities.recursive_cte)
+                    .into_iter()
+                    .map(|d| d.to_lsp()),
+            );
+        }
+
+        // WHERE and ORDER BY columns of UPDATE and DELETE are checked against
+        // their tables
+        if let Some(catalog) = catalog {
+            let fetcher = CatalogCompletionFetcher::new(catalog.clone());
+            for (statement, mut tables) in DiagnosticCollector::dml_statements(source) {
+                let mut populated = true;
+                for table in &mut tables {
+                    if fetcher.populate_table_columns(table).await.is_err()
+                        || table.columns.is_empty()
+                    {
+                        populated = false;
+                        break;
+                    }
+                }
+                // Tables the catalog doesn't know are reported on their own
+                if populated {
+                    tables.extend(parameters.clone());
+                    diagnostics.extend(
+                        self.collector
+                            .unknown_columns(source, statement, tables, dialect)
+                            .into_iter()
+                            .map(|d| d.to_lsp()),
+                    );
+                }
+            }
+        }
+
+        // NULL comparisons; only catalog columns are known to be NOT NULL
+        let fetcher = catalog
+            .as_ref()
+            .map(|catalog| CatalogCompletionFetcher::new(catalog.clone()));
+        for (statement, mut tables) in DiagnosticCollector::condition_statements(source) {
+            let mut populated = fetcher.is_some();
+            if let Some(fetcher) = &fetcher {
+                for table in &mut tables {
+                    if fetcher.populate_table_columns(table).await.is_err()
+                        || table.columns.is_empty()
+                    {
+                        populated = false;
+                        break;
+                    }
+                }
+            }
+            // A column of an unknown table could be taken for another's
+            if !populated {
+                tables.clear();
+            }
+            diagnostics.extend(
+                self.collector
+                    .null_comparisons(source, statement, tables, dialect)
+                    .into_iter()
+                    .map(|d| d.to_lsp()),
+            );
+        }
+
+        diagnostics
+    }
+
+    /// Report the columns of `source` removed by pending `migrations`
+    ///
+    /// Such a column is reported with its migration instead of as unknown.
+    fn add_pending_migrations(
+        &self,
+        diagnostics: &mut Vec<Diagnostic>,
+        source: &str,
+        migrations: &[ColumnMigration],
+    ) {
+        if migrations.is_empty() {
+            return;
+        }
+        let pending: Vec<Diagnostic> = self
+            .collector
+            .pending_migration_columns(source, migrations)
+            .into_iter()
+            .map(|d| d.to_lsp())
+            .collect();
+        diagnostics.retain(|d| !pending.iter().any(|p| p.range == d.range));
+        diagnostics.extend(pending);
+    }
+}
+
+#[tower_lsp::async_trait]
+impl DiagnosticsPublisher for ClientDiagnosticsPublisher {
+    async fn compute(&self, document: &Document) -> Vec<Diagnostic> {
+        let source = document.get_content();
+        let tree = document.tree();
+        let dialect = match document.parse_metadata() {
+            Some(metadata) => metadata.dialect,
+            None => match document.declared_dialect() {
+                Some(dialect) => dialect,
+                None => {
+                    self.request_context
+                        .config_for(document.uri())
+                        .await
+                        .dialect
+                }
+            },
+        };
+        let (catalog, migrations) = match self
+            .request_context
+            .config_and_catalog_for_document(document.uri(), dialect)
+            .await
+        {
+            Ok((config, catalog)) if config.diagnostics.pending_migrations => {
+                let migrations = self.workspace_index.pending_migrations(document.uri());
+                (
+                    Some(MigrationCatalog::overlay(catalog, migrations.clone())),
+                    migrations,
+                )
+            }
+            Ok((_, catalog)) => (Some(catalog), Vec::new()),
+            Err(_) => (None, Vec::new()),
+        };
+        let mut table_names: Vec<String> = match &catalog {
+            Some(catalog) => catalog
+                .list_tables()
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .map(|t| t.name)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut diagnostics: Vec<Diagnostic> = self
+            .collector
+            .collect_for_document(document)
+            .into_iter()
+            .map(|d| d.to_lsp())
+            .collect();
+        // Text wholly wrong for the dialect: the syntax errors alone overflow
+        // the cap, and the rest would be trimmed too
+        let config = self.request_context.config_for(document.uri()).await;
+        if config.diagnostics.skips_later_passes(diagnostics.len()) {
+            debug!(
+                "Skipping later diagnostics passes of {} after {} syntax diagnostics",
+                document.uri(),
+                diagnostics.len()
+            );
+            return diagnostics;
+        }
+
+        // Embedded SQL is checked on its own below, with its parameters in scope
+        let regions = document.embedded_regions();
+        let outer = mask_embedded_regions(&source, &regions);
+        // Read-only workspaces flag the statements that modify the database
+        if config.mode == WorkspaceMode::ReadOnly {
+            diagnostics.extend(
+                self.collector
+                    .write_statements(&outer)
+                    .into_iter()
+                    .map(|d| d.to_lsp()),
+            );
+        }
+        let mut outer_diagnostics = self
+            .text_diagnostics(
+                document.uri(),
+                &outer,
+                dialect,
+                &catalog,
+                &table_names,
+                None,
+            )
+            .await;
+        self.add_pending_migrations(&mut outer_diagnostics, &outer, &migrations);
+        diagnostics.extend(outer_diagnostics);
+
+        if !regions.is_empty() && !table_names.is_empty() {
+            // Tables created by the outer document are known inside its bodies
+            table_names.extend(
+                index_document(document.uri(), &outer)
+                    .into_iter()
+                    .map(|d| d.name),
+            );
+        }
+        for region in &regions {
+            let mut embedded = self
+                .text_diagnostics(
+                    document.uri(),
+                    &region.text,
+                    dialect,
+                    &catalog,
+                    &table_names,
+                    Some(region.parameter_table()),
+                )
+                .await;
+            self.add_pending_migrations(&mut embedded, &region.text, &migrations);
+            diagnostics.extend(embedded.into_iter().map(|mut diagnostic| {
+                diagnostic.range = region.to_outer_range(diagnostic.range);
+                diagnostic
+            }));
+        }
+
+        let rules = self.request_context.custom_rules().await;
+        if let (false, Some(tree)) = (rules.is_empty(), tree) {
+            let tree = tree.lock().await.clone();
+            diagnostics.extend(rules.diagnostics(dialect, &tree, &source));
+        }
+
+        diagnostics
+    }
+
+    async fn publish(&self, uri: Url, diagnostics: Vec<Diagnostic>, version: i32) {
+        let config = self.request_context.config_for(&uri).await;
+        publish_diagnostics_for_document(
+            &self.coalescer,
+            uri,
+            diagnostics,
+            Some(version),
+            &config.diagnostics,
+        )
+        .await;
+    }
+}
+
+/// Schedules diagnostics recomputation for open documents
+///
+/// Cloning is cheap and clones share the same tracked set and concurrency limit.
+#[derive(Clone)]
+pub struct DiagnosticsScheduler {
+    documents: Arc<DocumentStore>,
+    publisher: Arc<dyn DiagnosticsPublisher>,
+    permits: Arc<Semaphore>,
+    /// Open document URIs and their latest debounce generation
+    tracked: Arc<Mutex<HashMap<Url, u64>>>,
+    debounce: Duration,
+}
+
+impl DiagnosticsScheduler {
+    /// Create a scheduler with the default concurrency and debounce delay
+    pub fn new(documents: Arc<DocumentStore>, publisher: Arc<dyn DiagnosticsPublisher>) -> Self {
+        Self {
+            documents,
+            publisher,
+            permits: Arc::new(Semaphore::new(DEFAULT_CONCURRENCY)),
+            tracked: Arc::new(Mutex::new(HashMap::new())),
+            debounce: DEFAULT_DEBOUNCE,
+        }
+    }
+
+    /// Set the maximum number of concurrent revalidations
+    pub fn with_concurrency(mut self, limit: usize) -> Self {
+        self.permits = Arc::new(Semaphore::new(limit.max(1)));
+        self
+    }
+
+    /// Set the debounce delay used by [`schedule`](Self::schedule)
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Start tracking an open document
+    pub fn track(&self, uri: Url) {
+        self.tracked.lock().unwrap().entry(uri).or_insert(0);
+    }
+
+    /// Stop tracking a closed document
+    ///
+    /// Pending revalidations for the URI are dropped.
+    pub fn untrack(&self, uri: &Url) {
+        self.tracked.lock().unwrap().remove(uri);
+    }
+
+    /// URIs of all tracked documents
+    pub fn tracked_uris(&self) -> Vec<Url> {
+        self.tracked.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// Revalidate a document after the debounce delay
+    ///
+    /// Scheduling the same URI again before the delay elapses supersedes the
+    /// earlier request.
+    pub fn schedule(&self, uri: Url) -> JoinHandle<()> {
+        self.schedule_after(uri, self.debounce)
+    }
+
+    /// Revalidate a document without waiting for the debounce delay
+    pub fn schedule_now(&self, uri: Url) -> JoinHandle<()> {
+        self.schedule_after(uri, Duration::ZERO)
+    }
+
+    fn schedule_after(&self, uri: Url, delay: Duration) -> JoinHandle<()> {
+        let generation = self.bump_generation(&uri);
+        let scheduler = self.clone();
+
+        tokio::spawn(async move {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            if generation.is_some() && scheduler.current_generation(&uri) == generation {
+                scheduler.revalidate(&uri).await;
+            }
+        })
+    }
+
+    fn bump_generation(&self, uri: &Url) -> Option<u64> {
+        let mut tracked = self.tracked.lock().unwrap();
+        let generation = tracked.get_mut(uri)?;
+        *generation += 1;
+        Some(*generation)
+    }
+
+    fn current_generation(&self, uri: &Url) -> Option<u64> {
+        self.tracked.lock().unwrap().get(uri).copied()
+    }
+
+    /// Revalidate every tracked document and wait for all of them
+    pub async fn revalidate_all(&self) {
+        let uris = self.tracked_uris();
+        debug!("Revalidating {} open documents", uris.len());
+
+        let handles: Vec<_> = uris
+            .into_iter()
+            .map(|uri| {
+                let scheduler = self.clone();
+                tokio::spawn(async move { scheduler.revalidate(&uri).await })
+            })
+            .collect();
+
+        for handle in handles {
+            if let Err(e) = handle.await {
+                warn!("Diagnostics revalidation task failed: {}", e);
+            }
+        }
+    }
+
+    /// Recompute and publish diagnostics for one document
+    ///
+    /// Returns `false` if nothing was published, either because the document is
+    /// no longer open or because its text changed while diagnostics were
+    /// computed.
+    pub async fn revalidate(&self, uri: &Url) -> bool {
+        let Ok(_permit) = self.permits.acquire().await else {
+            return false;
+        };
+
+        let Some(document) = self.documents.get_document(uri).await else {
+            return false;
+        };
+        let diagnostics = self.publisher.compute(&document).await;
+
+        let current = self
+            .documents
+            .get_document(uri)
+            .await
+            .filter(|current| current.content_hash() == document.content_hash());
+        let Some(current) = current.filter(|_| self.current_generation(uri).is_some()) else {
+            debug!(
+                "Dropping diagnostics for {} computed at version {}",
+                uri,
+                document.version()
+            );
+            return false;
+        };
+
+        self.publisher
+            .publish(uri.clone(), diagnostics, current.version())
+            .await;
+        true
+    }
+
+    /// Revalidate all open documents whenever the config or the catalog
+    /// changes
+    ///
+    /// Document events are left to the handlers, which schedule the edited
+    /// document themselves. The task ends when the event bus is dropped.
+    pub fn listen(&self, mut events: broadcast::Receiver<ServerEvent>) -> JoinHandle<()> {
+        let scheduler = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(
+                        event @ (ServerEvent::ConfigChanged
+                        | ServerEvent::CatalogInvalidated { .. }),
+                    ) => debug!("Revalidating after {:?}", event),
+                    Ok(_) => continue,
+                    // Missed events may have changed anything
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        debug!("Skipped {} server events", skipped)
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+                scheduler.revalidate_all().await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::sync::RwLock;
+    use tower_lsp::lsp_types::{
+        DiagnosticSeverity, NumberOrString, Position, Range, TextDocumentContentChangeEvent,
+        VersionedTextDocumentIdentifier,
+    };
+
+    use crate::catalog_manager::CatalogManager;
+    use crate::config::{EngineConfig, SchemaSource, WorkspaceMode};
+
+    /// Reports every `FROM <table>` whose table is missing from the catalog
+    struct UnknownTablePublisher {
+        catalog_manager: Arc<RwLock<CatalogManager>>,
+        config: EngineConfig,
+        published: Mutex<HashMap<Url, Vec<Diagnostic>>>,
+    }
+
+    #[tower_lsp::async_trait]
+    impl DiagnosticsPublisher for UnknownTablePublisher {
+        async fn compute(&self, document: &Document) -> Vec<Diagnostic> {
+            let catalog = self
+                .catalog_manager
+                .write()
+                .await
+                .get_catalog(&self.config)
+                .await
+                .unwrap();
+
+            let content = document.get_content();
+            let words: Vec<&str> = content.split_whitespace().collect();
+            let mut diagnostics = Vec::new();
+            for pair in words.windows(2) {
+                if pair[0].eq_ignore_ascii_case("FROM")
+                    && catalog.get_columns(pair[1]).await.is_err()
+                {
+                    diagnostics.push(Diagnostic {
+                        range: Range::default(),
+                        message: format!("Unknown table '{}'", pair[1]),
+                        ..Default::default()
+                    });
+                }
+            }
+            diagnostics
+        }
+
+        async fn publish(&self, uri: Url, diagnostics: Vec<Diagnostic>, _version: i32) {
+            self.published.lock().unwrap().insert(uri, diagnostics);
+        }
+    }
+
+    /// Counts computations and sets the document's text during the first one
+    struct EditingPublisher {
+        documents: Arc<DocumentStore>,
+        text: &'static str,
+        computed: AtomicUsize,
+        published: AtomicUsize,
+    }
+
+    #[tower_lsp::async_trait]
+    impl DiagnosticsPublisher for EditingPublisher {
+        async fn compute(&self, document: &Document) -> Vec<Diagnostic> {
+            if self.computed.fetch_add(1, Ordering::SeqCst) == 0 {
+                let identifier = VersionedTextDocumentIdentifier {
+                    uri: document.uri().clone(),
+                    version: document.version() + 1,
+                };
+                let change = TextDocumentContentChangeEvent {
+                    range: None,
+                    range_length: None,
+                    text: self.text.to_string(),
+                };
+                self.documents
+                    .update_document(&identifier, &[change])
+                    .await
+                    .unwrap();
+            }
+            Vec::new()
+        }
+
+        async fn publish(&self, _uri: Url, _diagnostics: Vec<Diagnostic>, _version: i32) {
+            self.published.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn uri(name: &str) -> Url {
+        Url::parse(&format!("file:///tmp/{}.sql", name)).unwrap()
+    }
+
+    async fn open(documents: &DocumentStore, uri: &Url, content: &str) {
+        documents
+            .open_document(uri.clone(), content.to_string(), 1, "sql".to_string())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_catalog_refresh_clears_unknown_table_warnings() {
+        let path = std::env::temp_dir().join(format!(
+            "unified-sql-lsp-diagnostics-scheduler-{}.sql",
+            std::process::id()
+        ));
+        std::fs::write(&path, "CREATE TABLE users (id INT);").unwrap();
+
+        let catalog_manager = Arc::new(RwLock::new(CatalogManager::new()));
+        let publisher = Arc::new(UnknownTablePublisher {
+            catalog_manager: catalog_manager.clone(),
+            config: EngineConfig {
+                schema_source: SchemaSource::Ddl {
+                    paths: vec![path.clone()],
+                },
+                ..Default::default()
+            },
+            published: Mutex::new(HashMap::new()),
+        });
+        let documents = Arc::new(DocumentStore::new());
+        let scheduler = DiagnosticsScheduler::new(documents.clone(), publisher.clone());
+
+        let uris = [uri("a"), uri("b"), uri("c")];
+        for uri in &uris {
+            open(&documents, uri, "SELECT * FROM orders").await;
+            scheduler.track(uri.clone());
+        }
+        scheduler.revalidate_all().await;
+        for uri in &uris {
+            assert_eq!(publisher.published.lock().unwrap()[uri].len(), 1);
+        }
+
+        let listener = scheduler.listen(catalog_manager.read().await.subscribe());
+        std::fs::write(
+            &path,
+            "CREATE TABLE users (id INT);\nCREATE TABLE orders (id INT);",
+        )
+        .unwrap();
+        assert!(
+            catalog_manager
+                .write()
+                .await
+                .reload_ddl_catalog(&path)
+                .unwrap()
+        );
+
+        let cleared = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let done = uris.iter().all(|uri| {
+                    publisher
+                        .published
+                        .lock()
+                        .unwrap()
+                        .get(uri)
+                        .is_some_and(Vec::is_empty)
+                });
+                if done {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+
+        listener.abort();
+        std::fs::remove_file(&path).unwrap();
+        assert!(cleared.is_ok(), "diagnostics were not recomputed");
+    }
+
+    #[tokio::test]
+    async fn test_edit_during_revalidation_is_not_clobbered() {
+        let documents = Arc::new(DocumentStore::new());
+        let publisher = Arc::new(EditingPublisher {
+            documents: documents.clone(),
+            text: "SELECT 2",
+            computed: AtomicUsize::new(0),
+            published: AtomicUsize::new(0),
+        });
+        let scheduler = DiagnosticsScheduler::new(documents.clone(), publisher.clone());
+
+        let uri = uri("edited");
+        open(&documents, &uri, "SELECT 1").await;
+        scheduler.track(uri.clone());
+
+        assert!(!scheduler.revalidate(&uri).await);
+        assert_eq!(publisher.published.load(Ordering::SeqCst), 0);
+
+        // The follow-up run sees a stable version and publishes
+        assert!(scheduler.revalidate(&uri).await);
+        assert_eq!(publisher.published.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_resync_of_same_text_keeps_result() {
+        let documents = Arc::new(DocumentStore::new());
+        let publisher = Arc::new(EditingPublisher {
+            documents: documents.clone(),
+            text: "SELECT 1",
+            computed: AtomicUsize::new(0),
+            published: AtomicUsize::new(0),
+        });
+        let scheduler = DiagnosticsScheduler::new(documents.clone(), publisher.clone());
+
+        let uri = uri("resynced");
+        open(&documents, &uri, "SELECT 1").await;
+        scheduler.track(uri.clone());
+
+        // The version moved on but the text didn't, so the result still holds
+        assert!(scheduler.revalidate(&uri).await);
+        assert_eq!(publisher.published.load(Ordering::SeqCst), 1);
+        assert_eq!(documents.get_document(&uri).await.unwrap().version(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_schedule_debounces_and_skips_untracked() {
+        let documents = Arc::new(DocumentStore::new());
+        let publisher = Arc::new(EditingPublisher {
+            documents: documents.clone(),
+            text: "SELECT 2",
+            // Start past the editing run so computations are just counted
+            computed: AtomicUsize::new(1),
+            published: AtomicUsize::new(0),
+        });
+        let scheduler = DiagnosticsScheduler::new(documents.clone(), publisher.clone())
+            .with_debounce(Duration::from_millis(20));
+
+        let uri = uri("debounced");
+        open(&documents, &uri, "SELECT 1").await;
+        scheduler.track(uri.clone());
+
+        let handles: Vec<_> = (0..3).map(|_| scheduler.schedule(uri.clone())).collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert_eq!(publisher.computed.load(Ordering::SeqCst), 2);
+        assert_eq!(publisher.published.load(Ordering::SeqCst), 1);
+
+        scheduler.untrack(&uri);
+        scheduler.schedule_now(uri.clone()).await.unwrap();
+        assert_eq!(publisher.published.load(Ordering::SeqCst), 1);
+    }
+
+    struct NullSink;
+
+    #[tower_lsp::async_trait]
+    impl crate::diagnostics_coalescer::DiagnosticsSink for NullSink {
+        async fn send(&self, _uri: Url, _diagnostics: Vec<Diagnostic>, _version: Option<i32>) {}
+    }
+
+    type Notification = (Url, Vec<Diagnostic>, Option<i32>);
+
+    /// Records the notifications that would reach the client
+    #[derive(Default)]
+    struct RecordingSink {
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[tower_lsp::async_trait]
+    impl crate::diagnostics_coalescer::DiagnosticsSink for RecordingSink {
+        async fn send(&self, uri: Url, diagnostics: Vec<Diagnostic>, version: Option<i32>) {
+            self.sent.lock().unwrap().push((uri, diagnostics, version));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_publisher_sends_latest_debounced_version() {
+        let config = EngineConfig {
+            mode: WorkspaceMode::ReadOnly,
+            ..Default::default()
+        };
+        let request_context = RequestContext::new(
+            Arc::new(RwLock::new(Some(config))),
+            Arc::new(RwLock::new(CatalogManager::new())),
+        );
+        let sink = Arc::new(RecordingSink::default());
+        let publisher = Arc::new(ClientDiagnosticsPublisher::new(
+            DiagnosticsCoalescer::new(sink.clone()),
+            request_context,
+        ));
+        let documents = Arc::new(DocumentStore::new());
+        let scheduler = DiagnosticsScheduler::new(documents.clone(), publisher)
+            .with_debounce(Duration::from_millis(20));
+
+        let uri = uri("client");
+        open(&documents, &uri, "SELECT 1;").await;
+        scheduler.track(uri.clone());
+
+        // Each edit supersedes the revalidation scheduled for the previous one
+        let mut handles = vec![scheduler.schedule(uri.clone())];
+        for (version, text) in [
+            (2, "DELETE FROM users;"),
+            (3, "SELECT 1;\nDROP TABLE users;"),
+        ] {
+            let identifier = VersionedTextDocumentIdentifier {
+                uri: uri.clone(),
+                version,
+            };
+            let change = TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: text.to_string(),
+            };
+            documents
+                .update_document(&identifier, &[change])
+                .await
+                .unwrap();
+            handles.push(scheduler.schedule(uri.clone()));
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let sent = sink.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1, "{:?}", sent);
+        let (published_uri, diagnostics, version) = &sent[0];
+        assert_eq!(published_uri, &uri);
+        assert_eq!(*version, Some(3));
+        let writes: Vec<Range> = diagnostics
+            .iter()
+            .filter(|d| d.code == Some(NumberOrString::String("usql/read-only-write".to_string())))
+            .map(|d| d.range)
+            .collect();
+        assert_eq!(
+            writes,
+            vec![Range::new(Position::new(1, 0), Position::new(1, 16))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sql_function_body_diagnostics_map_to_outer_positions() {
+        let path = std::env::temp_dir().join(format!(
+            "unified-sql-lsp-embedded-diagnostics-{}.sql",
+            std::process::id()
+        ));
+        std::fs::write(&path, "CREATE TABLE users (id INT, name TEXT);").unwrap();
+
+        let config = EngineConfig {
+            schema_source: SchemaSource::Ddl {
+                paths: vec![path.clone()],
+            },
+            ..Default::default()
+        };
+        let request_context = RequestContext::new(
+            Arc::new(RwLock::new(Some(config))),
+            Arc::new(RwLock::new(CatalogManager::new())),
+        );
+        let publisher = ClientDiagnosticsPublisher::new(
+            DiagnosticsCoalescer::new(Arc::new(NullSink)),
+            request_context,
+        );
+
+        let source = "CREATE FUNCTION rename_user(in_user_id int, in_name text) RETURNS void AS $$\n  UPDATE users SET name = in_name WHERE id = in_user_id AND nme = in_name;\n$$ LANGUAGE sql;";
+        let document = Document::new(uri("embedded"), source.to_string(), 1, "sql".to_string());
+        let diagnostics = publisher.compute(&document).await;
+        std::fs::remove_file(&path).unwrap();
+
+        // Parameters resolve, the misspelled column is reported where it is
+        // written in the outer document
+        let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(diagnostics.len(), 1, "{:?}", messages);
+        assert!(messages[0].contains("nme"), "{:?}", messages);
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(1, 60), Position::new(1, 63))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pending_migration_of_another_open_document() {
+        let path = std::env::temp_dir().join(format!(
+            "unified-sql-lsp-pending-migration-{}.sql",
+            std::process::id()
+        ));
+        std::fs::write(&path, "CREATE TABLE users (id INT, legacy_flag INT);").unwrap();
+
+        let mut config = EngineConfig {
+            schema_source: SchemaSource::Ddl {
+                paths: vec![path.clone()],
+            },
+            ..Default::default()
+        };
+        config.diagnostics.pending_migrations = true;
+        let request_context = RequestContext::new(
+            Arc::new(RwLock::new(Some(config))),
+            Arc::new(RwLock::new(CatalogManager::new())),
+        );
+        let index = Arc::new(WorkspaceIndex::new());
+        let publisher = ClientDiagnosticsPublisher::new(
+            DiagnosticsCoalescer::new(Arc::new(NullSink)),
+            request_context,
+        )
+        .with_workspace_index(index.clone());
+
+        let migration = uri("migration");
+        index.update_open_document(
+            &migration,
+            "-- drop the flag\nALTER TABLE users DROP COLUMN legacy_flag;",
+        );
+        let source = "SELECT legacy_flag FROM users;\nDELETE FROM users WHERE legacy_flag = 1;";
+        let document = Document::new(uri("report"), source.to_string(), 1, "sql".to_string());
+        let diagnostics = publisher.compute(&document).await;
+        std::fs::remove_file(&path).unwrap();
+
+        // The WHERE column is not also reported as unknown
+        let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(diagnostics.len(), 2, "{:?}", messages);
+        for diagnostic in &diagnostics {
+            assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::INFORMATION));
+            assert_eq!(
+                diagnostic.message,
+                "column dropped by pending migration (migration.sql:2)"
+            );
+            let related = diagnostic.related_information.as_ref().unwrap();
+            assert_eq!(related[0].location.uri, migration);
+            assert_eq!(
+                related[0].location.range,
+                Range::new(Position::new(1, 30), Position::new(1, 41))
+            );
+        }
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(0, 7), Position::new(0, 18))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_only_mode_flags_write_statements() {
+        let config = Arc::new(RwLock::new(Some(EngineConfig::default())));
+        let request_context =
+            RequestContext::new(config.clone(), Arc::new(RwLock::new(CatalogManager::new())));
+        let publisher = ClientDiagnosticsPublisher::new(
+            DiagnosticsCoalescer::new(Arc::new(NullSink)),
+            request_context,
+        );
+
+        let source = "SELECT * FROM users;
+DELETE FROM users WHERE id = 1;";
+        let document = Document::new(uri("writes"), source.to_string(), 1, "sql".to_string());
+        let writes = |diagnostics: Vec<Diagnostic>| -> Vec<Range> {
+            diagnostics
+                .into_iter()
+                .filter(|d| {
+                    d.code == Some(NumberOrString::String("usql/read-only-write".to_string()))
+                })
+                .map(|d| d.range)
+                .collect()
+        };
+
+        assert!(writes(publisher.compute(&document).await).is_empty());
+
+        config.write().await.as_mut().unwrap().mode = WorkspaceMode::ReadOnly;
+        assert_eq!(
+            writes(publisher.compute(&document).await),
+            vec![Range::new(Position::new(1, 0), Position::new(1, 30))]
+        );
+    }
+}
@@ -13,7 +13,9 @@
 //! - Multiple open documents across different client connections
 //! - Document synchronization (open, change, close)
 //! - Text content management using Ropey for efficient edits
-//! - Document metadata (language ID, version, URI)
+//! - Document metadata (language ID, version, URI, declared dialect)
+//! - A content hash, so changes that leave the text as it was can skip
+//!   reparsing and diagnostics
 //!
 //! ## Architecture
 //!
@@ -47,9 +49,17 @@
 
 use ropey::Rope;
 use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
-use tower_lsp::lsp_types::{TextDocumentContentChangeEvent, Url, VersionedTextDocumentIdentifier};
+use tower_lsp::lsp_types::{
+    Position, Range, TextDocumentContentChangeEvent, Url, VersionedTextDocumentIdentifier,
+};
+
+use unified_sql_lsp_ir::Dialect;
+
+use crate::embedded::{EmbeddedRegion, find_embedded_regions};
 
 /// Parse metadata
 ///
@@ -58,9 +68,13 @@ use tower_lsp::lsp_types::{TextDocumentContentChangeEvent, Url, VersionedTextDoc
 pub struct ParseMetadata {
     pub parsed_at: std::time::SystemTime,
     pub parse_time_ms: u64,
-    pub dialect: unified_sql_lsp_ir::Dialect,
+    pub dialect: Dialect,
     pub has_errors: bool,
     pub error_count: usize,
+    /// Ranges of ERROR nodes in the tree
+    pub error_ranges: Vec<Range>,
+    /// Kind and range of MISSING nodes in the tree
+    pub missing_nodes: Vec<(String, Range)>,
 }
 
 /// Document metadata
@@ -72,6 +86,9 @@ pub struct DocumentMetadata {
     pub language_id: String,
     pub version: i32,
     pub line_count: usize,
+    /// Dialect declared by the document when it was opened, see
+    /// [`declared_dialect`]; `None` defers to the configuration
+    pub dialect: Option<Dialect>,
 }
 
 impl DocumentMetadata {
@@ -82,10 +99,41 @@ impl DocumentMetadata {
             language_id,
             version,
             line_count,
+            dialect: None,
         }
     }
 }
 
+/// Dialect a document declares for itself
+///
+/// A dialect-specific `language_id` ("mysql", "postgresql" or "postgres")
+/// wins. Otherwise a `-- dialect: <name>` comment among the comment lines at
+/// the top of the content is used, e.g. `-- dialect: postgresql`.
+pub fn declared_dialect(language_id: &str, content: &str) -> Option<Dialect> {
+    let parse = |name: &str| match name.to_ascii_lowercase().as_str() {
+        "mysql" => Some(Dialect::MySQL),
+        "postgresql" | "postgres" => Some(Dialect::PostgreSQL),
+        _ => None,
+    };
+
+    if let Some(dialect) = parse(language_id) {
+        return Some(dialect);
+    }
+
+    content
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with("--"))
+        .filter_map(|line| {
+            let comment = line.strip_prefix("--")?.trim();
+            let (key, value) = comment.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case("dialect")
+                .then(|| value.trim())
+        })
+        .find_map(parse)
+}
+
 /// A document managed by the LSP server
 ///
 /// Contains the document's content and metadata.
@@ -93,6 +141,7 @@ impl DocumentMetadata {
 pub struct Document {
     metadata: DocumentMetadata,
     content: Rope,
+    content_hash: u64,
     tree: Option<Arc<Mutex<tree_sitter::Tree>>>,
     parse_metadata: Option<Arc<ParseMetadata>>,
     previous_content: Option<Rope>,
@@ -103,10 +152,12 @@ impl Document {
         let rope = Rope::from_str(&content);
         let line_count = rope.len_lines();
 
-        let metadata = DocumentMetadata::new(uri, language_id, version, line_count);
+        let mut metadata = DocumentMetadata::new(uri, language_id, version, line_count);
+        metadata.dialect = declared_dialect(&metadata.language_id, &content);
 
         Self {
             metadata,
+            content_hash: hash_content(&rope),
             content: rope,
             tree: None,
             parse_metadata: None,
@@ -118,10 +169,24 @@ impl Document {
         &self.metadata.uri
     }
 
+    /// Check whether the document has no backing file
+    ///
+    /// True for `untitled:` buffers and SQL embedded in other documents (e.g.
+    /// notebook cells), which are edited like any other document but aren't
+    /// part of the workspace on disk.
+    pub fn is_virtual(&self) -> bool {
+        self.metadata.uri.scheme() != "file"
+    }
+
     pub fn language_id(&self) -> &str {
         &self.metadata.language_id
     }
 
+    /// Dialect the document declared when it was opened, if any
+    pub fn declared_dialect(&self) -> Option<Dialect> {
+        self.metadata.dialect
+    }
+
     pub fn version(&self) -> i32 {
         self.metadata.version
     }
@@ -134,6 +199,14 @@ impl Document {
         self.content.to_string()
     }
 
+    /// Hash of the content, equal for equal text
+    ///
+    /// Cheaper than comparing the text when deciding whether a change did
+    /// anything.
+    pub fn content_hash(&self) -> u64 {
+        self.content_hash
+    }
+
     /// Get a line of text
     ///
     /// # Arguments
@@ -274,6 +347,7 @@ impl Document {
         // Update metadata
         self.metadata.version = new_version;
         self.metadata.line_count = self.content.len_lines();
+        self.content_hash = hash_content(&self.content);
 
         Ok(())
     }
@@ -305,6 +379,50 @@ impl Document {
         self.parse_metadata.as_deref()
     }
 
+    /// Check whether the last parse found ERROR or MISSING nodes overlapping `range`
+    ///
+    /// Features that rewrite text (formatting, code actions) use this to leave
+    /// broken regions alone. Returns `false` when the document hasn't been parsed.
+    pub fn has_errors_in_range(&self, range: Range) -> bool {
+        let Some(metadata) = self.parse_metadata() else {
+            return false;
+        };
+
+        let overlaps = |other: &Range| other.start <= range.end && range.start <= other.end;
+        metadata.error_ranges.iter().any(overlaps)
+            || metadata.missing_nodes.iter().any(|(_, r)| overlaps(r))
+    }
+
+    /// Regions of the document holding SQL analyzed on its own
+    ///
+    /// See [`crate::embedded`].
+    pub fn embedded_regions(&self) -> Vec<EmbeddedRegion> {
+        find_embedded_regions(&self.get_content())
+    }
+
+    /// The embedded region containing `position`, if any
+    pub fn embedded_region_at(&self, position: Position) -> Option<EmbeddedRegion> {
+        self.embedded_regions()
+            .into_iter()
+            .find(|region| region.contains(position))
+    }
+
+    /// An unparsed document holding the text of `region`
+    ///
+    /// Shares the URI, version and language of this document, so features
+    /// keyed on them behave as in the outer document.
+    pub fn embedded_document(&self, region: &EmbeddedRegion) -> Document {
+        let mut document = Document::new(
+            self.uri().clone(),
+            region.text.clone(),
+            self.version(),
+            self.language_id().to_string(),
+        );
+        // Embedded SQL is written in the dialect of its owner
+        document.metadata.dialect = self.metadata.dialect;
+        document
+    }
+
     /// Get previous content (for incremental edits)
     pub fn previous_content(&self) -> Option<&Rope> {
         self.previous_content.as_ref()
@@ -316,6 +434,13 @@ impl Document {
     }
 }
 
+/// Hash of a rope's text, independent of how it is split into chunks
+fn hash_content(content: &Rope) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
 /// Thread-safe store for all open documents across all client connections.
 #[derive(Debug, Default)]
 pub struct DocumentStore {
@@ -360,10 +485,10 @@ impl DocumentStore {
     ///
     /// # Returns
     ///
-    /// true if the document was closed, false if it didn't exist
-    pub async fn close_document(&self, uri: &Url) -> bool {
+    /// The removed document, or `None` if it wasn't open
+    pub async fn close_document(&self, uri: &Url) -> Option<Document> {
         let mut docs = self.documents.write().await;
-        docs.remove(uri).is_some()
+        docs.remove(uri)
     }
 
     /// Update a document
@@ -494,12 +619,61 @@ pub enum DocumentError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::positions::to_context_range;
     use tower_lsp::lsp_types;
 
     fn create_test_uri() -> Url {
         Url::parse("file:///test.sql").unwrap()
     }
 
+    #[test]
+    fn test_declared_dialect() {
+        assert_eq!(declared_dialect("postgres", ""), Some(Dialect::PostgreSQL));
+        assert_eq!(
+            declared_dialect("sql", "-- migration 42\n-- Dialect: PostgreSQL\nSELECT 1"),
+            Some(Dialect::PostgreSQL)
+        );
+        // language_id wins over the comment
+        assert_eq!(
+            declared_dialect("mysql", "-- dialect: postgresql\nSELECT 1"),
+            Some(Dialect::MySQL)
+        );
+        // Only the comment header counts
+        assert_eq!(
+            declared_dialect("sql", "SELECT 1;\n-- dialect: postgresql"),
+            None
+        );
+        assert_eq!(declared_dialect("sql", "-- dialect: oracle"), None);
+    }
+
+    #[test]
+    fn test_document_has_errors_in_range() {
+        let mut doc = Document::new(
+            create_test_uri(),
+            "SELECT id FROM users WHERE (id = 1".to_string(),
+            1,
+            "sql".to_string(),
+        );
+        let range = |start, end| {
+            Range::new(
+                lsp_types::Position::new(0, start),
+                lsp_types::Position::new(0, end),
+            )
+        };
+        assert!(!doc.has_errors_in_range(range(0, 34)));
+
+        doc.parse_metadata = Some(Arc::new(
+            ParseMetadata::new(0, unified_sql_lsp_ir::Dialect::MySQL, true, 2).with_syntax_errors(
+                vec![to_context_range(range(27, 34))],
+                vec![(")".to_string(), to_context_range(range(34, 34)))],
+            ),
+        ));
+
+        assert!(doc.has_errors_in_range(range(30, 31)));
+        assert!(doc.has_errors_in_range(range(34, 34)));
+        assert!(!doc.has_errors_in_range(range(0, 20)));
+    }
+
     #[test]
     fn test_document_new() {
         let uri = create_test_uri();
@@ -516,6 +690,20 @@ mod tests {
         assert_eq!(doc.get_content(), "SELECT * FROM users");
     }
 
+    #[test]
+    fn test_document_is_virtual() {
+        let file = Document::new(create_test_uri(), String::new(), 1, "sql".to_string());
+        assert!(!file.is_virtual());
+
+        let untitled = Document::new(
+            Url::parse("untitled:Untitled-1").unwrap(),
+            String::new(),
+            1,
+            "sql".to_string(),
+        );
+        assert!(untitled.is_virtual());
+    }
+
     #[test]
     fn test_document_get_line() {
         let uri = create_test_uri();
@@ -569,6 +757,43 @@ mod tests {
         assert_eq!(doc.version(), 2);
     }
 
+    #[test]
+    fn test_document_content_hash_follows_text() {
+        let mut doc = Document::new(
+            create_test_uri(),
+            "SELECT 1".to_string(),
+            1,
+            "sql".to_string(),
+        );
+        let original = doc.content_hash();
+
+        let full = |text: &str| TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: text.to_string(),
+        };
+        doc.apply_changes(&[full("SELECT 1")], 2).unwrap();
+        assert_eq!(doc.content_hash(), original);
+        assert_eq!(doc.version(), 2);
+
+        doc.apply_changes(&[full("SELECT 2")], 3).unwrap();
+        assert_ne!(doc.content_hash(), original);
+
+        // Typing and deleting a character restores the hash
+        let edit = |start, end, text: &str| TextDocumentContentChangeEvent {
+            range: Some(Range::new(
+                lsp_types::Position::new(0, start),
+                lsp_types::Position::new(0, end),
+            )),
+            range_length: Some(end - start),
+            text: text.to_string(),
+        };
+        doc.apply_changes(&[edit(7, 8, "1"), edit(8, 8, "0"), edit(8, 9, "")], 4)
+            .unwrap();
+        assert_eq!(doc.get_content(), "SELECT 1");
+        assert_eq!(doc.content_hash(), original);
+    }
+
     #[test]
     fn test_document_apply_changes_incremental() {
         let uri = create_test_uri();
@@ -643,9 +868,11 @@ mod tests {
             .await
             .unwrap();
 
-        assert!(store.close_document(&uri).await);
+        let closed = store.close_document(&uri).await.unwrap();
+        assert_eq!(closed.version(), 1);
         assert!(!store.has_document(&uri).await);
         assert_eq!(store.document_count().await, 0);
+        assert!(store.close_document(&uri).await.is_none());
     }
 
     #[tokio::test]
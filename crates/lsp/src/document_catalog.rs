@@ -0,0 +1,210 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Document Catalog
+//!
+//! Tables created by a document itself (`CREATE TABLE report AS SELECT ...`,
+//! `SELECT ... INTO report`) are not in the database until the script runs.
+//! [`DocumentCatalog`] layers them over the configured catalog, so later
+//! statements of the same document complete their columns.
+//!
+//! Column names come from the creating query's projection; their types are
+//! unknown. A table the catalog already knows keeps the catalog's columns.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tower_lsp::lsp_types::{Position, Url};
+use unified_sql_lsp_catalog::{
+    Catalog, CatalogResult, ColumnMetadata, DataType, FunctionMetadata, SampleRows,
+    SequenceMetadata, TableMetadata,
+};
+
+use crate::workspace_symbols::{DefinitionKind, WorkspaceDefinition, index_document};
+
+/// Catalog overlaid with the tables a document defines
+pub struct DocumentCatalog {
+    inner: Arc<dyn Catalog>,
+    tables: Vec<TableMetadata>,
+}
+
+impl DocumentCatalog {
+    /// Overlay the tables and views defined in `content` before `position`
+    ///
+    /// Returns `None` when the document defines none, so callers keep using
+    /// `inner` directly.
+    pub fn overlay(
+        inner: Arc<dyn Catalog>,
+        uri: &Url,
+        content: &str,
+        position: Position,
+    ) -> Option<Arc<dyn Catalog>> {
+        let definitions: Vec<_> = index_document(uri, content)
+            .into_iter()
+            .filter(|definition| definition.range.end <= position)
+            .collect();
+        let catalog = Self::new(inner, definitions);
+
+        (!catalog.tables.is_empty()).then(|| Arc::new(catalog) as Arc<dyn Catalog>)
+    }
+
+    /// Create a catalog with the given definitions layered over `inner`
+    ///
+    /// CTE definitions are ignored; they are only visible in their statement.
+    pub fn new(inner: Arc<dyn Catalog>, definitions: Vec<WorkspaceDefinition>) -> Self {
+        let mut tables: Vec<TableMetadata> = Vec::new();
+        for definition in definitions {
+            if definition.kind == DefinitionKind::Cte {
+                continue;
+            }
+            // A later definition of the same name replaces an earlier one
+            tables.retain(|t| !t.name.eq_ignore_ascii_case(&definition.name));
+
+            let schema = definition.schema.unwrap_or_default();
+            let columns = definition
+                .columns
+                .into_iter()
+                .map(|name| ColumnMetadata::new(name, DataType::Other("unknown".to_string())))
+                .collect();
+            tables.push(TableMetadata::new(definition.name, schema).with_columns(columns));
+        }
+
+        Self { inner, tables }
+    }
+
+    fn document_table(&self, table: &str) -> Option<&TableMetadata> {
+        let name = table.rsplit('.').next().unwrap_or(table);
+        self.tables
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[async_trait]
+impl Catalog for DocumentCatalog {
+    async fn list_tables(&self) -> CatalogResult<Vec<TableMetadata>> {
+        let mut tables = self.inner.list_tables().await?;
+        let document_tables: Vec<TableMetadata> = self
+            .tables
+            .iter()
+            .filter(|table| {
+                !tables
+                    .iter()
+                    .any(|known| known.name.eq_ignore_ascii_case(&table.name))
+            })
+            .cloned()
+            .collect();
+        tables.extend(document_tables);
+        Ok(tables)
+    }
+
+    async fn get_columns(&self, table: &str) -> CatalogResult<Vec<ColumnMetadata>> {
+        let Some(document_table) = self.document_table(table) else {
+            return self.inner.get_columns(table).await;
+        };
+
+        match self.inner.get_columns(table).await {
+            Ok(columns) if !columns.is_empty() => Ok(columns),
+            _ => Ok(document_table.columns.clone()),
+        }
+    }
+
+    async fn list_functions(&self) -> CatalogResult<Vec<FunctionMetadata>> {
+        self.inner.list_functions().await
+    }
+
+    async fn list_roles(&self) -> CatalogResult<Vec<String>> {
+        self.inner.list_roles().await
+    }
+
+    async fn list_collations(&self) -> CatalogResult<Vec<String>> {
+        self.inner.list_collations().await
+    }
+
+    async fn list_sequences(&self) -> CatalogResult<Vec<SequenceMetadata>> {
+        self.inner.list_sequences().await
+    }
+
+    fn loaded_tables(&self) -> Option<Vec<TableMetadata>> {
+        self.inner.loaded_tables()
+    }
+
+    /// The tables created by the document are a schema of their own
+    fn has_schema(&self) -> bool {
+        true
+    }
+
+    async fn sample_rows(&self, table: &str, limit: usize) -> CatalogResult<SampleRows> {
+        self.inner.sample_rows(table, limit).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+    fn catalog() -> Arc<dyn Catalog> {
+        Arc::new(
+            MockCatalogBuilder::new()
+                .with_table(TableMetadata::new("users", "public").with_columns(vec![
+                    ColumnMetadata::new("id", DataType::Integer),
+                    ColumnMetadata::new("email", DataType::Text),
+                ]))
+                .build(),
+        )
+    }
+
+    fn uri() -> Url {
+        Url::parse("file:///workspace/report.sql").unwrap()
+    }
+
+    fn column_names(columns: &[ColumnMetadata]) -> Vec<&str> {
+        columns.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_overlay_registers_created_tables() {
+        let sql = "CREATE TABLE report AS SELECT u.id, lower(email) AS mail, count(*) FROM users u;\n\
+                   SELECT id AS user_id INTO TEMP recent FROM users;\n\
+                   SELECT ";
+        let catalog =
+            DocumentCatalog::overlay(catalog(), &uri(), sql, Position::new(2, 7)).unwrap();
+
+        let report = catalog.get_columns("report").await.unwrap();
+        assert_eq!(column_names(&report), vec!["id", "mail"]);
+        let recent = catalog.get_columns("recent").await.unwrap();
+        assert_eq!(column_names(&recent), vec!["user_id"]);
+
+        let tables = catalog.list_tables().await.unwrap();
+        let names: Vec<_> = tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["users", "report", "recent"]);
+
+        // Catalog tables are unaffected
+        let users = catalog.get_columns("users").await.unwrap();
+        assert_eq!(column_names(&users), vec!["id", "email"]);
+        assert!(catalog.get_columns("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_overlay_only_sees_earlier_definitions() {
+        let sql = "SELECT * FROM ; CREATE TABLE report AS SELECT id FROM users;";
+        assert!(DocumentCatalog::overlay(catalog(), &uri(), sql, Position::new(0, 14)).is_none());
+
+        let sql = "WITH totals AS (SELECT 1) SELECT * FROM totals; SELECT ";
+        assert!(DocumentCatalog::overlay(catalog(), &uri(), sql, Position::new(0, 55)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_catalog_columns_take_precedence() {
+        let sql = "CREATE TABLE users AS SELECT 1 AS one;\nSELECT ";
+        let catalog =
+            DocumentCatalog::overlay(catalog(), &uri(), sql, Position::new(1, 7)).unwrap();
+
+        let users = catalog.get_columns("users").await.unwrap();
+        assert_eq!(column_names(&users), vec!["id", "email"]);
+        assert_eq!(catalog.list_tables().await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,450 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Embedded SQL regions
+//!
+//! Parts of a document can hold SQL that the outer parse only sees as an
+//! opaque token. The body of a PostgreSQL `LANGUAGE sql` function is the
+//! main case:
+//!
+//! ```sql
+//! CREATE FUNCTION delete_user(in_user_id int) RETURNS void AS $$
+//!     DELETE FROM users WHERE id = in_user_id;
+//! $$ LANGUAGE sql;
+//! ```
+//!
+//! The outer parse reads the body as a dollar-quoted string, so completion
+//! and diagnostics inside it go through a second pass: the body text is
+//! analyzed as a standalone statement list and positions are translated
+//! between the outer document and the body with [`EmbeddedRegion::to_inner`]
+//! and [`EmbeddedRegion::to_outer`].
+//!
+//! Regions are found by a token scan rather than the CST, so they are
+//! available even when the outer statement doesn't parse.
+
+use std::ops::Range as ByteRange;
+
+use tower_lsp::lsp_types::{CompletionItem, CompletionTextEdit, Position, Range};
+use unified_sql_lsp_catalog::DataType;
+use unified_sql_lsp_context::{Token, TokenKind, lex, mask_ranges};
+use unified_sql_lsp_semantic::{ColumnSymbol, TableSymbol};
+
+/// Kind of SQL embedded in a document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddedKind {
+    /// Body of a `CREATE FUNCTION ... LANGUAGE sql`, either dollar-quoted
+    /// or a `BEGIN ATOMIC ... END` block
+    SqlFunctionBody,
+}
+
+/// SQL embedded in a document, analyzed on its own
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddedRegion {
+    /// What the region is embedded in
+    pub kind: EmbeddedKind,
+
+    /// Name of the object owning the region, e.g. the function name
+    pub owner: String,
+
+    /// Byte range of the embedded text in the outer document
+    pub range: ByteRange<usize>,
+
+    /// Position of the first embedded character in the outer document
+    pub start: Position,
+
+    /// Position just past the embedded text in the outer document
+    pub end: Position,
+
+    /// The embedded SQL
+    pub text: String,
+
+    /// Names visible inside the region as if they were columns, e.g.
+    /// function parameters
+    pub parameters: Vec<ColumnSymbol>,
+}
+
+impl EmbeddedRegion {
+    /// Check whether an outer document position falls inside the region
+    pub fn contains(&self, position: Position) -> bool {
+        self.start <= position && position <= self.end
+    }
+
+    /// Translate an outer document position into the embedded text
+    ///
+    /// Returns `None` for positions outside the region.
+    pub fn to_inner(&self, position: Position) -> Option<Position> {
+        if !self.contains(position) {
+            return None;
+        }
+        Some(if position.line == self.start.line {
+            Position::new(0, position.character - self.start.character)
+        } else {
+            Position::new(position.line - self.start.line, position.character)
+        })
+    }
+
+    /// Translate a position in the embedded text into the outer document
+    pub fn to_outer(&self, position: Position) -> Position {
+        if position.line == 0 {
+            Position::new(self.start.line, self.start.character + position.character)
+        } else {
+            Position::new(self.start.line + position.line, position.character)
+        }
+    }
+
+    /// Translate a range in the embedded text into the outer document
+    pub fn to_outer_range(&self, range: Range) -> Range {
+        Range::new(self.to_outer(range.start), self.to_outer(range.end))
+    }
+
+    /// Translate the edits of a completion item computed on the embedded text
+    pub fn to_outer_item(&self, mut item: CompletionItem) -> CompletionItem {
+        match &mut item.text_edit {
+            Some(CompletionTextEdit::Edit(edit)) => edit.range = self.to_outer_range(edit.range),
+            Some(CompletionTextEdit::InsertAndReplace(edit)) => {
+                edit.insert = self.to_outer_range(edit.insert);
+                edit.replace = self.to_outer_range(edit.replace);
+            }
+            None => {}
+        }
+        for edit in item.additional_text_edits.iter_mut().flatten() {
+            edit.range = self.to_outer_range(edit.range);
+        }
+        item
+    }
+
+    /// The parameters as a pseudo-table named after the owner
+    ///
+    /// Added to the scope of embedded statements so references like
+    /// `WHERE id = in_user_id` resolve.
+    pub fn parameter_table(&self) -> TableSymbol {
+        TableSymbol::new(&self.owner).with_columns(self.parameters.clone())
+    }
+}
+
+/// Find the embedded SQL regions of a document
+///
+/// Currently the bodies of `CREATE [OR REPLACE] FUNCTION` statements that
+/// declare `LANGUAGE sql`, or use the `BEGIN ATOMIC` form.
+pub fn find_embedded_regions(source: &str) -> Vec<EmbeddedRegion> {
+    let tokens: Vec<Token> = lex(source)
+        .filter(|token| token.kind != TokenKind::Comment)
+        .collect();
+    let mut regions = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        if is_word(&tokens[i], "FUNCTION")
+            && is_create_function(&tokens[..i])
+            && let Some((region, end)) = sql_function_body(source, &tokens, i + 1)
+        {
+            regions.push(region);
+            i = end;
+            continue;
+        }
+        i += 1;
+    }
+
+    regions
+}
+
+/// Replace the text of `regions` with spaces, keeping line breaks
+///
+/// Text-based checks of the outer document run on the result so they don't
+/// report on embedded SQL, which is checked on its own.
+pub fn mask_embedded_regions(source: &str, regions: &[EmbeddedRegion]) -> String {
+    mask_ranges(source, regions.iter().map(|region| region.range.clone()))
+}
+
+/// Check whether the tokens before `FUNCTION` end with `CREATE [OR REPLACE]`
+fn is_create_function(before: &[Token]) -> bool {
+    match before {
+        [.., create, or, replace] if is_word(or, "OR") && is_word(replace, "REPLACE") => {
+            is_word(create, "CREATE")
+        }
+        [.., create] => is_word(create, "CREATE"),
+        [] => false,
+    }
+}
+
+/// Parse the function whose name starts at token `from`
+///
+/// Returns the body region and the index of the token ending the statement,
+/// or `None` when the function has no SQL body.
+fn sql_function_body(
+    source: &str,
+    tokens: &[Token],
+    from: usize,
+) -> Option<(EmbeddedRegion, usize)> {
+    let name_start = tokens.get(from)?.offset;
+    let open = from
+        + tokens[from..]
+            .iter()
+            .position(|token| token.paren().is_some() || token.is_symbol(';'))?;
+    if tokens[open].paren() != Some(true) {
+        return None;
+    }
+    let owner = source[name_start..tokens[open].offset]
+        .trim()
+        .trim_matches('"')
+        .to_string();
+    if owner.is_empty() {
+        return None;
+    }
+
+    let depth = tokens[open].depth;
+    let close = open
+        + 1
+        + tokens[open + 1..].iter().position(|token| {
+            token.is_symbol(';') || (token.paren() == Some(false) && token.depth == depth)
+        })?;
+    if tokens[close].is_symbol(';') {
+        return None;
+    }
+    let parameters = parse_parameters(&source[tokens[open].end()..tokens[close].offset], &owner);
+
+    let mut body = None;
+    let mut end = close + 1;
+    while let Some(token) = tokens.get(end) {
+        if token.is_symbol(';') {
+            break;
+        }
+        if body.is_none() {
+            body = dollar_quoted_body(token);
+            if is_word(token, "BEGIN") && tokens.get(end + 1).is_some_and(|t| is_word(t, "ATOMIC"))
+            {
+                let start = tokens[end + 1].end();
+                let atomic_end = atomic_end(tokens, end + 2)?;
+                let range = start..tokens[atomic_end].offset;
+                // BEGIN ATOMIC is always SQL
+                return Some((region(source, &owner, range, parameters), atomic_end));
+            }
+        }
+        end += 1;
+    }
+
+    let body = body?;
+    if !declares_sql_language(&tokens[close + 1..end]) {
+        return None;
+    }
+    Some((region(source, &owner, body, parameters), end))
+}
+
+fn region(
+    source: &str,
+    owner: &str,
+    range: ByteRange<usize>,
+    parameters: Vec<ColumnSymbol>,
+) -> EmbeddedRegion {
+    EmbeddedRegion {
+        kind: EmbeddedKind::SqlFunctionBody,
+        owner: owner.to_string(),
+        start: position_at(source, range.start),
+        end: position_at(source, range.end),
+        text: source[range.clone()].to_string(),
+        range,
+        parameters,
+    }
+}
+
+/// Byte range between the tags of a closed `$tag$...$tag$` string
+fn dollar_quoted_body(token: &Token) -> Option<ByteRange<usize>> {
+    if token.kind != TokenKind::String || !token.text.starts_with('$') || !token.is_closed() {
+        return None;
+    }
+    let tag = token.text[1..].find('$')? + 2;
+    Some(token.offset + tag..token.end() - tag)
+}
+
+/// Index of the `END` closing a `BEGIN ATOMIC` block whose statements start
+/// at token `from`, skipping `CASE ... END`
+fn atomic_end(tokens: &[Token], from: usize) -> Option<usize> {
+    let mut case_depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(from) {
+        if is_word(token, "CASE") {
+            case_depth += 1;
+        } else if is_word(token, "END") {
+            if case_depth == 0 {
+                return Some(i);
+            }
+            case_depth -= 1;
+        }
+    }
+    None
+}
+
+/// Check for `LANGUAGE sql` or `LANGUAGE 'sql'` in the function header
+fn declares_sql_language(header: &[Token]) -> bool {
+    header
+        .windows(2)
+        .find(|pair| is_word(&pair[0], "LANGUAGE"))
+        .is_some_and(|pair| {
+            let language = &pair[1];
+            matches!(language.kind, TokenKind::Word | TokenKind::String)
+                && language.text.trim_matches('\'').eq_ignore_ascii_case("sql")
+        })
+}
+
+/// Parse the parameter list into pseudo-columns of `owner`
+///
+/// Unnamed parameters and `OUT` parameters, which the body can't reference,
+/// are skipped.
+fn parse_parameters(list: &str, owner: &str) -> Vec<ColumnSymbol> {
+    split_top_level(list)
+        .into_iter()
+        .filter_map(|parameter| {
+            let default = lex(parameter).find(|token| {
+                token.depth == 0 && (is_word(token, "DEFAULT") || token.is_symbol('='))
+            });
+            let declaration = match default {
+                Some(default) => &parameter[..default.offset],
+                None => parameter,
+            };
+            let mut tokens: Vec<&str> = declaration.split_whitespace().collect();
+            match tokens.first().map(|t| t.to_ascii_uppercase()).as_deref() {
+                Some("OUT") => return None,
+                Some("IN" | "INOUT" | "VARIADIC") => {
+                    tokens.remove(0);
+                }
+                _ => {}
+            }
+            // A lone type, or a type spelled in several words
+            if tokens.len() < 2 || !matches!(DataType::parse(&tokens.join(" ")), DataType::Other(_))
+            {
+                return None;
+            }
+            let name = tokens[0].trim_matches('"');
+            let data_type = DataType::parse(&tokens[1..].join(" "));
+            Some(ColumnSymbol::new(name, data_type, owner))
+        })
+        .collect()
+}
+
+/// Split on commas outside parentheses
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for comma in lex(list).filter(|token| token.depth == 0 && token.is_symbol(',')) {
+        parts.push(&list[start..comma.offset]);
+        start = comma.end();
+    }
+    parts.push(&list[start..]);
+    parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+fn is_word(token: &Token, word: &str) -> bool {
+    token
+        .word()
+        .is_some_and(|found| found.eq_ignore_ascii_case(word))
+}
+
+/// Character-based position of a byte offset
+fn position_at(source: &str, offset: usize) -> Position {
+    let before = &source[..offset];
+    let line = before.matches('\n').count() as u32;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Position::new(line, before[line_start..].chars().count() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOLLAR: &str = "SELECT 1;\nCREATE OR REPLACE FUNCTION delete_user(in_user_id int, OUT n int, text)\nRETURNS void AS $body$\n  DELETE FROM users WHERE id = in_user_id;\n$body$ LANGUAGE sql;";
+
+    #[test]
+    fn test_dollar_quoted_body() {
+        let regions = find_embedded_regions(DOLLAR);
+        assert_eq!(regions.len(), 1);
+        let region = &regions[0];
+
+        assert_eq!(region.kind, EmbeddedKind::SqlFunctionBody);
+        assert_eq!(region.owner, "delete_user");
+        assert_eq!(
+            region.text,
+            "\n  DELETE FROM users WHERE id = in_user_id;\n"
+        );
+        assert_eq!(&DOLLAR[region.range.clone()], region.text);
+        assert_eq!(region.start, Position::new(2, 22));
+        assert_eq!(region.end, Position::new(4, 0));
+
+        let parameters: Vec<_> = region
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), &p.data_type))
+            .collect();
+        assert_eq!(parameters, vec![("in_user_id", &DataType::Integer)]);
+    }
+
+    #[test]
+    fn test_position_translation() {
+        let region = find_embedded_regions(DOLLAR).remove(0);
+
+        // `in_user_id` in the WHERE clause, line 3 of the document
+        let outer = Position::new(3, 31);
+        let inner = region.to_inner(outer).unwrap();
+        assert_eq!(inner, Position::new(1, 31));
+        assert_eq!(region.to_outer(inner), outer);
+
+        // The first body line shares the line of the opening tag
+        assert_eq!(
+            region.to_inner(Position::new(2, 22)),
+            Some(Position::new(0, 0))
+        );
+        assert_eq!(region.to_outer(Position::new(0, 0)), Position::new(2, 22));
+
+        assert_eq!(region.to_inner(Position::new(2, 5)), None);
+        assert_eq!(region.to_inner(Position::new(4, 3)), None);
+    }
+
+    #[test]
+    fn test_begin_atomic_body() {
+        let source = "CREATE FUNCTION active_count(min_id bigint DEFAULT 0) RETURNS bigint\nBEGIN ATOMIC\n  SELECT CASE WHEN true THEN count(*) END FROM users WHERE id > min_id;\nEND;";
+        let regions = find_embedded_regions(source);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(
+            regions[0].text,
+            "\n  SELECT CASE WHEN true THEN count(*) END FROM users WHERE id > min_id;\n"
+        );
+        assert_eq!(regions[0].parameters[0].name, "min_id");
+        assert_eq!(regions[0].parameters[0].data_type, DataType::BigInt);
+    }
+
+    #[test]
+    fn test_strings_and_comments_are_skipped() {
+        let source = "CREATE FUNCTION label(sep text DEFAULT ';,') RETURNS text\nBEGIN ATOMIC\n  SELECT 'END' || sep; -- END\nEND;";
+        let regions = find_embedded_regions(source);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].text, "\n  SELECT 'END' || sep; -- END\n");
+        assert_eq!(regions[0].parameters.len(), 1);
+        assert_eq!(regions[0].parameters[0].name, "sep");
+    }
+
+    #[test]
+    fn test_other_languages_are_ignored() {
+        let plpgsql =
+            "CREATE FUNCTION f() RETURNS int AS $$ BEGIN RETURN 1; END $$ LANGUAGE plpgsql;";
+        assert!(find_embedded_regions(plpgsql).is_empty());
+
+        let quoted = "CREATE FUNCTION f() RETURNS int LANGUAGE 'sql' AS $$ SELECT 1 $$;";
+        assert_eq!(find_embedded_regions(quoted)[0].text, " SELECT 1 ");
+
+        let no_body = "DROP FUNCTION f(int); SELECT 'CREATE FUNCTION' FROM t;";
+        assert!(find_embedded_regions(no_body).is_empty());
+    }
+
+    #[test]
+    fn test_mask_embedded_regions() {
+        let regions = find_embedded_regions(DOLLAR);
+        let masked = mask_embedded_regions(DOLLAR, &regions);
+        assert_eq!(masked.len(), DOLLAR.len());
+        assert!(!masked.contains("DELETE"));
+        assert_eq!(masked.lines().count(), DOLLAR.lines().count());
+    }
+}
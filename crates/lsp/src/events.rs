@@ -0,0 +1,119 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Server events
+//!
+//! Components that derive state from the configuration, the catalog or the
+//! open documents learn about changes from [`ServerEvent`]s sent on an
+//! [`EventBus`], instead of every handler calling each of them in turn:
+//!
+//! - the diagnostics scheduler revalidates open documents
+//! - the request context drops cached sample rows and late catalog results
+//! - the workspace index follows open documents
+//! - the status reporter marks the table count stale and forgets closed
+//!   documents
+//!
+//! Emitting never blocks. Subscribers that fall behind observe a lag error
+//! and skip the missed events, so they should treat it as "anything may have
+//! changed" and resynchronize.
+
+use std::path::PathBuf;
+use tokio::sync::broadcast;
+use tower_lsp::lsp_types::Url;
+
+/// Capacity of the event channel
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Something other components may need to react to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerEvent {
+    /// Client settings or workspace roots changed
+    ConfigChanged,
+
+    /// Catalog metadata may have changed
+    CatalogInvalidated { scope: CatalogScope },
+
+    /// A document was opened or edited
+    DocumentChanged { uri: Url, version: i32 },
+
+    /// A document was closed
+    DocumentClosed { uri: Url },
+}
+
+/// Catalogs affected by a [`ServerEvent::CatalogInvalidated`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CatalogScope {
+    /// All cached catalogs were dropped and will be recreated on next use
+    All,
+
+    /// A DDL schema file was reloaded from disk
+    SchemaFile(PathBuf),
+}
+
+/// Broadcast channel of [`ServerEvent`]s
+///
+/// Clones share the same channel.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<ServerEvent>,
+}
+
+impl EventBus {
+    /// Create a bus without subscribers
+    pub fn new() -> Self {
+        Self {
+            sender: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    /// Send an event to the current subscribers
+    ///
+    /// Never waits on subscribers; events sent while nobody is subscribed
+    /// are dropped.
+    pub fn emit(&self, event: ServerEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    /// Receive the events emitted from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<ServerEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emit_without_subscribers() {
+        let bus = EventBus::new();
+        bus.emit(ServerEvent::ConfigChanged);
+
+        let mut events = bus.subscribe();
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_slow_subscriber_lags_instead_of_blocking() {
+        let bus = EventBus::new();
+        let mut events = bus.subscribe();
+
+        for _ in 0..EVENT_CHANNEL_CAPACITY + 1 {
+            bus.emit(ServerEvent::ConfigChanged);
+        }
+
+        assert!(matches!(
+            events.try_recv(),
+            Err(broadcast::error::TryRecvError::Lagged(1))
+        ));
+        assert_eq!(events.try_recv().unwrap(), ServerEvent::ConfigChanged);
+    }
+}
@@ -12,9 +12,11 @@
 //! The hover engine:
 //! 1. Parses the source to get a CST
 //! 2. Finds the node at cursor position
-//! 3. Builds a scope from the FROM clause using ScopeBuilder
-//! 4. Uses AliasResolver to resolve aliases to actual tables
-//! 5. Returns appropriate hover information based on node type
+//! 3. Builds a scope from the FROM clause using ScopeBuilder, or from the
+//!    tables of an UPDATE or DELETE outside any SELECT
+//! 4. For column references, resolves the qualifier through the aliases in
+//!    scope; unqualified columns found in several tables list every candidate
+//! 5. Falls back to AliasResolver and catalog lookups for other nodes
 //!
 //! ## Example
 //!
@@ -28,22 +30,45 @@
 //! - Builds scope from FROM clause, finding alias "u" -> "users"
 //! - Queries catalog for users.username column
 //! - Returns column type information
+//!
+//! ## Aggregates
+//!
+//! Hovering the name of an aggregate call such as `SUM(amount)` adds its
+//! return type and the GROUP BY expressions of the containing statement,
+//! or notes that it aggregates over all rows when there is no GROUP BY.
+//!
+//! ## User-defined functions
+//!
+//! Function hovers use the catalog's functions layered over the builtins,
+//! so routines defined in the database show their parameters and comment.
+//! User-defined functions of schemas hidden by the schema filter are left
+//! out.
+//!
+//! ## Sample rows
+//!
+//! With `hover.sampleRows` > 0 and a live connection, table hovers end with
+//! a preview of the table's first rows. Previews are cached per table for
+//! [`SAMPLE_ROWS_TTL`] so repeated hovers don't query the database again.
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tower_lsp::lsp_types::Position;
 use tree_sitter::Node;
-use unified_sql_lsp_catalog::Catalog;
-use unified_sql_lsp_function_registry::HoverInfoProvider;
+use unified_sql_lsp_catalog::{Catalog, ColumnMetadata, SampleRows, SequenceMetadata};
 use unified_sql_lsp_function_registry::hover::ColumnHoverInfo;
+use unified_sql_lsp_function_registry::{FunctionRegistry, HoverInfoProvider};
 use unified_sql_lsp_ir::Dialect;
 
 use unified_sql_lsp_context::{
     Position as ContextPosition, ScopeBuilder,
     find_node_at_position as context_find_node_at_position, find_parent_select,
+    position_to_byte_offset, statement_range,
 };
 
-use unified_sql_lsp_semantic::HoverService;
+use unified_sql_lsp_semantic::{HoverService, TableSymbol};
 
+use crate::config::SchemaFilter;
 use crate::document::Document;
 
 /// Hover engine for SQL queries
@@ -58,6 +83,20 @@ pub struct HoverEngine {
 
     /// Hover info provider for formatting responses
     hover_provider: HoverInfoProvider,
+
+    /// Schemas whose user-defined functions are shown
+    schema_filter: SchemaFilter,
+
+    /// Table data preview, when enabled
+    sample_rows: Option<SampleRowsPreview>,
+}
+
+/// Settings for appending sample rows to table hovers
+struct SampleRowsPreview {
+    limit: usize,
+    cache: Arc<SampleRowsCache>,
+    /// Identifies the database the rows come from (the connection string)
+    source: String,
 }
 
 impl HoverEngine {
@@ -67,9 +106,54 @@ impl HoverEngine {
             catalog,
             dialect,
             hover_provider: HoverInfoProvider::new(),
+            schema_filter: SchemaFilter::default(),
+            sample_rows: None,
         }
     }
 
+    /// Only show user-defined functions of schemas allowed by `filter`
+    pub fn with_schema_filter(mut self, filter: SchemaFilter) -> Self {
+        self.schema_filter = filter;
+        self
+    }
+
+    /// Hover provider knowing the catalog's functions besides the builtins
+    ///
+    /// Falls back to the builtins when the catalog can't list functions.
+    async fn function_hover_provider(&self) -> HoverInfoProvider {
+        let functions = match self.catalog.list_functions().await {
+            Ok(functions) => functions,
+            Err(e) => {
+                tracing::debug!("Cannot list catalog functions for hover: {}", e);
+                return HoverInfoProvider::new();
+            }
+        };
+        let functions = functions
+            .into_iter()
+            .filter(|f| self.schema_filter.is_function_allowed(f));
+        HoverInfoProvider::new().with_function_registry(
+            FunctionRegistry::new().with_catalog_functions(self.dialect, functions),
+        )
+    }
+
+    /// Append up to `limit` rows of table data to table hovers
+    ///
+    /// `source` identifies the database so cached rows are never shown for
+    /// another connection.
+    pub fn with_sample_rows(
+        mut self,
+        limit: usize,
+        cache: Arc<SampleRowsCache>,
+        source: impl Into<String>,
+    ) -> Self {
+        self.sample_rows = (limit > 0).then(|| SampleRowsPreview {
+            limit,
+            cache,
+            source: source.into(),
+        });
+        self
+    }
+
     /// Get hover information for a position in a document
     ///
     /// # Arguments
@@ -101,23 +185,59 @@ impl HoverEngine {
             return None;
         }
 
+        // Function calls may name routines defined in the database
+        let catalog_provider;
+        let function_provider = if is_function_name(&node) {
+            catalog_provider = self.function_hover_provider().await;
+            &catalog_provider
+        } else {
+            &self.hover_provider
+        };
+
+        // Aggregate calls also explain how the statement groups rows
+        if is_function_name(&node)
+            && let Some(select_node) = find_parent_select(&node)
+            && let Some(info) = function_provider.get_aggregate_hover(
+                &word,
+                &self.dialect,
+                group_by_expressions(&select_node, &source).as_deref(),
+            )
+        {
+            return Some(info);
+        }
+
         // Check for function names first
-        if let Some(info) = self.hover_provider.get_function_hover(&word, &self.dialect) {
+        if let Some(info) = function_provider.get_function_hover(&word, &self.dialect) {
             return Some(info);
         }
 
-        // Try to find parent SELECT statement to build scope
+        // Try to find parent SELECT statement to build scope; UPDATE and
+        // DELETE statements, RETURNING included, see their own tables
         let select_node_opt = find_parent_select(&node);
-        let visible_tables = select_node_opt
-            .as_ref()
-            .map(|n| Self::extract_visible_tables(n, &source))
-            .unwrap_or_default();
+        let scope_tables = match &select_node_opt {
+            Some(select_node) => Self::extract_scope_tables(select_node, &source),
+            None => {
+                let offset = position_to_byte_offset(&source, context_pos);
+                ScopeBuilder::dml_tables_from_text(&source[statement_range(&source, offset)])
+            }
+        };
+        let visible_tables: Vec<String> =
+            scope_tables.iter().map(|t| t.table_name.clone()).collect();
+
+        // Column references resolve through the aliases in scope
+        if let Some(part) = column_reference_part(&node, &source)
+            && let Some(info) = self
+                .get_column_reference_hover(&semantic_hover, part, &scope_tables)
+                .await
+        {
+            return Some(info);
+        }
 
         // Check if we're in a FROM clause (hovering over table name or alias)
         if semantic_hover.is_in_from_clause(&node) {
             // Try to resolve as table name first
             if let Some(table_name) = semantic_hover.resolve_table_name(&word).await {
-                return Some(self.hover_provider.get_table_hover(&table_name));
+                return Some(self.table_hover(&table_name).await);
             }
 
             // Try to resolve as table alias.
@@ -138,7 +258,7 @@ impl HoverEngine {
             if let Some(column) = semantic_hover.resolve_column(&word, &visible_tables).await {
                 return Some(
                     self.hover_provider
-                        .get_column_hover(&to_column_hover_info(&column)),
+                        .get_column_hover(&to_column_hover_info(None, &column)),
                 );
             }
 
@@ -146,7 +266,7 @@ impl HoverEngine {
             if let Some(column) = semantic_hover.resolve_simple_column(&word, &source).await {
                 return Some(
                     self.hover_provider
-                        .get_column_hover(&to_column_hover_info(&column)),
+                        .get_column_hover(&to_column_hover_info(None, &column)),
                 );
             }
 
@@ -158,33 +278,524 @@ impl HoverEngine {
             {
                 return Some(
                     self.hover_provider
-                        .get_column_hover(&to_column_hover_info(&column)),
+                        .get_column_hover(&to_column_hover_info(None, &column)),
                 );
             }
         }
 
         // Fallback: try as table name (for bare table references)
         if let Some(table_name) = semantic_hover.resolve_table_name(&word).await {
-            return Some(self.hover_provider.get_table_hover(&table_name));
+            return Some(self.table_hover(&table_name).await);
         }
 
         None
     }
 
-    fn extract_visible_tables(select_node: &Node<'_>, source: &str) -> Vec<String> {
+    /// Table hover, followed by the sample rows preview when enabled
+    async fn table_hover(&self, table: &str) -> String {
+        let mut hover = self.hover_provider.get_table_hover(table);
+
+        if let Some(preview) = &self.sample_rows
+            && let Some(rows) = preview
+                .cache
+                .get_or_fetch(self.catalog.as_ref(), &preview.source, table, preview.limit)
+                .await
+            && !rows.columns.is_empty()
+        {
+            hover.push_str("\n\n");
+            hover.push_str(&render_sample_rows(&rows));
+        }
+
+        hover
+    }
+
+    /// Column hover, followed by the sequence feeding the column if any
+    async fn column_hover(&self, table: &str, column: &ColumnMetadata) -> String {
+        let mut hover = self
+            .hover_provider
+            .get_column_hover(&to_column_hover_info(Some(table), column));
+
+        if column.sequence().is_some() {
+            let sequences = self.catalog.list_sequences().await.unwrap_or_default();
+            if let Some(note) = sequence_note(column, &sequences) {
+                hover.push_str("\n\n");
+                hover.push_str(&note);
+            }
+        }
+
+        hover
+    }
+
+    /// Hover for a `qualifier.column` or bare column reference
+    async fn get_column_reference_hover(
+        &self,
+        semantic_hover: &HoverService,
+        part: ColumnReferencePart,
+        scope_tables: &[TableSymbol],
+    ) -> Option<String> {
+        match part {
+            ColumnReferencePart::Qualifier(qualifier) => {
+                let table = semantic_hover.resolve_qualifier(&qualifier, scope_tables)?;
+                Some(self.table_hover(&table.table_name).await)
+            }
+            ColumnReferencePart::Column {
+                qualifier: Some(qualifier),
+                name,
+            } => {
+                let (table, column) = semantic_hover
+                    .resolve_scoped_column(&qualifier, &name, scope_tables)
+                    .await?;
+                Some(self.column_hover(&table, &column).await)
+            }
+            ColumnReferencePart::Column {
+                qualifier: None,
+                name,
+            } => {
+                let candidates = semantic_hover.column_candidates(&name, scope_tables).await;
+                match candidates.as_slice() {
+                    [] => None,
+                    [(table, column)] => Some(self.column_hover(table, column).await),
+                    _ => {
+                        let infos: Vec<ColumnHoverInfo> = candidates
+                            .iter()
+                            .map(|(table, column)| to_column_hover_info(Some(table), column))
+                            .collect();
+                        Some(
+                            self.hover_provider
+                                .get_ambiguous_column_hover(&name, &infos),
+                        )
+                    }
+                }
+            }
+        }
+    }
+
+    fn extract_scope_tables(select_node: &Node<'_>, source: &str) -> Vec<TableSymbol> {
         ScopeBuilder::build_from_select(select_node, source)
             .ok()
             .and_then(|scope_manager| scope_manager.get_scope(0).cloned())
-            .map(|scope| scope.tables.into_iter().map(|t| t.table_name).collect())
+            .map(|scope| scope.tables)
             .unwrap_or_default()
     }
 }
 
-fn to_column_hover_info(column: &unified_sql_lsp_catalog::ColumnMetadata) -> ColumnHoverInfo {
+/// How long fetched sample rows are reused
+pub const SAMPLE_ROWS_TTL: Duration = Duration::from_secs(60);
+
+/// Longest cell value shown in a sample rows preview, in characters
+const SAMPLE_VALUE_MAX_CHARS: usize = 40;
+
+/// Cache key: (source, table, limit)
+type SampleRowsKey = (String, String, usize);
+
+/// Per-table cache of sample rows
+///
+/// Failed fetches are cached as well, so a slow or unreachable database
+/// costs at most one timeout per table per TTL.
+#[derive(Debug)]
+pub struct SampleRowsCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<SampleRowsKey, (Instant, Option<SampleRows>)>>,
+}
+
+impl Default for SampleRowsCache {
+    fn default() -> Self {
+        Self::new(SAMPLE_ROWS_TTL)
+    }
+}
+
+impl SampleRowsCache {
+    /// Create a cache whose entries expire after `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cached rows for `table`, fetching them from the catalog when missing or expired
+    pub async fn get_or_fetch(
+        &self,
+        catalog: &dyn Catalog,
+        source: &str,
+        table: &str,
+        limit: usize,
+    ) -> Option<SampleRows> {
+        let key = (source.to_string(), table.to_string(), limit);
+
+        if let Some((fetched_at, rows)) = self.entries.lock().unwrap().get(&key)
+            && fetched_at.elapsed() < self.ttl
+        {
+            return rows.clone();
+        }
+
+        let rows = match catalog.sample_rows(table, limit).await {
+            Ok(rows) => Some(rows),
+            Err(e) => {
+                tracing::debug!("No sample rows for table '{}': {}", table, e);
+                None
+            }
+        };
+
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (fetched_at, _)| fetched_at.elapsed() < self.ttl);
+        entries.insert(key, (Instant::now(), rows.clone()));
+        rows
+    }
+
+    /// Drop all cached rows
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// Render sample rows as a markdown table
+///
+/// Long values are cut at 40 characters and NULL is shown as an italic *NULL*.
+pub fn render_sample_rows(sample: &SampleRows) -> String {
+    let row = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+
+    let mut lines = vec![
+        format!("**Sample rows** ({})", sample.rows.len()),
+        String::new(),
+        row(sample.columns.iter().map(|c| escape_cell(c)).collect()),
+        row(sample.columns.iter().map(|_| "---".to_string()).collect()),
+    ];
+    for values in &sample.rows {
+        lines.push(row(values
+            .iter()
+            .map(|value| match value {
+                Some(value) => escape_cell(&truncate_value(value)),
+                None => "*NULL*".to_string(),
+            })
+            .collect()));
+    }
+
+    lines.join("\n")
+}
+
+/// Cut a value to [`SAMPLE_VALUE_MAX_CHARS`], marking the cut with an ellipsis
+fn truncate_value(value: &str) -> String {
+    if value.chars().count() <= SAMPLE_VALUE_MAX_CHARS {
+        return value.to_string();
+    }
+    let mut truncated: String = value.chars().take(SAMPLE_VALUE_MAX_CHARS).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Keep a value from breaking the markdown table layout
+fn escape_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// The part of a column reference under the cursor
+#[derive(Debug, PartialEq)]
+enum ColumnReferencePart {
+    /// The `u` in `u.id`
+    Qualifier(String),
+    /// The `id` in `u.id` or a bare `id`
+    Column {
+        qualifier: Option<String>,
+        name: String,
+    },
+}
+
+/// Locate the enclosing `column_reference` and the part the node belongs to
+///
+/// The qualifier is the `table_name` child before the dot.
+fn column_reference_part(node: &Node<'_>, source: &str) -> Option<ColumnReferencePart> {
+    let reference = std::iter::successors(Some(*node), |n| n.parent())
+        .take_while(|n| {
+            matches!(
+                n.kind(),
+                "identifier" | "table_name" | "column_name" | "column_reference"
+            )
+        })
+        .find(|n| n.kind() == "column_reference")?;
+
+    let mut cursor = reference.walk();
+    let children: Vec<Node<'_>> = reference.named_children(&mut cursor).collect();
+    let child = |kind: &str| children.iter().find(|c| c.kind() == kind).copied();
+    let text = |n: Node<'_>| source.get(n.byte_range()).map(unquote_identifier);
+
+    let qualifier_node = child("table_name");
+    if let Some(q) = qualifier_node
+        && q.byte_range().contains(&node.start_byte())
+    {
+        return text(q).map(ColumnReferencePart::Qualifier);
+    }
+
+    Some(ColumnReferencePart::Column {
+        qualifier: qualifier_node.and_then(text),
+        name: text(child("column_name")?)?,
+    })
+}
+
+/// Strip backtick or double-quote delimiters from an identifier
+/// Whether `node` is (part of) the name of a function call
+fn is_function_name(node: &Node<'_>) -> bool {
+    let mut current = Some(*node);
+    while let Some(n) = current {
+        match n.kind() {
+            "function_name" => return true,
+            "identifier" => current = n.parent(),
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Expressions of the GROUP BY clause of `select_node`
+///
+/// Returns `None` when the statement has no GROUP BY.
+fn group_by_expressions(select_node: &Node<'_>, source: &str) -> Option<Vec<String>> {
+    let mut cursor = select_node.walk();
+    let group_by = select_node
+        .children(&mut cursor)
+        .find(|child| child.kind() == "group_by_clause")?;
+
+    let mut cursor = group_by.walk();
+    let expressions = group_by
+        .children(&mut cursor)
+        .filter(|child| matches!(child.kind(), "expression" | "column_ref"))
+        .filter_map(|child| source.get(child.byte_range()))
+        .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+    Some(expressions)
+}
+
+fn unquote_identifier(identifier: &str) -> String {
+    identifier
+        .trim()
+        .trim_matches(|c| c == '`' || c == '"')
+        .to_string()
+}
+
+/// Line naming the sequence behind a `nextval` default, with its increment
+/// when the catalog lists the sequence
+fn sequence_note(column: &ColumnMetadata, sequences: &[SequenceMetadata]) -> Option<String> {
+    let written = column.sequence()?;
+    let name = written.rsplit('.').next().unwrap_or(written);
+    Some(
+        match sequences
+            .iter()
+            .find(|s| s.name == name || format!("{}.{}", s.schema, s.name) == written)
+        {
+            Some(sequence) => format!(
+                "**Sequence** `{}` (increment {})",
+                written, sequence.increment
+            ),
+            None => format!("**Sequence** `{}`", written),
+        },
+    )
+}
+
+fn to_column_hover_info(table: Option<&str>, column: &ColumnMetadata) -> ColumnHoverInfo {
     ColumnHoverInfo {
         name: column.name.clone(),
+        table: table.map(str::to_string),
         data_type: column.data_type.clone(),
+        nullable: column.nullable,
+        comment: column.comment.clone(),
         is_primary_key: column.is_primary_key,
         is_foreign_key: column.is_foreign_key,
+        references: column
+            .references
+            .as_ref()
+            .map(|r| format!("{}.{}", r.table, r.column)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unified_sql_lsp_catalog::{DataType, FunctionMetadata, FunctionParameter};
+    use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+    fn sample() -> SampleRows {
+        SampleRows {
+            columns: vec!["id".to_string(), "bio".to_string()],
+            rows: vec![
+                vec![Some("1".to_string()), Some("a".repeat(50))],
+                vec![Some("2".to_string()), None],
+                vec![Some("3".to_string()), Some("x|y".to_string())],
+            ],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_table_hover_appends_sample_rows_and_reuses_cache() {
+        let catalog = MockCatalogBuilder::new()
+            .with_standard_schema()
+            .with_sample_rows("users", sample())
+            .build();
+        let cache = Arc::new(SampleRowsCache::default());
+        let engine = HoverEngine::new(Arc::new(catalog.clone()), Dialect::MySQL).with_sample_rows(
+            2,
+            cache.clone(),
+            "mysql://localhost/app",
+        );
+
+        let hover = engine.table_hover("users").await;
+        let expected = format!(
+            "```sql\nusers\n```\n\nTable\n\n**Sample rows** (2)\n\n| id | bio |\n| --- | --- |\n| 1 | {}… |\n| 2 | *NULL* |",
+            "a".repeat(40)
+        );
+        assert_eq!(hover, expected);
+        assert_eq!(catalog.sample_row_calls(), 1);
+
+        // Second hover within the TTL is served from the cache
+        assert_eq!(engine.table_hover("users").await, expected);
+        assert_eq!(catalog.sample_row_calls(), 1);
+
+        // Another connection doesn't see the cached rows
+        let other = HoverEngine::new(Arc::new(catalog.clone()), Dialect::MySQL).with_sample_rows(
+            2,
+            cache,
+            "mysql://localhost/other",
+        );
+        other.table_hover("users").await;
+        assert_eq!(catalog.sample_row_calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_table_hover_without_sample_rows() {
+        let catalog = MockCatalogBuilder::new()
+            .with_standard_schema()
+            .with_sample_rows("users", sample())
+            .build();
+
+        // Disabled by default
+        let engine = HoverEngine::new(Arc::new(catalog.clone()), Dialect::MySQL);
+        assert_eq!(
+            engine.table_hover("users").await,
+            "```sql\nusers\n```\n\nTable"
+        );
+
+        // Unsupported catalogs leave the hover unchanged
+        let engine = HoverEngine::new(Arc::new(catalog.clone()), Dialect::MySQL).with_sample_rows(
+            3,
+            Arc::new(SampleRowsCache::default()),
+            "",
+        );
+        assert_eq!(
+            engine.table_hover("orders").await,
+            "```sql\norders\n```\n\nTable"
+        );
+        assert_eq!(catalog.sample_row_calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_column_hover_shows_sequence() {
+        let catalog = MockCatalogBuilder::new()
+            .with_sequence(SequenceMetadata::new("users_id_seq", "public").with_increment(5))
+            .build();
+        let engine = HoverEngine::new(Arc::new(catalog), Dialect::PostgreSQL);
+
+        let id = ColumnMetadata::new("id", DataType::Integer)
+            .with_default("nextval('users_id_seq'::regclass)");
+        let hover = engine.column_hover("users", &id).await;
+        assert!(
+            hover.ends_with("**Sequence** `users_id_seq` (increment 5)"),
+            "{hover}"
+        );
+
+        // Sequences the catalog doesn't list are still named
+        let code = ColumnMetadata::new("code", DataType::Integer)
+            .with_default("nextval('billing.code_seq'::regclass)");
+        let hover = engine.column_hover("users", &code).await;
+        assert!(
+            hover.ends_with("**Sequence** `billing.code_seq`"),
+            "{hover}"
+        );
+
+        let name = ColumnMetadata::new("name", DataType::Text);
+        assert!(
+            !engine
+                .column_hover("users", &name)
+                .await
+                .contains("Sequence")
+        );
+    }
+
+    #[test]
+    fn test_render_sample_rows_escapes_cells() {
+        let rendered = render_sample_rows(&sample());
+        assert!(rendered.ends_with("| 3 | x\\|y |"), "{rendered}");
+    }
+
+    #[tokio::test]
+    async fn test_function_hover_includes_catalog_functions() {
+        let catalog = MockCatalogBuilder::new()
+            .with_function(
+                FunctionMetadata::new("order_total", DataType::Decimal)
+                    .with_parameters(vec![FunctionParameter {
+                        name: "p_order_id".to_string(),
+                        data_type: DataType::Integer,
+                        has_default: false,
+                        is_variadic: false,
+                    }])
+                    .user_defined("public")
+                    .with_description("Sum of the line items of an order"),
+            )
+            .with_function(FunctionMetadata::new("purge", DataType::Integer).user_defined("admin"))
+            // Overrides the builtin of the same name and arity
+            .with_function(
+                FunctionMetadata::new("now", DataType::Timestamp)
+                    .user_defined("public")
+                    .with_description("Frozen clock for tests"),
+            )
+            .build();
+        let engine = HoverEngine::new(Arc::new(catalog), Dialect::PostgreSQL);
+        let provider = engine.function_hover_provider().await;
+
+        let hover = provider
+            .get_function_hover("ORDER_TOTAL", &Dialect::PostgreSQL)
+            .unwrap();
+        assert!(hover.contains("order_total(p_order_id)"), "{}", hover);
+        assert!(hover.contains("Sum of the line items of an order"));
+        let purge = provider
+            .get_function_hover("purge", &Dialect::PostgreSQL)
+            .unwrap();
+        assert!(
+            purge.contains("User-defined function in `admin`"),
+            "{}",
+            purge
+        );
+        let now = provider
+            .get_function_hover("NOW", &Dialect::PostgreSQL)
+            .unwrap();
+        assert!(now.contains("Frozen clock for tests"), "{}", now);
+        // Builtins are still there
+        assert!(
+            provider
+                .get_function_hover("COUNT", &Dialect::PostgreSQL)
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_function_hover_applies_schema_filter() {
+        let catalog = MockCatalogBuilder::new()
+            .with_function(
+                FunctionMetadata::new("order_total", DataType::Decimal).user_defined("public"),
+            )
+            .with_function(FunctionMetadata::new("purge", DataType::Integer).user_defined("admin"))
+            .build();
+        let engine = HoverEngine::new(Arc::new(catalog), Dialect::PostgreSQL)
+            .with_schema_filter(SchemaFilter::new().allow_schema("public"));
+        let provider = engine.function_hover_provider().await;
+
+        assert!(
+            provider
+                .get_function_hover("order_total", &Dialect::PostgreSQL)
+                .is_some()
+        );
+        assert!(
+            provider
+                .get_function_hover("purge", &Dialect::PostgreSQL)
+                .is_none()
+        );
     }
 }
@@ -62,7 +62,7 @@
 //!
 //! ```rust,no_run
 //! use unified_sql_lsp_lsp::LspBackend;
-//! use tower_lsp::{LspService, Server};
+//! use tower_lsp::Server;
 //!
 //! #[tokio::main]
 //! async fn main() {
@@ -70,7 +70,7 @@
 //!     let stdout = tokio::io::stdout();
 //!
 //!     // Create the LSP service
-//!     let (service, socket) = LspService::new(LspBackend::new);
+//!     let (service, socket) = LspBackend::service();
 //!
 //!     // Run the server
 //!     Server::new(stdin, stdout, socket).serve(service).await;
@@ -81,7 +81,9 @@
 //!
 //! The server can be configured through:
 //!
-//! 1. **Client Settings** (recommended)
+//! 1. **Client Settings** (recommended), requested with
+//!    `workspace/configuration` when the client supports it and otherwise
+//!    pushed with `workspace/didChangeConfiguration`
 //! ```json
 //! {
 //!   "unifiedSqlLsp.dialect": "mysql",
@@ -90,6 +92,14 @@
 //! }
 //! ```
 //!
+//! A dumped schema file can replace the connection string:
+//! ```json
+//! {
+//!   "unifiedSqlLsp.dialect": "postgresql",
+//!   "unifiedSqlLsp.schemaSource": { "type": "ddl", "path": "./schema.sql" }
+//! }
+//! ```
+//!
 //! 2. **Configuration File** (CONFIG-001)
 //! ```yaml
 //! dialect: mysql
@@ -121,6 +131,18 @@
 //! let doc = store.get_document(&uri).await;
 //! ```
 //!
+//! ## Cargo Features
+//!
+//! - `lsp` (default): the language server; everything below
+//! - `parser`: [`ParserManager`] and completion context detection, with the
+//!   tower-lsp free [`Position`] and [`Range`]
+//! - `catalog`: catalog metadata types and [`DdlCatalog`]; live database
+//!   catalogs come with `lsp`
+//! - `wasm`: `parser` and `catalog`, for embedders without tokio
+//!
+//! Embedders disable default features and pick `parser` or
+//! `parser,catalog`. No type from tower-lsp appears in their API.
+//!
 //! ## Supported SQL Dialects
 //!
 //! - **MySQL** (5.7, 8.0) - Full support planned
@@ -132,6 +154,7 @@
 //! - [`backend`]: Main LSP server implementation
 //! - [`document`]: Document management and storage
 //! - [`config`]: Engine configuration and validation
+//! - [`diagnostics_scheduler`]: Debounced and catalog-driven diagnostics revalidation
 //!
 //! ## Error Handling
 //!
@@ -157,32 +180,116 @@
 //! cargo test --test integration
 //! ```
 
+#[cfg(feature = "lsp")]
 pub mod backend;
+#[cfg(feature = "lsp")]
 pub mod catalog_manager;
+#[cfg(feature = "lsp")]
+pub mod code_actions;
+#[cfg(feature = "lsp")]
+mod column_migrations;
+#[cfg(feature = "lsp")]
+mod column_references;
+#[cfg(feature = "lsp")]
 pub mod completion;
+#[cfg(feature = "lsp")]
 pub mod config;
+#[cfg(feature = "lsp")]
 pub mod diagnostic;
+#[cfg(feature = "lsp")]
+pub mod diagnostic_shift;
+#[cfg(feature = "lsp")]
+pub mod diagnostics_coalescer;
+#[cfg(feature = "lsp")]
+pub mod diagnostics_scheduler;
+#[cfg(feature = "lsp")]
 pub mod document;
+#[cfg(feature = "lsp")]
+mod document_catalog;
+#[cfg(feature = "lsp")]
+pub mod embedded;
+#[cfg(feature = "lsp")]
+pub mod events;
+#[cfg(feature = "lsp")]
 mod hover;
+#[cfg(feature = "lsp")]
+mod migration_catalog;
+#[cfg(feature = "parser")]
 pub mod parsing;
+#[cfg(feature = "lsp")]
+pub mod positions;
+#[cfg(feature = "lsp")]
+pub mod prefetch;
+#[cfg(feature = "lsp")]
+pub mod rename;
+#[cfg(feature = "lsp")]
 mod request_context;
+#[cfg(feature = "lsp")]
+pub mod rules;
+#[cfg(feature = "lsp")]
+pub mod schema_diff;
+#[cfg(feature = "lsp")]
+pub mod server_info;
+#[cfg(feature = "lsp")]
+pub mod status;
+#[cfg(feature = "lsp")]
 mod symbols;
+#[cfg(feature = "lsp")]
 pub mod sync;
+#[cfg(feature = "lsp")]
+mod table_references;
+#[cfg(feature = "lsp")]
 pub mod tcp;
+#[cfg(feature = "lsp")]
+mod token_scan;
+#[cfg(feature = "lsp")]
+pub mod workspace;
+#[cfg(feature = "lsp")]
+pub mod workspace_index;
+#[cfg(feature = "lsp")]
+mod workspace_symbols;
 
 // profiling module removed in "drop bench" commit
 // TODO: restore if benchmarking is re-added
 
 // Re-exports for convenience
+#[cfg(feature = "lsp")]
 pub use backend::{LspBackend, LspError};
+#[cfg(feature = "lsp")]
 pub use catalog_manager::CatalogManager;
+#[cfg(feature = "lsp")]
 pub use completion::CompletionEngine;
-pub use config::{ConfigError, ConnectionPoolConfig, DialectVersion, EngineConfig, SchemaFilter};
+#[cfg(feature = "lsp")]
+pub use config::{
+    CompletionConfig, ConfigError, ConnectionPoolConfig, DiagnosticsConfig, DialectVersion,
+    EngineConfig, HoverConfig, KeywordCase, RuleLevel, SchemaFilter, SchemaSource, ShowUnsupported,
+    WorkspaceIndexConfig, WorkspaceMode,
+};
+#[cfg(feature = "lsp")]
 pub use diagnostic::{DiagnosticCode, DiagnosticCollector, SqlDiagnostic};
+#[cfg(feature = "lsp")]
 pub use document::{Document, DocumentError, DocumentMetadata, DocumentStore, ParseMetadata};
+#[cfg(feature = "lsp")]
+pub use events::{CatalogScope, EventBus, ServerEvent};
+#[cfg(feature = "parser")]
 pub use parsing::{ParseError, ParseResult, ParserManager};
+#[cfg(feature = "lsp")]
 pub use sync::DocumentSync;
 
+// Embedder API of the `parser` and `catalog` features
+#[cfg(feature = "catalog")]
+pub use unified_sql_lsp_catalog::{
+    Catalog, CatalogError, CatalogResult, ColumnMetadata, DdlCatalog, FunctionMetadata,
+    TableMetadata,
+};
+#[cfg(feature = "parser")]
+pub use unified_sql_lsp_context::{
+    CompletionContext, Position, Range, detect_completion_context,
+    detect_completion_context_from_text,
+};
+#[cfg(feature = "parser")]
+pub use unified_sql_lsp_ir::Dialect;
+
 /// Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -0,0 +1,213 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Migration Catalog
+//!
+//! A migration open in the editor (`ALTER TABLE users DROP COLUMN
+//! legacy_flag`) hasn't run yet, but queries written against the database
+//! will break once it does. With `diagnostics.pendingMigrations` enabled,
+//! [`MigrationCatalog`] layers the column changes of the other open
+//! documents (recorded by the [`WorkspaceIndex`](crate::workspace_index::WorkspaceIndex))
+//! over the catalog used for diagnostics, and references to removed columns
+//! are reported with the location of the change.
+//!
+//! Completion keeps the plain catalog: offering columns that don't exist
+//! yet, or hiding ones that still do, would only confuse.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use unified_sql_lsp_catalog::{
+    Catalog, CatalogResult, ColumnMetadata, DataType, FunctionMetadata, SampleRows,
+    SequenceMetadata, TableMetadata,
+};
+
+use crate::column_migrations::{ColumnChange, ColumnMigration};
+
+/// Catalog with pending column changes applied
+pub struct MigrationCatalog {
+    inner: Arc<dyn Catalog>,
+    migrations: Vec<ColumnMigration>,
+}
+
+impl MigrationCatalog {
+    /// Overlay `migrations` on `inner`
+    ///
+    /// Returns `inner` itself when there are no migrations.
+    pub fn overlay(inner: Arc<dyn Catalog>, migrations: Vec<ColumnMigration>) -> Arc<dyn Catalog> {
+        if migrations.is_empty() {
+            inner
+        } else {
+            Arc::new(Self::new(inner, migrations))
+        }
+    }
+
+    /// Create a catalog with `migrations` applied over `inner`, in order
+    pub fn new(inner: Arc<dyn Catalog>, migrations: Vec<ColumnMigration>) -> Self {
+        Self { inner, migrations }
+    }
+}
+
+/// The migration that removed `column` from `table`, if it is still removed
+/// after all of `migrations`
+///
+/// A column dropped and added again, or renamed back, is not removed.
+pub fn removing_migration<'a>(
+    migrations: &'a [ColumnMigration],
+    table: &str,
+    column: &str,
+) -> Option<&'a ColumnMigration> {
+    let table = table.rsplit('.').next().unwrap_or(table);
+    migrations
+        .iter()
+        .filter(|m| m.table.eq_ignore_ascii_case(table))
+        .fold(None, |removed, migration| match &migration.change {
+            ColumnChange::Dropped(name) | ColumnChange::Renamed { from: name, .. }
+                if name.eq_ignore_ascii_case(column) =>
+            {
+                Some(migration)
+            }
+            ColumnChange::Added(name) | ColumnChange::Renamed { to: name, .. }
+                if name.eq_ignore_ascii_case(column) =>
+            {
+                None
+            }
+            _ => removed,
+        })
+}
+
+#[async_trait]
+impl Catalog for MigrationCatalog {
+    async fn list_tables(&self) -> CatalogResult<Vec<TableMetadata>> {
+        self.inner.list_tables().await
+    }
+
+    async fn get_columns(&self, table: &str) -> CatalogResult<Vec<ColumnMetadata>> {
+        let mut columns = self.inner.get_columns(table).await?;
+        let name = table.rsplit('.').next().unwrap_or(table);
+
+        for migration in self
+            .migrations
+            .iter()
+            .filter(|m| m.table.eq_ignore_ascii_case(name))
+        {
+            match &migration.change {
+                ColumnChange::Added(column) => {
+                    if !columns.iter().any(|c| c.name.eq_ignore_ascii_case(column)) {
+                        columns.push(ColumnMetadata::new(
+                            column.clone(),
+                            DataType::Other("unknown".to_string()),
+                        ));
+                    }
+                }
+                ColumnChange::Dropped(column) => {
+                    columns.retain(|c| !c.name.eq_ignore_ascii_case(column));
+                }
+                ColumnChange::Renamed { from, to } => {
+                    if let Some(c) = columns
+                        .iter_mut()
+                        .find(|c| c.name.eq_ignore_ascii_case(from))
+                    {
+                        c.name = to.clone();
+                    }
+                }
+            }
+        }
+
+        Ok(columns)
+    }
+
+    async fn list_functions(&self) -> CatalogResult<Vec<FunctionMetadata>> {
+        self.inner.list_functions().await
+    }
+
+    async fn list_roles(&self) -> CatalogResult<Vec<String>> {
+        self.inner.list_roles().await
+    }
+
+    async fn list_collations(&self) -> CatalogResult<Vec<String>> {
+        self.inner.list_collations().await
+    }
+
+    async fn list_sequences(&self) -> CatalogResult<Vec<SequenceMetadata>> {
+        self.inner.list_sequences().await
+    }
+
+    fn loaded_tables(&self) -> Option<Vec<TableMetadata>> {
+        self.inner.loaded_tables()
+    }
+
+    fn has_schema(&self) -> bool {
+        self.inner.has_schema()
+    }
+
+    async fn sample_rows(&self, table: &str, limit: usize) -> CatalogResult<SampleRows> {
+        self.inner.sample_rows(table, limit).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::Url;
+    use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+    use crate::column_migrations::column_migrations;
+
+    fn catalog() -> Arc<dyn Catalog> {
+        Arc::new(
+            MockCatalogBuilder::new()
+                .with_table(TableMetadata::new("users", "public").with_columns(vec![
+                    ColumnMetadata::new("id", DataType::Integer),
+                    ColumnMetadata::new("legacy_flag", DataType::Boolean),
+                    ColumnMetadata::new("mail", DataType::Text),
+                ]))
+                .build(),
+        )
+    }
+
+    fn migrations(sql: &str) -> Vec<ColumnMigration> {
+        column_migrations(&Url::parse("file:///db/migration.sql").unwrap(), sql)
+    }
+
+    #[tokio::test]
+    async fn test_overlay_applies_column_changes() {
+        let catalog = MigrationCatalog::overlay(
+            catalog(),
+            migrations(
+                "ALTER TABLE users DROP COLUMN legacy_flag, ADD COLUMN note TEXT;\n\
+                 ALTER TABLE public.users RENAME COLUMN mail TO email;",
+            ),
+        );
+
+        let columns = catalog.get_columns("users").await.unwrap();
+        let names: Vec<_> = columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "email", "note"]);
+        assert_eq!(columns[1].data_type, DataType::Text);
+    }
+
+    #[test]
+    fn test_removing_migration() {
+        let changes = migrations(
+            "ALTER TABLE users DROP legacy_flag;\n\
+             ALTER TABLE users CHANGE mail email VARCHAR(255);\n\
+             ALTER TABLE users ADD CONSTRAINT pk PRIMARY KEY (id), DROP INDEX idx_mail;",
+        );
+        assert_eq!(changes.len(), 2);
+
+        let dropped = removing_migration(&changes, "users", "LEGACY_FLAG").unwrap();
+        assert_eq!(dropped.range.start.line, 0);
+        assert!(removing_migration(&changes, "users", "mail").is_some());
+        assert!(removing_migration(&changes, "users", "email").is_none());
+        assert!(removing_migration(&changes, "orders", "legacy_flag").is_none());
+
+        // Added back later
+        let changes = migrations(
+            "ALTER TABLE users DROP COLUMN legacy_flag;\n\
+             ALTER TABLE users ADD COLUMN legacy_flag BOOLEAN;",
+        );
+        assert!(removing_migration(&changes, "users", "legacy_flag").is_none());
+    }
+}
@@ -12,9 +12,13 @@
 //! The parsing module handles:
 //! - Parser management for multiple SQL dialects
 //! - Full and incremental parsing
-//! - LSP change to tree-sitter edit conversion
+//! - LSP change to tree-sitter edit conversion (`lsp` feature)
 //! - Graceful error handling and reporting
 //!
+//! Positions and ranges are the tower-lsp free ones of
+//! [`unified_sql_lsp_context`], so the module builds with the `parser`
+//! feature alone. [`crate::positions`] converts them for the server.
+//!
 //! ## Architecture
 //!
 //! ```text
@@ -42,7 +46,7 @@
 //!     ParseResult::Success { tree, parse_time } => {
 //!         println!("Parsed in {:?}", parse_time);
 //!     }
-//!     ParseResult::Partial { tree, errors } => {
+//!     ParseResult::Partial { tree, errors, .. } => {
 //!         println!("Parsed with {} errors", errors.len());
 //!     }
 //!     ParseResult::Failed { error } => {
@@ -52,10 +56,10 @@
 //! ```
 
 use std::time::{Duration, Instant};
-use tower_lsp::lsp_types::*;
 use tracing::debug;
 
 use unified_sql_grammar::language_for_dialect;
+use unified_sql_lsp_context::{Position, Range};
 use unified_sql_lsp_ir::Dialect;
 
 /// Parser manager for multiple SQL dialects
@@ -136,20 +140,9 @@ impl ParserManager {
 
         let parse_time = start.elapsed();
 
-        // Check for ERROR nodes in the tree
-        let errors = self.collect_errors(&tree, text);
-
-        if errors.is_empty() {
-            ParseResult::Success {
-                tree: Some(tree),
-                parse_time,
-            }
-        } else {
-            ParseResult::Partial {
-                tree: Some(tree),
-                errors,
-            }
-        }
+        // Check for ERROR and MISSING nodes in the tree
+        let syntax_errors = self.collect_errors(&tree, text);
+        ParseResult::from_tree(tree, parse_time, syntax_errors)
     }
 
     /// Parse with incremental edit
@@ -205,43 +198,34 @@ impl ParserManager {
 
         let parse_time = start.elapsed();
 
-        // Check for ERROR nodes
-        let errors = self.collect_errors(&tree, text);
-
-        if errors.is_empty() {
-            ParseResult::Success {
-                tree: Some(tree),
-                parse_time,
-            }
-        } else {
-            ParseResult::Partial {
-                tree: Some(tree),
-                errors,
-            }
-        }
+        // Check for ERROR and MISSING nodes in the tree
+        let syntax_errors = self.collect_errors(&tree, text);
+        ParseResult::from_tree(tree, parse_time, syntax_errors)
     }
 
     /// Collect parse errors from tree
     ///
-    /// Finds all ERROR nodes in the tree and extracts error information.
-    fn collect_errors(&self, tree: &tree_sitter::Tree, text: &str) -> Vec<ParseError> {
-        let mut errors = Vec::new();
+    /// Finds all ERROR and MISSING nodes in a single walk, recording their
+    /// ranges so consumers don't have to walk the tree again.
+    fn collect_errors(&self, tree: &tree_sitter::Tree, text: &str) -> SyntaxErrors {
+        let mut errors = SyntaxErrors::default();
 
-        // Recursively find ERROR nodes
+        // Only walk when tree-sitter flagged an error somewhere
         let node = tree.root_node();
-        self.find_error_nodes(&node, &mut errors, text);
+        if node.has_error() {
+            self.find_error_nodes(&node, &mut errors, text);
+        }
 
         errors
     }
 
-    /// Recursively find ERROR nodes in tree
+    /// Recursively find ERROR and MISSING nodes in tree
     #[allow(clippy::only_used_in_recursion)]
-    fn find_error_nodes(&self, node: &tree_sitter::Node, errors: &mut Vec<ParseError>, text: &str) {
-        if node.kind() == "ERROR" {
-            // Get error location
-            let line = node.start_position().row;
-            let column = node.start_position().column;
+    fn find_error_nodes(&self, node: &tree_sitter::Node, errors: &mut SyntaxErrors, text: &str) {
+        let line = node.start_position().row;
+        let column = node.start_position().column;
 
+        if node.is_error() {
             // Try to extract the error text
             let start_byte = node.start_byte();
             let end_byte = node.end_byte();
@@ -251,21 +235,52 @@ impl ParserManager {
                 "<invalid bytes>".to_string()
             };
 
-            errors.push(ParseError::InvalidInput {
+            errors.errors.push(ParseError::InvalidInput {
                 line,
                 column,
                 message: format!("Syntax error: {}", error_text),
                 node_type: Some(node.kind().to_string()),
             });
+            errors.error_ranges.push(node_range(node));
+        } else if node.is_missing() {
+            errors.errors.push(ParseError::InvalidInput {
+                line,
+                column,
+                message: format!("Missing {}", node.kind()),
+                node_type: Some(node.kind().to_string()),
+            });
+            errors
+                .missing_nodes
+                .push((node.kind().to_string(), node_range(node)));
         }
 
-        // Recurse into children
+        // Recurse into children that contain errors
         for child in node.children(&mut node.walk()) {
-            self.find_error_nodes(&child, errors, text);
+            if child.has_error() {
+                self.find_error_nodes(&child, errors, text);
+            }
         }
     }
 }
 
+/// Syntax errors found in one walk over a parse tree
+#[derive(Debug, Clone, Default)]
+struct SyntaxErrors {
+    errors: Vec<ParseError>,
+    error_ranges: Vec<Range>,
+    missing_nodes: Vec<(String, Range)>,
+}
+
+/// Range covered by a tree-sitter node
+fn node_range(node: &tree_sitter::Node) -> Range {
+    let start = node.start_position();
+    let end = node.end_position();
+    Range {
+        start: Position::new(start.row as u32, start.column as u32),
+        end: Position::new(end.row as u32, end.column as u32),
+    }
+}
+
 /// Result of a parsing operation
 ///
 /// Represents the outcome of parsing SQL text with Tree-sitter.
@@ -291,6 +306,12 @@ pub enum ParseResult {
 
         /// List of parse errors
         errors: Vec<ParseError>,
+
+        /// Ranges of ERROR nodes, in tree order
+        error_ranges: Vec<Range>,
+
+        /// Kind and range of MISSING nodes inserted by error recovery
+        missing_nodes: Vec<(String, Range)>,
     },
 
     /// Failed parse (critical error)
@@ -303,6 +324,23 @@ pub enum ParseResult {
 }
 
 impl ParseResult {
+    /// Build the result for a freshly parsed tree
+    fn from_tree(tree: tree_sitter::Tree, parse_time: Duration, syntax: SyntaxErrors) -> Self {
+        if syntax.errors.is_empty() {
+            ParseResult::Success {
+                tree: Some(tree),
+                parse_time,
+            }
+        } else {
+            ParseResult::Partial {
+                tree: Some(tree),
+                errors: syntax.errors,
+                error_ranges: syntax.error_ranges,
+                missing_nodes: syntax.missing_nodes,
+            }
+        }
+    }
+
     /// Check if parse was successful (no errors)
     pub fn is_success(&self) -> bool {
         matches!(self, ParseResult::Success { .. })
@@ -335,6 +373,22 @@ impl ParseResult {
         }
     }
 
+    /// Get the ranges of ERROR nodes (empty unless partial)
+    pub fn error_ranges(&self) -> &[Range] {
+        match self {
+            ParseResult::Partial { error_ranges, .. } => error_ranges,
+            _ => &[],
+        }
+    }
+
+    /// Get the kind and range of MISSING nodes (empty unless partial)
+    pub fn missing_nodes(&self) -> &[(String, Range)] {
+        match self {
+            ParseResult::Partial { missing_nodes, .. } => missing_nodes,
+            _ => &[],
+        }
+    }
+
     /// Extract success result
     pub fn into_success(self) -> Option<(tree_sitter::Tree, Duration)> {
         match self {
@@ -376,9 +430,10 @@ pub enum ParseError {
 ///
 /// - `Some(InputEdit)` - If conversion successful
 /// - `None` - If change is not an incremental edit (e.g., full replacement)
+#[cfg(feature = "lsp")]
 pub fn lsp_change_to_input_edit(
     old_text: &ropey::Rope,
-    change: &TextDocumentContentChangeEvent,
+    change: &tower_lsp::lsp_types::TextDocumentContentChangeEvent,
 ) -> Option<tree_sitter::InputEdit> {
     // Only support incremental edits (with range)
     let range = change.range?;
@@ -432,10 +487,92 @@ pub fn lsp_change_to_input_edit(
     })
 }
 
+/// Nodes rendered by [`debug_tree`] before it truncates the output
+pub const DEBUG_TREE_NODE_LIMIT: usize = 5000;
+
+/// Render a parse tree as an indented S-expression, for debugging grammars
+///
+/// Named nodes (and missing tokens) are written as `(kind [line:col-line:col])`
+/// with zero-based positions, flagged ` ERROR` or ` MISSING` where tree-sitter
+/// recovered from a syntax error. With a `range`, only nodes overlapping it
+/// are rendered. After `node_limit` nodes the output ends with a
+/// `; truncated` note.
+pub fn debug_tree(tree: &tree_sitter::Tree, range: Option<Range>, node_limit: usize) -> String {
+    let mut writer = DebugTreeWriter {
+        out: String::new(),
+        nodes: 0,
+        node_limit,
+        range,
+    };
+    let complete = writer.write(&tree.root_node(), 0);
+    if !complete {
+        writer
+            .out
+            .push_str(&format!("\n; truncated after {} nodes", writer.node_limit));
+    }
+    writer.out
+}
+
+struct DebugTreeWriter {
+    out: String,
+    nodes: usize,
+    node_limit: usize,
+    range: Option<Range>,
+}
+
+impl DebugTreeWriter {
+    /// Write `node` and its subtree; returns `false` once the limit is hit
+    fn write(&mut self, node: &tree_sitter::Node, depth: usize) -> bool {
+        if self.nodes == self.node_limit {
+            return false;
+        }
+        self.nodes += 1;
+
+        let range = node_range(node);
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+        self.out.push_str(&format!(
+            "{}({} [{}:{}-{}:{}]",
+            "  ".repeat(depth),
+            node.kind(),
+            range.start.line,
+            range.start.character,
+            range.end.line,
+            range.end.character
+        ));
+        if node.is_error() {
+            self.out.push_str(" ERROR");
+        }
+        if node.is_missing() {
+            self.out.push_str(" MISSING");
+        }
+
+        let mut cursor = node.walk();
+        let children: Vec<_> = node
+            .children(&mut cursor)
+            .filter(|child| child.is_named() || child.is_missing())
+            .filter(|child| self.overlaps(&node_range(child)))
+            .collect();
+        for child in &children {
+            if !self.write(child, depth + 1) {
+                return false;
+            }
+        }
+
+        self.out.push(')');
+        true
+    }
+
+    fn overlaps(&self, range: &Range) -> bool {
+        self.range
+            .is_none_or(|wanted| range.end >= wanted.start && range.start <= wanted.end)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ropey::Rope;
 
     #[test]
     fn test_parse_text_simple() {
@@ -482,46 +619,115 @@ mod tests {
     }
 
     #[test]
-    fn test_lsp_change_to_input_edit_incremental() {
-        let old_text = Rope::from_str("SELECT * FROM users");
-
-        let change = TextDocumentContentChangeEvent {
-            range: Some(Range {
-                start: Position {
-                    line: 0,
-                    character: 7,
-                },
-                end: Position {
-                    line: 0,
-                    character: 8,
-                },
-            }),
-            range_length: Some(1),
-            text: "id".to_string(),
-        };
+    fn test_parse_collects_error_ranges() {
+        let manager = ParserManager::new();
 
-        let edit = lsp_change_to_input_edit(&old_text, &change);
+        if language_for_dialect(Dialect::MySQL).is_none() {
+            println!("Skipping test: MySQL grammar not compiled");
+            return;
+        }
 
-        assert!(edit.is_some());
-        let edit = edit.unwrap();
+        let result = manager.parse_text(Dialect::MySQL, "SELECT id FROM users WHERE id = = 1");
+        let tree = result.tree().expect("tree even with errors");
 
-        assert_eq!(edit.start_byte, 7);
-        assert_eq!(edit.old_end_byte, 8);
-        assert_eq!(edit.new_end_byte, 9); // "id" is 2 chars, start at 7
+        let mut expected = Vec::new();
+        let mut stack = vec![tree.root_node()];
+        while let Some(node) = stack.pop() {
+            if node.is_error() {
+                expected.push(node_range(&node));
+            }
+            let mut cursor = node.walk();
+            let children: Vec<_> = node.children(&mut cursor).collect();
+            stack.extend(children.into_iter().rev());
+        }
+
+        assert!(result.is_partial());
+        assert!(!expected.is_empty());
+        assert_eq!(result.error_ranges(), expected.as_slice());
     }
 
     #[test]
-    fn test_lsp_change_to_input_edit_full_replacement() {
-        let old_text = Rope::from_str("old content");
+    fn test_debug_tree_range_and_truncation() {
+        let manager = ParserManager::new();
 
-        let change = TextDocumentContentChangeEvent {
-            range: None, // Full document replacement
-            range_length: None,
-            text: "new content".to_string(),
-        };
+        if language_for_dialect(Dialect::MySQL).is_none() {
+            println!("Skipping test: MySQL grammar not compiled");
+            return;
+        }
+
+        let result = manager.parse_text(
+            Dialect::MySQL,
+            "SELECT id FROM users;\nSELECT name FROM orders;",
+        );
+        let tree = result.tree().expect("tree");
+
+        let full = debug_tree(tree, None, DEBUG_TREE_NODE_LIMIT);
+        assert!(full.starts_with("(source_file [0:0-"));
+        assert_eq!(full.matches("(select_statement").count(), 2);
+        assert!(!full.contains("truncated"));
+
+        // Only the second statement overlaps line 1
+        let second = debug_tree(
+            tree,
+            Some(Range::new(Position::new(1, 0), Position::new(1, 5))),
+            DEBUG_TREE_NODE_LIMIT,
+        );
+        assert_eq!(second.matches("(select_statement").count(), 1);
+        assert!(second.contains("(select_statement [1:0-"));
+
+        let truncated = debug_tree(tree, None, 2);
+        assert_eq!(truncated.lines().filter(|l| l.contains('[')).count(), 2);
+        assert!(truncated.ends_with("; truncated after 2 nodes"));
+    }
 
-        let edit = lsp_change_to_input_edit(&old_text, &change);
+    #[cfg(feature = "lsp")]
+    mod lsp_edits {
+        use super::*;
+        use ropey::Rope;
+        use tower_lsp::lsp_types::{Position, Range, TextDocumentContentChangeEvent};
+
+        #[test]
+        fn test_lsp_change_to_input_edit_incremental() {
+            let old_text = Rope::from_str("SELECT * FROM users");
+
+            let change = TextDocumentContentChangeEvent {
+                range: Some(Range {
+                    start: Position {
+                        line: 0,
+                        character: 7,
+                    },
+                    end: Position {
+                        line: 0,
+                        character: 8,
+                    },
+                }),
+                range_length: Some(1),
+                text: "id".to_string(),
+            };
 
-        assert!(edit.is_none(), "Full replacement should return None");
+            let edit = lsp_change_to_input_edit(&old_text, &change);
+
+            assert!(edit.is_some());
+            let edit = edit.unwrap();
+
+            assert_eq!(edit.start_byte, 7);
+            assert_eq!(edit.old_end_byte, 8);
+            assert_eq!(edit.new_end_byte, 9); // "id" is 2 chars, start at 7
+        }
+
+        #[test]
+        fn test_lsp_change_to_input_edit_full_replacement() {
+            let old_text = Rope::from_str("old content");
+
+            let change = TextDocumentContentChangeEvent {
+                range: None, // Full document replacement
+                range_length: None,
+                text: "new content".to_string(),
+            };
+
+            let edit = lsp_change_to_input_edit(&old_text, &change);
+
+            assert!(edit.is_none(), "Full replacement should return None");
+        }
     }
 }
@@ -0,0 +1,36 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Position conversions
+//!
+//! Parsing and context detection use the [`unified_sql_lsp_context`]
+//! `Position` and `Range`, which mirror the LSP types without depending on
+//! tower-lsp. The server converts at its boundary with these helpers.
+
+use tower_lsp::lsp_types::{Position, Range};
+use unified_sql_lsp_context::{Position as ContextPosition, Range as ContextRange};
+
+/// LSP position of a context position
+pub fn to_lsp_position(position: ContextPosition) -> Position {
+    Position::new(position.line, position.character)
+}
+
+/// LSP range of a context range
+pub fn to_lsp_range(range: ContextRange) -> Range {
+    Range::new(to_lsp_position(range.start), to_lsp_position(range.end))
+}
+
+/// Context position of an LSP position
+pub fn to_context_position(position: Position) -> ContextPosition {
+    ContextPosition::new(position.line, position.character)
+}
+
+/// Context range of an LSP range
+pub fn to_context_range(range: Range) -> ContextRange {
+    ContextRange::new(
+        to_context_position(range.start),
+        to_context_position(range.end),
+    )
+}
@@ -0,0 +1,452 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Catalog prefetch
+//!
+//! Without a warm-up the first completion pays for the whole schema
+//! introspection. With `catalog.prefetch` set (see
+//! [`crate::config::CatalogConfig`]), the server fetches the table list and,
+//! in `full` mode, the columns of the tables used most recently in the
+//! previous session in the background once it is initialized. Results land
+//! in [`LateCatalogResults`], which completions read first.
+//!
+//! The prefetch yields to interactive requests: it only calls the catalog
+//! while no request tracked by [`RequestActivity`] is running, and each call
+//! is bounded by the configured query timeout. Shutdown aborts it.
+//!
+//! [`RecentTables`] records the tables whose columns completions read and
+//! keeps them across sessions in the user's cache directory.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::Notify;
+use tracing::debug;
+use unified_sql_lsp_catalog::{
+    Catalog, CatalogResult, ColumnMetadata, FunctionMetadata, SampleRows, SequenceMetadata,
+    TableMetadata,
+};
+
+use crate::catalog_manager::LateCatalogResults;
+use crate::config::{CatalogConfig, CatalogPrefetch, EngineConfig, SchemaSource};
+
+/// Interactive requests in flight, which the prefetch waits for
+#[derive(Debug, Default)]
+pub struct RequestActivity {
+    active: AtomicUsize,
+    idle: Notify,
+}
+
+impl RequestActivity {
+    /// Mark a request as running until the returned guard is dropped
+    pub fn begin(self: &Arc<Self>) -> ActiveRequest {
+        self.active.fetch_add(1, Ordering::SeqCst);
+        ActiveRequest {
+            activity: self.clone(),
+        }
+    }
+
+    /// Wait until no request is running
+    pub async fn idle(&self) {
+        loop {
+            // Registered before the check, so a request ending in between
+            // still wakes us
+            let notified = self.idle.notified();
+            if self.active.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// A running request, see [`RequestActivity::begin`]
+#[derive(Debug)]
+pub struct ActiveRequest {
+    activity: Arc<RequestActivity>,
+}
+
+impl Drop for ActiveRequest {
+    fn drop(&mut self) {
+        if self.activity.active.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.activity.idle.notify_waiters();
+        }
+    }
+}
+
+/// Tables whose columns completions read, most recent first
+///
+/// At most [`CatalogConfig::MAX_RECENT_TABLES`] are remembered.
+#[derive(Debug, Default)]
+pub struct RecentTables {
+    tables: Mutex<VecDeque<String>>,
+    /// Where [`Self::save`] writes, set by [`Self::restore`]
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl RecentTables {
+    /// Record a use of `table`
+    pub fn touch(&self, table: &str) {
+        let mut tables = self.tables.lock().unwrap();
+        tables.retain(|t| t != table);
+        tables.push_front(table.to_string());
+        tables.truncate(CatalogConfig::MAX_RECENT_TABLES);
+    }
+
+    /// The `limit` most recently used tables
+    pub fn recent(&self, limit: usize) -> Vec<String> {
+        self.tables
+            .lock()
+            .unwrap()
+            .iter()
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Read the tables a previous session saved at `path`
+    ///
+    /// Tables used in this session stay ahead of them. Later saves go to
+    /// `path`. A missing or invalid file restores nothing.
+    pub fn restore(&self, path: PathBuf) {
+        let saved: Vec<String> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        {
+            let mut tables = self.tables.lock().unwrap();
+            for table in saved {
+                if !tables.contains(&table) {
+                    tables.push_back(table);
+                }
+            }
+            tables.truncate(CatalogConfig::MAX_RECENT_TABLES);
+        }
+        *self.path.lock().unwrap() = Some(path);
+    }
+
+    /// Write the tables where [`Self::restore`] read them
+    ///
+    /// Does nothing before a restore.
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = self.path.lock().unwrap().clone() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tables: Vec<String> = self.tables.lock().unwrap().iter().cloned().collect();
+        std::fs::write(path, serde_json::to_string(&tables)?)
+    }
+
+    /// Wrap `catalog` so the tables whose columns are read through it are
+    /// recorded here
+    pub fn wrap(self: &Arc<Self>, catalog: Arc<dyn Catalog>) -> Arc<dyn Catalog> {
+        Arc::new(RecordingCatalog {
+            inner: catalog,
+            recent: self.clone(),
+        })
+    }
+}
+
+/// Where the recent tables of the schema `config` reads are kept
+///
+/// One file per schema source under the user's cache directory
+/// (`$XDG_CACHE_HOME`, or `~/.cache`), named by a hash of the connection
+/// string or schema paths so no credentials end up in file names. `None`
+/// without a cache directory.
+pub fn recent_tables_path(config: &EngineConfig) -> Option<PathBuf> {
+    let cache = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    let source = match &config.schema_source {
+        SchemaSource::Connection => config.connection_string.clone(),
+        SchemaSource::Ddl { paths } => paths
+            .iter()
+            .map(|path| path.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+    Some(
+        cache
+            .join("unified-sql-lsp")
+            .join(format!("recent-tables-{:016x}.json", fnv1a(&source))),
+    )
+}
+
+/// FNV-1a hash, stable across builds unlike the std hasher
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Catalog wrapper backing [`RecentTables::wrap`]
+struct RecordingCatalog {
+    inner: Arc<dyn Catalog>,
+    recent: Arc<RecentTables>,
+}
+
+#[async_trait]
+impl Catalog for RecordingCatalog {
+    async fn list_tables(&self) -> CatalogResult<Vec<TableMetadata>> {
+        self.inner.list_tables().await
+    }
+
+    async fn get_columns(&self, table: &str) -> CatalogResult<Vec<ColumnMetadata>> {
+        self.get_columns_shared(table)
+            .await
+            .map(|columns| columns.to_vec())
+    }
+
+    async fn get_columns_shared(&self, table: &str) -> CatalogResult<Arc<[ColumnMetadata]>> {
+        let columns = self.inner.get_columns_shared(table).await?;
+        self.recent.touch(table);
+        Ok(columns)
+    }
+
+    async fn list_functions(&self) -> CatalogResult<Vec<FunctionMetadata>> {
+        self.inner.list_functions().await
+    }
+
+    async fn list_roles(&self) -> CatalogResult<Vec<String>> {
+        self.inner.list_roles().await
+    }
+
+    async fn list_collations(&self) -> CatalogResult<Vec<String>> {
+        self.inner.list_collations().await
+    }
+
+    async fn list_sequences(&self) -> CatalogResult<Vec<SequenceMetadata>> {
+        self.inner.list_sequences().await
+    }
+
+    fn loaded_tables(&self) -> Option<Vec<TableMetadata>> {
+        self.inner.loaded_tables()
+    }
+
+    fn has_schema(&self) -> bool {
+        self.inner.has_schema()
+    }
+
+    async fn sample_rows(&self, table: &str, limit: usize) -> CatalogResult<SampleRows> {
+        self.inner.sample_rows(table, limit).await
+    }
+}
+
+/// What a prefetch kept
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrefetchSummary {
+    /// Tables listed
+    pub tables: usize,
+    /// Tables whose columns were fetched
+    pub columns: usize,
+}
+
+/// Keep what `prefetch` asks of `catalog` in `results`
+///
+/// Every catalog call first waits for `activity` to go idle. A table list
+/// that fails or takes longer than `timeout` ends the prefetch, as does a
+/// timed out column list; `recent` tables the catalog doesn't know are
+/// skipped.
+pub async fn prefetch_catalog(
+    catalog: Arc<dyn Catalog>,
+    results: &LateCatalogResults,
+    activity: &RequestActivity,
+    prefetch: CatalogPrefetch,
+    recent: Vec<String>,
+    timeout: Duration,
+) -> PrefetchSummary {
+    let mut summary = PrefetchSummary::default();
+    if prefetch == CatalogPrefetch::Off || !catalog.has_schema() {
+        return summary;
+    }
+
+    activity.idle().await;
+    let tables = match tokio::time::timeout(timeout, catalog.list_tables()).await {
+        Ok(Ok(tables)) => tables,
+        Ok(Err(e)) => {
+            debug!(error = %e, "Prefetching the table list failed");
+            return summary;
+        }
+        Err(_) => {
+            debug!("Prefetching the table list timed out");
+            return summary;
+        }
+    };
+    summary.tables = tables.len();
+    results.keep_tables(&catalog, tables);
+
+    if prefetch != CatalogPrefetch::Full {
+        return summary;
+    }
+    for table in recent {
+        activity.idle().await;
+        match tokio::time::timeout(timeout, catalog.get_columns_shared(&table)).await {
+            Ok(Ok(columns)) => {
+                results.keep_columns(&catalog, &table, columns);
+                summary.columns += 1;
+            }
+            Ok(Err(e)) => debug!(table, error = %e, "Skipping prefetch of a table"),
+            Err(_) => {
+                debug!(table, "Prefetching columns timed out");
+                break;
+            }
+        }
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use unified_sql_lsp_catalog::DataType;
+
+    /// Catalog answering every call after `delay`
+    struct SlowCatalog {
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl Catalog for SlowCatalog {
+        async fn list_tables(&self) -> CatalogResult<Vec<TableMetadata>> {
+            tokio::time::sleep(self.delay).await;
+            Ok(vec![
+                TableMetadata::new("users", "public"),
+                TableMetadata::new("orders", "public"),
+            ])
+        }
+
+        async fn get_columns(&self, table: &str) -> CatalogResult<Vec<ColumnMetadata>> {
+            tokio::time::sleep(self.delay).await;
+            match table {
+                "users" | "orders" => Ok(vec![ColumnMetadata::new("id", DataType::Integer)]),
+                _ => Err(unified_sql_lsp_catalog::CatalogError::TableNotFound(
+                    table.to_string(),
+                    String::new(),
+                )),
+            }
+        }
+
+        async fn list_functions(&self) -> CatalogResult<Vec<FunctionMetadata>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_full_prefetch_serves_reads_from_memory() {
+        let delay = Duration::from_millis(100);
+        let catalog: Arc<dyn Catalog> = Arc::new(SlowCatalog { delay });
+        let results = Arc::new(LateCatalogResults::default());
+        let activity = Arc::new(RequestActivity::default());
+
+        let summary = prefetch_catalog(
+            catalog.clone(),
+            &results,
+            &activity,
+            CatalogPrefetch::Full,
+            vec!["users".to_string(), "gone".to_string()],
+            Duration::from_secs(5),
+        )
+        .await;
+        assert_eq!(
+            summary,
+            PrefetchSummary {
+                tables: 2,
+                columns: 1
+            }
+        );
+
+        let wrapped = results.wrap(catalog, None);
+        let started = Instant::now();
+        assert_eq!(wrapped.list_tables().await.unwrap().len(), 2);
+        assert_eq!(wrapped.get_columns("users").await.unwrap().len(), 1);
+        assert!(started.elapsed() < delay);
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_waits_for_requests_and_timeout() {
+        let catalog: Arc<dyn Catalog> = Arc::new(SlowCatalog {
+            delay: Duration::from_millis(50),
+        });
+        let results = Arc::new(LateCatalogResults::default());
+        let activity = Arc::new(RequestActivity::default());
+
+        // Nothing is fetched while a request runs
+        let request = activity.begin();
+        let prefetch = tokio::spawn({
+            let (catalog, results, activity) = (catalog.clone(), results.clone(), activity.clone());
+            async move {
+                prefetch_catalog(
+                    catalog,
+                    &results,
+                    &activity,
+                    CatalogPrefetch::Tables,
+                    Vec::new(),
+                    Duration::from_secs(5),
+                )
+                .await
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!prefetch.is_finished());
+        drop(request);
+        assert_eq!(prefetch.await.unwrap().tables, 2);
+
+        // A catalog slower than the timeout keeps nothing
+        let results = LateCatalogResults::default();
+        let summary = prefetch_catalog(
+            catalog,
+            &results,
+            &activity,
+            CatalogPrefetch::Full,
+            vec!["users".to_string()],
+            Duration::from_millis(10),
+        )
+        .await;
+        assert_eq!(summary, PrefetchSummary::default());
+    }
+
+    #[test]
+    fn test_recent_tables_restore_and_save() {
+        let path = std::env::temp_dir().join(format!(
+            "unified-sql-lsp-recent-tables-{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&path, r#"["orders", "users"]"#).unwrap();
+
+        let recent = Arc::new(RecentTables::default());
+        recent.touch("users");
+        recent.restore(path.clone());
+        assert_eq!(recent.recent(5), vec!["users", "orders"]);
+        assert_eq!(recent.recent(1), vec!["users"]);
+
+        recent.touch("orders");
+        recent.save().unwrap();
+        let next = RecentTables::default();
+        next.restore(path.clone());
+        assert_eq!(next.recent(5), vec!["orders", "users"]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_recent_tables_path_hides_connection_string() {
+        let config = EngineConfig {
+            connection_string: "postgresql://admin:secret@db/app".to_string(),
+            ..Default::default()
+        };
+        if let Some(path) = recent_tables_path(&config) {
+            assert!(!path.to_string_lossy().contains("secret"));
+            assert_eq!(recent_tables_path(&config), Some(path));
+        }
+    }
+}
@@ -0,0 +1,437 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Rename
+//!
+//! Renames tables, views and CTEs a document defines itself
+//! (`textDocument/rename`). Catalog objects aren't renamed: that would take
+//! a migration, not an edit.
+//!
+//! A CTE is renamed within its statement. A table or view created by the
+//! script (`CREATE TEMP TABLE`, `SELECT ... INTO`) is renamed in every later
+//! statement that uses it, up to a `DROP` of it; see
+//! [`document_symbol_references`] for the exact scope. The rename is refused
+//! when the new name is already used in any of those statements.
+
+use std::collections::HashMap;
+
+use tower_lsp::lsp_types::{Position, Range, TextEdit, Url, WorkspaceEdit};
+
+use crate::table_references::table_references;
+use crate::token_scan::{Token, same_name, tokenize};
+use crate::workspace_symbols::{DefinitionKind, index_document};
+
+/// Why a rename can't be done
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RenameError {
+    #[error("Only tables, views and CTEs defined in this document can be renamed")]
+    NotRenamable,
+
+    #[error("'{0}' is not a valid name")]
+    InvalidName(String),
+
+    #[error("'{0}' is already used where the renamed object is visible")]
+    Conflict(String),
+}
+
+/// Range of the renamable name at `position`, for `textDocument/prepareRename`
+pub fn prepare_rename(uri: &Url, content: &str, position: Position) -> Option<Range> {
+    let symbol = document_symbol_references(uri, content, position)?;
+    symbol
+        .occurrences
+        .into_iter()
+        .map(|occurrence| occurrence.range)
+        .find(|range| range.start <= position && position <= range.end)
+}
+
+/// Edits renaming the symbol at `position` to `new_name`
+///
+/// A quoted occurrence stays quoted with the same delimiters.
+pub fn rename(
+    uri: &Url,
+    content: &str,
+    position: Position,
+    new_name: &str,
+) -> Result<WorkspaceEdit, RenameError> {
+    let symbol =
+        document_symbol_references(uri, content, position).ok_or(RenameError::NotRenamable)?;
+    check_name(&symbol, new_name)?;
+
+    let edits = symbol
+        .occurrences
+        .into_iter()
+        .map(|occurrence| {
+            let new_text = match occurrence.delimiter {
+                Some('[') => format!("[{}]", new_name),
+                Some(delimiter) => format!("{}{}{}", delimiter, new_name, delimiter),
+                None => new_name.to_string(),
+            };
+            TextEdit::new(occurrence.range, new_text)
+        })
+        .collect();
+
+    Ok(WorkspaceEdit {
+        changes: Some(HashMap::from([(uri.clone(), edits)])),
+        ..Default::default()
+    })
+}
+
+/// Refuse names that need quoting or are already in use
+fn check_name(symbol: &DocumentSymbolReferences, new_name: &str) -> Result<(), RenameError> {
+    let valid = new_name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && new_name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if !valid {
+        return Err(RenameError::InvalidName(new_name.to_string()));
+    }
+
+    match symbol
+        .names_in_scope
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(new_name))
+    {
+        Some(name) => Err(RenameError::Conflict(name.clone())),
+        None => Ok(()),
+    }
+}
+
+/// A table, view or CTE defined in a document and the places naming it
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentSymbolReferences {
+    /// Name as defined, without delimiters
+    pub name: String,
+    /// Kind of object
+    pub kind: DefinitionKind,
+    /// The definition and every reference, in document order
+    pub occurrences: Vec<SymbolOccurrence>,
+    /// Other tables, views and CTEs named in the statements the symbol is
+    /// visible in, which a new name must not collide with
+    pub names_in_scope: Vec<String>,
+}
+
+/// One place naming a document symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolOccurrence {
+    /// Range of the name, including the delimiters of a quoted one
+    pub range: Range,
+    /// Opening delimiter of a quoted name
+    pub delimiter: Option<char>,
+}
+
+/// The document symbol named at `position`, with all its occurrences
+///
+/// A CTE is only visible in the statement defining it. A table or view
+/// created by the document is visible from its creating statement through
+/// the later statements, up to and including a `DROP` of it, or up to a
+/// statement creating it again; statements defining a CTE of the same name
+/// don't see it. Returns `None` when the name at `position` isn't defined
+/// by the document where it is used, e.g. a catalog table.
+pub fn document_symbol_references(
+    uri: &Url,
+    content: &str,
+    position: Position,
+) -> Option<DocumentSymbolReferences> {
+    let tokens = tokenize(content);
+    let at = tokens
+        .iter()
+        .position(|t| t.is_identifier() && t.range.start <= position && position <= t.range.end)?;
+    let target = &tokens[at];
+    let statements = statement_spans(&tokens);
+    let current = statements.iter().position(|span| span.contains(&at))?;
+    let statement_of = |range: Range| {
+        statements.iter().position(|span| {
+            tokens[span.clone()]
+                .iter()
+                .any(|t| t.range == range && t.is_identifier())
+        })
+    };
+
+    let definitions: Vec<_> = index_document(uri, content)
+        .into_iter()
+        .filter_map(|definition| Some((statement_of(definition.range)?, definition)))
+        .collect();
+    let defines = |statement: usize, kinds: &[DefinitionKind]| {
+        definitions
+            .iter()
+            .any(|(s, d)| *s == statement && kinds.contains(&d.kind) && same_name(&d.name, target))
+    };
+
+    let (kind, scope) = if defines(current, &[DefinitionKind::Cte]) {
+        (DefinitionKind::Cte, vec![current])
+    } else {
+        let (created, definition) = definitions.iter().rev().find(|(s, d)| {
+            *s <= current && d.kind != DefinitionKind::Cte && same_name(&d.name, target)
+        })?;
+        let mut scope = vec![*created];
+        for statement in created + 1..statements.len() {
+            let span = &tokens[statements[statement].clone()];
+            if defines(statement, &[DefinitionKind::Table, DefinitionKind::View]) {
+                break;
+            }
+            if !defines(statement, &[DefinitionKind::Cte]) {
+                scope.push(statement);
+            }
+            if drops(span, target) {
+                break;
+            }
+        }
+        if !scope.contains(&current) {
+            return None;
+        }
+        (definition.kind, scope)
+    };
+
+    let table_names = table_references(content);
+    let mut occurrences = Vec::new();
+    let mut names_in_scope = Vec::new();
+    for &statement in &scope {
+        let span = statements[statement].clone();
+        for i in span.clone() {
+            let token = &tokens[i];
+            if !token.is_identifier() || !names_table(&tokens, span.start, i, &table_names) {
+                continue;
+            }
+            if same_name(&token.text, target) {
+                occurrences.push(SymbolOccurrence {
+                    range: token.range,
+                    delimiter: token.delimiter,
+                });
+            } else {
+                names_in_scope.push(token.text.clone());
+            }
+        }
+        names_in_scope.extend(
+            definitions
+                .iter()
+                .filter(|(s, d)| *s == statement && !same_name(&d.name, target))
+                .map(|(_, d)| d.name.clone()),
+        );
+    }
+    // Tables the document created earlier are still there
+    names_in_scope.extend(
+        definitions
+            .iter()
+            .filter(|(s, d)| {
+                *s < scope[0] && d.kind != DefinitionKind::Cte && !same_name(&d.name, target)
+            })
+            .map(|(_, d)| d.name.clone()),
+    );
+    names_in_scope.sort();
+    names_in_scope.dedup();
+
+    Some(DocumentSymbolReferences {
+        name: target.text.clone(),
+        kind,
+        occurrences,
+        names_in_scope,
+    })
+}
+
+/// Token index ranges of the `;`-separated statements
+fn statement_spans(tokens: &[Token]) -> Vec<std::ops::Range<usize>> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        if token.is_symbol(';') {
+            spans.push(start..i);
+            start = i + 1;
+        }
+    }
+    spans.push(start..tokens.len());
+    spans
+}
+
+/// Whether the statement of `span` is `DROP TABLE|VIEW` of `target`
+fn drops(span: &[Token], target: &Token) -> bool {
+    span.first().is_some_and(|t| t.is_keyword("DROP"))
+        && span
+            .get(1)
+            .is_some_and(|t| t.is_keyword("TABLE") || t.is_keyword("VIEW"))
+        && span[2..]
+            .iter()
+            .any(|t| t.is_identifier() && same_name(&t.text, target))
+}
+
+/// Whether the identifier at `i` names a table, view or CTE: a FROM or
+/// JOIN table, the table of a DML or DDL statement, a CTE name, or the
+/// qualifier of a column
+///
+/// `start` is the first token of the statement.
+fn names_table(tokens: &[Token], start: usize, i: usize, table_names: &[(String, Range)]) -> bool {
+    let token = &tokens[i];
+    if table_names.iter().any(|(_, range)| *range == token.range) {
+        return true;
+    }
+    let previous = (i > start).then(|| &tokens[i - 1]);
+    let next = tokens.get(i + 1);
+    if previous.is_some_and(|p| p.is_symbol('.')) {
+        return false;
+    }
+    if previous.is_some_and(|p| {
+        [
+            "UPDATE",
+            "INTO",
+            "TABLE",
+            "VIEW",
+            "EXISTS",
+            "WITH",
+            "RECURSIVE",
+        ]
+        .iter()
+        .any(|k| p.is_keyword(k))
+    }) {
+        return true;
+    }
+    // CTE names after the first, and further tables of a DROP
+    if previous.is_some_and(|p| p.is_symbol(','))
+        && (next.is_some_and(|n| n.is_keyword("AS"))
+            || tokens[start..i]
+                .first()
+                .is_some_and(|first| first.is_keyword("DROP")))
+    {
+        return true;
+    }
+    // Qualifier of `name.column`, but not the schema of `FROM schema.table`
+    next.is_some_and(|n| n.is_symbol('.'))
+        && tokens.get(i + 2).is_some_and(Token::is_identifier)
+        && tokens.get(i + 3).is_none_or(|t| !t.is_symbol('.'))
+        && !previous.is_some_and(|p| p.is_keyword("FROM") || p.is_keyword("JOIN"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = "\
+CREATE TEMP TABLE report AS SELECT id, total FROM orders;
+SELECT r.id, report.total FROM report r JOIN users u ON u.id = r.id;
+INSERT INTO archive SELECT * FROM report;
+DROP TABLE report;
+SELECT * FROM report;";
+
+    fn uri() -> Url {
+        Url::parse("file:///workspace/script.sql").unwrap()
+    }
+
+    /// Position of the `nth` occurrence of `needle` in `content`
+    fn position_of(content: &str, needle: &str, nth: usize) -> Position {
+        let offset = content.match_indices(needle).nth(nth).unwrap().0;
+        let before = &content[..offset];
+        let line = before.matches('\n').count() as u32;
+        let column = before.rsplit('\n').next().unwrap().chars().count() as u32;
+        Position::new(line, column)
+    }
+
+    /// Apply the edits of `edit` to `content`
+    fn apply(content: &str, edit: WorkspaceEdit) -> String {
+        let mut lines: Vec<String> = content.lines().map(String::from).collect();
+        let mut edits = edit.changes.unwrap().remove(&uri()).unwrap();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start));
+        for edit in edits {
+            assert_eq!(edit.range.start.line, edit.range.end.line);
+            let line = &mut lines[edit.range.start.line as usize];
+            let start = edit.range.start.character as usize;
+            let end = edit.range.end.character as usize;
+            line.replace_range(start..end, &edit.new_text);
+        }
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_rename_temp_table_in_later_statements() {
+        // From a use in the second statement
+        let edit = rename(
+            &uri(),
+            SCRIPT,
+            position_of(SCRIPT, "report r", 0),
+            "summary",
+        )
+        .unwrap();
+        assert_eq!(
+            apply(SCRIPT, edit),
+            "\
+CREATE TEMP TABLE summary AS SELECT id, total FROM orders;
+SELECT r.id, summary.total FROM summary r JOIN users u ON u.id = r.id;
+INSERT INTO archive SELECT * FROM summary;
+DROP TABLE summary;
+SELECT * FROM report;"
+        );
+
+        // The same edits from the definition
+        let from_definition =
+            rename(&uri(), SCRIPT, position_of(SCRIPT, "report", 0), "summary").unwrap();
+        assert_eq!(apply(SCRIPT, from_definition), {
+            let edit = rename(&uri(), SCRIPT, position_of(SCRIPT, "report", 3), "summary");
+            apply(SCRIPT, edit.unwrap())
+        });
+    }
+
+    #[test]
+    fn test_rename_after_drop_is_not_the_temp_table() {
+        assert_eq!(
+            rename(&uri(), SCRIPT, position_of(SCRIPT, "report", 5), "summary"),
+            Err(RenameError::NotRenamable)
+        );
+        assert_eq!(
+            prepare_rename(&uri(), SCRIPT, position_of(SCRIPT, "orders", 0)),
+            None
+        );
+        assert_eq!(
+            prepare_rename(&uri(), SCRIPT, position_of(SCRIPT, "report r", 0)),
+            Some(Range::new(Position::new(1, 31), Position::new(1, 37)))
+        );
+    }
+
+    #[test]
+    fn test_rename_conflicts_in_any_affected_statement() {
+        let position = position_of(SCRIPT, "report", 0);
+        // Used only in the third statement
+        assert_eq!(
+            rename(&uri(), SCRIPT, position, "ARCHIVE"),
+            Err(RenameError::Conflict("archive".to_string()))
+        );
+        // An alias of the second statement
+        assert_eq!(
+            rename(&uri(), SCRIPT, position, "u"),
+            Err(RenameError::Conflict("u".to_string()))
+        );
+        assert_eq!(
+            rename(&uri(), SCRIPT, position, "bad name"),
+            Err(RenameError::InvalidName("bad name".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_rename_cte_stays_in_its_statement() {
+        let content = "\
+WITH recent AS (SELECT * FROM orders) SELECT recent.id FROM recent;
+SELECT * FROM recent;";
+        let edit = rename(&uri(), content, position_of(content, "recent", 2), "latest").unwrap();
+        assert_eq!(
+            apply(content, edit),
+            "\
+WITH latest AS (SELECT * FROM orders) SELECT latest.id FROM latest;
+SELECT * FROM recent;"
+        );
+    }
+
+    #[test]
+    fn test_cte_shadows_temp_table() {
+        let content = "\
+CREATE TEMP TABLE t1 AS SELECT 1 AS id;
+WITH t1 AS (SELECT 2 AS id) SELECT * FROM t1;
+SELECT * FROM \"t1\";";
+        let edit = rename(&uri(), content, position_of(content, "t1", 0), "t2").unwrap();
+        assert_eq!(
+            apply(content, edit),
+            "\
+CREATE TEMP TABLE t2 AS SELECT 1 AS id;
+WITH t1 AS (SELECT 2 AS id) SELECT * FROM t1;
+SELECT * FROM \"t2\";"
+        );
+    }
+}
@@ -5,18 +5,29 @@
 
 //! Request-level context and service access for LSP handlers.
 
+use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, broadcast};
+use tokio::task::JoinHandle;
+use tower_lsp::lsp_types::Url;
 use unified_sql_lsp_catalog::{Catalog, CatalogResult};
+use unified_sql_lsp_ir::Dialect;
 
-use crate::catalog_manager::CatalogManager;
+use crate::catalog_manager::{CatalogManager, LateCatalogResults};
 use crate::config::EngineConfig;
+use crate::events::ServerEvent;
+use crate::hover::SampleRowsCache;
+use crate::rules::CustomRules;
+use crate::workspace::WorkspaceRoots;
 
 /// Shared request context for resolving config and catalog services.
 #[derive(Clone)]
 pub struct RequestContext {
     config: Arc<RwLock<Option<EngineConfig>>>,
     catalog_manager: Arc<RwLock<CatalogManager>>,
+    custom_rules: Arc<RwLock<Arc<CustomRules>>>,
+    sample_rows_cache: Arc<SampleRowsCache>,
+    workspace_roots: WorkspaceRoots,
 }
 
 impl RequestContext {
@@ -27,9 +38,27 @@ impl RequestContext {
         Self {
             config,
             catalog_manager,
+            custom_rules: Arc::new(RwLock::new(Arc::new(CustomRules::default()))),
+            sample_rows_cache: Arc::new(SampleRowsCache::default()),
+            workspace_roots: WorkspaceRoots::new(),
         }
     }
 
+    /// Cache of table sample rows shown in hovers.
+    pub fn sample_rows_cache(&self) -> Arc<SampleRowsCache> {
+        self.sample_rows_cache.clone()
+    }
+
+    /// Currently loaded custom rules.
+    pub async fn custom_rules(&self) -> Arc<CustomRules> {
+        self.custom_rules.read().await.clone()
+    }
+
+    /// Replace the loaded custom rules.
+    pub async fn set_custom_rules(&self, rules: CustomRules) {
+        *self.custom_rules.write().await = Arc::new(rules);
+    }
+
     /// Return current config or the runtime fallback config.
     pub async fn config_or_fallback(&self) -> EngineConfig {
         match self.config.read().await.clone() {
@@ -38,6 +67,20 @@ impl RequestContext {
         }
     }
 
+    /// Workspace folders served, with their own configs.
+    pub fn workspace_roots(&self) -> WorkspaceRoots {
+        self.workspace_roots.clone()
+    }
+
+    /// Return the config of the workspace root holding `uri`, or the
+    /// client config for documents outside a configured root.
+    pub async fn config_for(&self, uri: &Url) -> EngineConfig {
+        match self.workspace_roots.config_for(uri) {
+            Some(cfg) => cfg,
+            None => self.config_or_fallback().await,
+        }
+    }
+
     /// Resolve a catalog for the given config.
     pub async fn catalog_for_config(
         &self,
@@ -46,10 +89,99 @@ impl RequestContext {
         self.catalog_manager.write().await.get_catalog(config).await
     }
 
+    /// Whether the catalog of `config` is connected or loaded.
+    pub async fn has_catalog(&self, config: &EngineConfig) -> bool {
+        self.catalog_manager.read().await.has_catalog(config)
+    }
+
+    /// Catalog results that arrived after a completion's latency budget.
+    pub async fn late_catalog_results(&self) -> Arc<LateCatalogResults> {
+        self.catalog_manager.read().await.late_results()
+    }
+
+    /// Reload the catalog backed by a DDL schema file, if one is loaded from `path`.
+    pub async fn reload_schema_file(&self, path: &Path) -> CatalogResult<bool> {
+        self.catalog_manager.write().await.reload_ddl_catalog(path)
+    }
+
+    /// Drop cached catalogs so they are recreated on next use.
+    pub async fn refresh_catalogs(&self) {
+        self.catalog_manager.write().await.refresh();
+    }
+
+    /// Drop the sample rows and late catalog results cached for completions
+    /// and hovers whenever the config or the catalog changes.
+    ///
+    /// The task ends when the event bus is dropped.
+    pub fn listen(&self, mut events: broadcast::Receiver<ServerEvent>) -> JoinHandle<()> {
+        let context = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(ServerEvent::ConfigChanged | ServerEvent::CatalogInvalidated { .. })
+                    | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+                context.sample_rows_cache.clear();
+                context.late_catalog_results().await.clear();
+            }
+        })
+    }
+
     /// Resolve both the config and its catalog in one call.
     pub async fn config_and_catalog(&self) -> CatalogResult<(EngineConfig, Arc<dyn Catalog>)> {
         let config = self.config_or_fallback().await;
         let catalog = self.catalog_for_config(&config).await?;
         Ok((config, catalog))
     }
+
+    /// Resolve the config for a document of `dialect` at `uri` and its catalog.
+    ///
+    /// The catalog is routed by the document's dialect, not the configured one.
+    pub async fn config_and_catalog_for_document(
+        &self,
+        uri: &Url,
+        dialect: Dialect,
+    ) -> CatalogResult<(EngineConfig, Arc<dyn Catalog>)> {
+        let config = self.config_for(uri).await.for_dialect(dialect);
+        let catalog = self.catalog_for_config(&config).await?;
+        Ok((config, catalog))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{DialectVersion, SchemaSource};
+
+    #[tokio::test]
+    async fn test_document_of_other_dialect_family_gets_keyword_only_catalog() {
+        let config = EngineConfig::new(
+            Dialect::MySQL,
+            DialectVersion::MySQL80,
+            "mysql://app@localhost:3306/shop",
+        );
+        let context = RequestContext::new(
+            Arc::new(RwLock::new(Some(config))),
+            Arc::new(RwLock::new(CatalogManager::new())),
+        );
+        let uri = Url::parse("file:///reports/monthly.sql").unwrap();
+
+        // A PostgreSQL document can't use the MySQL database
+        let (config, catalog) = context
+            .config_and_catalog_for_document(&uri, Dialect::PostgreSQL)
+            .await
+            .unwrap();
+        assert_eq!(config.dialect, Dialect::PostgreSQL);
+        assert_eq!(config.version, DialectVersion::PostgreSQL16);
+        assert!(config.connection_string.is_empty());
+        assert_eq!(config.schema_source, SchemaSource::Connection);
+        assert!(!catalog.has_schema());
+
+        // A MariaDB document shares the MySQL family and its connection
+        let config = context.config_for(&uri).await.for_dialect(Dialect::MariaDB);
+        assert_eq!(config.connection_string, "mysql://app@localhost:3306/shop");
+    }
 }
@@ -0,0 +1,422 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Custom rules
+//!
+//! Project-specific diagnostics and completion keywords loaded from the
+//! files listed in the `customRules` setting.
+//!
+//! Each file is a tree-sitter query (`.scm`) evaluated against the active
+//! dialect's grammar. Every pattern reports one diagnostic at its `@target`
+//! capture, described by `#set!` properties:
+//!
+//! ```scheme
+//! ((table_name) @target
+//!   (#eq? @target "audit_legacy")
+//!   (#set! message "{target} is deprecated, read from audit_events instead")
+//!   (#set! severity "warning")
+//!   (#set! code "custom/audit-legacy"))
+//! ```
+//!
+//! - `message` (required): `{target}` is replaced with the captured text
+//! - `severity`: `error|warning|info|hint`, defaults to `warning`
+//! - `code`: defaults to `custom/<file stem>`
+//!
+//! Extra completion keywords are declared in comment lines, which the query
+//! parser ignores:
+//!
+//! ```scheme
+//! ; @keywords where_clause: SOUNDS LIKE, REGEXP
+//! ```
+//!
+//! Queries are compiled once per dialect and cached.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use tracing::warn;
+use tree_sitter::{Query, QueryCursor, StreamingIterator, Tree};
+use unified_sql_grammar::language_for_dialect;
+use unified_sql_lsp_context::{CompletionContext, SqlKeyword};
+use unified_sql_lsp_ir::Dialect;
+
+use crate::config::RuleLevel;
+
+/// Source attached to diagnostics reported by custom rules
+const RULE_SOURCE: &str = "unified-sql-lsp (custom rule)";
+
+/// Sort priority of injected keywords, after the built-in ones
+const KEYWORD_PRIORITY: i32 = 90;
+
+/// A rule file as loaded from disk
+#[derive(Debug, Clone)]
+struct RuleFile {
+    path: PathBuf,
+    query: String,
+}
+
+/// A pattern of a compiled rule file
+struct CompiledPattern {
+    message: String,
+    severity: DiagnosticSeverity,
+    code: String,
+}
+
+/// A rule file compiled for one dialect
+struct CompiledRule {
+    query: Query,
+    target: u32,
+    patterns: Vec<CompiledPattern>,
+}
+
+/// Custom rules loaded from the `customRules` setting
+#[derive(Default)]
+pub struct CustomRules {
+    files: Vec<RuleFile>,
+    /// Extra keywords by completion context name
+    keywords: HashMap<String, Vec<String>>,
+    /// Compiled queries per dialect; files that fail to compile are left out
+    compiled: Mutex<HashMap<Dialect, Arc<Vec<CompiledRule>>>>,
+}
+
+impl CustomRules {
+    /// Load rule files and compile them for `dialect`
+    ///
+    /// Returns the rules along with one warning per file that could not be
+    /// read or compiled. Failing files are skipped.
+    pub fn load(paths: &[PathBuf], dialect: Dialect) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut rules = Self::default();
+
+        for path in paths {
+            match std::fs::read_to_string(path) {
+                Ok(query) => rules.add_file(path.clone(), query),
+                Err(e) => warnings.push(format!(
+                    "Failed to read custom rule file {}: {}",
+                    path.display(),
+                    e
+                )),
+            }
+        }
+
+        let (compiled, errors) = rules.compile(dialect);
+        warnings.extend(errors);
+        rules
+            .compiled
+            .lock()
+            .unwrap()
+            .insert(dialect, Arc::new(compiled));
+
+        (rules, warnings)
+    }
+
+    /// Whether no rule files are loaded
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Whether a loaded rule file reports diagnostics with `code`
+    pub fn defines_code(&self, code: &str) -> bool {
+        self.compiled.lock().unwrap().values().any(|rules| {
+            rules
+                .iter()
+                .any(|rule| rule.patterns.iter().any(|p| p.code == code))
+        })
+    }
+
+    /// Extra keywords declared for a completion context
+    pub fn keywords_for(&self, context: &CompletionContext) -> Vec<SqlKeyword> {
+        context_name(context)
+            .and_then(|name| self.keywords.get(name))
+            .map(|labels| {
+                labels
+                    .iter()
+                    .map(|label| SqlKeyword::new(label, Some("Custom keyword"), KEYWORD_PRIORITY))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Evaluate the rules against a parsed document
+    pub fn diagnostics(&self, dialect: Dialect, tree: &Tree, source: &str) -> Vec<Diagnostic> {
+        if self.files.is_empty() {
+            return Vec::new();
+        }
+
+        let mut diagnostics = Vec::new();
+        let mut cursor = QueryCursor::new();
+
+        for rule in self.compiled_for(dialect).iter() {
+            let mut matches = cursor.matches(&rule.query, tree.root_node(), source.as_bytes());
+            while let Some(m) = matches.next() {
+                let pattern = &rule.patterns[m.pattern_index];
+                let Some(capture) = m.captures.iter().find(|c| c.index == rule.target) else {
+                    continue;
+                };
+
+                let node = capture.node;
+                let text = node.utf8_text(source.as_bytes()).unwrap_or_default();
+                diagnostics.push(Diagnostic {
+                    range: Range {
+                        start: Position::new(
+                            node.start_position().row as u32,
+                            node.start_position().column as u32,
+                        ),
+                        end: Position::new(
+                            node.end_position().row as u32,
+                            node.end_position().column as u32,
+                        ),
+                    },
+                    severity: Some(pattern.severity),
+                    code: Some(NumberOrString::String(pattern.code.clone())),
+                    source: Some(RULE_SOURCE.to_string()),
+                    message: pattern.message.replace("{target}", text),
+                    ..Default::default()
+                });
+            }
+        }
+
+        diagnostics
+    }
+
+    fn add_file(&mut self, path: PathBuf, query: String) {
+        for (context, labels) in parse_keyword_directives(&query) {
+            self.keywords.entry(context).or_default().extend(labels);
+        }
+        self.files.push(RuleFile { path, query });
+    }
+
+    /// Compiled rules for `dialect`, compiling on first use
+    fn compiled_for(&self, dialect: Dialect) -> Arc<Vec<CompiledRule>> {
+        if let Some(compiled) = self.compiled.lock().unwrap().get(&dialect) {
+            return compiled.clone();
+        }
+
+        let (compiled, errors) = self.compile(dialect);
+        for error in errors {
+            warn!("{}", error);
+        }
+
+        let compiled = Arc::new(compiled);
+        self.compiled
+            .lock()
+            .unwrap()
+            .insert(dialect, compiled.clone());
+        compiled
+    }
+
+    fn compile(&self, dialect: Dialect) -> (Vec<CompiledRule>, Vec<String>) {
+        let Some(language) = language_for_dialect(dialect) else {
+            return (Vec::new(), Vec::new());
+        };
+
+        let mut compiled = Vec::new();
+        let mut errors = Vec::new();
+        for file in &self.files {
+            match compile_file(language, file) {
+                Ok(rule) => compiled.push(rule),
+                Err(e) => errors.push(format!(
+                    "Invalid custom rule file {}: {}",
+                    file.path.display(),
+                    e
+                )),
+            }
+        }
+
+        (compiled, errors)
+    }
+}
+
+fn compile_file(language: &tree_sitter::Language, file: &RuleFile) -> Result<CompiledRule, String> {
+    let query = Query::new(language, &file.query).map_err(|e| e.to_string())?;
+    let target = query
+        .capture_index_for_name("target")
+        .ok_or_else(|| "query has no @target capture".to_string())?;
+
+    let patterns = (0..query.pattern_count())
+        .map(|index| {
+            let property = |key: &str| {
+                query
+                    .property_settings(index)
+                    .iter()
+                    .find(|p| &*p.key == key)
+                    .and_then(|p| p.value.as_deref())
+            };
+
+            let message = property("message")
+                .ok_or_else(|| format!("pattern {} has no message", index + 1))?;
+            let severity = match property("severity") {
+                None => DiagnosticSeverity::WARNING,
+                Some(level) => match RuleLevel::parse(level) {
+                    Some(RuleLevel::Error) => DiagnosticSeverity::ERROR,
+                    Some(RuleLevel::Warning) => DiagnosticSeverity::WARNING,
+                    Some(RuleLevel::Info) => DiagnosticSeverity::INFORMATION,
+                    Some(RuleLevel::Hint) => DiagnosticSeverity::HINT,
+                    Some(RuleLevel::Off) | None => {
+                        return Err(format!(
+                            "pattern {} has invalid severity {}",
+                            index + 1,
+                            level
+                        ));
+                    }
+                },
+            };
+            let code = property("code")
+                .map(str::to_string)
+                .unwrap_or_else(|| default_code(&file.path));
+
+            Ok(CompiledPattern {
+                message: message.to_string(),
+                severity,
+                code,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(CompiledRule {
+        query,
+        target,
+        patterns,
+    })
+}
+
+/// Code reported by patterns that don't set one
+fn default_code(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    format!("custom/{}", stem)
+}
+
+/// Parse `; @keywords <context>: A, B` comment lines
+fn parse_keyword_directives(query: &str) -> Vec<(String, Vec<String>)> {
+    query
+        .lines()
+        .filter_map(|line| {
+            let directive = line
+                .trim_start()
+                .trim_start_matches(';')
+                .trim_start()
+                .strip_prefix("@keywords")?;
+            let (context, labels) = directive.split_once(':')?;
+            let labels = labels
+                .split(',')
+                .map(str::trim)
+                .filter(|label| !label.is_empty())
+                .map(str::to_string)
+                .collect();
+            Some((context.trim().to_string(), labels))
+        })
+        .collect()
+}
+
+/// Name used to declare keywords for a completion context
+fn context_name(context: &CompletionContext) -> Option<&'static str> {
+    match context {
+        CompletionContext::SelectProjection { .. } => Some("select_projection"),
+        CompletionContext::FromClause { .. } => Some("from_clause"),
+        CompletionContext::WhereClause { .. } => Some("where_clause"),
+        CompletionContext::JoinCondition { .. } => Some("join_condition"),
+        CompletionContext::OrderByClause { .. } => Some("order_by_clause"),
+        CompletionContext::GroupByClause { .. } => Some("group_by_clause"),
+        CompletionContext::LimitClause => Some("limit_clause"),
+        CompletionContext::HavingClause { .. } => Some("having_clause"),
+        CompletionContext::Keywords { .. } => Some("keywords"),
+        CompletionContext::ColumnDefinition { .. } => Some("column_definition"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AUDIT_RULE: &str = r#"; @keywords where_clause: SOUNDS LIKE, REGEXP
+((table_name) @target
+  (#eq? @target "audit_legacy")
+  (#set! message "{target} is deprecated, read from audit_events instead")
+  (#set! severity "warning"))
+"#;
+
+    fn write_rule(name: &str, contents: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("usql-rules-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn parse(dialect: Dialect, source: &str) -> Tree {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(language_for_dialect(dialect).unwrap())
+            .unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    #[test]
+    fn test_rule_flags_table() {
+        let path = write_rule("audit-legacy.scm", AUDIT_RULE);
+        let (rules, warnings) = CustomRules::load(&[path], Dialect::MySQL);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+
+        let source = "SELECT id FROM audit_legacy";
+        let tree = parse(Dialect::MySQL, source);
+        let diagnostics = rules.diagnostics(Dialect::MySQL, &tree, source);
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(
+            diagnostic.message,
+            "audit_legacy is deprecated, read from audit_events instead"
+        );
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(
+            diagnostic.code,
+            Some(NumberOrString::String("custom/audit-legacy".to_string()))
+        );
+        assert_eq!(diagnostic.range.start, Position::new(0, 15));
+        assert!(rules.defines_code("custom/audit-legacy"));
+
+        let source = "SELECT id FROM audit_events";
+        let tree = parse(Dialect::MySQL, source);
+        assert!(rules.diagnostics(Dialect::MySQL, &tree, source).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_query_warns_once() {
+        let path = write_rule("broken.scm", "((not_a_node) @target");
+        let (rules, warnings) = CustomRules::load(&[path], Dialect::MySQL);
+
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("broken.scm"));
+        assert!(!rules.is_empty());
+    }
+
+    #[test]
+    fn test_keyword_directives() {
+        let mut rules = CustomRules::default();
+        rules.add_file(PathBuf::from("audit-legacy.scm"), AUDIT_RULE.to_string());
+
+        let context = CompletionContext::WhereClause {
+            tables: Vec::new(),
+            qualifier: None,
+        };
+        let labels: Vec<_> = rules
+            .keywords_for(&context)
+            .into_iter()
+            .map(|k| k.label)
+            .collect();
+        assert_eq!(labels, vec!["SOUNDS LIKE", "REGEXP"]);
+        assert!(
+            rules
+                .keywords_for(&CompletionContext::LimitClause)
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,440 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Schema diff
+//!
+//! Compares the tables declared in the configured DDL schema files with the
+//! connected database, for the `unifiedSqlLsp.schemaDiff` command.
+//!
+//! Tables and columns are matched by name, ignoring case; schemas are not
+//! compared. Column types are compared as parsed [`DataType`]s, so `int(11)`
+//! and `INT` are the same type, and a length known on only one side is not a
+//! difference.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serde::Serialize;
+use unified_sql_lsp_catalog::{
+    Catalog, CatalogResult, ColumnMetadata, DataType, TableMetadata, format_data_type,
+};
+
+/// Differences kept by the `unifiedSqlLsp.schemaDiff` command
+pub const SCHEMA_DIFF_LIMIT: usize = 100;
+
+/// Differences between the schema files and the database
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaDiff {
+    /// Tables declared in the schema files but missing from the database
+    pub only_in_ddl: Vec<String>,
+
+    /// Tables in the database that no schema file declares
+    pub only_in_database: Vec<String>,
+
+    /// Column differences in tables found on both sides
+    pub columns: Vec<ColumnDifference>,
+
+    /// Differences left out by [`SchemaDiff::truncate`]
+    pub truncated: usize,
+}
+
+/// A column that differs between the schema files and the database
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnDifference {
+    /// Table name as declared in the schema files
+    pub table: String,
+
+    /// Column name, from whichever side has it
+    pub column: String,
+
+    /// How the column differs
+    #[serde(flatten)]
+    pub kind: ColumnDifferenceKind,
+}
+
+/// How a column differs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ColumnDifferenceKind {
+    /// Declared in the schema files but missing from the database
+    MissingInDatabase,
+
+    /// In the database but not declared in the schema files
+    MissingInDdl,
+
+    /// Types differ; both are formatted with [`format_data_type`]
+    TypeMismatch { ddl: String, database: String },
+
+    /// Nullability differs; `true` means nullable
+    NullabilityMismatch { ddl: bool, database: bool },
+}
+
+impl SchemaDiff {
+    /// Number of differences kept
+    pub fn len(&self) -> usize {
+        self.only_in_ddl.len() + self.only_in_database.len() + self.columns.len()
+    }
+
+    /// Whether both sides match, ignoring truncated differences
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Keep at most `limit` differences, counting the rest in `truncated`
+    ///
+    /// Missing tables are kept before column differences.
+    pub fn truncate(&mut self, limit: usize) {
+        fn keep<T>(items: &mut Vec<T>, remaining: &mut usize) -> usize {
+            let kept = items.len().min(*remaining);
+            let dropped = items.len() - kept;
+            items.truncate(kept);
+            *remaining -= kept;
+            dropped
+        }
+
+        let mut remaining = limit;
+        self.truncated += keep(&mut self.only_in_ddl, &mut remaining);
+        self.truncated += keep(&mut self.only_in_database, &mut remaining);
+        self.truncated += keep(&mut self.columns, &mut remaining);
+    }
+
+    /// Markdown summary for showing in the client
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("## Schema diff\n");
+        if self.is_empty() && self.truncated == 0 {
+            markdown.push_str("\nThe schema files match the database.\n");
+            return markdown;
+        }
+
+        for (title, tables) in [
+            ("Only in schema files", &self.only_in_ddl),
+            ("Only in database", &self.only_in_database),
+        ] {
+            if tables.is_empty() {
+                continue;
+            }
+            let _ = writeln!(markdown, "\n**{}** ({})\n", title, tables.len());
+            for table in tables {
+                let _ = writeln!(markdown, "- `{}`", table);
+            }
+        }
+
+        if !self.columns.is_empty() {
+            let _ = writeln!(
+                markdown,
+                "\n**Column differences** ({})\n\n| Table | Column | Difference |\n|---|---|---|",
+                self.columns.len()
+            );
+            for difference in &self.columns {
+                let _ = writeln!(
+                    markdown,
+                    "| `{}` | `{}` | {} |",
+                    difference.table,
+                    difference.column,
+                    difference.kind.describe()
+                );
+            }
+        }
+
+        if self.truncated > 0 {
+            let _ = writeln!(
+                markdown,
+                "\n…and {} more differences not shown.",
+                self.truncated
+            );
+        }
+        markdown
+    }
+}
+
+impl ColumnDifferenceKind {
+    fn describe(&self) -> String {
+        let nullability = |nullable: bool| if nullable { "NULL" } else { "NOT NULL" };
+        match self {
+            ColumnDifferenceKind::MissingInDatabase => "missing in database".to_string(),
+            ColumnDifferenceKind::MissingInDdl => "missing in schema files".to_string(),
+            ColumnDifferenceKind::TypeMismatch { ddl, database } => {
+                format!("type {} in schema files, {} in database", ddl, database)
+            }
+            ColumnDifferenceKind::NullabilityMismatch { ddl, database } => format!(
+                "{} in schema files, {} in database",
+                nullability(*ddl),
+                nullability(*database)
+            ),
+        }
+    }
+}
+
+/// Compare the tables of a DDL catalog with those of a live database
+///
+/// Differences are listed in table name order, then in the column order of
+/// the schema files, with columns only in the database last.
+pub async fn diff_catalogs(ddl: &dyn Catalog, database: &dyn Catalog) -> CatalogResult<SchemaDiff> {
+    let ddl_tables = tables_by_name(ddl.list_tables().await?);
+    let mut database_tables = tables_by_name(database.list_tables().await?);
+    let mut diff = SchemaDiff::default();
+
+    for (key, ddl_table) in ddl_tables {
+        let Some(database_table) = database_tables.remove(&key) else {
+            diff.only_in_ddl.push(ddl_table.name);
+            continue;
+        };
+        let ddl_columns = ddl.get_columns(&ddl_table.name).await?;
+        let database_columns = database.get_columns(&database_table.name).await?;
+        diff_columns(
+            &ddl_table.name,
+            &ddl_columns,
+            &database_columns,
+            &mut diff.columns,
+        );
+    }
+    diff.only_in_database = database_tables
+        .into_values()
+        .map(|table| table.name)
+        .collect();
+
+    Ok(diff)
+}
+
+fn tables_by_name(tables: Vec<TableMetadata>) -> BTreeMap<String, TableMetadata> {
+    tables
+        .into_iter()
+        .map(|table| (table.name.to_lowercase(), table))
+        .collect()
+}
+
+fn diff_columns(
+    table: &str,
+    ddl: &[ColumnMetadata],
+    database: &[ColumnMetadata],
+    differences: &mut Vec<ColumnDifference>,
+) {
+    let find = |columns: &'_ [ColumnMetadata], name: &str| {
+        columns
+            .iter()
+            .find(|column| column.name.eq_ignore_ascii_case(name))
+            .cloned()
+    };
+    let mut push = |column: &str, kind| {
+        differences.push(ColumnDifference {
+            table: table.to_string(),
+            column: column.to_string(),
+            kind,
+        })
+    };
+
+    for column in ddl {
+        let Some(live) = find(database, &column.name) else {
+            push(&column.name, ColumnDifferenceKind::MissingInDatabase);
+            continue;
+        };
+        if !same_type(&column.data_type, &live.data_type) {
+            push(
+                &column.name,
+                ColumnDifferenceKind::TypeMismatch {
+                    ddl: format_data_type(&column.data_type),
+                    database: format_data_type(&live.data_type),
+                },
+            );
+        }
+        if column.nullable != live.nullable {
+            push(
+                &column.name,
+                ColumnDifferenceKind::NullabilityMismatch {
+                    ddl: column.nullable,
+                    database: live.nullable,
+                },
+            );
+        }
+    }
+    for column in database {
+        if find(ddl, &column.name).is_none() {
+            push(&column.name, ColumnDifferenceKind::MissingInDdl);
+        }
+    }
+}
+
+/// Whether two types are the same, treating an unknown length as any length
+fn same_type(ddl: &DataType, database: &DataType) -> bool {
+    let same_length = |a: &Option<usize>, b: &Option<usize>| a.is_none() || b.is_none() || a == b;
+    match (ddl, database) {
+        (DataType::Varchar(a), DataType::Varchar(b))
+        | (DataType::Char(a), DataType::Char(b))
+        | (DataType::VarBinary(a), DataType::VarBinary(b)) => same_length(a, b),
+        (DataType::Array(a), DataType::Array(b)) => same_type(a, b),
+        (DataType::Other(a), DataType::Other(b)) => {
+            let normalize = |name: &str| {
+                name.split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .to_lowercase()
+            };
+            normalize(a) == normalize(b)
+        }
+        _ => ddl == database,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unified_sql_lsp_catalog::DdlCatalog;
+    use unified_sql_lsp_ir::Dialect;
+    use unified_sql_lsp_test_utils::MockCatalog;
+
+    const SCHEMA: &str = "
+        CREATE TABLE users (
+            id int(11) NOT NULL,
+            email varchar(255) NOT NULL,
+            name varchar(100),
+            age int
+        );
+        CREATE TABLE orders (
+            id bigint NOT NULL,
+            user_id int NOT NULL
+        );
+        CREATE TABLE audit_log (id int NOT NULL);
+    ";
+
+    fn database() -> MockCatalog {
+        // Seeded differences: audit_log is missing, users.name is NOT NULL
+        // and users.age is a BIGINT
+        MockCatalog::new()
+            .add_table(TableMetadata::new("users", "app").with_columns(vec![
+                ColumnMetadata::new("id", DataType::parse("INT")).with_nullable(false),
+                ColumnMetadata::new("email", DataType::Varchar(None)).with_nullable(false),
+                ColumnMetadata::new("name", DataType::parse("varchar(100)")).with_nullable(false),
+                ColumnMetadata::new("age", DataType::BigInt).with_nullable(true),
+            ]))
+            .add_table(TableMetadata::new("Orders", "app").with_columns(vec![
+                ColumnMetadata::new("id", DataType::parse("bigint(20)")).with_nullable(false),
+                ColumnMetadata::new("USER_ID", DataType::Integer).with_nullable(false),
+            ]))
+    }
+
+    #[tokio::test]
+    async fn test_diff_against_ddl_fixture() {
+        let ddl = DdlCatalog::from_sql(SCHEMA, Dialect::MySQL);
+        let diff = diff_catalogs(&ddl, &database()).await.unwrap();
+
+        assert_eq!(diff.only_in_ddl, vec!["audit_log".to_string()]);
+        assert!(diff.only_in_database.is_empty());
+        assert_eq!(
+            diff.columns,
+            vec![
+                ColumnDifference {
+                    table: "users".to_string(),
+                    column: "name".to_string(),
+                    kind: ColumnDifferenceKind::NullabilityMismatch {
+                        ddl: true,
+                        database: false,
+                    },
+                },
+                ColumnDifference {
+                    table: "users".to_string(),
+                    column: "age".to_string(),
+                    kind: ColumnDifferenceKind::TypeMismatch {
+                        ddl: "Integer".to_string(),
+                        database: "BigInt".to_string(),
+                    },
+                },
+            ]
+        );
+
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["onlyInDdl"], serde_json::json!(["audit_log"]));
+        assert_eq!(json["columns"][1]["kind"], "typeMismatch");
+        assert_eq!(json["columns"][1]["database"], "BigInt");
+
+        let markdown = diff.to_markdown();
+        assert!(markdown.contains("**Only in schema files** (1)\n\n- `audit_log`"));
+        assert!(
+            markdown
+                .contains("| `users` | `age` | type Integer in schema files, BigInt in database |")
+        );
+        assert!(
+            markdown.contains("| `users` | `name` | NULL in schema files, NOT NULL in database |")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_diff_missing_columns_and_tables() {
+        let ddl = MockCatalog::new().add_table(
+            TableMetadata::new("users", "app")
+                .with_columns(vec![ColumnMetadata::new("id", DataType::Integer)]),
+        );
+        let database = MockCatalog::new()
+            .add_table(
+                TableMetadata::new("users", "app")
+                    .with_columns(vec![ColumnMetadata::new("created_at", DataType::Timestamp)]),
+            )
+            .add_table(TableMetadata::new("sessions", "app"));
+
+        let diff = diff_catalogs(&ddl, &database).await.unwrap();
+        assert_eq!(diff.only_in_database, vec!["sessions".to_string()]);
+        let kinds: Vec<_> = diff
+            .columns
+            .iter()
+            .map(|d| (d.column.as_str(), d.kind.clone()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("id", ColumnDifferenceKind::MissingInDatabase),
+                ("created_at", ColumnDifferenceKind::MissingInDdl),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_truncate_counts_dropped_differences() {
+        let mut diff = SchemaDiff {
+            only_in_ddl: vec!["a".to_string(), "b".to_string()],
+            only_in_database: vec!["c".to_string()],
+            columns: vec![ColumnDifference {
+                table: "t".to_string(),
+                column: "x".to_string(),
+                kind: ColumnDifferenceKind::MissingInDdl,
+            }],
+            truncated: 0,
+        };
+        diff.truncate(3);
+
+        assert_eq!(diff.len(), 3);
+        assert_eq!(diff.truncated, 1);
+        assert!(diff.columns.is_empty());
+        assert!(
+            diff.to_markdown()
+                .ends_with("…and 1 more differences not shown.\n")
+        );
+
+        assert_eq!(
+            SchemaDiff::default().to_markdown(),
+            "## Schema diff\n\nThe schema files match the database.\n"
+        );
+    }
+
+    #[test]
+    fn test_same_type_normalizes_spellings() {
+        assert!(same_type(
+            &DataType::parse("int(11)"),
+            &DataType::parse("INT")
+        ));
+        assert!(same_type(
+            &DataType::parse("varchar(255)"),
+            &DataType::Varchar(None)
+        ));
+        assert!(!same_type(
+            &DataType::parse("varchar(255)"),
+            &DataType::parse("varchar(64)")
+        ));
+        assert!(same_type(
+            &DataType::parse("geometry"),
+            &DataType::parse("GEOMETRY")
+        ));
+    }
+}
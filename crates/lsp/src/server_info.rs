@@ -0,0 +1,215 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Server info request
+//!
+//! Editor plugins send a `unifiedSqlLsp/serverInfo` request (no params) to
+//! learn what this server build offers, e.g. to hide UI for features that
+//! weren't compiled in. The result is a [`ServerInfoResult`]:
+//!
+//! ```json
+//! {
+//!   "version": "0.1.0",
+//!   "features": ["catalog", "lsp", "parser"],
+//!   "supportedAbiVersions": { "min": 13, "max": 15 },
+//!   "dialects": [
+//!     { "grammar": "mysql-5.7", "dialect": "MySQL", "abiVersion": 14,
+//!       "nodeKindCount": 512, "compatible": true }
+//!   ],
+//!   "catalog": { "kind": "live", "connected": false },
+//!   "commands": ["unified-sql-lsp.refreshCatalog"]
+//! }
+//! ```
+//!
+//! Fields are only ever added to this schema, never renamed or removed.
+
+use serde::{Deserialize, Serialize};
+use unified_sql_grammar::{SUPPORTED_ABI_VERSIONS, grammar_info};
+use unified_sql_lsp_ir::Dialect;
+
+use crate::config::{EngineConfig, SchemaSource};
+
+/// Custom request describing the server build
+pub const SERVER_INFO_METHOD: &str = "unifiedSqlLsp/serverInfo";
+
+/// Result of the `unifiedSqlLsp/serverInfo` request
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerInfoResult {
+    /// Server crate version
+    pub version: String,
+
+    /// Cargo features the server was compiled with, sorted
+    pub features: Vec<String>,
+
+    /// Tree-sitter ABI versions the linked runtime can load
+    pub supported_abi_versions: AbiRange,
+
+    /// Compiled grammars
+    pub dialects: Vec<DialectInfo>,
+
+    /// Catalog of the client configuration
+    pub catalog: CatalogInfo,
+
+    /// Commands accepted by `workspace/executeCommand`
+    pub commands: Vec<String>,
+}
+
+/// Inclusive range of tree-sitter ABI versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AbiRange {
+    pub min: usize,
+    pub max: usize,
+}
+
+/// A compiled grammar
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DialectInfo {
+    /// Grammar name, e.g. `"mysql-5.7"`
+    pub grammar: String,
+
+    pub dialect: Dialect,
+
+    /// Tree-sitter ABI version the grammar was generated for
+    pub abi_version: usize,
+
+    /// Number of node kinds the grammar defines
+    pub node_kind_count: usize,
+
+    /// Whether the runtime can load the grammar
+    pub compatible: bool,
+}
+
+/// Where catalog metadata comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CatalogKind {
+    /// No connection string nor schema files: keyword-only completion
+    None,
+
+    /// Live database connection
+    Live,
+
+    /// DDL schema files
+    Ddl,
+}
+
+impl CatalogKind {
+    /// Kind of catalog `config` resolves to
+    pub fn of(config: &EngineConfig) -> Self {
+        match config.schema_source {
+            SchemaSource::Ddl { .. } => CatalogKind::Ddl,
+            SchemaSource::Connection if config.connection_string.is_empty() => CatalogKind::None,
+            SchemaSource::Connection => CatalogKind::Live,
+        }
+    }
+}
+
+/// Catalog of the client configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CatalogInfo {
+    pub kind: CatalogKind,
+
+    /// Whether the catalog is connected or its schema files are loaded;
+    /// always `false` for [`CatalogKind::None`]
+    pub connected: bool,
+}
+
+/// Cargo features of this crate enabled in the build
+pub fn compiled_features() -> Vec<String> {
+    [
+        ("catalog", cfg!(feature = "catalog")),
+        ("lsp", cfg!(feature = "lsp")),
+        ("parser", cfg!(feature = "parser")),
+        ("wasm", cfg!(feature = "wasm")),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(feature, _)| feature.to_string())
+    .collect()
+}
+
+/// Every compiled grammar, as reported by [`grammar_info`]
+pub fn compiled_dialects() -> Vec<DialectInfo> {
+    grammar_info()
+        .into_iter()
+        .map(|grammar| DialectInfo {
+            grammar: grammar.name.to_string(),
+            dialect: grammar.dialect,
+            abi_version: grammar.abi_version,
+            node_kind_count: grammar.node_kind_count,
+            compatible: grammar.compatible,
+        })
+        .collect()
+}
+
+/// Tree-sitter ABI versions the linked runtime can load
+pub fn supported_abi_versions() -> AbiRange {
+    AbiRange {
+        min: *SUPPORTED_ABI_VERSIONS.start(),
+        max: *SUPPORTED_ABI_VERSIONS.end(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_catalog_kind_of_config() {
+        let mut config = EngineConfig::default_runtime_fallback();
+        config.connection_string.clear();
+        assert_eq!(CatalogKind::of(&config), CatalogKind::None);
+
+        config.connection_string = "mysql://localhost/app".to_string();
+        assert_eq!(CatalogKind::of(&config), CatalogKind::Live);
+
+        config.schema_source = SchemaSource::Ddl {
+            paths: vec!["schema.sql".into()],
+        };
+        assert_eq!(CatalogKind::of(&config), CatalogKind::Ddl);
+    }
+
+    #[test]
+    fn test_schema_field_names() {
+        let info = ServerInfoResult {
+            version: "0.1.0".to_string(),
+            features: compiled_features(),
+            supported_abi_versions: AbiRange { min: 13, max: 15 },
+            dialects: vec![DialectInfo {
+                grammar: "mysql-5.7".to_string(),
+                dialect: Dialect::MySQL,
+                abi_version: 14,
+                node_kind_count: 10,
+                compatible: true,
+            }],
+            catalog: CatalogInfo {
+                kind: CatalogKind::None,
+                connected: false,
+            },
+            commands: vec![],
+        };
+
+        assert_eq!(
+            serde_json::to_value(&info).unwrap(),
+            json!({
+                "version": "0.1.0",
+                "features": ["catalog", "lsp", "parser"],
+                "supportedAbiVersions": { "min": 13, "max": 15 },
+                "dialects": [{
+                    "grammar": "mysql-5.7",
+                    "dialect": "MySQL",
+                    "abiVersion": 14,
+                    "nodeKindCount": 10,
+                    "compatible": true
+                }],
+                "catalog": { "kind": "none", "connected": false },
+                "commands": []
+            })
+        );
+    }
+}
@@ -0,0 +1,457 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Status notifications
+//!
+//! The server sends a `unifiedSqlLsp/status` notification ([`StatusParams`])
+//! describing the active document, so editors can show a status line item
+//! like "SQL: MySQL 8.0 • schema 142 tables • parse 3ms".
+//!
+//! A status is sent:
+//! - after a document is opened or changed; it becomes the active document
+//! - after diagnostics are published for the active document
+//! - after the config changes or catalog metadata is reloaded or dropped,
+//!   with `catalogStale` set until the tables are counted again
+//!
+//! [`StatusReporter`] sends at most one notification per [`STATUS_INTERVAL`].
+//! Statuses arriving within the interval replace each other and the latest
+//! one is sent when the interval ends. A status equal to the one last sent
+//! is not sent again.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tower_lsp::Client;
+use tower_lsp::lsp_types::notification::Notification;
+use tower_lsp::lsp_types::{Diagnostic, Url};
+use tracing::debug;
+use unified_sql_lsp_catalog::Catalog;
+use unified_sql_lsp_ir::Dialect;
+
+use crate::diagnostics_coalescer::DiagnosticsSink;
+use crate::events::ServerEvent;
+
+/// Custom notification describing the active document
+pub const STATUS_METHOD: &str = "unifiedSqlLsp/status";
+
+/// Minimum time between two status notifications
+pub const STATUS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The `unifiedSqlLsp/status` notification
+pub enum StatusNotification {}
+
+impl Notification for StatusNotification {
+    type Params = StatusParams;
+    const METHOD: &'static str = STATUS_METHOD;
+}
+
+/// Parameters of the `unifiedSqlLsp/status` notification
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusParams {
+    /// Active document
+    pub uri: Url,
+
+    /// Dialect the document is parsed with
+    pub dialect: Dialect,
+
+    /// Configured version of the dialect, e.g. `"8.0"`
+    pub version: String,
+
+    /// Tree-sitter ABI version of the grammar the document is parsed with;
+    /// `None` when no compatible grammar is loaded for the dialect
+    pub grammar_abi_version: Option<usize>,
+
+    /// Duration of the last parse; `None` until the document has a tree,
+    /// and zero for trees with syntax errors
+    pub parse_time_ms: Option<u64>,
+
+    /// Diagnostics last published for the document
+    pub diagnostics: usize,
+
+    /// Tables in the document's catalog; `None` until they are counted
+    pub catalog_tables: Option<usize>,
+
+    /// Whether catalog metadata changed since the tables were counted
+    pub catalog_stale: bool,
+}
+
+/// What the backend knows about the active document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentStatus {
+    pub uri: Url,
+    pub dialect: Dialect,
+    pub version: String,
+    pub grammar_abi_version: Option<usize>,
+    pub parse_time_ms: Option<u64>,
+}
+
+/// Delivers a status notification to the client
+#[tower_lsp::async_trait]
+pub trait StatusSink: Send + Sync {
+    async fn send(&self, status: StatusParams);
+}
+
+#[tower_lsp::async_trait]
+impl StatusSink for Client {
+    async fn send(&self, status: StatusParams) {
+        self.send_notification::<StatusNotification>(status).await;
+    }
+}
+
+/// Outbound status state
+#[derive(Default)]
+struct State {
+    /// Document of the last status reported
+    active: Option<DocumentStatus>,
+    /// Diagnostics count last published per document
+    diagnostics: HashMap<Url, usize>,
+    catalog_tables: Option<usize>,
+    catalog_stale: bool,
+    /// Whether a table count is running
+    counting: bool,
+    /// Whether a count was attempted since catalog metadata last changed
+    counted: bool,
+    last_sent: Option<StatusParams>,
+    last_sent_at: Option<Instant>,
+    /// Latest status waiting for the interval to end
+    pending: Option<StatusParams>,
+    /// Whether a delayed flush is already scheduled
+    flush_scheduled: bool,
+}
+
+impl State {
+    /// Status of the active document, if there is one
+    fn status(&self) -> Option<StatusParams> {
+        let active = self.active.as_ref()?;
+        Some(StatusParams {
+            uri: active.uri.clone(),
+            dialect: active.dialect,
+            version: active.version.clone(),
+            grammar_abi_version: active.grammar_abi_version,
+            parse_time_ms: active.parse_time_ms,
+            diagnostics: self.diagnostics.get(&active.uri).copied().unwrap_or(0),
+            catalog_tables: self.catalog_tables,
+            catalog_stale: self.catalog_stale,
+        })
+    }
+}
+
+/// Aggregates document, diagnostics and catalog state into rate limited
+/// status notifications
+///
+/// Cloning is cheap and clones share state.
+#[derive(Clone)]
+pub struct StatusReporter {
+    sink: Arc<dyn StatusSink>,
+    state: Arc<Mutex<State>>,
+    interval: Duration,
+}
+
+impl StatusReporter {
+    pub fn new(sink: Arc<dyn StatusSink>) -> Self {
+        Self {
+            sink,
+            state: Arc::new(Mutex::new(State::default())),
+            interval: STATUS_INTERVAL,
+        }
+    }
+
+    /// Set the minimum time between two notifications
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Make `document` the active document and report its status
+    pub async fn report(&self, document: DocumentStatus) {
+        self.state.lock().unwrap().active = Some(document);
+        self.send().await;
+    }
+
+    /// Record diagnostics published for a document
+    ///
+    /// Reports a new status when `uri` is the active document.
+    pub async fn diagnostics_published(&self, uri: &Url, count: usize) {
+        let active = {
+            let mut state = self.state.lock().unwrap();
+            state.diagnostics.insert(uri.clone(), count);
+            state
+                .active
+                .as_ref()
+                .is_some_and(|active| active.uri == *uri)
+        };
+        if active {
+            self.send().await;
+        }
+    }
+
+    /// Forget a closed document
+    pub fn forget(&self, uri: &Url) {
+        let mut state = self.state.lock().unwrap();
+        state.diagnostics.remove(uri);
+        if state
+            .active
+            .as_ref()
+            .is_some_and(|active| active.uri == *uri)
+        {
+            state.active = None;
+        }
+    }
+
+    /// Mark the table count stale and report it
+    pub async fn catalog_changed(&self) {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.catalog_stale = true;
+            state.counted = false;
+        }
+        self.send().await;
+    }
+
+    /// Claim the next table count
+    ///
+    /// Returns `true` once after each catalog change, and initially; the
+    /// caller should then call [`count_tables`](Self::count_tables) or
+    /// [`count_failed`](Self::count_failed).
+    pub fn begin_catalog_count(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.counted || state.counting {
+            return false;
+        }
+        state.counting = true;
+        true
+    }
+
+    /// Count the tables of `catalog` and report the result
+    ///
+    /// A failed count keeps the previous one, still marked stale. It isn't
+    /// retried until the catalog changes again.
+    pub async fn count_tables(&self, catalog: Arc<dyn Catalog>) {
+        let counted = catalog.list_tables().await;
+        {
+            let mut state = self.state.lock().unwrap();
+            state.counting = false;
+            state.counted = true;
+            match counted {
+                Ok(tables) => {
+                    state.catalog_tables = Some(tables.len());
+                    state.catalog_stale = false;
+                }
+                Err(e) => debug!("Failed to count catalog tables: {}", e),
+            }
+        }
+        self.send().await;
+    }
+
+    /// Record that a claimed count couldn't start
+    pub fn count_failed(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.counting = false;
+        state.counted = true;
+    }
+
+    /// Mark the table count stale whenever the config or catalog metadata
+    /// changes, and forget closed documents
+    ///
+    /// The task ends when the event bus is dropped.
+    pub fn listen(&self, mut events: broadcast::Receiver<ServerEvent>) -> JoinHandle<()> {
+        let reporter = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(ServerEvent::DocumentClosed { uri }) => reporter.forget(&uri),
+                    Ok(ServerEvent::DocumentChanged { .. }) => {}
+                    Ok(ServerEvent::ConfigChanged | ServerEvent::CatalogInvalidated { .. })
+                    | Err(broadcast::error::RecvError::Lagged(_)) => {
+                        reporter.catalog_changed().await
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// Send the current status, at most once per interval
+    async fn send(&self) {
+        let delay = {
+            let mut state = self.state.lock().unwrap();
+            let Some(status) = state.status() else {
+                return;
+            };
+            state.pending = Some(status);
+            if state.flush_scheduled {
+                return;
+            }
+
+            match state.last_sent_at.map(|at| at.elapsed()) {
+                Some(elapsed) if elapsed < self.interval => {
+                    state.flush_scheduled = true;
+                    self.interval - elapsed
+                }
+                _ => Duration::ZERO,
+            }
+        };
+
+        if delay.is_zero() {
+            self.flush().await;
+        } else {
+            let reporter = self.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                reporter.flush().await;
+            });
+        }
+    }
+
+    /// Send the pending status unless it repeats the last one
+    async fn flush(&self) {
+        let status = {
+            let mut state = self.state.lock().unwrap();
+            state.flush_scheduled = false;
+            let Some(status) = state.pending.take() else {
+                return;
+            };
+            if state.last_sent.as_ref() == Some(&status) {
+                return;
+            }
+            state.last_sent = Some(status.clone());
+            state.last_sent_at = Some(Instant::now());
+            status
+        };
+
+        self.sink.send(status).await;
+    }
+}
+
+/// Diagnostics sink that keeps the published counts for the status
+pub struct StatusDiagnosticsSink {
+    inner: Arc<dyn DiagnosticsSink>,
+    status: StatusReporter,
+}
+
+impl StatusDiagnosticsSink {
+    pub fn new(inner: Arc<dyn DiagnosticsSink>, status: StatusReporter) -> Self {
+        Self { inner, status }
+    }
+}
+
+#[tower_lsp::async_trait]
+impl DiagnosticsSink for StatusDiagnosticsSink {
+    async fn send(&self, uri: Url, diagnostics: Vec<Diagnostic>, version: Option<i32>) {
+        let count = diagnostics.len();
+        self.inner.send(uri.clone(), diagnostics, version).await;
+        self.status.diagnostics_published(&uri, count).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unified_sql_lsp_catalog::NullCatalog;
+
+    /// Records every notification instead of sending it
+    #[derive(Default)]
+    struct RecordingSink {
+        sent: Mutex<Vec<StatusParams>>,
+    }
+
+    #[tower_lsp::async_trait]
+    impl StatusSink for RecordingSink {
+        async fn send(&self, status: StatusParams) {
+            self.sent.lock().unwrap().push(status);
+        }
+    }
+
+    fn document(parse_time_ms: u64) -> DocumentStatus {
+        DocumentStatus {
+            uri: Url::parse("file:///tmp/status.sql").unwrap(),
+            dialect: Dialect::MySQL,
+            version: "8.0".to_string(),
+            grammar_abi_version: Some(15),
+            parse_time_ms: Some(parse_time_ms),
+        }
+    }
+
+    fn reporter(interval: Duration) -> (StatusReporter, Arc<RecordingSink>) {
+        let sink = Arc::new(RecordingSink::default());
+        (
+            StatusReporter::new(sink.clone()).with_interval(interval),
+            sink,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_status_combines_document_diagnostics_and_catalog() {
+        let (reporter, sink) = reporter(Duration::ZERO);
+        let uri = document(0).uri;
+
+        reporter.diagnostics_published(&uri, 2).await;
+        assert!(reporter.begin_catalog_count());
+        reporter.count_tables(Arc::new(NullCatalog)).await;
+        reporter.report(document(3)).await;
+
+        let sent = sink.sent.lock().unwrap();
+        assert_eq!(
+            sent.last(),
+            Some(&StatusParams {
+                uri,
+                dialect: Dialect::MySQL,
+                version: "8.0".to_string(),
+                grammar_abi_version: Some(15),
+                parse_time_ms: Some(3),
+                diagnostics: 2,
+                catalog_tables: Some(0),
+                catalog_stale: false,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_status_is_rate_limited_and_sends_the_latest() {
+        let (reporter, sink) = reporter(Duration::from_millis(50));
+
+        reporter.report(document(1)).await;
+        reporter.report(document(2)).await;
+        reporter.report(document(3)).await;
+        assert_eq!(sink.sent.lock().unwrap().len(), 1);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let sent = sink.sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[1].parse_time_ms, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_repeated_status_is_sent_once() {
+        let (reporter, sink) = reporter(Duration::ZERO);
+
+        reporter.report(document(1)).await;
+        reporter.report(document(1)).await;
+
+        assert_eq!(sink.sent.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_catalog_change_marks_count_stale() {
+        let (reporter, sink) = reporter(Duration::ZERO);
+        assert!(reporter.begin_catalog_count());
+        reporter.count_tables(Arc::new(NullCatalog)).await;
+        reporter.report(document(1)).await;
+        assert!(!reporter.begin_catalog_count());
+
+        reporter.catalog_changed().await;
+
+        assert!(sink.sent.lock().unwrap().last().unwrap().catalog_stale);
+        assert!(reporter.begin_catalog_count());
+        // A count is already running
+        assert!(!reporter.begin_catalog_count());
+    }
+}
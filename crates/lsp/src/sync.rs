@@ -20,7 +20,7 @@
 //! ```text
 //! DocumentSync
 //!     ├─→ ParserManager (from parsing module)
-//!     ├─→ Engine Config (for dialect override)
+//!     ├─→ Engine Config (default dialect for undeclared documents)
 //!     └─→ Document (for metadata extraction)
 //!           ↓
 //!        on_document_open()
@@ -29,6 +29,7 @@
 //! ```
 
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::SystemTime;
 use tokio::sync::RwLock;
 use tower_lsp::lsp_types::*;
@@ -36,7 +37,11 @@ use tracing::{debug, info, warn};
 
 use crate::config::EngineConfig;
 use crate::document::{Document, ParseMetadata};
+use crate::embedded::EmbeddedRegion;
 use crate::parsing::{ParseResult, ParserManager};
+use crate::positions::to_lsp_range;
+use crate::workspace::WorkspaceRoots;
+use unified_sql_lsp_context::Range as ContextRange;
 use unified_sql_lsp_ir::Dialect;
 
 // Re-export ParseMetadata with a constructor
@@ -49,8 +54,24 @@ impl ParseMetadata {
             dialect,
             has_errors,
             error_count,
+            error_ranges: Vec::new(),
+            missing_nodes: Vec::new(),
         }
     }
+
+    /// Attach the error ranges collected while parsing
+    pub fn with_syntax_errors(
+        mut self,
+        error_ranges: Vec<ContextRange>,
+        missing_nodes: Vec<(String, ContextRange)>,
+    ) -> Self {
+        self.error_ranges = error_ranges.into_iter().map(to_lsp_range).collect();
+        self.missing_nodes = missing_nodes
+            .into_iter()
+            .map(|(kind, range)| (kind, to_lsp_range(range)))
+            .collect();
+        self
+    }
 }
 
 /// Document synchronization manager
@@ -61,8 +82,14 @@ pub struct DocumentSync {
     /// Parser manager
     parsers: Arc<ParserManager>,
 
-    /// Engine configuration (for dialect override)
+    /// Engine configuration (default dialect for undeclared documents)
     config: Arc<RwLock<Option<EngineConfig>>>,
+
+    /// Workspace roots, whose configs override the engine configuration
+    workspace_roots: WorkspaceRoots,
+
+    /// Number of documents opened or changed that were parsed
+    parses: Arc<AtomicU64>,
 }
 
 impl DocumentSync {
@@ -71,15 +98,31 @@ impl DocumentSync {
         Self {
             parsers: Arc::new(ParserManager::new()),
             config,
+            workspace_roots: WorkspaceRoots::new(),
+            parses: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Number of parses run for opened and changed documents
+    pub fn parse_count(&self) -> u64 {
+        self.parses.load(Ordering::Relaxed)
+    }
+
+    /// Resolve dialects from the configs of `workspace_roots` first
+    pub fn with_workspace_roots(mut self, workspace_roots: WorkspaceRoots) -> Self {
+        self.workspace_roots = workspace_roots;
+        self
+    }
+
     /// Resolve the SQL dialect for a document
     ///
-    /// Dialect resolution priority:
-    /// 1. Engine config (if set)
-    /// 2. Document language_id ("mysql", "postgresql", "sql")
-    /// 3. Fallback to Base
+    /// The dialect is a property of the document, so documents of different
+    /// dialects can be open side by side. Resolution priority:
+    /// 1. Dialect declared by the document: its language_id ("mysql",
+    ///    "postgresql") or a `-- dialect:` comment, see [`declared_dialect`]
+    /// 2. Config of the workspace root holding the document (if any)
+    /// 3. Engine config (if set)
+    /// 4. Fallback to MySQL
     ///
     /// # Arguments
     ///
@@ -88,8 +131,22 @@ impl DocumentSync {
     /// # Returns
     ///
     /// The resolved SQL dialect
+    ///
+    /// [`declared_dialect`]: crate::document::declared_dialect
     pub fn resolve_dialect(&self, document: &Document) -> Dialect {
-        // 1. Check engine config
+        // 1. Check what the document declares
+        if let Some(dialect) = document.declared_dialect() {
+            debug!("Using dialect declared by the document: {:?}", dialect);
+            return dialect;
+        }
+
+        // 2. Check the workspace root's config
+        if let Some(config) = self.workspace_roots.config_for(document.uri()) {
+            debug!("Using dialect from workspace config: {:?}", config.dialect);
+            return config.dialect;
+        }
+
+        // 3. Check engine config
         // Note: We use try_read() to avoid blocking in async context
         if let Ok(config_guard) = self.config.try_read()
             && let Some(config) = config_guard.as_ref()
@@ -98,27 +155,12 @@ impl DocumentSync {
             return config.dialect;
         }
 
-        // 2. Check language_id
-        let language_id = document.language_id();
-
-        match language_id {
-            "mysql" => {
-                debug!("Resolved dialect as MySQL from language_id");
-                Dialect::MySQL
-            }
-            "postgresql" | "postgres" => {
-                debug!("Resolved dialect as PostgreSQL from language_id");
-                Dialect::PostgreSQL
-            }
-            _ => {
-                // Default to MySQL for generic SQL
-                warn!(
-                    "Language_id '{}' is generic SQL or unknown, defaulting to MySQL dialect",
-                    language_id
-                );
-                Dialect::MySQL
-            }
-        }
+        // Default to MySQL for generic SQL
+        warn!(
+            "Language_id '{}' is generic SQL or unknown, defaulting to MySQL dialect",
+            document.language_id()
+        );
+        Dialect::MySQL
     }
 
     /// Parse document on open (full parse)
@@ -144,6 +186,7 @@ impl DocumentSync {
             content.len()
         );
 
+        self.parses.fetch_add(1, Ordering::Relaxed);
         let result = self.parsers.parse_text(dialect, &content);
 
         match &result {
@@ -216,6 +259,7 @@ impl DocumentSync {
             content.len()
         );
 
+        self.parses.fetch_add(1, Ordering::Relaxed);
         let result = self.parsers.parse_text(dialect, &content);
 
         match &result {
@@ -240,15 +284,46 @@ impl DocumentSync {
         result
     }
 
+    /// Parse the SQL embedded in `document` at `region` as its own document
+    ///
+    /// The result holds only the embedded text, so positions in it are
+    /// relative to the region, see [`EmbeddedRegion::to_inner`]. It keeps no
+    /// tree when parsing fails.
+    pub fn parse_embedded(&self, document: &Document, region: &EmbeddedRegion) -> Document {
+        let dialect = self.resolve_dialect(document);
+        let mut embedded = document.embedded_document(region);
+
+        debug!(
+            "Parsing embedded region: uri={}, owner={}, length={}",
+            document.uri(),
+            region.owner,
+            region.text.len()
+        );
+
+        let result = self.parsers.parse_text(dialect, &region.text);
+        let metadata = self.create_metadata(&result, dialect);
+        if let Some(tree) = result.tree() {
+            embedded.set_tree(tree.clone(), metadata);
+        }
+        embedded
+    }
+
     /// Clear parse data on document close
     ///
     /// # Arguments
     ///
-    /// - `uri`: The document URI
-    pub fn on_document_close(&self, uri: &Url) {
-        debug!("Clearing parse data for closed document: uri={}", uri);
-        // Parse data is cleared when document is removed from store
-        // This is mainly for logging and future cleanup
+    /// - `document`: The document removed from the store
+    ///
+    /// # Returns
+    ///
+    /// The notification that clears the document's diagnostics on the client
+    pub fn on_document_close(&self, document: &Document) -> PublishDiagnosticsParams {
+        debug!(
+            "Clearing parse data for closed document: uri={}",
+            document.uri()
+        );
+        // Parse data went away with the document; only client state remains
+        PublishDiagnosticsParams::new(document.uri().clone(), Vec::new(), Some(document.version()))
     }
 
     /// Check if incremental parsing can be used
@@ -292,9 +367,15 @@ impl DocumentSync {
             ParseResult::Success { parse_time, .. } => {
                 ParseMetadata::new(parse_time.as_millis() as u64, dialect, false, 0)
             }
-            ParseResult::Partial { errors, .. } => {
+            ParseResult::Partial {
+                errors,
+                error_ranges,
+                missing_nodes,
+                ..
+            } => {
                 // Partial parse doesn't have timing info, use 0
                 ParseMetadata::new(0, dialect, true, errors.len())
+                    .with_syntax_errors(error_ranges.clone(), missing_nodes.clone())
             }
             ParseResult::Failed { .. } => {
                 // Create metadata even for failures
@@ -0,0 +1,457 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Table references
+//!
+//! Token scans for the tables a document reads and the CTEs of its `WITH`
+//! clauses: which CTEs are visible where (completion), and CTEs referencing
+//! themselves or each other in a cycle (diagnostics).
+
+use tower_lsp::lsp_types::{Position, Range};
+
+use crate::token_scan::{Token, same_name, skip_parens, tokenize};
+
+/// Words ending a table reference list, or following a table without an alias
+const CLAUSE_KEYWORDS: &[&str] = &[
+    "WHERE",
+    "JOIN",
+    "LEFT",
+    "RIGHT",
+    "INNER",
+    "OUTER",
+    "FULL",
+    "CROSS",
+    "NATURAL",
+    "ON",
+    "USING",
+    "GROUP",
+    "ORDER",
+    "HAVING",
+    "LIMIT",
+    "OFFSET",
+    "UNION",
+    "INTERSECT",
+    "EXCEPT",
+    "WINDOW",
+    "SET",
+    "RETURNING",
+    "INTO",
+    "VALUES",
+    "FOR",
+    "LATERAL",
+];
+
+/// Tables read by the `FROM` and `JOIN` clauses of a document, with the
+/// range of each name
+///
+/// Only plain names are returned: schema-qualified names, subqueries and
+/// table functions are skipped. `FROM` inside a call (`EXTRACT(YEAR FROM d)`)
+/// or after `IS DISTINCT` doesn't start a table list.
+pub fn table_references(content: &str) -> Vec<(String, Range)> {
+    let tokens = tokenize(content);
+    let mut references = Vec::new();
+    // Whether each open parenthesis level is a query
+    let mut queries = vec![false];
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.is_symbol('(') {
+            queries.push(false);
+        } else if token.is_symbol(')') {
+            if queries.len() > 1 {
+                queries.pop();
+            }
+        } else if token.is_symbol(';') {
+            queries = vec![false];
+        } else if ["SELECT", "DELETE", "UPDATE"]
+            .iter()
+            .any(|k| token.is_keyword(k))
+        {
+            *queries.last_mut().unwrap() = true;
+        } else if queries.last() == Some(&true)
+            && (token.is_keyword("JOIN")
+                || (token.is_keyword("FROM") && !(i > 0 && tokens[i - 1].is_keyword("DISTINCT"))))
+        {
+            collect_table_list(&tokens, i + 1, token.is_keyword("FROM"), &mut references);
+        }
+    }
+
+    references
+}
+
+/// Collect the table names of the list starting at `i`
+///
+/// After `JOIN` only one table follows; after `FROM` a comma continues the list.
+fn collect_table_list(
+    tokens: &[Token],
+    mut i: usize,
+    comma_list: bool,
+    references: &mut Vec<(String, Range)>,
+) {
+    loop {
+        if tokens.get(i).is_some_and(|t| t.is_keyword("ONLY")) {
+            i += 1;
+        }
+        let Some(name) = tokens
+            .get(i)
+            .filter(|t| t.is_identifier() && !CLAUSE_KEYWORDS.iter().any(|k| t.is_keyword(k)))
+        else {
+            return;
+        };
+        i += 1;
+        let qualified_or_call = tokens
+            .get(i)
+            .is_some_and(|t| t.is_symbol('.') || t.is_symbol('('));
+        if !qualified_or_call {
+            references.push((name.text.clone(), name.range));
+        }
+
+        if !comma_list {
+            return;
+        }
+        // Skip to the comma before the next table, if any
+        while let Some(token) = tokens.get(i) {
+            if token.is_symbol(',') {
+                break;
+            }
+            if token.is_symbol('(') {
+                i = skip_parens(tokens, i);
+                continue;
+            }
+            if token.is_symbol(';')
+                || token.is_symbol(')')
+                || CLAUSE_KEYWORDS.iter().any(|k| token.is_keyword(k))
+            {
+                return;
+            }
+            i += 1;
+        }
+        if tokens.get(i).is_none() {
+            return;
+        }
+        i += 1;
+    }
+}
+
+/// CTE names visible at `position`, in definition order
+///
+/// A CTE is visible in the query its `WITH` clause belongs to, nested
+/// queries included, and in the bodies of the CTEs defined after it. Inside
+/// its own body it is only visible for `WITH RECURSIVE`, and only when
+/// `recursive_cte` says the dialect version supports it.
+pub fn visible_ctes(content: &str, position: Position, recursive_cte: bool) -> Vec<String> {
+    let tokens = tokenize(content);
+    let contains = |range: &Range| range.start <= position && position <= range.end;
+    let mut names = Vec::new();
+
+    for clause in with_clauses(&tokens, end_position(content)) {
+        if !contains(&clause.scope) {
+            continue;
+        }
+        for (name, body) in &clause.ctes {
+            if contains(body) {
+                if clause.recursive && recursive_cte {
+                    names.push(name.text.clone());
+                }
+                // Later CTEs aren't visible in this one
+                break;
+            }
+            names.push(name.text.clone());
+        }
+    }
+
+    names
+}
+
+/// References to a CTE inside its own body that can't resolve to it
+///
+/// A CTE only sees itself with `WITH RECURSIVE`, on dialect versions where
+/// `recursive_cte` is set.
+pub fn cte_self_references(content: &str, recursive_cte: bool) -> Vec<(String, Range)> {
+    let tokens = tokenize(content);
+    let references = table_references(content);
+    let mut invalid = Vec::new();
+
+    for clause in with_clauses(&tokens, end_position(content)) {
+        if clause.recursive && recursive_cte {
+            continue;
+        }
+        for (name, body) in &clause.ctes {
+            invalid.extend(
+                references
+                    .iter()
+                    .filter(|(reference, range)| {
+                        body.start <= range.start
+                            && range.end <= body.end
+                            && same_name(reference, name)
+                    })
+                    .cloned(),
+            );
+        }
+    }
+
+    invalid
+}
+
+/// Cycles of CTEs of one `WITH` clause referencing each other, each with
+/// the reference closing it
+///
+/// `WITH a AS (SELECT * FROM b), b AS (SELECT * FROM a)` has the cycle
+/// `a → b → a`, closed by the `a` in the body of `b`. A CTE naming itself
+/// is left to [`cte_self_references`].
+pub fn cte_cycles(content: &str) -> Vec<(Vec<String>, Range)> {
+    let tokens = tokenize(content);
+    let references = table_references(content);
+    let mut cycles = Vec::new();
+
+    for clause in with_clauses(&tokens, end_position(content)) {
+        // The other CTEs each body references, with the first reference
+        let mut edges: Vec<Vec<(usize, Range)>> = vec![Vec::new(); clause.ctes.len()];
+        for (i, (_, body)) in clause.ctes.iter().enumerate() {
+            for (reference, range) in &references {
+                if range.start < body.start || body.end < range.end {
+                    continue;
+                }
+                let target = clause
+                    .ctes
+                    .iter()
+                    .position(|(name, _)| same_name(reference, name));
+                if let Some(j) = target
+                    && j != i
+                    && !edges[i].iter().any(|(k, _)| *k == j)
+                {
+                    edges[i].push((j, *range));
+                }
+            }
+        }
+
+        // Depth-first search without recursion; a reference to a CTE still
+        // on the path closes a cycle
+        let mut on_path = vec![false; clause.ctes.len()];
+        let mut done = vec![false; clause.ctes.len()];
+        for start in 0..clause.ctes.len() {
+            if done[start] {
+                continue;
+            }
+            let mut path = vec![(start, 0)];
+            on_path[start] = true;
+            while let Some(&(cte, edge)) = path.last() {
+                let Some(&(next, range)) = edges[cte].get(edge) else {
+                    on_path[cte] = false;
+                    done[cte] = true;
+                    path.pop();
+                    continue;
+                };
+                let last = path.len() - 1;
+                path[last].1 += 1;
+
+                if on_path[next] {
+                    let from = path.iter().position(|(c, _)| *c == next).unwrap_or(0);
+                    let names = path[from..]
+                        .iter()
+                        .chain(std::iter::once(&(next, 0)))
+                        .map(|(c, _)| clause.ctes[*c].0.text.clone())
+                        .collect();
+                    cycles.push((names, range));
+                } else if !done[next] {
+                    on_path[next] = true;
+                    path.push((next, 0));
+                }
+            }
+        }
+    }
+
+    cycles
+}
+
+/// A `WITH` clause: its CTE names with the range of their bodies
+struct WithClause {
+    recursive: bool,
+    ctes: Vec<(Token, Range)>,
+    /// The query the clause belongs to, from `WITH` to its end
+    scope: Range,
+}
+
+/// Every `WITH` clause of the tokens, nested ones included
+///
+/// A body or query left open runs to `end`, so clauses still being typed
+/// have a scope.
+fn with_clauses(tokens: &[Token], end: Position) -> Vec<WithClause> {
+    let mut clauses = Vec::new();
+
+    for (w, with) in tokens.iter().enumerate() {
+        if !with.is_keyword("WITH") {
+            continue;
+        }
+        let mut i = w + 1;
+        let recursive = tokens.get(i).is_some_and(|t| t.is_keyword("RECURSIVE"));
+        if recursive {
+            i += 1;
+        }
+
+        let mut ctes = Vec::new();
+        while let Some(name) = tokens.get(i).filter(|t| t.is_identifier()) {
+            let mut j = i + 1;
+            if tokens.get(j).is_some_and(|t| t.is_symbol('(')) {
+                j = skip_parens(tokens, j);
+            }
+            if !tokens.get(j).is_some_and(|t| t.is_keyword("AS")) {
+                break;
+            }
+            j += 1;
+            while tokens
+                .get(j)
+                .is_some_and(|t| t.is_keyword("NOT") || t.is_keyword("MATERIALIZED"))
+            {
+                j += 1;
+            }
+            if !tokens.get(j).is_some_and(|t| t.is_symbol('(')) {
+                break;
+            }
+            let close = matching_paren(tokens, j);
+            let body_end = close.map_or(end, |close| tokens[close].range.start);
+            ctes.push((name.clone(), Range::new(tokens[j].range.end, body_end)));
+
+            match close {
+                Some(close) if tokens.get(close + 1).is_some_and(|t| t.is_symbol(',')) => {
+                    i = close + 2;
+                }
+                _ => break,
+            }
+        }
+        if ctes.is_empty() {
+            continue;
+        }
+
+        // The clause's query ends at the parenthesis enclosing the WITH
+        let mut depth = 0;
+        let scope_end = tokens[w + 1..]
+            .iter()
+            .find(|t| {
+                if t.is_symbol('(') {
+                    depth += 1;
+                } else if t.is_symbol(')') {
+                    if depth == 0 {
+                        return true;
+                    }
+                    depth -= 1;
+                }
+                t.is_symbol(';')
+            })
+            .map_or(end, |t| t.range.start);
+
+        clauses.push(WithClause {
+            recursive,
+            ctes,
+            scope: Range::new(with.range.start, scope_end),
+        });
+    }
+
+    clauses
+}
+
+/// Index of the parenthesis closing the one at `open`
+fn matching_paren(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        if token.is_symbol('(') {
+            depth += 1;
+        } else if token.is_symbol(')') {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+/// Position just past the last character of `content`
+fn end_position(content: &str) -> Position {
+    let line = content.matches('\n').count() as u32;
+    let character = content
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .count() as u32;
+    Position::new(line, character)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_references() {
+        let sql = "SELECT * FROM users u, ONLY orders AS o JOIN app.audit a ON true\n\
+                   LEFT JOIN (SELECT 1 FROM items) i ON true WHERE x IN (SELECT id FROM logs);\n\
+                   SELECT substring(name FROM 2) FROM unnest(tags)";
+        let names: Vec<_> = table_references(sql)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, vec!["users", "orders", "items", "logs"]);
+        assert_eq!(
+            table_references("DELETE FROM users")[0].1,
+            Range::new(Position::new(0, 12), Position::new(0, 17))
+        );
+    }
+
+    #[test]
+    fn test_visible_ctes() {
+        let sql = "WITH RECURSIVE a AS (SELECT 1 FROM x), b AS (SELECT * FROM y) \
+                   SELECT * FROM (SELECT * FROM z) s;\nSELECT * FROM w";
+        let at = |needle: &str| Position::new(0, sql.find(needle).unwrap() as u32);
+
+        // Main query and its subqueries see every CTE
+        assert_eq!(visible_ctes(sql, at("z)"), true), vec!["a", "b"]);
+        assert_eq!(visible_ctes(sql, at(" s;"), true), vec!["a", "b"]);
+        // A body sees the earlier CTEs, and itself when recursive
+        assert_eq!(visible_ctes(sql, at("y)"), true), vec!["a", "b"]);
+        assert_eq!(visible_ctes(sql, at("y)"), false), vec!["a"]);
+        assert_eq!(visible_ctes(sql, at("x)"), false), Vec::<String>::new());
+        // Not in the next statement
+        assert!(visible_ctes(sql, Position::new(1, 14), true).is_empty());
+
+        // Bodies still being typed
+        let typing = "WITH a AS (SELECT 1), b AS (SELECT * FROM ";
+        let end = Position::new(0, typing.len() as u32);
+        assert_eq!(visible_ctes(typing, end, true), vec!["a"]);
+        let recursive = typing.replace("WITH", "WITH RECURSIVE");
+        let end = Position::new(0, recursive.len() as u32);
+        assert_eq!(visible_ctes(&recursive, end, true), vec!["a", "b"]);
+
+        // A WITH inside a subquery is only visible there
+        let nested = "SELECT * FROM (WITH n AS (SELECT 1) SELECT * FROM n) t JOIN ";
+        let end = Position::new(0, nested.len() as u32);
+        assert!(visible_ctes(nested, end, true).is_empty());
+        assert_eq!(visible_ctes(nested, Position::new(0, 51), true), vec!["n"]);
+    }
+
+    #[test]
+    fn test_cte_cycles() {
+        let sql = "WITH a AS (SELECT * FROM b), b AS (SELECT * FROM a) SELECT * FROM a";
+        let cycles = cte_cycles(sql);
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].0, vec!["a", "b", "a"]);
+        // Closed by the `a` in the body of `b`
+        assert_eq!(cycles[0].1.start, Position::new(0, 49));
+
+        let three = "WITH a AS (SELECT * FROM c), b AS (SELECT * FROM a JOIN a x ON true), \
+                     c AS (SELECT * FROM b) SELECT * FROM c";
+        let cycles = cte_cycles(three);
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].0, vec!["a", "c", "b", "a"]);
+
+        // Chains, self-references and CTEs of different clauses aren't cycles
+        let chain = "WITH a AS (SELECT 1), b AS (SELECT * FROM a) SELECT * FROM b";
+        assert!(cte_cycles(chain).is_empty());
+        let recursive = "WITH RECURSIVE t AS (SELECT 1 UNION ALL SELECT * FROM t) SELECT * FROM t";
+        assert!(cte_cycles(recursive).is_empty());
+        let separate = "WITH a AS (SELECT * FROM b) SELECT * FROM a; \
+                        WITH b AS (SELECT * FROM a) SELECT * FROM b";
+        assert!(cte_cycles(separate).is_empty());
+    }
+}
@@ -18,6 +18,15 @@
 //! LSP Backend (document store, completion engine, catalog)
 //! ```
 //!
+//! ## Connections
+//!
+//! Every connection gets a session of its own, so clients never see each
+//! other's documents; only the injected catalog and the parser manager are
+//! shared. A client's `shutdown`/`exit` ends its connection while the
+//! listener keeps accepting others. Connections beyond
+//! [`TcpServer::with_max_connections`] are refused, and connections silent
+//! for longer than [`TcpServer::with_idle_timeout`] are closed.
+//!
 //! ## Usage
 //!
 //! ```rust,no_run
@@ -37,7 +46,10 @@ use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tokio::net::TcpListener;
+use tokio::sync::Semaphore;
 use tokio_tungstenite::tungstenite::protocol::Message;
 use tracing::{debug, error, info, warn};
 
@@ -124,7 +136,16 @@ struct JsonRpcError {
     data: Option<JsonValue>,
 }
 
+/// Default cap on simultaneously open connections
+pub const DEFAULT_MAX_CONNECTIONS: usize = 64;
+
+/// Default time a connection may stay silent before it is closed
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
 /// Per-client session state
+///
+/// Documents are private to the session; the catalog and the parser
+/// manager are shared with the other sessions of the server.
 struct ClientSession {
     documents: Arc<DocumentStore>,
     #[allow(dead_code)]
@@ -132,18 +153,33 @@ struct ClientSession {
     #[allow(dead_code)]
     config: Arc<tokio::sync::RwLock<Option<EngineConfig>>>,
     catalog: Arc<dyn Catalog>,
+    parser_manager: Arc<ParserManager>,
+
+    /// A `shutdown` request was received; only `exit` is expected now
+    shut_down: AtomicBool,
+
+    /// An `exit` notification was received; the connection ends
+    exited: AtomicBool,
 }
 
 impl ClientSession {
-    fn new(catalog: Arc<dyn Catalog>) -> Self {
+    fn new(catalog: Arc<dyn Catalog>, parser_manager: Arc<ParserManager>) -> Self {
         Self {
             documents: Arc::new(DocumentStore::new()),
             catalog_manager: Arc::new(tokio::sync::RwLock::new(CatalogManager::new())),
             config: Arc::new(tokio::sync::RwLock::new(None)),
             catalog,
+            parser_manager,
+            shut_down: AtomicBool::new(false),
+            exited: AtomicBool::new(false),
         }
     }
 
+    /// Whether the client sent `exit`
+    fn exited(&self) -> bool {
+        self.exited.load(Ordering::Relaxed)
+    }
+
     async fn handle_did_open(&self, params: DidOpenTextDocumentParams) {
         let uri = params.text_document.uri.clone();
         let text = params.text_document.text;
@@ -166,7 +202,7 @@ impl ClientSession {
             let source = document.get_content();
             let dialect = Dialect::MySQL; // Default for playground
 
-            let parse_result = ParserManager::parse_text(&ParserManager::new(), dialect, &source);
+            let parse_result = self.parser_manager.parse_text(dialect, &source);
 
             match parse_result {
                 ParseResult::Success { tree, parse_time } => {
@@ -176,6 +212,8 @@ impl ClientSession {
                         dialect,
                         has_errors: false,
                         error_count: 0,
+                        error_ranges: Vec::new(),
+                        missing_nodes: Vec::new(),
                     };
                     if let Some(tree) = tree
                         && let Err(e) = self
@@ -186,14 +224,14 @@ impl ClientSession {
                         error!("Failed to update document tree: {}", e);
                     }
                 }
-                ParseResult::Partial { tree, errors } => {
-                    let metadata = ParseMetadata {
-                        parsed_at: std::time::SystemTime::now(),
-                        parse_time_ms: 0,
-                        dialect,
-                        has_errors: true,
-                        error_count: errors.len(),
-                    };
+                ParseResult::Partial {
+                    tree,
+                    errors,
+                    error_ranges,
+                    missing_nodes,
+                } => {
+                    let metadata = ParseMetadata::new(0, dialect, true, errors.len())
+                        .with_syntax_errors(error_ranges, missing_nodes);
                     if let Some(tree) = tree
                         && let Err(e) = self
                             .documents
@@ -239,7 +277,7 @@ impl ClientSession {
             let source = document.get_content();
             let dialect = Dialect::MySQL;
 
-            let parse_result = ParserManager::parse_text(&ParserManager::new(), dialect, &source);
+            let parse_result = self.parser_manager.parse_text(dialect, &source);
 
             match parse_result {
                 ParseResult::Success { tree, parse_time } => {
@@ -249,6 +287,8 @@ impl ClientSession {
                         dialect,
                         has_errors: false,
                         error_count: 0,
+                        error_ranges: Vec::new(),
+                        missing_nodes: Vec::new(),
                     };
                     if let Some(tree) = tree
                         && let Err(e) = self
@@ -259,14 +299,14 @@ impl ClientSession {
                         error!("Failed to update document tree: {}", e);
                     }
                 }
-                ParseResult::Partial { tree, errors } => {
-                    let metadata = ParseMetadata {
-                        parsed_at: std::time::SystemTime::now(),
-                        parse_time_ms: 0,
-                        dialect,
-                        has_errors: true,
-                        error_count: errors.len(),
-                    };
+                ParseResult::Partial {
+                    tree,
+                    errors,
+                    error_ranges,
+                    missing_nodes,
+                } => {
+                    let metadata = ParseMetadata::new(0, dialect, true, errors.len())
+                        .with_syntax_errors(error_ranges, missing_nodes);
                     if let Some(tree) = tree
                         && let Err(e) = self
                             .documents
@@ -343,43 +383,81 @@ pub struct TcpServer {
     listener: TcpListener,
     port: u16,
     catalog: Arc<dyn Catalog>,
+    parser_manager: Arc<ParserManager>,
+    max_connections: usize,
+    idle_timeout: Duration,
 }
 
 impl TcpServer {
     /// Create a new TCP server listening on the specified port
+    ///
+    /// Port 0 picks a free port; [`TcpServer::port`] tells which.
     pub async fn new(port: u16, catalog: Arc<dyn Catalog>) -> std::io::Result<Self> {
         let listener = TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
+        let port = listener.local_addr()?.port();
         info!("TCP LSP server listening on port {}", port);
 
         Ok(Self {
             listener,
             port,
             catalog,
+            parser_manager: Arc::new(ParserManager::new()),
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
         })
     }
 
+    /// Set the cap on simultaneously open connections
+    ///
+    /// Connections beyond the cap are closed right after being accepted.
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    /// Set how long a connection may stay silent before it is closed
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
     /// Get the actual port the server is listening on
     pub fn port(&self) -> u16 {
         self.port
     }
 
     /// Accept and handle incoming connections
+    ///
+    /// Each connection is served by its own task with its own session;
+    /// a connection ending never stops the listener.
     pub async fn serve(&self) -> std::io::Result<()> {
         info!("TCP LSP server ready to accept connections");
+        let slots = Arc::new(Semaphore::new(self.max_connections));
 
         loop {
             match self.listener.accept().await {
                 Ok((stream, addr)) => {
+                    let Ok(slot) = slots.clone().try_acquire_owned() else {
+                        warn!(
+                            "Refusing connection from {}: {} connections already open",
+                            addr, self.max_connections
+                        );
+                        continue;
+                    };
                     info!("New connection from {}", addr);
 
                     // Create a new session for this connection
-                    let session = ClientSession::new(self.catalog.clone());
+                    let session =
+                        ClientSession::new(self.catalog.clone(), self.parser_manager.clone());
+                    let idle_timeout = self.idle_timeout;
 
                     // Spawn a task to handle this connection
                     tokio::spawn(async move {
-                        if let Err(e) = handle_connection(stream, session).await {
-                            error!("Error handling connection: {}", e);
+                        if let Err(e) = handle_connection(stream, session, idle_timeout).await {
+                            error!("Error handling connection from {}: {}", addr, e);
                         }
+                        // The slot frees up once the connection is gone
+                        drop(slot);
                     });
                 }
                 Err(e) => {
@@ -391,10 +469,14 @@ impl TcpServer {
 }
 
 /// Handle a single WebSocket connection
+///
+/// Ends when the client closes the connection, sends `exit` or stays
+/// silent for `idle_timeout`.
 async fn handle_connection(
     stream: tokio::net::TcpStream,
     session: ClientSession,
-) -> Result<(), Box<dyn std::error::Error>> {
+    idle_timeout: Duration,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // Perform WebSocket handshake
     let ws_stream = tokio_tungstenite::accept_async(stream).await?;
     let (mut ws_sender, mut ws_receiver) = ws_stream.split();
@@ -402,7 +484,17 @@ async fn handle_connection(
     info!("WebSocket connection established");
 
     // Handle incoming messages
-    while let Some(msg_result) = ws_receiver.next().await {
+    loop {
+        let msg_result = match tokio::time::timeout(idle_timeout, ws_receiver.next()).await {
+            Ok(Some(msg_result)) => msg_result,
+            Ok(None) => break,
+            Err(_) => {
+                info!("Closing connection idle for {:?}", idle_timeout);
+                let _ = ws_sender.send(Message::Close(None)).await;
+                break;
+            }
+        };
+
         match msg_result {
             Ok(msg) => {
                 if msg.is_text() || msg.is_binary() {
@@ -436,6 +528,12 @@ async fn handle_connection(
                             break;
                         }
                     }
+
+                    if session.exited() {
+                        info!("Client exited");
+                        let _ = ws_sender.send(Message::Close(None)).await;
+                        break;
+                    }
                 } else if msg.is_close() {
                     info!("Client requested close");
                     break;
@@ -456,7 +554,7 @@ async fn handle_connection(
 async fn handle_lsp_message(
     message: &str,
     session: &ClientSession,
-) -> Result<JsonRpcResponse, Box<dyn std::error::Error>> {
+) -> Result<JsonRpcResponse, Box<dyn std::error::Error + Send + Sync>> {
     let request: JsonRpcRequest = serde_json::from_str(message)?;
 
     let id = request.id().unwrap_or(JsonValue::Null);
@@ -485,6 +583,7 @@ async fn handle_lsp_message(
             }
             "exit" => {
                 debug!("Received exit notification");
+                session.exited.store(true, Ordering::Relaxed);
             }
             _ => {
                 warn!("Unknown notification: {}", method);
@@ -503,6 +602,20 @@ async fn handle_lsp_message(
 
         let catalog = session.catalog.clone();
 
+        // After shutdown, the client may only exit
+        if session.shut_down.load(Ordering::Relaxed) {
+            return Ok(JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id,
+                result: None,
+                error: Some(JsonRpcError {
+                    code: -32600,
+                    message: format!("Server is shut down: {}", method),
+                    data: None,
+                }),
+            });
+        }
+
         // Call actual backend methods
         let result = match method.as_str() {
             "initialize" => {
@@ -525,7 +638,8 @@ async fn handle_lsp_message(
                 serde_json::json!({})
             }
             "shutdown" => {
-                serde_json::json!({})
+                session.shut_down.store(true, Ordering::Relaxed);
+                serde_json::json!(null)
             }
             "textDocument/completion" => {
                 let params_value = params.unwrap_or(JsonValue::Null);
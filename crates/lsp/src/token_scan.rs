@@ -0,0 +1,131 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Token scan
+//!
+//! Workspace symbols, rename and several diagnostics read SQL with a
+//! lightweight token scan rather than a full parse, so they also work on
+//! documents that don't parse cleanly yet. This module holds the tokens
+//! they share.
+
+use tower_lsp::lsp_types::{Position, Range};
+use unified_sql_lsp_context::tokens::{TokenKind as SqlTokenKind, lex};
+
+/// Kind of a scanned token
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Word,
+    QuotedIdentifier,
+    Symbol,
+}
+
+/// A word, quoted identifier or symbol of SQL text
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// Text without the delimiters of a quoted identifier
+    pub text: String,
+    /// Opening delimiter of a quoted identifier
+    pub delimiter: Option<char>,
+    pub range: Range,
+}
+
+impl Token {
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(keyword)
+    }
+
+    pub fn is_identifier(&self) -> bool {
+        matches!(self.kind, TokenKind::Word | TokenKind::QuotedIdentifier)
+    }
+
+    pub fn is_symbol(&self, symbol: char) -> bool {
+        self.kind == TokenKind::Symbol && self.text.starts_with(symbol)
+    }
+
+    /// The identifier as written, with the delimiters of a quoted one
+    pub fn written(&self) -> String {
+        match self.delimiter {
+            Some(delimiter @ ('"' | '`')) => format!("{}{}{}", delimiter, self.text, delimiter),
+            _ => self.text.clone(),
+        }
+    }
+}
+
+/// Split SQL text into words, quoted identifiers and symbols with positions,
+/// skipping whitespace, comments and string literals
+pub fn tokenize(content: &str) -> Vec<Token> {
+    let (mut line, mut col) = (0u32, 0u32);
+    let mut scanned = 0;
+    // Advance the position over content[scanned..offset]
+    let mut advance = |offset: usize| {
+        for c in content[scanned..offset].chars() {
+            if c == '\n' {
+                line += 1;
+                col = 0;
+            } else {
+                col += 1;
+            }
+        }
+        scanned = offset;
+        Position::new(line, col)
+    };
+
+    let mut tokens = Vec::new();
+    for token in lex(content) {
+        let (kind, text, delimiter) = match token.kind {
+            SqlTokenKind::String | SqlTokenKind::Comment => continue,
+            SqlTokenKind::Word => (TokenKind::Word, token.text, None),
+            SqlTokenKind::QuotedIdentifier => {
+                // Delimiters are ASCII; an unterminated identifier has no closing one
+                let delimiter = token.text.as_bytes()[0] as char;
+                let close = if delimiter == '[' { ']' } else { delimiter };
+                let inner = &token.text[1..];
+                let inner = inner.strip_suffix(close).unwrap_or(inner);
+                (TokenKind::QuotedIdentifier, inner, Some(delimiter))
+            }
+            SqlTokenKind::Symbol | SqlTokenKind::OpenParen | SqlTokenKind::CloseParen => {
+                (TokenKind::Symbol, token.text, None)
+            }
+        };
+        let start = advance(token.offset);
+        let end = advance(token.end());
+        tokens.push(Token {
+            kind,
+            text: text.to_string(),
+            delimiter,
+            range: Range::new(start, end),
+        });
+    }
+
+    tokens
+}
+
+/// Whether `name` names the same object as the identifier `token`
+///
+/// Unquoted names compare without case, quoted ones exactly.
+pub fn same_name(name: &str, token: &Token) -> bool {
+    match token.delimiter {
+        Some(_) => name == token.text,
+        None => name.eq_ignore_ascii_case(&token.text),
+    }
+}
+
+/// Skip a balanced parenthesized group starting at `i`
+pub fn skip_parens(tokens: &[Token], mut i: usize) -> usize {
+    let mut depth = 0;
+    while let Some(token) = tokens.get(i) {
+        if token.is_symbol('(') {
+            depth += 1;
+        } else if token.is_symbol(')') {
+            depth -= 1;
+            if depth == 0 {
+                return i + 1;
+            }
+        }
+        i += 1;
+    }
+    i
+}
@@ -0,0 +1,247 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Workspace roots
+//!
+//! A client may open several workspace folders at once. Each folder can
+//! carry its own [`WORKSPACE_CONFIG_FILE`] holding the same settings as the
+//! client's `unifiedSqlLsp` section, so one server can serve a MySQL project
+//! next to a PostgreSQL one.
+//!
+//! Documents are matched to the deepest root containing them. Documents
+//! outside every root, or under a root without a config file, use the
+//! client settings.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use serde_json::Value;
+use tower_lsp::lsp_types::Url;
+use tracing::{debug, warn};
+
+use crate::config::{EngineConfig, SETTINGS_SECTION, SchemaSource};
+
+/// Per-root configuration file, looked up directly in each workspace folder
+pub const WORKSPACE_CONFIG_FILE: &str = ".unified-sql-lsp.json";
+
+/// A workspace folder opened by the client
+#[derive(Debug, Clone)]
+pub struct WorkspaceRoot {
+    /// Folder URI as sent by the client
+    pub uri: Url,
+    /// Folder path on disk
+    pub path: PathBuf,
+    /// Configuration read from the folder's config file, if it has one
+    pub config: Option<EngineConfig>,
+}
+
+impl WorkspaceRoot {
+    /// Open the folder at `uri` and read its config file
+    ///
+    /// Returns `None` for URIs that aren't local paths.
+    pub fn open(uri: &Url) -> Option<Self> {
+        let path = uri.to_file_path().ok()?;
+        let config = discover_config(&path);
+        Some(Self {
+            uri: uri.clone(),
+            path,
+            config,
+        })
+    }
+}
+
+/// Read the [`WORKSPACE_CONFIG_FILE`] of the folder at `root`
+///
+/// The file holds either the bare settings section or an object with a
+/// `unifiedSqlLsp` key. Relative schema and custom rule paths are resolved
+/// against `root`. Missing or invalid files yield `None`.
+pub fn discover_config(root: &Path) -> Option<EngineConfig> {
+    let path = root.join(WORKSPACE_CONFIG_FILE);
+    let content = std::fs::read_to_string(&path).ok()?;
+    let value: Value = match serde_json::from_str(&content) {
+        Ok(value) => value,
+        Err(e) => {
+            warn!("Ignoring invalid {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    let settings = if value.get(SETTINGS_SECTION).is_some() {
+        value
+    } else {
+        serde_json::json!({ SETTINGS_SECTION: value })
+    };
+
+    let Some(mut config) = EngineConfig::from_lsp_settings(&settings) else {
+        warn!("Ignoring {}: not a valid configuration", path.display());
+        return None;
+    };
+    if let SchemaSource::Ddl { paths } = &mut config.schema_source {
+        for schema_path in paths.iter_mut() {
+            *schema_path = root.join(&*schema_path);
+        }
+    }
+    for rule_path in config.custom_rules.iter_mut() {
+        *rule_path = root.join(&*rule_path);
+    }
+    debug!(
+        "Loaded workspace config {}: dialect={:?}",
+        path.display(),
+        config.dialect
+    );
+    Some(config)
+}
+
+/// The workspace folders the server currently serves
+///
+/// Cloning yields another handle to the same set of roots.
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceRoots {
+    roots: Arc<RwLock<Vec<WorkspaceRoot>>>,
+}
+
+impl WorkspaceRoots {
+    /// Create an empty set of roots
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the folder at `uri`, replacing an earlier root with the same URI
+    ///
+    /// Returns whether the folder could be opened.
+    pub fn add(&self, uri: &Url) -> bool {
+        let Some(root) = WorkspaceRoot::open(uri) else {
+            warn!("Ignoring workspace folder that isn't a local path: {}", uri);
+            return false;
+        };
+        let mut roots = self.roots.write().unwrap();
+        roots.retain(|r| r.uri != root.uri);
+        roots.push(root);
+        true
+    }
+
+    /// Remove the folder at `uri`
+    ///
+    /// Returns whether such a root was being served.
+    pub fn remove(&self, uri: &Url) -> bool {
+        let mut roots = self.roots.write().unwrap();
+        let before = roots.len();
+        roots.retain(|r| &r.uri != uri);
+        roots.len() != before
+    }
+
+    /// Re-read the config file of every root
+    pub fn reload(&self) {
+        for root in self.roots.write().unwrap().iter_mut() {
+            root.config = discover_config(&root.path);
+        }
+    }
+
+    /// All roots, in the order they were added
+    pub fn all(&self) -> Vec<WorkspaceRoot> {
+        self.roots.read().unwrap().clone()
+    }
+
+    /// URIs of all roots
+    pub fn uris(&self) -> Vec<Url> {
+        self.roots
+            .read()
+            .unwrap()
+            .iter()
+            .map(|r| r.uri.clone())
+            .collect()
+    }
+
+    /// The deepest root containing the document at `uri`
+    pub fn root_for(&self, uri: &Url) -> Option<WorkspaceRoot> {
+        let path = uri.to_file_path().ok()?;
+        self.roots
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|r| path.starts_with(&r.path))
+            .max_by_key(|r| r.path.components().count())
+            .cloned()
+    }
+
+    /// Configuration of the deepest root containing `uri`, if it has one
+    pub fn config_for(&self, uri: &Url) -> Option<EngineConfig> {
+        self.root_for(uri).and_then(|r| r.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unified_sql_lsp_ir::Dialect;
+
+    fn temp_root(name: &str, config: Option<&str>) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("usql-workspace-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        if let Some(config) = config {
+            std::fs::write(dir.join(WORKSPACE_CONFIG_FILE), config).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_documents_match_deepest_root() {
+        let outer = temp_root(
+            "outer",
+            Some(
+                r#"{ "dialect": "mysql", "schemaSource": { "type": "ddl", "path": "schema.sql" } }"#,
+            ),
+        );
+        let inner = outer.join("pg");
+        std::fs::create_dir_all(&inner).unwrap();
+        std::fs::write(
+            inner.join(WORKSPACE_CONFIG_FILE),
+            r#"{ "unifiedSqlLsp": { "dialect": "postgresql", "connectionString": "postgresql://localhost/db" } }"#,
+        )
+        .unwrap();
+
+        let roots = WorkspaceRoots::new();
+        assert!(roots.add(&Url::from_directory_path(&outer).unwrap()));
+        assert!(roots.add(&Url::from_directory_path(&inner).unwrap()));
+
+        let doc = |path: PathBuf| Url::from_file_path(path).unwrap();
+        let mysql = roots.config_for(&doc(outer.join("a.sql"))).unwrap();
+        assert_eq!(mysql.dialect, Dialect::MySQL);
+        assert!(matches!(
+            &mysql.schema_source,
+            SchemaSource::Ddl { paths } if paths == &vec![outer.join("schema.sql")]
+        ));
+        let postgres = roots.config_for(&doc(inner.join("q/b.sql"))).unwrap();
+        assert_eq!(postgres.dialect, Dialect::PostgreSQL);
+
+        // A sibling whose name extends the root's is not inside it
+        let sibling = doc(PathBuf::from(format!("{}x/c.sql", outer.display())));
+        assert!(roots.config_for(&sibling).is_none());
+
+        assert!(roots.remove(&Url::from_directory_path(&inner).unwrap()));
+        let fallback = roots.config_for(&doc(inner.join("b.sql"))).unwrap();
+        assert_eq!(fallback.dialect, Dialect::MySQL);
+
+        std::fs::remove_dir_all(&outer).unwrap();
+    }
+
+    #[test]
+    fn test_root_without_config_file() {
+        let dir = temp_root("plain", None);
+        let roots = WorkspaceRoots::new();
+        roots.add(&Url::from_directory_path(&dir).unwrap());
+
+        let doc = Url::from_file_path(dir.join("a.sql")).unwrap();
+        assert!(roots.root_for(&doc).is_some());
+        assert!(roots.config_for(&doc).is_none());
+
+        // Invalid files are ignored too
+        std::fs::write(dir.join(WORKSPACE_CONFIG_FILE), "{ not json").unwrap();
+        roots.reload();
+        assert!(roots.config_for(&doc).is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
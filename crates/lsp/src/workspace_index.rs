@@ -0,0 +1,664 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Workspace SQL index
+//!
+//! `workspace/symbol` lists the tables and views defined by SQL files of the
+//! workspace, not only by open documents. [`WorkspaceIndex`] walks every
+//! workspace root once and keeps the definitions of each SQL file, updated
+//! from watched-file events afterwards.
+//!
+//! Monorepos vendor thousands of SQL files, so the walk skips:
+//!
+//! - files and directories ignored by a `.gitignore` on the way down,
+//! - files and directories matching [`WorkspaceIndexConfig::exclude`],
+//! - files not matching [`WorkspaceIndexConfig::include`],
+//!
+//! and stops after [`WorkspaceIndexConfig::max_files`] files. Watched-file
+//! updates go through the same filters.
+//!
+//! The index also records the column changes (`ALTER TABLE ... ADD`, `DROP`
+//! and `RENAME`) of open documents. Files on disk are taken as applied
+//! migrations, but an open one is still being written, so its changes are
+//! pending: diagnostics of other documents can overlay them on the catalog
+//! (see [`crate::migration_catalog`]).
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use tower_lsp::lsp_types::Url;
+
+use crate::column_migrations::{ColumnMigration, column_migrations};
+use crate::config::WorkspaceIndexConfig;
+use crate::workspace_symbols::{WorkspaceDefinition, index_document};
+
+/// Name of the ignore files honored by the walk
+const GITIGNORE_FILE: &str = ".gitignore";
+
+/// Outcome of indexing one workspace root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexSummary {
+    /// Files indexed under the root
+    pub files: usize,
+    /// Whether files were left out because `max_files` was reached
+    pub truncated: bool,
+}
+
+/// Definitions of the SQL files under the workspace roots
+#[derive(Debug, Default)]
+pub struct WorkspaceIndex {
+    files: RwLock<BTreeMap<PathBuf, Vec<WorkspaceDefinition>>>,
+    /// Definitions of the DDL schema files, which may lie outside the roots
+    schema_files: RwLock<BTreeMap<PathBuf, Vec<WorkspaceDefinition>>>,
+    /// Column changes of the open documents that make any
+    migrations: RwLock<BTreeMap<Url, Vec<ColumnMigration>>>,
+}
+
+impl WorkspaceIndex {
+    /// Create an empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index the files under `root`, replacing what was indexed there before
+    ///
+    /// Reads the disk; call it off the async runtime.
+    pub fn index_root(&self, root: &Path, config: &WorkspaceIndexConfig) -> IndexSummary {
+        let mut paths = Vec::new();
+        let complete = walk(
+            root,
+            "",
+            &Filter::new(config),
+            &mut Vec::new(),
+            &mut paths,
+            config.max_files,
+        );
+
+        let indexed: Vec<(PathBuf, Vec<WorkspaceDefinition>)> = paths
+            .into_iter()
+            .filter_map(|path| {
+                let definitions = read_definitions(&path)?;
+                Some((path, definitions))
+            })
+            .collect();
+        let summary = IndexSummary {
+            files: indexed.len(),
+            truncated: !complete,
+        };
+
+        let mut files = self.files.write().unwrap();
+        files.retain(|path, _| !path.starts_with(root));
+        files.extend(indexed);
+        summary
+    }
+
+    /// Forget every file under `root`
+    pub fn remove_root(&self, root: &Path) {
+        self.files
+            .write()
+            .unwrap()
+            .retain(|path, _| !path.starts_with(root));
+    }
+
+    /// Apply a change of the file at `path`, under the workspace `root`
+    ///
+    /// Deleted files and files the filters reject are dropped; others are
+    /// read again. A new file is not added once the root holds `max_files`.
+    /// Returns whether the index changed.
+    pub fn update_file(&self, root: &Path, path: &Path, config: &WorkspaceIndexConfig) -> bool {
+        if !path.is_file() || !accepts(root, path, config) {
+            return self.files.write().unwrap().remove(path).is_some();
+        }
+
+        let known = self.files.read().unwrap().contains_key(path);
+        if !known && self.files_under(root) >= config.max_files {
+            return false;
+        }
+        let Some(definitions) = read_definitions(path) else {
+            return false;
+        };
+        self.files
+            .write()
+            .unwrap()
+            .insert(path.to_path_buf(), definitions);
+        true
+    }
+
+    /// Index the DDL schema files at `paths`, replacing those indexed before
+    ///
+    /// Reads the disk; call it off the async runtime.
+    pub fn index_schema_files(&self, paths: &[PathBuf]) {
+        let indexed = paths
+            .iter()
+            .filter_map(|path| Some((path.clone(), read_definitions(path)?)))
+            .collect();
+        *self.schema_files.write().unwrap() = indexed;
+    }
+
+    /// Whether the file at `path` is indexed
+    pub fn contains(&self, path: &Path) -> bool {
+        self.files.read().unwrap().contains_key(path)
+    }
+
+    /// Definitions of every indexed file, in path order, then of the schema
+    /// files outside the workspace roots
+    pub fn definitions(&self) -> Vec<WorkspaceDefinition> {
+        let files = self.files.read().unwrap();
+        let schema_files = self.schema_files.read().unwrap();
+        let outside = schema_files
+            .iter()
+            .filter(|(path, _)| !files.contains_key(*path))
+            .map(|(_, definitions)| definitions);
+        files.values().chain(outside).flatten().cloned().collect()
+    }
+
+    /// Record the column changes of an open document
+    ///
+    /// Returns whether they differ from what was recorded before.
+    pub fn update_open_document(&self, uri: &Url, content: &str) -> bool {
+        let migrations = column_migrations(uri, content);
+        let mut recorded = self.migrations.write().unwrap();
+        if recorded.get(uri).map_or(&[][..], Vec::as_slice) == migrations.as_slice() {
+            return false;
+        }
+        if migrations.is_empty() {
+            recorded.remove(uri);
+        } else {
+            recorded.insert(uri.clone(), migrations);
+        }
+        true
+    }
+
+    /// Forget the column changes of a closed document
+    ///
+    /// Returns whether it had any.
+    pub fn close_document(&self, uri: &Url) -> bool {
+        self.migrations.write().unwrap().remove(uri).is_some()
+    }
+
+    /// Forget the column changes of documents no longer in `open`
+    ///
+    /// Returns whether any were forgotten.
+    pub fn retain_open_documents(&self, open: &[Url]) -> bool {
+        let mut recorded = self.migrations.write().unwrap();
+        let before = recorded.len();
+        recorded.retain(|uri, _| open.contains(uri));
+        recorded.len() != before
+    }
+
+    /// Column changes pending in open documents other than `except`, by
+    /// document URI and then in document order
+    pub fn pending_migrations(&self, except: &Url) -> Vec<ColumnMigration> {
+        self.migrations
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(uri, _)| *uri != except)
+            .flat_map(|(_, migrations)| migrations.iter().cloned())
+            .collect()
+    }
+
+    fn files_under(&self, root: &Path) -> usize {
+        self.files
+            .read()
+            .unwrap()
+            .keys()
+            .filter(|path| path.starts_with(root))
+            .count()
+    }
+}
+
+fn read_definitions(path: &Path) -> Option<Vec<WorkspaceDefinition>> {
+    let uri = Url::from_file_path(path).ok()?;
+    let content = std::fs::read_to_string(path).ok()?;
+    Some(index_document(&uri, &content))
+}
+
+/// Collect the accepted files under `dir` into `files`
+///
+/// `relative` is the path of `dir` from the root, `/`-separated.
+/// Returns `false` when `max_files` was reached before the walk finished.
+fn walk(
+    dir: &Path,
+    relative: &str,
+    filter: &Filter,
+    ignores: &mut Vec<Gitignore>,
+    files: &mut Vec<PathBuf>,
+    max_files: usize,
+) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return true;
+    };
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    let gitignore = Gitignore::read(dir, relative);
+    let pushed = gitignore.is_some();
+    ignores.extend(gitignore);
+
+    let mut complete = true;
+    for entry in entries {
+        // Symlinks are skipped so that link cycles can't trap the walk
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let child = join(relative, &name);
+        let is_dir = file_type.is_dir();
+
+        if filter.skips(ignores, &child, is_dir) {
+            continue;
+        }
+        if is_dir {
+            complete = walk(&entry.path(), &child, filter, ignores, files, max_files);
+        } else if file_type.is_file() && filter.includes(&child) {
+            if files.len() >= max_files {
+                complete = false;
+            } else {
+                files.push(entry.path());
+            }
+        }
+        if !complete {
+            break;
+        }
+    }
+
+    if pushed {
+        ignores.pop();
+    }
+    complete
+}
+
+/// Whether the walk from `root` would index the file at `path`
+fn accepts(root: &Path, path: &Path, config: &WorkspaceIndexConfig) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+    let components: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    let Some((file, dirs)) = components.split_last() else {
+        return false;
+    };
+
+    let filter = Filter::new(config);
+    let mut ignores: Vec<Gitignore> = Gitignore::read(root, "").into_iter().collect();
+    let mut dir = root.to_path_buf();
+    let mut current = String::new();
+    for name in dirs {
+        current = join(&current, name);
+        if filter.skips(&ignores, &current, true) {
+            return false;
+        }
+        dir.push(name);
+        ignores.extend(Gitignore::read(&dir, &current));
+    }
+
+    let file = join(&current, file);
+    !filter.skips(&ignores, &file, false) && filter.includes(&file)
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{dir}/{name}")
+    }
+}
+
+/// Include and exclude globs of the index settings
+struct Filter<'a> {
+    include: &'a [String],
+    exclude: &'a [String],
+}
+
+impl<'a> Filter<'a> {
+    fn new(config: &'a WorkspaceIndexConfig) -> Self {
+        Self {
+            include: &config.include,
+            exclude: &config.exclude,
+        }
+    }
+
+    /// Whether the entry at `relative` is ignored or excluded
+    fn skips(&self, ignores: &[Gitignore], relative: &str, is_dir: bool) -> bool {
+        let ignored = ignores
+            .iter()
+            .filter_map(|gitignore| gitignore.matches(relative, is_dir))
+            .next_back()
+            .unwrap_or(false);
+        ignored || self.exclude.iter().any(|glob| glob_matches(glob, relative))
+    }
+
+    fn includes(&self, relative: &str) -> bool {
+        self.include.iter().any(|glob| glob_matches(glob, relative))
+    }
+}
+
+/// Rules of one `.gitignore` file
+struct Gitignore {
+    /// Directory holding the file, relative to the root
+    base: String,
+    rules: Vec<IgnoreRule>,
+}
+
+struct IgnoreRule {
+    glob: String,
+    /// `!pattern`: re-include what an earlier rule ignored
+    negated: bool,
+    /// `pattern/`: only match directories
+    dir_only: bool,
+}
+
+impl Gitignore {
+    fn read(dir: &Path, base: &str) -> Option<Self> {
+        let content = std::fs::read_to_string(dir.join(GITIGNORE_FILE)).ok()?;
+        Some(Self::parse(base, &content))
+    }
+
+    fn parse(base: &str, content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                // A slash other than a trailing one anchors the pattern to this directory
+                let glob = match line.strip_prefix('/') {
+                    Some(anchored) if !anchored.contains('/') => format!("./{anchored}"),
+                    Some(anchored) => anchored.to_string(),
+                    None => line.to_string(),
+                };
+                IgnoreRule {
+                    glob,
+                    negated,
+                    dir_only,
+                }
+            })
+            .collect();
+
+        Self {
+            base: base.to_string(),
+            rules,
+        }
+    }
+
+    /// `Some(true)` if the last matching rule ignores the entry,
+    /// `Some(false)` if it re-includes it, `None` if no rule matches
+    fn matches(&self, relative: &str, is_dir: bool) -> Option<bool> {
+        let relative = if self.base.is_empty() {
+            relative
+        } else {
+            relative.strip_prefix(&self.base)?.strip_prefix('/')?
+        };
+        self.rules
+            .iter()
+            .filter(|rule| is_dir || !rule.dir_only)
+            .filter(|rule| glob_matches(&rule.glob, relative))
+            .map(|rule| !rule.negated)
+            .next_back()
+    }
+}
+
+/// Whether the relative `/`-separated `path` matches `glob`
+///
+/// `*` and `?` match within one component and `**` matches any number of
+/// components. A glob without `/` matches the last component at any depth;
+/// prefix it with `./` to only match at the top level.
+pub fn glob_matches(glob: &str, path: &str) -> bool {
+    let path: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    if let Some(top_level) = glob.strip_prefix("./") {
+        let glob: Vec<&str> = top_level.split('/').filter(|c| !c.is_empty()).collect();
+        return components_match(&glob, &path);
+    }
+    if !glob.contains('/') {
+        return path
+            .last()
+            .is_some_and(|name| wildcard_match(glob.as_bytes(), name.as_bytes()));
+    }
+    let glob: Vec<&str> = glob.split('/').filter(|c| !c.is_empty()).collect();
+    components_match(&glob, &path)
+}
+
+fn components_match(glob: &[&str], path: &[&str]) -> bool {
+    match glob.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| components_match(rest, &path[skip..])),
+        Some((first, rest)) => path.split_first().is_some_and(|(name, path_rest)| {
+            wildcard_match(first.as_bytes(), name.as_bytes()) && components_match(rest, path_rest)
+        }),
+    }
+}
+
+/// Match one component against a pattern of `*` and `?` wildcards
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| wildcard_match(rest, &name[skip..])),
+        Some((b'?', rest)) => !name.is_empty() && wildcard_match(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && wildcard_match(rest, &name[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unified_sql_lsp_ir::Dialect;
+
+    fn temp_tree(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("usql-index-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for (path, content) in files {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        root
+    }
+
+    fn names(index: &WorkspaceIndex) -> Vec<String> {
+        index.definitions().into_iter().map(|d| d.name).collect()
+    }
+
+    #[test]
+    fn test_index_skips_ignored_and_excluded_files() {
+        let root = temp_tree(
+            "ignored",
+            &[
+                (".gitignore", "generated/\n*.tmp.sql\n"),
+                ("db/schema.sql", "CREATE TABLE users (id INT);"),
+                ("db/scratch.tmp.sql", "CREATE TABLE scratch (id INT);"),
+                (
+                    "generated/schema.sql",
+                    "CREATE TABLE generated_table (id INT);",
+                ),
+                (
+                    "node_modules/pkg/schema.sql",
+                    "CREATE TABLE vendored (id INT);",
+                ),
+                ("notes.txt", "CREATE TABLE notes (id INT);"),
+            ],
+        );
+
+        let index = WorkspaceIndex::new();
+        let summary = index.index_root(&root, &WorkspaceIndexConfig::default());
+
+        assert_eq!(
+            summary,
+            IndexSummary {
+                files: 1,
+                truncated: false
+            }
+        );
+        assert_eq!(names(&index), vec!["users"]);
+    }
+
+    #[test]
+    fn test_index_nested_gitignore_and_negation() {
+        let root = temp_tree(
+            "nested",
+            &[
+                ("app/.gitignore", "*.sql\n!keep.sql\n"),
+                ("app/drop.sql", "CREATE TABLE dropped (id INT);"),
+                ("app/keep.sql", "CREATE TABLE kept (id INT);"),
+                ("drop.sql", "CREATE TABLE top_level (id INT);"),
+            ],
+        );
+
+        let index = WorkspaceIndex::new();
+        index.index_root(&root, &WorkspaceIndexConfig::default());
+
+        assert_eq!(names(&index), vec!["kept", "top_level"]);
+    }
+
+    #[test]
+    fn test_index_dialect_file_extensions() {
+        let root = temp_tree(
+            "extensions",
+            &[
+                ("schema.sql", "CREATE TABLE users (id INT);"),
+                ("functions.pgsql", "CREATE TABLE audit (id INT);"),
+                ("legacy.mysql", "CREATE TABLE legacy (id INT);"),
+            ],
+        );
+
+        let index = WorkspaceIndex::new();
+        index.index_root(
+            &root,
+            &WorkspaceIndexConfig::for_dialect(Dialect::PostgreSQL),
+        );
+        assert_eq!(names(&index), vec!["audit", "users"]);
+
+        index.index_root(&root, &WorkspaceIndexConfig::for_dialect(Dialect::MySQL));
+        assert_eq!(names(&index), vec!["legacy", "users"]);
+    }
+
+    #[test]
+    fn test_index_caps_file_count() {
+        let root = temp_tree(
+            "capped",
+            &[
+                ("a.sql", "CREATE TABLE a (id INT);"),
+                ("b.sql", "CREATE TABLE b (id INT);"),
+                ("c.sql", "CREATE TABLE c (id INT);"),
+            ],
+        );
+        let config = WorkspaceIndexConfig {
+            max_files: 2,
+            ..Default::default()
+        };
+
+        let index = WorkspaceIndex::new();
+        let summary = index.index_root(&root, &config);
+
+        assert!(summary.truncated);
+        assert_eq!(names(&index), vec!["a", "b"]);
+        assert!(!index.update_file(&root, &root.join("c.sql"), &config));
+    }
+
+    #[test]
+    fn test_update_file_applies_filters() {
+        let root = temp_tree(
+            "update",
+            &[
+                (".gitignore", "generated/\n"),
+                ("db/schema.sql", "CREATE TABLE users (id INT);"),
+            ],
+        );
+        let config = WorkspaceIndexConfig::default();
+        let index = WorkspaceIndex::new();
+        index.index_root(&root, &config);
+
+        let ignored = root.join("generated/new.sql");
+        std::fs::create_dir_all(ignored.parent().unwrap()).unwrap();
+        std::fs::write(&ignored, "CREATE TABLE generated_table (id INT);").unwrap();
+        assert!(!index.update_file(&root, &ignored, &config));
+
+        let added = root.join("db/orders.sql");
+        std::fs::write(&added, "CREATE TABLE orders (id INT);").unwrap();
+        assert!(index.update_file(&root, &added, &config));
+        assert_eq!(names(&index), vec!["orders", "users"]);
+
+        std::fs::remove_file(&added).unwrap();
+        assert!(index.update_file(&root, &added, &config));
+        assert!(!index.contains(&added));
+
+        index.remove_root(&root);
+        assert!(index.definitions().is_empty());
+    }
+
+    #[test]
+    fn test_index_schema_files_outside_roots() {
+        let root = temp_tree(
+            "schema-files",
+            &[("db/schema.sql", "CREATE TABLE users (id INT);")],
+        );
+        let schema = temp_tree(
+            "schema-source",
+            &[("schema.sql", "CREATE TABLE orders (id INT);")],
+        );
+
+        let index = WorkspaceIndex::new();
+        index.index_root(&root, &WorkspaceIndexConfig::default());
+        index.index_schema_files(&[schema.join("schema.sql"), root.join("db/schema.sql")]);
+        assert_eq!(names(&index), vec!["users", "orders"]);
+
+        // A schema file is read again only when the schema files are reindexed
+        std::fs::write(schema.join("schema.sql"), "CREATE TABLE invoices (id INT);").unwrap();
+        assert_eq!(names(&index), vec!["users", "orders"]);
+        index.index_schema_files(&[schema.join("schema.sql")]);
+        assert_eq!(names(&index), vec!["users", "invoices"]);
+
+        index.index_schema_files(&[]);
+        assert_eq!(names(&index), vec!["users"]);
+    }
+
+    #[test]
+    fn test_pending_migrations_of_open_documents() {
+        let migration = Url::parse("file:///workspace/migration.sql").unwrap();
+        let report = Url::parse("file:///workspace/report.sql").unwrap();
+        let index = WorkspaceIndex::new();
+
+        assert!(index.update_open_document(
+            &migration,
+            "ALTER TABLE users DROP COLUMN legacy_flag, ADD COLUMN note TEXT;"
+        ));
+        assert!(!index.update_open_document(&report, "SELECT legacy_flag FROM users;"));
+        // Unchanged content records nothing new
+        assert!(!index.update_open_document(
+            &migration,
+            "ALTER TABLE users DROP COLUMN legacy_flag, ADD COLUMN note TEXT;"
+        ));
+
+        let pending = index.pending_migrations(&report);
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].removed_column(), Some("legacy_flag"));
+        assert!(index.pending_migrations(&migration).is_empty());
+
+        assert!(index.close_document(&migration));
+        assert!(index.pending_migrations(&report).is_empty());
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("**/*.sql", "schema.sql"));
+        assert!(glob_matches("**/*.sql", "db/migrations/001.sql"));
+        assert!(glob_matches("db/**/*.sql", "db/a/b/c.sql"));
+        assert!(!glob_matches("db/*.sql", "db/a/c.sql"));
+        assert!(glob_matches("node_modules", "web/node_modules"));
+        assert!(glob_matches("./build", "build"));
+        assert!(!glob_matches("./build", "web/build"));
+        assert!(glob_matches("migration_??.sql", "db/migration_01.sql"));
+    }
+}
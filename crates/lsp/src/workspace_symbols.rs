@@ -0,0 +1,755 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Workspace Symbols
+//!
+//! This module provides LSP workspace symbols (`workspace/symbol`), letting
+//! clients search tables, views and CTEs across the catalog and the open
+//! documents.
+//!
+//! ## Sources
+//!
+//! - **Workspace definitions**: `CREATE TABLE` / `CREATE VIEW` statements,
+//!   `SELECT ... INTO` targets and `WITH` CTE names found in open documents
+//!   (and, through [`crate::workspace_index`], the SQL files of the workspace
+//!   and the DDL schema files)
+//! - **Catalog**: every table and view known to the catalog, when it answers
+//!
+//! A catalog table that is also created in the workspace points at the
+//! `CREATE` statement; otherwise it gets a virtual `untitled:` location.
+//!
+//! ## Kinds
+//!
+//! - Tables → `Struct`
+//! - Views → `Interface`
+//! - CTEs → `Variable`
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use tower_lsp::lsp_types::{
+    Location, OneOf, Range, SymbolInformation, SymbolKind, Url, WorkspaceLocation, WorkspaceSymbol,
+};
+use tracing::warn;
+use unified_sql_lsp_catalog::{Catalog, TableType};
+
+use crate::document::Document;
+use crate::token_scan::{Token, skip_parens, tokenize};
+
+/// Kind of object defined in a workspace document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefinitionKind {
+    Table,
+    View,
+    Cte,
+}
+
+impl DefinitionKind {
+    fn symbol_kind(self) -> SymbolKind {
+        match self {
+            DefinitionKind::Table => SymbolKind::STRUCT,
+            DefinitionKind::View => SymbolKind::INTERFACE,
+            DefinitionKind::Cte => SymbolKind::VARIABLE,
+        }
+    }
+}
+
+/// An object defined in a workspace document
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspaceDefinition {
+    /// Object name (without schema qualifier)
+    pub name: String,
+
+    /// Schema qualifier, if written (`CREATE TABLE app.users`)
+    pub schema: Option<String>,
+
+    /// Kind of object
+    pub kind: DefinitionKind,
+
+    /// Document the definition lives in
+    pub uri: Url,
+
+    /// Range of the object name
+    pub range: Range,
+
+    /// Column names, when the definition is a query (`CREATE TABLE ... AS
+    /// SELECT`, `SELECT ... INTO`)
+    pub columns: Vec<String>,
+}
+
+/// Workspace symbol provider
+///
+/// Combines catalog tables with definitions found in workspace documents.
+pub struct WorkspaceSymbolProvider {
+    catalog: Option<Arc<dyn Catalog>>,
+    limit: usize,
+    indexed: Vec<WorkspaceDefinition>,
+}
+
+impl WorkspaceSymbolProvider {
+    /// Create a provider listing workspace definitions only
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum number of symbols returned by a search
+    pub fn new(limit: usize) -> Self {
+        Self {
+            catalog: None,
+            limit,
+            indexed: Vec::new(),
+        }
+    }
+
+    /// Also list the tables and views of `catalog`
+    pub fn with_catalog(mut self, catalog: Arc<dyn Catalog>) -> Self {
+        self.catalog = Some(catalog);
+        self
+    }
+
+    /// Also list the definitions of indexed workspace files
+    ///
+    /// Files passed as documents to [`Self::search`] take their definitions
+    /// from the document, whose content may be newer than the disk.
+    pub fn with_index(mut self, definitions: Vec<WorkspaceDefinition>) -> Self {
+        self.indexed = definitions;
+        self
+    }
+
+    /// Search symbols matching `query`
+    ///
+    /// Matching is case-insensitive and fuzzy (the query characters must
+    /// appear in order). Results are ordered by match quality, then name, and
+    /// capped at the configured limit. A failing catalog is logged and
+    /// skipped, so workspace definitions are still listed.
+    pub async fn search(&self, query: &str, documents: &[Document]) -> Vec<WorkspaceSymbol> {
+        // Scratch buffers and embedded SQL aren't part of the workspace
+        let mut definitions: Vec<WorkspaceDefinition> = documents
+            .iter()
+            .filter(|doc| !doc.is_virtual())
+            .flat_map(|doc| index_document(doc.uri(), &doc.get_content()))
+            .collect();
+        definitions.extend(
+            self.indexed
+                .iter()
+                .filter(|d| !documents.iter().any(|doc| doc.uri() == &d.uri))
+                .cloned(),
+        );
+
+        let mut symbols = Vec::new();
+        let mut seen = HashSet::new();
+
+        let tables = match &self.catalog {
+            Some(catalog) => catalog.list_tables().await.unwrap_or_else(|e| {
+                warn!("Catalog tables unavailable for workspace symbols: {}", e);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        for table in tables {
+            let kind = match table.table_type {
+                TableType::View | TableType::MaterializedView => DefinitionKind::View,
+                _ => DefinitionKind::Table,
+            };
+            let location = definitions
+                .iter()
+                .find(|d| d.kind != DefinitionKind::Cte && d.name.eq_ignore_ascii_case(&table.name))
+                .map(|d| OneOf::Left(Location::new(d.uri.clone(), d.range)))
+                .unwrap_or_else(|| OneOf::Right(catalog_location(&table.schema, &table.name)));
+
+            seen.insert(table.name.to_lowercase());
+            symbols.push(symbol(
+                table.name,
+                kind,
+                Some(table.schema).filter(|s| !s.is_empty()),
+                location,
+            ));
+        }
+
+        for definition in definitions {
+            // CTEs are local to their statement, so each one is listed
+            let key = definition.name.to_lowercase();
+            if definition.kind != DefinitionKind::Cte && !seen.insert(key) {
+                continue;
+            }
+            let container = match definition.kind {
+                DefinitionKind::Cte => Some("WITH".to_string()),
+                _ => definition.schema,
+            };
+            symbols.push(symbol(
+                definition.name,
+                definition.kind,
+                container,
+                OneOf::Left(Location::new(definition.uri, definition.range)),
+            ));
+        }
+
+        let mut scored: Vec<(u32, WorkspaceSymbol)> = symbols
+            .into_iter()
+            .filter_map(|s| fuzzy_score(query, &s.name).map(|score| (score, s)))
+            .collect();
+        scored.sort_by(|(a_score, a), (b_score, b)| {
+            a_score
+                .cmp(b_score)
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
+
+        scored
+            .into_iter()
+            .take(self.limit)
+            .map(|(_, s)| s)
+            .collect()
+    }
+
+    /// Convert workspace symbols to the flat `SymbolInformation` response
+    ///
+    /// Symbols without a range point at the start of their virtual document.
+    #[allow(deprecated)]
+    pub fn to_symbol_information(symbols: Vec<WorkspaceSymbol>) -> Vec<SymbolInformation> {
+        symbols
+            .into_iter()
+            .map(|s| SymbolInformation {
+                name: s.name,
+                kind: s.kind,
+                tags: s.tags,
+                deprecated: None,
+                location: match s.location {
+                    OneOf::Left(location) => location,
+                    OneOf::Right(WorkspaceLocation { uri }) => Location::new(uri, Range::default()),
+                },
+                container_name: s.container_name,
+            })
+            .collect()
+    }
+}
+
+fn symbol(
+    name: String,
+    kind: DefinitionKind,
+    container_name: Option<String>,
+    location: OneOf<Location, WorkspaceLocation>,
+) -> WorkspaceSymbol {
+    WorkspaceSymbol {
+        name,
+        kind: kind.symbol_kind(),
+        tags: None,
+        container_name,
+        location,
+        data: None,
+    }
+}
+
+/// Virtual location for a catalog object that is not defined in the workspace
+fn catalog_location(schema: &str, name: &str) -> WorkspaceLocation {
+    let path = if schema.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", schema, name)
+    };
+    let uri = Url::parse(&format!("untitled:catalog/{}", path))
+        .unwrap_or_else(|_| Url::parse("untitled:catalog").expect("valid URL"));
+    WorkspaceLocation { uri }
+}
+
+/// Score a fuzzy match of `query` against `name` (lower is better)
+///
+/// Returns `None` if the query characters do not appear in order.
+fn fuzzy_score(query: &str, name: &str) -> Option<u32> {
+    let query = query.to_lowercase();
+    let name = name.to_lowercase();
+
+    if query.is_empty() || name == query {
+        return Some(0);
+    }
+    if name.starts_with(&query) {
+        return Some(1);
+    }
+    if name.contains(&query) {
+        return Some(2);
+    }
+
+    // Subsequence match, penalized by the characters skipped
+    let mut gaps = 0;
+    let mut chars = name.chars();
+    for q in query.chars() {
+        loop {
+            let c = chars.next()?;
+            if c == q {
+                break;
+            }
+            gaps += 1;
+        }
+    }
+    Some(3 + gaps)
+}
+
+/// Find the tables, views and CTEs defined in a document
+///
+/// This is a lightweight token scan rather than a full parse, so it also
+/// works for documents that do not parse cleanly yet.
+pub fn index_document(uri: &Url, content: &str) -> Vec<WorkspaceDefinition> {
+    let tokens = tokenize(content);
+    let mut definitions = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        if tokens[i].is_keyword("CREATE") {
+            if let Some((definition, next)) = parse_create(uri, &tokens, i + 1) {
+                definitions.push(definition);
+                i = next;
+                continue;
+            }
+        } else if tokens[i].is_keyword("SELECT") {
+            if let Some(definition) = parse_select_into(uri, &tokens, i + 1) {
+                definitions.push(definition);
+            }
+        } else if tokens[i].is_keyword("WITH") {
+            let next = parse_with(uri, &tokens, i + 1, &mut definitions);
+            if next > i + 1 {
+                i = next;
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    definitions
+}
+
+/// Parse `[OR REPLACE] [TEMP|TEMPORARY] [MATERIALIZED] TABLE|VIEW [IF NOT EXISTS] name`
+fn parse_create(uri: &Url, tokens: &[Token], mut i: usize) -> Option<(WorkspaceDefinition, usize)> {
+    while tokens.get(i).is_some_and(|t| {
+        ["OR", "REPLACE", "TEMP", "TEMPORARY", "MATERIALIZED"]
+            .iter()
+            .any(|k| t.is_keyword(k))
+    }) {
+        i += 1;
+    }
+
+    let kind = match tokens.get(i)? {
+        t if t.is_keyword("TABLE") => DefinitionKind::Table,
+        t if t.is_keyword("VIEW") => DefinitionKind::View,
+        _ => return None,
+    };
+    i += 1;
+
+    if tokens.get(i).is_some_and(|t| t.is_keyword("IF")) {
+        i += 3; // IF NOT EXISTS
+    }
+
+    let first = tokens.get(i).filter(|t| t.is_identifier())?;
+    i += 1;
+    let (schema, name) = if tokens.get(i).is_some_and(|t| t.is_symbol('.'))
+        && tokens.get(i + 1).is_some_and(Token::is_identifier)
+    {
+        i += 2;
+        (Some(first.text.clone()), &tokens[i - 1])
+    } else {
+        (None, first)
+    };
+
+    // `AS SELECT`, or MySQL's `CREATE TABLE name SELECT`
+    let mut query = i;
+    if tokens.get(query).is_some_and(|t| t.is_keyword("AS")) {
+        query += 1;
+    }
+    while tokens.get(query).is_some_and(|t| t.is_symbol('(')) {
+        query += 1;
+    }
+    let columns = if tokens.get(query).is_some_and(|t| t.is_keyword("SELECT")) {
+        projection_columns(tokens, query + 1).0
+    } else {
+        Vec::new()
+    };
+
+    Some((
+        WorkspaceDefinition {
+            name: name.text.clone(),
+            schema,
+            kind,
+            uri: uri.clone(),
+            range: name.range,
+            columns,
+        },
+        i,
+    ))
+}
+
+/// Parse the target of `SELECT ... INTO [TEMP|TEMPORARY|UNLOGGED] [TABLE] name`
+///
+/// `i` is the index after `SELECT`. MySQL's `INTO @var` and `INTO OUTFILE`
+/// are not tables.
+fn parse_select_into(uri: &Url, tokens: &[Token], i: usize) -> Option<WorkspaceDefinition> {
+    let (columns, mut i) = projection_columns(tokens, i);
+    if !tokens.get(i).is_some_and(|t| t.is_keyword("INTO")) {
+        return None;
+    }
+    i += 1;
+    while tokens.get(i).is_some_and(|t| {
+        ["TEMP", "TEMPORARY", "UNLOGGED", "TABLE"]
+            .iter()
+            .any(|k| t.is_keyword(k))
+    }) {
+        i += 1;
+    }
+
+    let first = tokens
+        .get(i)
+        .filter(|t| t.is_identifier() && !t.is_keyword("OUTFILE") && !t.is_keyword("DUMPFILE"))?;
+    let (schema, name) = if tokens.get(i + 1).is_some_and(|t| t.is_symbol('.'))
+        && tokens.get(i + 2).is_some_and(Token::is_identifier)
+    {
+        (Some(first.text.clone()), &tokens[i + 2])
+    } else {
+        (None, first)
+    };
+
+    Some(WorkspaceDefinition {
+        name: name.text.clone(),
+        schema,
+        kind: DefinitionKind::Table,
+        uri: uri.clone(),
+        range: name.range,
+        columns,
+    })
+}
+
+/// Output column names of the projection starting at `i` (after `SELECT`)
+///
+/// Returns the names and the index of the token ending the projection
+/// (`FROM`, `INTO`, `;` or a closing parenthesis). Items without a name of
+/// their own, like `*` or an unaliased expression, are skipped.
+fn projection_columns(tokens: &[Token], mut i: usize) -> (Vec<String>, usize) {
+    let mut columns = Vec::new();
+    let mut item: Vec<&Token> = Vec::new();
+    let mut depth = 0;
+
+    while let Some(token) = tokens.get(i) {
+        if depth == 0
+            && (token.is_keyword("FROM")
+                || token.is_keyword("INTO")
+                || token.is_symbol(';')
+                || token.is_symbol(')')
+                || token.is_symbol(','))
+        {
+            columns.extend(projection_item_name(&item));
+            item.clear();
+            if !token.is_symbol(',') {
+                break;
+            }
+        } else {
+            if token.is_symbol('(') {
+                depth += 1;
+            } else if token.is_symbol(')') {
+                depth -= 1;
+            }
+            item.push(token);
+        }
+        i += 1;
+    }
+    columns.extend(projection_item_name(&item));
+
+    (columns, i)
+}
+
+/// Name of one projection item: its alias, or the column it selects
+fn projection_item_name(item: &[&Token]) -> Option<String> {
+    let (last, rest) = item.split_last()?;
+    let named = match rest.last() {
+        None => true,
+        Some(previous) => {
+            previous.is_keyword("AS") || previous.is_symbol('.') || previous.is_identifier()
+        }
+    };
+    let is_name = last.is_identifier()
+        && !last.text.starts_with(|c: char| c.is_ascii_digit())
+        && !["DISTINCT", "END", "NULL", "TRUE", "FALSE"]
+            .iter()
+            .any(|k| last.is_keyword(k));
+
+    (named && is_name).then(|| last.text.clone())
+}
+
+/// Parse the CTE list after `WITH`, returning the index after the last CTE
+fn parse_with(
+    uri: &Url,
+    tokens: &[Token],
+    mut i: usize,
+    definitions: &mut Vec<WorkspaceDefinition>,
+) -> usize {
+    if tokens.get(i).is_some_and(|t| t.is_keyword("RECURSIVE")) {
+        i += 1;
+    }
+
+    loop {
+        let Some(name) = tokens.get(i).filter(|t| t.is_identifier()) else {
+            return i;
+        };
+        let mut j = i + 1;
+
+        // Optional column list
+        if tokens.get(j).is_some_and(|t| t.is_symbol('(')) {
+            j = skip_parens(tokens, j);
+        }
+        if !tokens.get(j).is_some_and(|t| t.is_keyword("AS")) {
+            return i;
+        }
+        j += 1;
+        while tokens
+            .get(j)
+            .is_some_and(|t| t.is_keyword("NOT") || t.is_keyword("MATERIALIZED"))
+        {
+            j += 1;
+        }
+
+        definitions.push(WorkspaceDefinition {
+            name: name.text.clone(),
+            schema: None,
+            kind: DefinitionKind::Cte,
+            uri: uri.clone(),
+            range: name.range,
+            columns: Vec::new(),
+        });
+
+        if tokens.get(j).is_some_and(|t| t.is_symbol('(')) {
+            j = skip_parens(tokens, j);
+        }
+        if !tokens.get(j).is_some_and(|t| t.is_symbol(',')) {
+            return j;
+        }
+        i = j + 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::Position;
+    use unified_sql_lsp_catalog::{
+        CatalogError, CatalogResult, ColumnMetadata, DataType, FunctionMetadata, TableMetadata,
+    };
+    use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+    fn catalog() -> Arc<dyn Catalog> {
+        Arc::new(
+            MockCatalogBuilder::new()
+                .with_table(
+                    TableMetadata::new("users", "public")
+                        .with_columns(vec![ColumnMetadata::new("id", DataType::Integer)]),
+                )
+                .with_table(TableMetadata::new("orders", "public"))
+                .with_table(TableMetadata::new("active_users", "public").with_type(TableType::View))
+                .build(),
+        )
+    }
+
+    fn document() -> Document {
+        let uri = Url::parse("file:///workspace/schema.sql").unwrap();
+        let sql = "-- CREATE TABLE commented_out (id INT);\n\
+                   CREATE TABLE IF NOT EXISTS app.users (\n  id INT PRIMARY KEY\n);\n\
+                   CREATE TABLE audit_log (id INT);\n\
+                   WITH recent_orders AS (SELECT * FROM orders), totals (n) AS (SELECT 1)\n\
+                   SELECT * FROM recent_orders;";
+        Document::new(uri, sql.to_string(), 1, "mysql".to_string())
+    }
+
+    fn find<'a>(symbols: &'a [WorkspaceSymbol], name: &str) -> &'a WorkspaceSymbol {
+        symbols
+            .iter()
+            .find(|s| s.name == name)
+            .unwrap_or_else(|| panic!("symbol {} not found", name))
+    }
+
+    #[test]
+    fn test_index_document() {
+        let doc = document();
+        let definitions = index_document(doc.uri(), &doc.get_content());
+
+        let names: Vec<_> = definitions
+            .iter()
+            .map(|d| (d.name.as_str(), d.kind))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("users", DefinitionKind::Table),
+                ("audit_log", DefinitionKind::Table),
+                ("recent_orders", DefinitionKind::Cte),
+                ("totals", DefinitionKind::Cte),
+            ]
+        );
+        assert_eq!(definitions[0].schema.as_deref(), Some("app"));
+        assert_eq!(
+            definitions[0].range,
+            Range::new(Position::new(1, 31), Position::new(1, 36))
+        );
+    }
+
+    #[test]
+    fn test_index_document_query_columns() {
+        let uri = Url::parse("file:///workspace/report.sql").unwrap();
+        let sql = "CREATE TABLE report AS (SELECT u.id, lower(email) AS mail, count(*), total FROM users u);\n\
+                   CREATE TABLE copy SELECT * FROM users;\n\
+                   SELECT DISTINCT id user_id, 1 INTO TEMP TABLE app.recent FROM users;\n\
+                   SELECT id INTO @last_id FROM users;\n\
+                   INSERT INTO audit SELECT id FROM users;";
+        let definitions = index_document(&uri, sql);
+
+        let tables: Vec<_> = definitions
+            .iter()
+            .map(|d| (d.name.as_str(), d.columns.clone()))
+            .collect();
+        assert_eq!(
+            tables,
+            vec![
+                (
+                    "report",
+                    vec!["id".to_string(), "mail".to_string(), "total".to_string()]
+                ),
+                ("copy", vec![]),
+                ("recent", vec!["user_id".to_string()]),
+            ]
+        );
+        assert_eq!(definitions[2].schema.as_deref(), Some("app"));
+        assert_eq!(definitions[2].kind, DefinitionKind::Table);
+    }
+
+    #[tokio::test]
+    async fn test_search_all_sources() {
+        let provider = WorkspaceSymbolProvider::new(100).with_catalog(catalog());
+        let symbols = provider.search("", &[document()]).await;
+
+        // Catalog table created in the workspace points at the CREATE statement
+        let users = find(&symbols, "users");
+        assert_eq!(users.kind, SymbolKind::STRUCT);
+        match &users.location {
+            OneOf::Left(location) => {
+                assert_eq!(location.uri.as_str(), "file:///workspace/schema.sql");
+                assert_eq!(location.range.start, Position::new(1, 31));
+            }
+            OneOf::Right(_) => panic!("expected a workspace location"),
+        }
+
+        // Catalog-only table gets a virtual location
+        let orders = find(&symbols, "orders");
+        match &orders.location {
+            OneOf::Right(location) => {
+                assert_eq!(location.uri.as_str(), "untitled:catalog/public.orders")
+            }
+            OneOf::Left(_) => panic!("expected a virtual location"),
+        }
+
+        assert_eq!(find(&symbols, "active_users").kind, SymbolKind::INTERFACE);
+        assert_eq!(find(&symbols, "audit_log").kind, SymbolKind::STRUCT);
+        assert_eq!(find(&symbols, "recent_orders").kind, SymbolKind::VARIABLE);
+
+        // users is listed once even though it is both in the catalog and the document
+        assert_eq!(symbols.iter().filter(|s| s.name == "users").count(), 1);
+        assert!(!symbols.iter().any(|s| s.name == "commented_out"));
+    }
+
+    #[tokio::test]
+    async fn test_search_fuzzy_ordering() {
+        let provider = WorkspaceSymbolProvider::new(100).with_catalog(catalog());
+        let symbols = provider.search("usr", &[document()]).await;
+
+        let names: Vec<_> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["users", "active_users"]);
+
+        let symbols = provider.search("ORD", &[document()]).await;
+        let names: Vec<_> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["orders", "recent_orders"]);
+    }
+
+    #[tokio::test]
+    async fn test_search_skips_virtual_documents() {
+        let provider = WorkspaceSymbolProvider::new(100).with_catalog(catalog());
+        let untitled = Document::new(
+            Url::parse("untitled:Untitled-1").unwrap(),
+            "CREATE TABLE scratch (id INT);".to_string(),
+            1,
+            "mysql".to_string(),
+        );
+        let symbols = provider.search("", &[untitled]).await;
+
+        assert!(!symbols.iter().any(|s| s.name == "scratch"));
+        assert!(matches!(find(&symbols, "users").location, OneOf::Right(_)));
+    }
+
+    #[tokio::test]
+    async fn test_search_indexed_files() {
+        let indexed_uri = Url::parse("file:///workspace/db/invoices.sql").unwrap();
+        let mut indexed = index_document(&indexed_uri, "CREATE TABLE invoices (id INT);");
+        // The open document's content wins over what was indexed from disk
+        indexed.extend(index_document(
+            document().uri(),
+            "CREATE TABLE stale_table (id INT);",
+        ));
+
+        let provider = WorkspaceSymbolProvider::new(100)
+            .with_catalog(catalog())
+            .with_index(indexed);
+        let symbols = provider.search("", &[document()]).await;
+
+        match &find(&symbols, "invoices").location {
+            OneOf::Left(location) => assert_eq!(location.uri, indexed_uri),
+            OneOf::Right(_) => panic!("expected a workspace location"),
+        }
+        assert!(!symbols.iter().any(|s| s.name == "stale_table"));
+    }
+
+    #[tokio::test]
+    async fn test_search_without_catalog_tables() {
+        struct Unreachable;
+
+        #[async_trait::async_trait]
+        impl Catalog for Unreachable {
+            async fn list_tables(&self) -> CatalogResult<Vec<TableMetadata>> {
+                Err(CatalogError::ConnectionFailed("refused".to_string()))
+            }
+
+            async fn get_columns(&self, _table: &str) -> CatalogResult<Vec<ColumnMetadata>> {
+                Ok(Vec::new())
+            }
+
+            async fn list_functions(&self) -> CatalogResult<Vec<FunctionMetadata>> {
+                Ok(Vec::new())
+            }
+        }
+
+        // Workspace definitions are listed when the catalog fails or is missing
+        for provider in [
+            WorkspaceSymbolProvider::new(100).with_catalog(Arc::new(Unreachable)),
+            WorkspaceSymbolProvider::new(100),
+        ] {
+            let symbols = provider.search("", &[document()]).await;
+            assert!(matches!(find(&symbols, "users").location, OneOf::Left(_)));
+            assert_eq!(find(&symbols, "recent_orders").kind, SymbolKind::VARIABLE);
+            assert!(!symbols.iter().any(|s| s.name == "orders"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_limit() {
+        let provider = WorkspaceSymbolProvider::new(2).with_catalog(catalog());
+        let symbols = provider.search("", &[document()]).await;
+        assert_eq!(symbols.len(), 2);
+    }
+
+    #[test]
+    fn test_to_symbol_information() {
+        let symbols = vec![symbol(
+            "orders".to_string(),
+            DefinitionKind::Table,
+            Some("public".to_string()),
+            OneOf::Right(catalog_location("public", "orders")),
+        )];
+
+        let info = WorkspaceSymbolProvider::to_symbol_information(symbols);
+        assert_eq!(
+            info[0].location.uri.as_str(),
+            "untitled:catalog/public.orders"
+        );
+        assert_eq!(info[0].container_name.as_deref(), Some("public"));
+    }
+}
@@ -0,0 +1,491 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! LspBackend integration tests
+//!
+//! Drives the backend through `LspService` the way a client would and records
+//! the notifications it sends back.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tower::{Service, ServiceExt};
+use tower_lsp::jsonrpc::{Request, Response};
+use tower_lsp::lsp_types::Url;
+use tower_lsp::{ClientSocket, LspService};
+use unified_sql_lsp_context::KeywordProvider;
+use unified_sql_lsp_lsp::backend::LspBackend;
+use unified_sql_lsp_lsp::events::ServerEvent;
+use unified_sql_lsp_lsp::workspace::WORKSPACE_CONFIG_FILE;
+
+/// Notifications the server has sent to the client so far
+type Sent = Arc<Mutex<Vec<Request>>>;
+
+async fn start() -> (LspService<LspBackend>, Sent) {
+    start_with(json!({}), Value::Null).await
+}
+
+/// Start a server for a client with `capabilities` whose
+/// `unifiedSqlLsp` settings section is `settings`
+async fn start_with(capabilities: Value, settings: Value) -> (LspService<LspBackend>, Sent) {
+    start_initialized(json!({ "capabilities": capabilities }), settings).await
+}
+
+/// Start a server with the given `initialize` params
+async fn start_initialized(params: Value, settings: Value) -> (LspService<LspBackend>, Sent) {
+    let (mut service, socket) = LspBackend::service();
+    let sent = record(socket, settings);
+
+    let initialize = Request::build("initialize").params(params).id(1).finish();
+    call(&mut service, initialize).await;
+    call(
+        &mut service,
+        Request::build("initialized").params(json!({})).finish(),
+    )
+    .await;
+
+    (service, sent)
+}
+
+/// Drain the client socket so server messages never block
+///
+/// Requests are answered like a client would: `workspace/configuration`
+/// with `settings` for each item, anything else with `null`.
+fn record(socket: ClientSocket, settings: Value) -> Sent {
+    let sent = Sent::default();
+    let sink = sent.clone();
+    let (mut requests, mut responses) = socket.split();
+    tokio::spawn(async move {
+        while let Some(request) = requests.next().await {
+            if let Some(id) = request.id().cloned() {
+                let result = match request.method() {
+                    "workspace/configuration" => {
+                        let items = request.params().and_then(|p| p["items"].as_array());
+                        json!(vec![settings.clone(); items.map_or(0, Vec::len)])
+                    }
+                    _ => Value::Null,
+                };
+                let _ = responses.send(Response::from_ok(id, result)).await;
+            }
+            sink.lock().unwrap().push(request);
+        }
+    });
+    sent
+}
+
+async fn call(service: &mut LspService<LspBackend>, request: Request) -> Option<Response> {
+    service.ready().await.unwrap().call(request).await.unwrap()
+}
+
+async fn open(service: &LspService<LspBackend>, uri: &Url) {
+    service
+        .inner()
+        .documents()
+        .open_document(
+            uri.clone(),
+            "SELECT  FROM users".to_string(),
+            3,
+            "sql".to_string(),
+        )
+        .await
+        .unwrap();
+}
+
+fn close_request(uri: &Url) -> Request {
+    Request::build("textDocument/didClose")
+        .params(json!({ "textDocument": { "uri": uri } }))
+        .finish()
+}
+
+/// `textDocument/publishDiagnostics` params sent for `uri`
+async fn published_diagnostics(sent: &Sent, uri: &Url) -> Vec<Value> {
+    // Let the recorder catch up with notifications already sent
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    sent.lock()
+        .unwrap()
+        .iter()
+        .filter(|r| r.method() == "textDocument/publishDiagnostics")
+        .filter_map(|r| r.params().cloned())
+        .filter(|p| p["uri"] == json!(uri))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_close_clears_diagnostics_once() {
+    let (mut service, sent) = start().await;
+    let uri = Url::parse("file:///close.sql").unwrap();
+    open(&service, &uri).await;
+
+    call(&mut service, close_request(&uri)).await;
+    // A duplicate close has nothing left to clear
+    call(&mut service, close_request(&uri)).await;
+
+    let published = published_diagnostics(&sent, &uri).await;
+    assert_eq!(published.len(), 1, "expected one clearing notification");
+    assert_eq!(published[0]["diagnostics"], json!([]));
+    assert_eq!(published[0]["version"], json!(3));
+}
+
+#[tokio::test]
+async fn test_close_then_complete_returns_empty() {
+    let (mut service, _sent) = start().await;
+    let uri = Url::parse("file:///late.sql").unwrap();
+    open(&service, &uri).await;
+    call(&mut service, close_request(&uri)).await;
+
+    let position =
+        json!({ "textDocument": { "uri": uri }, "position": { "line": 0, "character": 7 } });
+    for (id, method) in [(2, "textDocument/completion"), (3, "textDocument/hover")] {
+        let request = Request::build(method)
+            .params(position.clone())
+            .id(id)
+            .finish();
+        let response = call(&mut service, request).await.unwrap();
+        let (_, result) = response.into_parts();
+        assert_eq!(result.unwrap(), Value::Null, "{method} after close");
+    }
+}
+
+#[tokio::test]
+async fn test_syntax_tree_request() {
+    let (mut service, _sent) = start().await;
+    let uri = Url::parse("file:///tree.sql").unwrap();
+    // Open through the server so the document gets parsed
+    let did_open = Request::build("textDocument/didOpen")
+        .params(json!({ "textDocument": {
+            "uri": uri, "languageId": "sql", "version": 1, "text": "SELECT id FROM users"
+        } }))
+        .finish();
+    call(&mut service, did_open).await;
+
+    let request = Request::build("unifiedSqlLsp/syntaxTree")
+        .params(json!({ "textDocument": { "uri": uri } }))
+        .id(2)
+        .finish();
+    let response = call(&mut service, request).await.unwrap();
+    let (_, result) = response.into_parts();
+    let tree = result.unwrap();
+    assert!(
+        tree.as_str().unwrap().contains("select_statement"),
+        "unexpected tree: {tree}"
+    );
+
+    // Documents that aren't open have no tree
+    let closed = Url::parse("file:///closed.sql").unwrap();
+    let request = Request::build("unifiedSqlLsp/syntaxTree")
+        .params(json!({ "textDocument": { "uri": closed } }))
+        .id(3)
+        .finish();
+    let response = call(&mut service, request).await.unwrap();
+    let (_, result) = response.into_parts();
+    assert_eq!(result.unwrap(), Value::Null);
+}
+
+#[tokio::test]
+async fn test_resync_of_same_text_skips_reparse() {
+    let (mut service, _sent) = start().await;
+    let uri = Url::parse("file:///resync.sql").unwrap();
+    let text = "SELECT id FROM users";
+    let did_open = Request::build("textDocument/didOpen")
+        .params(json!({ "textDocument": {
+            "uri": uri, "languageId": "sql", "version": 1, "text": text
+        } }))
+        .finish();
+    call(&mut service, did_open).await;
+    let sync = |version: i32, text: &str| {
+        Request::build("textDocument/didChange")
+            .params(json!({
+                "textDocument": { "uri": uri, "version": version },
+                "contentChanges": [{ "text": text }],
+            }))
+            .finish()
+    };
+
+    // Focus changes resend the full text: only the version moves
+    call(&mut service, sync(2, text)).await;
+    call(&mut service, sync(3, text)).await;
+    let backend = service.inner();
+    assert_eq!(backend.document_sync().parse_count(), 1);
+    let document = backend.documents().get_document(&uri).await.unwrap();
+    assert_eq!(document.version(), 3);
+    assert!(document.tree().is_some());
+
+    call(&mut service, sync(4, "SELECT name FROM users")).await;
+    assert_eq!(service.inner().document_sync().parse_count(), 2);
+}
+
+#[tokio::test]
+async fn test_configuration_pulled_on_initialized() {
+    let capabilities = json!({ "workspace": {
+        "configuration": true,
+        "didChangeConfiguration": { "dynamicRegistration": true }
+    } });
+    let settings = json!({
+        "dialect": "postgresql",
+        "version": "14",
+        "connectionString": "postgresql://localhost/app",
+    });
+    let (service, sent) = start_with(capabilities, settings).await;
+
+    let config = service
+        .inner()
+        .get_config()
+        .await
+        .expect("settings applied");
+    assert_eq!(config.connection_string, "postgresql://localhost/app");
+
+    let methods: Vec<String> = sent
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|r| r.method().to_string())
+        .collect();
+    assert!(methods.contains(&"workspace/configuration".to_string()));
+    assert!(methods.contains(&"client/registerCapability".to_string()));
+}
+
+#[tokio::test]
+async fn test_configuration_not_pulled_without_capability() {
+    let settings = json!({ "dialect": "mysql", "connectionString": "mysql://localhost/app" });
+    let (service, sent) = start_with(json!({}), settings).await;
+
+    assert!(service.inner().get_config().await.is_none());
+    assert!(
+        !sent
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|r| r.method() == "workspace/configuration")
+    );
+}
+
+/// Workspace folder named `name` whose config file selects `dialect`
+fn workspace_folder(name: &str, dialect: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("usql-roots-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("schema.sql"), "CREATE TABLE users (id INT);").unwrap();
+    std::fs::write(
+        dir.join(WORKSPACE_CONFIG_FILE),
+        json!({ "dialect": dialect, "schemaSource": { "type": "ddl", "path": "schema.sql" } })
+            .to_string(),
+    )
+    .unwrap();
+    dir
+}
+
+/// Labels of the SELECT clause keywords offered for documents at `uri`
+async fn select_keywords(service: &LspService<LspBackend>, uri: &Url) -> HashSet<String> {
+    let config = service.inner().config_for(uri).await;
+    KeywordProvider::new(config.dialect)
+        .with_capabilities(config.version.capabilities())
+        .select_clause_keywords()
+        .labels()
+}
+
+#[tokio::test]
+async fn test_workspace_folders_use_their_own_dialect() {
+    let mysql = workspace_folder("mysql", "mysql");
+    let postgres = workspace_folder("postgres", "postgresql");
+    let folder = |path: &std::path::Path, name: &str| json!({ "uri": Url::from_directory_path(path).unwrap(), "name": name });
+    let params = json!({
+        "capabilities": {},
+        "workspaceFolders": [folder(&mysql, "mysql"), folder(&postgres, "postgres")],
+    });
+    let (mut service, _sent) = start_initialized(params, Value::Null).await;
+
+    let in_mysql = Url::from_file_path(mysql.join("a.sql")).unwrap();
+    let in_postgres = Url::from_file_path(postgres.join("sub/b.sql")).unwrap();
+    let mysql_keywords = select_keywords(&service, &in_mysql).await;
+    let postgres_keywords = select_keywords(&service, &in_postgres).await;
+    assert!(mysql_keywords.contains("LOCK IN SHARE MODE"));
+    assert!(!mysql_keywords.contains("FULL JOIN"));
+    assert!(postgres_keywords.contains("FULL JOIN"));
+    assert!(postgres_keywords.contains("DISTINCT ON"));
+
+    // Removing the PostgreSQL folder leaves its documents on the defaults
+    let change = Request::build("workspace/didChangeWorkspaceFolders")
+        .params(json!({ "event": { "added": [], "removed": [folder(&postgres, "postgres")] } }))
+        .finish();
+    call(&mut service, change).await;
+    assert!(
+        !select_keywords(&service, &in_postgres)
+            .await
+            .contains("FULL JOIN")
+    );
+
+    // And adding it back restores its config
+    let change = Request::build("workspace/didChangeWorkspaceFolders")
+        .params(json!({ "event": { "added": [folder(&postgres, "postgres")], "removed": [] } }))
+        .finish();
+    call(&mut service, change).await;
+    assert!(
+        select_keywords(&service, &in_postgres)
+            .await
+            .contains("FULL JOIN")
+    );
+
+    std::fs::remove_dir_all(&mysql).unwrap();
+    std::fs::remove_dir_all(&postgres).unwrap();
+}
+
+#[tokio::test]
+async fn test_status_sent_after_open() {
+    let schema = std::env::temp_dir().join(format!("usql-status-{}.sql", std::process::id()));
+    std::fs::write(
+        &schema,
+        "CREATE TABLE users (id INT);\nCREATE TABLE orders (id INT);",
+    )
+    .unwrap();
+    let capabilities = json!({ "workspace": { "configuration": true } });
+    let settings = json!({
+        "dialect": "mysql",
+        "version": "8.0",
+        "schemaSource": { "type": "ddl", "path": schema },
+    });
+    let (mut service, sent) = start_with(capabilities, settings).await;
+    let uri = Url::parse("file:///status.sql").unwrap();
+    let did_open = Request::build("textDocument/didOpen")
+        .params(json!({ "textDocument": {
+            "uri": uri, "languageId": "sql", "version": 1, "text": "SELECT id FROM users"
+        } }))
+        .finish();
+    call(&mut service, did_open).await;
+
+    // The table count lands in a status sent after the rate limit interval
+    tokio::time::sleep(Duration::from_millis(700)).await;
+    let statuses: Vec<Value> = sent
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|r| r.method() == "unifiedSqlLsp/status")
+        .filter_map(|r| r.params().cloned())
+        .collect();
+    let status = statuses.last().expect("status notification sent");
+    assert_eq!(status["uri"], json!(uri));
+    assert_eq!(status["dialect"], json!("MySQL"));
+    assert_eq!(status["version"], json!("8.0"));
+    assert!(status["parseTimeMs"].is_u64());
+    assert!(status["grammarAbiVersion"].is_u64());
+    assert_eq!(status["diagnostics"], json!(0));
+    assert_eq!(status["catalogTables"], json!(2));
+    assert_eq!(status["catalogStale"], json!(false));
+}
+
+#[tokio::test]
+async fn test_events_for_config_change_then_edit() {
+    let (mut service, _sent) = start().await;
+    let uri = Url::parse("file:///events.sql").unwrap();
+    let did_open = Request::build("textDocument/didOpen")
+        .params(json!({ "textDocument": {
+            "uri": uri, "languageId": "sql", "version": 1, "text": "SELECT id FROM users"
+        } }))
+        .finish();
+    call(&mut service, did_open).await;
+    let mut events = service.inner().subscribe_events();
+
+    let did_change_configuration = Request::build("workspace/didChangeConfiguration")
+        .params(json!({ "settings": { "unifiedSqlLsp": {
+            "dialect": "postgresql",
+            "version": "14",
+            "connectionString": "postgresql://localhost/app",
+        } } }))
+        .finish();
+    call(&mut service, did_change_configuration).await;
+    let did_change = Request::build("textDocument/didChange")
+        .params(json!({
+            "textDocument": { "uri": uri, "version": 2 },
+            "contentChanges": [{ "text": "SELECT name FROM users" }],
+        }))
+        .finish();
+    call(&mut service, did_change).await;
+
+    let mut recorded = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        recorded.push(event);
+    }
+    assert_eq!(
+        recorded,
+        vec![
+            ServerEvent::ConfigChanged,
+            ServerEvent::DocumentChanged { uri, version: 2 },
+        ]
+    );
+}
+
+/// Result of a `unifiedSqlLsp/serverInfo` request
+async fn server_info(service: &mut LspService<LspBackend>, id: i64) -> Value {
+    let request = Request::build("unifiedSqlLsp/serverInfo").id(id).finish();
+    let response = call(service, request).await.unwrap();
+    let (_, result) = response.into_parts();
+    result.unwrap()
+}
+
+#[tokio::test]
+async fn test_server_info_request() {
+    let dir = std::env::temp_dir().join(format!("usql-server-info-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let schema = dir.join("schema.sql");
+    std::fs::write(&schema, "CREATE TABLE users (id INT);").unwrap();
+    let capabilities = json!({ "workspace": { "configuration": true } });
+    let settings = json!({
+        "dialect": "mysql",
+        "version": "8.0",
+        "schemaSource": { "type": "ddl", "path": schema },
+    });
+    let (mut service, _sent) = start_with(capabilities, settings).await;
+
+    let info = server_info(&mut service, 2).await;
+    assert_eq!(info["version"], json!(unified_sql_lsp_lsp::VERSION));
+    let features = info["features"].as_array().unwrap();
+    assert!(features.contains(&json!("lsp")), "features: {info}");
+    assert!(
+        info["supportedAbiVersions"]["min"].as_u64()
+            <= info["supportedAbiVersions"]["max"].as_u64()
+    );
+    for dialect in info["dialects"].as_array().unwrap() {
+        for field in [
+            "grammar",
+            "dialect",
+            "abiVersion",
+            "nodeKindCount",
+            "compatible",
+        ] {
+            assert!(
+                dialect.get(field).is_some(),
+                "{field} missing from {dialect}"
+            );
+        }
+    }
+    assert_eq!(
+        info["commands"],
+        json!([
+            "unified-sql-lsp.refreshCatalog",
+            "unified-sql-lsp.diagnosticsStats",
+            "unified-sql-lsp.completionStats",
+            "unifiedSqlLsp.schemaDiff",
+        ])
+    );
+    // Schema files load on first use
+    assert_eq!(
+        info["catalog"],
+        json!({ "kind": "ddl", "connected": false })
+    );
+
+    let uri = Url::parse("file:///info.sql").unwrap();
+    open(&service, &uri).await;
+    let completion = Request::build("textDocument/completion")
+        .params(
+            json!({ "textDocument": { "uri": uri }, "position": { "line": 0, "character": 7 } }),
+        )
+        .id(3)
+        .finish();
+    call(&mut service, completion).await;
+
+    let info = server_info(&mut service, 4).await;
+    assert_eq!(info["catalog"], json!({ "kind": "ddl", "connected": true }));
+}
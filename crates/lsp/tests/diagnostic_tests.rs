@@ -197,10 +197,19 @@ async fn test_diagnostic_codes() {
     use unified_sql_lsp_lsp::diagnostic::DiagnosticCode;
 
     // Test all diagnostic codes
-    assert_eq!(DiagnosticCode::SyntaxError.as_str(), "SYNTAX-001");
-    assert_eq!(DiagnosticCode::UndefinedTable.as_str(), "SEMANTIC-001");
-    assert_eq!(DiagnosticCode::UndefinedColumn.as_str(), "SEMANTIC-002");
-    assert_eq!(DiagnosticCode::AmbiguousColumn.as_str(), "SEMANTIC-003");
+    assert_eq!(DiagnosticCode::SyntaxError.as_str(), "usql/syntax");
+    assert_eq!(
+        DiagnosticCode::UndefinedTable.as_str(),
+        "usql/unknown-table"
+    );
+    assert_eq!(
+        DiagnosticCode::UndefinedColumn.as_str(),
+        "usql/unknown-column"
+    );
+    assert_eq!(
+        DiagnosticCode::AmbiguousColumn.as_str(),
+        "usql/ambiguous-column"
+    );
 
     // Test custom code
     let custom = DiagnosticCode::Custom("CUSTOM-001".to_string());
@@ -243,7 +252,7 @@ async fn test_syntax_error_missing_from() {
             .collect();
         assert!(
             !syntax_errors.is_empty(),
-            "Should have SYNTAX-001 error code"
+            "Should have usql/syntax error code"
         );
         assert_eq!(syntax_errors[0].severity, DiagnosticSeverity::ERROR);
     }
@@ -390,7 +399,7 @@ async fn test_multiple_syntax_errors() {
             let all_syntax = diagnostics.iter().all(|d| {
                 d.code == Some(unified_sql_lsp_lsp::diagnostic::DiagnosticCode::SyntaxError)
             });
-            assert!(all_syntax, "All errors should be SYNTAX-001");
+            assert!(all_syntax, "All errors should be usql/syntax");
         }
     }
 }
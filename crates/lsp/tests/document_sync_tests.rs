@@ -11,12 +11,15 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tower_lsp::lsp_types::*;
+use unified_sql_lsp_catalog::TablePaging;
 use unified_sql_lsp_ir::Dialect;
 use unified_sql_lsp_lsp::config::{
-    ConnectionPoolConfig, DialectVersion, EngineConfig, SchemaFilter,
+    CatalogConfig, CompletionConfig, ConnectionPoolConfig, DiagnosticsConfig, DialectVersion,
+    EngineConfig, HoverConfig, SchemaFilter, SchemaSource, WorkspaceIndexConfig, WorkspaceMode,
 };
 use unified_sql_lsp_lsp::document::Document;
 use unified_sql_lsp_lsp::parsing::{ParseError, ParseResult};
+use unified_sql_lsp_lsp::positions::to_context_range;
 use unified_sql_lsp_lsp::sync::DocumentSync;
 
 fn create_test_document(content: &str, language_id: &str) -> Document {
@@ -74,22 +77,37 @@ fn test_document_sync_resolve_dialect_from_config() {
     let engine_config = EngineConfig {
         dialect: Dialect::PostgreSQL,
         version: DialectVersion::PostgreSQL14,
+        mode: WorkspaceMode::default(),
         connection_string: String::new(),
+        schema_source: SchemaSource::default(),
         schema_filter: SchemaFilter::default(),
         pool_config: ConnectionPoolConfig::default(),
         log_queries: false,
         query_timeout_secs: 5,
         cache_enabled: false,
+        completion: CompletionConfig::default(),
+        hover: HoverConfig::default(),
+        diagnostics: DiagnosticsConfig::default(),
+        custom_rules: Vec::new(),
+        workspace_symbol_limit: 100,
+        max_nesting_depth: 64,
+        workspace_index: WorkspaceIndexConfig::default(),
+        table_paging: TablePaging::default(),
+        catalog: CatalogConfig::default(),
     };
 
     let config = Arc::new(RwLock::new(Some(engine_config)));
     let sync = DocumentSync::new(config);
 
-    // Config should override language_id
-    let doc = create_test_document("SELECT 1", "mysql");
-    let dialect = sync.resolve_dialect(&doc);
+    // Config applies to documents that don't declare a dialect
+    let doc = create_test_document("SELECT 1", "sql");
+    assert_eq!(sync.resolve_dialect(&doc), Dialect::PostgreSQL);
 
-    assert_eq!(dialect, Dialect::PostgreSQL);
+    // A dialect-specific language_id or a dialect comment wins over the config
+    let doc = create_test_document("SELECT 1", "mysql");
+    assert_eq!(sync.resolve_dialect(&doc), Dialect::MySQL);
+    let doc = create_test_document("-- dialect: mysql\nSELECT 1", "sql");
+    assert_eq!(sync.resolve_dialect(&doc), Dialect::MySQL);
 }
 
 #[test]
@@ -124,6 +142,19 @@ fn test_document_sync_on_open_with_postgresql() {
     assert!(!result.is_failed(), "PostgreSQL parse should succeed");
 }
 
+#[test]
+fn test_document_sync_on_close_clears_diagnostics() {
+    let config = Arc::new(RwLock::new(None));
+    let sync = DocumentSync::new(config);
+
+    let doc = create_test_document("SELECT * FROM users", "mysql");
+    let cleared = sync.on_document_close(&doc);
+
+    assert_eq!(&cleared.uri, doc.uri());
+    assert!(cleared.diagnostics.is_empty());
+    assert_eq!(cleared.version, Some(doc.version()));
+}
+
 #[test]
 fn test_document_sync_metadata_creation_success() {
     let config = Arc::new(RwLock::new(None));
@@ -145,16 +176,25 @@ fn test_document_sync_metadata_creation_partial() {
     let config = Arc::new(RwLock::new(None));
     let sync = DocumentSync::new(config);
 
+    let error_range = Range::new(Position::new(0, 0), Position::new(0, 5));
+    let missing_range = Range::new(Position::new(0, 20), Position::new(0, 20));
     let partial = ParseResult::Partial {
         tree: None,
         errors: vec![ParseError::Generic {
             message: "Test error".to_string(),
         }],
+        error_ranges: vec![to_context_range(error_range)],
+        missing_nodes: vec![(")".to_string(), to_context_range(missing_range))],
     };
 
     let metadata = sync.create_metadata(&partial, Dialect::PostgreSQL);
     assert!(metadata.has_errors);
     assert_eq!(metadata.error_count, 1);
+    assert_eq!(metadata.error_ranges, vec![error_range]);
+    assert_eq!(
+        metadata.missing_nodes,
+        vec![(")".to_string(), missing_range)]
+    );
 }
 
 #[test]
@@ -228,30 +268,41 @@ fn test_document_sync_dialect_mapping() {
 }
 
 #[test]
-fn test_document_sync_config_overrides_language_id() {
-    // Test that engine config always takes precedence
+fn test_document_sync_language_id_overrides_config() {
+    // The document's own dialect takes precedence over the engine config
     let engine_config = EngineConfig {
         dialect: Dialect::MySQL,
         version: DialectVersion::MySQL80,
+        mode: WorkspaceMode::default(),
         connection_string: String::new(),
+        schema_source: SchemaSource::default(),
         schema_filter: SchemaFilter::default(),
         pool_config: ConnectionPoolConfig::default(),
         log_queries: false,
         query_timeout_secs: 30,
         cache_enabled: true,
+        completion: CompletionConfig::default(),
+        hover: HoverConfig::default(),
+        diagnostics: DiagnosticsConfig::default(),
+        custom_rules: Vec::new(),
+        workspace_symbol_limit: 100,
+        max_nesting_depth: 64,
+        workspace_index: WorkspaceIndexConfig::default(),
+        table_paging: TablePaging::default(),
+        catalog: CatalogConfig::default(),
     };
 
     let config = Arc::new(RwLock::new(Some(engine_config)));
     let sync = DocumentSync::new(config);
 
-    // Even with "postgresql" language_id, config should force MySQL
+    // A "postgresql" document stays PostgreSQL under a MySQL config
     let doc = create_test_document("SELECT 1", "postgresql");
     let dialect = sync.resolve_dialect(&doc);
 
     assert_eq!(
         dialect,
-        Dialect::MySQL,
-        "Config should override language_id"
+        Dialect::PostgreSQL,
+        "language_id should override config"
     );
 }
 
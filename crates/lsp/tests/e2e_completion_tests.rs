@@ -36,7 +36,7 @@ async fn create_and_parse_document(sql: &str, language_id: &str) -> Document {
                 document.set_tree(tree.clone(), metadata);
             }
         }
-        unified_sql_lsp_lsp::parsing::ParseResult::Partial { tree, errors } => {
+        unified_sql_lsp_lsp::parsing::ParseResult::Partial { tree, errors, .. } => {
             if let Some(tree) = tree {
                 let metadata = ParseMetadata::new(0, dialect, true, errors.len());
                 document.set_tree(tree.clone(), metadata);
@@ -0,0 +1,60 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! Supported feature combinations
+//!
+//! Embedders build this crate without default features, picking `parser`,
+//! `parser,catalog` or `wasm`. The API tests use only what those features
+//! expose. The matrix test checks every combination with `cargo check`; it
+//! builds the crate once per combination, so it only runs when asked:
+//!
+//! ```bash
+//! cargo test -p unified-sql-lsp-lsp --test feature_matrix -- --ignored
+//! ```
+
+use std::process::Command;
+
+/// Feature lists embedders may use instead of the default
+const COMBINATIONS: &[&str] = &["parser", "parser,catalog", "wasm"];
+
+#[test]
+#[ignore = "runs cargo check once per feature combination"]
+fn test_supported_feature_combinations_build() {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let manifest = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+    // A target directory of its own, so the checks don't wait on the lock
+    // of the build running this test
+    let target = concat!(env!("CARGO_TARGET_TMPDIR"), "/feature-matrix");
+
+    for features in COMBINATIONS {
+        let status = Command::new(&cargo)
+            .args(["check", "--lib", "--manifest-path", manifest])
+            .args(["--no-default-features", "--features", features])
+            .env("CARGO_TARGET_DIR", target)
+            .status()
+            .expect("cargo runs");
+        assert!(status.success(), "features `{}` don't build", features);
+    }
+}
+
+#[cfg(feature = "parser")]
+#[test]
+fn test_parser_api_detects_context() {
+    use unified_sql_lsp_lsp::{CompletionContext, Position, detect_completion_context_from_text};
+
+    let context = detect_completion_context_from_text(Position::new(0, 14), "SELECT * FROM ");
+    assert!(matches!(context, CompletionContext::FromClause { .. }));
+}
+
+#[cfg(feature = "catalog")]
+#[test]
+fn test_catalog_api_reads_ddl() {
+    use unified_sql_lsp_lsp::{Catalog, DdlCatalog, Dialect};
+
+    let catalog = DdlCatalog::from_sql("CREATE TABLE users (id INT);", Dialect::MySQL);
+    let tables = tokio_test::block_on(catalog.list_tables()).unwrap();
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].name, "users");
+}
@@ -9,6 +9,8 @@
 //! - Completion flow: Full pipeline from parsing to rendering
 //! - Multi-document operations: Concurrent access and thread-safety
 //! - Catalog integration: Error handling and schema filtering
+//! - Virtual documents: `untitled:` buffers through completion and diagnostics
+//! - Mixed dialects: documents of different dialects completed side by side
 
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -18,8 +20,10 @@ use unified_sql_lsp_catalog::{
 };
 use unified_sql_lsp_ir::Dialect;
 use unified_sql_lsp_lsp::completion::CompletionEngine;
+use unified_sql_lsp_lsp::diagnostic::{DiagnosticCode, DiagnosticCollector};
 use unified_sql_lsp_lsp::document::{Document, DocumentStore, ParseMetadata};
 use unified_sql_lsp_lsp::parsing::{ParseResult, ParserManager};
+use unified_sql_lsp_lsp::sync::DocumentSync;
 use unified_sql_lsp_test_utils::MockCatalogBuilder;
 
 async fn create_test_document(sql: &str, language_id: &str) -> Document {
@@ -42,7 +46,7 @@ async fn create_test_document(sql: &str, language_id: &str) -> Document {
                 document.set_tree(tree.clone(), metadata);
             }
         }
-        ParseResult::Partial { tree, errors } => {
+        ParseResult::Partial { tree, errors, .. } => {
             if let Some(tree) = tree {
                 let metadata = ParseMetadata::new(0, dialect, true, errors.len());
                 document.set_tree(tree.clone(), metadata);
@@ -635,3 +639,126 @@ async fn test_catalog_integration_mysql_vs_postgresql() {
     let _ = result_mysql.unwrap();
     let _ = result_pg.unwrap();
 }
+
+// =============================================================================
+// Virtual Document Tests (2 tests)
+// =============================================================================
+
+/// Open a document in a store and parse it the way the backend does
+async fn open_and_parse(
+    store: &DocumentStore,
+    uri: &Url,
+    sql: &str,
+    language_id: &str,
+) -> Document {
+    let sync = DocumentSync::new(Arc::new(tokio::sync::RwLock::new(None)));
+    store
+        .open_document(uri.clone(), sql.to_string(), 1, language_id.to_string())
+        .await
+        .unwrap();
+
+    let document = store.get_document(uri).await.unwrap();
+    let result = sync.on_document_open(&document);
+    let metadata = sync.create_metadata(&result, sync.resolve_dialect(&document));
+    if let Some(tree) = result.tree() {
+        store
+            .update_document_tree(uri, tree.clone(), metadata)
+            .await
+            .unwrap();
+    }
+
+    store.get_document(uri).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_untitled_document_completion_and_diagnostics() {
+    let store = DocumentStore::new();
+    let uri = Url::parse("untitled:Untitled-1").unwrap();
+    let document = open_and_parse(&store, &uri, "SELECT  FROM users", "sql").await;
+
+    let catalog = MockCatalogBuilder::new().with_standard_schema().build();
+    let engine = CompletionEngine::new(Arc::new(catalog));
+    let items = engine
+        .complete(&document, Position::new(0, 7))
+        .await
+        .expect("completion in untitled document")
+        .expect("completion items");
+    assert!(items.iter().any(|item| item.label == "id"));
+
+    let broken = open_and_parse(
+        &store,
+        &Url::parse("untitled:Untitled-2").unwrap(),
+        "SELECT id FROM users WHERE",
+        "sql",
+    )
+    .await;
+    let diagnostics = DiagnosticCollector::new().collect_for_document(&broken);
+    assert!(!diagnostics.is_empty());
+    assert!(
+        diagnostics
+            .iter()
+            .all(|d| d.code == Some(DiagnosticCode::SyntaxError))
+    );
+}
+
+#[tokio::test]
+async fn test_untitled_document_opened_empty_has_no_diagnostics() {
+    let store = DocumentStore::new();
+    let uri = Url::parse("untitled:Untitled-1").unwrap();
+    let document = open_and_parse(&store, &uri, "", "sql").await;
+
+    assert!(document.is_virtual());
+    assert!(
+        DiagnosticCollector::new()
+            .collect_for_document(&document)
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_mixed_dialect_documents_complete_independently() {
+    let store = DocumentStore::new();
+    let mysql = open_and_parse(
+        &store,
+        &Url::parse("file:///mysql.sql").unwrap(),
+        "SELECT  FROM users",
+        "mysql",
+    )
+    .await;
+    let postgres = open_and_parse(
+        &store,
+        &Url::parse("file:///postgres.sql").unwrap(),
+        "-- dialect: postgresql\nSELECT  FROM users",
+        "sql",
+    )
+    .await;
+    assert_eq!(
+        postgres.parse_metadata().unwrap().dialect,
+        Dialect::PostgreSQL
+    );
+
+    // One engine configured for MySQL serves both documents
+    let catalog = MockCatalogBuilder::new().with_standard_schema().build();
+    let engine = Arc::new(CompletionEngine::new(Arc::new(catalog)).with_dialect(Dialect::MySQL));
+    let labels = |items: Option<Vec<tower_lsp::lsp_types::CompletionItem>>| -> Vec<String> {
+        items
+            .expect("completion items")
+            .into_iter()
+            .map(|item| item.label)
+            .collect()
+    };
+
+    for _ in 0..5 {
+        let (mysql_items, postgres_items) = tokio::join!(
+            engine.complete(&mysql, Position::new(0, 7)),
+            engine.complete(&postgres, Position::new(1, 7)),
+        );
+        let mysql_labels = labels(mysql_items.unwrap());
+        let postgres_labels = labels(postgres_items.unwrap());
+
+        assert!(mysql_labels.iter().any(|l| l == "LOCK IN SHARE MODE"));
+        assert!(!mysql_labels.iter().any(|l| l == "DISTINCT ON"));
+        assert!(postgres_labels.iter().any(|l| l == "DISTINCT ON"));
+        assert!(!postgres_labels.iter().any(|l| l == "LOCK IN SHARE MODE"));
+    }
+}
@@ -50,7 +50,7 @@ fn test_parser_manager_with_syntax_error() {
         ParseResult::Success { .. } => {
             // Grammar might be lenient, this is OK
         }
-        ParseResult::Partial { errors, tree } => {
+        ParseResult::Partial { errors, tree, .. } => {
             assert!(tree.is_some(), "Expected tree even with errors");
             assert!(!errors.is_empty(), "Expected at least one error");
             assert!(errors[0].to_string().contains("Syntax error"));
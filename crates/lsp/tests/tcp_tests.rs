@@ -0,0 +1,174 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! TcpServer integration tests
+//!
+//! Connects WebSocket clients to a server on a free port and talks JSON-RPC
+//! the way the playground does.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::protocol::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+use unified_sql_lsp_catalog::StaticCatalog;
+use unified_sql_lsp_lsp::tcp::TcpServer;
+
+type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Start a server on a free port and return the port
+async fn serve(configure: impl FnOnce(TcpServer) -> TcpServer) -> u16 {
+    let server = TcpServer::new(0, Arc::new(StaticCatalog::new()))
+        .await
+        .unwrap();
+    let server = configure(server);
+    let port = server.port();
+    tokio::spawn(async move { server.serve().await });
+    port
+}
+
+async fn connect(port: u16) -> Client {
+    connect_async(format!("ws://127.0.0.1:{}", port))
+        .await
+        .unwrap()
+        .0
+}
+
+async fn notify(client: &mut Client, method: &str, params: Value) {
+    let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+    client
+        .send(Message::Text(message.to_string()))
+        .await
+        .unwrap();
+}
+
+/// Send a request and return the `result` of its response
+async fn request(client: &mut Client, id: u64, method: &str, params: Value) -> Value {
+    let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+    client
+        .send(Message::Text(message.to_string()))
+        .await
+        .unwrap();
+
+    loop {
+        let message = client.next().await.unwrap().unwrap();
+        let response: Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        if response["id"] == json!(id) {
+            return response["result"].clone();
+        }
+    }
+}
+
+async fn open(client: &mut Client, uri: &str, text: &str) {
+    notify(
+        client,
+        "textDocument/didOpen",
+        json!({ "textDocument": { "uri": uri, "languageId": "mysql", "version": 1, "text": text } }),
+    )
+    .await;
+}
+
+async fn complete(client: &mut Client, id: u64, uri: &str, character: u32) -> Value {
+    request(
+        client,
+        id,
+        "textDocument/completion",
+        json!({
+            "textDocument": { "uri": uri },
+            "position": { "line": 0, "character": character },
+        }),
+    )
+    .await
+}
+
+fn labels(result: &Value) -> Vec<String> {
+    result
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item["label"].as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn test_clients_have_isolated_documents() {
+    let port = serve(|server| server).await;
+    let mut first = connect(port).await;
+    let mut second = connect(port).await;
+    request(&mut first, 1, "initialize", json!({})).await;
+    request(&mut second, 1, "initialize", json!({})).await;
+
+    let from = "SELECT * FROM ";
+    open(&mut first, "file:///first.sql", from).await;
+    open(&mut second, "file:///second.sql", from).await;
+
+    let items = complete(&mut first, 2, "file:///first.sql", from.len() as u32).await;
+    assert!(labels(&items).contains(&"users".to_string()));
+    // Each client only sees its own documents
+    assert_eq!(
+        complete(&mut second, 2, "file:///first.sql", from.len() as u32).await,
+        Value::Null
+    );
+    assert_eq!(
+        complete(&mut first, 3, "file:///second.sql", from.len() as u32).await,
+        Value::Null
+    );
+
+    // One client leaving doesn't affect the other
+    assert_eq!(
+        request(&mut first, 4, "shutdown", Value::Null).await,
+        Value::Null
+    );
+    notify(&mut first, "exit", Value::Null).await;
+    assert!(matches!(
+        first.next().await,
+        Some(Ok(Message::Close(_))) | None
+    ));
+
+    let items = complete(&mut second, 3, "file:///second.sql", from.len() as u32).await;
+    assert!(labels(&items).contains(&"users".to_string()));
+
+    // The listener still accepts new clients
+    let mut third = connect(port).await;
+    assert!(request(&mut third, 1, "initialize", json!({})).await["capabilities"].is_object());
+}
+
+#[tokio::test]
+async fn test_connections_beyond_the_cap_are_refused() {
+    let port = serve(|server| server.with_max_connections(1)).await;
+    let mut first = connect(port).await;
+    request(&mut first, 1, "initialize", json!({})).await;
+
+    assert!(
+        connect_async(format!("ws://127.0.0.1:{}", port))
+            .await
+            .is_err()
+    );
+
+    // The slot frees up once the first client is gone
+    first.close(None).await.unwrap();
+    while first.next().await.is_some() {}
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let mut second = connect(port).await;
+    assert!(request(&mut second, 1, "initialize", json!({})).await["capabilities"].is_object());
+}
+
+#[tokio::test]
+async fn test_idle_connections_are_closed() {
+    let port = serve(|server| server.with_idle_timeout(Duration::from_millis(100))).await;
+    let mut client = connect(port).await;
+    request(&mut client, 1, "initialize", json!({})).await;
+
+    let closed = tokio::time::timeout(Duration::from_secs(2), client.next())
+        .await
+        .expect("idle connection was not closed");
+    assert!(matches!(closed, Some(Ok(Message::Close(_))) | None));
+}
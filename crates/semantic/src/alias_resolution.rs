@@ -15,15 +15,19 @@
 //!
 //! The resolver applies multiple strategies in order:
 //!
+//! Quoted names (`"Users"`) skip the strategies and only match the table
+//! spelled exactly as written.
+//!
 //! 1. **Exact Match** - Try loading the alias as a real table name
 //! 2. **Starts With** - Find tables where the name starts with the alias (e.g., "u" -> "users")
 //! 3. **First Letter + Numeric** - Match first letter with numeric suffix (e.g., "e1" -> "employees")
 //! 4. **Single Table Fallback** - If only one table exists, use it
 
-use crate::TableSymbol;
+use crate::{ColumnSymbol, TableSymbol};
 use std::sync::Arc;
 use tracing::{debug, instrument};
-use unified_sql_lsp_catalog::Catalog;
+use unified_sql_lsp_catalog::{Catalog, ColumnMetadata};
+use unified_sql_lsp_ir::IdentifierComparer;
 
 /// Strategy for resolving table aliases to actual table names
 #[derive(Debug, Clone, Copy)]
@@ -110,6 +114,15 @@ impl AliasResolver {
     pub async fn resolve(&self, alias: String) -> Result<ResolutionResult, AliasResolutionError> {
         debug!("Starting alias resolution for '{}'", alias);
 
+        // A quoted name refers to exactly one table, spelled as written
+        if IdentifierComparer::is_quoted(&alias) {
+            let name = IdentifierComparer::unquote(&alias);
+            return match self.try_exact_match(&name).await? {
+                ResolutionResult::Found(table) => Ok(ResolutionResult::Found(table)),
+                _ => Ok(ResolutionResult::NotFound),
+            };
+        }
+
         // Try each strategy in order
         for strategy in ResolutionStrategy::all() {
             debug!(?strategy, "Attempting resolution strategy");
@@ -159,23 +172,10 @@ impl AliasResolver {
 
     /// Strategy 1: Try exact table name match
     async fn try_exact_match(&self, alias: &str) -> Result<ResolutionResult, AliasResolutionError> {
-        match self.catalog.get_columns(alias).await {
+        match self.catalog.get_columns_shared(alias).await {
             Ok(columns) => {
                 let mut table = TableSymbol::new(alias);
-                table = table.with_columns(
-                    columns
-                        .into_iter()
-                        .map(|c| {
-                            crate::ColumnSymbol::new(
-                                c.name.clone(),
-                                c.data_type.clone(),
-                                alias.to_string(),
-                            )
-                            .with_primary_key_if(c.is_primary_key)
-                            .with_foreign_key_if(c.is_foreign_key)
-                        })
-                        .collect(),
-                );
+                table = table.with_shared_columns(column_symbols(&columns, alias));
 
                 if table.columns.is_empty() {
                     debug!("Exact match found but table has no columns");
@@ -205,24 +205,12 @@ impl AliasResolver {
         for table in &all_tables {
             if table.name.eq_ignore_ascii_case(alias) {
                 debug!(found_table = %table.name, "Found exact match for alias");
-                match self.catalog.get_columns(&table.name).await {
+                match self.catalog.get_columns_shared(&table.name).await {
                     Ok(columns) => {
                         let mut table_symbol = TableSymbol::new(&table.name);
                         table_symbol = table_symbol.with_alias(alias);
-                        table_symbol = table_symbol.with_columns(
-                            columns
-                                .into_iter()
-                                .map(|c| {
-                                    crate::ColumnSymbol::new(
-                                        c.name.clone(),
-                                        c.data_type.clone(),
-                                        table.name.clone(),
-                                    )
-                                    .with_primary_key_if(c.is_primary_key)
-                                    .with_foreign_key_if(c.is_foreign_key)
-                                })
-                                .collect(),
-                        );
+                        table_symbol =
+                            table_symbol.with_shared_columns(column_symbols(&columns, &table.name));
                         return Ok(ResolutionResult::Found(table_symbol));
                     }
                     Err(_) => continue,
@@ -257,23 +245,11 @@ impl AliasResolver {
         }
 
         if let Some((table_name, _)) = best_match {
-            if let Ok(columns) = self.catalog.get_columns(&table_name).await {
+            if let Ok(columns) = self.catalog.get_columns_shared(&table_name).await {
                 let mut table_symbol = TableSymbol::new(&table_name);
                 table_symbol = table_symbol.with_alias(alias);
-                table_symbol = table_symbol.with_columns(
-                    columns
-                        .into_iter()
-                        .map(|c| {
-                            crate::ColumnSymbol::new(
-                                c.name.clone(),
-                                c.data_type.clone(),
-                                table_name.clone(),
-                            )
-                            .with_primary_key_if(c.is_primary_key)
-                            .with_foreign_key_if(c.is_foreign_key)
-                        })
-                        .collect(),
-                );
+                table_symbol =
+                    table_symbol.with_shared_columns(column_symbols(&columns, &table_name));
                 return Ok(ResolutionResult::Found(table_symbol));
             }
         }
@@ -300,23 +276,11 @@ impl AliasResolver {
 
         if let Some((table_name, _)) = best_match {
             debug!(found_table = %table_name, "Found shortest table starting with alias");
-            if let Ok(columns) = self.catalog.get_columns(&table_name).await {
+            if let Ok(columns) = self.catalog.get_columns_shared(&table_name).await {
                 let mut table_symbol = TableSymbol::new(&table_name);
                 table_symbol = table_symbol.with_alias(alias);
-                table_symbol = table_symbol.with_columns(
-                    columns
-                        .into_iter()
-                        .map(|c| {
-                            crate::ColumnSymbol::new(
-                                c.name.clone(),
-                                c.data_type.clone(),
-                                table_name.clone(),
-                            )
-                            .with_primary_key_if(c.is_primary_key)
-                            .with_foreign_key_if(c.is_foreign_key)
-                        })
-                        .collect(),
-                );
+                table_symbol =
+                    table_symbol.with_shared_columns(column_symbols(&columns, &table_name));
                 return Ok(ResolutionResult::Found(table_symbol));
             }
         }
@@ -355,24 +319,12 @@ impl AliasResolver {
                     found_table = %table.name,
                     "Found table matching first letter pattern"
                 );
-                match self.catalog.get_columns(&table.name).await {
+                match self.catalog.get_columns_shared(&table.name).await {
                     Ok(columns) => {
                         let mut table_symbol = TableSymbol::new(&table.name);
                         table_symbol = table_symbol.with_alias(alias);
-                        table_symbol = table_symbol.with_columns(
-                            columns
-                                .into_iter()
-                                .map(|c| {
-                                    crate::ColumnSymbol::new(
-                                        c.name.clone(),
-                                        c.data_type.clone(),
-                                        table.name.clone(),
-                                    )
-                                    .with_primary_key_if(c.is_primary_key)
-                                    .with_foreign_key_if(c.is_foreign_key)
-                                })
-                                .collect(),
-                        );
+                        table_symbol =
+                            table_symbol.with_shared_columns(column_symbols(&columns, &table.name));
                         return Ok(ResolutionResult::Found(table_symbol));
                     }
                     Err(_) => continue,
@@ -394,24 +346,12 @@ impl AliasResolver {
         if all_tables.len() == 1 {
             let table_name = &all_tables[0].name;
             debug!(table = %table_name, "Using single table fallback");
-            match self.catalog.get_columns(table_name).await {
+            match self.catalog.get_columns_shared(table_name).await {
                 Ok(columns) => {
                     let mut table_symbol = TableSymbol::new(table_name);
                     table_symbol = table_symbol.with_alias(alias);
-                    table_symbol = table_symbol.with_columns(
-                        columns
-                            .into_iter()
-                            .map(|c| {
-                                crate::ColumnSymbol::new(
-                                    c.name.clone(),
-                                    c.data_type.clone(),
-                                    table_name.clone(),
-                                )
-                                .with_primary_key_if(c.is_primary_key)
-                                .with_foreign_key_if(c.is_foreign_key)
-                            })
-                            .collect(),
-                    );
+                    table_symbol =
+                        table_symbol.with_shared_columns(column_symbols(&columns, table_name));
                     Ok(ResolutionResult::Found(table_symbol))
                 }
                 Err(e) => Err(AliasResolutionError::Catalog(e)),
@@ -450,6 +390,20 @@ impl AliasResolver {
     }
 }
 
+/// Convert catalog columns of `table_name` into one shared symbol list
+fn column_symbols(columns: &[ColumnMetadata], table_name: &str) -> Arc<[ColumnSymbol]> {
+    columns
+        .iter()
+        .map(|c| {
+            ColumnSymbol::new(c.name.clone(), c.data_type.clone(), table_name)
+                .with_primary_key_if(c.is_primary_key)
+                .with_foreign_key_if(c.is_foreign_key)
+                .with_references(c.references.clone())
+                .with_not_null(!c.nullable)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -476,6 +430,26 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_resolution_keeps_foreign_key_targets() {
+        let catalog = MockCatalogBuilder::new().with_standard_schema().build();
+        let resolver = AliasResolver::new(Arc::new(catalog));
+
+        for alias in ["orders", "o"] {
+            match resolver.resolve(alias.to_string()).await.unwrap() {
+                ResolutionResult::Found(table) => {
+                    let user_id = table.find_column("user_id").unwrap();
+                    let target = user_id.references.as_ref().unwrap();
+                    assert_eq!(
+                        (target.table.as_str(), target.column.as_str()),
+                        ("users", "id")
+                    );
+                }
+                _ => panic!("Expected Found result"),
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_starts_with_resolution() {
         let catalog = MockCatalogBuilder::new()
@@ -17,7 +17,7 @@ use unified_sql_lsp_catalog::{Catalog, FunctionMetadata, FunctionType};
 use unified_sql_lsp_ir::{
     ColumnRef, Expr, Literal, OrderBy, Query, SelectItem, SelectStatement, SetOp, TableRef,
 };
-use unified_sql_lsp_ir::{CommonTableExpr, Dialect};
+use unified_sql_lsp_ir::{CommonTableExpr, Dialect, DialectExtensions, IdentifierComparer};
 
 use crate::error::{SemanticError, SemanticResult};
 use crate::resolution::ColumnResolver;
@@ -41,14 +41,13 @@ pub struct SemanticAnalyzer {
     catalog: Arc<dyn Catalog>,
 
     /// SQL dialect for the query being analyzed
-    #[allow(dead_code)]
     dialect: Dialect,
 
     /// Scope manager for tracking tables across query hierarchy
     scope_manager: ScopeManager,
 
     /// Cache of column metadata per table (table_name -> columns)
-    column_cache: HashMap<String, Vec<ColumnSymbol>>,
+    column_cache: HashMap<String, Arc<[ColumnSymbol]>>,
 
     /// Cache of function metadata from catalog
     function_cache: Vec<FunctionMetadata>,
@@ -150,6 +149,31 @@ impl SemanticAnalyzer {
                 self.populate_query_scope(select, scope_id)?;
                 scope_id
             }
+            SetOp::Update(update) => {
+                let scope_id = self
+                    .scope_manager
+                    .create_scope(ScopeType::Query, cte_scope_id);
+                self.populate_dml_scope(&update.table, &update.from, scope_id)?;
+                for assignment in &update.assignments {
+                    self.validate_expr(&assignment.value, scope_id)?;
+                }
+                if let Some(where_clause) = &update.where_clause {
+                    self.validate_expr(where_clause, scope_id)?;
+                }
+                self.validate_returning(update.returning.as_deref(), scope_id)?;
+                scope_id
+            }
+            SetOp::Delete(delete) => {
+                let scope_id = self
+                    .scope_manager
+                    .create_scope(ScopeType::Query, cte_scope_id);
+                self.populate_dml_scope(&delete.table, &delete.using, scope_id)?;
+                if let Some(where_clause) = &delete.where_clause {
+                    self.validate_expr(where_clause, scope_id)?;
+                }
+                self.validate_returning(delete.returning.as_deref(), scope_id)?;
+                scope_id
+            }
             _ => {
                 // Handle UNION, INTERSECT, EXCEPT
                 let (scope_id, _columns) =
@@ -190,6 +214,9 @@ impl SemanticAnalyzer {
         column_ref: &ColumnRef,
         scope_id: usize,
     ) -> SemanticResult<(&TableSymbol, &ColumnSymbol)> {
+        // Compared like the dialect compares column names
+        let column = IdentifierComparer::new(self.dialect).column(&column_ref.column);
+
         // Qualified reference: u.id
         if let Some(table_qualifier) = &column_ref.table {
             let table = self
@@ -197,14 +224,13 @@ impl SemanticAnalyzer {
                 .resolve_table(table_qualifier, scope_id)?;
 
             return table
-                .find_column(&column_ref.column)
+                .find_column(&column)
                 .ok_or_else(|| SemanticError::ColumnNotFound(column_ref.qualified()))
                 .map(|col| (table, col));
         }
 
         // Unqualified reference: id (search all visible tables)
-        self.scope_manager
-            .resolve_column(&column_ref.column, scope_id)
+        self.scope_manager.resolve_column(&column, scope_id)
     }
 
     /// Get all visible tables at a scope (including parent scopes)
@@ -334,12 +360,32 @@ impl SemanticAnalyzer {
                 table_names.push(insert.table.name.clone());
             }
             SetOp::Update(update) => {
-                // Extract target table
+                // Extract target table and those of UPDATE ... FROM
                 table_names.push(update.table.name.clone());
+                for table_ref in &update.from {
+                    if !cte_names.contains(&table_ref.name) {
+                        table_names.push(table_ref.name.clone());
+                    }
+                }
             }
             SetOp::Delete(delete) => {
-                // Extract target table
+                // Extract target table and those of DELETE ... USING
                 table_names.push(delete.table.name.clone());
+                for table_ref in &delete.using {
+                    if !cte_names.contains(&table_ref.name) {
+                        table_names.push(table_ref.name.clone());
+                    }
+                }
+            }
+            SetOp::Merge(merge) => {
+                // Extract target and source tables
+                table_names.push(merge.target.name.clone());
+                if !cte_names.contains(&merge.source.name) {
+                    table_names.push(merge.source.name.clone());
+                }
+            }
+            SetOp::Values(_) => {
+                // VALUES rows are literal data and reference no tables
             }
             SetOp::Union { left, right, .. }
             | SetOp::Intersect { left, right, .. }
@@ -372,14 +418,17 @@ impl SemanticAnalyzer {
             // Fetch from catalog
             let columns = self
                 .catalog
-                .get_columns(&table_name)
+                .get_columns_shared(&table_name)
                 .await
                 .map_err(|_| SemanticError::TableNotFound(table_name.clone()))?;
 
             // Convert to ColumnSymbol
-            let symbols: Vec<ColumnSymbol> = columns
-                .into_iter()
-                .map(|col| ColumnSymbol::new(col.name.clone(), col.data_type, &table_name))
+            let symbols: Arc<[ColumnSymbol]> = columns
+                .iter()
+                .map(|col| {
+                    ColumnSymbol::new(col.name.clone(), col.data_type.clone(), &table_name)
+                        .with_not_null(!col.nullable)
+                })
                 .collect();
 
             self.column_cache.insert(table_name, symbols);
@@ -445,6 +494,43 @@ impl SemanticAnalyzer {
         Ok(())
     }
 
+    /// Populate the scope of an UPDATE or DELETE
+    ///
+    /// The target keeps its alias; `others` are the tables of a PostgreSQL
+    /// `UPDATE ... FROM` or `DELETE ... USING`.
+    fn populate_dml_scope(
+        &mut self,
+        target: &TableRef,
+        others: &[TableRef],
+        scope_id: usize,
+    ) -> SemanticResult<()> {
+        self.process_table_ref(target, scope_id)?;
+        for table_ref in others {
+            self.process_table_ref(table_ref, scope_id)?;
+            for join in &table_ref.joins {
+                self.process_table_ref(&join.table, scope_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate the RETURNING list of an INSERT, UPDATE or DELETE
+    ///
+    /// Dialects without RETURNING get no scope for it: the list is left
+    /// to the dialect's compatibility hints.
+    fn validate_returning(
+        &self,
+        returning: Option<&[SelectItem]>,
+        scope_id: usize,
+    ) -> SemanticResult<()> {
+        match returning {
+            Some(items) if self.dialect.supports(DialectExtensions::Returning) => {
+                self.validate_projection(items, scope_id)
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Process a table reference and add it to scope
     fn process_table_ref(&mut self, table_ref: &TableRef, scope_id: usize) -> SemanticResult<()> {
         // First check if this is a CTE (synthetic table in scope hierarchy)
@@ -476,7 +562,7 @@ impl SemanticAnalyzer {
             .ok_or_else(|| SemanticError::TableNotFound(table_ref.name.clone()))?;
 
         // Create TableSymbol with alias
-        let mut table = TableSymbol::new(&table_ref.name).with_columns(columns.clone());
+        let mut table = TableSymbol::new(&table_ref.name).with_shared_columns(Arc::clone(columns));
 
         if let Some(alias) = &table_ref.alias {
             table = table.with_alias(alias);
@@ -536,7 +622,7 @@ impl SemanticAnalyzer {
                 }
                 Ok(())
             }
-            Expr::Cast { expr, .. } => {
+            Expr::Cast { expr, .. } | Expr::JsonPath { expr, .. } => {
                 self.validate_expr(expr, scope_id)?;
                 Ok(())
             }
@@ -754,7 +840,9 @@ impl SemanticAnalyzer {
                         .as_ref()
                         .is_some_and(|e| self.expr_contains_aggregate(e))
             }
-            Expr::Cast { expr, .. } => self.expr_contains_aggregate(expr),
+            Expr::Cast { expr, .. } | Expr::JsonPath { expr, .. } => {
+                self.expr_contains_aggregate(expr)
+            }
             Expr::Paren(inner) => self.expr_contains_aggregate(inner),
             _ => false,
         }
@@ -833,7 +921,9 @@ impl SemanticAnalyzer {
                 }
                 Ok(())
             }
-            Expr::Cast { expr, .. } => self.validate_having_without_group(expr, scope_id),
+            Expr::Cast { expr, .. } | Expr::JsonPath { expr, .. } => {
+                self.validate_having_without_group(expr, scope_id)
+            }
             Expr::Paren(inner) => self.validate_having_without_group(inner, scope_id),
             Expr::List(items) => {
                 for item in items {
@@ -885,7 +975,7 @@ impl SemanticAnalyzer {
             SelectItem::QualifiedWildcard(table_name) => {
                 let table = self.scope_manager.resolve_table(table_name, scope_id)?;
                 let mut columns = Vec::new();
-                for col in &table.columns {
+                for col in table.columns.iter() {
                     let col_ref =
                         ColumnRef::new(&col.name).with_table(table.display_name().to_string());
                     columns.push(SelectItem::UnnamedExpr(Expr::Column(col_ref)));
@@ -896,7 +986,7 @@ impl SemanticAnalyzer {
                 let tables = self.visible_tables(scope_id);
                 let mut columns = Vec::new();
                 for table in tables {
-                    for col in &table.columns {
+                    for col in table.columns.iter() {
                         let col_ref =
                             ColumnRef::new(&col.name).with_table(table.display_name().to_string());
                         columns.push(SelectItem::UnnamedExpr(Expr::Column(col_ref)));
@@ -1041,12 +1131,12 @@ impl SemanticAnalyzer {
                 cte_table = TableSymbol::new(&cte.name).with_columns(renamed_columns.clone());
             }
 
-            // Add CTE table to CTE scope
+            // Add CTE table to CTE scope, rejecting a repeated name
             let scope = self
                 .scope_manager
                 .get_scope_mut(cte_scope_id)
                 .ok_or_else(|| SemanticError::InvalidScope("CTE scope not found".to_string()))?;
-            scope.add_table(cte_table)?;
+            scope.add_cte(cte_table, self.dialect)?;
 
             // Store metadata
             cte_map.insert(
@@ -1209,6 +1299,8 @@ impl SemanticAnalyzer {
             SetOp::Insert(insert) => insert.table.name == cte_name,
             SetOp::Update(update) => update.table.name == cte_name,
             SetOp::Delete(delete) => delete.table.name == cte_name,
+            SetOp::Merge(merge) => merge.target.name == cte_name || merge.source.name == cte_name,
+            SetOp::Values(_) => false,
             SetOp::Union { left, right, .. }
             | SetOp::Intersect { left, right, .. }
             | SetOp::Except { left, right, .. } => {
@@ -1311,9 +1403,48 @@ impl SemanticAnalyzer {
                 let parent_scope = self.scope_manager.create_scope(ScopeType::Query, parent_id);
                 Ok((parent_scope, Vec::new()))
             }
+            SetOp::Merge(_merge) => {
+                // MERGE statements don't produce output columns
+                let parent_scope = self.scope_manager.create_scope(ScopeType::Query, parent_id);
+                Ok((parent_scope, Vec::new()))
+            }
+            SetOp::Values(rows) => {
+                let scope_id = self.scope_manager.create_scope(ScopeType::Query, parent_id);
+                let columns = self.infer_values_output_columns(rows, &[], "", scope_id)?;
+                Ok((scope_id, columns))
+            }
         }
     }
 
+    /// Infer output columns of a VALUES list
+    ///
+    /// Columns are named by `names` where given, otherwise `column1`,
+    /// `column2`, ... as in PostgreSQL. Types come from the first row.
+    fn infer_values_output_columns(
+        &self,
+        rows: &[Vec<Expr>],
+        names: &[String],
+        table: &str,
+        scope_id: usize,
+    ) -> SemanticResult<Vec<ColumnSymbol>> {
+        let Some(first_row) = rows.first() else {
+            return Ok(Vec::new());
+        };
+
+        first_row
+            .iter()
+            .enumerate()
+            .map(|(i, expr)| {
+                let name = names
+                    .get(i)
+                    .cloned()
+                    .unwrap_or_else(|| format!("column{}", i + 1));
+                let data_type = self.infer_expr_type(expr, scope_id)?;
+                Ok(ColumnSymbol::new(name, data_type, table))
+            })
+            .collect()
+    }
+
     /// Infer output columns from a SELECT statement
     ///
     /// Similar to `infer_cte_output_columns` but used for set operations.
@@ -1385,6 +1516,7 @@ impl SemanticAnalyzer {
     /// - Binary operations follow type promotion rules
     /// - Functions return type from metadata
     /// - CAST expressions return the target type
+    /// - JSON path expressions return JSON, or text when unquoted
     /// - CASE expressions return the most common type of all branches
     fn infer_expr_type(
         &self,
@@ -1429,8 +1561,15 @@ impl SemanticAnalyzer {
             // Function calls
             Expr::Function { name, args, .. } => self.infer_function_type(name, args, scope_id),
 
-            // CAST expression
-            Expr::Cast { type_name, .. } => self.parse_data_type_from_string(type_name),
+            // CAST expression - the cast target, with parameters such as numeric(10,2)
+            Expr::Cast { target_type, .. } => Ok(DataType::parse(target_type)),
+
+            // JSON path - `->` yields JSON, `->>` yields unquoted text
+            Expr::JsonPath { unquote, .. } => Ok(if *unquote {
+                DataType::Text
+            } else {
+                DataType::Json
+            }),
 
             // CASE expression - return most common type
             Expr::Case {
@@ -1562,32 +1701,6 @@ impl SemanticAnalyzer {
         }
     }
 
-    /// Parse data type from CAST string
-    fn parse_data_type_from_string(
-        &self,
-        type_name: &str,
-    ) -> SemanticResult<unified_sql_lsp_catalog::DataType> {
-        use unified_sql_lsp_catalog::DataType;
-
-        let type_lower = type_name.to_lowercase();
-
-        match type_lower.as_str() {
-            "int" | "integer" => Ok(DataType::Integer),
-            "bigint" => Ok(DataType::BigInt),
-            "smallint" => Ok(DataType::SmallInt),
-            "tinyint" => Ok(DataType::TinyInt),
-            "float" | "double" => Ok(DataType::Float),
-            "decimal" => Ok(DataType::Decimal),
-            "varchar" | "text" => Ok(DataType::Text),
-            "char" => Ok(DataType::Char(None)),
-            "boolean" | "bool" => Ok(DataType::Boolean),
-            "date" => Ok(DataType::Date),
-            "timestamp" => Ok(DataType::Timestamp),
-            "json" => Ok(DataType::Json),
-            _ => Ok(DataType::Other(type_name.to_string())),
-        }
-    }
-
     /// Find the most common type from a list of types
     fn find_most_common_type(
         &self,
@@ -1936,6 +2049,65 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
 
+    /// `SELECT name FROM users GROUP BY <group_by> ORDER BY <order_by>`
+    async fn analyze_ordering(
+        dialect: Dialect,
+        group_by: &str,
+        order_by: &str,
+    ) -> SemanticResult<usize> {
+        let catalog = Arc::new(MockCatalog::new());
+        let mut analyzer = SemanticAnalyzer::new(catalog, dialect);
+
+        let mut query = Query::new(dialect);
+        let mut select = SelectStatement::default();
+        select.from.push(TableRef {
+            name: "users".to_string(),
+            alias: None,
+            joins: Vec::new(),
+        });
+        select
+            .projection
+            .push(SelectItem::UnnamedExpr(Expr::Column(ColumnRef::new(
+                "name",
+            ))));
+        select.group_by.push(Expr::Column(ColumnRef::new(group_by)));
+
+        query.body = SetOp::Select(Box::new(select));
+        query.order_by = Some(vec![OrderBy {
+            expr: Expr::Column(ColumnRef::new(order_by)),
+            direction: None,
+        }]);
+        analyzer.analyze_query(&query).await
+    }
+
+    #[tokio::test]
+    async fn test_order_by_quoted_column_case() {
+        // Bare names match regardless of case on both dialects
+        for dialect in [Dialect::PostgreSQL, Dialect::MySQL] {
+            assert!(analyze_ordering(dialect, "Name", "NAME").await.is_ok());
+        }
+
+        // PostgreSQL only matches a quoted name spelled exactly
+        assert!(
+            analyze_ordering(Dialect::PostgreSQL, "\"name\"", "\"name\"")
+                .await
+                .is_ok()
+        );
+        let err = analyze_ordering(Dialect::PostgreSQL, "name", "\"Name\"")
+            .await
+            .unwrap_err();
+        assert_eq!(err, SemanticError::ColumnNotFound("\"Name\"".to_string()));
+        let err = analyze_ordering(Dialect::PostgreSQL, "\"Name\"", "name")
+            .await
+            .unwrap_err();
+        assert_eq!(err, SemanticError::ColumnNotFound("\"Name\"".to_string()));
+
+        // MySQL column names ignore case even when quoted
+        assert!(analyze_ordering(Dialect::MySQL, "`Name`", "`NAME`")
+            .await
+            .is_ok());
+    }
+
     #[tokio::test]
     async fn test_wildcard_unqualified() {
         let catalog = Arc::new(MockCatalog::new());
@@ -2470,6 +2642,59 @@ mod tests {
         assert!(result.is_ok(), "CTE with column list should succeed");
     }
 
+    #[tokio::test]
+    async fn test_cte_over_values() {
+        let catalog = Arc::new(MockCatalog::new());
+        let mut analyzer = SemanticAnalyzer::new(catalog, Dialect::PostgreSQL);
+
+        // WITH data(id, name) AS (VALUES (1, 'a'), (2, 'b')) SELECT * FROM data
+        let mut query = Query::new(Dialect::PostgreSQL);
+        let mut cte_query = Query::new(Dialect::PostgreSQL);
+        cte_query.body = SetOp::Values(vec![
+            vec![
+                Expr::Literal(Literal::Integer(1)),
+                Expr::Literal(Literal::String("a".to_string())),
+            ],
+            vec![
+                Expr::Literal(Literal::Integer(2)),
+                Expr::Literal(Literal::String("b".to_string())),
+            ],
+        ]);
+        query.ctes.push(CommonTableExpr {
+            name: "data".to_string(),
+            columns: vec!["id".to_string(), "name".to_string()],
+            query: Box::new(cte_query),
+            materialized: None,
+        });
+
+        let mut main_select = SelectStatement::default();
+        main_select.from.push(TableRef {
+            name: "data".to_string(),
+            alias: None,
+            joins: Vec::new(),
+        });
+        main_select.projection.push(SelectItem::Wildcard);
+        query.body = SetOp::Select(Box::new(main_select));
+
+        let result = analyzer.analyze_query(&query).await;
+        assert!(result.is_ok(), "CTE over VALUES: {:?}", result.err());
+
+        let scopes = analyzer.scope_manager();
+        let data = (0..scopes.scope_count())
+            .filter_map(|id| scopes.get_scope(id))
+            .find_map(|scope| scope.find_table("data"))
+            .expect("CTE table is in scope");
+        let columns: Vec<_> = data
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.data_type.clone()))
+            .collect();
+        assert_eq!(
+            columns,
+            vec![("id", DataType::Integer), ("name", DataType::Text)]
+        );
+    }
+
     #[tokio::test]
     async fn test_multiple_ctes() {
         let catalog = Arc::new(MockCatalog::new());
@@ -2738,4 +2963,113 @@ mod tests {
             panic!("Expected SetOperationColumnCountMismatch error");
         }
     }
+
+    #[tokio::test]
+    async fn test_infer_cast_and_json_path_types() {
+        let catalog = Arc::new(MockCatalog::new());
+        let analyzer = SemanticAnalyzer::new(catalog, Dialect::PostgreSQL);
+        let column = || Box::new(Expr::Column(ColumnRef::new("x")));
+
+        let cast = |target_type: &str| Expr::Cast {
+            expr: column(),
+            target_type: target_type.to_string(),
+        };
+        assert_eq!(
+            analyzer.infer_expr_type(&cast("text"), 0).unwrap(),
+            DataType::Text
+        );
+        assert_eq!(
+            analyzer.infer_expr_type(&cast("numeric(10,2)"), 0).unwrap(),
+            DataType::Decimal
+        );
+        assert_eq!(
+            analyzer.infer_expr_type(&cast("VARCHAR(64)"), 0).unwrap(),
+            DataType::Varchar(Some(64))
+        );
+
+        let json_path = |unquote| Expr::JsonPath {
+            expr: column(),
+            path: "$.name".to_string(),
+            unquote,
+        };
+        assert_eq!(
+            analyzer.infer_expr_type(&json_path(false), 0).unwrap(),
+            DataType::Json
+        );
+        assert_eq!(
+            analyzer.infer_expr_type(&json_path(true), 0).unwrap(),
+            DataType::Text
+        );
+    }
+
+    fn aliased_update_returning(dialect: Dialect, returned: ColumnRef) -> Query {
+        let users = TableRef {
+            name: "users".to_string(),
+            alias: Some("u".to_string()),
+            joins: Vec::new(),
+        };
+        let orders = TableRef {
+            name: "orders".to_string(),
+            alias: Some("o".to_string()),
+            joins: Vec::new(),
+        };
+        let update = unified_sql_lsp_ir::UpdateStatement {
+            table: users,
+            assignments: vec![unified_sql_lsp_ir::Assignment {
+                column: "name".to_string(),
+                value: Expr::Column(ColumnRef::new("total").with_table("o")),
+            }],
+            from: vec![orders],
+            where_clause: Some(Expr::BinaryOp {
+                left: Box::new(Expr::Column(ColumnRef::new("id").with_table("u"))),
+                op: BinaryOp::Eq,
+                right: Box::new(Expr::Column(ColumnRef::new("user_id").with_table("o"))),
+            }),
+            returning: Some(vec![
+                SelectItem::UnnamedExpr(Expr::Column(returned)),
+                SelectItem::QualifiedWildcard("u".to_string()),
+            ]),
+        };
+
+        let mut query = Query::new(dialect);
+        query.body = SetOp::Update(Box::new(update));
+        query
+    }
+
+    #[tokio::test]
+    async fn test_update_returning_resolves_target_alias() {
+        let catalog = Arc::new(MockCatalog::new());
+        let mut analyzer = SemanticAnalyzer::new(catalog, Dialect::PostgreSQL);
+
+        let query =
+            aliased_update_returning(Dialect::PostgreSQL, ColumnRef::new("email").with_table("u"));
+        let scope_id = analyzer.analyze_query(&query).await.unwrap();
+        let tables = analyzer.visible_tables(scope_id);
+        assert_eq!(tables.len(), 2);
+        assert!(tables.iter().any(|t| t.alias.as_deref() == Some("u")));
+
+        let (table, column) = analyzer
+            .resolve_column(&ColumnRef::new("email").with_table("u"), scope_id)
+            .unwrap();
+        assert_eq!(table.table_name, "users");
+        assert_eq!(column.name, "email");
+
+        let query =
+            aliased_update_returning(Dialect::PostgreSQL, ColumnRef::new("emial").with_table("u"));
+        assert_eq!(
+            analyzer.analyze_query(&query).await.unwrap_err(),
+            SemanticError::ColumnNotFound("u.emial".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_returning_not_scoped_on_mysql() {
+        let catalog = Arc::new(MockCatalog::new());
+        let mut analyzer = SemanticAnalyzer::new(catalog, Dialect::MySQL);
+
+        // MySQL has no RETURNING; the list is left to compatibility hints
+        let query =
+            aliased_update_returning(Dialect::MySQL, ColumnRef::new("emial").with_table("u"));
+        assert!(analyzer.analyze_query(&query).await.is_ok());
+    }
 }
@@ -9,6 +9,7 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tracing::debug;
 use unified_sql_lsp_catalog::Catalog;
+use unified_sql_lsp_ir::IdentifierComparer;
 
 use crate::{AliasResolutionError, AliasResolver, ColumnSymbol, ScopeManager, TableSymbol};
 
@@ -57,7 +58,7 @@ impl CompletionService {
         for table in &mut resolved_tables {
             if let Some(alias) = alias_to_table
                 .iter()
-                .find(|(_, table_name)| table_name.eq_ignore_ascii_case(&table.table_name))
+                .find(|(_, table_name)| IdentifierComparer::matches(table_name, &table.table_name))
                 .map(|(alias, _)| alias.clone())
             {
                 *table = table.clone().with_alias(alias);
@@ -66,9 +67,13 @@ impl CompletionService {
 
         let tables_to_render = match qualifier {
             Some(q) => {
+                // With both `users` and `"Users"` in scope, `users` means the former
+                let target =
+                    IdentifierComparer::find(q, &resolved_tables, |t| t.table_name.as_str())
+                        .map(|t| t.table_name.clone());
                 let exact_match: Vec<_> = resolved_tables
                     .iter()
-                    .filter(|t| t.table_name.eq_ignore_ascii_case(q))
+                    .filter(|t| Some(&t.table_name) == target.as_ref())
                     .cloned()
                     .collect();
                 if !exact_match.is_empty() {
@@ -76,15 +81,19 @@ impl CompletionService {
                 } else {
                     let alias_match: Vec<_> = resolved_tables
                         .iter()
-                        .filter(|t| t.alias.as_ref().is_some_and(|a| a.eq_ignore_ascii_case(q)))
+                        .filter(|t| {
+                            t.alias
+                                .as_ref()
+                                .is_some_and(|a| IdentifierComparer::matches(q, a))
+                        })
                         .cloned()
                         .collect();
                     if !alias_match.is_empty() {
                         alias_match
                     } else {
-                        let qualifier_matches_context = context_tables
-                            .iter()
-                            .any(|name| name.eq_ignore_ascii_case(q));
+                        let qualifier_matches_context = context_tables.iter().any(|name| {
+                            IdentifierComparer::matches(q, &IdentifierComparer::unquote(name))
+                        });
                         if qualifier_matches_context {
                             Vec::new()
                         } else {
@@ -120,7 +129,12 @@ impl CompletionService {
         let tables_to_render = match qualifier {
             Some(q) => resolved_tables
                 .iter()
-                .filter(|t| t.alias.as_ref().map(|a| a == q).unwrap_or(false) || t.table_name == q)
+                .filter(|t| {
+                    t.alias
+                        .as_ref()
+                        .is_some_and(|a| IdentifierComparer::matches(q, a))
+                        || IdentifierComparer::matches(q, &t.table_name)
+                })
                 .cloned()
                 .collect(),
             None => resolved_tables.clone(),
@@ -229,6 +243,9 @@ impl CompletionService {
         if meta.is_foreign_key {
             symbol = symbol.with_foreign_key();
         }
+        symbol = symbol
+            .with_references(meta.references.clone())
+            .with_not_null(!meta.nullable);
 
         symbol
     }
@@ -236,57 +253,233 @@ impl CompletionService {
 
 impl CompletionTextHeuristics {
     /// Extract selected column names from a SELECT projection list (upper-cased).
+    ///
+    /// Text fallback for when the projection can't be read from the tree.
+    /// Items are split on commas outside parentheses; only items that are a
+    /// plain (possibly qualified) column are returned, so columns passed to
+    /// a call like `COALESCE(a, b)` stay selectable.
     pub fn selected_projection_columns_upper(source: &str) -> HashSet<String> {
         let mut selected_columns = HashSet::new();
-        if let Some(select_pos) = source.to_uppercase().find("SELECT") {
-            let after_select = &source[select_pos + 6..];
-            if let Some(from_pos) = after_select.to_uppercase().find("FROM") {
-                let select_clause = &after_select[..from_pos];
-                for part in select_clause.split(',') {
-                    let part = part.trim();
-                    if part.is_empty() || part.starts_with('(') {
-                        continue;
-                    }
-
-                    let col_name = if let Some(as_pos) = part.to_uppercase().find(" AS ") {
-                        &part[..as_pos]
-                    } else {
-                        part
-                    }
-                    .trim();
-
-                    let final_col = if let Some(dot_pos) = col_name.find('.') {
-                        &col_name[dot_pos + 1..]
-                    } else {
-                        col_name
-                    };
-
-                    if !final_col.is_empty() && !final_col.ends_with('*') {
-                        selected_columns.insert(final_col.to_uppercase());
-                    }
+        let Some(select_pos) = find_keyword(source, "SELECT") else {
+            return selected_columns;
+        };
+        let after_select = &source[select_pos + "SELECT".len()..];
+
+        let mut items = Vec::new();
+        let mut depth = 0usize;
+        let mut item_start = 0;
+        let mut projection_end = None;
+        for (i, c) in after_select.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    items.push(&after_select[item_start..i]);
+                    item_start = i + 1;
                 }
+                _ if depth == 0 && keyword_at(after_select, i, "FROM") => {
+                    projection_end = Some(i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let Some(projection_end) = projection_end else {
+            return selected_columns;
+        };
+        items.push(&after_select[item_start..projection_end]);
+
+        for item in items {
+            let mut words = item.split_whitespace();
+            let Some(column) = words.next() else {
+                continue;
+            };
+            // `col`, `col alias` or `col AS alias`
+            let rest: Vec<&str> = words.collect();
+            let is_plain = match rest.as_slice() {
+                [] | [_] => true,
+                [as_kw, _] => as_kw.eq_ignore_ascii_case("AS"),
+                _ => false,
+            };
+            if !is_plain
+                || column.contains(['(', ')', '\'', '*'])
+                || column.ends_with('.')
+                || !column
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '"' || c == '`')
+            {
+                continue;
             }
+
+            let name = column.rsplit('.').next().unwrap_or(column);
+            selected_columns.insert(name.trim_matches(['"', '`']).to_uppercase());
         }
         selected_columns
     }
 
-    /// Detect whether source currently ends with a CASE keyword boundary.
-    pub fn ends_with_case_expression(source: &str) -> bool {
-        let text_upper = source.to_uppercase();
-        text_upper.ends_with("CASE ")
-            || text_upper.ends_with("CASE\t")
-            || text_upper.ends_with(" CASE ")
-            || text_upper.ends_with(" CASE\t")
+    /// Detect whether the word before the cursor (ignoring a partially typed
+    /// identifier) is the JOIN keyword, i.e. a table name is being completed
+    /// in a JOIN clause.
+    pub fn ends_with_join_keyword(text_before_cursor: &str) -> bool {
+        let without_prefix = text_before_cursor
+            .trim_end_matches(|c: char| c.is_alphanumeric() || c == '_' || c == '.');
+        without_prefix
+            .split_whitespace()
+            .last()
+            .is_some_and(|word| word.eq_ignore_ascii_case("JOIN"))
     }
 
-    /// Decide whether JOIN column completion should force table qualifier.
-    ///
-    /// USING clause does not use qualifiers, so it always returns false.
-    pub fn should_force_join_qualifier(source: &str, resolved_table_count: usize) -> bool {
-        if source.to_uppercase().contains("USING") {
-            false
-        } else {
-            resolved_table_count > 1
+    /// Detect whether the JOIN before the cursor takes no join condition,
+    /// i.e. it is a `CROSS JOIN` or a `NATURAL [LEFT | RIGHT ...] JOIN`.
+    pub fn ends_with_conditionless_join(text_before_cursor: &str) -> bool {
+        if !Self::ends_with_join_keyword(text_before_cursor) {
+            return false;
+        }
+        let without_prefix = text_before_cursor
+            .trim_end_matches(|c: char| c.is_alphanumeric() || c == '_' || c == '.');
+        let mut words = without_prefix.split_whitespace().rev().skip(1);
+        let Some(before_join) = words.next() else {
+            return false;
+        };
+        if before_join.eq_ignore_ascii_case("CROSS") || before_join.eq_ignore_ascii_case("NATURAL")
+        {
+            return true;
         }
+        // NATURAL LEFT [OUTER] JOIN and friends
+        words
+            .take(2)
+            .any(|word| word.eq_ignore_ascii_case("NATURAL"))
+    }
+}
+
+/// Byte offset of the first whole-word, case-insensitive `keyword` in `text`
+fn find_keyword(text: &str, keyword: &str) -> Option<usize> {
+    text.char_indices()
+        .map(|(i, _)| i)
+        .find(|&i| keyword_at(text, i, keyword))
+}
+
+/// Whether `text` has the whole word `keyword`, in any case, at byte `i`
+fn keyword_at(text: &str, i: usize, keyword: &str) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    text.get(i..i + keyword.len())
+        .is_some_and(|candidate| candidate.eq_ignore_ascii_case(keyword))
+        && !text[..i].chars().next_back().is_some_and(is_word)
+        && !text[i + keyword.len()..]
+            .chars()
+            .next()
+            .is_some_and(is_word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unified_sql_lsp_catalog::{ColumnMetadata, DataType, TableMetadata};
+    use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+    /// Catalog with both an unquoted `users` and a quoted `"Users"` table
+    fn mixed_case_service() -> CompletionService {
+        let catalog = MockCatalogBuilder::new()
+            .with_table(TableMetadata::new("users", "public").with_columns(vec![
+                ColumnMetadata::new("id", DataType::Integer),
+                ColumnMetadata::new("email", DataType::Text),
+            ]))
+            .with_table(TableMetadata::new("Users", "public").with_columns(vec![
+                ColumnMetadata::new("id", DataType::Integer),
+                ColumnMetadata::new("DisplayName", DataType::Text),
+            ]))
+            .build();
+        CompletionService::new(Arc::new(catalog))
+    }
+
+    fn columns(tables: &[TableSymbol]) -> Vec<(String, String)> {
+        tables
+            .iter()
+            .flat_map(|t| t.columns.iter())
+            .map(|c| (c.table_name.clone(), c.name.clone()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_quoted_and_unquoted_tables_resolve_distinctly() {
+        let service = mixed_case_service();
+        let context = || vec!["\"Users\"".to_string(), "users".to_string()];
+
+        let quoted = service
+            .resolve_context_tables(context(), Some("\"Users\""))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            columns(&quoted.tables_to_render),
+            vec![
+                ("Users".to_string(), "id".to_string()),
+                ("Users".to_string(), "DisplayName".to_string()),
+            ]
+        );
+
+        let unquoted = service
+            .resolve_context_tables(context(), Some("users"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            columns(&unquoted.tables_to_render),
+            vec![
+                ("users".to_string(), "id".to_string()),
+                ("users".to_string(), "email".to_string()),
+            ]
+        );
+        assert_eq!(unquoted.resolved_tables.len(), 2);
+
+        // A quoted name spelled differently matches nothing
+        let resolution = service
+            .resolve_context_tables(vec!["\"USERS\"".to_string()], None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(resolution.resolved_tables.is_empty());
+    }
+
+    #[test]
+    fn test_ends_with_conditionless_join() {
+        assert!(CompletionTextHeuristics::ends_with_conditionless_join(
+            "SELECT * FROM a CROSS JOIN "
+        ));
+        assert!(CompletionTextHeuristics::ends_with_conditionless_join(
+            "SELECT * FROM a cross join ord"
+        ));
+        assert!(CompletionTextHeuristics::ends_with_conditionless_join(
+            "SELECT * FROM a NATURAL LEFT OUTER JOIN "
+        ));
+        assert!(!CompletionTextHeuristics::ends_with_conditionless_join(
+            "SELECT * FROM a LEFT JOIN "
+        ));
+        assert!(!CompletionTextHeuristics::ends_with_conditionless_join(
+            "SELECT * FROM a FULL OUTER JOIN "
+        ));
+        assert!(!CompletionTextHeuristics::ends_with_conditionless_join(
+            "SELECT * FROM a CROSS JOIN b WHERE "
+        ));
+    }
+
+    #[test]
+    fn test_selected_projection_columns_ignore_call_arguments() {
+        let selected = CompletionTextHeuristics::selected_projection_columns_upper(
+            "SELECT COALESCE(first_name, last_name) AS n, u.email,\n  id ident,  FROM users u",
+        );
+        let mut selected: Vec<_> = selected.into_iter().collect();
+        selected.sort();
+        assert_eq!(selected, vec!["EMAIL", "ID"]);
+
+        // Nothing is excluded before FROM is written
+        assert!(
+            CompletionTextHeuristics::selected_projection_columns_upper("SELECT id, ").is_empty()
+        );
+        assert!(CompletionTextHeuristics::selected_projection_columns_upper(
+            "SELECT count(*), t.* FROM t"
+        )
+        .is_empty());
     }
 }
@@ -7,11 +7,46 @@
 //!
 //! This module defines error types used throughout the semantic analysis layer.
 
+use std::fmt;
 use thiserror::Error;
 
 /// Result type alias for semantic operations
 pub type SemanticResult<T> = Result<T, SemanticError>;
 
+/// A table providing a column in an ambiguous column reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmbiguousCandidate {
+    /// Display name (alias or table name) of the table
+    pub table: String,
+
+    /// Distance from the referencing scope (0 = same scope, 1 = parent, ...)
+    pub scope_depth: usize,
+}
+
+impl AmbiguousCandidate {
+    /// Create a new candidate
+    pub fn new(table: impl Into<String>, scope_depth: usize) -> Self {
+        Self {
+            table: table.into(),
+            scope_depth,
+        }
+    }
+}
+
+impl fmt::Display for AmbiguousCandidate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (scope depth {})", self.table, self.scope_depth)
+    }
+}
+
+fn format_candidates(candidates: &[AmbiguousCandidate]) -> String {
+    candidates
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Errors that can occur during semantic analysis
 #[derive(Debug, Error, Clone, PartialEq, Eq)]
 pub enum SemanticError {
@@ -24,13 +59,20 @@ pub enum SemanticError {
     ColumnNotFound(String),
 
     /// Column reference is ambiguous (found in multiple tables)
-    #[error("Ambiguous column reference: {0} (found in {1:?})")]
-    AmbiguousColumn(String, Vec<String>),
+    #[error(
+        "Ambiguous column reference: {0} (found in {candidates})",
+        candidates = format_candidates(.1)
+    )]
+    AmbiguousColumn(String, Vec<AmbiguousCandidate>),
 
     /// Duplicate table alias in the same scope
     #[error("Duplicate table alias: {0}")]
     DuplicateAlias(String),
 
+    /// CTE name defined twice in the same WITH clause
+    #[error("Duplicate CTE name: {0}")]
+    DuplicateCte(String),
+
     /// Invalid scope reference (e.g., non-existent parent)
     #[error("Invalid scope reference: {0}")]
     InvalidScope(String),
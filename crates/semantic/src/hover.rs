@@ -9,7 +9,7 @@ use std::sync::Arc;
 use tree_sitter::Node;
 use unified_sql_lsp_catalog::{Catalog, ColumnMetadata};
 
-use crate::{AliasResolver, ResolutionResult};
+use crate::{AliasResolver, ResolutionResult, TableSymbol};
 
 /// Semantic hover helper service.
 pub struct HoverService {
@@ -188,6 +188,63 @@ impl HoverService {
         None
     }
 
+    /// Resolve a qualifier (alias or table name) against the tables in scope.
+    ///
+    /// Aliases win over table names, so `FROM users u, orders users` maps
+    /// `users` to `orders`.
+    pub fn resolve_qualifier<'a>(
+        &self,
+        qualifier: &str,
+        scope_tables: &'a [TableSymbol],
+    ) -> Option<&'a TableSymbol> {
+        scope_tables
+            .iter()
+            .find(|t| {
+                t.alias
+                    .as_deref()
+                    .is_some_and(|alias| alias.eq_ignore_ascii_case(qualifier))
+            })
+            .or_else(|| {
+                scope_tables
+                    .iter()
+                    .find(|t| t.alias.is_none() && t.table_name.eq_ignore_ascii_case(qualifier))
+            })
+    }
+
+    /// Resolve `qualifier.column` through the tables in scope.
+    ///
+    /// Returns the concrete table name together with the column.
+    pub async fn resolve_scoped_column(
+        &self,
+        qualifier: &str,
+        column_name: &str,
+        scope_tables: &[TableSymbol],
+    ) -> Option<(String, ColumnMetadata)> {
+        let table = self.resolve_qualifier(qualifier, scope_tables)?;
+        let column = self.lookup_column(&table.table_name, column_name).await?;
+        Some((table.table_name.clone(), column))
+    }
+
+    /// Find every table in scope that has an unqualified column.
+    ///
+    /// More than one result means the reference is ambiguous.
+    pub async fn column_candidates(
+        &self,
+        column_name: &str,
+        scope_tables: &[TableSymbol],
+    ) -> Vec<(String, ColumnMetadata)> {
+        let mut candidates: Vec<(String, ColumnMetadata)> = Vec::new();
+        for table in scope_tables {
+            if candidates.iter().any(|(name, _)| *name == table.table_name) {
+                continue;
+            }
+            if let Some(column) = self.lookup_column(&table.table_name, column_name).await {
+                candidates.push((table.table_name.clone(), column));
+            }
+        }
+        candidates
+    }
+
     fn node_text(&self, node: &Node<'_>, source: &str) -> String {
         let start_byte = node.start_byte();
         let end_byte = node.end_byte();
@@ -205,3 +262,78 @@ impl HoverService {
             .find(|column| column.name.to_lowercase() == column_lower)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unified_sql_lsp_test_utils::MockCatalogBuilder;
+
+    fn service() -> HoverService {
+        let catalog = MockCatalogBuilder::new().with_standard_schema().build();
+        HoverService::new(Arc::new(catalog))
+    }
+
+    /// FROM users u JOIN orders o ON ...
+    fn join_scope() -> Vec<TableSymbol> {
+        vec![
+            TableSymbol::new("users").with_alias("u"),
+            TableSymbol::new("orders").with_alias("o"),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_qualified_column_resolves_through_alias() {
+        let hover = service();
+        let scope = join_scope();
+
+        let (table, column) = hover
+            .resolve_scoped_column("o", "id", &scope)
+            .await
+            .unwrap();
+        assert_eq!(table, "orders");
+        assert_eq!(column.name, "id");
+
+        let (table, _) = hover
+            .resolve_scoped_column("U", "id", &scope)
+            .await
+            .unwrap();
+        assert_eq!(table, "users");
+
+        // Column not in the qualified table
+        assert!(hover
+            .resolve_scoped_column("u", "total", &scope)
+            .await
+            .is_none());
+        // Unknown qualifier
+        assert!(hover
+            .resolve_scoped_column("x", "id", &scope)
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_alias_shadows_table_name() {
+        let hover = service();
+        let scope = vec![
+            TableSymbol::new("users").with_alias("u"),
+            TableSymbol::new("orders").with_alias("users"),
+        ];
+
+        let table = hover.resolve_qualifier("users", &scope).unwrap();
+        assert_eq!(table.table_name, "orders");
+    }
+
+    #[tokio::test]
+    async fn test_unqualified_shared_column_lists_all_tables() {
+        let hover = service();
+        let scope = join_scope();
+
+        let candidates = hover.column_candidates("id", &scope).await;
+        let tables: Vec<_> = candidates.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(tables, vec!["users", "orders"]);
+
+        let candidates = hover.column_candidates("total", &scope).await;
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].0, "orders");
+    }
+}
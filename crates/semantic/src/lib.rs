@@ -117,6 +117,7 @@ pub mod analyzer;
 pub mod completion;
 pub mod error;
 pub mod hover;
+pub mod lint;
 pub mod resolution;
 pub mod scope;
 pub mod symbol;
@@ -129,10 +130,12 @@ pub use alias_resolution::{
 };
 pub use analyzer::SemanticAnalyzer;
 pub use completion::{CompletionService, CompletionTextHeuristics, ContextTableResolution};
-pub use error::{SemanticError, SemanticResult};
+pub use error::{AmbiguousCandidate, SemanticError, SemanticResult};
 pub use hover::HoverService;
+pub use lint::{lint_condition, lint_in_subquery_limits, LintFinding, LintRule};
 pub use resolution::{
-    ColumnCandidate, ColumnResolutionResult, ColumnResolver, MatchKind, ResolutionConfig,
+    fuzzy_score, rank_fuzzy, ColumnCandidate, ColumnResolutionResult, ColumnResolver, MatchKind,
+    ResolutionConfig, UnknownColumn,
 };
 pub use scope::{Scope, ScopeManager, ScopeType};
 pub use symbol::{ColumnSymbol, TableSymbol};
@@ -0,0 +1,337 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+//
+//! # Lint rules
+//!
+//! Checks of lowered conditions that are valid SQL but almost certainly
+//! not what was meant:
+//!
+//! - [`LintRule::NotNullIsNull`]: `IS NULL` on a column the catalog declares
+//!   `NOT NULL` is always false, and `IS NOT NULL` always true
+//! - [`LintRule::EqualsNull`]: `= NULL` and `<> NULL` are never true in any
+//!   dialect; `IS NULL` and `IS NOT NULL` were meant
+//! - [`LintRule::LimitInSubquery`]: MySQL rejects `IN (SELECT ... LIMIT n)`,
+//!   which PostgreSQL runs
+//!
+//! Only columns that resolve to catalog metadata count as `NOT NULL`.
+//! Parameters, placeholders and other expressions of unknown nullability
+//! never trigger the first rule.
+
+use unified_sql_lsp_ir::dialect::DialectFamily;
+use unified_sql_lsp_ir::{BinaryOp, ColumnRef, Dialect, Expr, Literal};
+
+use crate::resolution::{ColumnResolutionResult, ColumnResolver};
+
+/// A lint rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintRule {
+    /// `IS [NOT] NULL` on a `NOT NULL` column
+    NotNullIsNull,
+    /// `= NULL` or `<> NULL` instead of `IS [NOT] NULL`
+    EqualsNull,
+    /// `LIMIT` in a subquery of `IN`, on a dialect without support for it
+    LimitInSubquery,
+}
+
+/// A condition a [`LintRule`] reports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    pub rule: LintRule,
+    pub message: String,
+    /// Text replacing the operator and `NULL`, for rules with a fix
+    pub replacement: Option<String>,
+}
+
+/// Lint every comparison with `NULL` in `condition`, in source order
+///
+/// Each finding comes with the index of its comparison in
+/// [`Expr::binary_exprs`], under which lowering mapped its source. Columns
+/// are resolved in `scope_id` of `resolver`.
+pub fn lint_condition(
+    condition: &Expr,
+    resolver: &ColumnResolver,
+    scope_id: usize,
+) -> Vec<(usize, LintFinding)> {
+    condition
+        .binary_exprs()
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, expr)| {
+            let Expr::BinaryOp { left, op, right } = expr else {
+                return None;
+            };
+            let operand = match (left.as_ref(), right.as_ref()) {
+                (operand, Expr::Literal(Literal::Null)) => operand,
+                // `NULL = x` reads the same
+                (Expr::Literal(Literal::Null), operand)
+                    if matches!(op, BinaryOp::Eq | BinaryOp::NotEq) =>
+                {
+                    operand
+                }
+                _ => return None,
+            };
+            let column = match operand {
+                Expr::Column(column) => Some(column),
+                _ => None,
+            };
+            let finding = lint_null_comparison(column, *op, resolver, scope_id)?;
+            Some((index, finding))
+        })
+        .collect()
+}
+
+/// Lint one comparison `<operand> <op> NULL`
+///
+/// `column` is the operand when it is a column reference; any other
+/// operand has unknown nullability.
+fn lint_null_comparison(
+    column: Option<&ColumnRef>,
+    op: BinaryOp,
+    resolver: &ColumnResolver,
+    scope_id: usize,
+) -> Option<LintFinding> {
+    match op {
+        BinaryOp::Eq | BinaryOp::NotEq => {
+            let (written, meant) = match op {
+                BinaryOp::Eq => ("= NULL", "IS NULL"),
+                _ => ("<> NULL", "IS NOT NULL"),
+            };
+            Some(LintFinding {
+                rule: LintRule::EqualsNull,
+                message: format!("comparison '{}' is never true; use '{}'", written, meant),
+                replacement: Some(meant.to_string()),
+            })
+        }
+        BinaryOp::Is | BinaryOp::IsNot => {
+            let column_ref = column?;
+            let ColumnResolutionResult::Found { column, .. } =
+                resolver.resolve_column(column_ref, scope_id)
+            else {
+                return None;
+            };
+            if !column.not_null {
+                return None;
+            }
+
+            let (check, outcome) = match op {
+                BinaryOp::Is => ("IS NULL", "false"),
+                _ => ("IS NOT NULL", "true"),
+            };
+            Some(LintFinding {
+                rule: LintRule::NotNullIsNull,
+                message: format!(
+                    "'{}' is declared NOT NULL, so '{}' is always {}",
+                    column_ref.qualified(),
+                    check,
+                    outcome
+                ),
+                replacement: None,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Lint the subqueries of `[NOT] IN` in `condition` that have a `LIMIT`
+///
+/// Every MySQL version rejects them, as do the other dialects of its family.
+/// PostgreSQL runs them, so its dialects have no findings. Each finding
+/// comes with the index of its `IN` in [`Expr::binary_exprs`].
+pub fn lint_in_subquery_limits(condition: &Expr, dialect: Dialect) -> Vec<(usize, LintFinding)> {
+    if dialect.family() != DialectFamily::MySQL {
+        return Vec::new();
+    }
+
+    condition
+        .binary_exprs()
+        .into_iter()
+        .enumerate()
+        .filter(|(_, expr)| {
+            let Expr::BinaryOp { op, right, .. } = expr else {
+                return false;
+            };
+            let mut subquery = right.as_ref();
+            while let Expr::Paren(inner) = subquery {
+                subquery = inner;
+            }
+            matches!(op, BinaryOp::In | BinaryOp::NotIn)
+                && matches!(subquery, Expr::Subquery(query) if query.limit.is_some())
+        })
+        .map(|(index, _)| {
+            let finding = LintFinding {
+                rule: LintRule::LimitInSubquery,
+                message: format!(
+                    "{:?} doesn't support LIMIT in an IN subquery; join the subquery instead, \
+                     as in 'JOIN (SELECT DISTINCT ... LIMIT n) AS limited ON ...'",
+                    dialect
+                ),
+                replacement: None,
+            };
+            (index, finding)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scope::{ScopeManager, ScopeType};
+    use crate::symbol::{ColumnSymbol, TableSymbol};
+    use unified_sql_lsp_catalog::DataType;
+    use unified_sql_lsp_ir::Query;
+
+    fn resolver() -> (ColumnResolver, usize) {
+        let mut manager = ScopeManager::new();
+        let scope_id = manager.create_scope(ScopeType::Query, None);
+        let users = TableSymbol::new("users").with_alias("u").with_columns(vec![
+            ColumnSymbol::new("id", DataType::Integer, "users").with_not_null(true),
+            ColumnSymbol::new("email", DataType::Text, "users"),
+        ]);
+        manager
+            .get_scope_mut(scope_id)
+            .unwrap()
+            .add_table(users)
+            .unwrap();
+        (ColumnResolver::new(manager), scope_id)
+    }
+
+    fn compare(left: Expr, op: BinaryOp, right: Expr) -> Expr {
+        Expr::BinaryOp {
+            left: Box::new(left),
+            op,
+            right: Box::new(right),
+        }
+    }
+
+    fn column(name: &str) -> Expr {
+        Expr::Column(ColumnRef::new(name))
+    }
+
+    fn null() -> Expr {
+        Expr::Literal(Literal::Null)
+    }
+
+    fn lint(condition: Expr) -> Vec<LintFinding> {
+        let (resolver, scope_id) = resolver();
+        lint_condition(&condition, &resolver, scope_id)
+            .into_iter()
+            .map(|(_, finding)| finding)
+            .collect()
+    }
+
+    #[test]
+    fn test_is_null_on_not_null_column() {
+        let findings = lint(compare(column("id"), BinaryOp::Is, null()));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, LintRule::NotNullIsNull);
+        assert_eq!(
+            findings[0].message,
+            "'id' is declared NOT NULL, so 'IS NULL' is always false"
+        );
+        assert_eq!(findings[0].replacement, None);
+
+        let qualified = Expr::Column(ColumnRef::new("id").with_table("u"));
+        let findings = lint(compare(qualified, BinaryOp::IsNot, null()));
+        assert_eq!(
+            findings[0].message,
+            "'u.id' is declared NOT NULL, so 'IS NOT NULL' is always true"
+        );
+    }
+
+    #[test]
+    fn test_unknown_nullability_is_not_reported() {
+        // Nullable column, placeholder parameter, unknown column, expression
+        assert!(lint(compare(column("email"), BinaryOp::Is, null())).is_empty());
+        assert!(lint(compare(column("__placeholder_0"), BinaryOp::Is, null())).is_empty());
+        assert!(lint(compare(column("missing"), BinaryOp::IsNot, null())).is_empty());
+        let call = Expr::Function {
+            name: "lower".to_string(),
+            args: vec![column("id")],
+            distinct: false,
+            filter: None,
+            over: None,
+        };
+        assert!(lint(compare(call, BinaryOp::Is, null())).is_empty());
+    }
+
+    #[test]
+    fn test_equals_null_in_nested_conditions() {
+        // id > 1 AND (email = NULL OR NULL <> id)
+        let condition = compare(
+            compare(
+                column("id"),
+                BinaryOp::Gt,
+                Expr::Literal(Literal::Integer(1)),
+            ),
+            BinaryOp::And,
+            Expr::Paren(Box::new(compare(
+                compare(column("email"), BinaryOp::Eq, null()),
+                BinaryOp::Or,
+                compare(null(), BinaryOp::NotEq, column("id")),
+            ))),
+        );
+        let (resolver, scope_id) = resolver();
+        let findings = lint_condition(&condition, &resolver, scope_id);
+        // The comparisons are the second and third binary expressions built
+        assert_eq!(
+            findings,
+            vec![
+                (
+                    1,
+                    LintFinding {
+                        rule: LintRule::EqualsNull,
+                        message: "comparison '= NULL' is never true; use 'IS NULL'".to_string(),
+                        replacement: Some("IS NULL".to_string()),
+                    }
+                ),
+                (
+                    2,
+                    LintFinding {
+                        rule: LintRule::EqualsNull,
+                        message: "comparison '<> NULL' is never true; use 'IS NOT NULL'"
+                            .to_string(),
+                        replacement: Some("IS NOT NULL".to_string()),
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_limit_in_subquery_only_for_mysql_family() {
+        let subquery = |limit: Option<i64>| {
+            let mut query = Query::new(Dialect::MySQL);
+            query.limit = limit.map(|n| Expr::Literal(Literal::Integer(n)));
+            Expr::Subquery(Box::new(query))
+        };
+        // email <> '' AND id NOT IN (SELECT ... LIMIT 5) OR id IN (SELECT ...)
+        let condition = compare(
+            compare(
+                compare(
+                    column("email"),
+                    BinaryOp::NotEq,
+                    Expr::Literal(Literal::String(String::new())),
+                ),
+                BinaryOp::And,
+                compare(column("id"), BinaryOp::NotIn, subquery(Some(5))),
+            ),
+            BinaryOp::Or,
+            compare(column("id"), BinaryOp::In, subquery(None)),
+        );
+
+        let findings = lint_in_subquery_limits(&condition, Dialect::MySQL);
+        assert_eq!(findings.len(), 1);
+        let (index, finding) = &findings[0];
+        assert_eq!(*index, 1);
+        assert_eq!(finding.rule, LintRule::LimitInSubquery);
+        assert!(finding.message.starts_with("MySQL doesn't support LIMIT"));
+        assert_eq!(lint_in_subquery_limits(&condition, Dialect::TiDB).len(), 1);
+        assert!(lint_in_subquery_limits(&condition, Dialect::PostgreSQL).is_empty());
+
+        // A limited subquery compared otherwise is fine
+        let scalar = compare(column("id"), BinaryOp::Eq, subquery(Some(1)));
+        assert!(lint_in_subquery_limits(&scalar, Dialect::MySQL).is_empty());
+    }
+}
@@ -6,17 +6,22 @@
 //! # Column Reference Resolution with Fuzzy Matching
 //!
 //! This module provides advanced column resolution capabilities including:
-//! - Exact and case-insensitive matching
-//! - Fuzzy matching using Levenshtein distance for typo correction
+//! - Exact and case-insensitive matching, with quoted names matching only
+//!   their exact spelling (see [`IdentifierComparer`])
+//! - Typo correction using Levenshtein distance
 //! - Prefix matching for partial column names
+//! - Fuzzy (subsequence) matching for partially typed names, e.g. `crtd` →
+//!   `created_at`, enabled with [`ResolutionConfig::fuzzy`]
 //! - Ambiguity detection with candidate suggestions
 //! - Configurable similarity thresholds
+//! - "Did you mean" replacements for unknown columns, limited to tables in scope
 
-use crate::error::SemanticError;
+use crate::error::{AmbiguousCandidate, SemanticError};
 use crate::scope::ScopeManager;
 use crate::symbol::{ColumnSymbol, TableSymbol};
 use std::cmp::{max, min};
-use unified_sql_lsp_ir::ColumnRef;
+use std::fmt;
+use unified_sql_lsp_ir::{ColumnRef, IdentifierComparer};
 
 /// Enhanced result type for column resolution with alternatives
 #[derive(Debug, Clone, PartialEq)]
@@ -88,9 +93,10 @@ impl ColumnResolutionResult {
                 Err(SemanticError::ColumnNotFound("column".to_string()))
             }
             ColumnResolutionResult::Ambiguous { candidates } => {
+                // Candidates are gathered from a flat table list, so no depth is known
                 let tables = candidates
                     .iter()
-                    .map(|c| c.table.display_name().to_string())
+                    .map(|c| AmbiguousCandidate::new(c.table.display_name(), 0))
                     .collect();
                 Err(SemanticError::AmbiguousColumn("column".to_string(), tables))
             }
@@ -120,7 +126,7 @@ impl ColumnCandidate {
             MatchKind::CaseInsensitive => {
                 self.relevance_score = 0.95;
             }
-            MatchKind::Fuzzy { distance } => {
+            MatchKind::Typo { distance } => {
                 // Base score from similarity
                 let base_score = similarity_score(query, name);
 
@@ -139,6 +145,9 @@ impl ColumnCandidate {
                 let total_len = name.len();
                 self.relevance_score = (prefix_len as f64 / total_len as f64) * 0.85;
             }
+            MatchKind::Fuzzy { score } => {
+                self.relevance_score = (score as f64 / fuzzy_max_score(query) as f64) * 0.9;
+            }
         }
 
         // Bonus for common column names (id, name, created_at, etc.)
@@ -151,6 +160,42 @@ impl ColumnCandidate {
     }
 }
 
+/// Minimum shared prefix for a column to be suggested despite a larger edit distance
+const MIN_SUGGESTION_PREFIX: usize = 4;
+
+/// A column reference that doesn't resolve, with an optional replacement
+///
+/// Displays as the diagnostic message, e.g.
+/// `unknown column 'emial'; did you mean 'email'?`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownColumn {
+    /// The reference as written, e.g. `u.emial`
+    pub reference: String,
+
+    /// Replacement for the whole reference, e.g. `u.email`
+    pub suggestion: Option<String>,
+
+    /// Whether the column is quoted and the suggestion only differs from it
+    /// in case, e.g. `"Name"` for the column `name`
+    pub case_mismatch: bool,
+}
+
+impl fmt::Display for UnknownColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown column '{}'", self.reference)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "; did you mean '{}'?", suggestion)?;
+        }
+        if self.case_mismatch {
+            write!(
+                f,
+                " (quoted identifiers are case-sensitive; unquoted ones are folded to lowercase)"
+            )?;
+        }
+        Ok(())
+    }
+}
+
 /// How the column matches the query
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MatchKind {
@@ -158,10 +203,12 @@ pub enum MatchKind {
     Exact,
     /// Case-insensitive match
     CaseInsensitive,
-    /// Fuzzy match (typo correction)
-    Fuzzy { distance: usize },
+    /// Typo correction within the Levenshtein `distance`
+    Typo { distance: usize },
     /// Similar prefix (e.g., "user_name" vs "user_firstname")
     PrefixMatch,
+    /// Query is a subsequence of the name (see [`fuzzy_score`])
+    Fuzzy { score: u32 },
 }
 
 /// Configuration for fuzzy matching
@@ -175,6 +222,9 @@ pub struct ResolutionConfig {
     pub max_suggestions: usize,
     /// Enable case-insensitive matching (default: true)
     pub case_insensitive: bool,
+    /// Minimum [`fuzzy_score`] for subsequence matching; `None` uses typo
+    /// and prefix matching instead (default: None)
+    pub fuzzy_threshold: Option<u32>,
 }
 
 impl Default for ResolutionConfig {
@@ -184,8 +234,28 @@ impl Default for ResolutionConfig {
             min_similarity: 0.6,
             max_suggestions: 5,
             case_insensitive: true,
+            fuzzy_threshold: None,
+        }
+    }
+}
+
+impl ResolutionConfig {
+    /// Subsequence matching for partially typed names
+    ///
+    /// Names whose [`fuzzy_score`] against the query reaches `threshold`
+    /// match; typo and prefix matching are not used.
+    pub fn fuzzy(threshold: u32) -> Self {
+        Self {
+            fuzzy_threshold: Some(threshold),
+            ..Default::default()
         }
     }
+
+    /// Fuzzy score of `candidate`, if fuzzy matching is enabled and it reaches the threshold
+    pub fn fuzzy_match(&self, query: &str, candidate: &str) -> Option<u32> {
+        let threshold = self.fuzzy_threshold?;
+        fuzzy_score(query, candidate).filter(|score| *score >= threshold)
+    }
 }
 
 /// Column resolver with fuzzy matching and suggestions
@@ -312,13 +382,14 @@ impl ColumnResolver {
     fn collect_candidates(&self, column_name: &str, scope_id: usize) -> Vec<ColumnCandidate> {
         let mut candidates = Vec::new();
         let tables = self.collect_visible_tables(scope_id);
+        let query = IdentifierComparer::unquote(column_name);
 
         for table in tables {
-            for column in &table.columns {
+            for column in table.columns.iter() {
                 let match_kind = self.determine_match_kind(column_name, &column.name);
 
                 // Only include candidates that meet our minimum criteria
-                if self.is_candidate_acceptable(&match_kind, &column.name, column_name) {
+                if self.is_candidate_acceptable(&match_kind, &column.name, &query) {
                     let mut candidate = ColumnCandidate {
                         table: table.clone(),
                         column: column.clone(),
@@ -326,17 +397,18 @@ impl ColumnResolver {
                         match_kind,
                     };
 
-                    candidate.calculate_score(column_name, &self.config);
+                    candidate.calculate_score(&query, &self.config);
                     candidates.push(candidate);
                 }
             }
         }
 
-        // Sort by relevance score (descending)
+        // Sort by relevance score (descending), ties alphabetically
         candidates.sort_by(|a, b| {
             b.relevance_score
                 .partial_cmp(&a.relevance_score)
                 .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.column.name.cmp(&b.column.name))
         });
 
         // Limit to max suggestions
@@ -346,23 +418,39 @@ impl ColumnResolver {
     }
 
     /// Determine what kind of match this is
+    ///
+    /// A quoted `query` is compared without its delimiters but never
+    /// matches case-insensitively.
     fn determine_match_kind(&self, query: &str, column_name: &str) -> MatchKind {
+        let quoted = IdentifierComparer::is_quoted(query);
+        let query = &IdentifierComparer::unquote(query);
+
         // Exact match
         if query == column_name {
             return MatchKind::Exact;
         }
 
         // Case-insensitive match
-        if self.config.case_insensitive && query.eq_ignore_ascii_case(column_name) {
+        if !quoted && self.config.case_insensitive && query.eq_ignore_ascii_case(column_name) {
             return MatchKind::CaseInsensitive;
         }
 
-        // Fuzzy match (Levenshtein distance)
+        // Subsequence match replaces typo and prefix matching when enabled
+        if self.config.fuzzy_threshold.is_some() {
+            return match self.config.fuzzy_match(query, column_name) {
+                Some(score) => MatchKind::Fuzzy { score },
+                None => MatchKind::Typo {
+                    distance: usize::MAX,
+                },
+            };
+        }
+
+        // Typo match (Levenshtein distance)
         let distance = levenshtein_distance(query, column_name);
         if distance <= self.config.max_distance {
             let similarity = similarity_score(query, column_name);
             if similarity >= self.config.min_similarity {
-                return MatchKind::Fuzzy { distance };
+                return MatchKind::Typo { distance };
             }
         }
 
@@ -374,7 +462,7 @@ impl ColumnResolver {
         }
 
         // No match
-        MatchKind::Fuzzy {
+        MatchKind::Typo {
             distance: usize::MAX,
         }
     }
@@ -388,13 +476,17 @@ impl ColumnResolver {
     ) -> bool {
         match match_kind {
             MatchKind::Exact | MatchKind::CaseInsensitive => true,
-            MatchKind::Fuzzy { distance } => {
+            MatchKind::Typo { distance } => {
                 *distance <= self.config.max_distance
                     && similarity_score(query, column_name) >= self.config.min_similarity
             }
             MatchKind::PrefixMatch => {
                 query.len() >= 3 // Require at least 3 chars for prefix match
             }
+            MatchKind::Fuzzy { score } => self
+                .config
+                .fuzzy_threshold
+                .is_some_and(|threshold| *score >= threshold),
         }
     }
 
@@ -405,11 +497,12 @@ impl ColumnResolver {
         table: &TableSymbol,
     ) -> Vec<ColumnCandidate> {
         let mut candidates = Vec::new();
+        let query = IdentifierComparer::unquote(column_name);
 
-        for column in &table.columns {
+        for column in table.columns.iter() {
             let match_kind = self.determine_match_kind(column_name, &column.name);
 
-            if self.is_candidate_acceptable(&match_kind, &column.name, column_name) {
+            if self.is_candidate_acceptable(&match_kind, &column.name, &query) {
                 let mut candidate = ColumnCandidate {
                     table: table.clone(),
                     column: column.clone(),
@@ -417,7 +510,7 @@ impl ColumnResolver {
                     match_kind,
                 };
 
-                candidate.calculate_score(column_name, &self.config);
+                candidate.calculate_score(&query, &self.config);
                 candidates.push(candidate);
             }
         }
@@ -426,6 +519,7 @@ impl ColumnResolver {
             b.relevance_score
                 .partial_cmp(&a.relevance_score)
                 .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.column.name.cmp(&b.column.name))
         });
 
         candidates.truncate(self.config.max_suggestions);
@@ -462,7 +556,7 @@ impl ColumnResolver {
         let mut candidates = Vec::new();
 
         for table in tables {
-            for column in &table.columns {
+            for column in table.columns.iter() {
                 candidates.push(ColumnCandidate {
                     table: table.clone(),
                     column: column.clone(),
@@ -479,6 +573,212 @@ impl ColumnResolver {
     pub fn find_similar_columns(&self, column_name: &str, scope_id: usize) -> Vec<ColumnCandidate> {
         self.collect_candidates(column_name, scope_id)
     }
+
+    /// Describe a column reference that doesn't resolve
+    ///
+    /// Returns `None` if the reference resolves (or is ambiguous, which is
+    /// reported separately). Otherwise the nearest column within
+    /// `max_distance` edits, or sharing a prefix of at least four characters,
+    /// is suggested. Only tables in scope are considered:
+    ///
+    /// - `emial` looks at every visible table and suggests `email`
+    /// - `u.emial` looks only at the table behind `u` and suggests `u.email`
+    /// - `u.total`, when `total` only exists in another visible table `o`,
+    ///   suggests the qualified form `o.total`
+    ///
+    /// References to tables that aren't in scope get no suggestion. A
+    /// quoted column keeps its delimiters in the suggestion; when the
+    /// suggested column only differs from it in case, the message explains
+    /// the case-folding rule.
+    pub fn diagnose_unknown(
+        &self,
+        column_ref: &ColumnRef,
+        scope_id: usize,
+    ) -> Option<UnknownColumn> {
+        if !matches!(
+            self.resolve_column(column_ref, scope_id),
+            ColumnResolutionResult::NotFoundWithSuggestions { .. }
+        ) {
+            return None;
+        }
+
+        let column = &IdentifierComparer::unquote(&column_ref.column);
+        let quoted = IdentifierComparer::is_quoted(&column_ref.column);
+        // The suggested column, written like the reference
+        let written = |c: &ColumnSymbol| {
+            if quoted {
+                let delimiter = &column_ref.column[..1];
+                format!("{}{}{}", delimiter, c.name, delimiter)
+            } else {
+                c.name.clone()
+            }
+        };
+
+        let nearest = match &column_ref.table {
+            Some(qualifier) => self
+                .scope_manager
+                .resolve_table(qualifier, scope_id)
+                .ok()
+                .and_then(|table| {
+                    self.nearest_column(column, std::slice::from_ref(table))
+                        .map(|c| (format!("{}.{}", qualifier, written(c)), c.clone()))
+                        .or_else(|| {
+                            let others: Vec<_> = self
+                                .collect_visible_tables(scope_id)
+                                .into_iter()
+                                .filter(|t| t.table_name != table.table_name)
+                                .collect();
+                            self.nearest_column_with_table(column, &others)
+                                .map(|(t, c)| {
+                                    (format!("{}.{}", t.display_name(), written(c)), c.clone())
+                                })
+                        })
+                }),
+            None => {
+                let tables = self.collect_visible_tables(scope_id);
+                self.nearest_column(column, &tables)
+                    .map(|c| (written(c), c.clone()))
+            }
+        };
+
+        Some(UnknownColumn {
+            reference: column_ref.qualified(),
+            case_mismatch: quoted
+                && nearest
+                    .as_ref()
+                    .is_some_and(|(_, c)| c.name.eq_ignore_ascii_case(column)),
+            suggestion: nearest.map(|(suggestion, _)| suggestion),
+        })
+    }
+
+    /// Nearest suggestible column among `tables`
+    fn nearest_column<'a>(
+        &self,
+        query: &str,
+        tables: &'a [TableSymbol],
+    ) -> Option<&'a ColumnSymbol> {
+        self.nearest_column_with_table(query, tables)
+            .map(|(_, column)| column)
+    }
+
+    /// Nearest suggestible column among `tables`, with the table it belongs to
+    ///
+    /// Candidates are ranked by edit distance, then by longest shared prefix;
+    /// ties keep the first table and column in scope order.
+    fn nearest_column_with_table<'a>(
+        &self,
+        query: &str,
+        tables: &'a [TableSymbol],
+    ) -> Option<(&'a TableSymbol, &'a ColumnSymbol)> {
+        let query_lower = query.to_lowercase();
+
+        tables
+            .iter()
+            .flat_map(|table| table.columns.iter().map(move |column| (table, column)))
+            .filter_map(|(table, column)| {
+                let name = column.name.to_lowercase();
+                let distance = levenshtein_distance(&query_lower, &name);
+                let prefix = common_prefix_len(&query_lower, &name);
+                (distance <= self.config.max_distance || prefix >= MIN_SUGGESTION_PREFIX)
+                    .then_some(((distance, std::cmp::Reverse(prefix)), table, column))
+            })
+            .min_by_key(|(rank, _, _)| *rank)
+            .map(|(_, table, column)| (table, column))
+    }
+}
+
+/// Score for each query character matched in a fuzzy match
+const FUZZY_MATCH_SCORE: u32 = 1;
+
+/// Bonus for a character matched right after the previous one
+const FUZZY_CONSECUTIVE_BONUS: u32 = 4;
+
+/// Bonus for a character matched at the start of a word
+const FUZZY_BOUNDARY_BONUS: u32 = 6;
+
+/// Score a fuzzy (subsequence) match of `query` against `candidate`
+///
+/// Returns `None` unless every query character appears in `candidate` in
+/// order, compared case-insensitively. Each matched character scores 1,
+/// plus 4 when it directly follows the previous match and 6 when it starts
+/// a word (start of the name, after `_`/`.`/`-`, or a camelCase hump).
+/// The best alignment is used, so `crtd` against `created_at` scores the
+/// `cr` run rather than the first `t`/`d` found.
+///
+/// An empty query matches everything with a score of 0.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let query: Vec<char> = query.chars().collect();
+    let candidate: Vec<char> = candidate.chars().collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let boundary = |j: usize| {
+        j == 0 || {
+            let (prev, cur) = (candidate[j - 1], candidate[j]);
+            !prev.is_alphanumeric() || (prev.is_lowercase() && cur.is_uppercase())
+        }
+    };
+    let char_score =
+        |j: usize| FUZZY_MATCH_SCORE + if boundary(j) { FUZZY_BOUNDARY_BONUS } else { 0 };
+    let same = |a: char, b: char| a.to_lowercase().eq(b.to_lowercase());
+
+    // best[j]: best score with the current query char matched at candidate[j]
+    let mut best: Vec<Option<u32>> = candidate
+        .iter()
+        .enumerate()
+        .map(|(j, &c)| same(query[0], c).then(|| char_score(j)))
+        .collect();
+
+    for &q in &query[1..] {
+        let mut next = vec![None; candidate.len()];
+        // Best score of the previous char matched strictly before j - 1
+        let mut best_before: Option<u32> = None;
+        for j in 1..candidate.len() {
+            if j >= 2 {
+                best_before = best_before.max(best[j - 2]);
+            }
+            if !same(q, candidate[j]) {
+                continue;
+            }
+            let gap = best_before.map(|s| s + char_score(j));
+            let run = best[j - 1].map(|s| s + char_score(j) + FUZZY_CONSECUTIVE_BONUS);
+            next[j] = gap.max(run);
+        }
+        best = next;
+    }
+
+    best.into_iter().flatten().max()
+}
+
+/// Highest possible [`fuzzy_score`] for a query, used to normalize scores
+fn fuzzy_max_score(query: &str) -> u32 {
+    let len = query.chars().count() as u32;
+    if len == 0 {
+        return 1;
+    }
+    (FUZZY_MATCH_SCORE + FUZZY_BOUNDARY_BONUS)
+        + (len - 1) * (FUZZY_MATCH_SCORE + FUZZY_BOUNDARY_BONUS + FUZZY_CONSECUTIVE_BONUS)
+}
+
+/// Rank candidates by [`fuzzy_score`], best first, ties alphabetically
+///
+/// Candidates that don't contain the query as a subsequence are left out.
+pub fn rank_fuzzy<'a>(
+    query: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<(&'a str, u32)> {
+    let mut ranked: Vec<(&str, u32)> = candidates
+        .into_iter()
+        .filter_map(|candidate| Some((candidate, fuzzy_score(query, candidate)?)))
+        .collect();
+    ranked.sort_by(|(a, a_score), (b, b_score)| b_score.cmp(a_score).then_with(|| a.cmp(b)));
+    ranked
+}
+
+/// Number of leading characters two strings share
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count()
 }
 
 /// Calculate Levenshtein distance between two strings
@@ -669,6 +969,65 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fuzzy_ranking() {
+        // (query, candidates, expected ranking)
+        let cases: &[(&str, &[&str], &[&str])] = &[
+            // Equal scores are broken alphabetically
+            (
+                "crtd",
+                &["credit_card", "created_at", "user_id"],
+                &["created_at", "credit_card"],
+            ),
+            (
+                "user",
+                &["users", "username", "user_id"],
+                &["user_id", "username", "users"],
+            ),
+            // Word-boundary matches beat scattered ones
+            ("uid", &["uuid", "user_id", "id"], &["user_id", "uuid"]),
+            ("ca", &["cart", "created_at"], &["created_at", "cart"]),
+            // camelCase humps count as word boundaries, matching ignores case
+            ("uN", &["username", "userName"], &["userName", "username"]),
+            ("xyz", &["id", "name"], &[]),
+        ];
+
+        for (query, candidates, expected) in cases {
+            let ranked: Vec<&str> = rank_fuzzy(query, candidates.iter().copied())
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect();
+            assert_eq!(&ranked, expected, "query {:?}", query);
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "id"), Some(0));
+        assert_eq!(fuzzy_score("crtd", "created_at"), Some(14));
+        assert_eq!(fuzzy_score("CRTD", "created_at"), Some(14));
+        assert_eq!(fuzzy_score("dtrc", "created_at"), None);
+        assert_eq!(fuzzy_score("ids", "id"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_resolution_config() {
+        let config = ResolutionConfig::fuzzy(10);
+        assert_eq!(config.fuzzy_match("crtd", "created_at"), Some(14));
+        // Scattered match below the threshold
+        assert_eq!(config.fuzzy_match("ae", "created_at"), None);
+        assert_eq!(
+            ResolutionConfig::default().fuzzy_match("crtd", "created_at"),
+            None
+        );
+
+        let resolver = ColumnResolver::with_config(create_test_scope_manager(), config);
+        let suggestions = resolver.find_similar_columns("crtd", 0);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].column.name, "created_at");
+        assert_eq!(suggestions[0].match_kind, MatchKind::Fuzzy { score: 14 });
+    }
+
     #[test]
     fn test_levenshtein_distance() {
         assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
@@ -770,4 +1129,83 @@ mod tests {
             ),
         }
     }
+
+    fn create_aliased_scope_manager() -> ScopeManager {
+        let mut manager = ScopeManager::new();
+        let scope_id = manager.create_scope(ScopeType::Query, None);
+        let scope = manager.get_scope_mut(scope_id).unwrap();
+
+        scope
+            .add_table(TableSymbol::new("users").with_alias("u").with_columns(vec![
+                ColumnSymbol::new("id", DataType::Integer, "users"),
+                ColumnSymbol::new("email", DataType::Text, "users"),
+                ColumnSymbol::new("created_at", DataType::Timestamp, "users"),
+            ]))
+            .unwrap();
+        scope
+            .add_table(
+                TableSymbol::new("orders")
+                    .with_alias("o")
+                    .with_columns(vec![
+                        ColumnSymbol::new("id", DataType::Integer, "orders"),
+                        ColumnSymbol::new("user_id", DataType::Integer, "orders"),
+                        ColumnSymbol::new("total", DataType::Integer, "orders"),
+                    ]),
+            )
+            .unwrap();
+
+        manager
+    }
+
+    fn unknown_message(column_ref: ColumnRef) -> Option<String> {
+        let resolver = ColumnResolver::new(create_aliased_scope_manager());
+        resolver
+            .diagnose_unknown(&column_ref, 0)
+            .map(|unknown| unknown.to_string())
+    }
+
+    #[test]
+    fn test_unknown_column_suggests_nearest_in_scope() {
+        assert_eq!(
+            unknown_message(ColumnRef::new("emial")).as_deref(),
+            Some("unknown column 'emial'; did you mean 'email'?")
+        );
+        // Common prefix of at least four characters
+        assert_eq!(
+            unknown_message(ColumnRef::new("created")).as_deref(),
+            Some("unknown column 'created'; did you mean 'created_at'?")
+        );
+        // Resolvable references are not reported
+        assert_eq!(unknown_message(ColumnRef::new("email")), None);
+    }
+
+    #[test]
+    fn test_unknown_qualified_column_only_considers_its_table() {
+        assert_eq!(
+            unknown_message(ColumnRef::new("emial").with_table("u")).as_deref(),
+            Some("unknown column 'u.emial'; did you mean 'u.email'?")
+        );
+        // `user_id` only exists in `orders`, so the qualified form is suggested
+        assert_eq!(
+            unknown_message(ColumnRef::new("user_id").with_table("u")).as_deref(),
+            Some("unknown column 'u.user_id'; did you mean 'o.user_id'?")
+        );
+    }
+
+    #[test]
+    fn test_unknown_column_in_other_table_proposes_qualified_form() {
+        assert_eq!(
+            unknown_message(ColumnRef::new("totl").with_table("u")).as_deref(),
+            Some("unknown column 'u.totl'; did you mean 'o.total'?")
+        );
+        // Columns of tables that aren't in scope are never suggested
+        assert_eq!(
+            unknown_message(ColumnRef::new("price")).as_deref(),
+            Some("unknown column 'price'")
+        );
+        assert_eq!(
+            unknown_message(ColumnRef::new("id").with_table("p")).as_deref(),
+            Some("unknown column 'p.id'")
+        );
+    }
 }
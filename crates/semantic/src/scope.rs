@@ -7,10 +7,21 @@
 //!
 //! This module defines scope types and the scope manager for tracking
 //! tables and their visibility across nested SQL queries.
-
-use crate::error::{SemanticError, SemanticResult};
+//!
+//! ## Shadowing
+//!
+//! A table name or alias in a scope shadows the same name in any parent
+//! scope: resolution walks from the referencing scope outwards and stops at
+//! the nearest scope that defines the name. Within a single scope, names must
+//! be unique; duplicates are rejected and recorded as
+//! [`SemanticError::DuplicateAlias`], or [`SemanticError::DuplicateCte`] for
+//! the CTEs of one `WITH` clause, which are compared the way the dialect
+//! folds identifiers.
+
+use crate::error::{AmbiguousCandidate, SemanticError, SemanticResult};
 use crate::symbol::{ColumnSymbol, TableSymbol};
 use serde::{Deserialize, Serialize};
+use unified_sql_lsp_ir::{Dialect, IdentifierComparer, Query, SetOp, TableRef};
 
 /// Type of scope in a SQL query
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -34,6 +45,17 @@ pub struct Scope {
     pub parent_id: Option<usize>,
     pub tables: Vec<TableSymbol>,
     pub scope_type: ScopeType,
+    /// Errors recorded while populating this scope (e.g. duplicate aliases)
+    #[serde(skip)]
+    pub errors: Vec<SemanticError>,
+    /// Definition numbers of `tables`, in a scope built by
+    /// [`ScopeManager::for_query`]
+    #[serde(skip)]
+    pub definitions: Vec<usize>,
+    /// Definition numbers of the first and the repeated name behind each of
+    /// `errors`, in a scope built by [`ScopeManager::for_query`]
+    #[serde(skip)]
+    pub redefinitions: Vec<(usize, usize)>,
 }
 
 impl Scope {
@@ -59,6 +81,9 @@ impl Scope {
             parent_id: None,
             tables: Vec::new(),
             scope_type,
+            errors: Vec::new(),
+            definitions: Vec::new(),
+            redefinitions: Vec::new(),
         }
     }
 
@@ -103,17 +128,69 @@ impl Scope {
     ///
     /// # Returns
     ///
-    /// `Ok(())` if successful, `Err(SemanticError)` if duplicate alias
+    /// `Ok(())` if successful, `Err(SemanticError)` if duplicate alias.
+    /// A duplicate is also recorded in [`Scope::errors`] so it can be reported
+    /// as a diagnostic even when the caller continues past it; the first table
+    /// with the alias stays the one that resolves.
     pub fn add_table(&mut self, table: TableSymbol) -> SemanticResult<()> {
-        // Check for duplicate alias
+        // Check for duplicate alias (`FROM a x JOIN b x`)
         let display_name = table.display_name();
         if self.tables.iter().any(|t| t.display_name() == display_name) {
-            return Err(SemanticError::DuplicateAlias(display_name.to_string()));
+            let error = SemanticError::DuplicateAlias(display_name.to_string());
+            self.errors.push(error.clone());
+            return Err(error);
+        }
+
+        self.tables.push(table);
+        Ok(())
+    }
+
+    /// Add the synthetic table of a CTE to this scope
+    ///
+    /// Like [`Self::add_table`], but a name repeating an earlier CTE the way
+    /// `dialect` folds identifiers is recorded and returned as
+    /// [`SemanticError::DuplicateCte`].
+    pub fn add_cte(&mut self, table: TableSymbol, dialect: Dialect) -> SemanticResult<()> {
+        if self.find_folded(&table.table_name, dialect).is_some() {
+            let error = SemanticError::DuplicateCte(table.table_name.clone());
+            self.errors.push(error.clone());
+            return Err(error);
         }
 
         self.tables.push(table);
         Ok(())
     }
+
+    /// Position in `tables` of the name `name` collides with in `dialect`
+    fn find_folded(&self, name: &str, dialect: Dialect) -> Option<usize> {
+        // Names are stored unquoted; render them back to fold them
+        let identifiers = IdentifierComparer::new(dialect);
+        let folded = |name: &str| identifiers.fold(&identifiers.render(name));
+        let key = folded(name);
+        self.tables
+            .iter()
+            .position(|t| folded(t.display_name()) == key)
+    }
+
+    /// Add `table`, the definition numbered `number`, as a CTE or as a
+    /// table of a FROM list
+    ///
+    /// Both kinds of names are compared the way `dialect` folds them.
+    fn define(&mut self, table: TableSymbol, number: usize, is_cte: bool, dialect: Dialect) {
+        let name = table.display_name();
+        let Some(first) = self.find_folded(name, dialect) else {
+            self.tables.push(table);
+            self.definitions.push(number);
+            return;
+        };
+
+        self.errors.push(if is_cte {
+            SemanticError::DuplicateCte(name.to_string())
+        } else {
+            SemanticError::DuplicateAlias(name.to_string())
+        });
+        self.redefinitions.push((self.definitions[first], number));
+    }
 }
 
 /// Manages hierarchical scopes and symbol resolution
@@ -204,6 +281,9 @@ impl ScopeManager {
 
     /// Resolve a table by name, searching through the current scope and all parent scopes
     ///
+    /// The nearest scope wins: a derived table or alias named `users` in a
+    /// subquery shadows a `users` table in the enclosing query.
+    ///
     /// # Arguments
     ///
     /// * `name` - Table name or alias to resolve
@@ -250,6 +330,11 @@ impl ScopeManager {
 
     /// Resolve a column by name, searching through all visible tables
     ///
+    /// Like tables, the nearest scope wins: a column of a table in the
+    /// referencing scope shadows a column of the same name in an enclosing
+    /// query, and a correlated reference resolves to the closest enclosing
+    /// scope that has the column.
+    ///
     /// # Arguments
     ///
     /// * `name` - Column name to resolve
@@ -259,39 +344,137 @@ impl ScopeManager {
     ///
     /// `Ok((&TableSymbol, &ColumnSymbol))` if found uniquely
     /// `Err(SemanticError::ColumnNotFound)` if not found
-    /// `Err(SemanticError::AmbiguousColumn)` if found in multiple tables
+    /// `Err(SemanticError::AmbiguousColumn)` if found in several tables of
+    /// the nearest scope having it, with that scope's depth (0 = the
+    /// referencing scope)
     pub fn resolve_column(
         &self,
         name: &str,
         scope_id: usize,
     ) -> SemanticResult<(&TableSymbol, &ColumnSymbol)> {
         let mut current_id = Some(scope_id);
-        let mut found = Vec::new();
+        let mut depth = 0;
 
-        // Collect all matching columns from visible scopes
         while let Some(id) = current_id {
-            if let Some(scope) = self.get_scope(id) {
-                for table in &scope.tables {
-                    if let Some(column) = table.find_column(name) {
-                        found.push((table, column));
-                    }
-                }
-                current_id = scope.parent_id;
-            } else {
+            let Some(scope) = self.get_scope(id) else {
                 return Err(SemanticError::InvalidScope(format!("scope {}", id)));
+            };
+            let found: Vec<_> = scope
+                .tables
+                .iter()
+                .filter_map(|table| Some((table, table.find_column(name)?)))
+                .collect();
+
+            match found.as_slice() {
+                [] => {}
+                [(table, column)] => return Ok((table, column)),
+                _ => {
+                    let candidates = found
+                        .iter()
+                        .map(|(t, _)| AmbiguousCandidate::new(t.display_name(), depth))
+                        .collect();
+                    return Err(SemanticError::AmbiguousColumn(name.to_string(), candidates));
+                }
             }
+            current_id = scope.parent_id;
+            depth += 1;
         }
 
-        match found.len() {
-            0 => Err(SemanticError::ColumnNotFound(name.to_string())),
-            1 => Ok((found[0].0, found[0].1)),
-            _ => {
-                let tables: Vec<String> = found
-                    .iter()
-                    .map(|(t, _)| t.display_name().to_string())
-                    .collect();
-                Err(SemanticError::AmbiguousColumn(name.to_string(), tables))
+        Err(SemanticError::ColumnNotFound(name.to_string()))
+    }
+
+    /// Collect the errors recorded in all scopes, in scope creation order
+    pub fn errors(&self) -> impl Iterator<Item = &SemanticError> {
+        self.scopes.iter().flat_map(|scope| scope.errors.iter())
+    }
+
+    /// [`Self::errors`] of a manager built by [`Self::for_query`], each with
+    /// the numbers of the first and the repeated definition behind it
+    pub fn redefinitions(&self) -> impl Iterator<Item = (&SemanticError, (usize, usize))> {
+        self.scopes
+            .iter()
+            .flat_map(|scope| scope.errors.iter().zip(scope.redefinitions.iter().copied()))
+    }
+
+    /// Build the scopes of `query` from the names it defines
+    ///
+    /// Each `WITH` clause gets a CTE scope holding its CTEs, each SELECT a
+    /// query scope holding the tables of its FROM list; tables have no
+    /// columns. Names defined twice in a scope are recorded in its errors,
+    /// compared the way `dialect` folds identifiers.
+    ///
+    /// Definitions are numbered in source order, as lowering maps them: each
+    /// CTE before the definitions of its query, each table before its joins.
+    /// Subqueries used as values are left out.
+    pub fn for_query(query: &Query, dialect: Dialect) -> Self {
+        let mut manager = Self::new();
+        manager.define_query(query, None, dialect, &mut 0);
+        manager
+    }
+
+    fn define_query(
+        &mut self,
+        query: &Query,
+        parent_id: Option<usize>,
+        dialect: Dialect,
+        next: &mut usize,
+    ) {
+        let mut parent_id = parent_id;
+        if !query.ctes.is_empty() {
+            let cte_scope_id = self.create_scope(ScopeType::CTE, parent_id);
+            for cte in &query.ctes {
+                let table = TableSymbol::new(&cte.name);
+                self.define_in(cte_scope_id, table, true, dialect, next);
+                self.define_query(&cte.query, Some(cte_scope_id), dialect, next);
             }
+            parent_id = Some(cte_scope_id);
+        }
+
+        match &query.body {
+            SetOp::Select(select) => {
+                let scope_id = self.create_scope(ScopeType::Query, parent_id);
+                for table_ref in &select.from {
+                    // A bare JOIN lowers to a nameless table holding it
+                    if !table_ref.name.is_empty() {
+                        let table = Self::table_symbol(table_ref);
+                        self.define_in(scope_id, table, false, dialect, next);
+                    }
+                    for join in &table_ref.joins {
+                        let table = Self::table_symbol(&join.table);
+                        self.define_in(scope_id, table, false, dialect, next);
+                    }
+                }
+            }
+            SetOp::Union { left, right, .. }
+            | SetOp::Intersect { left, right, .. }
+            | SetOp::Except { left, right, .. } => {
+                self.define_query(left, parent_id, dialect, next);
+                self.define_query(right, parent_id, dialect, next);
+            }
+            _ => {}
+        }
+    }
+
+    fn define_in(
+        &mut self,
+        scope_id: usize,
+        table: TableSymbol,
+        is_cte: bool,
+        dialect: Dialect,
+        next: &mut usize,
+    ) {
+        let number = *next;
+        *next += 1;
+        if let Some(scope) = self.get_scope_mut(scope_id) {
+            scope.define(table, number, is_cte, dialect);
+        }
+    }
+
+    fn table_symbol(table_ref: &TableRef) -> TableSymbol {
+        let table = TableSymbol::new(&table_ref.name);
+        match &table_ref.alias {
+            Some(alias) => table.with_alias(alias),
+            None => table,
         }
     }
 
@@ -346,6 +529,180 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_scope_duplicate_alias_recorded() {
+        let mut manager = ScopeManager::new();
+        let scope_id = manager.create_scope(ScopeType::Query, None);
+        let scope = manager.get_scope_mut(scope_id).unwrap();
+
+        // FROM users x JOIN orders x
+        scope
+            .add_table(TableSymbol::new("users").with_alias("x"))
+            .unwrap();
+        let result = scope.add_table(TableSymbol::new("orders").with_alias("x"));
+
+        assert_eq!(
+            result.unwrap_err(),
+            SemanticError::DuplicateAlias("x".to_string())
+        );
+        let errors: Vec<_> = manager.errors().collect();
+        assert_eq!(
+            errors,
+            vec![&SemanticError::DuplicateAlias("x".to_string())]
+        );
+
+        // The first table keeps the alias
+        let resolved = manager.resolve_table("x", scope_id).unwrap();
+        assert_eq!(resolved.table_name, "users");
+    }
+
+    #[test]
+    fn test_scope_add_cte_folds_per_dialect() {
+        // Names as lowered: `"Recent"` is stored as `Recent`
+        let check = |names: &[&str], dialect: Dialect| -> Vec<SemanticError> {
+            let mut scope = Scope::new(0, ScopeType::CTE);
+            for name in names {
+                let _ = scope.add_cte(TableSymbol::new(*name), dialect);
+            }
+            scope.errors
+        };
+
+        assert!(check(&["recent", "Recent"], Dialect::PostgreSQL).is_empty());
+        assert_eq!(
+            check(&["recent", "totals", "recent"], Dialect::PostgreSQL),
+            vec![SemanticError::DuplicateCte("recent".to_string())]
+        );
+        assert_eq!(
+            check(&["recent", "Recent"], Dialect::MySQL),
+            vec![SemanticError::DuplicateCte("Recent".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_scope_manager_for_query_numbers_definitions() {
+        use unified_sql_lsp_ir::{CommonTableExpr, Join, JoinCondition, JoinType, SelectStatement};
+
+        let table = |name: &str, alias: Option<&str>| TableRef {
+            name: name.to_string(),
+            alias: alias.map(str::to_string),
+            joins: Vec::new(),
+        };
+        let select = |from: Vec<TableRef>| {
+            let mut query = Query::new(Dialect::PostgreSQL);
+            query.body = SetOp::Select(Box::new(SelectStatement {
+                from,
+                ..Default::default()
+            }));
+            query
+        };
+        let cte = |name: &str, query: Query| CommonTableExpr {
+            name: name.to_string(),
+            columns: Vec::new(),
+            query: Box::new(query),
+            materialized: None,
+        };
+
+        // WITH recent AS (SELECT * FROM orders), recent AS (SELECT * FROM users)
+        // SELECT * FROM recent x JOIN users x ON true
+        let mut from = table("recent", Some("x"));
+        from.joins.push(Join {
+            join_type: JoinType::Inner,
+            table: table("users", Some("x")),
+            condition: JoinCondition::Using(Vec::new()),
+        });
+        let mut query = select(vec![from]);
+        query.ctes = vec![
+            cte("recent", select(vec![table("orders", None)])),
+            cte("recent", select(vec![table("users", None)])),
+        ];
+
+        let manager = ScopeManager::for_query(&query, Dialect::PostgreSQL);
+        let redefinitions: Vec<_> = manager.redefinitions().collect();
+        assert_eq!(
+            redefinitions,
+            vec![
+                (&SemanticError::DuplicateCte("recent".to_string()), (0, 2)),
+                (&SemanticError::DuplicateAlias("x".to_string()), (4, 5)),
+            ]
+        );
+        assert_eq!(manager.errors().count(), 2);
+    }
+
+    #[test]
+    fn test_scope_manager_resolve_table_shadowed() {
+        let mut manager = ScopeManager::new();
+        let parent_id = manager.create_scope(ScopeType::Query, None);
+        let child_id = manager.create_scope(ScopeType::Subquery, Some(parent_id));
+
+        // Outer query: FROM users; inner query: FROM orders AS users
+        manager
+            .get_scope_mut(parent_id)
+            .unwrap()
+            .add_table(create_mock_table())
+            .unwrap();
+        manager
+            .get_scope_mut(child_id)
+            .unwrap()
+            .add_table(create_mock_orders_table().with_alias("users"))
+            .unwrap();
+
+        let inner = manager.resolve_table("users", child_id).unwrap();
+        assert_eq!(inner.table_name, "orders");
+
+        let outer = manager.resolve_table("users", parent_id).unwrap();
+        assert_eq!(outer.table_name, "users");
+        assert!(manager.errors().next().is_none());
+    }
+
+    #[test]
+    fn test_scope_manager_resolve_column_ambiguous_depths() {
+        let mut manager = ScopeManager::new();
+        let parent_id = manager.create_scope(ScopeType::Query, None);
+        let child_id = manager.create_scope(ScopeType::Subquery, Some(parent_id));
+
+        let _ = manager
+            .get_scope_mut(parent_id)
+            .unwrap()
+            .add_table(create_mock_table());
+        let _ = manager.get_scope_mut(parent_id).unwrap().add_table(
+            TableSymbol::new("teams").with_columns(vec![ColumnSymbol::new(
+                "name",
+                DataType::Text,
+                "teams",
+            )]),
+        );
+        let _ = manager
+            .get_scope_mut(child_id)
+            .unwrap()
+            .add_table(create_mock_orders_table());
+
+        // The subquery's own orders.id shadows the outer u.id
+        let (table, column) = manager.resolve_column("id", child_id).unwrap();
+        assert_eq!(table.table_name, "orders");
+        assert_eq!(column.name, "id");
+
+        // A correlated reference resolves in the enclosing query
+        let (table, _) = manager.resolve_column("email", child_id).unwrap();
+        assert_eq!(table.table_name, "users");
+
+        // Ambiguity is only among the tables of the nearest scope with a match
+        let err = manager.resolve_column("name", child_id).unwrap_err();
+        assert_eq!(
+            err,
+            SemanticError::AmbiguousColumn(
+                "name".to_string(),
+                vec![
+                    AmbiguousCandidate::new("u", 1),
+                    AmbiguousCandidate::new("teams", 1),
+                ]
+            )
+        );
+        assert_eq!(
+            err.to_string(),
+            "Ambiguous column reference: name (found in u (scope depth 1), teams (scope depth 1))"
+        );
+    }
+
     #[test]
     fn test_scope_manager_new() {
         let manager = ScopeManager::new();
@@ -8,7 +8,9 @@
 //! This module defines symbol types representing tables and columns in SQL queries.
 
 use serde::{Deserialize, Serialize};
-use unified_sql_lsp_catalog::DataType;
+use std::sync::Arc;
+use unified_sql_lsp_catalog::{DataType, TableReference};
+use unified_sql_lsp_ir::IdentifierComparer;
 
 /// Represents a table symbol in a SQL query
 ///
@@ -22,7 +24,10 @@ pub struct TableSymbol {
     pub alias: Option<String>,
 
     /// Columns available from this table
-    pub columns: Vec<ColumnSymbol>,
+    ///
+    /// Shared, so cloning a symbol for each completion stage doesn't copy
+    /// wide tables' column lists.
+    pub columns: Arc<[ColumnSymbol]>,
 }
 
 impl TableSymbol {
@@ -44,7 +49,7 @@ impl TableSymbol {
         Self {
             table_name: table_name.into(),
             alias: None,
-            columns: Vec::new(),
+            columns: Arc::new([]),
         }
     }
 
@@ -73,6 +78,15 @@ impl TableSymbol {
     ///
     /// * `columns` - Vector of column symbols
     pub fn with_columns(mut self, columns: Vec<ColumnSymbol>) -> Self {
+        self.columns = columns.into();
+        self
+    }
+
+    /// Set the columns for this table to an already shared column list
+    ///
+    /// The list is not copied, so symbols built from the same catalog
+    /// result point at the same columns.
+    pub fn with_shared_columns(mut self, columns: Arc<[ColumnSymbol]>) -> Self {
         self.columns = columns;
         self
     }
@@ -122,11 +136,13 @@ impl TableSymbol {
     ///
     /// # Arguments
     ///
-    /// * `name` - The column name to find
+    /// * `name` - The column name as written in SQL
     ///
     /// # Returns
     ///
-    /// `Some(&ColumnSymbol)` if found, `None` otherwise
+    /// `Some(&ColumnSymbol)` if found, `None` otherwise. A quoted name only
+    /// finds its exact spelling; a bare one is compared regardless of case,
+    /// preferring the exact spelling (see [`IdentifierComparer::find`]).
     ///
     /// # Examples
     ///
@@ -141,10 +157,12 @@ impl TableSymbol {
     ///     ]);
     ///
     /// assert!(table.find_column("id").is_some());
+    /// assert!(table.find_column("Name").is_some());
+    /// assert!(table.find_column("\"Name\"").is_none());
     /// assert!(table.find_column("email").is_none());
     /// ```
     pub fn find_column(&self, name: &str) -> Option<&ColumnSymbol> {
-        self.columns.iter().find(|c| c.name == name)
+        IdentifierComparer::find(name, self.columns.iter(), |c| c.name.as_str())
     }
 }
 
@@ -167,6 +185,15 @@ pub struct ColumnSymbol {
     /// Whether this column is a foreign key
     #[serde(default)]
     pub is_foreign_key: bool,
+
+    /// Column referenced by this foreign key, if known
+    #[serde(default)]
+    pub references: Option<TableReference>,
+
+    /// Whether the catalog declares this column `NOT NULL`; `false` when
+    /// it is nullable or its nullability is unknown
+    #[serde(default)]
+    pub not_null: bool,
 }
 
 impl ColumnSymbol {
@@ -199,6 +226,8 @@ impl ColumnSymbol {
             table_name: table_name.into(),
             is_primary_key: false,
             is_foreign_key: false,
+            references: None,
+            not_null: false,
         }
     }
 
@@ -236,6 +265,28 @@ impl ColumnSymbol {
         self
     }
 
+    /// Mark this column as a foreign key referencing `table.column`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use unified_sql_lsp_semantic::ColumnSymbol;
+    /// use unified_sql_lsp_catalog::DataType;
+    ///
+    /// let column = ColumnSymbol::new("user_id", DataType::Integer, "orders")
+    ///     .with_reference("users", "id");
+    /// assert!(column.is_foreign_key);
+    /// assert_eq!(column.references.unwrap().table, "users");
+    /// ```
+    pub fn with_reference(mut self, table: impl Into<String>, column: impl Into<String>) -> Self {
+        self.is_foreign_key = true;
+        self.references = Some(TableReference {
+            table: table.into(),
+            column: column.into(),
+        });
+        self
+    }
+
     /// Conditionally mark this column as a primary key
     pub fn with_primary_key_if(mut self, is_pk: bool) -> Self {
         self.is_primary_key = is_pk;
@@ -247,4 +298,26 @@ impl ColumnSymbol {
         self.is_foreign_key = is_fk;
         self
     }
+
+    /// Set the column referenced by this foreign key, if known
+    pub fn with_references(mut self, references: Option<TableReference>) -> Self {
+        self.references = references;
+        self
+    }
+
+    /// Mark this column as declared `NOT NULL` in the catalog
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use unified_sql_lsp_semantic::ColumnSymbol;
+    /// use unified_sql_lsp_catalog::DataType;
+    ///
+    /// let column = ColumnSymbol::new("id", DataType::Integer, "users").with_not_null(true);
+    /// assert!(column.not_null);
+    /// ```
+    pub fn with_not_null(mut self, not_null: bool) -> Self {
+        self.not_null = not_null;
+        self
+    }
 }
@@ -40,13 +40,7 @@ impl SyntaxDiagnosticAnalyzer {
         let mut found_real_errors = false;
 
         if root.has_error() {
-            self.collect_error_nodes_recursive(
-                &root,
-                source,
-                &mut diagnostics,
-                &mut found_real_errors,
-                0,
-            );
+            self.collect_error_nodes(&root, source, &mut diagnostics, &mut found_real_errors);
         }
 
         if diagnostics.is_empty() && root.has_error() && found_real_errors {
@@ -64,40 +58,34 @@ impl SyntaxDiagnosticAnalyzer {
         diagnostics
     }
 
-    fn collect_error_nodes_recursive(
+    /// Collect the ERROR nodes under `root`, outermost first
+    ///
+    /// Children of an ignored ERROR node are still searched. The walk moves
+    /// a tree cursor instead of recursing, so deeply nested queries can't
+    /// overflow the stack; subtrees without errors are skipped.
+    fn collect_error_nodes(
         &self,
-        node: &tree_sitter::Node,
+        root: &tree_sitter::Node,
         source: &str,
         diagnostics: &mut Vec<SyntaxDiagnostic>,
         found_real_errors: &mut bool,
-        depth: usize,
     ) {
-        if depth > 100 {
-            return;
-        }
-
-        if node.kind() == "ERROR" {
-            if self.should_ignore_error_node(node, source) {
-                // Continue into children.
-            } else {
+        let mut cursor = root.walk();
+        loop {
+            let node = cursor.node();
+            let mut descend = node.has_error();
+            if node.kind() == "ERROR" && !self.should_ignore_error_node(&node, source) {
                 *found_real_errors = true;
-                diagnostics.push(self.create_error_diagnostic(node, source));
-                return;
+                diagnostics.push(self.create_error_diagnostic(&node, source));
+                descend = false;
             }
-        }
 
-        let mut cursor = node.walk();
-        if cursor.goto_first_child() {
-            loop {
-                self.collect_error_nodes_recursive(
-                    &cursor.node(),
-                    source,
-                    diagnostics,
-                    found_real_errors,
-                    depth + 1,
-                );
-                if !cursor.goto_next_sibling() {
-                    break;
+            if descend && cursor.goto_first_child() {
+                continue;
+            }
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() {
+                    return;
                 }
             }
         }
@@ -109,7 +97,11 @@ impl SyntaxDiagnosticAnalyzer {
         trimmed.is_empty() || trimmed.len() == 1
     }
 
-    fn create_error_diagnostic(&self, node: &tree_sitter::Node, source: &str) -> SyntaxDiagnostic {
+    pub fn create_error_diagnostic(
+        &self,
+        node: &tree_sitter::Node,
+        source: &str,
+    ) -> SyntaxDiagnostic {
         let start_pos = node.start_position();
         let end_pos = node.end_position();
         let error_text = &source[node.byte_range()];
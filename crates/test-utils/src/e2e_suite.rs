@@ -0,0 +1,269 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! Reader for the e2e YAML suites
+//!
+//! The suites under `tests/e2e-rs/tests/<engine>-<version>/` run against
+//! live databases. Unit tests reuse their SQL and cursor positions without
+//! depending on the e2e workspace: this reads each suite's name, dialect
+//! and completion cases, and ignores expectations and database setup.
+//!
+//! A case's cursor is its `cursor: { line, character }` if given, else the
+//! first `|` marker in its SQL. Every `|` is removed from the SQL, as the
+//! e2e runner does.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use lsp_types::Position;
+use serde_yaml::Value;
+use thiserror::Error;
+use unified_sql_lsp_ir::Dialect;
+
+/// A suite file with its completion cases
+#[derive(Debug, Clone)]
+pub struct E2eSuite {
+    /// Path of the suite file
+    pub path: PathBuf,
+    /// The `name:` of the suite
+    pub name: String,
+    /// Engine version from the suite directory, e.g. `5.7` for `mysql-5.7`
+    pub version: Option<String>,
+    pub cases: Vec<E2eCase>,
+}
+
+/// A completion case of a suite
+#[derive(Debug, Clone)]
+pub struct E2eCase {
+    pub name: String,
+    pub dialect: Dialect,
+    /// SQL without cursor markers
+    pub sql: String,
+    pub cursor: Position,
+}
+
+/// Errors reading e2e suites
+#[derive(Debug, Error)]
+pub enum E2eSuiteError {
+    #[error("{path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("{path}: {source}")]
+    Yaml {
+        path: PathBuf,
+        source: serde_yaml::Error,
+    },
+
+    #[error("{path}: {message}")]
+    Invalid { path: PathBuf, message: String },
+}
+
+/// Directory holding the e2e suites of this repository
+pub fn e2e_suites_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../tests/e2e-rs/tests")
+}
+
+/// Load every suite under `root`, sorted by path
+///
+/// Files whose name starts with `_` are shared defaults, not suites.
+pub fn load_e2e_suites(root: &Path) -> Result<Vec<E2eSuite>, E2eSuiteError> {
+    let mut paths = Vec::new();
+    collect_yaml_files(root, &mut paths)?;
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter(|path| {
+            !path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('_'))
+        })
+        .map(|path| {
+            let version = path
+                .strip_prefix(root)
+                .ok()
+                .and_then(|relative| relative.components().next())
+                .and_then(|engine| {
+                    let engine = engine.as_os_str().to_string_lossy();
+                    engine
+                        .split_once('-')
+                        .map(|(_, version)| version.to_string())
+                });
+            load_e2e_suite(&path, version)
+        })
+        .collect()
+}
+
+/// Load the suite at `path`
+pub fn load_e2e_suite(path: &Path, version: Option<String>) -> Result<E2eSuite, E2eSuiteError> {
+    let suite = read_yaml(path)?;
+    let invalid = |message: String| E2eSuiteError::Invalid {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let default_dialect = suite_dialect(path, &suite)?;
+    let mut cases = Vec::new();
+    for test in suite["tests"].as_sequence().into_iter().flatten() {
+        let name = test["name"].as_str().unwrap_or_default().to_string();
+        if !is_completion_case(test) {
+            continue;
+        }
+        let Some(marked) = test["sql"].as_str() else {
+            return Err(invalid(format!("case '{}' has no sql", name)));
+        };
+
+        let dialect = match test["dialect"].as_str() {
+            Some(dialect) => Some(parse_dialect(dialect).ok_or_else(|| {
+                invalid(format!("case '{}' has unknown dialect '{}'", name, dialect))
+            })?),
+            None => default_dialect,
+        }
+        .ok_or_else(|| invalid(format!("case '{}' has no dialect", name)))?;
+
+        let cursor = match &test["cursor"] {
+            Value::Mapping(_) => Some(Position::new(
+                test["cursor"]["line"].as_u64().unwrap_or_default() as u32,
+                test["cursor"]["character"].as_u64().unwrap_or_default() as u32,
+            )),
+            _ => cursor_marker_position(marked),
+        }
+        .ok_or_else(|| invalid(format!("case '{}' has no cursor", name)))?;
+
+        cases.push(E2eCase {
+            name,
+            dialect,
+            sql: marked.replace('|', ""),
+            cursor,
+        });
+    }
+
+    Ok(E2eSuite {
+        path: path.to_path_buf(),
+        name: suite["name"].as_str().unwrap_or_default().to_string(),
+        version,
+        cases,
+    })
+}
+
+/// Position of the first `|` cursor marker in `sql`
+pub fn cursor_marker_position(sql: &str) -> Option<Position> {
+    let cursor = sql.find('|')?;
+    let before = &sql[..cursor];
+    let line = before.matches('\n').count() as u32;
+    let character = before[before.rfind('\n').map_or(0, |pos| pos + 1)..]
+        .chars()
+        .count() as u32;
+    Some(Position::new(line, character))
+}
+
+/// Whether `test` requests completion at its initial cursor
+fn is_completion_case(test: &Value) -> bool {
+    !test["expect_completion"].is_null()
+        || test["steps"]
+            .as_sequence()
+            .and_then(|steps| steps.first())
+            .is_some_and(|step| !step["completion"].is_null())
+}
+
+/// The `database.dialect` of a suite, or of the file it `extends`
+fn suite_dialect(path: &Path, suite: &Value) -> Result<Option<Dialect>, E2eSuiteError> {
+    if let Some(dialect) = suite["database"]["dialect"].as_str() {
+        return parse_dialect(dialect)
+            .map(Some)
+            .ok_or_else(|| E2eSuiteError::Invalid {
+                path: path.to_path_buf(),
+                message: format!("unknown dialect '{}'", dialect),
+            });
+    }
+
+    match suite["extends"].as_str() {
+        Some(extends) => {
+            let base = path.parent().unwrap_or(Path::new(".")).join(extends);
+            suite_dialect(&base, &read_yaml(&base)?)
+        }
+        None => Ok(None),
+    }
+}
+
+fn parse_dialect(dialect: &str) -> Option<Dialect> {
+    match dialect.to_ascii_lowercase().as_str() {
+        "mysql" => Some(Dialect::MySQL),
+        "postgresql" | "postgres" => Some(Dialect::PostgreSQL),
+        _ => None,
+    }
+}
+
+fn read_yaml(path: &Path) -> Result<Value, E2eSuiteError> {
+    let content = fs::read_to_string(path).map_err(|source| E2eSuiteError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    serde_yaml::from_str(&content).map_err(|source| E2eSuiteError::Yaml {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn collect_yaml_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), E2eSuiteError> {
+    let entries = fs::read_dir(dir).map_err(|source| E2eSuiteError::Io {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_yaml_files(&path, paths)?;
+        } else if path.extension().is_some_and(|ext| ext == "yaml") {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_marker_position() {
+        assert_eq!(
+            cursor_marker_position("SELECT * FROM |"),
+            Some(Position::new(0, 14))
+        );
+        assert_eq!(
+            cursor_marker_position("SELECT *\nFROM users u\nWHERE u.|"),
+            Some(Position::new(2, 8))
+        );
+        assert_eq!(cursor_marker_position("SELECT 1"), None);
+    }
+
+    #[test]
+    fn test_load_repository_suites() {
+        let suites = load_e2e_suites(&e2e_suites_dir()).unwrap();
+        let from = suites
+            .iter()
+            .find(|s| s.path.ends_with("mysql-5.7/completion/from_clause.yaml"))
+            .unwrap();
+
+        // The dialect comes from the extended defaults
+        assert_eq!(from.version.as_deref(), Some("5.7"));
+        let case = &from.cases[0];
+        assert_eq!(case.dialect, Dialect::MySQL);
+        assert_eq!(case.sql, "SELECT * FROM ");
+        assert_eq!(case.cursor, Position::new(0, 14));
+
+        // Diagnostics and hover suites have no completion cases
+        assert!(
+            suites
+                .iter()
+                .filter(|s| s.path.to_string_lossy().contains("/diagnostics/"))
+                .all(|s| s.cases.is_empty())
+        );
+    }
+}
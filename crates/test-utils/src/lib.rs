@@ -10,8 +10,10 @@
 //! - CST node builders for lowering tests
 //! - SQL-specific test helpers and assertions
 //! - Test fixtures and sample data
+//! - A reader for the e2e YAML suites, to reuse their cases in unit tests
 
 pub mod assertions;
+pub mod e2e_suite;
 pub mod fixtures;
 pub mod mock_catalog;
 pub mod mock_cst;
@@ -19,6 +21,7 @@ pub mod test_case_parser;
 pub mod test_case_validator;
 
 // Re-exports for convenience
+pub use e2e_suite::{E2eCase, E2eSuite, e2e_suites_dir, load_e2e_suites};
 pub use mock_catalog::{MockCatalog, MockCatalogBuilder};
 pub use mock_cst::{MockCstBuilder, MockCstNode};
 pub use test_case_parser::{Dialect, ExpectedItem, TestCase, parse_test_content, parse_test_file};
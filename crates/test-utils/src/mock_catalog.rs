@@ -7,17 +7,25 @@
 //!
 //! Provides an in-memory catalog with builder pattern for easy test setup
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use unified_sql_lsp_catalog::{
     Catalog, CatalogError, CatalogResult, ColumnMetadata, DataType, FunctionMetadata, FunctionType,
-    TableMetadata, TableType,
+    SampleRows, SequenceMetadata, TableMetadata, TableType,
 };
 
 /// In-memory mock catalog for testing
 #[derive(Debug, Clone)]
 pub struct MockCatalog {
-    tables: HashMap<String, TableMetadata>,
+    /// Keyed by `schema.name`, ordered so tables are listed the same every time
+    tables: BTreeMap<String, TableMetadata>,
     functions: Vec<FunctionMetadata>,
+    roles: Vec<String>,
+    sequences: Vec<SequenceMetadata>,
+    sample_rows: HashMap<String, SampleRows>,
+    /// `sample_rows` calls, shared between clones
+    sample_row_calls: Arc<AtomicUsize>,
 }
 
 impl Default for MockCatalog {
@@ -30,8 +38,12 @@ impl MockCatalog {
     /// Create a new empty mock catalog
     pub fn new() -> Self {
         Self {
-            tables: HashMap::new(),
+            tables: BTreeMap::new(),
             functions: Vec::new(),
+            roles: Vec::new(),
+            sequences: Vec::new(),
+            sample_rows: HashMap::new(),
+            sample_row_calls: Arc::new(AtomicUsize::new(0)),
         }
     }
 
@@ -48,6 +60,51 @@ impl MockCatalog {
         self
     }
 
+    /// Add a role to the catalog
+    pub fn add_role(mut self, role: impl Into<String>) -> Self {
+        self.roles.push(role.into());
+        self
+    }
+
+    /// Add a sequence to the catalog
+    pub fn add_sequence(mut self, sequence: SequenceMetadata) -> Self {
+        self.sequences.push(sequence);
+        self
+    }
+
+    /// Mark `table.column` as a foreign key referencing `ref_table.ref_column`
+    ///
+    /// Does nothing if the table or column hasn't been added.
+    pub fn add_foreign_key(
+        mut self,
+        table: &str,
+        column: &str,
+        ref_table: impl Into<String>,
+        ref_column: impl Into<String>,
+    ) -> Self {
+        if let Some(col) = self
+            .tables
+            .values_mut()
+            .filter(|t| t.name == table)
+            .flat_map(|t| t.columns.iter_mut())
+            .find(|c| c.name == column)
+        {
+            *col = col.clone().with_foreign_key(ref_table, ref_column);
+        }
+        self
+    }
+
+    /// Set the rows returned by `sample_rows` for a table
+    pub fn add_sample_rows(mut self, table: impl Into<String>, rows: SampleRows) -> Self {
+        self.sample_rows.insert(table.into(), rows);
+        self
+    }
+
+    /// Number of `sample_rows` calls made so far
+    pub fn sample_row_calls(&self) -> usize {
+        self.sample_row_calls.load(Ordering::SeqCst)
+    }
+
     /// Get a table by name (any schema)
     pub fn get_table(&self, name: &str) -> Option<&TableMetadata> {
         self.tables.values().find(|t| t.name == name)
@@ -74,6 +131,23 @@ impl Catalog for MockCatalog {
     async fn list_functions(&self) -> CatalogResult<Vec<FunctionMetadata>> {
         Ok(self.functions.clone())
     }
+
+    async fn list_roles(&self) -> CatalogResult<Vec<String>> {
+        Ok(self.roles.clone())
+    }
+
+    async fn list_sequences(&self) -> CatalogResult<Vec<SequenceMetadata>> {
+        Ok(self.sequences.clone())
+    }
+
+    async fn sample_rows(&self, table: &str, limit: usize) -> CatalogResult<SampleRows> {
+        self.sample_row_calls.fetch_add(1, Ordering::SeqCst);
+        let mut sample = self.sample_rows.get(table).cloned().ok_or_else(|| {
+            CatalogError::NotSupported(format!("no sample rows for table '{}'", table))
+        })?;
+        sample.rows.truncate(limit);
+        Ok(sample)
+    }
 }
 
 /// Builder for creating mock catalogs with a fluent API
@@ -205,12 +279,44 @@ impl MockCatalogBuilder {
         self
     }
 
+    /// Mark `table.column` of an added table as a foreign key
+    pub fn with_foreign_key(
+        mut self,
+        table: &str,
+        column: &str,
+        ref_table: impl Into<String>,
+        ref_column: impl Into<String>,
+    ) -> Self {
+        self.catalog = self
+            .catalog
+            .add_foreign_key(table, column, ref_table, ref_column);
+        self
+    }
+
+    /// Set the rows returned by `sample_rows` for a table
+    pub fn with_sample_rows(mut self, table: impl Into<String>, rows: SampleRows) -> Self {
+        self.catalog = self.catalog.add_sample_rows(table, rows);
+        self
+    }
+
     /// Add a custom function
     pub fn with_function(mut self, function: FunctionMetadata) -> Self {
         self.catalog = self.catalog.add_function(function);
         self
     }
 
+    /// Add a role
+    pub fn with_role(mut self, role: impl Into<String>) -> Self {
+        self.catalog = self.catalog.add_role(role);
+        self
+    }
+
+    /// Add a sequence
+    pub fn with_sequence(mut self, sequence: SequenceMetadata) -> Self {
+        self.catalog = self.catalog.add_sequence(sequence);
+        self
+    }
+
     /// Build the mock catalog
     pub fn build(self) -> MockCatalog {
         self.catalog
@@ -248,6 +354,26 @@ mod tests {
         assert!(column_names.contains(&"created_at"));
     }
 
+    #[tokio::test]
+    async fn test_mock_catalog_foreign_keys() {
+        let catalog = MockCatalogBuilder::new()
+            .with_standard_schema()
+            .with_foreign_key("orders", "status", "statuses", "code")
+            .build();
+
+        let columns = catalog.get_columns("orders").await.unwrap();
+        let target = |name: &str| {
+            columns
+                .iter()
+                .find(|c| c.name == name)
+                .and_then(|c| c.references.clone())
+                .map(|r| format!("{}.{}", r.table, r.column))
+        };
+        assert_eq!(target("user_id").as_deref(), Some("users.id"));
+        assert_eq!(target("status").as_deref(), Some("statuses.code"));
+        assert_eq!(target("total"), None);
+    }
+
     #[tokio::test]
     async fn test_mock_catalog_list_functions() {
         let catalog = MockCatalogBuilder::new().with_standard_schema().build();
@@ -258,4 +384,31 @@ mod tests {
         let has_count = functions.iter().any(|f| f.name == "count");
         assert!(has_count);
     }
+
+    #[tokio::test]
+    async fn test_mock_catalog_list_roles() {
+        let catalog = MockCatalogBuilder::new().build();
+        assert!(catalog.list_roles().await.unwrap().is_empty());
+
+        let catalog = MockCatalogBuilder::new()
+            .with_role("analyst")
+            .with_role("app_user")
+            .build();
+        assert_eq!(
+            catalog.list_roles().await.unwrap(),
+            vec!["analyst", "app_user"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_catalog_list_sequences() {
+        let catalog = MockCatalogBuilder::new()
+            .with_sequence(SequenceMetadata::new("users_id_seq", "public").with_increment(5))
+            .build();
+
+        let sequences = catalog.list_sequences().await.unwrap();
+        assert_eq!(sequences.len(), 1);
+        assert_eq!(sequences[0].name, "users_id_seq");
+        assert_eq!(sequences[0].increment, 5);
+    }
 }
@@ -60,8 +60,8 @@ pub fn generate_engine_tests(input: TokenStream) -> TokenStream {
     );
     let engine_ident = proc_macro2::Ident::new(engine_enum_name, proc_macro2::Span::call_site());
     let serial_key = proc_macro2::Ident::new(engine_name, proc_macro2::Span::call_site());
-    let requested_test_types =
-        parse_test_types_from_input(&input_str).unwrap_or_else(|| get_test_types_for_engine(engine_name));
+    let requested_test_types = parse_test_types_from_input(&input_str)
+        .unwrap_or_else(|| get_test_types_for_engine(engine_name));
     let test_cases = discover_test_cases(engine_dir_name, engine_name, &requested_test_types);
 
     let generated_case_tests: Vec<proc_macro2::TokenStream> = test_cases
@@ -72,6 +72,15 @@ pub fn generate_engine_tests(input: TokenStream) -> TokenStream {
             let suite_path_literal = proc_macro2::Literal::string(&test_case.suite_path);
             let case_index_literal = proc_macro2::Literal::usize_unsuffixed(test_case.case_index);
             let label_literal = proc_macro2::Literal::string(&test_case.label);
+            let retries = match test_case.retries {
+                Retries::Count(count) => {
+                    let count = proc_macro2::Literal::u32_unsuffixed(count);
+                    quote! { #count }
+                }
+                Retries::FlakyDefault => {
+                    quote! { unified_sql_lsp_e2e_core::retry::DEFAULT_FLAKY_RETRIES }
+                }
+            };
 
             quote! {
                 #[tokio::test]
@@ -83,9 +92,13 @@ pub fn generate_engine_tests(input: TokenStream) -> TokenStream {
                     use unified_sql_lsp_e2e_core::{EngineManagerEngine, ensure_engine_ready};
                     let _guard = ensure_engine_ready(&EngineManagerEngine::#engine_ident).await?;
 
-                    unified_sql_lsp_e2e_core::run_case(#suite_path_literal, #case_index_literal)
-                        .await
-                        .map_err(|e| anyhow::anyhow!("{} failed: {}", #label_literal, e))
+                    unified_sql_lsp_e2e_core::run_case_with_retries(
+                        #suite_path_literal,
+                        #case_index_literal,
+                        Some(#retries),
+                    )
+                    .await
+                    .map_err(|e| anyhow::anyhow!("{} failed: {}", #label_literal, e))
                 }
             }
         })
@@ -138,12 +151,47 @@ fn get_test_types_for_engine(engine_name: &str) -> Vec<String> {
 
 #[derive(Debug, Deserialize)]
 struct MacroSuite {
+    #[serde(default)]
+    defaults: MacroRetries,
     tests: Vec<MacroCase>,
 }
 
 #[derive(Debug, Deserialize)]
 struct MacroCase {
     name: String,
+    #[serde(flatten)]
+    retries: MacroRetries,
+}
+
+/// `flaky` / `retries` of a case or of the suite's `defaults:`
+///
+/// Settings inherited through `extends:` are not seen here.
+#[derive(Debug, Default, Deserialize)]
+struct MacroRetries {
+    flaky: Option<bool>,
+    retries: Option<u32>,
+}
+
+/// Retries passed to the generated test
+#[derive(Debug, Clone, Copy)]
+enum Retries {
+    Count(u32),
+    /// `flaky: true` without a count, resolved by the core crate
+    FlakyDefault,
+}
+
+impl MacroRetries {
+    /// Resolve the case's settings over the suite defaults
+    fn resolve(&self, defaults: &MacroRetries) -> Retries {
+        match (
+            self.retries.or(defaults.retries),
+            self.flaky.or(defaults.flaky),
+        ) {
+            (Some(count), _) => Retries::Count(count),
+            (None, Some(true)) => Retries::FlakyDefault,
+            (None, _) => Retries::Count(0),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -152,6 +200,7 @@ struct DiscoveredCase {
     suite_path: String,
     case_index: usize,
     label: String,
+    retries: Retries,
 }
 
 fn discover_test_cases(
@@ -179,7 +228,13 @@ fn discover_test_cases(
         for entry in entries {
             let path = entry
                 .unwrap_or_else(|e| panic!("Failed to read path for pattern `{pattern}`: {e}"));
-            yaml_paths.push(path);
+            // `_`-prefixed files hold shared settings included via `extends`
+            let is_shared = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('_'));
+            if !is_shared {
+                yaml_paths.push(path);
+            }
         }
     }
 
@@ -223,6 +278,7 @@ fn discover_test_cases(
                 suite_path: suite_path.clone(),
                 case_index,
                 label: format!("{engine_dir_name}:{category}:{meta_case}"),
+                retries: case.retries.resolve(&suite.defaults),
             });
         }
     }
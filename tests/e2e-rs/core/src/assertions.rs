@@ -6,10 +6,27 @@
 //! Test assertion helpers
 //!
 //! Provides assertions for completion, diagnostics, and hover.
+//!
+//! [`assert_apply_and_parse`] accepts a completion item the way an editor
+//! does and reparses the document with the engine's grammar, so an item
+//! whose label is right but whose inserted text is not valid SQL still
+//! fails.
+//!
+//! [`assert_hover_snapshot`] compares the whole hover markdown against a
+//! checked-in file. Set `E2E_UPDATE_SNAPSHOTS=1` to rewrite the files with
+//! what the server returns instead.
 
 use anyhow::{Result, bail};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::ops::Range as ByteRange;
+use std::path::Path;
 use tower_lsp::lsp_types::*;
+use unified_sql_grammar::language_for_dialect_with_version;
+
+use crate::engine_manager::Engine;
+use crate::fixtures::grammar;
+use crate::scenario::position_offset;
+use crate::yaml_parser::{CompletionItemExpectation, CountBound, ItemKind};
 
 /// Assert completion contains specific items
 pub fn assert_completion_contains(items: &[CompletionItem], expected: &[String]) -> Result<()> {
@@ -72,6 +89,43 @@ pub fn assert_completion_min_count(items: &[CompletionItem], min: usize) -> Resu
     Ok(())
 }
 
+/// Assert the number of completion items of each kind
+///
+/// Items are grouped by [`CompletionItemKind`]; kinds without items count
+/// zero. On failure, every mismatch is listed with the actual distribution.
+pub fn assert_completion_kind_counts(
+    items: &[CompletionItem],
+    expected: &BTreeMap<ItemKind, CountBound>,
+) -> Result<()> {
+    let mut actual: BTreeMap<&str, usize> = BTreeMap::new();
+    for item in items {
+        let kind = item
+            .kind
+            .and_then(ItemKind::of)
+            .map_or("none", |kind| kind.name());
+        *actual.entry(kind).or_default() += 1;
+    }
+
+    let mismatches: Vec<String> = expected
+        .iter()
+        .filter_map(|(kind, bound)| {
+            let count = actual.get(kind.name()).copied().unwrap_or(0);
+            (!bound.contains(count))
+                .then(|| format!("expected {} {} items, got {}", bound, kind.name(), count))
+        })
+        .collect();
+
+    if !mismatches.is_empty() {
+        bail!(
+            "Completion kind counts don't match: {}. Actual distribution: {:?}",
+            mismatches.join("; "),
+            actual
+        );
+    }
+
+    Ok(())
+}
+
 /// Assert completion items are in specific order (first N items)
 pub fn assert_completion_order(items: &[CompletionItem], expected_order: &[String]) -> Result<()> {
     for (i, expected_label) in expected_order.iter().enumerate() {
@@ -180,21 +234,7 @@ pub fn assert_diagnostics_with_codes(
 pub fn assert_hover_contains(hover: Option<&Hover>, expected: &str) -> Result<()> {
     match hover {
         Some(h) => {
-            let content = match &h.contents {
-                HoverContents::Markup(markup) => markup.value.as_str(),
-                HoverContents::Array(contents) => {
-                    // Handle array of MarkupContent or String
-                    contents
-                        .first()
-                        .and_then(|c| match c {
-                            MarkedString::String(s) => Some(s.as_str()),
-                            MarkedString::LanguageString(ls) => Some(ls.value.as_str()),
-                        })
-                        .unwrap_or("")
-                }
-                _ => "",
-            };
-
+            let content = hover_text(h);
             if !content.contains(expected) {
                 bail!(
                     "Expected hover to contain '{}', but got: {}",
@@ -211,6 +251,84 @@ pub fn assert_hover_contains(hover: Option<&Hover>, expected: &str) -> Result<()
     Ok(())
 }
 
+/// Environment variable that rewrites hover snapshots instead of comparing
+pub const UPDATE_SNAPSHOTS_ENV: &str = "E2E_UPDATE_SNAPSHOTS";
+
+/// Whether snapshots should be rewritten with the actual output
+pub fn snapshot_update_enabled() -> bool {
+    match std::env::var(UPDATE_SNAPSHOTS_ENV) {
+        Ok(value) => value != "0" && !value.eq_ignore_ascii_case("false"),
+        Err(_) => false,
+    }
+}
+
+/// Assert the hover markdown equals the contents of the `snapshot` file
+///
+/// Trailing whitespace is ignored. With [`UPDATE_SNAPSHOTS_ENV`] set the
+/// file is written instead.
+pub fn assert_hover_snapshot(hover: Option<&Hover>, snapshot: &Path) -> Result<()> {
+    let Some(hover) = hover else {
+        bail!("Expected hover result, but got None");
+    };
+    check_snapshot(hover_text(hover), snapshot, snapshot_update_enabled())
+}
+
+fn check_snapshot(actual: &str, snapshot: &Path, update: bool) -> Result<()> {
+    let actual = actual.trim_end();
+    if update {
+        if let Some(dir) = snapshot.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(snapshot, format!("{}\n", actual))?;
+        return Ok(());
+    }
+
+    let expected = std::fs::read_to_string(snapshot).map_err(|e| {
+        anyhow::anyhow!(
+            "Cannot read snapshot {}: {} (run with {}=1 to create it)",
+            snapshot.display(),
+            e,
+            UPDATE_SNAPSHOTS_ENV
+        )
+    })?;
+    let expected = expected.trim_end();
+    if actual == expected {
+        return Ok(());
+    }
+
+    let line = expected
+        .lines()
+        .zip(actual.lines())
+        .position(|(expected, actual)| expected != actual)
+        .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+    bail!(
+        "Hover differs from snapshot {} at line {} (run with {}=1 to update it)\n--- expected ---\n{}\n--- actual ---\n{}",
+        snapshot.display(),
+        line + 1,
+        UPDATE_SNAPSHOTS_ENV,
+        expected,
+        actual
+    )
+}
+
+/// Markdown or plain text of a hover
+fn hover_text(hover: &Hover) -> &str {
+    match &hover.contents {
+        HoverContents::Markup(markup) => markup.value.as_str(),
+        HoverContents::Array(contents) => {
+            // Handle array of MarkupContent or String
+            contents
+                .first()
+                .map(|c| match c {
+                    MarkedString::String(s) => s.as_str(),
+                    MarkedString::LanguageString(ls) => ls.value.as_str(),
+                })
+                .unwrap_or("")
+        }
+        _ => "",
+    }
+}
+
 // ============================================================================
 // ENHANCED ASSERTIONS (Phase 5)
 // ============================================================================
@@ -242,6 +360,52 @@ pub fn assert_completion_item_exact(
     Ok(())
 }
 
+/// Assert completion items have the expected fields
+///
+/// Fields left unset in an expectation are not compared.
+pub fn assert_completion_items(
+    items: &[CompletionItem],
+    expected: &[CompletionItemExpectation],
+) -> Result<()> {
+    for expect in expected {
+        let item = items.iter().find(|i| i.label == expect.label).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Expected to find item '{}' in completion, but it was not found. Available: {:?}",
+                expect.label,
+                items.iter().map(|i| &i.label).collect::<Vec<_>>()
+            )
+        })?;
+
+        if let Some(detail) = &expect.detail
+            && item.detail.as_ref() != Some(detail)
+        {
+            bail!(
+                "Expected item '{}' to have detail {:?}, but got {:?}",
+                expect.label,
+                detail,
+                item.detail
+            );
+        }
+
+        let description = item
+            .label_details
+            .as_ref()
+            .and_then(|d| d.description.as_ref());
+        if let Some(expected_description) = &expect.description
+            && description != Some(expected_description)
+        {
+            bail!(
+                "Expected item '{}' to have description {:?}, but got {:?}",
+                expect.label,
+                expected_description,
+                description
+            );
+        }
+    }
+
+    Ok(())
+}
+
 /// Assert text edit range and insert text
 /// Note: This is a simplified version that checks if text_edit exists
 /// Full implementation requires understanding CompletionTextEdit structure
@@ -306,3 +470,286 @@ pub fn assert_completion_kind(
 
     Ok(())
 }
+
+/// Assert that accepting the completion item `label` leaves valid SQL
+///
+/// The item is applied to `text` at `position` with [`apply_completion`]
+/// and the result is parsed with the grammar of `engine`. Fails if an error
+/// or missing node touches the inserted text or the characters around it;
+/// errors elsewhere in the document are not the item's fault.
+pub fn assert_apply_and_parse(
+    items: &[CompletionItem],
+    label: &str,
+    text: &str,
+    position: Position,
+    engine: Engine,
+) -> Result<()> {
+    let item = items.iter().find(|i| i.label == label).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Expected to find item '{}' in completion, but it was not found. Available: {:?}",
+            label,
+            items.iter().map(|i| &i.label).collect::<Vec<_>>()
+        )
+    })?;
+    let (applied, edited) = apply_completion(item, text, position)?;
+
+    let (dialect, version) = grammar(engine);
+    let Some(language) = language_for_dialect_with_version(dialect, Some(version)) else {
+        tracing::warn!("No grammar for {}, skipping apply_and_parse", engine.name());
+        return Ok(());
+    };
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(language)?;
+    let tree = parser.parse(&applied, None).ok_or_else(|| {
+        anyhow::anyhow!("Failed to parse the document after applying '{}'", label)
+    })?;
+
+    let around = edited.start.saturating_sub(1)..edited.end + 1;
+    if let Some(error) = error_in(tree.root_node(), &around) {
+        bail!(
+            "Applying '{}' produced SQL that does not parse for {}: {} at byte {} in:\n{}",
+            label,
+            engine.name(),
+            if error.is_missing() {
+                format!("missing {}", error.kind())
+            } else {
+                "syntax error".to_string()
+            },
+            error.start_byte(),
+            applied
+        );
+    }
+
+    Ok(())
+}
+
+/// Apply `item` to `text` as an editor accepting it at `position` would
+///
+/// The item's text edit is used when it has one, with the insert range of
+/// an insert/replace edit. Otherwise its insert text (or label) replaces
+/// the identifier before the cursor. Snippets are expanded to the defaults
+/// of their placeholders.
+///
+/// Returns the new text and the byte range of the inserted text in it.
+pub fn apply_completion(
+    item: &CompletionItem,
+    text: &str,
+    position: Position,
+) -> Result<(String, ByteRange<usize>)> {
+    let (range, new_text) = match &item.text_edit {
+        Some(CompletionTextEdit::Edit(edit)) => {
+            let start = position_offset(text, edit.range.start)?;
+            (
+                start..position_offset(text, edit.range.end)?,
+                edit.new_text.as_str(),
+            )
+        }
+        Some(CompletionTextEdit::InsertAndReplace(edit)) => {
+            let start = position_offset(text, edit.insert.start)?;
+            (
+                start..position_offset(text, edit.insert.end)?,
+                edit.new_text.as_str(),
+            )
+        }
+        None => {
+            let cursor = position_offset(text, position)?;
+            let typed = text[..cursor]
+                .chars()
+                .rev()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .map(char::len_utf8)
+                .sum::<usize>();
+            (
+                cursor - typed..cursor,
+                item.insert_text.as_deref().unwrap_or(&item.label),
+            )
+        }
+    };
+
+    let new_text = match item.insert_text_format {
+        Some(InsertTextFormat::SNIPPET) => expand_snippet(new_text),
+        _ => new_text.to_string(),
+    };
+
+    let mut applied = text.to_string();
+    applied.replace_range(range.clone(), &new_text);
+    Ok((applied, range.start..range.start + new_text.len()))
+}
+
+/// Text of `snippet` with tab stops removed and placeholders replaced by
+/// their default, or the first of their choices
+fn expand_snippet(snippet: &str) -> String {
+    let mut expanded = String::new();
+    let mut chars = snippet.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => expanded.extend(chars.next()),
+            '$' if chars.peek() == Some(&'{') => {
+                chars.next();
+                let mut body = String::new();
+                let mut depth = 1;
+                for c in chars.by_ref() {
+                    match c {
+                        '{' => depth += 1,
+                        '}' if depth == 1 => break,
+                        '}' => depth -= 1,
+                        _ => {}
+                    }
+                    body.push(c);
+                }
+
+                let body = body.trim_start_matches(|c: char| c.is_ascii_digit());
+                if let Some(default) = body.strip_prefix(':') {
+                    expanded.push_str(&expand_snippet(default));
+                } else if let Some(choices) = body.strip_prefix('|') {
+                    expanded.push_str(choices.split([',', '|']).next().unwrap_or_default());
+                }
+            }
+            '$' if chars.peek().is_some_and(char::is_ascii_digit) => {
+                while chars.next_if(char::is_ascii_digit).is_some() {}
+            }
+            _ => expanded.push(c),
+        }
+    }
+    expanded
+}
+
+/// First error or missing node overlapping the byte range `range`
+fn error_in<'a>(
+    root: tree_sitter::Node<'a>,
+    range: &ByteRange<usize>,
+) -> Option<tree_sitter::Node<'a>> {
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.end_byte() < range.start || node.start_byte() > range.end {
+            continue;
+        }
+        if node.is_error() || node.is_missing() {
+            return Some(node);
+        }
+        if node.has_error() {
+            let mut cursor = node.walk();
+            let children: Vec<_> = node.children(&mut cursor).collect();
+            stack.extend(children.into_iter().rev());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(label: &str) -> CompletionItem {
+        CompletionItem {
+            label: label.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_apply_completion_text_edit() {
+        // `users.id` after `users.` replaces the typed tail only
+        let text = "SELECT users.i FROM users";
+        let edit = TextEdit::new(
+            Range::new(Position::new(0, 13), Position::new(0, 14)),
+            "id".to_string(),
+        );
+        let item = CompletionItem {
+            text_edit: Some(CompletionTextEdit::Edit(edit)),
+            ..item("users.id")
+        };
+        let (applied, edited) = apply_completion(&item, text, Position::new(0, 14)).unwrap();
+        assert_eq!(applied, "SELECT users.id FROM users");
+        assert_eq!(&applied[edited], "id");
+    }
+
+    #[test]
+    fn test_apply_completion_insert_text_and_snippet() {
+        let (applied, edited) =
+            apply_completion(&item("users"), "SELECT * FROM us", Position::new(0, 16)).unwrap();
+        assert_eq!(applied, "SELECT * FROM users");
+        assert_eq!(edited, 14..19);
+
+        let snippet = CompletionItem {
+            insert_text: Some("GENERATED ${1|ALWAYS,BY DEFAULT|} AS IDENTITY$0".to_string()),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..item("GENERATED … AS IDENTITY")
+        };
+        let text = "CREATE TABLE t (id integer GEN)";
+        let (applied, _) = apply_completion(&snippet, text, Position::new(0, 30)).unwrap();
+        assert_eq!(
+            applied,
+            "CREATE TABLE t (id integer GENERATED ALWAYS AS IDENTITY)"
+        );
+    }
+
+    #[test]
+    fn test_expand_snippet() {
+        assert_eq!(
+            expand_snippet("SELECT ${1:columns} FROM ${2:table}$0"),
+            "SELECT columns FROM table"
+        );
+        assert_eq!(expand_snippet("COUNT(${1:${2:*}})"), "COUNT(*)");
+        assert_eq!(expand_snippet("cost \\$1 ${1}x"), "cost $1 x");
+    }
+
+    #[test]
+    fn test_kind_counts_report_distribution() {
+        let kind = |label: &str, kind| CompletionItem {
+            kind: Some(kind),
+            ..item(label)
+        };
+        let mut items = vec![kind("id", CompletionItemKind::FIELD)];
+        items.extend(["AND", "OR", "NOT"].map(|label| kind(label, CompletionItemKind::KEYWORD)));
+        let bounds =
+            |yaml: &str| -> BTreeMap<ItemKind, CountBound> { serde_yaml::from_str(yaml).unwrap() };
+
+        assert!(
+            assert_completion_kind_counts(
+                &items,
+                &bounds("{field: 1, keyword: {max: 3}, function: 0}")
+            )
+            .is_ok()
+        );
+
+        let err =
+            assert_completion_kind_counts(&items, &bounds("{field: {min: 3}, keyword: {max: 2}}"))
+                .unwrap_err()
+                .to_string();
+        assert!(
+            err.contains("expected at least 3 field items, got 1"),
+            "{err}"
+        );
+        assert!(
+            err.contains("expected at most 2 keyword items, got 3"),
+            "{err}"
+        );
+        assert!(err.contains(r#"{"field": 1, "keyword": 3}"#), "{err}");
+    }
+
+    #[test]
+    fn test_hover_snapshot_update_then_compare() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot = dir.path().join("snapshots/count.md");
+        let hover = "```sql\nCOUNT -> ...\n```\n\nCount the number of rows";
+
+        let missing = check_snapshot(hover, &snapshot, false).unwrap_err();
+        assert!(
+            missing.to_string().contains(UPDATE_SNAPSHOTS_ENV),
+            "{missing}"
+        );
+
+        check_snapshot(hover, &snapshot, true).unwrap();
+        assert!(
+            std::fs::read_to_string(&snapshot)
+                .unwrap()
+                .ends_with("rows\n")
+        );
+        check_snapshot(hover, &snapshot, false).unwrap();
+
+        let changed = "```sql\nCOUNT(expr) -> ...\n```\n\nCount the number of rows";
+        let err = check_snapshot(changed, &snapshot, false).unwrap_err();
+        assert!(err.to_string().contains("at line 2"), "{err}");
+    }
+}
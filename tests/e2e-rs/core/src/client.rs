@@ -92,6 +92,9 @@ pub struct LspConnection {
 
     /// Mock client for receiving server notifications
     client: MockClient,
+
+    /// `unifiedSqlLsp` settings section returned for `workspace/configuration`
+    settings: serde_json::Value,
 }
 
 impl LspConnection {
@@ -102,6 +105,7 @@ impl LspConnection {
             stdout: BufReader::new(stdout),
             next_id: Arc::new(std::sync::atomic::AtomicU64::new(1)),
             client: MockClient::new(),
+            settings: serde_json::Value::Null,
         }
     }
 
@@ -180,6 +184,15 @@ impl LspConnection {
 
             // Check if this is a response (has "id") or a notification (has "method" but no "id")
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content_str) {
+                // A request from the server (has both) is answered in place
+                if let (Some(id), Some(method)) =
+                    (json.get("id"), json.get("method").and_then(|m| m.as_str()))
+                {
+                    self.respond_to_server_request(id.clone(), method, json.get("params"))
+                        .await?;
+                    continue;
+                }
+
                 // If it has an "id" field, it's a response - return it
                 if json.get("id").is_some() {
                     return Ok(content_str);
@@ -221,11 +234,63 @@ impl LspConnection {
         }
     }
 
+    /// Answer a request the server sent to the client
+    ///
+    /// `workspace/configuration` gets the settings last sent with
+    /// [`Self::did_change_configuration`] for every item; other requests
+    /// (e.g. `client/registerCapability`) get `null`.
+    async fn respond_to_server_request(
+        &mut self,
+        id: serde_json::Value,
+        method: &str,
+        params: Option<&serde_json::Value>,
+    ) -> Result<()> {
+        debug_log!("!!! CLIENT: Answering server request: {}", method);
+        let result = match method {
+            "workspace/configuration" => {
+                let items = params
+                    .and_then(|p| p.get("items"))
+                    .and_then(|i| i.as_array())
+                    .map_or(0, Vec::len);
+                serde_json::Value::Array(vec![self.settings.clone(); items])
+            }
+            _ => serde_json::Value::Null,
+        };
+
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": result,
+        });
+        self.send_message(&serde_json::to_string(&response)?).await
+    }
+
     /// Initialize LSP server
     pub async fn initialize(&mut self) -> Result<InitializeResult> {
         let params = InitializeParams {
             process_id: Some(std::process::id()),
             root_uri: Some(Url::parse("file:///test")?),
+            capabilities: ClientCapabilities {
+                workspace: Some(WorkspaceClientCapabilities {
+                    configuration: Some(true),
+                    did_change_configuration: Some(DynamicRegistrationClientCapabilities {
+                        dynamic_registration: Some(true),
+                    }),
+                    ..Default::default()
+                }),
+                // As editors do, so snippet items are tested too
+                text_document: Some(TextDocumentClientCapabilities {
+                    completion: Some(CompletionClientCapabilities {
+                        completion_item: Some(CompletionItemCapability {
+                            snippet_support: Some(true),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
             ..Default::default()
         };
 
@@ -282,6 +347,22 @@ impl LspConnection {
             .await
     }
 
+    /// Change an open document
+    pub async fn did_change(
+        &mut self,
+        uri: Url,
+        version: i32,
+        content_changes: Vec<TextDocumentContentChangeEvent>,
+    ) -> Result<()> {
+        let params = DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier { uri, version },
+            content_changes,
+        };
+
+        self.notify("textDocument/didChange".to_string(), params)
+            .await
+    }
+
     /// Close a document
     pub async fn did_close(&mut self, uri: Url) -> Result<()> {
         let params = DidCloseTextDocumentParams {
@@ -304,13 +385,12 @@ impl LspConnection {
             connection_string
         );
 
+        self.settings = serde_json::json!({
+            "dialect": dialect,
+            "connectionString": connection_string
+        });
         let params = DidChangeConfigurationParams {
-            settings: serde_json::json!({
-                "unifiedSqlLsp": {
-                    "dialect": dialect,
-                    "connectionString": connection_string
-                }
-            }),
+            settings: serde_json::json!({ "unifiedSqlLsp": self.settings }),
         };
 
         debug_log!("!!! CLIENT: Calling notify for workspace/didChangeConfiguration");
@@ -6,13 +6,34 @@
 //! Docker Compose management for E2E tests
 //!
 //! Provides automatic startup and teardown of Docker Compose services.
+//!
+//! Services are brought up one engine at a time, so a run that only touches
+//! MySQL 5.7 never starts the PostgreSQL containers. [`DockerCompose`]
+//! remembers which services it started itself; services that were already
+//! running belong to someone else and are left alone on cleanup.
+//!
+//! All docker invocations go through the [`DockerCli`] trait so the service
+//! selection logic can be tested without a Docker daemon.
 
 use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::process::Command;
 use tracing::{info, warn};
 
+/// Number of times `docker compose up` is attempted before giving up
+const UP_ATTEMPTS: usize = 3;
+
+/// Default pause between `docker compose up` attempts
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// Compose project name used by the E2E suite
+const DEFAULT_PROJECT_NAME: &str = "unified-sql-lsp-e2e";
+
 /// Find docker-compose.yml by searching upward from the start directory
 ///
 /// This function searches upward from CARGO_MANIFEST_DIR or current directory
@@ -46,14 +67,134 @@ pub fn find_docker_compose_file() -> Result<PathBuf> {
     }
 }
 
+/// Output of a single docker CLI invocation
+#[derive(Debug, Clone, Default)]
+pub struct CommandOutput {
+    /// Whether the command exited successfully
+    pub success: bool,
+    /// Captured standard output
+    pub stdout: String,
+    /// Captured standard error
+    pub stderr: String,
+}
+
+/// Runs docker CLI commands
+///
+/// Implemented by [`SystemDocker`] for real runs and by mocks in tests.
+#[async_trait]
+pub trait DockerCli: Send + Sync {
+    /// Run `docker` with the given arguments
+    async fn run(&self, args: &[String]) -> Result<CommandOutput>;
+}
+
+/// The `docker` binary on the `PATH`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemDocker;
+
+#[async_trait]
+impl DockerCli for SystemDocker {
+    async fn run(&self, args: &[String]) -> Result<CommandOutput> {
+        let output = Command::new("docker")
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .await?;
+
+        Ok(CommandOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        })
+    }
+}
+
+/// Maps engine names (e.g. "mysql-5.7") to compose service names
+///
+/// The default map matches the bundled docker-compose.yml, where each
+/// service is named after its engine. Compose files with other service
+/// names need an explicit map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComposeServices {
+    services: BTreeMap<String, String>,
+}
+
+impl Default for ComposeServices {
+    fn default() -> Self {
+        Self::new()
+            .with_service("mysql-5.7", "mysql-5.7")
+            .with_service("mysql-8.0", "mysql-8.0")
+            .with_service("postgresql-12", "postgresql-12")
+            .with_service("postgresql-16", "postgresql-16")
+    }
+}
+
+impl ComposeServices {
+    /// Create an empty map
+    pub fn new() -> Self {
+        Self {
+            services: BTreeMap::new(),
+        }
+    }
+
+    /// Map `engine` to the compose service `service`
+    pub fn with_service(mut self, engine: impl Into<String>, service: impl Into<String>) -> Self {
+        self.services.insert(engine.into(), service.into());
+        self
+    }
+
+    /// Compose service of `engine`, if mapped
+    pub fn service_for(&self, engine: &str) -> Option<&str> {
+        self.services.get(engine).map(String::as_str)
+    }
+
+    /// All mapped services, deduplicated
+    pub fn services(&self) -> Vec<&str> {
+        let unique: BTreeSet<&str> = self.services.values().map(String::as_str).collect();
+        unique.into_iter().collect()
+    }
+}
+
+/// Host port named in a `docker compose up` port conflict error
+///
+/// Recognizes the messages Docker prints when a published port is taken,
+/// e.g. "Bind for 0.0.0.0:3307 failed: port is already allocated".
+pub fn port_conflict(stderr: &str) -> Option<u16> {
+    const MARKERS: [&str; 3] = [
+        "port is already allocated",
+        "address already in use",
+        "ports are not available",
+    ];
+
+    stderr
+        .lines()
+        .filter(|line| MARKERS.iter().any(|marker| line.contains(marker)))
+        .find_map(|line| {
+            line.split_whitespace().find_map(|token| {
+                let token = token.trim_end_matches([':', ',', '.']);
+                let (host, port) = token.rsplit_once(':')?;
+                if host.is_empty() {
+                    return None;
+                }
+                port.parse().ok()
+            })
+        })
+}
+
 /// Docker Compose manager
 pub struct DockerCompose {
     /// Path to docker-compose.yml file
     compose_file: PathBuf,
     /// Project name (to avoid conflicts with other projects)
     project_name: String,
-    /// Whether we started the services (used for cleanup)
-    started: bool,
+    /// Engine to compose service mapping
+    services: ComposeServices,
+    /// Runner for docker commands
+    docker: Arc<dyn DockerCli>,
+    /// Services this manager started (and therefore stops on cleanup)
+    started_services: BTreeSet<String>,
+    /// Pause between `docker compose up` attempts
+    retry_delay: Duration,
 }
 
 impl DockerCompose {
@@ -67,7 +208,10 @@ impl DockerCompose {
         Self {
             compose_file: compose_file.as_ref().to_path_buf(),
             project_name,
-            started: false,
+            services: ComposeServices::default(),
+            docker: Arc::new(SystemDocker),
+            started_services: BTreeSet::new(),
+            retry_delay: DEFAULT_RETRY_DELAY,
         }
     }
 
@@ -78,175 +222,251 @@ impl DockerCompose {
     /// the workspace root's docker-compose.yml file.
     pub fn from_default_config() -> Result<Self> {
         let compose_file = find_docker_compose_file()?;
-        Ok(Self::new(compose_file, "unified-sql-lsp-e2e".to_string()))
+        Ok(Self::new(compose_file, DEFAULT_PROJECT_NAME.to_string()))
+    }
+
+    /// Use an explicit engine to service mapping
+    pub fn with_services(mut self, services: ComposeServices) -> Self {
+        self.services = services;
+        self
+    }
+
+    /// Run docker commands through `docker` instead of the system binary
+    pub fn with_docker(mut self, docker: Arc<dyn DockerCli>) -> Self {
+        self.docker = docker;
+        self
     }
 
-    /// Check if services are already running
+    /// Wait `delay` between `docker compose up` attempts
+    pub fn with_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Services started by this manager, in name order
+    pub fn started_services(&self) -> Vec<String> {
+        self.started_services.iter().cloned().collect()
+    }
+
+    /// Full docker arguments for a compose subcommand on this project
+    fn compose_args(&self, args: &[&str]) -> Vec<String> {
+        let mut full = vec![
+            "compose".to_string(),
+            "-f".to_string(),
+            self.compose_file.to_string_lossy().into_owned(),
+            "-p".to_string(),
+            self.project_name.clone(),
+        ];
+        full.extend(args.iter().map(|arg| arg.to_string()));
+        full
+    }
+
+    /// Docker arguments that stop and remove the services this manager started
+    ///
+    /// Returns `None` when nothing was started. Used by synchronous process-exit
+    /// hooks that can't await [`DockerCompose::stop`].
+    pub fn stop_args(&self) -> Option<Vec<String>> {
+        if self.started_services.is_empty() {
+            return None;
+        }
+        let mut args = vec!["rm", "--stop", "--force"];
+        args.extend(self.started_services.iter().map(String::as_str));
+        Some(self.compose_args(&args))
+    }
+
+    /// Check if any service of the project is running
     pub async fn is_running(&self) -> Result<bool> {
         info!("Checking if Docker Compose services are running...");
 
-        let output = Command::new("docker")
-            .args([
-                "compose",
-                "-f",
-                &self.compose_file.to_string_lossy(),
-                "-p",
-                &self.project_name,
-                "ps",
-                "-q",
-            ])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await?;
+        let output = self.docker.run(&self.compose_args(&["ps", "-q"])).await?;
 
         // If we get container IDs, services are running
-        let running = !output.stdout.is_empty();
+        let running = !output.stdout.trim().is_empty();
         info!("Docker Compose services running: {}", running);
         Ok(running)
     }
 
-    /// Start Docker Compose services
+    /// Check if a single compose service is running
+    pub async fn is_service_running(&self, service: &str) -> Result<bool> {
+        let output = self
+            .docker
+            .run(&self.compose_args(&["ps", "-q", "--status", "running", service]))
+            .await?;
+        Ok(!output.stdout.trim().is_empty())
+    }
+
+    /// Start every mapped service that isn't running yet
     pub async fn start(&mut self) -> Result<()> {
         info!("Starting Docker Compose services...");
 
-        // Check if already running
-        if self.is_running().await? {
-            info!("Services already running, skipping startup");
-            self.started = false;
-            return Ok(());
+        let services: Vec<String> = self
+            .services
+            .services()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        for service in &services {
+            self.ensure_service(service).await?;
         }
 
-        info!("Starting docker-compose up...");
-        let output = Command::new("docker")
-            .args([
-                "compose",
-                "-f",
-                &self.compose_file.to_string_lossy(),
-                "-p",
-                &self.project_name,
-                "up",
-                "-d",
-            ])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow::anyhow!(
-                "Failed to start Docker Compose services: {}",
-                stderr
-            ));
-        }
+        info!("Docker Compose services ready");
+        Ok(())
+    }
 
-        self.started = true;
-        info!("Docker Compose services started successfully");
+    /// Bring up the compose service of `engine` (e.g. "mysql-5.7")
+    pub async fn ensure_engine(&mut self, engine: &str) -> Result<()> {
+        let service = self
+            .services
+            .service_for(engine)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No compose service configured for engine {} in {}",
+                    engine,
+                    self.compose_file.display()
+                )
+            })?
+            .to_string();
+        self.ensure_service(&service).await
+    }
 
-        // Wait for MySQL to be ready
-        self.wait_for_mysql().await?;
+    /// Bring up `service` unless it is already running
+    ///
+    /// Waits for the service's healthcheck. Services that were running before
+    /// are not recorded as started by this manager.
+    pub async fn ensure_service(&mut self, service: &str) -> Result<()> {
+        if self.started_services.contains(service) {
+            return Ok(());
+        }
+        if self.is_service_running(service).await? {
+            info!(
+                "Compose service {} already running, not managing it",
+                service
+            );
+            return Ok(());
+        }
 
+        self.up_with_retry(service).await?;
+        self.started_services.insert(service.to_string());
+        info!("Compose service {} started", service);
         Ok(())
     }
 
-    /// Wait for MySQL 5.7 to be ready
-    async fn wait_for_mysql(&self) -> Result<()> {
-        info!("Waiting for MySQL 5.7 to be ready...");
-
-        let mut retries = 60; // 60 seconds max
-        let interval = tokio::time::Duration::from_secs(1);
-
-        while retries > 0 {
-            // Check if MySQL 5.7 is accepting connections
-            let output = Command::new("docker")
-                .args([
-                    "exec",
-                    "unified-sql-lsp-mysql-57",
-                    "mysqladmin",
-                    "ping",
-                    "-h",
-                    "localhost",
-                    "-uroot",
-                    "-proot_password",
-                ])
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .output()
-                .await;
-
-            if let Ok(out) = output {
-                if out.status.success() {
-                    info!("MySQL 5.7 is ready!");
-                    return Ok(());
-                }
+    /// Run `docker compose up` for `service`, retrying transient failures
+    ///
+    /// Port conflicts aren't transient and fail immediately.
+    async fn up_with_retry(&self, service: &str) -> Result<()> {
+        let args = self.compose_args(&["up", "-d", "--wait", service]);
+        let mut last_error = String::new();
+
+        for attempt in 1..=UP_ATTEMPTS {
+            info!(
+                "Starting compose service {} (attempt {}/{})",
+                service, attempt, UP_ATTEMPTS
+            );
+            let output = self.docker.run(&args).await?;
+            if output.success {
+                return Ok(());
+            }
+
+            if let Some(port) = port_conflict(&output.stderr) {
+                return Err(self.port_conflict_error(service, port).await);
             }
 
-            warn!("MySQL 5.7 not ready yet, retrying... ({})", retries);
-            tokio::time::sleep(interval).await;
-            retries -= 1;
+            last_error = output.stderr.trim().to_string();
+            warn!(
+                "docker compose up {} failed (attempt {}/{}): {}",
+                service, attempt, UP_ATTEMPTS, last_error
+            );
+            if attempt < UP_ATTEMPTS {
+                tokio::time::sleep(self.retry_delay).await;
+            }
         }
 
-        Err(anyhow::anyhow!("MySQL 5.7 did not become ready in time"))
+        Err(anyhow::anyhow!(
+            "Failed to start compose service {} after {} attempts: {}",
+            service,
+            UP_ATTEMPTS,
+            last_error
+        ))
+    }
+
+    /// Error for a service whose host port is taken, naming the culprit
+    async fn port_conflict_error(&self, service: &str, port: u16) -> anyhow::Error {
+        let filter = format!("publish={}", port);
+        let owner = self
+            .docker
+            .run(&[
+                "ps".to_string(),
+                "--filter".to_string(),
+                filter,
+                "--format".to_string(),
+                "{{.Names}}".to_string(),
+            ])
+            .await
+            .ok()
+            .and_then(|output| output.stdout.lines().next().map(str::to_string))
+            .filter(|name| !name.trim().is_empty());
+
+        match owner {
+            Some(container) => anyhow::anyhow!(
+                "Cannot start compose service {}: host port {} is already used by container '{}'. \
+                 Stop it with `docker stop {}` or change the port mapping in {}",
+                service,
+                port,
+                container,
+                container,
+                self.compose_file.display()
+            ),
+            None => anyhow::anyhow!(
+                "Cannot start compose service {}: host port {} is already in use by a process \
+                 outside Docker. Free the port or change the port mapping in {}",
+                service,
+                port,
+                self.compose_file.display()
+            ),
+        }
     }
 
-    /// Stop Docker Compose services
+    /// Stop the services this manager started
     pub async fn stop(&mut self) -> Result<()> {
-        info!("Stopping Docker Compose services...");
+        let Some(args) = self.stop_args() else {
+            info!("No Docker Compose services started by this process");
+            return Ok(());
+        };
+        info!(
+            "Stopping Docker Compose services: {:?}",
+            self.started_services
+        );
 
-        let output = Command::new("docker")
-            .args([
-                "compose",
-                "-f",
-                &self.compose_file.to_string_lossy(),
-                "-p",
-                &self.project_name,
-                "down",
-            ])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await?;
+        let output = self.docker.run(&args).await?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            warn!("Failed to stop Docker Compose services: {}", stderr);
+        if !output.success {
+            warn!("Failed to stop Docker Compose services: {}", output.stderr);
             // Don't return error, just warn
         } else {
             info!("Docker Compose services stopped successfully");
         }
 
-        self.started = false;
+        self.started_services.clear();
         Ok(())
     }
 
     /// Remove volumes (for cleanup)
+    ///
+    /// Takes the whole project down, including services this manager
+    /// didn't start.
     pub async fn remove_volumes(&mut self) -> Result<()> {
         info!("Removing Docker Compose volumes...");
 
-        let output = Command::new("docker")
-            .args([
-                "compose",
-                "-f",
-                &self.compose_file.to_string_lossy(),
-                "-p",
-                &self.project_name,
-                "down",
-                "-v",
-            ])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await?;
+        let output = self.docker.run(&self.compose_args(&["down", "-v"])).await?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            warn!("Failed to remove volumes: {}", stderr);
+        if !output.success {
+            warn!("Failed to remove volumes: {}", output.stderr);
         } else {
             info!("Volumes removed successfully");
         }
 
-        self.started = false;
+        self.started_services.clear();
         Ok(())
     }
 }
@@ -255,8 +475,217 @@ impl Drop for DockerCompose {
     fn drop(&mut self) {
         // Note: Drop is synchronous, so we can't await async operations here
         // Users should call stop() explicitly or use cleanup_database()
-        if self.started {
+        if !self.started_services.is_empty() {
             warn!("DockerCompose dropped without stopping. Consider calling stop() explicitly.");
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    type Handler = Box<dyn Fn(&str) -> CommandOutput + Send + Sync>;
+
+    /// Records docker invocations and answers them from a handler
+    struct MockDocker {
+        calls: Mutex<Vec<String>>,
+        handler: Handler,
+    }
+
+    impl MockDocker {
+        fn new(handler: impl Fn(&str) -> CommandOutput + Send + Sync + 'static) -> Arc<Self> {
+            Arc::new(Self {
+                calls: Mutex::new(Vec::new()),
+                handler: Box::new(handler),
+            })
+        }
+
+        /// Invocations whose arguments contain `needle`
+        fn calls_with(&self, needle: &str) -> Vec<String> {
+            self.calls
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|call| call.contains(needle))
+                .cloned()
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl DockerCli for MockDocker {
+        async fn run(&self, args: &[String]) -> Result<CommandOutput> {
+            let call = args.join(" ");
+            self.calls.lock().unwrap().push(call.clone());
+            Ok((self.handler)(&call))
+        }
+    }
+
+    fn ok(stdout: &str) -> CommandOutput {
+        CommandOutput {
+            success: true,
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+        }
+    }
+
+    fn failed(stderr: &str) -> CommandOutput {
+        CommandOutput {
+            success: false,
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+        }
+    }
+
+    fn manager(docker: Arc<MockDocker>) -> DockerCompose {
+        DockerCompose::new("/e2e/docker-compose.yml", "e2e".to_string())
+            .with_docker(docker)
+            .with_retry_delay(Duration::ZERO)
+    }
+
+    #[tokio::test]
+    async fn test_ensure_engine_starts_only_its_service() {
+        let docker = MockDocker::new(|_| ok(""));
+        let mut compose = manager(docker.clone());
+
+        compose.ensure_engine("postgresql-12").await.unwrap();
+        compose.ensure_engine("postgresql-12").await.unwrap();
+
+        assert_eq!(
+            docker.calls_with(" up "),
+            vec!["compose -f /e2e/docker-compose.yml -p e2e up -d --wait postgresql-12"]
+        );
+        assert_eq!(compose.started_services(), vec!["postgresql-12"]);
+        assert_eq!(
+            compose.stop_args().unwrap().join(" "),
+            "compose -f /e2e/docker-compose.yml -p e2e rm --stop --force postgresql-12"
+        );
+        compose.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_running_services_are_not_stopped() {
+        // mysql-5.7 was already up before this process
+        let docker = MockDocker::new(|call| {
+            if call.contains(" ps ") && call.ends_with("mysql-5.7") {
+                ok("abc123\n")
+            } else {
+                ok("")
+            }
+        });
+        let mut compose = manager(docker.clone());
+
+        compose.start().await.unwrap();
+
+        let ups = docker.calls_with(" up ");
+        assert_eq!(ups.len(), 3);
+        assert!(ups.iter().all(|call| !call.ends_with("mysql-5.7")));
+        assert_eq!(
+            compose.started_services(),
+            vec!["mysql-8.0", "postgresql-12", "postgresql-16"]
+        );
+        compose.stop().await.unwrap();
+        assert!(compose.stop_args().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_transient_up_failures_are_retried() {
+        let attempts = Arc::new(Mutex::new(0));
+        let counter = attempts.clone();
+        let docker = MockDocker::new(move |call| {
+            if !call.contains(" up ") {
+                return ok("");
+            }
+            let mut attempts = counter.lock().unwrap();
+            *attempts += 1;
+            if *attempts < 3 {
+                failed("Error response from daemon: i/o timeout")
+            } else {
+                ok("")
+            }
+        });
+        let mut compose = manager(docker.clone());
+
+        compose.ensure_engine("mysql-8.0").await.unwrap();
+        assert_eq!(docker.calls_with(" up ").len(), 3);
+        assert_eq!(compose.started_services(), vec!["mysql-8.0"]);
+        compose.stop().await.unwrap();
+
+        // A fourth failure in a row is not retried again
+        let docker = MockDocker::new(|call| {
+            if call.contains(" up ") {
+                failed("Error response from daemon: i/o timeout")
+            } else {
+                ok("")
+            }
+        });
+        let mut compose = manager(docker.clone());
+        let err = compose.ensure_engine("mysql-8.0").await.unwrap_err();
+        assert!(err.to_string().contains("after 3 attempts"));
+        assert_eq!(docker.calls_with(" up ").len(), 3);
+        assert!(compose.started_services().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_port_conflict_names_container() {
+        let docker = MockDocker::new(|call| {
+            if call.contains(" up ") {
+                failed(
+                    "Error response from daemon: driver failed programming external connectivity: \
+                     Bind for 0.0.0.0:3307 failed: port is already allocated",
+                )
+            } else if call.contains("publish=3307") {
+                ok("legacy-mysql\n")
+            } else {
+                ok("")
+            }
+        });
+        let mut compose = manager(docker.clone());
+
+        let err = compose.ensure_engine("mysql-5.7").await.unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("port 3307"), "{}", message);
+        assert!(message.contains("'legacy-mysql'"), "{}", message);
+        assert!(message.contains("docker stop legacy-mysql"), "{}", message);
+        // Port conflicts are not retried
+        assert_eq!(docker.calls_with(" up ").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_explicit_service_map() {
+        let docker = MockDocker::new(|_| ok(""));
+        let services = ComposeServices::new().with_service("mysql-5.7", "db");
+        let mut compose = manager(docker.clone()).with_services(services);
+
+        compose.ensure_engine("mysql-5.7").await.unwrap();
+        assert_eq!(
+            docker.calls_with(" up "),
+            vec!["compose -f /e2e/docker-compose.yml -p e2e up -d --wait db"]
+        );
+
+        let err = compose.ensure_engine("postgresql-16").await.unwrap_err();
+        assert!(err.to_string().contains("No compose service configured"));
+        compose.stop().await.unwrap();
+    }
+
+    #[test]
+    fn test_port_conflict_parsing() {
+        assert_eq!(
+            port_conflict("Bind for 0.0.0.0:5433 failed: port is already allocated"),
+            Some(5433)
+        );
+        assert_eq!(
+            port_conflict(
+                "Error starting userland proxy: listen tcp4 0.0.0.0:3308: bind: address already in use"
+            ),
+            Some(3308)
+        );
+        assert_eq!(
+            port_conflict("ports are not available: exposing port TCP 127.0.0.1:5434 -> 0.0.0.0:0"),
+            Some(5434)
+        );
+        assert_eq!(port_conflict("pull access denied for mysql"), None);
+    }
+}
@@ -91,6 +91,16 @@ impl Engine {
         }
     }
 
+    /// Directory name of this engine's tests, e.g. "mysql-5.7"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Engine::MySQL57 => "mysql-5.7",
+            Engine::MySQL80 => "mysql-8.0",
+            Engine::PostgreSQL12 => "postgresql-12",
+            Engine::PostgreSQL16 => "postgresql-16",
+        }
+    }
+
     /// Get serial test key for this engine
     ///
     /// Used with `#[serial(key)]` attribute to ensure tests for same engine run serially.
@@ -148,6 +158,8 @@ static ENGINE_STATES: LazyLock<HashMap<Engine, Arc<EngineState>>> = LazyLock::ne
 });
 
 /// Global Docker Compose manager (shared across all engines)
+///
+/// Tracks which services this process started so the exit hook stops only those.
 static DOCKER_COMPOSE: LazyLock<Arc<tokio::sync::RwLock<Option<DockerCompose>>>> =
     LazyLock::new(|| Arc::new(tokio::sync::RwLock::new(None)));
 
@@ -161,7 +173,8 @@ fn should_cleanup_docker_on_process_exit() -> bool {
 /// Global cleanup function called when test process exits
 ///
 /// This uses the `ctor` crate to register a destructor that runs when
-/// the test binary exits, ensuring Docker Compose services are stopped.
+/// the test binary exits, stopping the Docker Compose services this process
+/// started. Services that were already running are left alone.
 #[ctor::dtor]
 fn global_cleanup() {
     use std::sync::atomic::{AtomicBool, Ordering};
@@ -177,34 +190,17 @@ fn global_cleanup() {
             return;
         }
 
-        // Check if we started Docker Compose services
-        let needs_cleanup = DOCKER_COMPOSE
+        // Only stop the services this process started
+        let stop_args = DOCKER_COMPOSE
             .try_read()
-            .map(|guard| guard.is_some())
-            .unwrap_or(false);
+            .ok()
+            .and_then(|guard| guard.as_ref().and_then(DockerCompose::stop_args));
 
-        if needs_cleanup {
+        if let Some(args) = stop_args {
             debug_log!("!!! Global cleanup: stopping Docker Compose services...");
 
-            // Find the compose file path by searching upward
-            let compose_file = crate::docker::find_docker_compose_file()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|e| {
-                    debug_log!("!!! Failed to find docker-compose.yml: {}", e);
-                    "tests/e2e-rs/docker-compose.yml".to_string()
-                });
-
             // Use std::process::Command for synchronous execution
-            let result = std::process::Command::new("docker")
-                .args([
-                    "compose",
-                    "-f",
-                    &compose_file,
-                    "-p",
-                    "unified-sql-lsp-e2e",
-                    "down",
-                ])
-                .output();
+            let result = std::process::Command::new("docker").args(&args).output();
 
             match result {
                 Ok(output) => {
@@ -218,7 +214,7 @@ fn global_cleanup() {
                     }
                 }
                 Err(e) => {
-                    debug_log!("!!! Failed to execute docker compose down: {}", e);
+                    debug_log!("!!! Failed to execute docker compose rm: {}", e);
                 }
             }
         }
@@ -248,7 +244,7 @@ impl EngineGuard {
     ///
     /// This function:
     /// 1. Increments the active test count for the engine
-    /// 2. Starts Docker Compose if this is the very first test across all engines
+    /// 2. Starts the engine's Docker Compose service if it isn't running yet
     /// 3. Initializes the database if this is the first test for this engine
     ///
     /// The returned guard will automatically destroy the database on drop
@@ -258,18 +254,17 @@ impl EngineGuard {
             .get(&engine)
             .ok_or_else(|| anyhow::anyhow!("Engine not found in state registry"))?;
 
-        // Start Docker Compose services if not already started
+        // Bring up this engine's compose service if not already started
         {
-            let docker_guard = DOCKER_COMPOSE.read().await;
-            if docker_guard.is_none() {
-                drop(docker_guard);
-                tracing::info!("Starting Docker Compose services...");
-                let mut docker_compose = DockerCompose::from_default_config()?;
-                docker_compose.start().await?;
-
-                let mut compose_guard = DOCKER_COMPOSE.write().await;
-                *compose_guard = Some(docker_compose);
-                tracing::info!("Docker Compose services started");
+            let mut compose_guard = DOCKER_COMPOSE.write().await;
+            if compose_guard.is_none() {
+                *compose_guard = Some(match crate::orchestrator::TestOrchestrator::global() {
+                    Some(orchestrator) => orchestrator.docker_compose()?,
+                    None => DockerCompose::from_default_config()?,
+                });
+            }
+            if let Some(compose) = compose_guard.as_mut() {
+                compose.ensure_engine(engine.name()).await?;
             }
         }
 
@@ -402,6 +397,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_engine_name_round_trips_through_path() {
+        for engine in [
+            Engine::MySQL57,
+            Engine::MySQL80,
+            Engine::PostgreSQL12,
+            Engine::PostgreSQL16,
+        ] {
+            let path = format!("tests/{}/completion/test.yaml", engine.name());
+            assert_eq!(Engine::from_path(Path::new(&path)), engine);
+        }
+    }
+
     #[test]
     fn test_serial_key() {
         assert_eq!(Engine::MySQL57.serial_key(), "mysql_57");
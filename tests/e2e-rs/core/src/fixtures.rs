@@ -0,0 +1,170 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! Schema and data fixture handling
+//!
+//! Fixtures are shared by all engines of a dialect. A fixture can be
+//! overridden for one engine by a file named after it next to the shared
+//! one: `schema.mysql-5.7.sql` is loaded instead of `schema.sql` for MySQL
+//! 5.7 tests.
+//!
+//! Schema files are parsed with the engine's grammar before they are
+//! loaded, so syntax the engine doesn't support fails with the offending
+//! statement instead of a database error much later. Set
+//! `E2E_SKIP_FIXTURE_VALIDATION=1` to load them without validation.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use unified_sql_grammar::{DialectVersion, language_for_dialect_with_version};
+use unified_sql_lsp_ir::Dialect;
+
+use crate::engine_manager::Engine;
+
+/// Environment variable that disables fixture validation
+pub const SKIP_FIXTURE_VALIDATION_ENV: &str = "E2E_SKIP_FIXTURE_VALIDATION";
+
+/// Whether fixtures should be validated before loading
+pub fn fixture_validation_enabled() -> bool {
+    match std::env::var(SKIP_FIXTURE_VALIDATION_ENV) {
+        Ok(value) => value == "0" || value.eq_ignore_ascii_case("false"),
+        Err(_) => true,
+    }
+}
+
+/// Fixture to load for `engine`: its per-engine override if present
+pub fn resolve_fixture(path: &Path, engine: Engine) -> PathBuf {
+    let (Some(stem), Some(extension)) = (path.file_stem(), path.extension()) else {
+        return path.to_path_buf();
+    };
+
+    let mut name = stem.to_os_string();
+    name.push(format!(".{}.", engine.name()));
+    name.push(extension);
+    let candidate = path.with_file_name(name);
+
+    if candidate.exists() {
+        candidate
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Check that the schema fixture at `path` parses with the engine's grammar
+///
+/// # Errors
+///
+/// Returns an error naming the file, line and statement of the first
+/// syntax error.
+pub fn validate_fixture(path: &Path, engine: Engine) -> Result<()> {
+    let sql = std::fs::read_to_string(path)?;
+    let (dialect, version) = grammar(engine);
+    let Some(language) = language_for_dialect_with_version(dialect, Some(version)) else {
+        tracing::warn!(
+            "No grammar for {}, skipping fixture validation",
+            engine.name()
+        );
+        return Ok(());
+    };
+
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(language)?;
+    let tree = parser
+        .parse(&sql, None)
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse fixture {}", path.display()))?;
+
+    match first_error(tree.root_node()) {
+        Some(offset) => Err(anyhow::anyhow!(fixture_error(path, &sql, offset, engine))),
+        None => Ok(()),
+    }
+}
+
+/// Dialect and grammar version of an engine
+pub fn grammar(engine: Engine) -> (Dialect, DialectVersion) {
+    match engine {
+        Engine::MySQL57 => (Dialect::MySQL, DialectVersion::MySQL57),
+        Engine::MySQL80 => (Dialect::MySQL, DialectVersion::MySQL80),
+        Engine::PostgreSQL12 => (Dialect::PostgreSQL, DialectVersion::PostgreSQL12),
+        Engine::PostgreSQL16 => (Dialect::PostgreSQL, DialectVersion::PostgreSQL14),
+    }
+}
+
+/// Byte offset of the first error or missing node in document order
+fn first_error(root: tree_sitter::Node) -> Option<usize> {
+    if !root.has_error() {
+        return None;
+    }
+
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.is_error() || node.is_missing() {
+            return Some(node.start_byte());
+        }
+        let mut cursor = node.walk();
+        let children: Vec<_> = node
+            .children(&mut cursor)
+            .filter(|child| child.has_error())
+            .collect();
+        stack.extend(children.into_iter().rev());
+    }
+    None
+}
+
+/// Message for a syntax error at byte `offset` of the fixture `sql`
+fn fixture_error(path: &Path, sql: &str, offset: usize, engine: Engine) -> String {
+    let offset = offset.min(sql.len());
+    let line = sql[..offset].matches('\n').count() + 1;
+
+    // The statement is delimited by the semicolons around the error
+    let start = sql[..offset].rfind(';').map_or(0, |pos| pos + 1);
+    let end = sql[offset..]
+        .find(';')
+        .map_or(sql.len(), |pos| offset + pos + 1);
+    let statement = sql[start..end].trim();
+
+    format!(
+        "Fixture {}:{} does not parse for {}:\n{}\n(set {}=1 to load it anyway)",
+        path.display(),
+        line,
+        engine.name(),
+        statement,
+        SKIP_FIXTURE_VALIDATION_ENV
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_fixture_prefers_engine_override() {
+        let dir = tempfile::tempdir().unwrap();
+        let schema = dir.path().join("schema.sql");
+        std::fs::write(&schema, "CREATE TABLE t (id INT);").unwrap();
+        let override_57 = dir.path().join("schema.mysql-5.7.sql");
+        std::fs::write(&override_57, "CREATE TABLE t (id INT);").unwrap();
+
+        assert_eq!(resolve_fixture(&schema, Engine::MySQL57), override_57);
+        assert_eq!(resolve_fixture(&schema, Engine::MySQL80), schema);
+        // Missing files resolve to themselves
+        let missing = dir.path().join("data.sql");
+        assert_eq!(resolve_fixture(&missing, Engine::MySQL57), missing);
+    }
+
+    #[test]
+    fn test_fixture_error_names_file_line_and_statement() {
+        let sql = "CREATE TABLE users (id INT);\n\
+                   CREATE VIEW ranked AS\n  SELECT id, ROW_NUMBER() OVER (ORDER BY id) FROM users;\n\
+                   CREATE TABLE orders (id INT);\n";
+        let offset = sql.find("OVER").unwrap();
+        let message = fixture_error(Path::new("schema.sql"), sql, offset, Engine::MySQL57);
+
+        assert_eq!(
+            message,
+            "Fixture schema.sql:3 does not parse for mysql-5.7:\n\
+             CREATE VIEW ranked AS\n  SELECT id, ROW_NUMBER() OVER (ORDER BY id) FROM users;\n\
+             (set E2E_SKIP_FIXTURE_VALIDATION=1 to load it anyway)"
+        );
+    }
+}
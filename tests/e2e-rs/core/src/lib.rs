@@ -34,11 +34,15 @@ pub mod db_pool;
 pub mod docker;
 pub mod engine_manager;
 pub mod execution;
+pub mod fixtures;
 pub mod lifecycle;
 pub mod logging;
 pub mod lsp_pool;
 pub mod orchestrator;
+pub mod retry;
 pub mod runner;
+pub mod scenario;
+pub mod server_logs;
 pub mod utils;
 pub mod yaml_parser;
 
@@ -64,16 +68,20 @@ use db::adapter_from_test_path;
 use docker::DockerCompose;
 
 use logging::initialize;
+use retry::{CaseReport, CaseStatus, run_with_retries};
 
 /// Global Docker Compose manager (initialized once, thread-safe)
 static DOCKER_COMPOSE: LazyLock<Arc<RwLock<Option<DockerCompose>>>> =
     LazyLock::new(|| Arc::new(RwLock::new(None)));
 
 struct SharedLsp {
-    _runner: runner::LspRunner,
+    runner: runner::LspRunner,
     conn: LspConnection,
 }
 
+/// How long `expect_logs` waits for a line to reach the capture buffer
+const LOG_WAIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
 /// Global shared LSP process/connection for this test process.
 static SHARED_LSP: LazyLock<Arc<Mutex<Option<SharedLsp>>>> =
     LazyLock::new(|| Arc::new(Mutex::new(None)));
@@ -191,10 +199,16 @@ pub async fn run_test(
         .parent()
         .ok_or_else(|| anyhow::anyhow!("Cannot get parent directory of suite file"))?;
 
+    let engine = engine_manager::Engine::from_path(suite_path);
+    let validate = fixtures::fixture_validation_enabled();
+
     for schema_path in &suite.database.schemas {
-        let full_path = suite_dir.join(schema_path);
+        let full_path = fixtures::resolve_fixture(&suite_dir.join(schema_path), engine);
         debug_log!("!!! Loading schema from: {:?}", full_path);
         if full_path.exists() {
+            if validate {
+                fixtures::validate_fixture(&full_path, engine)?;
+            }
             debug_log!("!!! Schema file exists, loading...");
             if let Err(e) = adapter.load_schema(&full_path).await {
                 debug_log!("!!! Failed to load schema: {}", e);
@@ -207,7 +221,7 @@ pub async fn run_test(
     }
 
     for data_path in &suite.database.data {
-        let full_path = suite_dir.join(data_path);
+        let full_path = fixtures::resolve_fixture(&suite_dir.join(data_path), engine);
         debug_log!("!!! Loading data from: {:?}", full_path);
         if full_path.exists() {
             debug_log!("!!! Data file exists, loading...");
@@ -237,7 +251,7 @@ pub async fn run_test(
         info!("Shared LSP server initialized");
 
         *shared_lsp_guard = Some(SharedLsp {
-            _runner: lsp_runner,
+            runner: lsp_runner,
             conn,
         });
     }
@@ -245,12 +259,45 @@ pub async fn run_test(
     let shared_lsp = shared_lsp_guard
         .as_mut()
         .ok_or_else(|| anyhow::anyhow!("Shared LSP not initialized"))?;
-    let conn = &mut shared_lsp.conn;
 
+    // Only this case's server output should be reported or asserted on
+    let logs = shared_lsp.runner.logs();
+    logs.clear();
+
+    let connection_string = adapter.connection_string().to_string();
+    let result = run_steps(
+        suite,
+        test,
+        suite_dir,
+        engine,
+        &connection_string,
+        &mut shared_lsp.conn,
+        &logs,
+    )
+    .await;
+    drop(shared_lsp_guard);
+
+    if let Err(e) = result {
+        return Err(attach_server_logs(e, &logs));
+    }
+
+    info!("Test passed: {}", test.name);
+    Ok(())
+}
+
+/// Configure the shared server, open the case's document and run its assertions
+async fn run_steps(
+    suite: &TestSuite,
+    test: &yaml_parser::TestCase,
+    suite_dir: &std::path::Path,
+    engine: engine_manager::Engine,
+    connection_string: &str,
+    conn: &mut LspConnection,
+    logs: &server_logs::ServerLogBuffer,
+) -> Result<()> {
     // 4.5. Set engine configuration through did_change_configuration
     // Use connection string from adapter (determined by test path)
-    let connection_string = adapter.connection_string().to_string();
-    let dialect = suite.database.dialect.clone();
+    let dialect = suite.dialect_for(test).to_string();
     info!(
         "Setting engine configuration: dialect={}, connection={}",
         dialect, connection_string
@@ -258,7 +305,7 @@ pub async fn run_test(
 
     // Send the configuration notification.
     // We reuse one LSP process across tests, so every case must reconfigure it.
-    conn.did_change_configuration(&dialect, &connection_string)
+    conn.did_change_configuration(&dialect, connection_string)
         .await?;
     info!("Engine configuration set");
 
@@ -268,12 +315,7 @@ pub async fn run_test(
     // Give server time to process the configuration
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
-    // 5. Get cursor position and strip marker
-    let position = suite.extract_cursor(test)?;
-    let sql = suite.strip_cursor_marker(test);
-    info!("Cursor position: {:?}", position);
-
-    // 6. Open document
+    // 5. Open document
     info!("Opening document...");
     // Generate a unique URI to avoid cross-test document state interference.
     let sanitized_name: String = test
@@ -289,83 +331,296 @@ pub async fn run_test(
         "file:///test_{}_{}.sql",
         sanitized_name, unique_id
     ))?;
-    let dialect = suite.database.dialect.clone();
+    let dialect = suite.dialect_for(test).to_string();
+    let sql = suite.strip_cursor_marker(test);
     conn.did_open(uri.clone(), dialect, sql).await?;
     info!("Document opened");
 
     // Give server time to parse
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
-    // 7. Run assertions based on test expectations
-    if let Some(completion_expect) = &test.expect_completion {
-        let completion_items = conn
-            .completion(uri.clone(), position)
-            .await?
-            .unwrap_or_default();
+    // 6. Run assertions based on test expectations
+    let result = if test.steps.is_empty() {
+        run_expectations(suite, test, suite_dir, engine, conn, &uri).await
+    } else {
+        run_scenario(test, suite_dir, engine, conn, &uri).await
+    };
 
-        if !completion_expect.contains.is_empty() {
-            assertions::assert_completion_contains(&completion_items, &completion_expect.contains)?;
-        }
+    let result = match result {
+        Ok(()) => check_logs(test, logs).await,
+        Err(e) => Err(e),
+    };
 
-        if !completion_expect.not_contains.is_empty() {
-            assertions::assert_completion_not_contains(
-                &completion_items,
-                &completion_expect.not_contains,
-            )?;
-        }
+    // Best-effort cleanup of opened document in shared LSP process.
+    let _ = conn.did_close(uri).await;
 
-        if let Some(count) = completion_expect.count {
-            assertions::assert_completion_count(&completion_items, count)?;
-        }
+    result
+}
 
-        if let Some(min_count) = completion_expect.min_count {
-            assertions::assert_completion_min_count(&completion_items, min_count)?;
-        }
+/// Assert the case's top-level expectations on the opened document
+async fn run_expectations(
+    suite: &TestSuite,
+    test: &yaml_parser::TestCase,
+    suite_dir: &std::path::Path,
+    engine: engine_manager::Engine,
+    conn: &mut LspConnection,
+    uri: &tower_lsp::lsp_types::Url,
+) -> Result<()> {
+    let position = suite.extract_cursor(test)?;
+    info!("Cursor position: {:?}", position);
 
-        if !completion_expect.order.is_empty() {
-            assertions::assert_completion_order(&completion_items, &completion_expect.order)?;
-        }
+    if let Some(completion_expect) = &test.expect_completion {
+        let text = suite.strip_cursor_marker(test);
+        check_completion(conn, uri, position, completion_expect, &text, engine).await?;
     }
 
     if let Some(diag_expect) = &test.expect_diagnostics {
-        // Read any pending notifications (like publish_diagnostics)
-        conn.read_pending_notifications().await?;
+        check_diagnostics(conn, uri, diag_expect).await?;
+    }
+
+    if let Some(hover_expect) = &test.expect_hover {
+        check_hover(conn, uri, position, hover_expect, suite_dir).await?;
+    }
+
+    Ok(())
+}
+
+/// Run the case's `steps:` in order against the opened document
+async fn run_scenario(
+    test: &yaml_parser::TestCase,
+    suite_dir: &std::path::Path,
+    engine: engine_manager::Engine,
+    conn: &mut LspConnection,
+    uri: &tower_lsp::lsp_types::Url,
+) -> Result<()> {
+    if test.expect_completion.is_some()
+        || test.expect_diagnostics.is_some()
+        || test.expect_hover.is_some()
+    {
+        return Err(anyhow::anyhow!(
+            "Test '{}' mixes steps with expect_completion/expect_diagnostics/expect_hover; move them into steps",
+            test.name
+        ));
+    }
+
+    let mut document = scenario::ScenarioDocument::new(&test.sql);
+
+    for (index, step) in test.steps.iter().enumerate() {
+        let kind = step
+            .kind()
+            .map_err(|e| anyhow::anyhow!("Step {}: {}", index + 1, e))?;
+        info!("Step {}: {}", index + 1, kind);
+
+        let result = if let Some(edit) = &step.edit {
+            apply_edit(conn, uri, &mut document, edit).await
+        } else if let Some(completion_expect) = &step.completion {
+            let position = document.cursor(step.cursor.as_ref())?;
+            check_completion(
+                conn,
+                uri,
+                position,
+                completion_expect,
+                document.text(),
+                engine,
+            )
+            .await
+        } else if let Some(diag_expect) = &step.diagnostics {
+            check_diagnostics(conn, uri, diag_expect).await
+        } else if let Some(hover_expect) = &step.hover {
+            let position = document.cursor(step.cursor.as_ref())?;
+            check_hover(conn, uri, position, hover_expect, suite_dir).await
+        } else {
+            Ok(())
+        };
+
+        result.map_err(|e| {
+            anyhow::anyhow!(
+                "Step {} ({}) failed: {}\nDocument:\n{}",
+                index + 1,
+                kind,
+                e,
+                document.text()
+            )
+        })?;
+    }
 
-        let diagnostics = conn.get_diagnostics(&uri).await.unwrap_or_default();
+    Ok(())
+}
+
+async fn apply_edit(
+    conn: &mut LspConnection,
+    uri: &tower_lsp::lsp_types::Url,
+    document: &mut scenario::ScenarioDocument,
+    edit: &yaml_parser::EditStep,
+) -> Result<()> {
+    let change = document.apply(edit)?;
+    conn.did_change(uri.clone(), document.version(), vec![change])
+        .await?;
+
+    // Give server time to re-parse
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    Ok(())
+}
 
-        assertions::assert_diagnostics(
-            &diagnostics,
-            diag_expect.error_count,
-            diag_expect.warning_count,
+async fn check_completion(
+    conn: &mut LspConnection,
+    uri: &tower_lsp::lsp_types::Url,
+    position: tower_lsp::lsp_types::Position,
+    completion_expect: &yaml_parser::CompletionExpectation,
+    text: &str,
+    engine: engine_manager::Engine,
+) -> Result<()> {
+    let completion_items = conn
+        .completion(uri.clone(), position)
+        .await?
+        .unwrap_or_default();
+
+    if !completion_expect.contains.is_empty() {
+        assertions::assert_completion_contains(&completion_items, &completion_expect.contains)?;
+    }
+
+    if !completion_expect.not_contains.is_empty() {
+        assertions::assert_completion_not_contains(
+            &completion_items,
+            &completion_expect.not_contains,
         )?;
+    }
 
-        if !diag_expect.error_messages.is_empty() {
-            for expected_msg in &diag_expect.error_messages {
-                let found = diagnostics.iter().any(|d| d.message.contains(expected_msg));
-                if !found {
-                    return Err(anyhow::anyhow!(
-                        "Expected diagnostics to contain error message '{}', but it was not found. Diagnostics: {:?}",
-                        expected_msg,
-                        diagnostics
-                    ));
-                }
+    if let Some(count) = completion_expect.count {
+        assertions::assert_completion_count(&completion_items, count)?;
+    }
+
+    if let Some(min_count) = completion_expect.min_count {
+        assertions::assert_completion_min_count(&completion_items, min_count)?;
+    }
+
+    if !completion_expect.kind_counts.is_empty() {
+        assertions::assert_completion_kind_counts(
+            &completion_items,
+            &completion_expect.kind_counts,
+        )?;
+    }
+
+    if !completion_expect.order.is_empty() {
+        assertions::assert_completion_order(&completion_items, &completion_expect.order)?;
+    }
+
+    if !completion_expect.items.is_empty() {
+        assertions::assert_completion_items(&completion_items, &completion_expect.items)?;
+    }
+
+    if let Some(label) = &completion_expect.apply_and_parse {
+        assertions::assert_apply_and_parse(&completion_items, label, text, position, engine)?;
+    }
+
+    Ok(())
+}
+
+async fn check_diagnostics(
+    conn: &mut LspConnection,
+    uri: &tower_lsp::lsp_types::Url,
+    diag_expect: &yaml_parser::DiagnosticsExpectation,
+) -> Result<()> {
+    // Read any pending notifications (like publish_diagnostics)
+    conn.read_pending_notifications().await?;
+
+    let diagnostics = conn.get_diagnostics(uri).await.unwrap_or_default();
+
+    assertions::assert_diagnostics(
+        &diagnostics,
+        diag_expect.error_count,
+        diag_expect.warning_count,
+    )?;
+
+    if !diag_expect.error_messages.is_empty() {
+        for expected_msg in &diag_expect.error_messages {
+            let found = diagnostics.iter().any(|d| d.message.contains(expected_msg));
+            if !found {
+                return Err(anyhow::anyhow!(
+                    "Expected diagnostics to contain error message '{}', but it was not found. Diagnostics: {:?}",
+                    expected_msg,
+                    diagnostics
+                ));
             }
         }
     }
 
-    if let Some(hover_expect) = &test.expect_hover {
-        let hover_result = conn.hover(uri.clone(), position).await?;
-        assertions::assert_hover_contains(hover_result.as_ref(), &hover_expect.contains)?;
+    Ok(())
+}
+
+async fn check_hover(
+    conn: &mut LspConnection,
+    uri: &tower_lsp::lsp_types::Url,
+    position: tower_lsp::lsp_types::Position,
+    hover_expect: &yaml_parser::HoverExpectation,
+    suite_dir: &std::path::Path,
+) -> Result<()> {
+    if hover_expect.contains.is_none() && hover_expect.snapshot.is_none() {
+        return Err(anyhow::anyhow!(
+            "expect_hover needs `contains` or `snapshot`"
+        ));
     }
 
-    // Best-effort cleanup of opened document in shared LSP process.
-    let _ = conn.did_close(uri).await;
-    drop(shared_lsp_guard);
+    let hover_result = conn.hover(uri.clone(), position).await?;
+
+    if let Some(expected) = &hover_expect.contains {
+        assertions::assert_hover_contains(hover_result.as_ref(), expected)?;
+    }
+
+    if let Some(snapshot) = &hover_expect.snapshot {
+        assertions::assert_hover_snapshot(hover_result.as_ref(), &suite_dir.join(snapshot))?;
+    }
+
+    Ok(())
+}
+
+async fn check_logs(
+    test: &yaml_parser::TestCase,
+    logs: &server_logs::ServerLogBuffer,
+) -> Result<()> {
+    let Some(logs_expect) = &test.expect_logs else {
+        return Ok(());
+    };
+
+    let mut missing = Vec::new();
+    for needle in &logs_expect.contains {
+        if !logs.wait_for(needle, LOG_WAIT_TIMEOUT).await {
+            missing.push(needle.as_str());
+        }
+    }
+    if !missing.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Expected server logs to contain {:?}, but they were not found",
+            missing
+        ));
+    }
 
-    info!("Test passed: {}", test.name);
     Ok(())
 }
 
+/// Add the server's recent output to a failing case's error
+///
+/// The full capture goes to the debug log, which is flushed to disk on failure.
+fn attach_server_logs(err: anyhow::Error, logs: &server_logs::ServerLogBuffer) -> anyhow::Error {
+    let lines = logs.lines();
+    if lines.is_empty() {
+        return err;
+    }
+
+    for line in &lines {
+        logging::record(format!("[server] {}", line));
+    }
+
+    let tail = logs.tail(server_logs::FAILURE_TAIL_LINES);
+    anyhow::anyhow!(
+        "{}\n--- last {} of {} server log lines ---\n{}",
+        err,
+        tail.len(),
+        lines.len(),
+        tail.join("\n")
+    )
+}
+
 fn resolve_suite_path(suite_path: impl AsRef<std::path::Path>) -> Result<std::path::PathBuf> {
     let path = suite_path.as_ref();
     if path.is_absolute() {
@@ -422,8 +677,63 @@ fn test_label(path: &std::path::Path, test_name: &str) -> String {
     format!("{engine}:{category}:{meta_case}")
 }
 
+/// Print a case's final status, with its failed attempts when it was retried
+fn report_case(report: &CaseReport) {
+    match report.status() {
+        CaseStatus::Passed => eprintln!("Test completed: {}", report.label),
+        CaseStatus::Failed if report.attempts.len() == 1 => {
+            eprintln!("Test FAILED: {}", report.label)
+        }
+        _ => {
+            for line in report.lines() {
+                eprintln!("Test {}", line);
+            }
+        }
+    }
+}
+
+/// Run a case with [`run_test`], retrying it as configured
+///
+/// Every attempt opens its document under a fresh URI.
+async fn run_test_with_retries(
+    suite: &TestSuite,
+    test: &yaml_parser::TestCase,
+    suite_path: &std::path::Path,
+    label: &str,
+    retries: u32,
+) -> CaseReport {
+    let report = run_with_retries(label, retries, move |attempt| {
+        if attempt > 1 {
+            eprintln!(
+                "Retrying test: {} (attempt {} of {})",
+                label,
+                attempt,
+                retries + 1
+            );
+        }
+        run_test(suite, test, suite_path)
+    })
+    .await;
+    report_case(&report);
+    report
+}
+
 /// Run a single test case by index in a suite
+///
+/// The case is retried as many times as it declares with `flaky` or
+/// `retries`, see [`retry`].
 pub async fn run_case(suite_path: impl AsRef<std::path::Path>, case_index: usize) -> Result<()> {
+    run_case_with_retries(suite_path, case_index, None).await
+}
+
+/// Run a single test case by index in a suite, with an explicit retry count
+///
+/// `retries` overrides the count the case declares; `None` keeps it.
+pub async fn run_case_with_retries(
+    suite_path: impl AsRef<std::path::Path>,
+    case_index: usize,
+    retries: Option<u32>,
+) -> Result<()> {
     // Disable anyhow backtrace for cleaner error output
     unsafe {
         std::env::set_var("RUST_BACKTRACE", "0");
@@ -444,16 +754,14 @@ pub async fn run_case(suite_path: impl AsRef<std::path::Path>, case_index: usize
     })?;
 
     let case_label = test_label(&resolved_path, &test.name);
+    let retries = retries.unwrap_or_else(|| test.retry_count());
     eprintln!("Running test: {}", case_label);
 
-    match run_test(&suite, test, &resolved_path).await {
-        Ok(_) => {
-            eprintln!("Test completed: {}", case_label);
-            Ok(())
-        }
+    let report = run_test_with_retries(&suite, test, &resolved_path, &case_label, retries).await;
+    match report.into_result() {
+        Ok(()) => Ok(()),
         Err(e) => {
             let _ = logging::flush_to_file();
-            eprintln!("Test FAILED: {}", case_label);
             if let Some(log_path) = logging::log_path() {
                 eprintln!("Full debug log: {}", log_path.display());
             }
@@ -483,27 +791,39 @@ pub async fn run_suite(suite_path: impl AsRef<std::path::Path>) -> Result<()> {
     );
 
     let mut failed_tests = Vec::new();
+    let mut retried_tests = Vec::new();
 
     for test in &suite.tests {
         let case_label = test_label(&resolved_path, &test.name);
         eprintln!("Running test: {}", case_label);
-        match run_test(&suite, test, &resolved_path).await {
-            Ok(_) => {
-                eprintln!("Test completed: {}", case_label);
-            }
-            Err(e) => {
-                // First failure: flush logs to file
-                if failed_tests.is_empty() {
-                    let _ = logging::flush_to_file();
-                }
-                eprintln!("Test FAILED: {}", case_label);
-                debug_log!("!!! Test FAILED: {} - {}", case_label, e);
-                failed_tests.push((case_label, e));
+        let report = run_test_with_retries(
+            &suite,
+            test,
+            &resolved_path,
+            &case_label,
+            test.retry_count(),
+        )
+        .await;
+        if report.attempts.len() > 1 {
+            retried_tests.push(report.lines());
+        }
+        if let Err(e) = report.into_result() {
+            // First failure: flush logs to file
+            if failed_tests.is_empty() {
+                let _ = logging::flush_to_file();
             }
+            debug_log!("!!! Test FAILED: {} - {}", case_label, e);
+            failed_tests.push((case_label, e));
         }
     }
 
     // Report summary
+    if !retried_tests.is_empty() {
+        eprintln!("\n{} test(s) were retried", retried_tests.len());
+        for line in retried_tests.iter().flatten() {
+            eprintln!("  {}", line);
+        }
+    }
     if !failed_tests.is_empty() {
         eprintln!("\n{} test(s) failed", failed_tests.len());
         for (name, _) in &failed_tests {
@@ -543,34 +863,17 @@ fn global_cleanup() {
         return;
     }
 
-    // Check if we started Docker Compose services
-    let needs_cleanup = DOCKER_COMPOSE
+    // Only stop the services this process started
+    let stop_args = DOCKER_COMPOSE
         .try_read()
-        .map(|guard| guard.is_some())
-        .unwrap_or(false);
+        .ok()
+        .and_then(|guard| guard.as_ref().and_then(DockerCompose::stop_args));
 
-    if needs_cleanup {
+    if let Some(args) = stop_args {
         debug_log!("!!! Global cleanup: stopping Docker Compose services...");
 
-        // Find the compose file path by searching upward
-        let compose_file = crate::docker::find_docker_compose_file()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|e| {
-                debug_log!("!!! Failed to find docker-compose.yml: {}", e);
-                "tests/e2e-rs/docker-compose.yml".to_string()
-            });
-
         // Use std::process::Command for synchronous execution
-        let result = std::process::Command::new("docker")
-            .args([
-                "compose",
-                "-f",
-                &compose_file,
-                "-p",
-                "unified-sql-lsp-e2e",
-                "down",
-            ])
-            .output();
+        let result = std::process::Command::new("docker").args(&args).output();
 
         match result {
             Ok(output) => {
@@ -584,7 +887,7 @@ fn global_cleanup() {
                 }
             }
             Err(e) => {
-                debug_log!("!!! Failed to execute docker compose down: {}", e);
+                debug_log!("!!! Failed to execute docker compose rm: {}", e);
             }
         }
     }
@@ -15,6 +15,8 @@ use tokio::sync::RwLock;
 use tracing::{Span, info_span};
 use uuid::Uuid;
 
+use crate::docker::{ComposeServices, DockerCompose};
+
 /// Database engine types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Engine {
@@ -42,6 +44,9 @@ pub struct TestOrchestrator {
     pub db_pool: Arc<crate::db_pool::DatabasePoolManager>,
     /// LSP client pool manager
     pub lsp_pool: Arc<crate::lsp_pool::LspClientManager>,
+    config: OrchestratorConfig,
+    /// Docker Compose services started by `init_docker`
+    docker: RwLock<Option<DockerCompose>>,
 }
 
 #[derive(Debug, Clone)]
@@ -49,6 +54,10 @@ pub struct OrchestratorConfig {
     pub max_concurrent_tests: usize,
     pub db_pool_size: usize,
     pub lsp_pool_size: usize,
+    /// Compose file to use instead of the nearest docker-compose.yml
+    pub compose_file: Option<std::path::PathBuf>,
+    /// Compose service of each engine
+    pub compose_services: ComposeServices,
 }
 
 impl Default for OrchestratorConfig {
@@ -57,10 +66,30 @@ impl Default for OrchestratorConfig {
             max_concurrent_tests: 10,
             db_pool_size: 10,
             lsp_pool_size: 5,
+            compose_file: None,
+            compose_services: ComposeServices::default(),
         }
     }
 }
 
+impl OrchestratorConfig {
+    /// Use the compose file at `path`
+    pub fn with_compose_file(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.compose_file = Some(path.into());
+        self
+    }
+
+    /// Run `engine` from the compose service `service`
+    ///
+    /// Needed for compose files whose services aren't named after engines.
+    pub fn with_compose_service(mut self, engine: Engine, service: impl Into<String>) -> Self {
+        self.compose_services = self
+            .compose_services
+            .with_service(engine.to_string(), service);
+        self
+    }
+}
+
 /// Test registry entry
 #[derive(Debug)]
 pub struct TestRegistryEntry {
@@ -97,6 +126,8 @@ impl TestOrchestrator {
             test_registry: Arc::new(RwLock::new(TestRegistry::default())),
             db_pool,
             lsp_pool,
+            config,
+            docker: RwLock::new(None),
         })
     }
 
@@ -177,40 +208,46 @@ impl TestOrchestrator {
         &self.lsp_pool
     }
 
+    /// Create a Docker Compose manager for the configured compose file and services
+    pub fn docker_compose(&self) -> anyhow::Result<DockerCompose> {
+        let compose = match &self.config.compose_file {
+            Some(path) => DockerCompose::new(path, "unified-sql-lsp-e2e".to_string()),
+            None => DockerCompose::from_default_config()?,
+        };
+        Ok(compose.with_services(self.config.compose_services.clone()))
+    }
+
     /// Initialize Docker services for testing
     ///
-    /// This method starts Docker Compose services if they are not already running.
-    /// It integrates with the existing docker module for service management.
+    /// Starts every configured compose service that is not already running.
     pub async fn init_docker(&self) -> anyhow::Result<()> {
         use tracing::info;
 
         info!("Initializing Docker Compose services...");
 
-        // Check if services are already running
-        let mut compose = crate::docker::DockerCompose::from_default_config()?;
-
-        if compose.is_running().await? {
-            info!("Docker Compose services already running");
-            return Ok(());
+        let mut docker = self.docker.write().await;
+        if docker.is_none() {
+            *docker = Some(self.docker_compose()?);
         }
-
-        // Start services
-        compose.start().await?;
-        info!("Docker Compose services started successfully");
+        if let Some(compose) = docker.as_mut() {
+            compose.start().await?;
+        }
+        info!("Docker Compose services ready");
 
         Ok(())
     }
 
     /// Shutdown Docker services
     ///
-    /// This method stops Docker Compose services. Should be called after all tests complete.
+    /// Stops the services `init_docker` started. Should be called after all tests complete.
     pub async fn shutdown_docker(&self) -> anyhow::Result<()> {
         use tracing::info;
 
         info!("Shutting down Docker Compose services...");
 
-        let mut compose = crate::docker::DockerCompose::from_default_config()?;
-        compose.stop().await?;
+        if let Some(mut compose) = self.docker.write().await.take() {
+            compose.stop().await?;
+        }
 
         info!("Docker Compose services stopped");
 
@@ -0,0 +1,226 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Retrying known-flaky cases
+//!
+//! Some diagnostics timing cases race against live databases. Instead of
+//! hand-marking them, a case can declare `flaky: true` or `retries: n`:
+//!
+//! ```yaml
+//! tests:
+//!   - name: "diagnostics after catalog refresh"
+//!     retries: 3
+//!     sql: "SELECT * FROM missing_table"
+//! ```
+//!
+//! A failing attempt is then run again, each time with a fresh document URI.
+//! Every attempt is kept in the [`CaseReport`], and a case that only passed
+//! on a later attempt is labeled `passed-after-retry` so flakiness stays
+//! visible. Cases declaring neither field run exactly once.
+
+use std::fmt;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+/// Retries of a case marked `flaky: true` without an explicit `retries`
+pub const DEFAULT_FLAKY_RETRIES: u32 = 2;
+
+/// Final status of a case
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseStatus {
+    /// Passed on the first attempt
+    Passed,
+    /// Failed at least once, then passed
+    PassedAfterRetry,
+    /// Failed on every attempt
+    Failed,
+}
+
+impl CaseStatus {
+    /// Label used in reports: `passed`, `passed-after-retry` or `failed`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CaseStatus::Passed => "passed",
+            CaseStatus::PassedAfterRetry => "passed-after-retry",
+            CaseStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for CaseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One run of a case
+#[derive(Debug)]
+pub struct Attempt {
+    /// 1 for the first run
+    pub number: u32,
+    /// Wall time of the run
+    pub duration: Duration,
+    /// Why the run failed, `None` if it passed
+    pub error: Option<anyhow::Error>,
+}
+
+/// Every attempt of one case
+#[derive(Debug)]
+pub struct CaseReport {
+    /// Case label (`engine:category:case`)
+    pub label: String,
+    /// Attempts in the order they ran; never empty
+    pub attempts: Vec<Attempt>,
+}
+
+impl CaseReport {
+    /// Final status, from the last attempt
+    pub fn status(&self) -> CaseStatus {
+        match self.attempts.last() {
+            Some(last) if last.error.is_none() && self.attempts.len() == 1 => CaseStatus::Passed,
+            Some(last) if last.error.is_none() => CaseStatus::PassedAfterRetry,
+            _ => CaseStatus::Failed,
+        }
+    }
+
+    /// Error of the last attempt, if it failed
+    pub fn error(&self) -> Option<&anyhow::Error> {
+        self.attempts.last().and_then(|a| a.error.as_ref())
+    }
+
+    /// Report lines: the case status, then one line per failed attempt
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "{}: {} ({} attempt{})",
+            self.label,
+            self.status(),
+            self.attempts.len(),
+            if self.attempts.len() == 1 { "" } else { "s" }
+        )];
+        for attempt in &self.attempts {
+            if let Some(error) = &attempt.error {
+                let reason = error.to_string();
+                lines.push(format!(
+                    "  attempt {} failed after {:.1}s: {}",
+                    attempt.number,
+                    attempt.duration.as_secs_f64(),
+                    reason.lines().next().unwrap_or_default()
+                ));
+            }
+        }
+        lines
+    }
+
+    /// `Ok` unless the case failed on every attempt
+    pub fn into_result(mut self) -> Result<()> {
+        match self.attempts.pop().and_then(|a| a.error) {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Run `attempt` until it passes, at most `retries + 1` times
+///
+/// `attempt` receives the attempt number, starting at 1.
+pub async fn run_with_retries<F, Fut>(
+    label: impl Into<String>,
+    retries: u32,
+    mut attempt: F,
+) -> CaseReport
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let mut attempts = Vec::new();
+    for number in 1..=retries.saturating_add(1) {
+        let started = Instant::now();
+        let error = attempt(number).await.err();
+        let passed = error.is_none();
+        attempts.push(Attempt {
+            number,
+            duration: started.elapsed(),
+            error,
+        });
+        if passed {
+            break;
+        }
+    }
+
+    CaseReport {
+        label: label.into(),
+        attempts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Closure failing on its first `failures` calls, then passing
+    fn failing_then_passing(
+        failures: u32,
+        calls: &Cell<u32>,
+    ) -> impl FnMut(u32) -> std::future::Ready<Result<()>> + '_ {
+        move |number| {
+            calls.set(calls.get() + 1);
+            assert_eq!(number, calls.get());
+            std::future::ready(if number <= failures {
+                Err(anyhow::anyhow!("attempt {} timed out", number))
+            } else {
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_passes_after_retry() {
+        let calls = Cell::new(0);
+        let report = run_with_retries(
+            "mysql-5.7:diagnostics:racy",
+            3,
+            failing_then_passing(2, &calls),
+        )
+        .await;
+
+        assert_eq!(calls.get(), 3);
+        assert_eq!(report.status(), CaseStatus::PassedAfterRetry);
+        assert_eq!(
+            report.lines()[0],
+            "mysql-5.7:diagnostics:racy: passed-after-retry (3 attempts)"
+        );
+        assert!(report.lines()[1].starts_with("  attempt 1 failed after"));
+        assert!(report.lines()[2].ends_with("attempt 2 timed out"));
+        assert!(report.into_result().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fails_when_retries_run_out() {
+        let calls = Cell::new(0);
+        let report = run_with_retries("case", 1, failing_then_passing(5, &calls)).await;
+
+        assert_eq!(calls.get(), 2);
+        assert_eq!(report.status(), CaseStatus::Failed);
+        assert_eq!(report.error().unwrap().to_string(), "attempt 2 timed out");
+        assert!(report.into_result().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_without_retries_runs_once() {
+        let calls = Cell::new(0);
+        let report = run_with_retries("case", 0, failing_then_passing(1, &calls)).await;
+
+        assert_eq!(calls.get(), 1);
+        assert_eq!(report.status(), CaseStatus::Failed);
+
+        let calls = Cell::new(0);
+        let report = run_with_retries("case", 0, failing_then_passing(0, &calls)).await;
+        assert_eq!(report.status(), CaseStatus::Passed);
+        assert_eq!(report.lines(), vec!["case: passed (1 attempt)"]);
+    }
+}
@@ -13,6 +13,11 @@ use std::process::Stdio;
 use tokio::process::Command as TokioCommand;
 use tracing::{debug, info};
 
+use crate::server_logs::ServerLogBuffer;
+
+/// Server log filter used unless `E2E_SERVER_LOG` overrides it
+const DEFAULT_SERVER_LOG: &str = "unified_sql_lsp=debug";
+
 /// Find workspace root by searching upward for a Cargo.toml with [workspace] section
 pub fn find_workspace_root(start_dir: &Path) -> Result<std::path::PathBuf> {
     let mut current = start_dir;
@@ -53,7 +58,10 @@ pub struct LspRunner {
     /// Server process handle
     process: Option<tokio::process::Child>,
 
-    /// Background task for forwarding stderr
+    /// Captured server stderr
+    logs: ServerLogBuffer,
+
+    /// Background task reading stderr into `logs`
     _stderr_task: Option<tokio::task::JoinHandle<()>>,
 }
 
@@ -63,6 +71,7 @@ impl LspRunner {
         Self {
             binary_path: binary_path.as_ref().to_path_buf(),
             process: None,
+            logs: ServerLogBuffer::default(),
             _stderr_task: None,
         }
     }
@@ -130,14 +139,16 @@ impl LspRunner {
         let mut cmd = TokioCommand::new(&self.binary_path);
 
         // Set up stdio for LSP communication
-        // Pipe stderr so we can forward it to parent stderr
+        // Pipe stderr so it can be captured per test case
         cmd.stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
         // Set environment variables for testing
-        // Suppress LSP server stderr output to keep test output clean
-        cmd.env("RUST_LOG", "error");
+        // Server logs are captured, not printed, so debug output stays out of the terminal
+        let log_filter =
+            std::env::var("E2E_SERVER_LOG").unwrap_or_else(|_| DEFAULT_SERVER_LOG.to_string());
+        cmd.env("RUST_LOG", log_filter);
         cmd.env("RUST_BACKTRACE", "0");
 
         // Spawn the process
@@ -148,16 +159,9 @@ impl LspRunner {
         let pid = child.id();
         info!("LSP server spawned with PID: {:?}", pid);
 
-        // Consume stderr silently to prevent pipe buffer filling
-        // but don't print it to keep test output clean
+        // Drain stderr continuously so the server never blocks on a full pipe
         if let Some(stderr) = child.stderr.take() {
-            tokio::spawn(async move {
-                use tokio::io::AsyncReadExt;
-                let mut reader = stderr;
-                let mut buffer = Vec::new();
-                // Read and silently discard all stderr output
-                let _ = reader.read_to_end(&mut buffer).await;
-            });
+            self._stderr_task = Some(self.logs.spawn_reader(stderr));
         }
 
         self.process = Some(child);
@@ -165,6 +169,11 @@ impl LspRunner {
         Ok(())
     }
 
+    /// Captured server stderr
+    pub fn logs(&self) -> ServerLogBuffer {
+        self.logs.clone()
+    }
+
     /// Get stdin handle for sending LSP requests
     pub fn stdin(&mut self) -> Result<tokio::process::ChildStdin> {
         self.process
@@ -0,0 +1,184 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Multi-step scenarios
+//!
+//! Tracks the text of a document while a test case's `steps:` edit it, so
+//! every edit can be sent as an incremental `textDocument/didChange` and the
+//! cursor follows the `|` markers written in the edits.
+
+use anyhow::Result;
+use tower_lsp::lsp_types::{Position, Range, TextDocumentContentChangeEvent};
+
+use crate::yaml_parser::{CursorPos, EditStep, cursor_marker_position};
+
+/// Client-side copy of a document edited by a scenario
+#[derive(Debug, Clone)]
+pub struct ScenarioDocument {
+    text: String,
+    version: i32,
+    cursor: Option<Position>,
+}
+
+impl ScenarioDocument {
+    /// Start from the case's SQL, taking the cursor from its `|` marker if any
+    pub fn new(sql: &str) -> Self {
+        Self {
+            text: sql.replace('|', ""),
+            version: 1,
+            cursor: cursor_marker_position(sql),
+        }
+    }
+
+    /// Current document text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Version of the current text, as last sent to the server
+    pub fn version(&self) -> i32 {
+        self.version
+    }
+
+    /// Cursor for a query step: the step's own cursor, else the last marker
+    pub fn cursor(&self, explicit: Option<&CursorPos>) -> Result<Position> {
+        if let Some(cursor) = explicit {
+            return Ok(Position::new(cursor.line, cursor.character));
+        }
+        self.cursor.ok_or_else(|| {
+            anyhow::anyhow!("No cursor marker '|' in the document and no step cursor provided")
+        })
+    }
+
+    /// Apply `edit` and return the change to send to the server
+    ///
+    /// A `|` marker in the edit text moves the cursor; otherwise the cursor is
+    /// left where it was.
+    pub fn apply(&mut self, edit: &EditStep) -> Result<TextDocumentContentChangeEvent> {
+        let text = edit.text.replace('|', "");
+        let (start, end, range) = match &edit.range {
+            Some(range) => {
+                let start = Position::new(range.start.line, range.start.character);
+                let end = Position::new(range.end.line, range.end.character);
+                let start_offset = self.offset(start)?;
+                let end_offset = self.offset(end)?;
+                if start_offset > end_offset {
+                    anyhow::bail!("Edit range starts after it ends: {:?}", range);
+                }
+                (start_offset, end_offset, Some(Range::new(start, end)))
+            }
+            None => (0, self.text.len(), None),
+        };
+
+        if edit.text.contains('|') {
+            let marked = format!("{}{}", &self.text[..start], edit.text);
+            self.cursor = cursor_marker_position(&marked);
+        }
+
+        self.text.replace_range(start..end, &text);
+        self.version += 1;
+
+        Ok(TextDocumentContentChangeEvent {
+            range,
+            range_length: None,
+            text,
+        })
+    }
+
+    fn offset(&self, position: Position) -> Result<usize> {
+        position_offset(&self.text, position)
+    }
+}
+
+/// Byte offset of `position` in `text`, counting characters like the `|`
+/// marker does
+pub fn position_offset(text: &str, position: Position) -> Result<usize> {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        let newline = text[line_start..].find('\n').ok_or_else(|| {
+            anyhow::anyhow!("Position {:?} is past the end of the document", position)
+        })?;
+        line_start += newline + 1;
+    }
+
+    let line = text[line_start..].split('\n').next().unwrap_or_default();
+    let column = position.character as usize;
+    match line.char_indices().nth(column) {
+        Some((offset, _)) => Ok(line_start + offset),
+        None if line.chars().count() == column => Ok(line_start + line.len()),
+        None => anyhow::bail!("Position {:?} is past the end of its line", position),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yaml_parser::EditRange;
+
+    fn range_edit(start: (u32, u32), end: (u32, u32), text: &str) -> EditStep {
+        EditStep {
+            range: Some(EditRange {
+                start: CursorPos {
+                    line: start.0,
+                    character: start.1,
+                },
+                end: CursorPos {
+                    line: end.0,
+                    character: end.1,
+                },
+            }),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_range_edit_moves_cursor_to_marker() {
+        let mut doc = ScenarioDocument::new("SELECT | FROM users u");
+        assert_eq!(doc.cursor(None).unwrap(), Position::new(0, 7));
+
+        let change = doc
+            .apply(&range_edit(
+                (0, 20),
+                (0, 20),
+                "\nJOIN orders o ON o.user_id = u.id",
+            ))
+            .unwrap();
+        assert_eq!(change.text, "\nJOIN orders o ON o.user_id = u.id");
+        assert_eq!(
+            change.range,
+            Some(Range::new(Position::new(0, 20), Position::new(0, 20)))
+        );
+        assert_eq!(doc.version(), 2);
+        // No marker in the edit, the cursor stays put
+        assert_eq!(doc.cursor(None).unwrap(), Position::new(0, 7));
+
+        doc.apply(&range_edit((0, 7), (0, 7), "o.|")).unwrap();
+        assert_eq!(
+            doc.text(),
+            "SELECT o. FROM users u\nJOIN orders o ON o.user_id = u.id"
+        );
+        assert_eq!(doc.cursor(None).unwrap(), Position::new(0, 9));
+    }
+
+    #[test]
+    fn test_full_replace_and_bad_ranges() {
+        let mut doc = ScenarioDocument::new("SELECT 1");
+        assert!(doc.cursor(None).is_err());
+
+        let change = doc
+            .apply(&EditStep {
+                range: None,
+                text: "SELECT *\nFROM |".to_string(),
+            })
+            .unwrap();
+        assert_eq!(change.range, None);
+        assert_eq!(doc.text(), "SELECT *\nFROM ");
+        assert_eq!(doc.cursor(None).unwrap(), Position::new(1, 5));
+
+        assert!(doc.apply(&range_edit((2, 0), (2, 0), "x")).is_err());
+        assert!(doc.apply(&range_edit((0, 3), (0, 1), "x")).is_err());
+        assert_eq!(doc.version(), 2);
+    }
+}
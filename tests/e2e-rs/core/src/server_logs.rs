@@ -0,0 +1,175 @@
+// Copyright (c) 2025 woxQAQ
+//
+// Licensed under the MIT License or Apache License 2.0
+// See LICENSE files for details
+
+//! # Server log capture
+//!
+//! Collects the LSP server's stderr so a failing case can show what the
+//! server was doing, and so cases can assert on server-side behavior with
+//! `expect_logs`.
+//!
+//! Lines are read by a dedicated task as soon as they are written, so the
+//! server never blocks on a full stderr pipe.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::task::JoinHandle;
+
+/// Lines kept per test case before the oldest are dropped
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+/// Lines attached to the error of a failing case
+pub const FAILURE_TAIL_LINES: usize = 200;
+
+/// Bounded buffer of server log lines, shared with the reader task
+#[derive(Debug, Clone)]
+pub struct ServerLogBuffer {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    capacity: usize,
+}
+
+impl Default for ServerLogBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl ServerLogBuffer {
+    /// Create a buffer holding at most `capacity` lines
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity.min(1024)))),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Append a line, dropping the oldest one when full
+    pub fn push(&self, line: String) {
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Drop all captured lines (called at the start of each case)
+    pub fn clear(&self) {
+        self.lines.lock().unwrap().clear();
+    }
+
+    /// Number of captured lines
+    pub fn len(&self) -> usize {
+        self.lines.lock().unwrap().len()
+    }
+
+    /// Whether no lines are captured
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// All captured lines, oldest first
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// The last `n` captured lines, oldest first
+    pub fn tail(&self, n: usize) -> Vec<String> {
+        let lines = self.lines.lock().unwrap();
+        lines
+            .iter()
+            .skip(lines.len().saturating_sub(n))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether any captured line contains `needle`
+    pub fn contains(&self, needle: &str) -> bool {
+        self.lines
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|l| l.contains(needle))
+    }
+
+    /// Wait until a line containing `needle` is captured
+    ///
+    /// Stderr is read asynchronously, so a line logged while handling the
+    /// last request may arrive shortly after its response.
+    pub async fn wait_for(&self, needle: &str, timeout: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if self.contains(needle) {
+                return true;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    /// Start a task that reads `reader` line by line into this buffer
+    pub fn spawn_reader<R>(&self, reader: R) -> JoinHandle<()>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let buffer = self.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(reader).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                buffer.push(line);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_drops_oldest() {
+        let buffer = ServerLogBuffer::new(3);
+        for i in 0..5 {
+            buffer.push(format!("line {}", i));
+        }
+
+        assert_eq!(buffer.lines(), vec!["line 2", "line 3", "line 4"]);
+        assert_eq!(buffer.tail(2), vec!["line 3", "line 4"]);
+        assert_eq!(buffer.tail(10).len(), 3);
+        assert!(buffer.contains("line 4"));
+        assert!(!buffer.contains("line 0"));
+
+        buffer.clear();
+        assert!(buffer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reader_task_drains_large_output() {
+        let (mut writer, reader) = tokio::io::duplex(64);
+        let buffer = ServerLogBuffer::new(DEFAULT_CAPACITY);
+        let task = buffer.spawn_reader(reader);
+
+        // Far more than the pipe holds: only completes if the reader keeps up
+        use tokio::io::AsyncWriteExt;
+        for i in 0..2_000 {
+            writer
+                .write_all(format!("DEBUG handled request {}\n", i).as_bytes())
+                .await
+                .unwrap();
+        }
+        drop(writer);
+        task.await.unwrap();
+
+        assert_eq!(buffer.len(), 2_000);
+        assert!(
+            buffer
+                .wait_for("handled request 1999", Duration::from_millis(100))
+                .await
+        );
+    }
+}
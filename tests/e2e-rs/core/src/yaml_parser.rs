@@ -6,10 +6,43 @@
 //! YAML test definition parser
 //!
 //! Parses test case definitions from YAML files into structured Rust types.
+//!
+//! ## Shared settings
+//!
+//! Suites can avoid repeating their database setup with two top-level keys:
+//!
+//! - `extends: ../_defaults.yaml` deep-merges another YAML file under the
+//!   suite. Fields in the suite win, mappings are merged key by key, and
+//!   `database.schemas` / `database.data` paths in the extended file resolve
+//!   relative to that file.
+//! - `defaults:` holds per-test fields (e.g. `dialect`, or
+//!   `expect_completion.not_contains`) applied to every test. Values set in a
+//!   test win and lists are extended. `expect_*` blocks are only merged into
+//!   tests that declare that block, so a completion default never turns a
+//!   hover test into a completion test.
+//!
+//! ```yaml
+//! extends: ../_defaults.yaml
+//! defaults:
+//!   expect_completion:
+//!     not_contains: ["information_schema"]
+//! tests:
+//!   - name: "tables after FROM"
+//!     sql: "SELECT * FROM |"
+//!     expect_completion:
+//!       contains: ["users"]
+//! ```
 
-use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use tower_lsp::lsp_types::Position;
+use serde_yaml::{Mapping, Value};
+use tower_lsp::lsp_types::{CompletionItemKind, Position};
+
+use crate::retry::DEFAULT_FLAKY_RETRIES;
 
 /// Test suite definition from YAML
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -53,6 +86,10 @@ pub struct TestCase {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 
+    /// Dialect override for this test (defaults to `database.dialect`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dialect: Option<String>,
+
     /// SQL snippet with cursor marker
     pub sql: String,
 
@@ -60,6 +97,11 @@ pub struct TestCase {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cursor: Option<CursorPos>,
 
+    /// Word whose first occurrence in `sql` is the cursor position, instead
+    /// of a `|` marker
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor_at: Option<String>,
+
     /// Expected completion results
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expect_completion: Option<CompletionExpectation>,
@@ -71,6 +113,104 @@ pub struct TestCase {
     /// Expected hover result
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expect_hover: Option<HoverExpectation>,
+
+    /// Expected server log output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expect_logs: Option<LogsExpectation>,
+
+    /// Edits and assertions run in order against the document opened from `sql`
+    ///
+    /// The `|` marker is optional in `sql` when steps are used.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<TestStep>,
+
+    /// Known to be racy: retried [`DEFAULT_FLAKY_RETRIES`] times after a failure
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub flaky: bool,
+
+    /// Times the case is retried after a failure, overriding `flaky`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+}
+
+impl TestCase {
+    /// Times the case is run again after a failure (0 unless flaky)
+    pub fn retry_count(&self) -> u32 {
+        match (self.retries, self.flaky) {
+            (Some(retries), _) => retries,
+            (None, true) => DEFAULT_FLAKY_RETRIES,
+            (None, false) => 0,
+        }
+    }
+}
+
+/// One step of a multi-step test case
+///
+/// Exactly one of `edit`, `completion`, `diagnostics` or `hover` is set.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct TestStep {
+    /// Change sent to the server as `textDocument/didChange`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edit: Option<EditStep>,
+
+    /// Cursor for a completion or hover step, defaults to the last `|` marker
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<CursorPos>,
+
+    /// Expected completion results at the cursor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion: Option<CompletionExpectation>,
+
+    /// Expected diagnostics for the current document
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<DiagnosticsExpectation>,
+
+    /// Expected hover result at the cursor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hover: Option<HoverExpectation>,
+}
+
+impl TestStep {
+    /// Name of the action this step performs
+    ///
+    /// Fails unless exactly one action is set.
+    pub fn kind(&self) -> Result<&'static str> {
+        let kinds: Vec<&'static str> = [
+            ("edit", self.edit.is_some()),
+            ("completion", self.completion.is_some()),
+            ("diagnostics", self.diagnostics.is_some()),
+            ("hover", self.hover.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(kind, set)| set.then_some(kind))
+        .collect();
+
+        match kinds.as_slice() {
+            [kind] => Ok(kind),
+            _ => anyhow::bail!(
+                "A step must set exactly one of edit, completion, diagnostics or hover, found {:?}",
+                kinds
+            ),
+        }
+    }
+}
+
+/// Document edit
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct EditStep {
+    /// Replaced range, the whole document is replaced when omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<EditRange>,
+
+    /// New text; a `|` marker in it moves the cursor and is not sent
+    pub text: String,
+}
+
+/// Range of an edit in the document before the edit
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct EditRange {
+    pub start: CursorPos,
+    pub end: CursorPos,
 }
 
 /// Cursor position
@@ -105,6 +245,147 @@ pub struct CompletionExpectation {
     /// Minimum count
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min_count: Option<usize>,
+
+    /// Items that must be present with the given fields
+    #[serde(default)]
+    pub items: Vec<CompletionItemExpectation>,
+
+    /// Number of items per kind, e.g. `{ field: { min: 3 }, keyword: { max: 10 } }`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub kind_counts: BTreeMap<ItemKind, CountBound>,
+
+    /// Label of an item whose edit, applied at the cursor, must leave SQL
+    /// that the engine's grammar parses around the inserted text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apply_and_parse: Option<String>,
+}
+
+/// Expected fields of a single completion item
+///
+/// Only the fields that are set are compared.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct CompletionItemExpectation {
+    /// Item label
+    pub label: String,
+
+    /// Expected `detail`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+
+    /// Expected `label_details.description`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Completion item kinds by their YAML name
+const ITEM_KINDS: [(&str, CompletionItemKind); 25] = [
+    ("text", CompletionItemKind::TEXT),
+    ("method", CompletionItemKind::METHOD),
+    ("function", CompletionItemKind::FUNCTION),
+    ("constructor", CompletionItemKind::CONSTRUCTOR),
+    ("field", CompletionItemKind::FIELD),
+    ("variable", CompletionItemKind::VARIABLE),
+    ("class", CompletionItemKind::CLASS),
+    ("interface", CompletionItemKind::INTERFACE),
+    ("module", CompletionItemKind::MODULE),
+    ("property", CompletionItemKind::PROPERTY),
+    ("unit", CompletionItemKind::UNIT),
+    ("value", CompletionItemKind::VALUE),
+    ("enum", CompletionItemKind::ENUM),
+    ("keyword", CompletionItemKind::KEYWORD),
+    ("snippet", CompletionItemKind::SNIPPET),
+    ("color", CompletionItemKind::COLOR),
+    ("file", CompletionItemKind::FILE),
+    ("reference", CompletionItemKind::REFERENCE),
+    ("folder", CompletionItemKind::FOLDER),
+    ("enum_member", CompletionItemKind::ENUM_MEMBER),
+    ("constant", CompletionItemKind::CONSTANT),
+    ("struct", CompletionItemKind::STRUCT),
+    ("event", CompletionItemKind::EVENT),
+    ("operator", CompletionItemKind::OPERATOR),
+    ("type_parameter", CompletionItemKind::TYPE_PARAMETER),
+];
+
+/// A completion item kind named in YAML, e.g. `keyword` or `enum_member`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ItemKind(usize);
+
+impl ItemKind {
+    /// YAML name of `kind`; `None` for kinds outside the LSP specification
+    pub fn of(kind: CompletionItemKind) -> Option<Self> {
+        ITEM_KINDS
+            .iter()
+            .position(|(_, known)| *known == kind)
+            .map(ItemKind)
+    }
+
+    pub fn name(&self) -> &'static str {
+        ITEM_KINDS[self.0].0
+    }
+}
+
+impl TryFrom<String> for ItemKind {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        ITEM_KINDS
+            .iter()
+            .position(|(known, _)| *known == name)
+            .map(ItemKind)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = ITEM_KINDS.iter().map(|(name, _)| *name).collect();
+                format!(
+                    "unknown completion item kind `{}`, expected one of: {}",
+                    name,
+                    valid.join(", ")
+                )
+            })
+    }
+}
+
+impl From<ItemKind> for String {
+    fn from(kind: ItemKind) -> Self {
+        kind.name().to_string()
+    }
+}
+
+/// Expected number of items: exact (`3`) or bounded (`{ min: 3, max: 10 }`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum CountBound {
+    Exact(usize),
+    Between {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<usize>,
+    },
+}
+
+impl CountBound {
+    pub fn contains(&self, count: usize) -> bool {
+        match *self {
+            CountBound::Exact(expected) => count == expected,
+            CountBound::Between { min, max } => {
+                min.is_none_or(|min| count >= min) && max.is_none_or(|max| count <= max)
+            }
+        }
+    }
+}
+
+impl fmt::Display for CountBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            CountBound::Exact(expected) => write!(f, "exactly {}", expected),
+            CountBound::Between { min, max } => match (min, max) {
+                (Some(min), Some(max)) => write!(f, "{} to {}", min, max),
+                (Some(min), None) => write!(f, "at least {}", min),
+                (None, Some(max)) => write!(f, "at most {}", max),
+                (None, None) => write!(f, "any number"),
+            },
+        }
+    }
 }
 
 /// Diagnostics expectations
@@ -127,27 +408,60 @@ pub struct DiagnosticsExpectation {
 }
 
 /// Hover expectations
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct HoverExpectation {
     /// Expected hover content (substring match)
-    pub contains: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contains: Option<String>,
+
+    /// File holding the full expected hover markdown, relative to the suite
+    ///
+    /// Rewritten instead of compared when `E2E_UPDATE_SNAPSHOTS=1` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<String>,
 
     /// Hover should contain markdown
     #[serde(default)]
     pub is_markdown: bool,
 }
 
+/// Server log expectations
+///
+/// Asserts on server-side behavior that isn't visible in LSP responses.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct LogsExpectation {
+    /// Substrings that must appear in the server's stderr during the case
+    #[serde(default)]
+    pub contains: Vec<String>,
+}
+
 impl TestSuite {
     /// Parse test suite from YAML string
+    ///
+    /// `defaults:` is applied; `extends:` needs a file location and is
+    /// rejected here, use [`TestSuite::from_file`] instead.
     pub fn from_yaml(yaml: &str) -> Result<Self> {
-        let suite: TestSuite = serde_yaml::from_str(yaml)?;
-        Ok(suite)
+        let value: Value = serde_yaml::from_str(yaml)?;
+        if value.get("extends").is_some() {
+            anyhow::bail!("`extends` is only supported when loading a suite from a file");
+        }
+        Self::from_value(value)
     }
 
-    /// Parse test suite from YAML file
+    /// Parse test suite from YAML file, resolving `extends:` and `defaults:`
     pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self> {
-        let yaml = std::fs::read_to_string(path)?;
-        Self::from_yaml(&yaml)
+        let value = load_with_extends(path.as_ref(), &mut Vec::new())?;
+        Self::from_value(value)
+    }
+
+    fn from_value(mut value: Value) -> Result<Self> {
+        apply_test_defaults(&mut value);
+        Ok(serde_yaml::from_value(value)?)
+    }
+
+    /// Dialect used by a test, falling back to the suite database dialect
+    pub fn dialect_for<'a>(&'a self, test: &'a TestCase) -> &'a str {
+        test.dialect.as_deref().unwrap_or(&self.database.dialect)
     }
 
     /// Extract cursor position from SQL with | marker
@@ -157,38 +471,621 @@ impl TestSuite {
             return Ok(Position::new(cursor.line, cursor.character));
         }
 
+        if let Some(word) = &test.cursor_at {
+            return word_position(&self.strip_cursor_marker(test), word)
+                .ok_or_else(|| anyhow::anyhow!("cursor_at word '{}' not found in SQL", word));
+        }
+
         // Otherwise, find | marker in SQL
-        let sql = &test.sql;
-        let cursor_char = '|';
+        cursor_marker_position(&test.sql).ok_or_else(|| {
+            anyhow::anyhow!(
+                "No cursor marker '|' found in SQL and no explicit cursor position provided"
+            )
+        })
+    }
+
+    /// Strip cursor marker from SQL
+    pub fn strip_cursor_marker(&self, test: &TestCase) -> String {
+        test.sql.replace('|', "")
+    }
+}
+
+/// Position of the first `|` cursor marker in `sql`
+pub fn cursor_marker_position(sql: &str) -> Option<Position> {
+    let mut line = 0u32;
+    let mut character = 0u32;
+
+    for ch in sql.chars() {
+        if ch == '|' {
+            return Some(Position::new(line, character));
+        }
+        if ch == '\n' {
+            line += 1;
+            character = 0;
+        } else {
+            character += 1;
+        }
+    }
+
+    None
+}
+
+/// Position of the first occurrence of `word` in `sql` as a whole word
+///
+/// Occurrences inside a longer identifier don't count, so `id` skips
+/// `user_id`.
+pub fn word_position(sql: &str, word: &str) -> Option<Position> {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+    let (offset, _) = sql.match_indices(word).find(|(offset, _)| {
+        let before = sql[..*offset].chars().next_back();
+        let after = sql[offset + word.len()..].chars().next();
+        !before.is_some_and(is_identifier) && !after.is_some_and(is_identifier)
+    })?;
+
+    let before = &sql[..offset];
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    Some(Position::new(
+        before.matches('\n').count() as u32,
+        before[line_start..].chars().count() as u32,
+    ))
+}
+
+/// Load a YAML file and merge it over the file named by its `extends:` key
+///
+/// `chain` holds the files being loaded to report `extends` cycles.
+fn load_with_extends(path: &Path, chain: &mut Vec<PathBuf>) -> Result<Value> {
+    let yaml = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut value: Value = serde_yaml::from_str(&yaml)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
 
-        let mut line = 0u32;
-        let mut character = 0u32;
-        let mut found = false;
+    let Some(extends) = value.as_mapping_mut().and_then(|m| m.remove("extends")) else {
+        return Ok(value);
+    };
+    let extends = extends
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("`extends` in {} must be a path", path.display()))?;
+
+    let base_path = path.parent().unwrap_or(Path::new(".")).join(extends);
+    let key = base_path
+        .canonicalize()
+        .unwrap_or_else(|_| base_path.clone());
+    if chain.contains(&key) {
+        anyhow::bail!("Cyclic `extends` through {}", base_path.display());
+    }
 
-        for ch in sql.chars() {
-            if ch == cursor_char {
-                found = true;
-                break;
+    chain.push(key);
+    let mut base = load_with_extends(&base_path, chain)?;
+
+    let key = chain.pop().unwrap_or(base_path);
+    if let Some(base_dir) = key.parent() {
+        resolve_database_paths(&mut base, base_dir);
+    }
+    merge_override(&mut base, value);
+    Ok(base)
+}
+
+/// Make `database.schemas` and `database.data` entries relative to `dir`
+///
+/// Absolute paths, including ones already resolved by a nested `extends`,
+/// are left untouched.
+fn resolve_database_paths(value: &mut Value, dir: &Path) {
+    let Some(database) = value.get_mut("database") else {
+        return;
+    };
+
+    for key in ["schemas", "data"] {
+        let Some(Value::Sequence(paths)) = database.get_mut(key) else {
+            continue;
+        };
+        for path in paths.iter_mut() {
+            if let Value::String(p) = path {
+                *p = dir.join(&*p).to_string_lossy().into_owned();
             }
-            if ch == '\n' {
-                line += 1;
-                character = 0;
-            } else {
-                character += 1;
+        }
+    }
+}
+
+/// Deep-merge `overlay` into `base`; values from `overlay` win
+///
+/// Mappings are merged key by key, anything else is replaced.
+fn merge_override(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_override(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
             }
         }
+        (base, overlay) => *base = overlay,
+    }
+}
 
-        if !found {
-            return Err(anyhow::anyhow!(
-                "No cursor marker '|' found in SQL and no explicit cursor position provided"
-            ));
+/// Apply the suite `defaults:` mapping to every test case
+fn apply_test_defaults(suite: &mut Value) {
+    let Some(Value::Mapping(defaults)) = suite.as_mapping_mut().and_then(|m| m.remove("defaults"))
+    else {
+        return;
+    };
+
+    let Some(Value::Sequence(tests)) = suite.get_mut("tests") else {
+        return;
+    };
+
+    for test in tests.iter_mut().filter_map(Value::as_mapping_mut) {
+        for (key, default) in &defaults {
+            match test.get_mut(key) {
+                Some(existing) => merge_default(existing, default),
+                None if is_expectation_key(key) => {}
+                None => {
+                    test.insert(key.clone(), default.clone());
+                }
+            }
         }
+    }
+}
 
-        Ok(Position::new(line, character))
+/// Merge a default into a value set by a test; values in the test win
+///
+/// Mappings are merged key by key and lists are extended with missing items.
+fn merge_default(value: &mut Value, default: &Value) {
+    match (value, default) {
+        (Value::Mapping(value), Value::Mapping(default)) => merge_default_mapping(value, default),
+        (Value::Sequence(value), Value::Sequence(default)) => {
+            for item in default {
+                if !value.contains(item) {
+                    value.push(item.clone());
+                }
+            }
+        }
+        _ => {}
     }
+}
 
-    /// Strip cursor marker from SQL
-    pub fn strip_cursor_marker(&self, test: &TestCase) -> String {
-        test.sql.replace('|', "")
+fn merge_default_mapping(value: &mut Mapping, default: &Mapping) {
+    for (key, default) in default {
+        match value.get_mut(key) {
+            Some(existing) => merge_default(existing, default),
+            None => {
+                value.insert(key.clone(), default.clone());
+            }
+        }
+    }
+}
+
+fn is_expectation_key(key: &Value) -> bool {
+    key.as_str().is_some_and(|k| k.starts_with("expect_"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    const DEFAULTS: &str = r#"
+database:
+  dialect: "mysql"
+  connection_string: "mysql://localhost:3307/test_db"
+  schemas:
+    - "../fixtures/schema.sql"
+  data:
+    - "../fixtures/data.sql"
+"#;
+
+    #[test]
+    fn test_extends_merges_with_suite_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "engine/_defaults.yaml", DEFAULTS);
+        let suite_path = write(
+            dir.path(),
+            "engine/completion/suite.yaml",
+            r#"
+name: "suite"
+extends: "../_defaults.yaml"
+database:
+  connection_string: "mysql://localhost:3308/test_db"
+tests:
+  - name: "case"
+    sql: "SELECT |"
+"#,
+        );
+
+        let suite = TestSuite::from_file(&suite_path).unwrap();
+
+        assert_eq!(suite.database.dialect, "mysql");
+        assert_eq!(
+            suite.database.connection_string.as_deref(),
+            Some("mysql://localhost:3308/test_db")
+        );
+        assert_eq!(suite.tests.len(), 1);
+    }
+
+    #[test]
+    fn test_extends_resolves_paths_relative_to_extended_file() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "engine/_defaults.yaml", DEFAULTS);
+        let suite_path = write(
+            dir.path(),
+            "engine/completion/suite.yaml",
+            "name: \"suite\"\nextends: \"../_defaults.yaml\"\ntests: []\n",
+        );
+
+        let suite = TestSuite::from_file(&suite_path).unwrap();
+
+        let engine_dir = dir.path().canonicalize().unwrap().join("engine");
+        let suite_dir = suite_path.parent().unwrap();
+        assert_eq!(
+            suite_dir.join(&suite.database.schemas[0]),
+            engine_dir.join("../fixtures/schema.sql")
+        );
+        assert_eq!(
+            suite_dir.join(&suite.database.data[0]),
+            engine_dir.join("../fixtures/data.sql")
+        );
+    }
+
+    #[test]
+    fn test_suite_paths_replace_extended_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "engine/_defaults.yaml", DEFAULTS);
+        let suite_path = write(
+            dir.path(),
+            "engine/completion/suite.yaml",
+            r#"
+name: "suite"
+extends: "../_defaults.yaml"
+database:
+  schemas:
+    - "local.sql"
+tests: []
+"#,
+        );
+
+        let suite = TestSuite::from_file(&suite_path).unwrap();
+
+        assert_eq!(suite.database.schemas, vec!["local.sql".to_string()]);
+        assert_eq!(suite.database.data.len(), 1);
+    }
+
+    #[test]
+    fn test_extends_cycle_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "a.yaml", "extends: \"b.yaml\"\n");
+        let b = write(dir.path(), "b.yaml", "extends: \"a.yaml\"\n");
+
+        let err = TestSuite::from_file(&b).unwrap_err();
+        assert!(err.to_string().contains("Cyclic"), "{err}");
+    }
+
+    #[test]
+    fn test_defaults_apply_to_tests() {
+        let suite = TestSuite::from_yaml(
+            r#"
+name: "suite"
+database:
+  dialect: "mysql"
+defaults:
+  dialect: "postgresql"
+  expect_completion:
+    not_contains: ["information_schema"]
+tests:
+  - name: "inherits"
+    sql: "SELECT * FROM |"
+    expect_completion:
+      not_contains: ["pg_catalog"]
+  - name: "overrides"
+    dialect: "mysql"
+    sql: "SELECT * FROM |"
+    expect_completion:
+      contains: ["users"]
+  - name: "hover only"
+    sql: "SELECT id| FROM users"
+    expect_hover:
+      contains: "id"
+"#,
+        )
+        .unwrap();
+
+        let inherits = &suite.tests[0];
+        assert_eq!(suite.dialect_for(inherits), "postgresql");
+        assert_eq!(
+            inherits.expect_completion.as_ref().unwrap().not_contains,
+            vec!["pg_catalog".to_string(), "information_schema".to_string()]
+        );
+
+        let overrides = &suite.tests[1];
+        assert_eq!(suite.dialect_for(overrides), "mysql");
+        let completion = overrides.expect_completion.as_ref().unwrap();
+        assert_eq!(completion.contains, vec!["users".to_string()]);
+        assert_eq!(
+            completion.not_contains,
+            vec!["information_schema".to_string()]
+        );
+
+        // Expectation defaults don't add blocks to tests that lack them
+        assert!(suite.tests[2].expect_completion.is_none());
+    }
+
+    #[test]
+    fn test_parse_expect_logs() {
+        let suite = TestSuite::from_yaml(
+            r#"
+name: "suite"
+database:
+  dialect: "mysql"
+tests:
+  - name: "join context"
+    sql: "SELECT * FROM users JOIN |"
+    expect_logs:
+      contains: ["Detected FROM/JOIN context"]
+  - name: "no log expectation"
+    sql: "SELECT * FROM |"
+"#,
+        )
+        .unwrap();
+
+        let logs = suite.tests[0].expect_logs.as_ref().unwrap();
+        assert_eq!(
+            logs.contains,
+            vec!["Detected FROM/JOIN context".to_string()]
+        );
+        assert!(suite.tests[1].expect_logs.is_none());
+    }
+
+    #[test]
+    fn test_parse_completion_items() {
+        let suite = TestSuite::from_yaml(
+            r#"
+name: "suite"
+database:
+  dialect: "postgresql"
+tests:
+  - name: "foreign key detail"
+    sql: "SELECT * FROM orders WHERE |"
+    expect_completion:
+      items:
+        - label: "user_id"
+          detail: "Integer → users.id"
+        - label: "id"
+"#,
+        )
+        .unwrap();
+
+        let items = &suite.tests[0].expect_completion.as_ref().unwrap().items;
+        assert_eq!(
+            items,
+            &vec![
+                CompletionItemExpectation {
+                    label: "user_id".to_string(),
+                    detail: Some("Integer → users.id".to_string()),
+                    description: None,
+                },
+                CompletionItemExpectation {
+                    label: "id".to_string(),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mysql_57_completion_suites_use_shared_defaults() {
+        let pattern = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../tests/mysql-5.7/completion/*.yaml")
+            .to_string_lossy()
+            .into_owned();
+
+        let mut loaded = 0;
+        for path in glob::glob(&pattern).unwrap() {
+            let path = path.unwrap();
+            let suite = TestSuite::from_file(&path).unwrap();
+            assert_eq!(suite.database.dialect, "mysql", "{}", path.display());
+
+            let schema = path.parent().unwrap().join(&suite.database.schemas[0]);
+            assert!(schema.exists(), "{}: {}", path.display(), schema.display());
+            loaded += 1;
+        }
+        assert!(loaded > 0);
+    }
+
+    #[test]
+    fn test_from_yaml_rejects_extends() {
+        let err = TestSuite::from_yaml("extends: \"../_defaults.yaml\"\n").unwrap_err();
+        assert!(err.to_string().contains("extends"));
+    }
+
+    #[test]
+    fn test_parse_steps() {
+        let suite = TestSuite::from_yaml(
+            r#"
+name: "suite"
+database:
+  dialect: "postgresql"
+tests:
+  - name: "add a join"
+    sql: "SELECT | FROM users u"
+    steps:
+      - completion:
+          contains: ["u.id"]
+      - edit:
+          range:
+            start: { line: 0, character: 20 }
+            end: { line: 0, character: 20 }
+          text: " JOIN orders o ON o.user_id = u.id"
+      - edit:
+          text: "SELECT o.| FROM orders o"
+      - hover:
+          contains: "orders"
+        cursor: { line: 0, character: 8 }
+      - completion:
+          contains: ["user_id"]
+        diagnostics:
+          error_count: 0
+"#,
+        )
+        .unwrap();
+
+        let steps = &suite.tests[0].steps;
+        assert_eq!(steps.len(), 5);
+        assert_eq!(steps[0].kind().unwrap(), "completion");
+        assert_eq!(steps[1].kind().unwrap(), "edit");
+        let range = steps[1].edit.as_ref().unwrap().range.as_ref().unwrap();
+        assert_eq!((range.start.line, range.start.character), (0, 20));
+        assert!(steps[2].edit.as_ref().unwrap().range.is_none());
+        assert_eq!(steps[3].kind().unwrap(), "hover");
+        assert_eq!(steps[3].cursor.as_ref().unwrap().character, 8);
+        assert!(steps[4].kind().is_err());
+        assert!(suite.tests[0].expect_completion.is_none());
+    }
+
+    #[test]
+    fn test_parse_retries() {
+        let suite = TestSuite::from_yaml(
+            r#"
+name: "suite"
+database:
+  dialect: "mysql"
+tests:
+  - name: "stable"
+    sql: "SELECT |"
+  - name: "racy"
+    flaky: true
+    sql: "SELECT |"
+  - name: "very racy"
+    flaky: true
+    retries: 4
+    sql: "SELECT |"
+"#,
+        )
+        .unwrap();
+
+        let retries: Vec<u32> = suite.tests.iter().map(TestCase::retry_count).collect();
+        assert_eq!(retries, vec![0, DEFAULT_FLAKY_RETRIES, 4]);
+    }
+
+    #[test]
+    fn test_parse_kind_counts() {
+        let suite = TestSuite::from_yaml(
+            r#"
+name: "suite"
+database:
+  dialect: "mysql"
+tests:
+  - name: "keywords don't bury columns"
+    sql: "SELECT * FROM users WHERE |"
+    expect_completion:
+      kind_counts:
+        field: { min: 3 }
+        keyword: { max: 10 }
+        enum_member: 0
+"#,
+        )
+        .unwrap();
+
+        let kind_counts = &suite.tests[0]
+            .expect_completion
+            .as_ref()
+            .unwrap()
+            .kind_counts;
+        let bound = |name: &str| kind_counts[&ItemKind::try_from(name.to_string()).unwrap()];
+        assert_eq!(
+            bound("field"),
+            CountBound::Between {
+                min: Some(3),
+                max: None
+            }
+        );
+        assert_eq!(
+            bound("keyword"),
+            CountBound::Between {
+                min: None,
+                max: Some(10)
+            }
+        );
+        assert_eq!(bound("enum_member"), CountBound::Exact(0));
+    }
+
+    #[test]
+    fn test_unknown_kind_fails_loading() {
+        let err = TestSuite::from_yaml(
+            r#"
+name: "suite"
+database:
+  dialect: "mysql"
+tests:
+  - name: "typo"
+    sql: "SELECT |"
+    expect_completion:
+      kind_counts:
+        columns: { min: 3 }
+"#,
+        )
+        .unwrap_err();
+
+        let message = err.to_string();
+        assert!(
+            message.contains("unknown completion item kind `columns`"),
+            "{message}"
+        );
+        assert!(message.contains("field, variable"), "{message}");
+    }
+
+    #[test]
+    fn test_word_position() {
+        assert_eq!(
+            word_position("SELECT user_id, id FROM users", "id"),
+            Some(Position::new(0, 16))
+        );
+        assert_eq!(
+            word_position("SELECT id\nFROM users u", "u"),
+            Some(Position::new(1, 11))
+        );
+        assert_eq!(
+            word_position("SELECT 'é', id FROM users", "id"),
+            Some(Position::new(0, 12))
+        );
+        assert_eq!(word_position("SELECT ids FROM users", "id"), None);
+    }
+
+    #[test]
+    fn test_parse_cursor_at_and_hover_snapshot() {
+        let suite = TestSuite::from_yaml(
+            r#"
+name: "suite"
+database:
+  dialect: "mysql"
+tests:
+  - name: "snapshot"
+    sql: "SELECT COUNT(*) FROM users"
+    cursor_at: "COUNT"
+    expect_hover:
+      snapshot: "snapshots/count.md"
+  - name: "missing word"
+    sql: "SELECT 1"
+    cursor_at: "users"
+    expect_hover:
+      contains: "users"
+"#,
+        )
+        .unwrap();
+
+        let snapshot = &suite.tests[0];
+        assert_eq!(suite.extract_cursor(snapshot).unwrap(), Position::new(0, 7));
+        let hover = snapshot.expect_hover.as_ref().unwrap();
+        assert_eq!(hover.snapshot.as_deref(), Some("snapshots/count.md"));
+        assert_eq!(hover.contains, None);
+
+        let err = suite.extract_cursor(&suite.tests[1]).unwrap_err();
+        assert!(err.to_string().contains("cursor_at word 'users'"), "{err}");
     }
 }
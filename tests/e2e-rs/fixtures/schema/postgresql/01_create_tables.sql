@@ -362,6 +362,16 @@ BEGIN
 END;
 $$ LANGUAGE plpgsql;
 
+-- Scalar function with a comment, offered by completion through the live catalog
+CREATE OR REPLACE FUNCTION order_total(p_order_id INTEGER)
+RETURNS NUMERIC AS $$
+    SELECT COALESCE(SUM(quantity * unit_price), 0)
+    FROM order_items
+    WHERE order_id = p_order_id;
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION order_total(INTEGER) IS 'Sum of the line items of an order';
+
 -- ============================================================================
 -- POSTGRESQL-SPECIFIC FEATURES FOR TESTING
 -- ============================================================================